@@ -0,0 +1,100 @@
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_config::{
+    configs::{chain::StateKeeperConfig, ObservabilityConfig},
+    NetworkConfig, PostgresConfig,
+};
+use zksync_core::replay::BatchReplayer;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_env_config::FromEnv;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Re-executes historical L1 batches and compares the results with the stored ones",
+    long_about = None
+)]
+struct Cli {
+    /// First L1 batch to replay.
+    #[arg(long = "from")]
+    from_l1_batch: u32,
+    /// Last L1 batch to replay (inclusive). If not specified, only the `--from` batch is replayed.
+    #[arg(long = "to")]
+    to_l1_batch: Option<u32>,
+    /// Outputs replay reports as JSON objects (one per line), so that they are machine-readable.
+    #[arg(long)]
+    json: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let cli = Cli::parse();
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let state_keeper_config =
+        StateKeeperConfig::from_env().context("StateKeeperConfig::from_env()")?;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    // Replay doesn't modify the database, so it can use the replica.
+    let connection_pool = ConnectionPool::<Core>::builder(
+        postgres_config.replica_url()?,
+        postgres_config.max_connections()?,
+    )
+    .build()
+    .await
+    .context("failed to build a connection pool")?;
+    let replayer = BatchReplayer::new(
+        connection_pool,
+        network_config.zksync_network_id,
+        &state_keeper_config,
+    );
+
+    let to_l1_batch = cli.to_l1_batch.unwrap_or(cli.from_l1_batch);
+    let mut inconsistent_batches = vec![];
+    for number in cli.from_l1_batch..=to_l1_batch {
+        let l1_batch_number = L1BatchNumber(number);
+        let report = replayer.replay(l1_batch_number).await?;
+        if cli.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else if report.is_consistent() {
+            println!(
+                "L1 batch #{l1_batch_number} ({} transactions) is consistent",
+                report.tx_count
+            );
+        } else {
+            println!(
+                "L1 batch #{l1_batch_number} ({} transactions) has {} divergence(s):",
+                report.tx_count,
+                report.divergences.len()
+            );
+            for divergence in &report.divergences {
+                println!("  - {divergence}");
+            }
+        }
+
+        if !report.is_consistent() {
+            inconsistent_batches.push(l1_batch_number);
+        }
+    }
+
+    anyhow::ensure!(
+        inconsistent_batches.is_empty(),
+        "Replayed execution results diverge for L1 batches {inconsistent_batches:?}"
+    );
+    Ok(())
+}
@@ -175,6 +175,9 @@ async fn main() -> anyhow::Result<()> {
                 );
             }
 
+            // Finish previous rollbacks first, so that they don't interfere with the new one.
+            block_reverter.complete_interrupted_rollbacks().await;
+
             let mut flags = BlockReverterFlags::empty();
             if rollback_postgres {
                 flags |= BlockReverterFlags::POSTGRES;
@@ -1,16 +1,24 @@
-use std::{env, time::Duration};
+use std::{
+    env,
+    num::{NonZeroU32, NonZeroUsize},
+    time::Duration,
+};
 
 use anyhow::Context;
-use serde::Deserialize;
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer};
 use url::Url;
 use zksync_basic_types::{Address, L1ChainId, L2ChainId};
-use zksync_config::{configs::chain::L1BatchCommitDataGeneratorMode, ObjectStoreConfig};
+use zksync_config::{
+    configs::{chain::L1BatchCommitDataGeneratorMode, database::RocksdbColumnFamilyConfig},
+    ObjectStoreConfig,
+};
 use zksync_core::{
     api_server::{
         tx_sender::TxSenderConfig,
         web3::{state::InternalApiConfig, Namespace},
     },
     consensus,
+    sync_layer::ActionQueue,
     temp_config_store::decode_yaml,
 };
 use zksync_types::{api::BridgeAddresses, fee_model::FeeParams};
@@ -147,6 +155,23 @@ pub struct OptionalENConfig {
     /// Maximum response body size in MiBs. Default is 10 MiB.
     #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
     pub max_response_body_size_mb: usize,
+    /// Timeout for handling a single JSON-RPC request in seconds. Once it has passed, VM execution and potentially
+    /// expensive DB queries performed for the request are aborted. If not set, requests are not timed out.
+    api_request_timeout_sec: Option<u64>,
+    /// Maximum number of requests per minute for each RPC method, shared by all clients of a server.
+    /// Requests exceeding the limit are rejected with the -32005 error. If not set, methods are not rate-limited.
+    pub api_method_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of requests per minute from a single client IP address, as reported by a reverse proxy
+    /// in the `X-Forwarded-For` header. Requests exceeding the limit are rejected with the -32005 error.
+    /// If not set, clients are not rate-limited.
+    pub api_client_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of requests handled concurrently by each API server. Once the limit is reached,
+    /// new requests are rejected with the -32005 error. If not set, concurrent requests are not limited.
+    pub api_max_concurrent_requests: Option<usize>,
+    /// Whether to serve a read-only subset of the API (e.g., `eth_chainId`) while the node storage is being
+    /// initialized or rolled back. Other methods are rejected with a "node is syncing" error.
+    #[serde(default)]
+    pub read_only_api_during_recovery: bool,
 
     // Other API config settings
     /// Interval between polling DB for pubsub (in ms).
@@ -191,6 +216,10 @@ pub struct OptionalENConfig {
     /// Maximum number of transactions to be stored in the mempool cache. Default is 10000.
     #[serde(default = "OptionalENConfig::default_mempool_cache_size")]
     pub mempool_cache_size: usize,
+    /// Size of the cache for immutable lookups (factory deps by hash, protocol versions and details of executed
+    /// miniblocks) in MiBs. Default is 0, i.e., the cache is disabled.
+    #[serde(default)]
+    lookup_cache_size_mb: usize,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -230,6 +259,10 @@ pub struct OptionalENConfig {
         default = "OptionalENConfig::default_max_l1_batches_per_tree_iter"
     )]
     pub max_l1_batches_per_tree_iter: usize,
+    /// Maximum number of L1 batches, updates for which are written to RocksDB in a single write batch
+    /// while the Merkle tree is catching up. Larger values reduce compaction load during re-sync.
+    #[serde(default = "OptionalENConfig::default_max_batches_per_tree_commit")]
+    pub max_batches_per_tree_commit: usize,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     #[serde(default = "OptionalENConfig::default_merkle_tree_multi_get_chunk_size")]
@@ -245,12 +278,36 @@ pub struct OptionalENConfig {
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Number of threads in a dedicated thread pool used to hash Merkle tree nodes (0 means the number
+    /// of logical CPUs). If not specified, the tree shares the global thread pool.
+    #[serde(default)]
+    pub merkle_tree_hashing_thread_count: Option<usize>,
+    /// Whether to rebuild the Merkle tree from Postgres if the tree RocksDB instance is empty (e.g., was lost),
+    /// instead of recomputing it by replaying all L1 batches.
+    #[serde(default)]
+    pub merkle_tree_rebuild_from_postgres: bool,
+    /// Tuning options for column families in the Merkle tree RocksDB. Specified as a JSON array with the same format
+    /// as for the main node, e.g. `[{"name": "stale_keys", "compaction_style": "universal"}]`.
+    #[serde(default, deserialize_with = "deserialize_json")]
+    pub merkle_tree_column_families: Vec<RocksdbColumnFamilyConfig>,
+
+    // State keeper cache config
+    /// Capacity of the block cache for the state keeper RocksDB. If not specified, the default RocksDB cache
+    /// options will be used.
+    #[serde(default)]
+    state_keeper_db_block_cache_size_mb: Option<usize>,
+    /// Tuning options for column families in the state keeper RocksDB, in the same format as
+    /// `merkle_tree_column_families`.
+    #[serde(default, deserialize_with = "deserialize_json")]
+    pub state_keeper_db_column_families: Vec<RocksdbColumnFamilyConfig>,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
     database_long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     database_slow_query_threshold_ms: Option<u64>,
+    /// Default timeout in milliseconds for DB queries. Queries exceeding the timeout are aborted.
+    database_query_timeout_ms: Option<u64>,
 
     // Other config settings
     /// Port on which the Prometheus exporter server is listening.
@@ -263,6 +320,24 @@ pub struct OptionalENConfig {
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(default = "OptionalENConfig::default_miniblock_seal_queue_capacity")]
     pub miniblock_seal_queue_capacity: usize,
+    /// Target latency in milliseconds of sealing miniblocks. If set, miniblocks waiting in the seal queue
+    /// are sealed in a single DB transaction, which speeds up syncing if the node lags behind the main node.
+    miniblock_seal_batch_latency_target_ms: Option<u64>,
+    /// Max time in milliseconds that the miniblock sealer waits for new miniblocks to fill a batch
+    /// if `miniblock_seal_batch_latency_target_ms` is set. By default, the sealer doesn't wait.
+    miniblock_seal_batch_max_delay_ms: Option<u64>,
+    /// Maximum number of actions (opened L1 batches / miniblocks, transactions, and seal markers) fetched
+    /// from the main node but not yet processed by the state keeper. Once this many actions are queued,
+    /// fetching is paused until the state keeper catches up.
+    #[serde(default = "OptionalENConfig::default_action_queue_capacity")]
+    pub action_queue_capacity: NonZeroUsize,
+    /// Number of worker threads used by the state keeper to speculatively pre-execute fetched transactions in parallel.
+    /// Pre-execution only prefetches storage reads for the transactions; the authoritative execution remains
+    /// sequential. Each worker uses a separate storage instance (e.g., a Postgres connection if the state keeper cache
+    /// is not initialized yet), so the state keeper holds `parallel_tx_reexecution_workers + 1` storage instances
+    /// while executing an L1 batch. 0 (the default) disables pre-execution.
+    #[serde(default)]
+    pub parallel_tx_reexecution_workers: usize,
     /// Configures whether to persist protective reads when persisting L1 batches in the state keeper.
     /// Protective reads are never required by full nodes so far, not until such a node runs a full Merkle tree
     /// (presumably, to participate in L1 batch proving).
@@ -277,6 +352,36 @@ pub struct OptionalENConfig {
 
     #[serde(default = "OptionalENConfig::default_l1_batch_commit_data_generator_mode")]
     pub l1_batch_commit_data_generator_mode: L1BatchCommitDataGeneratorMode,
+    /// Run the consensus component in the read-only attestor mode: miniblocks are fetched via JSON-RPC
+    /// from the main node, while consensus only verifies and stores block certificates received
+    /// over the gossip network. Has no effect if consensus is not configured.
+    #[serde(default)]
+    pub consensus_attestor_mode: bool,
+    /// If set, selected node metrics (sync lag, L1 batch numbers, queue sizes etc.) are persisted to Postgres
+    /// with this interval, so that they can be analyzed after an incident even if Prometheus wasn't scraping the node.
+    pub metrics_snapshots_interval_sec: Option<u64>,
+    /// WebSocket URL of the main node. If set, fee params are received via a subscription instead of being
+    /// polled from the main node; polling is still used as a fallback if the subscription fails.
+    pub main_node_ws_url: Option<String>,
+    /// Interval between fee params polls from the main node, in milliseconds.
+    #[serde(default = "OptionalENConfig::default_fee_params_poll_interval_ms")]
+    fee_params_poll_interval_ms: u64,
+    /// Number of poll intervals without a successful fee params refresh after which fee params are considered stale.
+    /// Stale fee params mark the fee params fetcher as not ready in the node health check.
+    #[serde(default = "OptionalENConfig::default_fee_params_max_stale_intervals")]
+    pub fee_params_max_stale_intervals: u32,
+    /// If set, the state keeper will not start new miniblocks while fee params are stale.
+    #[serde(default)]
+    pub stop_sealing_on_stale_fee_params: bool,
+    /// If set, event payloads and transaction bytes of sealed miniblocks are compressed in Postgres
+    /// in the background.
+    #[serde(default)]
+    pub compress_block_bodies: bool,
+    /// Address of the main node signer wallet expected to sign L2 blocks fetched via `en_syncL2Block`. If set, L2 blocks
+    /// that are not signed by this address are rejected, which allows detecting modification of sync data in transit.
+    /// Requires the main node to sign L2 blocks (`sign_sync_blocks` in the main node API config).
+    // Not a part of `RemoteENConfig` for the same reason as `contracts_diamond_proxy_addr`.
+    pub main_node_block_signer_addr: Option<Address>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -341,6 +446,10 @@ impl OptionalENConfig {
         20
     }
 
+    const fn default_max_batches_per_tree_commit() -> usize {
+        1
+    }
+
     const fn default_vm_concurrency_limit() -> usize {
         // The default limit is large so that it does not create a bottleneck on its own.
         // VM execution can still be limited by Tokio runtime parallelism and/or the number
@@ -396,6 +505,10 @@ impl OptionalENConfig {
         10
     }
 
+    fn default_action_queue_capacity() -> NonZeroUsize {
+        ActionQueue::DEFAULT_CAPACITY
+    }
+
     const fn default_protective_reads_persistence_enabled() -> bool {
         true
     }
@@ -412,10 +525,26 @@ impl OptionalENConfig {
         L1BatchCommitDataGeneratorMode::Rollup
     }
 
+    const fn default_fee_params_poll_interval_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_fee_params_max_stale_intervals() -> u32 {
+        10
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
 
+    pub fn api_request_timeout(&self) -> Option<Duration> {
+        self.api_request_timeout_sec.map(Duration::from_secs)
+    }
+
+    pub fn fee_params_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.fee_params_poll_interval_ms)
+    }
+
     pub fn metadata_calculator_delay(&self) -> Duration {
         Duration::from_millis(self.metadata_calculator_delay)
     }
@@ -435,6 +564,11 @@ impl OptionalENConfig {
         self.latest_values_cache_size_mb * BYTES_IN_MEGABYTE
     }
 
+    /// Returns the size of the lookup cache in bytes.
+    pub fn lookup_cache_size(&self) -> usize {
+        self.lookup_cache_size_mb * BYTES_IN_MEGABYTE
+    }
+
     /// Returns the size of block cache for Merkle tree in bytes.
     pub fn merkle_tree_block_cache_size(&self) -> usize {
         self.merkle_tree_block_cache_size_mb * BYTES_IN_MEGABYTE
@@ -450,6 +584,11 @@ impl OptionalENConfig {
         Duration::from_secs(self.merkle_tree_stalled_writes_timeout_sec)
     }
 
+    pub fn state_keeper_db_block_cache_size(&self) -> Option<usize> {
+        self.state_keeper_db_block_cache_size_mb
+            .map(|size| size * BYTES_IN_MEGABYTE)
+    }
+
     pub fn long_connection_threshold(&self) -> Option<Duration> {
         self.database_long_connection_threshold_ms
             .map(Duration::from_millis)
@@ -460,6 +599,19 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn miniblock_seal_batch_latency_target(&self) -> Option<Duration> {
+        self.miniblock_seal_batch_latency_target_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn miniblock_seal_batch_max_delay(&self) -> Duration {
+        Duration::from_millis(self.miniblock_seal_batch_max_delay_ms.unwrap_or(0))
+    }
+
+    pub fn query_timeout(&self) -> Option<Duration> {
+        self.database_query_timeout_ms.map(Duration::from_millis)
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
         self.api_namespaces
             .clone()
@@ -483,6 +635,10 @@ impl OptionalENConfig {
     pub fn mempool_cache_update_interval(&self) -> Duration {
         Duration::from_millis(self.mempool_cache_update_interval)
     }
+
+    pub fn metrics_snapshots_interval(&self) -> Option<Duration> {
+        self.metrics_snapshots_interval_sec.map(Duration::from_secs)
+    }
 }
 
 /// This part of the external node config is required for its operation.
@@ -529,6 +685,8 @@ impl RequiredENConfig {
 pub struct PostgresConfig {
     pub database_url: String,
     pub max_connections: u32,
+    /// Postgres schema storing all tables for the node. See the main node config for details.
+    pub schema: Option<String>,
 }
 
 impl PostgresConfig {
@@ -540,6 +698,7 @@ impl PostgresConfig {
                 .context("DATABASE_POOL_SIZE env variable is not set")?
                 .parse()
                 .context("Unable to parse DATABASE_POOL_SIZE env variable")?,
+            schema: env::var("DATABASE_SCHEMA").ok(),
         })
     }
 }
@@ -565,14 +724,34 @@ pub(crate) fn read_consensus_config() -> anyhow::Result<Option<consensus::Config
 #[derive(Debug, Clone)]
 pub struct SnapshotsRecoveryConfig {
     pub snapshots_object_store: ObjectStoreConfig,
+    /// Addresses trusted to sign snapshot headers (e.g., the main node signer wallet). If non-empty, snapshot recovery
+    /// only proceeds if the snapshot header is signed by one of these addresses. Requires the main node
+    /// to sign snapshot headers (`sign_snapshot_headers` in the main node API config).
+    pub trusted_signers: Vec<Address>,
+    /// Base URLs of public GCS buckets mirroring the snapshots object store. Mirrors are accessed anonymously
+    /// and are tried in order for each snapshot blob that cannot be fetched from the main object store.
+    pub mirror_bucket_urls: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotsSourcesConfig {
+    #[serde(default)]
+    trusted_signers: Vec<Address>,
+    #[serde(default)]
+    mirror_bucket_urls: Vec<String>,
 }
 
 pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecoveryConfig> {
     let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
         .from_env::<ObjectStoreConfig>()
         .context("failed loading snapshot object store config from env variables")?;
+    let sources_config = envy::prefixed("EN_SNAPSHOTS_")
+        .from_env::<SnapshotsSourcesConfig>()
+        .context("failed loading snapshot sources config from env variables")?;
     Ok(SnapshotsRecoveryConfig {
         snapshots_object_store,
+        trusted_signers: sources_config.trusted_signers,
+        mirror_bucket_urls: sources_config.mirror_bucket_urls,
     })
 }
 
@@ -677,6 +856,16 @@ where
         .unwrap_or_else(|_| panic!("unable to parse {} env variable", name))
 }
 
+/// Deserializes a value encoded as a JSON string (e.g., in an env variable).
+fn deserialize_json<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let json = String::deserialize(deserializer)?;
+    serde_json::from_str(&json).map_err(de::Error::custom)
+}
+
 impl From<ExternalNodeConfig> for InternalApiConfig {
     fn from(config: ExternalNodeConfig) -> Self {
         Self {
@@ -701,8 +890,13 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
             filters_disabled: config.optional.filters_disabled,
+            // External nodes don't have a mempool; transactions are proxied to the main node.
+            mempool_inspection_enabled: false,
+            // External nodes don't record transaction execution metrics.
+            contract_execution_stats_enabled: false,
             mempool_cache_update_interval: config.optional.mempool_cache_update_interval(),
             mempool_cache_size: config.optional.mempool_cache_size,
+            lookup_cache_size: config.optional.lookup_cache_size(),
             dummy_verifier: config.remote.dummy_verifier,
             l1_batch_commit_data_generator_mode: config.remote.l1_batch_commit_data_generator_mode,
         }
@@ -724,6 +918,9 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
             // and they will be enforced by the main node anyway.
             max_allowed_l2_tx_gas_limit: u64::MAX,
             validation_computational_gas_limit: u32::MAX,
+            // Pending transactions are managed by the main node.
+            replacement_fee_bump_percent: None,
+            max_pending_txs_per_account: None,
             chain_id: config.remote.l2_chain_id,
             l1_to_l2_transactions_compatibility_mode: config
                 .optional
@@ -1,5 +1,7 @@
 //! Tests for EN configuration.
 
+use zksync_config::configs::database::{RocksdbCompactionStyle, RocksdbCompression};
+
 use super::*;
 
 #[test]
@@ -17,6 +19,7 @@ fn parsing_optional_config_from_empty_env() {
     assert_eq!(config.max_nonce_ahead, 50);
     assert_eq!(config.estimate_gas_scale_factor, 1.2);
     assert_eq!(config.vm_concurrency_limit, 2_048);
+    assert_eq!(config.parallel_tx_reexecution_workers, 0);
     assert_eq!(config.factory_deps_cache_size(), 128 * BYTES_IN_MEGABYTE);
     assert_eq!(config.latest_values_cache_size(), 128 * BYTES_IN_MEGABYTE);
     assert_eq!(config.merkle_tree_multi_get_chunk_size, 500);
@@ -24,6 +27,10 @@ fn parsing_optional_config_from_empty_env() {
         config.merkle_tree_block_cache_size(),
         128 * BYTES_IN_MEGABYTE
     );
+    assert_eq!(config.merkle_tree_hashing_thread_count, None);
+    assert!(!config.merkle_tree_rebuild_from_postgres);
+    assert!(config.merkle_tree_column_families.is_empty());
+    assert_eq!(config.state_keeper_db_block_cache_size(), None);
     assert_eq!(config.max_response_body_size(), 10 * BYTES_IN_MEGABYTE);
     assert_eq!(
         config.l1_batch_commit_data_generator_mode,
@@ -48,8 +55,16 @@ fn parsing_optional_config_from_env() {
         ("EN_LATEST_VALUES_CACHE_SIZE_MB", "50"),
         ("EN_MERKLE_TREE_MULTI_GET_CHUNK_SIZE", "1000"),
         ("EN_MERKLE_TREE_BLOCK_CACHE_SIZE_MB", "32"),
+        ("EN_MERKLE_TREE_HASHING_THREAD_COUNT", "4"),
+        ("EN_MERKLE_TREE_REBUILD_FROM_POSTGRES", "true"),
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
+        ("EN_PARALLEL_TX_REEXECUTION_WORKERS", "4"),
         ("EN_L1_BATCH_COMMIT_DATA_GENERATOR_MODE", "Validium"),
+        (
+            "EN_MERKLE_TREE_COLUMN_FAMILIES",
+            r#"[{"name": "stale_keys", "compaction_style": "universal", "compression": "lz4"}]"#,
+        ),
+        ("EN_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB", "64"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -76,9 +91,29 @@ fn parsing_optional_config_from_env() {
         config.merkle_tree_block_cache_size(),
         32 * BYTES_IN_MEGABYTE
     );
+    assert_eq!(config.merkle_tree_hashing_thread_count, Some(4));
+    assert!(config.merkle_tree_rebuild_from_postgres);
     assert_eq!(config.max_response_body_size(), BYTES_IN_MEGABYTE);
+    assert_eq!(config.parallel_tx_reexecution_workers, 4);
     assert_eq!(
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitDataGeneratorMode::Validium
     );
+    let [column_family] = config.merkle_tree_column_families.as_slice() else {
+        panic!(
+            "unexpected column families: {:?}",
+            config.merkle_tree_column_families
+        );
+    };
+    assert_eq!(column_family.name, "stale_keys");
+    assert_eq!(
+        column_family.compaction_style,
+        Some(RocksdbCompactionStyle::Universal)
+    );
+    assert_eq!(column_family.compression, Some(RocksdbCompression::Lz4));
+    assert_eq!(
+        config.state_keeper_db_block_cache_size(),
+        Some(64 * BYTES_IN_MEGABYTE)
+    );
+    assert!(config.state_keeper_db_column_families.is_empty());
 }
@@ -0,0 +1,89 @@
+//! Operator commands for sync actions that the node has failed to apply (dead letters).
+
+use anyhow::Context as _;
+use zksync_dal::{
+    sync_dead_letters_dal::{DeadLetterResolution, SyncDeadLetter},
+    Connection, ConnectionPool, Core, CoreDal,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DeadLetterCommand {
+    List,
+    Retry(u64),
+    Skip(u64),
+}
+
+fn print_dead_letter(dead_letter: &SyncDeadLetter) {
+    let resolution = dead_letter
+        .resolution
+        .map_or("unresolved", DeadLetterResolution::as_str);
+    println!(
+        "#{} [{resolution}] miniblock #{}, recorded at {}",
+        dead_letter.id, dead_letter.miniblock_number, dead_letter.created_at
+    );
+    if let Some(tx_hash) = dead_letter.tx_hash {
+        println!("  tx hash: {tx_hash:?}");
+    }
+    println!("  error: {}", dead_letter.error);
+    println!("  action: {}", dead_letter.action);
+}
+
+async fn resolve(
+    storage: &mut Connection<'_, Core>,
+    id: u64,
+    resolution: DeadLetterResolution,
+) -> anyhow::Result<()> {
+    let resolved = storage
+        .sync_dead_letters_dal()
+        .resolve_dead_letter(id, resolution)
+        .await?;
+    anyhow::ensure!(
+        resolved,
+        "dead letter #{id} doesn't exist or is already resolved"
+    );
+    println!(
+        "Resolved dead letter #{id} as `{resolution}`; it will take effect after the node restart"
+    );
+    Ok(())
+}
+
+pub(crate) async fn run_command(
+    pool: &ConnectionPool<Core>,
+    command: DeadLetterCommand,
+) -> anyhow::Result<()> {
+    let mut storage = pool.connection_tagged("dead_letters").await?;
+    match command {
+        DeadLetterCommand::List => {
+            let dead_letters = storage
+                .sync_dead_letters_dal()
+                .get_dead_letters(true)
+                .await?;
+            if dead_letters.is_empty() {
+                println!("No unresolved dead letters");
+            }
+            for dead_letter in &dead_letters {
+                print_dead_letter(dead_letter);
+            }
+            Ok(())
+        }
+        DeadLetterCommand::Retry(id) => {
+            resolve(&mut storage, id, DeadLetterResolution::Retry).await
+        }
+        DeadLetterCommand::Skip(id) => {
+            let dead_letter = storage
+                .sync_dead_letters_dal()
+                .get_dead_letter(id)
+                .await?
+                .with_context(|| format!("dead letter #{id} doesn't exist"))?;
+            anyhow::ensure!(
+                dead_letter.tx_hash.is_some(),
+                "dead letter #{id} doesn't correspond to a transaction; only transactions can be skipped"
+            );
+            tracing::warn!(
+                "Skipping transaction {:?}; the node state will diverge from the main node",
+                dead_letter.tx_hash
+            );
+            resolve(&mut storage, id, DeadLetterResolution::Skip).await
+        }
+    }
+}
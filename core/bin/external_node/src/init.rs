@@ -1,12 +1,20 @@
 //! EN initialization logic.
 
+use std::path::Path;
+
 use anyhow::Context as _;
 use zksync_basic_types::{L1BatchNumber, L2ChainId};
-use zksync_core::sync_layer::genesis::perform_genesis_if_needed;
+use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+use zksync_core::{
+    metadata_calculator::import_tree_snapshot,
+    sync_layer::{genesis::perform_genesis_if_needed, MainNodeClient},
+};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::AppHealthCheck;
 use zksync_object_store::ObjectStoreFactory;
-use zksync_snapshots_applier::SnapshotsApplierConfig;
+use zksync_snapshots_applier::{
+    SnapshotBlobMirror, SnapshotsApplierConfig, TrustedSnapshotSigners,
+};
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::config::read_snapshots_recovery_config;
@@ -25,6 +33,7 @@ pub(crate) async fn ensure_storage_initialized(
     app_health: &AppHealthCheck,
     l2_chain_id: L2ChainId,
     consider_snapshot_recovery: bool,
+    merkle_tree_path: &str,
 ) -> anyhow::Result<()> {
     let mut storage = pool.connection_tagged("en").await?;
     let genesis_l1_batch = storage
@@ -57,7 +66,19 @@ pub(crate) async fn ensure_storage_initialized(
         (None, None) => {
             tracing::info!("Node has neither genesis L1 batch, nor snapshot recovery info");
             if consider_snapshot_recovery {
-                InitDecision::SnapshotRecovery
+                let capabilities = main_node_client
+                    .fetch_capabilities()
+                    .await
+                    .context("failed discovering main node capabilities")?;
+                if capabilities.snapshots() {
+                    InitDecision::SnapshotRecovery
+                } else {
+                    tracing::warn!(
+                        "Snapshot recovery is enabled, but the main node doesn't serve snapshots; \
+                         falling back to syncing from genesis"
+                    );
+                    InitDecision::Genesis
+                }
             } else {
                 InitDecision::Genesis
             }
@@ -82,17 +103,69 @@ pub(crate) async fn ensure_storage_initialized(
 
             tracing::warn!("Proceeding with snapshot recovery. This is an experimental feature; use at your own risk");
             let recovery_config = read_snapshots_recovery_config()?;
-            let blob_store = ObjectStoreFactory::new(recovery_config.snapshots_object_store)
-                .create_store()
-                .await;
+            let primary_config = recovery_config.snapshots_object_store;
+            let blob_store_factory = ObjectStoreFactory::new(primary_config.clone());
+            app_health.insert_component(blob_store_factory.health_check());
+            let blob_store = blob_store_factory.create_store().await;
+            let mut mirrors = Vec::with_capacity(recovery_config.mirror_bucket_urls.len());
+            for bucket_base_url in recovery_config.mirror_bucket_urls {
+                let mirror_config = ObjectStoreConfig {
+                    mode: ObjectStoreMode::GCSAnonymousReadOnly {
+                        bucket_base_url: bucket_base_url.clone(),
+                    },
+                    cold_storage_class: None,
+                    ..primary_config.clone()
+                };
+                mirrors.push(SnapshotBlobMirror {
+                    name: bucket_base_url,
+                    store: ObjectStoreFactory::new(mirror_config).create_store().await,
+                });
+            }
 
-            let config = SnapshotsApplierConfig::default();
+            let trusted_signers = if recovery_config.trusted_signers.is_empty() {
+                tracing::warn!(
+                    "No trusted snapshot signers are configured; snapshot header provenance will not be verified"
+                );
+                None
+            } else {
+                Some(TrustedSnapshotSigners {
+                    chain_id: l2_chain_id,
+                    addresses: recovery_config.trusted_signers,
+                })
+            };
+            let config = SnapshotsApplierConfig {
+                trusted_signers,
+                mirrors,
+                ..SnapshotsApplierConfig::default()
+            };
             app_health.insert_component(config.health_check());
             config
                 .run(pool, main_node_client, &blob_store)
                 .await
                 .context("snapshot recovery failed")?;
             tracing::info!("Snapshot recovery is complete");
+
+            let snapshot_recovery = pool
+                .connection_tagged("en")
+                .await?
+                .snapshot_recovery_dal()
+                .get_applied_snapshot_status()
+                .await?
+                .context("snapshot recovery info is missing after snapshot recovery")?;
+            let import_result = import_tree_snapshot(
+                &*blob_store,
+                snapshot_recovery.l1_batch_number,
+                snapshot_recovery.l1_batch_root_hash,
+                Path::new(merkle_tree_path),
+            )
+            .await;
+            match import_result {
+                Ok(true) => tracing::info!("Merkle tree is imported from the tree snapshot"),
+                Ok(false) => { /* The tree will be initialized as usual */ }
+                Err(err) => tracing::warn!(
+                    "Failed importing Merkle tree snapshot; the tree will be recovered from storage logs: {err:#}"
+                ),
+            }
         }
     }
     Ok(())
@@ -18,8 +18,9 @@ use zksync_core::{
         healthcheck::HealthCheckHandle,
         tree::{TreeApiClient, TreeApiHttpClient},
         tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
-        web3::{ApiBuilder, Namespace},
+        web3::{ApiBuilder, Namespace, ReadOnlyApiServer},
     },
+    block_body_compressor::{BlockBodyCompressor, BlockBodyCompressorConfig},
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert, NodeRole},
     commitment_generator::CommitmentGenerator,
     consensus,
@@ -30,17 +31,18 @@ use zksync_core::{
     },
     l1_gas_price::MainNodeFeeParamsFetcher,
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
+    metrics_snapshotter::{MetricsSnapshotter, MetricsSnapshotterConfig},
     reorg_detector::{self, ReorgDetector},
-    setup_sigint_handler,
+    schema_guard, setup_sigint_handler,
     state_keeper::{
         seal_criteria::NoopSealer, AsyncRocksdbCache, BatchExecutor, MainBatchExecutor,
         OutputHandler, StateKeeperPersistence, ZkSyncStateKeeper,
     },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO, ActionQueue,
-        MainNodeClient, SyncState,
+        MainNodeClient, NodeState, NodeStateHandle, ProtocolVersionCheck, SyncState,
     },
-    utils::ensure_l1_batch_commit_data_generation_mode,
+    utils::{ensure_l1_batch_commit_data_generation_mode, rocksdb_column_families},
 };
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
 use zksync_db_connection::{
@@ -49,18 +51,20 @@ use zksync_db_connection::{
 use zksync_eth_client::clients::QueryClient;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_state::PostgresStorageCaches;
-use zksync_storage::RocksDB;
+use zksync_storage::{RocksDB, RocksDBOptions};
 use zksync_types::L2ChainId;
 use zksync_utils::wait_for_tasks::ManagedTasks;
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::{
     config::{observability::observability_config_from_env, ExternalNodeConfig},
+    dead_letters::DeadLetterCommand,
     helpers::MainNodeHealthCheck,
     init::ensure_storage_initialized,
 };
 
 mod config;
+mod dead_letters;
 mod helpers;
 mod init;
 mod metrics;
@@ -78,14 +82,24 @@ async fn build_state_keeper(
     output_handler: OutputHandler,
     stop_receiver: watch::Receiver<bool>,
     chain_id: L2ChainId,
+    stale_fee_params_receiver: watch::Receiver<bool>,
     task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
 ) -> anyhow::Result<ZkSyncStateKeeper> {
-    // We only need call traces on the external node if the `debug_` namespace is enabled.
-    let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
+    // We only need call traces on the external node if the `debug_` or `trace_` namespace is on.
+    let api_namespaces = config.optional.api_namespaces();
+    let save_call_traces =
+        api_namespaces.contains(&Namespace::Debug) || api_namespaces.contains(&Namespace::Trace);
 
     let (storage_factory, task) = AsyncRocksdbCache::new(
         connection_pool.clone(),
         state_keeper_db_path,
+        RocksDBOptions {
+            block_cache_capacity: config.optional.state_keeper_db_block_cache_size(),
+            column_families: rocksdb_column_families(
+                &config.optional.state_keeper_db_column_families,
+            ),
+            ..RocksDBOptions::default()
+        },
         config.optional.enum_index_migration_chunk_size,
     );
     let mut stop_receiver_clone = stop_receiver.clone();
@@ -94,16 +108,15 @@ async fn build_state_keeper(
         stop_receiver_clone.changed().await?;
         result
     }));
-    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(MainBatchExecutor::new(
-        Arc::new(storage_factory),
-        save_call_traces,
-        true,
-    ));
+    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(
+        MainBatchExecutor::new(Arc::new(storage_factory), save_call_traces, true)
+            .with_parallel_reexecution(config.optional.parallel_tx_reexecution_workers),
+    );
 
     let main_node_url = config.required.main_node_url()?;
     let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
         .context("Failed creating JSON-RPC client for main node")?;
-    let io = ExternalIO::new(
+    let mut io = ExternalIO::new(
         connection_pool,
         action_queue,
         Box::new(main_node_client),
@@ -111,6 +124,9 @@ async fn build_state_keeper(
     )
     .await
     .context("Failed initializing I/O for external node state keeper")?;
+    if config.optional.stop_sealing_on_stale_fee_params {
+        io = io.with_stale_fee_params_guard(stale_fee_params_receiver);
+    }
 
     Ok(ZkSyncStateKeeper::new(
         stop_receiver,
@@ -134,34 +150,39 @@ async fn run_tree(
         mode: MerkleTreeMode::Lightweight,
         delay_interval: config.optional.metadata_calculator_delay(),
         max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
+        max_batches_per_commit: config.optional.max_batches_per_tree_commit,
         multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
         block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
         memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
         stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+        hashing_thread_count: config.optional.merkle_tree_hashing_thread_count,
+        rebuild_from_postgres: config.optional.merkle_tree_rebuild_from_postgres,
+        column_families: rocksdb_column_families(&config.optional.merkle_tree_column_families),
+        proof_data_follower_path: None,
     };
     let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
         .await
         .context("failed initializing metadata calculator")?;
-    let tree_reader = Arc::new(metadata_calculator.tree_reader());
+    let reader_pool_size = api_config
+        .map_or_else(MerkleTreeApiConfig::default_reader_pool_size, |config| {
+            config.reader_pool_size
+        });
+    let tree_reader_pool = metadata_calculator.tree_reader_pool(reader_pool_size);
     app_health.insert_component(metadata_calculator.tree_health_check());
 
     if let Some(api_config) = api_config {
         let address = (Ipv4Addr::UNSPECIFIED, api_config.port).into();
-        let tree_reader = metadata_calculator.tree_reader();
+        let tree_reader_pool = tree_reader_pool.clone();
         let stop_receiver = stop_receiver.clone();
-        task_futures.push(tokio::spawn(async move {
-            tree_reader
-                .wait()
-                .await
-                .run_api_server(address, stop_receiver)
-                .await
-        }));
+        task_futures.push(tokio::spawn(
+            tree_reader_pool.run_api_server(address, stop_receiver),
+        ));
     }
 
     let tree_handle = task::spawn(metadata_calculator.run(tree_pool, stop_receiver));
 
     task_futures.push(tree_handle);
-    Ok(tree_reader)
+    Ok(Arc::new(tree_reader_pool))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -178,13 +199,28 @@ async fn run_core(
     // Create components.
     let sync_state = SyncState::default();
     app_health.insert_custom_component(Arc::new(sync_state.clone()));
-    let (action_queue_sender, action_queue) = ActionQueue::new();
+    // Report protocol upgrades on the main node that this node cannot execute. Syncing stops
+    // before the first block with such a version instead of failing.
+    let protocol_version_check =
+        ProtocolVersionCheck::new(Box::new(main_node_client.clone()), sync_state.clone());
+    task_handles.push(tokio::spawn(
+        protocol_version_check.run(stop_receiver.clone()),
+    ));
+    let (action_queue_sender, action_queue) =
+        ActionQueue::with_capacity(config.optional.action_queue_capacity);
 
     let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
         connection_pool.clone(),
         config.remote.l2_erc20_bridge_addr,
         config.optional.miniblock_seal_queue_capacity,
     );
+    let miniblock_sealer = match config.optional.miniblock_seal_batch_latency_target() {
+        Some(latency_target) => miniblock_sealer.with_adaptive_batching(
+            latency_target,
+            config.optional.miniblock_seal_batch_max_delay(),
+        ),
+        None => miniblock_sealer,
+    };
     task_handles.push(tokio::spawn(miniblock_sealer.run()));
 
     let mut persistence = persistence.with_tx_insertion();
@@ -205,6 +241,7 @@ async fn run_core(
         output_handler,
         stop_receiver.clone(),
         config.remote.l2_chain_id,
+        fee_params_fetcher.stale_fee_params_receiver(),
         task_handles,
     )
     .await?;
@@ -213,6 +250,14 @@ async fn run_core(
         let ctx = ctx::root();
         let cfg = config.consensus.clone();
         let mut stop_receiver = stop_receiver.clone();
+        let block_signer =
+            config
+                .optional
+                .main_node_block_signer_addr
+                .map(|address| consensus::BlockSigner {
+                    chain_id: config.remote.l2_chain_id,
+                    address,
+                });
         let fetcher = consensus::Fetcher {
             store: consensus::Store(connection_pool.clone()),
             sync_state: sync_state.clone(),
@@ -224,19 +269,64 @@ async fn run_core(
                     refresh: time::Duration::milliseconds(30),
                 },
             ),
+            block_signer,
         };
+        let attestor = (cfg.is_some() && config.optional.consensus_attestor_mode).then(|| {
+            consensus::Fetcher {
+                store: consensus::Store(connection_pool.clone()),
+                sync_state: sync_state.clone(),
+                client: Box::new(main_node_client.clone()),
+                limiter: limiter::Limiter::new(
+                    &ctx,
+                    limiter::Rate {
+                        burst: 10,
+                        refresh: time::Duration::milliseconds(30),
+                    },
+                ),
+                block_signer,
+            }
+        });
+        let reserved_addrs: [(&str, std::net::SocketAddr); 3] = [
+            (
+                "HTTP API server",
+                ([0, 0, 0, 0], config.required.http_port).into(),
+            ),
+            (
+                "WS API server",
+                ([0, 0, 0, 0], config.required.ws_port).into(),
+            ),
+            (
+                "healthcheck server",
+                ([0, 0, 0, 0], config.required.healthcheck_port).into(),
+            ),
+        ];
         let actions = action_queue_sender;
         async move {
             scope::run!(&ctx, |ctx, s| async {
                 s.spawn_bg(async {
-                    let res = match cfg {
-                        Some(cfg) => {
+                    let res = match (cfg, attestor) {
+                        (Some(cfg), Some(attestor)) => {
                             let secrets = config::read_consensus_secrets()
                                 .context("config::read_consensus_secrets()")?
                                 .context("consensus secrets missing")?;
-                            fetcher.run_p2p(ctx, actions, cfg.p2p(&secrets)?).await
+                            cfg.validate(&secrets, &reserved_addrs)?;
+                            let p2p = cfg.p2p(&secrets)?;
+                            scope::run!(ctx, |ctx, s| async {
+                                s.spawn_bg(attestor.run_attestor(ctx, p2p));
+                                fetcher.run_centralized(ctx, actions).await
+                            })
+                            .await
                         }
-                        None => fetcher.run_centralized(ctx, actions).await,
+                        (Some(cfg), None) => {
+                            let secrets = config::read_consensus_secrets()
+                                .context("config::read_consensus_secrets()")?
+                                .context("consensus secrets missing")?;
+                            cfg.validate(&secrets, &reserved_addrs)?;
+                            fetcher
+                                .run_p2p(ctx, actions, cfg.p2p(&secrets)?, cfg.admin(&secrets)?)
+                                .await
+                        }
+                        (None, _) => fetcher.run_centralized(ctx, actions).await,
                     };
                     tracing::info!("Consensus actor stopped");
                     res
@@ -264,6 +354,7 @@ async fn run_core(
     let fee_address_migration_handle =
         task::spawn(state_keeper.run_fee_address_migration(connection_pool.clone()));
     let sk_handle = task::spawn(state_keeper.run());
+    app_health.insert_component(fee_params_fetcher.health_check());
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
     let remote_diamond_proxy_addr = config.remote.diamond_proxy_addr;
@@ -351,6 +442,35 @@ async fn run_core(
     Ok(sync_state)
 }
 
+/// Spawns read-only API servers serving requests while the node storage is being initialized
+/// or rolled back. The servers stop once the node becomes ready.
+fn spawn_read_only_api(
+    config: &ExternalNodeConfig,
+    components: &HashSet<Component>,
+    node_state: &NodeStateHandle,
+    stop_receiver: watch::Receiver<bool>,
+) -> Vec<JoinHandle<anyhow::Result<()>>> {
+    let mut servers = vec![];
+    if components.contains(&Component::HttpApi) {
+        servers.push(ReadOnlyApiServer::http(
+            config.clone().into(),
+            config.required.http_port,
+            node_state.clone(),
+        ));
+    }
+    if components.contains(&Component::WsApi) {
+        servers.push(ReadOnlyApiServer::ws(
+            config.clone().into(),
+            config.required.ws_port,
+            node_state.clone(),
+        ));
+    }
+    servers
+        .into_iter()
+        .map(|server| tokio::spawn(server.run(stop_receiver.clone())))
+        .collect()
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_api(
     config: &ExternalNodeConfig,
@@ -451,6 +571,22 @@ async fn run_api(
             .with_tree_api(tree_reader.clone())
             .with_sync_state(sync_state.clone())
             .enable_api_namespaces(config.optional.api_namespaces());
+        let builder = match config.optional.api_request_timeout() {
+            Some(timeout) => builder.with_request_timeout(timeout),
+            None => builder,
+        };
+        let builder = match config.optional.api_method_requests_per_minute_limit {
+            Some(limit) => builder.with_method_requests_per_minute_limit(limit),
+            None => builder,
+        };
+        let builder = match config.optional.api_client_requests_per_minute_limit {
+            Some(limit) => builder.with_client_requests_per_minute_limit(limit),
+            None => builder,
+        };
+        let builder = match config.optional.api_max_concurrent_requests {
+            Some(limit) => builder.with_max_concurrent_requests(limit),
+            None => builder,
+        };
 
         let http_server_handles = builder
             .build()
@@ -475,6 +611,22 @@ async fn run_api(
             .with_tree_api(tree_reader)
             .with_sync_state(sync_state)
             .enable_api_namespaces(config.optional.api_namespaces());
+        let builder = match config.optional.api_request_timeout() {
+            Some(timeout) => builder.with_request_timeout(timeout),
+            None => builder,
+        };
+        let builder = match config.optional.api_method_requests_per_minute_limit {
+            Some(limit) => builder.with_method_requests_per_minute_limit(limit),
+            None => builder,
+        };
+        let builder = match config.optional.api_client_requests_per_minute_limit {
+            Some(limit) => builder.with_client_requests_per_minute_limit(limit),
+            None => builder,
+        };
+        let builder = match config.optional.api_max_concurrent_requests {
+            Some(limit) => builder.with_max_concurrent_requests(limit),
+            None => builder,
+        };
 
         let ws_server_handles = builder
             .build()
@@ -552,6 +704,7 @@ async fn init_tasks(
                     .tree_component
                     .api_port
                     .context("should contain tree api port")?,
+                reader_pool_size: MerkleTreeApiConfig::default_reader_pool_size(),
             })
         } else {
             None
@@ -571,7 +724,16 @@ async fn init_tasks(
         None
     };
 
-    let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
+    let mut fee_params_fetcher =
+        MainNodeFeeParamsFetcher::new(main_node_client.clone(), connection_pool.clone())
+            .await
+            .context("failed initializing main node fee params fetcher")?
+            .with_poll_interval(config.optional.fee_params_poll_interval())
+            .with_max_stale_intervals(config.optional.fee_params_max_stale_intervals);
+    if let Some(ws_url) = &config.optional.main_node_ws_url {
+        fee_params_fetcher = fee_params_fetcher.with_subscription(ws_url.clone());
+    }
+    let fee_params_fetcher = Arc::new(fee_params_fetcher);
 
     let sync_state = if components.contains(&Component::Core) {
         run_core(
@@ -614,6 +776,28 @@ async fn init_tasks(
         .await?;
     }
 
+    if let Some(interval) = config.optional.metrics_snapshots_interval() {
+        let snapshotter_config = MetricsSnapshotterConfig {
+            interval,
+            ..MetricsSnapshotterConfig::default()
+        };
+        let pool = ConnectionPool::singleton(&config.postgres.database_url)
+            .build()
+            .await
+            .context("failed to build a metrics_snapshotter_pool")?;
+        let metrics_snapshotter = MetricsSnapshotter::new(snapshotter_config, pool);
+        task_handles.push(tokio::spawn(metrics_snapshotter.run(stop_receiver.clone())));
+    }
+
+    if config.optional.compress_block_bodies {
+        let pool = ConnectionPool::singleton(&config.postgres.database_url)
+            .build()
+            .await
+            .context("failed to build a block_body_compressor_pool")?;
+        let compressor = BlockBodyCompressor::new(BlockBodyCompressorConfig::default(), pool);
+        task_handles.push(tokio::spawn(compressor.run(stop_receiver.clone())));
+    }
+
     if let Some(port) = config.optional.prometheus_port {
         let (prometheus_health_check, prometheus_health_updater) =
             ReactiveHealthCheck::new("prometheus_exporter");
@@ -667,6 +851,32 @@ struct Cli {
     /// Comma-separated list of components to launch.
     #[arg(long, default_value = "all")]
     components: ComponentsToRun,
+    /// Print unresolved sync actions that the node has failed to apply (dead letters) and exit.
+    #[arg(long)]
+    list_dead_letters: bool,
+    /// Mark the dead letter with the specified ID to be retried and exit. The failed action is re-applied
+    /// once the node is restarted (e.g., after it was updated).
+    #[arg(long, value_name = "ID")]
+    retry_dead_letter: Option<u64>,
+    /// Mark the transaction from the dead letter with the specified ID to be skipped and exit. Skipping a transaction
+    /// makes the node state diverge from the main node, so this requires `--allow-state-divergence`.
+    #[arg(long, value_name = "ID", requires = "allow_state_divergence")]
+    skip_dead_letter: Option<u64>,
+    /// Acknowledge that skipping a transaction makes the node state diverge from the main node.
+    #[arg(long)]
+    allow_state_divergence: bool,
+}
+
+impl Cli {
+    fn dead_letter_command(&self) -> Option<DeadLetterCommand> {
+        if self.list_dead_letters {
+            Some(DeadLetterCommand::List)
+        } else if let Some(id) = self.retry_dead_letter {
+            Some(DeadLetterCommand::Retry(id))
+        } else {
+            self.skip_dead_letter.map(DeadLetterCommand::Skip)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
@@ -753,9 +963,15 @@ async fn main() -> anyhow::Result<()> {
     if let Some(threshold) = config.optional.slow_query_threshold() {
         ConnectionPool::<Core>::global_config().set_slow_query_threshold(threshold)?;
     }
+    if let Some(timeout) = config.optional.query_timeout() {
+        ConnectionPool::<Core>::global_config().set_query_timeout(timeout)?;
+    }
     if let Some(threshold) = config.optional.long_connection_threshold() {
         ConnectionPool::<Core>::global_config().set_long_connection_threshold(threshold)?;
     }
+    if let Some(schema) = &config.postgres.schema {
+        ConnectionPool::<Core>::set_global_schema(schema)?;
+    }
 
     let connection_pool = ConnectionPool::<Core>::builder(
         &config.postgres.database_url,
@@ -764,6 +980,10 @@ async fn main() -> anyhow::Result<()> {
     .build()
     .await
     .context("failed to build a connection_pool")?;
+    schema_guard::ensure_compatibility(&connection_pool).await?;
+    if let Some(command) = opt.dead_letter_command() {
+        return dead_letters::run_command(&connection_pool, command).await;
+    }
 
     let main_node_url = config
         .required
@@ -813,6 +1033,19 @@ async fn main() -> anyhow::Result<()> {
         }),
     ];
 
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let node_state = NodeStateHandle::new(NodeState::InitializingStorage);
+    let read_only_api_tasks = if config.optional.read_only_api_during_recovery {
+        spawn_read_only_api(
+            &config,
+            &opt.components.0,
+            &node_state,
+            stop_receiver.clone(),
+        )
+    } else {
+        vec![]
+    };
+
     // Make sure that the node storage is initialized either via genesis or snapshot recovery.
     ensure_storage_initialized(
         &connection_pool,
@@ -820,6 +1053,7 @@ async fn main() -> anyhow::Result<()> {
         &app_health,
         config.remote.l2_chain_id,
         opt.enable_snapshots_recovery,
+        &config.required.merkle_tree_path,
     )
     .await?;
     let sigint_receiver = setup_sigint_handler();
@@ -833,6 +1067,7 @@ async fn main() -> anyhow::Result<()> {
         connection_pool.clone(),
         L1ExecutedBatchesRevert::Allowed,
     );
+    reverter.complete_interrupted_rollbacks().await;
 
     let mut reorg_detector = ReorgDetector::new(main_node_client.clone(), connection_pool.clone());
     // We're checking for the reorg in the beginning because we expect that if reorg is detected during
@@ -842,6 +1077,7 @@ async fn main() -> anyhow::Result<()> {
     match reorg_detector.check_consistency().await {
         Ok(()) => {}
         Err(reorg_detector::Error::ReorgDetected(last_correct_l1_batch)) => {
+            node_state.set(NodeState::RollingBack);
             tracing::info!("Rolling back to l1 batch number {last_correct_l1_batch}");
             reverter
                 .rollback_db(last_correct_l1_batch, BlockReverterFlags::all())
@@ -851,6 +1087,7 @@ async fn main() -> anyhow::Result<()> {
         Err(err) => return Err(err).context("reorg_detector.check_consistency()"),
     }
     if opt.revert_pending_l1_batch {
+        node_state.set(NodeState::RollingBack);
         tracing::info!("Rolling pending L1 batch back..");
         let mut connection = connection_pool.connection().await?;
         let sealed_l1_batch_number = connection
@@ -870,7 +1107,12 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("Rollback successfully completed");
     }
 
-    let (stop_sender, stop_receiver) = watch::channel(false);
+    node_state.set(NodeState::Ready);
+    // Read-only API servers must stop before the fully functional servers bind to the same ports.
+    for task in read_only_api_tasks {
+        task.await.context("read-only API server panicked")??;
+    }
+
     init_tasks(
         &config,
         connection_pool.clone(),
@@ -0,0 +1,172 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_config::{
+    configs::ObservabilityConfig, ContractsConfig, ETHConfig, NetworkConfig, PostgresConfig,
+};
+use zksync_core::governance::{GovernanceOperator, GovernanceProposal};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_env_config::FromEnv;
+use zksync_eth_client::clients::PKSigningClient;
+use zksync_types::H256;
+
+/// Environment variable with the private key of the governance contract owner.
+const GOVERNOR_PRIVATE_KEY_VAR: &str = "GOVERNOR_PRIVATE_KEY";
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Proposes, schedules and executes governance operations on L1",
+    long_about = None
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Simulates scheduling of a proposal on L1 without recording it or sending any transactions.
+    #[command(name = "dry-run")]
+    DryRun {
+        /// Path to the JSON file with the proposal.
+        #[arg(long)]
+        proposal: PathBuf,
+    },
+    /// Simulates a proposal and records it in the audit trail.
+    Propose {
+        /// Path to the JSON file with the proposal.
+        #[arg(long)]
+        proposal: PathBuf,
+    },
+    /// Sends the transaction scheduling a proposed operation to L1 and waits until it's mined.
+    Schedule {
+        /// ID of the operation returned by the `propose` command.
+        #[arg(long)]
+        operation_id: H256,
+    },
+    /// Sends the transaction executing a scheduled operation to L1 and waits until it's mined.
+    Execute {
+        /// ID of the operation returned by the `propose` command.
+        #[arg(long)]
+        operation_id: H256,
+    },
+    /// Outputs the audit trail record for an operation.
+    Status {
+        /// ID of the operation returned by the `propose` command.
+        #[arg(long)]
+        operation_id: H256,
+    },
+}
+
+async fn read_proposal(path: &Path) -> anyhow::Result<GovernanceProposal> {
+    let raw_proposal = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed reading proposal from {path:?}"))?;
+    serde_json::from_slice(&raw_proposal)
+        .with_context(|| format!("failed parsing proposal from {path:?}"))
+}
+
+fn create_operator(
+    connection_pool: ConnectionPool<Core>,
+    eth_config: &ETHConfig,
+    contracts: &ContractsConfig,
+    network_config: &NetworkConfig,
+) -> anyhow::Result<GovernanceOperator> {
+    let private_key = std::env::var(GOVERNOR_PRIVATE_KEY_VAR)
+        .with_context(|| format!("{GOVERNOR_PRIVATE_KEY_VAR} is not set"))?;
+    let private_key: H256 = private_key
+        .parse()
+        .with_context(|| format!("{GOVERNOR_PRIVATE_KEY_VAR} is not a valid private key"))?;
+    let default_priority_fee_per_gas = eth_config
+        .gas_adjuster
+        .context("gas_adjuster")?
+        .default_priority_fee_per_gas;
+    let eth_client = PKSigningClient::new_raw(
+        private_key,
+        contracts.diamond_proxy_addr,
+        default_priority_fee_per_gas,
+        network_config.network.chain_id(),
+        &eth_config.web3_url,
+    );
+    Ok(GovernanceOperator::new(
+        connection_pool,
+        Arc::new(eth_client),
+        contracts,
+    ))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let cli = Cli::parse();
+    let eth_config = ETHConfig::from_env().context("ETHConfig::from_env()")?;
+    let contracts = ContractsConfig::from_env().context("ContractsConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+
+    let connection_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let operator = || {
+        create_operator(
+            connection_pool.clone(),
+            &eth_config,
+            &contracts,
+            &network_config,
+        )
+    };
+
+    match cli.command {
+        Command::DryRun { proposal } => {
+            let proposal = read_proposal(&proposal).await?;
+            let operation_id = operator()?.dry_run(&proposal).await?;
+            println!("Simulation succeeded; operation ID: {operation_id:?}");
+        }
+        Command::Propose { proposal } => {
+            let proposal = read_proposal(&proposal).await?;
+            let operation_id = operator()?.propose(&proposal).await?;
+            println!("Proposed operation {operation_id:?}");
+        }
+        Command::Schedule { operation_id } => {
+            let tx_hash = operator()?.schedule(operation_id).await?;
+            println!("Scheduled operation {operation_id:?} in L1 transaction {tx_hash:?}");
+        }
+        Command::Execute { operation_id } => {
+            let tx_hash = operator()?.execute(operation_id).await?;
+            println!("Executed operation {operation_id:?} in L1 transaction {tx_hash:?}");
+        }
+        Command::Status { operation_id } => {
+            let mut storage = connection_pool.connection().await?;
+            let operation = storage
+                .governance_dal()
+                .get_operation(operation_id)
+                .await?
+                .with_context(|| {
+                    format!("governance operation {operation_id:?} is not proposed")
+                })?;
+            println!("{operation:#?}");
+        }
+    }
+    Ok(())
+}
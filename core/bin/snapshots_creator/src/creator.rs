@@ -9,8 +9,8 @@ use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_object_store::ObjectStore;
 use zksync_types::{
     snapshots::{
-        uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotMetadata,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     L1BatchNumber, MiniblockNumber,
 };
@@ -87,7 +87,13 @@ impl SnapshotCreator {
             return Ok(());
         }
 
-        let hashed_keys_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number,
+            chunk_id,
+        };
+        // Chunk contents are determined by the chunk ID, so that a chunk has the same contents
+        // regardless of the order in which chunks are processed.
+        let hashed_keys_range = key.hashed_keys_range(chunk_count);
         let mut conn = self.connect_to_replica().await?;
 
         let latency =
@@ -107,10 +113,7 @@ impl SnapshotCreator {
         let latency =
             METRICS.storage_logs_processing_duration[&StorageChunkStage::SaveToGcs].start();
         let storage_logs_chunk = SnapshotStorageLogsChunk { storage_logs: logs };
-        let key = SnapshotStorageLogsStorageKey {
-            l1_batch_number,
-            chunk_id,
-        };
+        let content_hash = storage_logs_chunk.content_hash();
         let filename = self
             .blob_store
             .put(key, &storage_logs_chunk)
@@ -128,7 +131,12 @@ impl SnapshotCreator {
             .await?;
         master_conn
             .snapshots_dal()
-            .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &output_filepath)
+            .add_storage_logs_filepath_for_snapshot(
+                l1_batch_number,
+                chunk_id,
+                &output_filepath,
+                content_hash,
+            )
             .await?;
         #[cfg(test)]
         self.event_listener.on_chunk_saved();
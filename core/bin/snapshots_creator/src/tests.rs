@@ -152,6 +152,7 @@ async fn create_miniblock(
         protocol_version: Some(Default::default()),
         virtual_blocks: 0,
         gas_limit: 0,
+        logs_bloom: Default::default(),
     };
 
     conn.blocks_dal()
@@ -340,6 +341,21 @@ async fn persisting_snapshot_logs() {
 
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
+
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    for (chunk_id, content_hash) in snapshot_metadata.storage_logs_hashes.iter().enumerate() {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+        assert_eq!(*content_hash, Some(chunk.content_hash()));
+    }
 }
 
 async fn assert_storage_logs(
@@ -9,21 +9,27 @@ use zksync_config::{
             CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
             StateKeeperConfig,
         },
+        eth_sender::PubdataSendingMode,
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        ContractsConfig, FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
-        FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, ObservabilityConfig,
-        PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        wallets::Wallets,
+        ContractsConfig, DataRetentionConfig, FinalityWebhooksConfig, FriProofCompressorConfig,
+        FriProverConfig, FriProverGatewayConfig, FriWitnessGeneratorConfig,
+        FriWitnessVectorGeneratorConfig, GeneralConfig, ObservabilityConfig, PrometheusConfig,
+        ProofDataHandlerConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, ETHConfig, ETHWatchConfig, GasAdjusterConfig,
     GenesisConfig, ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
 use zksync_core::{
-    genesis, genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
+    config_export::ConfigExport,
+    doctor::Doctor,
+    ensure_schema_compatibility, genesis, genesis_init, initialize_components, is_genesis_needed,
+    setup_sigint_handler,
     temp_config_store::{decode_yaml, decode_yaml_repr, Secrets, TempConfigStore},
     Component, Components,
 };
-use zksync_env_config::FromEnv;
+use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::ManagedTasks;
 
@@ -44,6 +50,13 @@ struct Cli {
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
+    /// Check the environment (Postgres, RocksDB directories, object store, L1 RPC, etc.), print the report and exit.
+    #[arg(long)]
+    doctor: bool,
+    /// Print the effective general config (after applying defaults and env overrides) with secrets redacted
+    /// in the YAML format and exit.
+    #[arg(long)]
+    dump_config: bool,
     /// Comma-separated list of components to launch.
     #[arg(
         long,
@@ -91,16 +104,23 @@ async fn main() -> anyhow::Result<()> {
     // Load env config and use it if file config is not provided
     let tmp_config = load_env_config()?;
 
-    let configs = match opt.config_path {
+    let configs = match &opt.config_path {
         None => tmp_config.general(),
         Some(path) => {
-            let yaml =
-                std::fs::read_to_string(&path).with_context(|| path.display().to_string())?;
+            let yaml = std::fs::read_to_string(path).with_context(|| path.display().to_string())?;
             decode_yaml_repr::<zksync_protobuf_config::proto::general::GeneralConfig>(&yaml)
                 .context("failed decoding general YAML config")?
         }
     };
 
+    if opt.dump_config {
+        let yaml = ConfigExport::new(configs)
+            .to_yaml()
+            .context("failed exporting config")?;
+        print!("{yaml}");
+        return Ok(());
+    }
+
     let observability_config = configs
         .observability
         .clone()
@@ -128,7 +148,11 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let wallets = match opt.wallets_path {
-        None => tmp_config.wallets(),
+        None => {
+            let mut wallets = tmp_config.wallets();
+            wallets.signer = Wallets::from_env()?.signer;
+            wallets
+        }
         Some(path) => {
             let yaml =
                 std::fs::read_to_string(&path).with_context(|| path.display().to_string())?;
@@ -172,6 +196,11 @@ async fn main() -> anyhow::Result<()> {
 
     let postgres_config = configs.postgres_config.clone().context("PostgresConfig")?;
 
+    if opt.doctor {
+        return run_doctor(&configs, postgres_config).await;
+    }
+
+    ensure_schema_compatibility(&postgres_config).await?;
     if opt.genesis || is_genesis_needed(&postgres_config).await {
         genesis_init(genesis.clone(), &postgres_config)
             .await
@@ -205,6 +234,7 @@ async fn main() -> anyhow::Result<()> {
     // Run core actors.
     let (core_task_handles, stop_sender, health_check_handle) = initialize_components(
         &configs,
+        opt.config_path.as_deref(),
         &wallets,
         &genesis,
         &contracts_config,
@@ -242,6 +272,36 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_doctor(
+    configs: &GeneralConfig,
+    postgres_config: PostgresConfig,
+) -> anyhow::Result<()> {
+    let mut doctor = Doctor::new().with_postgres(postgres_config);
+    if let Some(db_config) = &configs.db_config {
+        doctor = doctor
+            .with_rocksdb_path(&db_config.state_keeper_db_path)
+            .with_rocksdb_path(&db_config.merkle_tree.path);
+    }
+    let object_store_config = configs
+        .prover_config
+        .as_ref()
+        .and_then(|config| config.object_store.clone());
+    if let Some(object_store_config) = object_store_config {
+        doctor = doctor.with_object_store(object_store_config);
+    }
+    if let Some(eth) = &configs.eth {
+        let require_blobs = eth.sender.as_ref().map_or(false, |sender| {
+            sender.pubdata_sending_mode == PubdataSendingMode::Blobs
+        });
+        doctor = doctor.with_l1_rpc(&eth.web3_url, require_blobs);
+    }
+
+    let report = doctor.run().await;
+    println!("{report}");
+    anyhow::ensure!(!report.has_failures(), "environment checks have failed");
+    Ok(())
+}
+
 fn load_env_config() -> anyhow::Result<TempConfigStore> {
     Ok(TempConfigStore {
         postgres_config: PostgresConfig::from_env().ok(),
@@ -273,6 +333,14 @@ fn load_env_config() -> anyhow::Result<TempConfigStore> {
         gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
         object_store_config: ObjectStoreConfig::from_env().ok(),
         observability: ObservabilityConfig::from_env().ok(),
-        snapshot_creator: SnapshotsCreatorConfig::from_env().ok(),
+        snapshot_creator: SnapshotsCreatorConfig::from_env().ok().map(|mut config| {
+            // The snapshots object store is configured separately from the snapshot creator itself.
+            config.object_store = SnapshotsObjectStoreConfig::from_env()
+                .ok()
+                .map(|store_config| store_config.0);
+            config
+        }),
+        finality_webhooks_config: FinalityWebhooksConfig::from_env().ok(),
+        data_retention_config: DataRetentionConfig::from_env().ok(),
     })
 }
@@ -48,11 +48,22 @@ pub struct Web3JsonRpcConfig {
     pub pubsub_polling_interval: Option<u64>,
     /// Tx nonce: how far ahead from the committed nonce can it be.
     pub max_nonce_ahead: u32,
+    /// Minimum bump of `max_fee_per_gas` and `max_priority_fee_per_gas` (in percent) required for a transaction
+    /// to replace a pending transaction with the same initiator and nonce. If not set, pending transactions
+    /// can be replaced without a fee bump.
+    pub replacement_fee_bump_percent: Option<u32>,
+    /// Maximum number of pending (i.e., not included into a miniblock) transactions per initiator account.
+    /// Transactions replacing pending transactions are not affected by the limit. If not set, the number
+    /// of pending transactions is only limited by `max_nonce_ahead`.
+    pub max_pending_txs_per_account: Option<u32>,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block
     pub gas_price_scale_factor: f64,
     /// Timeout for requests (in s)
     pub request_timeout: Option<u64>,
+    /// Deadline for handling a single JSON-RPC request (in s). Once it has passed, VM execution and potentially
+    /// expensive DB queries performed for the request are aborted. If not set, requests are not timed out.
+    pub request_deadline_sec: Option<u64>,
     /// Private keys for accounts managed by node
     pub account_pks: Option<Vec<H256>>,
     /// The factor by which to scale the gasLimit
@@ -91,8 +102,20 @@ pub struct Web3JsonRpcConfig {
     pub max_response_body_size_mb: Option<usize>,
     /// Maximum number of requests per minute for the WebSocket server.
     /// The value is per active connection.
-    /// Note: For HTTP, rate limiting is expected to be configured on the infra level.
+    /// Note: For HTTP, per-client rate limiting is configured with `client_requests_per_minute_limit`.
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of requests per minute for each RPC method, shared by all clients of a server.
+    /// Requests exceeding the limit are rejected with the -32005 error. If not set, methods are not rate-limited.
+    pub method_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of requests per minute from a single client IP address, as reported by a reverse proxy
+    /// in the `X-Forwarded-For` header. Requests exceeding the limit are rejected with the -32005 error;
+    /// requests without the header are not limited. For the WS server, the limit applies to connection handshakes.
+    /// If not set, clients are not rate-limited.
+    pub client_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of requests handled concurrently by each server (HTTP and WS servers are limited separately).
+    /// Once the limit is reached, new requests are rejected with the -32005 error. If not set, the number
+    /// of concurrent requests is not limited.
+    pub max_concurrent_requests: Option<usize>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
     /// Polling period for mempool cache update - how often the mempool cache is updated from the database.
@@ -100,6 +123,33 @@ pub struct Web3JsonRpcConfig {
     pub mempool_cache_update_interval: Option<u64>,
     /// Maximum number of transactions to be stored in the mempool cache. Default is 10000.
     pub mempool_cache_size: Option<usize>,
+    /// Size of the cache for immutable lookups (factory deps by hash, protocol versions and details
+    /// of executed miniblocks) in MiBs. Default is 0, i.e., the cache is disabled.
+    pub lookup_cache_size_mb: Option<usize>,
+    /// Whether to sign L2 blocks returned by `en_syncL2Block` with the signer wallet key, so that external nodes
+    /// can detect modification of sync data in transit.
+    #[serde(default)]
+    pub sign_sync_blocks: bool,
+    /// Whether to sign snapshot headers returned by the `snapshots` namespace with the signer wallet key, so that
+    /// external nodes can verify snapshot provenance before recovery.
+    #[serde(default)]
+    pub sign_snapshot_headers: bool,
+    /// Maximum lag of the Merkle tree (in L1 batches) relative to the latest sealed L1 batch. If the lag exceeds
+    /// this value, the API server health is reported as affected. Only applies if the tree runs in the same process
+    /// as the API server. If not set, the tree lag is not monitored.
+    pub tree_lag_limit: Option<u32>,
+    /// Whether to reject `zks_getProof` requests while the tree lag exceeds `tree_lag_limit`.
+    #[serde(default)]
+    pub reject_proofs_on_tree_lag: bool,
+    /// Whether to serve `zks_mempoolStatus` and `zks_mempoolContent` methods exposing the state of the mempool.
+    /// These methods are intended for operators and should not be enabled on public endpoints.
+    #[serde(default)]
+    pub mempool_inspection_enabled: bool,
+    /// Whether to serve the `zks_getContractExecutionStats` method exposing execution metrics of transactions
+    /// recorded by the state keeper. This method is intended for operators and should not be enabled
+    /// on public endpoints.
+    #[serde(default)]
+    pub contract_execution_stats_enabled: bool,
 }
 
 impl Web3JsonRpcConfig {
@@ -118,8 +168,11 @@ impl Web3JsonRpcConfig {
             subscriptions_limit: Some(10000),
             pubsub_polling_interval: Some(200),
             max_nonce_ahead: 50,
+            replacement_fee_bump_percent: None,
+            max_pending_txs_per_account: None,
             gas_price_scale_factor: 1.2,
             request_timeout: Default::default(),
+            request_deadline_sec: None,
             account_pks: Default::default(),
             estimate_gas_scale_factor: 1.2,
             estimate_gas_acceptable_overestimation: 1000,
@@ -134,8 +187,18 @@ impl Web3JsonRpcConfig {
             max_batch_request_size: Default::default(),
             max_response_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
+            method_requests_per_minute_limit: None,
+            client_requests_per_minute_limit: None,
+            max_concurrent_requests: None,
             mempool_cache_update_interval: Default::default(),
             mempool_cache_size: Default::default(),
+            lookup_cache_size_mb: Default::default(),
+            sign_sync_blocks: false,
+            sign_snapshot_headers: false,
+            tree_lag_limit: None,
+            reject_proofs_on_tree_lag: false,
+            mempool_inspection_enabled: false,
+            contract_execution_stats_enabled: false,
             tree_api_url: None,
         }
     }
@@ -168,6 +231,10 @@ impl Web3JsonRpcConfig {
         Duration::from_secs(self.request_timeout.unwrap_or(10))
     }
 
+    pub fn request_deadline(&self) -> Option<Duration> {
+        self.request_deadline_sec.map(Duration::from_secs)
+    }
+
     pub fn account_pks(&self) -> Vec<H256> {
         self.account_pks.clone().unwrap_or_default()
     }
@@ -224,6 +291,11 @@ impl Web3JsonRpcConfig {
     pub fn mempool_cache_size(&self) -> usize {
         self.mempool_cache_size.unwrap_or(10_000)
     }
+
+    /// Returns the size of the lookup cache in bytes.
+    pub fn lookup_cache_size(&self) -> usize {
+        self.lookup_cache_size_mb.unwrap_or(0) * super::BYTES_IN_MEGABYTE
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -236,6 +308,9 @@ pub struct HealthCheckConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     pub hard_time_limit_ms: Option<u64>,
+    /// Bearer token authorizing requests to the `/config` endpoint, which returns the effective node configuration
+    /// with secrets redacted. If not specified, the endpoint is disabled.
+    pub config_export_token: Option<String>,
 }
 
 impl HealthCheckConfig {
@@ -272,10 +347,31 @@ pub struct MerkleTreeApiConfig {
     /// Port to bind the Merkle tree API server to.
     #[serde(default = "MerkleTreeApiConfig::default_port")]
     pub port: u16,
+    /// Maximum number of tree readers (i.e., RocksDB snapshots) concurrently serving API requests.
+    #[serde(default = "MerkleTreeApiConfig::default_reader_pool_size")]
+    pub reader_pool_size: usize,
 }
 
 impl MerkleTreeApiConfig {
     const fn default_port() -> u16 {
         3_072
     }
+
+    pub const fn default_reader_pool_size() -> usize {
+        16
+    }
+
+    /// Checks the config for values that cannot be used by the tree API server.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.reader_pool_size > 0,
+            "`reader_pool_size` must be positive; a pool without readers cannot serve requests"
+        );
+        anyhow::ensure!(
+            u32::try_from(self.reader_pool_size).is_ok(),
+            "`reader_pool_size` is too large: {}",
+            self.reader_pool_size
+        );
+        Ok(())
+    }
 }
@@ -1,4 +1,8 @@
-use std::{str::FromStr, time::Duration};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    str::FromStr,
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::{
@@ -144,6 +148,76 @@ pub struct StateKeeperConfig {
 
     /// The version of the fee model to use.
     pub fee_model_version: FeeModelVersion,
+    /// Number of transactions in the mempool above which the mempool is considered congested, and the fair L2 gas price
+    /// is scaled up. If not set, congestion pricing is disabled.
+    pub congestion_mempool_size_threshold: Option<u64>,
+    /// Maximum multiplier applied to the fair L2 gas price under congestion. The multiplier grows linearly
+    /// from 1 at the congestion threshold to this value at the doubled threshold. Must be at least 1.
+    pub congestion_l2_gas_price_multiplier: Option<f64>,
+
+    /// Target latency in milliseconds of sealing miniblocks by the asynchronous miniblock sealer. If set, miniblocks
+    /// waiting in the seal queue are sealed in a single DB transaction, with the number of inserted events
+    /// and storage logs adjusted based on the observed sealing latency. If not set, each miniblock is sealed
+    /// in a separate transaction.
+    pub miniblock_seal_batch_latency_target_ms: Option<u64>,
+    /// Max time in milliseconds that the miniblock sealer waits for new miniblocks to fill a batch
+    /// if `miniblock_seal_batch_latency_target_ms` is set. If not set, the sealer doesn't wait
+    /// and only batches miniblocks already waiting in the seal queue.
+    pub miniblock_seal_batch_max_delay_ms: Option<u64>,
+    /// Share of executed transactions for which execution metrics (wall time, gas, pubdata and storage writes)
+    /// are recorded to Postgres, from 0 to 1. Transactions are sampled deterministically based on their hashes.
+    /// If not set, per-transaction execution metrics are not recorded.
+    pub tx_execution_metrics_sampling_rate: Option<f64>,
+    /// Number of latest miniblocks for which recorded per-transaction execution metrics are retained.
+    pub tx_execution_metrics_retention_miniblocks: Option<u64>,
+
+    /// Maximum total size of transactions in a miniblock, as encoded in the consensus miniblock payload.
+    /// Transactions that would make a miniblock exceed this size are deferred to the next miniblock before execution,
+    /// and transactions that exceed it on their own are rejected. Should be set below the consensus `max_payload_size`
+    /// to leave room for the miniblock header. If not set, the miniblock payload size is not limited.
+    pub max_miniblock_payload_size: Option<usize>,
+    /// Names of L1 batch seal criteria that are disabled, e.g. `pub_data_size` or `no_txs_timeout`.
+    /// Criteria are enabled by default; disabling criteria related to circuits or the bootloader capacity
+    /// may lead to L1 batches that cannot be executed or proven.
+    #[serde(default)]
+    pub disabled_seal_criteria: Vec<String>,
+    /// Port of the state keeper admin HTTP server allowing to request a soft shutdown of the state keeper
+    /// (i.e., sealing the current L1 batch and stopping processing transactions) and to update the transaction filter
+    /// policy at runtime. If not set, the server is not started. The server only listens on the loopback interface.
+    pub admin_port: Option<u16>,
+    /// Bearer token authorizing mutating requests to the state keeper admin server. Required if `admin_port` is set.
+    pub admin_token: Option<String>,
+
+    /// Addresses that cannot initiate or be the recipient of L2 transactions included into batches.
+    #[serde(default)]
+    pub tx_filter_denied_addresses: Vec<Address>,
+    /// If not empty, only L2 transactions initiated by these accounts are included into batches.
+    #[serde(default)]
+    pub tx_filter_allowed_initiators: Vec<Address>,
+    /// If not empty, only these accounts can deploy contracts.
+    #[serde(default)]
+    pub tx_filter_allowed_deployers: Vec<Address>,
+    /// Maximum calldata size of L2 transactions included into batches. If not set, the calldata size is not limited
+    /// by the filter (it is still limited by the miniblock payload size, if it is set).
+    pub tx_filter_max_calldata_size: Option<usize>,
+    /// If set, each transaction and the batch tip are additionally executed in the VM corresponding
+    /// to this protocol version (the "shadow" VM), and execution results are compared with the main VM.
+    /// Divergences are recorded to Postgres; they don't affect the produced batches.
+    pub shadow_vm_protocol_version: Option<u16>,
+    /// Whether to persist protective reads of L1 batches in a background task instead of when sealing L1 batches
+    /// (protective reads are required to run a full Merkle tree).
+    #[serde(default)]
+    pub offload_protective_reads: bool,
+    /// Target miniblock time in milliseconds for the adaptive miniblock sealing policy. If set, the miniblock
+    /// sealing deadline is adjusted so that the configured percentile of miniblock times tracks this target;
+    /// `miniblock_commit_deadline_ms` is used as the upper bound for the deadline. If not set, miniblocks are sealed
+    /// after the fixed `miniblock_commit_deadline_ms` timeout.
+    pub miniblock_adaptive_target_ms: Option<u64>,
+    /// Percentile of miniblock times targeted by the adaptive miniblock sealing policy, from 0 (exclusive) to 1.
+    pub miniblock_adaptive_target_percentile: Option<f64>,
+    /// Number of transactions after which a miniblock is sealed immediately by the adaptive miniblock sealing policy.
+    /// If not set, miniblocks are only sealed by the deadline.
+    pub miniblock_adaptive_max_tx_count: Option<usize>,
 
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
@@ -194,6 +268,25 @@ impl StateKeeperConfig {
             max_pubdata_per_batch: 100_000,
             minimal_l2_gas_price: 100000000,
             fee_model_version: FeeModelVersion::V2,
+            congestion_mempool_size_threshold: None,
+            congestion_l2_gas_price_multiplier: None,
+            miniblock_seal_batch_latency_target_ms: None,
+            miniblock_seal_batch_max_delay_ms: None,
+            tx_execution_metrics_sampling_rate: None,
+            tx_execution_metrics_retention_miniblocks: None,
+            max_miniblock_payload_size: None,
+            disabled_seal_criteria: vec![],
+            admin_port: None,
+            admin_token: None,
+            tx_filter_denied_addresses: vec![],
+            tx_filter_allowed_initiators: vec![],
+            tx_filter_allowed_deployers: vec![],
+            tx_filter_max_calldata_size: None,
+            shadow_vm_protocol_version: None,
+            offload_protective_reads: false,
+            miniblock_adaptive_target_ms: None,
+            miniblock_adaptive_target_percentile: None,
+            miniblock_adaptive_max_tx_count: None,
             validation_computational_gas_limit: 300000,
             save_call_traces: true,
             virtual_blocks_interval: 1,
@@ -205,9 +298,60 @@ impl StateKeeperConfig {
         }
     }
 
+    /// Checks the config for values that cannot be used by the state keeper.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        // Thresholds exceeding the circuit capacity would allow sealing L1 batches that cannot be proven.
+        anyhow::ensure!(
+            self.reject_tx_at_geometry_percentage <= 1.0
+                && self.close_block_at_geometry_percentage <= 1.0,
+            "geometry seal thresholds must not exceed 1.0 (reject_tx_at_geometry_percentage: {}, \
+             close_block_at_geometry_percentage: {})",
+            self.reject_tx_at_geometry_percentage,
+            self.close_block_at_geometry_percentage
+        );
+        anyhow::ensure!(
+            self.admin_port.is_none() || self.admin_token.is_some(),
+            "`admin_port` is set, but `admin_token` is missing; the state keeper admin server requires authorization"
+        );
+        // Congestion pricing must not decrease the L2 gas price.
+        anyhow::ensure!(
+            self.congestion_l2_gas_price_multiplier() >= 1.0,
+            "`congestion_l2_gas_price_multiplier` must be at least 1, got {}",
+            self.congestion_l2_gas_price_multiplier()
+        );
+        Ok(())
+    }
+
     pub fn enum_index_migration_chunk_size(&self) -> usize {
         self.enum_index_migration_chunk_size.unwrap_or(1_000)
     }
+
+    pub fn congestion_l2_gas_price_multiplier(&self) -> f64 {
+        self.congestion_l2_gas_price_multiplier.unwrap_or(2.0)
+    }
+
+    pub fn miniblock_seal_batch_latency_target(&self) -> Option<Duration> {
+        self.miniblock_seal_batch_latency_target_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn miniblock_seal_batch_max_delay(&self) -> Duration {
+        Duration::from_millis(self.miniblock_seal_batch_max_delay_ms.unwrap_or(0))
+    }
+
+    pub fn tx_execution_metrics_retention_miniblocks(&self) -> u64 {
+        self.tx_execution_metrics_retention_miniblocks
+            .unwrap_or(1_000_000)
+    }
+
+    pub fn miniblock_adaptive_target_percentile(&self) -> f64 {
+        self.miniblock_adaptive_target_percentile.unwrap_or(0.9)
+    }
+
+    pub fn admin_bind_addr(&self) -> Option<SocketAddr> {
+        let port = self.admin_port?;
+        Some(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -248,6 +392,29 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Maximum gap between the account nonce in the mempool and the nonce of a pending L2 transaction.
+    /// Transactions exceeding the gap are evicted from the mempool and removed from Postgres. If not set,
+    /// transactions are not evicted based on their nonce.
+    pub max_nonce_gap: Option<u32>,
+    /// Durability of L2 transactions submitted to the mempool.
+    #[serde(default)]
+    pub durability: MempoolDurability,
+}
+
+/// Durability level of L2 transactions accepted into the mempool. Regardless of the level, transactions
+/// are written to Postgres before they are acknowledged to the submitter, and the mempool is rehydrated
+/// from Postgres after a restart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolDurability {
+    /// A transaction is acknowledged only after its insertion is flushed to the Postgres WAL, so that
+    /// acknowledged transactions survive a Postgres crash.
+    #[default]
+    Synchronous,
+    /// A transaction is acknowledged as soon as its insertion is committed, without waiting for the WAL flush
+    /// (i.e., with `synchronous_commit = off`). This reduces submission latency, but the most recently
+    /// acknowledged transactions may be lost if Postgres crashes. Restarts of the server are not affected.
+    Asynchronous,
 }
 
 impl MempoolConfig {
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for the retention policies of historical data in Postgres. Data older than the retention period
+/// for its table is periodically removed by the `data_retention` component.
+///
+/// Age of the data is determined by the timestamp of the miniblock it belongs to. If a retention period
+/// for a table is not specified, data in the table is retained indefinitely.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DataRetentionConfig {
+    /// Retention period for events, in days.
+    pub events_retention_days: Option<u32>,
+    /// Retention period for transaction call traces, in days.
+    pub call_traces_retention_days: Option<u32>,
+    /// Retention period for (legacy) transaction traces, in days.
+    pub transaction_traces_retention_days: Option<u32>,
+    /// Interval between checks for expired data, in milliseconds.
+    #[serde(default = "DataRetentionConfig::default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Maximum number of rows removed in a single DB query. Limits the duration of locks held on the affected tables.
+    #[serde(default = "DataRetentionConfig::default_deletion_batch_size")]
+    pub deletion_batch_size: u32,
+}
+
+impl DataRetentionConfig {
+    const fn default_poll_interval_ms() -> u64 {
+        60_000
+    }
+
+    const fn default_deletion_batch_size() -> u32 {
+        10_000
+    }
+
+    pub fn for_tests() -> Self {
+        Self {
+            events_retention_days: Some(30),
+            call_traces_retention_days: Some(7),
+            transaction_traces_retention_days: None,
+            poll_interval_ms: 10,
+            deletion_batch_size: 100,
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+}
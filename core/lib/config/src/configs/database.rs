@@ -18,6 +18,51 @@ pub enum MerkleTreeMode {
     Lightweight,
 }
 
+/// Compaction style for a RocksDB column family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompactionStyle {
+    Level,
+    Universal,
+}
+
+/// Compression algorithm for a RocksDB column family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompression {
+    None,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+/// Tuning options for a single RocksDB column family. All options are optional; if an option is not set,
+/// the default value hard-coded for the column family will be used.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RocksdbColumnFamilyConfig {
+    /// Name of the column family, e.g. `default` or `stale_keys` for the Merkle tree, or `state` for
+    /// the state keeper cache.
+    pub name: String,
+    /// Size of a single memtable (aka write buffer) for the column family.
+    #[serde(default)]
+    pub write_buffer_size_mb: Option<usize>,
+    #[serde(default)]
+    pub compaction_style: Option<RocksdbCompactionStyle>,
+    /// Number of bits per key used by bloom filters. 0 disables bloom filters for the column family.
+    #[serde(default)]
+    pub bloom_filter_bits_per_key: Option<f64>,
+    #[serde(default)]
+    pub compression: Option<RocksdbCompression>,
+}
+
+impl RocksdbColumnFamilyConfig {
+    /// Returns the memtable size in bytes.
+    pub fn write_buffer_size(&self) -> Option<usize> {
+        self.write_buffer_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct MerkleTreeConfig {
     /// Path to the RocksDB data directory for Merkle tree.
@@ -44,6 +89,54 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Maximum number of L1 batches, updates for which are accumulated in memory and written to RocksDB
+    /// in a single write batch while the tree is catching up. Larger values reduce RocksDB write amplification
+    /// (and thus compaction load) during re-sync at the cost of memory usage. Once the tree has caught up,
+    /// updates are written after each iteration regardless of this value.
+    #[serde(default = "MerkleTreeConfig::default_max_batches_per_commit")]
+    pub max_batches_per_commit: usize,
+    /// Number of threads in a dedicated thread pool used to hash tree nodes during updates and recovery.
+    /// 0 means using the default number of threads (the number of logical CPUs). If not specified,
+    /// the tree will share the global `rayon` thread pool.
+    #[serde(default)]
+    pub hashing_thread_count: Option<usize>,
+    /// Interval between consistency checks of the Merkle tree against Postgres. If not specified,
+    /// consistency checks are disabled.
+    #[serde(default)]
+    pub consistency_check_interval_sec: Option<u64>,
+    /// Number of tree leaves sampled during a single consistency check.
+    #[serde(default = "MerkleTreeConfig::default_consistency_check_sample_size")]
+    pub consistency_check_sample_size: usize,
+    /// Whether to roll back the tree to the last L1 batch unaffected by a detected divergence, so that
+    /// the affected L1 batches are recomputed. If not set, divergences are only reported.
+    #[serde(default)]
+    pub consistency_check_repair: bool,
+    /// Whether to rebuild the tree from Postgres (initial writes and latest storage values) if the tree
+    /// RocksDB instance is empty and there is no snapshot to recover from. This is much faster than
+    /// recomputing the tree by replaying all L1 batches.
+    #[serde(default)]
+    pub rebuild_from_postgres: bool,
+    /// Path to the RocksDB data directory for the follower Merkle tree generating proof data. Only supported
+    /// in the full mode. If set, the main tree only computes root hashes, so that L1 batch metadata is available
+    /// sooner, and proof data (witness inputs) is generated by the follower tree asynchronously.
+    #[serde(default)]
+    pub proof_data_follower_path: Option<String>,
+    /// Interval between checks for new storage log snapshots, for which the Merkle tree is exported
+    /// to the object store, so that external nodes can download the tree instead of recovering it
+    /// from storage logs. If not specified, tree snapshots are not exported.
+    #[serde(default)]
+    pub snapshot_export_interval_sec: Option<u64>,
+    /// Maximum number of tree nodes in a single exported snapshot chunk.
+    #[serde(default = "MerkleTreeConfig::default_snapshot_export_chunk_size")]
+    pub snapshot_export_chunk_size: usize,
+    /// Interval between checks of tree root hashes against state roots committed on L1 for newly committed
+    /// L1 batches. If not specified, the checks are disabled.
+    #[serde(default)]
+    pub l1_root_check_interval_sec: Option<u64>,
+    /// Tuning options for column families in the Merkle tree RocksDB. Column families not mentioned here
+    /// use the default options. Can only be set in the file-based config.
+    #[serde(default)]
+    pub column_families: Vec<RocksdbColumnFamilyConfig>,
 }
 
 impl Default for MerkleTreeConfig {
@@ -56,6 +149,17 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            max_batches_per_commit: Self::default_max_batches_per_commit(),
+            hashing_thread_count: None,
+            consistency_check_interval_sec: None,
+            consistency_check_sample_size: Self::default_consistency_check_sample_size(),
+            consistency_check_repair: false,
+            rebuild_from_postgres: false,
+            proof_data_follower_path: None,
+            snapshot_export_interval_sec: None,
+            snapshot_export_chunk_size: Self::default_snapshot_export_chunk_size(),
+            l1_root_check_interval_sec: None,
+            column_families: vec![],
         }
     }
 }
@@ -85,6 +189,18 @@ impl MerkleTreeConfig {
         20
     }
 
+    pub const fn default_max_batches_per_commit() -> usize {
+        1
+    }
+
+    const fn default_consistency_check_sample_size() -> usize {
+        100
+    }
+
+    const fn default_snapshot_export_chunk_size() -> usize {
+        100_000
+    }
+
     /// Returns the size of block cache size for Merkle tree in bytes.
     pub fn block_cache_size(&self) -> usize {
         self.block_cache_size_mb * super::BYTES_IN_MEGABYTE
@@ -99,6 +215,22 @@ impl MerkleTreeConfig {
     pub fn stalled_writes_timeout(&self) -> Duration {
         Duration::from_secs(self.stalled_writes_timeout_sec)
     }
+
+    /// Returns the interval between tree consistency checks, or `None` if checks are disabled.
+    pub fn consistency_check_interval(&self) -> Option<Duration> {
+        self.consistency_check_interval_sec.map(Duration::from_secs)
+    }
+
+    /// Returns the interval between checks for new snapshots to export the tree for, or `None`
+    /// if the tree snapshot export is disabled.
+    pub fn snapshot_export_interval(&self) -> Option<Duration> {
+        self.snapshot_export_interval_sec.map(Duration::from_secs)
+    }
+
+    /// Returns the interval between checks of tree root hashes against L1, or `None` if checks are disabled.
+    pub fn l1_root_check_interval(&self) -> Option<Duration> {
+        self.l1_root_check_interval_sec.map(Duration::from_secs)
+    }
 }
 
 /// Database configuration.
@@ -107,6 +239,26 @@ pub struct DBConfig {
     /// Path to the RocksDB data directory that serves state cache.
     #[serde(default = "DBConfig::default_state_keeper_db_path")]
     pub state_keeper_db_path: String,
+    /// Capacity of the block cache for the state keeper RocksDB. If not specified, the default RocksDB cache
+    /// options will be used.
+    #[serde(default)]
+    pub state_keeper_db_block_cache_size_mb: Option<usize>,
+    /// Tuning options for column families in the state keeper RocksDB. Can only be set in the file-based config.
+    #[serde(default)]
+    pub state_keeper_db_column_families: Vec<RocksdbColumnFamilyConfig>,
+    /// Capacity of the in-memory LRU cache for state values read from the state keeper RocksDB.
+    /// If set to 0 (the default), the cache is disabled.
+    #[serde(default)]
+    pub state_keeper_db_values_cache_size_mb: usize,
+    /// Capacity of the in-memory LRU cache for factory dependencies (i.e., bytecodes) read from the state keeper RocksDB.
+    /// If set to 0 (the default), the cache is disabled.
+    #[serde(default)]
+    pub state_keeper_db_factory_deps_cache_size_mb: usize,
+    /// Number of latest L1 batches whose touched storage slots are preloaded into the values cache after the state keeper
+    /// RocksDB is initialized. Has no effect if the values cache is disabled. If set to 0 (the default), the cache
+    /// is not warmed up.
+    #[serde(default)]
+    pub state_keeper_db_cache_warm_up_l1_batches: u32,
     /// Merkle tree configuration.
     #[serde(skip)]
     // ^ Filled in separately in `Self::from_env()`. We cannot use `serde(flatten)` because it
@@ -118,6 +270,22 @@ impl DBConfig {
     fn default_state_keeper_db_path() -> String {
         "./db/state_keeper".to_owned()
     }
+
+    /// Returns the size of block cache for the state keeper RocksDB in bytes.
+    pub fn state_keeper_db_block_cache_size(&self) -> Option<usize> {
+        self.state_keeper_db_block_cache_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE)
+    }
+
+    /// Returns the size of the in-memory values cache for the state keeper RocksDB in bytes.
+    pub fn state_keeper_db_values_cache_size(&self) -> u64 {
+        (self.state_keeper_db_values_cache_size_mb * super::BYTES_IN_MEGABYTE) as u64
+    }
+
+    /// Returns the size of the in-memory factory deps cache for the state keeper RocksDB in bytes.
+    pub fn state_keeper_db_factory_deps_cache_size(&self) -> u64 {
+        (self.state_keeper_db_factory_deps_cache_size_mb * super::BYTES_IN_MEGABYTE) as u64
+    }
 }
 
 /// Collection of different database URLs and general PostgreSQL options.
@@ -142,10 +310,20 @@ pub struct PostgresConfig {
     /// Statement timeout in seconds for Postgres connections. Applies only to the replica
     /// connection pool used by the API servers.
     pub statement_timeout_sec: Option<u64>,
+    /// Maximum replication lag of the replica database in seconds. If set, read-only queries of the API servers
+    /// are routed to the master database while the replica lags behind by more than this value.
+    /// If not set, these queries are always served by the replica database.
+    pub max_replica_lag_sec: Option<u64>,
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
     pub long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     pub slow_query_threshold_ms: Option<u64>,
+    /// Default timeout in milliseconds for DB queries issued by the DAL. Queries exceeding the timeout are aborted.
+    /// If not set, queries are not limited (other than by the Postgres statement timeout, if any).
+    pub query_timeout_ms: Option<u64>,
+    /// Postgres schema storing all tables for the node. Allows multiple chains to share a single database,
+    /// with each chain using a separate schema. If not set, the default `search_path` is used.
+    pub schema: Option<String>,
     pub test_server_url: Option<String>,
     pub test_prover_url: Option<String>,
 }
@@ -186,6 +364,11 @@ impl PostgresConfig {
         self.statement_timeout_sec.map(Duration::from_secs)
     }
 
+    /// Returns the maximum replication lag of the replica database.
+    pub fn max_replica_lag(&self) -> Option<Duration> {
+        self.max_replica_lag_sec.map(Duration::from_secs)
+    }
+
     /// Returns the acquire timeout for a single connection attempt.
     pub fn acquire_timeout(&self) -> Option<Duration> {
         self.acquire_timeout_sec.map(Duration::from_secs)
@@ -198,4 +381,9 @@ impl PostgresConfig {
     pub fn slow_query_threshold(&self) -> Option<Duration> {
         self.slow_query_threshold_ms.map(Duration::from_millis)
     }
+
+    /// Returns the default timeout for DB queries issued by the DAL.
+    pub fn query_timeout(&self) -> Option<Duration> {
+        self.query_timeout_ms.map(Duration::from_millis)
+    }
 }
@@ -14,6 +14,9 @@ pub struct ETHConfig {
     pub gas_adjuster: Option<GasAdjusterConfig>,
     pub watcher: Option<ETHWatchConfig>,
     pub web3_url: String,
+    /// Additional L1 RPC URLs queried by `GasAdjuster` together with `web3_url`. If specified,
+    /// L1 fee data is aggregated across all URLs, so that a single faulty L1 provider cannot skew fees.
+    pub gas_price_web3_urls: Vec<String>,
 }
 
 impl ETHConfig {
@@ -53,12 +56,18 @@ impl ETHConfig {
                 num_samples_for_blob_base_fee_estimate: 10,
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
+                fee_estimation_strategy: FeeEstimationStrategy::Median,
+                ewma_smoothing_factor: 0.2,
+                fee_estimation_percentile: 0.75,
+                l1_gas_price_quorum: 1,
+                l1_gas_price_max_deviation: 0.5,
             }),
             watcher: Some(ETHWatchConfig {
                 confirmations_for_eth_event: None,
                 eth_node_poll_interval: 0,
             }),
             web3_url: "localhost:8545".to_string(),
+            gas_price_web3_urls: vec![],
         }
     }
 }
@@ -83,6 +92,19 @@ pub enum PubdataSendingMode {
     Blobs,
 }
 
+/// Strategy used by `GasAdjuster` to estimate L1 fees from the fees of recent L1 blocks.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum FeeEstimationStrategy {
+    /// Median of the collected samples. Robust to short fee spikes, but slow to react to sustained fee changes.
+    #[default]
+    Median,
+    /// Exponentially weighted moving average of the collected samples; newer samples have more weight.
+    /// Responsiveness is controlled by `ewma_smoothing_factor`.
+    Ewma,
+    /// Configurable percentile of the collected samples (see `fee_estimation_percentile`).
+    Percentile,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct SenderConfig {
     pub aggregated_proof_sizes: Vec<usize>,
@@ -175,6 +197,24 @@ pub struct GasAdjusterConfig {
     pub internal_pubdata_pricing_multiplier: f64,
     /// Max blob base fee that is allowed to be used.
     pub max_blob_base_fee: Option<u64>,
+    /// Strategy used to estimate the base fee and blob base fee from the collected samples.
+    #[serde(default)]
+    pub fee_estimation_strategy: FeeEstimationStrategy,
+    /// Weight of the newest sample for the EWMA fee estimation strategy; must be in `(0, 1]`.
+    /// Greater values make the estimate more responsive to fee changes.
+    #[serde(default = "GasAdjusterConfig::default_ewma_smoothing_factor")]
+    pub ewma_smoothing_factor: f64,
+    /// Percentile of samples used by the percentile fee estimation strategy; must be in `[0, 1]`.
+    #[serde(default = "GasAdjusterConfig::default_fee_estimation_percentile")]
+    pub fee_estimation_percentile: f64,
+    /// Minimum number of L1 providers that must agree on a fee value if multiple L1 providers are configured
+    /// (see `ETHConfig::gas_price_web3_urls`).
+    #[serde(default = "GasAdjusterConfig::default_l1_gas_price_quorum")]
+    pub l1_gas_price_quorum: usize,
+    /// Maximum relative deviation of a fee value reported by an L1 provider from the median across providers.
+    /// Values deviating more are considered outliers and are discarded.
+    #[serde(default = "GasAdjusterConfig::default_l1_gas_price_max_deviation")]
+    pub l1_gas_price_max_deviation: f64,
 }
 
 impl GasAdjusterConfig {
@@ -191,6 +231,24 @@ impl GasAdjusterConfig {
         self.max_blob_base_fee.unwrap_or(u64::MAX)
     }
 
+    /// Checks that parameters of the selected fee estimation strategy are valid.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        match self.fee_estimation_strategy {
+            FeeEstimationStrategy::Median => {}
+            FeeEstimationStrategy::Ewma => anyhow::ensure!(
+                self.ewma_smoothing_factor > 0.0 && self.ewma_smoothing_factor <= 1.0,
+                "ewma_smoothing_factor must be in (0, 1], got {}",
+                self.ewma_smoothing_factor
+            ),
+            FeeEstimationStrategy::Percentile => anyhow::ensure!(
+                (0.0..=1.0).contains(&self.fee_estimation_percentile),
+                "fee_estimation_percentile must be in [0, 1], got {}",
+                self.fee_estimation_percentile
+            ),
+        }
+        Ok(())
+    }
+
     pub const fn default_num_samples_for_blob_base_fee_estimate() -> usize {
         10
     }
@@ -198,4 +256,20 @@ impl GasAdjusterConfig {
     pub const fn default_internal_pubdata_pricing_multiplier() -> f64 {
         1.0
     }
+
+    pub const fn default_ewma_smoothing_factor() -> f64 {
+        0.2
+    }
+
+    pub const fn default_fee_estimation_percentile() -> f64 {
+        0.75
+    }
+
+    pub const fn default_l1_gas_price_quorum() -> usize {
+        1
+    }
+
+    pub const fn default_l1_gas_price_max_deviation() -> f64 {
+        0.5
+    }
 }
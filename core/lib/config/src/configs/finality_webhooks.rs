@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for push notifications about L1 batches transitioning between L1 states
+/// (committed, proven, executed).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FinalityWebhooksConfig {
+    /// URLs of webhooks to notify. Each webhook receives all notifications, in the L1 batch order.
+    pub urls: Vec<String>,
+    /// Interval between checks for new L1 batch state transitions, in milliseconds.
+    #[serde(default = "FinalityWebhooksConfig::default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Maximum number of attempts to deliver a notification during a single poll. If all attempts fail,
+    /// delivery is resumed from the same notification on the next poll.
+    #[serde(default = "FinalityWebhooksConfig::default_max_delivery_attempts")]
+    pub max_delivery_attempts: u32,
+    /// Timeout for a single delivery request, in milliseconds.
+    #[serde(default = "FinalityWebhooksConfig::default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+impl FinalityWebhooksConfig {
+    const fn default_poll_interval_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_max_delivery_attempts() -> u32 {
+        5
+    }
+
+    const fn default_request_timeout_ms() -> u64 {
+        10_000
+    }
+
+    pub fn for_tests() -> Self {
+        Self {
+            urls: vec!["http://127.0.0.1:8080/finality".to_owned()],
+            poll_interval_ms: 10,
+            max_delivery_attempts: 3,
+            request_timeout_ms: 1_000,
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+}
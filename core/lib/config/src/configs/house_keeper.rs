@@ -15,6 +15,22 @@ pub struct HouseKeeperConfig {
     pub proof_compressor_stats_reporting_interval_ms: u64,
     pub prover_job_archiver_reporting_interval_ms: Option<u64>,
     pub prover_job_archiver_archiving_interval_secs: Option<u64>,
+    /// Interval between runs of the object store archiver moving artifacts for old executed L1 batches
+    /// to the cold storage.
+    pub object_store_archiver_interval_ms: Option<u64>,
+    /// Minimum time since an L1 batch was executed on L1 for its artifacts (or a storage snapshot for it)
+    /// to be moved to the cold storage.
+    pub object_store_archiver_retention_secs: Option<u64>,
+    /// Interval between runs of the object store garbage collector removing artifacts for old executed L1 batches
+    /// and old storage snapshots.
+    pub object_store_gc_interval_ms: Option<u64>,
+    /// Minimum time since an L1 batch was executed on L1 for its artifacts (or a storage snapshot for it)
+    /// to be removed from the object store. Should be greater than the archiver retention, if the archiver is enabled.
+    pub object_store_gc_retention_secs: Option<u64>,
+    /// Interval between runs of the rotator creating partitions of the `storage_logs` table for upcoming miniblocks.
+    pub storage_logs_partition_rotation_interval_ms: Option<u64>,
+    /// Number of miniblocks covered by a single partition of the `storage_logs` table.
+    pub storage_logs_partition_size: Option<u32>,
 }
 
 impl HouseKeeperConfig {
@@ -22,4 +38,18 @@ impl HouseKeeperConfig {
         self.prover_job_archiver_reporting_interval_ms.is_some()
             && self.prover_job_archiver_archiving_interval_secs.is_some()
     }
+
+    pub fn object_store_archiver_enabled(&self) -> bool {
+        self.object_store_archiver_interval_ms.is_some()
+            && self.object_store_archiver_retention_secs.is_some()
+    }
+
+    pub fn object_store_gc_enabled(&self) -> bool {
+        self.object_store_gc_interval_ms.is_some() && self.object_store_gc_retention_secs.is_some()
+    }
+
+    pub fn storage_logs_partition_rotation_enabled(&self) -> bool {
+        self.storage_logs_partition_rotation_interval_ms.is_some()
+            && self.storage_logs_partition_size.is_some()
+    }
 }
@@ -3,9 +3,11 @@ pub use self::{
     api::ApiConfig,
     contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig,
+    data_retention::DataRetentionConfig,
     database::{DBConfig, PostgresConfig},
     eth_sender::{ETHConfig, GasAdjusterConfig},
     eth_watch::ETHWatchConfig,
+    finality_webhooks::FinalityWebhooksConfig,
     fri_proof_compressor::FriProofCompressorConfig,
     fri_prover::FriProverConfig,
     fri_prover_gateway::FriProverGatewayConfig,
@@ -25,9 +27,11 @@ pub mod api;
 pub mod chain;
 pub mod contract_verifier;
 pub mod contracts;
+pub mod data_retention;
 pub mod database;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod finality_webhooks;
 pub mod fri_proof_compressor;
 pub mod fri_prover;
 pub mod fri_prover_gateway;
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 /// Configuration for the object store
@@ -5,14 +7,78 @@ use serde::Deserialize;
 pub struct ObjectStoreConfig {
     #[serde(flatten)]
     pub mode: ObjectStoreMode,
+    /// Maximum number of retries for requests failed with a transient error (e.g., a network error).
     #[serde(default = "ObjectStoreConfig::default_max_retries")]
     pub max_retries: u16,
+    /// Timeout for a single request attempt to the store. If not set, requests are not timed out.
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+    /// Number of consecutive failed requests (after retries) after which the circuit breaker opens, i.e.,
+    /// requests start failing immediately without reaching the store. 0 disables the circuit breaker.
+    #[serde(default = "ObjectStoreConfig::default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    /// Period after which an open circuit breaker lets requests to the store through again.
+    #[serde(default = "ObjectStoreConfig::default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Storage class that old objects are transitioned to by the lifecycle management. The value
+    /// is interpreted by the backend: for GCS, it's a storage class name (e.g., `COLDLINE` or `ARCHIVE`);
+    /// for S3, it's a storage class name as well (e.g., `GLACIER` or `DEEP_ARCHIVE`); for Azure Blob Storage,
+    /// it's an access tier (`Cool`, `Cold` or `Archive`). The file-backed store uses `file_backed_archive_path` instead.
+    /// If not set, objects are never moved to cold storage.
+    #[serde(default)]
+    pub cold_storage_class: Option<String>,
+    /// Whether to store objects in the content-addressed mode, i.e., keyed by the hash of their contents,
+    /// with the logical keys pointing to the contents. Identical objects are stored only once in this mode;
+    /// contents are removed once the last object referencing them is removed. Processes sharing a store
+    /// must not concurrently store and remove objects with identical contents.
+    /// Objects stored before the mode was enabled remain readable.
+    #[serde(default)]
+    pub content_addressed: bool,
+    /// Hex-encoded static AES-256 master key used to encrypt objects at rest. Each object is encrypted with
+    /// a separate data key, which is wrapped with the master key. Mutually exclusive with `encryption_kms_key_id`.
+    #[serde(default)]
+    pub encryption_master_key: Option<String>,
+    /// ID or ARN of the AWS KMS key used as the master key to encrypt objects at rest. Mutually exclusive
+    /// with `encryption_master_key`.
+    #[serde(default)]
+    pub encryption_kms_key_id: Option<String>,
+    /// Names of buckets encrypted at rest (e.g., `storage_logs_snapshots` or `proofs_fri`) if encryption is enabled.
+    /// If empty, all buckets are encrypted. Objects stored before encryption was enabled remain readable.
+    #[serde(default)]
+    pub encrypted_buckets: Vec<String>,
 }
 
 impl ObjectStoreConfig {
     const fn default_max_retries() -> u16 {
         5
     }
+
+    pub const fn default_circuit_breaker_threshold() -> u32 {
+        10
+    }
+
+    pub const fn default_circuit_breaker_cooldown_secs() -> u64 {
+        30
+    }
+
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout_secs.map(Duration::from_secs)
+    }
+
+    pub fn circuit_breaker_cooldown(&self) -> Duration {
+        Duration::from_secs(self.circuit_breaker_cooldown_secs)
+    }
+
+    /// Checks whether objects can be moved to cold storage with this config.
+    pub fn is_archiving_configured(&self) -> bool {
+        match &self.mode {
+            ObjectStoreMode::FileBacked {
+                file_backed_archive_path,
+                ..
+            } => file_backed_archive_path.is_some(),
+            _ => self.cold_storage_class.is_some(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -30,5 +96,48 @@ pub enum ObjectStoreMode {
     },
     FileBacked {
         file_backed_base_path: String,
+        /// Whether to flush written objects to the storage device before considering them stored.
+        /// Recommended if the store is used in production (e.g., for snapshots).
+        #[serde(default)]
+        file_backed_fsync: bool,
+        /// Whether to shard objects in each bucket (except for snapshot buckets, which must be accessible
+        /// via the storage prefix) among subdirectories. Must not be changed
+        /// for an existing store since objects stored with a different setting become inaccessible.
+        #[serde(default)]
+        file_backed_sharding: bool,
+        /// Path to the directory that archived objects are moved to. If not set, objects are never archived.
+        #[serde(default)]
+        file_backed_archive_path: Option<String>,
+    },
+    /// AWS S3 or an S3-compatible store (e.g., MinIO).
+    S3 {
+        /// Name of the bucket.
+        bucket_base_url: String,
+        region: String,
+        /// Endpoint URL overriding the default AWS endpoint for the region (e.g., `http://localhost:9000` for MinIO).
+        #[serde(default)]
+        endpoint: Option<String>,
+        /// Whether to address buckets using paths (`{endpoint}/{bucket}/{key}`) instead of subdomains
+        /// (`{bucket}.{endpoint}/{key}`). Required by most self-hosted S3-compatible stores.
+        #[serde(default)]
+        force_path_style: bool,
+        /// Static access key ID. If not set, credentials are resolved using the default AWS credential chain
+        /// (environment variables, shared credential files, or IAM roles for EC2 instances / EKS service accounts).
+        #[serde(default)]
+        access_key_id: Option<String>,
+        /// Static secret access key. Must be set together with `access_key_id`.
+        #[serde(default)]
+        secret_access_key: Option<String>,
+    },
+    /// Azure Blob Storage.
+    AzureBlob {
+        /// Name of the storage account.
+        azure_storage_account: String,
+        /// Name of the container.
+        bucket_base_url: String,
+        /// Shared access signature (SAS) token granting access to the container. If not set, the managed identity
+        /// of the Azure VM / AKS pod is used.
+        #[serde(default)]
+        azure_sas_token: Option<String>,
     },
 }
@@ -60,6 +60,11 @@ pub struct StateKeeper {
 pub struct Wallets {
     pub eth_sender: Option<EthSender>,
     pub state_keeper: Option<StateKeeper>,
+    /// Wallet signing data served by the main node to external parties: L2 blocks for
+    /// external nodes, snapshot headers and finality notifications. Should differ from
+    /// the L1 operator wallets, so that the key used by these components cannot be used
+    /// to send L1 transactions.
+    pub signer: Option<Wallet>,
 }
 
 impl Wallets {
@@ -74,6 +79,7 @@ impl Wallets {
             state_keeper: Some(StateKeeper {
                 fee_account: AddressWallet::from_address(H160::repeat_byte(0x3)),
             }),
+            signer: Some(Wallet::from_private_key(H256::repeat_byte(0x4), None).unwrap()),
         }
     }
 }
@@ -60,8 +60,11 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             subscriptions_limit: self.sample(rng),
             pubsub_polling_interval: self.sample(rng),
             max_nonce_ahead: self.sample(rng),
+            replacement_fee_bump_percent: self.sample(rng),
+            max_pending_txs_per_account: self.sample(rng),
             gas_price_scale_factor: self.sample(rng),
             request_timeout: self.sample_opt(|| self.sample(rng)),
+            request_deadline_sec: self.sample(rng),
             account_pks: self.sample_opt(|| self.sample_range(rng).map(|_| rng.gen()).collect()),
             estimate_gas_scale_factor: self.sample(rng),
             estimate_gas_acceptable_overestimation: self.sample(rng),
@@ -76,9 +79,19 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             max_batch_request_size: self.sample(rng),
             max_response_body_size_mb: self.sample(rng),
             websocket_requests_per_minute_limit: self.sample(rng),
+            method_requests_per_minute_limit: self.sample(rng),
+            client_requests_per_minute_limit: self.sample(rng),
+            max_concurrent_requests: self.sample(rng),
             tree_api_url: self.sample(rng),
             mempool_cache_update_interval: self.sample(rng),
             mempool_cache_size: self.sample(rng),
+            lookup_cache_size_mb: self.sample(rng),
+            sign_sync_blocks: self.sample(rng),
+            sign_snapshot_headers: self.sample(rng),
+            tree_lag_limit: self.sample(rng),
+            reject_proofs_on_tree_lag: self.sample(rng),
+            mempool_inspection_enabled: self.sample(rng),
+            contract_execution_stats_enabled: self.sample(rng),
         }
     }
 }
@@ -89,6 +102,7 @@ impl Distribution<configs::api::HealthCheckConfig> for EncodeDist {
             port: self.sample(rng),
             slow_time_limit_ms: self.sample(rng),
             hard_time_limit_ms: self.sample(rng),
+            config_export_token: self.sample(rng),
         }
     }
 }
@@ -106,6 +120,7 @@ impl Distribution<configs::api::MerkleTreeApiConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::api::MerkleTreeApiConfig {
         configs::api::MerkleTreeApiConfig {
             port: self.sample(rng),
+            reader_pool_size: rng.gen_range(1..=64),
         }
     }
 }
@@ -166,6 +181,27 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             max_gas_per_batch: self.sample(rng),
             max_pubdata_per_batch: self.sample(rng),
             fee_model_version: self.sample(rng),
+            congestion_mempool_size_threshold: self.sample(rng),
+            // Must be at least 1.
+            congestion_l2_gas_price_multiplier: rng.gen::<bool>().then(|| rng.gen_range(1.0..4.0)),
+            miniblock_seal_batch_latency_target_ms: self.sample(rng),
+            miniblock_seal_batch_max_delay_ms: self.sample(rng),
+            tx_execution_metrics_sampling_rate: self.sample(rng),
+            tx_execution_metrics_retention_miniblocks: self.sample(rng),
+            max_miniblock_payload_size: self.sample(rng),
+            disabled_seal_criteria: self.sample_collect(rng),
+            admin_port: self.sample(rng),
+            // Always set since it's required if `admin_port` is set.
+            admin_token: Some(format!("{:x}", rng.gen::<u128>())),
+            tx_filter_denied_addresses: (0..rng.gen_range(0..3)).map(|_| rng.gen()).collect(),
+            tx_filter_allowed_initiators: (0..rng.gen_range(0..3)).map(|_| rng.gen()).collect(),
+            tx_filter_allowed_deployers: (0..rng.gen_range(0..3)).map(|_| rng.gen()).collect(),
+            tx_filter_max_calldata_size: self.sample(rng),
+            shadow_vm_protocol_version: self.sample(rng),
+            offload_protective_reads: self.sample(rng),
+            miniblock_adaptive_target_ms: self.sample(rng),
+            miniblock_adaptive_target_percentile: self.sample(rng),
+            miniblock_adaptive_max_tx_count: self.sample(rng),
             validation_computational_gas_limit: self.sample(rng),
             save_call_traces: self.sample(rng),
             virtual_blocks_interval: self.sample(rng),
@@ -208,6 +244,18 @@ impl Distribution<configs::chain::MempoolConfig> for EncodeDist {
             stuck_tx_timeout: self.sample(rng),
             remove_stuck_txs: self.sample(rng),
             delay_interval: self.sample(rng),
+            max_nonce_gap: self.sample(rng),
+            durability: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::chain::MempoolDurability> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::chain::MempoolDurability {
+        type T = configs::chain::MempoolDurability;
+        match rng.gen_range(0..2) {
+            0 => T::Synchronous,
+            _ => T::Asynchronous,
         }
     }
 }
@@ -253,6 +301,40 @@ impl Distribution<configs::database::MerkleTreeMode> for EncodeDist {
     }
 }
 
+impl Distribution<configs::database::RocksdbCompactionStyle> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::RocksdbCompactionStyle {
+        type T = configs::database::RocksdbCompactionStyle;
+        match rng.gen_range(0..2) {
+            0 => T::Level,
+            _ => T::Universal,
+        }
+    }
+}
+
+impl Distribution<configs::database::RocksdbCompression> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::RocksdbCompression {
+        type T = configs::database::RocksdbCompression;
+        match rng.gen_range(0..4) {
+            0 => T::None,
+            1 => T::Snappy,
+            2 => T::Lz4,
+            _ => T::Zstd,
+        }
+    }
+}
+
+impl Distribution<configs::database::RocksdbColumnFamilyConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::RocksdbColumnFamilyConfig {
+        configs::database::RocksdbColumnFamilyConfig {
+            name: self.sample(rng),
+            write_buffer_size_mb: self.sample(rng),
+            compaction_style: self.sample_opt(|| self.sample(rng)),
+            bloom_filter_bits_per_key: self.sample(rng),
+            compression: self.sample_opt(|| self.sample(rng)),
+        }
+    }
+}
+
 impl Distribution<configs::database::MerkleTreeConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::MerkleTreeConfig {
         configs::database::MerkleTreeConfig {
@@ -263,6 +345,17 @@ impl Distribution<configs::database::MerkleTreeConfig> for EncodeDist {
             memtable_capacity_mb: self.sample(rng),
             stalled_writes_timeout_sec: self.sample(rng),
             max_l1_batches_per_iter: self.sample(rng),
+            max_batches_per_commit: self.sample(rng),
+            hashing_thread_count: self.sample(rng),
+            consistency_check_interval_sec: self.sample(rng),
+            consistency_check_sample_size: self.sample(rng),
+            consistency_check_repair: self.sample(rng),
+            rebuild_from_postgres: self.sample(rng),
+            proof_data_follower_path: self.sample(rng),
+            snapshot_export_interval_sec: self.sample(rng),
+            snapshot_export_chunk_size: self.sample(rng),
+            l1_root_check_interval_sec: self.sample(rng),
+            column_families: self.sample_collect(rng),
         }
     }
 }
@@ -271,6 +364,11 @@ impl Distribution<configs::database::DBConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::DBConfig {
         configs::database::DBConfig {
             state_keeper_db_path: self.sample(rng),
+            state_keeper_db_block_cache_size_mb: self.sample(rng),
+            state_keeper_db_column_families: self.sample_collect(rng),
+            state_keeper_db_values_cache_size_mb: self.sample(rng),
+            state_keeper_db_factory_deps_cache_size_mb: self.sample(rng),
+            state_keeper_db_cache_warm_up_l1_batches: self.sample(rng),
             merkle_tree: self.sample(rng),
         }
     }
@@ -286,8 +384,11 @@ impl Distribution<configs::database::PostgresConfig> for EncodeDist {
             max_connections_master: self.sample(rng),
             acquire_timeout_sec: self.sample(rng),
             statement_timeout_sec: self.sample(rng),
+            max_replica_lag_sec: self.sample(rng),
             long_connection_threshold_ms: self.sample(rng),
             slow_query_threshold_ms: self.sample(rng),
+            query_timeout_ms: self.sample(rng),
+            schema: self.sample(rng),
             test_server_url: self.sample(rng),
             test_prover_url: self.sample(rng),
         }
@@ -301,6 +402,7 @@ impl Distribution<configs::ETHConfig> for EncodeDist {
             gas_adjuster: self.sample(rng),
             watcher: self.sample(rng),
             web3_url: self.sample(rng),
+            gas_price_web3_urls: self.sample_collect(rng),
         }
     }
 }
@@ -361,6 +463,17 @@ impl Distribution<configs::eth_sender::SenderConfig> for EncodeDist {
     }
 }
 
+impl Distribution<configs::eth_sender::FeeEstimationStrategy> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::FeeEstimationStrategy {
+        type T = configs::eth_sender::FeeEstimationStrategy;
+        match rng.gen_range(0..3) {
+            0 => T::Median,
+            1 => T::Ewma,
+            _ => T::Percentile,
+        }
+    }
+}
+
 impl Distribution<configs::eth_sender::GasAdjusterConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::GasAdjusterConfig {
         configs::eth_sender::GasAdjusterConfig {
@@ -375,6 +488,11 @@ impl Distribution<configs::eth_sender::GasAdjusterConfig> for EncodeDist {
             num_samples_for_blob_base_fee_estimate: self.sample(rng),
             internal_pubdata_pricing_multiplier: self.sample(rng),
             max_blob_base_fee: self.sample(rng),
+            fee_estimation_strategy: self.sample(rng),
+            ewma_smoothing_factor: self.sample(rng),
+            fee_estimation_percentile: self.sample(rng),
+            l1_gas_price_quorum: self.sample(rng),
+            l1_gas_price_max_deviation: self.sample(rng),
         }
     }
 }
@@ -565,6 +683,12 @@ impl Distribution<configs::house_keeper::HouseKeeperConfig> for EncodeDist {
             proof_compressor_stats_reporting_interval_ms: self.sample(rng),
             prover_job_archiver_reporting_interval_ms: self.sample(rng),
             prover_job_archiver_archiving_interval_secs: self.sample(rng),
+            object_store_archiver_interval_ms: self.sample(rng),
+            object_store_archiver_retention_secs: self.sample(rng),
+            object_store_gc_interval_ms: self.sample(rng),
+            object_store_gc_retention_secs: self.sample(rng),
+            storage_logs_partition_rotation_interval_ms: self.sample(rng),
+            storage_logs_partition_size: self.sample(rng),
         }
     }
 }
@@ -572,7 +696,7 @@ impl Distribution<configs::house_keeper::HouseKeeperConfig> for EncodeDist {
 impl Distribution<configs::object_store::ObjectStoreMode> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::object_store::ObjectStoreMode {
         type T = configs::object_store::ObjectStoreMode;
-        match rng.gen_range(0..4) {
+        match rng.gen_range(0..6) {
             0 => T::GCS {
                 bucket_base_url: self.sample(rng),
             },
@@ -582,6 +706,22 @@ impl Distribution<configs::object_store::ObjectStoreMode> for EncodeDist {
             },
             2 => T::FileBacked {
                 file_backed_base_path: self.sample(rng),
+                file_backed_fsync: self.sample(rng),
+                file_backed_sharding: self.sample(rng),
+                file_backed_archive_path: self.sample(rng),
+            },
+            3 => T::S3 {
+                bucket_base_url: self.sample(rng),
+                region: self.sample(rng),
+                endpoint: self.sample(rng),
+                force_path_style: self.sample(rng),
+                access_key_id: self.sample(rng),
+                secret_access_key: self.sample(rng),
+            },
+            4 => T::AzureBlob {
+                azure_storage_account: self.sample(rng),
+                bucket_base_url: self.sample(rng),
+                azure_sas_token: self.sample(rng),
             },
             _ => T::GCSAnonymousReadOnly {
                 bucket_base_url: self.sample(rng),
@@ -595,6 +735,14 @@ impl Distribution<configs::ObjectStoreConfig> for EncodeDist {
         configs::ObjectStoreConfig {
             mode: self.sample(rng),
             max_retries: self.sample(rng),
+            request_timeout_secs: self.sample(rng),
+            circuit_breaker_threshold: self.sample(rng),
+            circuit_breaker_cooldown_secs: self.sample(rng),
+            cold_storage_class: self.sample(rng),
+            content_addressed: self.sample(rng),
+            encryption_master_key: self.sample(rng),
+            encryption_kms_key_id: self.sample(rng),
+            encrypted_buckets: self.sample_collect(rng),
         }
     }
 }
@@ -608,6 +756,29 @@ impl Distribution<configs::ProofDataHandlerConfig> for EncodeDist {
     }
 }
 
+impl Distribution<configs::FinalityWebhooksConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::FinalityWebhooksConfig {
+        configs::FinalityWebhooksConfig {
+            urls: self.sample_collect(rng),
+            poll_interval_ms: self.sample(rng),
+            max_delivery_attempts: self.sample(rng),
+            request_timeout_ms: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::DataRetentionConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::DataRetentionConfig {
+        configs::DataRetentionConfig {
+            events_retention_days: self.sample(rng),
+            call_traces_retention_days: self.sample(rng),
+            transaction_traces_retention_days: self.sample(rng),
+            poll_interval_ms: self.sample(rng),
+            deletion_batch_size: self.sample(rng),
+        }
+    }
+}
+
 impl Distribution<configs::SnapshotsCreatorConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::SnapshotsCreatorConfig {
         configs::SnapshotsCreatorConfig {
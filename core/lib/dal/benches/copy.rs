@@ -0,0 +1,278 @@
+//! Benchmarks comparing bulk insertion of storage logs and events using the text `COPY` format (used by
+//! `insert_storage_logs()` / `save_events()`) and the binary `COPY` format (used by `insert_storage_logs_copy()` /
+//! `insert_events_copy()`). Snapshot recovery (inserting storage logs and initial writes from a snapshot chunk)
+//! is benchmarked as well.
+//!
+//! The benchmarks require a Postgres instance; its URL is taken from the `TEST_DATABASE_URL` env var,
+//! same as for DAL unit tests.
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::{
+    block::{MiniblockHasher, MiniblockHeader},
+    fee_model::BatchFeeInput,
+    snapshots::SnapshotStorageLog,
+    tx::IncludedTxLocation,
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
+    StorageKey, StorageLog, VmEvent, H256,
+};
+
+const ROW_COUNTS: &[usize] = &[100, 1_000, 10_000];
+/// Number of storage logs / events per transaction.
+const ROWS_PER_TX: usize = 10;
+
+#[derive(Debug, Clone, Copy)]
+enum CopyFormat {
+    Text,
+    Binary,
+}
+
+impl CopyFormat {
+    const ALL: [Self; 2] = [Self::Text, Self::Binary];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Binary => "binary",
+        }
+    }
+}
+
+async fn prepare_pool() -> ConnectionPool<Core> {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+
+    let number = MiniblockNumber(1);
+    let protocol_version = ProtocolVersionId::default();
+    let header = MiniblockHeader {
+        number,
+        timestamp: 1,
+        hash: MiniblockHasher::new(number, 0, H256::zero()).finalize(protocol_version),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        fee_account_address: Address::default(),
+        gas_per_pubdata_limit: 100,
+        base_fee_per_gas: 100,
+        batch_fee_input: BatchFeeInput::l1_pegged(100, 100),
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(protocol_version),
+        virtual_blocks: 1,
+        gas_limit: 0,
+        logs_bloom: Default::default(),
+    };
+    conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+    drop(conn);
+    pool
+}
+
+fn generate_storage_logs(count: usize) -> Vec<(H256, Vec<StorageLog>)> {
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    let logs: Vec<_> = (0..count as u64)
+        .map(|i| {
+            let key = StorageKey::new(account, H256::from_low_u64_be(i));
+            StorageLog::new_write_log(key, H256::from_low_u64_be(i + 1))
+        })
+        .collect();
+    logs.chunks(ROWS_PER_TX)
+        .enumerate()
+        .map(|(i, chunk)| (H256::from_low_u64_be(i as u64), chunk.to_vec()))
+        .collect()
+}
+
+fn generate_events(count: usize) -> Vec<(IncludedTxLocation, Vec<VmEvent>)> {
+    let events: Vec<_> = (0..count)
+        .map(|i| VmEvent {
+            location: (L1BatchNumber(1), (i / ROWS_PER_TX) as u32),
+            address: Address::repeat_byte(i as u8),
+            indexed_topics: (0..i % 5).map(|j| H256::repeat_byte(j as u8)).collect(),
+            value: vec![i as u8; 64],
+        })
+        .collect();
+    events
+        .chunks(ROWS_PER_TX)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(i as u64),
+                tx_index_in_miniblock: i as u32,
+                tx_initiator_address: Address::repeat_byte(1),
+            };
+            (location, chunk.to_vec())
+        })
+        .collect()
+}
+
+fn generate_snapshot_logs(count: usize) -> Vec<SnapshotStorageLog> {
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    (0..count as u64)
+        .map(|i| SnapshotStorageLog {
+            key: StorageKey::new(account, H256::from_low_u64_be(i)),
+            value: H256::from_low_u64_be(i + 1),
+            l1_batch_number_of_initial_write: L1BatchNumber(1),
+            enumeration_index: i + 1,
+        })
+        .collect()
+}
+
+/// Measures the total insertion time for `iters` iterations. Each insertion is rolled back afterwards.
+async fn insert_storage_logs(
+    pool: &ConnectionPool<Core>,
+    format: CopyFormat,
+    logs: &[(H256, Vec<StorageLog>)],
+    iters: u64,
+) -> Duration {
+    let mut conn = pool.connection().await.unwrap();
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let started_at = Instant::now();
+        let mut dal = transaction.storage_logs_dal();
+        match format {
+            CopyFormat::Text => dal.insert_storage_logs(MiniblockNumber(1), logs).await,
+            CopyFormat::Binary => dal.insert_storage_logs_copy(MiniblockNumber(1), logs).await,
+        }
+        .unwrap();
+        total += started_at.elapsed();
+        // Dropping the transaction rolls it back.
+    }
+    total
+}
+
+async fn insert_events(
+    pool: &ConnectionPool<Core>,
+    format: CopyFormat,
+    events: &[(IncludedTxLocation, Vec<VmEvent>)],
+    iters: u64,
+) -> Duration {
+    let events: Vec<_> = events
+        .iter()
+        .map(|(location, events)| (*location, events.iter().collect()))
+        .collect();
+    let mut conn = pool.connection().await.unwrap();
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let started_at = Instant::now();
+        let mut dal = transaction.events_dal();
+        match format {
+            CopyFormat::Text => dal.save_events(MiniblockNumber(1), &events).await,
+            CopyFormat::Binary => dal
+                .insert_events_copy(MiniblockNumber(1), &events)
+                .await
+                .unwrap(),
+        }
+        total += started_at.elapsed();
+    }
+    total
+}
+
+/// Inserts storage logs and initial writes in the same way as the snapshot applier does for a single chunk.
+async fn insert_snapshot_logs(
+    pool: &ConnectionPool<Core>,
+    format: CopyFormat,
+    logs: &[SnapshotStorageLog],
+    iters: u64,
+) -> Duration {
+    let mut conn = pool.connection().await.unwrap();
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let started_at = Instant::now();
+        match format {
+            CopyFormat::Text => {
+                transaction
+                    .storage_logs_dal()
+                    .insert_storage_logs_from_snapshot(MiniblockNumber(1), logs)
+                    .await
+                    .unwrap();
+                transaction
+                    .storage_logs_dedup_dal()
+                    .insert_initial_writes_from_snapshot(logs)
+                    .await
+                    .unwrap();
+            }
+            CopyFormat::Binary => {
+                transaction
+                    .storage_logs_dal()
+                    .insert_storage_logs_from_snapshot_copy(MiniblockNumber(1), logs)
+                    .await
+                    .unwrap();
+                transaction
+                    .storage_logs_dedup_dal()
+                    .insert_initial_writes_from_snapshot_copy(logs)
+                    .await
+                    .unwrap();
+            }
+        }
+        total += started_at.elapsed();
+    }
+    total
+}
+
+fn copy_benches(criterion: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let pool = runtime.block_on(prepare_pool());
+
+    let mut storage_logs_benches = criterion.benchmark_group("insert_storage_logs");
+    for &count in ROW_COUNTS {
+        let logs = generate_storage_logs(count);
+        storage_logs_benches.throughput(Throughput::Elements(count as u64));
+        for format in CopyFormat::ALL {
+            storage_logs_benches.bench_with_input(
+                BenchmarkId::new(format.name(), count),
+                &logs,
+                |bencher, logs| {
+                    bencher.iter_custom(|iters| {
+                        runtime.block_on(insert_storage_logs(&pool, format, logs, iters))
+                    });
+                },
+            );
+        }
+    }
+    storage_logs_benches.finish();
+
+    let mut events_benches = criterion.benchmark_group("insert_events");
+    for &count in ROW_COUNTS {
+        let events = generate_events(count);
+        events_benches.throughput(Throughput::Elements(count as u64));
+        for format in CopyFormat::ALL {
+            events_benches.bench_with_input(
+                BenchmarkId::new(format.name(), count),
+                &events,
+                |bencher, events| {
+                    bencher.iter_custom(|iters| {
+                        runtime.block_on(insert_events(&pool, format, events, iters))
+                    });
+                },
+            );
+        }
+    }
+    events_benches.finish();
+
+    let mut snapshot_benches = criterion.benchmark_group("insert_snapshot_logs");
+    for &count in ROW_COUNTS {
+        let logs = generate_snapshot_logs(count);
+        snapshot_benches.throughput(Throughput::Elements(count as u64));
+        for format in CopyFormat::ALL {
+            snapshot_benches.bench_with_input(
+                BenchmarkId::new(format.name(), count),
+                &logs,
+                |bencher, logs| {
+                    bencher.iter_custom(|iters| {
+                        runtime.block_on(insert_snapshot_logs(&pool, format, logs, iters))
+                    });
+                },
+            );
+        }
+    }
+    snapshot_benches.finish();
+}
+
+criterion_group!(benches, copy_benches);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS metrics_snapshots
+(
+    id         BIGSERIAL PRIMARY KEY,
+    metrics    JSONB     NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS metrics_snapshots_created_at_idx ON metrics_snapshots (created_at);
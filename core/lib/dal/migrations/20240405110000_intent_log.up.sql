@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS intent_log
+(
+    id         BIGSERIAL PRIMARY KEY,
+    operation  TEXT      NOT NULL,
+    params     JSONB     NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS intent_log_operation_idx ON intent_log (operation);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS governance_operations
+(
+    id               BIGSERIAL PRIMARY KEY,
+    operation_id     BYTEA     NOT NULL UNIQUE,
+    description      TEXT      NOT NULL,
+    operation        JSONB     NOT NULL,
+    delay            BIGINT    NOT NULL,
+    status           TEXT      NOT NULL,
+    schedule_tx_hash BYTEA,
+    execute_tx_hash  BYTEA,
+    error            TEXT,
+    created_at       TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS governance_operations_status_idx ON governance_operations (status);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS main_node_fee_params;
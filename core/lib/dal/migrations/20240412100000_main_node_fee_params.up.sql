@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS main_node_fee_params (
+    fee_params JSONB NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    -- artificial primary key ensuring that the table contains at most 1 row.
+    fake_key BOOLEAN PRIMARY KEY,
+    CHECK (fake_key)
+);
@@ -0,0 +1,4 @@
+-- Compressed values must be decompressed before reverting this migration; otherwise, they will become unreadable.
+DROP TABLE IF EXISTS block_body_compression;
+ALTER TABLE transactions DROP COLUMN IF EXISTS input_compression;
+ALTER TABLE events DROP COLUMN IF EXISTS value_compression;
@@ -0,0 +1,11 @@
+-- `NULL` means that the value is not compressed.
+ALTER TABLE events ADD COLUMN IF NOT EXISTS value_compression SMALLINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS input_compression SMALLINT;
+
+CREATE TABLE IF NOT EXISTS block_body_compression (
+    last_compressed_miniblock BIGINT NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    -- artificial primary key ensuring that the table contains at most 1 row.
+    fake_key BOOLEAN PRIMARY KEY,
+    CHECK (fake_key)
+);
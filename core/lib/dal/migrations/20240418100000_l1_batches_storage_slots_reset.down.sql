@@ -0,0 +1 @@
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS storage_slots_reset;
@@ -0,0 +1,2 @@
+-- Number of storage slots reset to their original values by transactions in the batch.
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS storage_slots_reset BIGINT;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS underfunded_priority_ops;
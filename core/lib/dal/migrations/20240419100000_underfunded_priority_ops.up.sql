@@ -0,0 +1,13 @@
+-- Priority operations that were underfunded w.r.t. the L2 fee model at the moment they were received by the server.
+CREATE TABLE IF NOT EXISTS underfunded_priority_ops
+(
+    priority_op_id                 BIGINT      NOT NULL PRIMARY KEY,
+    tx_hash                        BYTEA       NOT NULL REFERENCES transactions (hash) ON DELETE CASCADE,
+    max_fee_per_gas                NUMERIC(80) NOT NULL,
+    required_max_fee_per_gas       NUMERIC(80) NOT NULL,
+    gas_per_pubdata_limit          NUMERIC(80) NOT NULL,
+    required_gas_per_pubdata_limit NUMERIC(80) NOT NULL,
+
+    created_at                     TIMESTAMP   NOT NULL
+);
+CREATE INDEX IF NOT EXISTS underfunded_priority_ops_tx_hash_idx ON underfunded_priority_ops (tx_hash);
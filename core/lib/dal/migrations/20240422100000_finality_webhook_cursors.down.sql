@@ -0,0 +1 @@
+DROP TABLE IF EXISTS finality_webhook_cursors;
@@ -0,0 +1,12 @@
+-- Last L1 batch for which a finality webhook notification was delivered, per webhook and L1 batch stage.
+-- Webhooks are identified by the hash of their URL, so that URLs (which may contain credentials) are not persisted.
+CREATE TABLE IF NOT EXISTS finality_webhook_cursors
+(
+    webhook_id      BYTEA     NOT NULL,
+    action_type     TEXT      NOT NULL,
+    l1_batch_number BIGINT    NOT NULL,
+
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL,
+    PRIMARY KEY (webhook_id, action_type)
+);
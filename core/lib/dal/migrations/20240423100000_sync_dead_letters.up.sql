@@ -0,0 +1,16 @@
+-- Sync actions that the external node has failed to apply, together with the error and the operator's resolution.
+CREATE TABLE IF NOT EXISTS sync_dead_letters
+(
+    id               BIGSERIAL PRIMARY KEY,
+    miniblock_number BIGINT    NOT NULL,
+    tx_hash          BYTEA,
+    action           TEXT      NOT NULL,
+    error            TEXT      NOT NULL,
+    resolution       TEXT,
+
+    created_at       TIMESTAMP NOT NULL,
+    resolved_at      TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS sync_dead_letters_skipped_tx_hash_idx
+    ON sync_dead_letters (tx_hash) WHERE resolution = 'skip';
@@ -0,0 +1,15 @@
+-- Per-transaction execution metrics recorded by the state keeper (potentially for a sample of transactions).
+CREATE TABLE IF NOT EXISTS tx_execution_metrics
+(
+    tx_hash           BYTEA     NOT NULL PRIMARY KEY,
+    miniblock_number  BIGINT    NOT NULL,
+    contract_address  BYTEA     NOT NULL,
+    execution_time_us BIGINT    NOT NULL,
+    gas_used          BIGINT    NOT NULL,
+    pubdata_bytes     BIGINT    NOT NULL,
+    storage_writes    BIGINT    NOT NULL,
+
+    created_at        TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS tx_execution_metrics_miniblock_number_idx ON tx_execution_metrics (miniblock_number);
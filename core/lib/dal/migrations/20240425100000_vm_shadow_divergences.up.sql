@@ -0,0 +1,14 @@
+-- Divergences between the main VM and the shadow VM detected by the state keeper in the shadow execution mode.
+CREATE TABLE IF NOT EXISTS vm_shadow_divergences
+(
+    id                BIGSERIAL PRIMARY KEY,
+    l1_batch_number   BIGINT    NOT NULL,
+    -- `NULL` for divergences in the batch tip (i.e., when finishing the batch).
+    tx_hash           BYTEA,
+    shadow_vm_version TEXT      NOT NULL,
+    details           TEXT      NOT NULL,
+
+    created_at        TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS vm_shadow_divergences_l1_batch_number_idx ON vm_shadow_divergences (l1_batch_number);
@@ -0,0 +1,3 @@
+ALTER TABLE tx_execution_metrics
+    DROP COLUMN IF EXISTS computational_gas_used,
+    DROP COLUMN IF EXISTS circuits_used;
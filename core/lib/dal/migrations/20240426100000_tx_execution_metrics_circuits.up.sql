@@ -0,0 +1,4 @@
+ALTER TABLE tx_execution_metrics
+    ADD COLUMN IF NOT EXISTS computational_gas_used BIGINT NOT NULL DEFAULT 0,
+    -- Estimated number of circuits of all types used by the transaction (may be fractional).
+    ADD COLUMN IF NOT EXISTS circuits_used DOUBLE PRECISION NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- L1 batches with protective reads persisted asynchronously by the protective reads writer, which are not persisted yet.
+CREATE TABLE IF NOT EXISTS pending_protective_reads
+(
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+
+    created_at      TIMESTAMP NOT NULL
+);
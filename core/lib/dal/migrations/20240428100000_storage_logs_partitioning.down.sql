@@ -0,0 +1,21 @@
+CREATE TABLE storage_logs_unpartitioned
+(
+    hashed_key       BYTEA     NOT NULL,
+    address          BYTEA     NOT NULL,
+    key              BYTEA     NOT NULL,
+    value            BYTEA     NOT NULL,
+    operation_number INT       NOT NULL,
+    tx_hash          BYTEA     NOT NULL,
+    miniblock_number BIGINT    NOT NULL,
+
+    created_at       TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL
+);
+INSERT INTO storage_logs_unpartitioned SELECT * FROM storage_logs;
+DROP TABLE storage_logs;
+
+ALTER TABLE storage_logs_unpartitioned RENAME TO storage_logs;
+ALTER TABLE storage_logs ADD PRIMARY KEY (hashed_key, miniblock_number, operation_number);
+CREATE INDEX storage_logs_block_number_idx ON storage_logs (miniblock_number);
+CREATE INDEX storage_logs_contract_address_tx_hash_idx_upd ON storage_logs (tx_hash)
+    WHERE (address = '\x0000000000000000000000000000000000008002'::bytea);
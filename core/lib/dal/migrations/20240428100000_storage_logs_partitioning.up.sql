@@ -0,0 +1,40 @@
+-- Converts `storage_logs` into a table partitioned by miniblock ranges. Existing rows are kept in a single partition
+-- (`storage_logs_legacy`) covering all miniblocks present in the table; attaching it requires a single scan of the table.
+-- Partitions for subsequent miniblocks are created by the storage logs partition rotator in the house keeper;
+-- rows not covered by any range partition are stored in the `storage_logs_default` partition.
+ALTER TABLE storage_logs RENAME TO storage_logs_legacy;
+ALTER TABLE storage_logs_legacy RENAME CONSTRAINT storage_logs_pkey TO storage_logs_legacy_pkey;
+ALTER INDEX storage_logs_block_number_idx RENAME TO storage_logs_legacy_block_number_idx;
+ALTER INDEX storage_logs_contract_address_tx_hash_idx_upd RENAME TO storage_logs_legacy_contract_address_tx_hash_idx_upd;
+
+CREATE TABLE storage_logs
+(
+    hashed_key       BYTEA     NOT NULL,
+    address          BYTEA     NOT NULL,
+    key              BYTEA     NOT NULL,
+    value            BYTEA     NOT NULL,
+    operation_number INT       NOT NULL,
+    tx_hash          BYTEA     NOT NULL,
+    miniblock_number BIGINT    NOT NULL,
+
+    created_at       TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL,
+    PRIMARY KEY (hashed_key, miniblock_number, operation_number)
+) PARTITION BY RANGE (miniblock_number);
+
+CREATE INDEX storage_logs_block_number_idx ON storage_logs (miniblock_number);
+CREATE INDEX storage_logs_contract_address_tx_hash_idx_upd ON storage_logs (tx_hash)
+    WHERE (address = '\x0000000000000000000000000000000000008002'::bytea);
+
+DO $$
+DECLARE
+    legacy_end BIGINT;
+BEGIN
+    SELECT COALESCE(MAX(miniblock_number) + 1, 0) INTO legacy_end FROM storage_logs_legacy;
+    EXECUTE format(
+        'ALTER TABLE storage_logs ATTACH PARTITION storage_logs_legacy FOR VALUES FROM (MINVALUE) TO (%s)',
+        legacy_end
+    );
+END $$;
+
+CREATE TABLE storage_logs_default PARTITION OF storage_logs DEFAULT;
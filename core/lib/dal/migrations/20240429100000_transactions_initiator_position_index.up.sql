@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS transactions_initiator_address_miniblock_number_tx_index_idx
+    ON transactions (initiator_address, miniblock_number, index_in_block);
@@ -0,0 +1,12 @@
+-- Addresses participating in calls from stored call traces; used to filter traces by address in `trace_filter`.
+CREATE TABLE IF NOT EXISTS call_trace_addresses (
+    tx_hash BYTEA NOT NULL REFERENCES call_traces (tx_hash) ON DELETE CASCADE,
+    address BYTEA NOT NULL,
+    -- `true` if the address is the sender of at least one call in the trace, `false` if it's a recipient.
+    is_sender BOOLEAN NOT NULL,
+    miniblock_number BIGINT NOT NULL,
+    PRIMARY KEY (tx_hash, address, is_sender)
+);
+
+CREATE INDEX IF NOT EXISTS call_trace_addresses_address_miniblock_number_idx
+    ON call_trace_addresses (address, is_sender, miniblock_number);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS logs_bloom_ranges;
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS logs_bloom;
@@ -0,0 +1,11 @@
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS logs_bloom BYTEA;
+
+-- Logs blooms aggregated over fixed-size ranges of miniblocks; used to skip ranges in `eth_getLogs`.
+-- A range is removed together with its last miniblock, e.g. on reverts.
+CREATE TABLE IF NOT EXISTS logs_bloom_ranges (
+    start_miniblock BIGINT PRIMARY KEY,
+    end_miniblock BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    logs_bloom BYTEA NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS logs_bloom_ranges_end_miniblock_idx ON logs_bloom_ranges (end_miniblock);
@@ -0,0 +1 @@
+ALTER TABLE snapshots DROP COLUMN IF EXISTS storage_logs_hashes;
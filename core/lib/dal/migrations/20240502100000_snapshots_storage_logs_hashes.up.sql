@@ -0,0 +1,4 @@
+-- Content hashes of storage log chunks; an empty value means that the hash is unknown.
+ALTER TABLE snapshots ADD COLUMN IF NOT EXISTS storage_logs_hashes BYTEA[] NOT NULL DEFAULT '{}';
+UPDATE snapshots
+SET storage_logs_hashes = ARRAY_FILL(''::BYTEA, ARRAY[CARDINALITY(storage_logs_filepaths)]);
@@ -0,0 +1,8 @@
+-- Progress of house keeper jobs managing artifacts of L1 batches in the object store, so that the jobs
+-- don't rescan all L1 batches after a restart.
+CREATE TABLE IF NOT EXISTS object_store_lifecycle_progress (
+    job TEXT PRIMARY KEY,
+    next_l1_batch BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS sync_dead_letters_unresolved_action_idx;
@@ -0,0 +1,13 @@
+-- Keep a single unresolved dead letter per failed action, so that the node re-applying the same action
+-- after restarts doesn't flood the table with duplicates.
+DELETE FROM sync_dead_letters
+WHERE resolution IS NULL
+    AND id NOT IN (
+        SELECT MIN(id)
+        FROM sync_dead_letters
+        WHERE resolution IS NULL
+        GROUP BY miniblock_number, tx_hash, action
+    );
+
+CREATE UNIQUE INDEX IF NOT EXISTS sync_dead_letters_unresolved_action_idx
+    ON sync_dead_letters (miniblock_number, COALESCE(tx_hash, ''::BYTEA), action) WHERE resolution IS NULL;
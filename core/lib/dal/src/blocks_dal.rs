@@ -3,6 +3,7 @@ use std::{
     convert::{Into, TryInto},
     ops,
     ops::RangeInclusive,
+    time::Duration,
 };
 
 use anyhow::Context as _;
@@ -396,6 +397,34 @@ impl BlocksDal<'_, '_> {
         Ok(Some(storage_refunds))
     }
 
+    /// Returns the number of storage slots reset to their original values by transactions
+    /// in the specified L1 batch. Returns `None` if the batch is not present or was sealed
+    /// before this value was tracked.
+    pub async fn get_storage_slots_reset(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                storage_slots_reset
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            i64::from(number.0)
+        )
+        .instrument("get_storage_slots_reset")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row
+            .and_then(|row| row.storage_slots_reset)
+            .map(|count| count as u64))
+    }
+
     pub async fn set_eth_tx_id(
         &mut self,
         number_range: ops::RangeInclusive<L1BatchNumber>,
@@ -464,6 +493,7 @@ impl BlocksDal<'_, '_> {
         initial_bootloader_contents: &[(usize, U256)],
         predicted_block_gas: BlockGasCount,
         storage_refunds: &[u32],
+        storage_slots_reset: u64,
         predicted_circuits_by_type: CircuitStatistic, // predicted number of circuits for each circuit type
     ) -> anyhow::Result<()> {
         let priority_onchain_data: Vec<Vec<u8>> = header
@@ -514,6 +544,7 @@ impl BlocksDal<'_, '_> {
                     protocol_version,
                     system_logs,
                     storage_refunds,
+                    storage_slots_reset,
                     pubdata_input,
                     predicted_circuits_by_type,
                     created_at,
@@ -541,6 +572,7 @@ impl BlocksDal<'_, '_> {
                     $18,
                     $19,
                     $20,
+                    $21,
                     NOW(),
                     NOW()
                 )
@@ -563,6 +595,7 @@ impl BlocksDal<'_, '_> {
             header.protocol_version.map(|v| v as i32),
             &system_logs,
             &storage_refunds,
+            storage_slots_reset as i64,
             pubdata_input,
             serde_json::to_value(predicted_circuits_by_type).unwrap(),
         )
@@ -601,6 +634,7 @@ impl BlocksDal<'_, '_> {
                     virtual_blocks,
                     fair_pubdata_price,
                     gas_limit,
+                    logs_bloom,
                     created_at,
                     updated_at
                 )
@@ -622,6 +656,7 @@ impl BlocksDal<'_, '_> {
                     $14,
                     $15,
                     $16,
+                    $17,
                     NOW(),
                     NOW()
                 )
@@ -648,6 +683,7 @@ impl BlocksDal<'_, '_> {
             i64::from(miniblock_header.virtual_blocks),
             miniblock_header.batch_fee_input.fair_pubdata_price() as i64,
             miniblock_header.gas_limit as i64,
+            miniblock_header.logs_bloom.as_bytes(),
         )
         .execute(self.storage.conn())
         .await?;
@@ -676,7 +712,8 @@ impl BlocksDal<'_, '_> {
                 protocol_version,
                 virtual_blocks,
                 fair_pubdata_price,
-                gas_limit
+                gas_limit,
+                logs_bloom
             FROM
                 miniblocks
             ORDER BY
@@ -723,7 +760,8 @@ impl BlocksDal<'_, '_> {
                 protocol_version,
                 virtual_blocks,
                 fair_pubdata_price,
-                gas_limit
+                gas_limit,
+                logs_bloom
             FROM
                 miniblocks
             WHERE
@@ -1108,6 +1146,30 @@ impl BlocksDal<'_, '_> {
         .map(|row| L1BatchNumber(row.number as u32)))
     }
 
+    /// Returns the number of the last L1 batch which Ethereum execute tx was confirmed at least `min_age` ago.
+    pub async fn get_last_l1_batch_executed_before(
+        &mut self,
+        min_age: Duration,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(number) AS "number"
+            FROM
+                l1_batches
+                JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+            WHERE
+                execute_tx.confirmed_at <= NOW() - MAKE_INTERVAL(secs => $1)
+            "#,
+            min_age.as_secs_f64()
+        )
+        .instrument("get_last_l1_batch_executed_before")
+        .with_arg("min_age", &min_age)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
     /// This method returns batches that are confirmed on L1. That is, it doesn't wait for the proofs to be generated.
     ///
     /// # Params:
@@ -2402,7 +2464,7 @@ impl BlocksDal<'_, '_> {
     }
 
     pub async fn insert_mock_l1_batch(&mut self, header: &L1BatchHeader) -> anyhow::Result<()> {
-        self.insert_l1_batch(header, &[], Default::default(), &[], Default::default())
+        self.insert_l1_batch(header, &[], Default::default(), &[], 0, Default::default())
             .await
     }
 
@@ -2510,7 +2572,7 @@ mod tests {
             execute: 10,
         };
         conn.blocks_dal()
-            .insert_l1_batch(&header, &[], predicted_gas, &[], Default::default())
+            .insert_l1_batch(&header, &[], predicted_gas, &[], 0, Default::default())
             .await
             .unwrap();
 
@@ -2518,7 +2580,7 @@ mod tests {
         header.timestamp += 100;
         predicted_gas += predicted_gas;
         conn.blocks_dal()
-            .insert_l1_batch(&header, &[], predicted_gas, &[], Default::default())
+            .insert_l1_batch(&header, &[], predicted_gas, &[], 0, Default::default())
             .await
             .unwrap();
 
@@ -2551,6 +2613,38 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn getting_storage_slots_reset() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(&header, &[], Default::default(), &[], 5, Default::default())
+            .await
+            .unwrap();
+
+        let slots_reset = conn
+            .blocks_dal()
+            .get_storage_slots_reset(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(slots_reset, Some(5));
+        let slots_reset = conn
+            .blocks_dal()
+            .get_storage_slots_reset(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(slots_reset, None);
+    }
+
     #[allow(deprecated)] // that's the whole point
     #[tokio::test]
     async fn checking_fee_account_address_in_l1_batches() {
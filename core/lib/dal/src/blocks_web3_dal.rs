@@ -1,3 +1,5 @@
+use std::ops;
+
 use zksync_db_connection::{
     connection::Connection, instrument::InstrumentExt, interpolate_query, match_query_as,
 };
@@ -5,9 +7,10 @@ use zksync_system_constants::EMPTY_UNCLES_HASH;
 use zksync_types::{
     api,
     l2_to_l1_log::L2ToL1Log,
+    tx::SealedTxLocation,
     vm_trace::Call,
     web3::types::{BlockHeader, U64},
-    Bytes, L1BatchNumber, MiniblockNumber, H160, H2048, H256, U256,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H160, H2048, H256, U256,
 };
 use zksync_utils::bigdecimal_to_u256;
 
@@ -15,7 +18,7 @@ use crate::{
     models::{
         storage_block::{
             ResolvedL1BatchForMiniblock, StorageBlockDetails, StorageL1BatchDetails,
-            LEGACY_BLOCK_GAS_LIMIT,
+            StorageMiniblockFeeInput, LEGACY_BLOCK_GAS_LIMIT,
         },
         storage_transaction::CallTrace,
     },
@@ -41,6 +44,7 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.timestamp,
                 miniblocks.base_fee_per_gas,
                 miniblocks.gas_limit AS "block_gas_limit?",
+                miniblocks.logs_bloom,
                 prev_miniblock.hash AS "parent_hash?",
                 l1_batches.timestamp AS "l1_batch_timestamp?",
                 transactions.gas_limit AS "transaction_gas_limit?",
@@ -82,6 +86,11 @@ impl BlocksWeb3Dal<'_, '_> {
                         .unwrap_or(i64::from(LEGACY_BLOCK_GAS_LIMIT))
                         as u64)
                         .into(),
+                    logs_bloom: row
+                        .logs_bloom
+                        .as_deref()
+                        .map(H2048::from_slice)
+                        .unwrap_or_default(),
                     // TODO: include logs
                     ..api::Block::default()
                 }
@@ -481,17 +490,120 @@ impl BlocksWeb3Dal<'_, '_> {
         .collect())
     }
 
-    /// Returns `base_fee_per_gas` for miniblock range [min(newest_block - block_count + 1, 0), newest_block]
-    /// in descending order of miniblock numbers.
+    /// Returns call traces for transactions in the specified miniblock range in the order of their execution.
+    /// If `from_addresses` / `to_addresses` are non-empty, only traces containing a call from / to one of
+    /// the specified addresses are returned. Traces are paginated using `after`, the location of the last
+    /// transaction returned on the previous page.
+    pub async fn get_traces_for_filter(
+        &mut self,
+        numbers: ops::RangeInclusive<MiniblockNumber>,
+        from_addresses: &[Address],
+        to_addresses: &[Address],
+        after: Option<&SealedTxLocation>,
+        limit: usize,
+    ) -> sqlx::Result<Vec<(SealedTxLocation, Call)>> {
+        let from_addresses: Vec<_> = from_addresses.iter().map(Address::as_bytes).collect();
+        let to_addresses: Vec<_> = to_addresses.iter().map(Address::as_bytes).collect();
+        let (after_miniblock, after_index) = match after {
+            Some(location) => (
+                i64::from(location.miniblock_number.0),
+                location.tx_index_in_miniblock as i32,
+            ),
+            None => (i64::from(numbers.start().0), -1),
+        };
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.hash AS tx_hash,
+                transactions.miniblock_number AS "miniblock_number!",
+                transactions.index_in_block AS "index_in_block!",
+                miniblocks.hash AS miniblock_hash,
+                call_traces.call_trace
+            FROM
+                call_traces
+                INNER JOIN transactions ON call_traces.tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                transactions.miniblock_number BETWEEN $1 AND $2
+                AND (transactions.miniblock_number, transactions.index_in_block) > ($3, $4)
+                AND (
+                    CARDINALITY($5::bytea[]) = 0
+                    OR call_traces.tx_hash IN (
+                        SELECT
+                            tx_hash
+                        FROM
+                            call_trace_addresses
+                        WHERE
+                            address = ANY ($5)
+                            AND is_sender
+                            AND miniblock_number BETWEEN $1 AND $2
+                    )
+                )
+                AND (
+                    CARDINALITY($6::bytea[]) = 0
+                    OR call_traces.tx_hash IN (
+                        SELECT
+                            tx_hash
+                        FROM
+                            call_trace_addresses
+                        WHERE
+                            address = ANY ($6)
+                            AND NOT is_sender
+                            AND miniblock_number BETWEEN $1 AND $2
+                    )
+                )
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            LIMIT
+                $7
+            "#,
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0),
+            after_miniblock,
+            after_index,
+            &from_addresses as &[&[u8]],
+            &to_addresses as &[&[u8]],
+            limit as i64
+        )
+        .instrument("get_traces_for_filter")
+        .with_arg("numbers", &numbers)
+        .with_arg("after", &after)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let location = SealedTxLocation {
+                    miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                    miniblock_hash: H256::from_slice(&row.miniblock_hash),
+                    tx_hash: H256::from_slice(&row.tx_hash),
+                    tx_index_in_miniblock: row.index_in_block as u32,
+                };
+                let call = Call::from(CallTrace {
+                    call_trace: row.call_trace,
+                });
+                (location, call)
+            })
+            .collect())
+    }
+
+    /// Returns numbers, `base_fee_per_gas` and gas limits for miniblock range
+    /// [min(newest_block - block_count + 1, 0), newest_block] in descending order of miniblock numbers.
     pub async fn get_fee_history(
         &mut self,
         newest_block: MiniblockNumber,
         block_count: u64,
-    ) -> sqlx::Result<Vec<U256>> {
+    ) -> sqlx::Result<Vec<(MiniblockNumber, U256, u64)>> {
         let result: Vec<_> = sqlx::query!(
             r#"
             SELECT
-                base_fee_per_gas
+                number,
+                base_fee_per_gas,
+                gas_limit
             FROM
                 miniblocks
             WHERE
@@ -507,12 +619,96 @@ impl BlocksWeb3Dal<'_, '_> {
         .fetch_all(self.storage.conn())
         .await?
         .into_iter()
-        .map(|row| bigdecimal_to_u256(row.base_fee_per_gas))
+        .map(|row| {
+            let gas_limit = row.gas_limit.unwrap_or(i64::from(LEGACY_BLOCK_GAS_LIMIT));
+            (
+                MiniblockNumber(row.number as u32),
+                bigdecimal_to_u256(row.base_fee_per_gas),
+                gas_limit as u64,
+            )
+        })
         .collect();
 
         Ok(result)
     }
 
+    /// Returns effective gas prices and gas used by transactions in the specified range of miniblocks,
+    /// ordered by miniblock number and then by index in the miniblock.
+    pub async fn get_fee_history_transactions(
+        &mut self,
+        numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<(MiniblockNumber, U256, U256)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number AS "miniblock_number!",
+                effective_gas_price AS "effective_gas_price!",
+                gas_limit AS "gas_limit!",
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND effective_gas_price IS NOT NULL
+                AND gas_limit IS NOT NULL
+            ORDER BY
+                miniblock_number,
+                index_in_block
+            "#,
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let gas_limit = bigdecimal_to_u256(row.gas_limit);
+                let gas_used = gas_limit.saturating_sub(U256::from(row.refunded_gas as u64));
+                (
+                    MiniblockNumber(row.miniblock_number as u32),
+                    bigdecimal_to_u256(row.effective_gas_price),
+                    gas_used,
+                )
+            })
+            .collect())
+    }
+
+    /// Returns fee inputs for the specified range of miniblocks, ordered by miniblock number.
+    /// Miniblocks missing from the storage are skipped.
+    pub async fn get_miniblock_fee_inputs(
+        &mut self,
+        numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<api::MiniblockFeeInput>> {
+        let rows = sqlx::query_as!(
+            StorageMiniblockFeeInput,
+            r#"
+            SELECT
+                number,
+                timestamp,
+                protocol_version,
+                l1_gas_price,
+                l2_fair_gas_price,
+                fair_pubdata_price
+            FROM
+                miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            ORDER BY
+                number
+            "#,
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0)
+        )
+        .instrument("get_miniblock_fee_inputs")
+        .with_arg("numbers", &numbers)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     pub async fn get_block_details(
         &mut self,
         block_number: MiniblockNumber,
@@ -657,6 +853,7 @@ mod tests {
     use zksync_types::{
         block::{MiniblockHasher, MiniblockHeader},
         fee::TransactionExecutionMetrics,
+        fee_model::BatchFeeInput,
         Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
     };
 
@@ -898,5 +1095,73 @@ mod tests {
             let expected_trace = tx_result.call_trace().unwrap();
             assert_eq!(*trace, expected_trace);
         }
+
+        let numbers = MiniblockNumber(1)..=MiniblockNumber(1);
+        let traces = conn
+            .blocks_web3_dal()
+            .get_traces_for_filter(numbers.clone(), &[], &[], None, 10)
+            .await
+            .unwrap();
+        assert_eq!(traces.len(), 2);
+        for (i, ((location, trace), tx_result)) in traces.iter().zip(&tx_results).enumerate() {
+            assert_eq!(location.miniblock_number, MiniblockNumber(1));
+            assert_eq!(location.tx_hash, tx_result.hash);
+            assert_eq!(location.tx_index_in_miniblock, i as u32);
+            assert_eq!(*trace, tx_result.call_trace().unwrap());
+        }
+
+        let paginated_traces = conn
+            .blocks_web3_dal()
+            .get_traces_for_filter(numbers.clone(), &[], &[], Some(&traces[0].0), 10)
+            .await
+            .unwrap();
+        assert_eq!(paginated_traces, traces[1..]);
+
+        let sender = Address::from_low_u64_be(1);
+        let traces_from_sender = conn
+            .blocks_web3_dal()
+            .get_traces_for_filter(numbers.clone(), &[sender], &[], None, 10)
+            .await
+            .unwrap();
+        assert_eq!(traces_from_sender, traces[1..]);
+        let traces_to_sender = conn
+            .blocks_web3_dal()
+            .get_traces_for_filter(numbers, &[], &[sender], None, 10)
+            .await
+            .unwrap();
+        assert_eq!(traces_to_sender, traces[..1]);
+    }
+
+    #[tokio::test]
+    async fn getting_miniblock_fee_inputs() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let mut fee_inputs = vec![];
+        for number in 0..3 {
+            let mut header = create_miniblock_header(number);
+            header.batch_fee_input = BatchFeeInput::pubdata_independent(
+                1_000 + u64::from(number),
+                100,
+                10_000 + u64::from(number),
+            );
+            conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+            fee_inputs.push(header.batch_fee_input);
+        }
+
+        let inputs = conn
+            .blocks_web3_dal()
+            .get_miniblock_fee_inputs(MiniblockNumber(1)..=MiniblockNumber(5))
+            .await
+            .unwrap();
+        let numbers: Vec<_> = inputs.iter().map(|input| input.number).collect();
+        assert_eq!(numbers, [MiniblockNumber(1), MiniblockNumber(2)]);
+        for input in &inputs {
+            assert_eq!(input.timestamp, u64::from(input.number.0));
+            assert_eq!(input.fee_input, fee_inputs[input.number.0 as usize]);
+        }
     }
 }
@@ -0,0 +1,85 @@
+//! Compression of large byte columns at rest.
+//!
+//! A compressed value is accompanied by a non-`NULL` codec in the corresponding `*_compression` column
+//! (e.g., `events.value_compression`); `NULL` means that the value is stored as is. Thus, compressed
+//! and uncompressed values can coexist in the same column, and existing data can be compressed
+//! in the background. Values are decompressed transparently when loaded by the DAL.
+
+use std::io::{Read, Write};
+
+use anyhow::Context as _;
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+/// Codec for values compressed with DEFLATE.
+pub(crate) const DEFLATE_CODEC: i16 = 1;
+
+/// Compresses a value. Returns `None` if compression doesn't reduce the value size.
+pub(crate) fn compress(value: &[u8]) -> Option<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::with_capacity(value.len()), Compression::fast());
+    encoder
+        .write_all(value)
+        .expect("failed writing to in-memory buffer");
+    let compressed = encoder
+        .finish()
+        .expect("failed writing to in-memory buffer");
+    (compressed.len() < value.len()).then_some(compressed)
+}
+
+/// Decompresses a value loaded from Postgres.
+///
+/// # Errors
+///
+/// Returns an error if the codec is unknown or the value is corrupted.
+pub(crate) fn decompress(value: Vec<u8>, codec: Option<i16>) -> anyhow::Result<Vec<u8>> {
+    match codec {
+        None => Ok(value),
+        Some(DEFLATE_CODEC) => {
+            let mut decompressed = Vec::with_capacity(value.len() * 2);
+            DeflateDecoder::new(value.as_slice())
+                .read_to_end(&mut decompressed)
+                .context("corrupted compressed value in Postgres")?;
+            Ok(decompressed)
+        }
+        Some(codec) => anyhow::bail!("unknown compression codec {codec} in Postgres"),
+    }
+}
+
+/// Converts a decompression error so that it can be returned from DAL methods returning [`sqlx::Result`].
+pub(crate) fn decode_error(err: anyhow::Error) -> sqlx::Error {
+    sqlx::Error::Decode(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_roundtrip() {
+        let value: Vec<u8> = (0..1_024_u32).flat_map(|i| (i % 7).to_be_bytes()).collect();
+        let compressed = compress(&value).unwrap();
+        assert!(compressed.len() < value.len());
+        assert_eq!(decompress(compressed, Some(DEFLATE_CODEC)).unwrap(), value);
+        assert_eq!(decompress(value.clone(), None).unwrap(), value);
+    }
+
+    #[test]
+    fn decompressing_invalid_values() {
+        let value: Vec<u8> = (0..1_024_u32).flat_map(|i| (i % 7).to_be_bytes()).collect();
+        let compressed = compress(&value).unwrap();
+        let err = decompress(compressed.clone(), Some(100)).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown compression codec"),
+            "{err}"
+        );
+
+        let truncated = compressed[..compressed.len() / 2].to_vec();
+        let err = decompress(truncated, Some(DEFLATE_CODEC)).unwrap_err();
+        assert!(err.to_string().contains("corrupted"), "{err}");
+    }
+
+    #[test]
+    fn incompressible_values_are_not_compressed() {
+        let value = vec![42_u8];
+        assert_eq!(compress(&value), None);
+    }
+}
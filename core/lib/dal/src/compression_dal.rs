@@ -0,0 +1,397 @@
+use std::ops;
+
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::MiniblockNumber;
+
+use crate::{
+    compression::{compress, DEFLATE_CODEC},
+    Core,
+};
+
+/// Statistics of compressing values in a byte column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionStats {
+    /// Number of values that were compressed.
+    pub compressed_values: usize,
+    /// Total size of compressed values before compression.
+    pub raw_bytes: usize,
+    /// Total size of compressed values after compression.
+    pub compressed_bytes: usize,
+}
+
+impl CompressionStats {
+    fn observe(&mut self, raw_len: usize, compressed_len: usize) {
+        self.compressed_values += 1;
+        self.raw_bytes += raw_len;
+        self.compressed_bytes += compressed_len;
+    }
+}
+
+/// DAL compressing large byte columns of miniblock bodies (event payloads and raw transaction bytes)
+/// at rest. Compressed values are transparently decompressed when loaded by other DALs.
+#[derive(Debug)]
+pub struct CompressionDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl CompressionDal<'_, '_> {
+    /// Returns the last miniblock processed by the background compression, if any.
+    pub async fn get_last_compressed_miniblock(&mut self) -> sqlx::Result<Option<MiniblockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_compressed_miniblock
+            FROM
+                block_body_compression
+            WHERE
+                fake_key
+            "#
+        )
+        .instrument("get_last_compressed_miniblock")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| MiniblockNumber(row.last_compressed_miniblock as u32)))
+    }
+
+    /// Sets the last miniblock processed by the background compression.
+    pub async fn set_last_compressed_miniblock(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                block_body_compression (fake_key, last_compressed_miniblock, updated_at)
+            VALUES
+                (TRUE, $1, NOW())
+            ON CONFLICT (fake_key) DO
+            UPDATE
+            SET
+                last_compressed_miniblock = excluded.last_compressed_miniblock,
+                updated_at = excluded.updated_at
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("set_last_compressed_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Compresses uncompressed event payloads with size at least `min_value_size` bytes emitted
+    /// in the specified miniblocks. Payloads that don't shrink after compression are left as is.
+    pub async fn compress_event_values(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        min_value_size: usize,
+    ) -> sqlx::Result<CompressionStats> {
+        let mut transaction = self.storage.start_transaction().await?;
+        // Rows are locked so that they cannot be replaced (e.g., by a miniblock revert) until they are updated.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                event_index_in_block,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND value_compression IS NULL
+                AND LENGTH(value) >= $3
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            FOR UPDATE
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0),
+            min_value_size as i32
+        )
+        .instrument("compress_event_values#select")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(&mut transaction)
+        .await?;
+
+        let mut stats = CompressionStats::default();
+        let mut miniblock_numbers = vec![];
+        let mut event_indices = vec![];
+        let mut values = vec![];
+        for row in rows {
+            let Some(value) = compress(&row.value) else {
+                continue;
+            };
+            stats.observe(row.value.len(), value.len());
+            miniblock_numbers.push(row.miniblock_number);
+            event_indices.push(row.event_index_in_block);
+            values.push(value);
+        }
+        if stats.compressed_values == 0 {
+            return Ok(stats);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE events
+            SET
+                value = data_table.value,
+                value_compression = $4
+            FROM
+                (
+                    SELECT
+                        UNNEST($1::BIGINT[]) AS miniblock_number,
+                        UNNEST($2::INT[]) AS event_index_in_block,
+                        UNNEST($3::BYTEA[]) AS value
+                ) AS data_table
+            WHERE
+                events.miniblock_number = data_table.miniblock_number
+                AND events.event_index_in_block = data_table.event_index_in_block
+            "#,
+            &miniblock_numbers,
+            &event_indices,
+            &values,
+            DEFLATE_CODEC
+        )
+        .instrument("compress_event_values#update")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("values.len", &values.len())
+        .execute(&mut transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(stats)
+    }
+
+    /// Compresses uncompressed raw bytes with size at least `min_value_size` bytes of L2 transactions
+    /// included into the specified miniblocks. Values that don't shrink after compression are left as is.
+    pub async fn compress_transaction_inputs(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        min_value_size: usize,
+    ) -> sqlx::Result<CompressionStats> {
+        let mut transaction = self.storage.start_transaction().await?;
+        // Rows are locked so that they cannot be replaced (e.g., by a miniblock revert) until they are updated.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                input AS "input!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND input IS NOT NULL
+                AND input_compression IS NULL
+                AND LENGTH(input) >= $3
+            ORDER BY
+                hash
+            FOR UPDATE
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0),
+            min_value_size as i32
+        )
+        .instrument("compress_transaction_inputs#select")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(&mut transaction)
+        .await?;
+
+        let mut stats = CompressionStats::default();
+        let mut hashes = vec![];
+        let mut inputs = vec![];
+        for row in rows {
+            let Some(input) = compress(&row.input) else {
+                continue;
+            };
+            stats.observe(row.input.len(), input.len());
+            hashes.push(row.hash);
+            inputs.push(input);
+        }
+        if stats.compressed_values == 0 {
+            return Ok(stats);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                input = data_table.input,
+                input_compression = $3
+            FROM
+                (
+                    SELECT
+                        UNNEST($1::BYTEA[]) AS hash,
+                        UNNEST($2::BYTEA[]) AS input
+                ) AS data_table
+            WHERE
+                transactions.hash = data_table.hash
+            "#,
+            &hashes,
+            &inputs,
+            DEFLATE_CODEC
+        )
+        .instrument("compress_transaction_inputs#update")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("inputs.len", &inputs.len())
+        .execute(&mut transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        fee::TransactionExecutionMetrics, tx::IncludedTxLocation, Address,
+        ExecuteTransactionCommon, L1BatchNumber, ProtocolVersion, VmEvent, H256, U256,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool, Core, CoreDal,
+    };
+
+    const MIN_VALUE_SIZE: usize = 64;
+
+    async fn prepare_storage(conn: &mut Connection<'_, Core>) {
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..=1 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn compressing_event_values() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        prepare_storage(&mut conn).await;
+
+        let large_value = vec![1_u8; 1_024];
+        let events = [
+            VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: Address::repeat_byte(1),
+                indexed_topics: vec![H256::repeat_byte(1)],
+                value: large_value.clone(),
+            },
+            VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: Address::repeat_byte(2),
+                indexed_topics: vec![H256::repeat_byte(2)],
+                value: vec![2; 32],
+            },
+        ];
+        let location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &[(location, events.iter().collect())])
+            .await;
+
+        let miniblocks = MiniblockNumber(0)..=MiniblockNumber(1);
+        let stats = conn
+            .compression_dal()
+            .compress_event_values(miniblocks.clone(), MIN_VALUE_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(stats.compressed_values, 1);
+        assert_eq!(stats.raw_bytes, large_value.len());
+        assert!(stats.compressed_bytes < stats.raw_bytes, "{stats:?}");
+
+        // Compressed values must not be compressed again.
+        let stats = conn
+            .compression_dal()
+            .compress_event_values(miniblocks, MIN_VALUE_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(stats, CompressionStats::default());
+
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        let log_values: Vec<_> = logs.iter().map(|log| &log.data.0).collect();
+        assert_eq!(log_values, [&events[0].value, &events[1].value]);
+    }
+
+    #[tokio::test]
+    async fn compressing_transaction_inputs() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        prepare_storage(&mut conn).await;
+
+        let input = vec![1_u8; 1_024];
+        let mut tx = mock_l2_transaction();
+        tx.set_input(input.clone(), H256::random());
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[mock_execution_result(tx)],
+                U256::from(1),
+            )
+            .await;
+
+        let stats = conn
+            .compression_dal()
+            .compress_transaction_inputs(MiniblockNumber(0)..=MiniblockNumber(1), MIN_VALUE_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(stats.compressed_values, 1);
+        assert_eq!(stats.raw_bytes, input.len());
+        assert!(stats.compressed_bytes < stats.raw_bytes, "{stats:?}");
+
+        let miniblocks = conn
+            .transactions_dal()
+            .get_miniblocks_to_reexecute()
+            .await
+            .unwrap();
+        assert_eq!(miniblocks.len(), 1);
+        let [restored_tx] = miniblocks[0].txs.as_slice() else {
+            panic!("Unexpected transactions: {:?}", miniblocks[0].txs);
+        };
+        assert_eq!(restored_tx.raw_bytes.as_ref().unwrap().0, input);
+        let ExecuteTransactionCommon::L2(common_data) = &restored_tx.common_data else {
+            panic!("Unexpected transaction: {restored_tx:?}");
+        };
+        assert_eq!(common_data.input_data(), Some(input.as_slice()));
+    }
+
+    #[tokio::test]
+    async fn persisting_compression_cursor() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let cursor = conn
+            .compression_dal()
+            .get_last_compressed_miniblock()
+            .await
+            .unwrap();
+        assert_eq!(cursor, None);
+
+        for number in [5, 10] {
+            conn.compression_dal()
+                .set_last_compressed_miniblock(MiniblockNumber(number))
+                .await
+                .unwrap();
+            let cursor = conn
+                .compression_dal()
+                .get_last_compressed_miniblock()
+                .await
+                .unwrap();
+            assert_eq!(cursor, Some(MiniblockNumber(number)));
+        }
+    }
+}
@@ -2,7 +2,8 @@ use std::{collections::HashMap, fmt};
 
 use sqlx::types::chrono::Utc;
 use zksync_db_connection::{
-    connection::Connection, instrument::InstrumentExt, write_str, writeln_str,
+    connection::Connection, copy::BinaryCopyBuffer, instrument::InstrumentExt, write_str,
+    writeln_str,
 };
 use zksync_system_constants::L1_MESSENGER_ADDRESS;
 use zksync_types::{
@@ -10,14 +11,19 @@ use zksync_types::{
     event::L1_MESSENGER_BYTECODE_PUBLICATION_EVENT_SIGNATURE,
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     tx::IncludedTxLocation,
-    Address, L1BatchNumber, MiniblockNumber, VmEvent, H256,
+    Address, L1BatchNumber, MiniblockNumber, VmEvent, H2048, H256,
 };
 
 use crate::{
+    compression::{decode_error, decompress},
     models::storage_event::{StorageL2ToL1Log, StorageWeb3Log},
     Core, CoreDal, SqlxError,
 };
 
+/// Number of miniblocks in a range for which logs blooms are aggregated. Ranges are aligned, i.e., start
+/// with a miniblock number divisible by this value.
+pub(crate) const LOGS_BLOOM_RANGE_SIZE: u32 = 1_000;
+
 /// Wrapper around an optional event topic allowing to hex-format it for `COPY` instructions.
 #[derive(Debug)]
 struct EventTopic<'a>(Option<&'a H256>);
@@ -99,6 +105,72 @@ impl EventsDal<'_, '_> {
         copy.finish().await.unwrap();
     }
 
+    /// Same as [`Self::save_events()`], but uses the binary `COPY` format, which is faster for large numbers of events.
+    pub async fn insert_events_copy(
+        &mut self,
+        block_number: MiniblockNumber,
+        all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
+    ) -> sqlx::Result<()> {
+        self.insert_events_copy_for_miniblocks(&[(block_number, all_block_events)])
+            .await
+    }
+
+    /// Same as [`Self::insert_events_copy()`], but inserts events for several miniblocks
+    /// with a single `COPY` statement.
+    pub async fn insert_events_copy_for_miniblocks(
+        &mut self,
+        events_by_miniblock: &[(MiniblockNumber, &[(IncludedTxLocation, Vec<&VmEvent>)])],
+    ) -> sqlx::Result<()> {
+        let mut buffer = BinaryCopyBuffer::new(14);
+        let now = Utc::now().naive_utc();
+        for &(block_number, all_block_events) in events_by_miniblock {
+            let mut event_index_in_block = 0_u32;
+            for (tx_location, events) in all_block_events {
+                let IncludedTxLocation {
+                    tx_hash,
+                    tx_index_in_miniblock,
+                    tx_initiator_address,
+                } = tx_location;
+
+                for (event_index_in_tx, event) in events.iter().enumerate() {
+                    buffer
+                        .start_row()
+                        .write_i64(block_number.0.into())
+                        .write_bytes(tx_hash.as_bytes())
+                        .write_i32(*tx_index_in_miniblock as i32)
+                        .write_bytes(event.address.as_bytes())
+                        .write_i32(event_index_in_block as i32)
+                        .write_i32(event_index_in_tx as i32);
+                    for topic_index in 0..4 {
+                        let topic = event.indexed_topics.get(topic_index);
+                        buffer.write_bytes(topic.map_or(&[][..], H256::as_bytes));
+                    }
+                    buffer
+                        .write_bytes(&event.value)
+                        .write_bytes(tx_initiator_address.as_bytes())
+                        .write_timestamp(now)
+                        .write_timestamp(now);
+                    event_index_in_block += 1;
+                }
+            }
+        }
+
+        buffer
+            .send(
+                self.storage.conn(),
+                "COPY events(
+                    miniblock_number, tx_hash, tx_index_in_block, address,
+                    event_index_in_block, event_index_in_tx,
+                    topic1, topic2, topic3, topic4, value,
+                    tx_initiator_address,
+                    created_at, updated_at
+                )
+                FROM STDIN (FORMAT BINARY)",
+            )
+            .await?;
+        Ok(())
+    }
+
     /// Removes events with a block number strictly greater than the specified `block_number`.
     pub async fn rollback_events(&mut self, block_number: MiniblockNumber) {
         sqlx::query!(
@@ -114,6 +186,72 @@ impl EventsDal<'_, '_> {
         .unwrap();
     }
 
+    /// Aggregates logs blooms for the range of miniblocks ending with `last_miniblock`, provided that it is
+    /// the last miniblock in its range. Should be called after each sealed miniblock. Returns `true`
+    /// if the range was aggregated.
+    ///
+    /// Ranges containing miniblocks without a persisted logs bloom (e.g., ones sealed before blooms were
+    /// introduced, or missing after snapshot recovery) are not aggregated, so they are never skipped
+    /// when looking up logs.
+    pub async fn aggregate_logs_blooms(
+        &mut self,
+        last_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<bool> {
+        if (last_miniblock.0 + 1) % LOGS_BLOOM_RANGE_SIZE != 0 {
+            return Ok(false);
+        }
+        let start_miniblock = last_miniblock.0 + 1 - LOGS_BLOOM_RANGE_SIZE;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                logs_bloom
+            FROM
+                miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            "#,
+            i64::from(start_miniblock),
+            i64::from(last_miniblock.0)
+        )
+        .instrument("aggregate_logs_blooms#get_blooms")
+        .with_arg("last_miniblock", &last_miniblock)
+        .fetch_all(self.storage)
+        .await?;
+        if rows.len() != LOGS_BLOOM_RANGE_SIZE as usize {
+            return Ok(false);
+        }
+        let mut range_bloom = H2048::zero();
+        for row in rows {
+            let Some(bloom) = row.logs_bloom else {
+                return Ok(false);
+            };
+            range_bloom.accrue_bloom(&H2048::from_slice(&bloom));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                logs_bloom_ranges (start_miniblock, end_miniblock, logs_bloom)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (start_miniblock) DO
+            UPDATE
+            SET
+                end_miniblock = excluded.end_miniblock,
+                logs_bloom = excluded.logs_bloom
+            "#,
+            i64::from(start_miniblock),
+            i64::from(last_miniblock.0),
+            range_bloom.as_bytes()
+        )
+        .instrument("aggregate_logs_blooms#insert_range")
+        .with_arg("last_miniblock", &last_miniblock)
+        .execute(self.storage)
+        .await?;
+        Ok(true)
+    }
+
     /// Saves user L2-to-L1 logs from a miniblock. Logs must be ordered by transaction location
     /// and within each transaction.
     pub async fn save_user_l2_to_l1_logs(
@@ -209,6 +347,7 @@ impl EventsDal<'_, '_> {
                 topic3,
                 topic4,
                 value,
+                value_compression,
                 NULL::bytea AS "block_hash",
                 NULL::BIGINT AS "l1_batch_number?",
                 miniblock_number,
@@ -232,7 +371,7 @@ impl EventsDal<'_, '_> {
         let mut result = HashMap::<H256, Vec<api::Log>>::new();
 
         for storage_log in logs {
-            let current_log = api::Log::from(storage_log);
+            let current_log = api::Log::try_from(storage_log).map_err(decode_error)?;
             let tx_hash = current_log.transaction_hash.unwrap();
             result.entry(tx_hash).or_default().push(current_log);
         }
@@ -255,7 +394,8 @@ impl EventsDal<'_, '_> {
         let result: Vec<_> = sqlx::query!(
             r#"
             SELECT
-                value
+                value,
+                value_compression
             FROM
                 events
             WHERE
@@ -274,8 +414,11 @@ impl EventsDal<'_, '_> {
         .fetch_all(self.storage.conn())
         .await?
         .into_iter()
-        .map(|row| H256::from_slice(&row.value))
-        .collect();
+        .map(|row| {
+            let value = decompress(row.value, row.value_compression).map_err(decode_error)?;
+            Ok(H256::from_slice(&value))
+        })
+        .collect::<Result<_, SqlxError>>()?;
 
         Ok(result)
     }
@@ -353,6 +496,7 @@ impl EventsDal<'_, '_> {
                 topic3,
                 topic4,
                 value,
+                value_compression,
                 event_index_in_tx
             FROM
                 events
@@ -384,14 +528,14 @@ impl EventsDal<'_, '_> {
             if row.event_index_in_tx == 0 {
                 tx_index_in_l1_batch += 1;
             }
-            VmEvent {
+            Ok(VmEvent {
                 location: (l1_batch_number, tx_index_in_l1_batch as u32),
                 address: Address::from_slice(&row.address),
                 indexed_topics,
-                value: row.value,
-            }
+                value: decompress(row.value, row.value_compression).map_err(decode_error)?,
+            })
         })
-        .collect();
+        .collect::<Result<_, SqlxError>>()?;
         Ok(Some(events))
     }
 }
@@ -478,6 +622,60 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn storing_events_via_binary_copy() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2] {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let events: Vec<_> = (0..5).map(|i| create_vm_event(i, i % 5)).collect();
+        let all_events: Vec<_> = events
+            .chunks(2)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let location = IncludedTxLocation {
+                    tx_hash: H256::repeat_byte(i as u8 + 1),
+                    tx_index_in_miniblock: i as u32,
+                    tx_initiator_address: Address::repeat_byte(i as u8 + 1),
+                };
+                (location, chunk.iter().collect())
+            })
+            .collect();
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &all_events)
+            .await;
+        conn.events_dal()
+            .insert_events_copy(MiniblockNumber(2), &all_events)
+            .await
+            .unwrap();
+
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 10);
+        let (text_logs, binary_logs) = logs.split_at(5);
+        for (text_log, binary_log) in text_logs.iter().zip(binary_logs) {
+            assert_eq!(binary_log.block_number, Some(2_u64.into()));
+            assert_eq!(binary_log.block_hash, Some(create_miniblock_header(2).hash));
+            let binary_log = api::Log {
+                block_number: text_log.block_number,
+                block_hash: text_log.block_hash,
+                ..binary_log.clone()
+            };
+            assert_eq!(*text_log, binary_log);
+        }
+    }
+
     fn create_l2_to_l1_log(tx_number_in_block: u16, index: u8) -> UserL2ToL1Log {
         UserL2ToL1Log(L2ToL1Log {
             shard_id: 0,
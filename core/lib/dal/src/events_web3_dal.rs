@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use sqlx::{
     postgres::PgArguments,
     query::{Query, QueryAs},
@@ -6,10 +8,38 @@ use sqlx::{
 use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
 use zksync_types::{
     api::{GetLogsFilter, Log},
-    Address, MiniblockNumber, H256,
+    Address, MiniblockNumber, H2048, H256,
+};
+
+use crate::{
+    compression::decode_error,
+    models::storage_event::StorageWeb3Log,
+    pagination::{Page, PageCursor},
+    Core, SqlxError,
 };
 
-use crate::{models::storage_event::StorageWeb3Log, Core, SqlxError};
+/// Maximum number of disjoint miniblock ranges queried for logs. If skipping miniblock ranges based on logs blooms
+/// produces more ranges, logs are queried in a single range covering all of them.
+const MAX_QUERIED_RANGES: usize = 32;
+
+/// Excludes sorted, disjoint `excluded_ranges` from the `full_range`, returning the remaining sorted ranges.
+fn exclude_ranges(
+    full_range: RangeInclusive<u32>,
+    excluded_ranges: impl Iterator<Item = RangeInclusive<u32>>,
+) -> Vec<RangeInclusive<u32>> {
+    let mut ranges = vec![];
+    let mut next_start = *full_range.start();
+    for excluded_range in excluded_ranges {
+        if *excluded_range.start() > next_start {
+            ranges.push(next_start..=(*excluded_range.start() - 1).min(*full_range.end()));
+        }
+        next_start = next_start.max(excluded_range.end().saturating_add(1));
+    }
+    if next_start <= *full_range.end() {
+        ranges.push(next_start..=*full_range.end());
+    }
+    ranges
+}
 
 #[derive(Debug)]
 pub struct EventsWeb3Dal<'a, 'c> {
@@ -25,7 +55,12 @@ impl EventsWeb3Dal<'_, '_> {
         offset: usize,
     ) -> Result<Option<MiniblockNumber>, SqlxError> {
         {
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(filter);
+            let ranges = self.get_candidate_ranges(filter).await?;
+            if ranges.is_empty() {
+                return Ok(None);
+            }
+            let (mut where_sql, arg_index) = self.build_get_logs_where_clause(filter);
+            where_sql += &Self::build_ranges_sql_filter(filter, &ranges);
 
             let query = format!(
                 r#"
@@ -66,20 +101,72 @@ impl EventsWeb3Dal<'_, '_> {
     }
 
     /// Returns logs for given filter.
-    #[allow(clippy::type_complexity)]
     pub async fn get_logs(
         &mut self,
         filter: GetLogsFilter,
         limit: usize,
     ) -> Result<Vec<Log>, SqlxError> {
+        let db_logs = self.get_storage_logs(filter, None, limit).await?;
+        Self::convert_logs(db_logs)
+    }
+
+    /// Returns a page of logs for given filter. Unlike [`Self::get_logs()`], logs can be iterated over
+    /// by passing the returned `next_cursor` as `after` for the next page; the page depth
+    /// doesn't affect query performance.
+    pub async fn get_logs_page(
+        &mut self,
+        filter: GetLogsFilter,
+        after: Option<PageCursor>,
+        limit: usize,
+    ) -> Result<Page<Log>, SqlxError> {
+        let db_logs = self.get_storage_logs(filter, after, limit).await?;
+        let page = Page::new(db_logs, limit, |log| {
+            PageCursor::new(
+                MiniblockNumber(log.miniblock_number as u32),
+                log.event_index_in_block as u32,
+            )
+        });
+        Ok(Page {
+            items: Self::convert_logs(page.items)?,
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    fn convert_logs(db_logs: Vec<StorageWeb3Log>) -> Result<Vec<Log>, SqlxError> {
+        db_logs
+            .into_iter()
+            .map(Log::try_from)
+            .collect::<anyhow::Result<_>>()
+            .map_err(decode_error)
+    }
+
+    async fn get_storage_logs(
+        &mut self,
+        filter: GetLogsFilter,
+        after: Option<PageCursor>,
+        limit: usize,
+    ) -> Result<Vec<StorageWeb3Log>, SqlxError> {
         {
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+            let ranges = self.get_candidate_ranges(&filter).await?;
+            if ranges.is_empty() {
+                return Ok(vec![]);
+            }
+            let (mut where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+            where_sql += &Self::build_ranges_sql_filter(&filter, &ranges);
+            if let Some(cursor) = after {
+                // Row comparison allows Postgres to use the `(miniblock_number, event_index_in_block)`
+                // primary key index to skip preceding logs.
+                where_sql += &format!(
+                    " AND ((miniblock_number, event_index_in_block) > ({}, {}))",
+                    cursor.miniblock_number.0, cursor.index
+                );
+            }
 
             let query = format!(
                 r#"
                 WITH events_select AS (
                     SELECT
-                        address, topic1, topic2, topic3, topic4, value,
+                        address, topic1, topic2, topic3, topic4, value, value_compression,
                         miniblock_number, tx_hash, tx_index_in_block,
                         event_index_in_block, event_index_in_tx
                     FROM events
@@ -115,11 +202,76 @@ impl EventsWeb3Dal<'_, '_> {
                 .instrument("get_logs")
                 .report_latency()
                 .with_arg("filter", &filter)
+                .with_arg("after", &after)
                 .with_arg("limit", &limit)
                 .fetch_all(self.storage)
                 .await?;
-            let logs = db_logs.into_iter().map(Into::into).collect();
-            Ok(logs)
+            Ok(db_logs)
+        }
+    }
+
+    /// Returns miniblock ranges within the filter range that may contain logs matching the filter. Ranges
+    /// with aggregated logs blooms not matching the filter are skipped. The returned ranges are sorted
+    /// and disjoint; if they are empty, there are no matching logs.
+    async fn get_candidate_ranges(
+        &mut self,
+        filter: &GetLogsFilter,
+    ) -> Result<Vec<RangeInclusive<u32>>, SqlxError> {
+        let full_range = filter.from_block.0..=filter.to_block.0;
+        if !filter.is_restrictive() || full_range.is_empty() {
+            return Ok(vec![full_range]);
+        }
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                start_miniblock,
+                end_miniblock,
+                logs_bloom
+            FROM
+                logs_bloom_ranges
+            WHERE
+                end_miniblock >= $1
+                AND start_miniblock <= $2
+            ORDER BY
+                start_miniblock
+            "#,
+            i64::from(filter.from_block.0),
+            i64::from(filter.to_block.0)
+        )
+        .instrument("get_candidate_ranges")
+        .with_arg("filter", filter)
+        .fetch_all(self.storage)
+        .await?;
+
+        let skipped_ranges = rows.into_iter().filter_map(|row| {
+            let bloom = H2048::from_slice(&row.logs_bloom);
+            let range = row.start_miniblock as u32..=row.end_miniblock as u32;
+            (!filter.may_match_bloom(&bloom)).then_some(range)
+        });
+        Ok(exclude_ranges(full_range, skipped_ranges))
+    }
+
+    /// Builds an SQL filter restricting logs to the specified miniblock ranges (e.g., ones returned
+    /// by [`Self::get_candidate_ranges()`]). Must be appended to the [`Self::build_get_logs_where_clause()`] output.
+    fn build_ranges_sql_filter(filter: &GetLogsFilter, ranges: &[RangeInclusive<u32>]) -> String {
+        match ranges {
+            [] => " AND FALSE".to_owned(),
+            [range] if *range == (filter.from_block.0..=filter.to_block.0) => String::new(),
+            [first, .., last] if ranges.len() > MAX_QUERIED_RANGES => {
+                let (start, end) = (first.start(), last.end());
+                format!(" AND (miniblock_number BETWEEN {start} AND {end})")
+            }
+            _ => {
+                let range_filters: Vec<_> = ranges
+                    .iter()
+                    .map(|range| {
+                        let (start, end) = (range.start(), range.end());
+                        format!("(miniblock_number BETWEEN {start} AND {end})")
+                    })
+                    .collect();
+                format!(" AND ({})", range_filters.join(" OR "))
+            }
         }
     }
 
@@ -209,6 +361,7 @@ impl EventsWeb3Dal<'_, '_> {
                             topic3,
                             topic4,
                             value,
+                            value_compression,
                             miniblock_number,
                             tx_hash,
                             tx_index_in_block,
@@ -230,6 +383,7 @@ impl EventsWeb3Dal<'_, '_> {
                     topic3 AS "topic3!",
                     topic4 AS "topic4!",
                     value AS "value!",
+                    value_compression,
                     miniblock_number AS "miniblock_number!",
                     miniblocks.l1_batch_number AS "l1_batch_number?",
                     tx_hash AS "tx_hash!",
@@ -247,18 +401,58 @@ impl EventsWeb3Dal<'_, '_> {
             )
             .fetch_all(self.storage.conn())
             .await?;
-            let logs = db_logs.into_iter().map(Into::into).collect();
-            Ok(logs)
+            Self::convert_logs(db_logs)
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{Address, H256};
+    use zksync_types::{
+        event::build_logs_bloom, tx::IncludedTxLocation, Address, L1BatchNumber, ProtocolVersion,
+        VmEvent, H256,
+    };
 
     use super::*;
-    use crate::{ConnectionPool, Core};
+    use crate::{
+        events_dal::LOGS_BLOOM_RANGE_SIZE, tests::create_miniblock_header, ConnectionPool, Core,
+        CoreDal,
+    };
+
+    #[test]
+    fn excluding_ranges() {
+        assert_eq!(exclude_ranges(0..=99, std::iter::empty()), [0..=99]);
+        assert!(exclude_ranges(0..=99, [0..=99].into_iter()).is_empty());
+        assert_eq!(
+            exclude_ranges(50..=2_500, [0..=999, 2_000..=2_999].into_iter()),
+            [1_000..=1_999]
+        );
+        assert_eq!(
+            exclude_ranges(0..=2_500, [1_000..=1_999].into_iter()),
+            [0..=999, 2_000..=2_500]
+        );
+    }
+
+    #[test]
+    fn building_ranges_sql_filter() {
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(2_500),
+            addresses: vec![Address::repeat_byte(1)],
+            topics: vec![],
+        };
+
+        let sql = EventsWeb3Dal::build_ranges_sql_filter(&filter, &[0..=2_500]);
+        assert_eq!(sql, "");
+        let sql = EventsWeb3Dal::build_ranges_sql_filter(&filter, &[0..=999, 2_000..=2_500]);
+        assert_eq!(
+            sql,
+            " AND ((miniblock_number BETWEEN 0 AND 999) OR (miniblock_number BETWEEN 2000 AND 2500))"
+        );
+        let ranges: Vec<_> = (0..50).map(|i| i * 50..=i * 50 + 10).collect();
+        let sql = EventsWeb3Dal::build_ranges_sql_filter(&filter, &ranges);
+        assert_eq!(sql, " AND (miniblock_number BETWEEN 0 AND 2460)");
+    }
 
     #[tokio::test]
     async fn test_build_get_logs_where_clause() {
@@ -336,4 +530,185 @@ mod tests {
         assert_eq!(actual_sql, expected_sql);
         assert_eq!(actual_arg_index, expected_arg_index);
     }
+
+    #[tokio::test]
+    async fn getting_logs_by_pages() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let events: Vec<_> = (0..3_u8)
+            .map(|i| VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: Address::repeat_byte(i),
+                indexed_topics: vec![H256::repeat_byte(i)],
+                value: vec![i],
+            })
+            .collect();
+        for number in 1..=3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number.into()),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::zero(),
+            };
+            conn.events_dal()
+                .save_events(
+                    MiniblockNumber(number),
+                    &[(location, events.iter().collect())],
+                )
+                .await;
+        }
+
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(1),
+            to_block: MiniblockNumber(3),
+            addresses: vec![],
+            topics: vec![],
+        };
+        let mut all_logs = vec![];
+        let mut cursor = None;
+        loop {
+            let page = conn
+                .events_web3_dal()
+                .get_logs_page(filter.clone(), cursor, 4)
+                .await
+                .unwrap();
+            assert!(page.items.len() <= 4);
+            all_logs.extend(page.items);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        let expected_logs = conn.events_web3_dal().get_logs(filter, 100).await.unwrap();
+        assert_eq!(expected_logs.len(), 9);
+        assert_eq!(all_logs, expected_logs);
+    }
+
+    #[tokio::test]
+    async fn skipping_miniblocks_using_logs_blooms() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let create_event = |byte: u8| VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(byte),
+            indexed_topics: vec![H256::repeat_byte(byte)],
+            value: vec![byte],
+        };
+        // The first event is in an aggregated range; the second one is after it.
+        let events_by_miniblock = [
+            (5, create_event(1)),
+            (LOGS_BLOOM_RANGE_SIZE + 5, create_event(2)),
+        ];
+        let last_miniblock = LOGS_BLOOM_RANGE_SIZE + 10;
+        for number in 0..=last_miniblock {
+            let event = events_by_miniblock
+                .iter()
+                .find_map(|(event_number, event)| (*event_number == number).then_some(event));
+            let mut header = create_miniblock_header(number);
+            header.logs_bloom = build_logs_bloom(event);
+            conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+            if let Some(event) = event {
+                let location = IncludedTxLocation {
+                    tx_hash: H256::from_low_u64_be(number.into()),
+                    tx_index_in_miniblock: 0,
+                    tx_initiator_address: Address::zero(),
+                };
+                conn.events_dal()
+                    .save_events(MiniblockNumber(number), &[(location, vec![event])])
+                    .await;
+            }
+
+            let is_aggregated = conn
+                .events_dal()
+                .aggregate_logs_blooms(MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(is_aggregated, number == LOGS_BLOOM_RANGE_SIZE - 1);
+        }
+
+        let mut filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(last_miniblock),
+            addresses: vec![Address::repeat_byte(1)],
+            topics: vec![],
+        };
+        let ranges = conn
+            .events_web3_dal()
+            .get_candidate_ranges(&filter)
+            .await
+            .unwrap();
+        assert_eq!(ranges, [0..=last_miniblock]);
+        let logs = conn
+            .events_web3_dal()
+            .get_logs(filter.clone(), 100)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, Some(5.into()));
+
+        filter.addresses = vec![Address::repeat_byte(2)];
+        let ranges = conn
+            .events_web3_dal()
+            .get_candidate_ranges(&filter)
+            .await
+            .unwrap();
+        assert_eq!(ranges, [LOGS_BLOOM_RANGE_SIZE..=last_miniblock]);
+        let logs = conn
+            .events_web3_dal()
+            .get_logs(filter.clone(), 100)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(
+            logs[0].block_number,
+            Some((LOGS_BLOOM_RANGE_SIZE + 5).into())
+        );
+
+        filter.to_block = MiniblockNumber(LOGS_BLOOM_RANGE_SIZE - 1);
+        let ranges = conn
+            .events_web3_dal()
+            .get_candidate_ranges(&filter)
+            .await
+            .unwrap();
+        assert!(ranges.is_empty());
+        let logs = conn
+            .events_web3_dal()
+            .get_logs(filter.clone(), 100)
+            .await
+            .unwrap();
+        assert!(logs.is_empty());
+        let block_number = conn
+            .events_web3_dal()
+            .get_log_block_number(&filter, 0)
+            .await
+            .unwrap();
+        assert_eq!(block_number, None);
+
+        // Reverting miniblocks should remove the affected aggregated range.
+        conn.events_dal()
+            .rollback_events(MiniblockNumber(LOGS_BLOOM_RANGE_SIZE - 10))
+            .await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(LOGS_BLOOM_RANGE_SIZE - 10))
+            .await
+            .unwrap();
+        filter.to_block = MiniblockNumber(LOGS_BLOOM_RANGE_SIZE - 10);
+        let ranges = conn
+            .events_web3_dal()
+            .get_candidate_ranges(&filter)
+            .await
+            .unwrap();
+        assert_eq!(ranges, [0..=LOGS_BLOOM_RANGE_SIZE - 10]);
+    }
 }
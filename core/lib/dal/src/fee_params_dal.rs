@@ -0,0 +1,110 @@
+use anyhow::Context as _;
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::fee_model::FeeParams;
+
+use crate::Core;
+
+/// DAL persisting fee params fetched from the main node by the external node.
+#[derive(Debug)]
+pub struct FeeParamsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl FeeParamsDal<'_, '_> {
+    /// Returns the last persisted fee params fetched from the main node, if any.
+    pub async fn get_main_node_fee_params(&mut self) -> anyhow::Result<Option<FeeParams>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                fee_params
+            FROM
+                main_node_fee_params
+            WHERE
+                fake_key
+            "#
+        )
+        .instrument("get_main_node_fee_params")
+        .fetch_optional(self.storage)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let fee_params = serde_json::from_value(row.fee_params)
+            .context("invalid value for main node fee params in the DB")?;
+        Ok(Some(fee_params))
+    }
+
+    /// Persists fee params fetched from the main node, overwriting the previously persisted ones.
+    pub async fn set_main_node_fee_params(&mut self, fee_params: &FeeParams) -> anyhow::Result<()> {
+        let fee_params =
+            serde_json::to_value(fee_params).context("failed serializing fee params")?;
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                main_node_fee_params (fake_key, fee_params, updated_at)
+            VALUES
+                (TRUE, $1, NOW())
+            ON CONFLICT (fake_key) DO
+            UPDATE
+            SET
+                fee_params = excluded.fee_params,
+                updated_at = excluded.updated_at
+            "#,
+            fee_params
+        )
+        .instrument("set_main_node_fee_params")
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::fee_model::{FeeModelConfigV1, FeeParamsV1};
+
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn persisting_main_node_fee_params() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let fee_params = conn
+            .fee_params_dal()
+            .get_main_node_fee_params()
+            .await
+            .unwrap();
+        assert_eq!(fee_params, None);
+
+        let fee_params = FeeParams::sensible_v1_default();
+        conn.fee_params_dal()
+            .set_main_node_fee_params(&fee_params)
+            .await
+            .unwrap();
+        let persisted = conn
+            .fee_params_dal()
+            .get_main_node_fee_params()
+            .await
+            .unwrap();
+        assert_eq!(persisted, Some(fee_params));
+
+        let new_fee_params = FeeParams::V1(FeeParamsV1 {
+            config: FeeModelConfigV1 {
+                minimal_l2_gas_price: 200_000_000,
+            },
+            l1_gas_price: 5_000_000_000,
+        });
+        conn.fee_params_dal()
+            .set_main_node_fee_params(&new_fee_params)
+            .await
+            .unwrap();
+        let persisted = conn
+            .fee_params_dal()
+            .get_main_node_fee_params()
+            .await
+            .unwrap();
+        assert_eq!(persisted, Some(new_fee_params));
+    }
+}
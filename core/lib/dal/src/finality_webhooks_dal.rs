@@ -0,0 +1,276 @@
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::{aggregated_operations::AggregatedActionType, L1BatchNumber, H256};
+
+use crate::Core;
+
+/// Transition of an L1 batch to a certain L1 state (committed, proven or executed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1BatchFinalityUpdate {
+    pub l1_batch_number: L1BatchNumber,
+    pub action_type: AggregatedActionType,
+    /// Root hash of the L1 batch.
+    pub root_hash: Option<H256>,
+    pub commitment: Option<H256>,
+    /// Hash of the confirmed L1 transaction that has performed the action.
+    pub eth_tx_hash: H256,
+    pub confirmed_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct FinalityWebhooksDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl FinalityWebhooksDal<'_, '_> {
+    /// Returns L1 batches after `after` (exclusive) for which an L1 transaction of the specified type
+    /// is confirmed, ordered by the L1 batch number.
+    pub async fn get_finality_updates(
+        &mut self,
+        action_type: AggregatedActionType,
+        after: L1BatchNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchFinalityUpdate>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.hash,
+                l1_batches.commitment,
+                eth_txs_history.tx_hash,
+                eth_txs_history.confirmed_at AS "confirmed_at!"
+            FROM
+                l1_batches
+                INNER JOIN eth_txs_history ON (
+                    eth_txs_history.eth_tx_id = CASE $1
+                        WHEN 'CommitBlocks' THEN l1_batches.eth_commit_tx_id
+                        WHEN 'PublishProofBlocksOnchain' THEN l1_batches.eth_prove_tx_id
+                        ELSE l1_batches.eth_execute_tx_id
+                    END
+                    AND eth_txs_history.confirmed_at IS NOT NULL
+                )
+            WHERE
+                l1_batches.number > $2
+            ORDER BY
+                l1_batches.number
+            LIMIT
+                $3
+            "#,
+            action_type.as_str(),
+            i64::from(after.0),
+            limit as i64
+        )
+        .instrument("get_finality_updates")
+        .with_arg("action_type", &action_type)
+        .with_arg("after", &after)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchFinalityUpdate {
+                l1_batch_number: L1BatchNumber(row.number as u32),
+                action_type,
+                root_hash: row.hash.as_deref().map(H256::from_slice),
+                commitment: row.commitment.as_deref().map(H256::from_slice),
+                eth_tx_hash: row.tx_hash.parse().expect("invalid L1 tx hash in DB"),
+                confirmed_at: row.confirmed_at,
+            })
+            .collect())
+    }
+
+    /// Returns the last L1 batch for which a notification of the specified type was delivered to the webhook.
+    /// Webhooks are identified by an opaque ID (e.g., a hash of the webhook URL).
+    pub async fn get_cursor(
+        &mut self,
+        webhook_id: H256,
+        action_type: AggregatedActionType,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                finality_webhook_cursors
+            WHERE
+                webhook_id = $1
+                AND action_type = $2
+            "#,
+            webhook_id.as_bytes(),
+            action_type.as_str()
+        )
+        .instrument("get_finality_webhook_cursor")
+        .with_arg("webhook_id", &webhook_id)
+        .with_arg("action_type", &action_type)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| L1BatchNumber(row.l1_batch_number as u32)))
+    }
+
+    pub async fn set_cursor(
+        &mut self,
+        webhook_id: H256,
+        action_type: AggregatedActionType,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                finality_webhook_cursors (
+                    webhook_id,
+                    action_type,
+                    l1_batch_number,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (webhook_id, action_type) DO
+            UPDATE
+            SET
+                l1_batch_number = $3,
+                updated_at = NOW()
+            "#,
+            webhook_id.as_bytes(),
+            action_type.as_str(),
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("set_finality_webhook_cursor")
+        .with_arg("webhook_id", &webhook_id)
+        .with_arg("action_type", &action_type)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, Address, ProtocolVersion, ProtocolVersionId, U256};
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn getting_finality_updates() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=3 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        let eth_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::repeat_byte(1),
+                100,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1)..=L1BatchNumber(2),
+                eth_tx.id,
+                AggregatedActionType::Commit,
+            )
+            .await
+            .unwrap();
+        let eth_tx_hash = H256::repeat_byte(0x11);
+        conn.eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 100, 10, None, eth_tx_hash, &[])
+            .await
+            .unwrap();
+
+        // The commit transaction isn't confirmed yet.
+        let updates = conn
+            .finality_webhooks_dal()
+            .get_finality_updates(AggregatedActionType::Commit, L1BatchNumber(0), 10)
+            .await
+            .unwrap();
+        assert!(updates.is_empty(), "{updates:?}");
+
+        conn.eth_sender_dal()
+            .confirm_tx(eth_tx_hash, U256::zero())
+            .await
+            .unwrap();
+        let updates = conn
+            .finality_webhooks_dal()
+            .get_finality_updates(AggregatedActionType::Commit, L1BatchNumber(0), 10)
+            .await
+            .unwrap();
+        let l1_batch_numbers: Vec<_> = updates
+            .iter()
+            .map(|update| update.l1_batch_number)
+            .collect();
+        assert_eq!(l1_batch_numbers, [L1BatchNumber(1), L1BatchNumber(2)]);
+        assert!(updates
+            .iter()
+            .all(|update| update.eth_tx_hash == eth_tx_hash));
+
+        let updates = conn
+            .finality_webhooks_dal()
+            .get_finality_updates(AggregatedActionType::Commit, L1BatchNumber(1), 10)
+            .await
+            .unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].l1_batch_number, L1BatchNumber(2));
+
+        let updates = conn
+            .finality_webhooks_dal()
+            .get_finality_updates(AggregatedActionType::Execute, L1BatchNumber(0), 10)
+            .await
+            .unwrap();
+        assert!(updates.is_empty(), "{updates:?}");
+    }
+
+    #[tokio::test]
+    async fn updating_cursors() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let webhook_id = H256::repeat_byte(1);
+        let mut dal = conn.finality_webhooks_dal();
+
+        let cursor = dal
+            .get_cursor(webhook_id, AggregatedActionType::Commit)
+            .await
+            .unwrap();
+        assert_eq!(cursor, None);
+        dal.set_cursor(webhook_id, AggregatedActionType::Commit, L1BatchNumber(5))
+            .await
+            .unwrap();
+        dal.set_cursor(webhook_id, AggregatedActionType::Commit, L1BatchNumber(7))
+            .await
+            .unwrap();
+        let cursor = dal
+            .get_cursor(webhook_id, AggregatedActionType::Commit)
+            .await
+            .unwrap();
+        assert_eq!(cursor, Some(L1BatchNumber(7)));
+        let cursor = dal
+            .get_cursor(webhook_id, AggregatedActionType::Execute)
+            .await
+            .unwrap();
+        assert_eq!(cursor, None);
+    }
+}
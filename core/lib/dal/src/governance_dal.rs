@@ -0,0 +1,303 @@
+use std::str::FromStr;
+
+use anyhow::Context as _;
+use sqlx::types::chrono::NaiveDateTime;
+use strum::{Display, EnumString};
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::H256;
+
+use crate::Core;
+
+/// Status of a governance operation in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+pub enum GovernanceOperationStatus {
+    /// Operation was recorded by the node, but wasn't scheduled on L1 yet.
+    #[strum(serialize = "proposed")]
+    Proposed,
+    /// Transaction scheduling the operation was successfully mined on L1.
+    #[strum(serialize = "scheduled")]
+    Scheduled,
+    /// Transaction executing the operation was successfully mined on L1.
+    #[strum(serialize = "executed")]
+    Executed,
+    /// Operation has failed; see the error message for details.
+    #[strum(serialize = "failed")]
+    Failed,
+}
+
+/// Governance operation recorded in the audit trail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredGovernanceOperation {
+    pub operation_id: H256,
+    pub description: String,
+    /// Serialized operation; its format is defined by the governance module of the node.
+    pub operation: serde_json::Value,
+    /// Delay (in seconds) between scheduling and execution of the operation.
+    pub delay: u64,
+    pub status: GovernanceOperationStatus,
+    pub schedule_tx_hash: Option<H256>,
+    pub execute_tx_hash: Option<H256>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct GovernanceDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl GovernanceDal<'_, '_> {
+    /// Records a newly proposed governance operation.
+    pub async fn insert_operation(
+        &mut self,
+        operation_id: H256,
+        description: &str,
+        operation: &serde_json::Value,
+        delay: u64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                governance_operations (
+                    operation_id,
+                    description,
+                    operation,
+                    delay,
+                    status,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, NOW(), NOW())
+            "#,
+            operation_id.as_bytes(),
+            description,
+            operation,
+            delay as i64,
+            GovernanceOperationStatus::Proposed.to_string()
+        )
+        .instrument("insert_governance_operation")
+        .with_arg("operation_id", &operation_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks the operation as scheduled by the specified L1 transaction.
+    pub async fn mark_operation_scheduled(
+        &mut self,
+        operation_id: H256,
+        tx_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE governance_operations
+            SET
+                status = $2,
+                schedule_tx_hash = $3,
+                updated_at = NOW()
+            WHERE
+                operation_id = $1
+            "#,
+            operation_id.as_bytes(),
+            GovernanceOperationStatus::Scheduled.to_string(),
+            tx_hash.as_bytes()
+        )
+        .instrument("mark_governance_operation_scheduled")
+        .with_arg("operation_id", &operation_id)
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks the operation as executed by the specified L1 transaction.
+    pub async fn mark_operation_executed(
+        &mut self,
+        operation_id: H256,
+        tx_hash: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE governance_operations
+            SET
+                status = $2,
+                execute_tx_hash = $3,
+                updated_at = NOW()
+            WHERE
+                operation_id = $1
+            "#,
+            operation_id.as_bytes(),
+            GovernanceOperationStatus::Executed.to_string(),
+            tx_hash.as_bytes()
+        )
+        .instrument("mark_governance_operation_executed")
+        .with_arg("operation_id", &operation_id)
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Marks the operation as failed with the specified error.
+    pub async fn mark_operation_failed(
+        &mut self,
+        operation_id: H256,
+        error: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE governance_operations
+            SET
+                status = $2,
+                error = $3,
+                updated_at = NOW()
+            WHERE
+                operation_id = $1
+            "#,
+            operation_id.as_bytes(),
+            GovernanceOperationStatus::Failed.to_string(),
+            error
+        )
+        .instrument("mark_governance_operation_failed")
+        .with_arg("operation_id", &operation_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_operation(
+        &mut self,
+        operation_id: H256,
+    ) -> anyhow::Result<Option<StoredGovernanceOperation>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                operation_id,
+                description,
+                operation,
+                delay,
+                status,
+                schedule_tx_hash,
+                execute_tx_hash,
+                error,
+                created_at,
+                updated_at
+            FROM
+                governance_operations
+            WHERE
+                operation_id = $1
+            "#,
+            operation_id.as_bytes()
+        )
+        .instrument("get_governance_operation")
+        .with_arg("operation_id", &operation_id)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let status = GovernanceOperationStatus::from_str(&row.status)
+            .with_context(|| format!("invalid governance operation status: {}", row.status))?;
+        Ok(Some(StoredGovernanceOperation {
+            operation_id: H256::from_slice(&row.operation_id),
+            description: row.description,
+            operation: row.operation,
+            delay: row.delay as u64,
+            status,
+            schedule_tx_hash: row.schedule_tx_hash.as_deref().map(H256::from_slice),
+            execute_tx_hash: row.execute_tx_hash.as_deref().map(H256::from_slice),
+            error: row.error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }))
+    }
+
+    /// Returns IDs of operations with the specified status in the order they were proposed.
+    pub async fn get_operation_ids_with_status(
+        &mut self,
+        status: GovernanceOperationStatus,
+    ) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                operation_id
+            FROM
+                governance_operations
+            WHERE
+                status = $1
+            ORDER BY
+                id
+            "#,
+            status.to_string()
+        )
+        .instrument("get_governance_operation_ids_with_status")
+        .with_arg("status", &status)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.operation_id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn governance_operation_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let operation_id = H256::repeat_byte(1);
+        let operation = serde_json::json!({ "calls": [] });
+        conn.governance_dal()
+            .insert_operation(operation_id, "test", &operation, 60)
+            .await
+            .unwrap();
+
+        let stored = conn
+            .governance_dal()
+            .get_operation(operation_id)
+            .await
+            .unwrap()
+            .expect("no operation");
+        assert_eq!(stored.description, "test");
+        assert_eq!(stored.operation, operation);
+        assert_eq!(stored.delay, 60);
+        assert_eq!(stored.status, GovernanceOperationStatus::Proposed);
+        assert_eq!(stored.schedule_tx_hash, None);
+
+        let schedule_tx_hash = H256::repeat_byte(2);
+        conn.governance_dal()
+            .mark_operation_scheduled(operation_id, schedule_tx_hash)
+            .await
+            .unwrap();
+        let scheduled_ids = conn
+            .governance_dal()
+            .get_operation_ids_with_status(GovernanceOperationStatus::Scheduled)
+            .await
+            .unwrap();
+        assert_eq!(scheduled_ids, [operation_id]);
+
+        let execute_tx_hash = H256::repeat_byte(3);
+        conn.governance_dal()
+            .mark_operation_executed(operation_id, execute_tx_hash)
+            .await
+            .unwrap();
+        let stored = conn
+            .governance_dal()
+            .get_operation(operation_id)
+            .await
+            .unwrap()
+            .expect("no operation");
+        assert_eq!(stored.status, GovernanceOperationStatus::Executed);
+        assert_eq!(stored.schedule_tx_hash, Some(schedule_tx_hash));
+        assert_eq!(stored.execute_tx_hash, Some(execute_tx_hash));
+    }
+}
@@ -0,0 +1,153 @@
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+
+use crate::Core;
+
+/// Intent to perform an operation spanning Postgres and other stores (e.g., RocksDB instances)
+/// that wasn't completed yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredIntent {
+    pub id: i64,
+    /// Operation parameters; their format depends on the operation.
+    pub params: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct IntentLogDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl IntentLogDal<'_, '_> {
+    /// Records an intent to perform the specified operation. Returns the ID of the recorded intent.
+    pub async fn insert_intent(
+        &mut self,
+        operation: &str,
+        params: &serde_json::Value,
+    ) -> sqlx::Result<i64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                intent_log (operation, params, created_at)
+            VALUES
+                ($1, $2, NOW())
+            RETURNING
+                id
+            "#,
+            operation,
+            params
+        )
+        .instrument("insert_intent")
+        .with_arg("operation", &operation)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id)
+    }
+
+    /// Returns uncompleted intents for the specified operation in the order they were recorded.
+    pub async fn get_pending_intents(
+        &mut self,
+        operation: &str,
+    ) -> sqlx::Result<Vec<StoredIntent>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                params,
+                created_at
+            FROM
+                intent_log
+            WHERE
+                operation = $1
+            ORDER BY
+                id
+            "#,
+            operation
+        )
+        .instrument("get_pending_intents")
+        .with_arg("operation", &operation)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StoredIntent {
+                id: row.id,
+                params: row.params,
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    /// Marks the intent with the specified ID as completed, removing it from the log.
+    pub async fn complete_intent(&mut self, id: i64) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM intent_log
+            WHERE
+                id = $1
+            "#,
+            id
+        )
+        .instrument("complete_intent")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn recording_and_completing_intents() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let intents = conn
+            .intent_log_dal()
+            .get_pending_intents("revert")
+            .await
+            .unwrap();
+        assert!(intents.is_empty());
+
+        let params = serde_json::json!({ "l1_batch_number": 3 });
+        let first_id = conn
+            .intent_log_dal()
+            .insert_intent("revert", &params)
+            .await
+            .unwrap();
+        let second_id = conn
+            .intent_log_dal()
+            .insert_intent("revert", &serde_json::json!({ "l1_batch_number": 2 }))
+            .await
+            .unwrap();
+        conn.intent_log_dal()
+            .insert_intent("other", &params)
+            .await
+            .unwrap();
+
+        let intents = conn
+            .intent_log_dal()
+            .get_pending_intents("revert")
+            .await
+            .unwrap();
+        let intent_ids: Vec<_> = intents.iter().map(|intent| intent.id).collect();
+        assert_eq!(intent_ids, [first_id, second_id]);
+        assert_eq!(intents[0].params, params);
+
+        conn.intent_log_dal()
+            .complete_intent(first_id)
+            .await
+            .unwrap();
+        let intents = conn
+            .intent_log_dal()
+            .get_pending_intents("revert")
+            .await
+            .unwrap();
+        assert_eq!(intents.len(), 1);
+        assert_eq!(intents[0].id, second_id);
+    }
+}
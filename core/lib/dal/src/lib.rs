@@ -9,32 +9,47 @@ pub use zksync_db_connection::{connection::Connection, connection_pool::Connecti
 
 use crate::{
     basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
-    blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
+    blocks_web3_dal::BlocksWeb3Dal, compression_dal::CompressionDal, consensus_dal::ConsensusDal,
     contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
     events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
-    proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
-    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
+    fee_params_dal::FeeParamsDal, finality_webhooks_dal::FinalityWebhooksDal,
+    governance_dal::GovernanceDal, intent_log_dal::IntentLogDal,
+    metrics_snapshots_dal::MetricsSnapshotsDal,
+    object_store_lifecycle_dal::ObjectStoreLifecycleDal, proof_generation_dal::ProofGenerationDal,
+    protocol_versions_dal::ProtocolVersionsDal,
+    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, retention_dal::RetentionDal,
     snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
     snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
     storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
-    tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
-    transactions_web3_dal::TransactionsWeb3Dal,
+    sync_dal::SyncDal, sync_dead_letters_dal::SyncDeadLettersDal, system_dal::SystemDal,
+    tokens_dal::TokensDal, tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
+    transactions_web3_dal::TransactionsWeb3Dal, tx_execution_metrics_dal::TxExecutionMetricsDal,
+    vm_shadow_divergences_dal::VmShadowDivergencesDal,
 };
 
 pub mod basic_witness_input_producer_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+mod compression;
+pub mod compression_dal;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
 pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
+pub mod fee_params_dal;
+pub mod finality_webhooks_dal;
+pub mod governance_dal;
+pub mod intent_log_dal;
+pub mod metrics_snapshots_dal;
 mod models;
+pub mod object_store_lifecycle_dal;
+pub mod pagination;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
+pub mod retention_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
@@ -43,11 +58,14 @@ pub mod storage_logs_dal;
 pub mod storage_logs_dedup_dal;
 pub mod storage_web3_dal;
 pub mod sync_dal;
+pub mod sync_dead_letters_dal;
 pub mod system_dal;
 pub mod tokens_dal;
 pub mod tokens_web3_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
+pub mod tx_execution_metrics_dal;
+pub mod vm_shadow_divergences_dal;
 
 pub mod metrics;
 
@@ -116,6 +134,28 @@ where
     fn snapshots_creator_dal(&mut self) -> SnapshotsCreatorDal<'_, 'a>;
 
     fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a>;
+
+    fn metrics_snapshots_dal(&mut self) -> MetricsSnapshotsDal<'_, 'a>;
+
+    fn object_store_lifecycle_dal(&mut self) -> ObjectStoreLifecycleDal<'_, 'a>;
+
+    fn intent_log_dal(&mut self) -> IntentLogDal<'_, 'a>;
+
+    fn governance_dal(&mut self) -> GovernanceDal<'_, 'a>;
+
+    fn fee_params_dal(&mut self) -> FeeParamsDal<'_, 'a>;
+
+    fn compression_dal(&mut self) -> CompressionDal<'_, 'a>;
+
+    fn finality_webhooks_dal(&mut self) -> FinalityWebhooksDal<'_, 'a>;
+
+    fn sync_dead_letters_dal(&mut self) -> SyncDeadLettersDal<'_, 'a>;
+
+    fn tx_execution_metrics_dal(&mut self) -> TxExecutionMetricsDal<'_, 'a>;
+
+    fn vm_shadow_divergences_dal(&mut self) -> VmShadowDivergencesDal<'_, 'a>;
+
+    fn retention_dal(&mut self) -> RetentionDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -226,4 +266,48 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
     fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    fn metrics_snapshots_dal(&mut self) -> MetricsSnapshotsDal<'_, 'a> {
+        MetricsSnapshotsDal { storage: self }
+    }
+
+    fn object_store_lifecycle_dal(&mut self) -> ObjectStoreLifecycleDal<'_, 'a> {
+        ObjectStoreLifecycleDal { storage: self }
+    }
+
+    fn intent_log_dal(&mut self) -> IntentLogDal<'_, 'a> {
+        IntentLogDal { storage: self }
+    }
+
+    fn governance_dal(&mut self) -> GovernanceDal<'_, 'a> {
+        GovernanceDal { storage: self }
+    }
+
+    fn fee_params_dal(&mut self) -> FeeParamsDal<'_, 'a> {
+        FeeParamsDal { storage: self }
+    }
+
+    fn compression_dal(&mut self) -> CompressionDal<'_, 'a> {
+        CompressionDal { storage: self }
+    }
+
+    fn finality_webhooks_dal(&mut self) -> FinalityWebhooksDal<'_, 'a> {
+        FinalityWebhooksDal { storage: self }
+    }
+
+    fn sync_dead_letters_dal(&mut self) -> SyncDeadLettersDal<'_, 'a> {
+        SyncDeadLettersDal { storage: self }
+    }
+
+    fn tx_execution_metrics_dal(&mut self) -> TxExecutionMetricsDal<'_, 'a> {
+        TxExecutionMetricsDal { storage: self }
+    }
+
+    fn vm_shadow_divergences_dal(&mut self) -> VmShadowDivergencesDal<'_, 'a> {
+        VmShadowDivergencesDal { storage: self }
+    }
+
+    fn retention_dal(&mut self) -> RetentionDal<'_, 'a> {
+        RetentionDal { storage: self }
+    }
 }
@@ -0,0 +1,141 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+
+use crate::Core;
+
+/// Values of selected node metrics captured at a certain moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsSnapshot {
+    pub created_at: NaiveDateTime,
+    /// Metric values keyed by the metric name with labels, e.g. `external_node_sync_lag`
+    /// or `server_state_keeper_l1_batch_age{stage="sealed"}`.
+    pub metrics: BTreeMap<String, f64>,
+}
+
+#[derive(Debug)]
+pub struct MetricsSnapshotsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl MetricsSnapshotsDal<'_, '_> {
+    pub async fn insert_snapshot(&mut self, metrics: &BTreeMap<String, f64>) -> sqlx::Result<()> {
+        let metrics = serde_json::to_value(metrics).expect("failed serializing metrics");
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                metrics_snapshots (metrics, created_at)
+            VALUES
+                ($1, NOW())
+            "#,
+            metrics
+        )
+        .instrument("insert_metrics_snapshot")
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns snapshots created at or after `since`, ordered by creation time.
+    pub async fn get_snapshots(
+        &mut self,
+        since: NaiveDateTime,
+    ) -> sqlx::Result<Vec<MetricsSnapshot>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                metrics,
+                created_at
+            FROM
+                metrics_snapshots
+            WHERE
+                created_at >= $1
+            ORDER BY
+                created_at
+            "#,
+            since
+        )
+        .instrument("get_metrics_snapshots")
+        .with_arg("since", &since)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MetricsSnapshot {
+                created_at: row.created_at,
+                metrics: serde_json::from_value(row.metrics)
+                    .expect("invalid metrics snapshot in DB"),
+            })
+            .collect())
+    }
+
+    /// Removes snapshots older than `retention`. Returns the number of removed snapshots.
+    pub async fn prune_snapshots(&mut self, retention: Duration) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM metrics_snapshots
+            WHERE
+                created_at < NOW() - MAKE_INTERVAL(secs => $1)
+            "#,
+            retention.as_secs_f64()
+        )
+        .instrument("prune_metrics_snapshots")
+        .with_arg("retention", &retention)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn inserting_and_pruning_metrics_snapshots() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let since = NaiveDateTime::default();
+        let snapshots = conn
+            .metrics_snapshots_dal()
+            .get_snapshots(since)
+            .await
+            .unwrap();
+        assert!(snapshots.is_empty());
+
+        let metrics = BTreeMap::from([
+            ("external_node_sync_lag".to_owned(), 3.0),
+            (
+                r#"server_state_keeper_l1_batch_age{stage="sealed"}"#.to_owned(),
+                1.5,
+            ),
+        ]);
+        conn.metrics_snapshots_dal()
+            .insert_snapshot(&metrics)
+            .await
+            .unwrap();
+        let snapshots = conn
+            .metrics_snapshots_dal()
+            .get_snapshots(since)
+            .await
+            .unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].metrics, metrics);
+
+        let pruned = conn
+            .metrics_snapshots_dal()
+            .prune_snapshots(Duration::from_secs(3_600))
+            .await
+            .unwrap();
+        assert_eq!(pruned, 0);
+        let pruned = conn
+            .metrics_snapshots_dal()
+            .prune_snapshots(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(pruned, 1);
+    }
+}
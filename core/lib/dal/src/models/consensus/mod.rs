@@ -96,6 +96,15 @@ impl Payload {
     pub fn encode(&self) -> validator::Payload {
         validator::Payload(zksync_protobuf::encode(self))
     }
+
+    /// Returns the number of bytes occupied by `tx` in the encoded payload. The encoded payload size is the sum
+    /// of sizes of all its transactions plus a small overhead for the other payload fields.
+    pub fn encoded_transaction_size(tx: &Transaction) -> usize {
+        /// Tag of the `transactions` field in the `Payload` message.
+        const TRANSACTIONS_TAG: u32 = 8;
+
+        prost::encoding::message::encoded_len(TRANSACTIONS_TAG, &proto::Transaction::build(tx))
+    }
 }
 
 impl ProtoRepr for proto::Transaction {
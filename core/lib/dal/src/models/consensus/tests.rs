@@ -4,7 +4,10 @@ use zksync_protobuf::{
     repr::{decode, encode},
     ProtoRepr,
 };
-use zksync_types::{Bytes, Execute, ExecuteTransactionCommon, Transaction};
+use zksync_types::{
+    Address, Bytes, Execute, ExecuteTransactionCommon, L1BatchNumber, ProtocolVersionId,
+    Transaction, H256,
+};
 
 use crate::tests::{mock_l1_execute, mock_l2_transaction, mock_protocol_upgrade_transaction};
 
@@ -18,6 +21,39 @@ fn test_encoding() {
     );
 }
 
+#[test]
+fn encoded_transaction_size_is_accurate() {
+    let transactions: Vec<Transaction> = vec![
+        mock_l1_execute().into(),
+        mock_l2_transaction().into(),
+        mock_protocol_upgrade_transaction().into(),
+    ];
+    let mut payload = super::Payload {
+        protocol_version: ProtocolVersionId::latest(),
+        hash: H256::repeat_byte(1),
+        l1_batch_number: L1BatchNumber(1),
+        timestamp: 1,
+        l1_gas_price: 100,
+        l2_fair_gas_price: 100,
+        fair_pubdata_price: Some(100),
+        virtual_blocks: 1,
+        operator_address: Address::repeat_byte(2),
+        transactions: vec![],
+        last_in_batch: false,
+    };
+    let empty_payload_size = payload.encode().0.len();
+
+    let transactions_size: usize = transactions
+        .iter()
+        .map(super::Payload::encoded_transaction_size)
+        .sum();
+    payload.transactions = transactions;
+    assert_eq!(
+        payload.encode().0.len(),
+        empty_payload_size + transactions_size
+    );
+}
+
 fn encode_decode<P, C>(msg: P::Type)
 where
     P: ProtoRepr,
@@ -428,30 +428,18 @@ pub struct StorageMiniblockHeader {
     /// The formal value of the gas limit for the miniblock.
     /// This value should bound the maximal amount of gas that can be spent by transactions in the miniblock.
     pub gas_limit: Option<i64>,
+    pub logs_bloom: Option<Vec<u8>>,
 }
 
 impl From<StorageMiniblockHeader> for MiniblockHeader {
     fn from(row: StorageMiniblockHeader) -> Self {
         let protocol_version = row.protocol_version.map(|v| (v as u16).try_into().unwrap());
-
-        let fee_input = protocol_version
-            .filter(|version: &ProtocolVersionId| version.is_post_1_4_1())
-            .map(|_| {
-                BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
-                    fair_pubdata_price: row
-                        .fair_pubdata_price
-                        .expect("No fair pubdata price for 1.4.1 miniblock")
-                        as u64,
-                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
-                    l1_gas_price: row.l1_gas_price as u64,
-                })
-            })
-            .unwrap_or_else(|| {
-                BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
-                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
-                    l1_gas_price: row.l1_gas_price as u64,
-                })
-            });
+        let fee_input = miniblock_fee_input(
+            protocol_version,
+            row.l1_gas_price,
+            row.l2_fair_gas_price,
+            row.fair_pubdata_price,
+        );
 
         MiniblockHeader {
             number: MiniblockNumber(row.number as u32),
@@ -470,6 +458,62 @@ impl From<StorageMiniblockHeader> for MiniblockHeader {
             protocol_version,
             virtual_blocks: row.virtual_blocks as u32,
             gas_limit: row.gas_limit.unwrap_or(i64::from(LEGACY_BLOCK_GAS_LIMIT)) as u64,
+            logs_bloom: row
+                .logs_bloom
+                .map(|bloom| H2048::from_slice(&bloom))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn miniblock_fee_input(
+    protocol_version: Option<ProtocolVersionId>,
+    l1_gas_price: i64,
+    l2_fair_gas_price: i64,
+    fair_pubdata_price: Option<i64>,
+) -> BatchFeeInput {
+    protocol_version
+        .filter(|version| version.is_post_1_4_1())
+        .map(|_| {
+            BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+                fair_pubdata_price: fair_pubdata_price
+                    .expect("No fair pubdata price for 1.4.1 miniblock")
+                    as u64,
+                fair_l2_gas_price: l2_fair_gas_price as u64,
+                l1_gas_price: l1_gas_price as u64,
+            })
+        })
+        .unwrap_or_else(|| {
+            BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
+                fair_l2_gas_price: l2_fair_gas_price as u64,
+                l1_gas_price: l1_gas_price as u64,
+            })
+        })
+}
+
+/// Projection of the `miniblocks` table corresponding to [`api::MiniblockFeeInput`].
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageMiniblockFeeInput {
+    pub number: i64,
+    pub timestamp: i64,
+    pub protocol_version: Option<i32>,
+    pub l1_gas_price: i64,
+    pub l2_fair_gas_price: i64,
+    pub fair_pubdata_price: Option<i64>,
+}
+
+impl From<StorageMiniblockFeeInput> for api::MiniblockFeeInput {
+    fn from(row: StorageMiniblockFeeInput) -> Self {
+        let protocol_version = row.protocol_version.map(|v| (v as u16).try_into().unwrap());
+        Self {
+            number: MiniblockNumber(row.number as u32),
+            timestamp: row.timestamp as u64,
+            fee_input: miniblock_fee_input(
+                protocol_version,
+                row.l1_gas_price,
+                row.l2_fair_gas_price,
+                row.fair_pubdata_price,
+            ),
         }
     }
 }
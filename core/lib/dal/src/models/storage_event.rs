@@ -4,6 +4,8 @@ use zksync_types::{
     Address, H256,
 };
 
+use crate::compression::decompress;
+
 #[derive(sqlx::FromRow, Debug, Clone)]
 pub struct StorageWeb3Log {
     pub address: Vec<u8>,
@@ -12,6 +14,7 @@ pub struct StorageWeb3Log {
     pub topic3: Vec<u8>,
     pub topic4: Vec<u8>,
     pub value: Vec<u8>,
+    pub value_compression: Option<i16>,
     pub block_hash: Option<Vec<u8>>,
     pub miniblock_number: i64,
     pub l1_batch_number: Option<i64>,
@@ -21,8 +24,10 @@ pub struct StorageWeb3Log {
     pub event_index_in_tx: i32,
 }
 
-impl From<StorageWeb3Log> for Log {
-    fn from(log: StorageWeb3Log) -> Log {
+impl TryFrom<StorageWeb3Log> for Log {
+    type Error = anyhow::Error;
+
+    fn try_from(log: StorageWeb3Log) -> anyhow::Result<Log> {
         let topics = vec![log.topic1, log.topic2, log.topic3, log.topic4]
             .into_iter()
             .filter_map(|topic| {
@@ -33,10 +38,10 @@ impl From<StorageWeb3Log> for Log {
                 }
             })
             .collect();
-        Log {
+        Ok(Log {
             address: Address::from_slice(&log.address),
             topics,
-            data: Bytes(log.value),
+            data: Bytes(decompress(log.value, log.value_compression)?),
             block_hash: log.block_hash.map(|hash| H256::from_slice(&hash)),
             block_number: Some(U64::from(log.miniblock_number as u32)),
             l1_batch_number: log.l1_batch_number.map(U64::from),
@@ -46,7 +51,7 @@ impl From<StorageWeb3Log> for Log {
             transaction_log_index: Some(U256::from(log.event_index_in_tx as u32)),
             log_type: None,
             removed: Some(false),
-        }
+        })
     }
 }
 
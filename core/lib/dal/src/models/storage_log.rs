@@ -1,6 +1,6 @@
 use zksync_types::{L1BatchNumber, MiniblockNumber, H160, H256, U256};
 
-/// Model of the initial write record from the `initial_writes` table. Should only be used in tests.
+/// Model of the initial write record from the `initial_writes` table.
 #[derive(Debug, PartialEq)]
 pub struct DbInitialWrite {
     pub hashed_key: H256,
@@ -108,6 +108,7 @@ impl SyncBlock {
             virtual_blocks: Some(self.virtual_blocks),
             hash: Some(self.hash),
             protocol_version: self.protocol_version,
+            signature: None,
         }
     }
 
@@ -1,5 +1,6 @@
 use std::{convert::TryInto, str::FromStr};
 
+use anyhow::Context as _;
 use bigdecimal::Zero;
 use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_types::{
@@ -18,7 +19,7 @@ use zksync_types::{
 };
 use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
-use crate::BigDecimal;
+use crate::{compression::decompress, BigDecimal};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 #[cfg_attr(test, derive(Default))]
@@ -37,6 +38,7 @@ pub struct StorageTransaction {
     pub gas_per_storage_limit: Option<BigDecimal>,
     pub gas_per_pubdata_limit: Option<BigDecimal>,
     pub input: Option<Vec<u8>>,
+    pub input_compression: Option<i16>,
     pub tx_format: Option<i32>,
     pub data: serde_json::Value,
     pub received_at: NaiveDateTime,
@@ -68,6 +70,16 @@ pub struct StorageTransaction {
     pub updated_at: NaiveDateTime,
 }
 
+impl StorageTransaction {
+    /// Decompresses raw transaction bytes if they are compressed at rest.
+    fn decompress_input(&mut self) -> anyhow::Result<()> {
+        if let Some(input) = self.input.take() {
+            self.input = Some(decompress(input, self.input_compression.take())?);
+        }
+        Ok(())
+    }
+}
+
 impl From<StorageTransaction> for L1TxCommonData {
     fn from(tx: StorageTransaction) -> Self {
         let gas_limit = {
@@ -145,6 +157,7 @@ impl From<StorageTransaction> for L1TxCommonData {
     }
 }
 
+/// Expects raw transaction bytes to be decompressed (see [`StorageTransaction::decompress_input()`]).
 impl From<StorageTransaction> for L2TxCommonData {
     fn from(tx: StorageTransaction) -> Self {
         let gas_limit = {
@@ -289,13 +302,17 @@ impl From<StorageTransaction> for ProtocolUpgradeTxCommonData {
     }
 }
 
-impl From<StorageTransaction> for Transaction {
-    fn from(tx: StorageTransaction) -> Self {
+impl TryFrom<StorageTransaction> for Transaction {
+    type Error = anyhow::Error;
+
+    fn try_from(mut tx: StorageTransaction) -> anyhow::Result<Self> {
         let hash = H256::from_slice(&tx.hash);
+        tx.decompress_input()
+            .with_context(|| format!("failed decompressing input for tx {hash:?}"))?;
         let execute = serde_json::from_value::<Execute>(tx.data.clone())
             .unwrap_or_else(|_| panic!("invalid json in database for tx {:?}", hash));
         let received_timestamp_ms = tx.received_at.timestamp_millis() as u64;
-        match tx.tx_format {
+        Ok(match tx.tx_format {
             Some(t) if t == i32::from(PRIORITY_OPERATION_L2_TX_TYPE) => Transaction {
                 common_data: ExecuteTransactionCommon::L1(tx.into()),
                 execute,
@@ -314,7 +331,7 @@ impl From<StorageTransaction> for Transaction {
                 execute,
                 received_timestamp_ms,
             },
-        }
+        })
     }
 }
 
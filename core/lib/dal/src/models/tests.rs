@@ -99,7 +99,7 @@ fn l2_storage_tx(tx_format: i32) -> StorageTransaction {
 #[test]
 fn storage_tx_to_l1_tx() {
     let stx = l1_storage_tx();
-    let tx = Transaction::from(stx.clone());
+    let tx = Transaction::try_from(stx.clone()).unwrap();
 
     let execute: Execute = serde_json::from_value(stx.data.clone()).unwrap();
     assert_eq!(execute, tx.execute);
@@ -156,12 +156,13 @@ fn storage_tx_to_l1_tx() {
 
 #[test]
 fn storage_tx_to_l1_tx_with_defaults() {
-    let tx_with_defaults = Transaction::from(StorageTransaction {
+    let tx_with_defaults = Transaction::try_from(StorageTransaction {
         l1_tx_refund_recipient: None,
         max_fee_per_gas: None,
         l1_block_number: None,
         ..l1_storage_tx()
-    });
+    })
+    .unwrap();
 
     if let ExecuteTransactionCommon::L1(l1_data) = tx_with_defaults.common_data {
         assert_eq!(0, l1_data.eth_block);
@@ -175,7 +176,7 @@ fn storage_tx_to_l1_tx_with_defaults() {
 #[test]
 fn storage_tx_to_protocol_upgrade_tx() {
     let stx = protocol_upgrade_storage_tx();
-    let tx = Transaction::from(stx.clone());
+    let tx = Transaction::try_from(stx.clone()).unwrap();
 
     let execute: Execute = serde_json::from_value(stx.data.clone()).unwrap();
     assert_eq!(execute, tx.execute);
@@ -222,12 +223,13 @@ fn storage_tx_to_protocol_upgrade_tx() {
 #[test]
 fn storage_tx_to_protocol_upgrade_tx_with_defaults() {
     let stx = protocol_upgrade_storage_tx();
-    let tx_with_defaults = Transaction::from(StorageTransaction {
+    let tx_with_defaults = Transaction::try_from(StorageTransaction {
         l1_tx_mint: None,
         max_fee_per_gas: None,
         l1_block_number: None,
         ..stx.clone()
-    });
+    })
+    .unwrap();
 
     if let ExecuteTransactionCommon::ProtocolUpgrade(l1_data) = tx_with_defaults.common_data {
         assert_eq!(U256::default(), l1_data.to_mint);
@@ -241,7 +243,7 @@ fn storage_tx_to_protocol_upgrade_tx_with_defaults() {
 /// Tests storage transaction to layer 2 transaction logic with different transaction types
 fn storage_tx_to_l2_tx(i_tx_format: i32, o_tx_format: i32) {
     let stx = l2_storage_tx(i_tx_format);
-    let tx = Transaction::from(stx.clone());
+    let tx = Transaction::try_from(stx.clone()).unwrap();
 
     let execute: Execute = serde_json::from_value(stx.data.clone()).unwrap();
     assert_eq!(execute, tx.execute);
@@ -308,5 +310,5 @@ fn storage_tx_to_l2_tx_legacy() {
 #[should_panic(expected = "Unsupported tx type")]
 fn storage_tx_to_l2_tx_unsupported_tx_type() {
     let stx = l2_storage_tx(1984);
-    _ = Transaction::from(stx.clone());
+    _ = Transaction::try_from(stx.clone()).unwrap();
 }
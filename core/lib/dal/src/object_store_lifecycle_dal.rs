@@ -0,0 +1,117 @@
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+/// House keeper job managing artifacts of L1 batches in the object store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectStoreLifecycleJob {
+    /// Removes expired artifacts.
+    GarbageCollector,
+    /// Moves artifacts to the cold storage tier.
+    Archiver,
+    /// Moves storage snapshots to the cold storage tier. Progress is tracked separately from [`Self::Archiver`]
+    /// since snapshots are archived only after they are superseded by a newer snapshot.
+    SnapshotArchiver,
+}
+
+impl ObjectStoreLifecycleJob {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::GarbageCollector => "garbage_collector",
+            Self::Archiver => "archiver",
+            Self::SnapshotArchiver => "snapshot_archiver",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectStoreLifecycleDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl ObjectStoreLifecycleDal<'_, '_> {
+    /// Returns the next L1 batch to be processed by the specified job, or `None` if the job hasn't processed
+    /// any L1 batches yet.
+    pub async fn get_next_l1_batch(
+        &mut self,
+        job: ObjectStoreLifecycleJob,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                next_l1_batch
+            FROM
+                object_store_lifecycle_progress
+            WHERE
+                job = $1
+            "#,
+            job.as_str()
+        )
+        .instrument("get_object_store_lifecycle_next_l1_batch")
+        .with_arg("job", &job.as_str())
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| L1BatchNumber(row.next_l1_batch as u32)))
+    }
+
+    pub async fn set_next_l1_batch(
+        &mut self,
+        job: ObjectStoreLifecycleJob,
+        next_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                object_store_lifecycle_progress (job, next_l1_batch, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (job) DO
+            UPDATE
+            SET
+                next_l1_batch = $2,
+                updated_at = NOW()
+            "#,
+            job.as_str(),
+            i64::from(next_l1_batch.0)
+        )
+        .instrument("set_object_store_lifecycle_next_l1_batch")
+        .with_arg("job", &job.as_str())
+        .with_arg("next_l1_batch", &next_l1_batch)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    #[tokio::test]
+    async fn persisting_object_store_lifecycle_progress() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.object_store_lifecycle_dal();
+        let job = ObjectStoreLifecycleJob::GarbageCollector;
+        assert_eq!(dal.get_next_l1_batch(job).await.unwrap(), None);
+
+        dal.set_next_l1_batch(job, L1BatchNumber(5)).await.unwrap();
+        assert_eq!(
+            dal.get_next_l1_batch(job).await.unwrap(),
+            Some(L1BatchNumber(5))
+        );
+        dal.set_next_l1_batch(job, L1BatchNumber(10)).await.unwrap();
+        assert_eq!(
+            dal.get_next_l1_batch(job).await.unwrap(),
+            Some(L1BatchNumber(10))
+        );
+        // Progress is tracked separately for each job.
+        let archiver_progress = dal
+            .get_next_l1_batch(ObjectStoreLifecycleJob::Archiver)
+            .await
+            .unwrap();
+        assert_eq!(archiver_progress, None);
+    }
+}
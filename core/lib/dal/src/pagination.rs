@@ -0,0 +1,126 @@
+//! Keyset pagination for DAL methods returning potentially long lists of items (logs, transactions, storage logs).
+//!
+//! Instead of skipping a number of rows using `OFFSET` (which requires Postgres to read and discard all skipped rows,
+//! and thus degrades linearly with the page depth), paginated methods return items strictly after the position
+//! of the last item on the previous page. Positions are stable: items inserted after a page was returned
+//! don't shift subsequent pages.
+
+use std::fmt;
+
+use zksync_types::MiniblockNumber;
+
+/// Position of an item in a paginated list. Items are ordered by the miniblock number and then
+/// by the item index in the miniblock (e.g., the event index for logs, or the transaction index for transactions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PageCursor {
+    pub miniblock_number: MiniblockNumber,
+    pub index: u32,
+}
+
+/// Error returned when decoding an invalid [`PageCursor`].
+#[derive(Debug, thiserror::Error)]
+#[error("invalid page cursor")]
+pub struct InvalidPageCursor;
+
+impl PageCursor {
+    const ENCODED_LEN: usize = 8;
+
+    pub fn new(miniblock_number: MiniblockNumber, index: u32) -> Self {
+        Self {
+            miniblock_number,
+            index,
+        }
+    }
+
+    /// Encodes this cursor as an opaque string that can be returned to API clients.
+    pub fn encode(&self) -> String {
+        let mut bytes = [0_u8; Self::ENCODED_LEN];
+        bytes[..4].copy_from_slice(&self.miniblock_number.0.to_be_bytes());
+        bytes[4..].copy_from_slice(&self.index.to_be_bytes());
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    /// Decodes a cursor previously produced by [`Self::encode()`].
+    pub fn decode(s: &str) -> Result<Self, InvalidPageCursor> {
+        let s = s.strip_prefix("0x").ok_or(InvalidPageCursor)?;
+        let bytes = hex::decode(s).map_err(|_| InvalidPageCursor)?;
+        let bytes: [u8; Self::ENCODED_LEN] = bytes.try_into().map_err(|_| InvalidPageCursor)?;
+        let miniblock_number = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let index = u32::from_be_bytes(bytes[4..].try_into().unwrap());
+        Ok(Self::new(MiniblockNumber(miniblock_number), index))
+    }
+
+    /// Returns positional query arguments for the cursor, with the position before all items if the cursor
+    /// is not specified.
+    pub(crate) fn query_args(cursor: Option<Self>) -> (i64, i32) {
+        cursor.map_or((-1, -1), |cursor| {
+            (i64::from(cursor.miniblock_number.0), cursor.index as i32)
+        })
+    }
+}
+
+impl fmt::Display for PageCursor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "miniblock #{}, index {}",
+            self.miniblock_number, self.index
+        )
+    }
+}
+
+/// Page of items returned by a paginated DAL method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor to get the next page. `None` if this is the last page.
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl<T> Page<T> {
+    /// Creates a page from the items fetched with the specified `limit`. If the limit is reached, there may be more
+    /// items, so the next cursor is set to the position of the last item.
+    pub(crate) fn new(items: Vec<T>, limit: usize, position: impl Fn(&T) -> PageCursor) -> Self {
+        let next_cursor = if items.len() >= limit {
+            items.last().map(position)
+        } else {
+            None
+        };
+        Self { items, next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_cursor() {
+        let cursor = PageCursor::new(MiniblockNumber(0x0102_0304), 5);
+        let encoded = cursor.encode();
+        assert_eq!(encoded, "0x0102030400000005");
+        assert_eq!(PageCursor::decode(&encoded).unwrap(), cursor);
+
+        for invalid in [
+            "",
+            "0102030400000005",
+            "0x01020304",
+            "0x0102030400000005ff",
+            "0xzz",
+        ] {
+            PageCursor::decode(invalid).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn creating_page() {
+        let position = |&i: &u32| PageCursor::new(MiniblockNumber(i), 0);
+        let page = Page::new(vec![1, 2, 3], 3, position);
+        assert_eq!(
+            page.next_cursor,
+            Some(PageCursor::new(MiniblockNumber(3), 0))
+        );
+        let page = Page::new(vec![1, 2], 3, position);
+        assert_eq!(page.next_cursor, None);
+    }
+}
@@ -0,0 +1,254 @@
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::MiniblockNumber;
+
+use crate::Core;
+
+/// Table with data that can be removed after a certain retention period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetentionTable {
+    /// Events emitted by transactions.
+    Events,
+    /// Call traces of transactions (used by `debug_*` RPC methods).
+    CallTraces,
+    /// Legacy transaction traces.
+    TransactionTraces,
+}
+
+impl RetentionTable {
+    pub const ALL: [Self; 3] = [Self::Events, Self::CallTraces, Self::TransactionTraces];
+
+    /// Returns the name of the Postgres table.
+    pub fn table_name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::CallTraces => "call_traces",
+            Self::TransactionTraces => "transaction_traces",
+        }
+    }
+}
+
+/// DAL removing expired data in tables with a retention policy. Data age is determined
+/// by the timestamp of the miniblock the data belongs to.
+#[derive(Debug)]
+pub struct RetentionDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl RetentionDal<'_, '_> {
+    /// Returns the last miniblock with the timestamp strictly less than the specified one (in seconds since UNIX epoch).
+    pub async fn get_last_miniblock_before(
+        &mut self,
+        timestamp: u64,
+    ) -> sqlx::Result<Option<MiniblockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(number) AS "number?"
+            FROM
+                miniblocks
+            WHERE
+                timestamp < $1
+            "#,
+            timestamp as i64
+        )
+        .instrument("get_last_miniblock_before")
+        .with_arg("timestamp", &timestamp)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| MiniblockNumber(number as u32)))
+    }
+
+    /// Removes at most `limit` rows from the specified table belonging to miniblocks up to and including
+    /// `last_expired_miniblock`. Returns the number of removed rows; if it's less than `limit`, there are no more
+    /// expired rows in the table.
+    pub async fn delete_expired_rows(
+        &mut self,
+        table: RetentionTable,
+        last_expired_miniblock: MiniblockNumber,
+        limit: usize,
+    ) -> sqlx::Result<u64> {
+        let last_expired_miniblock_i64 = i64::from(last_expired_miniblock.0);
+        let limit_i64 = limit as i64;
+        let result = match table {
+            RetentionTable::Events => {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM events
+                    WHERE
+                        ctid = ANY (
+                            ARRAY(
+                                SELECT
+                                    ctid
+                                FROM
+                                    events
+                                WHERE
+                                    miniblock_number <= $1
+                                LIMIT
+                                    $2
+                            )
+                        )
+                    "#,
+                    last_expired_miniblock_i64,
+                    limit_i64
+                )
+                .instrument("delete_expired_events")
+                .with_arg("last_expired_miniblock", &last_expired_miniblock)
+                .with_arg("limit", &limit)
+                .report_latency()
+                .execute(self.storage)
+                .await?
+            }
+            RetentionTable::CallTraces => {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM call_traces
+                    WHERE
+                        tx_hash = ANY (
+                            ARRAY(
+                                SELECT
+                                    call_traces.tx_hash
+                                FROM
+                                    call_traces
+                                    INNER JOIN transactions ON transactions.hash = call_traces.tx_hash
+                                WHERE
+                                    transactions.miniblock_number <= $1
+                                LIMIT
+                                    $2
+                            )
+                        )
+                    "#,
+                    last_expired_miniblock_i64,
+                    limit_i64
+                )
+                .instrument("delete_expired_call_traces")
+                .with_arg("last_expired_miniblock", &last_expired_miniblock)
+                .with_arg("limit", &limit)
+                .report_latency()
+                .execute(self.storage)
+                .await?
+            }
+            RetentionTable::TransactionTraces => {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM transaction_traces
+                    WHERE
+                        tx_hash = ANY (
+                            ARRAY(
+                                SELECT
+                                    transaction_traces.tx_hash
+                                FROM
+                                    transaction_traces
+                                    INNER JOIN transactions ON transactions.hash = transaction_traces.tx_hash
+                                WHERE
+                                    transactions.miniblock_number <= $1
+                                LIMIT
+                                    $2
+                            )
+                        )
+                    "#,
+                    last_expired_miniblock_i64,
+                    limit_i64
+                )
+                .instrument("delete_expired_transaction_traces")
+                .with_arg("last_expired_miniblock", &last_expired_miniblock)
+                .with_arg("limit", &limit)
+                .report_latency()
+                .execute(self.storage)
+                .await?
+            }
+        };
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        tx::IncludedTxLocation, Address, L1BatchNumber, ProtocolVersion, VmEvent, H256,
+    };
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool, CoreDal};
+
+    async fn insert_miniblock_with_events(conn: &mut Connection<'_, Core>, number: u32) {
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        let events: Vec<_> = (0..3_u8)
+            .map(|i| VmEvent {
+                location: (L1BatchNumber(number), 0),
+                address: Address::repeat_byte(i),
+                indexed_topics: vec![H256::repeat_byte(i)],
+                value: vec![i],
+            })
+            .collect();
+        let location = IncludedTxLocation {
+            tx_hash: H256::from_low_u64_be(number.into()),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::zero(),
+        };
+        conn.events_dal()
+            .save_events(
+                MiniblockNumber(number),
+                &[(location, events.iter().collect())],
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn deleting_expired_events() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=3 {
+            insert_miniblock_with_events(&mut conn, number).await;
+        }
+
+        // Miniblock timestamps are equal to their numbers.
+        let mut dal = conn.retention_dal();
+        assert_eq!(dal.get_last_miniblock_before(1).await.unwrap(), None);
+        let last_expired_miniblock = dal.get_last_miniblock_before(3).await.unwrap();
+        assert_eq!(last_expired_miniblock, Some(MiniblockNumber(2)));
+
+        let deleted_count = dal
+            .delete_expired_rows(RetentionTable::Events, MiniblockNumber(2), 4)
+            .await
+            .unwrap();
+        assert_eq!(deleted_count, 4);
+        let deleted_count = dal
+            .delete_expired_rows(RetentionTable::Events, MiniblockNumber(2), 4)
+            .await
+            .unwrap();
+        assert_eq!(deleted_count, 2);
+        let deleted_count = dal
+            .delete_expired_rows(RetentionTable::Events, MiniblockNumber(2), 4)
+            .await
+            .unwrap();
+        assert_eq!(deleted_count, 0);
+
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 3);
+        assert!(logs
+            .iter()
+            .all(|log| log.block_number == Some(3_u64.into())));
+
+        for table in [
+            RetentionTable::CallTraces,
+            RetentionTable::TransactionTraces,
+        ] {
+            let deleted_count = conn
+                .retention_dal()
+                .delete_expired_rows(table, MiniblockNumber(3), 100)
+                .await
+                .unwrap();
+            assert_eq!(deleted_count, 0);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
 use zksync_types::{
     snapshots::{AllSnapshots, SnapshotMetadata, SnapshotVersion},
-    L1BatchNumber,
+    L1BatchNumber, H256,
 };
 
 use crate::Core;
@@ -11,6 +11,7 @@ struct StorageSnapshotMetadata {
     version: i32,
     l1_batch_number: i64,
     storage_logs_filepaths: Vec<String>,
+    storage_logs_hashes: Vec<Vec<u8>>,
     factory_deps_filepath: String,
 }
 
@@ -27,6 +28,18 @@ impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
                 index: "version".to_owned(),
                 source: err.into(),
             })?;
+        let storage_logs_hashes = row
+            .storage_logs_hashes
+            .into_iter()
+            .map(|hash| match hash.len() {
+                0 => Ok(None),
+                32 => Ok(Some(H256::from_slice(&hash))),
+                len => Err(sqlx::Error::ColumnDecode {
+                    index: "storage_logs_hashes".to_owned(),
+                    source: format!("unexpected hash length: {len}").into(),
+                }),
+            })
+            .collect::<Result<_, _>>()?;
 
         Ok(Self {
             version,
@@ -36,6 +49,7 @@ impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
                 .into_iter()
                 .map(|path| (!path.is_empty()).then_some(path))
                 .collect(),
+            storage_logs_hashes,
             factory_deps_filepath: row.factory_deps_filepath,
         })
     }
@@ -61,12 +75,21 @@ impl SnapshotsDal<'_, '_> {
                     VERSION,
                     l1_batch_number,
                     storage_logs_filepaths,
+                    storage_logs_hashes,
                     factory_deps_filepath,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]), $4, NOW(), NOW())
+                (
+                    $1,
+                    $2,
+                    ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]),
+                    ARRAY_FILL(''::BYTEA, ARRAY[$3::INTEGER]),
+                    $4,
+                    NOW(),
+                    NOW()
+                )
             "#,
             version as i32,
             l1_batch_number.0 as i32,
@@ -80,17 +103,22 @@ impl SnapshotsDal<'_, '_> {
         Ok(())
     }
 
+    /// Records the object store path and the content hash (as returned by
+    /// [`SnapshotStorageLogsChunk::content_hash()`](zksync_types::snapshots::SnapshotStorageLogsChunk::content_hash()))
+    /// of a storage logs chunk.
     pub async fn add_storage_logs_filepath_for_snapshot(
         &mut self,
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         storage_logs_filepath: &str,
+        storage_logs_hash: H256,
     ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             UPDATE snapshots
             SET
                 storage_logs_filepaths[$2] = $3,
+                storage_logs_hashes[$2] = $4,
                 updated_at = NOW()
             WHERE
                 l1_batch_number = $1
@@ -98,6 +126,7 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32,
             chunk_id as i32 + 1,
             storage_logs_filepath,
+            storage_logs_hash.as_bytes(),
         )
         .execute(self.storage.conn())
         .await?;
@@ -141,7 +170,8 @@ impl SnapshotsDal<'_, '_> {
                 VERSION,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                storage_logs_filepaths,
+                storage_logs_hashes
             FROM
                 snapshots
             ORDER BY
@@ -169,7 +199,8 @@ impl SnapshotsDal<'_, '_> {
                 VERSION,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                storage_logs_filepaths,
+                storage_logs_hashes
             FROM
                 snapshots
             WHERE
@@ -184,6 +215,23 @@ impl SnapshotsDal<'_, '_> {
 
         row.map(TryFrom::try_from).transpose()
     }
+
+    /// Removes metadata for the snapshot at the specified L1 batch. Returns `false` if there was no such snapshot.
+    pub async fn delete_snapshot(&mut self, l1_batch_number: L1BatchNumber) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM snapshots
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i32
+        )
+        .instrument("delete_snapshot")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 #[cfg(test)]
@@ -218,6 +266,7 @@ mod tests {
                 l1_batch_number,
                 i,
                 "gs:///bucket/chunk.bin",
+                H256::repeat_byte(i as u8),
             )
             .await
             .unwrap();
@@ -235,6 +284,15 @@ mod tests {
             .expect("Failed to retrieve snapshot")
             .unwrap();
         assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(
+            snapshot_metadata.storage_logs_hashes,
+            [Some(H256::repeat_byte(0)), Some(H256::repeat_byte(1))]
+        );
+
+        assert!(dal.delete_snapshot(l1_batch_number).await.unwrap());
+        let snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(snapshots.snapshots_l1_batch_numbers, []);
+        assert!(!dal.delete_snapshot(l1_batch_number).await.unwrap());
     }
 
     #[tokio::test]
@@ -253,33 +311,50 @@ mod tests {
         .expect("Failed to add snapshot");
 
         let storage_log_filepaths = ["gs:///bucket/test_file1.bin", "gs:///bucket/test_file2.bin"];
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 1, storage_log_filepaths[1])
-            .await
-            .unwrap();
+        let storage_log_hashes = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            storage_log_filepaths[1],
+            storage_log_hashes[1],
+        )
+        .await
+        .unwrap();
 
-        let files = dal
+        let snapshot_metadata = dal
             .get_snapshot_metadata(l1_batch_number)
             .await
             .expect("Failed to retrieve snapshot")
-            .unwrap()
-            .storage_logs_filepaths;
+            .unwrap();
         assert_eq!(
-            files,
+            snapshot_metadata.storage_logs_filepaths,
             [None, Some("gs:///bucket/test_file2.bin".to_string())]
         );
+        assert_eq!(
+            snapshot_metadata.storage_logs_hashes,
+            [None, Some(storage_log_hashes[1])]
+        );
 
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 0, storage_log_filepaths[0])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            0,
+            storage_log_filepaths[0],
+            storage_log_hashes[0],
+        )
+        .await
+        .unwrap();
 
-        let files = dal
+        let snapshot_metadata = dal
             .get_snapshot_metadata(l1_batch_number)
             .await
             .expect("Failed to retrieve snapshot")
-            .unwrap()
-            .storage_logs_filepaths;
+            .unwrap();
+        assert_eq!(
+            snapshot_metadata.storage_logs_hashes,
+            storage_log_hashes.map(Some)
+        );
         assert_eq!(
-            files,
+            snapshot_metadata.storage_logs_filepaths,
             [
                 Some("gs:///bucket/test_file1.bin".to_string()),
                 Some("gs:///bucket/test_file2.bin".to_string())
@@ -2,7 +2,8 @@ use std::{collections::HashMap, ops, time::Instant};
 
 use sqlx::types::chrono::Utc;
 use zksync_db_connection::{
-    connection::Connection, instrument::InstrumentExt, write_str, writeln_str,
+    connection::Connection, copy::BinaryCopyBuffer, instrument::InstrumentExt, write_str,
+    writeln_str,
 };
 use zksync_types::{
     get_code_key, snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber,
@@ -10,7 +11,19 @@ use zksync_types::{
 };
 
 pub use crate::models::storage_log::{DbStorageLog, StorageRecoveryLogEntry};
-use crate::{Core, CoreDal};
+use crate::{
+    pagination::{Page, PageCursor},
+    Core, CoreDal,
+};
+
+/// Partition of the `storage_logs` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageLogsPartition {
+    pub name: String,
+    /// Range of miniblocks covered by the partition, or `None` for the default partition (the one storing rows
+    /// not covered by other partitions).
+    pub miniblocks: Option<ops::Range<MiniblockNumber>>,
+}
 
 #[derive(Debug)]
 pub struct StorageLogsDal<'a, 'c> {
@@ -71,6 +84,58 @@ impl StorageLogsDal<'_, '_> {
         Ok(())
     }
 
+    /// Same as [`Self::insert_storage_logs()`], but uses the binary `COPY` format, which is faster
+    /// for large numbers of logs (e.g., when sealing a miniblock with many transactions).
+    pub async fn insert_storage_logs_copy(
+        &mut self,
+        block_number: MiniblockNumber,
+        logs: &[(H256, Vec<StorageLog>)],
+    ) -> sqlx::Result<()> {
+        self.insert_storage_logs_copy_for_miniblocks(&[(block_number, logs)])
+            .await
+    }
+
+    /// Same as [`Self::insert_storage_logs_copy()`], but inserts logs for several miniblocks
+    /// with a single `COPY` statement.
+    pub async fn insert_storage_logs_copy_for_miniblocks(
+        &mut self,
+        logs_by_miniblock: &[(MiniblockNumber, &[(H256, Vec<StorageLog>)])],
+    ) -> sqlx::Result<()> {
+        let mut buffer = BinaryCopyBuffer::new(9);
+        let now = Utc::now().naive_utc();
+        for &(block_number, logs) in logs_by_miniblock {
+            let mut operation_number = 0_u32;
+            for (tx_hash, logs) in logs {
+                for log in logs {
+                    buffer
+                        .start_row()
+                        .write_bytes(log.key.hashed_key().as_bytes())
+                        .write_bytes(log.key.address().as_bytes())
+                        .write_bytes(log.key.key().as_bytes())
+                        .write_bytes(log.value.as_bytes())
+                        .write_i32(operation_number as i32)
+                        .write_bytes(tx_hash.as_bytes())
+                        .write_i64(block_number.0.into())
+                        .write_timestamp(now)
+                        .write_timestamp(now);
+                    operation_number += 1;
+                }
+            }
+        }
+
+        buffer
+            .send(
+                self.storage.conn(),
+                "COPY storage_logs(
+                    hashed_key, address, key, value, operation_number, tx_hash, miniblock_number,
+                    created_at, updated_at
+                )
+                FROM STDIN (FORMAT BINARY)",
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn insert_storage_logs_from_snapshot(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -111,6 +176,41 @@ impl StorageLogsDal<'_, '_> {
         Ok(())
     }
 
+    /// Same as [`Self::insert_storage_logs_from_snapshot()`], but uses the binary `COPY` format.
+    pub async fn insert_storage_logs_from_snapshot_copy(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> sqlx::Result<()> {
+        let mut buffer = BinaryCopyBuffer::new(9);
+        let now = Utc::now().naive_utc();
+        for log in snapshot_storage_logs {
+            buffer
+                .start_row()
+                .write_bytes(log.key.hashed_key().as_bytes())
+                .write_bytes(log.key.address().as_bytes())
+                .write_bytes(log.key.key().as_bytes())
+                .write_bytes(log.value.as_bytes())
+                .write_i32(log.enumeration_index as i32)
+                .write_bytes(H256::zero().as_bytes())
+                .write_i64(miniblock_number.0.into())
+                .write_timestamp(now)
+                .write_timestamp(now);
+        }
+
+        buffer
+            .send(
+                self.storage.conn(),
+                "COPY storage_logs(
+                    hashed_key, address, key, value, operation_number, tx_hash, miniblock_number,
+                    created_at, updated_at
+                )
+                FROM STDIN (FORMAT BINARY)",
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn append_storage_logs(
         &mut self,
         block_number: MiniblockNumber,
@@ -251,10 +351,26 @@ impl StorageLogsDal<'_, '_> {
     }
 
     /// Removes all storage logs with a miniblock number strictly greater than the specified `block_number`.
+    ///
+    /// Partitions entirely consisting of removed miniblocks are truncated, so that their rows don't need to be
+    /// deleted (and vacuumed) one by one.
     pub async fn rollback_storage_logs(
         &mut self,
         block_number: MiniblockNumber,
     ) -> sqlx::Result<()> {
+        for partition in self.get_partitions().await? {
+            let Some(miniblocks) = &partition.miniblocks else {
+                continue;
+            };
+            if miniblocks.start > block_number && !miniblocks.is_empty() {
+                sqlx::query(&format!("TRUNCATE {}", partition.name))
+                    .instrument("rollback_storage_logs#truncate")
+                    .with_arg("partition", &partition.name)
+                    .execute(self.storage)
+                    .await?;
+            }
+        }
+
         sqlx::query!(
             r#"
             DELETE FROM storage_logs
@@ -268,6 +384,103 @@ impl StorageLogsDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns all partitions of the `storage_logs` table ordered by the first covered miniblock.
+    /// The default partition goes first.
+    pub async fn get_partitions(&mut self) -> sqlx::Result<Vec<StorageLogsPartition>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                child.relname::TEXT AS "name!",
+                SUBSTRING(
+                    pg_get_expr(child.relpartbound, child.oid)
+                    FROM
+                        'FROM \(''(\d+)''\)'
+                )::BIGINT AS "start_miniblock?",
+                SUBSTRING(
+                    pg_get_expr(child.relpartbound, child.oid)
+                    FROM
+                        'TO \(''(\d+)''\)'
+                )::BIGINT AS "end_miniblock?"
+            FROM
+                pg_inherits
+                INNER JOIN pg_class parent ON parent.oid = pg_inherits.inhparent
+                INNER JOIN pg_class child ON child.oid = pg_inherits.inhrelid
+            WHERE
+                parent.relname = 'storage_logs'
+            ORDER BY
+                "end_miniblock?" NULLS FIRST
+            "#
+        )
+        .instrument("get_storage_logs_partitions")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                // The legacy partition has no lower bound.
+                let start = MiniblockNumber(row.start_miniblock.unwrap_or(0) as u32);
+                let miniblocks = row
+                    .end_miniblock
+                    .map(|end| start..MiniblockNumber(end as u32));
+                StorageLogsPartition {
+                    name: row.name,
+                    miniblocks,
+                }
+            })
+            .collect())
+    }
+
+    /// Returns the last miniblock with storage logs in the default partition of the `storage_logs` table.
+    /// Range partitions created later must not cover this miniblock.
+    pub async fn get_last_miniblock_in_default_partition(
+        &mut self,
+    ) -> sqlx::Result<Option<MiniblockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(miniblock_number) AS "number?"
+            FROM
+                storage_logs_default
+            "#
+        )
+        .instrument("get_last_miniblock_in_default_partition")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| MiniblockNumber(number as u32)))
+    }
+
+    /// Creates a partition of the `storage_logs` table covering the specified miniblocks. The partition is created
+    /// as a standalone table and then attached, so that the `storage_logs` table isn't locked exclusively.
+    pub async fn create_partition(
+        &mut self,
+        miniblocks: ops::Range<MiniblockNumber>,
+    ) -> sqlx::Result<StorageLogsPartition> {
+        let name = format!("storage_logs_{}_{}", miniblocks.start, miniblocks.end);
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query(&format!(
+            "CREATE TABLE {name} (LIKE storage_logs INCLUDING DEFAULTS INCLUDING CONSTRAINTS)"
+        ))
+        .instrument("create_storage_logs_partition#create")
+        .with_arg("miniblocks", &miniblocks)
+        .execute(&mut transaction)
+        .await?;
+        sqlx::query(&format!(
+            "ALTER TABLE storage_logs ATTACH PARTITION {name} FOR VALUES FROM ({}) TO ({})",
+            miniblocks.start, miniblocks.end
+        ))
+        .instrument("create_storage_logs_partition#attach")
+        .with_arg("miniblocks", &miniblocks)
+        .execute(&mut transaction)
+        .await?;
+        transaction.commit().await?;
+
+        Ok(StorageLogsPartition {
+            name,
+            miniblocks: Some(miniblocks),
+        })
+    }
+
     pub async fn is_contract_deployed_at_address(&mut self, address: Address) -> bool {
         let hashed_key = get_code_key(&address).hashed_key();
         let row = sqlx::query!(
@@ -598,6 +811,125 @@ impl StorageLogsDal<'_, '_> {
             .collect())
     }
 
+    /// Returns current values for the specified keys at the specified `miniblock_number` together with
+    /// the L1 batches in which these values were written. The L1 batch is `None` if the value was written
+    /// in a miniblock not present in Postgres (e.g., if the value was recovered from a snapshot).
+    /// Keys without any writes are not present in the returned map.
+    pub async fn get_storage_values_with_l1_batches(
+        &mut self,
+        hashed_keys: &[H256],
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<HashMap<H256, (H256, Option<L1BatchNumber>)>> {
+        let hashed_keys: Vec<_> = hashed_keys.iter().map(H256::as_bytes).collect();
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                u.hashed_key AS "hashed_key!",
+                sl.value AS "value!",
+                miniblocks.l1_batch_number AS "l1_batch_number?"
+            FROM
+                UNNEST($1::bytea[]) AS u (hashed_key)
+                INNER JOIN LATERAL (
+                    SELECT
+                        value,
+                        miniblock_number
+                    FROM
+                        storage_logs
+                    WHERE
+                        hashed_key = u.hashed_key
+                        AND miniblock_number <= $2
+                    ORDER BY
+                        miniblock_number DESC,
+                        operation_number DESC
+                    LIMIT
+                        1
+                ) sl ON TRUE
+                LEFT JOIN miniblocks ON miniblocks.number = sl.miniblock_number
+            "#,
+            &hashed_keys as &[&[u8]],
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_storage_values_with_l1_batches")
+        .with_arg("hashed_keys.len", &hashed_keys.len())
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let key = H256::from_slice(&row.hashed_key);
+                let value = H256::from_slice(&row.value);
+                let l1_batch_number = row
+                    .l1_batch_number
+                    .map(|number| L1BatchNumber(number as u32));
+                (key, (value, l1_batch_number))
+            })
+            .collect())
+    }
+
+    /// Returns a page of raw storage logs in the specified miniblock range, ordered by the miniblock number
+    /// and then by the operation number in the miniblock.
+    pub async fn get_storage_logs_page(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        after: Option<PageCursor>,
+        limit: usize,
+    ) -> sqlx::Result<Page<DbStorageLog>> {
+        let (after_miniblock, after_operation) = PageCursor::query_args(after);
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hashed_key,
+                address,
+                key,
+                value,
+                operation_number,
+                tx_hash,
+                miniblock_number
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND (miniblock_number, operation_number) > ($3, $4)
+            ORDER BY
+                miniblock_number,
+                operation_number
+            LIMIT
+                $5
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0),
+            after_miniblock,
+            after_operation,
+            limit as i64
+        )
+        .instrument("get_storage_logs_page")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("after", &after)
+        .with_arg("limit", &limit)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        let logs = rows
+            .into_iter()
+            .map(|row| DbStorageLog {
+                hashed_key: H256::from_slice(&row.hashed_key),
+                address: H160::from_slice(&row.address),
+                key: H256::from_slice(&row.key),
+                value: H256::from_slice(&row.value),
+                operation_number: row.operation_number as u64,
+                tx_hash: H256::from_slice(&row.tx_hash),
+                miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+            })
+            .collect();
+        Ok(Page::new(logs, limit, |log| {
+            PageCursor::new(log.miniblock_number, log.operation_number as u32)
+        }))
+    }
+
     /// Retrieves all storage log entries for testing purposes.
     pub async fn dump_all_storage_logs_for_tests(&mut self) -> Vec<DbStorageLog> {
         let rows = sqlx::query!(
@@ -761,7 +1093,9 @@ mod tests {
     use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
 
     use super::*;
-    use crate::{tests::create_miniblock_header, ConnectionPool, Core};
+    use crate::{
+        models::storage_log::DbInitialWrite, tests::create_miniblock_header, ConnectionPool, Core,
+    };
 
     async fn insert_miniblock(conn: &mut Connection<'_, Core>, number: u32, logs: Vec<StorageLog>) {
         let header = L1BatchHeader::new(
@@ -792,6 +1126,76 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn partitioning_storage_logs() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let partitions = conn.storage_logs_dal().get_partitions().await.unwrap();
+        assert_eq!(partitions.len(), 2, "{partitions:?}");
+        assert_eq!(partitions[0].name, "storage_logs_default");
+        assert_eq!(partitions[0].miniblocks, None);
+        assert_eq!(partitions[1].name, "storage_logs_legacy");
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let log = StorageLog::new_write_log(StorageKey::new(account, H256::zero()), H256::zero());
+        insert_miniblock(&mut conn, 1, vec![log]).await;
+        let last_miniblock = conn
+            .storage_logs_dal()
+            .get_last_miniblock_in_default_partition()
+            .await
+            .unwrap();
+        assert_eq!(last_miniblock, Some(MiniblockNumber(1)));
+
+        for start in [2, 4] {
+            let miniblocks = MiniblockNumber(start)..MiniblockNumber(start + 2);
+            conn.storage_logs_dal()
+                .create_partition(miniblocks)
+                .await
+                .unwrap();
+        }
+        let partitions = conn.storage_logs_dal().get_partitions().await.unwrap();
+        let partition_ranges: Vec<_> = partitions[2..]
+            .iter()
+            .map(|partition| partition.miniblocks.clone().unwrap())
+            .collect();
+        assert_eq!(
+            partition_ranges,
+            [
+                MiniblockNumber(2)..MiniblockNumber(4),
+                MiniblockNumber(4)..MiniblockNumber(6)
+            ]
+        );
+
+        for number in 2..=5 {
+            let key = StorageKey::new(account, H256::from_low_u64_be(number.into()));
+            let log = StorageLog::new_write_log(key, H256::repeat_byte(number as u8));
+            insert_miniblock(&mut conn, number, vec![log]).await;
+        }
+        let last_miniblock = conn
+            .storage_logs_dal()
+            .get_last_miniblock_in_default_partition()
+            .await
+            .unwrap();
+        assert_eq!(last_miniblock, Some(MiniblockNumber(1)));
+
+        // The last partition should be truncated, and a log in the first partition deleted.
+        conn.storage_logs_dal()
+            .rollback_storage_logs(MiniblockNumber(2))
+            .await
+            .unwrap();
+        let logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        let mut miniblock_numbers: Vec<_> = logs.iter().map(|log| log.miniblock_number).collect();
+        miniblock_numbers.sort_unstable();
+        assert_eq!(miniblock_numbers, [MiniblockNumber(1), MiniblockNumber(2)]);
+    }
+
     #[tokio::test]
     async fn inserting_storage_logs() {
         let pool = ConnectionPool::<Core>::test_pool().await;
@@ -838,6 +1242,114 @@ mod tests {
         test_rollback(&mut conn, first_key, second_key).await;
     }
 
+    #[tokio::test]
+    async fn inserting_storage_logs_via_binary_copy() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in [1, 2, 3] {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let logs: Vec<_> = (0..3)
+            .map(|i| {
+                let key = StorageKey::new(account, H256::from_low_u64_be(i));
+                let tx_hash = H256::from_low_u64_be(i);
+                (
+                    tx_hash,
+                    vec![StorageLog::new_write_log(key, H256::repeat_byte(i as u8 + 1)); 2],
+                )
+            })
+            .collect();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &logs)
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs_copy_for_miniblocks(&[
+                (MiniblockNumber(2), &logs[..]),
+                (MiniblockNumber(3), &logs[..]),
+            ])
+            .await
+            .unwrap();
+
+        let mut all_logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        all_logs.sort_unstable_by_key(|log| (log.miniblock_number, log.operation_number));
+        assert_eq!(all_logs.len(), 18);
+        let (text_logs, binary_logs) = all_logs.split_at_mut(6);
+        for (i, chunk) in binary_logs.chunks_mut(6).enumerate() {
+            for log in chunk.iter_mut() {
+                assert_eq!(log.miniblock_number, MiniblockNumber(i as u32 + 2));
+                log.miniblock_number = MiniblockNumber(1);
+            }
+            assert_eq!(text_logs, chunk);
+        }
+    }
+
+    #[tokio::test]
+    async fn inserting_snapshot_storage_logs_via_binary_copy() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let snapshot_logs: Vec<_> = (0..5)
+            .map(|i| SnapshotStorageLog {
+                key: StorageKey::new(account, H256::from_low_u64_be(i)),
+                value: H256::repeat_byte(i as u8 + 1),
+                l1_batch_number_of_initial_write: L1BatchNumber(i as u32 + 1),
+                enumeration_index: i + 10,
+            })
+            .collect();
+        conn.storage_logs_dal()
+            .insert_storage_logs_from_snapshot(MiniblockNumber(1), &snapshot_logs)
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs_from_snapshot_copy(MiniblockNumber(2), &snapshot_logs)
+            .await
+            .unwrap();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes_from_snapshot_copy(&snapshot_logs)
+            .await
+            .unwrap();
+
+        let mut all_logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        all_logs.sort_unstable_by_key(|log| (log.miniblock_number, log.operation_number));
+        assert_eq!(all_logs.len(), 10);
+        let (text_logs, binary_logs) = all_logs.split_at_mut(5);
+        for log in binary_logs.iter_mut() {
+            assert_eq!(log.miniblock_number, MiniblockNumber(2));
+            log.miniblock_number = MiniblockNumber(1);
+        }
+        assert_eq!(text_logs, binary_logs);
+
+        let mut initial_writes = conn
+            .storage_logs_dedup_dal()
+            .dump_all_initial_writes_for_tests()
+            .await;
+        initial_writes.sort_unstable_by_key(|write| write.index);
+        let expected_writes: Vec<_> = snapshot_logs
+            .iter()
+            .map(|log| DbInitialWrite {
+                hashed_key: log.key.hashed_key(),
+                l1_batch_number: log.l1_batch_number_of_initial_write,
+                index: log.enumeration_index,
+            })
+            .collect();
+        assert_eq!(initial_writes, expected_writes);
+    }
+
     async fn test_rollback(
         conn: &mut Connection<'_, Core>,
         key: StorageKey,
@@ -1,14 +1,16 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops};
 
 use sqlx::types::chrono::Utc;
-use zksync_db_connection::connection::Connection;
+use zksync_db_connection::{
+    connection::Connection, copy::BinaryCopyBuffer, instrument::InstrumentExt,
+};
 use zksync_types::{
     snapshots::SnapshotStorageLog, zk_evm_types::LogQuery, AccountTreeId, Address, L1BatchNumber,
-    StorageKey, H256,
+    MiniblockNumber, StorageKey, H256,
 };
 use zksync_utils::u256_to_h256;
 
-pub use crate::models::storage_log::DbInitialWrite;
+pub use crate::models::storage_log::{DbInitialWrite, StorageRecoveryLogEntry};
 use crate::Core;
 
 #[derive(Debug)]
@@ -81,6 +83,33 @@ impl StorageLogsDedupDal<'_, '_> {
         Ok(())
     }
 
+    /// Same as [`Self::insert_initial_writes_from_snapshot()`], but uses the binary `COPY` format.
+    pub async fn insert_initial_writes_from_snapshot_copy(
+        &mut self,
+        snapshot_storage_logs: &[SnapshotStorageLog],
+    ) -> sqlx::Result<()> {
+        let mut buffer = BinaryCopyBuffer::new(5);
+        let now = Utc::now().naive_utc();
+        for log in snapshot_storage_logs {
+            buffer
+                .start_row()
+                .write_bytes(log.key.hashed_key().as_bytes())
+                .write_i64(log.enumeration_index as i64)
+                .write_i64(log.l1_batch_number_of_initial_write.0.into())
+                .write_timestamp(now)
+                .write_timestamp(now);
+        }
+
+        buffer
+            .send(
+                self.storage.conn(),
+                "COPY initial_writes (hashed_key, index, l1_batch_number, created_at, updated_at) \
+                FROM STDIN (FORMAT BINARY)",
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn insert_initial_writes(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -149,6 +178,99 @@ impl StorageLogsDedupDal<'_, '_> {
             .collect())
     }
 
+    /// Marks protective reads for the specified L1 batch as pending, i.e. to be persisted asynchronously
+    /// by the protective reads writer.
+    pub async fn mark_protective_reads_as_pending(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pending_protective_reads (l1_batch_number, created_at)
+            VALUES
+                ($1, NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_protective_reads_as_pending")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the pending mark for protective reads of the specified L1 batch. Returns `false` if the protective reads
+    /// were not pending.
+    pub async fn remove_pending_protective_reads(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM pending_protective_reads
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("remove_pending_protective_reads")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns L1 batches with pending protective reads in the ascending order.
+    pub async fn get_l1_batches_with_pending_protective_reads(
+        &mut self,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                pending_protective_reads
+            ORDER BY
+                l1_batch_number
+            "#
+        )
+        .instrument("get_l1_batches_with_pending_protective_reads")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect())
+    }
+
+    /// Checks whether protective reads for the specified L1 batch are pending, i.e. not persisted yet.
+    pub async fn are_protective_reads_pending(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<bool> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        pending_protective_reads
+                    WHERE
+                        l1_batch_number = $1
+                ) AS "is_pending!"
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("are_protective_reads_pending")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?
+        .is_pending)
+    }
+
     async fn max_enumeration_index(&mut self) -> sqlx::Result<Option<u64>> {
         Ok(sqlx::query!(
             r#"
@@ -233,6 +355,212 @@ impl StorageLogsDedupDal<'_, '_> {
         .collect())
     }
 
+    /// Returns up to `limit` initial writes made in or before the specified L1 batch with hashed keys
+    /// greater than or equal to `start_key`, ordered by hashed key. Since hashed keys are uniformly distributed,
+    /// this can be used to efficiently sample initial writes by choosing a random `start_key`.
+    pub async fn sample_initial_writes(
+        &mut self,
+        start_key: H256,
+        l1_batch_number: L1BatchNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<DbInitialWrite>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hashed_key,
+                l1_batch_number,
+                INDEX
+            FROM
+                initial_writes
+            WHERE
+                hashed_key >= $1
+                AND l1_batch_number <= $2
+            ORDER BY
+                hashed_key
+            LIMIT
+                $3
+            "#,
+            start_key.as_bytes(),
+            i64::from(l1_batch_number.0),
+            limit as i64
+        )
+        .instrument("sample_initial_writes")
+        .with_arg("start_key", &start_key)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DbInitialWrite {
+                hashed_key: H256::from_slice(&row.hashed_key),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                index: row.index as u64,
+            })
+            .collect())
+    }
+
+    /// Returns the maximum enumeration index assigned in or before the specified L1 batch. Since enumeration indices
+    /// are assigned sequentially starting from 1, this is equal to the number of initial writes up to the batch.
+    pub async fn max_enumeration_index_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(INDEX) AS "max?"
+            FROM
+                initial_writes
+            WHERE
+                l1_batch_number = (
+                    SELECT
+                        MAX(l1_batch_number) AS "max?"
+                    FROM
+                        initial_writes
+                    WHERE
+                        l1_batch_number <= $1
+                )
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("max_enumeration_index_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.max.map(|max| max as u64))
+    }
+
+    /// Gets a starting tree entry for each of the supplied `key_ranges` as of the specified L1 batch, which
+    /// must end with `last_miniblock`. This method is used when rebuilding the Merkle tree from Postgres.
+    pub async fn get_chunk_starts_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        last_miniblock: MiniblockNumber,
+        key_ranges: &[ops::RangeInclusive<H256>],
+    ) -> sqlx::Result<Vec<Option<StorageRecoveryLogEntry>>> {
+        let (start_keys, end_keys): (Vec<_>, Vec<_>) = key_ranges
+            .iter()
+            .map(|range| (range.start().as_bytes(), range.end().as_bytes()))
+            .unzip();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                iw.hashed_key AS "hashed_key?",
+                iw.index AS "index?",
+                (
+                    SELECT
+                        value
+                    FROM
+                        storage_logs
+                    WHERE
+                        storage_logs.hashed_key = iw.hashed_key
+                        AND storage_logs.miniblock_number <= $2
+                    ORDER BY
+                        storage_logs.miniblock_number DESC,
+                        storage_logs.operation_number DESC
+                    LIMIT
+                        1
+                ) AS "value?"
+            FROM
+                UNNEST($3::bytea[], $4::bytea[]) AS u (start_key, end_key)
+                LEFT JOIN LATERAL (
+                    SELECT
+                        hashed_key,
+                        INDEX
+                    FROM
+                        initial_writes
+                    WHERE
+                        initial_writes.l1_batch_number <= $1
+                        AND initial_writes.hashed_key >= u.start_key
+                        AND initial_writes.hashed_key <= u.end_key
+                    ORDER BY
+                        initial_writes.hashed_key
+                    LIMIT
+                        1
+                ) iw ON TRUE
+            "#,
+            i64::from(l1_batch_number.0),
+            i64::from(last_miniblock.0),
+            &start_keys as &[&[u8]],
+            &end_keys as &[&[u8]],
+        )
+        .instrument("get_chunk_starts_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("key_ranges.len", &key_ranges.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        let rows = rows.into_iter().map(|row| {
+            Some(StorageRecoveryLogEntry {
+                key: H256::from_slice(row.hashed_key.as_ref()?),
+                value: H256::from_slice(row.value.as_ref()?),
+                leaf_index: row.index? as u64,
+            })
+        });
+        Ok(rows.collect())
+    }
+
+    /// Fetches tree entries (i.e., latest values and enumeration indices) for the specified `key_range` as of
+    /// the specified L1 batch, which must end with `last_miniblock`. This method is used when rebuilding
+    /// the Merkle tree from Postgres.
+    pub async fn get_tree_entries_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        last_miniblock: MiniblockNumber,
+        key_range: ops::RangeInclusive<H256>,
+    ) -> sqlx::Result<Vec<StorageRecoveryLogEntry>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                initial_writes.hashed_key,
+                initial_writes.index,
+                sl.value AS "value!"
+            FROM
+                initial_writes
+                INNER JOIN LATERAL (
+                    SELECT
+                        value
+                    FROM
+                        storage_logs
+                    WHERE
+                        storage_logs.hashed_key = initial_writes.hashed_key
+                        AND storage_logs.miniblock_number <= $2
+                    ORDER BY
+                        storage_logs.miniblock_number DESC,
+                        storage_logs.operation_number DESC
+                    LIMIT
+                        1
+                ) sl ON TRUE
+            WHERE
+                initial_writes.l1_batch_number <= $1
+                AND initial_writes.hashed_key >= $3
+                AND initial_writes.hashed_key <= $4
+            ORDER BY
+                initial_writes.hashed_key
+            "#,
+            i64::from(l1_batch_number.0),
+            i64::from(last_miniblock.0),
+            key_range.start().as_bytes(),
+            key_range.end().as_bytes()
+        )
+        .instrument("get_tree_entries_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("key_range", &key_range)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        let rows = rows.into_iter().map(|row| StorageRecoveryLogEntry {
+            key: H256::from_slice(&row.hashed_key),
+            value: H256::from_slice(&row.value),
+            leaf_index: row.index as u64,
+        });
+        Ok(rows.collect())
+    }
+
     /// Retrieves all initial write entries for testing purposes.
     pub async fn dump_all_initial_writes_for_tests(&mut self) -> Vec<DbInitialWrite> {
         let rows = sqlx::query!(
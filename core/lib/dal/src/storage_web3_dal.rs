@@ -30,6 +30,34 @@ impl StorageWeb3Dal<'_, '_> {
         Ok(decompose_full_nonce(full_nonce).0)
     }
 
+    /// Batched version of [`Self::get_address_historical_nonce()`] using a single DB query. Like it,
+    /// does not check if a block with this number exists in the database.
+    pub async fn get_addresses_historical_nonces(
+        &mut self,
+        addresses: &[Address],
+        block_number: MiniblockNumber,
+    ) -> sqlx::Result<HashMap<Address, U256>> {
+        let nonce_keys: HashMap<_, _> = addresses
+            .iter()
+            .map(|address| (get_nonce_key(address).hashed_key(), *address))
+            .collect();
+        let hashed_keys: Vec<_> = nonce_keys.keys().copied().collect();
+
+        let values = self
+            .storage
+            .storage_logs_dal()
+            .get_storage_values(&hashed_keys, block_number)
+            .await?;
+        Ok(values
+            .into_iter()
+            .filter_map(|(hashed_key, value)| {
+                let address = nonce_keys.get(&hashed_key)?;
+                let full_nonce = h256_to_u256(value.unwrap_or_default());
+                Some((*address, decompose_full_nonce(full_nonce).0))
+            })
+            .collect())
+    }
+
     /// Returns the current *stored* nonces (i.e., w/o accounting for pending transactions) for the specified accounts.
     pub async fn get_nonces_for_addresses(
         &mut self,
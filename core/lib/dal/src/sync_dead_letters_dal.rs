@@ -0,0 +1,324 @@
+use std::{fmt, str::FromStr};
+
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::{MiniblockNumber, H256};
+
+use crate::Core;
+
+/// Resolution of a [`SyncDeadLetter`] by the node operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadLetterResolution {
+    /// The action should be re-applied after the node restart (e.g., after the node was updated).
+    Retry,
+    /// The transaction should be skipped when re-applying the failed miniblock. This makes the node state diverge
+    /// from the main node.
+    Skip,
+}
+
+impl DeadLetterResolution {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Retry => "retry",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+impl fmt::Display for DeadLetterResolution {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeadLetterResolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "retry" => Ok(Self::Retry),
+            "skip" => Ok(Self::Skip),
+            _ => Err(format!("unknown dead letter resolution: `{s}`")),
+        }
+    }
+}
+
+/// Sync action that the external node has failed to apply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncDeadLetter {
+    pub id: u64,
+    /// Miniblock that the action belongs to.
+    pub miniblock_number: MiniblockNumber,
+    /// Hash of the transaction if the action is a transaction.
+    pub tx_hash: Option<H256>,
+    /// Debug representation of the action.
+    pub action: String,
+    pub error: String,
+    pub resolution: Option<DeadLetterResolution>,
+    pub created_at: NaiveDateTime,
+    pub resolved_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug)]
+struct StorageSyncDeadLetter {
+    id: i64,
+    miniblock_number: i64,
+    tx_hash: Option<Vec<u8>>,
+    action: String,
+    error: String,
+    resolution: Option<String>,
+    created_at: NaiveDateTime,
+    resolved_at: Option<NaiveDateTime>,
+}
+
+impl From<StorageSyncDeadLetter> for SyncDeadLetter {
+    fn from(row: StorageSyncDeadLetter) -> Self {
+        Self {
+            id: row.id as u64,
+            miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+            tx_hash: row.tx_hash.as_deref().map(H256::from_slice),
+            action: row.action,
+            error: row.error,
+            resolution: row
+                .resolution
+                .map(|resolution| resolution.parse().expect("invalid resolution in DB")),
+            created_at: row.created_at,
+            resolved_at: row.resolved_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SyncDeadLettersDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl SyncDeadLettersDal<'_, '_> {
+    /// Persists a failed action and returns the ID of the dead letter. If there's an unresolved dead letter
+    /// for the same action already (e.g., if the node has failed applying the action again after a restart),
+    /// its error is updated and its ID is returned instead of creating a new dead letter.
+    pub async fn insert_dead_letter(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        tx_hash: Option<H256>,
+        action: &str,
+        error: &str,
+    ) -> sqlx::Result<u64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                sync_dead_letters (miniblock_number, tx_hash, action, error, created_at)
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            ON CONFLICT (miniblock_number, COALESCE(tx_hash, ''::BYTEA), action)
+            WHERE
+                resolution IS NULL DO
+            UPDATE
+            SET
+                error = $4
+            RETURNING
+                id
+            "#,
+            i64::from(miniblock_number.0),
+            tx_hash.as_ref().map(H256::as_bytes),
+            action,
+            error
+        )
+        .instrument("insert_dead_letter")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id as u64)
+    }
+
+    /// Returns dead letters ordered by their IDs.
+    pub async fn get_dead_letters(
+        &mut self,
+        only_unresolved: bool,
+    ) -> sqlx::Result<Vec<SyncDeadLetter>> {
+        let rows = sqlx::query_as!(
+            StorageSyncDeadLetter,
+            r#"
+            SELECT
+                id,
+                miniblock_number,
+                tx_hash,
+                action,
+                error,
+                resolution,
+                created_at,
+                resolved_at
+            FROM
+                sync_dead_letters
+            WHERE
+                $1 = FALSE
+                OR resolution IS NULL
+            ORDER BY
+                id
+            "#,
+            only_unresolved
+        )
+        .instrument("get_dead_letters")
+        .with_arg("only_unresolved", &only_unresolved)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn get_dead_letter(&mut self, id: u64) -> sqlx::Result<Option<SyncDeadLetter>> {
+        let row = sqlx::query_as!(
+            StorageSyncDeadLetter,
+            r#"
+            SELECT
+                id,
+                miniblock_number,
+                tx_hash,
+                action,
+                error,
+                resolution,
+                created_at,
+                resolved_at
+            FROM
+                sync_dead_letters
+            WHERE
+                id = $1
+            "#,
+            id as i64
+        )
+        .instrument("get_dead_letter")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Resolves a dead letter. Returns `false` if the dead letter doesn't exist or is already resolved.
+    pub async fn resolve_dead_letter(
+        &mut self,
+        id: u64,
+        resolution: DeadLetterResolution,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE sync_dead_letters
+            SET
+                resolution = $2,
+                resolved_at = NOW()
+            WHERE
+                id = $1
+                AND resolution IS NULL
+            "#,
+            id as i64,
+            resolution.as_str()
+        )
+        .instrument("resolve_dead_letter")
+        .with_arg("id", &id)
+        .with_arg("resolution", &resolution)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns hashes of transactions that the operator has decided to skip.
+    pub async fn get_skipped_tx_hashes(&mut self) -> sqlx::Result<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash AS "tx_hash!"
+            FROM
+                sync_dead_letters
+            WHERE
+                resolution = 'skip'
+                AND tx_hash IS NOT NULL
+            "#
+        )
+        .instrument("get_skipped_tx_hashes")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.tx_hash))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn resolving_dead_letters() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.sync_dead_letters_dal();
+
+        let tx_hash = H256::repeat_byte(1);
+        let tx_letter_id = dal
+            .insert_dead_letter(MiniblockNumber(5), Some(tx_hash), "Tx(..)", "rejected")
+            .await
+            .unwrap();
+        let other_letter_id = dal
+            .insert_dead_letter(MiniblockNumber(6), None, "SealBatch", "unexpected action")
+            .await
+            .unwrap();
+
+        let letters = dal.get_dead_letters(true).await.unwrap();
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].id, tx_letter_id);
+        assert_eq!(letters[0].miniblock_number, MiniblockNumber(5));
+        assert_eq!(letters[0].tx_hash, Some(tx_hash));
+        assert_eq!(letters[0].error, "rejected");
+        assert_eq!(letters[0].resolution, None);
+        assert_eq!(letters[1].id, other_letter_id);
+        assert_eq!(letters[1].tx_hash, None);
+        assert!(dal.get_skipped_tx_hashes().await.unwrap().is_empty());
+
+        // Failing the same action again should update the existing dead letter.
+        let repeated_letter_id = dal
+            .insert_dead_letter(
+                MiniblockNumber(5),
+                Some(tx_hash),
+                "Tx(..)",
+                "rejected again",
+            )
+            .await
+            .unwrap();
+        assert_eq!(repeated_letter_id, tx_letter_id);
+        let letters = dal.get_dead_letters(true).await.unwrap();
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].error, "rejected again");
+
+        let resolved = dal
+            .resolve_dead_letter(tx_letter_id, DeadLetterResolution::Skip)
+            .await
+            .unwrap();
+        assert!(resolved);
+        // Resolutions cannot be changed.
+        let resolved = dal
+            .resolve_dead_letter(tx_letter_id, DeadLetterResolution::Retry)
+            .await
+            .unwrap();
+        assert!(!resolved);
+        let resolved = dal
+            .resolve_dead_letter(other_letter_id, DeadLetterResolution::Retry)
+            .await
+            .unwrap();
+        assert!(resolved);
+
+        assert!(dal.get_dead_letters(true).await.unwrap().is_empty());
+        let letter = dal.get_dead_letter(tx_letter_id).await.unwrap().unwrap();
+        assert_eq!(letter.resolution, Some(DeadLetterResolution::Skip));
+        assert!(letter.resolved_at.is_some());
+        assert_eq!(dal.get_skipped_tx_hashes().await.unwrap(), [tx_hash]);
+
+        // Once the dead letter is resolved, a new failure creates a new dead letter.
+        let new_letter_id = dal
+            .insert_dead_letter(MiniblockNumber(6), None, "SealBatch", "unexpected action")
+            .await
+            .unwrap();
+        assert_ne!(new_letter_id, other_letter_id);
+        assert_eq!(dal.get_dead_letters(false).await.unwrap().len(), 3);
+    }
+}
@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 
+use sqlx::migrate::Migrator;
 use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
 
 use crate::Core;
 
+/// Migrations embedded into the binary at compile time. These are the migrations the binary expects
+/// to be applied to the database.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
 #[derive(Debug)]
 pub(crate) struct TableSize {
     pub table_size: u64,
@@ -12,6 +17,28 @@ pub(crate) struct TableSize {
     pub total_size: u64,
 }
 
+/// Information about the Postgres server.
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseServerInfo {
+    /// Server version as an integer, e.g. 140005 for 14.5.
+    pub version_num: u32,
+    /// Maximum number of concurrent connections allowed by the server.
+    pub max_connections: u32,
+    /// Current server time as a Unix timestamp in seconds.
+    pub timestamp: f64,
+}
+
+/// Migration applied to the database, as recorded by `sqlx` in the `_sqlx_migrations` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    /// Whether the migration was applied successfully.
+    pub success: bool,
+    /// SHA-384 checksum of the migration SQL.
+    pub checksum: Vec<u8>,
+}
+
 pub struct SystemDal<'a, 'c> {
     pub storage: &'a mut Connection<'c, Core>,
 }
@@ -42,6 +69,60 @@ impl SystemDal<'_, '_> {
         })
     }
 
+    pub async fn get_server_info(&mut self) -> sqlx::Result<DatabaseServerInfo> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                CURRENT_SETTING('server_version_num')::INT AS "version_num!",
+                CURRENT_SETTING('max_connections')::INT AS "max_connections!",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                        NOW()
+                )::FLOAT8 AS "timestamp!"
+            "#
+        )
+        .instrument("get_server_info")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(DatabaseServerInfo {
+            version_num: row.version_num as u32,
+            max_connections: row.max_connections as u32,
+            timestamp: row.timestamp,
+        })
+    }
+
+    /// Returns migrations applied to the database ordered by version.
+    pub async fn get_applied_migrations(&mut self) -> sqlx::Result<Vec<AppliedMigration>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                version,
+                description,
+                success,
+                checksum
+            FROM
+                _sqlx_migrations
+            ORDER BY
+                version
+            "#
+        )
+        .instrument("get_applied_migrations")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| AppliedMigration {
+                version: row.version,
+                description: row.description,
+                success: row.success,
+                checksum: row.checksum,
+            })
+            .collect())
+    }
+
     pub(crate) async fn get_table_sizes(&mut self) -> sqlx::Result<HashMap<String, TableSize>> {
         let rows = sqlx::query!(
             r#"
@@ -47,6 +47,7 @@ pub(crate) fn create_miniblock_header(number: u32) -> MiniblockHeader {
         protocol_version: Some(protocol_version),
         virtual_blocks: 1,
         gas_limit: 0,
+        logs_bloom: Default::default(),
     }
 }
 
@@ -148,6 +149,7 @@ pub(crate) fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionRe
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        execution_time: Duration::ZERO,
     }
 }
 
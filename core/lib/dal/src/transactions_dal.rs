@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use bigdecimal::BigDecimal;
@@ -8,6 +12,7 @@ use zksync_db_connection::{
     connection::Connection, instrument::InstrumentExt, utils::pg_interval_from_duration,
 };
 use zksync_types::{
+    api,
     block::MiniblockExecutionData,
     fee::TransactionExecutionMetrics,
     l1::L1Tx,
@@ -21,6 +26,7 @@ use zksync_types::{
 use zksync_utils::u256_to_big_decimal;
 
 use crate::{
+    compression::decode_error,
     models::storage_transaction::{CallTrace, StorageTransaction},
     Core,
 };
@@ -159,6 +165,42 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Marks a priority operation as underfunded w.r.t. the current fee model. The operation itself must be
+    /// inserted beforehand using [`Self::insert_transaction_l1()`].
+    pub async fn insert_underfunded_priority_op(
+        &mut self,
+        op: &api::UnderfundedPriorityOp,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                underfunded_priority_ops (
+                    priority_op_id,
+                    tx_hash,
+                    max_fee_per_gas,
+                    required_max_fee_per_gas,
+                    gas_per_pubdata_limit,
+                    required_gas_per_pubdata_limit,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, NOW())
+            ON CONFLICT (priority_op_id) DO NOTHING
+            "#,
+            op.priority_op_id.0 as i64,
+            op.tx_hash.as_bytes(),
+            u256_to_big_decimal(op.max_fee_per_gas),
+            u256_to_big_decimal(op.required_max_fee_per_gas),
+            u256_to_big_decimal(op.gas_per_pubdata_limit),
+            u256_to_big_decimal(op.required_gas_per_pubdata_limit)
+        )
+        .instrument("insert_underfunded_priority_op")
+        .with_arg("priority_op_id", &op.priority_op_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn insert_system_transaction(&mut self, tx: ProtocolUpgradeTx) {
         {
             let contract_address = tx.execute.contract_address.as_bytes().to_vec();
@@ -255,6 +297,21 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Makes the current Postgres transaction commit without waiting for the WAL flush (i.e., sets
+    /// `synchronous_commit = off` for the transaction). Has no effect outside a transaction.
+    pub async fn set_asynchronous_commit(&mut self) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            SELECT
+                SET_CONFIG('synchronous_commit', 'off', TRUE)
+            "#
+        )
+        .instrument("set_asynchronous_commit")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn insert_transaction_l2(
         &mut self,
         tx: L2Tx,
@@ -369,6 +426,7 @@ impl TransactionsDal<'_, '_> {
                 max_priority_fee_per_gas = $7,
                 gas_per_pubdata_limit = $8,
                 input = $9,
+                input_compression = NULL,
                 data = $10,
                 tx_format = $11,
                 contract_address = $12,
@@ -537,6 +595,9 @@ impl TransactionsDal<'_, '_> {
 
             let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
             let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+            let mut call_trace_address_tx_hashes = vec![];
+            let mut call_trace_addresses = vec![];
+            let mut call_trace_address_is_sender = vec![];
             transactions
                 .iter()
                 .enumerate()
@@ -560,6 +621,11 @@ impl TransactionsDal<'_, '_> {
                     };
 
                     if let Some(call_trace) = tx_res.call_trace() {
+                        for (address, is_sender) in addresses_in_call_trace(&call_trace) {
+                            call_trace_address_tx_hashes.push(hash.0.to_vec());
+                            call_trace_addresses.push(address.0.to_vec());
+                            call_trace_address_is_sender.push(is_sender);
+                        }
                         bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
                         call_traces_tx_hashes.push(hash.0.to_vec());
                     }
@@ -640,6 +706,7 @@ impl TransactionsDal<'_, '_> {
                         max_priority_fee_per_gas = data_table.max_priority_fee_per_gas,
                         gas_per_pubdata_limit = data_table.gas_per_pubdata_limit,
                         input = data_table.input,
+                        input_compression = NULL,
                         data = data_table.data,
                         tx_format = data_table.tx_format,
                         miniblock_number = $21,
@@ -817,6 +884,30 @@ impl TransactionsDal<'_, '_> {
                 .await
                 .unwrap();
             }
+            if !call_trace_addresses.is_empty() {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO
+                        call_trace_addresses (tx_hash, address, is_sender, miniblock_number)
+                    SELECT
+                        u.tx_hash,
+                        u.address,
+                        u.is_sender,
+                        $4
+                    FROM
+                        UNNEST($1::bytea[], $2::bytea[], $3::BOOLEAN[]) AS u (tx_hash, address, is_sender)
+                    "#,
+                    &call_trace_address_tx_hashes,
+                    &call_trace_addresses,
+                    &call_trace_address_is_sender,
+                    i64::from(miniblock_number.0)
+                )
+                .instrument("insert_call_trace_addresses")
+                .report_latency()
+                .execute(&mut transaction)
+                .await
+                .unwrap();
+            }
             transaction.commit().await.unwrap();
         }
     }
@@ -903,6 +994,33 @@ impl TransactionsDal<'_, '_> {
         Ok(rows.len())
     }
 
+    /// Removes L2 transactions evicted from the mempool. Transactions that were included into a miniblock
+    /// or replaced after eviction are not removed. Returns the number of removed transactions.
+    pub async fn remove_evicted_txs(&mut self, tx_hashes: &[H256]) -> sqlx::Result<usize> {
+        if tx_hashes.is_empty() {
+            return Ok(0);
+        }
+
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                hash = ANY ($1)
+                AND in_mempool = TRUE
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+            "#,
+            &tx_hashes as &[&[u8]]
+        )
+        .instrument("remove_evicted_txs")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
     /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
     /// the latter are only used to bootstrap mempool for given account.
     pub async fn sync_mempool(
@@ -995,8 +1113,11 @@ impl TransactionsDal<'_, '_> {
         .fetch_all(self.storage.conn())
         .await?;
 
-        let transactions = transactions.into_iter().map(|tx| tx.into()).collect();
-        Ok(transactions)
+        transactions
+            .into_iter()
+            .map(Transaction::try_from)
+            .collect::<anyhow::Result<_>>()
+            .map_err(decode_error)
     }
 
     pub async fn reset_mempool(&mut self) -> sqlx::Result<()> {
@@ -1144,12 +1265,12 @@ impl TransactionsDal<'_, '_> {
             .group_by(|tx| tx.miniblock_number.unwrap())
             .into_iter()
             .map(|(miniblock_number, txs)| {
-                (
-                    MiniblockNumber(miniblock_number as u32),
-                    txs.map(Transaction::from).collect::<Vec<_>>(),
-                )
+                let txs = txs
+                    .map(Transaction::try_from)
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                anyhow::Ok((MiniblockNumber(miniblock_number as u32), txs))
             })
-            .collect();
+            .collect::<anyhow::Result<_>>()?;
         if transactions_by_miniblock.is_empty() {
             return Ok(Vec::new());
         }
@@ -1327,8 +1448,21 @@ impl TransactionsDal<'_, '_> {
         .fetch_optional(self.storage.conn())
         .await
         .unwrap()
-        .map(|tx| tx.into())
+        .map(|tx| Transaction::try_from(tx).expect("failed converting stored transaction"))
+    }
+}
+
+/// Returns unique addresses participating in the calls from the trace, together with a flag whether the address
+/// is the sender of a call.
+fn addresses_in_call_trace(call_trace: &Call) -> HashSet<(Address, bool)> {
+    let mut addresses = HashSet::new();
+    let mut calls = vec![call_trace];
+    while let Some(call) = calls.pop() {
+        addresses.insert((call.from, true));
+        addresses.insert((call.to, false));
+        calls.extend(&call.calls);
     }
+    addresses
 }
 
 #[cfg(test)]
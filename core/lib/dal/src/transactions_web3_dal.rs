@@ -1,17 +1,23 @@
-use sqlx::types::chrono::NaiveDateTime;
+use std::collections::HashMap;
+
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
 use zksync_db_connection::{
     connection::Connection, instrument::InstrumentExt, interpolate_query, match_query_as,
 };
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Transaction,
-    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
+    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Nonce, PriorityOpId,
+    Transaction, ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
+    U256,
 };
+use zksync_utils::bigdecimal_to_u256;
 
 use crate::{
+    compression::decode_error,
     models::storage_transaction::{
         StorageApiTransaction, StorageTransaction, StorageTransactionDetails,
         StorageTransactionReceipt,
     },
+    pagination::{Page, PageCursor},
     Core, CoreDal, SqlxError,
 };
 
@@ -19,6 +25,16 @@ use crate::{
 enum TransactionSelector<'a> {
     Hashes(&'a [H256]),
     Position(MiniblockNumber, u32),
+    /// Transactions with the specified initiator strictly after the cursor position, with the limit.
+    Initiator(Address, Option<PageCursor>, usize),
+}
+
+/// Fee parameters of a pending L2 transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTxFees {
+    pub tx_hash: H256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
 }
 
 #[derive(Debug)]
@@ -188,6 +204,16 @@ impl TransactionsWeb3Dal<'_, '_> {
                     i64::from(block_number.0),
                     idx as i32
                 ),
+                TransactionSelector::Initiator(address, after, limit) => (
+                    "transactions.initiator_address = $1 \
+                     AND (transactions.miniblock_number, transactions.index_in_block) > ($2, $3) \
+                     ORDER BY transactions.miniblock_number, transactions.index_in_block \
+                     LIMIT $4";
+                    address.as_bytes(),
+                    PageCursor::query_args(after).0,
+                    PageCursor::query_args(after).1,
+                    limit as i64
+                ),
             }
         );
 
@@ -223,6 +249,29 @@ impl TransactionsWeb3Dal<'_, '_> {
             .next())
     }
 
+    /// Returns a page of transactions initiated by the specified address, ordered by their position
+    /// in the chain. Transactions not included into a miniblock are not returned.
+    pub async fn get_transactions_by_initiator(
+        &mut self,
+        initiator_address: Address,
+        after: Option<PageCursor>,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Page<api::Transaction>> {
+        let transactions = self
+            .get_transactions_inner(
+                TransactionSelector::Initiator(initiator_address, after, limit),
+                chain_id,
+            )
+            .await?;
+        Ok(Page::new(transactions, limit, |tx| {
+            // Both fields are always set for transactions included into a miniblock.
+            let block_number = tx.block_number.unwrap_or_default().as_u32();
+            let index = tx.transaction_index.unwrap_or_default().as_u32();
+            PageCursor::new(MiniblockNumber(block_number), index)
+        }))
+    }
+
     pub async fn get_transaction_details(
         &mut self,
         hash: H256,
@@ -310,6 +359,51 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(hashes)
     }
 
+    /// Returns brief information about txs which were received after `from_timestamp`, together with
+    /// the time of receiving each tx.
+    pub async fn get_pending_txs_after(
+        &mut self,
+        from_timestamp: NaiveDateTime,
+        limit: Option<usize>,
+    ) -> Result<Vec<(NaiveDateTime, api::PendingTransactionInfo)>, SqlxError> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                initiator_address,
+                contract_address,
+                value,
+                received_at
+            FROM
+                transactions
+            WHERE
+                received_at > $1
+            ORDER BY
+                received_at ASC
+            LIMIT
+                $2
+            "#,
+            from_timestamp,
+            limit.map(|limit| limit as i64)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let transactions = records
+            .into_iter()
+            .map(|record| {
+                let info = api::PendingTransactionInfo {
+                    hash: H256::from_slice(&record.hash),
+                    from: Address::from_slice(&record.initiator_address),
+                    to: record.contract_address.as_deref().map(Address::from_slice),
+                    value: bigdecimal_to_u256(record.value),
+                };
+                (record.received_at, info)
+            })
+            .collect();
+        Ok(transactions)
+    }
+
     /// `committed_next_nonce` should equal the nonce for `initiator_address` in the storage.
     pub async fn next_nonce_by_initiator_account(
         &mut self,
@@ -347,7 +441,134 @@ impl TransactionsWeb3Dal<'_, '_> {
         .map(|row| row.nonce as u64)
         .collect();
 
-        // Find pending nonce as the first "gap" in nonces.
+        Ok(Self::find_pending_nonce(committed_next_nonce, non_rejected_nonces).into())
+    }
+
+    /// Batched version of [`Self::next_nonce_by_initiator_account()`] using a single DB query.
+    /// Accepts committed next nonces for initiator accounts and returns a pending nonce for each of the accounts.
+    pub async fn next_nonces_by_initiator_accounts(
+        &mut self,
+        committed_next_nonces: &HashMap<Address, u64>,
+    ) -> Result<HashMap<Address, U256>, SqlxError> {
+        let (addresses, nonces): (Vec<_>, Vec<_>) = committed_next_nonces
+            .iter()
+            .map(|(address, &nonce)| (address.as_bytes(), nonce as i64))
+            .unzip();
+
+        // The query is equivalent to running the query in `next_nonce_by_initiator_account()` for each account.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.initiator_address AS "initiator_address!",
+                transactions.nonce AS "nonce!"
+            FROM
+                transactions
+                INNER JOIN UNNEST($1::bytea[], $2::BIGINT[]) AS u (initiator_address, committed_nonce) ON transactions.initiator_address = u.initiator_address
+            WHERE
+                transactions.nonce >= u.committed_nonce
+                AND transactions.is_priority = FALSE
+                AND (
+                    transactions.miniblock_number IS NOT NULL
+                    OR transactions.error IS NULL
+                )
+            ORDER BY
+                transactions.initiator_address,
+                transactions.nonce
+            "#,
+            &addresses as &[&[u8]],
+            &nonces
+        )
+        .instrument("next_nonces_by_initiator_accounts")
+        .with_arg("addresses.len", &addresses.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut non_rejected_nonces = HashMap::<_, Vec<_>>::with_capacity(addresses.len());
+        for row in rows {
+            let address = Address::from_slice(&row.initiator_address);
+            non_rejected_nonces
+                .entry(address)
+                .or_default()
+                .push(row.nonce as u64);
+        }
+        Ok(committed_next_nonces
+            .iter()
+            .map(|(&address, &committed_next_nonce)| {
+                let nonces = non_rejected_nonces.remove(&address).unwrap_or_default();
+                let pending_nonce = Self::find_pending_nonce(committed_next_nonce, nonces);
+                (address, pending_nonce.into())
+            })
+            .collect())
+    }
+
+    /// Returns fee parameters of the pending (i.e., not included into a miniblock and not rejected) L2 transaction
+    /// with the specified initiator and nonce, if any.
+    pub async fn get_pending_tx_fees(
+        &mut self,
+        initiator_address: Address,
+        nonce: Nonce,
+    ) -> Result<Option<PendingTxFees>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                max_fee_per_gas AS "max_fee_per_gas!",
+                max_priority_fee_per_gas AS "max_priority_fee_per_gas!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce = $2
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(nonce.0)
+        )
+        .instrument("get_pending_tx_fees")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| PendingTxFees {
+            tx_hash: H256::from_slice(&row.hash),
+            max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+            max_priority_fee_per_gas: bigdecimal_to_u256(row.max_priority_fee_per_gas),
+        }))
+    }
+
+    /// Returns the number of pending (i.e., not included into a miniblock and not rejected) L2 transactions
+    /// initiated by the specified account.
+    pub async fn pending_txs_count_by_initiator_account(
+        &mut self,
+        initiator_address: Address,
+    ) -> Result<usize, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#,
+            initiator_address.as_bytes()
+        )
+        .instrument("pending_txs_count_by_initiator_account")
+        .with_arg("initiator_address", &initiator_address)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.count as usize)
+    }
+
+    /// Finds the pending nonce as the first "gap" in the sorted non-rejected nonces.
+    fn find_pending_nonce(committed_next_nonce: u64, non_rejected_nonces: Vec<u64>) -> u64 {
         let mut pending_nonce = committed_next_nonce;
         for nonce in non_rejected_nonces {
             if pending_nonce == nonce {
@@ -356,8 +577,7 @@ impl TransactionsWeb3Dal<'_, '_> {
                 break;
             }
         }
-
-        Ok(U256::from(pending_nonce))
+        pending_nonce
     }
 
     /// Returns the server transactions (not API ones) from a certain miniblock.
@@ -383,19 +603,162 @@ impl TransactionsWeb3Dal<'_, '_> {
         .fetch_all(self.storage.conn())
         .await?;
 
-        Ok(rows.into_iter().map(Into::into).collect())
+        rows.into_iter()
+            .map(Transaction::try_from)
+            .collect::<anyhow::Result<_>>()
+            .map_err(decode_error)
+    }
+
+    /// Returns priority operations that were marked as underfunded on receipt and are not executed yet,
+    /// ordered by the priority operation ID.
+    pub async fn get_pending_underfunded_priority_ops(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::UnderfundedPriorityOp>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                underfunded_priority_ops.priority_op_id,
+                underfunded_priority_ops.tx_hash,
+                underfunded_priority_ops.max_fee_per_gas,
+                underfunded_priority_ops.required_max_fee_per_gas,
+                underfunded_priority_ops.gas_per_pubdata_limit,
+                underfunded_priority_ops.required_gas_per_pubdata_limit
+            FROM
+                underfunded_priority_ops
+                INNER JOIN transactions ON transactions.hash = underfunded_priority_ops.tx_hash
+            WHERE
+                transactions.miniblock_number IS NULL
+            ORDER BY
+                underfunded_priority_ops.priority_op_id
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_pending_underfunded_priority_ops")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::UnderfundedPriorityOp {
+                priority_op_id: PriorityOpId(row.priority_op_id as u64),
+                tx_hash: H256::from_slice(&row.tx_hash),
+                max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+                required_max_fee_per_gas: bigdecimal_to_u256(row.required_max_fee_per_gas),
+                gas_per_pubdata_limit: bigdecimal_to_u256(row.gas_per_pubdata_limit),
+                required_gas_per_pubdata_limit: bigdecimal_to_u256(
+                    row.required_gas_per_pubdata_limit,
+                ),
+            })
+            .collect())
+    }
+
+    /// Returns the summary of pending (i.e., not included into a miniblock and not rejected) transactions.
+    pub async fn get_mempool_status(&mut self) -> sqlx::Result<api::MempoolStatus> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        is_priority = TRUE
+                ) AS "l1_count!",
+                COUNT(DISTINCT initiator_address) FILTER (
+                    WHERE
+                        is_priority = FALSE
+                ) AS "sender_count!",
+                MIN(received_at) AS oldest_received_at
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND error IS NULL
+            "#
+        )
+        .instrument("get_mempool_status")
+        .fetch_one(self.storage)
+        .await?;
+
+        let oldest_tx_received_at = row
+            .oldest_received_at
+            .map(|received_at| DateTime::<Utc>::from_naive_utc_and_offset(received_at, Utc));
+        let oldest_tx_age_ms = oldest_tx_received_at.map(|received_at| {
+            let age = Utc::now().signed_duration_since(received_at);
+            age.num_milliseconds().max(0) as u64
+        });
+        Ok(api::MempoolStatus {
+            pending_tx_count: row.count as u64,
+            pending_l1_tx_count: row.l1_count as u64,
+            sender_count: row.sender_count as u64,
+            oldest_tx_received_at,
+            oldest_tx_age_ms,
+        })
+    }
+
+    /// Returns initiators of pending (i.e., not included into a miniblock and not rejected) L2 transactions
+    /// together with information about their transactions. Initiators are ordered by the descending number
+    /// of pending transactions.
+    pub async fn get_mempool_senders(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::MempoolSender>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                initiator_address,
+                COUNT(*) AS "count!",
+                MIN(nonce) AS "min_nonce!",
+                MAX(nonce) AS "max_nonce!",
+                MIN(received_at) AS "oldest_received_at!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+            GROUP BY
+                initiator_address
+            ORDER BY
+                COUNT(*) DESC,
+                initiator_address
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_mempool_senders")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::MempoolSender {
+                address: Address::from_slice(&row.initiator_address),
+                pending_tx_count: row.count as u64,
+                min_nonce: Nonce(row.min_nonce as u32),
+                max_nonce: Nonce(row.max_nonce as u32),
+                oldest_tx_received_at: DateTime::from_naive_utc_and_offset(
+                    row.oldest_received_at,
+                    Utc,
+                ),
+            })
+            .collect())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
-    use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, Nonce, ProtocolVersion};
+    use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, ProtocolVersion};
 
     use super::*;
     use crate::{
-        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        tests::{
+            create_miniblock_header, mock_execution_result, mock_l1_execute, mock_l2_transaction,
+        },
         ConnectionPool, Core, CoreDal,
     };
 
@@ -607,6 +970,124 @@ mod tests {
         assert_eq!(next_nonce, 2.into());
     }
 
+    #[tokio::test]
+    async fn getting_pending_transactions_info() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let initiator = Address::repeat_byte(1);
+        let mut tx_by_nonce = HashMap::new();
+        for nonce in [0, 1, 2] {
+            let mut tx = mock_l2_transaction();
+            // Changing transaction fields invalidates its signature, but it's OK for test purposes
+            tx.common_data.nonce = Nonce(nonce);
+            tx.common_data.initiator_address = initiator;
+            tx_by_nonce.insert(nonce, tx.clone());
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+
+        let pending_count = conn
+            .transactions_web3_dal()
+            .pending_txs_count_by_initiator_account(initiator)
+            .await
+            .unwrap();
+        assert_eq!(pending_count, 3);
+        let fees = conn
+            .transactions_web3_dal()
+            .get_pending_tx_fees(initiator, Nonce(1))
+            .await
+            .unwrap();
+        let expected_fee = &tx_by_nonce[&1].common_data.fee;
+        assert_eq!(
+            fees,
+            Some(PendingTxFees {
+                tx_hash: tx_by_nonce[&1].hash(),
+                max_fee_per_gas: expected_fee.max_fee_per_gas,
+                max_priority_fee_per_gas: expected_fee.max_priority_fee_per_gas,
+            })
+        );
+
+        // Rejected transactions must not be considered pending.
+        conn.transactions_dal()
+            .mark_tx_as_rejected(tx_by_nonce[&1].hash(), "oops")
+            .await;
+        let pending_count = conn
+            .transactions_web3_dal()
+            .pending_txs_count_by_initiator_account(initiator)
+            .await
+            .unwrap();
+        assert_eq!(pending_count, 2);
+        let fees = conn
+            .transactions_web3_dal()
+            .get_pending_tx_fees(initiator, Nonce(1))
+            .await
+            .unwrap();
+        assert_eq!(fees, None);
+    }
+
+    #[tokio::test]
+    async fn getting_mempool_status_and_senders() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let status = conn
+            .transactions_web3_dal()
+            .get_mempool_status()
+            .await
+            .unwrap();
+        assert_eq!(status.pending_tx_count, 0);
+        assert_eq!(status.oldest_tx_received_at, None);
+        assert_eq!(status.oldest_tx_age_ms, None);
+
+        let initiators = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        for (initiator, nonces) in initiators.into_iter().zip([&[0, 1, 3][..], &[5]]) {
+            for &nonce in nonces {
+                let mut tx = mock_l2_transaction();
+                // Changing transaction fields invalidates its signature, but it's OK for test purposes
+                tx.common_data.nonce = Nonce(nonce);
+                tx.common_data.initiator_address = initiator;
+                conn.transactions_dal()
+                    .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let status = conn
+            .transactions_web3_dal()
+            .get_mempool_status()
+            .await
+            .unwrap();
+        assert_eq!(status.pending_tx_count, 4);
+        assert_eq!(status.pending_l1_tx_count, 0);
+        assert_eq!(status.sender_count, 2);
+        assert!(status.oldest_tx_received_at.is_some());
+        assert!(status.oldest_tx_age_ms.is_some());
+
+        let senders = conn
+            .transactions_web3_dal()
+            .get_mempool_senders(10)
+            .await
+            .unwrap();
+        assert_eq!(senders.len(), 2);
+        assert_eq!(senders[0].address, initiators[0]);
+        assert_eq!(senders[0].pending_tx_count, 3);
+        assert_eq!(senders[0].min_nonce, Nonce(0));
+        assert_eq!(senders[0].max_nonce, Nonce(3));
+        assert_eq!(senders[1].address, initiators[1]);
+        assert_eq!(senders[1].pending_tx_count, 1);
+        assert_eq!(senders[1].min_nonce, Nonce(5));
+
+        let senders = conn
+            .transactions_web3_dal()
+            .get_mempool_senders(1)
+            .await
+            .unwrap();
+        assert_eq!(senders.len(), 1);
+        assert_eq!(senders[0].address, initiators[0]);
+    }
+
     #[tokio::test]
     async fn getting_next_nonce_by_initiator_account_after_snapshot_recovery() {
         // Emulate snapshot recovery: no transactions with past nonces are present in the storage
@@ -636,4 +1117,89 @@ mod tests {
             .unwrap();
         assert_eq!(next_nonce, 2.into());
     }
+
+    #[tokio::test]
+    async fn getting_next_nonces_by_initiator_accounts() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let initiators = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        for (initiator, nonces) in initiators.iter().zip([&[0, 1, 4][..], &[3, 4]]) {
+            for &nonce in nonces {
+                let mut tx = mock_l2_transaction();
+                // Changing transaction fields invalidates its signature, but it's OK for test purposes
+                tx.common_data.nonce = Nonce(nonce);
+                tx.common_data.initiator_address = *initiator;
+                conn.transactions_dal()
+                    .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let unknown_initiator = Address::repeat_byte(3);
+        let committed_next_nonces = HashMap::from([
+            (initiators[0], 0),
+            (initiators[1], 3),
+            (unknown_initiator, 5),
+        ]);
+        let next_nonces = conn
+            .transactions_web3_dal()
+            .next_nonces_by_initiator_accounts(&committed_next_nonces)
+            .await
+            .unwrap();
+
+        let expected_nonces = HashMap::from([
+            (initiators[0], 2.into()),
+            (initiators[1], 5.into()),
+            (unknown_initiator, 5.into()),
+        ]);
+        assert_eq!(next_nonces, expected_nonces);
+        for (&initiator, &committed_next_nonce) in &committed_next_nonces {
+            let next_nonce = conn
+                .transactions_web3_dal()
+                .next_nonce_by_initiator_account(initiator, committed_next_nonce)
+                .await
+                .unwrap();
+            assert_eq!(next_nonces[&initiator], next_nonce);
+        }
+    }
+
+    #[tokio::test]
+    async fn getting_underfunded_priority_ops() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let tx = mock_l1_execute();
+        let op = api::UnderfundedPriorityOp {
+            priority_op_id: tx.serial_id(),
+            tx_hash: tx.hash(),
+            max_fee_per_gas: tx.common_data.max_fee_per_gas,
+            required_max_fee_per_gas: 250_000_000.into(),
+            gas_per_pubdata_limit: tx.common_data.gas_per_pubdata_limit,
+            required_gas_per_pubdata_limit: 100.into(),
+        };
+        conn.transactions_dal()
+            .insert_transaction_l1(tx, 1.into())
+            .await;
+        conn.transactions_dal()
+            .insert_underfunded_priority_op(&op)
+            .await
+            .unwrap();
+
+        let ops = conn
+            .transactions_web3_dal()
+            .get_pending_underfunded_priority_ops(10)
+            .await
+            .unwrap();
+        assert_eq!(ops, [op]);
+        let ops = conn
+            .transactions_web3_dal()
+            .get_pending_underfunded_priority_ops(0)
+            .await
+            .unwrap();
+        assert_eq!(ops, []);
+    }
 }
@@ -0,0 +1,306 @@
+use std::{ops, time::Duration};
+
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::{api, Address, MiniblockNumber, H256};
+
+use crate::Core;
+
+/// Execution metrics of a single transaction recorded by the state keeper.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxExecutionMetricsEntry {
+    pub tx_hash: H256,
+    pub miniblock_number: MiniblockNumber,
+    /// Address of the contract called by the transaction.
+    pub contract_address: Address,
+    pub execution_time: Duration,
+    pub gas_used: u64,
+    pub pubdata_bytes: u64,
+    pub storage_writes: u64,
+    pub computational_gas_used: u64,
+    /// Estimated number of circuits of all types used by the transaction.
+    pub circuits_used: f64,
+}
+
+#[derive(Debug)]
+pub struct TxExecutionMetricsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl TxExecutionMetricsDal<'_, '_> {
+    /// Inserts metrics for the provided transactions. Metrics for transactions that already have
+    /// recorded metrics (e.g., if a transaction was re-executed after a node restart) are overwritten.
+    pub async fn insert_metrics(
+        &mut self,
+        entries: &[TxExecutionMetricsEntry],
+    ) -> sqlx::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx_hashes = Vec::with_capacity(entries.len());
+        let mut miniblock_numbers = Vec::with_capacity(entries.len());
+        let mut contract_addresses = Vec::with_capacity(entries.len());
+        let mut execution_times = Vec::with_capacity(entries.len());
+        let mut gas_used = Vec::with_capacity(entries.len());
+        let mut pubdata_bytes = Vec::with_capacity(entries.len());
+        let mut storage_writes = Vec::with_capacity(entries.len());
+        let mut computational_gas_used = Vec::with_capacity(entries.len());
+        let mut circuits_used = Vec::with_capacity(entries.len());
+        for entry in entries {
+            tx_hashes.push(entry.tx_hash.as_bytes());
+            miniblock_numbers.push(i64::from(entry.miniblock_number.0));
+            contract_addresses.push(entry.contract_address.as_bytes());
+            execution_times.push(entry.execution_time.as_micros() as i64);
+            gas_used.push(entry.gas_used as i64);
+            pubdata_bytes.push(entry.pubdata_bytes as i64);
+            storage_writes.push(entry.storage_writes as i64);
+            computational_gas_used.push(entry.computational_gas_used as i64);
+            circuits_used.push(entry.circuits_used);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                tx_execution_metrics (
+                    tx_hash,
+                    miniblock_number,
+                    contract_address,
+                    execution_time_us,
+                    gas_used,
+                    pubdata_bytes,
+                    storage_writes,
+                    computational_gas_used,
+                    circuits_used,
+                    created_at
+                )
+            SELECT
+                u.tx_hash,
+                u.miniblock_number,
+                u.contract_address,
+                u.execution_time_us,
+                u.gas_used,
+                u.pubdata_bytes,
+                u.storage_writes,
+                u.computational_gas_used,
+                u.circuits_used,
+                NOW()
+            FROM
+                UNNEST(
+                    $1::bytea[],
+                    $2::BIGINT[],
+                    $3::bytea[],
+                    $4::BIGINT[],
+                    $5::BIGINT[],
+                    $6::BIGINT[],
+                    $7::BIGINT[],
+                    $8::BIGINT[],
+                    $9::DOUBLE PRECISION[]
+                ) AS u (
+                    tx_hash,
+                    miniblock_number,
+                    contract_address,
+                    execution_time_us,
+                    gas_used,
+                    pubdata_bytes,
+                    storage_writes,
+                    computational_gas_used,
+                    circuits_used
+                )
+            ON CONFLICT (tx_hash) DO
+            UPDATE
+            SET
+                miniblock_number = excluded.miniblock_number,
+                execution_time_us = excluded.execution_time_us,
+                gas_used = excluded.gas_used,
+                pubdata_bytes = excluded.pubdata_bytes,
+                storage_writes = excluded.storage_writes,
+                computational_gas_used = excluded.computational_gas_used,
+                circuits_used = excluded.circuits_used,
+                created_at = excluded.created_at
+            "#,
+            &tx_hashes as &[&[u8]],
+            &miniblock_numbers,
+            &contract_addresses as &[&[u8]],
+            &execution_times,
+            &gas_used,
+            &pubdata_bytes,
+            &storage_writes,
+            &computational_gas_used,
+            &circuits_used
+        )
+        .instrument("insert_tx_execution_metrics")
+        .with_arg("entries.len", &entries.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns metrics aggregated by the called contract for transactions in the specified miniblocks.
+    /// Contracts are ordered by the total execution time descending, so that the most expensive contracts
+    /// are returned first. Metrics are sensitive (they reveal the load profile of the sequencer), so they should only
+    /// be exposed via APIs explicitly enabled by the operator.
+    pub async fn get_contract_execution_stats(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::ContractExecutionStats>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                contract_address,
+                COUNT(*) AS "tx_count!",
+                SUM(execution_time_us)::BIGINT AS "total_execution_time_us!",
+                MAX(execution_time_us) AS "max_execution_time_us!",
+                SUM(gas_used)::BIGINT AS "total_gas_used!",
+                SUM(pubdata_bytes)::BIGINT AS "total_pubdata_bytes!",
+                SUM(storage_writes)::BIGINT AS "total_storage_writes!",
+                SUM(computational_gas_used)::BIGINT AS "total_computational_gas_used!",
+                SUM(circuits_used) AS "total_circuits_used!"
+            FROM
+                tx_execution_metrics
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            GROUP BY
+                contract_address
+            ORDER BY
+                SUM(execution_time_us) DESC,
+                contract_address
+            LIMIT
+                $3
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0),
+            limit as i64
+        )
+        .instrument("get_contract_execution_stats")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::ContractExecutionStats {
+                contract_address: Address::from_slice(&row.contract_address),
+                tx_count: row.tx_count as u64,
+                total_execution_time_us: row.total_execution_time_us as u64,
+                max_execution_time_us: row.max_execution_time_us as u64,
+                total_gas_used: row.total_gas_used as u64,
+                total_pubdata_bytes: row.total_pubdata_bytes as u64,
+                total_storage_writes: row.total_storage_writes as u64,
+                total_computational_gas_used: row.total_computational_gas_used as u64,
+                total_circuits_used: row.total_circuits_used,
+            })
+            .collect())
+    }
+
+    /// Removes metrics for transactions in miniblocks before `miniblock_number`. Returns the number of removed entries.
+    pub async fn prune_metrics(&mut self, miniblock_number: MiniblockNumber) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM tx_execution_metrics
+            WHERE
+                miniblock_number < $1
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .instrument("prune_tx_execution_metrics")
+        .with_arg("miniblock_number", &miniblock_number)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    fn mock_entry(
+        tx_hash: u8,
+        miniblock_number: u32,
+        contract_address: Address,
+        execution_time_us: u64,
+    ) -> TxExecutionMetricsEntry {
+        TxExecutionMetricsEntry {
+            tx_hash: H256::repeat_byte(tx_hash),
+            miniblock_number: MiniblockNumber(miniblock_number),
+            contract_address,
+            execution_time: Duration::from_micros(execution_time_us),
+            gas_used: 100_000,
+            pubdata_bytes: 64,
+            storage_writes: 2,
+            computational_gas_used: 50_000,
+            circuits_used: 0.25,
+        }
+    }
+
+    #[tokio::test]
+    async fn aggregating_tx_execution_metrics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.tx_execution_metrics_dal();
+
+        let fast_contract = Address::repeat_byte(1);
+        let slow_contract = Address::repeat_byte(2);
+        let entries = [
+            mock_entry(1, 1, fast_contract, 100),
+            mock_entry(2, 1, slow_contract, 5_000),
+            mock_entry(3, 2, fast_contract, 300),
+            mock_entry(4, 3, slow_contract, 1_000),
+        ];
+        dal.insert_metrics(&entries).await.unwrap();
+        // Re-inserting metrics should be idempotent.
+        dal.insert_metrics(&entries[..1]).await.unwrap();
+
+        let stats = dal
+            .get_contract_execution_stats(MiniblockNumber(1)..=MiniblockNumber(2), 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            [
+                api::ContractExecutionStats {
+                    contract_address: slow_contract,
+                    tx_count: 1,
+                    total_execution_time_us: 5_000,
+                    max_execution_time_us: 5_000,
+                    total_gas_used: 100_000,
+                    total_pubdata_bytes: 64,
+                    total_storage_writes: 2,
+                    total_computational_gas_used: 50_000,
+                    total_circuits_used: 0.25,
+                },
+                api::ContractExecutionStats {
+                    contract_address: fast_contract,
+                    tx_count: 2,
+                    total_execution_time_us: 400,
+                    max_execution_time_us: 300,
+                    total_gas_used: 200_000,
+                    total_pubdata_bytes: 128,
+                    total_storage_writes: 4,
+                    total_computational_gas_used: 100_000,
+                    total_circuits_used: 0.5,
+                },
+            ]
+        );
+
+        let stats = dal
+            .get_contract_execution_stats(MiniblockNumber(0)..=MiniblockNumber(10), 1)
+            .await
+            .unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].contract_address, slow_contract);
+        assert_eq!(stats[0].tx_count, 2);
+
+        let pruned = dal.prune_metrics(MiniblockNumber(2)).await.unwrap();
+        assert_eq!(pruned, 2);
+        let stats = dal
+            .get_contract_execution_stats(MiniblockNumber(0)..=MiniblockNumber(10), 10)
+            .await
+            .unwrap();
+        let tx_counts: Vec<_> = stats.iter().map(|stats| stats.tx_count).collect();
+        assert_eq!(tx_counts, [1, 1]);
+    }
+}
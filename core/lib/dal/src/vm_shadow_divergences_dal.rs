@@ -0,0 +1,156 @@
+use zksync_db_connection::{connection::Connection, instrument::InstrumentExt};
+use zksync_types::{L1BatchNumber, H256};
+
+use crate::Core;
+
+/// Divergence between the main VM and the shadow VM recorded by the state keeper.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VmShadowDivergence {
+    pub l1_batch_number: L1BatchNumber,
+    /// Hash of the diverged transaction; `None` if results diverge when finishing the batch.
+    pub tx_hash: Option<H256>,
+    /// Version of the shadow VM in a human-readable form.
+    pub shadow_vm_version: String,
+    /// Human-readable description of the divergence.
+    pub details: String,
+}
+
+#[derive(Debug)]
+pub struct VmShadowDivergencesDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl VmShadowDivergencesDal<'_, '_> {
+    pub async fn insert_divergences(
+        &mut self,
+        divergences: &[VmShadowDivergence],
+    ) -> sqlx::Result<()> {
+        if divergences.is_empty() {
+            return Ok(());
+        }
+
+        let mut l1_batch_numbers = Vec::with_capacity(divergences.len());
+        let mut tx_hashes = Vec::with_capacity(divergences.len());
+        let mut shadow_vm_versions = Vec::with_capacity(divergences.len());
+        let mut details = Vec::with_capacity(divergences.len());
+        for divergence in divergences {
+            l1_batch_numbers.push(i64::from(divergence.l1_batch_number.0));
+            tx_hashes.push(divergence.tx_hash.map(|hash| hash.as_bytes().to_vec()));
+            shadow_vm_versions.push(divergence.shadow_vm_version.clone());
+            details.push(divergence.details.clone());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_shadow_divergences (
+                    l1_batch_number,
+                    tx_hash,
+                    shadow_vm_version,
+                    details,
+                    created_at
+                )
+            SELECT
+                u.l1_batch_number,
+                u.tx_hash,
+                u.shadow_vm_version,
+                u.details,
+                NOW()
+            FROM
+                UNNEST($1::BIGINT[], $2::bytea[], $3::TEXT[], $4::TEXT[]) AS u (
+                    l1_batch_number,
+                    tx_hash,
+                    shadow_vm_version,
+                    details
+                )
+            "#,
+            &l1_batch_numbers,
+            &tx_hashes as &[Option<Vec<u8>>],
+            &shadow_vm_versions,
+            &details
+        )
+        .instrument("insert_vm_shadow_divergences")
+        .with_arg("divergences.len", &divergences.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns divergences recorded for the specified L1 batch in the order they were recorded.
+    pub async fn get_divergences(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<VmShadowDivergence>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                shadow_vm_version,
+                details
+            FROM
+                vm_shadow_divergences
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                id
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_vm_shadow_divergences")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VmShadowDivergence {
+                l1_batch_number,
+                tx_hash: row.tx_hash.as_deref().map(H256::from_slice),
+                shadow_vm_version: row.shadow_vm_version,
+                details: row.details,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn inserting_and_getting_divergences() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_shadow_divergences_dal();
+
+        let divergences = [
+            VmShadowDivergence {
+                l1_batch_number: L1BatchNumber(1),
+                tx_hash: Some(H256::repeat_byte(1)),
+                shadow_vm_version: "Vm1_4_2".to_owned(),
+                details: "gas used: 100 vs 200".to_owned(),
+            },
+            VmShadowDivergence {
+                l1_batch_number: L1BatchNumber(1),
+                tx_hash: None,
+                shadow_vm_version: "Vm1_4_2".to_owned(),
+                details: "block tip events differ".to_owned(),
+            },
+            VmShadowDivergence {
+                l1_batch_number: L1BatchNumber(2),
+                tx_hash: Some(H256::repeat_byte(2)),
+                shadow_vm_version: "Vm1_4_2".to_owned(),
+                details: "refunds differ".to_owned(),
+            },
+        ];
+        dal.insert_divergences(&divergences).await.unwrap();
+
+        let batch_divergences = dal.get_divergences(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(batch_divergences, divergences[..2]);
+        let batch_divergences = dal.get_divergences(L1BatchNumber(2)).await.unwrap();
+        assert_eq!(batch_divergences, divergences[2..]);
+        let batch_divergences = dal.get_divergences(L1BatchNumber(3)).await.unwrap();
+        assert!(batch_divergences.is_empty());
+    }
+}
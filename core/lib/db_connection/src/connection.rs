@@ -1,6 +1,6 @@
 use std::{
     collections::HashMap,
-    fmt,
+    fmt, io,
     panic::Location,
     sync::{
         atomic::{AtomicUsize, Ordering},
@@ -13,7 +13,11 @@ use sqlx::{
     pool::PoolConnection, types::chrono, Connection as _, PgConnection, Postgres, Transaction,
 };
 
-use crate::{connection_pool::ConnectionPool, metrics::CONNECTION_METRICS, utils::InternalMarker};
+use crate::{
+    connection_pool::ConnectionPool,
+    metrics::CONNECTION_METRICS,
+    utils::{deadline_exceeded_error, InternalMarker},
+};
 
 /// Tags that can be associated with a connection.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -142,7 +146,7 @@ enum ConnectionInner<'a> {
 }
 
 /// Marker trait for restricting using all possible types as a storage marker.
-pub trait DbMarker {}
+pub trait DbMarker: 'static {}
 
 /// Storage processor is the main storage interaction point.
 /// It holds down the connection (either direct or pooled) to the database
@@ -150,6 +154,7 @@ pub trait DbMarker {}
 #[derive(Debug)]
 pub struct Connection<'a, DB: DbMarker> {
     inner: ConnectionInner<'a>,
+    deadline: Option<Instant>,
     _marker: std::marker::PhantomData<DB>,
 }
 
@@ -174,6 +179,7 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         });
         Self {
             inner,
+            deadline: None,
             _marker: Default::default(),
         }
     }
@@ -186,10 +192,46 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         };
         Ok(Connection {
             inner,
+            deadline: self.deadline,
             _marker: Default::default(),
         })
     }
 
+    /// Sets the deadline for all subsequent queries on this connection. Queries issued after the deadline
+    /// fail without being executed, and queries in progress are aborted by Postgres via `statement_timeout`.
+    /// Errors caused by the deadline can be recognized using [`is_deadline_exceeded()`](crate::utils::is_deadline_exceeded()).
+    ///
+    /// Since `statement_timeout` is set for the entire Postgres session, a pooled connection with a deadline
+    /// is closed once dropped instead of being returned to the pool. (For transactions, the timeout is scoped
+    /// to the transaction.) Thus, deadlines should only be used for potentially long-running operations.
+    pub async fn set_deadline(&mut self, deadline: Instant) -> sqlx::Result<()> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if timeout.is_zero() {
+            return Err(deadline_exceeded_error());
+        }
+        // Zero `statement_timeout` disables the timeout, so we round the timeout up.
+        let timeout_ms = timeout.as_millis().max(1);
+        let scope = if self.in_transaction() {
+            "LOCAL"
+        } else {
+            "SESSION"
+        };
+        sqlx::query(&format!("SET {scope} statement_timeout = {timeout_ms}"))
+            .execute(self.conn())
+            .await?;
+
+        if let ConnectionInner::Pooled(pooled) = &mut self.inner {
+            pooled.connection.close_on_drop();
+        }
+        self.deadline = Some(deadline);
+        Ok(())
+    }
+
+    /// Returns the query deadline set for this connection, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
     /// Checks if the `Connection` is currently within database transaction.
     pub fn in_transaction(&self) -> bool {
         matches!(self.inner, ConnectionInner::Transaction { .. })
@@ -207,6 +249,16 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         }
     }
 
+    /// Ensures that the pooled connection is closed once dropped if the query output signals a timeout.
+    /// In this case, the connection may still be busy executing the query, so it shouldn't be returned to the pool.
+    pub(crate) fn close_on_timeout<T>(&mut self, output: &sqlx::Result<T>) {
+        let is_timeout =
+            matches!(output, Err(sqlx::Error::Io(err)) if err.kind() == io::ErrorKind::TimedOut);
+        if let (true, ConnectionInner::Pooled(pooled)) = (is_timeout, &mut self.inner) {
+            pooled.connection.close_on_drop();
+        }
+    }
+
     pub fn conn(&mut self) -> &mut PgConnection {
         self.conn_and_tags().0
     }
@@ -221,7 +273,10 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{connection_pool::ConnectionPool, utils::InternalMarker};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{instrument::InstrumentExt, utils::is_deadline_exceeded};
 
     #[tokio::test]
     async fn processor_tags_propagate_to_transactions() {
@@ -255,4 +310,41 @@ mod tests {
             assert!(traced.is_empty());
         }
     }
+
+    #[tokio::test]
+    async fn setting_query_deadline() {
+        let pool = ConnectionPool::<InternalMarker>::constrained_test_pool(1).await;
+        let mut connection = pool.connection().await.unwrap();
+        let deadline = Instant::now() + Duration::from_millis(500);
+        connection.set_deadline(deadline).await.unwrap();
+        assert_eq!(connection.deadline(), Some(deadline));
+
+        let err = sqlx::query("SELECT pg_sleep(2)")
+            .map(drop)
+            .instrument("slow")
+            .fetch_optional(&mut connection)
+            .await
+            .unwrap_err();
+        assert!(is_deadline_exceeded(&err), "{err}");
+
+        // Queries issued after the deadline should fail immediately.
+        tokio::time::sleep_until(deadline.into()).await;
+        let err = sqlx::query("SELECT 1")
+            .map(drop)
+            .instrument("fast")
+            .fetch_optional(&mut connection)
+            .await
+            .unwrap_err();
+        assert!(is_deadline_exceeded(&err), "{err}");
+        drop(connection);
+
+        // The statement timeout must not leak to other connections.
+        let mut connection = pool.connection().await.unwrap();
+        assert_eq!(connection.deadline(), None);
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(connection.conn())
+            .await
+            .unwrap();
+        assert_eq!(timeout, "0");
+    }
 }
@@ -1,13 +1,14 @@
 use std::{
+    any::TypeId,
     env, fmt,
     future::Future,
     marker::PhantomData,
     panic::Location,
     sync::{
         atomic::{AtomicU64, Ordering},
-        Arc,
+        Arc, Mutex,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
@@ -29,6 +30,9 @@ pub struct ConnectionPoolBuilder<DB: DbMarker> {
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    schema: Option<String>,
+    replica_url: Option<String>,
+    max_replica_lag: Option<Duration>,
     _marker: PhantomData<DB>,
 }
 
@@ -40,6 +44,9 @@ impl<DB: DbMarker> fmt::Debug for ConnectionPoolBuilder<DB> {
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("schema", &self.schema)
+            .field("has_replica", &self.replica_url.is_some())
+            .field("max_replica_lag", &self.max_replica_lag)
             .finish()
     }
 }
@@ -69,6 +76,34 @@ impl<DB: DbMarker> ConnectionPoolBuilder<DB> {
         self
     }
 
+    /// Sets the Postgres schema used by all connections in the pool (by setting `search_path` for the connections).
+    /// This allows multiple chains to share a single Postgres database, with each chain storing its tables
+    /// in a separate schema. If not specified, the [global schema](ConnectionPool::set_global_schema()) for the DB type
+    /// will be used; if it's not set either, `search_path` will not be changed.
+    pub fn set_schema(&mut self, schema: Option<String>) -> &mut Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Sets the URL of a read replica for the pool. If set, connections acquired via
+    /// [`ConnectionPool::read_connection()`] and [`ConnectionPool::read_connection_tagged()`] are served
+    /// by the replica, unless it lags behind the main database by more than the [max replica lag](Self::set_max_replica_lag()).
+    /// Other connections are always served by the main database.
+    ///
+    /// The replica pool uses the same parameters (max size, timeouts etc.) as the main pool.
+    pub fn set_replica_url(&mut self, replica_url: Option<String>) -> &mut Self {
+        self.replica_url = replica_url;
+        self
+    }
+
+    /// Sets the maximum replication lag of the replica set via [`Self::set_replica_url()`]. If the replica lags
+    /// behind by more than this value, read connections fall back to the main database until the replica catches up.
+    /// If not specified, the replication lag is not checked.
+    pub fn set_max_replica_lag(&mut self, max_lag: Option<Duration>) -> &mut Self {
+        self.max_replica_lag = max_lag;
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
     pub fn max_size(&self) -> u32 {
         self.max_size
@@ -76,31 +111,52 @@ impl<DB: DbMarker> ConnectionPoolBuilder<DB> {
 
     /// Builds a connection pool from this builder.
     pub async fn build(&self) -> anyhow::Result<ConnectionPool<DB>> {
-        let options = PgPoolOptions::new()
-            .max_connections(self.max_size)
-            .acquire_timeout(self.acquire_timeout);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
-            .parse()
-            .context("Failed parsing database URL")?;
-        if let Some(timeout) = self.statement_timeout {
-            let timeout_string = format!("{}s", timeout.as_secs());
-            connect_options = connect_options.options([("statement_timeout", timeout_string)]);
-        }
-        let pool = options
-            .connect_with(connect_options)
+        let pool = self
+            .build_pg_pool(&self.database_url)
             .await
             .context("Failed connecting to database")?;
+        let replica = if let Some(replica_url) = &self.replica_url {
+            let pool = self
+                .build_pg_pool(replica_url)
+                .await
+                .context("Failed connecting to replica database")?;
+            Some(Arc::new(ReplicaPool::new(pool, self.max_replica_lag)))
+        } else {
+            None
+        };
         tracing::info!("Created DB pool with parameters {self:?}");
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
             inner: pool,
+            replica,
             max_size: self.max_size,
             traced_connections: None,
             _marker: Default::default(),
         })
     }
 
+    async fn build_pg_pool(&self, database_url: &str) -> anyhow::Result<PgPool> {
+        let options = PgPoolOptions::new()
+            .max_connections(self.max_size)
+            .acquire_timeout(self.acquire_timeout);
+        let mut connect_options: PgConnectOptions = database_url
+            .parse()
+            .context("Failed parsing database URL")?;
+        if let Some(timeout) = self.statement_timeout {
+            let timeout_string = format!("{}s", timeout.as_secs());
+            connect_options = connect_options.options([("statement_timeout", timeout_string)]);
+        }
+        let schema = self
+            .schema
+            .clone()
+            .or_else(|| ConnectionPool::<DB>::global_config().schema::<DB>());
+        if let Some(schema) = schema {
+            validate_schema_name(&schema)?;
+            connect_options = connect_options.options([("search_path", schema)]);
+        }
+        Ok(options.connect_with(connect_options).await?)
+    }
+
     /// Builds a connection pool that has a single connection.
     pub async fn build_singleton(&self) -> anyhow::Result<ConnectionPool<DB>> {
         let singleton_builder = Self {
@@ -108,6 +164,9 @@ impl<DB: DbMarker> ConnectionPoolBuilder<DB> {
             max_size: 1,
             acquire_timeout: self.acquire_timeout,
             statement_timeout: self.statement_timeout,
+            schema: self.schema.clone(),
+            replica_url: self.replica_url.clone(),
+            max_replica_lag: self.max_replica_lag,
             _marker: self._marker,
         };
         singleton_builder.build().await
@@ -199,12 +258,36 @@ impl TestTemplate {
     }
 }
 
+/// Checks that the provided Postgres schema name is a valid unquoted identifier, so that it can be safely
+/// used in `search_path` and SQL statements.
+fn validate_schema_name(schema: &str) -> anyhow::Result<()> {
+    const MAX_IDENTIFIER_LEN: usize = 63;
+
+    let mut chars = schema.chars();
+    let first_char = chars.next().context("Postgres schema name is empty")?;
+    anyhow::ensure!(
+        schema.len() <= MAX_IDENTIFIER_LEN,
+        "Postgres schema name `{schema}` exceeds {MAX_IDENTIFIER_LEN} bytes"
+    );
+    anyhow::ensure!(
+        (first_char.is_ascii_lowercase() || first_char == '_')
+            && chars.all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_'),
+        "Postgres schema name `{schema}` is invalid; only lowercase ASCII letters, digits and underscores are allowed, \
+         and the name must not start with a digit"
+    );
+    Ok(())
+}
+
 /// Global DB connection parameters applied to all [`ConnectionPool`] instances.
 #[derive(Debug)]
 pub struct GlobalConnectionPoolConfig {
     // We consider millisecond precision to be enough for config purposes.
     long_connection_threshold_ms: AtomicU64,
     slow_query_threshold_ms: AtomicU64,
+    /// Zero means that the query timeout is not set.
+    query_timeout_ms: AtomicU64,
+    /// Postgres schemas keyed by the DB type, so that e.g. the core and prover DBs can use different schemas.
+    schemas: Mutex<Vec<(TypeId, String)>>,
 }
 
 impl GlobalConnectionPoolConfig {
@@ -212,9 +295,19 @@ impl GlobalConnectionPoolConfig {
         Self {
             long_connection_threshold_ms: AtomicU64::new(5_000), // 5 seconds
             slow_query_threshold_ms: AtomicU64::new(100),        // 0.1 seconds
+            query_timeout_ms: AtomicU64::new(0),
+            schemas: Mutex::new(Vec::new()),
         }
     }
 
+    pub(crate) fn schema<DB: DbMarker>(&self) -> Option<String> {
+        let schemas = self.schemas.lock().expect("schemas are poisoned");
+        schemas
+            .iter()
+            .find(|(db_type, _)| *db_type == TypeId::of::<DB>())
+            .map(|(_, schema)| schema.clone())
+    }
+
     pub(crate) fn long_connection_threshold(&self) -> Duration {
         Duration::from_millis(self.long_connection_threshold_ms.load(Ordering::Relaxed))
     }
@@ -223,6 +316,11 @@ impl GlobalConnectionPoolConfig {
         Duration::from_millis(self.slow_query_threshold_ms.load(Ordering::Relaxed))
     }
 
+    pub(crate) fn query_timeout(&self) -> Option<Duration> {
+        let millis = self.query_timeout_ms.load(Ordering::Relaxed);
+        (millis > 0).then(|| Duration::from_millis(millis))
+    }
+
     /// Sets the threshold for the DB connection lifetime to denote a connection as long-living and log its details.
     pub fn set_long_connection_threshold(&self, threshold: Duration) -> anyhow::Result<&Self> {
         let millis = u64::try_from(threshold.as_millis())
@@ -242,11 +340,123 @@ impl GlobalConnectionPoolConfig {
         tracing::info!("Set slow query threshold to {threshold:?}");
         Ok(self)
     }
+
+    /// Sets the default timeout for instrumented DB queries (see [`Instrumented`](crate::instrument::Instrumented)).
+    /// A query exceeding the timeout fails with an error recognized by [`is_deadline_exceeded()`](crate::utils::is_deadline_exceeded()),
+    /// and the connection it was executed on is closed once dropped. The timeout can be overridden for specific queries.
+    pub fn set_query_timeout(&self, timeout: Duration) -> anyhow::Result<&Self> {
+        let millis =
+            u64::try_from(timeout.as_millis()).context("query_timeout is unreasonably large")?;
+        anyhow::ensure!(millis > 0, "query_timeout must be positive");
+        self.query_timeout_ms.store(millis, Ordering::Relaxed);
+        tracing::info!("Set query timeout to {timeout:?}");
+        Ok(self)
+    }
+
+    fn set_schema<DB: DbMarker>(&self, schema: &str) -> anyhow::Result<()> {
+        validate_schema_name(schema)?;
+        let mut schemas = self.schemas.lock().expect("schemas are poisoned");
+        let db_type = TypeId::of::<DB>();
+        if let Some((_, prev_schema)) = schemas.iter().find(|(ty, _)| *ty == db_type) {
+            anyhow::ensure!(
+                prev_schema == schema,
+                "Postgres schema is already set to `{prev_schema}`; cannot change it to `{schema}`"
+            );
+        } else {
+            schemas.push((db_type, schema.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+/// Read replica of a [`ConnectionPool`] together with its replication lag status.
+#[derive(Debug)]
+struct ReplicaPool {
+    inner: PgPool,
+    max_lag: Option<Duration>,
+    /// Time of the latest lag check and whether the replica was lagging according to it.
+    lag_status: Mutex<(Option<Instant>, bool)>,
+}
+
+impl ReplicaPool {
+    /// Interval between replication lag checks.
+    const LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+    fn new(inner: PgPool, max_lag: Option<Duration>) -> Self {
+        Self {
+            inner,
+            max_lag,
+            lag_status: Mutex::new((None, false)),
+        }
+    }
+
+    /// Checks whether the replica can be used to serve reads. The replication lag is checked at most once
+    /// per [`Self::LAG_CHECK_INTERVAL`]; while a check is in progress, the previous check result is used.
+    async fn is_available(&self) -> bool {
+        let Some(max_lag) = self.max_lag else {
+            return true;
+        };
+        {
+            let mut lag_status = self
+                .lag_status
+                .lock()
+                .expect("replica lag status is poisoned");
+            let (checked_at, is_lagging) = &mut *lag_status;
+            let is_fresh = checked_at.map_or(false, |at| at.elapsed() < Self::LAG_CHECK_INTERVAL);
+            if is_fresh {
+                return !*is_lagging;
+            }
+            *checked_at = Some(Instant::now());
+        }
+
+        let is_lagging = match self.get_lag().await {
+            Ok(lag) => {
+                CONNECTION_METRICS.replica_lag.set(lag);
+                lag > max_lag
+            }
+            Err(err) => {
+                tracing::warn!("Failed getting replication lag of the replica DB: {err:#}");
+                true
+            }
+        };
+        let mut lag_status = self
+            .lag_status
+            .lock()
+            .expect("replica lag status is poisoned");
+        if is_lagging != lag_status.1 {
+            if is_lagging {
+                tracing::warn!(
+                    "Replica DB lags behind by more than {max_lag:?}; routing reads to the main DB"
+                );
+            } else {
+                tracing::info!("Replica DB has caught up; routing reads to the replica");
+            }
+        }
+        lag_status.1 = is_lagging;
+        !is_lagging
+    }
+
+    async fn get_lag(&self) -> anyhow::Result<Duration> {
+        let mut connection = self.inner.acquire().await?;
+        // If the replica has replayed all received WAL, it is considered to be synced, since the replay timestamp
+        // only changes on new transactions on the main DB. If the DB is not a replica, both LSNs are `NULL`,
+        // and so is the replay timestamp.
+        let lag_sec: f64 = sqlx::query_scalar(
+            "SELECT \
+                CASE WHEN PG_LAST_WAL_RECEIVE_LSN() = PG_LAST_WAL_REPLAY_LSN() THEN 0 \
+                ELSE COALESCE(EXTRACT(EPOCH FROM NOW() - PG_LAST_XACT_REPLAY_TIMESTAMP()), 0) \
+                END::FLOAT8",
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+        Ok(Duration::from_secs_f64(lag_sec.max(0.0)))
+    }
 }
 
 #[derive(Clone)]
 pub struct ConnectionPool<DB: DbMarker> {
     pub(crate) inner: PgPool,
+    replica: Option<Arc<ReplicaPool>>,
     database_url: String,
     max_size: u32,
     pub(crate) traced_connections: Option<Arc<TracedConnections>>,
@@ -260,6 +470,7 @@ impl<DB: DbMarker> fmt::Debug for ConnectionPool<DB> {
         formatter
             .debug_struct("ConnectionPool")
             .field("max_size", &self.max_size)
+            .field("has_replica", &self.replica.is_some())
             .finish_non_exhaustive()
     }
 }
@@ -274,6 +485,18 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         &CONFIG
     }
 
+    /// Sets the Postgres schema used by all pools for this DB type that don't override it explicitly
+    /// (see [`ConnectionPoolBuilder::set_schema()`]). Schemas are set separately for each DB type,
+    /// so that, e.g., the core and prover DBs may use different schemas. The schema can only be set once.
+    pub fn set_global_schema(schema: &str) -> anyhow::Result<()> {
+        Self::global_config().set_schema::<DB>(schema)?;
+        tracing::info!(
+            "Set Postgres schema for `{}` to `{schema}`",
+            std::any::type_name::<DB>()
+        );
+        Ok(())
+    }
+
     /// Creates a test pool with a reasonably large number of connections.
     ///
     /// Test pools trace their active connections. If acquiring a connection fails (e.g., with a timeout),
@@ -308,6 +531,9 @@ impl<DB: DbMarker> ConnectionPool<DB> {
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            schema: None,
+            replica_url: None,
+            max_replica_lag: None,
             _marker: Default::default(),
         }
     }
@@ -325,6 +551,15 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         self.max_size
     }
 
+    /// Closes the pool: waits until all acquired connections are released and closes them.
+    /// Attempts to acquire a connection from the closed pool (or any of its clones) will fail.
+    pub async fn close(&self) {
+        self.inner.close().await;
+        if let Some(replica) = &self.replica {
+            replica.inner.close().await;
+        }
+    }
+
     /// Creates a `Connection` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -334,7 +569,7 @@ impl<DB: DbMarker> ConnectionPool<DB> {
     /// This method is intended to be used in crucial contexts, where the
     /// database access is must-have (e.g. block committer).
     pub async fn connection(&self) -> anyhow::Result<Connection<'_, DB>> {
-        self.connection_inner(None).await
+        self.connection_inner(None, &self.inner).await
     }
 
     /// A version of `connection` that would also expose the duration of the connection
@@ -354,17 +589,56 @@ impl<DB: DbMarker> ConnectionPool<DB> {
                 requester,
                 location,
             };
-            self.connection_inner(Some(tags)).await
+            self.connection_inner(Some(tags), &self.inner).await
+        }
+    }
+
+    /// Creates a connection that should only be used for reads. If the pool has a [replica](ConnectionPoolBuilder::set_replica_url())
+    /// which doesn't lag too much behind the main database, the connection is served by the replica;
+    /// otherwise, it is served by the main database. Thus, data written by the caller may not be immediately
+    /// visible via the returned connection.
+    pub async fn read_connection(&self) -> anyhow::Result<Connection<'_, DB>> {
+        let pool = self.read_pool().await;
+        self.connection_inner(None, pool).await
+    }
+
+    /// Tagged version of [`Self::read_connection()`]. See [`Self::connection_tagged()`] for details on tagging.
+    #[track_caller] // In order to use it, we have to de-sugar `async fn`
+    pub fn read_connection_tagged(
+        &self,
+        requester: &'static str,
+    ) -> impl Future<Output = anyhow::Result<Connection<'_, DB>>> + '_ {
+        let location = Location::caller();
+        async move {
+            let tags = ConnectionTags {
+                requester,
+                location,
+            };
+            let pool = self.read_pool().await;
+            self.connection_inner(Some(tags), pool).await
+        }
+    }
+
+    async fn read_pool(&self) -> &PgPool {
+        let Some(replica) = &self.replica else {
+            return &self.inner;
+        };
+        if replica.is_available().await {
+            &replica.inner
+        } else {
+            CONNECTION_METRICS.replica_fallback.inc();
+            &self.inner
         }
     }
 
     async fn connection_inner(
         &self,
         tags: Option<ConnectionTags>,
+        pool: &PgPool,
     ) -> anyhow::Result<Connection<'_, DB>> {
         let acquire_latency = CONNECTION_METRICS.acquire.start();
         let conn = self
-            .acquire_connection_retried(tags.as_ref())
+            .acquire_connection_retried(tags.as_ref(), pool)
             .await
             .context("acquire_connection_retried()")?;
         let elapsed = acquire_latency.observe();
@@ -382,17 +656,16 @@ impl<DB: DbMarker> ConnectionPool<DB> {
     async fn acquire_connection_retried(
         &self,
         tags: Option<&ConnectionTags>,
+        pool: &PgPool,
     ) -> anyhow::Result<PoolConnection<Postgres>> {
         const DB_CONNECTION_RETRIES: usize = 3;
         const AVG_BACKOFF_INTERVAL: Duration = Duration::from_secs(1);
 
         for _ in 0..DB_CONNECTION_RETRIES {
-            CONNECTION_METRICS
-                .pool_size
-                .observe(self.inner.size() as usize);
-            CONNECTION_METRICS.pool_idle.observe(self.inner.num_idle());
+            CONNECTION_METRICS.pool_size.observe(pool.size() as usize);
+            CONNECTION_METRICS.pool_idle.observe(pool.num_idle());
 
-            let connection = self.inner.acquire().await;
+            let connection = pool.acquire().await;
             let connection_err = match connection {
                 Ok(connection) => return Ok(connection),
                 Err(err) => err,
@@ -410,7 +683,7 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         }
 
         // Attempting to get the pooled connection for the last time
-        match self.inner.acquire().await {
+        match pool.acquire().await {
             Ok(conn) => Ok(conn),
             Err(err) => {
                 Self::report_connection_error(&err);
@@ -465,4 +738,110 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[test]
+    fn validating_schema_name() {
+        for schema in ["chain", "chain_270", "_chain"] {
+            validate_schema_name(schema).unwrap();
+        }
+        for schema in [
+            "",
+            "270",
+            "Chain",
+            "chain-270",
+            "chain; DROP TABLE",
+            &"a".repeat(64),
+        ] {
+            validate_schema_name(schema).unwrap_err();
+        }
+    }
+
+    #[derive(Debug)]
+    struct OtherMarker;
+
+    impl DbMarker for OtherMarker {}
+
+    #[test]
+    fn setting_global_schema() {
+        ConnectionPool::<OtherMarker>::set_global_schema("chain_270").unwrap();
+        ConnectionPool::<OtherMarker>::set_global_schema("chain_270").unwrap();
+        ConnectionPool::<OtherMarker>::set_global_schema("chain_271").unwrap_err();
+
+        let global_config = ConnectionPool::<OtherMarker>::global_config();
+        assert_eq!(
+            global_config.schema::<OtherMarker>().as_deref(),
+            Some("chain_270")
+        );
+        // The schema is not shared with other DB types.
+        assert_eq!(global_config.schema::<InternalMarker>(), None);
+    }
+
+    #[tokio::test]
+    async fn setting_schema() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db::<InternalMarker>(1)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::<InternalMarker>::singleton(&db_url)
+            .build()
+            .await
+            .unwrap();
+        let mut storage = pool.connection().await.unwrap();
+        sqlx::query("CREATE SCHEMA chain_270")
+            .execute(storage.conn())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let pool = ConnectionPool::<InternalMarker>::singleton(&db_url)
+            .set_schema(Some("chain_270".to_owned()))
+            .build()
+            .await
+            .unwrap();
+        let mut storage = pool.connection().await.unwrap();
+        let (current_schema,): (String,) = sqlx::query_as("SELECT current_schema()")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert_eq!(current_schema, "chain_270");
+    }
+
+    #[tokio::test]
+    async fn routing_reads_to_replica() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db::<InternalMarker>(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::<InternalMarker>::singleton(&db_url)
+            .set_replica_url(Some(db_url.clone()))
+            .set_max_replica_lag(Some(Duration::from_secs(10)))
+            .build()
+            .await
+            .unwrap();
+        let replica = pool.replica.as_ref().unwrap();
+        // The test DB is not a replica, so it should be considered synced.
+        assert_eq!(replica.get_lag().await.unwrap(), Duration::ZERO);
+        assert!(replica.is_available().await);
+        assert!(replica.lag_status.lock().unwrap().0.is_some());
+
+        // Both the main and replica pools are singletons, so the connections must be served by different pools.
+        let mut storage = pool.connection().await.unwrap();
+        let mut read_storage = pool.read_connection().await.unwrap();
+        let (value,): (i32,) = sqlx::query_as("SELECT 1")
+            .fetch_one(read_storage.conn())
+            .await
+            .unwrap();
+        assert_eq!(value, 1);
+        let (value,): (i32,) = sqlx::query_as("SELECT 2")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert_eq!(value, 2);
+    }
 }
@@ -0,0 +1,67 @@
+//! Classification of database errors.
+
+use std::{error, fmt};
+
+use zksync_utils::error::{ClassifyError, ErrorKind};
+
+/// Wrapper for [`sqlx::Error`] implementing [`ClassifyError`]. The trait cannot be implemented
+/// for `sqlx::Error` directly since both are defined outside this crate.
+///
+/// Display and source of the wrapper are the same as for the wrapped error.
+#[derive(Debug)]
+pub struct DbError(pub sqlx::Error);
+
+impl From<sqlx::Error> for DbError {
+    fn from(err: sqlx::Error) -> Self {
+        Self(err)
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, formatter)
+    }
+}
+
+impl error::Error for DbError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl ClassifyError for DbError {
+    fn error_kind(&self) -> ErrorKind {
+        match &self.0 {
+            sqlx::Error::Configuration(_) => ErrorKind::Configuration,
+            sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => ErrorKind::DataCorruption,
+            sqlx::Error::Database(_)
+            | sqlx::Error::RowNotFound
+            | sqlx::Error::ColumnNotFound(_)
+            | sqlx::Error::ColumnIndexOutOfBounds { .. }
+            | sqlx::Error::TypeNotFound { .. } => ErrorKind::Fatal,
+            _ => ErrorKind::Retryable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_utils::error::ClassifiedError;
+
+    use super::*;
+
+    #[test]
+    fn classifying_db_errors() {
+        let err = DbError(sqlx::Error::PoolTimedOut);
+        assert_eq!(err.error_kind(), ErrorKind::Retryable);
+        let err = ClassifiedError::new(err);
+        assert_eq!(err.to_string(), sqlx::Error::PoolTimedOut.to_string());
+        let err = anyhow::Error::from(err).context("context");
+        assert_eq!(err.error_kind(), ErrorKind::Retryable);
+
+        let err = DbError(sqlx::Error::RowNotFound);
+        assert_eq!(err.error_kind(), ErrorKind::Fatal);
+        let err = DbError(sqlx::Error::Configuration("invalid URL".into()));
+        assert_eq!(err.error_kind(), ErrorKind::Configuration);
+    }
+}
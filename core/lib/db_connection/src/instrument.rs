@@ -3,15 +3,17 @@
 //! Query instrumentation allows to:
 //!
 //! - Report query latency as a metric
-//! - Report slow and failing queries as metrics
-//! - Log slow and failing queries together with their arguments, which makes it easier to debug.
+//! - Report slow, failing and timed out queries as metrics
+//! - Report the number of returned / affected rows as a metric
+//! - Log slow and failing queries together with their arguments, which makes it easier to debug
+//! - Abort queries exceeding a timeout (either set globally, or for a specific query).
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
 //! its `instrument()` method can be placed on the output of `query*` functions or macros. You can then call
 //! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
 //! and/or [to add logged args](Instrumented::with_arg()) for a query.
 
-use std::{fmt, future::Future, panic::Location};
+use std::{fmt, future::Future, io, panic::Location, time};
 
 use sqlx::{
     postgres::{PgQueryResult, PgRow},
@@ -24,7 +26,7 @@ use crate::{
     connection::{Connection, ConnectionTags, DbMarker},
     connection_pool::ConnectionPool,
     metrics::REQUEST_METRICS,
-    utils::InternalMarker,
+    utils::{deadline_exceeded_error, query_timeout_error, InternalMarker},
 };
 
 type ThreadSafeDebug<'a> = dyn fmt::Debug + Send + Sync + 'a;
@@ -106,6 +108,7 @@ struct InstrumentedData<'a> {
     args: QueryArgs<'a>,
     report_latency: bool,
     slow_query_reporting_enabled: bool,
+    timeout: Option<time::Duration>,
 }
 
 impl<'a> InstrumentedData<'a> {
@@ -116,13 +119,16 @@ impl<'a> InstrumentedData<'a> {
             args: QueryArgs::default(),
             report_latency: false,
             slow_query_reporting_enabled: true,
+            timeout: None,
         }
     }
 
     async fn fetch<R>(
         self,
         connection_tags: Option<&ConnectionTags>,
+        deadline: Option<time::Instant>,
         query_future: impl Future<Output = Result<R, sqlx::Error>>,
+        row_count: fn(&R) -> u64,
     ) -> Result<R, sqlx::Error> {
         let Self {
             name,
@@ -130,12 +136,31 @@ impl<'a> InstrumentedData<'a> {
             args,
             report_latency,
             slow_query_reporting_enabled,
+            timeout,
         } = self;
+        if deadline.map_or(false, |deadline| time::Instant::now() >= deadline) {
+            let connection_tags = ConnectionTags::display(connection_tags);
+            tracing::info!(
+                "Query {name}{args} called at {file}:{line} [{connection_tags}] is not executed since its deadline has passed",
+                file = location.file(),
+                line = location.line()
+            );
+            return Err(deadline_exceeded_error());
+        }
         let started_at = Instant::now();
+        let global_config = ConnectionPool::<InternalMarker>::global_config();
+        let timeout = timeout.or_else(|| global_config.query_timeout());
+        let query_future = async {
+            let Some(timeout) = timeout else {
+                return query_future.await;
+            };
+            tokio::time::timeout(timeout, query_future)
+                .await
+                .unwrap_or_else(|_| Err(query_timeout_error(timeout)))
+        };
         tokio::pin!(query_future);
 
-        let slow_query_threshold =
-            ConnectionPool::<InternalMarker>::global_config().slow_query_threshold();
+        let slow_query_threshold = global_config.slow_query_threshold();
         let mut is_slow = false;
         let output =
             tokio::time::timeout_at(started_at + slow_query_threshold, &mut query_future).await;
@@ -159,21 +184,35 @@ impl<'a> InstrumentedData<'a> {
         let elapsed = started_at.elapsed();
         if report_latency {
             REQUEST_METRICS.request[&name].observe(elapsed);
+            if let Ok(output) = &output {
+                REQUEST_METRICS.request_rows[&name].observe(row_count(output));
+            }
         }
 
         let connection_tags = ConnectionTags::display(connection_tags);
-        if let Err(err) = &output {
+        let is_timed_out = timeout.is_some()
+            && matches!(&output, Err(sqlx::Error::Io(err)) if err.kind() == io::ErrorKind::TimedOut);
+        if is_timed_out {
+            tracing::warn!(
+                "Query {name}{args} called at {file}:{line} [{connection_tags}] was aborted after {elapsed:?} since it has exceeded its timeout",
+                file = location.file(),
+                line = location.line()
+            );
+            REQUEST_METRICS.request_timeout[&name].inc();
+        } else if let Err(err) = &output {
             tracing::warn!(
                 "Query {name}{args} called at {file}:{line} [{connection_tags}] has resulted in error: {err}",
                 file = location.file(),
                 line = location.line()
             );
             REQUEST_METRICS.request_error[&name].inc();
-        } else if is_slow {
+        } else if let (true, Ok(output)) = (is_slow, &output) {
             tracing::info!(
-                "Slow query {name}{args} called at {file}:{line} [{connection_tags}] has finished after {elapsed:?}",
+                "Slow query {name}{args} called at {file}:{line} [{connection_tags}] has finished after {elapsed:?} \
+                 with {rows} row(s) returned / affected",
                 file = location.file(),
-                line = location.line()
+                line = location.line(),
+                rows = row_count(output)
             );
         }
         output
@@ -189,8 +228,16 @@ impl<'a> InstrumentedData<'a> {
 ///   the query name, its args provided via [Self::with_arg()`] and the caller location.
 /// - If the query returns an error, it is logged with a `WARN` level. The logged info is everything
 ///   included in the case of a slow query, plus the error info.
-/// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
-///   respectively). The query name is included as a metric label; args are not included for obvious reasons.
+/// - If the query exceeds its timeout (set either globally via [`GlobalConnectionPoolConfig`] or for the query via
+///   [`Self::with_timeout()`]), it is aborted and logged with a `WARN` level. The connection the query was executed on
+///   is closed once dropped rather than returned to the pool, since it may still have an unfinished query.
+/// - Slow, erroneous and timed out queries are also reported using metrics (`dal.request.slow`, `dal.request.error`
+///   and `dal.request.timeout`, respectively). The query name is included as a metric label; args are not included
+///   for obvious reasons.
+/// - If [latency reporting](Self::report_latency()) is enabled, the number of returned / affected rows
+///   is reported as well (`dal.request.rows`).
+///
+/// [`GlobalConnectionPoolConfig`]: crate::connection_pool::GlobalConnectionPoolConfig
 #[derive(Debug)]
 pub struct Instrumented<'a, Q> {
     query: Q,
@@ -209,6 +256,12 @@ impl<'a, Q> Instrumented<'a, Q> {
         self
     }
 
+    /// Sets the timeout for this query, overriding the global timeout (if any).
+    pub fn with_timeout(mut self, timeout: time::Duration) -> Self {
+        self.data.timeout = Some(timeout);
+        self
+    }
+
     /// Adds a traced query argument. The argument will be logged (using `Debug`) if the query executes too slow
     /// or finishes with an error.
     pub fn with_arg(mut self, name: &'static str, value: &'a ThreadSafeDebug) -> Self {
@@ -226,8 +279,19 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> sqlx::Result<PgQueryResult> {
+        let deadline = storage.deadline();
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.execute(conn)).await
+        let output = self
+            .data
+            .fetch(
+                tags,
+                deadline,
+                self.query.execute(conn),
+                PgQueryResult::rows_affected,
+            )
+            .await;
+        storage.close_on_timeout(&output);
+        output
     }
 
     /// Fetches an optional row using this query.
@@ -235,8 +299,16 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> Result<Option<PgRow>, sqlx::Error> {
+        let deadline = storage.deadline();
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let output = self
+            .data
+            .fetch(tags, deadline, self.query.fetch_optional(conn), |row| {
+                u64::from(row.is_some())
+            })
+            .await;
+        storage.close_on_timeout(&output);
+        output
     }
 }
 
@@ -250,8 +322,16 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> sqlx::Result<Vec<O>> {
+        let deadline = storage.deadline();
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let output = self
+            .data
+            .fetch(tags, deadline, self.query.fetch_all(conn), |rows| {
+                rows.len() as u64
+            })
+            .await;
+        storage.close_on_timeout(&output);
+        output
     }
 }
 
@@ -266,8 +346,16 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> sqlx::Result<Option<O>> {
+        let deadline = storage.deadline();
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let output = self
+            .data
+            .fetch(tags, deadline, self.query.fetch_optional(conn), |row| {
+                u64::from(row.is_some())
+            })
+            .await;
+        storage.close_on_timeout(&output);
+        output
     }
 
     /// Fetches a single row using this query.
@@ -275,8 +363,14 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> sqlx::Result<O> {
+        let deadline = storage.deadline();
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        let output = self
+            .data
+            .fetch(tags, deadline, self.query.fetch_one(conn), |_| 1)
+            .await;
+        storage.close_on_timeout(&output);
+        output
     }
 
     /// Fetches all rows using this query and collects them into a `Vec`.
@@ -284,8 +378,16 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> sqlx::Result<Vec<O>> {
+        let deadline = storage.deadline();
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let output = self
+            .data
+            .fetch(tags, deadline, self.query.fetch_all(conn), |rows| {
+                rows.len() as u64
+            })
+            .await;
+        storage.close_on_timeout(&output);
+        output
     }
 }
 
@@ -294,7 +396,10 @@ mod tests {
     use zksync_basic_types::{MiniblockNumber, H256};
 
     use super::*;
-    use crate::{connection_pool::ConnectionPool, utils::InternalMarker};
+    use crate::{
+        connection_pool::ConnectionPool,
+        utils::{is_deadline_exceeded, InternalMarker},
+    };
 
     #[tokio::test]
     async fn instrumenting_erroneous_query() {
@@ -327,4 +432,20 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn instrumenting_query_with_timeout() {
+        let pool = ConnectionPool::<InternalMarker>::test_pool().await;
+
+        let mut conn = pool.connection().await.unwrap();
+        let err = sqlx::query("SELECT pg_sleep(2)")
+            .map(drop)
+            .instrument("timed_out")
+            .with_arg("miniblock", &MiniblockNumber(1))
+            .with_timeout(time::Duration::from_millis(500))
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap_err();
+        assert!(is_deadline_exceeded(&err), "{err:?}");
+    }
 }
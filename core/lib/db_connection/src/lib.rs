@@ -1,5 +1,7 @@
 pub mod connection;
 pub mod connection_pool;
+pub mod copy;
+pub mod error;
 pub mod healthcheck;
 pub mod instrument;
 pub mod metrics;
@@ -1,10 +1,12 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
+const ROW_COUNT_BUCKETS: Buckets = Buckets::exponential(1.0..=100_000.0, 10.0);
+
 /// Request-related DB metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "sql")]
@@ -18,6 +20,12 @@ pub(crate) struct RequestMetrics {
     /// Counter of errored DB requests.
     #[metrics(labels = ["method"])]
     pub request_error: LabeledFamily<&'static str, Counter>,
+    /// Counter of DB requests that have exceeded their timeout.
+    #[metrics(labels = ["method"])]
+    pub request_timeout: LabeledFamily<&'static str, Counter>,
+    /// Number of rows returned or affected by a DB request.
+    #[metrics(buckets = ROW_COUNT_BUCKETS, labels = ["method"])]
+    pub request_rows: LabeledFamily<&'static str, Histogram<u64>>,
 }
 
 #[vise::register]
@@ -90,6 +98,11 @@ pub(crate) struct ConnectionMetrics {
     /// Lifetime of a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["requester"])]
     pub lifetime: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Last observed replication lag of the replica DB used for read connections.
+    #[metrics(unit = Unit::Seconds)]
+    pub replica_lag: Gauge<Duration>,
+    /// Number of read connections served by the main DB because the replica DB was lagging.
+    pub replica_fallback: Counter,
 }
 
 #[vise::register]
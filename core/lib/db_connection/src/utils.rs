@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{io, time::Duration};
 
 use sqlx::{postgres::types::PgInterval, types::chrono::NaiveTime};
 
@@ -26,3 +26,29 @@ pub const fn pg_interval_from_duration(processing_timeout: Duration) -> PgInterv
         microseconds: processing_timeout.as_micros() as i64,
     }
 }
+
+/// Checks whether the error was caused by exceeding the query deadline set via
+/// [`Connection::set_deadline()`](crate::connection::Connection::set_deadline()), or by exceeding the query timeout
+/// (see [`Instrumented::with_timeout()`](crate::instrument::Instrumented::with_timeout())).
+pub fn is_deadline_exceeded(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(err) => err.kind() == io::ErrorKind::TimedOut,
+        // `query_canceled` error code, which is returned by Postgres if `statement_timeout` is exceeded
+        sqlx::Error::Database(err) => err.code().as_deref() == Some("57014"),
+        _ => false,
+    }
+}
+
+pub(crate) fn deadline_exceeded_error() -> sqlx::Error {
+    sqlx::Error::Io(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "query deadline exceeded",
+    ))
+}
+
+pub(crate) fn query_timeout_error(timeout: Duration) -> sqlx::Error {
+    sqlx::Error::Io(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("query timed out after {timeout:?}"),
+    ))
+}
@@ -40,7 +40,9 @@ impl FromEnv for ContractVerificationApiConfig {
 impl FromEnv for MerkleTreeApiConfig {
     /// Loads configuration from env variables.
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("merkle_tree_api", "API_MERKLE_TREE_")
+        let config: Self = envy_load("merkle_tree_api", "API_MERKLE_TREE_")?;
+        config.validate()?;
+        Ok(config)
     }
 }
 
@@ -66,7 +68,10 @@ mod tests {
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
                 max_nonce_ahead: 5,
+                replacement_fee_bump_percent: Some(10),
+                max_pending_txs_per_account: Some(16),
                 request_timeout: Some(10),
+                request_deadline_sec: Some(30),
                 account_pks: Some(vec![
                     hash("0x0000000000000000000000000000000000000000000000000000000000000001"),
                     hash("0x0000000000000000000000000000000000000000000000000000000000000002"),
@@ -85,9 +90,19 @@ mod tests {
                 max_batch_request_size: Some(200),
                 max_response_body_size_mb: Some(10),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
+                method_requests_per_minute_limit: Some(NonZeroU32::new(600).unwrap()),
+                client_requests_per_minute_limit: Some(NonZeroU32::new(120).unwrap()),
+                max_concurrent_requests: Some(1_000),
                 tree_api_url: None,
                 mempool_cache_update_interval: Some(50),
                 mempool_cache_size: Some(10000),
+                lookup_cache_size_mb: Some(64),
+                sign_sync_blocks: true,
+                sign_snapshot_headers: true,
+                tree_lag_limit: Some(10),
+                reject_proofs_on_tree_lag: true,
+                mempool_inspection_enabled: true,
+                contract_execution_stats_enabled: true,
             },
             prometheus: PrometheusConfig {
                 listener_port: 3312,
@@ -98,8 +113,12 @@ mod tests {
                 port: 8081,
                 slow_time_limit_ms: Some(250),
                 hard_time_limit_ms: Some(2_000),
+                config_export_token: Some("correct-horse-battery-staple".to_owned()),
+            },
+            merkle_tree: MerkleTreeApiConfig {
+                port: 8082,
+                reader_pool_size: 32,
             },
-            merkle_tree: MerkleTreeApiConfig { port: 8082 },
         }
     }
 
@@ -117,8 +136,11 @@ mod tests {
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
             API_WEB3_JSON_RPC_MAX_NONCE_AHEAD=5
+            API_WEB3_JSON_RPC_REPLACEMENT_FEE_BUMP_PERCENT=10
+            API_WEB3_JSON_RPC_MAX_PENDING_TXS_PER_ACCOUNT=16
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
             API_WEB3_JSON_RPC_REQUEST_TIMEOUT=10
+            API_WEB3_JSON_RPC_REQUEST_DEADLINE_SEC=30
             API_WEB3_JSON_RPC_ACCOUNT_PKS="0x0000000000000000000000000000000000000000000000000000000000000001,0x0000000000000000000000000000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
@@ -131,7 +153,17 @@ mod tests {
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_METHOD_REQUESTS_PER_MINUTE_LIMIT=600
+            API_WEB3_JSON_RPC_CLIENT_REQUESTS_PER_MINUTE_LIMIT=120
+            API_WEB3_JSON_RPC_MAX_CONCURRENT_REQUESTS=1000
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_SIZE=10000
+            API_WEB3_JSON_RPC_LOOKUP_CACHE_SIZE_MB=64
+            API_WEB3_JSON_RPC_SIGN_SYNC_BLOCKS=true
+            API_WEB3_JSON_RPC_SIGN_SNAPSHOT_HEADERS=true
+            API_WEB3_JSON_RPC_TREE_LAG_LIMIT=10
+            API_WEB3_JSON_RPC_REJECT_PROOFS_ON_TREE_LAG=true
+            API_WEB3_JSON_RPC_MEMPOOL_INSPECTION_ENABLED=true
+            API_WEB3_JSON_RPC_CONTRACT_EXECUTION_STATS_ENABLED=true
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_UPDATE_INTERVAL=50
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
@@ -142,11 +174,26 @@ mod tests {
             API_HEALTHCHECK_PORT=8081
             API_HEALTHCHECK_SLOW_TIME_LIMIT_MS=250
             API_HEALTHCHECK_HARD_TIME_LIMIT_MS=2000
+            API_HEALTHCHECK_CONFIG_EXPORT_TOKEN=correct-horse-battery-staple
             API_MERKLE_TREE_PORT=8082
+            API_MERKLE_TREE_READER_POOL_SIZE=32
         "#;
         lock.set_env(config);
 
         let actual = ApiConfig::from_env().unwrap();
         assert_eq!(actual, expected_config());
     }
+
+    #[test]
+    fn merkle_tree_api_from_env_with_empty_reader_pool() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            API_MERKLE_TREE_PORT=8082
+            API_MERKLE_TREE_READER_POOL_SIZE=0
+        "#;
+        lock.set_env(config);
+
+        let err = MerkleTreeApiConfig::from_env().unwrap_err().to_string();
+        assert!(err.contains("reader_pool_size"), "{err}");
+    }
 }
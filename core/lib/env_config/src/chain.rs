@@ -12,7 +12,9 @@ impl FromEnv for NetworkConfig {
 
 impl FromEnv for StateKeeperConfig {
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("state_keeper", "CHAIN_STATE_KEEPER_")
+        let config: Self = envy_load("state_keeper", "CHAIN_STATE_KEEPER_")?;
+        config.validate()?;
+        Ok(config)
     }
 }
 
@@ -37,7 +39,9 @@ impl FromEnv for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::L2ChainId;
-    use zksync_config::configs::chain::{FeeModelVersion, L1BatchCommitDataGeneratorMode};
+    use zksync_config::configs::chain::{
+        FeeModelVersion, L1BatchCommitDataGeneratorMode, MempoolDurability,
+    };
 
     use super::*;
     use crate::test_utils::{addr, hash, EnvMutex};
@@ -93,6 +97,31 @@ mod tests {
             max_gas_per_batch: 200_000_000,
             max_pubdata_per_batch: 100_000,
             fee_model_version: FeeModelVersion::V2,
+            congestion_mempool_size_threshold: Some(10_000),
+            congestion_l2_gas_price_multiplier: Some(1.5),
+            miniblock_seal_batch_latency_target_ms: Some(50),
+            miniblock_seal_batch_max_delay_ms: Some(20),
+            tx_execution_metrics_sampling_rate: Some(0.1),
+            tx_execution_metrics_retention_miniblocks: Some(100_000),
+            max_miniblock_payload_size: Some(4_500_000),
+            disabled_seal_criteria: vec![
+                "gas_for_batch_tip".to_owned(),
+                "no_txs_timeout".to_owned(),
+            ],
+            admin_port: Some(3080),
+            admin_token: Some("correct-horse-battery-staple".to_owned()),
+            tx_filter_denied_addresses: vec![addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7")],
+            tx_filter_allowed_initiators: vec![],
+            tx_filter_allowed_deployers: vec![
+                addr("0000000000000000000000000000000000000001"),
+                addr("0000000000000000000000000000000000000002"),
+            ],
+            tx_filter_max_calldata_size: Some(100_000),
+            shadow_vm_protocol_version: Some(23),
+            offload_protective_reads: true,
+            miniblock_adaptive_target_ms: Some(500),
+            miniblock_adaptive_target_percentile: Some(0.95),
+            miniblock_adaptive_max_tx_count: Some(200),
             validation_computational_gas_limit: 10_000_000,
             save_call_traces: false,
             virtual_blocks_interval: 1,
@@ -131,6 +160,24 @@ mod tests {
             CHAIN_STATE_KEEPER_MAX_GAS_PER_BATCH="200000000"
             CHAIN_STATE_KEEPER_MAX_PUBDATA_PER_BATCH="100000"
             CHAIN_STATE_KEEPER_FEE_MODEL_VERSION="V2"
+            CHAIN_STATE_KEEPER_CONGESTION_MEMPOOL_SIZE_THRESHOLD="10000"
+            CHAIN_STATE_KEEPER_CONGESTION_L2_GAS_PRICE_MULTIPLIER="1.5"
+            CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_BATCH_LATENCY_TARGET_MS="50"
+            CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_BATCH_MAX_DELAY_MS="20"
+            CHAIN_STATE_KEEPER_TX_EXECUTION_METRICS_SAMPLING_RATE="0.1"
+            CHAIN_STATE_KEEPER_TX_EXECUTION_METRICS_RETENTION_MINIBLOCKS="100000"
+            CHAIN_STATE_KEEPER_MAX_MINIBLOCK_PAYLOAD_SIZE="4500000"
+            CHAIN_STATE_KEEPER_DISABLED_SEAL_CRITERIA="gas_for_batch_tip,no_txs_timeout"
+            CHAIN_STATE_KEEPER_ADMIN_PORT="3080"
+            CHAIN_STATE_KEEPER_ADMIN_TOKEN="correct-horse-battery-staple"
+            CHAIN_STATE_KEEPER_TX_FILTER_DENIED_ADDRESSES="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+            CHAIN_STATE_KEEPER_TX_FILTER_ALLOWED_DEPLOYERS="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
+            CHAIN_STATE_KEEPER_TX_FILTER_MAX_CALLDATA_SIZE="100000"
+            CHAIN_STATE_KEEPER_SHADOW_VM_PROTOCOL_VERSION="23"
+            CHAIN_STATE_KEEPER_OFFLOAD_PROTECTIVE_READS="true"
+            CHAIN_STATE_KEEPER_MINIBLOCK_ADAPTIVE_TARGET_MS="500"
+            CHAIN_STATE_KEEPER_MINIBLOCK_ADAPTIVE_TARGET_PERCENTILE="0.95"
+            CHAIN_STATE_KEEPER_MINIBLOCK_ADAPTIVE_MAX_TX_COUNT="200"
             CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
@@ -163,6 +210,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn state_keeper_from_env_with_invalid_geometry_threshold() {
+        let mut lock = MUTEX.lock();
+        let config = state_keeper_config(ROLLUP_L1_BATCH_COMMIT_DATA_GENERATOR_MODE).replace(
+            "CHAIN_STATE_KEEPER_REJECT_TX_AT_GEOMETRY_PERCENTAGE=\"0.3\"",
+            "CHAIN_STATE_KEEPER_REJECT_TX_AT_GEOMETRY_PERCENTAGE=\"1.05\"",
+        );
+        lock.set_env(&config);
+
+        let err = StateKeeperConfig::from_env().unwrap_err().to_string();
+        assert!(err.contains("geometry seal thresholds"), "{err}");
+    }
+
+    #[test]
+    fn state_keeper_from_env_with_invalid_congestion_multiplier() {
+        let mut lock = MUTEX.lock();
+        let config = state_keeper_config(ROLLUP_L1_BATCH_COMMIT_DATA_GENERATOR_MODE).replace(
+            "CHAIN_STATE_KEEPER_CONGESTION_L2_GAS_PRICE_MULTIPLIER=\"1.5\"",
+            "CHAIN_STATE_KEEPER_CONGESTION_L2_GAS_PRICE_MULTIPLIER=\"0.5\"",
+        );
+        lock.set_env(&config);
+
+        let err = StateKeeperConfig::from_env().unwrap_err().to_string();
+        assert!(err.contains("congestion_l2_gas_price_multiplier"), "{err}");
+    }
+
     fn expected_mempool_config() -> MempoolConfig {
         MempoolConfig {
             sync_interval_ms: 10,
@@ -171,6 +244,8 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            max_nonce_gap: Some(50),
+            durability: MempoolDurability::Asynchronous,
         }
     }
 
@@ -184,6 +259,8 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_MAX_NONCE_GAP="50"
+            CHAIN_MEMPOOL_DURABILITY="asynchronous"
         "#;
         lock.set_env(config);
 
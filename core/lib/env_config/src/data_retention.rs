@@ -0,0 +1,40 @@
+use zksync_config::configs::DataRetentionConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for DataRetentionConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("data_retention", "DATA_RETENTION_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> DataRetentionConfig {
+        DataRetentionConfig {
+            events_retention_days: Some(90),
+            call_traces_retention_days: Some(14),
+            transaction_traces_retention_days: None,
+            poll_interval_ms: 30_000,
+            deletion_batch_size: 10_000,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            DATA_RETENTION_EVENTS_RETENTION_DAYS="90"
+            DATA_RETENTION_CALL_TRACES_RETENTION_DAYS="14"
+            DATA_RETENTION_POLL_INTERVAL_MS="30000"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = DataRetentionConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -43,9 +43,12 @@ impl FromEnv for PostgresConfig {
         let max_connections_master = parse_optional_var("DATABASE_POOL_SIZE_MASTER")?;
         let acquire_timeout_sec = parse_optional_var("DATABASE_ACQUIRE_TIMEOUT_SEC")?;
         let statement_timeout_sec = parse_optional_var("DATABASE_STATEMENT_TIMEOUT_SEC")?;
+        let max_replica_lag_sec = parse_optional_var("DATABASE_MAX_REPLICA_LAG_SEC")?;
         let long_connection_threshold_ms =
             parse_optional_var("DATABASE_LONG_CONNECTION_THRESHOLD_MS")?;
         let slow_query_threshold_ms = parse_optional_var("DATABASE_SLOW_QUERY_THRESHOLD_MS")?;
+        let query_timeout_ms = parse_optional_var("DATABASE_QUERY_TIMEOUT_MS")?;
+        let schema = env::var("DATABASE_SCHEMA").ok();
 
         Ok(Self {
             master_url,
@@ -55,8 +58,11 @@ impl FromEnv for PostgresConfig {
             max_connections_master,
             acquire_timeout_sec,
             statement_timeout_sec,
+            max_replica_lag_sec,
             long_connection_threshold_ms,
             slow_query_threshold_ms,
+            query_timeout_ms,
+            schema,
             test_server_url,
             test_prover_url,
         })
@@ -79,23 +85,63 @@ mod tests {
         let mut lock = MUTEX.lock();
         let config = r#"
             DATABASE_STATE_KEEPER_DB_PATH="/db/state_keeper"
+            DATABASE_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB=64
+            DATABASE_STATE_KEEPER_DB_VALUES_CACHE_SIZE_MB=128
+            DATABASE_STATE_KEEPER_DB_FACTORY_DEPS_CACHE_SIZE_MB=32
+            DATABASE_STATE_KEEPER_DB_CACHE_WARM_UP_L1_BATCHES=10
             DATABASE_MERKLE_TREE_PATH="/db/tree"
             DATABASE_MERKLE_TREE_MODE=lightweight
             DATABASE_MERKLE_TREE_MULTI_GET_CHUNK_SIZE=250
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_MAX_BATCHES_PER_COMMIT=10
+            DATABASE_MERKLE_TREE_HASHING_THREAD_COUNT=4
+            DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_INTERVAL_SEC=600
+            DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_SAMPLE_SIZE=1000
+            DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_REPAIR=true
+            DATABASE_MERKLE_TREE_REBUILD_FROM_POSTGRES=true
+            DATABASE_MERKLE_TREE_PROOF_DATA_FOLLOWER_PATH="/db/tree_follower"
+            DATABASE_MERKLE_TREE_SNAPSHOT_EXPORT_INTERVAL_SEC=3600
+            DATABASE_MERKLE_TREE_SNAPSHOT_EXPORT_CHUNK_SIZE=50000
+            DATABASE_MERKLE_TREE_L1_ROOT_CHECK_INTERVAL_SEC=60
         "#;
         lock.set_env(config);
 
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.state_keeper_db_path, "/db/state_keeper");
+        assert_eq!(db_config.state_keeper_db_block_cache_size_mb, Some(64));
+        assert_eq!(db_config.state_keeper_db_values_cache_size_mb, 128);
+        assert_eq!(db_config.state_keeper_db_factory_deps_cache_size_mb, 32);
+        assert_eq!(db_config.state_keeper_db_cache_warm_up_l1_batches, 10);
         assert_eq!(db_config.merkle_tree.path, "/db/tree");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Lightweight);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 250);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
+        assert_eq!(db_config.merkle_tree.max_batches_per_commit, 10);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(db_config.merkle_tree.hashing_thread_count, Some(4));
+        assert_eq!(
+            db_config.merkle_tree.consistency_check_interval(),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(db_config.merkle_tree.consistency_check_sample_size, 1000);
+        assert!(db_config.merkle_tree.consistency_check_repair);
+        assert!(db_config.merkle_tree.rebuild_from_postgres);
+        assert_eq!(
+            db_config.merkle_tree.proof_data_follower_path.as_deref(),
+            Some("/db/tree_follower")
+        );
+        assert_eq!(
+            db_config.merkle_tree.snapshot_export_interval(),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(db_config.merkle_tree.snapshot_export_chunk_size, 50_000);
+        assert_eq!(
+            db_config.merkle_tree.l1_root_check_interval(),
+            Some(Duration::from_secs(60))
+        );
     }
 
     #[test]
@@ -103,6 +149,10 @@ mod tests {
         let mut lock = MUTEX.lock();
         lock.remove_env(&[
             "DATABASE_STATE_KEEPER_DB_PATH",
+            "DATABASE_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB",
+            "DATABASE_STATE_KEEPER_DB_VALUES_CACHE_SIZE_MB",
+            "DATABASE_STATE_KEEPER_DB_FACTORY_DEPS_CACHE_SIZE_MB",
+            "DATABASE_STATE_KEEPER_DB_CACHE_WARM_UP_L1_BATCHES",
             "DATABASE_MERKLE_TREE_BACKUP_PATH",
             "DATABASE_MERKLE_TREE_PATH",
             "DATABASE_MERKLE_TREE_MODE",
@@ -111,17 +161,42 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_MAX_BATCHES_PER_COMMIT",
+            "DATABASE_MERKLE_TREE_HASHING_THREAD_COUNT",
+            "DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_INTERVAL_SEC",
+            "DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_SAMPLE_SIZE",
+            "DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_REPAIR",
+            "DATABASE_MERKLE_TREE_REBUILD_FROM_POSTGRES",
+            "DATABASE_MERKLE_TREE_PROOF_DATA_FOLLOWER_PATH",
+            "DATABASE_MERKLE_TREE_SNAPSHOT_EXPORT_INTERVAL_SEC",
+            "DATABASE_MERKLE_TREE_SNAPSHOT_EXPORT_CHUNK_SIZE",
+            "DATABASE_MERKLE_TREE_L1_ROOT_CHECK_INTERVAL_SEC",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.state_keeper_db_path, "./db/state_keeper");
+        assert_eq!(db_config.state_keeper_db_block_cache_size(), None);
+        assert!(db_config.state_keeper_db_column_families.is_empty());
+        assert_eq!(db_config.state_keeper_db_values_cache_size(), 0);
+        assert_eq!(db_config.state_keeper_db_cache_warm_up_l1_batches, 0);
         assert_eq!(db_config.merkle_tree.path, "./db/lightweight-new");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Full);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 500);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 20);
+        assert_eq!(db_config.merkle_tree.max_batches_per_commit, 1);
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.hashing_thread_count, None);
+        assert_eq!(db_config.merkle_tree.consistency_check_interval(), None);
+        assert_eq!(db_config.merkle_tree.consistency_check_sample_size, 100);
+        assert!(!db_config.merkle_tree.consistency_check_repair);
+        assert!(!db_config.merkle_tree.rebuild_from_postgres);
+        assert_eq!(db_config.merkle_tree.proof_data_follower_path, None);
+        assert_eq!(db_config.merkle_tree.snapshot_export_interval(), None);
+        assert_eq!(db_config.merkle_tree.snapshot_export_chunk_size, 100_000);
+        assert_eq!(db_config.merkle_tree.l1_root_check_interval(), None);
+        assert!(db_config.merkle_tree.column_families.is_empty());
 
         // Check that new env variable for Merkle tree path is supported
         lock.set_env("DATABASE_MERKLE_TREE_PATH=/db/tree/main");
@@ -149,8 +224,11 @@ mod tests {
             DATABASE_POOL_SIZE=50
             DATABASE_ACQUIRE_TIMEOUT_SEC=15
             DATABASE_STATEMENT_TIMEOUT_SEC=300
+            DATABASE_MAX_REPLICA_LAG_SEC=5
             DATABASE_LONG_CONNECTION_THRESHOLD_MS=3000
             DATABASE_SLOW_QUERY_THRESHOLD_MS=150
+            DATABASE_QUERY_TIMEOUT_MS=10000
+            DATABASE_SCHEMA=chain_270
         "#;
         lock.set_env(config);
 
@@ -168,6 +246,10 @@ mod tests {
             postgres_config.acquire_timeout(),
             Some(Duration::from_secs(15))
         );
+        assert_eq!(
+            postgres_config.max_replica_lag(),
+            Some(Duration::from_secs(5))
+        );
         assert_eq!(
             postgres_config.long_connection_threshold(),
             Some(Duration::from_secs(3))
@@ -176,5 +258,10 @@ mod tests {
             postgres_config.slow_query_threshold(),
             Some(Duration::from_millis(150))
         );
+        assert_eq!(
+            postgres_config.query_timeout(),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(postgres_config.schema.as_deref(), Some("chain_270"));
     }
 }
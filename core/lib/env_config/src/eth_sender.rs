@@ -12,6 +12,9 @@ impl FromEnv for ETHConfig {
             gas_adjuster: GasAdjusterConfig::from_env().ok(),
             watcher: ETHWatchConfig::from_env().ok(),
             web3_url: std::env::var("ETH_CLIENT_WEB3_URL").context("ETH_CLIENT_WEB3_URL")?,
+            gas_price_web3_urls: std::env::var("ETH_CLIENT_GAS_PRICE_WEB3_URLS")
+                .map(|urls| urls.split(',').map(str::to_owned).collect())
+                .unwrap_or_default(),
         })
     }
 }
@@ -24,14 +27,16 @@ impl FromEnv for SenderConfig {
 
 impl FromEnv for GasAdjusterConfig {
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("eth_sender.gas_adjuster", "ETH_SENDER_GAS_ADJUSTER_")
+        let config: Self = envy_load("eth_sender.gas_adjuster", "ETH_SENDER_GAS_ADJUSTER_")?;
+        config.validate()?;
+        Ok(config)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use zksync_config::configs::eth_sender::{
-        ProofLoadingMode, ProofSendingMode, PubdataSendingMode,
+        FeeEstimationStrategy, ProofLoadingMode, ProofSendingMode, PubdataSendingMode,
     };
 
     use super::*;
@@ -74,55 +79,69 @@ mod tests {
                 num_samples_for_blob_base_fee_estimate: 10,
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
+                fee_estimation_strategy: FeeEstimationStrategy::Ewma,
+                ewma_smoothing_factor: 0.3,
+                fee_estimation_percentile: 0.75,
+                l1_gas_price_quorum: 2,
+                l1_gas_price_max_deviation: 0.5,
             }),
             watcher: Some(ETHWatchConfig {
                 confirmations_for_eth_event: Some(0),
                 eth_node_poll_interval: 300,
             }),
             web3_url: "http://127.0.0.1:8545".to_string(),
+            gas_price_web3_urls: vec![
+                "http://127.0.0.1:8546".to_string(),
+                "http://127.0.0.1:8547".to_string(),
+            ],
         }
     }
 
+    const ENV_CONFIG: &str = r#"
+        ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT = "0"
+        ETH_WATCH_ETH_NODE_POLL_INTERVAL = "30"
+        ETH_SENDER_SENDER_WAIT_CONFIRMATIONS="1"
+        ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
+        ETH_SENDER_SENDER_AGGREGATE_TX_POLL_PERIOD="3"
+        ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
+        ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
+        ETH_SENDER_SENDER_PROOF_SENDING_MODE="SkipEveryProof"
+        ETH_SENDER_GAS_ADJUSTER_DEFAULT_PRIORITY_FEE_PER_GAS="20000000000"
+        ETH_SENDER_GAS_ADJUSTER_MAX_BASE_FEE_SAMPLES="10000"
+        ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_A="1.5"
+        ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_B="1.0005"
+        ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
+        ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
+        ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
+        ETH_SENDER_GAS_ADJUSTER_MAX_BLOB_BASE_FEE_SAMPLES="10"
+        ETH_SENDER_GAS_ADJUSTER_INTERNAL_PUBDATA_PRICING_MULTIPLIER="1.0"
+        ETH_SENDER_GAS_ADJUSTER_FEE_ESTIMATION_STRATEGY="Ewma"
+        ETH_SENDER_GAS_ADJUSTER_EWMA_SMOOTHING_FACTOR="0.3"
+        ETH_SENDER_GAS_ADJUSTER_L1_GAS_PRICE_QUORUM="2"
+        ETH_SENDER_WAIT_FOR_PROOFS="false"
+        ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
+        ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
+        ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_EXECUTE="4"
+        ETH_SENDER_SENDER_AGGREGATED_BLOCK_COMMIT_DEADLINE="30"
+        ETH_SENDER_SENDER_AGGREGATED_BLOCK_PROVE_DEADLINE="3000"
+        ETH_SENDER_SENDER_AGGREGATED_BLOCK_EXECUTE_DEADLINE="4000"
+        ETH_SENDER_SENDER_TIMESTAMP_CRITERIA_MAX_ALLOWED_LAG="30"
+        ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
+        ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
+        ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
+        ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
+        ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
+        ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
+        ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
+        ETH_CLIENT_GAS_PRICE_WEB3_URLS="http://127.0.0.1:8546,http://127.0.0.1:8547"
+
+    "#;
+
     #[test]
     #[allow(deprecated)]
     fn from_env() {
         let mut lock = MUTEX.lock();
-        let config = r#"
-            ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT = "0"
-            ETH_WATCH_ETH_NODE_POLL_INTERVAL = "30"
-            ETH_SENDER_SENDER_WAIT_CONFIRMATIONS="1"
-            ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
-            ETH_SENDER_SENDER_AGGREGATE_TX_POLL_PERIOD="3"
-            ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
-            ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
-            ETH_SENDER_SENDER_PROOF_SENDING_MODE="SkipEveryProof"
-            ETH_SENDER_GAS_ADJUSTER_DEFAULT_PRIORITY_FEE_PER_GAS="20000000000"
-            ETH_SENDER_GAS_ADJUSTER_MAX_BASE_FEE_SAMPLES="10000"
-            ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_A="1.5"
-            ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_B="1.0005"
-            ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
-            ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
-            ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
-            ETH_SENDER_GAS_ADJUSTER_MAX_BLOB_BASE_FEE_SAMPLES="10"
-            ETH_SENDER_GAS_ADJUSTER_INTERNAL_PUBDATA_PRICING_MULTIPLIER="1.0"
-            ETH_SENDER_WAIT_FOR_PROOFS="false"
-            ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
-            ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
-            ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_EXECUTE="4"
-            ETH_SENDER_SENDER_AGGREGATED_BLOCK_COMMIT_DEADLINE="30"
-            ETH_SENDER_SENDER_AGGREGATED_BLOCK_PROVE_DEADLINE="3000"
-            ETH_SENDER_SENDER_AGGREGATED_BLOCK_EXECUTE_DEADLINE="4000"
-            ETH_SENDER_SENDER_TIMESTAMP_CRITERIA_MAX_ALLOWED_LAG="30"
-            ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
-            ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
-            ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
-            ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
-            ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
-            ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
-            ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
-
-        "#;
-        lock.set_env(config);
+        lock.set_env(ENV_CONFIG);
 
         let actual = ETHConfig::from_env().unwrap();
         assert_eq!(actual, expected_config());
@@ -131,4 +150,17 @@ mod tests {
             hash("27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be")
         );
     }
+
+    #[test]
+    fn gas_adjuster_from_env_with_invalid_strategy_params() {
+        let mut lock = MUTEX.lock();
+        let config = ENV_CONFIG.replace(
+            "ETH_SENDER_GAS_ADJUSTER_EWMA_SMOOTHING_FACTOR=\"0.3\"",
+            "ETH_SENDER_GAS_ADJUSTER_EWMA_SMOOTHING_FACTOR=\"1.5\"",
+        );
+        lock.set_env(&config);
+
+        let err = GasAdjusterConfig::from_env().unwrap_err().to_string();
+        assert!(err.contains("ewma_smoothing_factor"), "{err}");
+    }
 }
@@ -0,0 +1,42 @@
+use zksync_config::configs::FinalityWebhooksConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for FinalityWebhooksConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("finality_webhooks", "FINALITY_WEBHOOKS_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> FinalityWebhooksConfig {
+        FinalityWebhooksConfig {
+            urls: vec![
+                "https://custodian.example.com/finality".to_owned(),
+                "http://127.0.0.1:8080/".to_owned(),
+            ],
+            poll_interval_ms: 1_000,
+            max_delivery_attempts: 3,
+            request_timeout_ms: 10_000,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            FINALITY_WEBHOOKS_URLS="https://custodian.example.com/finality,http://127.0.0.1:8080/"
+            FINALITY_WEBHOOKS_POLL_INTERVAL_MS="1000"
+            FINALITY_WEBHOOKS_MAX_DELIVERY_ATTEMPTS="3"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = FinalityWebhooksConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -44,6 +44,14 @@ mod tests {
                     gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
                 },
                 max_retries: 5,
+                request_timeout_secs: None,
+                circuit_breaker_threshold: 10,
+                circuit_breaker_cooldown_secs: 30,
+                cold_storage_class: None,
+                content_addressed: false,
+                encryption_master_key: None,
+                encryption_kms_key_id: None,
+                encrypted_buckets: vec![],
             }),
         }
     }
@@ -29,6 +29,12 @@ mod tests {
             proof_compressor_stats_reporting_interval_ms: 10_000,
             prover_job_archiver_reporting_interval_ms: Some(1_800_000),
             prover_job_archiver_archiving_interval_secs: Some(172_800),
+            object_store_archiver_interval_ms: Some(600_000),
+            object_store_archiver_retention_secs: Some(2_592_000),
+            object_store_gc_interval_ms: Some(3_600_000),
+            object_store_gc_retention_secs: Some(15_552_000),
+            storage_logs_partition_rotation_interval_ms: Some(60_000),
+            storage_logs_partition_size: Some(100_000),
         }
     }
 
@@ -50,6 +56,12 @@ mod tests {
             HOUSE_KEEPER_PROOF_COMPRESSOR_JOB_RETRYING_INTERVAL_MS="30000"
             HOUSE_KEEPER_PROVER_JOB_ARCHIVER_REPORTING_INTERVAL_MS="1800000"
             HOUSE_KEEPER_PROVER_JOB_ARCHIVER_ARCHIVING_INTERVAL_SECS="172800"
+            HOUSE_KEEPER_OBJECT_STORE_ARCHIVER_INTERVAL_MS="600000"
+            HOUSE_KEEPER_OBJECT_STORE_ARCHIVER_RETENTION_SECS="2592000"
+            HOUSE_KEEPER_OBJECT_STORE_GC_INTERVAL_MS="3600000"
+            HOUSE_KEEPER_OBJECT_STORE_GC_RETENTION_SECS="15552000"
+            HOUSE_KEEPER_STORAGE_LOGS_PARTITION_ROTATION_INTERVAL_MS="60000"
+            HOUSE_KEEPER_STORAGE_LOGS_PARTITION_SIZE="100000"
         "#;
         lock.set_env(config);
 
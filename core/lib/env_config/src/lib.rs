@@ -5,9 +5,11 @@ mod api;
 mod chain;
 mod contract_verifier;
 mod contracts;
+mod data_retention;
 mod database;
 mod eth_sender;
 mod eth_watch;
+mod finality_webhooks;
 mod fri_proof_compressor;
 mod fri_prover;
 mod fri_prover_gateway;
@@ -56,6 +56,14 @@ mod tests {
                 gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
             },
             max_retries: 5,
+            request_timeout_secs: None,
+            circuit_breaker_threshold: 10,
+            circuit_breaker_cooldown_secs: 30,
+            cold_storage_class: Some("COLDLINE".to_owned()),
+            content_addressed: false,
+            encryption_master_key: None,
+            encryption_kms_key_id: None,
+            encrypted_buckets: vec![],
         }
     }
 
@@ -67,10 +75,24 @@ mod tests {
             OBJECT_STORE_MODE="GCSWithCredentialFile"
             OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials.json"
             OBJECT_STORE_MAX_RETRIES="5"
+            OBJECT_STORE_COLD_STORAGE_CLASS="COLDLINE"
+            OBJECT_STORE_REQUEST_TIMEOUT_SECS="60"
+            OBJECT_STORE_CIRCUIT_BREAKER_THRESHOLD="3"
+            OBJECT_STORE_CONTENT_ADDRESSED="true"
+            OBJECT_STORE_ENCRYPTION_KMS_KEY_ID="alias/zksync"
+            OBJECT_STORE_ENCRYPTED_BUCKETS="proofs_fri,storage_logs_snapshots"
         "#;
         lock.set_env(config);
         let actual = ObjectStoreConfig::from_env().unwrap();
-        assert_eq!(actual, expected_gcs_config("/base/url"));
+        let expected = ObjectStoreConfig {
+            request_timeout_secs: Some(60),
+            circuit_breaker_threshold: 3,
+            content_addressed: true,
+            encryption_kms_key_id: Some("alias/zksync".to_owned()),
+            encrypted_buckets: vec!["proofs_fri".to_owned(), "storage_logs_snapshots".to_owned()],
+            ..expected_gcs_config("/base/url")
+        };
+        assert_eq!(actual, expected);
     }
 
     #[test]
@@ -79,6 +101,8 @@ mod tests {
         let config = r#"
             OBJECT_STORE_MODE="FileBacked"
             OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            OBJECT_STORE_FILE_BACKED_FSYNC="true"
+            OBJECT_STORE_FILE_BACKED_ARCHIVE_PATH="artifacts/archive"
         "#;
         lock.set_env(config);
         let actual = ObjectStoreConfig::from_env().unwrap();
@@ -86,6 +110,58 @@ mod tests {
             actual.mode,
             ObjectStoreMode::FileBacked {
                 file_backed_base_path: "artifacts".to_owned(),
+                file_backed_fsync: true,
+                file_backed_sharding: false,
+                file_backed_archive_path: Some("artifacts/archive".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn s3_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="S3"
+            OBJECT_STORE_BUCKET_BASE_URL="snapshots"
+            OBJECT_STORE_REGION="us-east-1"
+            OBJECT_STORE_ENDPOINT="http://localhost:9000"
+            OBJECT_STORE_FORCE_PATH_STYLE="true"
+            OBJECT_STORE_ACCESS_KEY_ID="minioadmin"
+            OBJECT_STORE_SECRET_ACCESS_KEY="minioadmin"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::S3 {
+                bucket_base_url: "snapshots".to_owned(),
+                region: "us-east-1".to_owned(),
+                endpoint: Some("http://localhost:9000".to_owned()),
+                force_path_style: true,
+                access_key_id: Some("minioadmin".to_owned()),
+                secret_access_key: Some("minioadmin".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn azure_blob_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="AzureBlob"
+            OBJECT_STORE_AZURE_STORAGE_ACCOUNT="zksync"
+            OBJECT_STORE_BUCKET_BASE_URL="snapshots"
+            OBJECT_STORE_COLD_STORAGE_CLASS="Archive"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(actual.cold_storage_class.as_deref(), Some("Archive"));
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::AzureBlob {
+                azure_storage_account: "zksync".to_owned(),
+                bucket_base_url: "snapshots".to_owned(),
+                azure_sas_token: None,
             }
         );
     }
@@ -41,9 +41,17 @@ impl FromEnv for Wallets {
             None
         };
 
+        let signer = std::env::var("MISC_SIGNER_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().context("Malformed signer pk"))
+            .transpose()?
+            .map(|pk| Wallet::from_private_key(pk, None))
+            .transpose()?;
+
         Ok(Self {
             eth_sender,
             state_keeper,
+            signer,
         })
     }
 }
@@ -78,9 +78,23 @@ struct MockEthereumInner {
     current_nonce: u64,
     pending_nonce: u64,
     nonces: BTreeMap<u64, u64>,
+    /// First block numbers of simulated L1 reorgs.
+    reorgs: Vec<u64>,
 }
 
 impl MockEthereumInner {
+    /// Block hashes encode the block number and the number of reorgs affecting the block.
+    fn block_hash(&self, block_number: u64) -> H256 {
+        let reorg_count = self
+            .reorgs
+            .iter()
+            .filter(|&&first_reorged_block| first_reorged_block <= block_number)
+            .count();
+        let mut hash = H256::from_low_u64_be(block_number);
+        hash.0[0] = reorg_count as u8;
+        hash
+    }
+
     fn execute_tx(
         &mut self,
         tx_hash: H256,
@@ -109,6 +123,7 @@ impl MockEthereumInner {
             receipt: TransactionReceipt {
                 gas_used: Some(21000u32.into()),
                 block_number: Some(block_number.into()),
+                block_hash: Some(self.block_hash(block_number)),
                 transaction_hash: tx_hash,
                 ..TransactionReceipt::default()
             },
@@ -194,6 +209,18 @@ impl MockEthereum {
         self.inner.read().unwrap().sent_txs.len()
     }
 
+    /// Returns hashes of the sent transactions that are not executed yet, ordered by nonce.
+    pub fn pending_tx_hashes(&self) -> Vec<H256> {
+        let inner = self.inner.read().unwrap();
+        let mut pending_txs: Vec<_> = inner
+            .sent_txs
+            .values()
+            .filter(|tx| !inner.tx_statuses.contains_key(&tx.hash))
+            .collect();
+        pending_txs.sort_unstable_by_key(|tx| tx.nonce);
+        pending_txs.into_iter().map(|tx| tx.hash).collect()
+    }
+
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
     /// as a success.
     pub fn execute_tx(
@@ -244,6 +271,18 @@ impl MockEthereum {
         ))
     }
 
+    /// Simulates an L1 reorg replacing all blocks starting from `first_reorged_block` with empty blocks.
+    /// Transactions executed in the reorged blocks become pending again.
+    pub fn reorg(&self, first_reorged_block: u64) {
+        let mut inner = self.inner.write().unwrap();
+        inner.reorgs.push(first_reorged_block);
+        inner.tx_statuses.retain(|_, status| {
+            status.receipt.block_number.unwrap().as_u64() < first_reorged_block
+        });
+        inner.nonces.retain(|&block, _| block < first_reorged_block);
+        inner.current_nonce = inner.nonces.values().next_back().copied().unwrap_or(0);
+    }
+
     pub fn advance_block_number(&self, val: u64) -> u64 {
         let mut inner = self.inner.write().unwrap();
         inner.block_number += val;
@@ -413,8 +452,10 @@ impl EthInterface for MockEthereum {
                     .get(number.as_usize())
                     .map(|base_fee| (*base_fee).into());
 
+                let hash = self.inner.read().unwrap().block_hash(number.as_u64());
                 Ok(Some(Block {
                     number: Some(number),
+                    hash: Some(hash),
                     excess_blob_gas,
                     base_fee_per_gas,
                     ..Default::default()
@@ -10,9 +10,11 @@ use zksync_types::{
         },
         ethabi,
         types::{Address, BlockId, TransactionReceipt, H256, U256},
+        Error as Web3Error,
     },
     Bytes, EIP_4844_TX_TYPE, H160, H2048, U64,
 };
+use zksync_utils::error::{ClassifyError, ErrorKind};
 
 /// Wrapper for `Vec<ethabi::Token>` that doesn't wrap them in an additional array in `Tokenize` implementation.
 #[derive(Debug)]
@@ -123,6 +125,34 @@ pub enum Error {
     Eip4844MissingBlobVersionedHashes,
 }
 
+impl ClassifyError for Error {
+    fn error_kind(&self) -> ErrorKind {
+        match self {
+            Self::EthereumGateway(err) | Self::Contract(ContractError::Api(err)) => {
+                web3_error_kind(err)
+            }
+            Self::Contract(ContractError::InvalidOutputType(_) | ContractError::Abi(_))
+            | Self::Decode(_) => ErrorKind::DataCorruption,
+            Self::Signer(_) => ErrorKind::Configuration,
+            Self::Contract(_)
+            | Self::WrongFeeProvided(..)
+            | Self::Eip4844MissingMaxFeePerBlobGas
+            | Self::Eip4844MissingBlobVersionedHashes => ErrorKind::Fatal,
+        }
+    }
+}
+
+fn web3_error_kind(err: &Web3Error) -> ErrorKind {
+    match err {
+        // RPC errors returned by the L1 node (e.g., rate limiting) are considered transient as well.
+        Web3Error::Unreachable | Web3Error::Transport(_) | Web3Error::Io(_) | Web3Error::Rpc(_) => {
+            ErrorKind::Retryable
+        }
+        Web3Error::Decoder(_) | Web3Error::InvalidResponse(_) => ErrorKind::DataCorruption,
+        _ => ErrorKind::Fatal,
+    }
+}
+
 /// Raw transaction bytes.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RawTransactionBytes(pub(crate) Vec<u8>);
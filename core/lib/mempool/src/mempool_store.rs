@@ -1,7 +1,7 @@
 use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
 use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
@@ -10,6 +10,8 @@ use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    /// Hashes of L2 transactions evicted from the mempool because of a nonce gap.
+    pub evicted_transactions: Vec<H256>,
 }
 
 #[derive(Debug)]
@@ -30,6 +32,10 @@ pub struct MempoolStore {
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
+    /// Maximum gap between the account nonce and the nonce of an L2 transaction accepted to the mempool.
+    max_nonce_gap: Option<u32>,
+    /// L2 transactions evicted because of `max_nonce_gap` since the last [`Self::get_mempool_info()`] call.
+    evicted_transactions: Vec<H256>,
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
@@ -43,11 +49,19 @@ impl MempoolStore {
             l2_priority_queue: BTreeSet::new(),
             next_priority_id,
             stashed_accounts: vec![],
+            max_nonce_gap: None,
+            evicted_transactions: vec![],
             size: 0,
             capacity,
         }
     }
 
+    /// Sets the maximum gap between the account nonce and the nonce of an L2 transaction. Transactions exceeding
+    /// the gap are evicted on insertion and are reported in [`MempoolInfo`].
+    pub fn set_max_nonce_gap(&mut self, max_nonce_gap: Option<u32>) {
+        self.max_nonce_gap = max_nonce_gap;
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -101,6 +115,22 @@ impl MempoolStore {
         initial_nonces: &HashMap<Address, Nonce>,
     ) {
         let account = transaction.initiator_account();
+        if let Some(max_nonce_gap) = self.max_nonce_gap {
+            let account_nonce = self.l2_transactions_per_account.get(&account).map_or_else(
+                || initial_nonces.get(&account).cloned().unwrap_or(Nonce(0)),
+                AccountTransactions::nonce,
+            );
+            let nonce = transaction.common_data.nonce;
+            if nonce.0 > account_nonce.0.saturating_add(max_nonce_gap) {
+                tracing::debug!(
+                    "evicting L2 transaction {:?} with nonce {nonce} for account {account:?} \
+                     with nonce {account_nonce}",
+                    transaction.hash()
+                );
+                self.evicted_transactions.push(transaction.hash());
+                return;
+            }
+        }
 
         let metadata = match self.l2_transactions_per_account.entry(account) {
             hash_map::Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
@@ -209,6 +239,7 @@ impl MempoolStore {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
             purged_accounts: self.gc(),
+            evicted_transactions: std::mem::take(&mut self.evicted_transactions),
         }
     }
 
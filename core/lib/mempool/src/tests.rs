@@ -365,6 +365,41 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn evicting_transactions_with_nonce_gap() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    mempool.set_max_nonce_gap(Some(2));
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let gapped_tx = gen_l2_tx_with_hash(account0, Nonce(8));
+    let gapped_tx_hash = gapped_tx.hash();
+    let transactions = vec![
+        gen_l2_tx_with_hash(account0, Nonce(5)),
+        gen_l2_tx_with_hash(account0, Nonce(7)),
+        gapped_tx,
+        gen_l2_tx_with_hash(account1, Nonce(2)),
+    ];
+    mempool.insert(transactions, HashMap::from([(account0, Nonce(5))]));
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+    assert_eq!(
+        mempool.get_mempool_info().evicted_transactions,
+        [gapped_tx_hash]
+    );
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account0, 5)
+    );
+    // The account nonce has advanced, so the transaction doesn't exceed the nonce gap now.
+    mempool.insert(
+        vec![gen_l2_tx_with_hash(account0, Nonce(8))],
+        HashMap::new(),
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -384,6 +419,21 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
     txn.into()
 }
 
+fn gen_l2_tx_with_hash(address: Address, nonce: Nonce) -> Transaction {
+    let mut txn = L2Tx::new(
+        Address::default(),
+        Vec::new(),
+        nonce,
+        Fee::default(),
+        address,
+        U256::zero(),
+        None,
+        Default::default(),
+    );
+    txn.set_input(vec![], H256::random());
+    txn.into()
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
@@ -74,6 +74,11 @@ impl AccountTransactions {
         self.transactions.len()
     }
 
+    /// Returns the nonce of the next transaction from this account to be sent to the state keeper.
+    pub fn nonce(&self) -> Nonce {
+        self.nonce
+    }
+
     fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
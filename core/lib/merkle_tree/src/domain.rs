@@ -1,6 +1,6 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
-use rayon::{ThreadPool, ThreadPoolBuilder};
+use rayon::ThreadPool;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_prover_interface::inputs::{PrepareBasicCircuitsJob, StorageLogMetadata};
 use zksync_types::{
@@ -9,11 +9,13 @@ use zksync_types::{
 };
 
 use crate::{
+    snapshot::{TreeSnapshotError, TreeSnapshotExporter},
     storage::{PatchSet, Patched, RocksDBWrapper},
     types::{
         Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
         TREE_DEPTH,
     },
+    utils::create_thread_pool,
     BlockOutput, HashTree, MerkleTree, NoVersionError,
 };
 
@@ -53,14 +55,6 @@ pub struct ZkSyncTree {
 }
 
 impl ZkSyncTree {
-    fn create_thread_pool(thread_count: usize) -> ThreadPool {
-        ThreadPoolBuilder::new()
-            .thread_name(|idx| format!("new-merkle-tree-{idx}"))
-            .num_threads(thread_count)
-            .build()
-            .expect("failed initializing `rayon` thread pool")
-    }
-
     /// Returns metadata based on `storage_logs` generated by the genesis L1 batch. This does not
     /// create a persistent tree.
     pub fn process_genesis_batch(storage_logs: &[TreeInstruction<StorageKey>]) -> BlockOutput {
@@ -132,7 +126,7 @@ impl ZkSyncTree {
     /// If `thread_count` is 0, the default number of threads will be used; see `rayon` docs
     /// for details.
     pub fn use_dedicated_thread_pool(&mut self, thread_count: usize) {
-        self.thread_pool = Some(Self::create_thread_pool(thread_count));
+        self.thread_pool = Some(create_thread_pool("new-merkle-tree", thread_count));
     }
 
     /// Returns the current root hash of this tree.
@@ -411,11 +405,24 @@ impl Clone for ZkSyncTreeReader {
 }
 
 impl ZkSyncTreeReader {
+    /// Returns a reader pinned to a point-in-time snapshot of the underlying RocksDB. Unlike ordinary readers,
+    /// the returned reader is unaffected by concurrent tree updates (including reverts and pruning),
+    /// so it can be used to serve consistent reads while the tree is being written to.
+    pub fn snapshot(&self) -> Self {
+        Self(MerkleTree::new(self.0.db.snapshot()))
+    }
+
     /// Returns the current root hash of this tree.
     pub fn root_hash(&self) -> ValueHash {
         self.0.latest_root_hash()
     }
 
+    /// Returns the root hash of the tree after processing the specified L1 batch, or `None` if the tree
+    /// doesn't contain a version for the batch (e.g., if the batch was not processed yet).
+    pub fn l1_batch_root_hash(&self, l1_batch_number: L1BatchNumber) -> Option<ValueHash> {
+        self.0.root_hash(u64::from(l1_batch_number.0))
+    }
+
     /// Returns the next L1 batch number that should be processed by the tree.
     #[allow(clippy::missing_panics_doc)]
     pub fn next_l1_batch_number(&self) -> L1BatchNumber {
@@ -444,4 +451,23 @@ impl ZkSyncTreeReader {
         let version = u64::from(l1_batch_number.0);
         self.0.entries_with_proofs(version, keys)
     }
+
+    /// Creates an exporter of the tree snapshot after processing the specified L1 batch.
+    /// `chunk_size` specifies the maximum number of tree nodes in a single exported chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree doesn't contain a version for the L1 batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn snapshot_exporter(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        chunk_size: usize,
+    ) -> Result<TreeSnapshotExporter<RocksDBWrapper>, TreeSnapshotError> {
+        let version = u64::from(l1_batch_number.0);
+        TreeSnapshotExporter::new(self.0.db.clone(), version, chunk_size)
+    }
 }
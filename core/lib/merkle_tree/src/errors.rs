@@ -22,6 +22,12 @@ pub enum DeserializeErrorKind {
     /// Bit mask specifying a child kind in an internal tree node is invalid.
     #[error("invalid bit mask specifying a child kind in an internal tree node")]
     InvalidChildKind,
+    /// Tag specifying a node kind in a tree snapshot chunk is invalid.
+    #[error("invalid tag specifying a node kind in a tree snapshot chunk")]
+    InvalidSnapshotTag,
+    /// Number of nibbles in a node key is invalid.
+    #[error("invalid number of nibbles in a node key")]
+    InvalidNibbleCount,
 
     /// Missing required tag in the tree manifest.
     #[error("missing required tag `{0}` in tree manifest")]
@@ -73,6 +79,8 @@ pub enum ErrorContext {
     LeafIndex,
     /// Version of a child in an internal node.
     Version,
+    /// Tree snapshot chunk.
+    SnapshotChunk,
 }
 
 impl fmt::Display for ErrorContext {
@@ -87,6 +95,7 @@ impl fmt::Display for ErrorContext {
             Self::LeafCount => formatter.write_str("number of leaf nodes"),
             Self::LeafIndex => formatter.write_str("leaf index"),
             Self::Version => formatter.write_str("version of a child"),
+            Self::SnapshotChunk => formatter.write_str("tree snapshot chunk"),
         }
     }
 }
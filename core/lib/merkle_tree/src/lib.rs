@@ -71,6 +71,7 @@ mod hasher;
 mod metrics;
 mod pruning;
 pub mod recovery;
+pub mod snapshot;
 mod storage;
 mod types;
 mod utils;
@@ -24,6 +24,10 @@
 //! The recovery process is tolerant to crashes and may be resumed from the middle. To find the latest
 //! recovered key, you may use [`MerkleTreeRecovery::last_processed_key()`].
 //!
+//! Node hashes for each extended chunk are computed in parallel using `rayon`. By default, the global `rayon`
+//! thread pool is used; a dedicated pool can be configured using [`MerkleTreeRecovery::use_dedicated_thread_pool()`].
+//! The number of threads does not influence the recovered tree.
+//!
 //! `RecoveryEntry` chunks are not validated during recovery. They can be authenticated using
 //! [`TreeRangeDigest`](crate::TreeRangeDigest)s provided that the tree root hash is authenticated
 //! using external means.
@@ -37,12 +41,14 @@
 
 use std::time::Instant;
 
+use rayon::ThreadPool;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 
 use crate::{
     hasher::{HashTree, HasherWithStats},
     storage::{PatchSet, PruneDatabase, PrunePatchSet, Storage},
     types::{Key, Manifest, Root, TreeEntry, TreeTags, ValueHash},
+    utils::create_thread_pool,
 };
 
 /// Handle to a Merkle tree during its recovery.
@@ -51,6 +57,7 @@ pub struct MerkleTreeRecovery<DB, H = Blake2Hasher> {
     pub(crate) db: DB,
     hasher: H,
     recovered_version: u64,
+    thread_pool: Option<ThreadPool>,
 }
 
 impl<DB: PruneDatabase> MerkleTreeRecovery<DB> {
@@ -107,6 +114,24 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
             db,
             hasher,
             recovered_version,
+            thread_pool: None,
+        }
+    }
+
+    /// Signals that the tree should use a dedicated `rayon` thread pool for hash computations
+    /// when extending the tree.
+    ///
+    /// If `thread_count` is 0, the default number of threads will be used; see `rayon` docs
+    /// for details.
+    pub fn use_dedicated_thread_pool(&mut self, thread_count: usize) {
+        self.thread_pool = Some(create_thread_pool("merkle-tree-recovery", thread_count));
+    }
+
+    fn in_thread_pool<T: Send>(&self, action: impl FnOnce() -> T + Send) -> T {
+        if let Some(thread_pool) = &self.thread_pool {
+            thread_pool.install(action)
+        } else {
+            action()
         }
     }
 
@@ -151,8 +176,10 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
         tracing::debug!("Started extending tree");
 
         let started_at = Instant::now();
-        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
-        let patch = storage.extend_during_linear_recovery(entries);
+        let patch = self.in_thread_pool(|| {
+            let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+            storage.extend_during_linear_recovery(entries)
+        });
         tracing::debug!("Finished processing keys; took {:?}", started_at.elapsed());
 
         let started_at = Instant::now();
@@ -174,8 +201,10 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
         tracing::debug!("Started extending tree");
 
         let started_at = Instant::now();
-        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
-        let patch = storage.extend_during_random_recovery(entries);
+        let patch = self.in_thread_pool(|| {
+            let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+            storage.extend_during_random_recovery(entries)
+        });
         tracing::debug!("Finished processing keys; took {:?}", started_at.elapsed());
 
         let started_at = Instant::now();
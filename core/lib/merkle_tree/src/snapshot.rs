@@ -0,0 +1,503 @@
+//! Merkle tree snapshots.
+//!
+//! # Overview
+//!
+//! A **tree snapshot** consists of all nodes of a Merkle tree at a specific version. Unlike [recovery](crate::recovery),
+//! which restores a tree from its entries, importing a snapshot doesn't require computing node hashes;
+//! nodes are copied from the exporting tree verbatim. This makes importing a snapshot much faster for large trees.
+//!
+//! A snapshot is produced by [`TreeSnapshotExporter`] as a sequence of [`TreeSnapshotChunk`]s. The first chunk
+//! contains the tree root; other nodes are exported in the depth-first order. Chunks can be
+//! [serialized](TreeSnapshotChunk::serialize()) in order to be stored externally (e.g., in an object store),
+//! and are [imported](TreeSnapshotImporter) into an empty database.
+//!
+//! Similar to a recovered tree, an imported tree is only *observably* identical to the original tree:
+//! all nodes in the imported tree have the snapshot version.
+//!
+//! Chunks are not validated during import. Unless the snapshot source is trusted, the imported tree should be checked
+//! using [`MerkleTree::verify_consistency()`](crate::MerkleTree::verify_consistency()) after the import.
+
+use zksync_crypto::hasher::blake2::Blake2Hasher;
+
+use crate::{
+    errors::{DeserializeError, DeserializeErrorKind, ErrorContext},
+    hasher::{HashTree, HasherWithStats},
+    storage::PatchSet,
+    types::{
+        InternalNode, LeafNode, Manifest, Nibbles, NibblesBytes, Node, NodeKey, Root, ValueHash,
+        KEY_SIZE,
+    },
+    Database, NoVersionError,
+};
+
+/// Errors that can occur when exporting or importing a tree snapshot.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TreeSnapshotError {
+    /// Exported tree version is missing.
+    #[error(transparent)]
+    NoVersion(#[from] NoVersionError),
+    /// Error deserializing tree data from the database.
+    #[error("failed deserializing tree data: {0}")]
+    Deserialize(#[from] DeserializeError),
+    /// Tree node referenced by its parent is missing (e.g., because it was pruned).
+    #[error("tree node at `{0}` referenced by its parent is missing")]
+    MissingNode(NodeKey),
+    /// Imported snapshot doesn't contain the tree root.
+    #[error("tree snapshot doesn't contain the tree root")]
+    MissingRoot,
+}
+
+/// Chunk of a tree snapshot produced by [`TreeSnapshotExporter`].
+#[derive(Debug, Clone)]
+pub struct TreeSnapshotChunk {
+    root: Option<Root>,
+    nodes: Vec<(Nibbles, Node)>,
+}
+
+impl TreeSnapshotChunk {
+    const NO_ROOT_TAG: u8 = 0;
+    const ROOT_TAG: u8 = 1;
+    const LEAF_TAG: u8 = 0;
+    const INTERNAL_NODE_TAG: u8 = 1;
+
+    /// Returns the number of nodes in this chunk, including the root node if it is present.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len() + usize::from(self.root.is_some())
+    }
+
+    /// Serializes this chunk to bytes.
+    ///
+    /// A chunk is serialized as an optional length-prefixed root, followed by a length-prefixed list
+    /// of nodes. Each node is serialized as its key (the number of nibbles followed by nibble bytes),
+    /// a tag specifying the node kind, and the length-prefixed node in the database format.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.node_count() * 128);
+        // ^ 128 looks somewhat reasonable as node capacity
+        let mut node_bytes = Vec::with_capacity(128);
+
+        if let Some(root) = &self.root {
+            buffer.push(Self::ROOT_TAG);
+            root.serialize(&mut node_bytes);
+            Self::write_bytes(&mut buffer, &node_bytes);
+        } else {
+            buffer.push(Self::NO_ROOT_TAG);
+        }
+
+        leb128::write::unsigned(&mut buffer, self.nodes.len() as u64).unwrap();
+        for (nibbles, node) in &self.nodes {
+            let nibble_count = nibbles.nibble_count();
+            buffer.push(nibble_count as u8);
+            // ^ conversion is safe: `nibble_count <= 64`
+            buffer.extend_from_slice(&nibbles.bytes()[..(nibble_count + 1) / 2]);
+            buffer.push(match node {
+                Node::Leaf(_) => Self::LEAF_TAG,
+                Node::Internal(_) => Self::INTERNAL_NODE_TAG,
+            });
+            node_bytes.clear();
+            node.serialize(&mut node_bytes);
+            Self::write_bytes(&mut buffer, &node_bytes);
+        }
+        buffer
+    }
+
+    fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+        leb128::write::unsigned(buffer, bytes.len() as u64).unwrap();
+        buffer.extend_from_slice(bytes);
+    }
+
+    /// Deserializes a chunk from bytes produced by [`Self::serialize()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are malformed.
+    pub fn deserialize(mut bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Self::deserialize_inner(&mut bytes)
+            .map_err(|err| err.with_context(ErrorContext::SnapshotChunk))
+    }
+
+    fn deserialize_inner(bytes: &mut &[u8]) -> Result<Self, DeserializeError> {
+        let root = match Self::read_byte(bytes)? {
+            Self::NO_ROOT_TAG => None,
+            Self::ROOT_TAG => Some(Root::deserialize(Self::read_bytes(bytes)?)?),
+            _ => return Err(DeserializeErrorKind::InvalidSnapshotTag.into()),
+        };
+
+        let node_count = leb128::read::unsigned(bytes).map_err(DeserializeErrorKind::Leb128)?;
+        let mut nodes = Vec::new();
+        for _ in 0..node_count {
+            let nibbles = Self::read_nibbles(bytes)?;
+            let tag = Self::read_byte(bytes)?;
+            let node_bytes = Self::read_bytes(bytes)?;
+            let node = match tag {
+                Self::LEAF_TAG => LeafNode::deserialize(node_bytes)?.into(),
+                Self::INTERNAL_NODE_TAG => InternalNode::deserialize(node_bytes)?.into(),
+                _ => return Err(DeserializeErrorKind::InvalidSnapshotTag.into()),
+            };
+            nodes.push((nibbles, node));
+        }
+        Ok(Self { root, nodes })
+    }
+
+    fn read_byte(bytes: &mut &[u8]) -> Result<u8, DeserializeError> {
+        let (&byte, rest) = bytes
+            .split_first()
+            .ok_or(DeserializeErrorKind::UnexpectedEof)?;
+        *bytes = rest;
+        Ok(byte)
+    }
+
+    fn read_bytes<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], DeserializeError> {
+        let len = leb128::read::unsigned(bytes).map_err(DeserializeErrorKind::Leb128)?;
+        let len = usize::try_from(len).map_err(|_| DeserializeErrorKind::UnexpectedEof)?;
+        if bytes.len() < len {
+            return Err(DeserializeErrorKind::UnexpectedEof.into());
+        }
+        let (value, rest) = bytes.split_at(len);
+        *bytes = rest;
+        Ok(value)
+    }
+
+    fn read_nibbles(bytes: &mut &[u8]) -> Result<Nibbles, DeserializeError> {
+        let nibble_count = usize::from(Self::read_byte(bytes)?);
+        // Root nodes are serialized separately, so node keys cannot be empty.
+        if nibble_count == 0 || nibble_count > 2 * KEY_SIZE {
+            return Err(DeserializeErrorKind::InvalidNibbleCount.into());
+        }
+        let byte_len = (nibble_count + 1) / 2;
+        if bytes.len() < byte_len {
+            return Err(DeserializeErrorKind::UnexpectedEof.into());
+        }
+
+        let mut nibble_bytes = NibblesBytes::default();
+        nibble_bytes[..byte_len].copy_from_slice(&bytes[..byte_len]);
+        if nibble_count % 2 == 1 {
+            // The unused nibble needs to be zeroized in order for `Eq` / `Hash` traits to work properly.
+            nibble_bytes[byte_len - 1] &= 0xf0;
+        }
+        *bytes = &bytes[byte_len..];
+        Ok(Nibbles::from_parts(nibble_bytes, nibble_count))
+    }
+}
+
+/// Exporter of a Merkle tree version as a sequence of [`TreeSnapshotChunk`]s.
+#[derive(Debug)]
+pub struct TreeSnapshotExporter<DB> {
+    db: DB,
+    version: u64,
+    chunk_size: usize,
+    root: Option<Root>,
+    /// Keys of the nodes to be exported together with an indicator whether a node is a leaf. Keys
+    /// have node versions from the exported tree, which may be lesser than the exported version.
+    pending_nodes: Vec<(NodeKey, bool)>,
+}
+
+impl<DB: Database> TreeSnapshotExporter<DB> {
+    /// Creates an exporter for the specified tree `version`. `chunk_size` specifies the maximum number
+    /// of nodes in a single exported chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version is missing or cannot be read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn new(db: DB, version: u64, chunk_size: usize) -> Result<Self, TreeSnapshotError> {
+        assert!(chunk_size > 0, "Snapshot chunk size must be positive");
+
+        let Some(root) = db.try_root(version)? else {
+            let manifest = db.try_manifest()?;
+            let version_count = manifest.map_or(0, |manifest| manifest.version_count);
+            return Err(NoVersionError {
+                missing_version: version,
+                version_count,
+            }
+            .into());
+        };
+
+        let mut this = Self {
+            db,
+            version,
+            chunk_size,
+            root: None,
+            pending_nodes: vec![],
+        };
+        this.root = Some(match root {
+            Root::Empty => Root::Empty,
+            Root::Filled { leaf_count, node } => Root::Filled {
+                leaf_count,
+                node: this.enqueue_children(Nibbles::EMPTY, node),
+            },
+        });
+        Ok(this)
+    }
+
+    /// Returns the exported tree version.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Adds children of the provided node to the export queue and re-versions child references
+    /// in the node to the exported version.
+    fn enqueue_children(&mut self, nibbles: Nibbles, mut node: Node) -> Node {
+        if let Node::Internal(internal_node) = &mut node {
+            let children: Vec<_> = internal_node
+                .children()
+                .map(|(nibble, child_ref)| {
+                    let child_nibbles = nibbles.push(nibble).unwrap();
+                    // ^ `unwrap()` is safe: internal nodes cannot be located at the maximum depth
+                    (
+                        child_nibbles.with_version(child_ref.version),
+                        child_ref.is_leaf,
+                    )
+                })
+                .collect();
+            // Reverse children so that they are exported in the order of increasing nibbles.
+            self.pending_nodes.extend(children.into_iter().rev());
+
+            for child_ref in internal_node.child_refs_mut() {
+                child_ref.version = self.version;
+            }
+        }
+        node
+    }
+
+    /// Exports the next snapshot chunk. Returns `None` if all nodes are exported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tree node cannot be read, or if it is missing (e.g., because
+    /// the exported version was pruned).
+    pub fn next_chunk(&mut self) -> Result<Option<TreeSnapshotChunk>, TreeSnapshotError> {
+        let root = self.root.take();
+        if root.is_none() && self.pending_nodes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut node_count = usize::from(root.is_some());
+        let mut nodes = vec![];
+        while node_count < self.chunk_size {
+            let Some((key, is_leaf)) = self.pending_nodes.pop() else {
+                break;
+            };
+            let node = self
+                .db
+                .try_tree_node(&key, is_leaf)?
+                .ok_or(TreeSnapshotError::MissingNode(key))?;
+            let node = self.enqueue_children(key.nibbles, node);
+            nodes.push((key.nibbles, node));
+            node_count += 1;
+        }
+        Ok(Some(TreeSnapshotChunk { root, nodes }))
+    }
+}
+
+/// Importer of a tree snapshot exported by [`TreeSnapshotExporter`] into an empty database.
+///
+/// Until the import is [finalized](Self::finalize()), the tree is marked as being recovered,
+/// so it cannot be accessed using ordinary [`MerkleTree`](crate::MerkleTree) APIs.
+#[derive(Debug)]
+pub struct TreeSnapshotImporter<DB, H = Blake2Hasher> {
+    db: DB,
+    hasher: H,
+    manifest: Manifest,
+    version: u64,
+}
+
+impl<DB: Database> TreeSnapshotImporter<DB> {
+    /// Creates a snapshot importer with the default Blake2 hasher.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same situations as [`Self::with_hasher()`].
+    pub fn new(db: DB, version: u64) -> Self {
+        Self::with_hasher(db, version, Blake2Hasher)
+    }
+}
+
+impl<DB: Database, H: HashTree> TreeSnapshotImporter<DB, H> {
+    /// Creates a snapshot importer with the specified hasher. `version` is the version of the imported snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the database is not empty.
+    pub fn with_hasher(mut db: DB, version: u64, hasher: H) -> Self {
+        let is_empty = db
+            .manifest()
+            .map_or(true, |manifest| manifest.version_count == 0);
+        assert!(
+            is_empty,
+            "Tree snapshot can only be imported into an empty database"
+        );
+
+        let mut manifest = Manifest::new(version + 1, &hasher);
+        if let Some(tags) = &mut manifest.tags {
+            tags.is_recovering = true;
+        }
+        db.apply_patch(PatchSet::from_manifest(manifest.clone()));
+        Self {
+            db,
+            hasher,
+            manifest,
+            version,
+        }
+    }
+
+    /// Returns the version of the imported snapshot.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Imports the next snapshot chunk. Chunks must be imported in the same order as they were exported.
+    pub fn import_chunk(&mut self, chunk: TreeSnapshotChunk) {
+        let nodes = chunk
+            .nodes
+            .into_iter()
+            .map(|(nibbles, node)| (nibbles.with_version(self.version), node));
+        let patch = PatchSet::for_snapshot_chunk(
+            self.manifest.clone(),
+            self.version,
+            chunk.root,
+            nodes.collect(),
+        );
+        self.db.apply_patch(patch);
+    }
+
+    /// Returns the root hash of the imported tree, or `None` if the tree root is not imported yet.
+    pub fn root_hash(&self) -> Option<ValueHash> {
+        Some(match self.db.root(self.version)? {
+            Root::Empty => self.hasher.empty_tree_hash(),
+            Root::Filled { node, .. } => node.hash(&mut HasherWithStats::new(&self.hasher), 0),
+        })
+    }
+
+    /// Finalizes the import, allowing to access the tree using ordinary [`MerkleTree`](crate::MerkleTree) APIs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree root was not imported.
+    pub fn finalize(mut self) -> Result<DB, TreeSnapshotError> {
+        if self.db.try_root(self.version)?.is_none() {
+            return Err(TreeSnapshotError::MissingRoot);
+        }
+        if let Some(tags) = &mut self.manifest.tags {
+            tags.is_recovering = false;
+        }
+        self.db.apply_patch(PatchSet::from_manifest(self.manifest));
+        Ok(self.db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{types::TreeEntry, Key, MerkleTree};
+
+    fn create_tree(entry_count: u64) -> MerkleTree<PatchSet> {
+        let entries = (1..=entry_count).map(|i| {
+            let key = Key::from(i * 0x1_0000_0001);
+            TreeEntry::new(key, i, ValueHash::from_low_u64_be(i))
+        });
+        let entries: Vec<_> = entries.collect();
+
+        let mut tree = MerkleTree::new(PatchSet::default());
+        for chunk in entries.chunks(10) {
+            tree.extend(chunk.to_vec());
+        }
+        tree
+    }
+
+    fn export_chunks(db: &mut PatchSet, version: u64, chunk_size: usize) -> Vec<TreeSnapshotChunk> {
+        let mut exporter = TreeSnapshotExporter::new(db, version, chunk_size).unwrap();
+        let mut chunks = vec![];
+        while let Some(chunk) = exporter.next_chunk().unwrap() {
+            assert!(chunk.node_count() <= chunk_size);
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    #[test]
+    fn exporting_and_importing_snapshot() {
+        let mut tree = create_tree(50);
+        let version = tree.latest_version().unwrap();
+        let chunks = export_chunks(&mut tree.db, version, 7);
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].root.is_some());
+        assert!(chunks[1..].iter().all(|chunk| chunk.root.is_none()));
+
+        let mut importer = TreeSnapshotImporter::new(PatchSet::default(), version);
+        for chunk in chunks {
+            let chunk = TreeSnapshotChunk::deserialize(&chunk.serialize()).unwrap();
+            importer.import_chunk(chunk);
+        }
+        assert_eq!(importer.root_hash(), Some(tree.latest_root_hash()));
+
+        let imported_tree = MerkleTree::new(importer.finalize().unwrap());
+        assert_eq!(imported_tree.latest_version(), Some(version));
+        assert_eq!(imported_tree.latest_root_hash(), tree.latest_root_hash());
+        imported_tree.verify_consistency(version, true).unwrap();
+    }
+
+    #[test]
+    fn exporting_empty_tree() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        tree.extend(vec![]);
+        let chunks = export_chunks(&mut tree.db, 0, 10);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].root, Some(Root::Empty));
+
+        let mut importer = TreeSnapshotImporter::new(PatchSet::default(), 0);
+        importer.import_chunk(chunks.into_iter().next().unwrap());
+        let imported_tree = MerkleTree::new(importer.finalize().unwrap());
+        assert_eq!(
+            imported_tree.latest_root_hash(),
+            Blake2Hasher.empty_tree_hash()
+        );
+    }
+
+    #[test]
+    fn exporting_missing_version() {
+        let mut tree = create_tree(20);
+        let err = TreeSnapshotExporter::new(&mut tree.db, 5, 10).unwrap_err();
+        assert_matches!(
+            err,
+            TreeSnapshotError::NoVersion(NoVersionError {
+                missing_version: 5,
+                version_count: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn finalizing_import_without_root() {
+        let importer = TreeSnapshotImporter::new(PatchSet::default(), 3);
+        let err = importer.finalize().unwrap_err();
+        assert_matches!(err, TreeSnapshotError::MissingRoot);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tree snapshot can only be imported into an empty database")]
+    fn importing_into_non_empty_tree() {
+        let tree = create_tree(10);
+        TreeSnapshotImporter::new(tree.db, 3);
+    }
+
+    #[test]
+    fn deserializing_malformed_chunk() {
+        let mut tree = create_tree(10);
+        let chunk = export_chunks(&mut tree.db, 0, 100).pop().unwrap();
+        let bytes = chunk.serialize();
+
+        let err = TreeSnapshotChunk::deserialize(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(err.to_string().contains("tree snapshot chunk"), "{err}");
+        let mut invalid_bytes = bytes;
+        invalid_bytes[0] = 2;
+        let err = TreeSnapshotChunk::deserialize(&invalid_bytes).unwrap_err();
+        assert!(err.to_string().contains("invalid tag"), "{err}");
+    }
+}
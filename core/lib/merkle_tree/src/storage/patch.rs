@@ -32,7 +32,9 @@ pub(super) struct PartialPatchSet {
 
 impl PartialPatchSet {
     pub fn merge(&mut self, other: Self) {
-        self.root = other.root;
+        if other.root.is_some() {
+            self.root = other.root;
+        }
         self.nodes.extend(other.nodes);
     }
 }
@@ -74,6 +76,26 @@ impl PatchSet {
         )
     }
 
+    /// Creates a patch adding nodes to an existing tree `version`. Used when importing tree snapshots;
+    /// unlike other patches, the `root` may be missing, and there are no stale keys.
+    pub(crate) fn for_snapshot_chunk(
+        manifest: Manifest,
+        version: u64,
+        root: Option<Root>,
+        nodes: HashMap<NodeKey, Node>,
+    ) -> Self {
+        debug_assert_eq!(manifest.version_count, version + 1);
+        debug_assert!(nodes.keys().all(|key| key.version == version));
+
+        let partial_patch = PartialPatchSet { root, nodes };
+        Self {
+            manifest,
+            patches_by_version: HashMap::from([(version, partial_patch)]),
+            updated_version: Some(version),
+            stale_keys_by_version: HashMap::new(),
+        }
+    }
+
     pub(super) fn new(
         manifest: Manifest,
         version: u64,
@@ -1,9 +1,11 @@
 //! RocksDB implementation of [`Database`].
 
-use std::path::Path;
+use std::{path::Path, sync::Arc};
 
 use rayon::prelude::*;
-use zksync_storage::{db::NamedColumnFamily, rocksdb, rocksdb::DBPinnableSlice, RocksDB};
+use zksync_storage::{
+    db::NamedColumnFamily, rocksdb, rocksdb::DBPinnableSlice, RocksDB, RocksDBSnapshot,
+};
 
 use crate::{
     errors::{DeserializeError, ErrorContext},
@@ -51,11 +53,19 @@ impl NamedColumnFamily for MerkleTreeColumnFamily {
 /// The intended usage of cloning is to have no more than one component of each kind modifying RocksDB
 /// (i.e., no more than one `MerkleTree` and no more than one `MerkleTreePruner`).
 ///
+///
+/// # Snapshots
+///
+/// A wrapper reading from a point-in-time RocksDB snapshot can be obtained using [`Self::snapshot()`].
+/// Such a wrapper can be used to serve consistent reads while the tree is being updated. It must not be used
+/// for writes.
+///
 /// [`MerkleTree`]: crate::MerkleTree
 /// [`MerkleTreePruner`]: crate::MerkleTreePruner
 #[derive(Debug, Clone)]
 pub struct RocksDBWrapper {
     db: RocksDB<MerkleTreeColumnFamily>,
+    snapshot: Option<Arc<RocksDBSnapshot<MerkleTreeColumnFamily>>>,
     multi_get_chunk_size: usize,
 }
 
@@ -91,10 +101,24 @@ impl RocksDBWrapper {
         self.multi_get_chunk_size = chunk_size;
     }
 
+    /// Returns a wrapper reading from a snapshot of RocksDB taken at the moment of the call.
+    /// Writes to the database performed after this point are not visible via the returned wrapper.
+    pub fn snapshot(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            snapshot: Some(Arc::new(self.db.snapshot())),
+            multi_get_chunk_size: self.multi_get_chunk_size,
+        }
+    }
+
     fn raw_node(&self, key: &[u8]) -> Option<Vec<u8>> {
-        self.db
-            .get_cf(MerkleTreeColumnFamily::Tree, key)
-            .expect("Failed reading from RocksDB")
+        let tree_cf = MerkleTreeColumnFamily::Tree;
+        let result = if let Some(snapshot) = &self.snapshot {
+            snapshot.get_cf(tree_cf, key)
+        } else {
+            self.db.get_cf(tree_cf, key)
+        };
+        result.expect("Failed reading from RocksDB")
     }
 
     fn raw_nodes(&self, keys: &NodeKeys) -> Vec<Option<DBPinnableSlice<'_>>> {
@@ -103,7 +127,12 @@ impl RocksDBWrapper {
         keys.par_chunks(self.multi_get_chunk_size)
             .map(|chunk| {
                 let keys = chunk.iter().map(|(key, _)| key.to_db_key());
-                let results = self.db.multi_get_cf(MerkleTreeColumnFamily::Tree, keys);
+                let tree_cf = MerkleTreeColumnFamily::Tree;
+                let results = if let Some(snapshot) = &self.snapshot {
+                    snapshot.multi_get_cf(tree_cf, keys)
+                } else {
+                    self.db.multi_get_cf(tree_cf, keys)
+                };
                 results
                     .into_iter()
                     .map(|result| result.expect("Failed reading from RocksDB"))
@@ -143,6 +172,7 @@ impl From<RocksDB<MerkleTreeColumnFamily>> for RocksDBWrapper {
     fn from(db: RocksDB<MerkleTreeColumnFamily>) -> Self {
         Self {
             db,
+            snapshot: None,
             multi_get_chunk_size: usize::MAX,
         }
     }
@@ -192,6 +222,10 @@ impl Database for RocksDBWrapper {
     }
 
     fn apply_patch(&mut self, patch: PatchSet) {
+        assert!(
+            self.snapshot.is_none(),
+            "Cannot write to a RocksDB snapshot"
+        );
         let tree_cf = MerkleTreeColumnFamily::Tree;
         let mut write_batch = self.db.new_write_batch();
         let mut node_bytes = Vec::with_capacity(128);
@@ -269,6 +303,10 @@ impl PruneDatabase for RocksDBWrapper {
     }
 
     fn prune(&mut self, patch: PrunePatchSet) {
+        assert!(
+            self.snapshot.is_none(),
+            "Cannot write to a RocksDB snapshot"
+        );
         let mut write_batch = self.db.new_write_batch();
 
         let tree_cf = MerkleTreeColumnFamily::Tree;
@@ -340,6 +378,28 @@ mod tests {
         assert_contains_exactly_keys(&db, &expected_keys);
     }
 
+    #[test]
+    fn reading_from_snapshot() {
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path()).unwrap();
+        let root = Root::new(2, Node::Internal(InternalNode::default()));
+        let nodes = generate_nodes(0, &[1, 2]);
+        db.apply_patch(create_patch(0, root, nodes));
+
+        let snapshot = db.snapshot();
+        let root = Root::new(4, Node::Internal(InternalNode::default()));
+        let nodes = generate_nodes(1, &[3, 4]);
+        let node_keys: Vec<_> = nodes.keys().map(|key| (*key, true)).collect();
+        db.apply_patch(create_patch(1, root, nodes));
+
+        assert_eq!(db.manifest().unwrap().version_count, 2);
+        assert_eq!(snapshot.manifest().unwrap().version_count, 1);
+        assert!(db.root(1).is_some());
+        assert!(snapshot.root(1).is_none());
+        assert!(db.tree_nodes(&node_keys).iter().all(Option::is_some));
+        assert!(snapshot.tree_nodes(&node_keys).iter().all(Option::is_none));
+    }
+
     fn assert_contains_exactly_keys(db: &RocksDBWrapper, expected_keys: &HashSet<NodeKey>) {
         let cf = MerkleTreeColumnFamily::Tree;
         let actual_keys: HashSet<_> = db
@@ -15,7 +15,7 @@ use crate::{
 const LEB128_SIZE_ESTIMATE: usize = 3;
 
 impl LeafNode {
-    pub(super) fn deserialize(bytes: &[u8]) -> Result<Self, DeserializeError> {
+    pub(crate) fn deserialize(bytes: &[u8]) -> Result<Self, DeserializeError> {
         if bytes.len() < KEY_SIZE + HASH_SIZE {
             return Err(DeserializeErrorKind::UnexpectedEof.into());
         }
@@ -105,7 +105,7 @@ impl ChildRef {
 }
 
 impl InternalNode {
-    pub(super) fn deserialize(bytes: &[u8]) -> Result<Self, DeserializeError> {
+    pub(crate) fn deserialize(bytes: &[u8]) -> Result<Self, DeserializeError> {
         if bytes.len() < 4 {
             let err = DeserializeErrorKind::UnexpectedEof;
             return Err(err.with_context(ErrorContext::ChildrenMask));
@@ -162,7 +162,7 @@ impl InternalNode {
 }
 
 impl Root {
-    pub(super) fn deserialize(mut bytes: &[u8]) -> Result<Self, DeserializeError> {
+    pub(crate) fn deserialize(mut bytes: &[u8]) -> Result<Self, DeserializeError> {
         let leaf_count = leb128::read::unsigned(&mut bytes).map_err(|err| {
             DeserializeErrorKind::Leb128(err).with_context(ErrorContext::LeafCount)
         })?;
@@ -174,7 +174,7 @@ impl Root {
         Ok(Self::new(leaf_count, node))
     }
 
-    pub(super) fn serialize(&self, buffer: &mut Vec<u8>) {
+    pub(crate) fn serialize(&self, buffer: &mut Vec<u8>) {
         match self {
             Self::Empty => {
                 leb128::write::unsigned(buffer, 0 /* leaf_count */).unwrap();
@@ -188,7 +188,7 @@ impl Root {
 }
 
 impl Node {
-    pub(super) fn serialize(&self, buffer: &mut Vec<u8>) {
+    pub(crate) fn serialize(&self, buffer: &mut Vec<u8>) {
         match self {
             Self::Internal(node) => node.serialize(buffer),
             Self::Leaf(leaf) => leaf.serialize(buffer),
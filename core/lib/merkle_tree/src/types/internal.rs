@@ -451,7 +451,6 @@ impl InternalNode {
         self.children.values()
     }
 
-    #[cfg(test)]
     pub(crate) fn child_refs_mut(&mut self) -> impl Iterator<Item = &mut ChildRef> + '_ {
         self.children.values_mut()
     }
@@ -2,6 +2,8 @@
 
 use std::{iter::Peekable, vec};
 
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
 use crate::types::Key;
 
 /// Map with keys in the range `0..16`.
@@ -119,6 +121,19 @@ pub(crate) fn find_diverging_bit(lhs: Key, rhs: Key) -> usize {
     diff.leading_zeros() as usize
 }
 
+/// Creates a dedicated `rayon` thread pool for tree operations. If `thread_count` is 0,
+/// the default number of threads will be used; see `rayon` docs for details.
+pub(crate) fn create_thread_pool(
+    thread_name_prefix: &'static str,
+    thread_count: usize,
+) -> ThreadPool {
+    ThreadPoolBuilder::new()
+        .thread_name(move |idx| format!("{thread_name_prefix}-{idx}"))
+        .num_threads(thread_count)
+        .build()
+        .expect("failed initializing `rayon` thread pool")
+}
+
 /// Merges several vectors of items into a single vector, where each original vector
 /// and the resulting vector are ordered by the item index (the first element of the tuple
 /// in the original vectors).
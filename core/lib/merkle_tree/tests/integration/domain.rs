@@ -110,6 +110,51 @@ fn basic_workflow_multiblock() {
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(12));
 }
 
+#[test]
+fn snapshot_reader_is_unaffected_by_tree_updates() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let (first_logs, second_logs) = logs.split_at(50);
+
+    let db = RocksDB::new(temp_dir.as_ref()).unwrap();
+    let mut tree = ZkSyncTree::new_lightweight(db.into());
+    let first_metadata = tree.process_l1_batch(first_logs);
+    let second_metadata = tree.process_l1_batch(second_logs);
+    tree.save();
+
+    let reader = tree.reader().snapshot();
+    assert_eq!(reader.next_l1_batch_number(), L1BatchNumber(2));
+    assert_eq!(reader.root_hash(), second_metadata.root_hash);
+
+    // Revert the tree and write a batch with different contents.
+    tree.revert_logs(L1BatchNumber(0));
+    tree.save();
+    tree.process_l1_batch(&second_logs[..10]);
+    tree.save();
+    let live_reader = tree.reader();
+    assert_ne!(live_reader.root_hash(), second_metadata.root_hash);
+
+    assert_eq!(reader.next_l1_batch_number(), L1BatchNumber(2));
+    assert_eq!(reader.root_hash(), second_metadata.root_hash);
+    assert_eq!(
+        reader.l1_batch_root_hash(L1BatchNumber(0)),
+        Some(first_metadata.root_hash)
+    );
+    assert_eq!(
+        reader.l1_batch_root_hash(L1BatchNumber(1)),
+        Some(second_metadata.root_hash)
+    );
+    let keys: Vec<_> = second_logs
+        .iter()
+        .map(|instruction| match instruction {
+            TreeInstruction::Write(entry) => entry.key.hashed_key_u256(),
+            TreeInstruction::Read(_) => unreachable!(),
+        })
+        .collect();
+    let entries = reader.entries_with_proofs(L1BatchNumber(1), &keys).unwrap();
+    assert!(entries.iter().all(|entry| !entry.base.is_empty()));
+}
+
 #[test]
 fn filtering_out_no_op_writes() {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
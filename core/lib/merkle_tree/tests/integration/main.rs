@@ -5,3 +5,4 @@ mod consistency;
 mod domain;
 mod merkle_tree;
 mod recovery;
+mod snapshot;
@@ -37,6 +37,21 @@ fn recovery_basics() {
     tree.verify_consistency(recovered_version, true).unwrap();
 }
 
+#[test_casing(3, [1, 2, 4])]
+fn recovery_with_dedicated_thread_pool(thread_count: usize) {
+    let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
+    let recovered_version = 123;
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), recovered_version);
+    recovery.use_dedicated_thread_pool(thread_count);
+    for chunk in kvs.chunks(17) {
+        recovery.extend_random(chunk.to_vec());
+    }
+    assert_eq!(recovery.root_hash(), *expected_hash);
+
+    let tree = MerkleTree::new(recovery.finalize());
+    tree.verify_consistency(recovered_version, true).unwrap();
+}
+
 fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk_size: usize) {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut recovery_entries = kvs.clone();
@@ -0,0 +1,65 @@
+//! Tests for exporting and importing tree snapshots.
+
+use test_casing::test_casing;
+use zksync_merkle_tree::{
+    snapshot::{TreeSnapshotChunk, TreeSnapshotExporter, TreeSnapshotImporter},
+    Database, MerkleTree, PatchSet,
+};
+
+use crate::common::{generate_key_value_pairs, ENTRIES_AND_HASH};
+
+fn test_snapshot_roundtrip(
+    mut source_db: impl Database,
+    target_db: impl Database,
+    chunk_size: usize,
+) {
+    let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
+    let mut tree = MerkleTree::new(&mut source_db);
+    for chunk in kvs.chunks(13) {
+        tree.extend(chunk.to_vec());
+    }
+    let snapshot_version = tree.latest_version().unwrap();
+    assert_eq!(tree.latest_root_hash(), *expected_hash);
+    // Update the tree after the snapshot version; this should not influence the exported snapshot.
+    tree.extend(generate_key_value_pairs(1_000..1_010));
+
+    let mut exporter = TreeSnapshotExporter::new(source_db, snapshot_version, chunk_size).unwrap();
+    let mut importer = TreeSnapshotImporter::new(target_db, snapshot_version);
+    while let Some(chunk) = exporter.next_chunk().unwrap() {
+        let chunk = TreeSnapshotChunk::deserialize(&chunk.serialize()).unwrap();
+        importer.import_chunk(chunk);
+    }
+    assert_eq!(importer.root_hash(), Some(*expected_hash));
+
+    let imported_tree = MerkleTree::new(importer.finalize().unwrap());
+    assert_eq!(imported_tree.latest_version(), Some(snapshot_version));
+    assert_eq!(imported_tree.latest_root_hash(), *expected_hash);
+    imported_tree
+        .verify_consistency(snapshot_version, true)
+        .unwrap();
+
+    let keys: Vec<_> = kvs.iter().map(|entry| entry.key).collect();
+    let imported_entries = imported_tree.entries(snapshot_version, &keys).unwrap();
+    assert_eq!(imported_entries, *kvs);
+}
+
+#[test_casing(4, [1, 10, 100, 10_000])]
+fn snapshot_roundtrip(chunk_size: usize) {
+    test_snapshot_roundtrip(PatchSet::default(), PatchSet::default(), chunk_size);
+}
+
+mod rocksdb {
+    use tempfile::TempDir;
+    use zksync_merkle_tree::RocksDBWrapper;
+
+    use super::*;
+
+    #[test_casing(4, [1, 10, 100, 10_000])]
+    fn snapshot_roundtrip(chunk_size: usize) {
+        let source_dir = TempDir::new().unwrap();
+        let source_db = RocksDBWrapper::new(source_dir.path()).unwrap();
+        let target_dir = TempDir::new().unwrap();
+        let target_db = RocksDBWrapper::new(target_dir.path()).unwrap();
+        test_snapshot_roundtrip(source_db, target_db, chunk_size);
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use zksync_utils::error::{ClassifyError, ErrorKind};
 
 /// Errors related to bytecode compression.
 #[derive(Debug, Error)]
@@ -6,3 +7,9 @@ pub enum BytecodeCompressionError {
     #[error("Bytecode compression failed")]
     BytecodeCompressionFailed,
 }
+
+impl ClassifyError for BytecodeCompressionError {
+    fn error_kind(&self) -> ErrorKind {
+        ErrorKind::Fatal
+    }
+}
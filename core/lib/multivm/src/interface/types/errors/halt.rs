@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use zksync_utils::error::{ClassifyError, ErrorKind};
+
 use super::VmRevertReason;
 
 /// Structure for non-contract errors from the Virtual Machine (EVM).
@@ -119,3 +121,10 @@ impl Display for Halt {
         }
     }
 }
+
+/// VM execution is deterministic, so re-executing a halted transaction never helps.
+impl ClassifyError for Halt {
+    fn error_kind(&self) -> ErrorKind {
+        ErrorKind::Fatal
+    }
+}
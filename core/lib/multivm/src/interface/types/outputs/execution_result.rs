@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use zksync_system_constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
@@ -33,6 +35,40 @@ impl VmExecutionLogs {
     pub fn total_l2_to_l1_logs_count(&self) -> usize {
         self.user_l2_to_l1_logs.len() + self.system_l2_to_l1_logs.len()
     }
+
+    /// Returns the number of storage slots written during the execution which have the same values
+    /// after the execution as before it. Slots all writes to which were rolled back are not counted.
+    pub fn storage_slots_reset_count(&self) -> usize {
+        // Maps a slot to its initial value, final value and the number of writes that are not rolled back.
+        let mut slot_values = HashMap::new();
+        for log in &self.storage_logs {
+            let query = &log.log_query;
+            if !query.rw_flag {
+                continue;
+            }
+            // Rollback queries undo the write, i.e., restore `read_value` of the query.
+            let (value_before, value_after) = if query.rollback {
+                (query.written_value, query.read_value)
+            } else {
+                (query.read_value, query.written_value)
+            };
+            let (_, final_value, write_count) = slot_values
+                .entry((query.address, query.key))
+                .or_insert((value_before, value_after, 0_usize));
+            *final_value = value_after;
+            if query.rollback {
+                *write_count = write_count.saturating_sub(1);
+            } else {
+                *write_count += 1;
+            }
+        }
+        slot_values
+            .values()
+            .filter(|(initial_value, final_value, write_count)| {
+                *write_count > 0 && initial_value == final_value
+            })
+            .count()
+    }
 }
 
 /// Result and logs of the VM execution.
@@ -102,6 +138,95 @@ impl VmExecutionResultAndLogs {
             computational_gas_used: self.statistics.computational_gas_used,
             pubdata_published: self.statistics.pubdata_published,
             circuit_statistic: self.statistics.circuit_statistic,
+            storage_slots_reset: self.logs.storage_slots_reset_count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        zk_evm_types::{LogQuery, Timestamp},
+        StorageLogQueryType, H160, U256,
+    };
+
+    use super::*;
+
+    fn write_query(
+        key: u64,
+        read_value: u64,
+        written_value: u64,
+        rollback: bool,
+    ) -> StorageLogQuery {
+        StorageLogQuery {
+            log_query: LogQuery {
+                timestamp: Timestamp(0),
+                tx_number_in_block: 0,
+                aux_byte: 0,
+                shard_id: 0,
+                address: H160::repeat_byte(1),
+                key: U256::from(key),
+                read_value: U256::from(read_value),
+                written_value: U256::from(written_value),
+                rw_flag: true,
+                rollback,
+                is_service: false,
+            },
+            log_type: StorageLogQueryType::RepeatedWrite,
         }
     }
+
+    fn reset_count(storage_logs: Vec<StorageLogQuery>) -> usize {
+        VmExecutionLogs {
+            storage_logs,
+            ..VmExecutionLogs::default()
+        }
+        .storage_slots_reset_count()
+    }
+
+    #[test]
+    fn counting_reset_storage_slots() {
+        // Slot written and then reset to the original value.
+        assert_eq!(
+            reset_count(vec![
+                write_query(0, 1, 2, false),
+                write_query(0, 2, 1, false)
+            ]),
+            1
+        );
+        // Slot changed by the execution.
+        assert_eq!(
+            reset_count(vec![
+                write_query(0, 1, 2, false),
+                write_query(0, 2, 3, false)
+            ]),
+            0
+        );
+        // Write to the original value.
+        assert_eq!(reset_count(vec![write_query(0, 1, 1, false)]), 1);
+    }
+
+    #[test]
+    fn rolled_back_writes_are_not_counted() {
+        let logs = vec![write_query(0, 1, 2, false), write_query(0, 1, 2, true)];
+        assert_eq!(reset_count(logs), 0);
+
+        // Only the second write is rolled back, so the slot is reset by the first write pair.
+        let logs = vec![
+            write_query(0, 1, 2, false),
+            write_query(0, 2, 1, false),
+            write_query(0, 1, 3, false),
+            write_query(0, 1, 3, true),
+        ];
+        assert_eq!(reset_count(logs), 1);
+
+        // Rolled-back writes to different slots.
+        let logs = vec![
+            write_query(0, 1, 2, false),
+            write_query(1, 5, 6, false),
+            write_query(1, 5, 6, true),
+            write_query(0, 1, 2, true),
+        ];
+        assert_eq!(reset_count(logs), 0);
+    }
 }
@@ -0,0 +1,45 @@
+use std::time::Instant;
+
+use crate::glue::tracers::IntoOldVmTracer;
+
+pub mod vm_1_4_1;
+pub mod vm_1_4_2;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Tracer stopping the VM execution once the specified deadline has passed.
+///
+/// Not supported for VM versions preceding `vm_virtual_blocks`; for them, the tracer is a no-op.
+#[derive(Debug, Clone)]
+pub struct ExecutionDeadline {
+    deadline: Instant,
+    cycles: u32,
+    is_exceeded: bool,
+}
+
+impl ExecutionDeadline {
+    /// Reason of the VM halt caused by this tracer.
+    pub const HALT_REASON: &'static str = "Execution deadline exceeded";
+    /// Number of VM cycles between deadline checks. Getting the current time on each cycle would be wasteful.
+    const CHECK_INTERVAL: u32 = 1_024;
+
+    pub fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            cycles: 0,
+            is_exceeded: false,
+        }
+    }
+
+    fn check_after_cycle(&mut self) -> bool {
+        self.cycles = self.cycles.wrapping_add(1);
+        if !self.is_exceeded && self.cycles % Self::CHECK_INTERVAL == 0 {
+            self.is_exceeded = Instant::now() >= self.deadline;
+        }
+        self.is_exceeded
+    }
+}
+
+impl IntoOldVmTracer for ExecutionDeadline {}
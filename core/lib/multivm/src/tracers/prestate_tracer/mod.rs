@@ -8,8 +8,11 @@ use zksync_types::{
 };
 use zksync_utils::{address_to_h256, h256_to_u256};
 
+use crate::glue::tracers::IntoOldVmTracer;
+
 pub mod vm_1_4_1;
 pub mod vm_1_4_2;
+pub mod vm_boojum_integration;
 pub mod vm_latest;
 pub mod vm_refunds_enhancement;
 pub mod vm_virtual_blocks;
@@ -45,7 +48,8 @@ impl fmt::Display for Account {
     }
 }
 
-type State = HashMap<Address, Account>;
+/// State of accounts touched by a transaction.
+pub type State = HashMap<Address, Account>;
 
 #[derive(Debug, Clone)]
 pub struct PrestateTracer {
@@ -71,6 +75,8 @@ pub struct PrestateTracerConfig {
     diff_mode: bool,
 }
 
+impl IntoOldVmTracer for PrestateTracer {}
+
 pub fn process_modified_storage_keys<S>(
     prestate: State,
     storage: &StoragePtr<S>,
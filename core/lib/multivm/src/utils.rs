@@ -1,4 +1,5 @@
 use zksync_types::{
+    circuit::CircuitCapacity,
     fee_model::{BatchFeeInput, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput},
     VmVersion, U256,
 };
@@ -321,6 +322,25 @@ pub fn get_max_gas_per_pubdata_byte(version: VmVersion) -> u64 {
     }
 }
 
+/// Returns the capacity of base layer circuits assumed by the specified VM version when estimating
+/// the number of circuits required to prove transactions, or `None` if the version doesn't estimate it.
+pub fn get_circuit_capacity(version: VmVersion) -> Option<CircuitCapacity> {
+    match version {
+        VmVersion::M5WithRefunds
+        | VmVersion::M5WithoutRefunds
+        | VmVersion::M6Initial
+        | VmVersion::M6BugWithCompressionFixed
+        | VmVersion::Vm1_3_2
+        | VmVersion::VmVirtualBlocks
+        | VmVersion::VmVirtualBlocksRefundsEnhancement => None,
+        VmVersion::VmBoojumIntegration => {
+            Some(crate::vm_boojum_integration::tracers::circuits_capacity::CIRCUIT_CAPACITY)
+        }
+        VmVersion::Vm1_4_1 => Some(crate::vm_1_4_1::tracers::circuits_capacity::CIRCUIT_CAPACITY),
+        VmVersion::Vm1_4_2 => Some(crate::vm_1_4_2::tracers::circuits_capacity::CIRCUIT_CAPACITY),
+    }
+}
+
 pub fn get_used_bootloader_memory_bytes(version: VmVersion) -> usize {
     match version {
         VmVersion::M5WithRefunds | VmVersion::M5WithoutRefunds => {
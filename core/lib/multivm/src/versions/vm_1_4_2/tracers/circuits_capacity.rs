@@ -1,5 +1,5 @@
 use circuit_sequencer_api_1_4_2::{geometry_config::get_geometry_config, toolset::GeometryConfig};
-use zksync_types::circuit::{CircuitCycleStatistic, CircuitStatistic};
+use zksync_types::circuit::{CircuitCapacity, CircuitCycleStatistic, CircuitStatistic};
 
 // "Rich addressing" opcodes are opcodes that can write their return value/read the input onto the stack
 // and so take 1-2 RAM permutations more than an average opcode.
@@ -41,27 +41,21 @@ pub(crate) const PRECOMPILE_LOG_DEMUXER_CYCLES: u32 = 1;
 
 const GEOMETRY_CONFIG: GeometryConfig = get_geometry_config();
 
+/// Capacity of base layer circuits assumed by this VM version.
+pub(crate) const CIRCUIT_CAPACITY: CircuitCapacity = CircuitCapacity {
+    main_vm: GEOMETRY_CONFIG.cycles_per_vm_snapshot,
+    ram_permutation: GEOMETRY_CONFIG.cycles_per_ram_permutation,
+    storage_application: GEOMETRY_CONFIG.cycles_per_storage_application,
+    storage_sorter: GEOMETRY_CONFIG.cycles_per_storage_sorter,
+    code_decommitter: GEOMETRY_CONFIG.cycles_per_code_decommitter,
+    code_decommitter_sorter: GEOMETRY_CONFIG.cycles_code_decommitter_sorter,
+    log_demuxer: GEOMETRY_CONFIG.cycles_per_log_demuxer,
+    events_sorter: GEOMETRY_CONFIG.cycles_per_events_or_l1_messages_sorter,
+    keccak256: GEOMETRY_CONFIG.cycles_per_keccak256_circuit,
+    ecrecover: GEOMETRY_CONFIG.cycles_per_ecrecover_circuit,
+    sha256: GEOMETRY_CONFIG.cycles_per_sha256_circuit,
+};
+
 pub(crate) fn circuit_statistic_from_cycles(cycles: CircuitCycleStatistic) -> CircuitStatistic {
-    CircuitStatistic {
-        main_vm: cycles.main_vm_cycles as f32 / GEOMETRY_CONFIG.cycles_per_vm_snapshot as f32,
-        ram_permutation: cycles.ram_permutation_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_ram_permutation as f32,
-        storage_application: cycles.storage_application_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_storage_application as f32,
-        storage_sorter: cycles.storage_sorter_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_storage_sorter as f32,
-        code_decommitter: cycles.code_decommitter_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_code_decommitter as f32,
-        code_decommitter_sorter: cycles.code_decommitter_sorter_cycles as f32
-            / GEOMETRY_CONFIG.cycles_code_decommitter_sorter as f32,
-        log_demuxer: cycles.log_demuxer_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_log_demuxer as f32,
-        events_sorter: cycles.events_sorter_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_events_or_l1_messages_sorter as f32,
-        keccak256: cycles.keccak256_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_keccak256_circuit as f32,
-        ecrecover: cycles.ecrecover_cycles as f32
-            / GEOMETRY_CONFIG.cycles_per_ecrecover_circuit as f32,
-        sha256: cycles.sha256_cycles as f32 / GEOMETRY_CONFIG.cycles_per_sha256_circuit as f32,
-    }
+    CIRCUIT_CAPACITY.circuit_statistic(&cycles)
 }
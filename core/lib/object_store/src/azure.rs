@@ -0,0 +1,283 @@
+//! Azure Blob Storage-based [`ObjectStore`] implementation.
+
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use azure_core::{
+    error::{Error as AzureError, ErrorKind as AzureErrorKind},
+    RetryOptions,
+};
+use azure_identity::ImdsManagedIdentityCredential;
+use azure_storage::StorageCredentials;
+use azure_storage_blobs::{
+    blob::{BlobBlockType, BlockList},
+    prelude::{AccessTier, BlockId, ClientBuilder, ContainerClient, RehydratePriority},
+};
+
+use crate::{
+    metrics::{LIFECYCLE_METRICS, REMOTE_STORE_METRICS},
+    multipart::{ChunkStream, Parts},
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+    resilience::is_transient_http_status,
+};
+
+/// Error codes returned by Azure for missing blobs / containers.
+const NOT_FOUND_ERROR_CODES: &[&str] = &["BlobNotFound", "ContainerNotFound"];
+/// Error code returned by Azure when reading a blob in the archive tier.
+const BLOB_ARCHIVED_ERROR_CODE: &str = "BlobArchived";
+/// Error code returned by Azure when changing the tier of a blob that is already being rehydrated.
+const BLOB_BEING_REHYDRATED_ERROR_CODE: &str = "BlobBeingRehydrated";
+/// Size of blocks in block-wise uploads.
+const BLOCK_SIZE: usize = 4 << 20;
+
+#[derive(Clone)]
+pub enum AzureBlobAuthMode {
+    /// Shared access signature (SAS) token granting access to the container.
+    SasToken(String),
+    /// Managed identity of the Azure VM / AKS pod the node is running on.
+    ManagedIdentity,
+}
+
+impl fmt::Debug for AzureBlobAuthMode {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Never output the token.
+            Self::SasToken(_) => formatter.write_str("SasToken(_)"),
+            Self::ManagedIdentity => formatter.write_str("ManagedIdentity"),
+        }
+    }
+}
+
+pub struct AzureBlobStorage {
+    account: String,
+    container: String,
+    /// Access tier (`Cool`, `Cold` or `Archive`) that archived objects are moved to.
+    cold_access_tier: Option<AccessTier>,
+    client: ContainerClient,
+}
+
+impl fmt::Debug for AzureBlobStorage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AzureBlobStorage")
+            .field("account", &self.account)
+            .field("container", &self.container)
+            .field("cold_access_tier", &self.cold_access_tier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureBlobStorage {
+    pub fn new(
+        auth_mode: AzureBlobAuthMode,
+        account: String,
+        container: String,
+        cold_storage_class: Option<String>,
+    ) -> Self {
+        let credentials = match auth_mode {
+            AzureBlobAuthMode::SasToken(token) => {
+                StorageCredentials::sas_token(token).expect("invalid Azure SAS token")
+            }
+            AzureBlobAuthMode::ManagedIdentity => {
+                let credential = ImdsManagedIdentityCredential::default();
+                StorageCredentials::token_credential(Arc::new(credential))
+            }
+        };
+        let cold_access_tier = cold_storage_class.map(|class| match class.as_str() {
+            "Cool" => AccessTier::Cool,
+            "Cold" => AccessTier::Cold,
+            "Archive" => AccessTier::Archive,
+            _ => panic!("unsupported Azure access tier for cold storage: `{class}`"),
+        });
+
+        // Requests are retried by the resilience layer wrapping the store.
+        let client = ClientBuilder::new(account.clone(), credentials)
+            .retry(RetryOptions::none())
+            .container_client(container.clone());
+        Self {
+            account,
+            container,
+            cold_access_tier,
+            client,
+        }
+    }
+
+    fn filename(bucket: Bucket, key: &str) -> String {
+        format!("{bucket}/{key}")
+    }
+
+    /// Requests rehydrating an archived blob. Returns `Ok(true)` if rehydration was started by this call.
+    async fn start_thawing(&self, filename: &str) -> Result<bool, ObjectStoreError> {
+        let response = self
+            .client
+            .blob_client(filename)
+            .set_blob_tier(AccessTier::Hot)
+            .rehydrate_priority(RehydratePriority::Standard)
+            .await;
+        match response {
+            Ok(_) => Ok(true),
+            Err(err) if error_code(&err) == Some(BLOB_BEING_REHYDRATED_ERROR_CODE) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn error_code(err: &AzureError) -> Option<&str> {
+    err.as_http_error()?.error_code()
+}
+
+impl From<AzureError> for ObjectStoreError {
+    fn from(err: AzureError) -> Self {
+        let is_not_found =
+            error_code(&err).map_or(false, |code| NOT_FOUND_ERROR_CODES.contains(&code));
+        let is_transient = match err.kind() {
+            AzureErrorKind::Io => true,
+            AzureErrorKind::HttpResponse { status, .. } => is_transient_http_status(*status as u16),
+            _ => false,
+        };
+
+        if is_not_found {
+            ObjectStoreError::KeyNotFound(err.into())
+        } else if is_transient {
+            ObjectStoreError::Transient(err.into())
+        } else {
+            ObjectStoreError::Other(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for AzureBlobStorage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = REMOTE_STORE_METRICS.start_fetch(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Fetching data from Azure for key {filename} from container {}",
+            self.container
+        );
+
+        let blob = match self.client.blob_client(&filename).get_content().await {
+            Ok(blob) => blob,
+            Err(err) if error_code(&err) == Some(BLOB_ARCHIVED_ERROR_CODE) => {
+                if self.start_thawing(&filename).await? {
+                    LIFECYCLE_METRICS.thawed_objects[&bucket.as_str()].inc();
+                }
+                let error_message = format!("key {key} in bucket {bucket} is being restored");
+                return Err(ObjectStoreError::Thawing(error_message.into()));
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from Azure for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(blob)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = REMOTE_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Storing data to Azure for key {filename} from container {}",
+            self.container
+        );
+
+        self.client
+            .blob_client(filename)
+            .put_block_blob(value)
+            .await?;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to Azure for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    // Azure doesn't have an explicit abort operation for block-wise uploads; uncommitted blocks
+    // are garbage-collected automatically.
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let mut parts = Parts::new(chunks, BLOCK_SIZE);
+        let mut part = parts.next().await?;
+        if part.is_last {
+            // The object is small enough to be uploaded in a single request.
+            return self.put_raw(bucket, key, part.data).await;
+        }
+
+        let store_latency = REMOTE_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Starting block-wise upload to Azure for key {filename} from container {}",
+            self.container
+        );
+        let blob_client = self.client.blob_client(filename);
+        let mut block_list = BlockList::default();
+        loop {
+            // All block IDs in a blob must have the same length.
+            let block_id = BlockId::new(format!("{:08}", block_list.blocks.len()));
+            blob_client.put_block(block_id.clone(), part.data).await?;
+            block_list
+                .blocks
+                .push(BlobBlockType::new_uncommitted(block_id));
+            if part.is_last {
+                break;
+            }
+            part = parts.next().await?;
+        }
+        blob_client.put_block_list(block_list).await?;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to Azure for key {key} from bucket {bucket} using block-wise upload and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Removing data from Azure for key {filename} from container {}",
+            self.container
+        );
+        self.client.blob_client(filename).delete().await?;
+        Ok(())
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let Some(access_tier) = &self.cold_access_tier else {
+            return Ok(());
+        };
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Moving object {filename} in container {} to access tier {access_tier:?}",
+            self.container
+        );
+
+        self.client
+            .blob_client(filename)
+            .set_blob_tier(access_tier.clone())
+            .await?;
+        LIFECYCLE_METRICS.archived_objects[&bucket.as_str()].inc();
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!(
+            "https://{}.blob.core.windows.net/{}/{}",
+            self.account,
+            self.container,
+            bucket.as_str()
+        )
+    }
+}
@@ -0,0 +1,367 @@
+//! Content-addressed storage mode.
+//!
+//! In this mode, object contents are stored under keys derived from their SHA-256 hash, and the logical key
+//! of an object points to its contents via a small reference object. As a result, identical objects
+//! (e.g., witness inputs repeated across batches) are stored only once.
+//!
+//! Each contents object is accompanied by an index listing keys referencing it. The index is used to remove
+//! the contents once the last referencing key is removed or overwritten.
+
+use std::{collections::BTreeSet, fmt, sync::Arc};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::{
+    multipart::ChunkStream,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Prefix of reference objects. Objects not starting with it are treated as regular (non-content-addressed)
+/// objects, e.g. ones stored before the content-addressed mode was enabled.
+const REFERENCE_PREFIX: &[u8] = b"zksync-content-ref:";
+const HASH_LEN: usize = 32;
+/// Number of locks used to serialize index updates.
+const LOCK_COUNT: usize = 64;
+
+fn content_key(hash: &[u8]) -> String {
+    format!("content-{}", hex::encode(hash))
+}
+
+fn index_key(hash: &[u8]) -> String {
+    format!("{}.refs", content_key(hash))
+}
+
+/// Parses a reference object, returning the hash of the referenced contents.
+fn parse_reference(object: &[u8]) -> Option<&[u8]> {
+    let hash = object.strip_prefix(REFERENCE_PREFIX)?;
+    (hash.len() == HASH_LEN).then_some(hash)
+}
+
+/// [`ObjectStore`] wrapper storing objects in the content-addressed mode.
+///
+/// Since contents may be shared among several keys, removing a key removes the contents only if no other keys
+/// reference them. Index updates are serialized within a single store instance, but not among processes;
+/// thus, processes sharing a store must not concurrently put and remove objects with identical contents.
+/// Buckets with objects accessed directly via their URLs (i.e., snapshot buckets) are not content-addressed.
+pub(crate) struct ContentAddressedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    /// Locks for index updates, selected by the contents hash.
+    locks: Vec<Mutex<()>>,
+}
+
+impl fmt::Debug for ContentAddressedObjectStore {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ContentAddressedObjectStore")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ContentAddressedObjectStore {
+    pub fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            inner,
+            locks: (0..LOCK_COUNT).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    fn is_content_addressed(bucket: Bucket) -> bool {
+        !matches!(bucket, Bucket::StorageSnapshot | Bucket::TreeSnapshot)
+    }
+
+    fn lock(&self, hash: &[u8]) -> &Mutex<()> {
+        &self.locks[usize::from(hash[0]) % LOCK_COUNT]
+    }
+
+    /// Returns the hash of the contents referenced by the key, or `None` if the key doesn't exist
+    /// or is not stored in the content-addressed mode.
+    async fn referenced_hash(
+        &self,
+        bucket: Bucket,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, ObjectStoreError> {
+        match self.inner.get_raw(bucket, key).await {
+            Ok(object) => Ok(parse_reference(&object).map(<[u8]>::to_vec)),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn read_index(
+        &self,
+        bucket: Bucket,
+        hash: &[u8],
+    ) -> Result<Option<BTreeSet<String>>, ObjectStoreError> {
+        match self.inner.get_raw(bucket, &index_key(hash)).await {
+            Ok(raw) => bincode::deserialize(&raw)
+                .map(Some)
+                .map_err(|err| ObjectStoreError::Serialization(err.into())),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn write_index(
+        &self,
+        bucket: Bucket,
+        hash: &[u8],
+        index: &BTreeSet<String>,
+    ) -> Result<(), ObjectStoreError> {
+        let raw =
+            bincode::serialize(index).map_err(|err| ObjectStoreError::Serialization(err.into()))?;
+        self.inner.put_raw(bucket, &index_key(hash), raw).await
+    }
+
+    /// Removes `key` from the index of the contents, and removes the contents if they are no longer referenced.
+    async fn release_contents(
+        &self,
+        bucket: Bucket,
+        hash: &[u8],
+        key: &str,
+    ) -> Result<(), ObjectStoreError> {
+        let _guard = self.lock(hash).lock().await;
+        let Some(mut index) = self.read_index(bucket, hash).await? else {
+            // The number of references is unknown, so the contents are retained.
+            tracing::warn!(
+                "Index for contents `{}` in bucket `{bucket}` is missing; contents will not be removed",
+                content_key(hash)
+            );
+            return Ok(());
+        };
+        if !index.remove(key) {
+            return Ok(());
+        }
+
+        if index.is_empty() {
+            // Contents are removed before the index, so that the index is retained if removal fails.
+            self.inner.remove_raw(bucket, &content_key(hash)).await?;
+            self.inner.remove_raw(bucket, &index_key(hash)).await
+        } else {
+            self.write_index(bucket, hash, &index).await
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ContentAddressedObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let object = self.inner.get_raw(bucket, key).await?;
+        if !Self::is_content_addressed(bucket) {
+            return Ok(object);
+        }
+        match parse_reference(&object) {
+            Some(hash) => self.inner.get_raw(bucket, &content_key(hash)).await,
+            None => Ok(object),
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        if !Self::is_content_addressed(bucket) {
+            return self.inner.put_raw(bucket, key, value).await;
+        }
+
+        let hash = Sha256::digest(&value);
+        let previous_hash = self.referenced_hash(bucket, key).await?;
+        {
+            let _guard = self.lock(&hash).lock().await;
+            let mut index = self.read_index(bucket, &hash).await?.unwrap_or_default();
+            if index.insert(key.to_owned()) {
+                self.write_index(bucket, &hash, &index).await?;
+            }
+            // Contents are stored after the index is updated, so that they are restored if a concurrent removal
+            // has removed them. Contents are stored before the reference, so that the reference is never dangling.
+            // If the contents are already present, they are overwritten with the same data.
+            self.inner
+                .put_raw(bucket, &content_key(&hash), value)
+                .await?;
+        }
+        let reference = [REFERENCE_PREFIX, hash.as_slice()].concat();
+        self.inner.put_raw(bucket, key, reference).await?;
+
+        if let Some(previous_hash) = previous_hash {
+            if previous_hash != hash.as_slice() {
+                self.release_contents(bucket, &previous_hash, key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // In the content-addressed mode, contents must be hashed before being stored, so the stream is buffered.
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        if !Self::is_content_addressed(bucket) {
+            return self.inner.put_stream_raw(bucket, key, chunks).await;
+        }
+
+        let mut value = vec![];
+        while let Some(chunk) = chunks.next().await {
+            value.extend_from_slice(&chunk?);
+        }
+        self.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        if !Self::is_content_addressed(bucket) {
+            return self.inner.remove_raw(bucket, key).await;
+        }
+
+        let hash = self.referenced_hash(bucket, key).await?;
+        // The reference is removed first, so that it's never dangling.
+        self.inner.remove_raw(bucket, key).await?;
+        if let Some(hash) = hash {
+            self.release_contents(bucket, &hash, key).await?;
+        }
+        Ok(())
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        if !Self::is_content_addressed(bucket) {
+            return self.inner.archive_raw(bucket, key).await;
+        }
+        // Reference objects are tiny, so it's the contents that should be moved to cold storage.
+        let object = self.inner.get_raw(bucket, key).await?;
+        match parse_reference(&object) {
+            Some(hash) => self.inner.archive_raw(bucket, &content_key(hash)).await,
+            None => self.inner.archive_raw(bucket, key).await,
+        }
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStore;
+
+    #[tokio::test]
+    async fn identical_objects_are_stored_once() {
+        let inner = Arc::new(MockStore::default());
+        let store = ContentAddressedObjectStore::new(inner.clone());
+
+        let value = vec![42; 1_024];
+        store
+            .put_raw(Bucket::WitnessInput, "1.bin", value.clone())
+            .await
+            .unwrap();
+        store
+            .put_raw(Bucket::WitnessInput, "2.bin", value.clone())
+            .await
+            .unwrap();
+        store
+            .put_raw(Bucket::WitnessInput, "3.bin", vec![23; 1_024])
+            .await
+            .unwrap();
+
+        for key in ["1.bin", "2.bin"] {
+            let stored = store.get_raw(Bucket::WitnessInput, key).await.unwrap();
+            assert_eq!(stored, value);
+            let reference = inner.get_raw(Bucket::WitnessInput, key).await.unwrap();
+            assert!(reference.len() < value.len());
+        }
+        let content_key = content_key(&Sha256::digest(&value));
+        let contents = inner
+            .get_raw(Bucket::WitnessInput, &content_key)
+            .await
+            .unwrap();
+        assert_eq!(contents, value);
+
+        // Removing a key must not affect other keys with the same contents.
+        store
+            .remove_raw(Bucket::WitnessInput, "1.bin")
+            .await
+            .unwrap();
+        let err = store
+            .get_raw(Bucket::WitnessInput, "1.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        let stored = store.get_raw(Bucket::WitnessInput, "2.bin").await.unwrap();
+        assert_eq!(stored, value);
+
+        // Removing the last key referencing the contents removes the contents as well.
+        store
+            .remove_raw(Bucket::WitnessInput, "2.bin")
+            .await
+            .unwrap();
+        for key in [content_key.clone(), format!("{content_key}.refs")] {
+            let err = inner.get_raw(Bucket::WitnessInput, &key).await.unwrap_err();
+            assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        }
+        let stored = store.get_raw(Bucket::WitnessInput, "3.bin").await.unwrap();
+        assert_eq!(stored, [23; 1_024]);
+    }
+
+    #[tokio::test]
+    async fn overwriting_object_releases_previous_contents() {
+        let inner = Arc::new(MockStore::default());
+        let store = ContentAddressedObjectStore::new(inner.clone());
+
+        let old_value = vec![42; 1_024];
+        store
+            .put_raw(Bucket::WitnessInput, "1.bin", old_value.clone())
+            .await
+            .unwrap();
+        // Overwriting an object with the same contents must not remove them.
+        store
+            .put_raw(Bucket::WitnessInput, "1.bin", old_value.clone())
+            .await
+            .unwrap();
+        let stored = store.get_raw(Bucket::WitnessInput, "1.bin").await.unwrap();
+        assert_eq!(stored, old_value);
+
+        store
+            .put_raw(Bucket::WitnessInput, "1.bin", vec![23; 1_024])
+            .await
+            .unwrap();
+        let stored = store.get_raw(Bucket::WitnessInput, "1.bin").await.unwrap();
+        assert_eq!(stored, [23; 1_024]);
+        let old_content_key = content_key(&Sha256::digest(&old_value));
+        let err = inner
+            .get_raw(Bucket::WitnessInput, &old_content_key)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn regular_objects_can_be_read() {
+        let inner = Arc::new(MockStore::default());
+        inner
+            .put_raw(Bucket::ProofsFri, "legacy.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let store = ContentAddressedObjectStore::new(inner.clone());
+        let stored = store
+            .get_raw(Bucket::ProofsFri, "legacy.bin")
+            .await
+            .unwrap();
+        assert_eq!(stored, [1, 2, 3]);
+
+        // Snapshot buckets are not content-addressed.
+        store
+            .put_raw(Bucket::StorageSnapshot, "chunk.bin", vec![4, 5])
+            .await
+            .unwrap();
+        let stored = inner
+            .get_raw(Bucket::StorageSnapshot, "chunk.bin")
+            .await
+            .unwrap();
+        assert_eq!(stored, [4, 5]);
+    }
+}
@@ -0,0 +1,378 @@
+//! Encryption at rest for objects.
+//!
+//! Objects are encrypted using envelope encryption: each object is encrypted with a fresh data key
+//! (AES-256-GCM), and the data key is encrypted ("wrapped") with a master key and stored alongside the object.
+//! The master key is either static (provided in the config) or managed by AWS KMS, in which case it never leaves KMS.
+
+use std::{collections::HashSet, fmt, sync::Arc};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use async_trait::async_trait;
+use aws_sdk_kms::primitives::Blob;
+use futures::StreamExt;
+
+use crate::{
+    multipart::ChunkStream,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Header of encrypted objects. Objects not starting with it are treated as unencrypted, e.g. ones stored
+/// before encryption was enabled.
+const ENCRYPTED_OBJECT_HEADER: &[u8] = b"zksync-encrypted-v1:";
+/// Length of an AES-GCM nonce.
+const NONCE_LEN: usize = 12;
+
+/// Master key used to wrap data keys.
+#[derive(Clone)]
+pub enum MasterKey {
+    /// Static AES-256 key.
+    Static(Key<Aes256Gcm>),
+    /// Key managed by AWS KMS. Credentials are resolved using the default AWS credential chain.
+    AwsKms {
+        client: aws_sdk_kms::Client,
+        key_id: String,
+    },
+}
+
+impl fmt::Debug for MasterKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Never output the key.
+            Self::Static(_) => formatter.write_str("Static(_)"),
+            Self::AwsKms { key_id, .. } => formatter
+                .debug_struct("AwsKms")
+                .field("key_id", key_id)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl MasterKey {
+    /// Parses a static key from its hex representation.
+    pub fn from_hex(hex_key: &str) -> anyhow::Result<Self> {
+        let bytes = hex::decode(hex_key.strip_prefix("0x").unwrap_or(hex_key))?;
+        anyhow::ensure!(
+            bytes.len() == 32,
+            "master key must have 32 bytes, got {}",
+            bytes.len()
+        );
+        Ok(Self::Static(*Key::<Aes256Gcm>::from_slice(&bytes)))
+    }
+
+    pub async fn aws_kms(key_id: String) -> Self {
+        let sdk_config = aws_config::load_from_env().await;
+        Self::AwsKms {
+            client: aws_sdk_kms::Client::new(&sdk_config),
+            key_id,
+        }
+    }
+
+    async fn wrap(&self, data_key: &[u8]) -> Result<Vec<u8>, ObjectStoreError> {
+        match self {
+            Self::Static(key) => {
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                let wrapped_key = Aes256Gcm::new(key)
+                    .encrypt(&nonce, data_key)
+                    .map_err(|_| ObjectStoreError::Other("failed wrapping data key".into()))?;
+                Ok([nonce.as_slice(), &wrapped_key].concat())
+            }
+            Self::AwsKms { client, key_id } => {
+                let output = client
+                    .encrypt()
+                    .key_id(key_id)
+                    .plaintext(Blob::new(data_key))
+                    .send()
+                    .await
+                    .map_err(|err| ObjectStoreError::Other(err.into()))?;
+                let wrapped_key = output.ciphertext_blob().ok_or_else(|| {
+                    ObjectStoreError::Other("KMS returned no wrapped data key".into())
+                })?;
+                Ok(wrapped_key.as_ref().to_vec())
+            }
+        }
+    }
+
+    async fn unwrap(&self, wrapped_key: &[u8]) -> Result<Vec<u8>, ObjectStoreError> {
+        match self {
+            Self::Static(key) => {
+                if wrapped_key.len() < NONCE_LEN {
+                    return Err(ObjectStoreError::Other(
+                        "wrapped data key is too short".into(),
+                    ));
+                }
+                let (nonce, wrapped_key) = wrapped_key.split_at(NONCE_LEN);
+                Aes256Gcm::new(key)
+                    .decrypt(Nonce::from_slice(nonce), wrapped_key)
+                    .map_err(|_| ObjectStoreError::Other("failed unwrapping data key".into()))
+            }
+            Self::AwsKms { client, key_id } => {
+                let output = client
+                    .decrypt()
+                    .key_id(key_id)
+                    .ciphertext_blob(Blob::new(wrapped_key))
+                    .send()
+                    .await
+                    .map_err(|err| ObjectStoreError::Other(err.into()))?;
+                let data_key = output.plaintext().ok_or_else(|| {
+                    ObjectStoreError::Other("KMS returned no unwrapped data key".into())
+                })?;
+                Ok(data_key.as_ref().to_vec())
+            }
+        }
+    }
+}
+
+/// Encrypted object layout (after the header): wrapped data key length (u16, big-endian), wrapped data key,
+/// nonce, and the ciphertext with the authentication tag.
+struct EncryptedObject<'a> {
+    wrapped_key: &'a [u8],
+    nonce: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+impl<'a> EncryptedObject<'a> {
+    /// Returns `None` if the object is not encrypted.
+    fn parse(object: &'a [u8]) -> Option<Result<Self, ObjectStoreError>> {
+        let object = object.strip_prefix(ENCRYPTED_OBJECT_HEADER)?;
+        let parsed = Self::parse_body(object)
+            .ok_or_else(|| ObjectStoreError::Other("malformed encrypted object".into()));
+        Some(parsed)
+    }
+
+    fn parse_body(body: &'a [u8]) -> Option<Self> {
+        let [len0, len1, rest @ ..] = body else {
+            return None;
+        };
+        let wrapped_key_len = usize::from(u16::from_be_bytes([*len0, *len1]));
+        if rest.len() < wrapped_key_len + NONCE_LEN {
+            return None;
+        }
+        let (wrapped_key, rest) = rest.split_at(wrapped_key_len);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        Some(Self {
+            wrapped_key,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let wrapped_key_len =
+            u16::try_from(self.wrapped_key.len()).expect("wrapped key is too long");
+        [
+            ENCRYPTED_OBJECT_HEADER,
+            &wrapped_key_len.to_be_bytes(),
+            self.wrapped_key,
+            self.nonce,
+            self.ciphertext,
+        ]
+        .concat()
+    }
+}
+
+/// [`ObjectStore`] wrapper encrypting objects in the specified buckets.
+///
+/// Encryption is bound to the object location: an encrypted object cannot be decrypted if moved to another key.
+/// Unencrypted objects (e.g., stored before encryption was enabled) are returned as-is.
+#[derive(Debug)]
+pub(crate) struct EncryptedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    master_key: MasterKey,
+    /// Names of encrypted buckets. If empty, all buckets are encrypted.
+    buckets: HashSet<String>,
+}
+
+impl EncryptedObjectStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, master_key: MasterKey, buckets: Vec<String>) -> Self {
+        Self {
+            inner,
+            master_key,
+            buckets: buckets.into_iter().collect(),
+        }
+    }
+
+    fn is_encrypted(&self, bucket: Bucket) -> bool {
+        self.buckets.is_empty() || self.buckets.contains(bucket.as_str())
+    }
+
+    /// Additional authenticated data binding the ciphertext to the object location.
+    fn aad(bucket: Bucket, key: &str) -> String {
+        format!("{bucket}/{key}")
+    }
+
+    async fn encrypt(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: &[u8],
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let data_key = Aes256Gcm::generate_key(&mut OsRng);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = Self::aad(bucket, key);
+        let payload = Payload {
+            msg: value,
+            aad: aad.as_bytes(),
+        };
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(&nonce, payload)
+            .map_err(|_| ObjectStoreError::Other(format!("failed encrypting {aad}").into()))?;
+        let wrapped_key = self.master_key.wrap(&data_key).await?;
+        let object = EncryptedObject {
+            wrapped_key: &wrapped_key,
+            nonce: &nonce,
+            ciphertext: &ciphertext,
+        };
+        Ok(object.serialize())
+    }
+
+    async fn decrypt(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        object: &EncryptedObject<'_>,
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let data_key = self.master_key.unwrap(object.wrapped_key).await?;
+        let cipher = Aes256Gcm::new_from_slice(&data_key)
+            .map_err(|_| ObjectStoreError::Other("unwrapped data key has invalid length".into()))?;
+        let aad = Self::aad(bucket, key);
+        let payload = Payload {
+            msg: object.ciphertext,
+            aad: aad.as_bytes(),
+        };
+        cipher
+            .decrypt(Nonce::from_slice(object.nonce), payload)
+            .map_err(|_| ObjectStoreError::Other(format!("failed decrypting {aad}").into()))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncryptedObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let object = self.inner.get_raw(bucket, key).await?;
+        match EncryptedObject::parse(&object) {
+            Some(encrypted) => self.decrypt(bucket, key, &encrypted?).await,
+            None => Ok(object),
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let value = if self.is_encrypted(bucket) {
+            self.encrypt(bucket, key, &value).await?
+        } else {
+            value
+        };
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    // Objects are encrypted as a whole, so the stream is buffered for encrypted buckets.
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        if !self.is_encrypted(bucket) {
+            return self.inner.put_stream_raw(bucket, key, chunks).await;
+        }
+
+        let mut value = vec![];
+        while let Some(chunk) = chunks.next().await {
+            value.extend_from_slice(&chunk?);
+        }
+        self.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.archive_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStore;
+
+    fn test_master_key() -> MasterKey {
+        MasterKey::from_hex(&"42".repeat(32)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn objects_are_encrypted() {
+        let inner = Arc::new(MockStore::default());
+        let store = EncryptedObjectStore::new(
+            inner.clone(),
+            test_master_key(),
+            vec!["proofs_fri".to_owned()],
+        );
+
+        let value = b"test object".to_vec();
+        store
+            .put_raw(Bucket::ProofsFri, "proof.bin", value.clone())
+            .await
+            .unwrap();
+        let stored = inner.get_raw(Bucket::ProofsFri, "proof.bin").await.unwrap();
+        assert!(stored.starts_with(ENCRYPTED_OBJECT_HEADER));
+        assert!(!stored.windows(value.len()).any(|window| window == value));
+        let decrypted = store.get_raw(Bucket::ProofsFri, "proof.bin").await.unwrap();
+        assert_eq!(decrypted, value);
+
+        // Buckets not specified in the config are not encrypted.
+        store
+            .put_raw(Bucket::WitnessInput, "input.bin", value.clone())
+            .await
+            .unwrap();
+        let stored = inner
+            .get_raw(Bucket::WitnessInput, "input.bin")
+            .await
+            .unwrap();
+        assert_eq!(stored, value);
+        let read = store
+            .get_raw(Bucket::WitnessInput, "input.bin")
+            .await
+            .unwrap();
+        assert_eq!(read, value);
+    }
+
+    #[tokio::test]
+    async fn encrypted_objects_are_bound_to_location() {
+        let inner = Arc::new(MockStore::default());
+        let store = EncryptedObjectStore::new(inner.clone(), test_master_key(), vec![]);
+        store
+            .put_raw(Bucket::ProofsFri, "1.bin", b"test".to_vec())
+            .await
+            .unwrap();
+
+        let stored = inner.get_raw(Bucket::ProofsFri, "1.bin").await.unwrap();
+        inner
+            .put_raw(Bucket::ProofsFri, "2.bin", stored)
+            .await
+            .unwrap();
+        let err = store.get_raw(Bucket::ProofsFri, "2.bin").await.unwrap_err();
+        assert!(err.to_string().contains("failed decrypting"), "{err}");
+
+        // Objects cannot be decrypted with another master key.
+        let other_key = MasterKey::from_hex(&"23".repeat(32)).unwrap();
+        let other_store = EncryptedObjectStore::new(inner, other_key, vec![]);
+        let err = other_store
+            .get_raw(Bucket::ProofsFri, "1.bin")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unwrapping"), "{err}");
+    }
+}
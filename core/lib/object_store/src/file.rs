@@ -1,9 +1,24 @@
-use std::fmt::Debug;
+use std::{
+    collections::HashSet,
+    fmt::Debug,
+    iter,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 use async_trait::async_trait;
-use tokio::{fs, io};
+use futures::{stream, StreamExt};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs,
+    io::{self, AsyncWriteExt},
+};
 
-use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+use crate::{
+    metrics::LIFECYCLE_METRICS,
+    multipart::ChunkStream,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
 
 impl From<io::Error> for ObjectStoreError {
     fn from(err: io::Error) -> Self {
@@ -14,38 +29,183 @@ impl From<io::Error> for ObjectStoreError {
     }
 }
 
+const ALL_BUCKETS: &[Bucket] = &[
+    Bucket::ProverJobs,
+    Bucket::WitnessInput,
+    Bucket::LeafAggregationWitnessJobs,
+    Bucket::NodeAggregationWitnessJobs,
+    Bucket::SchedulerWitnessJobs,
+    Bucket::ProverJobsFri,
+    Bucket::LeafAggregationWitnessJobsFri,
+    Bucket::NodeAggregationWitnessJobsFri,
+    Bucket::SchedulerWitnessJobsFri,
+    Bucket::ProofsFri,
+    Bucket::StorageSnapshot,
+    Bucket::TreeSnapshot,
+];
+
+/// File-backed object store. If `cold_base_dir` is set, archived objects are moved to this directory
+/// (which may reside on a cheaper storage medium) and are restored to `base_dir` on access.
+///
+/// Objects are written atomically: data is written to a temporary file in the same directory, which is then renamed
+/// to the object filename. Thus, a partially written object is never observed, even if the process crashes mid-write;
+/// the only possible artifacts of a crash are orphaned temporary files.
 #[derive(Debug)]
 pub(crate) struct FileBackedObjectStore {
     base_dir: String,
+    cold_base_dir: Option<String>,
+    /// Whether to flush written objects to the storage device before considering them stored.
+    fsync: bool,
+    /// Whether to shard objects in a bucket among subdirectories.
+    sharding: bool,
+    /// Filenames of the objects being currently restored from the cold storage.
+    thawing_objects: Arc<Mutex<HashSet<String>>>,
 }
 
 impl FileBackedObjectStore {
-    pub async fn new(base_dir: String) -> Self {
-        for bucket in &[
-            Bucket::ProverJobs,
-            Bucket::WitnessInput,
-            Bucket::LeafAggregationWitnessJobs,
-            Bucket::NodeAggregationWitnessJobs,
-            Bucket::SchedulerWitnessJobs,
-            Bucket::ProverJobsFri,
-            Bucket::LeafAggregationWitnessJobsFri,
-            Bucket::NodeAggregationWitnessJobsFri,
-            Bucket::SchedulerWitnessJobsFri,
-            Bucket::ProofsFri,
-            Bucket::StorageSnapshot,
-        ] {
-            let bucket_path = format!("{base_dir}/{bucket}");
-            fs::create_dir_all(&bucket_path)
-                .await
-                .unwrap_or_else(|err| {
-                    panic!("failed creating bucket `{bucket_path}`: {err}");
-                });
+    pub async fn new(base_dir: String, cold_base_dir: Option<String>) -> Self {
+        for dir in iter::once(&base_dir).chain(&cold_base_dir) {
+            for bucket in ALL_BUCKETS {
+                let bucket_path = format!("{dir}/{bucket}");
+                fs::create_dir_all(&bucket_path)
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("failed creating bucket `{bucket_path}`: {err}");
+                    });
+            }
+        }
+        FileBackedObjectStore {
+            base_dir,
+            cold_base_dir,
+            fsync: false,
+            sharding: false,
+            thawing_objects: Arc::default(),
+        }
+    }
+
+    /// Enables flushing written objects (and the directory entries pointing to them) to the storage device
+    /// before returning from write operations. This makes stored objects durable across power losses / OS crashes
+    /// at the cost of slower writes.
+    pub fn with_fsync(mut self, fsync: bool) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Enables sharding objects in each bucket among 256 subdirectories, based on the first byte of the key hash.
+    /// This keeps directories reasonably small for buckets with many objects (some filesystems, e.g. NFS,
+    /// degrade with huge directories). Objects stored with sharding disabled are not accessible with it enabled
+    /// and vice versa, so sharding must not be switched for an existing store.
+    ///
+    /// Snapshot buckets are never sharded: snapshot objects are referenced by URLs built from
+    /// [`ObjectStore::storage_prefix_raw()`] and the object key, so they must reside directly in the bucket directory.
+    pub fn with_sharding(mut self, sharding: bool) -> Self {
+        self.sharding = sharding;
+        self
+    }
+
+    fn is_sharded(&self, bucket: Bucket) -> bool {
+        self.sharding && !matches!(bucket, Bucket::StorageSnapshot | Bucket::TreeSnapshot)
+    }
+
+    fn object_path(&self, base_dir: &str, bucket: Bucket, key: &str) -> String {
+        if self.is_sharded(bucket) {
+            let shard = Sha256::digest(key.as_bytes())[0];
+            format!("{base_dir}/{bucket}/{shard:02x}/{key}")
+        } else {
+            format!("{base_dir}/{bucket}/{key}")
         }
-        FileBackedObjectStore { base_dir }
     }
 
     fn filename(&self, bucket: Bucket, key: &str) -> String {
-        format!("{}/{bucket}/{key}", self.base_dir)
+        self.object_path(&self.base_dir, bucket, key)
+    }
+
+    fn cold_filename(&self, bucket: Bucket, key: &str) -> Option<String> {
+        let cold_base_dir = self.cold_base_dir.as_ref()?;
+        Some(self.object_path(cold_base_dir, bucket, key))
+    }
+
+    /// Ensures that the parent directory for the specified file exists. Bucket directories are created
+    /// on initialization, so this is only necessary if the bucket is sharded.
+    async fn ensure_parent_dir(&self, bucket: Bucket, filename: &str) -> io::Result<()> {
+        if !self.is_sharded(bucket) {
+            return Ok(());
+        }
+        if let Some(parent) = Path::new(filename).parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        Ok(())
+    }
+
+    /// Syncs the directory containing the specified file, so that changes to the directory entry of the file
+    /// (e.g., the file being renamed) are durable.
+    async fn sync_parent_dir(&self, filename: &str) -> io::Result<()> {
+        if !self.fsync {
+            return Ok(());
+        }
+        if let Some(parent) = Path::new(filename).parent() {
+            fs::File::open(parent).await?.sync_all().await?;
+        }
+        Ok(())
+    }
+
+    /// Atomically writes the object to `filename`.
+    async fn write_object(
+        &self,
+        bucket: Bucket,
+        filename: &str,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        self.ensure_parent_dir(bucket, filename).await?;
+        // The temporary filename is randomized so that concurrent writes of the same object
+        // (potentially, from different processes) don't interfere.
+        let tmp_filename = format!("{filename}.{:016x}.tmp", rand::random::<u64>());
+        let result: Result<(), ObjectStoreError> = async {
+            let mut file = fs::File::create(&tmp_filename).await?;
+            while let Some(chunk) = chunks.next().await {
+                file.write_all(&chunk?).await?;
+            }
+            file.flush().await?;
+            if self.fsync {
+                file.sync_all().await?;
+            }
+            drop(file);
+            fs::rename(&tmp_filename, filename).await?;
+            self.sync_parent_dir(filename).await?;
+            Ok(())
+        }
+        .await;
+
+        if result.is_err() {
+            fs::remove_file(&tmp_filename).await.ok();
+        }
+        result
+    }
+
+    /// Starts restoring an object from the cold storage in the background unless it's being restored already.
+    fn start_thawing(&self, bucket: Bucket, filename: String, cold_filename: String) {
+        let mut thawing_objects = self.thawing_objects.lock().unwrap();
+        if !thawing_objects.insert(filename.clone()) {
+            return;
+        }
+        drop(thawing_objects);
+        LIFECYCLE_METRICS.thawed_objects[&bucket.as_str()].inc();
+
+        let thawing_objects = self.thawing_objects.clone();
+        tokio::spawn(async move {
+            // Copy to a temporary file first so that a partially restored object is never observed.
+            let tmp_filename = format!("{filename}.thawing");
+            let result = async {
+                fs::copy(&cold_filename, &tmp_filename).await?;
+                fs::rename(&tmp_filename, &filename).await?;
+                fs::remove_file(&cold_filename).await
+            }
+            .await;
+            if let Err(err) = result {
+                tracing::warn!("Failed restoring object `{filename}` from cold storage: {err}");
+            }
+            thawing_objects.lock().unwrap().remove(&filename);
+        });
     }
 }
 
@@ -53,7 +213,20 @@ impl FileBackedObjectStore {
 impl ObjectStore for FileBackedObjectStore {
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
         let filename = self.filename(bucket, key);
-        fs::read(filename).await.map_err(From::from)
+        let err = match fs::read(&filename).await {
+            Ok(bytes) => return Ok(bytes),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => err,
+            Err(err) => return Err(err.into()),
+        };
+
+        if let Some(cold_filename) = self.cold_filename(bucket, key) {
+            if fs::try_exists(&cold_filename).await? {
+                self.start_thawing(bucket, filename, cold_filename);
+                let error_message = format!("key {key} in bucket {bucket} is being restored");
+                return Err(ObjectStoreError::Thawing(error_message.into()));
+            }
+        }
+        Err(err.into())
     }
 
     async fn put_raw(
@@ -63,12 +236,62 @@ impl ObjectStore for FileBackedObjectStore {
         value: Vec<u8>,
     ) -> Result<(), ObjectStoreError> {
         let filename = self.filename(bucket, key);
-        fs::write(filename, value).await.map_err(From::from)
+        let chunks = stream::once(async { Ok(value) }).boxed();
+        self.write_object(bucket, &filename, chunks).await
+    }
+
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let filename = self.filename(bucket, key);
+        self.write_object(bucket, &filename, chunks).await
     }
 
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
         let filename = self.filename(bucket, key);
-        fs::remove_file(filename).await.map_err(From::from)
+        let mut removed_cold_object = false;
+        if let Some(cold_filename) = self.cold_filename(bucket, key) {
+            match fs::remove_file(cold_filename).await {
+                Ok(()) => removed_cold_object = true,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => { /* continue */ }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        match fs::remove_file(filename).await {
+            Err(err) if err.kind() == io::ErrorKind::NotFound && removed_cold_object => Ok(()),
+            result => result.map_err(From::from),
+        }
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let Some(cold_filename) = self.cold_filename(bucket, key) else {
+            return Ok(()); // cold storage is not configured
+        };
+        let filename = self.filename(bucket, key);
+        self.ensure_parent_dir(bucket, &cold_filename).await?;
+        match fs::copy(&filename, &cold_filename).await {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                // The object may have been archived already.
+                return if fs::try_exists(&cold_filename).await? {
+                    Ok(())
+                } else {
+                    Err(err.into())
+                };
+            }
+            Err(err) => return Err(err.into()),
+        }
+        if self.fsync {
+            // The cold copy must be durable before the hot object is removed.
+            fs::File::open(&cold_filename).await?.sync_all().await?;
+            self.sync_parent_dir(&cold_filename).await?;
+        }
+        fs::remove_file(filename).await?;
+        LIFECYCLE_METRICS.archived_objects[&bucket.as_str()].inc();
+        Ok(())
     }
 
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
@@ -86,7 +309,7 @@ mod test {
     async fn test_get() {
         let dir = TempDir::new("test-data").unwrap();
         let path = dir.into_path().into_os_string().into_string().unwrap();
-        let object_store = FileBackedObjectStore::new(path).await;
+        let object_store = FileBackedObjectStore::new(path, None).await;
         let expected = vec![9, 0, 8, 9, 0, 7];
         let result = object_store
             .put_raw(Bucket::ProverJobs, "test-key.bin", expected.clone())
@@ -103,7 +326,7 @@ mod test {
     async fn test_put() {
         let dir = TempDir::new("test-data").unwrap();
         let path = dir.into_path().into_os_string().into_string().unwrap();
-        let object_store = FileBackedObjectStore::new(path).await;
+        let object_store = FileBackedObjectStore::new(path, None).await;
         let bytes = vec![9, 0, 8, 9, 0, 7];
         let result = object_store
             .put_raw(Bucket::ProverJobs, "test-key.bin", bytes)
@@ -111,11 +334,110 @@ mod test {
         assert!(result.is_ok(), "result must be OK");
     }
 
+    #[tokio::test]
+    async fn test_put_stream() {
+        let dir = TempDir::new("test-data").unwrap();
+        let path = dir.into_path().into_os_string().into_string().unwrap();
+        let object_store = FileBackedObjectStore::new(path, None).await;
+        let chunks = vec![Ok(vec![9, 0, 8]), Ok(vec![]), Ok(vec![9, 0, 7])];
+        object_store
+            .put_stream_raw(
+                Bucket::ProverJobs,
+                "test-key.bin",
+                stream::iter(chunks).boxed(),
+            )
+            .await
+            .unwrap();
+        let bytes = object_store
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(bytes, [9, 0, 8, 9, 0, 7]);
+
+        // A failed upload must not leave a partially written object.
+        let chunks = vec![
+            Ok(vec![1, 2, 3]),
+            Err(ObjectStoreError::Other("oops".into())),
+        ];
+        object_store
+            .put_stream_raw(
+                Bucket::ProverJobs,
+                "other-key.bin",
+                stream::iter(chunks).boxed(),
+            )
+            .await
+            .unwrap_err();
+        let err = object_store
+            .get_raw(Bucket::ProverJobs, "other-key.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn sharded_store_with_fsync() {
+        let dir = TempDir::new("test-data").unwrap();
+        let path = dir.path().join("hot");
+        let cold_path = dir.path().join("cold");
+        let object_store = FileBackedObjectStore::new(
+            path.to_str().unwrap().to_owned(),
+            Some(cold_path.to_str().unwrap().to_owned()),
+        )
+        .await
+        .with_fsync(true)
+        .with_sharding(true);
+
+        let bytes = vec![9, 0, 8, 9, 0, 7];
+        object_store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", bytes.clone())
+            .await
+            .unwrap();
+        let filename = object_store.filename(Bucket::ProverJobs, "test-key.bin");
+        let shard_dir = Path::new(&filename).parent().unwrap();
+        assert_ne!(shard_dir, path.join("prover_jobs"));
+        // No temporary files should be left after the write.
+        let mut entries = fs::read_dir(shard_dir).await.unwrap();
+        let mut filenames = vec![];
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            filenames.push(entry.file_name().into_string().unwrap());
+        }
+        assert_eq!(filenames, ["test-key.bin"]);
+
+        let stored_bytes = object_store
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(stored_bytes, bytes);
+
+        object_store
+            .archive_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert!(!fs::try_exists(&filename).await.unwrap());
+        object_store
+            .remove_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        let cold_filename = object_store
+            .cold_filename(Bucket::ProverJobs, "test-key.bin")
+            .unwrap();
+        assert!(!fs::try_exists(&cold_filename).await.unwrap());
+
+        // Snapshot objects must be accessible via the storage prefix.
+        object_store
+            .put_raw(Bucket::StorageSnapshot, "snapshot.bin", bytes.clone())
+            .await
+            .unwrap();
+        let prefix = object_store.storage_prefix_raw(Bucket::StorageSnapshot);
+        let snapshot_bytes = fs::read(format!("{prefix}/snapshot.bin")).await.unwrap();
+        assert_eq!(snapshot_bytes, bytes);
+    }
+
     #[tokio::test]
     async fn test_remove() {
         let dir = TempDir::new("test-data").unwrap();
         let path = dir.into_path().into_os_string().into_string().unwrap();
-        let object_store = FileBackedObjectStore::new(path).await;
+        let object_store = FileBackedObjectStore::new(path, None).await;
         let result = object_store
             .put_raw(Bucket::ProverJobs, "test-key.bin", vec![0, 1])
             .await;
@@ -125,4 +447,61 @@ mod test {
             .await;
         assert!(result.is_ok(), "result must be OK");
     }
+
+    #[tokio::test]
+    async fn archived_object_is_restored_on_access() {
+        let dir = TempDir::new("test-data").unwrap();
+        let path = dir
+            .path()
+            .join("hot")
+            .into_os_string()
+            .into_string()
+            .unwrap();
+        let cold_path = dir
+            .path()
+            .join("cold")
+            .into_os_string()
+            .into_string()
+            .unwrap();
+        let object_store = FileBackedObjectStore::new(path, Some(cold_path)).await;
+        let bytes = vec![9, 0, 8, 9, 0, 7];
+        object_store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", bytes.clone())
+            .await
+            .unwrap();
+        object_store
+            .archive_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        // Repeated archiving is a no-op.
+        object_store
+            .archive_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+
+        let err = object_store
+            .get_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Thawing(_)), "{err:?}");
+        let restored_bytes = loop {
+            match object_store
+                .get_raw(Bucket::ProverJobs, "test-key.bin")
+                .await
+            {
+                Ok(bytes) => break bytes,
+                Err(ObjectStoreError::Thawing(_)) => {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+                Err(err) => panic!("unexpected error: {err:?}"),
+            }
+        };
+        assert_eq!(restored_bytes, bytes);
+
+        let err = object_store
+            .archive_raw(Bucket::ProverJobs, "missing-key.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err:?}");
+    }
 }
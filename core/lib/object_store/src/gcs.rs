@@ -11,18 +11,26 @@ use google_cloud_storage::{
             delete::DeleteObjectRequest,
             download::Range,
             get::GetObjectRequest,
+            rewrite::RewriteObjectRequest,
             upload::{Media, UploadObjectRequest, UploadType},
+            Object,
         },
+        resumable_upload_client::ChunkSize,
         Error as HttpError,
     },
 };
 use http::StatusCode;
 
 use crate::{
-    metrics::GCS_METRICS,
+    metrics::{LIFECYCLE_METRICS, REMOTE_STORE_METRICS},
+    multipart::{ChunkStream, Parts},
     raw::{Bucket, ObjectStore, ObjectStoreError},
+    resilience::is_transient_http_status,
 };
 
+/// Size of chunks in resumable uploads. GCS requires all chunks except for the last one to be a multiple of 256 KiB.
+const RESUMABLE_UPLOAD_CHUNK_SIZE: usize = 8 << 20;
+
 async fn retry<T, E, Fut, F>(max_retries: u16, mut f: F) -> Result<T, E>
 where
     E: fmt::Display,
@@ -47,9 +55,12 @@ where
     }
 }
 
+/// GCS-backed object store. Requests are retried by the resilience layer wrapping the store;
+/// the only retried operation in the store itself is fetching the client config on initialization.
 pub struct GoogleCloudStorage {
     bucket_prefix: String,
-    max_retries: u16,
+    /// Storage class (e.g., `COLDLINE`) that archived objects are rewritten to.
+    cold_storage_class: Option<String>,
     client: Client,
 }
 
@@ -58,7 +69,7 @@ impl fmt::Debug for GoogleCloudStorage {
         formatter
             .debug_struct("GoogleCloudStorage")
             .field("bucket_prefix", &self.bucket_prefix)
-            .field("max_retries", &self.max_retries)
+            .field("cold_storage_class", &self.cold_storage_class)
             .finish_non_exhaustive()
     }
 }
@@ -75,6 +86,7 @@ impl GoogleCloudStorage {
         auth_mode: GoogleCloudStorageAuthMode,
         bucket_prefix: String,
         max_retries: u16,
+        cold_storage_class: Option<String>,
     ) -> Self {
         let client_config = retry(max_retries, || Self::get_client_config(auth_mode.clone()))
             .await
@@ -83,7 +95,7 @@ impl GoogleCloudStorage {
         Self {
             client: Client::new(client_config),
             bucket_prefix,
-            max_retries,
+            cold_storage_class,
         }
     }
 
@@ -127,7 +139,8 @@ impl GoogleCloudStorage {
             ..DeleteObjectRequest::default()
         };
         async move {
-            retry(self.max_retries, || self.client.delete_object(&request))
+            self.client
+                .delete_object(&request)
                 .await
                 .map_err(ObjectStoreError::from)
         }
@@ -136,18 +149,24 @@ impl GoogleCloudStorage {
 
 impl From<HttpError> for ObjectStoreError {
     fn from(err: HttpError) -> Self {
-        let is_not_found = match &err {
-            HttpError::HttpClient(err) => err
-                .status()
-                .map_or(false, |status| matches!(status, StatusCode::NOT_FOUND)),
-            HttpError::Response(response) => response.code == StatusCode::NOT_FOUND.as_u16(),
-            HttpError::TokenSource(_) => false,
+        let status = match &err {
+            HttpError::HttpClient(err) => err.status().map(|status| status.as_u16()),
+            HttpError::Response(response) => Some(response.code),
+            HttpError::TokenSource(_) => None,
         };
 
-        if is_not_found {
-            ObjectStoreError::KeyNotFound(err.into())
-        } else {
-            ObjectStoreError::Other(err.into())
+        match status {
+            Some(status) if status == StatusCode::NOT_FOUND.as_u16() => {
+                ObjectStoreError::KeyNotFound(err.into())
+            }
+            Some(status) if is_transient_http_status(status) => {
+                ObjectStoreError::Transient(err.into())
+            }
+            // HTTP client errors without a status are connection errors or timeouts.
+            None if matches!(err, HttpError::HttpClient(_)) => {
+                ObjectStoreError::Transient(err.into())
+            }
+            _ => ObjectStoreError::Other(err.into()),
         }
     }
 }
@@ -155,7 +174,7 @@ impl From<HttpError> for ObjectStoreError {
 #[async_trait]
 impl ObjectStore for GoogleCloudStorage {
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
-        let fetch_latency = GCS_METRICS.start_fetch(bucket);
+        let fetch_latency = REMOTE_STORE_METRICS.start_fetch(bucket);
         let filename = Self::filename(bucket.as_str(), key);
         tracing::trace!(
             "Fetching data from GCS for key {filename} from bucket {}",
@@ -168,10 +187,7 @@ impl ObjectStore for GoogleCloudStorage {
             ..GetObjectRequest::default()
         };
         let range = Range::default();
-        let blob = retry(self.max_retries, || {
-            self.client.download_object(&request, &range)
-        })
-        .await;
+        let blob = self.client.download_object(&request, &range).await;
 
         let elapsed = fetch_latency.observe();
         tracing::trace!(
@@ -186,7 +202,7 @@ impl ObjectStore for GoogleCloudStorage {
         key: &str,
         value: Vec<u8>,
     ) -> Result<(), ObjectStoreError> {
-        let store_latency = GCS_METRICS.start_store(bucket);
+        let store_latency = REMOTE_STORE_METRICS.start_store(bucket);
         let filename = Self::filename(bucket.as_str(), key);
         tracing::trace!(
             "Storing data to GCS for key {filename} from bucket {}",
@@ -198,11 +214,10 @@ impl ObjectStore for GoogleCloudStorage {
             bucket: self.bucket_prefix.clone(),
             ..Default::default()
         };
-        let object = retry(self.max_retries, || {
-            self.client
-                .upload_object(&request, value.clone(), &upload_type)
-        })
-        .await;
+        let object = self
+            .client
+            .upload_object(&request, value, &upload_type)
+            .await;
 
         let elapsed = store_latency.observe();
         tracing::trace!(
@@ -211,10 +226,112 @@ impl ObjectStore for GoogleCloudStorage {
         object.map(drop).map_err(ObjectStoreError::from)
     }
 
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let mut parts = Parts::new(chunks, RESUMABLE_UPLOAD_CHUNK_SIZE);
+        let mut part = parts.next().await?;
+        if part.is_last {
+            // The object is small enough to be uploaded in a single request.
+            return self.put_raw(bucket, key, part.data).await;
+        }
+
+        let store_latency = REMOTE_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Starting resumable upload to GCS for key {filename} from bucket {}",
+            self.bucket_prefix
+        );
+        let upload_type = UploadType::Simple(Media::new(filename.clone()));
+        let request = UploadObjectRequest {
+            bucket: self.bucket_prefix.clone(),
+            ..Default::default()
+        };
+        let uploader = self
+            .client
+            .prepare_resumable_upload(&request, &upload_type)
+            .await?;
+
+        let result: Result<(), ObjectStoreError> = async {
+            loop {
+                let part_len = part.data.len() as u64;
+                let total_size = part.is_last.then_some(part.offset + part_len);
+                let chunk_size =
+                    ChunkSize::new(part.offset, part.offset + part_len - 1, total_size);
+                uploader
+                    .upload_multiple_chunk(part.data, &chunk_size)
+                    .await?;
+                if part.is_last {
+                    return Ok(());
+                }
+                part = parts.next().await?;
+            }
+        }
+        .await;
+
+        if let Err(err) = &result {
+            tracing::info!("Cancelling resumable upload to GCS for key {filename}: {err}");
+            if let Err(cancel_err) = uploader.cancel().await {
+                tracing::warn!(
+                    "Failed cancelling resumable upload to GCS for key {filename}: {cancel_err}"
+                );
+            }
+            return result;
+        }
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to GCS for key {key} from bucket {bucket} using resumable upload and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
         self.remove_inner(bucket.as_str(), key).await
     }
 
+    // GCS storage classes are all online, so archived objects don't need to be restored on access.
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let Some(storage_class) = &self.cold_storage_class else {
+            return Ok(());
+        };
+        let filename = Self::filename(bucket.as_str(), key);
+        tracing::trace!(
+            "Moving object {filename} in bucket {} to storage class {storage_class}",
+            self.bucket_prefix
+        );
+
+        // Storage class of an object can only be changed by rewriting the object in place.
+        // Large objects may require several rewrite calls.
+        let mut request = RewriteObjectRequest {
+            destination_bucket: self.bucket_prefix.clone(),
+            destination_object: filename.clone(),
+            source_bucket: self.bucket_prefix.clone(),
+            source_object: filename,
+            destination_metadata: Some(Object {
+                storage_class: Some(storage_class.clone()),
+                ..Object::default()
+            }),
+            ..RewriteObjectRequest::default()
+        };
+        loop {
+            let response = self
+                .client
+                .rewrite_object(&request)
+                .await
+                .map_err(ObjectStoreError::from)?;
+            if response.done {
+                break;
+            }
+            request.rewrite_token = response.rewrite_token;
+        }
+        LIFECYCLE_METRICS.archived_objects[&bucket.as_str()].inc();
+        Ok(())
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         format!(
             "https://storage.googleapis.com/{}/{}",
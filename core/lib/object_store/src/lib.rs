@@ -3,11 +3,29 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - S3-based storage (also usable with S3-compatible stores, such as MinIO)
+//! - Azure Blob Storage-based storage
+//!
+//! All implementations support moving old objects to a cheaper cold storage tier
+//! (see [`ObjectStore::archive_raw()`]); the storage class used for that is configured
+//! per backend.
+//!
+//! Optionally, objects can be stored in the content-addressed mode, in which identical objects are stored only once
+//! (see [`ObjectStoreConfig::content_addressed`](zksync_config::ObjectStoreConfig::content_addressed)).
+//!
+//! Objects can be encrypted at rest using envelope encryption with a static or AWS KMS-managed master key
+//! (see [`ObjectStoreConfig::encryption_master_key`](zksync_config::ObjectStoreConfig::encryption_master_key)).
+//!
+//! Large objects can be uploaded without buffering them in memory using [`ObjectStore::put_stream_raw()`].
+//! Remote stores use their native multipart upload mechanisms for this; failed uploads are aborted.
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
 //! The configuration can be provided explicitly (see [`ObjectStoreFactory::new()`])
 //! or obtained from the environment (see [`ObjectStoreFactory::from_env()`]).
+//! Stores created by the factory time out and retry requests failed with transient errors,
+//! and stop sending requests for some time (with the outage reported by [`ObjectStoreFactory::health_check()`])
+//! if the store is persistently unavailable.
 //!
 //! Besides the lower-level storage abstraction, the crate provides high-level
 //! typesafe `<dyn ObjectStore>::get()` and `<dyn ObjectStore>::put()` methods
@@ -23,12 +41,18 @@
     clippy::doc_markdown
 )]
 
+mod azure;
+mod content_addressed;
+mod encryption;
 mod file;
 mod gcs;
 mod metrics;
 mod mock;
+mod multipart;
 mod objects;
 mod raw;
+mod resilience;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -39,6 +63,7 @@ pub mod _reexports {
 }
 
 pub use self::{
+    multipart::ChunkStream,
     objects::StoredObject,
     raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory},
 };
@@ -2,22 +2,22 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Histogram, LabeledFamily, LatencyObserver, Metrics};
+use vise::{Buckets, Counter, Histogram, LabeledFamily, LatencyObserver, Metrics};
 
 use crate::Bucket;
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_object_store")]
-pub(crate) struct GcsMetrics {
-    /// Latency to fetch an object from GCS.
+pub(crate) struct RemoteStoreMetrics {
+    /// Latency to fetch an object from a remote store (GCS, S3 or Azure Blob Storage).
     #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
     fetching_time: LabeledFamily<&'static str, Histogram<Duration>>,
-    /// Latency to store an object in GCS.
+    /// Latency to store an object in a remote store (GCS, S3 or Azure Blob Storage).
     #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
     storing_time: LabeledFamily<&'static str, Histogram<Duration>>,
 }
 
-impl GcsMetrics {
+impl RemoteStoreMetrics {
     pub fn start_fetch(&self, bucket: Bucket) -> LatencyObserver<'_> {
         self.fetching_time[&bucket.as_str()].start()
     }
@@ -28,4 +28,39 @@ impl GcsMetrics {
 }
 
 #[vise::register]
-pub(crate) static GCS_METRICS: vise::Global<GcsMetrics> = vise::Global::new();
+pub(crate) static REMOTE_STORE_METRICS: vise::Global<RemoteStoreMetrics> = vise::Global::new();
+
+/// Metrics for the lifecycle management of stored objects.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store_lifecycle")]
+pub(crate) struct LifecycleMetrics {
+    /// Number of objects moved to the cold storage.
+    #[metrics(labels = ["bucket"])]
+    pub archived_objects: LabeledFamily<&'static str, Counter>,
+    /// Number of objects for which restoration from the cold storage was requested.
+    #[metrics(labels = ["bucket"])]
+    pub thawed_objects: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+pub(crate) static LIFECYCLE_METRICS: vise::Global<LifecycleMetrics> = vise::Global::new();
+
+/// Metrics for the resilience layer wrapping object stores.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store")]
+pub(crate) struct ResilienceMetrics {
+    /// Number of retried requests to the store.
+    #[metrics(labels = ["bucket"])]
+    pub retried_requests: LabeledFamily<&'static str, Counter>,
+    /// Number of request attempts that have timed out.
+    #[metrics(labels = ["bucket"])]
+    pub timed_out_requests: LabeledFamily<&'static str, Counter>,
+    /// Number of requests rejected because the circuit breaker was open.
+    #[metrics(labels = ["bucket"])]
+    pub rejected_requests: LabeledFamily<&'static str, Counter>,
+    /// Number of times the circuit breaker has opened.
+    pub circuit_breaker_openings: Counter,
+}
+
+#[vise::register]
+pub(crate) static RESILIENCE_METRICS: vise::Global<ResilienceMetrics> = vise::Global::new();
@@ -9,20 +9,46 @@ use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
 
 type BucketMap = HashMap<String, Vec<u8>>;
 
+#[derive(Debug, Default)]
+struct MockStoreInner {
+    hot: HashMap<Bucket, BucketMap>,
+    cold: HashMap<Bucket, BucketMap>,
+}
+
+/// Mock store keeping objects in memory. Archived objects are moved to a separate cold map; accessing them
+/// moves them back and returns [`ObjectStoreError::Thawing`], so that the next access succeeds.
 #[derive(Debug, Default)]
 pub(crate) struct MockStore {
-    inner: Mutex<HashMap<Bucket, BucketMap>>,
+    inner: Mutex<MockStoreInner>,
 }
 
 #[async_trait]
 impl ObjectStore for MockStore {
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
-        let lock = self.inner.lock().await;
-        let maybe_bytes = lock.get(&bucket).and_then(|bucket_map| bucket_map.get(key));
-        maybe_bytes.cloned().ok_or_else(|| {
-            let error_message = format!("missing key: {key} in bucket {bucket}");
-            ObjectStoreError::KeyNotFound(error_message.into())
-        })
+        let mut lock = self.inner.lock().await;
+        let maybe_bytes = lock
+            .hot
+            .get(&bucket)
+            .and_then(|bucket_map| bucket_map.get(key));
+        if let Some(bytes) = maybe_bytes {
+            return Ok(bytes.clone());
+        }
+
+        let maybe_cold_bytes = lock
+            .cold
+            .get_mut(&bucket)
+            .and_then(|bucket_map| bucket_map.remove(key));
+        if let Some(bytes) = maybe_cold_bytes {
+            lock.hot
+                .entry(bucket)
+                .or_default()
+                .insert(key.to_owned(), bytes);
+            let error_message = format!("key {key} in bucket {bucket} is being restored");
+            return Err(ObjectStoreError::Thawing(error_message.into()));
+        }
+
+        let error_message = format!("missing key: {key} in bucket {bucket}");
+        Err(ObjectStoreError::KeyNotFound(error_message.into()))
     }
 
     async fn put_raw(
@@ -32,17 +58,46 @@ impl ObjectStore for MockStore {
         value: Vec<u8>,
     ) -> Result<(), ObjectStoreError> {
         let mut lock = self.inner.lock().await;
-        let bucket_map = lock.entry(bucket).or_default();
+        if let Some(cold_bucket_map) = lock.cold.get_mut(&bucket) {
+            cold_bucket_map.remove(key);
+        }
+        let bucket_map = lock.hot.entry(bucket).or_default();
         bucket_map.insert(key.to_owned(), value);
         Ok(())
     }
 
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
         let mut lock = self.inner.lock().await;
-        let Some(bucket_map) = lock.get_mut(&bucket) else {
-            return Ok(());
+        let inner = &mut *lock;
+        for bucket_maps in [&mut inner.hot, &mut inner.cold] {
+            if let Some(bucket_map) = bucket_maps.get_mut(&bucket) {
+                bucket_map.remove(key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let mut lock = self.inner.lock().await;
+        let maybe_bytes = lock
+            .hot
+            .get_mut(&bucket)
+            .and_then(|bucket_map| bucket_map.remove(key));
+        let Some(bytes) = maybe_bytes else {
+            let is_archived = lock
+                .cold
+                .get(&bucket)
+                .map_or(false, |bucket_map| bucket_map.contains_key(key));
+            if is_archived {
+                return Ok(());
+            }
+            let error_message = format!("missing key: {key} in bucket {bucket}");
+            return Err(ObjectStoreError::KeyNotFound(error_message.into()));
         };
-        bucket_map.remove(key);
+        lock.cold
+            .entry(bucket)
+            .or_default()
+            .insert(key.to_owned(), bytes);
         Ok(())
     }
 
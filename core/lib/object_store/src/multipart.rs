@@ -0,0 +1,132 @@
+//! Helpers for streaming (multipart) uploads.
+
+use std::mem;
+
+use futures::{stream::BoxStream, StreamExt};
+
+use crate::raw::ObjectStoreError;
+
+/// Stream of chunks uploaded using [`ObjectStore::put_stream_raw()`]. Chunks may have arbitrary sizes;
+/// they are regrouped into parts of the size suitable for the store. If the stream returns an error,
+/// the upload is aborted.
+///
+/// [`ObjectStore::put_stream_raw()`]: crate::ObjectStore::put_stream_raw()
+pub type ChunkStream<'a> = BoxStream<'a, Result<Vec<u8>, ObjectStoreError>>;
+
+/// Part of a multipart upload.
+#[derive(Debug)]
+pub(crate) struct Part {
+    pub data: Vec<u8>,
+    /// Offset of the part start in the uploaded object.
+    pub offset: u64,
+    /// Whether this is the last part of the object.
+    pub is_last: bool,
+}
+
+/// Splits a [`ChunkStream`] into parts of a fixed size. The last part may be smaller (and is empty
+/// if the stream is empty); all other parts have exactly the specified size.
+pub(crate) struct Parts<'a> {
+    chunks: ChunkStream<'a>,
+    part_size: usize,
+    buffer: Vec<u8>,
+    offset: u64,
+}
+
+impl<'a> Parts<'a> {
+    pub fn new(chunks: ChunkStream<'a>, part_size: usize) -> Self {
+        assert!(part_size > 0, "part size must be positive");
+        Self {
+            chunks,
+            part_size,
+            buffer: Vec::with_capacity(part_size),
+            offset: 0,
+        }
+    }
+
+    /// Returns the next part. Must not be called after the last part was returned.
+    pub async fn next(&mut self) -> Result<Part, ObjectStoreError> {
+        loop {
+            // A part is only emitted once it's known that it's not the last one, i.e., the buffer has more data.
+            if self.buffer.len() > self.part_size {
+                let rest = self.buffer.split_off(self.part_size);
+                let data = mem::replace(&mut self.buffer, rest);
+                return Ok(self.emit(data, false));
+            }
+
+            match self.chunks.next().await {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk?),
+                None => {
+                    let data = mem::take(&mut self.buffer);
+                    return Ok(self.emit(data, true));
+                }
+            }
+        }
+    }
+
+    fn emit(&mut self, data: Vec<u8>, is_last: bool) -> Part {
+        let offset = self.offset;
+        self.offset += data.len() as u64;
+        Part {
+            data,
+            offset,
+            is_last,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+
+    fn chunk_stream(chunks: Vec<Vec<u8>>) -> ChunkStream<'static> {
+        stream::iter(chunks.into_iter().map(Ok)).boxed()
+    }
+
+    async fn collect_parts(chunks: Vec<Vec<u8>>, part_size: usize) -> Vec<Part> {
+        let mut parts = Parts::new(chunk_stream(chunks), part_size);
+        let mut collected = vec![];
+        loop {
+            let part = parts.next().await.unwrap();
+            let is_last = part.is_last;
+            collected.push(part);
+            if is_last {
+                return collected;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn splitting_chunks_into_parts() {
+        let parts = collect_parts(vec![], 4).await;
+        assert_eq!(parts.len(), 1);
+        assert!(parts[0].is_last && parts[0].data.is_empty());
+
+        let parts = collect_parts(vec![vec![1, 2, 3], vec![4, 5, 6, 7, 8], vec![9]], 4).await;
+        let part_data: Vec<_> = parts.iter().map(|part| part.data.as_slice()).collect();
+        assert_eq!(part_data, [&[1, 2, 3, 4] as &[_], &[5, 6, 7, 8], &[9]]);
+        let offsets: Vec<_> = parts.iter().map(|part| part.offset).collect();
+        assert_eq!(offsets, [0, 4, 8]);
+        assert!(parts[2].is_last && !parts[1].is_last);
+
+        // The stream ending at a part boundary doesn't produce an empty last part.
+        let parts = collect_parts(vec![vec![1, 2, 3, 4, 5, 6, 7, 8]], 4).await;
+        let part_data: Vec<_> = parts.iter().map(|part| part.data.as_slice()).collect();
+        assert_eq!(part_data, [&[1, 2, 3, 4] as &[_], &[5, 6, 7, 8]]);
+        assert!(parts[1].is_last);
+    }
+
+    #[tokio::test]
+    async fn stream_error_is_propagated() {
+        let chunks = vec![
+            Ok(vec![1, 2, 3, 4, 5]),
+            Err(ObjectStoreError::Other("oops".into())),
+        ];
+        let mut parts = Parts::new(stream::iter(chunks).boxed(), 4);
+        let part = parts.next().await.unwrap();
+        assert_eq!(part.data, [1, 2, 3, 4]);
+        let err = parts.next().await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Other(_)), "{err}");
+    }
+}
@@ -14,7 +14,10 @@ use zksync_types::{
     L1BatchNumber,
 };
 
-use crate::raw::{BoxedError, Bucket, ObjectStore, ObjectStoreError};
+use crate::{
+    multipart::ChunkStream,
+    raw::{BoxedError, Bucket, ObjectStore, ObjectStoreError},
+};
 
 /// Object that can be stored in an [`ObjectStore`].
 pub trait StoredObject: Sized {
@@ -159,6 +162,43 @@ impl dyn ObjectStore + '_ {
         Ok(key)
     }
 
+    /// Stores an already serialized value supplied as a stream of chunks. This allows uploading large values
+    /// without buffering them in memory. See [`ObjectStore::put_stream_raw()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream returns an error or if the insertion / replacement operation fails.
+    pub async fn put_stream<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        chunks: ChunkStream<'_>,
+    ) -> Result<String, ObjectStoreError> {
+        let key = V::encode_key(key);
+        self.put_stream_raw(V::BUCKET, &key, chunks).await?;
+        Ok(key)
+    }
+
+    /// Moves the value associated with the key to the cold storage class. See
+    /// [`ObjectStore::archive_raw()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object does not exist or cannot be moved.
+    pub async fn archive<V: StoredObject>(&self, key: V::Key<'_>) -> Result<(), ObjectStoreError> {
+        let key = V::encode_key(key);
+        self.archive_raw(V::BUCKET, &key).await
+    }
+
+    /// Removes the value associated with the key. See [`ObjectStore::remove_raw()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if removal fails.
+    pub async fn remove<V: StoredObject>(&self, key: V::Key<'_>) -> Result<(), ObjectStoreError> {
+        let key = V::encode_key(key);
+        self.remove_raw(V::BUCKET, &key).await
+    }
+
     pub fn get_storage_prefix<V: StoredObject>(&self) -> String {
         self.storage_prefix_raw(V::BUCKET)
     }
@@ -166,6 +206,7 @@ impl dyn ObjectStore + '_ {
 
 #[cfg(test)]
 mod tests {
+    use futures::{stream, StreamExt};
     use zksync_types::{
         snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
         AccountTreeId, Bytes, StorageKey, H160, H256,
@@ -248,4 +289,53 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn objects_can_be_uploaded_as_streams() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![SnapshotFactoryDependency {
+                bytecode: Bytes(vec![1; 1_000]),
+            }],
+        };
+        let bytes = factory_deps.serialize().unwrap();
+        let chunks: Vec<_> = bytes.chunks(100).map(|chunk| Ok(chunk.to_vec())).collect();
+        store
+            .put_stream::<SnapshotFactoryDependencies>(key, stream::iter(chunks).boxed())
+            .await
+            .unwrap();
+        let reconstructed_factory_deps = store.get(key).await.unwrap();
+        assert_eq!(factory_deps, reconstructed_factory_deps);
+    }
+
+    #[tokio::test]
+    async fn archived_objects_are_thawed_on_access() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![SnapshotFactoryDependency {
+                bytecode: Bytes(vec![1, 51, 101, 201, 255]),
+            }],
+        };
+        store.put(key, &factory_deps).await.unwrap();
+        store
+            .archive::<SnapshotFactoryDependencies>(key)
+            .await
+            .unwrap();
+
+        let err = store
+            .get::<SnapshotFactoryDependencies>(key)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Thawing(_)), "{err:?}");
+        let restored_factory_deps = store.get(key).await.unwrap();
+        assert_eq!(factory_deps, restored_factory_deps);
+
+        let err = store
+            .archive::<SnapshotFactoryDependencies>(L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err:?}");
+    }
 }
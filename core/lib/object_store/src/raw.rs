@@ -1,12 +1,21 @@
-use std::{error, fmt, sync::Arc};
+use std::{error, fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
+use zksync_health_check::ReactiveHealthCheck;
+use zksync_utils::error::{ClassifyError, ErrorKind};
 
 use crate::{
+    azure::{AzureBlobAuthMode, AzureBlobStorage},
+    content_addressed::ContentAddressedObjectStore,
+    encryption::{EncryptedObjectStore, MasterKey},
     file::FileBackedObjectStore,
     gcs::{GoogleCloudStorage, GoogleCloudStorageAuthMode},
     mock::MockStore,
+    multipart::ChunkStream,
+    resilience::{CircuitBreaker, ResilientObjectStore},
+    s3::{S3AuthMode, S3Endpoint, S3Storage},
 };
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
@@ -24,10 +33,11 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    TreeSnapshot,
 }
 
 impl Bucket {
-    pub(crate) fn as_str(self) -> &'static str {
+    pub fn as_str(self) -> &'static str {
         match self {
             Self::ProverJobs => "prover_jobs",
             Self::WitnessInput => "witness_inputs",
@@ -40,6 +50,7 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::TreeSnapshot => "merkle_tree_snapshots",
         }
     }
 }
@@ -60,7 +71,13 @@ pub enum ObjectStoreError {
     KeyNotFound(BoxedError),
     /// Object (de)serialization failed.
     Serialization(BoxedError),
-    /// Other error has occurred when accessing the store (e.g., a network error).
+    /// An object with the specified key is in the cold storage and is being restored from it.
+    /// The object will become available after some time, so the request should be retried later.
+    Thawing(BoxedError),
+    /// Transient error has occurred when accessing the store (e.g., a network error, a timeout, or a 5xx response).
+    /// The request may succeed if retried.
+    Transient(BoxedError),
+    /// Other error has occurred when accessing the store (e.g., an authorization error).
     Other(BoxedError),
 }
 
@@ -69,6 +86,8 @@ impl fmt::Display for ObjectStoreError {
         match self {
             Self::KeyNotFound(err) => write!(formatter, "key not found: {err}"),
             Self::Serialization(err) => write!(formatter, "serialization error: {err}"),
+            Self::Thawing(err) => write!(formatter, "object is being restored: {err}"),
+            Self::Transient(err) => write!(formatter, "transient error: {err}"),
             Self::Other(err) => write!(formatter, "other error: {err}"),
         }
     }
@@ -77,9 +96,21 @@ impl fmt::Display for ObjectStoreError {
 impl error::Error for ObjectStoreError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
-            Self::KeyNotFound(err) | Self::Serialization(err) | Self::Other(err) => {
-                Some(err.as_ref())
-            }
+            Self::KeyNotFound(err)
+            | Self::Serialization(err)
+            | Self::Thawing(err)
+            | Self::Transient(err)
+            | Self::Other(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+impl ClassifyError for ObjectStoreError {
+    fn error_kind(&self) -> ErrorKind {
+        match self {
+            Self::KeyNotFound(_) => ErrorKind::Fatal,
+            Self::Serialization(_) => ErrorKind::DataCorruption,
+            Self::Thawing(_) | Self::Transient(_) | Self::Other(_) => ErrorKind::Retryable,
         }
     }
 }
@@ -98,6 +129,8 @@ pub trait ObjectStore: 'static + fmt::Debug + Send + Sync {
     /// # Errors
     ///
     /// Returns an error if an object with the `key` does not exist or cannot be accessed.
+    /// If the object was moved to the cold storage and needs to be restored before it can be accessed,
+    /// restoration is started and [`ObjectStoreError::Thawing`] is returned.
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError>;
 
     /// Stores the value associating it with the key into the given bucket.
@@ -113,6 +146,30 @@ pub trait ObjectStore: 'static + fmt::Debug + Send + Sync {
         value: Vec<u8>,
     ) -> Result<(), ObjectStoreError>;
 
+    /// Stores the value supplied as a stream of chunks, associating it with the key into the given bucket.
+    /// Unlike [`Self::put_raw()`], this method doesn't require buffering the entire value in memory;
+    /// depending on the store, the value is uploaded in parts (e.g., using a multipart upload for S3).
+    /// If the upload fails, the partially uploaded value is discarded. Since the stream is consumed by the upload,
+    /// failed uploads are not retried.
+    ///
+    /// The default implementation buffers the entire value and calls [`Self::put_raw()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream returns an error, or if the insertion / replacement operation fails.
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        mut chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let mut value = vec![];
+        while let Some(chunk) = chunks.next().await {
+            value.extend_from_slice(&chunk?);
+        }
+        self.put_raw(bucket, key, value).await
+    }
+
     /// Removes the value associated with the key from the given bucket if it exists.
     ///
     /// # Errors
@@ -120,6 +177,19 @@ pub trait ObjectStore: 'static + fmt::Debug + Send + Sync {
     /// Returns an error if removal fails.
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError>;
 
+    /// Moves the value associated with the key in the given bucket to the cold storage class,
+    /// which is cheaper to store, but may be slower to access. The value remains accessible
+    /// via [`Self::get_raw()`], which may need to restore (thaw) it first.
+    ///
+    /// The default implementation is a no-op, which is appropriate for stores without a cold storage tier.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object with the `key` does not exist or cannot be moved.
+    async fn archive_raw(&self, _bucket: Bucket, _key: &str) -> Result<(), ObjectStoreError> {
+        Ok(())
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String;
 }
 
@@ -138,10 +208,23 @@ impl<T: ObjectStore + ?Sized> ObjectStore for Arc<T> {
         (**self).put_raw(bucket, key, value).await
     }
 
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        (**self).put_stream_raw(bucket, key, chunks).await
+    }
+
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
         (**self).remove_raw(bucket, key).await
     }
 
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        (**self).archive_raw(bucket, key).await
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         (**self).storage_prefix_raw(bucket)
     }
@@ -154,9 +237,16 @@ enum ObjectStoreOrigin {
 }
 
 /// Factory of [`ObjectStore`]s.
+///
+/// Stores created from a config are wrapped in a resilience layer timing out and retrying requests,
+/// and, if configured, in layers for [encryption at rest](ObjectStoreConfig::encryption_master_key)
+/// and the [content-addressed mode](ObjectStoreConfig::content_addressed).
+/// All stores created by the same factory share a circuit breaker; its state can be monitored
+/// using [`Self::health_check()`].
 #[derive(Debug)]
 pub struct ObjectStoreFactory {
     origin: ObjectStoreOrigin,
+    circuit_breaker: Arc<CircuitBreaker>,
 }
 
 impl ObjectStoreFactory {
@@ -164,11 +254,16 @@ impl ObjectStoreFactory {
     ///
     /// # Panics
     ///
-    /// If the GCS-backed implementation is configured, this constructor will panic if called
+    /// If the GCS-, S3- or Azure-backed implementation is configured, this constructor will panic if called
     /// outside the Tokio runtime.
     pub fn new(config: ObjectStoreConfig) -> Self {
+        let circuit_breaker = CircuitBreaker::new(
+            config.circuit_breaker_threshold,
+            config.circuit_breaker_cooldown(),
+        );
         Self {
             origin: ObjectStoreOrigin::Config(config),
+            circuit_breaker: Arc::new(circuit_breaker),
         }
     }
 
@@ -178,17 +273,60 @@ impl ObjectStoreFactory {
     pub fn mock() -> Self {
         Self {
             origin: ObjectStoreOrigin::Mock(Arc::new(MockStore::default())),
+            circuit_breaker: Arc::new(CircuitBreaker::new(0, Duration::ZERO)),
         }
     }
 
+    /// Returns a health check for the stores created by this factory. The check is affected while
+    /// the circuit breaker is open, i.e., while the store is persistently unavailable.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.circuit_breaker.health_check()
+    }
+
     /// Creates an [`ObjectStore`].
     pub async fn create_store(&self) -> Arc<dyn ObjectStore> {
         match &self.origin {
-            ObjectStoreOrigin::Config(config) => Self::create_from_config(config).await,
+            ObjectStoreOrigin::Config(config) => {
+                let store = Self::create_from_config(config).await;
+                let store: Arc<dyn ObjectStore> = Arc::new(ResilientObjectStore::new(
+                    store,
+                    config.max_retries,
+                    config.request_timeout(),
+                    self.circuit_breaker.clone(),
+                ));
+                let store: Arc<dyn ObjectStore> = match Self::encryption_master_key(config).await {
+                    Some(master_key) => Arc::new(EncryptedObjectStore::new(
+                        store,
+                        master_key,
+                        config.encrypted_buckets.clone(),
+                    )),
+                    None => store,
+                };
+                if config.content_addressed {
+                    Arc::new(ContentAddressedObjectStore::new(store))
+                } else {
+                    store
+                }
+            }
             ObjectStoreOrigin::Mock(store) => Arc::new(Arc::clone(store)),
         }
     }
 
+    async fn encryption_master_key(config: &ObjectStoreConfig) -> Option<MasterKey> {
+        match (&config.encryption_master_key, &config.encryption_kms_key_id) {
+            (Some(hex_key), None) => {
+                let master_key = MasterKey::from_hex(hex_key)
+                    .unwrap_or_else(|err| panic!("invalid object store encryption key: {err}"));
+                Some(master_key)
+            }
+            (None, Some(key_id)) => Some(MasterKey::aws_kms(key_id.clone()).await),
+            (None, None) => None,
+            (Some(_), Some(_)) => {
+                panic!("`encryption_master_key` and `encryption_kms_key_id` are mutually exclusive")
+            }
+        }
+    }
+
     async fn create_from_config(config: &ObjectStoreConfig) -> Arc<dyn ObjectStore> {
         match &config.mode {
             ObjectStoreMode::GCS { bucket_base_url } => {
@@ -199,6 +337,7 @@ impl ObjectStoreFactory {
                     GoogleCloudStorageAuthMode::Authenticated,
                     bucket_base_url.clone(),
                     config.max_retries,
+                    config.cold_storage_class.clone(),
                 )
                 .await;
                 Arc::new(store)
@@ -214,15 +353,25 @@ impl ObjectStoreFactory {
                     ),
                     bucket_base_url.clone(),
                     config.max_retries,
+                    config.cold_storage_class.clone(),
                 )
                 .await;
                 Arc::new(store)
             }
             ObjectStoreMode::FileBacked {
                 file_backed_base_path,
+                file_backed_fsync,
+                file_backed_sharding,
+                file_backed_archive_path,
             } => {
                 tracing::trace!("Initialized FileBacked Object store");
-                let store = FileBackedObjectStore::new(file_backed_base_path.clone()).await;
+                let store = FileBackedObjectStore::new(
+                    file_backed_base_path.clone(),
+                    file_backed_archive_path.clone(),
+                )
+                .await
+                .with_fsync(*file_backed_fsync)
+                .with_sharding(*file_backed_sharding);
                 Arc::new(store)
             }
             ObjectStoreMode::GCSAnonymousReadOnly { bucket_base_url } => {
@@ -231,10 +380,60 @@ impl ObjectStoreFactory {
                     GoogleCloudStorageAuthMode::Anonymous,
                     bucket_base_url.clone(),
                     config.max_retries,
+                    config.cold_storage_class.clone(),
                 )
                 .await;
                 Arc::new(store)
             }
+            ObjectStoreMode::S3 {
+                bucket_base_url,
+                region,
+                endpoint,
+                force_path_style,
+                access_key_id,
+                secret_access_key,
+            } => {
+                let auth_mode = match (access_key_id, secret_access_key) {
+                    (Some(access_key_id), Some(secret_access_key)) => S3AuthMode::Static {
+                        access_key_id: access_key_id.clone(),
+                        secret_access_key: secret_access_key.clone(),
+                    },
+                    (None, None) => S3AuthMode::Default,
+                    _ => panic!("S3 `access_key_id` and `secret_access_key` must be set together"),
+                };
+                tracing::trace!("Initialized S3 Object store");
+                let endpoint = S3Endpoint {
+                    region: region.clone(),
+                    url: endpoint.clone(),
+                    force_path_style: *force_path_style,
+                };
+                let store = S3Storage::new(
+                    auth_mode,
+                    endpoint,
+                    bucket_base_url.clone(),
+                    config.cold_storage_class.clone(),
+                )
+                .await;
+                Arc::new(store)
+            }
+            ObjectStoreMode::AzureBlob {
+                azure_storage_account,
+                bucket_base_url,
+                azure_sas_token,
+            } => {
+                let auth_mode = match azure_sas_token {
+                    Some(token) => AzureBlobAuthMode::SasToken(token.clone()),
+                    None => AzureBlobAuthMode::ManagedIdentity,
+                };
+                tracing::trace!("Initialized Azure Blob Object store with {auth_mode:?} auth mode");
+                let store = AzureBlobStorage::new(
+                    auth_mode,
+                    azure_storage_account.clone(),
+                    bucket_base_url.clone(),
+                    config.cold_storage_class.clone(),
+                );
+                Arc::new(store)
+            }
         }
     }
 }
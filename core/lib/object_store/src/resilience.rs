@@ -0,0 +1,410 @@
+//! Resilience layer wrapping all [`ObjectStore`] backends created from a config.
+//!
+//! The layer times out individual request attempts, retries requests failed with a
+//! [transient error](ObjectStoreError::Transient) with jittered exponential backoff, and trips a circuit breaker
+//! if requests keep failing after retries. While the circuit breaker is open, requests fail immediately
+//! without reaching the store, and the outage is surfaced via the object store health check.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use rand::Rng;
+use serde::Serialize;
+use tokio::time::Instant;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+
+use crate::{
+    metrics::RESILIENCE_METRICS,
+    multipart::ChunkStream,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Delay before the first retry. Subsequent delays are doubled.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Checks whether an HTTP status code returned by a store signals a transient error.
+pub(crate) fn is_transient_http_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+#[derive(Debug, Serialize)]
+struct CircuitBreakerHealthDetails {
+    consecutive_failures: u32,
+    last_error: String,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Circuit breaker shared by all stores created by the same factory.
+///
+/// The breaker opens after `threshold` consecutive requests have failed with a transient error (after retries).
+/// After `cooldown`, requests are let through again; the breaker closes after the first successful request,
+/// or re-opens after the first failed one.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitBreakerState>,
+    health_updater: HealthUpdater,
+}
+
+impl CircuitBreaker {
+    /// Creates a breaker. If `threshold` is 0, the breaker never opens.
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("object_store");
+        health_updater.update(HealthStatus::Ready.into());
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::default(),
+            health_updater,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Checks whether a request to the specified bucket can be sent to the store.
+    fn check(&self, bucket: Bucket) -> Result<(), ObjectStoreError> {
+        let state = self.state.lock().unwrap();
+        if let Some(opened_at) = state.opened_at {
+            if opened_at.elapsed() < self.cooldown {
+                RESILIENCE_METRICS.rejected_requests[&bucket.as_str()].inc();
+                let message = format!(
+                    "circuit breaker is open after {} consecutive failures",
+                    state.consecutive_failures
+                );
+                return Err(ObjectStoreError::Transient(message.into()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the result of a request. Non-transient errors (e.g., a missing key) mean that the store is reachable.
+    fn record<T>(&self, result: &Result<T, ObjectStoreError>) {
+        match result {
+            Err(err @ ObjectStoreError::Transient(_)) => self.record_failure(err),
+            _ => self.record_success(),
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.consecutive_failures == 0 {
+            return;
+        }
+        if state.opened_at.is_some() {
+            tracing::info!("Object store has recovered; closing circuit breaker");
+            self.health_updater.update(HealthStatus::Ready.into());
+        }
+        *state = CircuitBreakerState::default();
+    }
+
+    fn record_failure(&self, err: &ObjectStoreError) {
+        if self.threshold == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures < self.threshold {
+            return;
+        }
+        if state.opened_at.is_none() {
+            tracing::warn!(
+                "Object store requests have failed {} times in a row, opening circuit breaker for {:?}; \
+                 last error: {err}",
+                state.consecutive_failures,
+                self.cooldown
+            );
+            RESILIENCE_METRICS.circuit_breaker_openings.inc();
+        }
+        // (Re-)open the breaker; this also handles failed requests after the cooldown.
+        state.opened_at = Some(Instant::now());
+
+        let details = CircuitBreakerHealthDetails {
+            consecutive_failures: state.consecutive_failures,
+            last_error: err.to_string(),
+        };
+        self.health_updater
+            .update(Health::from(HealthStatus::Affected).with_details(details));
+    }
+}
+
+/// [`ObjectStore`] wrapper implementing timeouts, retries and circuit breaking.
+#[derive(Debug)]
+pub(crate) struct ResilientObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    max_retries: u16,
+    request_timeout: Option<Duration>,
+    circuit_breaker: Arc<CircuitBreaker>,
+}
+
+impl ResilientObjectStore {
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        max_retries: u16,
+        request_timeout: Option<Duration>,
+        circuit_breaker: Arc<CircuitBreaker>,
+    ) -> Self {
+        Self {
+            inner,
+            max_retries,
+            request_timeout,
+            circuit_breaker,
+        }
+    }
+
+    fn jittered(backoff: Duration) -> Duration {
+        rand::thread_rng().gen_range(backoff / 2..=backoff)
+    }
+
+    async fn request<T, Fut>(
+        &self,
+        bucket: Bucket,
+        operation: &str,
+        f: impl Fn() -> Fut,
+    ) -> Result<T, ObjectStoreError>
+    where
+        Fut: Future<Output = Result<T, ObjectStoreError>>,
+    {
+        self.circuit_breaker.check(bucket)?;
+        let mut retries = 0;
+        let mut backoff = INITIAL_BACKOFF;
+        let result = loop {
+            let result = match self.request_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, f())
+                    .await
+                    .unwrap_or_else(|_| {
+                        RESILIENCE_METRICS.timed_out_requests[&bucket.as_str()].inc();
+                        let message = format!("request timed out after {timeout:?}");
+                        Err(ObjectStoreError::Transient(message.into()))
+                    }),
+                None => f().await,
+            };
+
+            match result {
+                Err(err @ ObjectStoreError::Transient(_)) if retries < self.max_retries => {
+                    retries += 1;
+                    let delay = Self::jittered(backoff);
+                    tracing::warn!(
+                        %err,
+                        "Failed `{operation}` request for bucket {bucket} ({retries}/{}), retrying in {delay:?}",
+                        self.max_retries
+                    );
+                    RESILIENCE_METRICS.retried_requests[&bucket.as_str()].inc();
+                    tokio::time::sleep(delay).await;
+                    backoff *= 2;
+                }
+                result => break result,
+            }
+        };
+
+        self.circuit_breaker.record(&result);
+        result
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ResilientObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        self.request(bucket, "get", || self.inner.get_raw(bucket, key))
+            .await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        self.request(bucket, "put", || {
+            self.inner.put_raw(bucket, key, value.clone())
+        })
+        .await
+    }
+
+    // Stream uploads cannot be retried or timed out as a whole, since the stream is consumed by the upload,
+    // and its consumption rate is controlled by the caller.
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        self.circuit_breaker.check(bucket)?;
+        let result = self.inner.put_stream_raw(bucket, key, chunks).await;
+        self.circuit_breaker.record(&result);
+        result
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.request(bucket, "remove", || self.inner.remove_raw(bucket, key))
+            .await
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.request(bucket, "archive", || self.inner.archive_raw(bucket, key))
+            .await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use zksync_health_check::CheckHealth;
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    /// Store failing the specified number of first requests with a transient error.
+    #[derive(Debug, Default)]
+    struct FlakyStore {
+        inner: MockStore,
+        failures_left: AtomicUsize,
+        requests: AtomicUsize,
+    }
+
+    impl FlakyStore {
+        fn new(failures: usize) -> Self {
+            Self {
+                failures_left: AtomicUsize::new(failures),
+                ..Self::default()
+            }
+        }
+
+        fn maybe_fail(&self) -> Result<(), ObjectStoreError> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let failures_left = self.failures_left.load(Ordering::SeqCst);
+            if failures_left > 0 {
+                self.failures_left
+                    .store(failures_left - 1, Ordering::SeqCst);
+                return Err(ObjectStoreError::Transient("service unavailable".into()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            self.maybe_fail()?;
+            self.inner.get_raw(bucket, key).await
+        }
+
+        async fn put_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            self.maybe_fail()?;
+            self.inner.put_raw(bucket, key, value).await
+        }
+
+        async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+            self.maybe_fail()?;
+            self.inner.remove_raw(bucket, key).await
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            self.inner.storage_prefix_raw(bucket)
+        }
+    }
+
+    fn resilient_store(
+        inner: &Arc<FlakyStore>,
+        max_retries: u16,
+        circuit_breaker: &Arc<CircuitBreaker>,
+    ) -> ResilientObjectStore {
+        let inner: Arc<dyn ObjectStore> = inner.clone();
+        ResilientObjectStore::new(inner, max_retries, None, circuit_breaker.clone())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transient_errors_are_retried() {
+        let inner = Arc::new(FlakyStore::new(2));
+        let circuit_breaker = Arc::new(CircuitBreaker::new(1, Duration::from_secs(30)));
+        let store = resilient_store(&inner, 2, &circuit_breaker);
+
+        store
+            .put_raw(Bucket::ProverJobs, "test", vec![1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(inner.requests.load(Ordering::SeqCst), 3);
+        let value = store.get_raw(Bucket::ProverJobs, "test").await.unwrap();
+        assert_eq!(value, [1, 2, 3]);
+
+        let health = circuit_breaker.health_check().check_health().await;
+        assert_eq!(health.status(), HealthStatus::Ready);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_transient_errors_are_not_retried() {
+        let inner = Arc::new(FlakyStore::new(0));
+        let circuit_breaker = Arc::new(CircuitBreaker::new(1, Duration::from_secs(30)));
+        let store = resilient_store(&inner, 2, &circuit_breaker);
+
+        let err = store
+            .get_raw(Bucket::ProverJobs, "missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        assert_eq!(inner.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_opens_and_closes() {
+        let inner = Arc::new(FlakyStore::new(3));
+        let cooldown = Duration::from_secs(30);
+        let circuit_breaker = Arc::new(CircuitBreaker::new(2, cooldown));
+        let health_check = circuit_breaker.health_check();
+        let store = resilient_store(&inner, 0, &circuit_breaker);
+
+        for _ in 0..2 {
+            let err = store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+            assert!(matches!(err, ObjectStoreError::Transient(_)), "{err}");
+        }
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::Affected
+        );
+
+        // Requests are rejected without reaching the store.
+        let err = store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+        assert!(err.to_string().contains("circuit breaker"), "{err}");
+        assert_eq!(inner.requests.load(Ordering::SeqCst), 2);
+
+        // After the cooldown, a failed request re-opens the breaker.
+        tokio::time::advance(cooldown).await;
+        store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+        assert_eq!(inner.requests.load(Ordering::SeqCst), 3);
+        store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+        assert_eq!(inner.requests.load(Ordering::SeqCst), 3);
+
+        // The store has recovered; a missing key closes the breaker.
+        tokio::time::advance(cooldown).await;
+        let err = store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+        store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+        assert_eq!(inner.requests.load(Ordering::SeqCst), 5);
+    }
+}
@@ -0,0 +1,461 @@
+//! S3-based [`ObjectStore`] implementation. Works both with AWS S3 and S3-compatible stores (e.g., MinIO).
+
+use std::{error, fmt};
+
+use async_trait::async_trait;
+use aws_config::{retry::RetryConfig, BehaviorVersion, Region};
+use aws_sdk_s3::{
+    config::{http::HttpResponse, Credentials},
+    error::{ProvideErrorMetadata, SdkError},
+    operation::get_object::GetObjectError,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart, RestoreRequest, StorageClass},
+    Client,
+};
+
+use crate::{
+    metrics::{LIFECYCLE_METRICS, REMOTE_STORE_METRICS},
+    multipart::{ChunkStream, Part, Parts},
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+    resilience::is_transient_http_status,
+};
+
+/// Number of days an object restored from an archival storage class (e.g., `GLACIER`) stays accessible.
+/// After this period, the restored copy is removed, and the object needs to be restored again.
+const RESTORED_OBJECT_LIFETIME_DAYS: i32 = 1;
+
+/// Error code returned by S3 if a restoration request is sent for an object that is already being restored.
+const RESTORE_ALREADY_IN_PROGRESS: &str = "RestoreAlreadyInProgress";
+
+/// Size of parts in multipart uploads. S3 requires all parts except for the last one to be at least 5 MiB.
+const MULTIPART_PART_SIZE: usize = 8 << 20;
+
+#[derive(Clone)]
+pub enum S3AuthMode {
+    /// Static access key.
+    Static {
+        access_key_id: String,
+        secret_access_key: String,
+    },
+    /// Credentials resolved using the default AWS credential chain (environment variables,
+    /// shared credential files, IAM roles etc.).
+    Default,
+}
+
+impl fmt::Debug for S3AuthMode {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Never output the secret key.
+            Self::Static { access_key_id, .. } => formatter
+                .debug_struct("Static")
+                .field("access_key_id", access_key_id)
+                .finish_non_exhaustive(),
+            Self::Default => formatter.write_str("Default"),
+        }
+    }
+}
+
+/// Location of an S3 (or S3-compatible) store.
+#[derive(Debug, Clone)]
+pub struct S3Endpoint {
+    pub region: String,
+    /// Overrides the default AWS endpoint for the region.
+    pub url: Option<String>,
+    pub force_path_style: bool,
+}
+
+impl S3Endpoint {
+    /// Returns the URL of the bucket, taking into account the addressing style.
+    fn bucket_url(&self, bucket: &str) -> String {
+        let default_host;
+        let (scheme, host) = match self.url.as_deref().map(|url| url.trim_end_matches('/')) {
+            Some(url) => url.split_once("://").unwrap_or(("https", url)),
+            None => {
+                default_host = format!("s3.{}.amazonaws.com", self.region);
+                ("https", default_host.as_str())
+            }
+        };
+
+        if self.force_path_style {
+            format!("{scheme}://{host}/{bucket}")
+        } else {
+            format!("{scheme}://{bucket}.{host}")
+        }
+    }
+}
+
+pub struct S3Storage {
+    bucket: String,
+    endpoint: S3Endpoint,
+    /// Storage class (e.g., `GLACIER`) that archived objects are copied to.
+    cold_storage_class: Option<String>,
+    client: Client,
+}
+
+impl fmt::Debug for S3Storage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3Storage")
+            .field("bucket", &self.bucket)
+            .field("endpoint", &self.endpoint)
+            .field("cold_storage_class", &self.cold_storage_class)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    pub async fn new(
+        auth_mode: S3AuthMode,
+        endpoint: S3Endpoint,
+        bucket: String,
+        cold_storage_class: Option<String>,
+    ) -> Self {
+        // Requests are retried by the resilience layer wrapping the store.
+        let retry_config = RetryConfig::disabled();
+        let mut config_loader = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(endpoint.region.clone()))
+            .retry_config(retry_config);
+        if let Some(url) = &endpoint.url {
+            config_loader = config_loader.endpoint_url(url);
+        }
+        if let S3AuthMode::Static {
+            access_key_id,
+            secret_access_key,
+        } = auth_mode
+        {
+            let credentials = Credentials::new(
+                access_key_id,
+                secret_access_key,
+                None,
+                None,
+                "zksync_object_store",
+            );
+            config_loader = config_loader.credentials_provider(credentials);
+        }
+        let sdk_config = config_loader.load().await;
+
+        let client_config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(endpoint.force_path_style)
+            .build();
+        Self {
+            client: Client::from_conf(client_config),
+            bucket,
+            endpoint,
+            cold_storage_class,
+        }
+    }
+
+    fn filename(bucket: Bucket, key: &str) -> String {
+        format!("{bucket}/{key}")
+    }
+
+    /// Requests restoring an archived object. Returns `Ok(true)` if restoration was started by this call.
+    async fn start_thawing(&self, filename: &str) -> Result<bool, ObjectStoreError> {
+        let restore_request = RestoreRequest::builder()
+            .days(RESTORED_OBJECT_LIFETIME_DAYS)
+            .build();
+        let response = self
+            .client
+            .restore_object()
+            .bucket(&self.bucket)
+            .key(filename)
+            .restore_request(restore_request)
+            .send()
+            .await;
+        match response {
+            Ok(_) => Ok(true),
+            Err(err) if err.code() == Some(RESTORE_ALREADY_IN_PROGRESS) => Ok(false),
+            Err(err) => Err(convert_error(err)),
+        }
+    }
+
+    /// Uploads all parts of a multipart upload with the specified ID and completes the upload.
+    async fn upload_parts(
+        &self,
+        filename: &str,
+        upload_id: &str,
+        mut part: Part,
+        parts: &mut Parts<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let mut completed_parts = vec![];
+        loop {
+            // Part numbers are 1-based.
+            let part_number = completed_parts.len() as i32 + 1;
+            let output = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(filename)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(part.data))
+                .send()
+                .await
+                .map_err(convert_error)?;
+            completed_parts.push(
+                CompletedPart::builder()
+                    .part_number(part_number)
+                    .set_e_tag(output.e_tag().map(str::to_owned))
+                    .build(),
+            );
+            if part.is_last {
+                break;
+            }
+            part = parts.next().await?;
+        }
+
+        let completed_upload = CompletedMultipartUpload::builder()
+            .set_parts(Some(completed_parts))
+            .build();
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(filename)
+            .upload_id(upload_id)
+            .multipart_upload(completed_upload)
+            .send()
+            .await
+            .map_err(convert_error)?;
+        Ok(())
+    }
+}
+
+fn convert_error<E>(err: SdkError<E, HttpResponse>) -> ObjectStoreError
+where
+    E: ProvideErrorMetadata + error::Error + Send + Sync + 'static,
+{
+    let is_not_found = matches!(err.code(), Some("NoSuchKey" | "NotFound"));
+    let is_transient = match &err {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) | SdkError::ResponseError(_) => {
+            true
+        }
+        SdkError::ServiceError(err) => is_transient_http_status(err.raw().status().as_u16()),
+        _ => false,
+    };
+
+    if is_not_found {
+        ObjectStoreError::KeyNotFound(err.into())
+    } else if is_transient {
+        ObjectStoreError::Transient(err.into())
+    } else {
+        ObjectStoreError::Other(err.into())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Storage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = REMOTE_STORE_METRICS.start_fetch(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Fetching data from S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&filename)
+            .send()
+            .await;
+        let output = match response {
+            Ok(output) => output,
+            Err(err) => {
+                // S3 returns this error for objects in archival storage classes that weren't restored.
+                let is_archived = err
+                    .as_service_error()
+                    .map_or(false, GetObjectError::is_invalid_object_state);
+                if !is_archived {
+                    return Err(convert_error(err));
+                }
+                if self.start_thawing(&filename).await? {
+                    LIFECYCLE_METRICS.thawed_objects[&bucket.as_str()].inc();
+                }
+                let error_message = format!("key {key} in bucket {bucket} is being restored");
+                return Err(ObjectStoreError::Thawing(error_message.into()));
+            }
+        };
+        let blob = output
+            .body
+            .collect()
+            .await
+            .map_err(|err| ObjectStoreError::Transient(err.into()))?;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(blob.into_bytes().to_vec())
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = REMOTE_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Storing data to S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(filename)
+            .body(ByteStream::from(value))
+            .send()
+            .await
+            .map_err(convert_error)?;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let mut parts = Parts::new(chunks, MULTIPART_PART_SIZE);
+        let first_part = parts.next().await?;
+        if first_part.is_last {
+            // The object is small enough to be uploaded in a single request.
+            return self.put_raw(bucket, key, first_part.data).await;
+        }
+
+        let store_latency = REMOTE_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Starting multipart upload to S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&filename)
+            .send()
+            .await
+            .map_err(convert_error)?;
+        let upload_id = output.upload_id().ok_or_else(|| {
+            ObjectStoreError::Other("S3 returned no ID for the created multipart upload".into())
+        })?;
+
+        let result = self
+            .upload_parts(&filename, upload_id, first_part, &mut parts)
+            .await;
+        if let Err(err) = &result {
+            tracing::info!("Aborting multipart upload to S3 for key {filename}: {err}");
+            // Uploaded parts are billed until the upload is aborted, so it's important to clean them up.
+            let abort_result = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket)
+                .key(&filename)
+                .upload_id(upload_id)
+                .send()
+                .await;
+            if let Err(abort_err) = abort_result {
+                tracing::warn!(
+                    "Failed aborting multipart upload to S3 for key {filename}: {abort_err}"
+                );
+            }
+            return result;
+        }
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to S3 for key {key} from bucket {bucket} using multipart upload and it took: {elapsed:?}"
+        );
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Removing data from S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(filename)
+            .send()
+            .await
+            .map_err(convert_error)?;
+        Ok(())
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let Some(storage_class) = &self.cold_storage_class else {
+            return Ok(());
+        };
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Moving object {filename} in bucket {} to storage class {storage_class}",
+            self.bucket
+        );
+
+        // Storage class of an object can only be changed by copying the object onto itself. Object keys
+        // consist of URL-safe chars only, so the copy source doesn't need to be encoded.
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .key(&filename)
+            .copy_source(format!("{}/{filename}", self.bucket))
+            .storage_class(StorageClass::from(storage_class.as_str()))
+            .send()
+            .await
+            .map_err(convert_error)?;
+        LIFECYCLE_METRICS.archived_objects[&bucket.as_str()].inc();
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!(
+            "{}/{}",
+            self.endpoint.bucket_url(&self.bucket),
+            bucket.as_str()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_urls() {
+        let mut endpoint = S3Endpoint {
+            region: "eu-west-1".to_owned(),
+            url: None,
+            force_path_style: false,
+        };
+        assert_eq!(
+            endpoint.bucket_url("test"),
+            "https://test.s3.eu-west-1.amazonaws.com"
+        );
+        endpoint.force_path_style = true;
+        assert_eq!(
+            endpoint.bucket_url("test"),
+            "https://s3.eu-west-1.amazonaws.com/test"
+        );
+
+        endpoint.url = Some("http://localhost:9000/".to_owned());
+        assert_eq!(endpoint.bucket_url("test"), "http://localhost:9000/test");
+        endpoint.force_path_style = false;
+        assert_eq!(endpoint.bucket_url("test"), "http://test.localhost:9000");
+
+        endpoint.url = Some("minio.local".to_owned());
+        assert_eq!(endpoint.bucket_url("test"), "https://test.minio.local");
+    }
+}
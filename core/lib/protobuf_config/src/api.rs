@@ -55,13 +55,24 @@ impl ProtoRepr for proto::Web3JsonRpc {
             ws_url: required(&self.ws_url).context("ws_url")?.clone(),
             req_entities_limit: self.req_entities_limit,
             filters_disabled: self.filters_disabled.unwrap_or(false),
+            sign_sync_blocks: self.sign_sync_blocks.unwrap_or(false),
+            sign_snapshot_headers: self.sign_snapshot_headers.unwrap_or(false),
+            tree_lag_limit: self.tree_lag_limit,
+            reject_proofs_on_tree_lag: self.reject_proofs_on_tree_lag.unwrap_or(false),
+            mempool_inspection_enabled: self.mempool_inspection_enabled.unwrap_or(false),
+            contract_execution_stats_enabled: self
+                .contract_execution_stats_enabled
+                .unwrap_or(false),
             filters_limit: self.filters_limit,
             subscriptions_limit: self.subscriptions_limit,
             pubsub_polling_interval: self.pubsub_polling_interval,
             max_nonce_ahead: *required(&self.max_nonce_ahead).context("max_nonce_ahead")?,
+            replacement_fee_bump_percent: self.replacement_fee_bump_percent,
+            max_pending_txs_per_account: self.max_pending_txs_per_account,
             gas_price_scale_factor: *required(&self.gas_price_scale_factor)
                 .context("gas_price_scale_factor")?,
             request_timeout: self.request_timeout,
+            request_deadline_sec: self.request_deadline_sec,
             account_pks,
             estimate_gas_scale_factor: *required(&self.estimate_gas_scale_factor)
                 .context("estimate_gas_scale_factor")?,
@@ -117,6 +128,21 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("websocket_requests_per_minute_limit")?,
+            method_requests_per_minute_limit: self
+                .method_requests_per_minute_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("method_requests_per_minute_limit")?,
+            client_requests_per_minute_limit: self
+                .client_requests_per_minute_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("client_requests_per_minute_limit")?,
+            max_concurrent_requests: self
+                .max_concurrent_requests
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_concurrent_requests")?,
             tree_api_url: self.tree_api_url.clone(),
             mempool_cache_update_interval: self.mempool_cache_update_interval,
             mempool_cache_size: self
@@ -124,6 +150,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("mempool_cache_size")?,
+            lookup_cache_size_mb: self
+                .lookup_cache_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("lookup_cache_size_mb")?,
         })
     }
     fn build(this: &Self::Type) -> Self {
@@ -134,14 +165,24 @@ impl ProtoRepr for proto::Web3JsonRpc {
             ws_url: Some(this.ws_url.clone()),
             req_entities_limit: this.req_entities_limit,
             filters_disabled: Some(this.filters_disabled),
+            sign_sync_blocks: Some(this.sign_sync_blocks),
+            sign_snapshot_headers: Some(this.sign_snapshot_headers),
+            tree_lag_limit: this.tree_lag_limit,
+            reject_proofs_on_tree_lag: Some(this.reject_proofs_on_tree_lag),
+            mempool_inspection_enabled: Some(this.mempool_inspection_enabled),
+            contract_execution_stats_enabled: Some(this.contract_execution_stats_enabled),
             mempool_cache_update_interval: this.mempool_cache_update_interval,
             mempool_cache_size: this.mempool_cache_size.map(|x| x.try_into().unwrap()),
+            lookup_cache_size_mb: this.lookup_cache_size_mb.map(|x| x.try_into().unwrap()),
             filters_limit: this.filters_limit,
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
             max_nonce_ahead: Some(this.max_nonce_ahead),
+            replacement_fee_bump_percent: this.replacement_fee_bump_percent,
+            max_pending_txs_per_account: this.max_pending_txs_per_account,
             gas_price_scale_factor: Some(this.gas_price_scale_factor),
             request_timeout: this.request_timeout,
+            request_deadline_sec: this.request_deadline_sec,
             account_pks: this
                 .account_pks
                 .as_ref()
@@ -176,6 +217,13 @@ impl ProtoRepr for proto::Web3JsonRpc {
             websocket_requests_per_minute_limit: this
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
+            method_requests_per_minute_limit: this
+                .method_requests_per_minute_limit
+                .map(|x| x.into()),
+            client_requests_per_minute_limit: this
+                .client_requests_per_minute_limit
+                .map(|x| x.into()),
+            max_concurrent_requests: this.max_concurrent_requests.map(|x| x as u64),
             tree_api_url: this.tree_api_url.clone(),
         }
     }
@@ -191,6 +239,7 @@ impl ProtoRepr for proto::HealthCheck {
                 .context("port")?,
             slow_time_limit_ms: self.slow_time_limit_ms,
             hard_time_limit_ms: self.hard_time_limit_ms,
+            config_export_token: self.config_export_token.clone(),
         })
     }
 
@@ -199,6 +248,7 @@ impl ProtoRepr for proto::HealthCheck {
             port: Some(this.port.into()),
             slow_time_limit_ms: this.slow_time_limit_ms,
             hard_time_limit_ms: this.hard_time_limit_ms,
+            config_export_token: this.config_export_token.clone(),
         }
     }
 }
@@ -206,15 +256,24 @@ impl ProtoRepr for proto::HealthCheck {
 impl ProtoRepr for proto::MerkleTreeApi {
     type Type = api::MerkleTreeApiConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
-        Ok(Self::Type {
+        let config = Self::Type {
             port: required(&self.port)
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("port")?,
-        })
+            reader_pool_size: self
+                .reader_pool_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("reader_pool_size")?
+                .unwrap_or_else(api::MerkleTreeApiConfig::default_reader_pool_size),
+        };
+        config.validate()?;
+        Ok(config)
     }
     fn build(this: &Self::Type) -> Self {
         Self {
             port: Some(this.port.into()),
+            reader_pool_size: Some(this.reader_pool_size.try_into().unwrap()),
         }
     }
 }
@@ -1,8 +1,24 @@
 use anyhow::Context as _;
+use zksync_basic_types::Address;
 use zksync_config::configs;
 use zksync_protobuf::{repr::ProtoRepr, required};
 
-use crate::proto::chain as proto;
+use crate::{parse_h160, proto::chain as proto};
+
+fn parse_addresses(addresses: &[String]) -> anyhow::Result<Vec<Address>> {
+    addresses
+        .iter()
+        .enumerate()
+        .map(|(i, address)| parse_h160(address).with_context(|| i.to_string()))
+        .collect()
+}
+
+fn format_addresses(addresses: &[Address]) -> Vec<String> {
+    addresses
+        .iter()
+        .map(|address| format!("{address:?}"))
+        .collect()
+}
 
 impl proto::FeeModelVersion {
     fn new(n: &configs::chain::FeeModelVersion) -> Self {
@@ -22,11 +38,29 @@ impl proto::FeeModelVersion {
     }
 }
 
+impl proto::MempoolDurability {
+    fn new(n: &configs::chain::MempoolDurability) -> Self {
+        use configs::chain::MempoolDurability as From;
+        match n {
+            From::Synchronous => Self::Synchronous,
+            From::Asynchronous => Self::Asynchronous,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::MempoolDurability {
+        use configs::chain::MempoolDurability as To;
+        match self {
+            Self::Synchronous => To::Synchronous,
+            Self::Asynchronous => To::Asynchronous,
+        }
+    }
+}
+
 impl ProtoRepr for proto::StateKeeper {
     type Type = configs::chain::StateKeeperConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         #[allow(deprecated)]
-        Ok(Self::Type {
+        let config = Self::Type {
             transaction_slots: required(&self.transaction_slots)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("transaction_slots")?,
@@ -81,6 +115,49 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            congestion_mempool_size_threshold: self.congestion_mempool_size_threshold,
+            congestion_l2_gas_price_multiplier: self.congestion_l2_gas_price_multiplier,
+            miniblock_seal_batch_latency_target_ms: self.miniblock_seal_batch_latency_target_ms,
+            miniblock_seal_batch_max_delay_ms: self.miniblock_seal_batch_max_delay_ms,
+            tx_execution_metrics_sampling_rate: self.tx_execution_metrics_sampling_rate,
+            tx_execution_metrics_retention_miniblocks: self
+                .tx_execution_metrics_retention_miniblocks,
+            max_miniblock_payload_size: self
+                .max_miniblock_payload_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_miniblock_payload_size")?,
+            disabled_seal_criteria: self.disabled_seal_criteria.clone(),
+            admin_port: self
+                .admin_port
+                .map(|x| x.try_into())
+                .transpose()
+                .context("admin_port")?,
+            admin_token: self.admin_token.clone(),
+            tx_filter_denied_addresses: parse_addresses(&self.tx_filter_denied_addresses)
+                .context("tx_filter_denied_addresses")?,
+            tx_filter_allowed_initiators: parse_addresses(&self.tx_filter_allowed_initiators)
+                .context("tx_filter_allowed_initiators")?,
+            tx_filter_allowed_deployers: parse_addresses(&self.tx_filter_allowed_deployers)
+                .context("tx_filter_allowed_deployers")?,
+            tx_filter_max_calldata_size: self
+                .tx_filter_max_calldata_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("tx_filter_max_calldata_size")?,
+            shadow_vm_protocol_version: self
+                .shadow_vm_protocol_version
+                .map(|x| x.try_into())
+                .transpose()
+                .context("shadow_vm_protocol_version")?,
+            offload_protective_reads: self.offload_protective_reads.unwrap_or(false),
+            miniblock_adaptive_target_ms: self.miniblock_adaptive_target_ms,
+            miniblock_adaptive_target_percentile: self.miniblock_adaptive_target_percentile,
+            miniblock_adaptive_max_tx_count: self
+                .miniblock_adaptive_max_tx_count
+                .map(|x| x.try_into())
+                .transpose()
+                .context("miniblock_adaptive_max_tx_count")?,
 
             // We need these values only for instantiating configs from environmental variables, so it's not
             // needed during the initialization from files
@@ -88,7 +165,9 @@ impl ProtoRepr for proto::StateKeeper {
             default_aa_hash: None,
             fee_account_addr: None,
             l1_batch_commit_data_generator_mode: Default::default(),
-        })
+        };
+        config.validate()?;
+        Ok(config)
     }
 
     fn build(this: &Self::Type) -> Self {
@@ -122,6 +201,32 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            congestion_mempool_size_threshold: this.congestion_mempool_size_threshold,
+            congestion_l2_gas_price_multiplier: this.congestion_l2_gas_price_multiplier,
+            miniblock_seal_batch_latency_target_ms: this.miniblock_seal_batch_latency_target_ms,
+            miniblock_seal_batch_max_delay_ms: this.miniblock_seal_batch_max_delay_ms,
+            tx_execution_metrics_sampling_rate: this.tx_execution_metrics_sampling_rate,
+            tx_execution_metrics_retention_miniblocks: this
+                .tx_execution_metrics_retention_miniblocks,
+            max_miniblock_payload_size: this
+                .max_miniblock_payload_size
+                .map(|x| x.try_into().unwrap()),
+            disabled_seal_criteria: this.disabled_seal_criteria.clone(),
+            admin_port: this.admin_port.map(Into::into),
+            admin_token: this.admin_token.clone(),
+            tx_filter_denied_addresses: format_addresses(&this.tx_filter_denied_addresses),
+            tx_filter_allowed_initiators: format_addresses(&this.tx_filter_allowed_initiators),
+            tx_filter_allowed_deployers: format_addresses(&this.tx_filter_allowed_deployers),
+            tx_filter_max_calldata_size: this
+                .tx_filter_max_calldata_size
+                .map(|x| x.try_into().unwrap()),
+            shadow_vm_protocol_version: this.shadow_vm_protocol_version.map(Into::into),
+            offload_protective_reads: Some(this.offload_protective_reads),
+            miniblock_adaptive_target_ms: this.miniblock_adaptive_target_ms,
+            miniblock_adaptive_target_percentile: this.miniblock_adaptive_target_percentile,
+            miniblock_adaptive_max_tx_count: this
+                .miniblock_adaptive_max_tx_count
+                .map(|x| x.try_into().unwrap()),
         }
     }
 }
@@ -153,6 +258,13 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
             remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            max_nonce_gap: self.max_nonce_gap,
+            durability: self
+                .durability
+                .map(proto::MempoolDurability::try_from)
+                .transpose()
+                .context("durability")?
+                .map_or_else(Default::default, |x| x.parse()),
         })
     }
 
@@ -164,6 +276,8 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            max_nonce_gap: this.max_nonce_gap,
+            durability: Some(proto::MempoolDurability::new(&this.durability).into()),
         }
     }
 }
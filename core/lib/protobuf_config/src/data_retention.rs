@@ -0,0 +1,29 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto::data_retention as proto;
+
+impl ProtoRepr for proto::DataRetention {
+    type Type = configs::DataRetentionConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            events_retention_days: self.events_retention_days,
+            call_traces_retention_days: self.call_traces_retention_days,
+            transaction_traces_retention_days: self.transaction_traces_retention_days,
+            poll_interval_ms: *required(&self.poll_interval_ms).context("poll_interval_ms")?,
+            deletion_batch_size: *required(&self.deletion_batch_size)
+                .context("deletion_batch_size")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            events_retention_days: this.events_retention_days,
+            call_traces_retention_days: this.call_traces_retention_days,
+            transaction_traces_retention_days: this.transaction_traces_retention_days,
+            poll_interval_ms: Some(this.poll_interval_ms),
+            deletion_batch_size: Some(this.deletion_batch_size),
+        }
+    }
+}
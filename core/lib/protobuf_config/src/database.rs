@@ -25,6 +25,97 @@ impl proto::MerkleTreeMode {
     }
 }
 
+impl proto::RocksdbCompactionStyle {
+    fn new(x: &configs::database::RocksdbCompactionStyle) -> Self {
+        use configs::database::RocksdbCompactionStyle as From;
+        match x {
+            From::Level => Self::Level,
+            From::Universal => Self::Universal,
+        }
+    }
+
+    fn parse(&self) -> configs::database::RocksdbCompactionStyle {
+        use configs::database::RocksdbCompactionStyle as To;
+        match self {
+            Self::Level => To::Level,
+            Self::Universal => To::Universal,
+        }
+    }
+}
+
+impl proto::RocksdbCompression {
+    fn new(x: &configs::database::RocksdbCompression) -> Self {
+        use configs::database::RocksdbCompression as From;
+        match x {
+            From::None => Self::None,
+            From::Snappy => Self::Snappy,
+            From::Lz4 => Self::Lz4,
+            From::Zstd => Self::Zstd,
+        }
+    }
+
+    fn parse(&self) -> configs::database::RocksdbCompression {
+        use configs::database::RocksdbCompression as To;
+        match self {
+            Self::None => To::None,
+            Self::Snappy => To::Snappy,
+            Self::Lz4 => To::Lz4,
+            Self::Zstd => To::Zstd,
+        }
+    }
+}
+
+impl ProtoRepr for proto::RocksdbColumnFamily {
+    type Type = configs::database::RocksdbColumnFamilyConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            name: required(&self.name).context("name")?.clone(),
+            write_buffer_size_mb: self
+                .write_buffer_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("write_buffer_size_mb")?,
+            compaction_style: self
+                .compaction_style
+                .map(proto::RocksdbCompactionStyle::try_from)
+                .transpose()
+                .context("compaction_style")?
+                .map(|x| x.parse()),
+            bloom_filter_bits_per_key: self.bloom_filter_bits_per_key,
+            compression: self
+                .compression
+                .map(proto::RocksdbCompression::try_from)
+                .transpose()
+                .context("compression")?
+                .map(|x| x.parse()),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            name: Some(this.name.clone()),
+            write_buffer_size_mb: this.write_buffer_size_mb.map(|x| x.try_into().unwrap()),
+            compaction_style: this
+                .compaction_style
+                .map(|x| proto::RocksdbCompactionStyle::new(&x).into()),
+            bloom_filter_bits_per_key: this.bloom_filter_bits_per_key,
+            compression: this
+                .compression
+                .map(|x| proto::RocksdbCompression::new(&x).into()),
+        }
+    }
+}
+
+fn read_column_families(
+    column_families: &[proto::RocksdbColumnFamily],
+) -> anyhow::Result<Vec<configs::database::RocksdbColumnFamilyConfig>> {
+    column_families
+        .iter()
+        .enumerate()
+        .map(|(i, x)| x.read().context(i))
+        .collect()
+}
+
 impl ProtoRepr for proto::MerkleTree {
     type Type = configs::database::MerkleTreeConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -48,6 +139,33 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            max_batches_per_commit: self
+                .max_batches_per_commit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_batches_per_commit")?
+                .unwrap_or_else(
+                    configs::database::MerkleTreeConfig::default_max_batches_per_commit,
+                ),
+            hashing_thread_count: self
+                .hashing_thread_count
+                .map(|x| x.try_into())
+                .transpose()
+                .context("hashing_thread_count")?,
+            consistency_check_interval_sec: self.consistency_check_interval_sec,
+            consistency_check_sample_size: required(&self.consistency_check_sample_size)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("consistency_check_sample_size")?,
+            consistency_check_repair: self.consistency_check_repair.unwrap_or(false),
+            rebuild_from_postgres: self.rebuild_from_postgres.unwrap_or(false),
+            proof_data_follower_path: self.proof_data_follower_path.clone(),
+            snapshot_export_interval_sec: self.snapshot_export_interval_sec,
+            snapshot_export_chunk_size: required(&self.snapshot_export_chunk_size)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("snapshot_export_chunk_size")?,
+            l1_root_check_interval_sec: self.l1_root_check_interval_sec,
+            column_families: read_column_families(&self.column_families)
+                .context("column_families")?,
         })
     }
 
@@ -60,6 +178,19 @@ impl ProtoRepr for proto::MerkleTree {
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            max_batches_per_commit: Some(this.max_batches_per_commit.try_into().unwrap()),
+            hashing_thread_count: this.hashing_thread_count.map(|x| x.try_into().unwrap()),
+            consistency_check_interval_sec: this.consistency_check_interval_sec,
+            consistency_check_sample_size: Some(
+                this.consistency_check_sample_size.try_into().unwrap(),
+            ),
+            consistency_check_repair: Some(this.consistency_check_repair),
+            rebuild_from_postgres: Some(this.rebuild_from_postgres),
+            proof_data_follower_path: this.proof_data_follower_path.clone(),
+            snapshot_export_interval_sec: this.snapshot_export_interval_sec,
+            snapshot_export_chunk_size: Some(this.snapshot_export_chunk_size.try_into().unwrap()),
+            l1_root_check_interval_sec: this.l1_root_check_interval_sec,
+            column_families: this.column_families.iter().map(ProtoRepr::build).collect(),
         }
     }
 }
@@ -71,6 +202,28 @@ impl ProtoRepr for proto::Db {
             state_keeper_db_path: required(&self.state_keeper_db_path)
                 .context("state_keeper_db_path")?
                 .clone(),
+            state_keeper_db_block_cache_size_mb: self
+                .state_keeper_db_block_cache_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_block_cache_size_mb")?,
+            state_keeper_db_column_families: read_column_families(
+                &self.state_keeper_db_column_families,
+            )
+            .context("state_keeper_db_column_families")?,
+            state_keeper_db_values_cache_size_mb: self
+                .state_keeper_db_values_cache_size_mb
+                .unwrap_or(0)
+                .try_into()
+                .context("state_keeper_db_values_cache_size_mb")?,
+            state_keeper_db_factory_deps_cache_size_mb: self
+                .state_keeper_db_factory_deps_cache_size_mb
+                .unwrap_or(0)
+                .try_into()
+                .context("state_keeper_db_factory_deps_cache_size_mb")?,
+            state_keeper_db_cache_warm_up_l1_batches: self
+                .state_keeper_db_cache_warm_up_l1_batches
+                .unwrap_or(0),
             merkle_tree: read_required_repr(&self.merkle_tree).context("merkle_tree")?,
         })
     }
@@ -78,6 +231,27 @@ impl ProtoRepr for proto::Db {
     fn build(this: &Self::Type) -> Self {
         Self {
             state_keeper_db_path: Some(this.state_keeper_db_path.clone()),
+            state_keeper_db_block_cache_size_mb: this
+                .state_keeper_db_block_cache_size_mb
+                .map(|x| x.try_into().unwrap()),
+            state_keeper_db_column_families: this
+                .state_keeper_db_column_families
+                .iter()
+                .map(ProtoRepr::build)
+                .collect(),
+            state_keeper_db_values_cache_size_mb: Some(
+                this.state_keeper_db_values_cache_size_mb
+                    .try_into()
+                    .unwrap(),
+            ),
+            state_keeper_db_factory_deps_cache_size_mb: Some(
+                this.state_keeper_db_factory_deps_cache_size_mb
+                    .try_into()
+                    .unwrap(),
+            ),
+            state_keeper_db_cache_warm_up_l1_batches: Some(
+                this.state_keeper_db_cache_warm_up_l1_batches,
+            ),
             merkle_tree: Some(ProtoRepr::build(&this.merkle_tree)),
         }
     }
@@ -107,8 +281,11 @@ impl ProtoRepr for proto::Postgres {
             max_connections_master: self.max_connections_master,
             acquire_timeout_sec: self.acquire_timeout_sec,
             statement_timeout_sec: self.statement_timeout_sec,
+            max_replica_lag_sec: self.max_replica_lag_sec,
             long_connection_threshold_ms: self.long_connection_threshold_ms,
             slow_query_threshold_ms: self.slow_query_threshold_ms,
+            query_timeout_ms: self.query_timeout_ms,
+            schema: self.schema.clone(),
             test_server_url,
             test_prover_url,
         })
@@ -123,8 +300,11 @@ impl ProtoRepr for proto::Postgres {
             max_connections_master: this.max_connections_master,
             acquire_timeout_sec: this.acquire_timeout_sec,
             statement_timeout_sec: this.statement_timeout_sec,
+            max_replica_lag_sec: this.max_replica_lag_sec,
             long_connection_threshold_ms: this.long_connection_threshold_ms,
             slow_query_threshold_ms: this.slow_query_threshold_ms,
+            query_timeout_ms: this.query_timeout_ms,
+            schema: this.schema.clone(),
             test: Some(proto::TestDatabase {
                 server_url: this.test_server_url.clone(),
                 prover_url: this.test_prover_url.clone(),
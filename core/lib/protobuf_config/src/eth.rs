@@ -60,6 +60,26 @@ impl proto::PubdataSendingMode {
     }
 }
 
+impl proto::FeeEstimationStrategy {
+    fn new(x: &configs::eth_sender::FeeEstimationStrategy) -> Self {
+        use configs::eth_sender::FeeEstimationStrategy as From;
+        match x {
+            From::Median => Self::Median,
+            From::Ewma => Self::Ewma,
+            From::Percentile => Self::Percentile,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::FeeEstimationStrategy {
+        use configs::eth_sender::FeeEstimationStrategy as To;
+        match self {
+            Self::Median => To::Median,
+            Self::Ewma => To::Ewma,
+            Self::Percentile => To::Percentile,
+        }
+    }
+}
+
 impl ProtoRepr for proto::Eth {
     type Type = configs::eth_sender::ETHConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -68,6 +88,7 @@ impl ProtoRepr for proto::Eth {
             gas_adjuster: read_optional_repr(&self.gas_adjuster).context("gas_adjuster")?,
             watcher: read_optional_repr(&self.watcher).context("watcher")?,
             web3_url: required(&self.web3_url).context("web3_url")?.clone(),
+            gas_price_web3_urls: self.gas_price_web3_urls.clone(),
         })
     }
 
@@ -77,6 +98,7 @@ impl ProtoRepr for proto::Eth {
             gas_adjuster: this.gas_adjuster.as_ref().map(ProtoRepr::build),
             watcher: this.watcher.as_ref().map(ProtoRepr::build),
             web3_url: Some(this.web3_url.clone()),
+            gas_price_web3_urls: this.gas_price_web3_urls.clone(),
         }
     }
 }
@@ -170,7 +192,7 @@ impl ProtoRepr for proto::Sender {
 impl ProtoRepr for proto::GasAdjuster {
     type Type = configs::eth_sender::GasAdjusterConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
-        Ok(Self::Type {
+        let config = Self::Type {
             default_priority_fee_per_gas: *required(&self.default_priority_fee_per_gas)
                 .context("default_priority_fee_per_gas")?,
             max_base_fee_samples: required(&self.max_base_fee_samples)
@@ -195,7 +217,30 @@ impl ProtoRepr for proto::GasAdjuster {
             )
             .context("internal_pubdata_pricing_multiplier")?,
             max_blob_base_fee: self.max_blob_base_fee,
-        })
+            fee_estimation_strategy: self
+                .fee_estimation_strategy
+                .map(proto::FeeEstimationStrategy::try_from)
+                .transpose()
+                .context("fee_estimation_strategy")?
+                .map_or_else(Default::default, |x| x.parse()),
+            ewma_smoothing_factor: self
+                .ewma_smoothing_factor
+                .unwrap_or_else(Self::Type::default_ewma_smoothing_factor),
+            fee_estimation_percentile: self
+                .fee_estimation_percentile
+                .unwrap_or_else(Self::Type::default_fee_estimation_percentile),
+            l1_gas_price_quorum: self
+                .l1_gas_price_quorum
+                .map(|x| x.try_into())
+                .transpose()
+                .context("l1_gas_price_quorum")?
+                .unwrap_or_else(Self::Type::default_l1_gas_price_quorum),
+            l1_gas_price_max_deviation: self
+                .l1_gas_price_max_deviation
+                .unwrap_or_else(Self::Type::default_l1_gas_price_max_deviation),
+        };
+        config.validate()?;
+        Ok(config)
     }
 
     fn build(this: &Self::Type) -> Self {
@@ -215,6 +260,13 @@ impl ProtoRepr for proto::GasAdjuster {
             ),
             internal_pubdata_pricing_multiplier: Some(this.internal_pubdata_pricing_multiplier),
             max_blob_base_fee: this.max_blob_base_fee,
+            fee_estimation_strategy: Some(
+                proto::FeeEstimationStrategy::new(&this.fee_estimation_strategy).into(),
+            ),
+            ewma_smoothing_factor: Some(this.ewma_smoothing_factor),
+            fee_estimation_percentile: Some(this.fee_estimation_percentile),
+            l1_gas_price_quorum: Some(this.l1_gas_price_quorum.try_into().unwrap()),
+            l1_gas_price_max_deviation: Some(this.l1_gas_price_max_deviation),
         }
     }
 }
@@ -0,0 +1,28 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto::finality_webhooks as proto;
+
+impl ProtoRepr for proto::FinalityWebhooks {
+    type Type = configs::FinalityWebhooksConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            urls: self.urls.clone(),
+            poll_interval_ms: *required(&self.poll_interval_ms).context("poll_interval_ms")?,
+            max_delivery_attempts: *required(&self.max_delivery_attempts)
+                .context("max_delivery_attempts")?,
+            request_timeout_ms: *required(&self.request_timeout_ms)
+                .context("request_timeout_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            urls: this.urls.clone(),
+            poll_interval_ms: Some(this.poll_interval_ms),
+            max_delivery_attempts: Some(this.max_delivery_attempts),
+            request_timeout_ms: Some(this.request_timeout_ms),
+        }
+    }
+}
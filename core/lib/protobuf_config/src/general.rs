@@ -37,6 +37,10 @@ impl ProtoRepr for proto::GeneralConfig {
             snapshot_creator: read_optional_repr(&self.snapshot_creator)
                 .context("snapshot_creator")?,
             observability: read_optional_repr(&self.observability).context("observability")?,
+            finality_webhooks_config: read_optional_repr(&self.finality_webhooks)
+                .context("finality_webhooks")?,
+            data_retention_config: read_optional_repr(&self.data_retention)
+                .context("data_retention")?,
         })
     }
 
@@ -68,6 +72,8 @@ impl ProtoRepr for proto::GeneralConfig {
             eth: this.eth.as_ref().map(ProtoRepr::build),
             snapshot_creator: this.snapshot_creator.as_ref().map(ProtoRepr::build),
             observability: this.observability.as_ref().map(ProtoRepr::build),
+            finality_webhooks: this.finality_webhooks_config.as_ref().map(ProtoRepr::build),
+            data_retention: this.data_retention_config.as_ref().map(ProtoRepr::build),
         }
     }
 }
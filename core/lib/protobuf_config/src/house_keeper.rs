@@ -46,6 +46,13 @@ impl ProtoRepr for proto::HouseKeeper {
                 .prover_job_archiver_reporting_interval_ms,
             prover_job_archiver_archiving_interval_secs: self
                 .prover_job_archiver_archiving_interval_secs,
+            object_store_archiver_interval_ms: self.object_store_archiver_interval_ms,
+            object_store_archiver_retention_secs: self.object_store_archiver_retention_secs,
+            object_store_gc_interval_ms: self.object_store_gc_interval_ms,
+            object_store_gc_retention_secs: self.object_store_gc_retention_secs,
+            storage_logs_partition_rotation_interval_ms: self
+                .storage_logs_partition_rotation_interval_ms,
+            storage_logs_partition_size: self.storage_logs_partition_size,
         })
     }
 
@@ -77,6 +84,13 @@ impl ProtoRepr for proto::HouseKeeper {
                 .prover_job_archiver_reporting_interval_ms,
             prover_job_archiver_archiving_interval_secs: this
                 .prover_job_archiver_archiving_interval_secs,
+            object_store_archiver_interval_ms: this.object_store_archiver_interval_ms,
+            object_store_archiver_retention_secs: this.object_store_archiver_retention_secs,
+            object_store_gc_interval_ms: this.object_store_gc_interval_ms,
+            object_store_gc_retention_secs: this.object_store_gc_retention_secs,
+            storage_logs_partition_rotation_interval_ms: this
+                .storage_logs_partition_rotation_interval_ms,
+            storage_logs_partition_size: this.storage_logs_partition_size,
         }
     }
 }
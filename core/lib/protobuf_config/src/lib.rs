@@ -9,8 +9,10 @@ mod chain;
 mod circuit_breaker;
 mod contract_verifier;
 mod contracts;
+mod data_retention;
 mod database;
 mod eth;
+mod finality_webhooks;
 mod general;
 mod genesis;
 mod house_keeper;
@@ -36,6 +36,28 @@ impl ProtoRepr for proto::ObjectStore {
                 file_backed_base_path: required(&mode.file_backed_base_path)
                     .context("file_backed_base_path")?
                     .clone(),
+                file_backed_fsync: mode.file_backed_fsync.unwrap_or(false),
+                file_backed_sharding: mode.file_backed_sharding.unwrap_or(false),
+                file_backed_archive_path: mode.file_backed_archive_path.clone(),
+            },
+            proto::object_store::Mode::S3(mode) => ObjectStoreMode::S3 {
+                bucket_base_url: required(&mode.bucket_base_url)
+                    .context("bucket_base_url")?
+                    .clone(),
+                region: required(&mode.region).context("region")?.clone(),
+                endpoint: mode.endpoint.clone(),
+                force_path_style: mode.force_path_style.unwrap_or(false),
+                access_key_id: mode.access_key_id.clone(),
+                secret_access_key: mode.secret_access_key.clone(),
+            },
+            proto::object_store::Mode::AzureBlob(mode) => ObjectStoreMode::AzureBlob {
+                azure_storage_account: required(&mode.azure_storage_account)
+                    .context("azure_storage_account")?
+                    .clone(),
+                bucket_base_url: required(&mode.bucket_base_url)
+                    .context("bucket_base_url")?
+                    .clone(),
+                azure_sas_token: mode.azure_sas_token.clone(),
             },
         };
 
@@ -44,6 +66,18 @@ impl ProtoRepr for proto::ObjectStore {
             max_retries: required(&self.max_retries)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_retries")?,
+            request_timeout_secs: self.request_timeout_secs,
+            circuit_breaker_threshold: self
+                .circuit_breaker_threshold
+                .unwrap_or_else(ObjectStoreConfig::default_circuit_breaker_threshold),
+            circuit_breaker_cooldown_secs: self
+                .circuit_breaker_cooldown_secs
+                .unwrap_or_else(ObjectStoreConfig::default_circuit_breaker_cooldown_secs),
+            cold_storage_class: self.cold_storage_class.clone(),
+            content_addressed: self.content_addressed.unwrap_or(false),
+            encryption_master_key: self.encryption_master_key.clone(),
+            encryption_kms_key_id: self.encryption_kms_key_id.clone(),
+            encrypted_buckets: self.encrypted_buckets.clone(),
         })
     }
 
@@ -72,14 +106,52 @@ impl ProtoRepr for proto::ObjectStore {
             }
             ObjectStoreMode::FileBacked {
                 file_backed_base_path,
+                file_backed_fsync,
+                file_backed_sharding,
+                file_backed_archive_path,
             } => proto::object_store::Mode::FileBacked(proto::object_store::FileBacked {
                 file_backed_base_path: Some(file_backed_base_path.clone()),
+                file_backed_fsync: Some(*file_backed_fsync),
+                file_backed_sharding: Some(*file_backed_sharding),
+                file_backed_archive_path: file_backed_archive_path.clone(),
+            }),
+            ObjectStoreMode::S3 {
+                bucket_base_url,
+                region,
+                endpoint,
+                force_path_style,
+                access_key_id,
+                secret_access_key,
+            } => proto::object_store::Mode::S3(proto::object_store::S3 {
+                bucket_base_url: Some(bucket_base_url.clone()),
+                region: Some(region.clone()),
+                endpoint: endpoint.clone(),
+                force_path_style: Some(*force_path_style),
+                access_key_id: access_key_id.clone(),
+                secret_access_key: secret_access_key.clone(),
+            }),
+            ObjectStoreMode::AzureBlob {
+                azure_storage_account,
+                bucket_base_url,
+                azure_sas_token,
+            } => proto::object_store::Mode::AzureBlob(proto::object_store::AzureBlob {
+                azure_storage_account: Some(azure_storage_account.clone()),
+                bucket_base_url: Some(bucket_base_url.clone()),
+                azure_sas_token: azure_sas_token.clone(),
             }),
         };
 
         Self {
             mode: Some(mode),
             max_retries: Some(this.max_retries.into()),
+            request_timeout_secs: this.request_timeout_secs,
+            circuit_breaker_threshold: Some(this.circuit_breaker_threshold),
+            circuit_breaker_cooldown_secs: Some(this.circuit_breaker_cooldown_secs),
+            cold_storage_class: this.cold_storage_class.clone(),
+            content_addressed: Some(this.content_addressed),
+            encryption_master_key: this.encryption_master_key.clone(),
+            encryption_kms_key_id: this.encryption_kms_key_id.clone(),
+            encrypted_buckets: this.encrypted_buckets.clone(),
         }
     }
 }
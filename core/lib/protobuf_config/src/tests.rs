@@ -37,6 +37,8 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::prover::ProofDataHandler>>(rng);
     test_encode_all_formats::<ReprConv<proto::snapshot_creator::SnapshotsCreator>>(rng);
     test_encode_all_formats::<ReprConv<proto::observability::Observability>>(rng);
+    test_encode_all_formats::<ReprConv<proto::finality_webhooks::FinalityWebhooks>>(rng);
+    test_encode_all_formats::<ReprConv<proto::data_retention::DataRetention>>(rng);
 }
 
 pub fn decode_yaml_repr<T: ProtoRepr>(
@@ -53,9 +53,21 @@ impl ProtoRepr for proto::Wallets {
             None
         };
 
+        let signer = self
+            .signer
+            .as_ref()
+            .map(|signer| {
+                Wallet::from_private_key(
+                    parse_h256(required(&signer.private_key).context("signer")?)?,
+                    signer.address.as_ref().and_then(|a| parse_h160(a).ok()),
+                )
+            })
+            .transpose()?;
+
         Ok(Self::Type {
             eth_sender,
             state_keeper,
+            signer,
         })
     }
 
@@ -85,10 +97,15 @@ impl ProtoRepr for proto::Wallets {
             .map(|state_keeper| proto::AddressWallet {
                 address: Some(format!("{:?}", state_keeper.fee_account.address())),
             });
+        let signer = this.signer.as_ref().map(|signer| proto::PrivateKeyWallet {
+            address: Some(format!("{:?}", signer.address())),
+            private_key: Some(format!("{:?}", signer.private_key())),
+        });
         Self {
             blob_operator,
             operator,
             fee_account,
+            signer,
         }
     }
 }
@@ -90,6 +90,11 @@ impl PrepareBasicCircuitsJob {
         self.next_enumeration_index
     }
 
+    /// Returns the number of storage logs (i.e., read / write operations with Merkle paths) in this job.
+    pub fn storage_logs_count(&self) -> usize {
+        self.merkle_paths.len()
+    }
+
     /// Reserves additional capacity for Merkle paths.
     pub fn reserve(&mut self, additional_capacity: usize) {
         self.merkle_paths.reserve(additional_capacity);
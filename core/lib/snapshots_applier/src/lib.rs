@@ -1,14 +1,15 @@
 //! Logic for applying application-level snapshots to Postgres storage.
 
-use std::{collections::HashMap, fmt, time::Duration};
+use std::{collections::HashMap, error, fmt, iter, sync::Arc};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use serde::Serialize;
 use tokio::sync::Semaphore;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, SqlxError};
+use zksync_db_connection::error::DbError;
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_object_store::{ObjectStore, StoredObject};
 use zksync_types::{
     api,
     snapshots::{
@@ -17,16 +18,20 @@ use zksync_types::{
     },
     tokens::TokenInfo,
     web3::futures,
-    L1BatchNumber, MiniblockNumber, H256,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, H256,
+};
+use zksync_utils::{
+    bytecode::hash_bytecode,
+    error::{ClassifiedError, ClassifyError, ErrorKind},
+    retry::RetryPolicy,
 };
-use zksync_utils::bytecode::hash_bytecode;
 use zksync_web3_decl::{
     error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
-    jsonrpsee::{core::client, http_client::HttpClient},
+    jsonrpsee::http_client::HttpClient,
     namespaces::{EnNamespaceClient, SnapshotsNamespaceClient, ZksNamespaceClient},
 };
 
-use self::metrics::{InitialStage, StorageLogsChunksStage, METRICS};
+use self::metrics::{BlobSourceLabel, InitialStage, StorageLogsChunksStage, METRICS};
 
 mod metrics;
 #[cfg(test)]
@@ -51,39 +56,39 @@ enum SnapshotsApplierError {
 }
 
 impl SnapshotsApplierError {
-    fn object_store(err: ObjectStoreError, context: String) -> Self {
-        match err {
-            ObjectStoreError::KeyNotFound(_) | ObjectStoreError::Serialization(_) => {
-                Self::Fatal(anyhow::Error::from(err).context(context))
-            }
-            ObjectStoreError::Other(_) => {
-                Self::Retryable(anyhow::Error::from(err).context(context))
-            }
+    fn new(err: anyhow::Error) -> Self {
+        if err.error_kind().is_retryable() {
+            Self::Retryable(err)
+        } else {
+            Self::Fatal(err)
         }
     }
 
+    /// Wraps an object store error with the specified context, retaining its classification.
+    fn classified<E>(err: E, context: impl Into<String>) -> Self
+    where
+        E: ClassifyError + error::Error + Send + Sync + 'static,
+    {
+        Self::new(anyhow::Error::from(ClassifiedError::new(err)).context(context.into()))
+    }
+
+    /// Wraps a DB error with the specified context, retaining its classification.
     fn db(err: SqlxError, context: impl Into<String>) -> Self {
-        let context = context.into();
-        match err {
-            SqlxError::Database(_)
-            | SqlxError::RowNotFound
-            | SqlxError::ColumnNotFound(_)
-            | SqlxError::Configuration(_)
-            | SqlxError::TypeNotFound { .. } => {
-                Self::Fatal(anyhow::Error::from(err).context(context))
-            }
-            _ => Self::Retryable(anyhow::Error::from(err).context(context)),
-        }
+        Self::classified(DbError(err), context)
     }
 }
 
 impl From<EnrichedClientError> for SnapshotsApplierError {
     fn from(err: EnrichedClientError) -> Self {
-        match err.as_ref() {
-            client::Error::Transport(_) | client::Error::RequestTimeout => {
-                Self::Retryable(err.into())
-            }
-            _ => Self::Fatal(err.into()),
+        Self::new(ClassifiedError::new(err).into())
+    }
+}
+
+impl ClassifyError for SnapshotsApplierError {
+    fn error_kind(&self) -> ErrorKind {
+        match self {
+            Self::Fatal(err) => err.error_kind(),
+            Self::Retryable(_) => ErrorKind::Retryable,
         }
     }
 }
@@ -103,6 +108,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>>;
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>>;
+
     async fn fetch_tokens(
         &self,
         at_miniblock: MiniblockNumber,
@@ -136,12 +146,19 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
             .get_all_snapshots()
             .rpc_context("get_all_snapshots")
             .await?;
-        let Some(newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
+        let Some(&newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
             return Ok(None);
         };
-        self.get_snapshot_by_l1_batch_number(*newest_snapshot)
+        self.fetch_snapshot(newest_snapshot).await
+    }
+
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number(l1_batch_number)
             .rpc_context("get_snapshot_by_l1_batch_number")
-            .with_arg("number", newest_snapshot)
+            .with_arg("number", &l1_batch_number)
             .await
     }
 
@@ -156,21 +173,51 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
     }
 }
 
+/// Signers trusted to sign snapshot headers (e.g., the main node operator).
+#[derive(Debug, Clone)]
+pub struct TrustedSnapshotSigners {
+    /// L2 chain ID the snapshot header must be signed for.
+    pub chain_id: L2ChainId,
+    /// Addresses of the accepted signers.
+    pub addresses: Vec<Address>,
+}
+
+/// Additional source of snapshot blobs (factory deps and storage logs chunks), e.g. a public mirror
+/// of the main snapshots bucket.
+#[derive(Debug, Clone)]
+pub struct SnapshotBlobMirror {
+    /// Human-readable name of the mirror used in logs and metrics.
+    pub name: String,
+    pub store: Arc<dyn ObjectStore>,
+}
+
+/// Source of snapshot blobs: either the primary object store, or one of the mirrors.
+#[derive(Debug, Clone, Copy)]
+struct BlobSource<'a> {
+    name: &'a str,
+    store: &'a dyn ObjectStore,
+}
+
 /// Snapshot applier configuration options.
 #[derive(Debug)]
 pub struct SnapshotsApplierConfig {
-    pub retry_count: usize,
-    pub initial_retry_backoff: Duration,
-    pub retry_backoff_multiplier: f32,
+    /// Policy of retrying recovery after transient errors.
+    pub retry_policy: RetryPolicy,
+    /// If set, the snapshot header must be signed by one of the trusted signers; otherwise, recovery
+    /// fails before any snapshot data is applied.
+    pub trusted_signers: Option<TrustedSnapshotSigners>,
+    /// Mirrors tried in order for each blob that cannot be fetched from the primary object store
+    /// or fails validation.
+    pub mirrors: Vec<SnapshotBlobMirror>,
     health_updater: HealthUpdater,
 }
 
 impl Default for SnapshotsApplierConfig {
     fn default() -> Self {
         Self {
-            retry_count: 5,
-            initial_retry_backoff: Duration::from_secs(2),
-            retry_backoff_multiplier: 2.0,
+            retry_policy: RetryPolicy::default(),
+            trusted_signers: None,
+            mirrors: Vec::new(),
             health_updater: ReactiveHealthCheck::new("snapshot_recovery").1,
         }
     }
@@ -180,7 +227,10 @@ impl SnapshotsApplierConfig {
     #[cfg(test)]
     fn for_tests() -> Self {
         Self {
-            initial_retry_backoff: Duration::from_millis(5),
+            retry_policy: RetryPolicy {
+                initial_backoff: std::time::Duration::from_millis(5),
+                ..RetryPolicy::default()
+            },
             ..Self::default()
         }
     }
@@ -205,44 +255,39 @@ impl SnapshotsApplierConfig {
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
         blob_store: &dyn ObjectStore,
     ) -> anyhow::Result<()> {
-        let mut backoff = self.initial_retry_backoff;
-        let mut last_error = None;
-        for retry_id in 0..self.retry_count {
-            let result = SnapshotsApplier::load_snapshot(
-                connection_pool,
-                main_node_client,
-                blob_store,
-                &self.health_updater,
-            )
+        let primary_source = BlobSource {
+            name: "primary",
+            store: blob_store,
+        };
+        let mirror_sources = self.mirrors.iter().map(|mirror| BlobSource {
+            name: &mirror.name,
+            store: mirror.store.as_ref(),
+        });
+        let blob_sources: Vec<_> = iter::once(primary_source).chain(mirror_sources).collect();
+
+        let result = self
+            .retry_policy
+            .run("snapshots_applier", || {
+                SnapshotsApplier::load_snapshot(
+                    connection_pool,
+                    main_node_client,
+                    &blob_sources,
+                    self.trusted_signers.as_ref(),
+                    &self.health_updater,
+                )
+            })
             .await;
-
-            match result {
-                Ok(()) => {
-                    // Freeze the health check in the "ready" status, so that the snapshot recovery isn't marked
-                    // as "shut down", which would lead to the app considered unhealthy.
-                    self.health_updater.freeze();
-                    return Ok(());
-                }
-                Err(SnapshotsApplierError::Fatal(err)) => {
-                    tracing::error!("Fatal error occurred during snapshots recovery: {err:?}");
-                    return Err(err);
-                }
-                Err(SnapshotsApplierError::Retryable(err)) => {
-                    tracing::warn!("Retryable error occurred during snapshots recovery: {err:?}");
-                    last_error = Some(err);
-                    tracing::info!(
-                        "Recovering from error; attempt {retry_id} / {}, retrying in {backoff:?}",
-                        self.retry_count
-                    );
-                    tokio::time::sleep(backoff).await;
-                    backoff = backoff.mul_f32(self.retry_backoff_multiplier);
-                }
+        match result {
+            Ok(()) => {
+                // Freeze the health check in the "ready" status, so that the snapshot recovery isn't marked
+                // as "shut down", which would lead to the app considered unhealthy.
+                self.health_updater.freeze();
+                Ok(())
+            }
+            Err(SnapshotsApplierError::Fatal(err) | SnapshotsApplierError::Retryable(err)) => {
+                Err(err)
             }
         }
-
-        let last_error = last_error.unwrap(); // `unwrap()` is safe: `last_error` was assigned at least once
-        tracing::error!("Snapshot recovery run out of retries; last error: {last_error:?}");
-        Err(last_error)
     }
 }
 
@@ -251,19 +296,24 @@ impl SnapshotsApplierConfig {
 struct SnapshotsApplier<'a> {
     connection_pool: &'a ConnectionPool<Core>,
     main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
-    blob_store: &'a dyn ObjectStore,
+    /// Sources of snapshot blobs in the order they should be tried. The first source is the primary object store.
+    blob_sources: &'a [BlobSource<'a>],
     applied_snapshot_status: SnapshotRecoveryStatus,
+    /// Expected content hashes of storage logs chunks from the snapshot header, ordered by chunk ID.
+    storage_logs_chunk_hashes: Vec<Option<H256>>,
     health_updater: &'a HealthUpdater,
     factory_deps_recovered: bool,
     tokens_recovered: bool,
 }
 
 impl<'a> SnapshotsApplier<'a> {
-    /// Recovers [`SnapshotRecoveryStatus`] from the storage and the main node.
+    /// Recovers [`SnapshotRecoveryStatus`] from the storage and the main node. Also returns the verified
+    /// snapshot header for the recovered L1 batch.
     async fn prepare_applied_snapshot_status(
         storage: &mut Connection<'_, Core>,
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
-    ) -> Result<(SnapshotRecoveryStatus, bool), SnapshotsApplierError> {
+        trusted_signers: Option<&TrustedSnapshotSigners>,
+    ) -> Result<(SnapshotRecoveryStatus, SnapshotHeader, bool), SnapshotsApplierError> {
         let latency =
             METRICS.initial_stage_duration[&InitialStage::FetchMetadataFromMainNode].start();
 
@@ -276,10 +326,36 @@ impl<'a> SnapshotsApplier<'a> {
             })?;
 
         if let Some(applied_snapshot_status) = applied_snapshot_status {
+            // Re-fetch and re-verify the snapshot header so that the remaining chunks are checked
+            // against the signed content hashes.
+            let l1_batch_number = applied_snapshot_status.l1_batch_number;
+            let snapshot = main_node_client
+                .fetch_snapshot(l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!("snapshot for L1 batch #{l1_batch_number} is missing on main node")
+                })?;
+            Self::verify_snapshot_header(
+                &snapshot,
+                trusted_signers,
+                applied_snapshot_status.l1_batch_root_hash,
+            )?;
+            if snapshot.storage_logs_chunks.len()
+                != applied_snapshot_status.storage_logs_chunks_processed.len()
+            {
+                let err = anyhow::anyhow!(
+                    "snapshot header for L1 batch #{l1_batch_number} has {} storage logs chunks, while \
+                     the applied snapshot status has {}",
+                    snapshot.storage_logs_chunks.len(),
+                    applied_snapshot_status.storage_logs_chunks_processed.len()
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+
             let latency = latency.observe();
             tracing::info!("Re-initialized snapshots applier after reset/failure in {latency:?}");
 
-            Ok((applied_snapshot_status, false))
+            Ok((applied_snapshot_status, snapshot, false))
         } else {
             let is_genesis_needed =
                 storage
@@ -296,8 +372,9 @@ impl<'a> SnapshotsApplier<'a> {
                 return Err(SnapshotsApplierError::Fatal(err));
             }
 
-            let recovery_status =
-                SnapshotsApplier::create_fresh_recovery_status(main_node_client).await?;
+            let (recovery_status, snapshot) =
+                SnapshotsApplier::create_fresh_recovery_status(main_node_client, trusted_signers)
+                    .await?;
 
             let storage_logs_count = storage
                 .storage_logs_dal()
@@ -317,14 +394,15 @@ impl<'a> SnapshotsApplier<'a> {
 
             let latency = latency.observe();
             tracing::info!("Initialized fresh snapshots applier in {latency:?}");
-            Ok((recovery_status, true))
+            Ok((recovery_status, snapshot, true))
         }
     }
 
     async fn load_snapshot(
         connection_pool: &'a ConnectionPool<Core>,
         main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
-        blob_store: &'a dyn ObjectStore,
+        blob_sources: &'a [BlobSource<'a>],
+        trusted_signers: Option<&TrustedSnapshotSigners>,
         health_updater: &'a HealthUpdater,
     ) -> Result<(), SnapshotsApplierError> {
         health_updater.update(HealthStatus::Ready.into());
@@ -336,15 +414,24 @@ impl<'a> SnapshotsApplier<'a> {
             SnapshotsApplierError::db(err, "failed starting initial DB transaction")
         })?;
 
-        let (applied_snapshot_status, created_from_scratch) =
-            Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
-                .await?;
+        let (applied_snapshot_status, snapshot, created_from_scratch) =
+            Self::prepare_applied_snapshot_status(
+                &mut storage_transaction,
+                main_node_client,
+                trusted_signers,
+            )
+            .await?;
 
         let mut this = Self {
             connection_pool,
             main_node_client,
-            blob_store,
+            blob_sources,
             applied_snapshot_status,
+            storage_logs_chunk_hashes: snapshot
+                .storage_logs_chunks
+                .iter()
+                .map(|chunk| chunk.content_hash)
+                .collect(),
             health_updater,
             factory_deps_recovered: !created_from_scratch,
             tokens_recovered: false,
@@ -388,7 +475,8 @@ impl<'a> SnapshotsApplier<'a> {
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
-    ) -> Result<SnapshotRecoveryStatus, SnapshotsApplierError> {
+        trusted_signers: Option<&TrustedSnapshotSigners>,
+    ) -> Result<(SnapshotRecoveryStatus, SnapshotHeader), SnapshotsApplierError> {
         let snapshot_response = main_node_client.fetch_newest_snapshot().await?;
 
         let snapshot = snapshot_response
@@ -400,7 +488,6 @@ impl<'a> SnapshotsApplier<'a> {
             snapshot.version,
             snapshot.storage_logs_chunks.len()
         );
-        Self::check_snapshot_version(snapshot.version)?;
 
         let l1_batch = main_node_client
             .fetch_l1_batch_details(l1_batch_number)
@@ -410,6 +497,7 @@ impl<'a> SnapshotsApplier<'a> {
             .base
             .root_hash
             .context("snapshot L1 batch fetched from main node doesn't have root hash set")?;
+        Self::verify_snapshot_header(&snapshot, trusted_signers, l1_batch_root_hash)?;
         let miniblock = main_node_client
             .fetch_l2_block_details(miniblock_number)
             .await?
@@ -428,7 +516,7 @@ impl<'a> SnapshotsApplier<'a> {
             return Err(err.into());
         }
 
-        Ok(SnapshotRecoveryStatus {
+        let status = SnapshotRecoveryStatus {
             l1_batch_number,
             l1_batch_timestamp: l1_batch.base.timestamp,
             l1_batch_root_hash,
@@ -437,7 +525,37 @@ impl<'a> SnapshotsApplier<'a> {
             miniblock_hash,
             protocol_version,
             storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
-        })
+        };
+        Ok((status, snapshot))
+    }
+
+    /// Verifies the snapshot header signature (if `trusted_signers` are specified) and format, and checks that
+    /// the L1 batch root hash in the header (if present) matches `expected_root_hash`.
+    fn verify_snapshot_header(
+        snapshot: &SnapshotHeader,
+        trusted_signers: Option<&TrustedSnapshotSigners>,
+        expected_root_hash: H256,
+    ) -> anyhow::Result<()> {
+        if let Some(signers) = trusted_signers {
+            let signer = snapshot
+                .verify_signature(signers.chain_id, &signers.addresses)
+                .context("snapshot header provenance cannot be verified")?;
+            tracing::info!("Verified snapshot header signature by trusted signer {signer:?}");
+        }
+        Self::check_snapshot_version(snapshot.version)?;
+        snapshot
+            .validate_storage_logs_chunks()
+            .context("malformed snapshot header")?;
+
+        if let Some(root_hash) = snapshot.l1_batch_root_hash {
+            anyhow::ensure!(
+                root_hash == expected_root_hash,
+                "L1 batch root hash in the snapshot header ({root_hash:?}) differs from the root hash \
+                 of L1 batch #{} on main node ({expected_root_hash:?})",
+                snapshot.l1_batch_number
+            );
+        }
+        Ok(())
     }
 
     fn check_snapshot_version(raw_version: u16) -> anyhow::Result<()> {
@@ -471,6 +589,49 @@ impl<'a> SnapshotsApplier<'a> {
             .update(Health::from(HealthStatus::Ready).with_details(details));
     }
 
+    /// Fetches a blob from the first source providing a blob that passes `validate`, trying sources in order.
+    /// Returns the blob together with the name of the source that has served it.
+    async fn fetch_blob<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        description: &str,
+        validate: impl Fn(&V) -> anyhow::Result<()>,
+    ) -> Result<(V, &'a str), SnapshotsApplierError> {
+        let mut error = None;
+        for source in self.blob_sources {
+            let err = match source.store.get::<V>(key).await {
+                Ok(value) => match validate(&value) {
+                    Ok(()) => {
+                        tracing::debug!("Fetched {description} from `{}` blob source", source.name);
+                        METRICS.blobs_fetched[&BlobSourceLabel::from(source.name)].inc();
+                        return Ok((value, source.name));
+                    }
+                    Err(err) => SnapshotsApplierError::Fatal(err.context(format!(
+                        "{description} fetched from `{}` blob source is invalid",
+                        source.name
+                    ))),
+                },
+                Err(err) => {
+                    let context = format!(
+                        "cannot fetch {description} from `{}` blob source",
+                        source.name
+                    );
+                    SnapshotsApplierError::classified(err, context)
+                }
+            };
+            tracing::warn!("{err:#}");
+            METRICS.blob_fetch_failures[&BlobSourceLabel::from(source.name)].inc();
+
+            // Prefer retryable errors, so that the recovery is retried if at least one source has failed transiently.
+            if matches!(err, SnapshotsApplierError::Retryable(_))
+                || !matches!(error, Some(SnapshotsApplierError::Retryable(_)))
+            {
+                error = Some(err);
+            }
+        }
+        Err(error.expect("no blob sources")) // the primary source is always present
+    }
+
     async fn recover_factory_deps(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -479,15 +640,12 @@ impl<'a> SnapshotsApplier<'a> {
 
         tracing::debug!("Fetching factory dependencies from object store");
         let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
-        let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let description = format!("factory deps for L1 batch #{l1_batch_number}");
+        let (factory_deps, source): (SnapshotFactoryDependencies, _) = self
+            .fetch_blob(l1_batch_number, &description, |_| Ok(()))
+            .await?;
         tracing::debug!(
-            "Fetched {} factory dependencies from object store",
+            "Fetched {} factory dependencies from `{source}` blob source",
             factory_deps.factory_deps.len()
         );
 
@@ -521,7 +679,7 @@ impl<'a> SnapshotsApplier<'a> {
     ) -> Result<(), SnapshotsApplierError> {
         storage
             .storage_logs_dedup_dal()
-            .insert_initial_writes_from_snapshot(storage_logs)
+            .insert_initial_writes_from_snapshot_copy(storage_logs)
             .await
             .map_err(|err| {
                 let context =
@@ -539,7 +697,7 @@ impl<'a> SnapshotsApplier<'a> {
     ) -> Result<(), SnapshotsApplierError> {
         storage
             .storage_logs_dal()
-            .insert_storage_logs_from_snapshot(
+            .insert_storage_logs_from_snapshot_copy(
                 self.applied_snapshot_status.miniblock_number,
                 storage_logs,
             )
@@ -568,17 +726,21 @@ impl<'a> SnapshotsApplier<'a> {
             chunk_id,
             l1_batch_number: self.applied_snapshot_status.l1_batch_number,
         };
-        let storage_snapshot_chunk: SnapshotStorageLogsChunk =
-            self.blob_store.get(storage_key).await.map_err(|err| {
-                let context =
-                    format!("cannot fetch storage logs {storage_key:?} from object store");
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let description = format!("storage logs chunk {storage_key:?}");
+        let (storage_snapshot_chunk, source) = self
+            .fetch_blob(
+                storage_key,
+                &description,
+                |chunk: &SnapshotStorageLogsChunk| {
+                    self.validate_storage_logs_chunk_hash(chunk_id, chunk)?;
+                    self.validate_storage_logs_chunk(storage_key, &chunk.storage_logs)
+                },
+            )
+            .await?;
         let storage_logs = &storage_snapshot_chunk.storage_logs;
-        self.validate_storage_logs_chunk(storage_logs)?;
         let latency = latency.observe();
         tracing::info!(
-            "Loaded {} storage logs from GCS for chunk {chunk_id} in {latency:?}",
+            "Loaded {} storage logs for chunk {chunk_id} from `{source}` blob source in {latency:?}",
             storage_logs.len()
         );
 
@@ -623,9 +785,21 @@ impl<'a> SnapshotsApplier<'a> {
     /// Performs basic sanity check for a storage logs chunk.
     fn validate_storage_logs_chunk(
         &self,
+        storage_key: SnapshotStorageLogsStorageKey,
         storage_logs: &[SnapshotStorageLog],
     ) -> anyhow::Result<()> {
+        let chunk_count = self
+            .applied_snapshot_status
+            .storage_logs_chunks_processed
+            .len();
+        let hashed_keys_range = storage_key.hashed_keys_range(chunk_count as u64);
         for log in storage_logs {
+            let hashed_key = log.key.hashed_key();
+            anyhow::ensure!(
+                hashed_keys_range.contains(&hashed_key),
+                "storage log with hashed key {hashed_key:?} is outside of the expected range {hashed_keys_range:?} \
+                 for chunk {storage_key:?}: {log:?}"
+            );
             anyhow::ensure!(
                 log.enumeration_index > 0,
                 "invalid storage log with zero enumeration_index: {log:?}"
@@ -638,6 +812,29 @@ impl<'a> SnapshotsApplier<'a> {
         Ok(())
     }
 
+    /// Checks that the content hash of a storage logs chunk matches the hash in the snapshot header
+    /// (if the header specifies one).
+    fn validate_storage_logs_chunk_hash(
+        &self,
+        chunk_id: u64,
+        chunk: &SnapshotStorageLogsChunk,
+    ) -> anyhow::Result<()> {
+        let expected_hash = self
+            .storage_logs_chunk_hashes
+            .get(chunk_id as usize)
+            .copied()
+            .flatten();
+        if let Some(expected_hash) = expected_hash {
+            let actual_hash = chunk.content_hash();
+            anyhow::ensure!(
+                actual_hash == expected_hash,
+                "content hash of storage logs chunk {chunk_id} ({actual_hash:?}) differs from the hash \
+                 in the snapshot header ({expected_hash:?})"
+            );
+        }
+        Ok(())
+    }
+
     async fn recover_storage_logs(&self) -> Result<(), SnapshotsApplierError> {
         let semaphore = Semaphore::new(self.connection_pool.max_size() as usize);
         let tasks = self
@@ -2,7 +2,9 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -18,6 +20,19 @@ pub(crate) enum InitialStage {
     ApplyFactoryDeps,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(crate) struct BlobSourceLabel {
+    source: String,
+}
+
+impl From<&str> for BlobSourceLabel {
+    fn from(source: &str) -> Self {
+        Self {
+            source: source.to_owned(),
+        }
+    }
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "snapshots_applier")]
 pub(crate) struct SnapshotsApplierMetrics {
@@ -38,6 +53,11 @@ pub(crate) struct SnapshotsApplierMetrics {
     /// Latency of storage log chunk processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub storage_logs_chunks_duration: Family<StorageLogsChunksStage, Histogram<Duration>>,
+
+    /// Number of snapshot blobs fetched from each blob source (the primary object store or a mirror).
+    pub blobs_fetched: Family<BlobSourceLabel, Counter>,
+    /// Number of failed or invalid snapshot blob fetches for each blob source.
+    pub blob_fetch_failures: Family<BlobSourceLabel, Counter>,
 }
 
 #[vise::register]
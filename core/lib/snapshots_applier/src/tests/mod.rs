@@ -6,7 +6,8 @@ use test_casing::test_casing;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
-    get_code_key, Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
+    get_code_key, Address, L1BatchNumber, L2ChainId, PackedEthSignature, ProtocolVersion,
+    ProtocolVersionId,
 };
 
 use self::utils::{
@@ -120,6 +121,7 @@ async fn applier_errors_after_genesis() {
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 0,
         gas_limit: 0,
+        logs_bloom: Default::default(),
     };
     storage
         .blocks_dal()
@@ -186,6 +188,68 @@ async fn applier_errors_with_unrecognized_snapshot_version() {
         .unwrap_err();
 }
 
+#[tokio::test]
+async fn applier_errors_with_malformed_storage_logs_chunks() {
+    let pool = ConnectionPool::test_pool().await;
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let expected_status = mock_recovery_status();
+    let mut snapshot_header = mock_snapshot_header(&expected_status);
+    snapshot_header.storage_logs_chunks[1].chunk_id = 0;
+    let client = MockMainNodeClient {
+        fetch_newest_snapshot_response: Some(snapshot_header),
+        ..MockMainNodeClient::default()
+    };
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("malformed snapshot header"),
+        "{err:#}"
+    );
+}
+
+#[tokio::test]
+async fn applier_errors_with_mismatched_l1_batch_root_hash() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.l1_batch_root_hash = Some(H256::repeat_byte(0xff));
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("root hash"), "{err:#}");
+}
+
+#[tokio::test]
+async fn applier_errors_with_mismatched_storage_logs_chunk_hash() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    // Tamper with a storage log value; the chunk is otherwise well-formed.
+    let chunk_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: expected_status.l1_batch_number,
+        chunk_id: 0,
+    };
+    let mut chunk: SnapshotStorageLogsChunk = object_store.get(chunk_key).await.unwrap();
+    chunk.storage_logs[0].value = H256::repeat_byte(0xff);
+    object_store.put(chunk_key, &chunk).await.unwrap();
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("content hash"), "{err:#}");
+}
+
 #[tokio::test]
 async fn applier_returns_error_on_fatal_object_store_error() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -230,6 +294,114 @@ async fn applier_returns_error_after_too_many_object_store_retries() {
     }));
 }
 
+#[tokio::test]
+async fn applier_fails_over_to_mirrors() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    // The primary store only contains a malformed storage logs chunk; all other blobs are missing.
+    let primary_store = ObjectStoreFactory::mock().create_store().await;
+    let chunk_count = expected_status.storage_logs_chunks_processed.len() as u64;
+    let chunk_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: expected_status.l1_batch_number,
+        chunk_id: 0,
+    };
+    let wrong_range = SnapshotStorageLogsStorageKey {
+        chunk_id: 1,
+        ..chunk_key
+    }
+    .hashed_keys_range(chunk_count);
+    let malformed_chunk = SnapshotStorageLogsChunk {
+        storage_logs: storage_logs
+            .iter()
+            .filter(|log| wrong_range.contains(&log.key.hashed_key()))
+            .cloned()
+            .collect(),
+    };
+    assert!(!malformed_chunk.storage_logs.is_empty());
+    primary_store
+        .put(chunk_key, &malformed_chunk)
+        .await
+        .unwrap();
+
+    // The first mirror is throttled.
+    let throttled_mirror = ObjectStoreWithErrors::new(object_store.clone(), |_| {
+        Err(ObjectStoreError::Other("too many requests".into()))
+    });
+    let config = SnapshotsApplierConfig {
+        mirrors: vec![
+            SnapshotBlobMirror {
+                name: "throttled".to_owned(),
+                store: Arc::new(throttled_mirror),
+            },
+            SnapshotBlobMirror {
+                name: "healthy".to_owned(),
+                store: object_store,
+            },
+        ],
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    config
+        .run(&pool, &client, primary_store.as_ref())
+        .await
+        .unwrap();
+
+    let mut storage = pool.connection().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(status.unwrap(), expected_status);
+    let storage_log_count = storage
+        .storage_logs_dal()
+        .get_storage_logs_row_count(expected_status.miniblock_number)
+        .await
+        .unwrap();
+    assert_eq!(storage_log_count, storage_logs.len() as u64);
+}
+
+#[tokio::test]
+async fn applier_verifies_snapshot_header_signature() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let chain_id = L2ChainId::default();
+    let private_key = H256::repeat_byte(0x42);
+    let trusted_signers = TrustedSnapshotSigners {
+        chain_id,
+        addresses: vec![PackedEthSignature::address_from_private_key(&private_key).unwrap()],
+    };
+
+    let config = SnapshotsApplierConfig {
+        trusted_signers: Some(trusted_signers.clone()),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let err = config.run(&pool, &client, &object_store).await.unwrap_err();
+    assert!(format!("{err:#}").contains("is not signed"), "{err:#}");
+    // No snapshot data should be applied.
+    let applied_status = pool
+        .connection()
+        .await
+        .unwrap()
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(applied_status, None);
+
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.sign(chain_id, &private_key).unwrap();
+    let config = SnapshotsApplierConfig {
+        trusted_signers: Some(trusted_signers),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    config.run(&pool, &client, &object_store).await.unwrap();
+}
+
 #[tokio::test]
 async fn recovering_tokens() {
     let pool = ConnectionPool::<Core>::test_pool().await;
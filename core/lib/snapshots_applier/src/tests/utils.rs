@@ -47,6 +47,16 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         Ok(self.fetch_newest_snapshot_response.clone())
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        Ok(self
+            .fetch_newest_snapshot_response
+            .clone()
+            .filter(|header| header.l1_batch_number == l1_batch_number))
+    }
+
     async fn fetch_tokens(
         &self,
         _at_miniblock: MiniblockNumber,
@@ -207,13 +217,17 @@ pub(super) fn mock_snapshot_header(status: &SnapshotRecoveryStatus) -> SnapshotH
             SnapshotStorageLogsChunkMetadata {
                 chunk_id: 0,
                 filepath: "file0".to_string(),
+                content_hash: None,
             },
             SnapshotStorageLogsChunkMetadata {
                 chunk_id: 1,
                 filepath: "file1".to_string(),
+                content_hash: None,
             },
         ],
         factory_deps_filepath: "some_filepath".to_string(),
+        l1_batch_root_hash: Some(status.l1_batch_root_hash),
+        signature: None,
     }
 }
 
@@ -235,26 +249,30 @@ pub(super) async fn prepare_clients(
         .await
         .unwrap();
 
-    let chunk_size = logs
-        .len()
-        .div_ceil(status.storage_logs_chunks_processed.len());
-    assert!(chunk_size > 0);
-
-    for (chunk_id, chunk) in logs.chunks(chunk_size).enumerate() {
-        let chunk_storage_logs = SnapshotStorageLogsChunk {
-            storage_logs: chunk.to_vec(),
-        };
+    let mut snapshot_header = mock_snapshot_header(status);
+    let chunk_count = status.storage_logs_chunks_processed.len() as u64;
+    for chunk_id in 0..chunk_count {
         let chunk_key = SnapshotStorageLogsStorageKey {
             l1_batch_number: status.l1_batch_number,
-            chunk_id: chunk_id as u64,
+            chunk_id,
+        };
+        let hashed_keys_range = chunk_key.hashed_keys_range(chunk_count);
+        let chunk_storage_logs = SnapshotStorageLogsChunk {
+            storage_logs: logs
+                .iter()
+                .filter(|log| hashed_keys_range.contains(&log.key.hashed_key()))
+                .cloned()
+                .collect(),
         };
         object_store
             .put(chunk_key, &chunk_storage_logs)
             .await
             .unwrap();
+        snapshot_header.storage_logs_chunks[chunk_id as usize].content_hash =
+            Some(chunk_storage_logs.content_hash());
     }
 
-    client.fetch_newest_snapshot_response = Some(mock_snapshot_header(status));
+    client.fetch_newest_snapshot_response = Some(snapshot_header);
     client.fetch_l1_batch_responses.insert(
         status.l1_batch_number,
         l1_batch_details(status.l1_batch_number, status.l1_batch_root_hash),
@@ -28,10 +28,12 @@ mod test_utils;
 mod witness;
 
 pub use self::{
-    cache::sequential_cache::SequentialCache,
+    cache::{lru_cache::LruCache, sequential_cache::SequentialCache, CacheValue},
     in_memory::InMemoryStorage,
     postgres::{PostgresStorage, PostgresStorageCaches, PostgresStorageCachesTask},
-    rocksdb::{RocksdbStorage, RocksdbStorageBuilder, StateKeeperColumnFamily},
+    rocksdb::{
+        RocksdbStorage, RocksdbStorageBuilder, RocksdbStorageCaches, StateKeeperColumnFamily,
+    },
     shadow_storage::ShadowStorage,
     storage_view::{StorageView, StorageViewMetrics},
     witness::WitnessStorage,
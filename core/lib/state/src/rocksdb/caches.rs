@@ -0,0 +1,174 @@
+//! In-memory caches for `RocksdbStorage`.
+
+use std::{
+    collections::HashSet,
+    mem,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_types::{L1BatchNumber, H256};
+
+use super::{RocksdbStorage, StateValue};
+use crate::cache::{lru_cache::LruCache, CacheValue};
+
+impl CacheValue<H256> for StateValue {
+    #[allow(clippy::cast_possible_truncation)] // doesn't happen in practice
+    fn cache_weight(&self) -> u32 {
+        const WEIGHT: usize = mem::size_of::<StateValue>() + mem::size_of::<H256>();
+        // ^ Since values are small in size, we want to account for key sizes as well
+
+        WEIGHT as u32
+    }
+}
+
+/// In-memory LRU caches for [`RocksdbStorage`](super::RocksdbStorage) shared among storage instances
+/// backed by the same RocksDB instance. Caches are used to speed up reads of hot storage slots
+/// and bytecodes compared to reading them from RocksDB (which involves decompression, potential disk I/O etc.).
+///
+/// - Cache for state values keyed by the hashed storage key. Updated when the storage is synchronized
+///   with Postgres; cleared on rollback.
+/// - Cache for factory dependencies. Never invalidated, except for rollbacks, since it is content-addressable.
+///
+/// Only values present in RocksDB are cached.
+///
+/// Caches are filled lazily by storages reading from RocksDB concurrently with updates of RocksDB. To prevent
+/// filling caches with values that were read before an update, caches track a generation incremented on each update;
+/// a value is only cached if the generation hasn't changed since it was read.
+#[derive(Debug, Clone)]
+pub struct RocksdbStorageCaches {
+    pub(super) values: LruCache<H256, StateValue>,
+    pub(super) factory_deps: LruCache<H256, Vec<u8>>,
+    generation: Arc<RwLock<u64>>,
+}
+
+impl RocksdbStorageCaches {
+    /// Creates caches with the specified capacities measured in bytes. A cache with zero capacity is disabled.
+    pub fn new(values_capacity: u64, factory_deps_capacity: u64) -> Self {
+        tracing::debug!(
+            "Initialized RocksDB storage caches with {values_capacity}B capacity for state values, \
+             {factory_deps_capacity}B capacity for factory deps"
+        );
+        Self {
+            values: LruCache::new("rocksdb_values_cache", values_capacity),
+            factory_deps: LruCache::new("rocksdb_factory_deps_cache", factory_deps_capacity),
+            generation: Arc::default(),
+        }
+    }
+
+    /// Returns the current generation of caches. Must be called before reading a value from RocksDB
+    /// that will be put into a cache.
+    pub(super) fn generation(&self) -> u64 {
+        *self
+            .generation
+            .read()
+            .expect("RocksDB caches lock is poisoned")
+    }
+
+    /// Inserts a value read from RocksDB into the values cache, unless RocksDB was updated after `generation`
+    /// was obtained.
+    pub(super) fn fill_value(&self, generation: u64, hashed_key: H256, value: StateValue) {
+        let current_generation = self
+            .generation
+            .read()
+            .expect("RocksDB caches lock is poisoned");
+        if *current_generation == generation {
+            self.values.insert(hashed_key, value);
+        }
+    }
+
+    /// Inserts a factory dependency read from RocksDB into the cache, unless RocksDB was updated after `generation`
+    /// was obtained.
+    pub(super) fn fill_factory_dep(&self, generation: u64, hash: H256, bytecode: Vec<u8>) {
+        let current_generation = self
+            .generation
+            .read()
+            .expect("RocksDB caches lock is poisoned");
+        if *current_generation == generation {
+            self.factory_deps.insert(hash, bytecode);
+        }
+    }
+
+    /// Updates caches after RocksDB was updated. Concurrent cache fills are blocked while `update` is running,
+    /// and fills with values read before the update are discarded afterwards.
+    pub(super) fn update(&self, update: impl FnOnce(&Self)) {
+        let mut generation = self
+            .generation
+            .write()
+            .expect("RocksDB caches lock is poisoned");
+        *generation += 1;
+        update(self);
+    }
+
+    /// Clears caches after RocksDB was rolled back.
+    pub(super) fn clear(&self) {
+        self.update(|caches| {
+            caches.values.clear();
+            caches.factory_deps.clear();
+        });
+    }
+}
+
+impl RocksdbStorage {
+    /// Preloads state values touched in the specified number of latest L1 batches processed by this storage
+    /// into the in-memory values cache. Such values are likely to be accessed by the following L1 batches
+    /// (e.g., they may belong to popular contracts), so preloading them stabilizes the latency of the first
+    /// L1 batches executed after a restart. Does nothing if caches are not enabled.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn warm_up_caches(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_count: u32,
+    ) -> anyhow::Result<()> {
+        let Some(caches) = self.caches.clone() else {
+            return Ok(());
+        };
+        let Some(next_l1_batch_number) = self.l1_batch_number().await else {
+            return Ok(());
+        };
+        if l1_batch_count == 0 || next_l1_batch_number == L1BatchNumber(0) {
+            return Ok(());
+        }
+
+        let started_at = Instant::now();
+        let first_l1_batch_number = next_l1_batch_number.0.saturating_sub(l1_batch_count);
+        let mut hashed_keys = HashSet::new();
+        for number in first_l1_batch_number..next_l1_batch_number.0 {
+            let number = L1BatchNumber(number);
+            let touched_slots = storage
+                .storage_logs_dal()
+                .get_touched_slots_for_l1_batch(number)
+                .await
+                .with_context(|| format!("failed loading touched slots for L1 batch {number}"))?;
+            hashed_keys.extend(touched_slots.into_keys().map(|key| key.hashed_key()));
+        }
+
+        let db = self.db.clone();
+        let loaded_count = tokio::task::spawn_blocking(move || {
+            let mut loaded_count = 0;
+            for hashed_key in hashed_keys {
+                let generation = caches.generation();
+                if let Some(state_value) = Self::read_state_value(&db, hashed_key) {
+                    caches.fill_value(generation, hashed_key, state_value);
+                    loaded_count += 1;
+                }
+            }
+            loaded_count
+        })
+        .await
+        .context("panicked warming up RocksDB storage caches")?;
+
+        tracing::info!(
+            "Warmed up RocksDB values cache with {loaded_count} values touched in L1 batches \
+             #{first_l1_batch_number}..={}; took {:?}",
+            next_l1_batch_number - 1,
+            started_at.elapsed()
+        );
+        Ok(())
+    }
+}
@@ -31,15 +31,17 @@ use anyhow::Context as _;
 use itertools::{Either, Itertools};
 use tokio::sync::watch;
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
+pub use self::caches::RocksdbStorageCaches;
 use self::metrics::METRICS;
 #[cfg(test)]
 use self::tests::RocksdbStorageEventListener;
 use crate::{InMemoryStorage, ReadStorage};
 
+mod caches;
 mod metrics;
 mod recovery;
 #[cfg(test)]
@@ -130,6 +132,7 @@ impl From<anyhow::Error> for RocksdbSyncError {
 #[derive(Debug)]
 pub struct RocksdbStorage {
     db: RocksDB<StateKeeperColumnFamily>,
+    caches: Option<RocksdbStorageCaches>,
     pending_patch: InMemoryStorage,
     enum_index_migration_chunk_size: usize,
     /// Test-only listeners to events produced by the storage.
@@ -147,6 +150,7 @@ impl RocksdbStorageBuilder {
     pub fn from_rocksdb(value: RocksDB<StateKeeperColumnFamily>) -> Self {
         RocksdbStorageBuilder(RocksdbStorage {
             db: value,
+            caches: None,
             pending_patch: InMemoryStorage::default(),
             enum_index_migration_chunk_size: 100,
             #[cfg(test)]
@@ -159,6 +163,12 @@ impl RocksdbStorageBuilder {
         self.0.enum_index_migration_chunk_size = chunk_size;
     }
 
+    /// Enables in-memory caches for the storage. The caches must be shared only among storages
+    /// backed by the same RocksDB instance.
+    pub fn enable_caches(&mut self, caches: RocksdbStorageCaches) {
+        self.0.caches = Some(caches);
+    }
+
     /// Returns the last processed l1 batch number + 1.
     ///
     /// # Panics
@@ -224,15 +234,34 @@ impl RocksdbStorage {
     ///
     /// Propagates RocksDB I/O errors.
     pub async fn builder(path: &Path) -> anyhow::Result<RocksdbStorageBuilder> {
-        Self::new(path.to_path_buf())
+        Self::builder_with_options(path, RocksDBOptions::default()).await
+    }
+
+    /// Creates a new storage builder with the provided RocksDB `path` and `options`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub async fn builder_with_options(
+        path: &Path,
+        options: RocksDBOptions,
+    ) -> anyhow::Result<RocksdbStorageBuilder> {
+        Self::with_options(path.to_path_buf(), options)
             .await
             .map(RocksdbStorageBuilder)
     }
 
+    #[cfg(test)]
     async fn new(path: PathBuf) -> anyhow::Result<Self> {
+        Self::with_options(path, RocksDBOptions::default()).await
+    }
+
+    async fn with_options(path: PathBuf, options: RocksDBOptions) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
-                db: RocksDB::new(&path).context("failed initializing state keeper RocksDB")?,
+                db: RocksDB::with_options(&path, options)
+                    .context("failed initializing state keeper RocksDB")?,
+                caches: None,
                 pending_patch: InMemoryStorage::default(),
                 enum_index_migration_chunk_size: 100,
                 #[cfg(test)]
@@ -415,6 +444,7 @@ impl RocksdbStorage {
         let key_count = keys.len();
 
         let db = self.db.clone();
+        let caches = self.caches.clone();
         tokio::task::spawn_blocking(move || {
             let mut write_batch = db.new_write_batch();
             for (key, value) in keys.iter().zip(values) {
@@ -448,7 +478,15 @@ impl RocksdbStorage {
                 }
             }
             db.write(write_batch)
-                .context("failed saving enum indices to RocksDB")
+                .context("failed saving enum indices to RocksDB")?;
+            if let Some(caches) = &caches {
+                caches.update(|caches| {
+                    for key in &keys {
+                        caches.values.remove(key);
+                    }
+                });
+            }
+            anyhow::Ok(())
         })
         .await
         .context("panicked while saving enum indices to RocksDB")??;
@@ -461,7 +499,21 @@ impl RocksdbStorage {
     }
 
     fn read_value_inner(&self, key: &StorageKey) -> Option<StorageValue> {
-        Self::read_state_value(&self.db, key.hashed_key()).map(|state_value| state_value.value)
+        self.read_cached_state_value(key.hashed_key())
+            .map(|state_value| state_value.value)
+    }
+
+    fn read_cached_state_value(&self, hashed_key: H256) -> Option<StateValue> {
+        let Some(caches) = &self.caches else {
+            return Self::read_state_value(&self.db, hashed_key);
+        };
+        if let Some(state_value) = caches.values.get(&hashed_key) {
+            return Some(state_value);
+        }
+        let generation = caches.generation();
+        let state_value = Self::read_state_value(&self.db, hashed_key)?;
+        caches.fill_value(generation, hashed_key, state_value);
+        Some(state_value)
     }
 
     fn read_state_value(
@@ -541,6 +593,7 @@ impl RocksdbStorage {
         );
 
         let db = self.db.clone();
+        let caches = self.caches.clone();
         tokio::task::spawn_blocking(move || {
             let mut batch = db.new_write_batch();
 
@@ -568,7 +621,11 @@ impl RocksdbStorage {
             }
 
             db.write(batch)
-                .context("failed to save state data into RocksDB")
+                .context("failed to save state data into RocksDB")?;
+            if let Some(caches) = &caches {
+                caches.clear();
+            }
+            anyhow::Ok(())
         })
         .await
         .context("panicked during revert")?
@@ -579,6 +636,7 @@ impl RocksdbStorage {
         let pending_patch = mem::take(&mut self.pending_patch);
 
         let db = self.db.clone();
+        let caches = self.caches.clone();
         let save_task = tokio::task::spawn_blocking(move || {
             let mut batch = db.new_write_batch();
             let cf = StateKeeperColumnFamily::State;
@@ -589,7 +647,7 @@ impl RocksdbStorage {
                     &serialize_l1_batch_number(l1_batch_number.0),
                 );
             }
-            for (key, (value, enum_index)) in pending_patch.state {
+            for (&key, &(value, enum_index)) in &pending_patch.state {
                 batch.put_cf(
                     cf,
                     &Self::serialize_state_key(key),
@@ -602,7 +660,21 @@ impl RocksdbStorage {
                 batch.put_cf(cf, &hash.to_fixed_bytes(), value.as_ref());
             }
             db.write(batch)
-                .context("failed to save state data into RocksDB")
+                .context("failed to save state data into RocksDB")?;
+
+            if let Some(caches) = &caches {
+                // Only update values that are already cached; new values will be loaded lazily.
+                caches.update(|caches| {
+                    for (key, (value, enum_index)) in pending_patch.state {
+                        if caches.values.get(&key).is_some() {
+                            caches
+                                .values
+                                .insert(key, StateValue::new(value, Some(enum_index)));
+                        }
+                    }
+                });
+            }
+            anyhow::Ok(())
         });
         save_task
             .await
@@ -670,16 +742,30 @@ impl ReadStorage for RocksdbStorage {
     }
 
     fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self
+            .caches
+            .as_ref()
+            .and_then(|caches| caches.factory_deps.get(&hash))
+        {
+            return Some(bytecode);
+        }
+
+        let generation = self.caches.as_ref().map(RocksdbStorageCaches::generation);
         let cf = StateKeeperColumnFamily::FactoryDeps;
-        self.db
+        let bytecode = self
+            .db
             .get_cf(cf, hash.as_bytes())
-            .expect("failed to read RocksDB state value")
+            .expect("failed to read RocksDB state value")?;
+        if let (Some(caches), Some(generation)) = (&self.caches, generation) {
+            caches.fill_factory_dep(generation, hash, bytecode.clone());
+        }
+        Some(bytecode)
     }
 
     fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
         // Can safely unwrap here since it indicates that the migration has not yet ended and boojum will
         // only be deployed when the migration is finished.
-        Self::read_state_value(&self.db, key.hashed_key())
+        self.read_cached_state_value(key.hashed_key())
             .map(|state_value| state_value.enum_index.unwrap())
     }
 }
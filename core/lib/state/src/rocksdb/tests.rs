@@ -1,6 +1,13 @@
 //! Tests for [`RocksdbStorage`].
 
-use std::fmt;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
 
 use assert_matches::assert_matches;
 use tempfile::TempDir;
@@ -241,6 +248,136 @@ async fn rocksdb_storage_revert() {
     }
 }
 
+#[tokio::test]
+async fn rocksdb_storage_with_caches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut conn).await;
+    let storage_logs = gen_storage_logs(20..40);
+    create_miniblock(&mut conn, MiniblockNumber(1), storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(1), &storage_logs).await;
+    let inserted_storage_logs = gen_storage_logs(50..60);
+    create_miniblock(&mut conn, MiniblockNumber(2), inserted_storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(2), &inserted_storage_logs).await;
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let caches = RocksdbStorageCaches::new(1 << 20, 1 << 20);
+    let mut builder = RocksdbStorage::builder(dir.path()).await.unwrap();
+    builder.enable_caches(caches.clone());
+    let storage = builder
+        .synchronize(&mut conn, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Storage synchronization unexpectedly stopped");
+
+    // Only slots touched in the last L1 batch should be loaded.
+    storage.warm_up_caches(&mut conn, 1).await.unwrap();
+    for log in &inserted_storage_logs {
+        let cached_value = caches.values.get(&log.key.hashed_key()).unwrap();
+        assert_eq!(cached_value.value, log.value);
+    }
+    for log in &storage_logs {
+        assert!(caches.values.get(&log.key.hashed_key()).is_none());
+    }
+
+    // Overwrite some of the cached values and check that the cache is updated after syncing.
+    let replaced_storage_logs: Vec<_> = inserted_storage_logs
+        .iter()
+        .step_by(2)
+        .map(|&log| StorageLog {
+            value: H256::repeat_byte(0xf0),
+            ..log
+        })
+        .collect();
+    create_miniblock(&mut conn, MiniblockNumber(3), replaced_storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(3), &[]).await;
+
+    let mut builder = RocksdbStorageBuilder::from_rocksdb(storage.into_rocksdb());
+    builder.enable_caches(caches.clone());
+    let mut storage = builder
+        .synchronize(&mut conn, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Storage synchronization unexpectedly stopped");
+    for log in &replaced_storage_logs {
+        assert_eq!(storage.read_value(&log.key), log.value);
+        let cached_value = caches.values.get(&log.key.hashed_key()).unwrap();
+        assert_eq!(cached_value.value, log.value);
+    }
+    for log in &storage_logs {
+        assert_eq!(storage.read_value(&log.key), log.value);
+        assert!(caches.values.get(&log.key.hashed_key()).is_some());
+    }
+
+    storage.rollback(&mut conn, L1BatchNumber(2)).await.unwrap();
+    for log in &replaced_storage_logs {
+        assert!(caches.values.get(&log.key.hashed_key()).is_none());
+    }
+    for log in &inserted_storage_logs {
+        assert_eq!(storage.read_value(&log.key), log.value);
+    }
+}
+
+#[test]
+fn values_read_before_update_are_not_cached() {
+    let caches = RocksdbStorageCaches::new(1 << 20, 1 << 20);
+    let hashed_key = H256::repeat_byte(1);
+    let stale_value = StateValue::new(H256::repeat_byte(2), Some(1));
+
+    let generation = caches.generation();
+    // Emulate RocksDB being updated after the value was read, but before it was put into the cache.
+    caches.update(|_| { /* do nothing */ });
+    caches.fill_value(generation, hashed_key, stale_value);
+    assert!(caches.values.get(&hashed_key).is_none());
+
+    let generation = caches.generation();
+    caches.fill_value(generation, hashed_key, stale_value);
+    let cached_value = caches.values.get(&hashed_key).unwrap();
+    assert_eq!(cached_value.value, stale_value.value);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rocksdb_caches_with_concurrent_reads_and_updates() {
+    const READER_COUNT: usize = 4;
+    const UPDATE_COUNT: u64 = 200;
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let caches = RocksdbStorageCaches::new(1 << 20, 1 << 20);
+    let mut storage = RocksdbStorage::new(dir.path().into()).await.unwrap();
+    storage.caches = Some(caches.clone());
+    let hashed_key = H256::repeat_byte(1);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let readers: Vec<_> = (0..READER_COUNT)
+        .map(|_| {
+            let mut reader = RocksdbStorageBuilder::from_rocksdb(storage.db.clone()).0;
+            reader.caches = Some(caches.clone());
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    reader.read_cached_state_value(hashed_key);
+                }
+            })
+        })
+        .collect();
+
+    for i in 1..=UPDATE_COUNT {
+        let value = H256::from_low_u64_be(i);
+        storage.pending_patch.state = HashMap::from([(hashed_key, (value, 1))]);
+        storage.save(None).await.unwrap();
+        // Values read by readers before the update must not end up in the cache.
+        if let Some(cached_value) = caches.values.get(&hashed_key) {
+            assert_eq!(cached_value.value, value, "stale value after update #{i}");
+        }
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().expect("reader panicked");
+    }
+}
+
 #[tokio::test]
 async fn rocksdb_enum_index_migration() {
     let pool = ConnectionPool::<Core>::test_pool().await;
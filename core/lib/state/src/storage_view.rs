@@ -92,6 +92,31 @@ where
     }
 }
 
+impl<S> ReadStorage for &mut S
+where
+    S: ReadStorage + ?Sized,
+{
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        (**self).read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        (**self).is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        (**self).load_factory_dep(hash)
+    }
+
+    fn is_bytecode_known(&mut self, bytecode_hash: &H256) -> bool {
+        (**self).is_bytecode_known(bytecode_hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        (**self).get_enumeration_index(key)
+    }
+}
+
 impl<S: ReadStorage + fmt::Debug> StorageView<S> {
     /// Creates a new storage view based on the underlying storage.
     pub fn new(storage_handle: S) -> Self {
@@ -86,6 +86,7 @@ pub(crate) async fn create_miniblock(
         protocol_version: Some(Default::default()),
         virtual_blocks: 0,
         gas_limit: 0,
+        logs_bloom: Default::default(),
     };
 
     conn.blocks_dal()
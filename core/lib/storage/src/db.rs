@@ -3,6 +3,7 @@ use std::{
     ffi::CStr,
     fmt, iter,
     marker::PhantomData,
+    mem,
     num::NonZeroU32,
     ops,
     path::Path,
@@ -12,8 +13,9 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBCompactionStyle,
+    DBCompressionType, DBPinnableSlice, Direction, IteratorMode, Options, PrefixRange, ReadOptions,
+    Snapshot, WriteOptions, DB,
 };
 
 use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
@@ -261,8 +263,47 @@ impl StalledWritesRetries {
     }
 }
 
+/// Tuning options for a single column family in [`RocksDB`]. Options that are not set retain their default values.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColumnFamilyOptions {
+    /// Byte size of a single memtable (aka write buffer). Takes precedence over
+    /// [`RocksDBOptions::large_memtable_capacity`].
+    pub write_buffer_size: Option<usize>,
+    /// Compaction style for the column family. By default, level-style compaction is used.
+    pub compaction_style: Option<DBCompactionStyle>,
+    /// Number of bits per key for bloom filters; 0 disables bloom filters. The default value is 10.
+    pub bloom_filter_bits_per_key: Option<f64>,
+    /// Compression algorithm for the column family. By default, Snappy compression is used.
+    pub compression: Option<DBCompressionType>,
+}
+
+impl ColumnFamilyOptions {
+    const DEFAULT_BLOOM_FILTER_BITS_PER_KEY: f64 = 10.0;
+
+    fn configure_block_based_options(&self, options: &mut BlockBasedOptions) {
+        let bloom_filter_bits_per_key = self
+            .bloom_filter_bits_per_key
+            .unwrap_or(Self::DEFAULT_BLOOM_FILTER_BITS_PER_KEY);
+        if bloom_filter_bits_per_key > 0.0 {
+            options.set_bloom_filter(bloom_filter_bits_per_key, false);
+        }
+    }
+
+    fn configure(&self, options: &mut Options) {
+        if let Some(write_buffer_size) = self.write_buffer_size {
+            options.set_write_buffer_size(write_buffer_size);
+        }
+        if let Some(compaction_style) = self.compaction_style {
+            options.set_compaction_style(compaction_style);
+        }
+        if let Some(compression) = self.compression {
+            options.set_compression_type(compression);
+        }
+    }
+}
+
 /// [`RocksDB`] options.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct RocksDBOptions {
     /// Byte capacity of the block cache (the main RocksDB cache for reads). If not set, default RocksDB
     /// cache options will be used.
@@ -277,6 +318,9 @@ pub struct RocksDBOptions {
     pub stalled_writes_retries: StalledWritesRetries,
     /// Number of open files that can be used by the DB. Default is None, for no limit.
     pub max_open_files: Option<NonZeroU32>,
+    /// Tuning options for column families keyed by the column family name (as returned by
+    /// [`NamedColumnFamily::name()`]). Column families not mentioned here use default options.
+    pub column_families: HashMap<String, ColumnFamilyOptions>,
 }
 
 impl Default for RocksDBOptions {
@@ -286,6 +330,7 @@ impl Default for RocksDBOptions {
             large_memtable_capacity: None,
             stalled_writes_retries: StalledWritesRetries::new(Duration::from_secs(10)),
             max_open_files: None,
+            column_families: HashMap::new(),
         }
     }
 }
@@ -348,19 +393,39 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             );
         }
 
+        let unknown_tuned_cfs: Vec<_> = options
+            .column_families
+            .keys()
+            .filter(|cf_name| !cfs_and_options.contains_key(cf_name.as_str()))
+            .collect();
+        if !unknown_tuned_cfs.is_empty() {
+            tracing::warn!(
+                "Options are specified for column families {unknown_tuned_cfs:?} not present in RocksDB `{}`; \
+                 these options will be ignored",
+                CF::DB_NAME
+            );
+        }
+
         // Open obsolete CFs as well; RocksDB initialization will panic otherwise.
         let cf_names = cfs_and_options.keys().copied().collect();
         let all_cfs_and_options = cfs_and_options
             .into_iter()
             .chain(obsolete_cfs.into_iter().map(|name| (name, false)));
         let cfs = all_cfs_and_options.map(|(cf_name, requires_tuning)| {
+            let cf_tuning = options
+                .column_families
+                .get(cf_name)
+                .copied()
+                .unwrap_or_default();
             let mut block_based_options = BlockBasedOptions::default();
-            block_based_options.set_bloom_filter(10.0, false);
+            cf_tuning.configure_block_based_options(&mut block_based_options);
             if let Some(cache) = &caches.shared {
                 block_based_options.set_block_cache(cache);
             }
             let memtable_capacity = options.large_memtable_capacity.filter(|_| requires_tuning);
-            let cf_options = Self::rocksdb_options(memtable_capacity, Some(block_based_options));
+            let mut cf_options =
+                Self::rocksdb_options(memtable_capacity, Some(block_based_options));
+            cf_tuning.configure(&mut cf_options);
             ColumnFamilyDescriptor::new(cf_name, cf_options)
         });
 
@@ -560,6 +625,63 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             .fuse()
         // ^ unwrap() is safe for the same reasons as in `prefix_iterator_cf()`.
     }
+
+    /// Takes a point-in-time snapshot of this database. Reads from the snapshot are not affected
+    /// by writes to the database performed after the snapshot was taken.
+    pub fn snapshot(&self) -> RocksDBSnapshot<CF> {
+        let snapshot = self.inner.db.snapshot();
+        // SAFETY: The snapshot borrows the `DB` instance, which is heap-allocated inside an `Arc`
+        // and thus has a stable address. The `Arc` is kept alive by `RocksDBSnapshot.db`, which is dropped
+        // after the snapshot (struct fields are dropped in the declaration order).
+        let snapshot = unsafe { mem::transmute::<Snapshot<'_>, Snapshot<'static>>(snapshot) };
+        RocksDBSnapshot {
+            snapshot,
+            db: self.clone(),
+        }
+    }
+}
+
+/// Point-in-time snapshot of a [`RocksDB`] instance obtained via [`RocksDB::snapshot()`].
+/// The snapshot is released when this object is dropped.
+pub struct RocksDBSnapshot<CF> {
+    // Must be declared before `db`; see `RocksDB::snapshot()` for details.
+    snapshot: Snapshot<'static>,
+    db: RocksDB<CF>,
+}
+
+impl<CF: fmt::Debug> fmt::Debug for RocksDBSnapshot<CF> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RocksDBSnapshot")
+            .field("db", &self.db)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<CF: NamedColumnFamily> RocksDBSnapshot<CF> {
+    fn read_options(&self) -> ReadOptions {
+        let mut options = ReadOptions::default();
+        options.set_snapshot(&self.snapshot);
+        options
+    }
+
+    pub fn get_cf(&self, cf: CF, key: &[u8]) -> Result<Option<Vec<u8>>, rocksdb::Error> {
+        let cf = self.db.column_family(cf);
+        self.db.inner.db.get_cf_opt(cf, key, &self.read_options())
+    }
+
+    pub fn multi_get_cf(
+        &self,
+        cf: CF,
+        keys: impl Iterator<Item = Vec<u8>>,
+    ) -> Vec<Result<Option<DBPinnableSlice<'_>>, rocksdb::Error>> {
+        let cf = self.db.column_family(cf);
+        let options = self.read_options();
+        self.db
+            .inner
+            .db
+            .batched_multi_get_cf_opt(cf, keys, false, &options)
+    }
 }
 
 impl RocksDB<()> {
@@ -688,6 +810,81 @@ mod tests {
         assert_eq!(value.unwrap(), b"value");
     }
 
+    #[test]
+    fn tuning_column_families() {
+        let temp_dir = TempDir::new().unwrap();
+        let other_cf_options = ColumnFamilyOptions {
+            write_buffer_size: Some(1 << 20),
+            compaction_style: Some(DBCompactionStyle::Universal),
+            bloom_filter_bits_per_key: Some(0.0),
+            compression: Some(DBCompressionType::Zstd),
+        };
+        let default_cf_options = ColumnFamilyOptions {
+            compression: Some(DBCompressionType::Lz4),
+            ..ColumnFamilyOptions::default()
+        };
+        let options = RocksDBOptions {
+            column_families: HashMap::from([
+                ("default".to_owned(), default_cf_options),
+                ("other".to_owned(), other_cf_options),
+                // Options for unknown CFs should be ignored.
+                ("junk".to_owned(), ColumnFamilyOptions::default()),
+            ]),
+            ..RocksDBOptions::default()
+        };
+        let db = RocksDB::<NewColumnFamilies>::with_options(temp_dir.path(), options.clone())
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"value");
+        batch.put_cf(NewColumnFamilies::Other, b"other", b"other_value");
+        db.write(batch).unwrap();
+        drop(db);
+
+        let db = RocksDB::<NewColumnFamilies>::with_options(temp_dir.path(), options).unwrap();
+        let value = db.get_cf(NewColumnFamilies::Default, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = db.get_cf(NewColumnFamilies::Other, b"other").unwrap();
+        assert_eq!(value.unwrap(), b"other_value");
+    }
+
+    #[test]
+    fn reading_from_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<NewColumnFamilies>::new(temp_dir.path()).unwrap();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"value");
+        db.write(batch).unwrap();
+
+        let snapshot = db.snapshot();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"new_value");
+        batch.put_cf(NewColumnFamilies::Other, b"other", b"other_value");
+        db.write(batch).unwrap();
+
+        let value = snapshot
+            .get_cf(NewColumnFamilies::Default, b"test")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = snapshot.get_cf(NewColumnFamilies::Other, b"other").unwrap();
+        assert_eq!(value, None);
+        let values =
+            snapshot.multi_get_cf(NewColumnFamilies::Default, [b"test".to_vec()].into_iter());
+        assert_eq!(
+            &*values[0].as_ref().unwrap().as_ref().unwrap()[..],
+            b"value"
+        );
+
+        let value = db.get_cf(NewColumnFamilies::Default, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"new_value");
+        // The snapshot should remain valid after the original DB handle is dropped.
+        drop(db);
+        let value = snapshot
+            .get_cf(NewColumnFamilies::Default, b"test")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value");
+    }
+
     #[derive(Debug, Clone, Copy)]
     struct JunkColumnFamily;
 
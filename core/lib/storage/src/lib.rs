@@ -1,5 +1,5 @@
 pub mod db;
 mod metrics;
 
-pub use db::{RocksDB, RocksDBOptions, StalledWritesRetries};
+pub use db::{ColumnFamilyOptions, RocksDB, RocksDBOptions, RocksDBSnapshot, StalledWritesRetries};
 pub use rocksdb;
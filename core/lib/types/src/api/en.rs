@@ -1,10 +1,13 @@
 //! API types related to the External Node specific methods.
 
+use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{Address, L1BatchNumber, MiniblockNumber, H256};
+use zksync_basic_types::{
+    web3::signing::keccak256, Address, L1BatchNumber, L2ChainId, MiniblockNumber, H256,
+};
 use zksync_contracts::BaseSystemContractsHashes;
 
-use crate::ProtocolVersionId;
+use crate::{PackedEthSignature, ProtocolVersionId};
 
 /// Representation of the L2 block, as needed for the EN synchronization.
 /// This structure has several fields that describe *L1 batch* rather than
@@ -43,7 +46,158 @@ pub struct SyncBlock {
     pub hash: Option<H256>,
     /// Version of the protocol used for this block.
     pub protocol_version: ProtocolVersionId,
+    /// Signature of [`Self::signed_digest()`] by the main node. Only present if the main node
+    /// is configured to sign L2 blocks served to external nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackedEthSignature>,
+}
+
+impl SyncBlock {
+    /// Domain separator for L2 block signatures.
+    const SIGNATURE_DOMAIN: &'static [u8] = b"zksync-era:en_syncL2Block:v1";
+
+    /// Returns the digest signed by the main node. The digest commits to all fields of the block
+    /// (other than the signature itself) and to the L2 chain ID, so that signed blocks cannot be replayed
+    /// on another chain. Transactions are committed to via hashes of their JSON serialization.
+    pub fn signed_digest(&self, chain_id: L2ChainId) -> H256 {
+        fn push_optional(preimage: &mut Vec<u8>, value: Option<&[u8]>) {
+            match value {
+                Some(value) => {
+                    preimage.push(1);
+                    preimage.extend_from_slice(value);
+                }
+                None => preimage.push(0),
+            }
+        }
+
+        let mut preimage = Self::SIGNATURE_DOMAIN.to_vec();
+        preimage.extend_from_slice(&chain_id.as_u64().to_be_bytes());
+        preimage.extend_from_slice(&self.number.0.to_be_bytes());
+        preimage.extend_from_slice(&self.l1_batch_number.0.to_be_bytes());
+        preimage.push(self.last_in_batch.into());
+        preimage.extend_from_slice(&self.timestamp.to_be_bytes());
+        preimage.extend_from_slice(&self.l1_gas_price.to_be_bytes());
+        preimage.extend_from_slice(&self.l2_fair_gas_price.to_be_bytes());
+        let fair_pubdata_price = self.fair_pubdata_price.map(u64::to_be_bytes);
+        push_optional(&mut preimage, fair_pubdata_price.as_ref().map(|x| &x[..]));
+        preimage.extend_from_slice(self.base_system_contracts_hashes.bootloader.as_bytes());
+        preimage.extend_from_slice(self.base_system_contracts_hashes.default_aa.as_bytes());
+        preimage.extend_from_slice(self.operator_address.as_bytes());
+        let virtual_blocks = self.virtual_blocks.map(u32::to_be_bytes);
+        push_optional(&mut preimage, virtual_blocks.as_ref().map(|x| &x[..]));
+        push_optional(&mut preimage, self.hash.as_ref().map(H256::as_bytes));
+        preimage.extend_from_slice(&(self.protocol_version as u16).to_be_bytes());
+
+        let transaction_hashes = self.transactions.as_ref().map(|transactions| {
+            let mut hashes = (transactions.len() as u64).to_be_bytes().to_vec();
+            for tx in transactions {
+                let serialized_tx = serde_json::to_vec(tx).expect("failed serializing transaction");
+                hashes.extend_from_slice(&keccak256(&serialized_tx));
+            }
+            hashes
+        });
+        push_optional(&mut preimage, transaction_hashes.as_deref());
+        H256(keccak256(&preimage))
+    }
+
+    /// Signs this block with the provided private key, replacing the existing signature (if any).
+    pub fn sign(&mut self, chain_id: L2ChainId, private_key: &H256) -> anyhow::Result<()> {
+        let digest = self.signed_digest(chain_id);
+        let signature = PackedEthSignature::sign_raw(private_key, &digest).context("sign_raw")?;
+        self.signature = Some(signature);
+        Ok(())
+    }
+
+    /// Checks that this block is signed by the `expected_signer`.
+    pub fn verify_signature(
+        &self,
+        chain_id: L2ChainId,
+        expected_signer: Address,
+    ) -> anyhow::Result<()> {
+        let signature = self
+            .signature
+            .as_ref()
+            .with_context(|| format!("L2 block #{} is not signed", self.number))?;
+        let signer = signature
+            .signature_recover_signer(&self.signed_digest(chain_id))
+            .context("failed recovering L2 block signer")?;
+        anyhow::ensure!(
+            signer == expected_signer,
+            "L2 block #{} is signed by unexpected signer {signer:?}; expected {expected_signer:?}",
+            self.number
+        );
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusGenesis(pub serde_json::Value);
+
+/// BFT quorum certificate (`CommitQC`) for an L2 block produced via consensus, serialized as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockCertificate(pub serde_json::Value);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_sync_block() -> SyncBlock {
+        SyncBlock {
+            number: MiniblockNumber(1),
+            l1_batch_number: L1BatchNumber(1),
+            last_in_batch: false,
+            timestamp: 1,
+            l1_gas_price: 2,
+            l2_fair_gas_price: 3,
+            fair_pubdata_price: Some(4),
+            base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            operator_address: Address::repeat_byte(1),
+            transactions: Some(vec![]),
+            virtual_blocks: Some(1),
+            hash: Some(H256::repeat_byte(2)),
+            protocol_version: ProtocolVersionId::latest(),
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn signing_sync_block() {
+        let chain_id = L2ChainId::default();
+        let private_key = H256::repeat_byte(0x42);
+        let signer = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+
+        let mut block = mock_sync_block();
+        block.verify_signature(chain_id, signer).unwrap_err();
+        block.sign(chain_id, &private_key).unwrap();
+        block.verify_signature(chain_id, signer).unwrap();
+
+        // The signature must survive a (de)serialization roundtrip.
+        let serialized = serde_json::to_value(&block).unwrap();
+        let block: SyncBlock = serde_json::from_value(serialized).unwrap();
+        block.verify_signature(chain_id, signer).unwrap();
+
+        block
+            .verify_signature(chain_id, Address::repeat_byte(1))
+            .unwrap_err();
+        block
+            .verify_signature(L2ChainId::from(123), signer)
+            .unwrap_err();
+        let mut tampered_block = block.clone();
+        tampered_block.l1_gas_price += 1;
+        tampered_block
+            .verify_signature(chain_id, signer)
+            .unwrap_err();
+        let mut tampered_block = block;
+        tampered_block.transactions = None;
+        tampered_block
+            .verify_signature(chain_id, signer)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn unsigned_sync_block_serialization() {
+        let block = mock_sync_block();
+        let serialized = serde_json::to_value(&block).unwrap();
+        assert!(serialized.get("signature").is_none(), "{serialized:#?}");
+    }
+}
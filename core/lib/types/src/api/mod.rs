@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
@@ -11,10 +13,12 @@ pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
+    ethabi::ethereum_types::BloomInput,
+    fee_model::BatchFeeInput,
     protocol_version::L1VerifierConfig,
     vm_trace::{Call, CallType},
     web3::types::{AccessList, Index, H2048},
-    Address, MiniblockNumber, ProtocolVersionId,
+    Address, L2ChainId, MiniblockNumber, Nonce, PriorityOpId, ProtocolVersionId,
 };
 
 pub mod en;
@@ -150,6 +154,35 @@ impl From<BlockIdVariant> for BlockId {
     }
 }
 
+/// zkSync-specific extension object allowing to refer to the last miniblock in an L1 batch.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct L1BatchNumberObject {
+    #[serde(rename = "zks_l1BatchNumber")]
+    pub l1_batch_number: L1BatchNumber,
+}
+
+/// Extension of [`BlockIdVariant`] used for `block` parameters in historical state queries
+/// (`eth_getStorageAt`, `eth_getCode`, `eth_getBalance`, `eth_call`). Besides standard EIP-1898 IDs,
+/// it allows to anchor the query at the end of an L1 batch via [`L1BatchNumberObject`].
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HistoricalBlockIdVariant {
+    Block(BlockIdVariant),
+    L1BatchNumberObject(L1BatchNumberObject),
+}
+
+impl From<BlockIdVariant> for HistoricalBlockIdVariant {
+    fn from(value: BlockIdVariant) -> Self {
+        Self::Block(value)
+    }
+}
+
+impl From<L1BatchNumber> for HistoricalBlockIdVariant {
+    fn from(l1_batch_number: L1BatchNumber) -> Self {
+        Self::L1BatchNumberObject(L1BatchNumberObject { l1_batch_number })
+    }
+}
+
 /// Transaction variant
 ///
 /// Utility structure. Some Web3 API methods have to return a block with a list of either full
@@ -178,7 +211,7 @@ impl From<H256> for TransactionId {
 }
 
 /// A struct with the proof for the L2->L1 log in a specific block.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct L2ToL1LogProof {
     /// The merkle path for the leaf.
@@ -189,6 +222,41 @@ pub struct L2ToL1LogProof {
     pub root: H256,
 }
 
+/// Proof for an interop (L2→L2) message sent via the L1 messenger on the source chain. The destination chain
+/// can verify the message against the L2-to-L1 logs root of the source L1 batch, which is stored on L1
+/// by the source chain's diamond proxy (discoverable via the bridgehub) once the batch is executed.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InteropMessageProof {
+    /// ID of the chain the message was sent from.
+    pub source_chain_id: L2ChainId,
+    /// L1 batch that includes the message.
+    pub l1_batch_number: L1BatchNumber,
+    /// Address of the message sender on the source chain.
+    pub sender: Address,
+    /// Keccak-256 hash of the message.
+    pub message_hash: H256,
+    /// Proof of the message inclusion into the L2-to-L1 logs tree of the L1 batch.
+    pub proof: L2ToL1LogProof,
+    /// Information about anchoring of the L1 batch on L1. `None` if the L1 batch is not executed yet;
+    /// the message cannot be relayed until then.
+    pub l1_anchor: Option<InteropL1Anchor>,
+}
+
+/// Information about anchoring an L1 batch on L1 used in [`InteropMessageProof`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InteropL1Anchor {
+    /// Address of the bridgehub contract on L1, if the chain is connected to the shared bridge.
+    pub bridgehub: Option<Address>,
+    /// Hash of the L1 transaction that committed the L1 batch.
+    pub commit_tx_hash: Option<H256>,
+    /// Hash of the L1 transaction that executed the L1 batch.
+    pub execute_tx_hash: H256,
+    /// Time the execute transaction was confirmed.
+    pub executed_at: DateTime<Utc>,
+}
+
 /// A struct with the two default bridge contracts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -577,6 +645,86 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Priority operation that did not provide enough funds to cover L2 fees at the time it was received
+/// from L1. Such an operation may fail during execution (while still consuming the provided fee).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnderfundedPriorityOp {
+    pub priority_op_id: PriorityOpId,
+    pub tx_hash: H256,
+    /// Max fee per gas provided by the operation.
+    pub max_fee_per_gas: U256,
+    /// Base fee per gas required by the fee model when the operation was received.
+    pub required_max_fee_per_gas: U256,
+    /// Gas per pubdata limit provided by the operation.
+    pub gas_per_pubdata_limit: U256,
+    /// Gas per pubdata required by the fee model when the operation was received.
+    pub required_gas_per_pubdata_limit: U256,
+}
+
+/// Summary of the mempool state, i.e. of transactions that are accepted by the node but not yet included
+/// into a miniblock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolStatus {
+    /// Total number of pending transactions, including L1 transactions.
+    pub pending_tx_count: u64,
+    /// Number of pending L1 (priority) transactions.
+    pub pending_l1_tx_count: u64,
+    /// Number of distinct initiators of pending L2 transactions.
+    pub sender_count: u64,
+    /// Time when the oldest pending transaction was received.
+    pub oldest_tx_received_at: Option<DateTime<Utc>>,
+    /// Age of the oldest pending transaction in milliseconds.
+    pub oldest_tx_age_ms: Option<u64>,
+}
+
+/// Pending L2 transactions of a single initiator account in the mempool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolSender {
+    pub address: Address,
+    pub pending_tx_count: u64,
+    pub min_nonce: Nonce,
+    pub max_nonce: Nonce,
+    /// Time when the oldest pending transaction of the account was received.
+    pub oldest_tx_received_at: DateTime<Utc>,
+}
+
+/// Brief information about a transaction received by the node. Used to filter `newPendingTransactions`
+/// subscriptions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionInfo {
+    pub hash: H256,
+    pub from: Address,
+    /// Recipient of the transaction; `None` for transactions without a recipient.
+    pub to: Option<Address>,
+    pub value: U256,
+}
+
+/// Execution metrics of transactions calling a certain contract, aggregated over a range of miniblocks.
+/// If the state keeper samples recorded transactions, only sampled transactions are accounted for.
+/// Returned by the `zks_getContractExecutionStats` method, which is only served if enabled by the operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractExecutionStats {
+    /// Address of the contract called by transactions (i.e., the transaction recipient).
+    pub contract_address: Address,
+    pub tx_count: u64,
+    /// Total wall time spent on executing transactions in microseconds.
+    pub total_execution_time_us: u64,
+    /// Maximum wall time spent on executing a single transaction in microseconds.
+    pub max_execution_time_us: u64,
+    pub total_gas_used: u64,
+    pub total_pubdata_bytes: u64,
+    pub total_storage_writes: u64,
+    pub total_computational_gas_used: u64,
+    /// Total estimated number of circuits (of all types) used by transactions. Allows estimating the share
+    /// of batch capacity taken by the contract.
+    pub total_circuits_used: f64,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: MiniblockNumber,
@@ -585,12 +733,70 @@ pub struct GetLogsFilter {
     pub topics: Vec<(u32, Vec<H256>)>,
 }
 
+impl GetLogsFilter {
+    /// Checks whether the filter restricts log addresses or topics, i.e., whether logs blooms can be used
+    /// to skip miniblocks when looking up logs.
+    pub fn is_restrictive(&self) -> bool {
+        !self.addresses.is_empty() || self.topics.iter().any(|(_, topics)| !topics.is_empty())
+    }
+
+    /// Checks whether a miniblock (or a range of miniblocks) with the specified logs bloom may contain
+    /// logs matching this filter. False positives are possible, false negatives are not.
+    pub fn may_match_bloom(&self, bloom: &H2048) -> bool {
+        let matches_addresses = self.addresses.is_empty()
+            || self
+                .addresses
+                .iter()
+                .any(|address| bloom.contains_input(BloomInput::Raw(address.as_bytes())));
+        matches_addresses
+            && self.topics.iter().all(|(_, topics)| {
+                topics.is_empty()
+                    || topics
+                        .iter()
+                        .any(|topic| bloom.contains_input(BloomInput::Raw(topic.as_bytes())))
+            })
+    }
+}
+
 /// Result of debugging block
 /// For some reasons geth returns result as {result: DebugCall}
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct ResultDebugCall {
-    pub result: DebugCall,
+pub struct ResultDebugCall<T = DebugCall> {
+    pub result: T,
+}
+
+/// Trace returned by the `debug` namespace methods; its format depends on the requested tracer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    Call(DebugCall),
+    Prestate(PrestateTrace),
+}
+
+/// Account state reported by `prestateTracer`. Fields not touched by the transaction are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub storage: HashMap<H256, H256>,
+}
+
+/// Output of `prestateTracer`, either in the default or in the diff mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+    Diff {
+        pre: HashMap<Address, PrestateAccount>,
+        post: HashMap<Address, PrestateAccount>,
+    },
+    Prestate(HashMap<Address, PrestateAccount>),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -657,12 +863,19 @@ pub struct ProtocolVersion {
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
     CallTracer,
+    PrestateTracer,
 }
 
+/// Options for the supported tracers; options not applicable to the requested tracer are ignored.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CallTracerConfig {
+    /// Used by `callTracer`.
+    #[serde(default)]
     pub only_top_call: bool,
+    /// Used by `prestateTracer`.
+    #[serde(default)]
+    pub diff_mode: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -711,6 +924,15 @@ pub struct BlockDetails {
     pub protocol_version: Option<ProtocolVersionId>,
 }
 
+/// Fee input used by the state keeper to execute a miniblock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiniblockFeeInput {
+    pub number: MiniblockNumber,
+    pub timestamp: u64,
+    pub fee_input: BatchFeeInput,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct L1BatchDetails {
@@ -82,6 +82,9 @@ pub struct MiniblockHeader {
     /// Note, that it is an `u64`, i.e. while the computational limit for the bootloader is an `u32` a much larger
     /// amount of gas can be spent on pubdata.
     pub gas_limit: u64,
+    /// Bloom filter for the events emitted in the miniblock. Zero for miniblocks sealed before
+    /// logs blooms were persisted.
+    pub logs_bloom: H2048,
 }
 
 /// Data needed to execute a miniblock in the VM.
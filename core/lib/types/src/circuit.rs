@@ -91,3 +91,129 @@ impl Add for CircuitStatistic {
         }
     }
 }
+
+/// Capacity of base layer circuits, i.e., the number of cycles of each type that fit into a single circuit.
+///
+/// The sequencer uses the capacity to estimate the number of circuits required to prove an L1 batch
+/// (see [`CircuitStatistic`]). If the sequencer assumes a larger capacity than the prover, it may produce
+/// batches that cannot be proven.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitCapacity {
+    pub main_vm: u32,
+    pub ram_permutation: u32,
+    pub storage_application: u32,
+    pub storage_sorter: u32,
+    pub code_decommitter: u32,
+    pub code_decommitter_sorter: u32,
+    pub log_demuxer: u32,
+    pub events_sorter: u32,
+    pub keccak256: u32,
+    pub ecrecover: u32,
+    pub sha256: u32,
+}
+
+impl CircuitCapacity {
+    /// Computes the number of circuits of each type required to process the specified number of cycles.
+    pub fn circuit_statistic(&self, cycles: &CircuitCycleStatistic) -> CircuitStatistic {
+        CircuitStatistic {
+            main_vm: cycles.main_vm_cycles as f32 / self.main_vm as f32,
+            ram_permutation: cycles.ram_permutation_cycles as f32 / self.ram_permutation as f32,
+            storage_application: cycles.storage_application_cycles as f32
+                / self.storage_application as f32,
+            storage_sorter: cycles.storage_sorter_cycles as f32 / self.storage_sorter as f32,
+            code_decommitter: cycles.code_decommitter_cycles as f32 / self.code_decommitter as f32,
+            code_decommitter_sorter: cycles.code_decommitter_sorter_cycles as f32
+                / self.code_decommitter_sorter as f32,
+            log_demuxer: cycles.log_demuxer_cycles as f32 / self.log_demuxer as f32,
+            events_sorter: cycles.events_sorter_cycles as f32 / self.events_sorter as f32,
+            keccak256: cycles.keccak256_cycles as f32 / self.keccak256 as f32,
+            ecrecover: cycles.ecrecover_cycles as f32 / self.ecrecover as f32,
+            sha256: cycles.sha256_cycles as f32 / self.sha256 as f32,
+        }
+    }
+
+    /// Checks that this capacity does not exceed `limit` for any circuit type, i.e. that circuit counts
+    /// estimated using this capacity never underestimate the ones estimated using `limit`.
+    pub fn ensure_within(&self, limit: &Self) -> anyhow::Result<()> {
+        let exceeding: Vec<_> = self
+            .entries()
+            .into_iter()
+            .zip(limit.entries())
+            .filter(|((_, capacity), (_, limit))| capacity > limit)
+            .map(|((name, capacity), (_, limit))| format!("{name}: {capacity} > {limit}"))
+            .collect();
+        anyhow::ensure!(
+            exceeding.is_empty(),
+            "circuit capacity exceeds the limit for circuit types: {}",
+            exceeding.join(", ")
+        );
+        Ok(())
+    }
+
+    fn entries(&self) -> [(&'static str, u32); 11] {
+        [
+            ("main_vm", self.main_vm),
+            ("ram_permutation", self.ram_permutation),
+            ("storage_application", self.storage_application),
+            ("storage_sorter", self.storage_sorter),
+            ("code_decommitter", self.code_decommitter),
+            ("code_decommitter_sorter", self.code_decommitter_sorter),
+            ("log_demuxer", self.log_demuxer),
+            ("events_sorter", self.events_sorter),
+            ("keccak256", self.keccak256),
+            ("ecrecover", self.ecrecover),
+            ("sha256", self.sha256),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPACITY: CircuitCapacity = CircuitCapacity {
+        main_vm: 100,
+        ram_permutation: 200,
+        storage_application: 10,
+        storage_sorter: 20,
+        code_decommitter: 30,
+        code_decommitter_sorter: 40,
+        log_demuxer: 50,
+        events_sorter: 60,
+        keccak256: 5,
+        ecrecover: 1,
+        sha256: 6,
+    };
+
+    #[test]
+    fn computing_circuit_statistic() {
+        let cycles = CircuitCycleStatistic {
+            main_vm_cycles: 150,
+            storage_application_cycles: 10,
+            ecrecover_cycles: 3,
+            ..CircuitCycleStatistic::new()
+        };
+        let statistic = CAPACITY.circuit_statistic(&cycles);
+        assert_eq!(statistic.main_vm, 1.5);
+        assert_eq!(statistic.storage_application, 1.0);
+        assert_eq!(statistic.ecrecover, 3.0);
+        assert_eq!(statistic.total(), 6);
+    }
+
+    #[test]
+    fn checking_circuit_capacity_limit() {
+        CAPACITY.ensure_within(&CAPACITY).unwrap();
+        let larger_limit = CircuitCapacity {
+            main_vm: 200,
+            ..CAPACITY
+        };
+        CAPACITY.ensure_within(&larger_limit).unwrap();
+
+        let err = larger_limit
+            .ensure_within(&CAPACITY)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("main_vm: 200 > 100"), "{err}");
+        assert!(!err.contains("sha256"), "{err}");
+    }
+}
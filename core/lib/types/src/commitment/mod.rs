@@ -229,6 +229,62 @@ impl SerializeCommitment for SystemL2ToL1Log {
     }
 }
 
+/// Merkle proof of inclusion of an L2-to-L1 log into the L2-to-L1 logs tree of an L1 batch. The root of this tree
+/// is a part of the L1 batch commitment and is stored on L1 once the batch is executed, which allows relaying
+/// messages sent via the L1 messenger to L1 or (via the bridgehub) to other chains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L2ToL1LogInclusionProof {
+    /// 0-based index of the log in the tree, i.e., among all L2-to-L1 logs of the L1 batch.
+    pub index: usize,
+    /// Hashes of sibling nodes from the leaf level up to the root.
+    pub merkle_path: Vec<H256>,
+    /// Root hash of the tree.
+    pub root: H256,
+}
+
+impl L2ToL1LogInclusionProof {
+    /// Creates a proof for the log with the specified `index` among all L2-to-L1 `logs` of an L1 batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for `logs`.
+    pub fn new(logs: &[L2ToL1Log], index: usize, protocol_version: ProtocolVersionId) -> Self {
+        let leaves = logs.iter().map(L2ToL1Log::to_bytes);
+        let (root, merkle_path) =
+            MiniMerkleTree::new(leaves, Some(l2_to_l1_logs_tree_size(protocol_version)))
+                .merkle_root_and_path(index);
+        Self {
+            index,
+            merkle_path,
+            root,
+        }
+    }
+
+    /// Computes the tree root hash from the provided `log` and the Merkle path in this proof.
+    pub fn root_for_log(&self, log: &L2ToL1Log) -> H256 {
+        let mut hash = H256(keccak256(&log.to_bytes()));
+        let mut index = self.index;
+        for sibling in &self.merkle_path {
+            let mut preimage = [0_u8; 64];
+            let (left, right) = if index % 2 == 0 {
+                (&hash, sibling)
+            } else {
+                (sibling, &hash)
+            };
+            preimage[..32].copy_from_slice(left.as_bytes());
+            preimage[32..].copy_from_slice(right.as_bytes());
+            hash = H256(keccak256(&preimage));
+            index /= 2;
+        }
+        hash
+    }
+
+    /// Checks whether this proof proves inclusion of the specified `log`.
+    pub fn verify(&self, log: &L2ToL1Log) -> bool {
+        self.root_for_log(log) == self.root
+    }
+}
+
 impl SerializeCommitment for InitialStorageWrite {
     const SERIALIZED_SIZE: usize = 64;
 
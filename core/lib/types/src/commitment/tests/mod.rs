@@ -45,3 +45,38 @@ fn post_boojum_1_4_1() {
 fn post_boojum_1_4_2() {
     run_test("post_boojum_1_4_2_test");
 }
+
+#[test]
+fn l2_to_l1_log_inclusion_proofs() {
+    let contents = read_to_string("src/commitment/tests/post_boojum_1_4_2_test.json").unwrap();
+    let commitment_test: CommitmentTest = serde_json::from_str(&contents).unwrap();
+    let common_input = commitment_test.input.common().clone();
+    let mut logs: Vec<_> = common_input
+        .l2_to_l1_logs
+        .iter()
+        .map(|log| log.0.clone())
+        .collect();
+    let commitment = L1BatchCommitment::new(commitment_test.input);
+
+    let proof = L2ToL1LogInclusionProof::new(&logs, 0, common_input.protocol_version);
+    assert_eq!(proof.root, commitment.l2_l1_logs_merkle_root());
+    assert!(proof.verify(&logs[0]));
+
+    logs.extend((1..5_u16).map(|i| L2ToL1Log {
+        tx_number_in_block: i,
+        value: H256::repeat_byte(i as u8),
+        ..logs[0].clone()
+    }));
+    let expected_root = MiniMerkleTree::new(
+        logs.iter().map(L2ToL1Log::to_bytes),
+        Some(l2_to_l1_logs_tree_size(common_input.protocol_version)),
+    )
+    .merkle_root();
+    for (i, log) in logs.iter().enumerate() {
+        let proof = L2ToL1LogInclusionProof::new(&logs, i, common_input.protocol_version);
+        assert_eq!(proof.root, expected_root);
+        assert!(proof.verify(log));
+        let other_log = &logs[(i + 1) % logs.len()];
+        assert!(!proof.verify(other_log));
+    }
+}
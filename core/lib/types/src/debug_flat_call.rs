@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::web3::types::{Bytes, U256};
+use zksync_basic_types::web3::types::{Bytes, H256, U256};
 
 use crate::{
     api::{DebugCall, DebugCallType, ResultDebugCall},
-    Address,
+    Address, MiniblockNumber,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -17,6 +17,18 @@ pub struct DebugCallFlat {
     pub revert_reason: Option<String>,
 }
 
+/// Flattened call together with the transaction it belongs to, as returned by `trace_filter`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedDebugCallFlat {
+    #[serde(flatten)]
+    pub call: DebugCallFlat,
+    pub block_number: MiniblockNumber,
+    pub block_hash: H256,
+    pub transaction_hash: H256,
+    pub transaction_position: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Action {
@@ -10,11 +10,11 @@ use zksync_utils::{
 };
 
 use crate::{
-    ethabi,
+    ethabi::{self, ethereum_types::BloomInput},
     l2_to_l1_log::L2ToL1Log,
     tokens::{TokenInfo, TokenMetadata},
     zk_evm_types::{LogQuery, Timestamp},
-    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H256, KNOWN_CODES_STORAGE_ADDRESS,
+    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H2048, H256, KNOWN_CODES_STORAGE_ADDRESS,
     L1_MESSENGER_ADDRESS, U256,
 };
 
@@ -41,6 +41,19 @@ impl VmEvent {
     }
 }
 
+/// Builds a logs bloom for the specified events in the same way as Ethereum: the bloom accrues
+/// the address and all indexed topics of each event.
+pub fn build_logs_bloom<'a>(events: impl IntoIterator<Item = &'a VmEvent>) -> H2048 {
+    let mut bloom = H2048::zero();
+    for event in events {
+        bloom.accrue(BloomInput::Raw(event.address.as_bytes()));
+        for topic in &event.indexed_topics {
+            bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+        }
+    }
+    bloom
+}
+
 pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "ContractDeployed",
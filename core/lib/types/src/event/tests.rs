@@ -1,6 +1,7 @@
 use zksync_system_constants::{BOOTLOADER_ADDRESS, L2_ETH_TOKEN_ADDRESS};
 
 use super::*;
+use crate::{api::GetLogsFilter, MiniblockNumber};
 
 fn create_l2_to_l1_log_sent_value(
     tx_number: U256,
@@ -169,3 +170,40 @@ fn test_convert_vm_events_to_log_queries() {
         assert_eq!(actual_list, expected_list);
     }
 }
+
+#[test]
+fn logs_bloom_basics() {
+    let event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: Address::repeat_byte(1),
+        indexed_topics: vec![H256::repeat_byte(2), H256::repeat_byte(3)],
+        value: vec![],
+    };
+    let bloom = build_logs_bloom([&event]);
+    assert_ne!(bloom, H2048::zero());
+
+    let mut filter = GetLogsFilter {
+        from_block: MiniblockNumber(0),
+        to_block: MiniblockNumber(1),
+        addresses: vec![],
+        topics: vec![],
+    };
+    assert!(!filter.is_restrictive());
+    assert!(filter.may_match_bloom(&bloom));
+    assert!(filter.may_match_bloom(&H2048::zero()));
+
+    filter.addresses = vec![Address::repeat_byte(23), event.address];
+    assert!(filter.is_restrictive());
+    assert!(filter.may_match_bloom(&bloom));
+    assert!(!filter.may_match_bloom(&H2048::zero()));
+
+    filter.topics = vec![(2, vec![H256::repeat_byte(3)])];
+    assert!(filter.may_match_bloom(&bloom));
+    filter.topics = vec![(1, vec![H256::repeat_byte(2)]), (2, vec![])];
+    assert!(filter.may_match_bloom(&bloom));
+    filter.topics = vec![
+        (1, vec![H256::repeat_byte(2)]),
+        (2, vec![H256::repeat_byte(23)]),
+    ];
+    assert!(!filter.may_match_bloom(&bloom));
+}
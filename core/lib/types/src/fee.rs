@@ -26,6 +26,8 @@ pub struct TransactionExecutionMetrics {
     pub total_updated_values_size: usize,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    #[serde(default)]
+    pub storage_slots_reset: usize,
 }
 
 impl Default for TransactionExecutionMetrics {
@@ -49,6 +51,7 @@ impl Default for TransactionExecutionMetrics {
             total_updated_values_size: 0,
             pubdata_published: 0,
             circuit_statistic: Default::default(),
+            storage_slots_reset: 0,
         }
     }
 }
@@ -79,6 +82,39 @@ impl Fee {
     }
 }
 
+/// Fee estimate returned by `zks_estimateFee`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    #[serde(flatten)]
+    pub fee: Fee,
+    /// Breakdown of the estimated gas limit. May be absent if the server doesn't support it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<FeeBreakdown>,
+}
+
+impl From<FeeEstimate> for Fee {
+    fn from(estimate: FeeEstimate) -> Self {
+        estimate.fee
+    }
+}
+
+/// Breakdown of the estimated gas limit into its components. The sum of `compute_gas`, `pubdata_gas`
+/// and `overhead_gas` is equal to the estimated gas limit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    /// Gas spent on the transaction execution, excluding publishing pubdata. Includes the safety margin
+    /// added to the estimate by the server.
+    pub compute_gas: U256,
+    /// Gas spent on publishing pubdata (storage writes, L2-to-L1 messages, bytecodes etc.).
+    pub pubdata_gas: U256,
+    /// Share of the batch overhead charged to the transaction.
+    pub overhead_gas: U256,
+    /// Fair L2 gas price used to charge for computation.
+    pub fair_l2_gas_price: U256,
+    /// Fair price of publishing a single byte of pubdata.
+    pub fair_pubdata_price: U256,
+}
+
 /// Returns how many slots would ABI-encoding of the transaction with such parameters take
 pub fn encoding_len(
     data_len: u64,
@@ -101,3 +137,39 @@ pub fn encoding_len(
 
     BASE_LEN + dynamic_len as usize
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_estimate_serialization() {
+        let fee = Fee {
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 250_000_000.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 800.into(),
+        };
+        let estimate = FeeEstimate {
+            fee: fee.clone(),
+            breakdown: Some(FeeBreakdown {
+                compute_gas: 600_000.into(),
+                pubdata_gas: 300_000.into(),
+                overhead_gas: 100_000.into(),
+                fair_l2_gas_price: 250_000_000.into(),
+                fair_pubdata_price: 200_000_000_000u64.into(),
+            }),
+        };
+        let json = serde_json::to_value(&estimate).unwrap();
+        assert_eq!(json["gas_limit"], "0xf4240");
+        assert_eq!(json["breakdown"]["compute_gas"], "0x927c0");
+        let restored: FeeEstimate = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, estimate);
+
+        // Responses without a breakdown (e.g., from older servers) must be parsed as well.
+        let json = serde_json::to_value(&fee).unwrap();
+        let restored: FeeEstimate = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.fee, fee);
+        assert_eq!(restored.breakdown, None);
+    }
+}
@@ -9,7 +9,8 @@ use crate::ProtocolVersionId;
 /// versions of Era prior to 1.4.1 integration.
 /// - `PubdataIndependent`: L1 gas price and pubdata price are not necessarily dependent on one another. This options is more suitable for the
 /// versions of Era after the 1.4.1 integration. It is expected that if a VM supports `PubdataIndependent` version, then it should also support `L1Pegged` version, but converting it into `PubdataIndependentBatchFeeModelInput` in-place.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum BatchFeeInput {
     L1Pegged(L1PeggedBatchFeeModelInput),
     PubdataIndependent(PubdataIndependentBatchFeeModelInput),
@@ -137,7 +138,8 @@ impl BatchFeeInput {
 }
 
 /// Pubdata is only published via calldata and so its price is pegged to the L1 gas price.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct L1PeggedBatchFeeModelInput {
     /// Fair L2 gas price to provide
     pub fair_l2_gas_price: u64,
@@ -146,7 +148,8 @@ pub struct L1PeggedBatchFeeModelInput {
 }
 
 /// Pubdata price may be independent from L1 gas price.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PubdataIndependentBatchFeeModelInput {
     /// Fair L2 gas price to provide
     pub fair_l2_gas_price: u64,
@@ -163,7 +166,7 @@ pub struct PubdataIndependentBatchFeeModelInput {
 /// - `V2`, the second model that was used in zkSync Era. There the pubdata price might be independent from the L1 gas price. Also,
 /// The fair L2 gas price is expected to both the proving/computation price for the operator and the costs that come from
 /// processing the batch on L1.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FeeModelConfig {
     V1(FeeModelConfigV1),
     V2(FeeModelConfigV2),
@@ -171,7 +174,7 @@ pub enum FeeModelConfig {
 
 /// Config params for the first version of the fee model. Here, the pubdata price is pegged to the L1 gas price and
 /// neither fair L2 gas price nor the pubdata price include the overhead for closing the batch
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct FeeModelConfigV1 {
     /// The minimal acceptable L2 gas price, i.e. the price that should include the cost of computation/proving as well
     /// as potentially premium for congestion.
@@ -179,7 +182,7 @@ pub struct FeeModelConfigV1 {
     pub minimal_l2_gas_price: u64,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct FeeModelConfigV2 {
     /// The minimal acceptable L2 gas price, i.e. the price that should include the cost of computation/proving as well
     /// as potentially premium for congestion.
@@ -228,20 +231,20 @@ impl FeeModelConfig {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct FeeParamsV1 {
     pub config: FeeModelConfigV1,
     pub l1_gas_price: u64,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct FeeParamsV2 {
     pub config: FeeModelConfigV2,
     pub l1_gas_price: u64,
     pub l1_pubdata_price: u64,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FeeParams {
     V1(FeeParamsV1),
     V2(FeeParamsV2),
@@ -258,3 +261,38 @@ impl FeeParams {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_fee_input_serialization() {
+        let input = BatchFeeInput::pubdata_independent(100, 200, 300);
+        let json = serde_json::to_value(input).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "pubdataIndependent": {
+                    "fairL2GasPrice": 200,
+                    "fairPubdataPrice": 300,
+                    "l1GasPrice": 100,
+                },
+            })
+        );
+        let restored: BatchFeeInput = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, input);
+
+        let input = BatchFeeInput::l1_pegged(100, 200);
+        let json = serde_json::to_value(input).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "l1Pegged": {
+                    "fairL2GasPrice": 200,
+                    "l1GasPrice": 100,
+                },
+            })
+        );
+    }
+}
@@ -3,11 +3,14 @@ use std::{convert::TryFrom, ops};
 use anyhow::Context;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{AccountTreeId, L1BatchNumber, MiniblockNumber, H256};
+use zksync_basic_types::{
+    web3::signing::keccak256, AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
+    H256,
+};
 use zksync_protobuf::{required, ProtoFmt};
 use zksync_utils::u256_to_h256;
 
-use crate::{Bytes, ProtocolVersionId, StorageKey, StorageValue, U256};
+use crate::{Bytes, PackedEthSignature, ProtocolVersionId, StorageKey, StorageValue, U256};
 
 /// Information about all snapshots persisted by the node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +41,10 @@ pub struct SnapshotMetadata {
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
     /// the corresponding path is `None`.
     pub storage_logs_filepaths: Vec<Option<String>>,
+    /// Content hashes of the storage log chunks as returned by [`SnapshotStorageLogsChunk::content_hash()`].
+    /// Ordered by the chunk ID. The hash is `None` if the chunk is not produced yet, or if it was produced
+    /// before hashes were recorded.
+    pub storage_logs_hashes: Vec<Option<H256>>,
 }
 
 impl SnapshotMetadata {
@@ -60,6 +67,124 @@ pub struct SnapshotHeader {
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
+    /// Root hash of the Merkle tree after the snapshot L1 batch. May be absent in headers returned
+    /// by older main nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_batch_root_hash: Option<H256>,
+    /// Signature of the header by the main node operator. Only present if the main node
+    /// is configured to sign snapshot headers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackedEthSignature>,
+}
+
+impl SnapshotHeader {
+    /// Domain separator for snapshot header signatures.
+    const SIGNATURE_DOMAIN: &'static [u8] = b"zksync-era:snapshotHeader:v2";
+
+    /// Returns the digest signed by the main node. The digest commits to all fields of the header
+    /// (other than the signature itself), including storage log chunk content hashes and the L1 batch root hash,
+    /// and to the L2 chain ID, so that signed headers cannot be replayed on another chain.
+    pub fn signed_digest(&self, chain_id: L2ChainId) -> H256 {
+        fn push_str(preimage: &mut Vec<u8>, value: &str) {
+            preimage.extend_from_slice(&(value.len() as u64).to_be_bytes());
+            preimage.extend_from_slice(value.as_bytes());
+        }
+
+        fn push_hash(preimage: &mut Vec<u8>, value: Option<H256>) {
+            if let Some(value) = value {
+                preimage.push(1);
+                preimage.extend_from_slice(value.as_bytes());
+            } else {
+                preimage.push(0);
+            }
+        }
+
+        let mut preimage = Self::SIGNATURE_DOMAIN.to_vec();
+        preimage.extend_from_slice(&chain_id.as_u64().to_be_bytes());
+        preimage.extend_from_slice(&self.version.to_be_bytes());
+        preimage.extend_from_slice(&self.l1_batch_number.0.to_be_bytes());
+        preimage.extend_from_slice(&self.miniblock_number.0.to_be_bytes());
+        preimage.extend_from_slice(&(self.storage_logs_chunks.len() as u64).to_be_bytes());
+        for chunk in &self.storage_logs_chunks {
+            preimage.extend_from_slice(&chunk.chunk_id.to_be_bytes());
+            push_str(&mut preimage, &chunk.filepath);
+            push_hash(&mut preimage, chunk.content_hash);
+        }
+        push_str(&mut preimage, &self.factory_deps_filepath);
+        push_hash(&mut preimage, self.l1_batch_root_hash);
+        H256(keccak256(&preimage))
+    }
+
+    /// Signs this header with the provided private key, replacing the existing signature (if any).
+    /// Returns an error if the header doesn't contain the L1 batch root hash or any of the chunk content hashes.
+    pub fn sign(&mut self, chain_id: L2ChainId, private_key: &H256) -> anyhow::Result<()> {
+        self.ensure_hashes_present()?;
+        let digest = self.signed_digest(chain_id);
+        let signature = PackedEthSignature::sign_raw(private_key, &digest).context("sign_raw")?;
+        self.signature = Some(signature);
+        Ok(())
+    }
+
+    /// Checks that this header contains the L1 batch root hash and all chunk content hashes, and that it is signed
+    /// by one of `trusted_signers`. Returns the recovered signer.
+    pub fn verify_signature(
+        &self,
+        chain_id: L2ChainId,
+        trusted_signers: &[Address],
+    ) -> anyhow::Result<Address> {
+        let signature = self.signature.as_ref().with_context(|| {
+            format!(
+                "snapshot header for L1 batch #{} is not signed",
+                self.l1_batch_number
+            )
+        })?;
+        let signer = signature
+            .signature_recover_signer(&self.signed_digest(chain_id))
+            .context("failed recovering snapshot header signer")?;
+        anyhow::ensure!(
+            trusted_signers.contains(&signer),
+            "snapshot header for L1 batch #{} is signed by untrusted signer {signer:?}; trusted signers: {trusted_signers:?}",
+            self.l1_batch_number
+        );
+        self.ensure_hashes_present()?;
+        Ok(signer)
+    }
+
+    fn ensure_hashes_present(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.l1_batch_root_hash.is_some(),
+            "snapshot header for L1 batch #{} doesn't contain the L1 batch root hash",
+            self.l1_batch_number
+        );
+        for chunk in &self.storage_logs_chunks {
+            anyhow::ensure!(
+                chunk.content_hash.is_some(),
+                "snapshot header for L1 batch #{} doesn't contain content hash for storage logs chunk #{}",
+                self.l1_batch_number,
+                chunk.chunk_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that storage log chunks are ordered by chunk ID and that their hashed key ranges
+    /// (as returned by [`SnapshotStorageLogsStorageKey::hashed_keys_range()`]) are disjoint
+    /// and jointly cover the entire hashed key space. Since ranges are derived from chunk IDs,
+    /// this is equivalent to chunk IDs forming the `0..chunk_count` sequence.
+    pub fn validate_storage_logs_chunks(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.storage_logs_chunks.is_empty(),
+            "snapshot doesn't contain storage log chunks"
+        );
+        for (expected_chunk_id, chunk) in self.storage_logs_chunks.iter().enumerate() {
+            anyhow::ensure!(
+                chunk.chunk_id == expected_chunk_id as u64,
+                "storage log chunks do not cover the entire hashed key space: expected chunk #{expected_chunk_id}, \
+                 got {chunk:?}"
+            );
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -68,8 +193,18 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub chunk_id: u64,
     // can be either be a file available under HTTP(s) or local filesystem path
     pub filepath: String,
+    /// Content hash of the chunk as returned by [`SnapshotStorageLogsChunk::content_hash()`]. May be absent
+    /// in headers returned by older main nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<H256>,
 }
 
+/// Object store key for a storage logs chunk.
+///
+/// Chunk IDs are not assigned in the order chunks are generated; instead, a chunk with ID `i` among `n` chunks
+/// contains all storage logs with hashed keys in [`uniform_hashed_keys_chunk(i, n)`](uniform_hashed_keys_chunk).
+/// Thus, both chunk IDs and the derived object store keys depend only on the L1 batch number and the chunk count,
+/// so a snapshot created several times (e.g., after a restart of the snapshot creator) has the same layout.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotStorageLogsStorageKey {
@@ -77,11 +212,39 @@ pub struct SnapshotStorageLogsStorageKey {
     pub chunk_id: u64,
 }
 
+impl SnapshotStorageLogsStorageKey {
+    /// Returns the range of hashed keys covered by this chunk provided that the snapshot has `chunk_count` chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`uniform_hashed_keys_chunk()`].
+    pub fn hashed_keys_range(&self, chunk_count: u64) -> ops::RangeInclusive<H256> {
+        uniform_hashed_keys_chunk(self.chunk_id, chunk_count)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SnapshotStorageLogsChunk {
     pub storage_logs: Vec<SnapshotStorageLog>,
 }
 
+impl SnapshotStorageLogsChunk {
+    /// Computes the content hash of this chunk. Unlike a hash of the serialized chunk, the content hash
+    /// doesn't depend on the serialization format or compression.
+    pub fn content_hash(&self) -> H256 {
+        let mut preimage = Vec::with_capacity(8 + self.storage_logs.len() * 128);
+        preimage.extend_from_slice(&(self.storage_logs.len() as u64).to_be_bytes());
+        for log in &self.storage_logs {
+            preimage.extend_from_slice(log.key.address().as_bytes());
+            preimage.extend_from_slice(log.key.key().as_bytes());
+            preimage.extend_from_slice(log.value.as_bytes());
+            preimage.extend_from_slice(&log.l1_batch_number_of_initial_write.0.to_be_bytes());
+            preimage.extend_from_slice(&log.enumeration_index.to_be_bytes());
+        }
+        H256(keccak256(&preimage))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SnapshotStorageLog {
     pub key: StorageKey,
@@ -300,4 +463,118 @@ mod tests {
             assert!(max_chunk_size - min_chunk_size < U256::from(chunks_count));
         }
     }
+
+    fn mock_header(chunk_ids: &[u64]) -> SnapshotHeader {
+        SnapshotHeader {
+            version: SnapshotVersion::Version0.into(),
+            l1_batch_number: L1BatchNumber(1),
+            miniblock_number: MiniblockNumber(1),
+            storage_logs_chunks: chunk_ids
+                .iter()
+                .map(|&chunk_id| SnapshotStorageLogsChunkMetadata {
+                    chunk_id,
+                    filepath: format!("file{chunk_id}"),
+                    content_hash: Some(H256::repeat_byte(chunk_id as u8)),
+                })
+                .collect(),
+            factory_deps_filepath: "factory_deps".to_owned(),
+            l1_batch_root_hash: Some(H256::repeat_byte(0xff)),
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn signing_snapshot_header() {
+        let chain_id = L2ChainId::default();
+        let private_key = H256::repeat_byte(0x42);
+        let signer = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let other_signer = Address::repeat_byte(1);
+
+        let mut header = mock_header(&[0, 1]);
+        header.verify_signature(chain_id, &[signer]).unwrap_err();
+        header.sign(chain_id, &private_key).unwrap();
+        let recovered_signer = header
+            .verify_signature(chain_id, &[other_signer, signer])
+            .unwrap();
+        assert_eq!(recovered_signer, signer);
+
+        // The signature must survive a (de)serialization roundtrip.
+        let serialized = serde_json::to_value(&header).unwrap();
+        let header: SnapshotHeader = serde_json::from_value(serialized).unwrap();
+        header.verify_signature(chain_id, &[signer]).unwrap();
+
+        header
+            .verify_signature(chain_id, &[other_signer])
+            .unwrap_err();
+        header
+            .verify_signature(L2ChainId::from(123), &[signer])
+            .unwrap_err();
+        let mut tampered_header = header.clone();
+        tampered_header.storage_logs_chunks[1].filepath = "evil_file".to_owned();
+        tampered_header
+            .verify_signature(chain_id, &[signer])
+            .unwrap_err();
+        let mut tampered_header = header.clone();
+        tampered_header.storage_logs_chunks[0].content_hash = Some(H256::zero());
+        tampered_header
+            .verify_signature(chain_id, &[signer])
+            .unwrap_err();
+        let mut tampered_header = header;
+        tampered_header.l1_batch_root_hash = Some(H256::zero());
+        tampered_header
+            .verify_signature(chain_id, &[signer])
+            .unwrap_err();
+    }
+
+    #[test]
+    fn signing_snapshot_header_requires_hashes() {
+        let chain_id = L2ChainId::default();
+        let private_key = H256::repeat_byte(0x42);
+
+        let mut header = mock_header(&[0, 1]);
+        header.l1_batch_root_hash = None;
+        header.sign(chain_id, &private_key).unwrap_err();
+
+        let mut header = mock_header(&[0, 1]);
+        header.storage_logs_chunks[1].content_hash = None;
+        header.sign(chain_id, &private_key).unwrap_err();
+    }
+
+    #[test]
+    fn storage_logs_chunk_content_hash() {
+        let log = SnapshotStorageLog {
+            key: StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero()),
+            value: H256::repeat_byte(2),
+            l1_batch_number_of_initial_write: L1BatchNumber(1),
+            enumeration_index: 1,
+        };
+        let chunk = SnapshotStorageLogsChunk {
+            storage_logs: vec![log.clone()],
+        };
+        let hash = chunk.content_hash();
+        assert_eq!(hash, chunk.clone().content_hash());
+
+        let mut tampered_chunk = chunk.clone();
+        tampered_chunk.storage_logs[0].value = H256::zero();
+        assert_ne!(tampered_chunk.content_hash(), hash);
+        let mut tampered_chunk = chunk.clone();
+        tampered_chunk.storage_logs[0].enumeration_index = 2;
+        assert_ne!(tampered_chunk.content_hash(), hash);
+        let mut tampered_chunk = chunk;
+        tampered_chunk.storage_logs.push(log);
+        assert_ne!(tampered_chunk.content_hash(), hash);
+    }
+
+    #[test]
+    fn validating_storage_logs_chunks() {
+        mock_header(&[0, 1, 2])
+            .validate_storage_logs_chunks()
+            .unwrap();
+
+        for invalid_chunk_ids in [&[][..], &[1, 2], &[0, 2], &[0, 1, 1], &[1, 0]] {
+            mock_header(invalid_chunk_ids)
+                .validate_storage_logs_chunks()
+                .unwrap_err();
+        }
+    }
 }
@@ -4,9 +4,9 @@
 //! it makes more sense to define the contents of each transaction chain-agnostic, and extent this data
 //! with metadata (such as fees and/or signatures) for L1 and L2 separately.
 
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
-use zksync_basic_types::{Address, H256};
+use zksync_basic_types::{Address, MiniblockNumber, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 use self::tx_execution_info::TxExecutionStatus;
@@ -28,6 +28,8 @@ pub struct TransactionExecutionResult {
     pub compressed_bytecodes: Vec<CompressedBytecodeInfo>,
     pub call_traces: Vec<Call>,
     pub revert_reason: Option<String>,
+    /// Wall time spent by the VM on executing the transaction.
+    pub execution_time: Duration,
 }
 
 impl TransactionExecutionResult {
@@ -54,3 +56,12 @@ pub struct IncludedTxLocation {
     pub tx_index_in_miniblock: u32,
     pub tx_initiator_address: Address,
 }
+
+/// Location of a transaction included into a sealed miniblock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SealedTxLocation {
+    pub miniblock_number: MiniblockNumber,
+    pub miniblock_hash: H256,
+    pub tx_hash: H256,
+    pub tx_index_in_miniblock: u32,
+}
@@ -71,6 +71,9 @@ pub struct ExecutionMetrics {
     pub computational_gas_used: u32,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    /// Number of storage slots whose values were reset to the values before the execution.
+    /// Writes to such slots are net-zero, so they don't need to be published as pubdata.
+    pub storage_slots_reset: usize,
 }
 
 impl ExecutionMetrics {
@@ -89,6 +92,7 @@ impl ExecutionMetrics {
             computational_gas_used: tx_metrics.computational_gas_used,
             pubdata_published: tx_metrics.pubdata_published,
             circuit_statistic: tx_metrics.circuit_statistic,
+            storage_slots_reset: tx_metrics.storage_slots_reset,
         }
     }
 
@@ -123,6 +127,7 @@ impl Add for ExecutionMetrics {
             computational_gas_used: self.computational_gas_used + other.computational_gas_used,
             pubdata_published: self.pubdata_published + other.pubdata_published,
             circuit_statistic: self.circuit_statistic + other.circuit_statistic,
+            storage_slots_reset: self.storage_slots_reset + other.storage_slots_reset,
         }
     }
 }
@@ -0,0 +1,127 @@
+//! Classification of errors shared by long-running components.
+
+use std::{error, fmt};
+
+/// Kind of an error determining how a long-running task should react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Transient error (e.g., a network error or a timeout). The failed operation can be retried.
+    Retryable,
+    /// Non-transient error not falling into any other category, e.g. a violated invariant.
+    Fatal,
+    /// Stored or received data is malformed or inconsistent, e.g. an object that cannot be deserialized.
+    DataCorruption,
+    /// Error caused by the node misconfiguration, e.g. invalid credentials or an invalid connection URL.
+    Configuration,
+}
+
+impl ErrorKind {
+    /// Checks whether an operation failed with an error of this kind can be retried.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::Retryable)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Retryable => "retryable",
+            Self::Fatal => "fatal",
+            Self::DataCorruption => "data corruption",
+            Self::Configuration => "configuration",
+        })
+    }
+}
+
+/// Error that can be classified into one of [`ErrorKind`]s.
+pub trait ClassifyError {
+    /// Returns the kind of this error.
+    fn error_kind(&self) -> ErrorKind;
+}
+
+/// Classification of an [`anyhow::Error`] is taken from the first [`ClassifiedError`] in its chain.
+/// Errors not containing a [`ClassifiedError`] are considered fatal.
+impl ClassifyError for anyhow::Error {
+    fn error_kind(&self) -> ErrorKind {
+        self.chain()
+            .find_map(|err| err.downcast_ref::<ClassifiedError>())
+            .map_or(ErrorKind::Fatal, |err| err.kind)
+    }
+}
+
+/// Error wrapper retaining the kind of the wrapped error after it's converted into an [`anyhow::Error`]
+/// (possibly with additional context).
+///
+/// Display and source of the wrapper are the same as for the wrapped error.
+#[derive(Debug)]
+pub struct ClassifiedError {
+    kind: ErrorKind,
+    inner: anyhow::Error,
+}
+
+impl ClassifiedError {
+    /// Wraps an error retaining its classification.
+    pub fn new<E>(err: E) -> Self
+    where
+        E: ClassifyError + error::Error + Send + Sync + 'static,
+    {
+        Self {
+            kind: err.error_kind(),
+            inner: err.into(),
+        }
+    }
+
+    /// Wraps an error assigning the specified kind to it.
+    pub fn with_kind(kind: ErrorKind, err: impl Into<anyhow::Error>) -> Self {
+        Self {
+            kind,
+            inner: err.into(),
+        }
+    }
+}
+
+impl fmt::Display for ClassifiedError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, formatter)
+    }
+}
+
+impl error::Error for ClassifiedError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.inner.source()
+    }
+}
+
+impl ClassifyError for ClassifiedError {
+    fn error_kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context as _;
+
+    use super::*;
+
+    #[test]
+    fn classifying_anyhow_errors() {
+        let err = anyhow::anyhow!("oops");
+        assert_eq!(err.error_kind(), ErrorKind::Fatal);
+
+        let err = ClassifiedError::with_kind(ErrorKind::Retryable, anyhow::anyhow!("timeout"));
+        assert_eq!(err.to_string(), "timeout");
+        let err = anyhow::Error::from(err);
+        assert_eq!(err.error_kind(), ErrorKind::Retryable);
+
+        let err = Err::<(), _>(err)
+            .context("first context")
+            .context("second context")
+            .unwrap_err();
+        assert_eq!(err.error_kind(), ErrorKind::Retryable);
+
+        let err = ClassifiedError::with_kind(ErrorKind::DataCorruption, anyhow::anyhow!("oops"));
+        let err = anyhow::Error::from(err).context("context");
+        assert_eq!(err.error_kind(), ErrorKind::DataCorruption);
+    }
+}
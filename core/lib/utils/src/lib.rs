@@ -2,9 +2,11 @@
 
 pub mod bytecode;
 mod convert;
+pub mod error;
 pub mod http_with_retries;
 pub mod misc;
 pub mod panic_extractor;
+pub mod retry;
 mod serde_wrappers;
 pub mod time;
 pub mod wait_for_tasks;
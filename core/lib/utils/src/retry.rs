@@ -0,0 +1,213 @@
+//! Generic retry and alerting logic shared by long-running tasks.
+
+use std::{fmt, future::Future, time::Duration};
+
+use crate::error::{ClassifyError, ErrorKind};
+
+/// Policy of retrying operations failed with [retryable](ErrorKind::Retryable) errors.
+/// Errors of other kinds are never retried.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of consecutive retries. If `None`, operations are retried indefinitely.
+    pub max_retries: Option<usize>,
+    /// Backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// Multiplier applied to the backoff after each consecutive retry.
+    pub backoff_multiplier: f32,
+    /// Upper bound for the backoff.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: Some(5),
+            initial_backoff: Duration::from_secs(2),
+            backoff_multiplier: 2.0,
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy retrying operations indefinitely with the fixed backoff.
+    pub fn indefinite(backoff: Duration) -> Self {
+        Self {
+            max_retries: None,
+            initial_backoff: backoff,
+            backoff_multiplier: 1.0,
+            max_backoff: backoff,
+        }
+    }
+
+    /// Creates a stateful [`Retrier`] following this policy for the specified component.
+    /// The component name is used in logs.
+    pub fn retrier(&self, component: &'static str) -> Retrier {
+        Retrier {
+            component,
+            policy: self.clone(),
+            retry_count: 0,
+            backoff: self.initial_backoff,
+        }
+    }
+
+    /// Runs the provided operation until it succeeds, fails with a non-retryable error, or retries are exhausted.
+    pub async fn run<T, E, Fut>(
+        &self,
+        component: &'static str,
+        mut operation: impl FnMut() -> Fut,
+    ) -> Result<T, E>
+    where
+        E: ClassifyError + fmt::Debug,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retrier = self.retrier(component);
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    let backoff = retrier.handle_error(err)?;
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+    }
+}
+
+/// Stateful retry logic following a [`RetryPolicy`]. Useful for tasks that cannot be expressed
+/// as a closure passed to [`RetryPolicy::run()`] (e.g., ones that mutate their state or need to be
+/// interrupted by a stop signal while waiting for a retry).
+///
+/// All handled errors are logged, so that they can be alerted on. Non-retryable errors and exhausted retries
+/// are logged with the error level (i.e., are reported to Sentry if it's configured).
+#[derive(Debug)]
+pub struct Retrier {
+    component: &'static str,
+    policy: RetryPolicy,
+    retry_count: usize,
+    backoff: Duration,
+}
+
+impl Retrier {
+    /// Handles an error returned by the operation. If the operation should be retried, returns
+    /// the backoff to wait before the next attempt; otherwise, returns the error back.
+    pub fn handle_error<E: ClassifyError + fmt::Debug>(&mut self, err: E) -> Result<Duration, E> {
+        let component = self.component;
+        let kind = err.error_kind();
+
+        if !kind.is_retryable() {
+            tracing::error!("Component `{component}` failed with {kind} error: {err:?}");
+            return Err(err);
+        }
+        if let Some(max_retries) = self.policy.max_retries {
+            if self.retry_count >= max_retries {
+                tracing::error!(
+                    "Component `{component}` has run out of retries ({max_retries}); last error: {err:?}"
+                );
+                return Err(err);
+            }
+        }
+
+        self.retry_count += 1;
+        let backoff = self.backoff;
+        self.backoff = backoff
+            .mul_f32(self.policy.backoff_multiplier)
+            .min(self.policy.max_backoff);
+        tracing::warn!(
+            "Component `{component}` failed with retryable error; retry #{} in {backoff:?}: {err:?}",
+            self.retry_count
+        );
+        Ok(backoff)
+    }
+
+    /// Resets the retry count and backoff. Should be called after the operation succeeds.
+    pub fn reset(&mut self) {
+        self.retry_count = 0;
+        self.backoff = self.policy.initial_backoff;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::error::ClassifiedError;
+
+    fn test_policy(max_retries: usize) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: Some(max_retries),
+            initial_backoff: Duration::from_millis(1),
+            backoff_multiplier: 2.0,
+            max_backoff: Duration::from_millis(3),
+        }
+    }
+
+    fn error(kind: ErrorKind) -> anyhow::Error {
+        ClassifiedError::with_kind(kind, anyhow::anyhow!("{kind} error")).into()
+    }
+
+    #[test]
+    fn retrier_backoff_and_reset() {
+        let mut retrier = test_policy(3).retrier("test");
+        let backoffs: Vec<_> = (0..3)
+            .map(|_| retrier.handle_error(error(ErrorKind::Retryable)).unwrap())
+            .collect();
+        assert_eq!(backoffs[0], Duration::from_millis(1));
+        assert!(backoffs[1] > backoffs[0], "{backoffs:?}");
+        assert_eq!(
+            backoffs[2],
+            Duration::from_millis(3),
+            "backoff must be capped"
+        );
+        retrier
+            .handle_error(error(ErrorKind::Retryable))
+            .unwrap_err();
+
+        retrier.reset();
+        let backoff = retrier.handle_error(error(ErrorKind::Retryable)).unwrap();
+        assert_eq!(backoff, Duration::from_millis(1));
+    }
+
+    #[test]
+    fn retrier_does_not_retry_non_retryable_errors() {
+        let mut retrier = RetryPolicy::indefinite(Duration::from_millis(1)).retrier("test");
+        for kind in [
+            ErrorKind::Fatal,
+            ErrorKind::DataCorruption,
+            ErrorKind::Configuration,
+        ] {
+            retrier.handle_error(error(kind)).unwrap_err();
+        }
+        retrier
+            .handle_error(anyhow::anyhow!("unclassified"))
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn running_operation_with_retries() {
+        let call_count = AtomicUsize::new(0);
+        let value = test_policy(3)
+            .run("test", || async {
+                if call_count.fetch_add(1, Ordering::Relaxed) < 2 {
+                    Err(error(ErrorKind::Retryable))
+                } else {
+                    Ok(42)
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(call_count.load(Ordering::Relaxed), 3);
+
+        call_count.store(0, Ordering::Relaxed);
+        test_policy(3)
+            .run("test", || async {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(error(ErrorKind::Configuration))
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+    }
+}
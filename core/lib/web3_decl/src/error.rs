@@ -11,10 +11,11 @@ use std::{
     task::{Context, Poll},
 };
 
-use jsonrpsee::core::ClientError;
+use jsonrpsee::{core::ClientError, types::error::ErrorCode};
 use pin_project_lite::pin_project;
 use thiserror::Error;
 use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
+use zksync_utils::error::{ClassifyError, ErrorKind};
 
 /// Server-side representation of the RPC error.
 #[derive(Debug, Error)]
@@ -33,17 +34,25 @@ pub enum Web3Error {
     SerializationError(#[from] SerializationTransactionError),
     #[error("More than four topics in filter")]
     TooManyTopics,
+    #[error("Too many addresses requested; the limit is {0}")]
+    TooManyAddresses(usize),
     #[error("Filter not found")]
     FilterNotFound,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
+    #[error("Reward percentiles must be non-decreasing values between 0 and 100")]
+    InvalidRewardPercentiles,
+    #[error("Too many traces requested; the limit is {0}")]
+    TooManyTraces(usize),
     #[error("Not implemented")]
     NotImplemented,
 
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("Merkle tree lags {0} L1 batches behind; repeat request later")]
+    TreeLagging(u32),
     #[error("Internal error")]
     InternalError(#[from] anyhow::Error),
 }
@@ -85,13 +94,28 @@ impl EnrichedClientError {
 
     /// Whether the error should be considered transient.
     pub fn is_transient(&self) -> bool {
+        self.error_kind().is_retryable()
+    }
+
+    /// Whether the error signals that the called method is not supported by the server.
+    pub fn is_method_not_found(&self) -> bool {
         matches!(
             self.as_ref(),
-            ClientError::Transport(_) | ClientError::RequestTimeout
+            ClientError::Call(err) if err.code() == ErrorCode::MethodNotFound.code()
         )
     }
 }
 
+impl ClassifyError for EnrichedClientError {
+    fn error_kind(&self) -> ErrorKind {
+        match self.as_ref() {
+            ClientError::Transport(_) | ClientError::RequestTimeout => ErrorKind::Retryable,
+            ClientError::ParseError(_) => ErrorKind::DataCorruption,
+            _ => ErrorKind::Fatal,
+        }
+    }
+}
+
 impl AsRef<ClientError> for EnrichedClientError {
     fn as_ref(&self) -> &ClientError {
         &self.inner_error
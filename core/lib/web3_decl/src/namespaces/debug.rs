@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, DebugTrace, ResultDebugCall, TracerConfig},
     debug_flat_call::DebugCallFlat,
     transaction_request::CallRequest,
 };
@@ -25,7 +25,7 @@ pub trait DebugNamespace {
         &self,
         block: BlockNumber,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Vec<ResultDebugCall>>;
+    ) -> RpcResult<Vec<ResultDebugCall<DebugTrace>>>;
     #[method(name = "traceBlockByNumber.callFlatTracer")]
     async fn trace_block_by_number_flat(
         &self,
@@ -37,7 +37,7 @@ pub trait DebugNamespace {
         &self,
         hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Vec<ResultDebugCall>>;
+    ) -> RpcResult<Vec<ResultDebugCall<DebugTrace>>>;
     #[method(name = "traceCall")]
     async fn trace_call(
         &self,
@@ -50,5 +50,5 @@ pub trait DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Option<DebugCall>>;
+    ) -> RpcResult<Option<DebugTrace>>;
 }
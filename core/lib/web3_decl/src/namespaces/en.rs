@@ -25,6 +25,14 @@ pub trait EnNamespace {
     #[method(name = "consensusGenesis")]
     async fn consensus_genesis(&self) -> RpcResult<Option<en::ConsensusGenesis>>;
 
+    /// Returns the consensus certificate for the specified L2 block, or `None` if the block
+    /// doesn't have a certificate (yet).
+    #[method(name = "blockCertificate")]
+    async fn block_certificate(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<en::BlockCertificate>>;
+
     /// Lists all tokens created at or before the specified `block_number`.
     ///
     /// This method is used by EN after snapshot recovery in order to recover token records.
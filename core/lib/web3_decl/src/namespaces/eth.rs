@@ -3,7 +3,10 @@ use jsonrpsee::{
     proc_macros::rpc,
 };
 use zksync_types::{
-    api::{BlockId, BlockIdVariant, BlockNumber, Transaction, TransactionVariant},
+    api::{
+        BlockId, BlockIdVariant, BlockNumber, HistoricalBlockIdVariant, Transaction,
+        TransactionVariant,
+    },
     transaction_request::CallRequest,
     Address, H256,
 };
@@ -33,7 +36,11 @@ pub trait EthNamespace {
     async fn chain_id(&self) -> RpcResult<U64>;
 
     #[method(name = "call")]
-    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes>;
+    async fn call(
+        &self,
+        req: CallRequest,
+        block: Option<HistoricalBlockIdVariant>,
+    ) -> RpcResult<Bytes>;
 
     #[method(name = "estimateGas")]
     async fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> RpcResult<U256>;
@@ -63,8 +70,11 @@ pub trait EthNamespace {
     async fn get_filter_changes(&self, filter_index: U256) -> RpcResult<FilterChanges>;
 
     #[method(name = "getBalance")]
-    async fn get_balance(&self, address: Address, block: Option<BlockIdVariant>)
-        -> RpcResult<U256>;
+    async fn get_balance(
+        &self,
+        address: Address,
+        block: Option<HistoricalBlockIdVariant>,
+    ) -> RpcResult<U256>;
 
     #[method(name = "getBlockByNumber")]
     async fn get_block_by_number(
@@ -99,14 +109,18 @@ pub trait EthNamespace {
     ) -> RpcResult<Option<U256>>;
 
     #[method(name = "getCode")]
-    async fn get_code(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<Bytes>;
+    async fn get_code(
+        &self,
+        address: Address,
+        block: Option<HistoricalBlockIdVariant>,
+    ) -> RpcResult<Bytes>;
 
     #[method(name = "getStorageAt")]
     async fn get_storage_at(
         &self,
         address: Address,
         idx: U256,
-        block: Option<BlockIdVariant>,
+        block: Option<HistoricalBlockIdVariant>,
     ) -> RpcResult<H256>;
 
     #[method(name = "getTransactionCount")]
@@ -172,7 +186,7 @@ pub trait EthNamespace {
         &self,
         block_count: U64,
         newest_block: BlockNumber,
-        reward_percentiles: Vec<f32>,
+        reward_percentiles: Option<Vec<f32>>,
     ) -> RpcResult<FeeHistory>;
 }
 
@@ -0,0 +1,21 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::debug_flat_call::LocalizedDebugCallFlat;
+
+use crate::types::TraceFilter;
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "trace")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "trace")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "trace")
+)]
+pub trait TraceNamespace {
+    #[method(name = "filter")]
+    async fn filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedDebugCallFlat>>;
+}
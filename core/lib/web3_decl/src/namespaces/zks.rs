@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        BlockDetails, BlockIdVariant, BridgeAddresses, ContractExecutionStats, InteropMessageProof,
+        L1BatchDetails, L2ToL1LogProof, MempoolSender, MempoolStatus, MiniblockFeeInput, Proof,
+        ProtocolVersion, TransactionDetails, UnderfundedPriorityOp,
     },
-    fee::Fee,
+    fee::FeeEstimate,
     fee_model::FeeParams,
     transaction_request::CallRequest,
     Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
@@ -28,7 +32,7 @@ use crate::types::Token;
 )]
 pub trait ZksNamespace {
     #[method(name = "estimateFee")]
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate>;
 
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
@@ -71,6 +75,15 @@ pub trait ZksNamespace {
         index: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>>;
 
+    /// Returns a proof for an interop message sent by the specified transaction via the L1 messenger.
+    /// `index` is the 0-based index of the message among messages sent by the transaction.
+    #[method(name = "getInteropMessageProof")]
+    async fn get_interop_message_proof(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> RpcResult<Option<InteropMessageProof>>;
+
     #[method(name = "L1BatchNumber")]
     async fn get_l1_batch_number(&self) -> RpcResult<U64>;
 
@@ -118,4 +131,74 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<Proof>>;
+
+    /// Returns nonces for the specified `addresses` in the same order. Like with `eth_getTransactionCount`,
+    /// the `pending` block (the default) accounts for transactions in the mempool.
+    #[method(name = "getNonces")]
+    async fn get_nonces(
+        &self,
+        addresses: Vec<Address>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<U256>>;
+
+    /// Returns fee inputs used to execute miniblocks in the specified inclusive range. The number
+    /// of returned miniblocks is capped by the server.
+    #[method(name = "getFeeHistory")]
+    async fn get_fee_history(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> RpcResult<Vec<MiniblockFeeInput>>;
+
+    /// Returns not yet executed priority operations that did not provide enough funds to cover L2 fees
+    /// at the time they were received from L1, ordered by priority op ID. Such operations are likely
+    /// to fail during execution. The number of returned operations is capped by the server.
+    #[method(name = "getUnderfundedPriorityOps")]
+    async fn get_underfunded_priority_ops(&self) -> RpcResult<Vec<UnderfundedPriorityOp>>;
+
+    /// Returns execution metrics of transactions in the specified inclusive range of miniblocks aggregated
+    /// by the called contract, with the most time-consuming contracts first. Metrics are only available
+    /// if the state keeper is configured to record them, and may be sampled. The number of returned
+    /// contracts and the range of miniblocks are capped by the server. This method is intended for operators;
+    /// it is only served by the main node if explicitly enabled in the API config.
+    #[method(name = "getContractExecutionStats")]
+    async fn get_contract_execution_stats(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> RpcResult<Vec<ContractExecutionStats>>;
+
+    /// Returns the summary of transactions pending in the mempool. This method is intended for operators;
+    /// it is only served by the main node if explicitly enabled in the API config.
+    #[method(name = "mempoolStatus")]
+    async fn mempool_status(&self) -> RpcResult<MempoolStatus>;
+
+    /// Returns initiators of L2 transactions pending in the mempool, with the accounts having the most pending
+    /// transactions first. The number of returned accounts is capped by the server. This method is intended
+    /// for operators; it is only served by the main node if explicitly enabled in the API config.
+    #[method(name = "mempoolContent")]
+    async fn mempool_content(&self) -> RpcResult<Vec<MempoolSender>>;
+}
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "zks")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "zks")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "zks")
+)]
+pub trait ZksPubSub {
+    /// Subscribes to fee params used by the node. The current params are sent immediately
+    /// after subscribing; after that, params are only sent when they change.
+    #[subscription(
+        name = "subscribeFeeParams" => "feeParams",
+        unsubscribe = "unsubscribeFeeParams",
+        item = FeeParams
+    )]
+    async fn subscribe_fee_params(&self) -> SubscriptionResult;
 }
@@ -14,13 +14,15 @@ use core::{
 use rlp::Rlp;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 pub use zksync_types::{
-    api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
+    api::{
+        Block, BlockNumber, Log, PendingTransactionInfo, TransactionReceipt, TransactionRequest,
+    },
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
     web3::{
         ethabi,
         types::{
-            Address, BlockHeader, Bytes, CallRequest, FeeHistory, Index, SyncState, TraceFilter,
-            Transaction, Work, H160, H256, H64, U256, U64,
+            Address, BlockHeader, Bytes, CallRequest, FeeHistory, Index, SyncState, Transaction,
+            Work, H160, H256, H64, U256, U64,
         },
     },
 };
@@ -188,6 +190,30 @@ pub struct Filter {
     pub block_hash: Option<H256>,
 }
 
+/// Filter for `trace_filter` requests.
+#[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    /// From Block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumber>,
+    /// To Block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumber>,
+    /// Senders of the calls; if empty, calls from any address match the filter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<Vec<Address>>,
+    /// Recipients of the calls; if empty, calls to any address match the filter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_address: Option<Vec<Address>>,
+    /// Number of matching calls to skip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<usize>,
+    /// Maximum number of calls to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
 /// Filter Builder
 #[derive(Default, Clone)]
 pub struct FilterBuilder {
@@ -249,15 +275,53 @@ impl FilterBuilder {
     }
 }
 
+/// Filter for `eth_subscribe`. `address` and `topics` apply to `logs` subscriptions; `from`, `to`
+/// and `min_value` apply to `newPendingTransactions` subscriptions.
 #[derive(Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PubSubFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub address: Option<ValueOrArray<H160>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<ValueOrArray<H160>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<ValueOrArray<H160>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<U256>,
 }
 
 impl PubSubFilter {
+    /// Checks whether this filter contains criteria for logs.
+    pub fn has_log_criteria(&self) -> bool {
+        self.address.is_some() || self.topics.is_some()
+    }
+
+    /// Checks whether this filter contains criteria for pending transactions.
+    pub fn has_transaction_criteria(&self) -> bool {
+        self.from.is_some() || self.to.is_some() || self.min_value.is_some()
+    }
+
+    pub fn matches_transaction(&self, tx: &PendingTransactionInfo) -> bool {
+        if let Some(senders) = &self.from {
+            if !senders.0.contains(&tx.from) {
+                return false;
+            }
+        }
+        if let Some(recipients) = &self.to {
+            if !tx.to.map_or(false, |to| recipients.0.contains(&to)) {
+                return false;
+            }
+        }
+        if let Some(min_value) = self.min_value {
+            if tx.value < min_value {
+                return false;
+            }
+        }
+        true
+    }
+
     pub fn matches(&self, log: &Log) -> bool {
         if let Some(addresses) = &self.address {
             if !addresses.0.contains(&log.address) {
@@ -350,7 +414,10 @@ pub enum PubSubResult {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::api::{BlockId, BlockIdVariant};
+    use zksync_types::{
+        api::{BlockId, BlockIdVariant, HistoricalBlockIdVariant},
+        L1BatchNumber,
+    };
 
     use super::*;
 
@@ -417,6 +484,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn historical_block_id_variant_serializing() {
+        let test_vector = [
+            (
+                r#""latest""#,
+                BlockIdVariant::BlockNumber(BlockNumber::Latest).into(),
+            ),
+            (
+                r#"{"blockNumber": "0x10"}"#,
+                BlockIdVariant::BlockNumberObject(zksync_types::api::BlockNumberObject {
+                    block_number: BlockNumber::Number(16.into()),
+                })
+                .into(),
+            ),
+            (
+                r#"{"zks_l1BatchNumber": 16}"#,
+                HistoricalBlockIdVariant::from(L1BatchNumber(16)),
+            ),
+        ];
+
+        for (serialized_repr, expected) in test_vector {
+            let deserialized: HistoricalBlockIdVariant =
+                serde_json::from_str(serialized_repr).unwrap();
+            assert_eq!(deserialized, expected);
+        }
+    }
+
     #[test]
     fn serializing_value_or_array() {
         let value = ValueOrArray::from(Address::repeat_byte(0x1f));
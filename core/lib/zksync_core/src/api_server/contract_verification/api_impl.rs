@@ -86,7 +86,7 @@ impl RestApi {
         let method_latency = METRICS.call[&"contract_verification_request_status"].start();
         let status = self_
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .unwrap()
             .contract_verification_dal()
@@ -106,7 +106,7 @@ impl RestApi {
         let method_latency = METRICS.call[&"contract_verification_zksolc_versions"].start();
         let versions = self_
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .unwrap()
             .contract_verification_dal()
@@ -123,7 +123,7 @@ impl RestApi {
         let method_latency = METRICS.call[&"contract_verification_solc_versions"].start();
         let versions = self_
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .unwrap()
             .contract_verification_dal()
@@ -140,7 +140,7 @@ impl RestApi {
         let method_latency = METRICS.call[&"contract_verification_zkvyper_versions"].start();
         let versions = self_
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .unwrap()
             .contract_verification_dal()
@@ -157,7 +157,7 @@ impl RestApi {
         let method_latency = METRICS.call[&"contract_verification_vyper_versions"].start();
         let versions = self_
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .unwrap()
             .contract_verification_dal()
@@ -178,7 +178,7 @@ impl RestApi {
 
         let info = self_
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .unwrap()
             .contract_verification_dal()
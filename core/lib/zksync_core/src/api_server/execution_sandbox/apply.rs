@@ -305,7 +305,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
 
     let rt_handle = vm_permit.rt_handle();
     let connection = rt_handle
-        .block_on(connection_pool.connection_tagged("api"))
+        .block_on(connection_pool.read_connection_tagged("api"))
         .context("failed acquiring DB connection")?;
     let connection_acquire_time = stage_started_at.elapsed();
     // We don't want to emit too many logs.
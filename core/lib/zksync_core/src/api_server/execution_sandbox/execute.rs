@@ -2,8 +2,8 @@
 
 use anyhow::Context as _;
 use multivm::{
-    interface::{TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
-    tracers::StorageInvocations,
+    interface::{ExecutionResult, Halt, TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    tracers::{ExecutionDeadline, StorageInvocations},
     vm_latest::constants::ETH_CALL_GAS_LIMIT,
     MultiVMTracer,
 };
@@ -17,6 +17,7 @@ use zksync_types::{
 #[cfg(test)]
 use super::testonly::MockTransactionExecutor;
 use super::{apply, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs, VmPermit};
+use crate::api_server::web3::metrics::{DeadlineLayer, API_METRICS};
 
 #[derive(Debug)]
 pub(crate) struct TxExecutionArgs {
@@ -74,6 +75,16 @@ impl TxExecutionArgs {
             enforced_base_fee: Some(base_fee),
         }
     }
+
+    fn for_replay(base_fee: u64) -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            enforced_base_fee: Some(base_fee),
+            missed_storage_invocation_limit: usize::MAX,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -155,6 +166,14 @@ impl TransactionExecutor {
         .await
         .context("transaction execution panicked")??;
 
+        if let ExecutionResult::Halt {
+            reason: Halt::TracerCustom(reason),
+        } = &execution_result.result
+        {
+            if reason == ExecutionDeadline::HALT_REASON {
+                API_METRICS.deadline_exceeded[&DeadlineLayer::Sandbox].inc();
+            }
+        }
         let metrics =
             vm_metrics::collect_tx_execution_metrics(total_factory_deps, &execution_result);
         Ok(TransactionExecutionOutput {
@@ -201,4 +220,63 @@ impl TransactionExecutor {
             .await?;
         Ok(output.vm)
     }
+
+    /// Re-executes transactions from a sealed miniblock, each with its own set of tracers. `block_args` must point to
+    /// the miniblock preceding the one the transactions are included in, and `txs` must be a prefix of the miniblock
+    /// transactions, so that each transaction observes the same state as during its original execution.
+    ///
+    /// The block context (e.g., the block number and timestamp) is taken from `block_args`, so it may differ
+    /// from the original one; this only matters for transactions depending on the block context.
+    pub async fn replay_txs_in_sandbox(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool<Core>,
+        txs: Vec<(Transaction, Vec<ApiTracer>)>,
+        block_args: BlockArgs,
+        base_fee: u64,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return txs
+                .iter()
+                .map(|(tx, _)| Ok(mock_executor.execute_tx(tx, &block_args)?.vm))
+                .collect();
+        }
+
+        let Some((first_tx, _)) = txs.first() else {
+            return Ok(vec![]);
+        };
+        let first_tx = first_tx.clone();
+        let execution_args = TxExecutionArgs::for_replay(base_fee);
+        tokio::task::spawn_blocking(move || {
+            let _span = span!(Level::DEBUG, "replay_in_sandbox").entered();
+            apply::apply_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                false,
+                &execution_args,
+                &connection_pool,
+                first_tx,
+                block_args,
+                |vm, _| {
+                    let results = txs.into_iter().map(|(tx, custom_tracers)| {
+                        let custom_tracers: Vec<_> = custom_tracers
+                            .into_iter()
+                            .map(|tracer| tracer.into_boxed())
+                            .collect();
+                        let (_, result) = vm.inspect_transaction_with_bytecode_compression(
+                            custom_tracers.into(),
+                            tx,
+                            true,
+                        );
+                        result
+                    });
+                    results.collect()
+                },
+            )
+        })
+        .await
+        .context("transaction replay panicked")?
+    }
 }
@@ -1,6 +1,10 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
-use multivm::{tracers::CallTracer, vm_latest::HistoryMode, MultiVMTracer, MultiVmTracerPointer};
+use multivm::{
+    tracers::{prestate_tracer, CallTracer, ExecutionDeadline, PrestateTracer},
+    vm_latest::HistoryMode,
+    MultiVMTracer, MultiVmTracerPointer,
+};
 use once_cell::sync::OnceCell;
 use zksync_state::WriteStorage;
 use zksync_types::vm_trace::Call;
@@ -9,6 +13,13 @@ use zksync_types::vm_trace::Call;
 #[derive(Debug)]
 pub(crate) enum ApiTracer {
     CallTracer(Arc<OnceCell<Vec<Call>>>),
+    /// Collects the state of touched accounts before and after execution.
+    Prestate {
+        diff_mode: bool,
+        result: Arc<OnceCell<(prestate_tracer::State, prestate_tracer::State)>>,
+    },
+    /// Stops execution once the deadline (usually, one of the API request being served) has passed.
+    Deadline(Instant),
 }
 
 impl ApiTracer {
@@ -20,6 +31,10 @@ impl ApiTracer {
     ) -> MultiVmTracerPointer<S, H> {
         match self {
             ApiTracer::CallTracer(tracer) => CallTracer::new(tracer.clone()).into_tracer_pointer(),
+            ApiTracer::Prestate { diff_mode, result } => {
+                PrestateTracer::new(diff_mode, result).into_tracer_pointer()
+            }
+            ApiTracer::Deadline(deadline) => ExecutionDeadline::new(deadline).into_tracer_pointer(),
         }
     }
 }
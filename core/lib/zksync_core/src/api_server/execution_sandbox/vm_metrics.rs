@@ -242,5 +242,6 @@ pub(super) fn collect_tx_execution_metrics(
         total_updated_values_size: writes_metrics.total_updated_values_size,
         pubdata_published: result.statistics.pubdata_published,
         circuit_statistic: result.statistics.circuit_statistic,
+        storage_slots_reset: result.logs.storage_slots_reset_count(),
     }
 }
@@ -1,9 +1,17 @@
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
 use tokio::sync::watch;
 use zksync_health_check::{AppHealth, AppHealthCheck};
 
+use crate::{config_export::ConfigExport, utils::auth::is_bearer_token_valid};
+
 async fn check_health(
     app_health_check: State<Arc<AppHealthCheck>>,
 ) -> (StatusCode, Json<AppHealth>) {
@@ -16,18 +24,48 @@ async fn check_health(
     (response_code, Json(response))
 }
 
+/// State of the `/config` endpoint.
+#[derive(Debug)]
+struct ConfigExportState {
+    /// Expected bearer token.
+    token: String,
+    config_export: ConfigExport,
+}
+
+async fn export_config(state: State<Arc<ConfigExportState>>, headers: HeaderMap) -> Response {
+    if !is_bearer_token_valid(&headers, &state.token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    match state.config_export.to_yaml() {
+        Ok(yaml) => ([(header::CONTENT_TYPE, "application/yaml")], yaml).into_response(),
+        Err(err) => {
+            tracing::warn!("Failed exporting config: {err:#}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
 async fn run_server(
     bind_address: &SocketAddr,
     app_health_check: Arc<AppHealthCheck>,
+    config_export: Option<ConfigExportState>,
     mut stop_receiver: watch::Receiver<bool>,
 ) {
     tracing::debug!(
         "Starting healthcheck server with checks {app_health_check:?} on {bind_address}"
     );
 
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/health", get(check_health))
         .with_state(app_health_check);
+    if let Some(config_export) = config_export {
+        tracing::info!("Config export is enabled for healthcheck server");
+        let config_routes = Router::new()
+            .route("/config", get(export_config))
+            .with_state(Arc::new(config_export));
+        app = app.merge(config_routes);
+    }
 
     axum::Server::bind(bind_address)
         .serve(app.into_make_service())
@@ -50,9 +88,33 @@ pub struct HealthCheckHandle {
 
 impl HealthCheckHandle {
     pub fn spawn_server(addr: SocketAddr, app_health_check: Arc<AppHealthCheck>) -> Self {
+        Self::spawn(addr, app_health_check, None)
+    }
+
+    /// Spawns the server additionally exposing the `/config` endpoint, which returns the effective node config
+    /// in the YAML format with secrets redacted. Requests to the endpoint must contain
+    /// the `Authorization: Bearer {token}` header.
+    pub fn spawn_server_with_config_export(
+        addr: SocketAddr,
+        app_health_check: Arc<AppHealthCheck>,
+        token: String,
+        config_export: ConfigExport,
+    ) -> Self {
+        let config_export = ConfigExportState {
+            token,
+            config_export,
+        };
+        Self::spawn(addr, app_health_check, Some(config_export))
+    }
+
+    fn spawn(
+        addr: SocketAddr,
+        app_health_check: Arc<AppHealthCheck>,
+        config_export: Option<ConfigExportState>,
+    ) -> Self {
         let (stop_sender, stop_receiver) = watch::channel(false);
         let server = tokio::spawn(async move {
-            run_server(&addr, app_health_check, stop_receiver).await;
+            run_server(&addr, app_health_check, config_export, stop_receiver).await;
         });
 
         Self {
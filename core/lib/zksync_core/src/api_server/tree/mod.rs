@@ -17,7 +17,9 @@ use zksync_merkle_tree::NoVersionError;
 use zksync_types::{L1BatchNumber, H256, U256};
 
 use self::metrics::{MerkleTreeApiMethod, API_METRICS};
-use crate::metadata_calculator::{AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo};
+use crate::metadata_calculator::{
+    AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo, TreeReaderPool,
+};
 
 mod metrics;
 #[cfg(test)]
@@ -65,6 +67,7 @@ impl TreeEntryWithProof {
 #[derive(Debug)]
 enum TreeApiServerError {
     NoTreeVersion(NoVersionError),
+    NotReady,
 }
 
 // Contains the same fields as `NoVersionError` and is serializable.
@@ -117,6 +120,15 @@ impl IntoResponse for TreeApiServerError {
                 };
                 (StatusCode::NOT_FOUND, headers, Json(body)).into_response()
             }
+            Self::NotReady => {
+                let body = Problem {
+                    r#type: "/errors#tree-not-ready",
+                    title: "Merkle tree not ready",
+                    detail: "Merkle tree is not initialized yet; repeat request later".to_owned(),
+                    data: (),
+                };
+                (StatusCode::SERVICE_UNAVAILABLE, headers, Json(body)).into_response()
+            }
         }
     }
 }
@@ -175,6 +187,27 @@ impl TreeApiClient for LazyAsyncTreeReader {
     }
 }
 
+/// In-memory client implementation serving requests from a pool of tree snapshots.
+#[async_trait]
+impl TreeApiClient for TreeReaderPool {
+    async fn get_info(&self) -> Result<MerkleTreeInfo, TreeApiError> {
+        let reader = self.acquire().await.ok_or(TreeApiError::NotReady)?;
+        Ok(reader.clone().info().await)
+    }
+
+    async fn get_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<Vec<TreeEntryWithProof>, TreeApiError> {
+        let reader = self.acquire().await.ok_or(TreeApiError::NotReady)?;
+        reader
+            .get_proofs_inner(l1_batch_number, hashed_keys)
+            .await
+            .map_err(TreeApiError::NoVersion)
+    }
+}
+
 /// [`TreeApiClient`] implementation requesting data from a Merkle tree API server.
 #[derive(Debug, Clone)]
 pub struct TreeApiHttpClient {
@@ -219,6 +252,9 @@ impl TreeApiClient for TreeApiHttpClient {
             .send()
             .await
             .context("Failed requesting tree info")?;
+        if response.status() == StatusCode::SERVICE_UNAVAILABLE {
+            return Err(TreeApiError::NotReady);
+        }
         let response = response
             .error_for_status()
             .context("Requesting tree info returned non-OK response")?;
@@ -256,6 +292,9 @@ impl TreeApiClient for TreeApiHttpClient {
                 .context("failed parsing error response")?;
             return Err(TreeApiError::NoVersion(problem_data.into()));
         }
+        if response.status() == StatusCode::SERVICE_UNAVAILABLE {
+            return Err(TreeApiError::NotReady);
+        }
 
         let response = response.error_for_status().with_context(|| {
             format!("requesting proofs for L1 batch #{l1_batch_number} returned non-OK response")
@@ -268,13 +307,6 @@ impl TreeApiClient for TreeApiHttpClient {
 }
 
 impl AsyncTreeReader {
-    async fn info_handler(State(this): State<Self>) -> Json<MerkleTreeInfo> {
-        let latency = API_METRICS.latency[&MerkleTreeApiMethod::Info].start();
-        let info = this.info().await;
-        latency.observe();
-        Json(info)
-    }
-
     async fn get_proofs_inner(
         &self,
         l1_batch_number: L1BatchNumber,
@@ -286,13 +318,26 @@ impl AsyncTreeReader {
             .await?;
         Ok(proofs.into_iter().map(TreeEntryWithProof::new).collect())
     }
+}
+
+impl TreeReaderPool {
+    async fn info_handler(
+        State(this): State<Self>,
+    ) -> Result<Json<MerkleTreeInfo>, TreeApiServerError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::Info].start();
+        let reader = this.acquire().await.ok_or(TreeApiServerError::NotReady)?;
+        let info = reader.clone().info().await;
+        latency.observe();
+        Ok(Json(info))
+    }
 
     async fn get_proofs_handler(
         State(this): State<Self>,
         Json(request): Json<TreeProofsRequest>,
     ) -> Result<Json<TreeProofsResponse>, TreeApiServerError> {
         let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetProofs].start();
-        let entries = this
+        let reader = this.acquire().await.ok_or(TreeApiServerError::NotReady)?;
+        let entries = reader
             .get_proofs_inner(request.l1_batch_number, request.hashed_keys)
             .await
             .map_err(TreeApiServerError::NoTreeVersion)?;
@@ -339,7 +384,8 @@ impl AsyncTreeReader {
         })
     }
 
-    /// Runs the HTTP API server.
+    /// Runs the HTTP API server. Until the tree is initialized, the server responds to all requests
+    /// with 503 Service Unavailable.
     pub async fn run_api_server(
         self,
         bind_address: SocketAddr,
@@ -1,10 +1,12 @@
 //! Tests for the Merkle tree API.
 
-use std::net::Ipv4Addr;
+use std::{net::Ipv4Addr, time::Duration};
 
 use assert_matches::assert_matches;
 use tempfile::TempDir;
+use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_dal::{ConnectionPool, Core};
+use zksync_merkle_tree::TreeEntry;
 
 use super::*;
 use crate::metadata_calculator::tests::{
@@ -19,19 +21,26 @@ async fn merkle_tree_api() {
     let api_addr = (Ipv4Addr::LOCALHOST, 0).into();
 
     reset_db_state(&pool, 5).await;
-    let tree_reader = calculator.tree_reader();
-    let calculator_task = tokio::spawn(run_calculator(calculator, pool));
-
+    let tree_reader_pool = calculator.tree_reader_pool(4);
     let (stop_sender, stop_receiver) = watch::channel(false);
-    let api_server = tree_reader
-        .wait()
-        .await
+    let api_server = tree_reader_pool
         .create_api_server(&api_addr, stop_receiver.clone())
         .unwrap();
     let local_addr = *api_server.local_addr();
     let api_server_task = tokio::spawn(api_server.run());
     let api_client = TreeApiHttpClient::new(&format!("http://{local_addr}"));
 
+    // The tree is not initialized yet.
+    let err = api_client.get_info().await.unwrap_err();
+    assert_matches!(err, TreeApiError::NotReady);
+    let err = api_client
+        .get_proofs(L1BatchNumber(0), vec![])
+        .await
+        .unwrap_err();
+    assert_matches!(err, TreeApiError::NotReady);
+
+    let calculator_task = tokio::spawn(run_calculator(calculator, pool));
+
     // Wait until the calculator processes initial L1 batches.
     calculator_task.await.unwrap();
 
@@ -92,6 +101,32 @@ async fn local_merkle_tree_client() {
     assert!(tree_info.leaf_count > 20);
     assert_eq!(tree_info.next_l1_batch_number, L1BatchNumber(6));
 
+    // Check that returned proofs (both inclusion and non-inclusion ones) verify against the root hash
+    // of the latest L1 batch after converting them back from the root-to-leaf order.
+    let mut hashed_keys: Vec<_> = gen_storage_logs(20..30, 1)[0]
+        .iter()
+        .map(|log| log.key.hashed_key_u256())
+        .collect();
+    hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
+    let proofs = tree_reader
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
+        .await
+        .unwrap();
+    assert_eq!(proofs.len(), hashed_keys.len());
+    for (proof, key) in proofs.into_iter().zip(hashed_keys) {
+        let mut merkle_path = proof.merkle_path;
+        merkle_path.reverse();
+        let proof = zksync_merkle_tree::TreeEntryWithProof {
+            base: TreeEntry {
+                key,
+                value: proof.value,
+                leaf_index: proof.index,
+            },
+            merkle_path,
+        };
+        proof.verify(&Blake2Hasher, tree_info.root_hash);
+    }
+
     let err = tree_reader
         .get_proofs(L1BatchNumber(10), vec![])
         .await
@@ -102,3 +137,62 @@ async fn local_merkle_tree_client() {
     assert_eq!(err.version_count, 6);
     assert_eq!(err.missing_version, 10);
 }
+
+#[tokio::test]
+async fn tree_reader_pool_limits_concurrent_readers() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+
+    reset_db_state(&pool, 5).await;
+    let tree_reader_pool = calculator.tree_reader_pool(2);
+    assert!(tree_reader_pool.acquire().await.is_none());
+    let err = tree_reader_pool.get_info().await.unwrap_err();
+    assert_matches!(err, TreeApiError::NotReady);
+
+    run_calculator(calculator, pool).await;
+
+    let first_reader = tree_reader_pool.acquire().await.unwrap();
+    let second_reader = tree_reader_pool.acquire().await.unwrap();
+    let third_reader_future = tree_reader_pool.acquire();
+    tokio::pin!(third_reader_future);
+    tokio::time::timeout(Duration::from_millis(50), &mut third_reader_future)
+        .await
+        .unwrap_err();
+
+    let tree_info = first_reader.clone().info().await;
+    assert_eq!(tree_info.next_l1_batch_number, L1BatchNumber(6));
+    drop(first_reader);
+    let third_reader = third_reader_future.await.unwrap();
+    for reader in [second_reader, third_reader] {
+        let info = reader.clone().info().await;
+        assert_eq!(info.root_hash, tree_info.root_hash);
+        assert_eq!(info.next_l1_batch_number, tree_info.next_l1_batch_number);
+    }
+}
+
+#[tokio::test]
+async fn closing_tree_reader_pool() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+
+    reset_db_state(&pool, 5).await;
+    let tree_reader_pool = calculator.tree_reader_pool(2);
+    run_calculator(calculator, pool).await;
+
+    let reader = tree_reader_pool.acquire().await.unwrap();
+    let pool_clone = tree_reader_pool.clone();
+    let close_future = pool_clone.close();
+    tokio::pin!(close_future);
+    // Closing must wait for the acquired reader to be dropped.
+    tokio::time::timeout(Duration::from_millis(50), &mut close_future)
+        .await
+        .unwrap_err();
+    drop(reader);
+    close_future.await;
+
+    assert!(tree_reader_pool.acquire().await.is_none());
+    // Closing is idempotent.
+    tree_reader_pool.close().await;
+}
@@ -1,6 +1,7 @@
 use std::collections::hash_map::{Entry, HashMap};
 
 use tokio::sync::Mutex;
+use zksync_config::configs::chain::MempoolDurability;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, Core, CoreDal};
 use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, Address, Nonce, H256};
 
@@ -11,9 +12,14 @@ use crate::{
 };
 
 /// Wrapper for the master DB pool that allows to submit transactions to the mempool.
+///
+/// Transactions are written through to Postgres before they are acknowledged; the in-memory mempool
+/// of the state keeper is loaded from Postgres (see [`MempoolFetcher`](crate::state_keeper::MempoolFetcher)),
+/// so acknowledged transactions survive a restart of the server.
 #[derive(Debug)]
 pub struct MasterPoolSink {
     master_pool: ConnectionPool<Core>,
+    durability: MempoolDurability,
     inflight_requests: Mutex<HashMap<(Address, Nonce), H256>>,
 }
 
@@ -21,9 +27,47 @@ impl MasterPoolSink {
     pub fn new(master_pool: ConnectionPool<Core>) -> Self {
         Self {
             master_pool,
+            durability: MempoolDurability::default(),
             inflight_requests: Mutex::new(HashMap::new()),
         }
     }
+
+    /// Sets the durability level for inserted transactions. If not called, [`MempoolDurability::Synchronous`]
+    /// is used.
+    pub fn with_durability(mut self, durability: MempoolDurability) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    async fn insert_transaction(
+        &self,
+        tx: L2Tx,
+        execution_metrics: TransactionExecutionMetrics,
+    ) -> anyhow::Result<L2TxSubmissionResult> {
+        let mut connection = self.master_pool.connection_tagged("api").await?;
+        let result = match self.durability {
+            MempoolDurability::Synchronous => {
+                connection
+                    .transactions_dal()
+                    .insert_transaction_l2(tx, execution_metrics)
+                    .await?
+            }
+            MempoolDurability::Asynchronous => {
+                let mut transaction = connection.start_transaction().await?;
+                transaction
+                    .transactions_dal()
+                    .set_asynchronous_commit()
+                    .await?;
+                let result = transaction
+                    .transactions_dal()
+                    .insert_transaction_l2(tx, execution_metrics)
+                    .await?;
+                transaction.commit().await?;
+                result
+            }
+        };
+        Ok(result)
+    }
 }
 
 #[async_trait::async_trait]
@@ -53,18 +97,14 @@ impl TxSink for MasterPoolSink {
         };
         drop(lock);
 
-        let result = match self.master_pool.connection_tagged("api").await {
-            Ok(mut connection) => connection
-                .transactions_dal()
-                .insert_transaction_l2(tx, execution_metrics)
-                .await
-                .map(|submission_res_handle| {
-                    APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
-                    submission_res_handle
-                })
-                .map_err(|err| anyhow::format_err!(err).into()),
-            Err(err) => Err(err.into()),
-        };
+        let result = self
+            .insert_transaction(tx, execution_metrics)
+            .await
+            .map(|submission_res_handle| {
+                APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
+                submission_res_handle
+            })
+            .map_err(Into::into);
 
         self.inflight_requests
             .lock()
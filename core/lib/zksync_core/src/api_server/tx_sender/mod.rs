@@ -18,7 +18,7 @@ use zksync_dal::{
 };
 use zksync_state::PostgresStorageCaches;
 use zksync_types::{
-    fee::{Fee, TransactionExecutionMetrics},
+    fee::{Fee, FeeBreakdown, FeeEstimate, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
     l1::is_l1_tx_type,
@@ -35,7 +35,7 @@ use self::tx_sink::TxSink;
 use crate::{
     api_server::{
         execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
+            get_pubdata_for_factory_deps, ApiTracer, BlockArgs, BlockStartInfo, SubmitTxStage,
             TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
             SANDBOX_METRICS,
         },
@@ -211,6 +211,10 @@ pub struct TxSenderConfig {
     pub fee_account_addr: Address,
     pub gas_price_scale_factor: f64,
     pub max_nonce_ahead: u32,
+    /// Minimum fee bump (in percent) for a transaction to replace a pending transaction with the same nonce.
+    pub replacement_fee_bump_percent: Option<u32>,
+    /// Maximum number of pending transactions per initiator account.
+    pub max_pending_txs_per_account: Option<u32>,
     pub max_allowed_l2_tx_gas_limit: u64,
     pub vm_execution_cache_misses_limit: Option<usize>,
     pub validation_computational_gas_limit: u32,
@@ -230,6 +234,8 @@ impl TxSenderConfig {
             fee_account_addr,
             gas_price_scale_factor: web3_json_config.gas_price_scale_factor,
             max_nonce_ahead: web3_json_config.max_nonce_ahead,
+            replacement_fee_bump_percent: web3_json_config.replacement_fee_bump_percent,
+            max_pending_txs_per_account: web3_json_config.max_pending_txs_per_account,
             max_allowed_l2_tx_gas_limit: state_keeper_config.max_allowed_l2_tx_gas_limit,
             vm_execution_cache_misses_limit: web3_json_config.vm_execution_cache_misses_limit,
             validation_computational_gas_limit: state_keeper_config
@@ -280,7 +286,7 @@ impl TxSender {
     async fn acquire_replica_connection(&self) -> anyhow::Result<Connection<'_, Core>> {
         self.0
             .replica_connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .context("failed acquiring connection to replica DB")
     }
@@ -466,6 +472,7 @@ impl TxSender {
         // We still double-check the nonce manually
         // to make sure that only the correct nonce is submitted and the transaction's hashes never repeat
         self.validate_account_nonce(tx).await?;
+        self.validate_pending_txs(tx).await?;
         // Even though without enough balance the tx will not pass anyway
         // we check the user for enough balance explicitly here for better DevEx.
         self.validate_enough_balance(tx).await?;
@@ -503,6 +510,60 @@ impl TxSender {
         }
     }
 
+    /// Checks the replacement rules and the per-account limit for pending transactions.
+    async fn validate_pending_txs(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let config = &self.0.sender_config;
+        if config.replacement_fee_bump_percent.is_none()
+            && config.max_pending_txs_per_account.is_none()
+        {
+            return Ok(());
+        }
+
+        let initiator_account = tx.initiator_account();
+        let mut connection = self.acquire_replica_connection().await?;
+        let pending_tx_fees = connection
+            .transactions_web3_dal()
+            .get_pending_tx_fees(initiator_account, tx.nonce())
+            .await
+            .with_context(|| format!("failed getting pending tx for {initiator_account:?}"))?;
+
+        if let Some(pending_tx_fees) = pending_tx_fees {
+            // The transaction replaces a pending transaction, so it's not subject to the per-account limit.
+            // Resubmitted transactions are reported as duplicates when persisted.
+            let Some(bump_percent) = config.replacement_fee_bump_percent else {
+                return Ok(());
+            };
+            if pending_tx_fees.tx_hash == tx.hash() {
+                return Ok(());
+            }
+            let fee = &tx.common_data.fee;
+            let is_bumped = is_fee_bumped(
+                fee.max_fee_per_gas,
+                pending_tx_fees.max_fee_per_gas,
+                bump_percent,
+            ) && is_fee_bumped(
+                fee.max_priority_fee_per_gas,
+                pending_tx_fees.max_priority_fee_per_gas,
+                bump_percent,
+            );
+            if !is_bumped {
+                return Err(SubmitTxError::ReplacementUnderpriced(bump_percent));
+            }
+        } else if let Some(max_pending_txs) = config.max_pending_txs_per_account {
+            let pending_txs_count = connection
+                .transactions_web3_dal()
+                .pending_txs_count_by_initiator_account(initiator_account)
+                .await
+                .with_context(|| {
+                    format!("failed getting pending txs count for {initiator_account:?}")
+                })?;
+            if pending_txs_count >= max_pending_txs as usize {
+                return Err(SubmitTxError::TooManyPendingTxs(max_pending_txs));
+            }
+        }
+        Ok(())
+    }
+
     async fn get_expected_nonce(&self, initiator_account: Address) -> anyhow::Result<Nonce> {
         let mut storage = self.acquire_replica_connection().await?;
         let latest_block_number = storage
@@ -579,6 +640,7 @@ impl TxSender {
         block_args: BlockArgs,
         base_fee: u64,
         vm_version: VmVersion,
+        deadline: Option<Instant>,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -624,7 +686,7 @@ impl TxSender {
                 self.0.replica_connection_pool.clone(),
                 tx.clone(),
                 block_args,
-                vec![],
+                deadline.map(ApiTracer::Deadline).into_iter().collect(),
             )
             .await?;
         Ok((execution_output.vm, execution_output.metrics))
@@ -644,12 +706,14 @@ impl TxSender {
         }
     }
 
+    /// Estimates the fee for a transaction. If `deadline` is specified, the estimation is aborted once it has passed.
     pub async fn get_txs_fee_in_wei(
         &self,
         mut tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u64,
-    ) -> Result<Fee, SubmitTxError> {
+        deadline: Option<Instant>,
+    ) -> Result<FeeEstimate, SubmitTxError> {
         let estimation_started_at = Instant::now();
 
         let mut connection = self.acquire_replica_connection().await?;
@@ -773,6 +837,10 @@ impl TxSender {
 
         let mut number_of_iterations = 0usize;
         while lower_bound + acceptable_overestimation < upper_bound {
+            if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                let err = anyhow::anyhow!("deadline exceeded during gas estimation for {tx_id}");
+                return Err(SubmitTxError::Internal(err));
+            }
             let mid = (lower_bound + upper_bound) / 2;
             // There is no way to distinct between errors due to out of gas
             // or normal execution errors, so we just hope that increasing the
@@ -789,6 +857,7 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    deadline,
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -829,6 +898,7 @@ impl TxSender {
                 block_args,
                 base_fee,
                 protocol_version.into(),
+                deadline,
             )
             .await
             .context("final estimate_gas step failed")?;
@@ -871,11 +941,28 @@ impl TxSender {
                 }
             };
 
-        Ok(Fee {
-            max_fee_per_gas: base_fee.into(),
-            max_priority_fee_per_gas: 0u32.into(),
-            gas_limit: full_gas_limit.into(),
-            gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+        // Pubdata is paid for using the gas limit for the transaction body; the remaining gas is spent on computation.
+        let execution_gas = tx_body_gas_limit + gas_for_bytecodes_pubdata;
+        let pubdata_gas = cmp::min(
+            u64::from(tx_metrics.pubdata_published) * gas_per_pubdata_byte,
+            execution_gas,
+        );
+        let breakdown = FeeBreakdown {
+            compute_gas: (execution_gas - pubdata_gas).into(),
+            pubdata_gas: pubdata_gas.into(),
+            overhead_gas: overhead.into(),
+            fair_l2_gas_price: fee_input.fair_l2_gas_price().into(),
+            fair_pubdata_price: fee_input.fair_pubdata_price().into(),
+        };
+
+        Ok(FeeEstimate {
+            fee: Fee {
+                max_fee_per_gas: base_fee.into(),
+                max_priority_fee_per_gas: 0u32.into(),
+                gas_limit: full_gas_limit.into(),
+                gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+            },
+            breakdown: Some(breakdown),
         })
     }
 
@@ -883,6 +970,7 @@ impl TxSender {
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        deadline: Option<Instant>,
     ) -> Result<Vec<u8>, SubmitTxError> {
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
@@ -897,7 +985,7 @@ impl TxSender {
                 tx,
                 block_args,
                 vm_execution_cache_misses_limit,
-                vec![],
+                deadline.map(ApiTracer::Deadline).into_iter().collect(),
             )
             .await?
             .into_api_call_result()
@@ -960,6 +1048,12 @@ impl TxSender {
     }
 }
 
+/// Checks whether `new_fee` is at least `bump_percent` percent greater than `old_fee`.
+fn is_fee_bumped(new_fee: U256, old_fee: U256, bump_percent: u32) -> bool {
+    // Multiplication is performed with extended precision since fees are not bounded at this point.
+    new_fee.full_mul(100.into()) >= old_fee.full_mul((100 + u64::from(bump_percent)).into())
+}
+
 /// During switch to the 1.4.1 protocol version, there will be a moment of discrepancy, when while
 /// the L2 has already upgraded to 1.4.1 (and thus suggests smaller overhead), the L1 is still on the previous version.
 ///
@@ -14,6 +14,10 @@ pub enum SubmitTxError {
     NonceIsTooLow(u32, u32, u32),
     #[error("insertion of another transaction with the same nonce is in progress")]
     InsertionInProgress,
+    #[error("replacement transaction underpriced: fees must be bumped by at least {0}%")]
+    ReplacementUnderpriced(u32),
+    #[error("too many pending transactions for the account, at most {0} are allowed")]
+    TooManyPendingTxs(u32),
     #[error("{0}")]
     IncorrectTx(#[from] TxCheckError),
     #[error("insufficient funds for gas + value. balance: {0}, fee: {1}, value: {2}")]
@@ -83,6 +87,8 @@ impl SubmitTxError {
             Self::NonceIsTooHigh(_, _, _) => "nonce-is-too-high",
             Self::NonceIsTooLow(_, _, _) => "nonce-is-too-low",
             Self::InsertionInProgress => "insertion-in-progress",
+            Self::ReplacementUnderpriced(_) => "replacement-underpriced",
+            Self::TooManyPendingTxs(_) => "too-many-pending-txs",
             Self::IncorrectTx(_) => "incorrect-tx",
             Self::NotEnoughBalanceForFeeValue(_, _, _) => "not-enough-balance-for-fee",
             Self::ExecutionReverted(_, _) => "execution-reverted",
@@ -1,13 +1,17 @@
 //! Tests for the transaction sender.
 
-use zksync_config::configs::wallets::Wallets;
+use test_casing::test_casing;
+use zksync_config::configs::{chain::MempoolDurability, wallets::Wallets};
 use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
 
-use super::*;
+use super::{master_pool_sink::MasterPoolSink, *};
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{insert_genesis_batch, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -31,12 +35,14 @@ pub(crate) async fn create_test_tx_sender(
         &tx_sender_config,
         &web3_config,
         &state_keeper_config,
+        MempoolDurability::Synchronous,
         pool.clone(),
         pool,
         batch_fee_model_input_provider,
         storage_caches,
     )
-    .await;
+    .await
+    .unwrap();
 
     Arc::get_mut(&mut tx_sender.0).unwrap().executor = tx_executor;
     (tx_sender, vm_barrier)
@@ -146,3 +152,44 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[test]
+fn checking_replacement_fee_bump() {
+    assert!(is_fee_bumped(110.into(), 100.into(), 10));
+    assert!(!is_fee_bumped(109.into(), 100.into(), 10));
+    assert!(is_fee_bumped(100.into(), 100.into(), 0));
+    assert!(is_fee_bumped(0.into(), 0.into(), 10));
+    // Fees are not bounded when checked, so the comparison must not overflow.
+    assert!(is_fee_bumped(U256::MAX, U256::MAX, 0));
+    assert!(!is_fee_bumped(U256::MAX, U256::MAX, 10));
+}
+
+#[test_casing(2, [MempoolDurability::Synchronous, MempoolDurability::Asynchronous])]
+#[tokio::test]
+async fn submitting_transaction_to_master_pool(durability: MempoolDurability) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let sink = MasterPoolSink::new(pool.clone()).with_durability(durability);
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+
+    let result = sink
+        .submit_tx(tx.clone(), TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+    let result = sink
+        .submit_tx(tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Duplicate);
+
+    // The transaction must be visible to other connections (e.g., the mempool fetcher).
+    let mut storage = pool.connection().await.unwrap();
+    let stored_tx = storage
+        .transactions_web3_dal()
+        .get_transaction_by_hash(tx_hash, L2ChainId::default())
+        .await
+        .unwrap()
+        .expect("transaction was not persisted");
+    assert_eq!(stored_tx.hash, tx_hash);
+}
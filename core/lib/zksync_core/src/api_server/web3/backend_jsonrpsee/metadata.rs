@@ -1,6 +1,11 @@
 //! Method metadata.
 
-use std::{cell::RefCell, mem, sync::Arc, time::Instant};
+use std::{
+    cell::RefCell,
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use thread_local::ThreadLocal;
 use zksync_types::api;
@@ -18,6 +23,8 @@ use crate::api_server::web3::metrics::API_METRICS;
 pub(crate) struct MethodMetadata {
     pub name: &'static str,
     pub started_at: Instant,
+    /// Deadline for the call, after which it is cancelled.
+    pub deadline: Option<Instant>,
     /// Block ID requested by the call.
     pub block_id: Option<api::BlockId>,
     /// Difference between the latest block number and the requested block ID.
@@ -27,10 +34,12 @@ pub(crate) struct MethodMetadata {
 }
 
 impl MethodMetadata {
-    fn new(name: &'static str) -> Self {
+    fn new(name: &'static str, timeout: Option<Duration>) -> Self {
+        let started_at = Instant::now();
         Self {
             name,
-            started_at: Instant::now(),
+            started_at,
+            deadline: timeout.map(|timeout| started_at + timeout),
             block_id: None,
             block_diff: None,
             has_app_error: false,
@@ -88,10 +97,24 @@ impl MethodTracer {
         }
     }
 
-    pub(super) fn new_call(self: &Arc<Self>, name: &'static str) -> MethodCall {
+    /// Returns the deadline for the current JSON-RPC method call, if any. Components performing potentially
+    /// long-running work (e.g., VM execution) should stop it once the deadline has passed.
+    ///
+    /// This should be called inside JSON-RPC method handlers; otherwise, this method always returns `None`.
+    pub fn deadline(&self) -> Option<Instant> {
+        let cell = self.inner.get_or_default();
+        let metadata = cell.borrow();
+        metadata.as_ref().and_then(|metadata| metadata.deadline)
+    }
+
+    pub(super) fn new_call(
+        self: &Arc<Self>,
+        name: &'static str,
+        timeout: Option<Duration>,
+    ) -> MethodCall {
         MethodCall {
             tracer: self.clone(),
-            meta: MethodMetadata::new(name),
+            meta: MethodMetadata::new(name, timeout),
             is_completed: false,
         }
     }
@@ -133,6 +156,10 @@ impl Drop for MethodCall {
 }
 
 impl MethodCall {
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.meta.deadline
+    }
+
     pub(super) fn set_as_current(&mut self) -> CurrentMethodGuard<'_> {
         let meta = &mut self.meta;
         let cell = self.tracer.inner.get_or_default();
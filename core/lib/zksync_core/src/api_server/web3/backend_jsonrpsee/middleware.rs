@@ -1,37 +1,52 @@
 use std::{
     collections::HashSet,
+    fmt,
     future::Future,
+    net::IpAddr,
     num::NonZeroU32,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Weak},
     task::{Context, Poll},
     time::{Duration, Instant},
 };
 
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Request as HttpRequest, Response as HttpResponse, StatusCode},
+};
+use futures::future;
 use governor::{
     clock::DefaultClock,
     middleware::NoOpMiddleware,
-    state::{InMemoryState, NotKeyed},
+    state::{keyed::DefaultKeyedStateStore, InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
 use once_cell::sync::OnceCell;
 use pin_project_lite::pin_project;
-use tokio::sync::watch;
+use tokio::{
+    sync::{watch, OwnedSemaphorePermit, Semaphore},
+    time::Sleep,
+};
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram,
+    LabeledFamily, Metrics,
 };
 use zksync_web3_decl::jsonrpsee::{
     server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
-    types::{error::ErrorCode, ErrorObject, Request},
+    types::{error::ErrorCode, ErrorObject, Id, Request},
     MethodResponse,
 };
 
 use super::metadata::{MethodCall, MethodTracer};
-use crate::api_server::web3::metrics::API_METRICS;
+use crate::{
+    api_server::web3::metrics::{DeadlineLayer, API_METRICS},
+    sync_layer::{NodeState, NodeStateHandle},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "transport", rename_all = "snake_case")]
 pub(crate) enum Transport {
+    Http,
     Ws,
 }
 
@@ -45,11 +60,29 @@ struct LimitMiddlewareMetrics {
     size: Family<Transport, Histogram<usize>>,
     /// Number of requests rejected by the limiter.
     rejected: Family<Transport, Counter>,
+    /// Number of requests rejected by per-method rate limiting, grouped by the method name.
+    #[metrics(labels = ["method"])]
+    method_rate_limited: LabeledFamily<&'static str, Counter>,
+    /// Number of requests shed because the server was overloaded.
+    overloaded: Family<Transport, Counter>,
+    /// Number of HTTP requests (incl. WebSocket handshakes) rejected by per-client rate limiting.
+    client_rate_limited: Family<Transport, Counter>,
 }
 
 #[vise::register]
 static METRICS: vise::Global<LimitMiddlewareMetrics> = vise::Global::new();
 
+/// Error code returned when a request is rejected because of rate limiting or server overload.
+/// Corresponds to the "Limit exceeded" error defined in EIP-1474.
+pub(crate) const LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+fn limit_exceeded_response(id: Id<'_>, message: &'static str) -> MethodResponse {
+    MethodResponse::error(
+        id,
+        ErrorObject::borrowed(LIMIT_EXCEEDED_CODE, message, None),
+    )
+}
+
 /// A rate-limiting middleware.
 ///
 /// `jsonrpsee` will allocate the instance of this struct once per session.
@@ -85,18 +118,7 @@ where
             // Note: if required, we can extract data on rate limiting from the error.
             if rate_limiter.check_n(num_requests).is_err() {
                 METRICS.rate_limited[&self.transport].inc();
-
-                let rp = MethodResponse::error(
-                    request.id,
-                    ErrorObject::borrowed(
-                        ErrorCode::ServerError(
-                            reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16().into(),
-                        )
-                        .code(),
-                        "Too many requests",
-                        None,
-                    ),
-                );
+                let rp = limit_exceeded_response(request.id, "Too many requests");
                 return ResponseFuture::ready(rp);
             }
         }
@@ -104,16 +126,300 @@ where
     }
 }
 
+type MethodRateLimiter =
+    RateLimiter<&'static str, DefaultKeyedStateStore<&'static str>, DefaultClock>;
+
+/// Limits shared by all connections to a server: per-method rate limits and the limit on the number
+/// of concurrently handled requests.
+///
+/// `jsonrpsee` doesn't expose client addresses to RPC middleware, so limits for individual clients
+/// are enforced on the HTTP level (see [`ClientRateLimitLayer`]) and per WebSocket connection
+/// (see [`LimitMiddleware`]).
+pub(crate) struct ServerLimits {
+    method_rate_limiter: Option<MethodRateLimiter>,
+    concurrency_limiter: Option<Arc<Semaphore>>,
+}
+
+impl fmt::Debug for ServerLimits {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ServerLimits")
+            .field(
+                "has_method_rate_limiter",
+                &self.method_rate_limiter.is_some(),
+            )
+            .field("concurrency_limiter", &self.concurrency_limiter)
+            .finish()
+    }
+}
+
+impl ServerLimits {
+    pub fn new(
+        method_requests_per_minute_limit: Option<NonZeroU32>,
+        max_concurrent_requests: Option<usize>,
+    ) -> Self {
+        Self {
+            method_rate_limiter: method_requests_per_minute_limit
+                .map(|limit| RateLimiter::keyed(Quota::per_minute(limit))),
+            concurrency_limiter: max_concurrent_requests
+                .map(|limit| Arc::new(Semaphore::new(limit))),
+        }
+    }
+}
+
+/// Middleware enforcing [`ServerLimits`]. Requests exceeding the limits are rejected with
+/// the [`LIMIT_EXCEEDED_CODE`] error without being passed to method handlers.
+#[derive(Debug)]
+pub(crate) struct ServerLimitMiddleware<S> {
+    inner: S,
+    limits: Arc<ServerLimits>,
+    registered_method_names: Arc<HashSet<&'static str>>,
+    transport: Transport,
+}
+
+impl<S> ServerLimitMiddleware<S> {
+    pub fn new(
+        inner: S,
+        limits: Arc<ServerLimits>,
+        registered_method_names: Arc<HashSet<&'static str>>,
+        transport: Transport,
+    ) -> Self {
+        Self {
+            inner,
+            limits,
+            registered_method_names,
+            transport,
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for ServerLimitMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<WithPermit<S::Future>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        // Shedding is checked first so that shed requests don't consume rate limit quota.
+        let permit = match &self.limits.concurrency_limiter {
+            Some(limiter) => {
+                let Ok(permit) = limiter.clone().try_acquire_owned() else {
+                    METRICS.overloaded[&self.transport].inc();
+                    let rp = limit_exceeded_response(request.id, "Server is overloaded");
+                    return ResponseFuture::ready(rp);
+                };
+                Some(permit)
+            }
+            None => None,
+        };
+
+        if let Some(rate_limiter) = &self.limits.method_rate_limiter {
+            // Unknown methods are not limited; they are rejected by the server anyway.
+            let method_name = self.registered_method_names.get(request.method_name());
+            if let Some(&method_name) = method_name {
+                if rate_limiter.check_key(&method_name).is_err() {
+                    METRICS.method_rate_limited[&method_name].inc();
+                    let rp = limit_exceeded_response(request.id, "Too many requests for method");
+                    return ResponseFuture::ready(rp);
+                }
+            }
+        }
+
+        ResponseFuture::future(WithPermit {
+            _permit: permit,
+            inner: self.inner.call(request),
+        })
+    }
+}
+
+type ClientRateLimiter = RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock>;
+
+/// HTTP-level middleware limiting the number of requests per minute from a single client IP address.
+/// For WebSocket servers, the limit applies to connection handshakes; requests within a connection
+/// are limited by [`LimitMiddleware`].
+///
+/// The client address is taken from the rightmost entry of the `X-Forwarded-For` header, i.e., the one added
+/// by the reverse proxy closest to the server; unlike other entries, it cannot be spoofed by clients.
+/// `jsonrpsee` doesn't expose the address of the TCP peer, so requests without the header are not limited.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientRateLimitLayer {
+    limiter: Arc<ClientRateLimiter>,
+    transport: Transport,
+}
+
+impl ClientRateLimitLayer {
+    /// Interval between pruning rate limiter state for clients that haven't sent requests recently.
+    const PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(requests_per_minute_limit: NonZeroU32, transport: Transport) -> Self {
+        let limiter = Arc::new(RateLimiter::keyed(Quota::per_minute(
+            requests_per_minute_limit,
+        )));
+        tokio::spawn(Self::prune_state(Arc::downgrade(&limiter)));
+        Self { limiter, transport }
+    }
+
+    /// Prunes the state of clients that haven't sent requests recently enough to be affected by rate limiting,
+    /// so that the state doesn't grow indefinitely. Stops once the server is dropped.
+    async fn prune_state(limiter: Weak<ClientRateLimiter>) {
+        loop {
+            tokio::time::sleep(Self::PRUNING_INTERVAL).await;
+            let Some(limiter) = limiter.upgrade() else {
+                return;
+            };
+            limiter.retain_recent();
+            limiter.shrink_to_fit();
+        }
+    }
+
+    fn client_ip(headers: &HeaderMap) -> Option<IpAddr> {
+        let header = headers.get_all("x-forwarded-for").iter().last()?;
+        let last_entry = header.to_str().ok()?.rsplit(',').next()?;
+        last_entry.trim().parse().ok()
+    }
+
+    fn too_many_requests_response() -> HttpResponse<Body> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "error": { "code": LIMIT_EXCEEDED_CODE, "message": "Too many requests from client" },
+            "id": null,
+        });
+        HttpResponse::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+}
+
+impl<S> tower::Layer<S> for ClientRateLimitLayer {
+    type Service = ClientRateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientRateLimitService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`ClientRateLimitLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct ClientRateLimitService<S> {
+    inner: S,
+    layer: ClientRateLimitLayer,
+}
+
+impl<S, B> tower::Service<HttpRequest<B>> for ClientRateLimitService<S>
+where
+    S: tower::Service<HttpRequest<B>, Response = HttpResponse<Body>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = future::Either<future::Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest<B>) -> Self::Future {
+        if let Some(client_ip) = ClientRateLimitLayer::client_ip(request.headers()) {
+            if self.layer.limiter.check_key(&client_ip).is_err() {
+                METRICS.client_rate_limited[&self.layer.transport].inc();
+                let response = ClientRateLimitLayer::too_many_requests_response();
+                return future::Either::Left(future::ready(Ok(response)));
+            }
+        }
+        future::Either::Right(self.inner.call(request))
+    }
+}
+
+pin_project! {
+    /// Future holding a concurrency permit (if any) until the wrapped request is handled.
+    #[derive(Debug)]
+    pub(crate) struct WithPermit<F> {
+        _permit: Option<OwnedSemaphorePermit>,
+        #[pin]
+        inner: F,
+    }
+}
+
+impl<F: Future> Future for WithPermit<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
+
+/// Methods served regardless of the node state. All of them only depend on the static node configuration.
+pub(crate) const READ_ONLY_METHODS: &[&str] = &[
+    "eth_chainId",
+    "net_version",
+    "net_peerCount",
+    "net_listening",
+    "web3_clientVersion",
+    "zks_L1ChainId",
+    "zks_getMainContract",
+    "zks_getBridgeContracts",
+    "zks_getBridgehubContract",
+    "zks_getTestnetPaymaster",
+];
+
+/// Middleware rejecting all methods except for [`READ_ONLY_METHODS`] with a "node is syncing" error
+/// until the node is ready. The error data contains the current node state.
+#[derive(Debug)]
+pub(crate) struct NodeStateMiddleware<S> {
+    inner: S,
+    node_state: NodeStateHandle,
+}
+
+impl<S> NodeStateMiddleware<S> {
+    pub fn new(inner: S, node_state: NodeStateHandle) -> Self {
+        Self { inner, node_state }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for NodeStateMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let node_state = self.node_state.get();
+        if node_state != NodeState::Ready && !READ_ONLY_METHODS.contains(&request.method_name()) {
+            let data = serde_json::json!({ "node_state": node_state });
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::owned(
+                    ErrorCode::ServerError(
+                        reqwest::StatusCode::SERVICE_UNAVAILABLE.as_u16().into(),
+                    )
+                    .code(),
+                    "Node is syncing",
+                    Some(data),
+                ),
+            );
+            return ResponseFuture::ready(rp);
+        }
+        ResponseFuture::future(self.inner.call(request))
+    }
+}
+
 /// RPC-level middleware that adds [`MethodCall`] metadata to method logic. Method handlers can then access this metadata
 /// using [`MethodTracer`], which is a part of `RpcState`. When the handler completes or is dropped, the results are reported
 /// as metrics.
 ///
 /// As an example, a method handler can set the requested block ID, which would then be used in relevant metric labels.
+///
+/// If the request timeout is set, the middleware also cancels method handlers that have exceeded it.
 #[derive(Debug)]
 pub(crate) struct MetadataMiddleware<S> {
     inner: S,
     registered_method_names: Arc<HashSet<&'static str>>,
     method_tracer: Arc<MethodTracer>,
+    request_timeout: Option<Duration>,
 }
 
 impl<S> MetadataMiddleware<S> {
@@ -121,11 +427,13 @@ impl<S> MetadataMiddleware<S> {
         inner: S,
         registered_method_names: Arc<HashSet<&'static str>>,
         method_tracer: Arc<MethodTracer>,
+        request_timeout: Option<Duration>,
     ) -> Self {
         Self {
             inner,
             registered_method_names,
             method_tracer,
+            request_timeout,
         }
     }
 }
@@ -145,10 +453,14 @@ where
             .copied()
             .unwrap_or("");
 
-        WithMethodCall {
-            call: self.method_tracer.new_call(method_name),
-            inner: self.inner.call(request),
-        }
+        let call = self
+            .method_tracer
+            .new_call(method_name, self.request_timeout);
+        WithMethodCall::new(
+            call,
+            request.id.clone().into_owned(),
+            self.inner.call(request),
+        )
     }
 }
 
@@ -156,25 +468,59 @@ pin_project! {
     #[derive(Debug)]
     pub(crate) struct WithMethodCall<F> {
         call: MethodCall,
+        request_id: Id<'static>,
+        #[pin]
+        deadline: Option<Sleep>,
         #[pin]
         inner: F,
     }
 }
 
+impl<F> WithMethodCall<F> {
+    fn new(call: MethodCall, request_id: Id<'static>, inner: F) -> Self {
+        let deadline = call
+            .deadline()
+            .map(|deadline| tokio::time::sleep_until(deadline.into()));
+        Self {
+            call,
+            request_id,
+            deadline,
+            inner,
+        }
+    }
+}
+
 impl<F: Future<Output = MethodResponse>> Future for WithMethodCall<F> {
     type Output = MethodResponse;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let projection = self.project();
         let guard = projection.call.set_as_current();
-        match projection.inner.poll(cx) {
-            Poll::Pending => Poll::Pending,
-            Poll::Ready(response) => {
-                drop(guard);
-                projection.call.observe_response(&response);
-                Poll::Ready(response)
-            }
+        if let Poll::Ready(response) = projection.inner.poll(cx) {
+            drop(guard);
+            projection.call.observe_response(&response);
+            return Poll::Ready(response);
+        }
+        drop(guard);
+
+        // The handler will be dropped together with this future, which cancels it.
+        let Some(deadline) = projection.deadline.as_pin_mut() else {
+            return Poll::Pending;
+        };
+        if deadline.poll(cx).is_pending() {
+            return Poll::Pending;
         }
+        API_METRICS.deadline_exceeded[&DeadlineLayer::Rpc].inc();
+        let response = MethodResponse::error(
+            projection.request_id.clone(),
+            ErrorObject::borrowed(
+                ErrorCode::ServerError(reqwest::StatusCode::REQUEST_TIMEOUT.as_u16().into()).code(),
+                "Request timed out",
+                None,
+            ),
+        );
+        projection.call.observe_response(&response);
+        Poll::Ready(response)
     }
 }
 
@@ -252,7 +598,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{borrow::Cow, time::Duration};
 
     use jsonrpsee::helpers::MethodResponseResult;
     use rand::{thread_rng, Rng};
@@ -293,10 +639,7 @@ mod tests {
                 }
             };
 
-            WithMethodCall {
-                call: method_tracer.new_call("test"),
-                inner,
-            }
+            WithMethodCall::new(method_tracer.new_call("test", None), Id::Number(i), inner)
         });
 
         if spawn_tasks {
@@ -318,6 +661,20 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn metadata_middleware_with_request_timeout() {
+        let method_tracer = Arc::new(MethodTracer::default());
+        let call = method_tracer.new_call("test", Some(Duration::from_millis(10)));
+        let inner = futures::future::pending::<MethodResponse>();
+        let response = WithMethodCall::new(call, Id::Number(1), inner).await;
+        assert!(!response.is_success());
+
+        let calls = method_tracer.recorded_calls().take();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0].metadata.deadline.is_some());
+        assert!(!calls[0].response.is_success());
+    }
+
     #[tokio::test]
     async fn traffic_tracker_basics() {
         let traffic_tracker = TrafficTracker::default();
@@ -332,4 +689,150 @@ mod tests {
         let elapsed = now.elapsed();
         assert!(elapsed >= Duration::from_millis(15), "{elapsed:?}");
     }
+
+    /// Service that never completes requests to the `slow` method and immediately succeeds for other methods.
+    #[derive(Debug)]
+    struct MockService;
+
+    impl<'a> RpcServiceT<'a> for MockService {
+        type Future = futures::future::BoxFuture<'a, MethodResponse>;
+
+        fn call(&self, request: Request<'a>) -> Self::Future {
+            if request.method_name() == "slow" {
+                return Box::pin(futures::future::pending());
+            }
+            Box::pin(futures::future::ready(MethodResponse {
+                result: "{}".to_string(),
+                success_or_error: MethodResponseResult::Success,
+                is_subscription: false,
+            }))
+        }
+    }
+
+    fn server_limit_middleware(limits: ServerLimits) -> ServerLimitMiddleware<MockService> {
+        let method_names = HashSet::from(["fast", "other", "slow"]);
+        ServerLimitMiddleware::new(
+            MockService,
+            Arc::new(limits),
+            Arc::new(method_names),
+            Transport::Http,
+        )
+    }
+
+    fn request(method: &str, id: u64) -> Request<'_> {
+        Request::new(Cow::Borrowed(method), None, Id::Number(id))
+    }
+
+    #[tokio::test]
+    async fn per_method_rate_limiting() {
+        let limits = ServerLimits::new(Some(NonZeroU32::new(2).unwrap()), None);
+        let middleware = server_limit_middleware(limits);
+
+        for id in 0..2 {
+            let response = middleware.call(request("fast", id)).await;
+            assert!(response.is_success());
+        }
+        let response = middleware.call(request("fast", 2)).await;
+        assert_eq!(
+            response.success_or_error.as_error_code(),
+            Some(LIMIT_EXCEEDED_CODE)
+        );
+
+        // Other methods have separate limits.
+        let response = middleware.call(request("other", 3)).await;
+        assert!(response.is_success());
+        // Unknown methods are not limited.
+        for id in 4..7 {
+            let response = middleware.call(request("unknown", id)).await;
+            assert!(response.is_success());
+        }
+    }
+
+    #[tokio::test]
+    async fn shedding_requests_on_overload() {
+        let limits = ServerLimits::new(None, Some(2));
+        let middleware = server_limit_middleware(limits);
+
+        let slow_requests: Vec<_> = (0..2)
+            .map(|id| middleware.call(request("slow", id)))
+            .collect();
+        let response = middleware.call(request("fast", 2)).await;
+        assert_eq!(
+            response.success_or_error.as_error_code(),
+            Some(LIMIT_EXCEEDED_CODE)
+        );
+
+        // Cancelling a request should free up capacity.
+        drop(slow_requests);
+        let response = middleware.call(request("fast", 3)).await;
+        assert!(response.is_success());
+    }
+
+    #[test]
+    fn extracting_client_ip() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(ClientRateLimitLayer::client_ip(&headers), None);
+        headers.insert("x-forwarded-for", "1.2.3.4".parse().unwrap());
+        assert_eq!(
+            ClientRateLimitLayer::client_ip(&headers),
+            Some([1, 2, 3, 4].into())
+        );
+        // Only the rightmost entry, added by the closest proxy, is trusted.
+        headers.insert("x-forwarded-for", "1.2.3.4, ::1".parse().unwrap());
+        assert_eq!(
+            ClientRateLimitLayer::client_ip(&headers),
+            Some(IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]))
+        );
+        headers.insert("x-forwarded-for", "1.2.3.4, garbage".parse().unwrap());
+        assert_eq!(ClientRateLimitLayer::client_ip(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn per_client_rate_limiting() {
+        use axum::body::HttpBody as _;
+        use tower::{Layer as _, ServiceExt as _};
+
+        let layer = ClientRateLimitLayer::new(NonZeroU32::new(2).unwrap(), Transport::Http);
+        let service = layer.layer(tower::service_fn(|_: HttpRequest<Body>| async {
+            Ok::<_, std::convert::Infallible>(HttpResponse::new(Body::empty()))
+        }));
+        let http_request = |client_ip: Option<&str>| {
+            let mut request = HttpRequest::new(Body::empty());
+            if let Some(client_ip) = client_ip {
+                let header_value = format!("10.0.0.1, {client_ip}");
+                request
+                    .headers_mut()
+                    .insert("x-forwarded-for", header_value.parse().unwrap());
+            }
+            request
+        };
+
+        for _ in 0..2 {
+            let response = service
+                .clone()
+                .oneshot(http_request(Some("1.2.3.4")))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = service
+            .clone()
+            .oneshot(http_request(Some("1.2.3.4")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let body = response.into_body().data().await.unwrap().unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], LIMIT_EXCEEDED_CODE);
+
+        // Other clients have separate limits, and requests without a client address are not limited.
+        for client_ip in [Some("5.6.7.8"), None, None, None] {
+            let response = service
+                .clone()
+                .oneshot(http_request(client_ip))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
 }
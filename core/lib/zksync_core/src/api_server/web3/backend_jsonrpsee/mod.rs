@@ -7,9 +7,14 @@ use zksync_web3_decl::{
     jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
 };
 
+#[cfg(test)]
+pub(crate) use self::middleware::{LIMIT_EXCEEDED_CODE, READ_ONLY_METHODS};
 pub(crate) use self::{
     metadata::{MethodMetadata, MethodTracer},
-    middleware::{LimitMiddleware, MetadataMiddleware, ShutdownMiddleware, TrafficTracker},
+    middleware::{
+        ClientRateLimitLayer, LimitMiddleware, MetadataMiddleware, NodeStateMiddleware,
+        ServerLimitMiddleware, ServerLimits, ShutdownMiddleware, TrafficTracker, Transport,
+    },
 };
 use crate::api_server::tx_sender::SubmitTxError;
 
@@ -35,13 +40,16 @@ impl MethodTracer {
             | Web3Error::PrunedBlock(_)
             | Web3Error::PrunedL1Batch(_)
             | Web3Error::TooManyTopics
+            | Web3Error::TooManyAddresses(_)
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFilterBlockHash
+            | Web3Error::InvalidRewardPercentiles
+            | Web3Error::TooManyTraces(_)
             | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _)
             | Web3Error::SerializationError(_)
             | Web3Error::ProxyError(_) => 3,
-            Web3Error::TreeApiUnavailable => 6,
+            Web3Error::TreeApiUnavailable | Web3Error::TreeLagging(_) => 6,
         };
         let message = match err {
             // Do not expose internal error details to the client.
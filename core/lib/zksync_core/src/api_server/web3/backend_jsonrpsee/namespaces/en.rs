@@ -25,6 +25,15 @@ impl EnNamespaceServer for EnNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn block_certificate(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<en::BlockCertificate>> {
+        self.block_certificate_impl(block_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn sync_tokens(
         &self,
         block_number: Option<MiniblockNumber>,
@@ -1,7 +1,7 @@
 use zksync_types::{
     api::{
-        Block, BlockId, BlockIdVariant, BlockNumber, Log, Transaction, TransactionId,
-        TransactionReceipt, TransactionVariant,
+        Block, BlockId, BlockIdVariant, BlockNumber, HistoricalBlockIdVariant, Log, Transaction,
+        TransactionId, TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
     web3::types::{FeeHistory, Index, SyncState},
@@ -27,8 +27,12 @@ impl EthNamespaceServer for EthNamespace {
         Ok(self.chain_id_impl())
     }
 
-    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
-        self.call_impl(req, block.map(Into::into))
+    async fn call(
+        &self,
+        req: CallRequest,
+        block: Option<HistoricalBlockIdVariant>,
+    ) -> RpcResult<Bytes> {
+        self.call_impl(req, block)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
@@ -90,9 +94,9 @@ impl EthNamespaceServer for EthNamespace {
     async fn get_balance(
         &self,
         address: Address,
-        block: Option<BlockIdVariant>,
+        block: Option<HistoricalBlockIdVariant>,
     ) -> RpcResult<U256> {
-        self.get_balance_impl(address, block.map(Into::into))
+        self.get_balance_impl(address, block)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
@@ -144,8 +148,12 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
-    async fn get_code(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
-        self.get_code_impl(address, block.map(Into::into))
+    async fn get_code(
+        &self,
+        address: Address,
+        block: Option<HistoricalBlockIdVariant>,
+    ) -> RpcResult<Bytes> {
+        self.get_code_impl(address, block)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
@@ -154,9 +162,9 @@ impl EthNamespaceServer for EthNamespace {
         &self,
         address: Address,
         idx: U256,
-        block: Option<BlockIdVariant>,
+        block: Option<HistoricalBlockIdVariant>,
     ) -> RpcResult<H256> {
-        self.get_storage_at_impl(address, idx, block.map(Into::into))
+        self.get_storage_at_impl(address, idx, block)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
@@ -252,7 +260,7 @@ impl EthNamespaceServer for EthNamespace {
         &self,
         block_count: U64,
         newest_block: BlockNumber,
-        reward_percentiles: Vec<f32>,
+        reward_percentiles: Option<Vec<f32>>,
     ) -> RpcResult<FeeHistory> {
         self.fee_history_impl(block_count, newest_block, reward_percentiles)
             .await
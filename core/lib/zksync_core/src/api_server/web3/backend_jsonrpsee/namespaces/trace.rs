@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use zksync_types::debug_flat_call::LocalizedDebugCallFlat;
+use zksync_web3_decl::{
+    jsonrpsee::core::RpcResult, namespaces::TraceNamespaceServer, types::TraceFilter,
+};
+
+use crate::api_server::web3::namespaces::TraceNamespace;
+
+#[async_trait]
+impl TraceNamespaceServer for TraceNamespace {
+    async fn filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedDebugCallFlat>> {
+        self.filter_impl(filter)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+}
@@ -2,10 +2,11 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        BlockDetails, BlockIdVariant, BridgeAddresses, ContractExecutionStats, InteropMessageProof,
+        L1BatchDetails, L2ToL1LogProof, MempoolSender, MempoolStatus, MiniblockFeeInput, Proof,
+        ProtocolVersion, TransactionDetails, UnderfundedPriorityOp,
     },
-    fee::Fee,
+    fee::FeeEstimate,
     fee_model::FeeParams,
     transaction_request::CallRequest,
     Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
@@ -20,7 +21,7 @@ use crate::api_server::web3::ZksNamespace;
 
 #[async_trait]
 impl ZksNamespaceServer for ZksNamespace {
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate> {
         self.estimate_fee_impl(req)
             .await
             .map_err(|err| self.current_method().map_err(err))
@@ -89,6 +90,16 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_interop_message_proof(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> RpcResult<Option<InteropMessageProof>> {
+        self.get_interop_message_proof_impl(tx_hash, index)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_l1_batch_number(&self) -> RpcResult<U64> {
         self.get_l1_batch_number_impl()
             .await
@@ -167,4 +178,52 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
+
+    async fn get_nonces(
+        &self,
+        addresses: Vec<Address>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<U256>> {
+        self.get_nonces_impl(addresses, block.map(Into::into))
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_fee_history(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> RpcResult<Vec<MiniblockFeeInput>> {
+        self.get_fee_history_impl(from_miniblock, to_miniblock)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_underfunded_priority_ops(&self) -> RpcResult<Vec<UnderfundedPriorityOp>> {
+        self.get_underfunded_priority_ops_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_contract_execution_stats(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> RpcResult<Vec<ContractExecutionStats>> {
+        self.get_contract_execution_stats_impl(from_miniblock, to_miniblock)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn mempool_status(&self) -> RpcResult<MempoolStatus> {
+        self.mempool_status_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn mempool_content(&self) -> RpcResult<Vec<MempoolSender>> {
+        self.mempool_content_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
 }
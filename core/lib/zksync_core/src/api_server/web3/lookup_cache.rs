@@ -0,0 +1,159 @@
+use std::{
+    future::Future,
+    mem,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_state::{CacheValue, LruCache};
+use zksync_types::{api, MiniblockNumber, ProtocolVersionId, H256};
+
+/// Interval between checks for new protocol versions.
+const PROTOCOL_VERSION_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wrapper for values stored in [`LookupCaches`] that have a fixed in-memory size.
+#[derive(Debug, Clone)]
+struct Cached<T>(T);
+
+impl<K, T: Clone + Send + Sync> CacheValue<K> for Cached<T> {
+    fn cache_weight(&self) -> u32 {
+        mem::size_of::<T>() as u32
+    }
+}
+
+/// Used to cut the number of Postgres queries made by API servers. Stores results of lookups that cannot change
+/// once they are returned by Postgres: factory deps by bytecode hash, protocol versions by ID, and details
+/// of miniblocks in executed L1 batches (executed batches cannot be reverted). Since only immutable data is cached,
+/// the cache never returns data that is older than a previous response of the same API server.
+///
+/// The only mutable cached value is the ID of the latest protocol version. It's updated by a task polling Postgres,
+/// which also invalidates cached protocol versions once a protocol upgrade is detected.
+#[derive(Debug, Clone)]
+pub(crate) struct LookupCaches {
+    factory_deps: LruCache<H256, Vec<u8>>,
+    protocol_versions: LruCache<u16, Cached<api::ProtocolVersion>>,
+    block_details: LruCache<MiniblockNumber, Cached<api::BlockDetails>>,
+    latest_protocol_version: Arc<RwLock<Option<ProtocolVersionId>>>,
+}
+
+impl LookupCaches {
+    /// Creates caches with the specified total capacity in bytes. If the capacity is 0, caching is disabled.
+    pub fn new(
+        connection_pool: ConnectionPool<Core>,
+        capacity: u64,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> (Self, impl Future<Output = anyhow::Result<()>>) {
+        // Factory deps are the largest values; protocol versions are few and small.
+        let this = Self {
+            factory_deps: LruCache::new("api_factory_deps", capacity / 2),
+            protocol_versions: LruCache::new("api_protocol_versions", capacity / 4),
+            block_details: LruCache::new("api_block_details", capacity / 4),
+            latest_protocol_version: Arc::default(),
+        };
+        let updater = this.clone();
+
+        let update_task = async move {
+            if capacity == 0 {
+                // Caching is disabled; there's nothing to update.
+                stop_receiver.changed().await.ok();
+                return Ok(());
+            }
+
+            loop {
+                if *stop_receiver.borrow() {
+                    tracing::debug!("Stopping lookup cache updates");
+                    return Ok(());
+                }
+
+                let mut connection = connection_pool.read_connection_tagged("api").await?;
+                let latest_version = connection.protocol_versions_dal().last_version_id().await;
+                drop(connection);
+                updater.update_latest_protocol_version(latest_version);
+
+                tokio::time::sleep(PROTOCOL_VERSION_UPDATE_INTERVAL).await;
+            }
+        };
+
+        (this, update_task)
+    }
+
+    fn update_latest_protocol_version(&self, latest_version: Option<ProtocolVersionId>) {
+        let mut latest_protocol_version = self.latest_protocol_version.write().unwrap();
+        if *latest_protocol_version != latest_version {
+            if let Some(prev_version) = *latest_protocol_version {
+                tracing::info!(
+                    "Detected protocol upgrade from {prev_version:?} to {latest_version:?}; \
+                     invalidating cached protocol versions"
+                );
+            }
+            self.protocol_versions.clear();
+            *latest_protocol_version = latest_version;
+        }
+    }
+
+    pub fn get_factory_dep(&self, hash: H256) -> Option<Vec<u8>> {
+        self.factory_deps.get(&hash)
+    }
+
+    pub fn insert_factory_dep(&self, hash: H256, bytecode: Vec<u8>) {
+        self.factory_deps.insert(hash, bytecode);
+    }
+
+    /// Gets a cached protocol version. If `version_id` is not specified, returns the latest version.
+    pub fn get_protocol_version(&self, version_id: Option<u16>) -> Option<api::ProtocolVersion> {
+        let version_id = match version_id {
+            Some(id) => id,
+            None => (*self.latest_protocol_version.read().unwrap())? as u16,
+        };
+        Some(self.protocol_versions.get(&version_id)?.0)
+    }
+
+    pub fn insert_protocol_version(&self, version: api::ProtocolVersion) {
+        self.protocol_versions
+            .insert(version.version_id, Cached(version));
+    }
+
+    pub fn get_block_details(&self, number: MiniblockNumber) -> Option<api::BlockDetails> {
+        Some(self.block_details.get(&number)?.0)
+    }
+
+    /// Caches block details if they are immutable, i.e., the block is in an executed L1 batch.
+    pub fn insert_block_details(&self, details: api::BlockDetails) {
+        if matches!(details.base.status, api::BlockStatus::Verified) {
+            self.block_details.insert(details.number, Cached(details));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn protocol_versions_are_invalidated_on_upgrade() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let (caches, _) = LookupCaches::new(pool, 1 << 20, stop_receiver);
+
+        let version_id = ProtocolVersionId::latest();
+        caches.update_latest_protocol_version(Some(version_id));
+        let version = api::ProtocolVersion {
+            version_id: version_id as u16,
+            ..api::ProtocolVersion::default()
+        };
+        caches.insert_protocol_version(version.clone());
+        let cached = caches.get_protocol_version(None).unwrap();
+        assert_eq!(cached.version_id, version.version_id);
+        assert!(caches
+            .get_protocol_version(Some(version_id as u16))
+            .is_some());
+
+        caches.update_latest_protocol_version(Some(ProtocolVersionId::next()));
+        assert!(caches.get_protocol_version(None).is_none());
+        assert!(caches
+            .get_protocol_version(Some(version_id as u16))
+            .is_none());
+    }
+}
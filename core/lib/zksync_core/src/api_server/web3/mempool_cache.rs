@@ -44,7 +44,7 @@ impl MempoolCache {
                     .unwrap_or_else(|| chrono::Utc::now().naive_utc() - INITIAL_LOOKBEHIND);
 
                 let latency = MEMPOOL_CACHE_METRICS.db_poll_latency.start();
-                let mut connection = connection_pool.connection_tagged("api").await?;
+                let mut connection = connection_pool.read_connection_tagged("api").await?;
                 let txs = connection
                     .transactions_web3_dal()
                     .get_pending_txs_hashes_after(last_timestamp, None)
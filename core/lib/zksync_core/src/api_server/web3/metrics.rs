@@ -6,6 +6,8 @@ use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     Metrics, Unit,
 };
+use zksync_dal::SqlxError;
+use zksync_db_connection::utils::is_deadline_exceeded;
 use zksync_types::api;
 use zksync_web3_decl::error::Web3Error;
 
@@ -97,10 +99,14 @@ enum Web3ErrorKind {
     TransactionSerialization,
     Proxy,
     TooManyTopics,
+    TooManyAddresses,
     FilterNotFound,
     LogsLimitExceeded,
     InvalidFilterBlockHash,
+    InvalidRewardPercentiles,
+    TooManyTraces,
     TreeApiUnavailable,
+    TreeLagging,
     Internal,
 }
 
@@ -113,15 +119,31 @@ impl Web3ErrorKind {
             Web3Error::ProxyError(_) => Self::Proxy,
             Web3Error::SerializationError(_) => Self::TransactionSerialization,
             Web3Error::TooManyTopics => Self::TooManyTopics,
+            Web3Error::TooManyAddresses(_) => Self::TooManyAddresses,
             Web3Error::FilterNotFound => Self::FilterNotFound,
             Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
+            Web3Error::InvalidRewardPercentiles => Self::InvalidRewardPercentiles,
+            Web3Error::TooManyTraces(_) => Self::TooManyTraces,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
+            Web3Error::TreeLagging(_) => Self::TreeLagging,
             Web3Error::InternalError(_) | Web3Error::NotImplemented => Self::Internal,
         }
     }
 }
 
+/// Layer at which a request deadline was detected to be exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "layer", rename_all = "snake_case")]
+pub(in crate::api_server) enum DeadlineLayer {
+    /// JSON-RPC server middleware (i.e., the method handler was cancelled).
+    Rpc,
+    /// Postgres queries.
+    Dal,
+    /// VM execution in the sandbox.
+    Sandbox,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
 #[metrics(rename_all = "snake_case")]
 enum ProtocolErrorOrigin {
@@ -171,6 +193,8 @@ pub(in crate::api_server) struct ApiMetrics {
     web3_errors: Family<Web3ErrorLabels, Counter>,
     /// Number of protocol errors grouped by error code and method name. Method name is not set for "method not found" errors.
     web3_rpc_errors: Family<ProtocolErrorLabels, Counter>,
+    /// Number of requests that have exceeded their deadline, grouped by the layer at which this was detected.
+    pub deadline_exceeded: Family<DeadlineLayer, Counter>,
     /// Number of transaction submission errors for a specific submission error reason.
     #[metrics(labels = ["reason"])]
     pub submit_tx_error: LabeledFamily<&'static str, Counter>,
@@ -231,6 +255,13 @@ impl ApiMetrics {
         match err {
             Web3Error::InternalError(err) => {
                 tracing::error!("Internal error in method `{method}`: {err}");
+                let is_deadline_error = err
+                    .chain()
+                    .filter_map(|err| err.downcast_ref::<SqlxError>())
+                    .any(is_deadline_exceeded);
+                if is_deadline_error {
+                    self.deadline_exceeded[&DeadlineLayer::Dal].inc();
+                }
             }
             Web3Error::ProxyError(err) => {
                 tracing::warn!("Error proxying call to main node in method `{method}`: {err}");
@@ -262,6 +293,7 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    FeeParams,
 }
 
 #[derive(Debug, Metrics)]
@@ -1,4 +1,10 @@
-use std::{collections::HashSet, net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    num::NonZeroU32,
+    sync::{Arc, Weak},
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
@@ -10,8 +16,8 @@ use tokio::{
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_dal::{ConnectionPool, Core};
-use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_types::MiniblockNumber;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{MiniblockNumber, H256};
 use zksync_web3_decl::{
     jsonrpsee::{
         server::{BatchRequestConfig, RpcServiceBuilder, ServerBuilder},
@@ -19,23 +25,27 @@ use zksync_web3_decl::{
     },
     namespaces::{
         DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        NetNamespaceServer, SnapshotsNamespaceServer, TraceNamespaceServer, Web3NamespaceServer,
+        ZksNamespaceServer, ZksPubSubServer,
     },
     types::Filter,
 };
 
 use self::{
     backend_jsonrpsee::{
-        LimitMiddleware, MetadataMiddleware, MethodTracer, ShutdownMiddleware, TrafficTracker,
+        ClientRateLimitLayer, LimitMiddleware, MetadataMiddleware, MethodTracer,
+        NodeStateMiddleware, ServerLimitMiddleware, ServerLimits, ShutdownMiddleware,
+        TrafficTracker, Transport,
     },
+    lookup_cache::LookupCaches,
     mempool_cache::MempoolCache,
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace,
+        TraceNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
+    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber, TreeLagLimit},
 };
 use crate::{
     api_server::{
@@ -43,19 +53,24 @@ use crate::{
         tree::TreeApiClient,
         tx_sender::TxSender,
     },
-    sync_layer::SyncState,
+    metadata_calculator::TreeLagReceiver,
+    sync_layer::{NodeStateHandle, SyncState},
     utils::wait_for_l1_batch,
 };
 
 pub mod backend_jsonrpsee;
+mod lookup_cache;
 mod mempool_cache;
 pub(super) mod metrics;
 pub mod namespaces;
 mod pubsub;
+mod read_only;
 pub mod state;
 #[cfg(test)]
 pub(crate) mod tests;
 
+pub use self::read_only::ReadOnlyApiServer;
+
 /// Timeout for graceful shutdown logic within API servers.
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
@@ -95,6 +110,7 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Trace,
 }
 
 impl Namespace {
@@ -127,8 +143,16 @@ struct OptionalApiParams {
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    method_requests_per_minute_limit: Option<NonZeroU32>,
+    client_requests_per_minute_limit: Option<NonZeroU32>,
+    max_concurrent_requests: Option<usize>,
+    request_timeout: Option<Duration>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
+    tree_lag_limit: Option<TreeLagLimit>,
+    sync_block_signing_key: Option<H256>,
+    snapshot_header_signing_key: Option<H256>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    node_state: Option<NodeStateHandle>,
 }
 
 /// Structure capable of spawning a configured Web3 API server along with all the required
@@ -238,11 +262,53 @@ impl ApiBuilder {
         self
     }
 
+    /// Limits the number of requests per minute for each RPC method. The limit is shared by all clients
+    /// of the server; requests exceeding it are rejected with the "limit exceeded" error (-32005).
+    pub fn with_method_requests_per_minute_limit(
+        mut self,
+        method_requests_per_minute_limit: NonZeroU32,
+    ) -> Self {
+        self.optional.method_requests_per_minute_limit = Some(method_requests_per_minute_limit);
+        self
+    }
+
+    /// Limits the number of requests per minute from a single client IP address, as reported by a reverse proxy
+    /// in the `X-Forwarded-For` header. Requests exceeding the limit are rejected with the 429 HTTP status
+    /// and the "limit exceeded" error (-32005). For the WS server, the limit applies to connection handshakes.
+    pub fn with_client_requests_per_minute_limit(
+        mut self,
+        client_requests_per_minute_limit: NonZeroU32,
+    ) -> Self {
+        self.optional.client_requests_per_minute_limit = Some(client_requests_per_minute_limit);
+        self
+    }
+
+    /// Limits the number of requests handled by the server concurrently. Once the limit is reached,
+    /// new requests are shed with the "limit exceeded" error (-32005) until some requests complete.
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.optional.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Sets the timeout for handling a single JSON-RPC request. Once the timeout has passed, the request handler
+    /// is cancelled, and VM execution and DB queries performed for the request are aborted.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.optional.request_timeout = Some(request_timeout);
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
     }
 
+    /// Makes the server reject all methods except for ones depending only on the static node configuration
+    /// with a "node is syncing" error while the node is not [ready](crate::sync_layer::NodeState::Ready).
+    pub fn with_node_state(mut self, node_state: NodeStateHandle) -> Self {
+        self.optional.node_state = Some(node_state);
+        self
+    }
+
     pub fn with_polling_interval(mut self, polling_interval: Duration) -> Self {
         self.polling_interval = polling_interval;
         self
@@ -259,6 +325,35 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables monitoring the Merkle tree lag. If the lag exceeds `max_lag` L1 batches, the server health
+    /// is changed to "affected"; additionally, if `reject_proofs` is set, `zks_getProof` requests are rejected
+    /// until the tree catches up.
+    pub fn with_tree_lag_limit(
+        mut self,
+        tree_lag: TreeLagReceiver,
+        max_lag: u32,
+        reject_proofs: bool,
+    ) -> Self {
+        self.optional.tree_lag_limit = Some(TreeLagLimit {
+            tree_lag,
+            max_lag,
+            reject_proofs,
+        });
+        self
+    }
+
+    /// Enables signing L2 blocks returned by `en_syncL2Block` with the specified private key.
+    pub fn with_sync_block_signing_key(mut self, private_key: H256) -> Self {
+        self.optional.sync_block_signing_key = Some(private_key);
+        self
+    }
+
+    /// Enables signing snapshot headers returned by the `snapshots` namespace with the specified private key.
+    pub fn with_snapshot_header_signing_key(mut self, private_key: H256) -> Self {
+        self.optional.snapshot_header_signing_key = Some(private_key);
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -310,8 +405,9 @@ impl ApiServer {
         self,
         last_sealed_miniblock: SealedMiniblockNumber,
         mempool_cache: MempoolCache,
+        lookup_caches: LookupCaches,
     ) -> anyhow::Result<RpcState> {
-        let mut storage = self.updaters_pool.connection_tagged("api").await?;
+        let mut storage = self.updaters_pool.read_connection_tagged("api").await?;
         let start_info = BlockStartInfo::new(&mut storage).await?;
         drop(storage);
 
@@ -334,8 +430,12 @@ impl ApiServer {
             api_config: self.config,
             start_info,
             mempool_cache,
+            lookup_caches,
             last_sealed_miniblock,
             tree_api: self.optional.tree_api,
+            tree_lag_limit: self.optional.tree_lag_limit,
+            sync_block_signing_key: self.optional.sync_block_signing_key,
+            snapshot_header_signing_key: self.optional.snapshot_header_signing_key,
         })
     }
 
@@ -344,17 +444,20 @@ impl ApiServer {
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
         mempool_cache: MempoolCache,
+        lookup_caches: LookupCaches,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
         let rpc_state = self
-            .build_rpc_state(last_sealed_miniblock, mempool_cache)
+            .build_rpc_state(last_sealed_miniblock, mempool_cache, lookup_caches)
             .await?;
 
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
         if let Some(pub_sub) = pub_sub {
-            rpc.merge(pub_sub.into_rpc())
+            rpc.merge(ZksPubSubServer::into_rpc(pub_sub.clone()))
+                .expect("Can't merge zks pubsub namespace");
+            rpc.merge(EthPubSubServer::into_rpc(pub_sub))
                 .expect("Can't merge eth pubsub namespace");
         }
 
@@ -382,6 +485,10 @@ impl ApiServer {
             rpc.merge(DebugNamespace::new(rpc_state.clone()).await.into_rpc())
                 .expect("Can't merge debug namespace");
         }
+        if namespaces.contains(&Namespace::Trace) {
+            rpc.merge(TraceNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge trace namespace");
+        }
         if namespaces.contains(&Namespace::Snapshots) {
             rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
                 .expect("Can't merge snapshots namespace");
@@ -468,6 +575,13 @@ impl ApiServer {
 
         tasks.push(tokio::spawn(mempool_cache_update_task));
 
+        let (lookup_caches, lookup_caches_update_task) = LookupCaches::new(
+            self.updaters_pool.clone(),
+            self.config.lookup_cache_size as u64,
+            stop_receiver.clone(),
+        );
+        tasks.push(tokio::spawn(lookup_caches_update_task));
+
         let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
@@ -478,6 +592,7 @@ impl ApiServer {
 
             tasks.extend(pub_sub.spawn_notifiers(
                 self.pool.clone(),
+                self.tx_sender.0.batch_fee_input_provider.clone(),
                 self.polling_interval,
                 stop_receiver.clone(),
             ));
@@ -494,6 +609,7 @@ impl ApiServer {
             stop_receiver,
             pub_sub,
             mempool_cache,
+            lookup_caches,
             last_sealed_miniblock,
             local_addr_sender,
         ));
@@ -506,11 +622,58 @@ impl ApiServer {
         })
     }
 
+    /// Marks the server as affected while the Merkle tree lag exceeds the configured limit.
+    async fn monitor_tree_lag(
+        mut tree_lag_limit: TreeLagLimit,
+        health_updater: Weak<HealthUpdater>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) {
+        let mut was_lagging = false;
+        loop {
+            let tree_lag = tree_lag_limit.excessive_lag();
+            let is_lagging = tree_lag.is_some();
+            if is_lagging != was_lagging {
+                let Some(health_updater) = health_updater.upgrade() else {
+                    return; // The server has stopped
+                };
+                if *stop_receiver.borrow() {
+                    return; // Do not override the "shutting down" status
+                }
+                let health = if let Some(tree_lag) = tree_lag {
+                    tracing::warn!(
+                        "Merkle tree lags {tree_lag} L1 batches behind, which exceeds the limit of {} L1 batches",
+                        tree_lag_limit.max_lag
+                    );
+                    Health::from(HealthStatus::Affected).with_details(serde_json::json!({
+                        "tree_lag": tree_lag,
+                        "max_tree_lag": tree_lag_limit.max_lag,
+                    }))
+                } else {
+                    tracing::info!("Merkle tree has caught up; tree lag is within the limit");
+                    HealthStatus::Ready.into()
+                };
+                health_updater.update(health);
+                was_lagging = is_lagging;
+            }
+
+            tokio::select! {
+                _ = stop_receiver.changed() => return,
+                lag = tree_lag_limit.tree_lag.changed() => {
+                    if lag.is_none() {
+                        tracing::info!("Metadata calculator has stopped; stopped monitoring tree lag");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
     async fn run_jsonrpsee_server(
         self,
         mut stop_receiver: watch::Receiver<bool>,
         pub_sub: Option<EthSubscribe>,
         mempool_cache: MempoolCache,
+        lookup_caches: LookupCaches,
         last_sealed_miniblock: SealedMiniblockNumber,
         local_addr_sender: oneshot::Sender<SocketAddr>,
     ) -> anyhow::Result<()> {
@@ -549,19 +712,33 @@ impl ApiServer {
             .response_body_size_limit
             .map_or(u32::MAX, |limit| limit as u32);
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let server_limits = Arc::new(ServerLimits::new(
+            self.optional.method_requests_per_minute_limit,
+            self.optional.max_concurrent_requests,
+        ));
+        let client_requests_per_minute_limit = self.optional.client_requests_per_minute_limit;
         let subscriptions_limit = self.optional.subscriptions_limit;
+        let request_timeout = self.optional.request_timeout;
+        let node_state = self.optional.node_state.clone();
         let vm_barrier = self.optional.vm_barrier.clone();
+        let tree_lag_limit = self.optional.tree_lag_limit.clone();
         let health_updater = self.health_updater.clone();
         let method_tracer = self.method_tracer.clone();
 
         let rpc = self
-            .build_rpc_module(pub_sub, last_sealed_miniblock, mempool_cache)
+            .build_rpc_module(pub_sub, last_sealed_miniblock, mempool_cache, lookup_caches)
             .await?;
         let registered_method_names = Arc::new(rpc.method_names().collect::<HashSet<_>>());
         tracing::debug!(
             "Built RPC module for {transport_str} server with {} methods: {registered_method_names:?}",
             registered_method_names.len()
         );
+        let method_names_for_limits = registered_method_names.clone();
+        let limits_transport = if is_http {
+            Transport::Http
+        } else {
+            Transport::Ws
+        };
 
         // Setup CORS.
         let cors = is_http.then(|| {
@@ -580,10 +757,13 @@ impl ApiServer {
                 future::ready(())
             }),
         );
+        let client_rate_limit = client_requests_per_minute_limit
+            .map(|limit| ClientRateLimitLayer::new(limit, limits_transport));
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(client_rate_limit);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -598,13 +778,31 @@ impl ApiServer {
                 ShutdownMiddleware::new(svc, traffic_tracker_for_middleware.clone())
             })
             .layer_fn(move |svc| {
-                MetadataMiddleware::new(svc, registered_method_names.clone(), method_tracer.clone())
+                MetadataMiddleware::new(
+                    svc,
+                    registered_method_names.clone(),
+                    method_tracer.clone(),
+                    request_timeout,
+                )
             })
+            .option_layer(node_state.map(|node_state| {
+                tower::layer::layer_fn(move |svc| NodeStateMiddleware::new(svc, node_state.clone()))
+            }))
             .option_layer((!is_http).then(|| {
                 tower::layer::layer_fn(move |svc| {
                     LimitMiddleware::new(svc, websocket_requests_per_minute_limit)
                 })
-            }));
+            }))
+            // Per-connection WS limits are checked first, so that requests rejected by them
+            // don't consume quotas shared by all clients.
+            .layer_fn(move |svc| {
+                ServerLimitMiddleware::new(
+                    svc,
+                    server_limits.clone(),
+                    method_names_for_limits.clone(),
+                    limits_transport,
+                )
+            });
 
         let server_builder = ServerBuilder::default()
             .max_connections(max_connections as u32)
@@ -636,6 +834,13 @@ impl ApiServer {
         tracing::info!("Initialized {transport_str} API on {local_addr:?}");
         local_addr_sender.send(local_addr).ok();
         health_updater.update(HealthStatus::Ready.into());
+        if let Some(tree_lag_limit) = tree_lag_limit {
+            tokio::spawn(Self::monitor_tree_lag(
+                tree_lag_limit,
+                Arc::downgrade(&health_updater),
+                stop_receiver.clone(),
+            ));
+        }
 
         // We want to be able to immediately stop the server task if the server stops on its own for whatever reason.
         // Hence, we monitor `stop_receiver` on a separate Tokio task.
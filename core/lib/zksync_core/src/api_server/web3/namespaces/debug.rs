@@ -1,19 +1,28 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::Context as _;
-use multivm::{interface::ExecutionResult, vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT};
+use multivm::{
+    interface::ExecutionResult, tracers::prestate_tracer,
+    vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT,
+};
 use once_cell::sync::OnceCell;
-use zksync_dal::CoreDal;
+use zksync_dal::{Connection, Core, CoreDal};
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, CallTracerConfig, DebugCall, DebugTrace, PrestateAccount,
+        PrestateTrace, ResultDebugCall, SupportedTracers, TracerConfig,
+    },
     debug_flat_call::{flatten_debug_calls, DebugCallFlat},
     fee_model::BatchFeeInput,
     l2::L2Tx,
     transaction_request::CallRequest,
+    utils::decompose_full_nonce,
     vm_trace::Call,
-    AccountTreeId, H256,
+    web3::types::Bytes,
+    AccountTreeId, Address, MiniblockNumber, Transaction, H256,
 };
+use zksync_utils::u256_to_h256;
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
@@ -61,33 +70,68 @@ impl DebugNamespace {
         &self,
         block_id: BlockId,
         options: Option<TracerConfig>,
-    ) -> Result<Vec<ResultDebugCall>, Web3Error> {
+    ) -> Result<Vec<ResultDebugCall<DebugTrace>>, Web3Error> {
         self.current_method().set_block_id(block_id);
 
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
+        let (tracer, tracer_config) = tracer_options(options);
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
         self.current_method()
             .set_block_diff(self.state.last_sealed_miniblock.diff(block_number));
 
+        let traces: Vec<_> = match tracer {
+            SupportedTracers::CallTracer => {
+                Self::trace_block_calls(&mut connection, block_number, tracer_config.only_top_call)
+                    .await?
+                    .into_iter()
+                    .map(DebugTrace::Call)
+                    .collect()
+            }
+            SupportedTracers::PrestateTracer => {
+                let txs = connection
+                    .transactions_web3_dal()
+                    .get_raw_miniblock_transactions(block_number)
+                    .await
+                    .context("get_raw_miniblock_transactions")?;
+                drop(connection);
+                self.trace_prestate(block_number, txs, 0, tracer_config.diff_mode)
+                    .await?
+                    .into_iter()
+                    .map(DebugTrace::Prestate)
+                    .collect()
+            }
+        };
+        Ok(traces
+            .into_iter()
+            .map(|result| ResultDebugCall { result })
+            .collect())
+    }
+
+    async fn trace_block_calls(
+        connection: &mut Connection<'_, Core>,
+        block_number: MiniblockNumber,
+        only_top_call: bool,
+    ) -> Result<Vec<DebugCall>, Web3Error> {
         let call_traces = connection
             .blocks_web3_dal()
             .get_traces_for_miniblock(block_number)
             .await
             .context("get_traces_for_miniblock")?;
-        let call_trace = call_traces
+        let call_traces = call_traces
             .into_iter()
             .map(|call_trace| {
                 let mut result: DebugCall = call_trace.into();
                 if only_top_call {
                     result.calls = vec![];
                 }
-                ResultDebugCall { result }
+                result
             })
             .collect();
-        Ok(call_trace)
+        Ok(call_traces)
     }
 
     #[tracing::instrument(skip(self))]
@@ -96,7 +140,24 @@ impl DebugNamespace {
         block_id: BlockId,
         options: Option<TracerConfig>,
     ) -> Result<Vec<DebugCallFlat>, Web3Error> {
-        let call_trace = self.debug_trace_block_impl(block_id, options).await?;
+        self.current_method().set_block_id(block_id);
+
+        let (_, tracer_config) = tracer_options(options);
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        self.current_method()
+            .set_block_diff(self.state.last_sealed_miniblock.diff(block_number));
+
+        let call_trace =
+            Self::trace_block_calls(&mut connection, block_number, tracer_config.only_top_call)
+                .await?
+                .into_iter()
+                .map(|result| ResultDebugCall { result })
+                .collect();
         let call_trace_flat = flatten_debug_calls(call_trace);
         Ok(call_trace_flat)
     }
@@ -106,23 +167,150 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Result<Option<DebugCall>, Web3Error> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
-        let call_trace = connection
-            .transactions_dal()
-            .get_call_trace(tx_hash)
+    ) -> Result<Option<DebugTrace>, Web3Error> {
+        let (tracer, tracer_config) = tracer_options(options);
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+
+        match tracer {
+            SupportedTracers::CallTracer => {
+                let call_trace = connection
+                    .transactions_dal()
+                    .get_call_trace(tx_hash)
+                    .await
+                    .context("get_call_trace")?;
+                Ok(call_trace.map(|call_trace| {
+                    let mut result: DebugCall = call_trace.into();
+                    if tracer_config.only_top_call {
+                        result.calls = vec![];
+                    }
+                    DebugTrace::Call(result)
+                }))
+            }
+            SupportedTracers::PrestateTracer => {
+                let tx = connection
+                    .transactions_web3_dal()
+                    .get_transaction_by_hash(tx_hash, self.state.api_config.l2_chain_id)
+                    .await
+                    .context("get_transaction_by_hash")?;
+                let Some(block_number) = tx.and_then(|tx| tx.block_number) else {
+                    return Ok(None); // The transaction is unknown or not included in a miniblock yet
+                };
+                let block_number = MiniblockNumber(block_number.as_u32());
+                let mut txs = connection
+                    .transactions_web3_dal()
+                    .get_raw_miniblock_transactions(block_number)
+                    .await
+                    .context("get_raw_miniblock_transactions")?;
+                drop(connection);
+
+                let tx_index = txs
+                    .iter()
+                    .position(|tx| tx.hash() == tx_hash)
+                    .with_context(|| {
+                        format!("transaction {tx_hash:?} is missing from miniblock #{block_number}")
+                    })?;
+                txs.truncate(tx_index + 1);
+                let mut traces = self
+                    .trace_prestate(block_number, txs, tx_index, tracer_config.diff_mode)
+                    .await?;
+                Ok(traces.pop().map(DebugTrace::Prestate))
+            }
+        }
+    }
+
+    /// Replays transactions from the specified miniblock with `prestateTracer`. `txs` must be a prefix
+    /// of the miniblock transactions; only transactions starting from `first_traced_tx` are traced.
+    async fn trace_prestate(
+        &self,
+        block_number: MiniblockNumber,
+        txs: Vec<Transaction>,
+        first_traced_tx: usize,
+        diff_mode: bool,
+    ) -> Result<Vec<PrestateTrace>, Web3Error> {
+        if txs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let base_fee = connection
+            .blocks_dal()
+            .get_miniblock_header(block_number)
             .await
-            .context("get_call_trace")?;
-        Ok(call_trace.map(|call_trace| {
-            let mut result: DebugCall = call_trace.into();
-            if only_top_call {
-                result.calls = vec![];
+            .context("get_miniblock_header")?
+            .ok_or(Web3Error::NoBlock)?
+            .base_fee_per_gas;
+        // Transactions are replayed on top of the state at the end of the previous miniblock. The genesis miniblock
+        // has no transactions, so the subtraction cannot underflow.
+        let prev_block_id = BlockId::Number(BlockNumber::Number((block_number.0 - 1).into()));
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, prev_block_id)
+            .await?;
+        drop(connection);
+
+        let deadline = self.current_method().deadline();
+        let mut tracer_results = vec![];
+        let mut replayed_txs = Vec::with_capacity(txs.len());
+        for (i, tx) in txs.into_iter().enumerate() {
+            let mut custom_tracers = vec![];
+            if i >= first_traced_tx {
+                let result = Arc::new(OnceCell::default());
+                tracer_results.push(result.clone());
+                custom_tracers.push(ApiTracer::Prestate { diff_mode, result });
+            }
+            if let Some(deadline) = deadline {
+                custom_tracers.push(ApiTracer::Deadline(deadline));
             }
-            result
-        }))
+            replayed_txs.push((tx, custom_tracers));
+        }
+
+        let vm_permit = self
+            .state
+            .tx_sender
+            .vm_concurrency_limiter()
+            .acquire()
+            .await;
+        let vm_permit = vm_permit.context("cannot acquire VM permit")?;
+        let executor = &self.state.tx_sender.0.executor;
+        executor
+            .replay_txs_in_sandbox(
+                vm_permit,
+                self.shared_args(),
+                self.state.connection_pool.clone(),
+                replayed_txs,
+                block_args,
+                base_fee,
+            )
+            .await?;
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let mut traces = Vec::with_capacity(tracer_results.len());
+        for result in tracer_results {
+            let (pre, post) = result
+                .get()
+                .cloned()
+                .context("prestate tracer didn't return a result")?;
+            let post = prestate_accounts(&mut connection, post).await?;
+            traces.push(if diff_mode {
+                let pre = prestate_accounts(&mut connection, pre).await?;
+                PrestateTrace::Diff { pre, post }
+            } else {
+                PrestateTrace::Prestate(post)
+            });
+        }
+        Ok(traces)
     }
 
     #[tracing::instrument(skip(self, request, block_id))]
@@ -135,11 +323,17 @@ impl DebugNamespace {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         self.current_method().set_block_id(block_id);
 
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let (tracer, tracer_config) = tracer_options(options);
+        if !matches!(tracer, SupportedTracers::CallTracer) {
+            return Err(Web3Error::NotImplemented);
+        }
+        let only_top_call = tracer_config.only_top_call;
 
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let block_args = self
             .state
             .resolve_block_args(&mut connection, block_id)
@@ -164,11 +358,14 @@ impl DebugNamespace {
 
         // We don't need properly trace if we only need top call
         let call_tracer_result = Arc::new(OnceCell::default());
-        let custom_tracers = if only_top_call {
+        let mut custom_tracers = if only_top_call {
             vec![]
         } else {
             vec![ApiTracer::CallTracer(call_tracer_result.clone())]
         };
+        if let Some(deadline) = self.current_method().deadline() {
+            custom_tracers.push(ApiTracer::Deadline(deadline));
+        }
 
         let executor = &self.state.tx_sender.0.executor;
         let result = executor
@@ -223,3 +420,40 @@ impl DebugNamespace {
         }
     }
 }
+
+fn tracer_options(options: Option<TracerConfig>) -> (SupportedTracers, CallTracerConfig) {
+    options.map_or_else(
+        || (SupportedTracers::CallTracer, CallTracerConfig::default()),
+        |options| (options.tracer, options.tracer_config),
+    )
+}
+
+/// Converts the state reported by the VM tracer to the format used by `prestateTracer` in geth.
+async fn prestate_accounts(
+    connection: &mut Connection<'_, Core>,
+    state: prestate_tracer::State,
+) -> anyhow::Result<HashMap<Address, PrestateAccount>> {
+    let mut accounts = HashMap::with_capacity(state.len());
+    for (address, account) in state {
+        // The VM reports the bytecode hash, while geth returns the bytecode itself.
+        let code = match account.code {
+            Some(code_hash) if !code_hash.is_zero() => connection
+                .factory_deps_dal()
+                .get_factory_dep(u256_to_h256(code_hash))
+                .await
+                .context("get_factory_dep")?
+                .map(Bytes),
+            _ => None,
+        };
+        let account = PrestateAccount {
+            balance: account.balance,
+            nonce: account
+                .nonce
+                .map(|nonce| decompose_full_nonce(nonce).0.as_u64()),
+            code,
+            storage: account.storage.unwrap_or_default(),
+        };
+        accounts.insert(address, account);
+    }
+    Ok(accounts)
+}
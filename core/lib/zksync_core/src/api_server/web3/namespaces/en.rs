@@ -1,5 +1,6 @@
 use anyhow::Context as _;
 use zksync_config::{configs::genesis::SharedBridge, GenesisConfig};
+use zksync_consensus_roles::validator;
 use zksync_dal::CoreDal;
 use zksync_types::{api::en, tokens::TokenInfo, L1BatchNumber, MiniblockNumber, H256};
 use zksync_web3_decl::error::Web3Error;
@@ -22,7 +23,7 @@ impl EnNamespace {
         let Some(genesis) = self
             .state
             .connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await?
             .consensus_dal()
             .genesis()
@@ -35,6 +36,27 @@ impl EnNamespace {
         )))
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn block_certificate_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<en::BlockCertificate>, Web3Error> {
+        let Some(certificate) = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?
+            .consensus_dal()
+            .certificate(validator::BlockNumber(block_number.0.into()))
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(en::BlockCertificate(
+            zksync_protobuf::serde::serialize(&certificate, serde_json::value::Serializer).unwrap(),
+        )))
+    }
+
     pub(crate) fn current_method(&self) -> &MethodTracer {
         &self.state.current_method
     }
@@ -45,12 +67,24 @@ impl EnNamespace {
         block_number: MiniblockNumber,
         include_transactions: bool,
     ) -> Result<Option<en::SyncBlock>, Web3Error> {
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
-        Ok(storage
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let mut block = storage
             .sync_dal()
             .sync_block(block_number, include_transactions)
             .await
-            .context("sync_block")?)
+            .context("sync_block")?;
+        drop(storage);
+
+        if let (Some(block), Some(private_key)) = (&mut block, &self.state.sync_block_signing_key) {
+            block
+                .sign(self.state.api_config.l2_chain_id, private_key)
+                .context("failed signing L2 block")?;
+        }
+        Ok(block)
     }
 
     #[tracing::instrument(skip(self))]
@@ -58,7 +92,11 @@ impl EnNamespace {
         &self,
         block_number: Option<MiniblockNumber>,
     ) -> Result<Vec<TokenInfo>, Web3Error> {
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         Ok(storage
             .tokens_web3_dal()
             .get_all_tokens(block_number)
@@ -69,7 +107,11 @@ impl EnNamespace {
     #[tracing::instrument(skip(self))]
     pub async fn genesis_config_impl(&self) -> Result<GenesisConfig, Web3Error> {
         // If this method will cause some load, we can cache everything in memory
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let genesis_batch = storage
             .blocks_dal()
             .get_storage_l1_batch(L1BatchNumber(0))
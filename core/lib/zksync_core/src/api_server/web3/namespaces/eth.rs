@@ -3,8 +3,8 @@ use zksync_dal::CoreDal;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        BlockId, BlockNumber, GetLogsFilter, HistoricalBlockIdVariant, Transaction, TransactionId,
+        TransactionReceipt, TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
     transaction_request::CallRequest,
@@ -44,7 +44,11 @@ impl EthNamespace {
 
     #[tracing::instrument(skip(self))]
     pub async fn get_block_number_impl(&self) -> Result<U64, Web3Error> {
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let block_number = storage
             .blocks_dal()
             .get_sealed_miniblock_number()
@@ -58,12 +62,23 @@ impl EthNamespace {
     pub async fn call_impl(
         &self,
         request: CallRequest,
-        block_id: Option<BlockId>,
+        block_id: Option<HistoricalBlockIdVariant>,
     ) -> Result<Bytes, Web3Error> {
-        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let block_id = match block_id {
+            Some(block_id) => {
+                self.state
+                    .resolve_historical_block_id(&mut connection, block_id)
+                    .await?
+            }
+            None => BlockId::Number(BlockNumber::Pending),
+        };
         self.current_method().set_block_id(block_id);
 
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
         let block_args = self
             .state
             .resolve_block_args(&mut connection, block_id)
@@ -76,7 +91,12 @@ impl EthNamespace {
         drop(connection);
 
         let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await?;
+        let deadline = self.current_method().deadline();
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, deadline)
+            .await?;
         Ok(call_result.into())
     }
 
@@ -123,12 +143,18 @@ impl EthNamespace {
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
 
+        let deadline = self.current_method().deadline();
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation as u64)
+            .get_txs_fee_in_wei(
+                tx.into(),
+                scale_factor,
+                acceptable_overestimation as u64,
+                deadline,
+            )
             .await?;
-        Ok(fee.gas_limit)
+        Ok(fee.fee.gas_limit)
     }
 
     #[tracing::instrument(skip(self))]
@@ -141,12 +167,23 @@ impl EthNamespace {
     pub async fn get_balance_impl(
         &self,
         address: Address,
-        block_id: Option<BlockId>,
+        block_id: Option<HistoricalBlockIdVariant>,
     ) -> Result<U256, Web3Error> {
-        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let block_id = match block_id {
+            Some(block_id) => {
+                self.state
+                    .resolve_historical_block_id(&mut connection, block_id)
+                    .await?
+            }
+            None => BlockId::Number(BlockNumber::Pending),
+        };
         self.current_method().set_block_id(block_id);
 
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
 
         let balance = connection
@@ -218,7 +255,11 @@ impl EthNamespace {
         self.current_method().set_block_id(block_id);
         self.state.start_info.ensure_not_pruned(block_id)?;
 
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let Some(block_number) = self
             .state
             .resolve_block_unchecked(&mut storage, block_id)
@@ -280,7 +321,11 @@ impl EthNamespace {
         self.current_method().set_block_id(block_id);
         self.state.start_info.ensure_not_pruned(block_id)?;
 
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let Some(block_number) = self
             .state
             .resolve_block_unchecked(&mut storage, block_id)
@@ -308,7 +353,11 @@ impl EthNamespace {
         self.current_method().set_block_id(block_id);
         self.state.start_info.ensure_not_pruned(block_id)?;
 
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let Some(block_number) = self
             .state
             .resolve_block_unchecked(&mut storage, block_id)
@@ -339,12 +388,23 @@ impl EthNamespace {
     pub async fn get_code_impl(
         &self,
         address: Address,
-        block_id: Option<BlockId>,
+        block_id: Option<HistoricalBlockIdVariant>,
     ) -> Result<Bytes, Web3Error> {
-        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let block_id = match block_id {
+            Some(block_id) => {
+                self.state
+                    .resolve_historical_block_id(&mut connection, block_id)
+                    .await?
+            }
+            None => BlockId::Number(BlockNumber::Pending),
+        };
         self.current_method().set_block_id(block_id);
 
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
         self.set_block_diff(block_number);
 
@@ -366,13 +426,24 @@ impl EthNamespace {
         &self,
         address: Address,
         idx: U256,
-        block_id: Option<BlockId>,
+        block_id: Option<HistoricalBlockIdVariant>,
     ) -> Result<H256, Web3Error> {
-        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let block_id = match block_id {
+            Some(block_id) => {
+                self.state
+                    .resolve_historical_block_id(&mut connection, block_id)
+                    .await?
+            }
+            None => BlockId::Number(BlockNumber::Pending),
+        };
         self.current_method().set_block_id(block_id);
 
         let storage_key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(idx));
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
         self.set_block_diff(block_number);
         let value = connection
@@ -393,7 +464,11 @@ impl EthNamespace {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         self.current_method().set_block_id(block_id);
 
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
 
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
         self.set_block_diff(block_number);
@@ -435,7 +510,11 @@ impl EthNamespace {
         &self,
         id: TransactionId,
     ) -> Result<Option<Transaction>, Web3Error> {
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let chain_id = self.state.api_config.l2_chain_id;
         let mut transaction = match id {
             TransactionId::Hash(hash) => storage
@@ -480,7 +559,7 @@ impl EthNamespace {
         let receipts = self
             .state
             .connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await?
             .transactions_web3_dal()
             .get_transaction_receipts(&[hash])
@@ -496,7 +575,11 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
-        let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let last_block_number = storage
             .blocks_dal()
             .get_sealed_miniblock_number()
@@ -633,10 +716,13 @@ impl EthNamespace {
         &self,
         block_count: U64,
         newest_block: BlockNumber,
-        reward_percentiles: Vec<f32>,
+        reward_percentiles: Option<Vec<f32>>,
     ) -> Result<FeeHistory, Web3Error> {
         self.current_method()
             .set_block_id(BlockId::Number(newest_block));
+        if let Some(percentiles) = &reward_percentiles {
+            validate_reward_percentiles(percentiles)?;
+        }
 
         // Limit `block_count`.
         let block_count = block_count
@@ -644,37 +730,81 @@ impl EthNamespace {
             .min(self.state.api_config.fee_history_limit)
             .max(1);
 
-        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let newest_miniblock = self
             .state
             .resolve_block(&mut connection, BlockId::Number(newest_block))
             .await?;
         self.set_block_diff(newest_miniblock);
 
-        let mut base_fee_per_gas = connection
+        let mut miniblocks = connection
             .blocks_web3_dal()
             .get_fee_history(newest_miniblock, block_count)
             .await
             .context("get_fee_history")?;
-        // DAL method returns fees in DESC order while we need ASC.
-        base_fee_per_gas.reverse();
-
-        let oldest_block = newest_miniblock.0 + 1 - base_fee_per_gas.len() as u32;
-        // We do not store gas used ratio for blocks, returns array of zeroes as a placeholder.
-        let gas_used_ratio = vec![0.0; base_fee_per_gas.len()];
-        // Effective priority gas price is currently 0.
-        let reward = Some(vec![
-            vec![U256::zero(); reward_percentiles.len()];
-            base_fee_per_gas.len()
-        ]);
+        // DAL method returns miniblocks in DESC order while we need ASC.
+        miniblocks.reverse();
+
+        let (Some(&(oldest_block, ..)), Some(&(last_block, last_base_fee, _))) =
+            (miniblocks.first(), miniblocks.last())
+        else {
+            // No miniblocks are stored yet, e.g. on a node recovered from a snapshot.
+            return Ok(FeeHistory {
+                oldest_block: web3::types::BlockNumber::Number(newest_miniblock.0.into()),
+                base_fee_per_gas: vec![],
+                gas_used_ratio: vec![],
+                reward: reward_percentiles.map(|_| vec![]),
+            });
+        };
+        let transactions = connection
+            .blocks_web3_dal()
+            .get_fee_history_transactions(oldest_block..=last_block)
+            .await
+            .context("get_fee_history_transactions")?;
+
+        let mut base_fee_per_gas = Vec::with_capacity(miniblocks.len() + 1);
+        let mut gas_used_ratio = Vec::with_capacity(miniblocks.len());
+        let mut reward = Vec::with_capacity(miniblocks.len());
+        let mut transactions = transactions.as_slice();
+        for &(number, base_fee, gas_limit) in &miniblocks {
+            // Transactions are ordered by miniblock number, so the ones for the current miniblock are at the start.
+            let split_idx =
+                transactions.partition_point(|&(tx_miniblock, ..)| tx_miniblock <= number);
+            let (miniblock_transactions, rest) = transactions.split_at(split_idx);
+            transactions = rest;
+
+            let mut priority_fees: Vec<_> = miniblock_transactions
+                .iter()
+                .map(|&(_, effective_gas_price, gas_used)| {
+                    (effective_gas_price.saturating_sub(base_fee), gas_used)
+                })
+                .collect();
+            let gas_used = priority_fees
+                .iter()
+                .fold(U256::zero(), |acc, &(_, gas_used)| acc + gas_used);
+
+            base_fee_per_gas.push(base_fee);
+            gas_used_ratio.push(if gas_limit == 0 {
+                0.0
+            } else {
+                gas_used.min(U256::from(gas_limit)).as_u64() as f64 / gas_limit as f64
+            });
+            if let Some(percentiles) = &reward_percentiles {
+                reward.push(compute_rewards(&mut priority_fees, gas_used, percentiles));
+            }
+        }
 
         // `base_fee_per_gas` for next miniblock cannot be calculated, appending last fee as a placeholder.
-        base_fee_per_gas.push(*base_fee_per_gas.last().unwrap());
+        base_fee_per_gas.push(last_base_fee);
         Ok(FeeHistory {
-            oldest_block: web3::types::BlockNumber::Number(oldest_block.into()),
+            oldest_block: web3::types::BlockNumber::Number(oldest_block.0.into()),
             base_fee_per_gas,
             gas_used_ratio,
-            reward,
+            reward: reward_percentiles.map(|_| reward),
         })
     }
 
@@ -685,7 +815,11 @@ impl EthNamespace {
     ) -> Result<FilterChanges, Web3Error> {
         Ok(match typed_filter {
             TypedFilter::Blocks(from_block) => {
-                let mut conn = self.state.connection_pool.connection_tagged("api").await?;
+                let mut conn = self
+                    .state
+                    .connection_pool
+                    .read_connection_tagged("api")
+                    .await?;
                 let (block_hashes, last_block_number) = conn
                     .blocks_web3_dal()
                     .get_block_hashes_since(*from_block, self.state.api_config.req_entities_limit)
@@ -715,7 +849,11 @@ impl EthNamespace {
                     }
                     None => {
                         // On cache miss, query the database.
-                        let mut conn = self.state.connection_pool.connection_tagged("api").await?;
+                        let mut conn = self
+                            .state
+                            .connection_pool
+                            .read_connection_tagged("api")
+                            .await?;
                         conn.transactions_web3_dal()
                             .get_pending_txs_hashes_after(
                                 *from_timestamp_excluded,
@@ -774,7 +912,18 @@ impl EthNamespace {
                     topics,
                 };
 
-                let mut storage = self.state.connection_pool.connection_tagged("api").await?;
+                let mut storage = self
+                    .state
+                    .connection_pool
+                    .read_connection_tagged("api")
+                    .await?;
+                // Log queries may be expensive, so we abort them once the request deadline has passed.
+                if let Some(deadline) = self.current_method().deadline() {
+                    storage
+                        .set_deadline(deadline)
+                        .await
+                        .context("set_deadline()")?;
+                }
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
@@ -847,3 +996,46 @@ impl EthNamespace {
     // - `compile_solidity`.
     // - `compile_serpent`.
 }
+
+fn validate_reward_percentiles(percentiles: &[f32]) -> Result<(), Web3Error> {
+    let is_in_range = percentiles
+        .iter()
+        .all(|percentile| (0.0..=100.0).contains(percentile));
+    let is_sorted = percentiles.windows(2).all(|window| window[0] <= window[1]);
+    if is_in_range && is_sorted {
+        Ok(())
+    } else {
+        Err(Web3Error::InvalidRewardPercentiles)
+    }
+}
+
+/// Computes `eth_feeHistory` rewards for a miniblock in the same way as Ethereum clients: transactions are sorted
+/// by their effective priority fee, and the reward for a percentile is the priority fee of the transaction
+/// at which the corresponding share of gas used in the miniblock is reached. `priority_fees` are pairs
+/// of the effective priority fee and gas used by a transaction; `percentiles` must be validated beforehand.
+fn compute_rewards(
+    priority_fees: &mut [(U256, U256)],
+    gas_used: U256,
+    percentiles: &[f32],
+) -> Vec<U256> {
+    if priority_fees.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+    priority_fees.sort_unstable_by_key(|&(fee, _)| fee);
+
+    let mut tx_index = 0;
+    let mut cumulative_gas = priority_fees[0].1;
+    percentiles
+        .iter()
+        .map(|&percentile| {
+            // Percentiles are converted to basis points to perform the computation in integers.
+            let basis_points = (f64::from(percentile) * 100.0).round() as u64;
+            let threshold = gas_used * basis_points / 10_000;
+            while cumulative_gas < threshold && tx_index + 1 < priority_fees.len() {
+                tx_index += 1;
+                cumulative_gas += priority_fees[tx_index].1;
+            }
+            priority_fees[tx_index].0
+        })
+        .collect()
+}
@@ -23,7 +23,11 @@ impl SnapshotsNamespace {
     }
 
     pub async fn get_all_snapshots_impl(&self) -> Result<AllSnapshots, Web3Error> {
-        let mut storage_processor = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let mut snapshots_dal = storage_processor.snapshots_dal();
         Ok(snapshots_dal
             .get_all_complete_snapshots()
@@ -35,7 +39,11 @@ impl SnapshotsNamespace {
         &self,
         l1_batch_number: L1BatchNumber,
     ) -> Result<Option<SnapshotHeader>, Web3Error> {
-        let mut storage_processor = self.state.connection_pool.connection_tagged("api").await?;
+        let mut storage_processor = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
         let snapshot_metadata = storage_processor
             .snapshots_dal()
             .get_snapshot_metadata(l1_batch_number)
@@ -55,11 +63,13 @@ impl SnapshotsNamespace {
 
         let chunks = snapshot_files
             .into_iter()
+            .zip(snapshot_metadata.storage_logs_hashes)
             .enumerate()
-            .filter_map(|(chunk_id, filepath)| {
+            .filter_map(|(chunk_id, (filepath, content_hash))| {
                 Some(SnapshotStorageLogsChunkMetadata {
                     chunk_id: chunk_id as u64,
                     filepath: filepath?,
+                    content_hash,
                 })
             })
             .collect();
@@ -69,13 +79,28 @@ impl SnapshotsNamespace {
             .await
             .context("get_miniblock_range_of_l1_batch")?
             .with_context(|| format!("missing miniblocks for L1 batch #{l1_batch_number}"))?;
+        let l1_batch_root_hash = storage_processor
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .context("get_l1_batch_state_root")?;
+
+        drop(storage_processor);
 
-        Ok(Some(SnapshotHeader {
+        let mut header = SnapshotHeader {
             version: snapshot_metadata.version.into(),
             l1_batch_number: snapshot_metadata.l1_batch_number,
             miniblock_number,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
-        }))
+            l1_batch_root_hash,
+            signature: None,
+        };
+        if let Some(private_key) = &self.state.snapshot_header_signing_key {
+            header
+                .sign(self.state.api_config.l2_chain_id, private_key)
+                .context("failed signing snapshot header")?;
+        }
+        Ok(Some(header))
     }
 }
@@ -0,0 +1,107 @@
+use anyhow::Context as _;
+use zksync_dal::CoreDal;
+use zksync_types::{
+    api::ResultDebugCall,
+    debug_flat_call::{flatten_debug_calls, LocalizedDebugCallFlat},
+};
+use zksync_web3_decl::{error::Web3Error, types::TraceFilter};
+
+use crate::api_server::web3::{backend_jsonrpsee::MethodTracer, state::RpcState};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TraceNamespace {
+    state: RpcState,
+}
+
+impl TraceNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.state.current_method
+    }
+
+    /// Returns flattened calls from the stored call traces matching the filter. Calls are filtered by their sender
+    /// and recipient; if both address filters are specified, a call must match both of them.
+    #[tracing::instrument(skip(self))]
+    pub async fn filter_impl(
+        &self,
+        filter: TraceFilter,
+    ) -> Result<Vec<LocalizedDebugCallFlat>, Web3Error> {
+        let limit = self.state.api_config.req_entities_limit;
+        let count = filter.count.unwrap_or(limit);
+        if count > limit {
+            return Err(Web3Error::TooManyTraces(limit));
+        }
+        let from_addresses = filter.from_address.unwrap_or_default();
+        let to_addresses = filter.to_address.unwrap_or_default();
+        if from_addresses.len() + to_addresses.len() > limit {
+            return Err(Web3Error::TooManyAddresses(limit));
+        }
+
+        let from_block = self
+            .state
+            .resolve_filter_block_number(filter.from_block)
+            .await?;
+        let to_block = self
+            .state
+            .resolve_filter_block_number(filter.to_block)
+            .await?;
+        self.state.start_info.ensure_not_pruned(from_block)?;
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?;
+        let mut calls_to_skip = filter.after.unwrap_or(0);
+        let mut calls = vec![];
+        let mut last_location = None;
+        'pages: while calls.len() < count {
+            let traces = connection
+                .blocks_web3_dal()
+                .get_traces_for_filter(
+                    from_block..=to_block,
+                    &from_addresses,
+                    &to_addresses,
+                    last_location.as_ref(),
+                    limit,
+                )
+                .await
+                .context("get_traces_for_filter")?;
+            let is_last_page = traces.len() < limit;
+
+            for (location, trace) in traces {
+                let flat_calls = flatten_debug_calls(vec![ResultDebugCall {
+                    result: trace.into(),
+                }]);
+                let matching_calls = flat_calls.into_iter().filter(|call| {
+                    (from_addresses.is_empty() || from_addresses.contains(&call.action.from))
+                        && (to_addresses.is_empty() || to_addresses.contains(&call.action.to))
+                });
+                for call in matching_calls {
+                    if calls_to_skip > 0 {
+                        calls_to_skip -= 1;
+                        continue;
+                    }
+                    if calls.len() == count {
+                        break 'pages;
+                    }
+                    calls.push(LocalizedDebugCallFlat {
+                        call,
+                        block_number: location.miniblock_number,
+                        block_hash: location.miniblock_hash,
+                        transaction_hash: location.tx_hash,
+                        transaction_position: location.tx_index_in_miniblock,
+                    });
+                }
+                last_location = Some(location);
+            }
+            if is_last_page {
+                break;
+            }
+        }
+        Ok(calls)
+    }
+}
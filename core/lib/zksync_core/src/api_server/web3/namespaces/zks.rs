@@ -2,25 +2,27 @@ use std::{collections::HashMap, convert::TryInto};
 
 use anyhow::Context as _;
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        BlockDetails, BlockId, BlockNumber, BridgeAddresses, ContractExecutionStats, GetLogsFilter,
+        InteropL1Anchor, InteropMessageProof, L1BatchDetails, L2ToL1LogProof, MempoolSender,
+        MempoolStatus, MiniblockFeeInput, Proof, ProtocolVersion, StorageProof, TransactionDetails,
+        UnderfundedPriorityOp,
     },
-    fee::Fee,
+    commitment::L2ToL1LogInclusionProof,
+    fee::FeeEstimate,
     fee_model::FeeParams,
     l1::L1Tx,
     l2::L2Tx,
-    l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log},
+    l2_to_l1_log::L2ToL1Log,
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
     utils::storage_key_for_standard_token_balance,
     AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, Transaction,
     L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
-use zksync_utils::{address_to_h256, h256_to_u256};
+use zksync_utils::{address_to_h256, h256_to_account_address, h256_to_u256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Token, H256},
@@ -46,11 +48,15 @@ impl ZksNamespace {
     }
 
     async fn connection(&self) -> Result<Connection<'_, Core>, Web3Error> {
-        Ok(self.state.connection_pool.connection_tagged("api").await?)
+        Ok(self
+            .state
+            .connection_pool
+            .read_connection_tagged("api")
+            .await?)
     }
 
     #[tracing::instrument(skip(self, request))]
-    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<FeeEstimate, Web3Error> {
         let mut request_with_gas_per_pubdata_overridden = request;
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
@@ -91,18 +97,20 @@ impl ZksNamespace {
             .map_err(Web3Error::SerializationError)?;
 
         let fee = self.estimate_fee(tx.into()).await?;
-        Ok(fee.gas_limit)
+        Ok(fee.fee.gas_limit)
     }
 
-    async fn estimate_fee(&self, tx: Transaction) -> Result<Fee, Web3Error> {
+    async fn estimate_fee(&self, tx: Transaction) -> Result<FeeEstimate, Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
 
+        let deadline = self.current_method().deadline();
+
         Ok(self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation as u64)
+            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation as u64, deadline)
             .await?)
     }
 
@@ -266,7 +274,7 @@ impl ZksNamespace {
                 },
             )
             .await?;
-        Ok(log_proof)
+        Ok(log_proof.map(|(_, proof)| proof))
     }
 
     async fn get_l2_to_l1_log_proof_inner(
@@ -275,8 +283,8 @@ impl ZksNamespace {
         l1_batch_number: L1BatchNumber,
         index_in_filtered_logs: usize,
         log_filter: impl Fn(&L2ToL1Log) -> bool,
-    ) -> Result<Option<L2ToL1LogProof>, Web3Error> {
-        let all_l1_logs_in_batch = storage
+    ) -> Result<Option<(L2ToL1Log, L2ToL1LogProof)>, Web3Error> {
+        let mut all_l1_logs_in_batch = storage
             .blocks_web3_dal()
             .get_l2_to_l1_logs(l1_batch_number)
             .await
@@ -300,20 +308,20 @@ impl ZksNamespace {
             return Ok(None);
         };
 
-        let merkle_tree_leaves = all_l1_logs_in_batch.iter().map(L2ToL1Log::to_bytes);
-
         let protocol_version = batch
             .protocol_version
             .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
-        let tree_size = l2_to_l1_logs_tree_size(protocol_version);
-
-        let (root, proof) = MiniMerkleTree::new(merkle_tree_leaves, Some(tree_size))
-            .merkle_root_and_path(l1_log_index);
-        Ok(Some(L2ToL1LogProof {
-            proof,
-            root,
-            id: l1_log_index as u32,
-        }))
+        let proof =
+            L2ToL1LogInclusionProof::new(&all_l1_logs_in_batch, l1_log_index, protocol_version);
+        let log = all_l1_logs_in_batch.swap_remove(l1_log_index);
+        Ok(Some((
+            log,
+            L2ToL1LogProof {
+                proof: proof.merkle_path,
+                root: proof.root,
+                id: l1_log_index as u32,
+            },
+        )))
     }
 
     #[tracing::instrument(skip(self))]
@@ -340,7 +348,63 @@ impl ZksNamespace {
                 |log| log.tx_number_in_block == l1_batch_tx_index,
             )
             .await?;
-        Ok(log_proof)
+        Ok(log_proof.map(|(_, proof)| proof))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_interop_message_proof_impl(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> Result<Option<InteropMessageProof>, Web3Error> {
+        let mut storage = self.connection().await?;
+        let Some((l1_batch_number, l1_batch_tx_index)) = storage
+            .blocks_web3_dal()
+            .get_l1_batch_info_for_tx(tx_hash)
+            .await
+            .context("get_l1_batch_info_for_tx")?
+        else {
+            return Ok(None);
+        };
+
+        let Some((log, proof)) = self
+            .get_l2_to_l1_log_proof_inner(
+                &mut storage,
+                l1_batch_number,
+                index.unwrap_or(0),
+                |log| {
+                    log.tx_number_in_block == l1_batch_tx_index
+                        && log.sender == L1_MESSENGER_ADDRESS
+                },
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let l1_batch_details = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(l1_batch_number)
+            .await
+            .context("get_l1_batch_details")?;
+        // The L2-to-L1 logs root of an L1 batch is only available on L1 after the batch is executed.
+        let l1_anchor = l1_batch_details.and_then(|details| {
+            Some(InteropL1Anchor {
+                bridgehub: self.state.api_config.bridgehub_proxy_addr,
+                commit_tx_hash: details.base.commit_tx_hash,
+                execute_tx_hash: details.base.execute_tx_hash?,
+                executed_at: details.base.executed_at?,
+            })
+        });
+
+        Ok(Some(InteropMessageProof {
+            source_chain_id: self.state.api_config.l2_chain_id,
+            l1_batch_number,
+            sender: h256_to_account_address(&log.key),
+            message_hash: log.value,
+            proof,
+            l1_anchor,
+        }))
     }
 
     #[tracing::instrument(skip(self))]
@@ -376,12 +440,22 @@ impl ZksNamespace {
         block_number: MiniblockNumber,
     ) -> Result<Option<BlockDetails>, Web3Error> {
         self.state.start_info.ensure_not_pruned(block_number)?;
+        if let Some(details) = self.state.lookup_caches.get_block_details(block_number) {
+            return Ok(Some(details));
+        }
+
         let mut storage = self.connection().await?;
-        Ok(storage
+        let details = storage
             .blocks_web3_dal()
             .get_block_details(block_number)
             .await
-            .context("get_block_details")?)
+            .context("get_block_details")?;
+        if let Some(details) = &details {
+            self.state
+                .lookup_caches
+                .insert_block_details(details.clone());
+        }
+        Ok(details)
     }
 
     #[tracing::instrument(skip(self))]
@@ -436,12 +510,22 @@ impl ZksNamespace {
         &self,
         hash: H256,
     ) -> Result<Option<Vec<u8>>, Web3Error> {
+        if let Some(bytecode) = self.state.lookup_caches.get_factory_dep(hash) {
+            return Ok(Some(bytecode));
+        }
+
         let mut storage = self.connection().await?;
-        Ok(storage
+        let bytecode = storage
             .factory_deps_dal()
             .get_factory_dep(hash)
             .await
-            .context("get_factory_dep")?)
+            .context("get_factory_dep")?;
+        if let Some(bytecode) = &bytecode {
+            self.state
+                .lookup_caches
+                .insert_factory_dep(hash, bytecode.clone());
+        }
+        Ok(bytecode)
     }
 
     #[tracing::instrument(skip(self))]
@@ -471,6 +555,10 @@ impl ZksNamespace {
         &self,
         version_id: Option<u16>,
     ) -> Result<Option<ProtocolVersion>, Web3Error> {
+        if let Some(version) = self.state.lookup_caches.get_protocol_version(version_id) {
+            return Ok(Some(version));
+        }
+
         let mut storage = self.connection().await?;
         let protocol_version = match version_id {
             Some(id) => {
@@ -486,6 +574,11 @@ impl ZksNamespace {
                     .await,
             ),
         };
+        if let Some(version) = &protocol_version {
+            self.state
+                .lookup_caches
+                .insert_protocol_version(version.clone());
+        }
         Ok(protocol_version)
     }
 
@@ -497,6 +590,9 @@ impl ZksNamespace {
         l1_batch_number: L1BatchNumber,
     ) -> Result<Option<Proof>, Web3Error> {
         self.state.start_info.ensure_not_pruned(l1_batch_number)?;
+        if let Some(tree_lag_limit) = &self.state.tree_lag_limit {
+            tree_lag_limit.ensure_proofs_allowed()?;
+        }
         let hashed_keys = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
@@ -538,4 +634,155 @@ impl ZksNamespace {
             storage_proof,
         }))
     }
+
+    #[tracing::instrument(skip(self, addresses))]
+    pub async fn get_nonces_impl(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+    ) -> Result<Vec<U256>, Web3Error> {
+        let limit = self.state.api_config.req_entities_limit;
+        if addresses.len() > limit {
+            return Err(Web3Error::TooManyAddresses(limit));
+        }
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        self.current_method().set_block_id(block_id);
+
+        let mut connection = self.connection().await?;
+        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let diff = self.state.last_sealed_miniblock.diff(block_number);
+        self.current_method().set_block_diff(diff);
+
+        let mut nonces = connection
+            .storage_web3_dal()
+            .get_addresses_historical_nonces(&addresses, block_number)
+            .await
+            .context("get_addresses_historical_nonces")?;
+
+        if matches!(block_id, BlockId::Number(BlockNumber::Pending)) {
+            // Nonce hints from the sink take precedence; nonces for the remaining accounts
+            // are computed from the mempool with a single DB query.
+            let mut committed_next_nonces = HashMap::new();
+            for &address in &addresses {
+                let Some(nonce) = nonces.get(&address) else {
+                    continue;
+                };
+                let nonce = u64::try_from(*nonce)
+                    .map_err(|err| anyhow::anyhow!("nonce conversion failed: {err}"))?;
+                let pending_nonce = self
+                    .state
+                    .tx_sink()
+                    .lookup_pending_nonce(address, nonce as u32)
+                    .await?;
+                if let Some(pending_nonce) = pending_nonce {
+                    nonces.insert(address, pending_nonce.0.into());
+                } else {
+                    committed_next_nonces.insert(address, nonce);
+                }
+            }
+
+            let pending_nonces = connection
+                .transactions_web3_dal()
+                .next_nonces_by_initiator_accounts(&committed_next_nonces)
+                .await
+                .context("next_nonces_by_initiator_accounts")?;
+            nonces.extend(pending_nonces);
+        }
+
+        Ok(addresses
+            .iter()
+            .map(|address| nonces.get(address).copied().unwrap_or_default())
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_fee_history_impl(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> Result<Vec<MiniblockFeeInput>, Web3Error> {
+        self.state.start_info.ensure_not_pruned(from_miniblock)?;
+        if from_miniblock > to_miniblock {
+            return Ok(vec![]);
+        }
+        let max_count = u32::try_from(self.state.api_config.req_entities_limit).unwrap_or(u32::MAX);
+        let to_miniblock = to_miniblock.min(MiniblockNumber(
+            from_miniblock.0.saturating_add(max_count.saturating_sub(1)),
+        ));
+
+        let mut storage = self.connection().await?;
+        Ok(storage
+            .blocks_web3_dal()
+            .get_miniblock_fee_inputs(from_miniblock..=to_miniblock)
+            .await
+            .context("get_miniblock_fee_inputs")?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_underfunded_priority_ops_impl(
+        &self,
+    ) -> Result<Vec<UnderfundedPriorityOp>, Web3Error> {
+        let mut storage = self.connection().await?;
+        Ok(storage
+            .transactions_web3_dal()
+            .get_pending_underfunded_priority_ops(self.state.api_config.req_entities_limit)
+            .await
+            .context("get_pending_underfunded_priority_ops")?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_contract_execution_stats_impl(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> Result<Vec<ContractExecutionStats>, Web3Error> {
+        if !self.state.api_config.contract_execution_stats_enabled {
+            return Err(Web3Error::NotImplemented);
+        }
+        if from_miniblock > to_miniblock {
+            return Ok(vec![]);
+        }
+        let limit = self.state.api_config.req_entities_limit;
+        let max_count = u32::try_from(limit).unwrap_or(u32::MAX);
+        let to_miniblock = to_miniblock.min(MiniblockNumber(
+            from_miniblock.0.saturating_add(max_count.saturating_sub(1)),
+        ));
+
+        let mut storage = self.connection().await?;
+        Ok(storage
+            .tx_execution_metrics_dal()
+            .get_contract_execution_stats(from_miniblock..=to_miniblock, limit)
+            .await
+            .context("get_contract_execution_stats")?)
+    }
+
+    fn ensure_mempool_inspection_enabled(&self) -> Result<(), Web3Error> {
+        if self.state.api_config.mempool_inspection_enabled {
+            Ok(())
+        } else {
+            Err(Web3Error::NotImplemented)
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn mempool_status_impl(&self) -> Result<MempoolStatus, Web3Error> {
+        self.ensure_mempool_inspection_enabled()?;
+        let mut storage = self.connection().await?;
+        Ok(storage
+            .transactions_web3_dal()
+            .get_mempool_status()
+            .await
+            .context("get_mempool_status")?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn mempool_content_impl(&self) -> Result<Vec<MempoolSender>, Web3Error> {
+        self.ensure_mempool_inspection_enabled()?;
+        let mut storage = self.connection().await?;
+        Ok(storage
+            .transactions_web3_dal()
+            .get_mempool_senders(self.state.api_config.req_entities_limit)
+            .await
+            .context("get_mempool_senders")?)
+    }
 }
@@ -1,5 +1,7 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::sync::Arc;
+
 use anyhow::{Context as _, Error};
 use chrono::NaiveDateTime;
 use futures::FutureExt;
@@ -9,7 +11,7 @@ use tokio::{
     time::{interval, Duration},
 };
 use zksync_dal::{ConnectionPool, Core, CoreDal};
-use zksync_types::{MiniblockNumber, H128, H256};
+use zksync_types::{api, fee_model::FeeParams, MiniblockNumber, H128};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -17,7 +19,7 @@ use zksync_web3_decl::{
         types::{error::ErrorCode, ErrorObject, SubscriptionId},
         PendingSubscriptionSink, SendTimeoutError, SubscriptionSink,
     },
-    namespaces::EthPubSubServer,
+    namespaces::{EthPubSubServer, ZksPubSubServer},
     types::{BlockHeader, Log, PubSubFilter, PubSubResult},
 };
 
@@ -25,7 +27,7 @@ use super::{
     metrics::{SubscriptionType, PUB_SUB_METRICS},
     namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
 };
-use crate::api_server::execution_sandbox::BlockStartInfo;
+use crate::{api_server::execution_sandbox::BlockStartInfo, fee_model::BatchFeeModelInputProvider};
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
@@ -48,20 +50,51 @@ pub(super) enum PubSubEvent {
     MiniblockAdvanced(SubscriptionType, MiniblockNumber),
 }
 
+/// Item broadcast to subscribers of a certain type.
+trait PubSubItem: Clone + Send + 'static {
+    /// Checks whether the item should be sent to a subscriber with the specified filter.
+    fn matches(&self, filter: &PubSubFilter) -> bool;
+
+    fn into_result(self) -> PubSubResult;
+}
+
+impl PubSubItem for PubSubResult {
+    fn matches(&self, filter: &PubSubFilter) -> bool {
+        match self {
+            Self::Log(log) => filter.matches(log),
+            _ => true,
+        }
+    }
+
+    fn into_result(self) -> PubSubResult {
+        self
+    }
+}
+
+impl PubSubItem for api::PendingTransactionInfo {
+    fn matches(&self, filter: &PubSubFilter) -> bool {
+        filter.matches_transaction(self)
+    }
+
+    fn into_result(self) -> PubSubResult {
+        PubSubResult::TxHash(self.hash)
+    }
+}
+
 /// Manager of notifications for a certain type of subscriptions.
 #[derive(Debug)]
-struct PubSubNotifier {
-    sender: broadcast::Sender<Vec<PubSubResult>>,
+struct PubSubNotifier<T = PubSubResult> {
+    sender: broadcast::Sender<Vec<T>>,
     connection_pool: ConnectionPool<Core>,
     polling_interval: Duration,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
-impl PubSubNotifier {
+impl<T> PubSubNotifier<T> {
     async fn get_starting_miniblock_number(&self) -> anyhow::Result<MiniblockNumber> {
         let mut storage = self
             .connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .context("connection_tagged")?;
         let sealed_miniblock_number = storage
@@ -84,6 +117,12 @@ impl PubSubNotifier {
             sender.send(event).ok();
         }
     }
+
+    fn send_pub_sub_results(&self, results: Vec<T>, sub_type: SubscriptionType) {
+        // Errors only on 0 receivers, but we want to go on if we have 0 subscribers so ignore the error.
+        self.sender.send(results).ok();
+        PUB_SUB_METRICS.broadcast_channel_len[&sub_type].set(self.sender.len());
+    }
 }
 
 impl PubSubNotifier {
@@ -117,18 +156,12 @@ impl PubSubNotifier {
         Ok(())
     }
 
-    fn send_pub_sub_results(&self, results: Vec<PubSubResult>, sub_type: SubscriptionType) {
-        // Errors only on 0 receivers, but we want to go on if we have 0 subscribers so ignore the error.
-        self.sender.send(results).ok();
-        PUB_SUB_METRICS.broadcast_channel_len[&sub_type].set(self.sender.len());
-    }
-
     async fn new_blocks(
         &self,
         last_block_number: MiniblockNumber,
     ) -> anyhow::Result<Vec<BlockHeader>> {
         self.connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .context("connection_tagged")?
             .blocks_web3_dal()
@@ -137,6 +170,48 @@ impl PubSubNotifier {
             .with_context(|| format!("get_block_headers_after({last_block_number})"))
     }
 
+    async fn notify_logs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut last_block_number = self.get_starting_miniblock_number().await?;
+
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, pubsub_logs_notifier is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Logs].start();
+            let new_logs = self.new_logs(last_block_number).await?;
+            db_latency.observe();
+
+            if let Some(last_log) = new_logs.last() {
+                last_block_number = MiniblockNumber(last_log.block_number.unwrap().as_u32());
+                let new_logs = new_logs.into_iter().map(PubSubResult::Log).collect();
+                self.send_pub_sub_results(new_logs, SubscriptionType::Logs);
+                self.emit_event(PubSubEvent::MiniblockAdvanced(
+                    SubscriptionType::Logs,
+                    last_block_number,
+                ));
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(SubscriptionType::Logs));
+        }
+        Ok(())
+    }
+
+    async fn new_logs(&self, last_block_number: MiniblockNumber) -> anyhow::Result<Vec<Log>> {
+        self.connection_pool
+            .read_connection_tagged("api")
+            .await
+            .context("connection_tagged")?
+            .events_web3_dal()
+            .get_all_logs(last_block_number)
+            .await
+            .context("events_web3_dal().get_all_logs()")
+    }
+}
+
+impl PubSubNotifier<api::PendingTransactionInfo> {
     async fn notify_txs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut last_time = chrono::Utc::now().naive_utc();
         let mut timer = interval(self.polling_interval);
@@ -153,10 +228,7 @@ impl PubSubNotifier {
 
             if let Some((new_last_time, _)) = new_txs.last() {
                 last_time = *new_last_time;
-                let new_txs = new_txs
-                    .into_iter()
-                    .map(|(_, tx_hash)| PubSubResult::TxHash(tx_hash))
-                    .collect();
+                let new_txs = new_txs.into_iter().map(|(_, tx)| tx).collect();
                 self.send_pub_sub_results(new_txs, SubscriptionType::Txs);
             }
             self.emit_event(PubSubEvent::NotifyIterationFinished(SubscriptionType::Txs));
@@ -164,63 +236,58 @@ impl PubSubNotifier {
         Ok(())
     }
 
-    async fn new_txs(&self, last_time: NaiveDateTime) -> Result<Vec<(NaiveDateTime, H256)>, Error> {
+    async fn new_txs(
+        &self,
+        last_time: NaiveDateTime,
+    ) -> Result<Vec<(NaiveDateTime, api::PendingTransactionInfo)>, Error> {
         self.connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await
             .context("connection_tagged")?
             .transactions_web3_dal()
-            .get_pending_txs_hashes_after(last_time, None)
+            .get_pending_txs_after(last_time, None)
             .await
-            .context("get_pending_txs_hashes_after()")
+            .context("get_pending_txs_after()")
     }
+}
 
-    async fn notify_logs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        let mut last_block_number = self.get_starting_miniblock_number().await?;
+/// Manager of notifications for fee params subscriptions. Unlike other notifiers, it only keeps the latest value,
+/// since subscribers are only interested in the current fee params.
+#[derive(Debug)]
+struct FeeParamsNotifier {
+    sender: Arc<watch::Sender<Option<FeeParams>>>,
+    batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    polling_interval: Duration,
+}
 
+impl FeeParamsNotifier {
+    async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut timer = interval(self.polling_interval);
         loop {
             if *stop_receiver.borrow() {
-                tracing::info!("Stop signal received, pubsub_logs_notifier is shutting down");
+                tracing::info!("Stop signal received, pubsub_fee_params_notifier is shutting down");
                 break;
             }
             timer.tick().await;
 
-            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Logs].start();
-            let new_logs = self.new_logs(last_block_number).await?;
-            db_latency.observe();
-
-            if let Some(last_log) = new_logs.last() {
-                last_block_number = MiniblockNumber(last_log.block_number.unwrap().as_u32());
-                let new_logs = new_logs.into_iter().map(PubSubResult::Log).collect();
-                self.send_pub_sub_results(new_logs, SubscriptionType::Logs);
-                self.emit_event(PubSubEvent::MiniblockAdvanced(
-                    SubscriptionType::Logs,
-                    last_block_number,
-                ));
-            }
-            self.emit_event(PubSubEvent::NotifyIterationFinished(SubscriptionType::Logs));
+            let fee_params = self.batch_fee_input_provider.get_fee_model_params();
+            self.sender.send_if_modified(|current| {
+                let is_modified = current.as_ref() != Some(&fee_params);
+                *current = Some(fee_params);
+                is_modified
+            });
         }
         Ok(())
     }
-
-    async fn new_logs(&self, last_block_number: MiniblockNumber) -> anyhow::Result<Vec<Log>> {
-        self.connection_pool
-            .connection_tagged("api")
-            .await
-            .context("connection_tagged")?
-            .events_web3_dal()
-            .get_all_logs(last_block_number)
-            .await
-            .context("events_web3_dal().get_all_logs()")
-    }
 }
 
 /// Subscription support for Web3 APIs.
+#[derive(Debug, Clone)]
 pub(super) struct EthSubscribe {
     blocks: broadcast::Sender<Vec<PubSubResult>>,
-    transactions: broadcast::Sender<Vec<PubSubResult>>,
+    transactions: broadcast::Sender<Vec<api::PendingTransactionInfo>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
+    fee_params: Arc<watch::Sender<Option<FeeParams>>>,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -229,11 +296,13 @@ impl EthSubscribe {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (fee_params, _) = watch::channel(None);
 
         Self {
             blocks,
             transactions,
             logs,
+            fee_params: Arc::new(fee_params),
             events_sender: None,
         }
     }
@@ -251,10 +320,10 @@ impl EthSubscribe {
         .await;
     }
 
-    async fn run_subscriber(
+    async fn run_subscriber<T: PubSubItem>(
         sink: SubscriptionSink,
         subscription_type: SubscriptionType,
-        mut receiver: broadcast::Receiver<Vec<PubSubResult>>,
+        mut receiver: broadcast::Receiver<Vec<T>>,
         filter: Option<PubSubFilter>,
     ) {
         let _guard = PUB_SUB_METRICS.active_subscribers[&subscription_type].inc_guard(1);
@@ -300,24 +369,22 @@ impl EthSubscribe {
         lifetime_latency.observe();
     }
 
-    async fn handle_new_items(
+    async fn handle_new_items<T: PubSubItem>(
         sink: &SubscriptionSink,
         subscription_type: SubscriptionType,
-        new_items: Vec<PubSubResult>,
+        new_items: Vec<T>,
         filter: Option<&PubSubFilter>,
     ) -> Result<(), SendTimeoutError> {
         let notify_latency = PUB_SUB_METRICS.notify_subscribers_latency[&subscription_type].start();
         for item in new_items {
-            if let PubSubResult::Log(log) = &item {
-                if let Some(filter) = &filter {
-                    if !filter.matches(log) {
-                        continue;
-                    }
+            if let Some(filter) = &filter {
+                if !item.matches(filter) {
+                    continue;
                 }
             }
 
             sink.send_timeout(
-                SubscriptionMessage::from_json(&item)
+                SubscriptionMessage::from_json(&item.into_result())
                     .expect("PubSubResult always serializable to json;qed"),
                 SUBSCRIPTION_SINK_SEND_TIMEOUT,
             )
@@ -330,6 +397,61 @@ impl EthSubscribe {
         Ok(())
     }
 
+    async fn run_fee_params_subscriber(
+        sink: SubscriptionSink,
+        mut receiver: watch::Receiver<Option<FeeParams>>,
+    ) {
+        const SUBSCRIPTION_TYPE: SubscriptionType = SubscriptionType::FeeParams;
+
+        let _guard = PUB_SUB_METRICS.active_subscribers[&SUBSCRIPTION_TYPE].inc_guard(1);
+        let lifetime_latency = PUB_SUB_METRICS.subscriber_lifetime[&SUBSCRIPTION_TYPE].start();
+        let closed = sink.closed().fuse();
+        tokio::pin!(closed);
+
+        loop {
+            let fee_params = *receiver.borrow_and_update();
+            if let Some(fee_params) = fee_params {
+                let message = SubscriptionMessage::from_json(&fee_params)
+                    .expect("FeeParams always serializable to json;qed");
+                let send_result = sink
+                    .send_timeout(message, SUBSCRIPTION_SINK_SEND_TIMEOUT)
+                    .await;
+                if send_result.is_err() {
+                    PUB_SUB_METRICS.subscriber_send_timeouts[&SUBSCRIPTION_TYPE].inc();
+                    break;
+                }
+                PUB_SUB_METRICS.notify[&SUBSCRIPTION_TYPE].inc();
+            }
+
+            tokio::select! {
+                changed_result = receiver.changed() => {
+                    if changed_result.is_err() {
+                        // The notifier task is shut down.
+                        break;
+                    }
+                }
+                _ = &mut closed => {
+                    break;
+                }
+            }
+        }
+        lifetime_latency.observe();
+    }
+
+    #[tracing::instrument(skip(self, pending_sink))]
+    pub async fn sub_fee_params(&self, pending_sink: PendingSubscriptionSink) {
+        let Ok(sink) = pending_sink.accept().await else {
+            return;
+        };
+        let receiver = self.fee_params.subscribe();
+        tokio::spawn(Self::run_fee_params_subscriber(sink, receiver));
+        if let Some(sender) = &self.events_sender {
+            sender
+                .send(PubSubEvent::Subscribed(SubscriptionType::FeeParams))
+                .ok();
+        }
+    }
+
     #[tracing::instrument(skip(self, pending_sink))]
     pub async fn sub(
         &self,
@@ -353,23 +475,29 @@ impl EthSubscribe {
                 Some(SubscriptionType::Blocks)
             }
             "newPendingTransactions" => {
-                let Ok(sink) = pending_sink.accept().await else {
-                    return;
-                };
-                let transactions_rx = self.transactions.subscribe();
-                tokio::spawn(Self::run_subscriber(
-                    sink,
-                    SubscriptionType::Txs,
-                    transactions_rx,
-                    None,
-                ));
-                Some(SubscriptionType::Txs)
+                let filter = params.unwrap_or_default();
+                if filter.has_log_criteria() {
+                    Self::reject(pending_sink).await;
+                    None
+                } else {
+                    let Ok(sink) = pending_sink.accept().await else {
+                        return;
+                    };
+                    let transactions_rx = self.transactions.subscribe();
+                    tokio::spawn(Self::run_subscriber(
+                        sink,
+                        SubscriptionType::Txs,
+                        transactions_rx,
+                        Some(filter),
+                    ));
+                    Some(SubscriptionType::Txs)
+                }
             }
             "logs" => {
                 let filter = params.unwrap_or_default();
                 let topic_count = filter.topics.as_ref().map_or(0, Vec::len);
 
-                if topic_count > EVENT_TOPIC_NUMBER_LIMIT {
+                if topic_count > EVENT_TOPIC_NUMBER_LIMIT || filter.has_transaction_criteria() {
                     Self::reject(pending_sink).await;
                     None
                 } else {
@@ -417,10 +545,11 @@ impl EthSubscribe {
     pub fn spawn_notifiers(
         &self,
         connection_pool: ConnectionPool<Core>,
+        batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(4);
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
@@ -446,7 +575,15 @@ impl EthSubscribe {
             polling_interval,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
+        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = FeeParamsNotifier {
+            sender: self.fee_params.clone(),
+            batch_fee_input_provider,
+            polling_interval,
+        };
+        let notifier_task = tokio::spawn(notifier.run(stop_receiver));
 
         notifier_tasks.push(notifier_task);
         notifier_tasks
@@ -465,3 +602,11 @@ impl EthPubSubServer for EthSubscribe {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl ZksPubSubServer for EthSubscribe {
+    async fn subscribe_fee_params(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        self.sub_fee_params(pending).await;
+        Ok(())
+    }
+}
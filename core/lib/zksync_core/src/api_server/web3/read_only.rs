@@ -0,0 +1,222 @@
+//! Read-only Web3 API server started while the node storage is being prepared.
+
+use std::net::SocketAddr;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use tower_http::cors::CorsLayer;
+use zksync_types::{api::BridgeAddresses, Address, U64};
+use zksync_web3_decl::{
+    jsonrpsee::{
+        server::{RpcServiceBuilder, ServerBuilder, ServerHandle},
+        types::ErrorObjectOwned,
+        RpcModule,
+    },
+    namespaces::{NetNamespaceServer, Web3NamespaceServer},
+};
+
+use super::{
+    backend_jsonrpsee::NodeStateMiddleware,
+    namespaces::{NetNamespace, Web3Namespace},
+    state::InternalApiConfig,
+    ApiTransport,
+};
+use crate::sync_layer::NodeStateHandle;
+
+type RpcResult<T> = Result<T, ErrorObjectOwned>;
+
+/// Web3 API server that can be started before the node storage is ready, e.g. during snapshot recovery
+/// or reorg rollback. The server only answers methods depending on the static node configuration (such as `eth_chainId`);
+/// other methods are rejected with a structured "node is syncing" error containing the current node state.
+///
+/// The server stops once the node becomes ready, so that the fully functional server can take over its port.
+#[derive(Debug)]
+pub struct ReadOnlyApiServer {
+    config: InternalApiConfig,
+    transport: ApiTransport,
+    node_state: NodeStateHandle,
+}
+
+impl ReadOnlyApiServer {
+    pub fn http(config: InternalApiConfig, port: u16, node_state: NodeStateHandle) -> Self {
+        Self {
+            config,
+            transport: ApiTransport::Http(([0, 0, 0, 0], port).into()),
+            node_state,
+        }
+    }
+
+    pub fn ws(config: InternalApiConfig, port: u16, node_state: NodeStateHandle) -> Self {
+        Self {
+            config,
+            transport: ApiTransport::WebSocket(([0, 0, 0, 0], port).into()),
+            node_state,
+        }
+    }
+
+    fn transport_str(&self) -> &'static str {
+        match self.transport {
+            ApiTransport::Http(_) => "HTTP",
+            ApiTransport::WebSocket(_) => "WS",
+        }
+    }
+
+    fn build_rpc_module(&self) -> anyhow::Result<RpcModule<InternalApiConfig>> {
+        let mut rpc = RpcModule::new(self.config.clone());
+        rpc.merge(NetNamespace::new(self.config.l2_chain_id).into_rpc())?;
+        rpc.merge(Web3Namespace.into_rpc())?;
+
+        rpc.register_method("eth_chainId", |_, config| -> RpcResult<U64> {
+            Ok(config.l2_chain_id.as_u64().into())
+        })?;
+        rpc.register_method("zks_L1ChainId", |_, config| -> RpcResult<U64> {
+            Ok(U64::from(*config.l1_chain_id))
+        })?;
+        rpc.register_method("zks_getMainContract", |_, config| -> RpcResult<Address> {
+            Ok(config.diamond_proxy_addr)
+        })?;
+        rpc.register_method(
+            "zks_getBridgeContracts",
+            |_, config| -> RpcResult<BridgeAddresses> { Ok(config.bridge_addresses.clone()) },
+        )?;
+        rpc.register_method(
+            "zks_getBridgehubContract",
+            |_, config| -> RpcResult<Option<Address>> { Ok(config.bridgehub_proxy_addr) },
+        )?;
+        rpc.register_method(
+            "zks_getTestnetPaymaster",
+            |_, config| -> RpcResult<Option<Address>> { Ok(config.l2_testnet_paymaster_addr) },
+        )?;
+        Ok(rpc)
+    }
+
+    async fn start(self) -> anyhow::Result<(SocketAddr, ServerHandle)> {
+        let transport_str = self.transport_str();
+        let rpc = self.build_rpc_module()?;
+        let node_state = self.node_state;
+        let rpc_middleware = RpcServiceBuilder::new()
+            .layer_fn(move |svc| NodeStateMiddleware::new(svc, node_state.clone()));
+
+        let (is_http, addr) = match self.transport {
+            ApiTransport::Http(addr) => (true, addr),
+            ApiTransport::WebSocket(addr) => (false, addr),
+        };
+        // Use the same CORS settings as the fully functional server.
+        let cors = is_http.then(|| {
+            CorsLayer::new()
+                .allow_methods([reqwest::Method::POST])
+                .allow_origin(tower_http::cors::Any)
+                .allow_headers([reqwest::header::CONTENT_TYPE])
+        });
+        let server_builder = ServerBuilder::default()
+            .set_http_middleware(tower::ServiceBuilder::new().option_layer(cors))
+            .set_rpc_middleware(rpc_middleware);
+        let server_builder = if is_http {
+            server_builder.http_only()
+        } else {
+            server_builder
+        };
+        let server = server_builder.build(addr).await.with_context(|| {
+            format!("Failed building read-only {transport_str} JSON-RPC server")
+        })?;
+        let local_addr = server.local_addr().with_context(|| {
+            format!("Failed getting local address for read-only {transport_str} JSON-RPC server")
+        })?;
+        Ok((local_addr, server.start(rpc)))
+    }
+
+    /// Runs the server until the node becomes ready or a stop signal is received.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let transport_str = self.transport_str();
+        let node_state = self.node_state.clone();
+        let (local_addr, server_handle) = self.start().await?;
+        tracing::info!("Initialized read-only {transport_str} API on {local_addr:?}");
+
+        tokio::select! {
+            () = node_state.wait_until_ready() => {
+                tracing::info!("Node is ready, stopping read-only {transport_str} JSON-RPC server");
+            }
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received, read-only {transport_str} JSON-RPC server is shutting down");
+            }
+        }
+        server_handle.stop().ok();
+        server_handle.stopped().await;
+        tracing::info!("Read-only {transport_str} JSON-RPC server stopped");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use zksync_config::{configs::api::Web3JsonRpcConfig, ContractsConfig, GenesisConfig};
+    use zksync_web3_decl::{
+        jsonrpsee::{core::ClientError, http_client::HttpClient},
+        namespaces::{EthNamespaceClient, NetNamespaceClient, ZksNamespaceClient},
+    };
+
+    use super::*;
+    use crate::{api_server::web3::backend_jsonrpsee::READ_ONLY_METHODS, sync_layer::NodeState};
+
+    fn test_config() -> InternalApiConfig {
+        InternalApiConfig::new(
+            &Web3JsonRpcConfig::for_tests(),
+            &ContractsConfig::for_tests(),
+            &GenesisConfig::for_tests(),
+        )
+    }
+
+    #[test]
+    fn read_only_server_serves_all_read_only_methods() {
+        let server = ReadOnlyApiServer::http(test_config(), 0, NodeStateHandle::default());
+        let rpc = server.build_rpc_module().unwrap();
+        let method_names: HashSet<_> = rpc.method_names().collect();
+        let expected_names: HashSet<_> = READ_ONLY_METHODS.iter().copied().collect();
+        assert_eq!(method_names, expected_names);
+    }
+
+    #[tokio::test]
+    async fn read_only_server_basics() {
+        let config = test_config();
+        let node_state = NodeStateHandle::new(NodeState::InitializingStorage);
+        let server = ReadOnlyApiServer::http(config.clone(), 0, node_state.clone());
+        let (local_addr, server_handle) = server.start().await.unwrap();
+        let client = <HttpClient>::builder()
+            .build(format!("http://{local_addr}/"))
+            .unwrap();
+
+        let chain_id = client.chain_id().await.unwrap();
+        assert_eq!(chain_id, config.l2_chain_id.as_u64().into());
+        let net_version = client.version().await.unwrap();
+        assert_eq!(net_version, config.l2_chain_id.as_u64().to_string());
+        let main_contract = client.get_main_contract().await.unwrap();
+        assert_eq!(main_contract, config.diamond_proxy_addr);
+
+        node_state.set(NodeState::RollingBack);
+        let err = client.get_block_number().await.unwrap_err();
+        let ClientError::Call(err) = err else {
+            panic!("Unexpected error: {err:?}");
+        };
+        assert_eq!(err.message(), "Node is syncing");
+        let data: serde_json::Value = serde_json::from_str(err.data().unwrap().get()).unwrap();
+        assert_eq!(data, serde_json::json!({ "node_state": "rolling_back" }));
+
+        server_handle.stop().unwrap();
+        server_handle.stopped().await;
+    }
+
+    #[tokio::test]
+    async fn read_only_server_stops_when_node_is_ready() {
+        let node_state = NodeStateHandle::new(NodeState::InitializingStorage);
+        let server = ReadOnlyApiServer::ws(test_config(), 0, node_state.clone());
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let server_task = tokio::spawn(server.run(stop_receiver));
+
+        tokio::task::yield_now().await;
+        assert!(!server_task.is_finished());
+        node_state.set(NodeState::Ready);
+        server_task.await.unwrap().unwrap();
+    }
+}
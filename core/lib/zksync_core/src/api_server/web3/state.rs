@@ -24,6 +24,7 @@ use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::{
     backend_jsonrpsee::MethodTracer,
+    lookup_cache::LookupCaches,
     mempool_cache::MempoolCache,
     metrics::{FilterType, FILTER_METRICS},
     TypedFilter,
@@ -34,6 +35,7 @@ use crate::{
         tree::TreeApiClient,
         tx_sender::{tx_sink::TxSink, TxSender},
     },
+    metadata_calculator::TreeLagReceiver,
     sync_layer::SyncState,
 };
 
@@ -97,8 +99,11 @@ pub struct InternalApiConfig {
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
     pub filters_disabled: bool,
+    pub mempool_inspection_enabled: bool,
+    pub contract_execution_stats_enabled: bool,
     pub mempool_cache_update_interval: Duration,
     pub mempool_cache_size: usize,
+    pub lookup_cache_size: usize,
     pub dummy_verifier: bool,
     pub l1_batch_commit_data_generator_mode: L1BatchCommitDataGeneratorMode,
 }
@@ -139,8 +144,11 @@ impl InternalApiConfig {
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
             filters_disabled: web3_config.filters_disabled,
+            mempool_inspection_enabled: web3_config.mempool_inspection_enabled,
+            contract_execution_stats_enabled: web3_config.contract_execution_stats_enabled,
             mempool_cache_update_interval: web3_config.mempool_cache_update_interval(),
             mempool_cache_size: web3_config.mempool_cache_size(),
+            lookup_cache_size: web3_config.lookup_cache_size(),
             dummy_verifier: genesis_config.dummy_verifier,
             l1_batch_commit_data_generator_mode: genesis_config.l1_batch_commit_data_generator_mode,
         }
@@ -173,7 +181,7 @@ impl SealedMiniblockNumber {
                     return Ok(());
                 }
 
-                let mut connection = connection_pool.connection_tagged("api").await.unwrap();
+                let mut connection = connection_pool.read_connection_tagged("api").await.unwrap();
                 let Some(last_sealed_miniblock) = connection
                     .blocks_dal()
                     .get_sealed_miniblock_number()
@@ -222,6 +230,30 @@ impl SealedMiniblockNumber {
     }
 }
 
+/// Limit on the Merkle tree lag relative to the latest sealed L1 batch.
+#[derive(Debug, Clone)]
+pub(crate) struct TreeLagLimit {
+    pub tree_lag: TreeLagReceiver,
+    /// Maximum acceptable lag in L1 batches.
+    pub max_lag: u32,
+    /// Whether to reject proof requests while the lag exceeds `max_lag`.
+    pub reject_proofs: bool,
+}
+
+impl TreeLagLimit {
+    /// Returns the current tree lag if it exceeds the limit.
+    pub fn excessive_lag(&self) -> Option<u32> {
+        self.tree_lag.get().filter(|&lag| lag > self.max_lag)
+    }
+
+    pub fn ensure_proofs_allowed(&self) -> Result<(), Web3Error> {
+        match self.excessive_lag() {
+            Some(lag) if self.reject_proofs => Err(Web3Error::TreeLagging(lag)),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Holder for the data required for the API to be functional.
 #[derive(Debug, Clone)]
 pub(crate) struct RpcState {
@@ -229,6 +261,7 @@ pub(crate) struct RpcState {
     pub(super) installed_filters: Option<Arc<Mutex<Filters>>>,
     pub(super) connection_pool: ConnectionPool<Core>,
     pub(super) tree_api: Option<Arc<dyn TreeApiClient>>,
+    pub(super) tree_lag_limit: Option<TreeLagLimit>,
     pub(super) tx_sender: TxSender,
     pub(super) sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
@@ -236,7 +269,12 @@ pub(crate) struct RpcState {
     /// from a snapshot.
     pub(super) start_info: BlockStartInfo,
     pub(super) mempool_cache: MempoolCache,
+    pub(super) lookup_caches: LookupCaches,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
+    /// Private key used to sign L2 blocks returned by `en_syncL2Block`.
+    pub(super) sync_block_signing_key: Option<H256>,
+    /// Private key used to sign snapshot headers returned by the `snapshots` namespace.
+    pub(super) snapshot_header_signing_key: Option<H256>,
 }
 
 impl RpcState {
@@ -277,6 +315,27 @@ impl RpcState {
             .ok_or(Web3Error::NoBlock)
     }
 
+    /// Resolves a block ID used in historical state queries to a standard block ID. An L1 batch anchor is resolved
+    /// to the last miniblock in the batch; the batch must be sealed.
+    pub(crate) async fn resolve_historical_block_id(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        block: api::HistoricalBlockIdVariant,
+    ) -> Result<api::BlockId, Web3Error> {
+        let l1_batch_number = match block {
+            api::HistoricalBlockIdVariant::Block(block) => return Ok(block.into()),
+            api::HistoricalBlockIdVariant::L1BatchNumberObject(object) => object.l1_batch_number,
+        };
+        self.start_info.ensure_not_pruned(l1_batch_number)?;
+        let (_, last_miniblock) = connection
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .context("get_miniblock_range_of_l1_batch")?
+            .ok_or(Web3Error::NoBlock)?;
+        Ok(api::BlockId::Number(last_miniblock.0.into()))
+    }
+
     /// Resolves the specified block ID to a block number, which is **not** guaranteed to be present in the node storage.
     /// Returns `None` if the block is known to not be present in the storage (e.g., it's a "finalized" block ID and no blocks
     /// were finalized yet).
@@ -327,7 +386,7 @@ impl RpcState {
 
         let block_number = block_number.unwrap_or(api::BlockNumber::Latest);
         let block_id = api::BlockId::Number(block_number);
-        let mut conn = self.connection_pool.connection_tagged("api").await?;
+        let mut conn = self.connection_pool.read_connection_tagged("api").await?;
         Ok(self.resolve_block(&mut conn, block_id).await.unwrap())
         // ^ `unwrap()` is safe: `resolve_block_id(api::BlockId::Number(_))` can only return `None`
         // if called with an explicit number, and we've handled this case earlier.
@@ -348,7 +407,7 @@ impl RpcState {
             (Some(block_hash), None, None) => {
                 let block_number = self
                     .connection_pool
-                    .connection_tagged("api")
+                    .read_connection_tagged("api")
                     .await?
                     .blocks_web3_dal()
                     .resolve_block_id(api::BlockId::Hash(block_hash))
@@ -373,7 +432,7 @@ impl RpcState {
     ) -> Result<MiniblockNumber, Web3Error> {
         let pending_block = self
             .connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await?
             .blocks_web3_dal()
             .resolve_block_id(api::BlockId::Number(api::BlockNumber::Pending))
@@ -397,7 +456,7 @@ impl RpcState {
         if call_request.nonce.is_some() {
             return Ok(());
         }
-        let mut connection = self.connection_pool.connection_tagged("api").await?;
+        let mut connection = self.connection_pool.read_connection_tagged("api").await?;
 
         let latest_block_id = api::BlockId::Number(api::BlockNumber::Latest);
         let latest_block_number = self.resolve_block(&mut connection, latest_block_id).await?;
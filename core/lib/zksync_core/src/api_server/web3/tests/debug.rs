@@ -1,7 +1,13 @@
 //! Tests for the `debug` Web3 namespace.
 
-use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
-use zksync_web3_decl::namespaces::DebugNamespaceClient;
+use zksync_types::{
+    transaction_request::CallRequest, tx::TransactionExecutionResult, vm_trace::Call,
+    BOOTLOADER_ADDRESS,
+};
+use zksync_web3_decl::{
+    namespaces::{DebugNamespaceClient, TraceNamespaceClient},
+    types::TraceFilter,
+};
 
 use super::*;
 
@@ -54,7 +60,12 @@ impl HttpTest for TraceBlockTest {
 
             assert_eq!(block_traces.len(), tx_results.len()); // equals to the number of transactions in the block
             for (trace, tx_result) in block_traces.iter().zip(&tx_results) {
-                let api::ResultDebugCall { result } = trace;
+                let api::ResultDebugCall {
+                    result: api::DebugTrace::Call(result),
+                } = trace
+                else {
+                    panic!("Unexpected trace: {trace:?}");
+                };
                 assert_eq!(result.from, Address::zero());
                 assert_eq!(result.to, BOOTLOADER_ADDRESS);
                 assert_eq!(result.gas, tx_result.transaction.gas_limit());
@@ -189,6 +200,9 @@ impl HttpTest for TraceTransactionTest {
             .trace_transaction(tx_results[0].hash, None)
             .await?
             .context("no transaction traces")?;
+        let api::DebugTrace::Call(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
         assert_eq!(result.from, Address::zero());
         assert_eq!(result.to, BOOTLOADER_ADDRESS);
         assert_eq!(result.gas, tx_results[0].transaction.gas_limit());
@@ -203,6 +217,88 @@ async fn tracing_transaction() {
     test_http_server(TraceTransactionTest).await;
 }
 
+#[derive(Debug)]
+struct TraceFilterTest;
+
+#[async_trait]
+impl HttpTest for TraceFilterTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let tx_results = [0, 1, 2].map(execute_l2_transaction_with_traces);
+        let mut storage = pool.connection().await?;
+        let new_miniblock = store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let block_filter = TraceFilter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Latest),
+            ..TraceFilter::default()
+        };
+        let calls = client.filter(block_filter.clone()).await?;
+        // Each transaction has a top-level call and 2 nested calls.
+        assert_eq!(calls.len(), tx_results.len() * 3);
+        for (i, call) in calls.iter().enumerate() {
+            let tx_result = &tx_results[i / 3];
+            assert_eq!(call.block_number, MiniblockNumber(1));
+            assert_eq!(call.block_hash, new_miniblock.hash);
+            assert_eq!(call.transaction_hash, tx_result.hash);
+            assert_eq!(call.transaction_position, (i / 3) as u32);
+        }
+        assert_eq!(calls[0].call.action.to, BOOTLOADER_ADDRESS);
+        assert_eq!(calls[4].call.action.from, Address::repeat_byte(1));
+
+        let paginated_calls = client
+            .filter(TraceFilter {
+                after: Some(2),
+                count: Some(3),
+                ..block_filter.clone()
+            })
+            .await?;
+        assert_eq!(paginated_calls, calls[2..5]);
+
+        let calls_from_address = client
+            .filter(TraceFilter {
+                from_address: Some(vec![Address::repeat_byte(1)]),
+                ..block_filter.clone()
+            })
+            .await?;
+        assert_eq!(calls_from_address, [calls[4].clone()]);
+        let calls_to_address = client
+            .filter(TraceFilter {
+                to_address: Some(vec![Address::repeat_byte(2)]),
+                ..block_filter.clone()
+            })
+            .await?;
+        assert_eq!(calls_to_address, [calls[4].clone()]);
+        let calls_from_and_to_address = client
+            .filter(TraceFilter {
+                from_address: Some(vec![Address::repeat_byte(1)]),
+                to_address: Some(vec![Address::repeat_byte(1)]),
+                ..block_filter.clone()
+            })
+            .await?;
+        assert!(calls_from_and_to_address.is_empty());
+
+        let error = client
+            .filter(TraceFilter {
+                count: Some(usize::MAX),
+                ..block_filter
+            })
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn filtering_traces() {
+    test_http_server(TraceFilterTest).await;
+}
+
 #[derive(Debug)]
 struct TraceBlockTestWithSnapshotRecovery;
 
@@ -239,3 +335,52 @@ impl HttpTest for TraceBlockTestWithSnapshotRecovery {
 async fn tracing_block_after_snapshot_recovery() {
     test_http_server(TraceBlockTestWithSnapshotRecovery).await;
 }
+
+#[derive(Debug)]
+struct PrestateTracerTest;
+
+#[async_trait]
+impl HttpTest for PrestateTracerTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        drop(storage);
+
+        let options: api::TracerConfig = serde_json::from_value(serde_json::json!({
+            "tracer": "prestateTracer",
+            "tracerConfig": { "diffMode": true },
+        }))?;
+        assert!(options.tracer_config.diff_mode);
+        assert!(!options.tracer_config.only_top_call);
+
+        let block_traces = client
+            .trace_block_by_number(1_u32.into(), Some(options.clone()))
+            .await?;
+        assert!(block_traces.is_empty(), "{block_traces:?}");
+
+        let tx_trace = client
+            .trace_transaction(H256::repeat_byte(1), Some(options.clone()))
+            .await?;
+        assert!(tx_trace.is_none(), "{tx_trace:?}");
+
+        let call_request = CallRequest {
+            to: Some(Address::repeat_byte(1)),
+            ..CallRequest::default()
+        };
+        let error = client
+            .trace_call(call_request, None, Some(options))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::MethodNotFound.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_with_prestate_tracer() {
+    test_http_server(PrestateTracerTest).await;
+}
@@ -18,14 +18,20 @@ use zksync_config::{
     },
     GenesisConfig,
 };
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, CoreDal};
+use zksync_dal::{
+    transactions_dal::L2TxSubmissionResult, tx_execution_metrics_dal::TxExecutionMetricsEntry,
+    Connection, ConnectionPool, CoreDal,
+};
 use zksync_health_check::CheckHealth;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     api,
     block::MiniblockHeader,
+    commitment::L2ToL1LogInclusionProof,
     fee::TransactionExecutionMetrics,
     get_nonce_key,
     l2::L2Tx,
+    l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     storage::get_code_key,
     tokens::{TokenInfo, TokenMetadata},
     tx::{
@@ -33,9 +39,10 @@ use zksync_types::{
         TransactionExecutionResult,
     },
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, StorageLog, VmEvent, H256, U64,
+    web3, AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, StorageLog, VmEvent, H256,
+    L1_MESSENGER_ADDRESS, U64,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{address_to_h256, u256_to_h256};
 use zksync_web3_decl::{
     jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
     namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
@@ -48,6 +55,7 @@ use crate::{
         tx_sender::tests::create_test_tx_sender,
     },
     genesis::{insert_genesis_batch, mock_genesis_config, GenesisParams},
+    sync_layer::{MainNodeCapabilities, MainNodeClient},
     utils::testonly::{
         create_l1_batch, create_l1_batch_metadata, create_l2_transaction, create_miniblock,
         l1_batch_metadata_to_commitment_artifacts, prepare_recovery_snapshot,
@@ -159,7 +167,7 @@ async fn spawn_server(
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
+    namespaces.extend([Namespace::Debug, Namespace::Trace, Namespace::Snapshots]);
 
     let server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
@@ -210,6 +218,11 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the `contract_execution_stats_enabled` configuration parameter for HTTP server startup
+    fn contract_execution_stats_enabled(&self) -> bool {
+        false
+    }
 }
 
 /// Storage initialization strategy.
@@ -287,6 +300,7 @@ async fn test_http_server(test: impl HttpTest) {
     let genesis = GenesisConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&web3_config, &contracts_config, &genesis);
     api_config.filters_disabled = test.filters_disabled();
+    api_config.contract_execution_stats_enabled = test.contract_execution_stats_enabled();
     let mut server_handles = spawn_http_server(
         api_config,
         pool.clone(),
@@ -339,6 +353,7 @@ fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionResult {
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        execution_time: Duration::ZERO,
     }
 }
 
@@ -655,12 +670,18 @@ impl HttpTest for StorageAccessWithSnapshotRecovery {
         let first_local_miniblock = StorageInitialization::SNAPSHOT_RECOVERY_BLOCK + 1;
         for number in [0, 1, first_local_miniblock.0 - 1] {
             let number = api::BlockIdVariant::BlockNumber(number.into());
-            let error = client.get_code(address, Some(number)).await.unwrap_err();
+            let error = client
+                .get_code(address, Some(number.into()))
+                .await
+                .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
-            let error = client.get_balance(address, Some(number)).await.unwrap_err();
+            let error = client
+                .get_balance(address, Some(number.into()))
+                .await
+                .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
             let error = client
-                .get_storage_at(address, 0.into(), Some(number))
+                .get_storage_at(address, 0.into(), Some(number.into()))
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -668,15 +689,50 @@ impl HttpTest for StorageAccessWithSnapshotRecovery {
 
         for number in [api::BlockNumber::Latest, first_local_miniblock.0.into()] {
             let number = api::BlockIdVariant::BlockNumber(number);
-            let code = client.get_code(address, Some(number)).await?;
+            let code = client.get_code(address, Some(number.into())).await?;
             assert_eq!(code.0, b"code");
-            let balance = client.get_balance(address, Some(number)).await?;
+            let balance = client.get_balance(address, Some(number.into())).await?;
             assert_eq!(balance, 123.into());
             let storage_value = client
-                .get_storage_at(address, 0.into(), Some(number))
+                .get_storage_at(address, 0.into(), Some(number.into()))
                 .await?;
             assert_eq!(storage_value, H256::repeat_byte(0xff));
         }
+
+        // Access storage at the end of L1 batches.
+        let first_local_l1_batch = StorageInitialization::SNAPSHOT_RECOVERY_BATCH + 1;
+        let l1_batch = api::HistoricalBlockIdVariant::from(first_local_l1_batch);
+        let code = client.get_code(address, Some(l1_batch)).await?;
+        assert_eq!(code.0, b"code");
+        let balance = client.get_balance(address, Some(l1_batch)).await?;
+        assert_eq!(balance, 123.into());
+        let storage_value = client
+            .get_storage_at(address, 0.into(), Some(l1_batch))
+            .await?;
+        assert_eq!(storage_value, H256::repeat_byte(0xff));
+
+        let pruned_l1_batch = api::HistoricalBlockIdVariant::from(first_local_l1_batch - 1);
+        let error = client
+            .get_code(address, Some(pruned_l1_batch))
+            .await
+            .unwrap_err();
+        assert_pruned_l1_batch_error(&error, first_local_l1_batch);
+        let error = client
+            .get_balance(address, Some(pruned_l1_batch))
+            .await
+            .unwrap_err();
+        assert_pruned_l1_batch_error(&error, first_local_l1_batch);
+
+        let future_l1_batch = api::HistoricalBlockIdVariant::from(first_local_l1_batch + 1);
+        let error = client
+            .get_storage_at(address, 0.into(), Some(future_l1_batch))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
         Ok(())
     }
 }
@@ -1099,3 +1155,296 @@ impl HttpTest for GenesisConfigTest {
 async fn tracing_genesis_config() {
     test_http_server(GenesisConfigTest).await;
 }
+
+#[derive(Debug)]
+struct MainNodeCapabilitiesTest;
+
+#[async_trait]
+impl HttpTest for MainNodeCapabilitiesTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let capabilities = client.fetch_capabilities().await?;
+        // The test server doesn't have consensus genesis.
+        assert_eq!(capabilities, MainNodeCapabilities::all(false));
+        assert!(capabilities.snapshots());
+        assert!(capabilities.ranged_sync());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn discovering_main_node_capabilities() {
+    test_http_server(MainNodeCapabilitiesTest).await;
+}
+
+#[derive(Debug)]
+struct InteropMessageProofTest;
+
+impl InteropMessageProofTest {
+    const SENDER: Address = Address::repeat_byte(0x23);
+
+    fn message_log(tx_number_in_block: u16, message_hash: H256) -> L2ToL1Log {
+        L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block,
+            sender: L1_MESSENGER_ADDRESS,
+            key: address_to_h256(&Self::SENDER),
+            value: message_hash,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for InteropMessageProofTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let tx_results = [execute_l2_transaction(create_l2_transaction(10, 200))];
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        let tx_hash = tx_results[0].hash;
+
+        let logs = vec![
+            // Sent by another transaction
+            Self::message_log(1, H256::repeat_byte(1)),
+            Self::message_log(0, H256::repeat_byte(2)),
+            // Not sent via the L1 messenger
+            L2ToL1Log {
+                sender: Address::repeat_byte(0xff),
+                ..Self::message_log(0, H256::repeat_byte(3))
+            },
+            Self::message_log(0, H256::repeat_byte(4)),
+        ];
+        let mut l1_batch = create_l1_batch(1);
+        l1_batch.l2_to_l1_logs = logs.iter().cloned().map(UserL2ToL1Log).collect();
+        storage.blocks_dal().insert_mock_l1_batch(&l1_batch).await?;
+        storage
+            .blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await;
+
+        let proof = client
+            .get_interop_message_proof(tx_hash, Some(1))
+            .await?
+            .context("no interop message proof")?;
+        assert_eq!(proof.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(proof.sender, Self::SENDER);
+        assert_eq!(proof.message_hash, H256::repeat_byte(4));
+        assert_eq!(proof.proof.id, 3);
+        assert_eq!(proof.l1_anchor, None);
+        let inclusion_proof = L2ToL1LogInclusionProof {
+            index: 3,
+            merkle_path: proof.proof.proof,
+            root: proof.proof.root,
+        };
+        assert!(inclusion_proof.verify(&logs[3]));
+
+        let missing_proof = client.get_interop_message_proof(tx_hash, Some(2)).await?;
+        assert_eq!(missing_proof, None);
+
+        // Mark the L1 batch as executed on L1.
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Execute,
+                Address::repeat_byte(1),
+                100,
+                None,
+                None,
+            )
+            .await?;
+        storage
+            .blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1)..=L1BatchNumber(1),
+                eth_tx.id,
+                AggregatedActionType::Execute,
+            )
+            .await?;
+        let execute_tx_hash = H256::repeat_byte(0x42);
+        storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 100, 10, None, execute_tx_hash, &[])
+            .await?;
+        storage
+            .eth_sender_dal()
+            .confirm_tx(execute_tx_hash, U256::zero())
+            .await?;
+
+        let proof = client
+            .get_interop_message_proof(tx_hash, None)
+            .await?
+            .context("no interop message proof")?;
+        assert_eq!(proof.message_hash, H256::repeat_byte(2));
+        assert_eq!(proof.proof.id, 1);
+        let l1_anchor = proof.l1_anchor.context("no L1 anchor")?;
+        assert_eq!(l1_anchor.commit_tx_hash, None);
+        assert_eq!(l1_anchor.execute_tx_hash, execute_tx_hash);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_interop_message_proof() {
+    test_http_server(InteropMessageProofTest).await;
+}
+
+#[derive(Debug)]
+struct MempoolInspectionDisabledTest;
+
+#[async_trait]
+impl HttpTest for MempoolInspectionDisabledTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let error = client.mempool_status().await.unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.code() == ErrorCode::MethodNotFound.code());
+        let error = client.mempool_content().await.unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.code() == ErrorCode::MethodNotFound.code());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn mempool_inspection_is_disabled_by_default() {
+    test_http_server(MempoolInspectionDisabledTest).await;
+}
+
+#[derive(Debug)]
+struct ContractExecutionStatsDisabledTest;
+
+#[async_trait]
+impl HttpTest for ContractExecutionStatsDisabledTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let error = client
+            .get_contract_execution_stats(MiniblockNumber(0), MiniblockNumber(10))
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.code() == ErrorCode::MethodNotFound.code());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn contract_execution_stats_are_disabled_by_default() {
+    test_http_server(ContractExecutionStatsDisabledTest).await;
+}
+
+#[derive(Debug)]
+struct ContractExecutionStatsTest;
+
+#[async_trait]
+impl HttpTest for ContractExecutionStatsTest {
+    fn contract_execution_stats_enabled(&self) -> bool {
+        true
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let contract_address = Address::repeat_byte(1);
+        let entries: Vec<_> = (1..=2_u8)
+            .map(|i| TxExecutionMetricsEntry {
+                tx_hash: H256::repeat_byte(i),
+                miniblock_number: MiniblockNumber(1),
+                contract_address,
+                execution_time: Duration::from_micros(100 * u64::from(i)),
+                gas_used: 1_000,
+                pubdata_bytes: 100,
+                storage_writes: 2,
+                computational_gas_used: 500 * u64::from(i),
+                circuits_used: 0.25 * f64::from(i),
+            })
+            .collect();
+        pool.connection()
+            .await?
+            .tx_execution_metrics_dal()
+            .insert_metrics(&entries)
+            .await?;
+
+        let stats = client
+            .get_contract_execution_stats(MiniblockNumber(0), MiniblockNumber(10))
+            .await?;
+        assert_eq!(stats.len(), 1, "{stats:?}");
+        let stats = &stats[0];
+        assert_eq!(stats.contract_address, contract_address);
+        assert_eq!(stats.tx_count, 2);
+        assert_eq!(stats.max_execution_time_us, 200);
+        assert_eq!(stats.total_computational_gas_used, 1_500);
+        assert!(
+            (stats.total_circuits_used - 0.75).abs() < 1e-9,
+            "{}",
+            stats.total_circuits_used
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_contract_execution_stats() {
+    test_http_server(ContractExecutionStatsTest).await;
+}
+
+#[derive(Debug)]
+struct FeeHistoryTest;
+
+#[async_trait]
+impl HttpTest for FeeHistoryTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+        ];
+        for result in &tx_results {
+            let l2_tx = result.transaction.clone().try_into().unwrap();
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(l2_tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+        let miniblock = MiniblockHeader {
+            gas_limit: 8_000,
+            ..create_miniblock(1)
+        };
+        storage.blocks_dal().insert_miniblock(&miniblock).await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(miniblock.number, &tx_results, 1.into())
+            .await;
+
+        let history = client
+            .fee_history(1.into(), api::BlockNumber::Latest, Some(vec![25.0, 75.0]))
+            .await?;
+        assert_eq!(
+            history.oldest_block,
+            web3::types::BlockNumber::Number(1.into())
+        );
+        let base_fee = U256::from(miniblock.base_fee_per_gas);
+        assert_eq!(history.base_fee_per_gas, [base_fee, base_fee]);
+        // Each transaction uses 1,000 gas.
+        assert_eq!(history.gas_used_ratio, [0.25]);
+        // Transactions are charged only for the base fee.
+        assert_eq!(history.reward, Some(vec![vec![U256::zero(); 2]]));
+
+        let history = client
+            .fee_history(1.into(), api::BlockNumber::Latest, None)
+            .await?;
+        assert_eq!(history.reward, None);
+
+        for percentiles in [vec![75.0, 25.0], vec![-1.0], vec![101.0]] {
+            let error = client
+                .fee_history(1.into(), api::BlockNumber::Latest, Some(percentiles))
+                .await
+                .unwrap_err();
+            assert_matches!(error, ClientError::Call(error) if error.code() == ErrorCode::InvalidParams.code());
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_fee_history() {
+    test_http_server(FeeHistoryTest).await;
+}
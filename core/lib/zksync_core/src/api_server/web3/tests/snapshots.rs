@@ -51,7 +51,12 @@ impl HttpTest for SnapshotBasicsTest {
             let path = format!("file:///storage_logs/chunk{chunk_id}");
             storage
                 .snapshots_dal()
-                .add_storage_logs_filepath_for_snapshot(L1BatchNumber(1), chunk_id, &path)
+                .add_storage_logs_filepath_for_snapshot(
+                    L1BatchNumber(1),
+                    chunk_id,
+                    &path,
+                    H256::repeat_byte(chunk_id as u8),
+                )
                 .await?;
         }
 
@@ -86,6 +91,10 @@ impl HttpTest for SnapshotBasicsTest {
         for chunk in &snapshot_header.storage_logs_chunks {
             assert!(self.chunk_ids.contains(&chunk.chunk_id));
             assert!(chunk.filepath.starts_with("file:///storage_logs/"));
+            assert_eq!(
+                chunk.content_hash,
+                Some(H256::repeat_byte(chunk.chunk_id as u8))
+            );
         }
         Ok(())
     }
@@ -62,7 +62,7 @@ impl HttpTest for CallTest {
         for (number, calldata) in valid_block_numbers_and_calldata {
             let number = api::BlockIdVariant::BlockNumber(number);
             let call_result = client
-                .call(Self::call_request(calldata), Some(number))
+                .call(Self::call_request(calldata), Some(number.into()))
                 .await?;
             assert_eq!(call_result.0, b"output");
         }
@@ -70,7 +70,7 @@ impl HttpTest for CallTest {
         let invalid_block_number = api::BlockNumber::from(100);
         let number = api::BlockIdVariant::BlockNumber(invalid_block_number);
         let error = client
-            .call(Self::call_request(b"100"), Some(number))
+            .call(Self::call_request(b"100"), Some(number.into()))
             .await
             .unwrap_err();
         if let ClientError::Call(error) = error {
@@ -111,7 +111,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         let call_result = client
             .call(
                 CallTest::call_request(b"pending"),
-                Some(pending_block_number),
+                Some(pending_block_number.into()),
             )
             .await?;
         assert_eq!(call_result.0, b"output");
@@ -121,7 +121,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         for number in pruned_block_numbers {
             let number = api::BlockIdVariant::BlockNumber(number.into());
             let error = client
-                .call(CallTest::call_request(b"pruned"), Some(number))
+                .call(CallTest::call_request(b"pruned"), Some(number.into()))
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -131,10 +131,28 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         for number in first_miniblock_numbers {
             let number = api::BlockIdVariant::BlockNumber(number);
             let call_result = client
-                .call(CallTest::call_request(b"first"), Some(number))
+                .call(CallTest::call_request(b"first"), Some(number.into()))
                 .await?;
             assert_eq!(call_result.0, b"output");
         }
+
+        // The call anchored at the first local L1 batch should be executed on top of its last miniblock.
+        let first_local_l1_batch = StorageInitialization::SNAPSHOT_RECOVERY_BATCH + 1;
+        let call_result = client
+            .call(
+                CallTest::call_request(b"first"),
+                Some(first_local_l1_batch.into()),
+            )
+            .await?;
+        assert_eq!(call_result.0, b"output");
+        let error = client
+            .call(
+                CallTest::call_request(b"pruned"),
+                Some((first_local_l1_batch - 1).into()),
+            )
+            .await
+            .unwrap_err();
+        assert_pruned_l1_batch_error(&error, first_local_l1_batch);
         Ok(())
     }
 }
@@ -360,7 +378,7 @@ impl HttpTest for TraceCallTestAfterSnapshotRecovery {
         for number in pruned_block_numbers {
             let number = api::BlockIdVariant::BlockNumber(number.into());
             let error = client
-                .call(CallTest::call_request(b"pruned"), Some(number))
+                .call(CallTest::call_request(b"pruned"), Some(number.into()))
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -4,7 +4,6 @@ use std::collections::HashSet;
 
 use async_trait::async_trait;
 use jsonrpsee::core::{client::ClientT, params::BatchRequestBuilder, ClientError};
-use reqwest::StatusCode;
 use tokio::sync::watch;
 use zksync_config::configs::chain::NetworkConfig;
 use zksync_dal::ConnectionPool;
@@ -15,12 +14,15 @@ use zksync_web3_decl::{
         rpc_params,
         ws_client::{WsClient, WsClientBuilder},
     },
-    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+    namespaces::{EthNamespaceClient, ZksNamespaceClient, ZksPubSubClient},
     types::{BlockHeader, PubSubFilter},
 };
 
 use super::*;
-use crate::api_server::web3::metrics::SubscriptionType;
+use crate::{
+    api_server::web3::{backend_jsonrpsee::LIMIT_EXCEEDED_CODE, metrics::SubscriptionType},
+    utils::testonly::MockBatchFeeParamsProvider,
+};
 
 #[allow(clippy::needless_pass_by_ref_mut)] // false positive
 async fn wait_for_subscription(
@@ -110,8 +112,12 @@ async fn notifiers_start_after_snapshot_recovery() {
     let (events_sender, mut events_receiver) = mpsc::unbounded_channel();
     let mut subscribe_logic = EthSubscribe::new();
     subscribe_logic.set_events_sender(events_sender);
-    let notifier_handles =
-        subscribe_logic.spawn_notifiers(pool.clone(), POLL_INTERVAL, stop_receiver);
+    let notifier_handles = subscribe_logic.spawn_notifiers(
+        pool.clone(),
+        Arc::new(MockBatchFeeParamsProvider::default()),
+        POLL_INTERVAL,
+        stop_receiver,
+    );
     assert!(!notifier_handles.is_empty());
 
     // Wait a little doing nothing and check that notifier tasks are still active (i.e., have not panicked).
@@ -317,6 +323,118 @@ async fn basic_subscriptions_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct PendingTransactionsFilterTest;
+
+#[async_trait]
+impl WsTest for PendingTransactionsFilterTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool<Core>,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Txs]).await;
+
+        let txs = [create_l2_transaction(1, 2), create_l2_transaction(1, 2)];
+        let sender_filter = PubSubFilter {
+            from: Some(txs[0].initiator_account().into()),
+            ..PubSubFilter::default()
+        };
+        let params = rpc_params!["newPendingTransactions", sender_filter];
+        let mut sender_subscription = client
+            .subscribe::<H256, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        let recipient_filter = PubSubFilter {
+            to: Some(txs[1].execute.contract_address.into()),
+            ..PubSubFilter::default()
+        };
+        let params = rpc_params!["newPendingTransactions", recipient_filter];
+        let mut recipient_subscription = client
+            .subscribe::<H256, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        for _ in 0..2 {
+            wait_for_subscription(&mut pub_sub_events, SubscriptionType::Txs).await;
+        }
+
+        // Log criteria are not supported for pending transactions.
+        let invalid_filter = PubSubFilter {
+            address: Some(Address::repeat_byte(23).into()),
+            ..PubSubFilter::default()
+        };
+        let params = rpc_params!["newPendingTransactions", invalid_filter];
+        client
+            .subscribe::<H256, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+
+        let mut storage = pool.connection().await?;
+        let tx_results = txs.map(execute_l2_transaction);
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let received_tx_hash = tokio::time::timeout(TEST_TIMEOUT, sender_subscription.next())
+            .await
+            .context("Timed out waiting for new tx hash")?
+            .context("Pending txs subscription terminated")??;
+        assert_eq!(received_tx_hash, tx_results[0].hash);
+        let received_tx_hash = tokio::time::timeout(TEST_TIMEOUT, recipient_subscription.next())
+            .await
+            .context("Timed out waiting for new tx hash")?
+            .context("Pending txs subscription terminated")??;
+        assert_eq!(received_tx_hash, tx_results[1].hash);
+
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Txs]).await;
+        // Check that no non-matching transactions were sent to subscribers.
+        tokio::time::timeout(POLL_INTERVAL, sender_subscription.next())
+            .await
+            .unwrap_err();
+        tokio::time::timeout(POLL_INTERVAL, recipient_subscription.next())
+            .await
+            .unwrap_err();
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn pending_transactions_subscription_with_filter() {
+    test_ws_server(PendingTransactionsFilterTest).await;
+}
+
+#[derive(Debug)]
+struct FeeParamsSubscriptionTest;
+
+#[async_trait]
+impl WsTest for FeeParamsSubscriptionTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        _pool: &ConnectionPool<Core>,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        let mut subscription = client.subscribe_fee_params().await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::FeeParams).await;
+
+        // Current fee params should be sent immediately after subscribing.
+        let received_params = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for fee params")?
+            .context("Fee params subscription terminated")??;
+        assert_eq!(received_params, MockBatchFeeParamsProvider::default().0);
+        // Fee params don't change, so no more notifications should be sent.
+        let next_params = tokio::time::timeout(POLL_INTERVAL * 5, subscription.next()).await;
+        assert!(next_params.is_err(), "{next_params:?}");
+
+        subscription.unsubscribe().await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn fee_params_subscription() {
+    test_ws_server(FeeParamsSubscriptionTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsTest {
     snapshot_recovery: bool,
@@ -344,15 +462,15 @@ impl LogSubscriptions {
             .await?;
         let address_filter = PubSubFilter {
             address: Some(Address::repeat_byte(23).into()),
-            topics: None,
+            ..PubSubFilter::default()
         };
         let params = rpc_params!["logs", address_filter];
         let address_subscription = client
             .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
             .await?;
         let topic_filter = PubSubFilter {
-            address: None,
             topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            ..PubSubFilter::default()
         };
         let params = rpc_params!["logs", topic_filter];
         let topic_subscription = client
@@ -592,6 +710,7 @@ impl WsTest for LogSubscriptionsWithDelayTest {
         let address_and_topic_filter = PubSubFilter {
             address: Some(Address::repeat_byte(23).into()),
             topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            ..PubSubFilter::default()
         };
         let params = rpc_params!["logs", address_and_topic_filter];
         let mut address_and_topic_subscription = client
@@ -645,7 +764,7 @@ impl WsTest for RateLimitingTest {
         let expected_err = client.chain_id().await.unwrap_err();
 
         if let ClientError::Call(error) = expected_err {
-            assert_eq!(error.code() as u16, StatusCode::TOO_MANY_REQUESTS.as_u16());
+            assert_eq!(error.code(), LIMIT_EXCEEDED_CODE);
             assert_eq!(error.message(), "Too many requests");
             assert!(error.data().is_none());
         } else {
@@ -692,7 +811,7 @@ impl WsTest for BatchGetsRateLimitedTest {
 
         let error = expected_err.next().unwrap();
 
-        assert_eq!(error.code() as u16, StatusCode::TOO_MANY_REQUESTS.as_u16());
+        assert_eq!(error.code(), LIMIT_EXCEEDED_CODE);
         assert_eq!(error.message(), "Too many requests");
         assert!(error.data().is_none());
 
@@ -0,0 +1,54 @@
+//! Metrics for the block body compressor.
+
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+use zksync_dal::compression_dal::CompressionStats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "column", rename_all = "snake_case")]
+pub(super) enum CompressedColumn {
+    EventValues,
+    TransactionInputs,
+}
+
+const COMPRESSION_RATIO_BUCKETS: Buckets = Buckets::exponential(1.0..=64.0, 2.0);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_block_body_compressor")]
+pub(super) struct BlockBodyCompressorMetrics {
+    /// Number of compressed values.
+    pub compressed_values: Family<CompressedColumn, Counter>,
+    /// Total size of compressed values before compression.
+    #[metrics(unit = Unit::Bytes)]
+    pub raw_bytes: Family<CompressedColumn, Counter>,
+    /// Total size of compressed values after compression.
+    #[metrics(unit = Unit::Bytes)]
+    pub compressed_bytes: Family<CompressedColumn, Counter>,
+    /// Ratio of the raw size to the compressed size for values compressed in a single iteration.
+    #[metrics(buckets = COMPRESSION_RATIO_BUCKETS)]
+    pub compression_ratio: Family<CompressedColumn, Histogram<f64>>,
+    /// Last miniblock processed by the compressor.
+    pub last_compressed_miniblock: Gauge<u64>,
+    /// Latency of a single compressor iteration.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub iteration_latency: Histogram<Duration>,
+}
+
+impl BlockBodyCompressorMetrics {
+    pub fn observe_stats(&self, column: CompressedColumn, stats: &CompressionStats) {
+        if stats.compressed_values == 0 {
+            return;
+        }
+        self.compressed_values[&column].inc_by(stats.compressed_values as u64);
+        self.raw_bytes[&column].inc_by(stats.raw_bytes as u64);
+        self.compressed_bytes[&column].inc_by(stats.compressed_bytes as u64);
+        let ratio = stats.raw_bytes as f64 / stats.compressed_bytes as f64;
+        self.compression_ratio[&column].observe(ratio);
+    }
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<BlockBodyCompressorMetrics> = vise::Global::new();
@@ -0,0 +1,177 @@
+//! Component compressing large byte columns of miniblock bodies (event payloads and raw transaction bytes)
+//! at rest in Postgres.
+//!
+//! Data is written uncompressed, so that compression doesn't add latency to sealing miniblocks. The compressor
+//! processes sealed miniblocks in the background, starting from the last processed miniblock persisted
+//! in Postgres, and thus also backfills data written before compression was enabled. Compressed data
+//! is transparently decompressed by the DAL on reads.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::MiniblockNumber;
+
+use self::metrics::{CompressedColumn, METRICS};
+
+mod metrics;
+
+/// Configuration of [`BlockBodyCompressor`].
+#[derive(Debug, Clone)]
+pub struct BlockBodyCompressorConfig {
+    /// Interval between checks for new sealed miniblocks if all miniblocks are processed.
+    pub poll_interval: Duration,
+    /// Maximum number of miniblocks processed in a single iteration. Bounds the duration of DB transactions
+    /// and the CPU time spent on compression between checks for a stop signal.
+    pub miniblocks_per_iteration: u32,
+    /// Values smaller than this size (in bytes) are not compressed. Compressing small values
+    /// has little effect on the storage size, but adds overhead to reads.
+    pub min_value_size: usize,
+}
+
+impl Default for BlockBodyCompressorConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(10),
+            miniblocks_per_iteration: 100,
+            min_value_size: 256,
+        }
+    }
+}
+
+/// Compresses event payloads and raw transaction bytes in sealed miniblocks.
+#[derive(Debug)]
+pub struct BlockBodyCompressor {
+    config: BlockBodyCompressorConfig,
+    pool: ConnectionPool<Core>,
+}
+
+impl BlockBodyCompressor {
+    pub fn new(config: BlockBodyCompressorConfig, pool: ConnectionPool<Core>) -> Self {
+        Self { config, pool }
+    }
+
+    /// Compresses the next range of sealed miniblocks. Returns `false` if there are no miniblocks to process.
+    async fn compress_next_miniblocks(&self) -> anyhow::Result<bool> {
+        let mut storage = self.pool.connection_tagged("block_body_compressor").await?;
+        let last_compressed_miniblock = storage
+            .compression_dal()
+            .get_last_compressed_miniblock()
+            .await
+            .context("failed getting last compressed miniblock")?;
+        let Some(sealed_miniblock) = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?
+        else {
+            return Ok(false);
+        };
+
+        if let Some(last_compressed) = last_compressed_miniblock {
+            if last_compressed > sealed_miniblock {
+                // Miniblocks were reverted; re-sealed miniblocks need to be compressed again.
+                tracing::info!(
+                    "Last compressed miniblock #{last_compressed} is greater than the last sealed \
+                     miniblock #{sealed_miniblock}, probably due to a revert; rewinding"
+                );
+                storage
+                    .compression_dal()
+                    .set_last_compressed_miniblock(sealed_miniblock)
+                    .await
+                    .context("failed rewinding last compressed miniblock")?;
+                return Ok(false);
+            }
+        }
+
+        let from_miniblock =
+            last_compressed_miniblock.map_or(MiniblockNumber(0), |number| number + 1);
+        if from_miniblock > sealed_miniblock {
+            return Ok(false);
+        }
+        let to_miniblock =
+            (from_miniblock + (self.config.miniblocks_per_iteration - 1)).min(sealed_miniblock);
+        let miniblocks = from_miniblock..=to_miniblock;
+
+        let latency = METRICS.iteration_latency.start();
+        let event_stats = storage
+            .compression_dal()
+            .compress_event_values(miniblocks.clone(), self.config.min_value_size)
+            .await
+            .with_context(|| format!("failed compressing event values in {miniblocks:?}"))?;
+        let tx_stats = storage
+            .compression_dal()
+            .compress_transaction_inputs(miniblocks.clone(), self.config.min_value_size)
+            .await
+            .with_context(|| format!("failed compressing transaction inputs in {miniblocks:?}"))?;
+        storage
+            .compression_dal()
+            .set_last_compressed_miniblock(to_miniblock)
+            .await
+            .context("failed updating last compressed miniblock")?;
+        latency.observe();
+
+        tracing::debug!(
+            "Compressed miniblocks {miniblocks:?}: events {event_stats:?}, \
+             transactions {tx_stats:?}"
+        );
+        METRICS.observe_stats(CompressedColumn::EventValues, &event_stats);
+        METRICS.observe_stats(CompressedColumn::TransactionInputs, &tx_stats);
+        METRICS.last_compressed_miniblock.set(to_miniblock.0.into());
+        Ok(true)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!("Starting block body compressor with {:?}", self.config);
+        anyhow::ensure!(
+            self.config.miniblocks_per_iteration > 0,
+            "`miniblocks_per_iteration` must be positive"
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            if self.compress_next_miniblocks().await? {
+                continue;
+            }
+            if tokio::time::timeout(self.config.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, block body compressor is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis::{insert_genesis_batch, GenesisParams};
+
+    #[tokio::test]
+    async fn compressor_advances_cursor() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let config = BlockBodyCompressorConfig {
+            miniblocks_per_iteration: 1,
+            ..BlockBodyCompressorConfig::default()
+        };
+        let compressor = BlockBodyCompressor::new(config, pool);
+        assert!(compressor.compress_next_miniblocks().await.unwrap());
+        let cursor = storage
+            .compression_dal()
+            .get_last_compressed_miniblock()
+            .await
+            .unwrap();
+        assert_eq!(cursor, Some(MiniblockNumber(0)));
+
+        // All sealed miniblocks are processed.
+        assert!(!compressor.compress_next_miniblocks().await.unwrap());
+    }
+}
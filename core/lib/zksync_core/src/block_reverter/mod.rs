@@ -1,7 +1,7 @@
 use std::{path::Path, time::Duration};
 
 use bitflags::bitflags;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 use zksync_config::{ContractsConfig, ETHConfig};
 use zksync_contracts::zksync_contract;
@@ -22,6 +22,8 @@ use zksync_types::{
     L1BatchNumber, PackedEthSignature, H160, H256, U256,
 };
 
+use crate::intent_log::{IntentLog, IntentOperation};
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
@@ -78,6 +80,17 @@ impl BlockReverterEthConfig {
     }
 }
 
+/// DB rollback recorded in the [`IntentLog`], so that it can be completed if it's interrupted.
+#[derive(Debug, Serialize, Deserialize)]
+struct RollbackIntent {
+    last_l1_batch_to_keep: L1BatchNumber,
+    flags: u32,
+}
+
+impl IntentOperation for RollbackIntent {
+    const NAME: &'static str = "block_reverter/rollback_db";
+}
+
 /// Role of the node.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NodeRole {
@@ -130,15 +143,14 @@ impl BlockReverter {
     }
 
     /// Rolls back DBs (Postgres + RocksDB) to a previous state.
+    ///
+    /// The rollback is recorded in the intent log before any DB is touched. If the rollback is interrupted,
+    /// it should be completed using [`Self::complete_interrupted_rollbacks()`].
     pub async fn rollback_db(
         &self,
         last_l1_batch_to_keep: L1BatchNumber,
         flags: BlockReverterFlags,
     ) {
-        let rollback_tree = flags.contains(BlockReverterFlags::TREE);
-        let rollback_postgres = flags.contains(BlockReverterFlags::POSTGRES);
-        let rollback_sk_cache = flags.contains(BlockReverterFlags::SK_CACHE);
-
         if matches!(
             self.executed_batches_revert_mode,
             L1ExecutedBatchesRevert::Disallowed
@@ -156,6 +168,48 @@ impl BlockReverter {
             );
         }
 
+        let intent_log = IntentLog::new(self.connection_pool.clone());
+        let intent = RollbackIntent {
+            last_l1_batch_to_keep,
+            flags: flags.bits(),
+        };
+        let intent_id = intent_log
+            .begin(&intent)
+            .await
+            .expect("failed recording DB rollback intent");
+        self.rollback_dbs(last_l1_batch_to_keep, flags).await;
+        intent_log
+            .complete(intent_id)
+            .await
+            .expect("failed completing DB rollback intent");
+    }
+
+    /// Completes DB rollbacks that were interrupted, e.g. by a node crash. Should be called on node startup
+    /// before DBs are accessed by other components.
+    pub async fn complete_interrupted_rollbacks(&self) {
+        let intent_log = IntentLog::new(self.connection_pool.clone());
+        let interrupted_rollbacks = intent_log
+            .pending::<RollbackIntent>()
+            .await
+            .expect("failed getting interrupted DB rollbacks");
+        for (intent_id, intent) in interrupted_rollbacks {
+            tracing::warn!("Found interrupted DB rollback {intent:?}; completing it");
+            let flags = BlockReverterFlags::from_bits_truncate(intent.flags);
+            self.rollback_dbs(intent.last_l1_batch_to_keep, flags).await;
+            intent_log
+                .complete(intent_id)
+                .await
+                .expect("failed completing DB rollback intent");
+        }
+    }
+
+    /// Rolls back DBs without any checks. Rolling back is idempotent, so it's safe to repeat
+    /// an interrupted rollback.
+    async fn rollback_dbs(&self, last_l1_batch_to_keep: L1BatchNumber, flags: BlockReverterFlags) {
+        let rollback_tree = flags.contains(BlockReverterFlags::TREE);
+        let rollback_postgres = flags.contains(BlockReverterFlags::POSTGRES);
+        let rollback_sk_cache = flags.contains(BlockReverterFlags::SK_CACHE);
+
         // Tree needs to be reverted first to keep state recoverable
         self.rollback_rocks_dbs(last_l1_batch_to_keep, rollback_tree, rollback_sk_cache)
             .await;
@@ -0,0 +1,160 @@
+//! Admin HTTP endpoint of the consensus task.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use zksync_concurrency::{ctx, net};
+use zksync_consensus_crypto::{Text, TextFmt as _};
+use zksync_consensus_roles::node;
+
+use super::{GossipPeers, ProposerControl};
+use crate::utils::auth::require_bearer_token;
+
+/// Configuration of the consensus admin HTTP server.
+#[derive(Clone, PartialEq)]
+pub struct AdminConfig {
+    /// Local socket address of the server.
+    pub addr: std::net::SocketAddr,
+    /// Token that must be supplied as `Authorization: Bearer {token}` to the endpoints changing
+    /// the node state.
+    pub token: String,
+}
+
+impl fmt::Debug for AdminConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AdminConfig")
+            .field("addr", &self.addr)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ProposerStatus {
+    paused: bool,
+}
+
+async fn proposer_status(control: State<ProposerControl>) -> Json<ProposerStatus> {
+    Json(ProposerStatus {
+        paused: control.is_paused(),
+    })
+}
+
+async fn pause_proposing(control: State<ProposerControl>) -> Json<ProposerStatus> {
+    control.pause_proposing();
+    proposer_status(control).await
+}
+
+async fn resume_proposing(control: State<ProposerControl>) -> Json<ProposerStatus> {
+    control.resume_proposing();
+    proposer_status(control).await
+}
+
+#[derive(Debug, Deserialize)]
+struct GossipPeerAddr {
+    addr: String,
+}
+
+fn parse_peer_key(key: &str) -> Result<node::PublicKey, (StatusCode, String)> {
+    Text::new(key).decode().map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("invalid node key: {err:#}"),
+        )
+    })
+}
+
+async fn gossip_peers(peers: State<GossipPeers>) -> Json<BTreeMap<String, String>> {
+    let peers = peers
+        .peers()
+        .into_iter()
+        .map(|(key, addr)| (key.encode(), addr.0))
+        .collect();
+    Json(peers)
+}
+
+async fn add_gossip_peer(
+    peers: State<GossipPeers>,
+    Path(key): Path<String>,
+    Json(body): Json<GossipPeerAddr>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let key = parse_peer_key(&key)?;
+    Ok(if peers.add_peer(key, net::Host(body.addr)) {
+        StatusCode::OK
+    } else {
+        StatusCode::NO_CONTENT
+    })
+}
+
+async fn remove_gossip_peer(
+    peers: State<GossipPeers>,
+    Path(key): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let key = parse_peer_key(&key)?;
+    Ok(if peers.remove_peer(&key) {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    })
+}
+
+/// Runs the admin server until `ctx` is canceled. The server exposes the following endpoints:
+///
+/// - `GET /gossip/peers`: returns the static outbound gossip peers as a `{ node_key: addr }` map.
+/// - `PUT /gossip/peers/:node_key`: adds a peer (or updates its address) using the `{ "addr": .. }` body.
+/// - `DELETE /gossip/peers/:node_key`: removes a peer.
+///
+/// If `control` is provided (main node only), the following endpoints are exposed as well:
+///
+/// - `GET /proposer`: returns whether block proposals are paused.
+/// - `POST /proposer/pause`: pauses block proposals after the current L1 batch.
+/// - `POST /proposer/resume`: resumes block proposals.
+///
+/// `PUT`, `DELETE` and `POST` endpoints require the bearer token from `config`.
+pub(super) async fn run_server(
+    ctx: &ctx::Ctx,
+    config: AdminConfig,
+    peers: GossipPeers,
+    control: Option<ProposerControl>,
+) -> anyhow::Result<()> {
+    let token: Arc<str> = config.token.into();
+    let auth = middleware::from_fn_with_state(token, require_bearer_token);
+    let mut app = Router::new()
+        .route(
+            "/gossip/peers/:node_key",
+            put(add_gossip_peer).delete(remove_gossip_peer),
+        )
+        .route_layer(auth.clone())
+        .route("/gossip/peers", get(gossip_peers))
+        .with_state(peers);
+    if let Some(control) = control {
+        app = app.merge(
+            Router::new()
+                .route("/proposer/pause", post(pause_proposing))
+                .route("/proposer/resume", post(resume_proposing))
+                .route_layer(auth)
+                .route("/proposer", get(proposer_status))
+                .with_state(control),
+        );
+    }
+
+    let bind_address = config.addr;
+
+    tracing::info!("Starting consensus admin server on {bind_address}");
+    axum::Server::try_bind(&bind_address)
+        .with_context(|| format!("failed binding consensus admin server to {bind_address}"))?
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(ctx.canceled())
+        .await
+        .context("consensus admin server failed")?;
+    tracing::info!("Consensus admin server shut down");
+    Ok(())
+}
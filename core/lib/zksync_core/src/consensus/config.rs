@@ -1,15 +1,18 @@
 //! Configuration utilities for the consensus component.
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 
 use anyhow::Context as _;
-use zksync_concurrency::net;
+use zksync_concurrency::{ctx, error::Wrap as _, net};
 use zksync_consensus_crypto::{read_required_text, Text, TextFmt};
 use zksync_consensus_executor as executor;
 use zksync_consensus_roles::{node, validator};
 use zksync_protobuf::{required, ProtoFmt};
 
 use crate::{
-    consensus::{fetcher::P2PConfig, MainNodeConfig},
+    consensus::{fetcher::P2PConfig, AdminConfig, MainNodeConfig, Store},
     proto::consensus as proto,
 };
 
@@ -20,6 +23,23 @@ fn read_optional_secret_text<T: TextFmt>(text: &Option<String>) -> anyhow::Resul
         .map_err(|_| anyhow::format_err!("invalid format"))
 }
 
+fn encode_keys<K: TextFmt>(keys: &[&K]) -> String {
+    let keys: Vec<_> = keys.iter().map(|key| key.encode()).collect();
+    format!("[{}]", keys.join(", "))
+}
+
+/// Checks whether two local socket addresses cannot be bound simultaneously.
+fn addrs_clash(a: std::net::SocketAddr, b: std::net::SocketAddr) -> bool {
+    a.port() == b.port() && (a.ip() == b.ip() || a.ip().is_unspecified() || b.ip().is_unspecified())
+}
+
+fn report_problems(problems: Vec<String>) -> anyhow::Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!("invalid consensus config:\n- {}", problems.join("\n- "))
+}
+
 /// Config (shared between main node and external node).
 #[derive(Clone, Debug, PartialEq)]
 pub struct Config {
@@ -44,6 +64,12 @@ pub struct Config {
     /// Outbound gossip connections that the node should actively try to
     /// establish and maintain.
     pub gossip_static_outbound: BTreeMap<node::PublicKey, net::Host>,
+
+    /// Local socket address of the admin HTTP server.
+    /// The server allows managing static outbound gossip peers at runtime and, on the main node,
+    /// pausing block proposals, e.g. to hand leadership over to a standby instance.
+    /// Requires `admin_token` to be set in secrets.
+    pub admin_addr: Option<std::net::SocketAddr>,
 }
 
 impl Config {
@@ -54,6 +80,8 @@ impl Config {
                 .validator_key
                 .clone()
                 .context("missing validator_key")?,
+            validators: self.validators.clone(),
+            admin: self.admin(secrets)?,
         })
     }
 
@@ -61,6 +89,137 @@ impl Config {
         Ok(self.executor_config(secrets.node_key.clone().context("missing node_key")?))
     }
 
+    /// Returns the admin server config, or `None` if the admin server is disabled.
+    pub fn admin(&self, secrets: &Secrets) -> anyhow::Result<Option<AdminConfig>> {
+        let Some(addr) = self.admin_addr else {
+            return Ok(None);
+        };
+        let token = secrets.admin_token.clone().context("missing admin_token")?;
+        Ok(Some(AdminConfig { addr, token }))
+    }
+
+    /// Checks the config for consistency with `secrets` and with the local addresses used by other
+    /// components of the node (`reserved_addrs`, e.g. API servers). Returns an error listing
+    /// all detected problems.
+    pub fn validate(
+        &self,
+        secrets: &Secrets,
+        reserved_addrs: &[(&str, std::net::SocketAddr)],
+    ) -> anyhow::Result<()> {
+        let mut problems = vec![];
+
+        if let Some(node_key) = &secrets.node_key {
+            let public_key = node_key.public();
+            if self.gossip_static_outbound.contains_key(&public_key) {
+                problems.push(format!(
+                    "`gossip_static_outbound` contains the key of this node ({}); \
+                     remove it, since the node cannot connect to itself",
+                    public_key.encode()
+                ));
+            }
+            if self.gossip_static_inbound.contains(&public_key) {
+                problems.push(format!(
+                    "`gossip_static_inbound` contains the key of this node ({}); remove it",
+                    public_key.encode()
+                ));
+            }
+        }
+        if let Some(validator_key) = &secrets.validator_key {
+            let public_key = validator_key.public();
+            if !self.validators.iter().any(|key| *key == public_key) {
+                problems.push(format!(
+                    "validator key from secrets ({}) is not in `validators`; \
+                     check that secrets correspond to this node",
+                    public_key.encode()
+                ));
+            }
+        }
+
+        if self.admin_addr.is_some() && secrets.admin_token.is_none() {
+            problems.push(
+                "`admin_addr` is set, but `admin_token` is missing from secrets; \
+                 the admin server cannot be started without authorization"
+                    .to_owned(),
+            );
+        }
+
+        let mut peers_by_addr = BTreeMap::<_, Vec<_>>::new();
+        for (key, addr) in &self.gossip_static_outbound {
+            peers_by_addr.entry(addr.0.as_str()).or_default().push(key);
+        }
+        for (addr, keys) in peers_by_addr {
+            if addr == self.public_addr.0 {
+                problems.push(format!(
+                    "`gossip_static_outbound` peer(s) {} use the public address \
+                     of this node ({addr})",
+                    encode_keys(&keys)
+                ));
+            } else if keys.len() > 1 {
+                problems.push(format!(
+                    "`gossip_static_outbound` peers {} share the same address {addr}; \
+                     each peer must have a distinct address",
+                    encode_keys(&keys)
+                ));
+            }
+        }
+
+        let mut consensus_addrs = vec![("consensus `server_addr`", self.server_addr)];
+        if let Some(admin_addr) = self.admin_addr {
+            consensus_addrs.push(("consensus `admin_addr`", admin_addr));
+        }
+        for (i, &(name, addr)) in consensus_addrs.iter().enumerate() {
+            let other_addrs = consensus_addrs[i + 1..].iter().chain(reserved_addrs);
+            for &(other_name, other_addr) in other_addrs {
+                if addrs_clash(addr, other_addr) {
+                    problems.push(format!(
+                        "{name} ({addr}) clashes with {other_name} ({other_addr}); \
+                         use a different port"
+                    ));
+                }
+            }
+        }
+
+        report_problems(problems)
+    }
+
+    /// Checks that the consensus genesis stored in `store` (if any) is consistent with the config
+    /// and `secrets` of the main node.
+    pub async fn validate_genesis(
+        &self,
+        ctx: &ctx::Ctx,
+        secrets: &Secrets,
+        store: &Store,
+    ) -> ctx::Result<()> {
+        let mut conn = store.access(ctx).await.wrap("access()")?;
+        let Some(genesis) = conn.genesis(ctx).await.wrap("genesis()")? else {
+            return Ok(());
+        };
+        drop(conn);
+
+        let mut problems = vec![];
+        if let Some(validator_key) = &secrets.validator_key {
+            let public_key = validator_key.public();
+            if !genesis.validators.iter().any(|key| *key == public_key) {
+                problems.push(format!(
+                    "validator key from secrets ({}) is not in the validator set of the consensus \
+                     genesis stored in DB; the node would not be able to produce certificates",
+                    public_key.encode()
+                ));
+            }
+        }
+        if genesis.validators != self.validators {
+            let genesis_validators: Vec<_> = genesis.validators.iter().collect();
+            let config_validators: Vec<_> = self.validators.iter().collect();
+            problems.push(format!(
+                "`validators` {} do not match the validator set of the consensus genesis \
+                 stored in DB {}",
+                encode_keys(&config_validators),
+                encode_keys(&genesis_validators)
+            ));
+        }
+        Ok(report_problems(problems)?)
+    }
+
     fn executor_config(&self, node_key: node::SecretKey) -> executor::Config {
         executor::Config {
             server_addr: self.server_addr,
@@ -120,6 +279,12 @@ impl ProtoFmt for Config {
                 .context("gossip_dynamic_inbound_limit")?,
             gossip_static_inbound,
             gossip_static_outbound,
+            admin_addr: r
+                .admin_addr
+                .as_ref()
+                .map(|addr| Text::new(addr).decode())
+                .transpose()
+                .context("admin_addr")?,
         })
     }
 
@@ -145,14 +310,26 @@ impl ProtoFmt for Config {
             gossip_dynamic_inbound_limit: Some(
                 self.gossip_dynamic_inbound_limit.try_into().unwrap(),
             ),
+            admin_addr: self.admin_addr.as_ref().map(TextFmt::encode),
         }
     }
 }
 
-#[derive(Debug)]
 pub struct Secrets {
     pub validator_key: Option<validator::SecretKey>,
     pub node_key: Option<node::SecretKey>,
+    /// Bearer token for the admin HTTP server.
+    pub admin_token: Option<String>,
+}
+
+impl fmt::Debug for Secrets {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Secrets")
+            .field("validator_key", &self.validator_key)
+            .field("node_key", &self.node_key)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ProtoFmt for Secrets {
@@ -161,6 +338,7 @@ impl ProtoFmt for Secrets {
         Ok(Self {
             validator_key: read_optional_secret_text(&r.validator_key).context("validator_key")?,
             node_key: read_optional_secret_text(&r.node_key).context("node_key")?,
+            admin_token: r.admin_token.clone(),
         })
     }
 
@@ -168,6 +346,7 @@ impl ProtoFmt for Secrets {
         Self::Proto {
             validator_key: self.validator_key.as_ref().map(TextFmt::encode),
             node_key: self.node_key.as_ref().map(TextFmt::encode),
+            admin_token: self.admin_token.clone(),
         }
     }
 }
@@ -3,12 +3,14 @@ use zksync_concurrency::{ctx, error::Wrap as _, limiter, scope, time};
 use zksync_consensus_executor as executor;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
-use zksync_types::MiniblockNumber;
+use zksync_types::{Address, L2ChainId, MiniblockNumber};
+use zksync_utils::retry::RetryPolicy;
 
 use crate::{
-    consensus::{storage, Store},
+    consensus::{admin, storage, AdminConfig, GossipPeers, Store},
     sync_layer::{
-        fetcher::FetchedBlock, sync_action::ActionQueueSender, MainNodeClient, SyncState,
+        fetcher::FetchedBlock, protocol_version::check_protocol_version,
+        sync_action::ActionQueueSender, MainNodeClient, SyncState,
     },
 };
 
@@ -21,18 +23,47 @@ pub struct Fetcher {
     pub client: Box<dyn MainNodeClient>,
     /// Rate limiter for `client.fetch_l2_block` requests.
     pub limiter: limiter::Limiter,
+    /// If set, miniblocks fetched using JSON-RPC must be signed by this signer.
+    pub block_signer: Option<BlockSigner>,
+}
+
+/// Expected signer of miniblocks fetched from the main node using JSON-RPC.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockSigner {
+    pub chain_id: L2ChainId,
+    pub address: Address,
 }
 
 impl Fetcher {
     /// Task fetching L2 blocks using peer-to-peer gossip network.
+    /// If `admin` is set, static gossip peers can be managed at runtime
+    /// via the admin HTTP server.
     /// NOTE: it still uses main node json RPC in some cases for now.
     pub async fn run_p2p(
         self,
         ctx: &ctx::Ctx,
         actions: ActionQueueSender,
         p2p: P2PConfig,
+        admin: Option<AdminConfig>,
     ) -> anyhow::Result<()> {
+        let capabilities = ctx
+            .wait(self.client.fetch_capabilities())
+            .await?
+            .context("fetch_capabilities()")?;
+        if !capabilities.consensus_genesis {
+            tracing::warn!(
+                "Main node doesn't provide consensus genesis; falling back to fetching blocks via JSON-RPC"
+            );
+            return self.run_centralized(ctx, actions).await;
+        }
+
+        let peers = GossipPeers::new(p2p.gossip_static_outbound.clone().into_iter().collect());
         let res: ctx::Result<()> = scope::run!(ctx, |ctx, s| async {
+            if let Some(admin_config) = admin {
+                s.spawn_bg(async {
+                    Ok(admin::run_server(ctx, admin_config, peers.clone(), None).await?)
+                });
+            }
             // Update sync state in the background.
             s.spawn_bg(self.fetch_state_loop(ctx));
 
@@ -53,20 +84,7 @@ impl Fetcher {
                 .await?;
             // Monitor the genesis of the main node.
             // If it changes, it means that a hard fork occurred and we need to reset the consensus state.
-            s.spawn_bg::<()>(async {
-                let old = genesis;
-                loop {
-                    if let Ok(new) = self.fetch_genesis(ctx).await {
-                        if new != old {
-                            return Err(anyhow::format_err!(
-                                "genesis changed: old {old:?}, new {new:?}"
-                            )
-                            .into());
-                        }
-                    }
-                    ctx.sleep(time::Duration::seconds(5)).await?;
-                }
-            });
+            s.spawn_bg(self.watch_genesis(ctx, genesis));
 
             // Run consensus component.
             let mut block_store = self.store.clone().into_block_store();
@@ -77,8 +95,51 @@ impl Fetcher {
                 .await
                 .wrap("BlockStore::new()")?;
             s.spawn_bg(async { Ok(runner.run(ctx).await?) });
+            peers
+                .run_executor(ctx, p2p, |config| executor::Executor {
+                    config,
+                    block_store: block_store.clone(),
+                    validator: None,
+                })
+                .await?;
+            Ok(())
+        })
+        .await;
+        match res {
+            Ok(()) | Err(ctx::Error::Canceled(_)) => Ok(()),
+            Err(ctx::Error::Internal(err)) => Err(err),
+        }
+    }
+
+    /// Task verifying and storing consensus certificates received over the gossip network,
+    /// without pushing any actions to the `StateKeeper` (read-only attestor mode).
+    /// Miniblocks are expected to be persisted by some other component: a certificate
+    /// for a miniblock is stored only once the miniblock is observed in storage.
+    pub async fn run_attestor(self, ctx: &ctx::Ctx, p2p: P2PConfig) -> anyhow::Result<()> {
+        let res: ctx::Result<()> = scope::run!(ctx, |ctx, s| async {
+            // Update sync state in the background.
+            s.spawn_bg(self.fetch_state_loop(ctx));
+
+            // Initialize genesis.
+            let genesis = self.fetch_genesis(ctx).await.wrap("fetch_genesis()")?;
+            self.store
+                .access(ctx)
+                .await
+                .wrap("access()")?
+                .try_update_genesis(ctx, &genesis)
+                .await
+                .wrap("set_genesis()")?;
+            s.spawn_bg(self.watch_genesis(ctx, genesis));
+
+            // Run consensus component. `BlockStore` without a cursor doesn't produce any actions;
+            // it only waits for the miniblocks to appear in storage and stores their certificates.
+            let block_store = self.store.clone().into_block_store();
+            let (block_store, runner) = BlockStore::new(ctx, Box::new(block_store))
+                .await
+                .wrap("BlockStore::new()")?;
+            s.spawn_bg(async { Ok(runner.run(ctx).await?) });
             let executor = executor::Executor {
-                config: p2p.clone(),
+                config: p2p,
                 block_store,
                 validator: None,
             };
@@ -147,19 +208,62 @@ impl Fetcher {
         Ok(zksync_protobuf::serde::deserialize(&genesis.0).context("deserialize(genesis)")?)
     }
 
+    /// Periodically fetches genesis from the main node and returns an error if it differs from `old`.
+    async fn watch_genesis(&self, ctx: &ctx::Ctx, old: validator::Genesis) -> ctx::Result<()> {
+        loop {
+            if let Ok(new) = self.fetch_genesis(ctx).await {
+                if new != old {
+                    return Err(
+                        anyhow::format_err!("genesis changed: old {old:?}, new {new:?}").into(),
+                    );
+                }
+            }
+            ctx.sleep(time::Duration::seconds(5)).await?;
+        }
+    }
+
     /// Fetches (with retries) the given block from the main node.
     async fn fetch_block(&self, ctx: &ctx::Ctx, n: MiniblockNumber) -> ctx::Result<FetchedBlock> {
         // TODO: consider removing sleep in favor to just relying on the rate limiter.
         const RETRY_INTERVAL: time::Duration = time::Duration::seconds(5);
+        let mut retrier =
+            RetryPolicy::indefinite(RETRY_INTERVAL.unsigned_abs()).retrier("consensus_fetcher");
         loop {
             self.limiter.acquire(ctx, 1).await?;
             let res = ctx.wait(self.client.fetch_l2_block(n, true)).await?;
             match res {
-                Ok(Some(block)) => return Ok(block.try_into()?),
+                Ok(Some(block)) => {
+                    if let Some(signer) = &self.block_signer {
+                        // Not retried: an invalid signature means that the main node or the connection to it
+                        // is compromised (or misconfigured), which requires operator attention.
+                        block
+                            .verify_signature(signer.chain_id, signer.address)
+                            .with_context(|| format!("miniblock #{n} has invalid signature"))?;
+                    }
+                    return Ok(block.try_into()?);
+                }
                 Ok(None) => {}
-                Err(err) if err.is_transient() => {}
                 Err(err) => {
-                    return Err(anyhow::format_err!("client.fetch_l2_block({}): {err}", n).into());
+                    // Transient errors are retried after a delay; other errors are reported.
+                    if let Err(err) = retrier.handle_error(err) {
+                        // Blocks with a protocol version unknown to this node cannot be deserialized.
+                        // Instead of failing, stop before the first such block until the node is updated.
+                        let version = ctx
+                            .wait(check_protocol_version(
+                                self.client.as_ref(),
+                                &self.sync_state,
+                            ))
+                            .await?;
+                        let Ok(Some(version)) = version else {
+                            return Err(
+                                anyhow::format_err!("client.fetch_l2_block({}): {err}", n).into()
+                            );
+                        };
+                        tracing::debug!(
+                            "Cannot fetch block {n}, main node runs unsupported protocol version {}: {err}",
+                            version.version_id
+                        );
+                    }
                 }
             }
             ctx.sleep(RETRY_INTERVAL).await?;
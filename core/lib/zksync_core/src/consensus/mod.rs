@@ -7,10 +7,15 @@ use zksync_consensus_executor as executor;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
 
-pub use self::{fetcher::*, storage::Store};
+pub use self::{
+    admin::AdminConfig, fetcher::*, peers::GossipPeers, proposer::ProposerControl, storage::Store,
+};
 
+mod admin;
 mod config;
 mod fetcher;
+mod peers;
+mod proposer;
 mod storage;
 #[cfg(test)]
 pub(crate) mod testonly;
@@ -24,32 +29,68 @@ pub use config::{Config, Secrets};
 pub struct MainNodeConfig {
     pub executor: executor::Config,
     pub validator_key: validator::SecretKey,
+    /// Validator set used to initialize consensus genesis if it's not stored in DB yet.
+    pub validators: validator::ValidatorSet,
+    /// Config of the admin HTTP server allowing to pause / resume block proposals
+    /// and to manage static gossip peers. If not set, the server is not started.
+    pub admin: Option<AdminConfig>,
 }
 
 impl MainNodeConfig {
     /// Task generating consensus certificates for the miniblocks generated by `StateKeeper`.
     /// Broadcasts the blocks with certificates to gossip network peers.
     pub async fn run(self, ctx: &ctx::Ctx, store: Store) -> anyhow::Result<()> {
+        self.run_with_control(ctx, store, ProposerControl::default())
+            .await
+    }
+
+    /// Same as [`Self::run()`], but block proposals can be paused using the provided `control`,
+    /// e.g. to hand leadership over to a standby instance.
+    pub async fn run_with_control(
+        self,
+        ctx: &ctx::Ctx,
+        store: Store,
+        control: ProposerControl,
+    ) -> anyhow::Result<()> {
+        let peers = GossipPeers::new(
+            self.executor
+                .gossip_static_outbound
+                .clone()
+                .into_iter()
+                .collect(),
+        );
         scope::run!(&ctx, |ctx, s| async {
+            if let Some(admin_config) = self.admin.clone() {
+                s.spawn_bg(admin::run_server(
+                    ctx,
+                    admin_config,
+                    peers.clone(),
+                    Some(control.clone()),
+                ));
+            }
             let mut block_store = store.clone().into_block_store();
             block_store
-                .try_init_genesis(ctx, &self.validator_key.public())
+                .try_init_genesis(ctx, &self.validators)
                 .await
                 .wrap("block_store.try_init_genesis()")?;
             let (block_store, runner) = BlockStore::new(ctx, Box::new(block_store))
                 .await
                 .wrap("BlockStore::new()")?;
             s.spawn_bg(runner.run(ctx));
-            let executor = executor::Executor {
-                config: self.executor,
-                block_store,
-                validator: Some(executor::Validator {
-                    key: self.validator_key,
-                    replica_store: Box::new(store.clone()),
-                    payload_manager: Box::new(store.clone()),
-                }),
-            };
-            executor.run(ctx).await
+            peers
+                .run_executor(ctx, self.executor, |config| executor::Executor {
+                    config,
+                    block_store: block_store.clone(),
+                    validator: Some(executor::Validator {
+                        key: self.validator_key.clone(),
+                        replica_store: Box::new(store.clone()),
+                        payload_manager: Box::new(proposer::Proposer {
+                            store: store.clone(),
+                            control: control.clone(),
+                        }),
+                    }),
+                })
+                .await
         })
         .await
     }
@@ -0,0 +1,81 @@
+//! Runtime management of static gossip peers.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use zksync_concurrency::{ctx, net, scope, sync};
+use zksync_consensus_executor as executor;
+use zksync_consensus_roles::node;
+
+/// Handle allowing to add / remove outbound gossip peers while the consensus component is running.
+///
+/// Changing the peers restarts the consensus executor with the updated network config;
+/// the block store and the consensus state are preserved across restarts.
+#[derive(Debug, Clone)]
+pub struct GossipPeers {
+    peers: Arc<sync::watch::Sender<BTreeMap<node::PublicKey, net::Host>>>,
+}
+
+impl GossipPeers {
+    /// Creates a handle with the initial set of peers.
+    pub fn new(peers: BTreeMap<node::PublicKey, net::Host>) -> Self {
+        Self {
+            peers: Arc::new(sync::watch::channel(peers).0),
+        }
+    }
+
+    /// Returns the current set of peers.
+    pub fn peers(&self) -> BTreeMap<node::PublicKey, net::Host> {
+        self.peers.borrow().clone()
+    }
+
+    /// Adds a peer or updates the address of an existing one. Returns `false` if the peer set is unchanged.
+    pub fn add_peer(&self, key: node::PublicKey, addr: net::Host) -> bool {
+        self.peers.send_if_modified(|peers| {
+            let prev_addr = peers.insert(key, addr.clone());
+            prev_addr.as_ref() != Some(&addr)
+        })
+    }
+
+    /// Removes a peer. Returns `false` if the peer was not present.
+    pub fn remove_peer(&self, key: &node::PublicKey) -> bool {
+        self.peers
+            .send_if_modified(|peers| peers.remove(key).is_some())
+    }
+
+    /// Runs the executor produced by `make_executor`, restarting it each time the set of peers changes.
+    pub(super) async fn run_executor<F>(
+        &self,
+        ctx: &ctx::Ctx,
+        mut config: executor::Config,
+        make_executor: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn(executor::Config) -> executor::Executor,
+    {
+        let mut peers_receiver = self.peers.subscribe();
+        loop {
+            let peers = peers_receiver.borrow_and_update().clone();
+            tracing::info!(
+                "Starting consensus executor with {} static gossip peer(s)",
+                peers.len()
+            );
+            config.gossip_static_outbound = peers.into_iter().collect();
+            let executor = make_executor(config.clone());
+            let res: ctx::Result<bool> = scope::run!(ctx, |ctx, s| async {
+                s.spawn_bg(async { Ok(executor.run(ctx).await?) });
+                // Returning from the scope cancels the executor.
+                Ok(ctx.wait(peers_receiver.changed()).await?.is_ok())
+            })
+            .await;
+            match res {
+                Ok(true) => {
+                    tracing::info!("Static gossip peers changed; restarting consensus executor");
+                }
+                // The sender is only dropped together with `self`, which cannot happen while this method is running.
+                Ok(false) => unreachable!("gossip peers sender dropped"),
+                Err(ctx::Error::Canceled(_)) => return Ok(()),
+                Err(ctx::Error::Internal(err)) => return Err(err),
+            }
+        }
+    }
+}
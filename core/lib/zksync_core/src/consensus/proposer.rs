@@ -0,0 +1,96 @@
+//! Control over block proposals made by the main node consensus task.
+
+use std::sync::Arc;
+
+use zksync_concurrency::{ctx, error::Wrap as _, sync};
+use zksync_consensus_bft::PayloadManager;
+use zksync_consensus_roles::validator;
+
+use super::Store;
+
+/// Handle allowing to pause block proposals of the main node consensus task, e.g. to hand leadership
+/// over to a standby instance during a sequencer upgrade.
+///
+/// Pausing takes effect at the L1 batch boundary: the task finishes proposing blocks for the current
+/// L1 batch and then waits until proposing is resumed.
+#[derive(Debug, Clone)]
+pub struct ProposerControl {
+    paused: Arc<sync::watch::Sender<bool>>,
+}
+
+impl Default for ProposerControl {
+    fn default() -> Self {
+        Self {
+            paused: Arc::new(sync::watch::channel(false).0),
+        }
+    }
+}
+
+impl ProposerControl {
+    /// Stops proposing blocks after the current L1 batch.
+    pub fn pause_proposing(&self) {
+        if !self.paused.send_replace(true) {
+            tracing::info!("Block proposals will be paused after the current L1 batch");
+        }
+    }
+
+    /// Resumes proposing blocks.
+    pub fn resume_proposing(&self) {
+        if self.paused.send_replace(false) {
+            tracing::info!("Block proposals are resumed");
+        }
+    }
+
+    /// Checks whether proposing blocks is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    async fn wait_until_resumed(&self, ctx: &ctx::Ctx) -> ctx::OrCanceled<()> {
+        let mut paused = self.paused.subscribe();
+        sync::wait_for(ctx, &mut paused, |paused| !*paused).await?;
+        Ok(())
+    }
+}
+
+/// `PayloadManager` respecting [`ProposerControl`].
+#[derive(Debug)]
+pub(super) struct Proposer {
+    pub(super) store: Store,
+    pub(super) control: ProposerControl,
+}
+
+#[async_trait::async_trait]
+impl PayloadManager for Proposer {
+    async fn propose(
+        &self,
+        ctx: &ctx::Ctx,
+        block_number: validator::BlockNumber,
+    ) -> ctx::Result<validator::Payload> {
+        if let Some(prev) = block_number.prev() {
+            // Only pause proposals at the L1 batch boundary, so that the standby instance
+            // starts proposing from a new L1 batch.
+            if self.control.is_paused() {
+                let prev_payload = self
+                    .store
+                    .wait_for_payload(ctx, prev)
+                    .await
+                    .wrap("wait_for_payload()")?;
+                if prev_payload.last_in_batch {
+                    tracing::info!("Proposing block {block_number} is paused");
+                    self.control.wait_until_resumed(ctx).await?;
+                }
+            }
+        }
+        self.store.propose(ctx, block_number).await
+    }
+
+    async fn verify(
+        &self,
+        ctx: &ctx::Ctx,
+        block_number: validator::BlockNumber,
+        payload: &validator::Payload,
+    ) -> ctx::Result<()> {
+        self.store.verify(ctx, block_number, payload).await
+    }
+}
@@ -227,12 +227,12 @@ impl Store {
 }
 
 impl BlockStore {
-    /// Initializes consensus genesis (with 1 validator) to start at the last miniblock in storage.
-    /// No-op if db already contains a genesis.
+    /// Initializes consensus genesis with the specified `validators` to start at the last miniblock
+    /// in storage. No-op if db already contains a genesis.
     pub async fn try_init_genesis(
         &mut self,
         ctx: &ctx::Ctx,
-        validator_key: &validator::PublicKey,
+        validators: &validator::ValidatorSet,
     ) -> ctx::Result<()> {
         let mut conn = self.inner.access(ctx).await.wrap("access()")?;
         let block_range = conn.block_range(ctx).await.wrap("block_range()")?;
@@ -244,8 +244,7 @@ impl BlockStore {
             return Ok(());
         }
         let genesis = validator::Genesis {
-            // `ValidatorSet::new()` with a single validator should never fail.
-            validators: validator::ValidatorSet::new([validator_key.clone()]).unwrap(),
+            validators: validators.clone(),
             fork: validator::Fork {
                 number: validator::ForkNumber(0),
                 first_block: block_range.end,
@@ -31,7 +31,7 @@ use crate::{
     sync_layer::{
         fetcher::FetchedTransaction,
         sync_action::{ActionQueue, ActionQueueSender, SyncAction},
-        ExternalIO, MainNodeClient, SyncState,
+        ExternalIO, MainNodeCapabilities, MainNodeClient, SyncState,
     },
     utils::testonly::{create_l1_batch_metadata, create_l2_transaction},
 };
@@ -61,6 +61,7 @@ impl MockMainNodeClient {
             virtual_blocks: Some(0),
             hash: Some(snapshot.miniblock_hash),
             protocol_version: ProtocolVersionId::latest(),
+            signature: None,
         };
 
         Self {
@@ -106,6 +107,16 @@ impl MainNodeClient for MockMainNodeClient {
         Ok(self.protocol_versions.get(&protocol_version).cloned())
     }
 
+    async fn fetch_latest_protocol_version(
+        &self,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        let latest = self
+            .protocol_versions
+            .iter()
+            .max_by_key(|(&version_id, _)| version_id);
+        Ok(latest.map(|(_, version)| version.clone()))
+    }
+
     async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
         if let Some(number) = self.l2_blocks.len().checked_sub(1) {
             Ok(MiniblockNumber(number as u32))
@@ -143,6 +154,10 @@ impl MainNodeClient for MockMainNodeClient {
     async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig> {
         Ok(mock_genesis_config())
     }
+
+    async fn fetch_capabilities(&self) -> EnrichedClientResult<MainNodeCapabilities> {
+        Ok(MainNodeCapabilities::all(false))
+    }
 }
 
 /// Fake StateKeeper for tests.
@@ -308,6 +323,7 @@ impl StateKeeper {
             client: Box::new(client),
             sync_state: SyncState::default(),
             limiter: unbounded_limiter(ctx),
+            block_signer: None,
         }
         .run_centralized(ctx, self.actions_sender)
         .await
@@ -325,8 +341,31 @@ impl StateKeeper {
             client: Box::new(client),
             sync_state: SyncState::default(),
             limiter: unbounded_limiter(ctx),
+            block_signer: None,
         }
-        .run_p2p(ctx, self.actions_sender, cfg)
+        .run_p2p(ctx, self.actions_sender, cfg, None)
+        .await
+    }
+
+    /// Runs the centralized fetcher together with the attestor,
+    /// so that the fetched miniblocks get certificates from the gossip network.
+    pub async fn run_attesting_fetcher(
+        self,
+        ctx: &ctx::Ctx,
+        client: HttpClient,
+        cfg: P2PConfig,
+    ) -> anyhow::Result<()> {
+        let attestor = Fetcher {
+            store: self.store.clone(),
+            client: Box::new(client.clone()),
+            sync_state: SyncState::default(),
+            limiter: unbounded_limiter(ctx),
+            block_signer: None,
+        };
+        scope::run!(ctx, |ctx, s| async {
+            s.spawn_bg(attestor.run_attestor(ctx, cfg));
+            self.run_centralized_fetcher(ctx, client).await
+        })
         .await
     }
 }
@@ -2,16 +2,18 @@ use anyhow::Context as _;
 use rand::{distributions::Distribution, Rng};
 use test_casing::test_casing;
 use tracing::Instrument as _;
-use zksync_concurrency::{ctx, scope};
+use zksync_concurrency::{ctx, net, scope, time};
+use zksync_consensus_bft::PayloadManager as _;
 use zksync_consensus_executor as executor;
 use zksync_consensus_network as network;
 use zksync_consensus_network::testonly::{new_configs, new_fullnode};
-use zksync_consensus_roles::validator::testonly::Setup;
+use zksync_consensus_roles::{node, validator::testonly::Setup};
 use zksync_consensus_storage as storage;
 use zksync_consensus_storage::PersistentBlockStore as _;
 use zksync_consensus_utils::EncodeDist;
 use zksync_protobuf::testonly::{test_encode_all_formats, FmtConv};
 use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_web3_decl::namespaces::EnNamespaceClient as _;
 
 use super::*;
 use crate::utils::testonly::Snapshot;
@@ -72,6 +74,191 @@ async fn test_validator_block_store() {
     }
 }
 
+#[test]
+fn test_gossip_peers() {
+    let rng = &mut ctx::test_root(&ctx::RealClock).rng();
+    let key = rng.gen::<node::SecretKey>().public();
+    let peers = GossipPeers::new([(key.clone(), net::Host("127.0.0.1:1234".into()))].into());
+
+    assert!(!peers.add_peer(key.clone(), net::Host("127.0.0.1:1234".into())));
+    assert!(peers.add_peer(key.clone(), net::Host("127.0.0.1:4321".into())));
+    let other_key = rng.gen::<node::SecretKey>().public();
+    assert!(peers.add_peer(other_key.clone(), net::Host("127.0.0.1:5678".into())));
+    assert_eq!(peers.peers().len(), 2);
+    assert_eq!(peers.peers()[&key], net::Host("127.0.0.1:4321".into()));
+
+    assert!(peers.remove_peer(&other_key));
+    assert!(!peers.remove_peer(&other_key));
+    assert_eq!(peers.peers().into_keys().collect::<Vec<_>>(), [key]);
+}
+
+#[test]
+fn test_proposer_control() {
+    let control = ProposerControl::default();
+    assert!(!control.is_paused());
+    control.pause_proposing();
+    assert!(control.is_paused());
+    // Pausing is idempotent.
+    control.pause_proposing();
+    assert!(control.is_paused());
+    control.resume_proposing();
+    assert!(!control.is_paused());
+}
+
+// Paused proposer should finish proposing blocks for the current L1 batch and stop at the batch boundary.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_proposer_pauses_at_batch_boundary() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+
+    scope::run!(ctx, |ctx, s| async {
+        let store = new_store(false).await;
+        let (mut sk, runner) = testonly::StateKeeper::new(ctx, store.clone()).await?;
+        s.spawn_bg(runner.run(ctx));
+        sk.push_block(1).await;
+        sk.push_block(1).await;
+        let block_in_batch = sk.last_block();
+        // Produces the last (fictive) block in the batch.
+        sk.seal_batch().await;
+        let last_block_in_batch = sk.last_block();
+        sk.push_block(1).await;
+        let first_block_in_next_batch = sk.last_block();
+        store
+            .wait_for_payload(ctx, first_block_in_next_batch)
+            .await
+            .context("wait_for_payload()")?;
+
+        let control = ProposerControl::default();
+        let proposer = proposer::Proposer {
+            store: store.clone(),
+            control: control.clone(),
+        };
+        control.pause_proposing();
+        for block in [block_in_batch, last_block_in_batch] {
+            let payload = proposer.propose(ctx, block).await?;
+            assert_eq!(payload, store.propose(ctx, block).await?);
+        }
+
+        let timeout_ctx = &ctx.with_timeout(time::Duration::seconds(1));
+        let res = proposer
+            .propose(timeout_ctx, first_block_in_next_batch)
+            .await;
+        assert!(matches!(res, Err(ctx::Error::Canceled(_))), "{res:?}");
+
+        // Resume proposing in the background while the proposer is waiting.
+        let resumed_control = control.clone();
+        s.spawn_bg(async move {
+            ctx.sleep(time::Duration::milliseconds(100)).await?;
+            resumed_control.resume_proposing();
+            Ok(())
+        });
+        let payload = proposer.propose(ctx, first_block_in_next_batch).await?;
+        assert_eq!(
+            payload,
+            store.propose(ctx, first_block_in_next_batch).await?
+        );
+        assert!(!control.is_paused());
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_config_validation() {
+    let rng = &mut ctx::test_root(&ctx::RealClock).rng();
+    let node_key: node::SecretKey = rng.gen();
+    let validator_key: validator::SecretKey = rng.gen();
+    let peer_key = rng.gen::<node::SecretKey>().public();
+    let mut cfg = Config {
+        server_addr: "127.0.0.1:3054".parse().unwrap(),
+        public_addr: net::Host("127.0.0.1:3054".into()),
+        validators: validator::ValidatorSet::new([validator_key.public()]).unwrap(),
+        max_payload_size: 1_000_000,
+        gossip_dynamic_inbound_limit: 10,
+        gossip_static_inbound: Default::default(),
+        gossip_static_outbound: [(peer_key, net::Host("127.0.0.1:3055".into()))].into(),
+        admin_addr: None,
+    };
+    let secrets = Secrets {
+        validator_key: Some(validator_key),
+        node_key: Some(node_key.clone()),
+        admin_token: None,
+    };
+    let reserved_addrs = [("HTTP API server", "0.0.0.0:3050".parse().unwrap())];
+    cfg.validate(&secrets, &reserved_addrs).unwrap();
+
+    cfg.gossip_static_outbound
+        .insert(node_key.public(), cfg.public_addr.clone());
+    cfg.admin_addr = Some("127.0.0.1:3050".parse().unwrap());
+    let err = cfg
+        .validate(&secrets, &reserved_addrs)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("contains the key of this node"), "{err}");
+    assert!(err.contains("public address of this node"), "{err}");
+    assert!(err.contains("clashes with HTTP API server"), "{err}");
+    assert!(err.contains("`admin_token` is missing"), "{err}");
+}
+
+// Consensus genesis initialized by the main node should use the validator set from the config,
+// so that it passes `Config::validate_genesis()`.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_genesis_validators() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::RealClock);
+    let rng = &mut ctx.rng();
+    let setup = Setup::new(rng, 3);
+    let store = new_store(false).await;
+    let cfg = Config {
+        server_addr: "127.0.0.1:3054".parse().unwrap(),
+        public_addr: net::Host("127.0.0.1:3054".into()),
+        validators: setup.genesis.validators.clone(),
+        max_payload_size: 1_000_000,
+        gossip_dynamic_inbound_limit: 10,
+        gossip_static_inbound: Default::default(),
+        gossip_static_outbound: Default::default(),
+        admin_addr: None,
+    };
+    let secrets = Secrets {
+        validator_key: Some(setup.keys[0].clone()),
+        node_key: None,
+        admin_token: None,
+    };
+    // There's no genesis in DB yet.
+    cfg.validate_genesis(ctx, &secrets, &store).await.unwrap();
+
+    let mut block_store = store.clone().into_block_store();
+    block_store
+        .try_init_genesis(ctx, &cfg.validators)
+        .await
+        .unwrap();
+    let genesis = store
+        .access(ctx)
+        .await
+        .unwrap()
+        .genesis(ctx)
+        .await
+        .unwrap()
+        .expect("no genesis");
+    assert_eq!(genesis.validators, cfg.validators);
+    cfg.validate_genesis(ctx, &secrets, &store).await.unwrap();
+
+    let single_validator_cfg = Config {
+        validators: validator::ValidatorSet::new([setup.keys[0].public()]).unwrap(),
+        ..cfg
+    };
+    let err = single_validator_cfg
+        .validate_genesis(ctx, &secrets, &store)
+        .await
+        .unwrap_err();
+    let ctx::Error::Internal(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.to_string();
+    assert!(err.contains("do not match the validator set"), "{err}");
+}
+
 fn executor_config(cfg: &network::Config) -> executor::Config {
     executor::Config {
         server_addr: *cfg.server_addr,
@@ -118,6 +305,8 @@ async fn test_validator(from_snapshot: bool) {
                 let cfg = MainNodeConfig {
                     executor: executor_config(&cfgs[0]),
                     validator_key: setup.keys[0].clone(),
+                    validators: setup.genesis.validators.clone(),
+                    admin: None,
                 };
                 s.spawn_bg(cfg.run(ctx, store.clone()));
 
@@ -153,6 +342,65 @@ async fn test_validator(from_snapshot: bool) {
     .unwrap();
 }
 
+// Certificates stored by the validator should be served via the `en_blockCertificate` RPC method.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_certificate_api() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+    let rng = &mut ctx.rng();
+    let setup = Setup::new(rng, 1);
+    let cfgs = new_configs(rng, &setup, 0);
+
+    scope::run!(ctx, |ctx, s| async {
+        let store = new_store(false).await;
+        let (mut sk, runner) = testonly::StateKeeper::new(ctx, store.clone()).await?;
+        s.spawn_bg(runner.run(ctx));
+        // API server needs at least 1 L1 batch to start.
+        sk.seal_batch().await;
+        let client = sk.connect(ctx).await?;
+
+        let cfg = MainNodeConfig {
+            executor: executor_config(&cfgs[0]),
+            validator_key: setup.keys[0].clone(),
+            validators: setup.genesis.validators.clone(),
+            admin: None,
+        };
+        s.spawn_bg(cfg.run(ctx, store.clone()));
+        sk.push_random_blocks(rng, 3).await;
+        store
+            .wait_for_certificate(ctx, sk.last_block())
+            .await
+            .context("wait_for_certificate()")?;
+        let want = store
+            .access(ctx)
+            .await
+            .wrap("access()")?
+            .certificate(ctx, sk.last_block())
+            .await
+            .wrap("certificate()")?
+            .context("certificate is missing")?;
+
+        let number = MiniblockNumber(sk.last_block().0.try_into().unwrap());
+        let got = client
+            .block_certificate(number)
+            .await
+            .context("block_certificate()")?
+            .context("certificate is missing")?;
+        let got: validator::CommitQC =
+            zksync_protobuf::serde::deserialize(got.0).context("deserialize()")?;
+        assert_eq!(got, want);
+
+        let missing = client
+            .block_certificate(number + 1)
+            .await
+            .context("block_certificate()")?;
+        assert!(missing.is_none());
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
 // Test running a validator node and 2 full nodes recovered from different snapshots.
 #[tokio::test(flavor = "multi_thread")]
 async fn test_nodes_from_various_snapshots() {
@@ -171,6 +419,8 @@ async fn test_nodes_from_various_snapshots() {
         let cfg = MainNodeConfig {
             executor: executor_config(&validator_cfg),
             validator_key: setup.keys[0].clone(),
+            validators: setup.genesis.validators.clone(),
+            admin: None,
         };
         s.spawn_bg(cfg.run(ctx, validator_store.clone()));
 
@@ -273,6 +523,8 @@ async fn test_full_nodes(from_snapshot: bool) {
         let cfg = MainNodeConfig {
             executor: executor_config(&validator_cfgs[0]),
             validator_key: setup.keys[0].clone(),
+            validators: setup.genesis.validators.clone(),
+            admin: None,
         };
         s.spawn_bg(cfg.run(ctx, validator_store.clone()));
 
@@ -340,6 +592,8 @@ async fn test_p2p_fetcher_backfill_certs(from_snapshot: bool) {
             MainNodeConfig {
                 executor: executor_config(&validator_cfg),
                 validator_key: setup.keys[0].clone(),
+                validators: setup.genesis.validators.clone(),
+                admin: None,
             }
             .run(ctx, validator_store.clone()),
         );
@@ -439,6 +693,56 @@ async fn test_centralized_fetcher(from_snapshot: bool) {
     .unwrap();
 }
 
+// Attestor stores certificates for the miniblocks fetched by the centralized fetcher.
+#[test_casing(2, [false, true])]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_attestor(from_snapshot: bool) {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+    let rng = &mut ctx.rng();
+    let setup = Setup::new(rng, 1);
+    let validator_cfg = new_configs(rng, &setup, 0)[0].clone();
+    let node_cfg = executor_config(&new_fullnode(rng, &validator_cfg));
+
+    scope::run!(ctx, |ctx, s| async {
+        tracing::info!("Spawn validator.");
+        let validator_store = new_store(from_snapshot).await;
+        let (mut validator, runner) =
+            testonly::StateKeeper::new(ctx, validator_store.clone()).await?;
+        s.spawn_bg(runner.run(ctx).instrument(tracing::info_span!("validator")));
+        s.spawn_bg(
+            MainNodeConfig {
+                executor: executor_config(&validator_cfg),
+                validator_key: setup.keys[0].clone(),
+                validators: setup.genesis.validators.clone(),
+                admin: None,
+            }
+            .run(ctx, validator_store.clone()),
+        );
+        // API server needs at least 1 L1 batch to start.
+        validator.seal_batch().await;
+
+        tracing::info!("Spawn attesting node.");
+        let node_store = new_store(from_snapshot).await;
+        let (node, runner) = testonly::StateKeeper::new(ctx, node_store.clone()).await?;
+        s.spawn_bg(runner.run(ctx).instrument(tracing::info_span!("node")));
+        s.spawn_bg(node.run_attesting_fetcher(ctx, validator.connect(ctx).await?, node_cfg));
+
+        tracing::info!("Produce some blocks and wait for node to certify them");
+        validator.push_random_blocks(rng, 5).await;
+        let want = validator_store
+            .wait_for_certificates_and_verify(ctx, validator.last_block())
+            .await?;
+        let got = node_store
+            .wait_for_certificates_and_verify(ctx, validator.last_block())
+            .await?;
+        assert_eq!(want, got);
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
 impl Distribution<Config> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Config {
         Config {
@@ -452,6 +756,7 @@ impl Distribution<Config> for EncodeDist {
                 .sample_range(rng)
                 .map(|_| (rng.gen(), self.sample(rng)))
                 .collect(),
+            admin_addr: self.sample_opt(|| self.sample(rng)),
         }
     }
 }
@@ -461,6 +766,7 @@ impl Distribution<Secrets> for EncodeDist {
         Secrets {
             validator_key: self.sample_opt(|| rng.gen()),
             node_key: self.sample_opt(|| rng.gen()),
+            admin_token: self.sample_opt(|| format!("{:x}", rng.gen::<u128>())),
         }
     }
 }
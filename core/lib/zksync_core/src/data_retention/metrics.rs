@@ -0,0 +1,25 @@
+//! Metrics for the data retention component.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, LabeledFamily, Metrics, Unit};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_data_retention")]
+pub(super) struct DataRetentionMetrics {
+    /// Number of rows removed from a table because their retention period has expired.
+    #[metrics(labels = ["table"])]
+    pub deleted_rows: LabeledFamily<&'static str, Counter>,
+    /// Last miniblock for which data in a table has expired.
+    #[metrics(labels = ["table"])]
+    pub last_expired_miniblock: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Latency of removing a single batch of expired rows.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["table"])]
+    pub deletion_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency of a single iteration (i.e., removing all expired rows from all tables).
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub iteration_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DataRetentionMetrics> = vise::Global::new();
@@ -0,0 +1,206 @@
+//! Component enforcing retention policies for historical data in Postgres (events and transaction traces).
+//!
+//! Data is considered expired once the miniblock it belongs to is older than the retention period configured
+//! for the table. Expired rows are removed in batches of bounded size, so that the component doesn't hold locks
+//! on the affected tables for a long time. Postgres doesn't return the freed space to the OS immediately;
+//! it's reused for new rows after the table is vacuumed (normally, by autovacuum).
+//!
+//! Removing data affects the API: e.g., `eth_getLogs` doesn't return events in expired miniblocks.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::DataRetentionConfig;
+use zksync_dal::{retention_dal::RetentionTable, ConnectionPool, Core, CoreDal};
+use zksync_utils::time::seconds_since_epoch;
+
+use self::metrics::METRICS;
+
+mod metrics;
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+/// Periodically removes data older than the configured retention period.
+#[derive(Debug)]
+pub struct DataRetention {
+    config: DataRetentionConfig,
+    pool: ConnectionPool<Core>,
+}
+
+impl DataRetention {
+    pub fn new(config: DataRetentionConfig, pool: ConnectionPool<Core>) -> Self {
+        Self { config, pool }
+    }
+
+    fn retention_period(&self, table: RetentionTable) -> Option<Duration> {
+        let days = match table {
+            RetentionTable::Events => self.config.events_retention_days,
+            RetentionTable::CallTraces => self.config.call_traces_retention_days,
+            RetentionTable::TransactionTraces => self.config.transaction_traces_retention_days,
+        }?;
+        Some(Duration::from_secs(u64::from(days) * SECONDS_IN_DAY))
+    }
+
+    /// Removes expired rows from all tables with a configured retention period. `now` is the current timestamp
+    /// in seconds since UNIX epoch.
+    async fn remove_expired_data(
+        &self,
+        now: u64,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let latency = METRICS.iteration_latency.start();
+        for table in RetentionTable::ALL {
+            let Some(retention_period) = self.retention_period(table) else {
+                continue;
+            };
+            let cutoff_timestamp = now.saturating_sub(retention_period.as_secs());
+            self.remove_expired_rows(table, cutoff_timestamp, stop_receiver)
+                .await
+                .with_context(|| format!("failed removing expired rows from `{table:?}`"))?;
+            if *stop_receiver.borrow() {
+                return Ok(());
+            }
+        }
+        latency.observe();
+        Ok(())
+    }
+
+    async fn remove_expired_rows(
+        &self,
+        table: RetentionTable,
+        cutoff_timestamp: u64,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let table_name = table.table_name();
+        let mut storage = self.pool.connection_tagged("data_retention").await?;
+        let Some(last_expired_miniblock) = storage
+            .retention_dal()
+            .get_last_miniblock_before(cutoff_timestamp)
+            .await
+            .context("failed getting last expired miniblock")?
+        else {
+            return Ok(());
+        };
+        METRICS.last_expired_miniblock[&table_name].set(last_expired_miniblock.0.into());
+
+        let batch_size = self.config.deletion_batch_size as usize;
+        let mut total_deleted_count = 0;
+        loop {
+            let latency = METRICS.deletion_latency[&table_name].start();
+            let deleted_count = storage
+                .retention_dal()
+                .delete_expired_rows(table, last_expired_miniblock, batch_size)
+                .await?;
+            latency.observe();
+            METRICS.deleted_rows[&table_name].inc_by(deleted_count);
+            total_deleted_count += deleted_count;
+
+            if deleted_count < batch_size as u64 || *stop_receiver.borrow() {
+                break;
+            }
+        }
+
+        if total_deleted_count > 0 {
+            tracing::info!(
+                "Removed {total_deleted_count} rows from `{table_name}` for miniblocks up to \
+                 #{last_expired_miniblock}"
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!("Starting data retention with {:?}", self.config);
+        anyhow::ensure!(
+            self.config.deletion_batch_size > 0,
+            "`deletion_batch_size` must be positive"
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            self.remove_expired_data(seconds_since_epoch(), &stop_receiver)
+                .await?;
+            if tokio::time::timeout(self.config.poll_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, data retention is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        tx::IncludedTxLocation, Address, L1BatchNumber, MiniblockNumber, VmEvent, H256,
+    };
+
+    use super::*;
+    use crate::{
+        genesis::{insert_genesis_batch, GenesisParams},
+        utils::testonly::create_miniblock,
+    };
+
+    #[tokio::test]
+    async fn removing_expired_events() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        // The first miniblock has a small timestamp, so its events will expire.
+        let mut recent_miniblock = create_miniblock(2);
+        recent_miniblock.timestamp = 10 * SECONDS_IN_DAY;
+        for miniblock in [create_miniblock(1), recent_miniblock] {
+            storage
+                .blocks_dal()
+                .insert_miniblock(&miniblock)
+                .await
+                .unwrap();
+        }
+
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2)],
+            value: vec![3; 32],
+        };
+        for number in [1, 2] {
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::zero(),
+            };
+            let events = vec![&event; 5];
+            storage
+                .events_dal()
+                .save_events(MiniblockNumber(number as u32), &[(location, events)])
+                .await;
+        }
+
+        let config = DataRetentionConfig {
+            events_retention_days: Some(5),
+            transaction_traces_retention_days: None,
+            ..DataRetentionConfig::for_tests()
+        };
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let retention = DataRetention::new(config, pool.clone());
+        retention
+            .remove_expired_data(10 * SECONDS_IN_DAY, &stop_receiver)
+            .await
+            .unwrap();
+
+        let logs = storage
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 5);
+        assert!(logs
+            .iter()
+            .all(|log| log.block_number == Some(2_u64.into())));
+    }
+}
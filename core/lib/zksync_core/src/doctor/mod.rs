@@ -0,0 +1,491 @@
+//! Self-check of the environment, run before the node attempts full startup.
+//!
+//! [`Doctor`] checks the external dependencies of the node (Postgres, RocksDB directories, object store
+//! and L1 RPC) as well as a couple of OS-level settings, and produces a [`DoctorReport`] listing
+//! the outcome of each check.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use zksync_config::{ObjectStoreConfig, PostgresConfig};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_eth_client::{clients::QueryClient, EthInterface};
+use zksync_object_store::{Bucket, ObjectStoreError, ObjectStoreFactory};
+use zksync_types::web3::{
+    self,
+    transports::Http,
+    types::{BlockId, BlockNumber},
+    Transport,
+};
+
+use crate::schema_guard;
+
+/// Minimum supported Postgres version (14.0) in the `server_version_num` format.
+const MIN_POSTGRES_VERSION_NUM: u32 = 140_000;
+/// Minimum recommended free disk space for RocksDB directories.
+const MIN_FREE_DISK_SPACE: u64 = 10 << 30;
+/// Minimum recommended soft limit on open files. RocksDB instances keep a lot of files open.
+const MIN_OPEN_FILES_LIMIT: u64 = 65_536;
+/// Maximum allowed clock skew between the node and external services.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5);
+/// Maximum allowed lag of the latest L1 block timestamp behind the local clock. L1 blocks are produced
+/// every 12 seconds, so a larger lag means that either the local clock or the L1 node is off.
+const MAX_L1_CLOCK_LAG: Duration = Duration::from_secs(5 * 60);
+/// JSON-RPC error code returned for unsupported methods.
+const METHOD_NOT_FOUND_CODE: i64 = -32_601;
+const COMPONENT: &str = "doctor";
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The check has passed.
+    Pass,
+    /// The check has found an issue that doesn't prevent the node from running, but may affect its performance
+    /// or some of its features.
+    Warn,
+    /// The check has found an issue that prevents the node from running correctly.
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        })
+    }
+}
+
+/// Result of a single check.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+/// Report produced by [`Doctor`].
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, message: impl Into<String>) {
+        self.checks.push(CheckResult {
+            name: name.into(),
+            status,
+            message: message.into(),
+        });
+    }
+
+    /// Records the outcome of a check, treating an error as a failure.
+    fn push_outcome(
+        &mut self,
+        name: impl Into<String>,
+        outcome: anyhow::Result<(CheckStatus, String)>,
+    ) {
+        match outcome {
+            Ok((status, message)) => self.push(name, status, message),
+            Err(err) => self.push(name, CheckStatus::Fail, format!("{err:#}")),
+        }
+    }
+
+    /// Checks whether any of the checks has failed.
+    pub fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(
+                formatter,
+                "[{}] {}: {}",
+                check.status, check.name, check.message
+            )?;
+        }
+        let failed_count = self
+            .checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+            .count();
+        write!(
+            formatter,
+            "{} checks, {failed_count} failed",
+            self.checks.len()
+        )
+    }
+}
+
+/// Environment self-check. Checks are configured using `with_*` methods; only configured dependencies
+/// are checked (OS-level checks are always performed).
+#[derive(Debug, Default)]
+pub struct Doctor {
+    postgres: Option<PostgresConfig>,
+    rocksdb_paths: Vec<PathBuf>,
+    object_store: Option<ObjectStoreConfig>,
+    l1_rpc_url: Option<String>,
+    require_blobs: bool,
+}
+
+impl Doctor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the version, settings and clock of the Postgres server.
+    pub fn with_postgres(mut self, config: PostgresConfig) -> Self {
+        self.postgres = Some(config);
+        self
+    }
+
+    /// Checks permissions and free disk space for a RocksDB directory. The directory may not exist yet.
+    pub fn with_rocksdb_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.rocksdb_paths.push(path.into());
+        self
+    }
+
+    /// Checks reachability of the object store.
+    pub fn with_object_store(mut self, config: ObjectStoreConfig) -> Self {
+        self.object_store = Some(config);
+        self
+    }
+
+    /// Checks the L1 RPC and its capabilities. If `require_blobs` is set, the absence of blob support
+    /// on L1 is treated as a failure.
+    pub fn with_l1_rpc(mut self, url: impl Into<String>, require_blobs: bool) -> Self {
+        self.l1_rpc_url = Some(url.into());
+        self.require_blobs = require_blobs;
+        self
+    }
+
+    /// Runs all configured checks.
+    pub async fn run(self) -> DoctorReport {
+        let mut report = DoctorReport::default();
+        if let Some(config) = &self.postgres {
+            Self::check_postgres(config, &mut report).await;
+        }
+        for path in &self.rocksdb_paths {
+            let name = format!("RocksDB directory `{}`", path.display());
+            report.push_outcome(name, Self::check_rocksdb_path(path));
+        }
+        if let Some(config) = &self.object_store {
+            report.push_outcome("object store", Self::check_object_store(config).await);
+        }
+        if let Some(url) = &self.l1_rpc_url {
+            self.check_l1_rpc(url, &mut report).await;
+        }
+        report.push_outcome("open files limit", check_open_files_limit());
+        report
+    }
+
+    async fn check_postgres(config: &PostgresConfig, report: &mut DoctorReport) {
+        let server_info = async {
+            let url = config.master_url()?;
+            let pool = ConnectionPool::<Core>::singleton(url)
+                .build()
+                .await
+                .context("failed building connection pool")?;
+            let mut storage = pool.connection_tagged(COMPONENT).await?;
+            let server_info = storage
+                .system_dal()
+                .get_server_info()
+                .await
+                .context("failed getting Postgres server info")?;
+            anyhow::Ok((pool, server_info))
+        };
+        let (pool, server_info) = match server_info.await {
+            Ok(output) => output,
+            Err(err) => {
+                report.push("Postgres", CheckStatus::Fail, format!("{err:#}"));
+                return;
+            }
+        };
+
+        let version_status = if server_info.version_num >= MIN_POSTGRES_VERSION_NUM {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        };
+        report.push(
+            "Postgres version",
+            version_status,
+            format!(
+                "server version {}, minimum supported {MIN_POSTGRES_VERSION_NUM}",
+                server_info.version_num
+            ),
+        );
+
+        // The main and master pools are separate, so both can be in use at the same time.
+        let required_connections =
+            config.max_connections.unwrap_or(0) + config.max_connections_master().unwrap_or(0);
+        let connections_status = if server_info.max_connections >= required_connections {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        };
+        report.push(
+            "Postgres max_connections",
+            connections_status,
+            format!(
+                "server allows {} connections, node pools may use up to {required_connections}",
+                server_info.max_connections
+            ),
+        );
+
+        let skew = (server_info.timestamp - unix_timestamp().as_secs_f64()).abs();
+        let skew_status = if skew <= MAX_CLOCK_SKEW.as_secs_f64() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        };
+        report.push(
+            "clock skew with Postgres",
+            skew_status,
+            format!("{skew:.3}s, maximum allowed {MAX_CLOCK_SKEW:?}"),
+        );
+
+        let schema_diff = schema_guard::check_schema(&pool).await.map(|diff| {
+            let status = if diff.is_empty() {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Fail
+            };
+            (status, diff.to_string())
+        });
+        report.push_outcome("Postgres schema", schema_diff);
+    }
+
+    fn check_rocksdb_path(path: &Path) -> anyhow::Result<(CheckStatus, String)> {
+        // The directory will be created by RocksDB if necessary, so we check its closest existing ancestor.
+        let existing_dir = path
+            .ancestors()
+            .find(|dir| dir.exists())
+            .context("no ancestor of the directory exists")?;
+        anyhow::ensure!(
+            existing_dir.is_dir(),
+            "`{}` is not a directory",
+            existing_dir.display()
+        );
+
+        let probe_path = existing_dir.join(".zksync_doctor_probe");
+        std::fs::write(&probe_path, b"")
+            .with_context(|| format!("`{}` is not writable", existing_dir.display()))?;
+        std::fs::remove_file(&probe_path)
+            .with_context(|| format!("failed removing `{}`", probe_path.display()))?;
+
+        let Some(free_space) = free_disk_space(existing_dir)? else {
+            let message = "writable; checking free disk space is not supported on this platform";
+            return Ok((CheckStatus::Pass, message.to_owned()));
+        };
+        let status = if free_space >= MIN_FREE_DISK_SPACE {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        };
+        let message = format!(
+            "writable, {} MiB free (recommended at least {} MiB)",
+            free_space >> 20,
+            MIN_FREE_DISK_SPACE >> 20
+        );
+        Ok((status, message))
+    }
+
+    async fn check_object_store(
+        config: &ObjectStoreConfig,
+    ) -> anyhow::Result<(CheckStatus, String)> {
+        let store = ObjectStoreFactory::new(config.clone()).create_store().await;
+        // The key doesn't exist, so a reachable store should return a "not found" error.
+        match store
+            .get_raw(Bucket::WitnessInput, "zksync_doctor_probe")
+            .await
+        {
+            Ok(_) | Err(ObjectStoreError::KeyNotFound(_)) => {
+                Ok((CheckStatus::Pass, "reachable".to_owned()))
+            }
+            Err(err) => Err(anyhow::Error::new(err).context("object store is not reachable")),
+        }
+    }
+
+    async fn check_l1_rpc(&self, url: &str, report: &mut DoctorReport) {
+        let client = match QueryClient::new(url) {
+            Ok(client) => client,
+            Err(err) => {
+                report.push(
+                    "L1 RPC",
+                    CheckStatus::Fail,
+                    format!("invalid L1 RPC URL: {err}"),
+                );
+                return;
+            }
+        };
+        let latest_block = match client
+            .block(BlockId::Number(BlockNumber::Latest), COMPONENT)
+            .await
+        {
+            Ok(Some(block)) => block,
+            Ok(None) => {
+                report.push("L1 RPC", CheckStatus::Fail, "latest L1 block is missing");
+                return;
+            }
+            Err(err) => {
+                report.push(
+                    "L1 RPC",
+                    CheckStatus::Fail,
+                    format!("L1 RPC is not reachable: {err}"),
+                );
+                return;
+            }
+        };
+        report.push("L1 RPC", CheckStatus::Pass, "reachable");
+
+        let (blobs_status, blobs_message) = if latest_block.excess_blob_gas.is_some() {
+            (CheckStatus::Pass, "supported")
+        } else if self.require_blobs {
+            (
+                CheckStatus::Fail,
+                "not supported, but required to publish pubdata in blobs",
+            )
+        } else {
+            (CheckStatus::Warn, "not supported")
+        };
+        report.push("L1 blob support", blobs_status, blobs_message);
+
+        let trace_support = check_l1_trace_support(url).await;
+        report.push_outcome("L1 trace support", trace_support);
+
+        let block_timestamp = Duration::from_secs(latest_block.timestamp.as_u64());
+        let now = unix_timestamp();
+        let (clock_status, clock_message) = if let Some(lead) = block_timestamp.checked_sub(now) {
+            let status = if lead <= MAX_CLOCK_SKEW {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Fail
+            };
+            (
+                status,
+                format!("latest L1 block is {lead:?} ahead of the local clock"),
+            )
+        } else {
+            let lag = now - block_timestamp;
+            let status = if lag <= MAX_L1_CLOCK_LAG {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Warn
+            };
+            (
+                status,
+                format!("latest L1 block is {lag:?} behind the local clock"),
+            )
+        };
+        report.push("clock skew with L1", clock_status, clock_message);
+    }
+}
+
+async fn check_l1_trace_support(url: &str) -> anyhow::Result<(CheckStatus, String)> {
+    let transport = Http::new(url).context("invalid L1 RPC URL")?;
+    // Tracing the genesis block is cheap, since it contains no transactions.
+    let params = vec![
+        serde_json::json!("0x0"),
+        serde_json::json!({ "tracer": "callTracer" }),
+    ];
+    match transport.execute("debug_traceBlockByNumber", params).await {
+        Ok(_) => Ok((CheckStatus::Pass, "supported".to_owned())),
+        Err(web3::Error::Rpc(err)) if err.code.code() == METHOD_NOT_FOUND_CODE => Ok((
+            CheckStatus::Warn,
+            "`debug_traceBlockByNumber` is not supported".to_owned(),
+        )),
+        // Other RPC errors (e.g., pruned state) mean that the method itself is supported.
+        Err(web3::Error::Rpc(err)) => Ok((
+            CheckStatus::Pass,
+            format!(
+                "supported (tracing the genesis block returned: {})",
+                err.message
+            ),
+        )),
+        Err(err) => Err(anyhow::Error::new(err).context("failed calling L1 RPC")),
+    }
+}
+
+fn unix_timestamp() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before Unix epoch")
+}
+
+#[cfg(unix)]
+fn free_disk_space(path: &Path) -> anyhow::Result<Option<u64>> {
+    let stat = nix::sys::statvfs::statvfs(path).context("`statvfs` failed")?;
+    #[allow(clippy::unnecessary_cast)] // field types differ among platforms
+    Ok(Some(
+        stat.blocks_available() as u64 * stat.fragment_size() as u64,
+    ))
+}
+
+#[cfg(not(unix))]
+fn free_disk_space(_path: &Path) -> anyhow::Result<Option<u64>> {
+    Ok(None)
+}
+
+#[cfg(unix)]
+fn check_open_files_limit() -> anyhow::Result<(CheckStatus, String)> {
+    use nix::sys::resource::{getrlimit, Resource};
+
+    let (soft_limit, _) = getrlimit(Resource::RLIMIT_NOFILE).context("`getrlimit` failed")?;
+    #[allow(clippy::unnecessary_cast)] // field types differ among platforms
+    let soft_limit = soft_limit as u64;
+    let status = if soft_limit >= MIN_OPEN_FILES_LIMIT {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Warn
+    };
+    let message = format!("{soft_limit}, recommended at least {MIN_OPEN_FILES_LIMIT}");
+    Ok((status, message))
+}
+
+#[cfg(not(unix))]
+fn check_open_files_limit() -> anyhow::Result<(CheckStatus, String)> {
+    Ok((CheckStatus::Pass, "not checked on this platform".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_with_failures() {
+        let mut report = DoctorReport::default();
+        report.push("first", CheckStatus::Pass, "ok");
+        report.push("second", CheckStatus::Warn, "meh");
+        assert!(!report.has_failures());
+
+        report.push_outcome("third", Err(anyhow::anyhow!("oops")));
+        assert!(report.has_failures());
+        let report = report.to_string();
+        assert!(report.contains("[FAIL] third: oops"), "{report}");
+        assert!(report.ends_with("3 checks, 1 failed"), "{report}");
+    }
+
+    #[test]
+    fn checking_rocksdb_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("state_keeper/db");
+        let (status, message) = Doctor::check_rocksdb_path(&db_path).unwrap();
+        assert_ne!(status, CheckStatus::Fail, "{message}");
+        assert!(!db_path.exists());
+
+        let file_path = temp_dir.path().join("file");
+        std::fs::write(&file_path, b"test").unwrap();
+        let err = Doctor::check_rocksdb_path(&file_path.join("db")).unwrap_err();
+        assert!(err.to_string().contains("not a directory"), "{err}");
+    }
+}
@@ -1,4 +1,5 @@
 use zksync_types::web3::contract;
+use zksync_utils::error::{ClassifyError, ErrorKind};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ETHSenderError {
@@ -7,3 +8,12 @@ pub enum ETHSenderError {
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
 }
+
+impl ClassifyError for ETHSenderError {
+    fn error_kind(&self) -> ErrorKind {
+        match self {
+            Self::EthereumGateWayError(err) => err.error_kind(),
+            Self::ParseError(_) => ErrorKind::DataCorruption,
+        }
+    }
+}
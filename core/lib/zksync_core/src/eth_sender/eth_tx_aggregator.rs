@@ -21,6 +21,7 @@ use zksync_types::{
     web3::{contract::Error as Web3ContractError, types::BlockNumber},
     Address, L2ChainId, ProtocolVersionId, H256, U256,
 };
+use zksync_utils::retry::RetryPolicy;
 
 use super::aggregated_operations::AggregatedOperation;
 use crate::{
@@ -122,6 +123,8 @@ impl EthTxAggregator {
 
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let pool = self.pool.clone();
+        let mut retrier = RetryPolicy::indefinite(self.config.aggregate_tx_poll_period())
+            .retrier("eth_tx_aggregator");
         loop {
             let mut storage = pool.connection_tagged("eth_sender").await.unwrap();
 
@@ -130,10 +133,14 @@ impl EthTxAggregator {
                 break;
             }
 
-            if let Err(err) = self.loop_iteration(&mut storage).await {
-                // Web3 API request failures can cause this,
-                // and anything more important is already properly reported.
-                tracing::warn!("eth_sender error {err:?}");
+            match self.loop_iteration(&mut storage).await {
+                Ok(()) => retrier.reset(),
+                Err(err) => {
+                    // Web3 API request failures can cause this, and anything more important is already
+                    // properly reported. The error is logged according to its kind, but the loop continues
+                    // regardless, retrying on the next iteration.
+                    retrier.handle_error(err).ok();
+                }
             }
 
             tokio::time::sleep(self.config.aggregate_tx_poll_period()).await;
@@ -17,9 +17,9 @@ use zksync_types::{
     },
     Address, L1BlockNumber, Nonce, EIP_1559_TX_TYPE, EIP_4844_TX_TYPE, H256, U256,
 };
-use zksync_utils::time::seconds_since_epoch;
+use zksync_utils::{retry::RetryPolicy, time::seconds_since_epoch};
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{metrics::METRICS, receipts_cache::ReceiptsCache, ETHSenderError};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
 #[derive(Debug)]
@@ -48,7 +48,11 @@ pub(super) struct L1BlockNumbers {
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to Ethereum.
 /// Based on eth_tx_history queue the component can mark txs as stuck and create the new attempt
-/// with higher gas price
+/// with higher gas price.
+///
+/// A mined transaction is only confirmed (i.e., its L1 batches are marked as committed / proven / executed)
+/// once its block is finalized. Before that, its receipt is re-checked on each new L1 block to detect
+/// L1 reorgs. If a transaction is reorged out, it will be resent.
 #[derive(Debug)]
 pub struct EthTxManager {
     /// A gateway through which the operator normally sends all its transactions.
@@ -59,6 +63,7 @@ pub struct EthTxManager {
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
     pool: ConnectionPool<Core>,
+    receipts: ReceiptsCache,
 }
 
 impl EthTxManager {
@@ -75,6 +80,7 @@ impl EthTxManager {
             config,
             gas_adjuster,
             pool,
+            receipts: ReceiptsCache::default(),
         }
     }
 
@@ -116,6 +122,51 @@ impl EthTxManager {
         None
     }
 
+    /// Returns the receipt for a mined `tx`. Cached receipts are checked against the canonical L1 chain,
+    /// so a receipt from a reorged out block is never returned.
+    async fn get_receipt(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        tx: &EthTx,
+    ) -> Result<Option<ExecutedTxStatus>, ETHSenderError> {
+        if let Some(status) = self.receipts.get(tx.id).cloned() {
+            if self.is_receipt_canonical(&status).await? {
+                return Ok(Some(status));
+            }
+            self.receipts.remove(tx.id);
+            tracing::warn!(
+                "L1 reorg detected: receipt for tx {:?} (eth_tx {}) is no longer in L1 block {:?}",
+                status.tx_hash,
+                tx.id,
+                status.receipt.block_number
+            );
+            METRICS.l1_reorged_txs.inc();
+        }
+
+        let status = self.check_all_sending_attempts(storage, tx).await;
+        if let Some(status) = &status {
+            self.receipts.insert(tx.id, status.clone());
+        }
+        Ok(status)
+    }
+
+    async fn is_receipt_canonical(
+        &self,
+        status: &ExecutedTxStatus,
+    ) -> Result<bool, ETHSenderError> {
+        let Some(block_number) = status.receipt.block_number else {
+            return Ok(false);
+        };
+        let block = self
+            .ethereum_gateway
+            .block(
+                BlockId::Number(BlockNumber::Number(block_number)),
+                "eth_tx_manager",
+            )
+            .await?;
+        Ok(block.map_or(false, |block| block.hash == status.receipt.block_hash))
+    }
+
     async fn calculate_fee(
         &self,
         storage: &mut Connection<'_, Core>,
@@ -483,6 +534,13 @@ impl EthTxManager {
             // that `tx` is not mined and we should resend it.
             // We only resend the first un-mined transaction.
             if operator_nonce.latest <= tx.nonce {
+                if self.receipts.remove(tx.id).is_some() {
+                    tracing::warn!(
+                        "L1 reorg detected: mined eth_tx {} is pending again and will be resent",
+                        tx.id
+                    );
+                    METRICS.l1_reorged_txs.inc();
+                }
                 // None means txs hasn't been sent yet
                 let first_sent_at_block = storage
                     .eth_sender_dal()
@@ -496,8 +554,10 @@ impl EthTxManager {
             // If on finalized block sender's nonce was > tx.nonce,
             // then `tx` is mined and confirmed (either successful or reverted).
             // Only then we will check the history to find the receipt.
-            // Otherwise, `tx` is mined but not confirmed, so we skip to the next one.
+            // Otherwise, `tx` is mined but not confirmed, so we only cache its receipt (or check
+            // that the cached one is still valid) and skip to the next one.
             if operator_nonce.finalized <= tx.nonce {
+                self.get_receipt(storage, &tx).await?;
                 continue;
             }
 
@@ -508,7 +568,7 @@ impl EthTxManager {
                 tx.nonce,
             );
 
-            match self.check_all_sending_attempts(storage, &tx).await {
+            match self.get_receipt(storage, &tx).await? {
                 Some(tx_status) => {
                     self.apply_tx_status(storage, &tx, tx_status, l1_block_numbers.finalized)
                         .await;
@@ -622,7 +682,7 @@ impl EthTxManager {
     }
 
     async fn apply_tx_status(
-        &self,
+        &mut self,
         storage: &mut Connection<'_, Core>,
         tx: &EthTx,
         tx_status: ExecutedTxStatus,
@@ -630,6 +690,7 @@ impl EthTxManager {
     ) {
         let receipt_block_number = tx_status.receipt.block_number.unwrap().as_u32();
         if receipt_block_number <= finalized_block.0 {
+            self.receipts.remove(tx.id);
             if tx_status.success {
                 self.confirm_tx(storage, tx, tx_status).await;
             } else {
@@ -737,6 +798,8 @@ impl EthTxManager {
         // It's mandatory to set `last_known_l1_block` to zero, otherwise the first iteration
         // will never check in-flight txs status
         let mut last_known_l1_block = L1BlockNumber(0);
+        let mut retrier =
+            RetryPolicy::indefinite(self.config.tx_poll_period()).retrier("eth_tx_manager");
         loop {
             let mut storage = pool.connection_tagged("eth_sender").await.unwrap();
 
@@ -746,11 +809,15 @@ impl EthTxManager {
             }
 
             match self.loop_iteration(&mut storage, last_known_l1_block).await {
-                Ok(block) => last_known_l1_block = block,
-                Err(e) => {
-                    // Web3 API request failures can cause this,
-                    // and anything more important is already properly reported.
-                    tracing::warn!("eth_sender error {:?}", e);
+                Ok(block) => {
+                    last_known_l1_block = block;
+                    retrier.reset();
+                }
+                Err(err) => {
+                    // Web3 API request failures can cause this, and anything more important is already
+                    // properly reported. The error is logged according to its kind, but the loop continues
+                    // regardless, retrying on the next iteration.
+                    retrier.handle_error(err).ok();
                 }
             }
 
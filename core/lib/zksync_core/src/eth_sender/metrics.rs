@@ -84,6 +84,8 @@ pub(super) struct EthSenderMetrics {
     pub block_range_size: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of transactions resent by the Ethereum sender.
     pub transaction_resent: Counter,
+    /// Number of mined, but not finalized transactions that were reorged out of L1.
+    pub l1_reorged_txs: Counter,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_base_fee_per_gas: Histogram<u64>,
     #[metrics(buckets = FEE_BUCKETS)]
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use zksync_eth_client::ExecutedTxStatus;
+
+/// Cache of receipts for `eth_tx`s that are mined, but not finalized on L1 yet.
+///
+/// Receipts may become invalid if their L1 block is reorged out. Thus, a cached receipt must be checked
+/// against the canonical L1 chain before use, and must be evicted if the check fails.
+#[derive(Debug, Default)]
+pub(super) struct ReceiptsCache {
+    receipts: HashMap<u32, ExecutedTxStatus>,
+}
+
+impl ReceiptsCache {
+    pub fn get(&self, eth_tx_id: u32) -> Option<&ExecutedTxStatus> {
+        self.receipts.get(&eth_tx_id)
+    }
+
+    pub fn insert(&mut self, eth_tx_id: u32, status: ExecutedTxStatus) {
+        self.receipts.insert(eth_tx_id, status);
+    }
+
+    pub fn remove(&mut self, eth_tx_id: u32) -> Option<ExecutedTxStatus> {
+        self.receipts.remove(&eth_tx_id)
+    }
+}
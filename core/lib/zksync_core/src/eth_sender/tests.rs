@@ -499,6 +499,81 @@ async fn dont_resend_already_mined(deployment_mode: DeploymentMode) -> anyhow::R
     Ok(())
 }
 
+// Tests that a mined transaction reorged out of L1 before it's finalized is not confirmed and is resent.
+#[test_casing(2, [DeploymentMode::Rollup, DeploymentMode::Validium])]
+#[tokio::test]
+async fn resend_reorged_out_tx(deployment_mode: DeploymentMode) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(
+        ConnectionPool::<Core>::test_pool().await,
+        vec![100; 100],
+        false,
+        false,
+        &deployment_mode,
+    )
+    .await;
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+    let hash = tester
+        .manager
+        .send_eth_tx(&mut tester.conn.connection().await.unwrap(), &tx, 0, block)
+        .await?;
+
+    // Mine the transaction, but don't finalize it.
+    tester
+        .gateway
+        .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS - 1);
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.connection().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(to_resend.is_none());
+
+    // Reorg the transaction out of L1.
+    tester.gateway.reorg(block.0.into());
+    tester.gateway.advance_block_number(1);
+    let block_numbers = tester.get_block_numbers().await;
+    let (to_resend, _) = tester
+        .manager
+        .monitor_inflight_transactions(&mut tester.conn.connection().await.unwrap(), block_numbers)
+        .await?
+        .expect("reorged out transaction is not resent");
+    assert_eq!(to_resend.id, tx.id);
+
+    let resent_hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &to_resend,
+            1,
+            block_numbers.latest,
+        )
+        .await?;
+    assert_ne!(resent_hash, hash);
+    assert_eq!(tester.gateway.sent_tx_count(), 2);
+
+    confirm_tx(&mut tester, resent_hash).await;
+    let inflight_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .unwrap();
+    assert!(inflight_txs.is_empty());
+    Ok(())
+}
+
 #[test_casing(2, [DeploymentMode::Rollup, DeploymentMode::Validium])]
 #[tokio::test]
 async fn three_scenarios(deployment_mode: DeploymentMode) -> anyhow::Result<()> {
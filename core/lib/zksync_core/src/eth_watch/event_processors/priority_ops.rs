@@ -1,8 +1,12 @@
 use std::convert::TryFrom;
 
+use multivm::utils::derive_base_fee_and_gas_per_pubdata;
 use zksync_contracts::zksync_contract;
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_types::{l1::L1Tx, web3::types::Log, PriorityOpId, H256};
+use zksync_types::{
+    api::UnderfundedPriorityOp, l1::L1Tx, web3::types::Log, PriorityOpId, ProtocolVersionId, H256,
+    U256,
+};
 
 use crate::{
     eth_watch::{
@@ -13,13 +17,58 @@ use crate::{
     metrics::{TxStage, APP_METRICS},
 };
 
-/// Responsible for saving new priority L1 transactions to the database.
+/// Responsible for saving new priority L1 transactions to the database. Transactions that do not provide
+/// enough funds to cover L2 fees at the time of receipt are additionally marked as underfunded.
 #[derive(Debug)]
 pub struct PriorityOpsEventProcessor {
     next_expected_priority_id: PriorityOpId,
     new_priority_request_signature: H256,
 }
 
+/// L2 fee requirements that priority ops are checked against on receipt.
+#[derive(Debug, Clone, Copy)]
+struct FeeRequirements {
+    base_fee_per_gas: U256,
+    gas_per_pubdata: U256,
+}
+
+impl FeeRequirements {
+    /// Loads requirements based on the fee input of the last sealed miniblock, which reflects the current
+    /// fee model state. Returns `None` if there are no miniblocks yet.
+    async fn load(storage: &mut Connection<'_, Core>) -> Option<Self> {
+        let header = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .expect("failed loading last sealed miniblock header")?;
+        let protocol_version = header
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let (base_fee_per_gas, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(header.batch_fee_input, protocol_version.into());
+        Some(Self {
+            base_fee_per_gas: base_fee_per_gas.into(),
+            gas_per_pubdata: gas_per_pubdata.into(),
+        })
+    }
+
+    /// Returns `Some(_)` if the `tx` does not provide enough funds to cover L2 fees.
+    fn check(&self, tx: &L1Tx) -> Option<UnderfundedPriorityOp> {
+        let max_fee_per_gas = tx.common_data.max_fee_per_gas;
+        let gas_per_pubdata_limit = tx.common_data.gas_per_pubdata_limit;
+        let is_underfunded =
+            max_fee_per_gas < self.base_fee_per_gas || gas_per_pubdata_limit < self.gas_per_pubdata;
+        is_underfunded.then(|| UnderfundedPriorityOp {
+            priority_op_id: tx.serial_id(),
+            tx_hash: tx.hash(),
+            max_fee_per_gas,
+            required_max_fee_per_gas: self.base_fee_per_gas,
+            gas_per_pubdata_limit,
+            required_gas_per_pubdata_limit: self.gas_per_pubdata,
+        })
+    }
+}
+
 impl PriorityOpsEventProcessor {
     pub fn new(next_expected_priority_id: PriorityOpId) -> Self {
         Self {
@@ -87,12 +136,34 @@ impl EventProcessor for PriorityOpsEventProcessor {
         let stage_latency = METRICS.poll_eth_node[&PollStage::PersistL1Txs].start();
         APP_METRICS.processed_txs[&TxStage::added_to_mempool()].inc();
         APP_METRICS.processed_l1_txs[&TxStage::added_to_mempool()].inc();
+        let fee_requirements = FeeRequirements::load(storage).await;
         for new_op in new_ops {
             let eth_block = new_op.eth_block();
+            let underfunded_op =
+                fee_requirements.and_then(|requirements| requirements.check(&new_op));
             storage
                 .transactions_dal()
                 .insert_transaction_l1(new_op, eth_block)
                 .await;
+
+            if let Some(op) = underfunded_op {
+                tracing::warn!(
+                    "Priority op #{} ({:?}) is underfunded: max fee per gas {} (required {}), \
+                     gas per pubdata limit {} (required {})",
+                    op.priority_op_id,
+                    op.tx_hash,
+                    op.max_fee_per_gas,
+                    op.required_max_fee_per_gas,
+                    op.gas_per_pubdata_limit,
+                    op.required_gas_per_pubdata_limit
+                );
+                METRICS.underfunded_priority_ops.inc();
+                storage
+                    .transactions_dal()
+                    .insert_underfunded_priority_op(&op)
+                    .await
+                    .expect("failed marking priority op as underfunded");
+            }
         }
         stage_latency.observe();
         self.next_expected_priority_id = last_new.serial_id().next();
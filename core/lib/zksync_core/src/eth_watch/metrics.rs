@@ -20,6 +20,8 @@ pub(super) struct EthWatcherMetrics {
     pub poll_eth_node: Family<PollStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_priority_op_events: Histogram<Duration>,
+    /// Number of received priority ops that do not cover L2 fees at the time of receipt.
+    pub underfunded_priority_ops: Counter,
 }
 
 #[vise::register]
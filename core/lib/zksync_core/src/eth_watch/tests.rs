@@ -13,8 +13,11 @@ use zksync_types::{
 };
 
 use super::client::Error;
-use crate::eth_watch::{
-    client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+use crate::{
+    eth_watch::{
+        client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+    },
+    utils::testonly::create_miniblock,
 };
 
 #[derive(Debug)]
@@ -248,6 +251,52 @@ async fn test_normal_operation_l1_txs() {
     assert_eq!(db_tx.common_data.serial_id.0, 2);
 }
 
+#[tokio::test]
+async fn underfunded_priority_ops_are_flagged() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let mut storage = connection_pool.connection().await.unwrap();
+    let mut miniblock = create_miniblock(0);
+    miniblock.protocol_version = None;
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let underfunded_tx = build_l1_tx(0, 10);
+    let mut funded_tx = build_l1_tx(1, 10);
+    funded_tx.common_data.max_fee_per_gas = 1_000_000_000.into();
+    funded_tx.common_data.gas_per_pubdata_limit = 800.into();
+    client
+        .add_transactions(&[underfunded_tx.clone(), funded_tx])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 2);
+    let underfunded_ops = storage
+        .transactions_web3_dal()
+        .get_pending_underfunded_priority_ops(10)
+        .await
+        .unwrap();
+    assert_eq!(underfunded_ops.len(), 1, "{underfunded_ops:?}");
+    let op = &underfunded_ops[0];
+    assert_eq!(op.priority_op_id, PriorityOpId(0));
+    assert_eq!(op.tx_hash, underfunded_tx.hash());
+    assert!(op.required_max_fee_per_gas > op.max_fee_per_gas);
+}
+
 #[tokio::test]
 async fn test_normal_operation_upgrades() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
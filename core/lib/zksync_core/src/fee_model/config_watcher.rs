@@ -0,0 +1,253 @@
+//! Reloading the fee model config at runtime.
+
+use std::{mem, path::PathBuf, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use zksync_protobuf_config::proto::general::GeneralConfig as GeneralConfigProto;
+use zksync_types::fee_model::FeeModelConfig;
+
+use crate::temp_config_store::decode_yaml_repr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+enum ConfigReloadResult {
+    /// The config was changed and applied.
+    Updated,
+    /// The config was changed, but the change cannot be applied at runtime.
+    Rejected,
+    /// The config file could not be read or parsed.
+    Failed,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_fee_model_config")]
+struct FeeModelConfigMetrics {
+    /// Number of fee model config reloads, excluding ones that haven't changed the config.
+    reloads: Family<ConfigReloadResult, Counter>,
+    /// Minimal L2 gas price in the currently used fee model config.
+    minimal_l2_gas_price: Gauge<u64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<FeeModelConfigMetrics> = vise::Global::new();
+
+/// Returns batch capacity limits `(max_gas_per_batch, max_pubdata_per_batch)` used by the fee model.
+fn batch_limits(config: &FeeModelConfig) -> Option<(u64, u64)> {
+    match config {
+        FeeModelConfig::V1(_) => None,
+        FeeModelConfig::V2(config) => {
+            Some((config.max_gas_per_batch, config.max_pubdata_per_batch))
+        }
+    }
+}
+
+fn minimal_l2_gas_price(config: &FeeModelConfig) -> u64 {
+    match config {
+        FeeModelConfig::V1(config) => config.minimal_l2_gas_price,
+        FeeModelConfig::V2(config) => config.minimal_l2_gas_price,
+    }
+}
+
+/// Periodically reloads the fee model config from the state keeper section of the general YAML config,
+/// and atomically swaps it in all [`MainNodeFeeInputProvider`](super::MainNodeFeeInputProvider)s subscribed
+/// to the watcher.
+///
+/// Only the fee model parameters (the minimal L2 gas price, overhead parts etc.) can be changed at runtime.
+/// Changing the fee model version or batch capacity limits (`max_gas_per_batch` and `max_pubdata_per_batch`)
+/// requires a restart; the limits are also used by state keeper seal criteria, which are not reloaded.
+#[derive(Debug)]
+pub struct FeeModelConfigWatcher {
+    config_path: PathBuf,
+    poll_interval: Duration,
+    sender: watch::Sender<FeeModelConfig>,
+}
+
+impl FeeModelConfigWatcher {
+    pub fn new(
+        config_path: PathBuf,
+        initial_config: FeeModelConfig,
+        poll_interval: Duration,
+    ) -> Self {
+        METRICS
+            .minimal_l2_gas_price
+            .set(minimal_l2_gas_price(&initial_config));
+        Self {
+            config_path,
+            poll_interval,
+            sender: watch::channel(initial_config).0,
+        }
+    }
+
+    /// Returns a receiver for the fee model config that can be passed to
+    /// [`MainNodeFeeInputProvider::from_config_receiver()`](super::MainNodeFeeInputProvider::from_config_receiver()).
+    pub fn subscribe(&self) -> watch::Receiver<FeeModelConfig> {
+        self.sender.subscribe()
+    }
+
+    fn parse_config(yaml: &str) -> anyhow::Result<FeeModelConfig> {
+        let config = decode_yaml_repr::<GeneralConfigProto>(yaml)
+            .context("failed decoding general YAML config")?;
+        let state_keeper_config = config
+            .state_keeper_config
+            .context("state keeper config is missing")?;
+        Ok(FeeModelConfig::from_state_keeper_config(
+            &state_keeper_config,
+        ))
+    }
+
+    /// Updates the config if it has changed. Returns `None` if the config is unchanged.
+    fn update_config(&self, new_config: FeeModelConfig) -> Option<ConfigReloadResult> {
+        let current_config = *self.sender.borrow();
+        if new_config == current_config {
+            return None;
+        }
+        if mem::discriminant(&new_config) != mem::discriminant(&current_config) {
+            tracing::error!(
+                "Fee model version cannot be changed at runtime; ignoring updated config {new_config:?} \
+                 (current config: {current_config:?})"
+            );
+            return Some(ConfigReloadResult::Rejected);
+        }
+        if batch_limits(&new_config) != batch_limits(&current_config) {
+            tracing::error!(
+                "Batch capacity limits cannot be changed at runtime since they are used by seal criteria; \
+                 ignoring updated config {new_config:?} (current config: {current_config:?})"
+            );
+            return Some(ConfigReloadResult::Rejected);
+        }
+
+        tracing::info!("Updated fee model config: {current_config:?} -> {new_config:?}");
+        self.sender.send_replace(new_config);
+        METRICS
+            .minimal_l2_gas_price
+            .set(minimal_l2_gas_price(&new_config));
+        Some(ConfigReloadResult::Updated)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Watching fee model config in `{}` with poll interval {:?}",
+            self.config_path.display(),
+            self.poll_interval
+        );
+        // Config file contents processed during the last poll. Used to parse and log each change only once.
+        let mut last_yaml = None;
+
+        while !*stop_receiver.borrow_and_update() {
+            match std::fs::read_to_string(&self.config_path) {
+                Ok(yaml) if last_yaml.as_ref() != Some(&yaml) => {
+                    let result = match Self::parse_config(&yaml) {
+                        Ok(new_config) => self.update_config(new_config),
+                        Err(err) => {
+                            tracing::warn!("Failed reloading fee model config: {err:#}");
+                            Some(ConfigReloadResult::Failed)
+                        }
+                    };
+                    if let Some(result) = result {
+                        METRICS.reloads[&result].inc();
+                    }
+                    last_yaml = Some(yaml);
+                }
+                Ok(_) => { /* The config file is unchanged */ }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed reading fee model config from `{}`: {err}",
+                        self.config_path.display()
+                    );
+                    METRICS.reloads[&ConfigReloadResult::Failed].inc();
+                }
+            }
+
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, fee model config watcher is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::fee_model::{FeeModelConfigV1, FeeModelConfigV2};
+
+    use super::*;
+
+    #[test]
+    fn updating_config() {
+        let initial_config = FeeModelConfig::V1(FeeModelConfigV1 {
+            minimal_l2_gas_price: 100,
+        });
+        let watcher =
+            FeeModelConfigWatcher::new(PathBuf::new(), initial_config, Duration::from_secs(1));
+        let receiver = watcher.subscribe();
+
+        assert_eq!(watcher.update_config(initial_config), None);
+
+        let new_config = FeeModelConfig::V1(FeeModelConfigV1 {
+            minimal_l2_gas_price: 200,
+        });
+        assert_eq!(
+            watcher.update_config(new_config),
+            Some(ConfigReloadResult::Updated)
+        );
+        assert_eq!(*receiver.borrow(), new_config);
+
+        let v2_config = FeeModelConfig::V2(FeeModelConfigV2 {
+            minimal_l2_gas_price: 200,
+            compute_overhead_part: 0.5,
+            pubdata_overhead_part: 0.5,
+            batch_overhead_l1_gas: 800_000,
+            max_gas_per_batch: 200_000_000,
+            max_pubdata_per_batch: 100_000,
+        });
+        assert_eq!(
+            watcher.update_config(v2_config),
+            Some(ConfigReloadResult::Rejected)
+        );
+        assert_eq!(*receiver.borrow(), new_config);
+    }
+
+    #[test]
+    fn batch_limits_cannot_be_updated() {
+        let initial_config = FeeModelConfigV2 {
+            minimal_l2_gas_price: 100,
+            compute_overhead_part: 0.5,
+            pubdata_overhead_part: 0.5,
+            batch_overhead_l1_gas: 800_000,
+            max_gas_per_batch: 200_000_000,
+            max_pubdata_per_batch: 100_000,
+        };
+        let watcher = FeeModelConfigWatcher::new(
+            PathBuf::new(),
+            FeeModelConfig::V2(initial_config),
+            Duration::from_secs(1),
+        );
+        let receiver = watcher.subscribe();
+
+        let new_config = FeeModelConfig::V2(FeeModelConfigV2 {
+            max_pubdata_per_batch: 120_000,
+            ..initial_config
+        });
+        assert_eq!(
+            watcher.update_config(new_config),
+            Some(ConfigReloadResult::Rejected)
+        );
+        assert_eq!(*receiver.borrow(), FeeModelConfig::V2(initial_config));
+
+        let new_config = FeeModelConfig::V2(FeeModelConfigV2 {
+            minimal_l2_gas_price: 200,
+            ..initial_config
+        });
+        assert_eq!(
+            watcher.update_config(new_config),
+            Some(ConfigReloadResult::Updated)
+        );
+        assert_eq!(*receiver.borrow(), new_config);
+    }
+}
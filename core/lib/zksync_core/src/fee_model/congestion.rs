@@ -0,0 +1,127 @@
+//! Congestion pricing for the main node fee model.
+
+use tokio::sync::watch;
+use vise::{Gauge, Metrics};
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::fee_model::FeeModelConfig;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_fee_model_congestion")]
+struct CongestionMetrics {
+    /// Last observed mempool size used for congestion pricing.
+    mempool_size: Gauge<u64>,
+    /// Last multiplier applied to the fair L2 gas price.
+    l2_gas_price_multiplier: Gauge<f64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<CongestionMetrics> = vise::Global::new();
+
+/// Parameters of congestion pricing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CongestionPricingConfig {
+    /// Number of transactions in the mempool above which the mempool is considered congested.
+    pub mempool_size_threshold: u64,
+    /// Multiplier applied to the fair L2 gas price once the mempool size reaches the doubled threshold.
+    pub max_l2_gas_price_multiplier: f64,
+}
+
+impl CongestionPricingConfig {
+    /// Returns `None` if congestion pricing is disabled in the config.
+    pub fn from_state_keeper_config(config: &StateKeeperConfig) -> Option<Self> {
+        Some(Self {
+            mempool_size_threshold: config.congestion_mempool_size_threshold?,
+            max_l2_gas_price_multiplier: config.congestion_l2_gas_price_multiplier(),
+        })
+    }
+
+    /// Similar to EIP-1559, the multiplier grows with the mempool size exceeding the target (i.e., the threshold):
+    /// it's 1 at the threshold and grows linearly to the max multiplier at the doubled threshold.
+    fn l2_gas_price_multiplier(&self, mempool_size: u64) -> f64 {
+        if mempool_size <= self.mempool_size_threshold {
+            return 1.0;
+        }
+        let excess = mempool_size - self.mempool_size_threshold;
+        let excess_ratio = excess as f64 / self.mempool_size_threshold.max(1) as f64;
+        1.0 + (self.max_l2_gas_price_multiplier - 1.0) * excess_ratio.min(1.0)
+    }
+}
+
+/// Congestion pricing based on the mempool size reported by the
+/// [`MempoolFetcher`](crate::state_keeper::MempoolFetcher).
+#[derive(Debug, Clone)]
+pub struct CongestionPricing {
+    config: CongestionPricingConfig,
+    mempool_size: watch::Receiver<u64>,
+}
+
+impl CongestionPricing {
+    /// The max multiplier in the config is expected to be at least 1; this is checked by [`StateKeeperConfig::validate()`].
+    pub fn new(config: CongestionPricingConfig, mempool_size: watch::Receiver<u64>) -> Self {
+        Self {
+            config,
+            mempool_size,
+        }
+    }
+
+    /// Scales the minimal L2 gas price in the provided fee model `config` according to the current mempool size.
+    pub(super) fn apply(&self, config: &mut FeeModelConfig) {
+        let mempool_size = *self.mempool_size.borrow();
+        let multiplier = self.config.l2_gas_price_multiplier(mempool_size);
+        METRICS.mempool_size.set(mempool_size);
+        METRICS.l2_gas_price_multiplier.set(multiplier);
+
+        let minimal_l2_gas_price = match config {
+            FeeModelConfig::V1(config) => &mut config.minimal_l2_gas_price,
+            FeeModelConfig::V2(config) => &mut config.minimal_l2_gas_price,
+        };
+        *minimal_l2_gas_price = (*minimal_l2_gas_price as f64 * multiplier) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::fee_model::FeeModelConfigV1;
+
+    use super::*;
+
+    #[test]
+    fn congestion_multiplier() {
+        let config = CongestionPricingConfig {
+            mempool_size_threshold: 100,
+            max_l2_gas_price_multiplier: 3.0,
+        };
+        assert_eq!(config.l2_gas_price_multiplier(0), 1.0);
+        assert_eq!(config.l2_gas_price_multiplier(100), 1.0);
+        assert_eq!(config.l2_gas_price_multiplier(150), 2.0);
+        assert_eq!(config.l2_gas_price_multiplier(200), 3.0);
+        assert_eq!(config.l2_gas_price_multiplier(1_000), 3.0);
+    }
+
+    #[test]
+    fn applying_congestion_pricing() {
+        let config = CongestionPricingConfig {
+            mempool_size_threshold: 100,
+            max_l2_gas_price_multiplier: 2.0,
+        };
+        let (mempool_size_sender, mempool_size) = watch::channel(0);
+        let pricing = CongestionPricing::new(config, mempool_size);
+        let initial_config = FeeModelConfig::V1(FeeModelConfigV1 {
+            minimal_l2_gas_price: 100,
+        });
+
+        let mut fee_model_config = initial_config;
+        pricing.apply(&mut fee_model_config);
+        assert_eq!(fee_model_config, initial_config);
+
+        mempool_size_sender.send_replace(150);
+        let mut fee_model_config = initial_config;
+        pricing.apply(&mut fee_model_config);
+        assert_eq!(
+            fee_model_config,
+            FeeModelConfig::V1(FeeModelConfigV1 {
+                minimal_l2_gas_price: 150,
+            })
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::{fmt, sync::Arc};
 
+use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_types::{
     fee_model::{
@@ -10,8 +11,15 @@ use zksync_types::{
 };
 use zksync_utils::ceil_div_u256;
 
+pub use self::{
+    config_watcher::FeeModelConfigWatcher,
+    congestion::{CongestionPricing, CongestionPricingConfig},
+};
 use crate::l1_gas_price::GasAdjuster;
 
+mod config_watcher;
+mod congestion;
+
 /// Trait responsible for providing fee info for a batch
 #[async_trait::async_trait]
 pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
@@ -51,15 +59,24 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
 /// The struct that represents the batch fee input provider to be used in the main node of the server, i.e.
 /// it explicitly gets the L1 gas price from the provider and uses it to calculate the batch fee input instead of getting
 /// it from other node.
+///
+/// If [congestion pricing](CongestionPricing) is enabled, the minimal L2 gas price in the returned fee params is scaled
+/// according to the mempool size. Since external nodes get fee params from the main node, the scaled price is propagated
+/// to them as well.
 #[derive(Debug)]
 pub struct MainNodeFeeInputProvider {
     provider: Arc<GasAdjuster>,
-    config: FeeModelConfig,
+    config: watch::Receiver<FeeModelConfig>,
+    congestion_pricing: Option<CongestionPricing>,
 }
 
 impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
     fn get_fee_model_params(&self) -> FeeParams {
-        match self.config {
+        let mut config = *self.config.borrow();
+        if let Some(congestion_pricing) = &self.congestion_pricing {
+            congestion_pricing.apply(&mut config);
+        }
+        match config {
             FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
                 config,
                 l1_gas_price: self.provider.estimate_effective_gas_price(),
@@ -75,7 +92,27 @@ impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
 
 impl MainNodeFeeInputProvider {
     pub fn new(provider: Arc<GasAdjuster>, config: FeeModelConfig) -> Self {
-        Self { provider, config }
+        Self::from_config_receiver(provider, watch::channel(config).1)
+    }
+
+    /// Creates a provider using the latest fee model config from the specified receiver, e.g. one obtained
+    /// from [`FeeModelConfigWatcher::subscribe()`]. Config updates are picked up without a restart.
+    pub fn from_config_receiver(
+        provider: Arc<GasAdjuster>,
+        config: watch::Receiver<FeeModelConfig>,
+    ) -> Self {
+        Self {
+            provider,
+            config,
+            congestion_pricing: None,
+        }
+    }
+
+    /// Enables congestion pricing for this provider.
+    #[must_use]
+    pub fn with_congestion_pricing(mut self, congestion_pricing: CongestionPricing) -> Self {
+        self.congestion_pricing = Some(congestion_pricing);
+        self
     }
 }
 
@@ -0,0 +1,31 @@
+//! Metrics for finality webhooks.
+
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+use super::FinalityStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum DeliveryResult {
+    Delivered,
+    Failed,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_finality_webhooks")]
+pub(super) struct FinalityWebhooksMetrics {
+    /// Number of notification delivery attempts grouped by the result.
+    pub delivery_attempts: Family<DeliveryResult, Counter>,
+    /// Latency of successful notification deliveries, including retries.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub delivery_latency: Histogram<Duration>,
+    /// Last L1 batch for which a notification was delivered, grouped by the L1 batch status.
+    pub last_notified_l1_batch: Family<FinalityStatus, Gauge<u64>>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<FinalityWebhooksMetrics> = vise::Global::new();
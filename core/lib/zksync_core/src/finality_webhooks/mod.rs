@@ -0,0 +1,381 @@
+//! Push notifications about L1 batches transitioning between L1 states (committed, proven, executed).
+//!
+//! Notifications are delivered to each configured webhook in the L1 batch order, with at-least-once semantics:
+//! the last delivered L1 batch is persisted per webhook and status, so that notifications aren't repeated
+//! after a restart, except for a notification that was delivered but not yet persisted. Receivers should
+//! deduplicate notifications by their [ID](FinalityNotification::id).
+//!
+//! Each webhook is served by a separate task, so that a slow or unavailable webhook doesn't delay notifications
+//! to other webhooks. Webhook URLs may contain credentials, so they are neither logged nor persisted.
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::watch;
+use vise::{EncodeLabelSet, EncodeLabelValue};
+use zksync_config::configs::FinalityWebhooksConfig;
+use zksync_dal::{finality_webhooks_dal::L1BatchFinalityUpdate, ConnectionPool, Core, CoreDal};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, web3::signing::keccak256, L1BatchNumber,
+    L2ChainId, PackedEthSignature, H256,
+};
+
+use self::metrics::{DeliveryResult, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// HTTP header containing the hex-encoded signature of the notification body.
+pub const SIGNATURE_HEADER: &str = "x-zksync-signature";
+/// HTTP header containing the notification ID.
+pub const NOTIFICATION_ID_HEADER: &str = "x-zksync-notification-id";
+
+/// Max number of notifications for each webhook and status loaded from Postgres in a single poll.
+const MAX_NOTIFICATIONS_PER_POLL: usize = 100;
+/// Backoff before the first delivery retry; doubled for each subsequent retry.
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// L1 status of an L1 batch reported in a [`FinalityNotification`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EncodeLabelValue, EncodeLabelSet)]
+#[serde(rename_all = "camelCase")]
+#[metrics(label = "status", rename_all = "snake_case")]
+pub enum FinalityStatus {
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl FinalityStatus {
+    const ALL: [Self; 3] = [Self::Committed, Self::Proven, Self::Executed];
+
+    fn action_type(self) -> AggregatedActionType {
+        match self {
+            Self::Committed => AggregatedActionType::Commit,
+            Self::Proven => AggregatedActionType::PublishProofOnchain,
+            Self::Executed => AggregatedActionType::Execute,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Committed => "committed",
+            Self::Proven => "proven",
+            Self::Executed => "executed",
+        }
+    }
+}
+
+/// Notification about an L1 batch reaching a certain [status](FinalityStatus) on L1. Serialized as a JSON object
+/// in the body of `POST` requests to webhooks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalityNotification {
+    /// Unique notification ID in the form `{chainId}:{status}:{l1BatchNumber}`.
+    pub id: String,
+    pub chain_id: L2ChainId,
+    pub l1_batch_number: L1BatchNumber,
+    pub status: FinalityStatus,
+    pub root_hash: Option<H256>,
+    pub commitment: Option<H256>,
+    /// Hash of the L1 transaction that has moved the L1 batch to this status.
+    pub eth_tx_hash: H256,
+    /// Time the L1 transaction was confirmed.
+    pub confirmed_at: DateTime<Utc>,
+}
+
+impl FinalityNotification {
+    /// Domain separator for notification signatures.
+    const SIGNATURE_DOMAIN: &'static [u8] = b"zksync-era:finalityNotification:v1";
+
+    fn new(chain_id: L2ChainId, status: FinalityStatus, update: L1BatchFinalityUpdate) -> Self {
+        Self {
+            id: format!(
+                "{}:{}:{}",
+                chain_id.as_u64(),
+                status.as_str(),
+                update.l1_batch_number
+            ),
+            chain_id,
+            l1_batch_number: update.l1_batch_number,
+            status,
+            root_hash: update.root_hash,
+            commitment: update.commitment,
+            eth_tx_hash: update.eth_tx_hash,
+            confirmed_at: DateTime::from_naive_utc_and_offset(update.confirmed_at, Utc),
+        }
+    }
+
+    /// Returns the digest signed by the main node for the specified serialized notification.
+    pub fn signed_digest(body: &[u8]) -> H256 {
+        let mut preimage = Self::SIGNATURE_DOMAIN.to_vec();
+        preimage.extend_from_slice(body);
+        H256(keccak256(&preimage))
+    }
+}
+
+/// Webhook URL together with its identifiers used in logs and Postgres.
+struct Webhook {
+    url: String,
+    /// Hash of the URL used as a key for delivery cursors in Postgres.
+    id: H256,
+    /// Redacted webhook description used in logs: the webhook index in the config and the URL origin.
+    name: String,
+}
+
+impl fmt::Debug for Webhook {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Webhook")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Webhook {
+    fn new(index: usize, url: String) -> Self {
+        let origin = reqwest::Url::parse(&url)
+            .ok()
+            .and_then(|url| Some(format!("{}://{}", url.scheme(), url.host_str()?)))
+            .unwrap_or_else(|| "invalid URL".to_owned());
+        Self {
+            id: H256(keccak256(url.as_bytes())),
+            name: format!("#{index} ({origin})"),
+            url,
+        }
+    }
+}
+
+/// Component sending [`FinalityNotification`]s to the configured webhooks.
+#[derive(Debug)]
+pub struct FinalityWebhooks {
+    config: FinalityWebhooksConfig,
+    pool: ConnectionPool<Core>,
+    chain_id: L2ChainId,
+    signing_key: H256,
+    client: reqwest::Client,
+}
+
+impl FinalityWebhooks {
+    /// Creates a new component. Notifications are signed with the provided private key (the signer wallet key).
+    pub fn new(
+        config: FinalityWebhooksConfig,
+        pool: ConnectionPool<Core>,
+        chain_id: L2ChainId,
+        signing_key: H256,
+    ) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout())
+            .build()
+            .context("failed building HTTP client")?;
+        Ok(Self {
+            config,
+            pool,
+            chain_id,
+            signing_key,
+            client,
+        })
+    }
+
+    /// Delivers all pending notifications to the specified webhook. Returns `Ok(false)` if a notification
+    /// could not be delivered.
+    async fn notify_webhook(
+        &self,
+        webhook: &Webhook,
+        status: FinalityStatus,
+    ) -> anyhow::Result<bool> {
+        let action_type = status.action_type();
+        let mut storage = self.pool.connection_tagged("finality_webhooks").await?;
+        let cursor = storage
+            .finality_webhooks_dal()
+            .get_cursor(webhook.id, action_type)
+            .await?;
+        let cursor = if let Some(cursor) = cursor {
+            cursor
+        } else {
+            // Don't send notifications for historical L1 batches to newly added webhooks.
+            let last_l1_batch = match status {
+                FinalityStatus::Committed => {
+                    storage
+                        .blocks_dal()
+                        .get_number_of_last_l1_batch_committed_on_eth()
+                        .await?
+                }
+                FinalityStatus::Proven => {
+                    storage
+                        .blocks_dal()
+                        .get_number_of_last_l1_batch_proven_on_eth()
+                        .await?
+                }
+                FinalityStatus::Executed => {
+                    storage
+                        .blocks_dal()
+                        .get_number_of_last_l1_batch_executed_on_eth()
+                        .await?
+                }
+            };
+            let cursor = last_l1_batch.unwrap_or(L1BatchNumber(0));
+            tracing::info!(
+                "Starting {} notifications for webhook {} after L1 batch #{cursor}",
+                status.as_str(),
+                webhook.name
+            );
+            storage
+                .finality_webhooks_dal()
+                .set_cursor(webhook.id, action_type, cursor)
+                .await?;
+            cursor
+        };
+
+        let updates = storage
+            .finality_webhooks_dal()
+            .get_finality_updates(action_type, cursor, MAX_NOTIFICATIONS_PER_POLL)
+            .await?;
+        drop(storage);
+
+        for update in updates {
+            let l1_batch_number = update.l1_batch_number;
+            let notification = FinalityNotification::new(self.chain_id, status, update);
+            if !self.deliver(webhook, &notification).await? {
+                return Ok(false);
+            }
+
+            let mut storage = self.pool.connection_tagged("finality_webhooks").await?;
+            storage
+                .finality_webhooks_dal()
+                .set_cursor(webhook.id, action_type, l1_batch_number)
+                .await?;
+            METRICS.last_notified_l1_batch[&status].set(l1_batch_number.0.into());
+        }
+        Ok(true)
+    }
+
+    /// Delivers a single notification, retrying with exponential backoff. Returns `Ok(false)`
+    /// if the notification was not delivered after the configured number of attempts.
+    async fn deliver(
+        &self,
+        webhook: &Webhook,
+        notification: &FinalityNotification,
+    ) -> anyhow::Result<bool> {
+        let body = serde_json::to_vec(notification).context("failed serializing notification")?;
+        let digest = FinalityNotification::signed_digest(&body);
+        let signature = PackedEthSignature::sign_raw(&self.signing_key, &digest)
+            .context("failed signing notification")?;
+        let signature = format!("0x{}", hex::encode(signature.serialize_packed()));
+
+        let started_at = Instant::now();
+        let mut backoff = INITIAL_RETRY_BACKOFF;
+        for attempt in 1..=self.config.max_delivery_attempts {
+            let response = self
+                .client
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(NOTIFICATION_ID_HEADER, &notification.id)
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+
+            match response {
+                Ok(_) => {
+                    METRICS.delivery_attempts[&DeliveryResult::Delivered].inc();
+                    METRICS.delivery_latency.observe(started_at.elapsed());
+                    tracing::debug!(
+                        "Delivered notification {} to webhook {}",
+                        notification.id,
+                        webhook.name
+                    );
+                    return Ok(true);
+                }
+                Err(err) => {
+                    METRICS.delivery_attempts[&DeliveryResult::Failed].inc();
+                    // The error is stripped of the URL, which may contain credentials.
+                    let err = err.without_url();
+                    tracing::warn!(
+                        "Failed delivering notification {} to webhook {} (attempt {attempt}/{}): {err}",
+                        notification.id,
+                        webhook.name,
+                        self.config.max_delivery_attempts
+                    );
+                }
+            }
+            if attempt < self.config.max_delivery_attempts {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+        Ok(false)
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting finality webhooks for {} URL(s) with poll interval {:?}",
+            self.config.urls.len(),
+            self.config.poll_interval()
+        );
+
+        let webhooks: Vec<_> = self
+            .config
+            .urls
+            .iter()
+            .enumerate()
+            .map(|(index, url)| Webhook::new(index, url.clone()))
+            .collect();
+        let this = Arc::new(self);
+        let tasks = webhooks.into_iter().map(|webhook| {
+            let this = this.clone();
+            let stop_receiver = stop_receiver.clone();
+            let handle =
+                tokio::spawn(async move { this.run_for_webhook(&webhook, stop_receiver).await });
+            async move { handle.await.context("finality webhook task panicked")? }
+        });
+        futures::future::try_join_all(tasks).await?;
+        tracing::info!("Stop signal received, finality webhooks are shutting down");
+        Ok(())
+    }
+
+    async fn run_for_webhook(
+        &self,
+        webhook: &Webhook,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            let notify_all = async {
+                for status in FinalityStatus::ALL {
+                    let delivered = self
+                        .notify_webhook(webhook, status)
+                        .await
+                        .with_context(|| format!("failed notifying webhook {}", webhook.name))?;
+                    if !delivered {
+                        // Notifications with other statuses may depend on this one (e.g., an L1 batch cannot
+                        // be executed before it's committed), so we don't send them until this one is delivered.
+                        break;
+                    }
+                }
+                anyhow::Ok(())
+            };
+            // Delivery can be interrupted at any point; this doesn't violate at-least-once semantics
+            // since cursors are only advanced after a notification is delivered.
+            tokio::select! {
+                result = notify_all => result?,
+                _ = stop_receiver.changed() => break,
+            }
+
+            if tokio::time::timeout(self.config.poll_interval(), stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
@@ -12,6 +12,4 @@ pub(super) const L1_BATCH_EXECUTE_BASE_COST: u32 = 30_000;
 pub(super) const EXECUTE_COMMIT_COST: u32 = 0;
 pub(super) const EXECUTE_EXECUTE_COST: u32 = 0;
 
-pub(super) const L1_OPERATION_EXECUTE_COST: u32 = 12_500;
-
 pub(super) const GAS_PER_BYTE: u32 = 18;
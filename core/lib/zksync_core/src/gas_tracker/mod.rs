@@ -27,12 +27,61 @@ pub fn l1_batch_base_cost(op: AggregatedActionType) -> u32 {
     }
 }
 
-fn base_tx_cost(tx: &Transaction, op: AggregatedActionType) -> u32 {
+/// L1 gas costs of processing priority (L1) transactions. The costs depend on the protocol version
+/// and should be obtained using [`Self::for_protocol_version()`].
+///
+/// The number of priority operations in an L1 batch is not limited separately; it's bounded by the transaction slots
+/// and the L1 gas limits for the batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1TxLimits {
+    /// L1 gas spent on a single priority operation by the `Commit` operation.
+    pub commit_gas_per_priority_op: u32,
+    /// L1 gas spent on a single priority operation by the `Execute` operation.
+    pub execute_gas_per_priority_op: u32,
+}
+
+impl L1TxLimits {
+    /// Pre-Boojum versions process L2-to-L1 logs (including the log emitted for each priority operation)
+    /// one by one when committing an L1 batch.
+    const PRE_BOOJUM: Self = Self {
+        commit_gas_per_priority_op: 1_000,
+        execute_gas_per_priority_op: 12_500,
+    };
+    /// Since Boojum, L2-to-L1 logs are committed as a Merkle root, and priority operations are checked
+    /// against a single rolling hash.
+    const BOOJUM: Self = Self {
+        commit_gas_per_priority_op: 0,
+        execute_gas_per_priority_op: 12_500,
+    };
+
+    /// Returns limits applicable to L1 batches with the specified protocol version.
+    pub fn for_protocol_version(protocol_version: ProtocolVersionId) -> Self {
+        if protocol_version.is_pre_boojum() {
+            Self::PRE_BOOJUM
+        } else {
+            Self::BOOJUM
+        }
+    }
+}
+
+fn base_tx_cost(
+    tx: &Transaction,
+    op: AggregatedActionType,
+    protocol_version: ProtocolVersionId,
+) -> u32 {
     match op {
-        AggregatedActionType::Commit => EXECUTE_COMMIT_COST,
+        AggregatedActionType::Commit => match tx.common_data {
+            ExecuteTransactionCommon::L1(_) => {
+                L1TxLimits::for_protocol_version(protocol_version).commit_gas_per_priority_op
+            }
+            ExecuteTransactionCommon::L2(_) => EXECUTE_COMMIT_COST,
+            ExecuteTransactionCommon::ProtocolUpgrade(_) => EXECUTE_COMMIT_COST,
+        },
         AggregatedActionType::PublishProofOnchain => 0,
         AggregatedActionType::Execute => match tx.common_data {
-            ExecuteTransactionCommon::L1(_) => L1_OPERATION_EXECUTE_COST,
+            ExecuteTransactionCommon::L1(_) => {
+                L1TxLimits::for_protocol_version(protocol_version).execute_gas_per_priority_op
+            }
             ExecuteTransactionCommon::L2(_) => EXECUTE_EXECUTE_COST,
             ExecuteTransactionCommon::ProtocolUpgrade(_) => EXECUTE_EXECUTE_COST,
         },
@@ -61,13 +110,18 @@ pub fn new_block_gas_count() -> BlockGasCount {
 pub fn gas_count_from_tx_and_metrics(
     tx: &Transaction,
     execution_metrics: &ExecutionMetrics,
+    protocol_version: ProtocolVersionId,
 ) -> BlockGasCount {
-    let commit = base_tx_cost(tx, AggregatedActionType::Commit)
+    let commit = base_tx_cost(tx, AggregatedActionType::Commit, protocol_version)
         + additional_pubdata_commit_cost(execution_metrics);
     BlockGasCount {
         commit,
-        prove: base_tx_cost(tx, AggregatedActionType::PublishProofOnchain),
-        execute: base_tx_cost(tx, AggregatedActionType::Execute),
+        prove: base_tx_cost(
+            tx,
+            AggregatedActionType::PublishProofOnchain,
+            protocol_version,
+        ),
+        execute: base_tx_cost(tx, AggregatedActionType::Execute, protocol_version),
     }
 }
 
@@ -89,3 +143,20 @@ pub fn gas_count_from_writes(
         execute: 0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn l1_tx_limits_depend_on_protocol_version() {
+        let pre_boojum_limits = L1TxLimits::for_protocol_version(ProtocolVersionId::Version17);
+        assert_eq!(pre_boojum_limits, L1TxLimits::PRE_BOOJUM);
+        assert!(pre_boojum_limits.commit_gas_per_priority_op > 0);
+
+        for version in [ProtocolVersionId::Version18, ProtocolVersionId::latest()] {
+            let limits = L1TxLimits::for_protocol_version(version);
+            assert_eq!(limits, L1TxLimits::BOOJUM, "{version:?}");
+        }
+    }
+}
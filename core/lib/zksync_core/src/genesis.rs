@@ -487,6 +487,7 @@ pub(crate) async fn create_genesis_l1_batch(
         protocol_version: Some(protocol_version),
         virtual_blocks: 0,
         gas_limit: 0,
+        logs_bloom: Default::default(),
     };
 
     let mut transaction = storage.start_transaction().await?;
@@ -502,6 +503,7 @@ pub(crate) async fn create_genesis_l1_batch(
             &[],
             BlockGasCount::default(),
             &[],
+            0,
             Default::default(),
         )
         .await?;
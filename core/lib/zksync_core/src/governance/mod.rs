@@ -0,0 +1,391 @@
+//! Governance operations executed through the L1 governance contract.
+//!
+//! An operation goes through the following stages, each of which is recorded in the audit trail
+//! in Postgres:
+//!
+//! 1. **Proposal.** The operation is encoded and simulated against L1 using `eth_call` on behalf
+//!    of the governance owner; if simulation succeeds, the operation is recorded as proposed.
+//! 2. **Scheduling.** The transaction scheduling the operation is signed with the owner key and sent to L1.
+//! 3. **Execution.** After the governance delay has passed, the transaction executing the operation
+//!    is signed and sent to L1.
+//!
+//! A stage is recorded as completed only after the corresponding L1 transaction is mined successfully;
+//! if the transaction reverts, the operation is marked as failed.
+//!
+//! Operators drive the workflow with the `governance_operator` CLI.
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_config::{configs::chain::L1BatchCommitDataGeneratorMode, ContractsConfig};
+use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_dal::{governance_dal::GovernanceOperationStatus, ConnectionPool, Core, CoreDal};
+use zksync_eth_client::{BoundEthInterface, CallFunctionArgs, Options};
+use zksync_l1_contract_interface::Detokenize;
+use zksync_types::{
+    ethabi::{self, Token},
+    web3::{signing::keccak256, types::Bytes},
+    Address, H256, U256,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Gas limit for governance transactions. Governance operations are rare, so it's set generously.
+const GOVERNANCE_TX_GAS_LIMIT: u64 = 10_000_000;
+/// Interval between polls of the L1 transaction status.
+const TX_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Maximum time to wait until a governance transaction is mined.
+const TX_MINING_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Fee parameters of the chain as defined by the `FeeParams` struct in L1 contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1FeeParams {
+    pub pubdata_pricing_mode: L1BatchCommitDataGeneratorMode,
+    pub batch_overhead_l1_gas: u32,
+    pub max_pubdata_per_batch: u32,
+    pub max_l2_gas_per_batch: u32,
+    pub priority_tx_max_pubdata: u32,
+    pub minimal_l2_gas_price: u64,
+}
+
+impl L1FeeParams {
+    fn into_token(self) -> Token {
+        let pubdata_pricing_mode = match self.pubdata_pricing_mode {
+            L1BatchCommitDataGeneratorMode::Rollup => 0_u8,
+            L1BatchCommitDataGeneratorMode::Validium => 1,
+        };
+        Token::Tuple(vec![
+            Token::Uint(pubdata_pricing_mode.into()),
+            Token::Uint(self.batch_overhead_l1_gas.into()),
+            Token::Uint(self.max_pubdata_per_batch.into()),
+            Token::Uint(self.max_l2_gas_per_batch.into()),
+            Token::Uint(self.priority_tx_max_pubdata.into()),
+            Token::Uint(self.minimal_l2_gas_price.into()),
+        ])
+    }
+}
+
+/// Action performed by a governance operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GovernanceAction {
+    /// Adds or removes a validator allowed to commit, prove and execute L1 batches.
+    SetValidator { validator: Address, active: bool },
+    /// Changes fee parameters of the chain.
+    ChangeFeeParams(L1FeeParams),
+    /// Executes an upgrade proposal on the diamond proxy. The calldata is produced by the upgrade tooling.
+    Upgrade { calldata: Bytes },
+    /// Arbitrary call from the governance contract.
+    Call {
+        target: Address,
+        value: U256,
+        data: Bytes,
+    },
+}
+
+impl GovernanceAction {
+    /// Returns `(target, value, calldata)` for the action.
+    fn to_call(&self, diamond_proxy_addr: Address) -> anyhow::Result<(Address, U256, Vec<u8>)> {
+        let encode_diamond_proxy_call = |name: &str, params: &[Token]| {
+            let contract = zksync_contract();
+            let function = contract
+                .function(name)
+                .with_context(|| format!("`{name}` function is missing from the zkSync ABI"))?;
+            let data = function
+                .encode_input(params)
+                .with_context(|| format!("failed encoding `{name}` call"))?;
+            anyhow::Ok((diamond_proxy_addr, U256::zero(), data))
+        };
+
+        match self {
+            Self::SetValidator { validator, active } => encode_diamond_proxy_call(
+                "setValidator",
+                &[Token::Address(*validator), Token::Bool(*active)],
+            ),
+            Self::ChangeFeeParams(params) => {
+                encode_diamond_proxy_call("changeFeeParams", &[params.into_token()])
+            }
+            Self::Upgrade { calldata } => {
+                Ok((diamond_proxy_addr, U256::zero(), calldata.0.clone()))
+            }
+            Self::Call {
+                target,
+                value,
+                data,
+            } => Ok((*target, *value, data.0.clone())),
+        }
+    }
+}
+
+/// Governance operation proposed by the operator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GovernanceProposal {
+    /// Human-readable description of the operation recorded in the audit trail.
+    pub description: String,
+    pub actions: Vec<GovernanceAction>,
+    /// ID of the operation that must be executed before this one, or zero if there is no such operation.
+    #[serde(default)]
+    pub predecessor: H256,
+    /// Salt distinguishing operations with the same actions.
+    #[serde(default)]
+    pub salt: H256,
+    /// Delay (in seconds) between scheduling and execution of the operation. Must be not less than
+    /// the minimum delay set in the governance contract.
+    pub delay: u64,
+}
+
+impl GovernanceProposal {
+    /// Encodes the proposal as the `Operation` struct of the governance contract.
+    fn operation_token(&self, diamond_proxy_addr: Address) -> anyhow::Result<Token> {
+        let calls = self
+            .actions
+            .iter()
+            .map(|action| {
+                let (target, value, data) = action.to_call(diamond_proxy_addr)?;
+                Ok(Token::Tuple(vec![
+                    Token::Address(target),
+                    Token::Uint(value),
+                    Token::Bytes(data),
+                ]))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Token::Tuple(vec![
+            Token::Array(calls),
+            Token::FixedBytes(self.predecessor.as_bytes().to_vec()),
+            Token::FixedBytes(self.salt.as_bytes().to_vec()),
+        ]))
+    }
+}
+
+/// Operation ID as computed by `hashOperation()` in the governance contract.
+fn operation_id(operation: &Token) -> H256 {
+    H256(keccak256(&ethabi::encode(&[operation.clone()])))
+}
+
+/// Proposes, schedules and executes governance operations on behalf of the governance owner.
+pub struct GovernanceOperator {
+    pool: ConnectionPool<Core>,
+    /// Client signing transactions with the governance owner key.
+    eth_client: Arc<dyn BoundEthInterface>,
+    governance_contract: ethabi::Contract,
+    governance_addr: Address,
+    diamond_proxy_addr: Address,
+    tx_status_poll_interval: Duration,
+}
+
+impl fmt::Debug for GovernanceOperator {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("GovernanceOperator")
+            .field("eth_client", &self.eth_client)
+            .field("governance_addr", &self.governance_addr)
+            .field("diamond_proxy_addr", &self.diamond_proxy_addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GovernanceOperator {
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        eth_client: Arc<dyn BoundEthInterface>,
+        contracts: &ContractsConfig,
+    ) -> Self {
+        Self {
+            pool,
+            eth_client,
+            governance_contract: governance_contract(),
+            governance_addr: contracts.governance_addr,
+            diamond_proxy_addr: contracts.diamond_proxy_addr,
+            tx_status_poll_interval: TX_STATUS_POLL_INTERVAL,
+        }
+    }
+
+    /// Simulates scheduling of the proposal on L1 without sending any transactions.
+    pub async fn dry_run(&self, proposal: &GovernanceProposal) -> anyhow::Result<H256> {
+        let operation = proposal.operation_token(self.diamond_proxy_addr)?;
+        self.simulate(operation.clone(), proposal.delay).await?;
+        Ok(operation_id(&operation))
+    }
+
+    async fn simulate(&self, operation: Token, delay: u64) -> anyhow::Result<()> {
+        let args = CallFunctionArgs::new("scheduleTransparent", (operation, U256::from(delay)))
+            .with_sender(self.eth_client.sender_account())
+            .for_contract(self.governance_addr, self.governance_contract.clone());
+        self.eth_client
+            .call_contract_function(args)
+            .await
+            .context("simulating operation scheduling failed")?;
+        Ok(())
+    }
+
+    /// Simulates the proposal and, if the simulation succeeds, records it in the audit trail.
+    /// Returns the ID of the proposed operation.
+    pub async fn propose(&self, proposal: &GovernanceProposal) -> anyhow::Result<H256> {
+        let operation_id = self.dry_run(proposal).await?;
+        let serialized_proposal =
+            serde_json::to_value(proposal).context("failed serializing proposal")?;
+        let mut storage = self.pool.connection_tagged("governance").await?;
+        storage
+            .governance_dal()
+            .insert_operation(
+                operation_id,
+                &proposal.description,
+                &serialized_proposal,
+                proposal.delay,
+            )
+            .await
+            .with_context(|| format!("failed recording operation {operation_id:?}"))?;
+        tracing::info!("Proposed governance operation {operation_id:?}: {proposal:?}");
+        Ok(operation_id)
+    }
+
+    async fn load_proposal(
+        &self,
+        operation_id: H256,
+        expected_status: GovernanceOperationStatus,
+    ) -> anyhow::Result<GovernanceProposal> {
+        let mut storage = self.pool.connection_tagged("governance").await?;
+        let operation = storage
+            .governance_dal()
+            .get_operation(operation_id)
+            .await?
+            .with_context(|| format!("governance operation {operation_id:?} is not proposed"))?;
+        anyhow::ensure!(
+            operation.status == expected_status,
+            "governance operation {operation_id:?} has unexpected status {}; expected {expected_status}",
+            operation.status
+        );
+        serde_json::from_value(operation.operation)
+            .with_context(|| format!("failed deserializing governance operation {operation_id:?}"))
+    }
+
+    /// Signs and sends a transaction calling `function` in the governance contract, and waits until
+    /// the transaction is mined successfully.
+    async fn send_governance_tx(&self, function: &str, params: &[Token]) -> anyhow::Result<H256> {
+        let data = self
+            .governance_contract
+            .function(function)
+            .with_context(|| format!("`{function}` function is missing from the governance ABI"))?
+            .encode_input(params)
+            .with_context(|| format!("failed encoding `{function}` call"))?;
+        let nonce = self.eth_client.pending_nonce("governance").await?;
+        let options = Options {
+            nonce: Some(nonce),
+            gas: Some(GOVERNANCE_TX_GAS_LIMIT.into()),
+            ..Options::default()
+        };
+        let signed_tx = self
+            .eth_client
+            .sign_prepared_tx_for_addr(data, self.governance_addr, options, "governance")
+            .await
+            .with_context(|| format!("failed signing `{function}` transaction"))?;
+        let tx_hash = self
+            .eth_client
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+            .with_context(|| format!("failed sending `{function}` transaction"))?;
+        tracing::info!("Sent `{function}` governance transaction {tx_hash:?}");
+        self.wait_for_success(tx_hash)
+            .await
+            .with_context(|| format!("`{function}` transaction {tx_hash:?} did not succeed"))?;
+        Ok(tx_hash)
+    }
+
+    async fn wait_for_success(&self, tx_hash: H256) -> anyhow::Result<()> {
+        let started_at = Instant::now();
+        loop {
+            let status = self
+                .eth_client
+                .get_tx_status(tx_hash, "governance")
+                .await
+                .context("failed getting transaction status")?;
+            if let Some(status) = status {
+                anyhow::ensure!(status.success, "transaction was reverted on L1");
+                return Ok(());
+            }
+            anyhow::ensure!(
+                started_at.elapsed() < TX_MINING_TIMEOUT,
+                "transaction was not mined in {TX_MINING_TIMEOUT:?}; check its status on L1"
+            );
+            tokio::time::sleep(self.tx_status_poll_interval).await;
+        }
+    }
+
+    async fn mark_failed(&self, operation_id: H256, err: &anyhow::Error) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("governance").await?;
+        storage
+            .governance_dal()
+            .mark_operation_failed(operation_id, &format!("{err:#}"))
+            .await?;
+        Ok(())
+    }
+
+    /// Schedules a proposed operation on L1. Returns the hash of the scheduling transaction.
+    pub async fn schedule(&self, operation_id: H256) -> anyhow::Result<H256> {
+        let proposal = self
+            .load_proposal(operation_id, GovernanceOperationStatus::Proposed)
+            .await?;
+        let operation = proposal.operation_token(self.diamond_proxy_addr)?;
+        let params = [operation, Token::Uint(proposal.delay.into())];
+        let tx_hash = match self
+            .send_governance_tx("scheduleTransparent", &params)
+            .await
+        {
+            Ok(tx_hash) => tx_hash,
+            Err(err) => {
+                self.mark_failed(operation_id, &err).await?;
+                return Err(err);
+            }
+        };
+
+        let mut storage = self.pool.connection_tagged("governance").await?;
+        storage
+            .governance_dal()
+            .mark_operation_scheduled(operation_id, tx_hash)
+            .await?;
+        tracing::info!("Scheduled governance operation {operation_id:?} in L1 tx {tx_hash:?}");
+        Ok(tx_hash)
+    }
+
+    /// Checks whether a scheduled operation can be executed, i.e. its scheduling transaction is mined
+    /// and the delay has passed.
+    pub async fn is_ready(&self, operation_id: H256) -> anyhow::Result<bool> {
+        let args = CallFunctionArgs::new("isOperationReady", operation_id)
+            .for_contract(self.governance_addr, self.governance_contract.clone());
+        let tokens = self.eth_client.call_contract_function(args).await?;
+        bool::from_tokens(tokens).context("failed decoding `isOperationReady` output")
+    }
+
+    /// Executes a scheduled operation on L1. Returns the hash of the execution transaction.
+    pub async fn execute(&self, operation_id: H256) -> anyhow::Result<H256> {
+        let proposal = self
+            .load_proposal(operation_id, GovernanceOperationStatus::Scheduled)
+            .await?;
+        anyhow::ensure!(
+            self.is_ready(operation_id).await?,
+            "governance operation {operation_id:?} is not ready for execution"
+        );
+        let operation = proposal.operation_token(self.diamond_proxy_addr)?;
+        let tx_hash = match self.send_governance_tx("execute", &[operation]).await {
+            Ok(tx_hash) => tx_hash,
+            Err(err) => {
+                self.mark_failed(operation_id, &err).await?;
+                return Err(err);
+            }
+        };
+
+        let mut storage = self.pool.connection_tagged("governance").await?;
+        storage
+            .governance_dal()
+            .mark_operation_executed(operation_id, tx_hash)
+            .await?;
+        tracing::info!("Executed governance operation {operation_id:?} in L1 tx {tx_hash:?}");
+        Ok(tx_hash)
+    }
+}
@@ -0,0 +1,169 @@
+use zksync_dal::governance_dal::GovernanceOperationStatus;
+use zksync_eth_client::clients::MockEthereum;
+
+use super::*;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn test_proposal() -> GovernanceProposal {
+    GovernanceProposal {
+        description: "Add validator".to_owned(),
+        actions: vec![GovernanceAction::SetValidator {
+            validator: Address::repeat_byte(0x23),
+            active: true,
+        }],
+        predecessor: H256::zero(),
+        salt: H256::repeat_byte(1),
+        delay: 0,
+    }
+}
+
+fn mock_eth_client(operation_ready: bool) -> Arc<MockEthereum> {
+    let governance_addr = ContractsConfig::for_tests().governance_addr;
+    let eth_client = MockEthereum::default().with_call_handler(move |call| {
+        assert_eq!(call.contract_address(), governance_addr);
+        match call.function_name() {
+            "scheduleTransparent" => Token::Tuple(vec![]),
+            "isOperationReady" => Token::Bool(operation_ready),
+            name => panic!("Unexpected eth_call: {name}"),
+        }
+    });
+    Arc::new(eth_client)
+}
+
+fn test_operator(pool: &ConnectionPool<Core>, eth_client: Arc<MockEthereum>) -> GovernanceOperator {
+    let mut operator =
+        GovernanceOperator::new(pool.clone(), eth_client, &ContractsConfig::for_tests());
+    operator.tx_status_poll_interval = POLL_INTERVAL;
+    operator
+}
+
+/// Waits until a transaction is sent to the mock L1 and executes it with the specified outcome.
+async fn execute_next_tx(eth_client: &MockEthereum, success: bool) -> H256 {
+    loop {
+        if let Some(&tx_hash) = eth_client.pending_tx_hashes().first() {
+            eth_client.execute_tx(tx_hash, success, 1);
+            return tx_hash;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[test]
+fn proposal_serialization() {
+    let proposal = test_proposal();
+    let serialized = serde_json::to_value(&proposal).unwrap();
+    assert_eq!(serialized["actions"][0]["type"], "set_validator");
+    let deserialized: GovernanceProposal = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized, proposal);
+}
+
+#[test]
+fn operation_id_depends_on_salt() {
+    let diamond_proxy_addr = Address::repeat_byte(0x09);
+    let mut proposal = test_proposal();
+    let operation = proposal.operation_token(diamond_proxy_addr).unwrap();
+    let id = operation_id(&operation);
+    proposal.salt = H256::repeat_byte(2);
+    let other_operation = proposal.operation_token(diamond_proxy_addr).unwrap();
+    assert_ne!(operation_id(&other_operation), id);
+}
+
+#[tokio::test]
+async fn governance_operation_workflow() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let eth_client = mock_eth_client(true);
+    let operator = test_operator(&pool, eth_client.clone());
+
+    let operation_id = operator.propose(&test_proposal()).await.unwrap();
+    let mut storage = pool.connection().await.unwrap();
+    let operation = storage
+        .governance_dal()
+        .get_operation(operation_id)
+        .await
+        .unwrap()
+        .expect("no operation");
+    assert_eq!(operation.status, GovernanceOperationStatus::Proposed);
+    assert_eq!(eth_client.sent_tx_count(), 0);
+
+    // Execution must not be possible before the operation is scheduled.
+    operator.execute(operation_id).await.unwrap_err();
+
+    let (schedule_tx_hash, mined_tx_hash) = tokio::join!(
+        operator.schedule(operation_id),
+        execute_next_tx(&eth_client, true)
+    );
+    let schedule_tx_hash = schedule_tx_hash.unwrap();
+    assert_eq!(schedule_tx_hash, mined_tx_hash);
+    assert_eq!(eth_client.sent_tx_count(), 1);
+    let (execute_tx_hash, mined_tx_hash) = tokio::join!(
+        operator.execute(operation_id),
+        execute_next_tx(&eth_client, true)
+    );
+    let execute_tx_hash = execute_tx_hash.unwrap();
+    assert_eq!(execute_tx_hash, mined_tx_hash);
+    assert_eq!(eth_client.sent_tx_count(), 2);
+
+    let operation = storage
+        .governance_dal()
+        .get_operation(operation_id)
+        .await
+        .unwrap()
+        .expect("no operation");
+    assert_eq!(operation.status, GovernanceOperationStatus::Executed);
+    assert_eq!(operation.schedule_tx_hash, Some(schedule_tx_hash));
+    assert_eq!(operation.execute_tx_hash, Some(execute_tx_hash));
+}
+
+#[tokio::test]
+async fn operation_is_not_executed_before_it_is_ready() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let eth_client = mock_eth_client(false);
+    let operator = test_operator(&pool, eth_client.clone());
+
+    let operation_id = operator.propose(&test_proposal()).await.unwrap();
+    let (schedule_result, _) = tokio::join!(
+        operator.schedule(operation_id),
+        execute_next_tx(&eth_client, true)
+    );
+    schedule_result.unwrap();
+    let err = operator
+        .execute(operation_id)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("not ready"), "{err}");
+    assert_eq!(eth_client.sent_tx_count(), 1);
+}
+
+#[tokio::test]
+async fn operation_with_reverted_execution_is_marked_as_failed() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let eth_client = mock_eth_client(true);
+    let operator = test_operator(&pool, eth_client.clone());
+
+    let operation_id = operator.propose(&test_proposal()).await.unwrap();
+    let (schedule_result, _) = tokio::join!(
+        operator.schedule(operation_id),
+        execute_next_tx(&eth_client, true)
+    );
+    schedule_result.unwrap();
+    let (execute_result, reverted_tx_hash) = tokio::join!(
+        operator.execute(operation_id),
+        execute_next_tx(&eth_client, false)
+    );
+    let err = format!("{:#}", execute_result.unwrap_err());
+    assert!(err.contains("reverted"), "{err}");
+
+    let mut storage = pool.connection().await.unwrap();
+    let operation = storage
+        .governance_dal()
+        .get_operation(operation_id)
+        .await
+        .unwrap()
+        .expect("no operation");
+    assert_eq!(operation.status, GovernanceOperationStatus::Failed);
+    assert_eq!(operation.execute_tx_hash, None);
+    let error = operation.error.expect("no error");
+    assert!(error.contains(&format!("{reverted_tx_hash:?}")), "{error}");
+}
@@ -7,5 +7,10 @@ pub mod fri_prover_queue_monitor;
 pub mod fri_scheduler_circuit_queuer;
 pub mod fri_witness_generator_jobs_retry_manager;
 pub mod fri_witness_generator_queue_monitor;
+pub mod object_store_archiver;
+pub mod object_store_gc;
 pub mod periodic_job;
+pub mod storage_logs_partition_rotator;
+#[cfg(test)]
+mod testonly;
 pub mod waiting_to_queued_fri_witness_job_mover;
@@ -0,0 +1,294 @@
+//! Lifecycle management for artifacts of executed L1 batches in the object store.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::{
+    object_store_lifecycle_dal::ObjectStoreLifecycleJob, Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_prover_interface::{inputs::PrepareBasicCircuitsJob, outputs::L1BatchProofForL1};
+use zksync_types::{
+    snapshots::{
+        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+    },
+    storage::witness_block_state::WitnessBlockState,
+    L1BatchNumber,
+};
+use zksync_utils::error::ClassifiedError;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Maximum number of L1 batches processed in a single iteration, so that the archiver doesn't stall
+/// for a long time when catching up.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+
+/// Moves artifacts (witness inputs and proofs) of L1 batches executed on L1 more than the configured
+/// retention period ago to the cold storage tier of the object store. Storage snapshots for L1 batches executed
+/// before the retention period are archived in the snapshots object store. Artifacts remain accessible afterwards,
+/// but may need to be restored first.
+///
+/// The newest storage snapshot is never archived since it's used by external nodes to recover. Archiving progress
+/// is persisted in Postgres, so that the archiver doesn't rescan all L1 batches and snapshots after a restart.
+#[derive(Debug)]
+pub struct ObjectStoreArchiver {
+    pool: ConnectionPool<Core>,
+    blob_store: Arc<dyn ObjectStore>,
+    snapshots_store: Arc<dyn ObjectStore>,
+    retention: Duration,
+    polling_interval_ms: u64,
+}
+
+impl ObjectStoreArchiver {
+    /// Creates a new archiver. `blob_store` must be the store containing L1 batch artifacts, and `snapshots_store`
+    /// the store with storage snapshot files. `pool` must point to the main Postgres instance since the archiver
+    /// persists its progress.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        blob_store: Arc<dyn ObjectStore>,
+        snapshots_store: Arc<dyn ObjectStore>,
+        retention: Duration,
+        polling_interval_ms: u64,
+    ) -> Self {
+        Self {
+            pool,
+            blob_store,
+            snapshots_store,
+            retention,
+            polling_interval_ms,
+        }
+    }
+
+    /// Archives a single object; objects that don't exist are skipped (not all artifacts are produced
+    /// for every L1 batch).
+    async fn archive<V: StoredObject>(
+        store: &dyn ObjectStore,
+        key: V::Key<'_>,
+    ) -> anyhow::Result<bool> {
+        match store.archive::<V>(key).await {
+            Ok(()) => Ok(true),
+            Err(ObjectStoreError::KeyNotFound(_)) => Ok(false),
+            Err(err) => Err(anyhow::Error::from(ClassifiedError::new(err))
+                .context(format!("failed archiving `{}`", V::encode_key(key)))),
+        }
+    }
+
+    async fn archive_l1_batch_artifacts(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let store = &*self.blob_store;
+        let mut archived_count = 0;
+        archived_count +=
+            usize::from(Self::archive::<PrepareBasicCircuitsJob>(store, l1_batch_number).await?);
+        archived_count +=
+            usize::from(Self::archive::<WitnessBlockState>(store, l1_batch_number).await?);
+        archived_count +=
+            usize::from(Self::archive::<L1BatchProofForL1>(store, l1_batch_number).await?);
+        tracing::debug!("Archived {archived_count} artifacts for L1 batch #{l1_batch_number}");
+        Ok(())
+    }
+
+    async fn archive_snapshot(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let snapshot = storage
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("snapshot for L1 batch #{l1_batch_number} disappeared"))?;
+
+        let store = &*self.snapshots_store;
+        Self::archive::<SnapshotFactoryDependencies>(store, l1_batch_number).await?;
+        for chunk_id in 0..snapshot.storage_logs_filepaths.len() {
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id: chunk_id as u64,
+            };
+            Self::archive::<SnapshotStorageLogsChunk>(store, key).await?;
+        }
+        tracing::info!(
+            "Archived storage snapshot for L1 batch #{l1_batch_number} with {} storage log chunks",
+            snapshot.storage_logs_filepaths.len()
+        );
+        Ok(())
+    }
+
+    async fn archive_snapshots(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        last_expired_l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let next_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::SnapshotArchiver)
+            .await?
+            .unwrap_or(L1BatchNumber(0));
+        let all_snapshots = storage.snapshots_dal().get_all_complete_snapshots().await?;
+        // Snapshots are ordered by descending L1 batch number; the newest snapshot is skipped. Since the newest
+        // snapshot is never archived, persisted progress never skips over a snapshot that wasn't archived.
+        let mut snapshots_to_archive: Vec<_> = all_snapshots
+            .snapshots_l1_batch_numbers
+            .iter()
+            .skip(1)
+            .copied()
+            .filter(|&number| number >= next_l1_batch && number <= last_expired_l1_batch)
+            .collect();
+        snapshots_to_archive.sort_unstable();
+
+        for l1_batch_number in snapshots_to_archive {
+            self.archive_snapshot(storage, l1_batch_number).await?;
+            storage
+                .object_store_lifecycle_dal()
+                .set_next_l1_batch(
+                    ObjectStoreLifecycleJob::SnapshotArchiver,
+                    l1_batch_number + 1,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ObjectStoreArchiver {
+    const SERVICE_NAME: &'static str = "ObjectStoreArchiver";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("object_store_archiver").await?;
+        // Retention is based on the time L1 batches were executed on L1, rather than on their timestamps,
+        // so that artifacts are never archived while they may still be needed by the L1 pipeline.
+        let Some(last_expired_l1_batch) = storage
+            .blocks_dal()
+            .get_last_l1_batch_executed_before(self.retention)
+            .await?
+        else {
+            return Ok(()); // no L1 batches executed before the retention period
+        };
+        let next_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::Archiver)
+            .await?;
+        let next_l1_batch = match next_l1_batch {
+            Some(number) => number,
+            None => storage
+                .blocks_dal()
+                .get_earliest_l1_batch_number()
+                .await?
+                .context("no L1 batches in Postgres, although some are executed")?,
+        };
+
+        if next_l1_batch <= last_expired_l1_batch {
+            let last_l1_batch = last_expired_l1_batch
+                .0
+                .min(next_l1_batch.0 + MAX_L1_BATCHES_PER_ITERATION - 1);
+            let last_l1_batch = L1BatchNumber(last_l1_batch);
+            for number in next_l1_batch.0..=last_l1_batch.0 {
+                self.archive_l1_batch_artifacts(L1BatchNumber(number))
+                    .await?;
+            }
+            storage
+                .object_store_lifecycle_dal()
+                .set_next_l1_batch(ObjectStoreLifecycleJob::Archiver, last_l1_batch + 1)
+                .await?;
+            tracing::info!("Archived artifacts for L1 batches {next_l1_batch}..={last_l1_batch}");
+        }
+
+        self.archive_snapshots(&mut storage, last_expired_l1_batch)
+            .await
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_object_store::ObjectStoreFactory;
+
+    use super::*;
+    use crate::house_keeper::testonly::{
+        confirm_execution, create_snapshot, prepare_storage, put_artifacts,
+    };
+
+    async fn is_archived(store: &dyn ObjectStore, l1_batch_number: L1BatchNumber) -> bool {
+        match store.get::<WitnessBlockState>(l1_batch_number).await {
+            Ok(_) => false,
+            Err(ObjectStoreError::Thawing(_)) => true,
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn archiving_artifacts_for_executed_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        prepare_storage(&mut storage, 3).await;
+        confirm_execution(&mut storage, 2).await;
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        for number in 1..=3 {
+            put_artifacts(&*blob_store, L1BatchNumber(number)).await;
+        }
+        let snapshots_store = ObjectStoreFactory::mock().create_store().await;
+        for number in 1..=2 {
+            create_snapshot(&mut storage, &*snapshots_store, L1BatchNumber(number)).await;
+        }
+
+        let mut archiver = ObjectStoreArchiver::new(
+            pool.clone(),
+            blob_store.clone(),
+            snapshots_store.clone(),
+            Duration::ZERO,
+            1_000,
+        );
+        archiver.run_routine_task().await.unwrap();
+
+        assert!(is_archived(&*blob_store, L1BatchNumber(1)).await);
+        assert!(is_archived(&*blob_store, L1BatchNumber(2)).await);
+        // L1 batch #3 is not executed yet.
+        assert!(!is_archived(&*blob_store, L1BatchNumber(3)).await);
+        let next_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::Archiver)
+            .await
+            .unwrap();
+        assert_eq!(next_l1_batch, Some(L1BatchNumber(3)));
+
+        // Only the older snapshot must be archived.
+        let err = snapshots_store
+            .get::<SnapshotFactoryDependencies>(L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Thawing(_)), "{err}");
+        snapshots_store
+            .get::<SnapshotFactoryDependencies>(L1BatchNumber(2))
+            .await
+            .unwrap();
+        let next_snapshot_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::SnapshotArchiver)
+            .await
+            .unwrap();
+        assert_eq!(next_snapshot_l1_batch, Some(L1BatchNumber(2)));
+
+        // Progress must survive a restart of the archiver: already archived L1 batches and snapshots
+        // are not revisited. (The artifacts and the snapshot were restored by the accesses above.)
+        let mut archiver = ObjectStoreArchiver::new(
+            pool,
+            blob_store.clone(),
+            snapshots_store.clone(),
+            Duration::ZERO,
+            1_000,
+        );
+        archiver.run_routine_task().await.unwrap();
+        assert!(!is_archived(&*blob_store, L1BatchNumber(1)).await);
+        snapshots_store
+            .get::<SnapshotFactoryDependencies>(L1BatchNumber(1))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,366 @@
+//! Garbage collection for artifacts of executed L1 batches in the object store.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
+use zksync_dal::{
+    object_store_lifecycle_dal::ObjectStoreLifecycleJob, Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_prover_interface::{inputs::PrepareBasicCircuitsJob, outputs::L1BatchProofForL1};
+use zksync_types::{
+    snapshots::{
+        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+    },
+    storage::witness_block_state::WitnessBlockState,
+    L1BatchNumber,
+};
+use zksync_utils::error::ClassifiedError;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Maximum number of L1 batches processed in a single iteration, so that the collector doesn't stall
+/// for a long time when catching up.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store_gc")]
+struct ObjectStoreGcMetrics {
+    /// Number of objects removed from the object store, grouped by the bucket.
+    #[metrics(labels = ["bucket"])]
+    removed_objects: LabeledFamily<&'static str, Counter>,
+    /// Number of objects that were expected to be removed, but were missing in the object store,
+    /// grouped by the bucket.
+    #[metrics(labels = ["bucket"])]
+    missing_objects: LabeledFamily<&'static str, Counter>,
+    /// Number of removed storage snapshots.
+    removed_snapshots: Counter,
+    /// Last L1 batch for which artifacts were removed.
+    last_collected_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ObjectStoreGcMetrics> = vise::Global::new();
+
+/// Removes artifacts (witness inputs and proofs) of L1 batches executed on L1 more than the configured
+/// retention period ago from the object store. Storage snapshots for L1 batches executed before the retention
+/// period are removed from the snapshots object store together with their metadata in Postgres.
+///
+/// The newest storage snapshot is never removed since it's used by external nodes to recover. Artifacts
+/// stored by the prover subsystem (e.g., prover jobs) are not managed by the collector. Collection progress
+/// is persisted in Postgres, so that the collector doesn't rescan all L1 batches after a restart.
+#[derive(Debug)]
+pub struct ObjectStoreGarbageCollector {
+    pool: ConnectionPool<Core>,
+    blob_store: Arc<dyn ObjectStore>,
+    snapshots_store: Arc<dyn ObjectStore>,
+    retention: Duration,
+    polling_interval_ms: u64,
+}
+
+impl ObjectStoreGarbageCollector {
+    /// Creates a new collector. `blob_store` must be the store containing L1 batch artifacts, and `snapshots_store`
+    /// the store with storage snapshot files (they are normally configured separately). `pool` must point
+    /// to the main Postgres instance since the collector modifies snapshot metadata.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        blob_store: Arc<dyn ObjectStore>,
+        snapshots_store: Arc<dyn ObjectStore>,
+        retention: Duration,
+        polling_interval_ms: u64,
+    ) -> Self {
+        Self {
+            pool,
+            blob_store,
+            snapshots_store,
+            retention,
+            polling_interval_ms,
+        }
+    }
+
+    /// Removes a single object. Returns `false` if the object doesn't exist; such objects are counted in metrics,
+    /// and it's up to the caller to decide whether they are expected.
+    async fn remove<V: StoredObject>(
+        store: &dyn ObjectStore,
+        key: V::Key<'_>,
+    ) -> anyhow::Result<bool> {
+        let bucket = V::BUCKET.as_str();
+        match store.remove::<V>(key).await {
+            Ok(()) => {
+                METRICS.removed_objects[&bucket].inc();
+                Ok(true)
+            }
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                METRICS.missing_objects[&bucket].inc();
+                Ok(false)
+            }
+            Err(err) => Err(anyhow::Error::from(ClassifiedError::new(err))
+                .context(format!("failed removing `{}`", V::encode_key(key)))),
+        }
+    }
+
+    async fn remove_l1_batch_artifacts(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let store = &*self.blob_store;
+        let mut missing_count = 0;
+        missing_count +=
+            usize::from(!Self::remove::<PrepareBasicCircuitsJob>(store, l1_batch_number).await?);
+        missing_count +=
+            usize::from(!Self::remove::<WitnessBlockState>(store, l1_batch_number).await?);
+        missing_count +=
+            usize::from(!Self::remove::<L1BatchProofForL1>(store, l1_batch_number).await?);
+        if missing_count > 0 {
+            // Not all artifacts are produced for every L1 batch (e.g., if proofs are not generated).
+            tracing::debug!(
+                "{missing_count} artifact(s) for L1 batch #{l1_batch_number} are missing in the object store"
+            );
+        }
+        Ok(())
+    }
+
+    async fn remove_snapshot(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let snapshot = storage
+            .snapshots_dal()
+            .get_snapshot_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("snapshot for L1 batch #{l1_batch_number} disappeared"))?;
+
+        // Files are removed before metadata, so that if the collector is interrupted, removal is retried
+        // on the next run instead of leaking the remaining files. Only snapshots older than the newest one
+        // are removed, so external nodes recovering from the newest snapshot are not affected.
+        let store = &*self.snapshots_store;
+        let chunk_count = snapshot.storage_logs_filepaths.len();
+        let mut missing_count = usize::from(
+            !Self::remove::<SnapshotFactoryDependencies>(store, l1_batch_number).await?,
+        );
+        for chunk_id in 0..chunk_count {
+            let key = SnapshotStorageLogsStorageKey {
+                l1_batch_number,
+                chunk_id: chunk_id as u64,
+            };
+            missing_count +=
+                usize::from(!Self::remove::<SnapshotStorageLogsChunk>(store, key).await?);
+        }
+        if missing_count > 0 {
+            tracing::warn!(
+                "{missing_count} file(s) of storage snapshot for L1 batch #{l1_batch_number} are missing in the object store; \
+                 they may have been removed by an interrupted collector run"
+            );
+        }
+
+        storage
+            .snapshots_dal()
+            .delete_snapshot(l1_batch_number)
+            .await?;
+        METRICS.removed_snapshots.inc();
+        tracing::info!(
+            "Removed storage snapshot for L1 batch #{l1_batch_number} with {chunk_count} storage log chunks"
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ObjectStoreGarbageCollector {
+    const SERVICE_NAME: &'static str = "ObjectStoreGarbageCollector";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("object_store_gc").await?;
+        // Retention is based on the time L1 batches were executed on L1, rather than on their timestamps,
+        // so that artifacts are never removed before they are no longer needed by the L1 pipeline.
+        let Some(last_expired_l1_batch) = storage
+            .blocks_dal()
+            .get_last_l1_batch_executed_before(self.retention)
+            .await?
+        else {
+            return Ok(()); // no L1 batches executed before the retention period
+        };
+        let next_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::GarbageCollector)
+            .await?;
+        let next_l1_batch = match next_l1_batch {
+            Some(number) => number,
+            None => storage
+                .blocks_dal()
+                .get_earliest_l1_batch_number()
+                .await?
+                .context("no L1 batches in Postgres, although some are executed")?,
+        };
+
+        if next_l1_batch <= last_expired_l1_batch {
+            let last_l1_batch = last_expired_l1_batch
+                .0
+                .min(next_l1_batch.0 + MAX_L1_BATCHES_PER_ITERATION - 1);
+            let last_l1_batch = L1BatchNumber(last_l1_batch);
+            for number in next_l1_batch.0..=last_l1_batch.0 {
+                self.remove_l1_batch_artifacts(L1BatchNumber(number))
+                    .await?;
+            }
+            storage
+                .object_store_lifecycle_dal()
+                .set_next_l1_batch(ObjectStoreLifecycleJob::GarbageCollector, last_l1_batch + 1)
+                .await?;
+            METRICS.last_collected_l1_batch.set(last_l1_batch.0.into());
+            tracing::info!("Removed artifacts for L1 batches {next_l1_batch}..={last_l1_batch}");
+        }
+
+        let all_snapshots = storage.snapshots_dal().get_all_complete_snapshots().await?;
+        // Snapshots are ordered by descending L1 batch number; the newest snapshot is skipped.
+        for &l1_batch_number in all_snapshots.snapshots_l1_batch_numbers.iter().skip(1) {
+            if l1_batch_number <= last_expired_l1_batch {
+                self.remove_snapshot(&mut storage, l1_batch_number).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_object_store::ObjectStoreFactory;
+
+    use super::*;
+    use crate::house_keeper::testonly::{
+        confirm_execution, create_snapshot, has_artifacts, prepare_storage, put_artifacts,
+    };
+
+    #[tokio::test]
+    async fn collecting_artifacts_for_executed_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        prepare_storage(&mut storage, 3).await;
+        confirm_execution(&mut storage, 2).await;
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        for number in 1..=3 {
+            put_artifacts(&*blob_store, L1BatchNumber(number)).await;
+        }
+
+        let snapshots_store = ObjectStoreFactory::mock().create_store().await;
+        let mut gc = ObjectStoreGarbageCollector::new(
+            pool.clone(),
+            blob_store.clone(),
+            snapshots_store.clone(),
+            Duration::ZERO,
+            1_000,
+        );
+        gc.run_routine_task().await.unwrap();
+
+        assert!(!has_artifacts(&*blob_store, L1BatchNumber(1)).await);
+        assert!(!has_artifacts(&*blob_store, L1BatchNumber(2)).await);
+        // L1 batch #3 is not executed yet.
+        assert!(has_artifacts(&*blob_store, L1BatchNumber(3)).await);
+        let next_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::GarbageCollector)
+            .await
+            .unwrap();
+        assert_eq!(next_l1_batch, Some(L1BatchNumber(3)));
+
+        // Progress must survive a restart of the collector: already processed L1 batches are not revisited.
+        put_artifacts(&*blob_store, L1BatchNumber(1)).await;
+        let mut gc = ObjectStoreGarbageCollector::new(
+            pool,
+            blob_store.clone(),
+            snapshots_store,
+            Duration::ZERO,
+            1_000,
+        );
+        gc.run_routine_task().await.unwrap();
+        assert!(has_artifacts(&*blob_store, L1BatchNumber(1)).await);
+    }
+
+    #[tokio::test]
+    async fn artifacts_are_retained_for_recently_executed_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        prepare_storage(&mut storage, 2).await;
+        confirm_execution(&mut storage, 2).await;
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        put_artifacts(&*blob_store, L1BatchNumber(1)).await;
+
+        let mut gc = ObjectStoreGarbageCollector::new(
+            pool,
+            blob_store.clone(),
+            ObjectStoreFactory::mock().create_store().await,
+            Duration::from_secs(3_600),
+            1_000,
+        );
+        gc.run_routine_task().await.unwrap();
+        assert!(has_artifacts(&*blob_store, L1BatchNumber(1)).await);
+        let next_l1_batch = storage
+            .object_store_lifecycle_dal()
+            .get_next_l1_batch(ObjectStoreLifecycleJob::GarbageCollector)
+            .await
+            .unwrap();
+        assert_eq!(next_l1_batch, None);
+    }
+
+    #[tokio::test]
+    async fn collecting_old_snapshots() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        prepare_storage(&mut storage, 2).await;
+        confirm_execution(&mut storage, 2).await;
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        let snapshots_store = ObjectStoreFactory::mock().create_store().await;
+        for number in 1..=2 {
+            create_snapshot(&mut storage, &*snapshots_store, L1BatchNumber(number)).await;
+        }
+
+        let mut gc = ObjectStoreGarbageCollector::new(
+            pool,
+            blob_store,
+            snapshots_store.clone(),
+            Duration::ZERO,
+            1_000,
+        );
+        gc.run_routine_task().await.unwrap();
+
+        let old_snapshot = storage
+            .snapshots_dal()
+            .get_snapshot_metadata(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(old_snapshot.is_none());
+        let err = snapshots_store
+            .get::<SnapshotFactoryDependencies>(L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: L1BatchNumber(1),
+            chunk_id: 0,
+        };
+        let err = snapshots_store
+            .get::<SnapshotStorageLogsChunk>(key)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+
+        // The newest snapshot must be retained.
+        let newest_snapshot = storage
+            .snapshots_dal()
+            .get_newest_snapshot_metadata()
+            .await
+            .unwrap()
+            .expect("newest snapshot was removed");
+        assert_eq!(newest_snapshot.l1_batch_number, L1BatchNumber(2));
+        snapshots_store
+            .get::<SnapshotFactoryDependencies>(L1BatchNumber(2))
+            .await
+            .unwrap();
+    }
+}
@@ -3,12 +3,16 @@ use std::time::Duration;
 use anyhow::Context;
 use async_trait::async_trait;
 use tokio::sync::watch;
+use zksync_utils::retry::RetryPolicy;
 
 #[async_trait]
 pub trait PeriodicJob: Sync + Send {
     const SERVICE_NAME: &'static str;
 
     /// Runs the routine task periodically in [`Self::polling_interval_ms()`] frequency.
+    ///
+    /// Errors classified as [retryable](zksync_utils::error::ErrorKind::Retryable) are reported,
+    /// and the task is retried on the next iteration; other errors stop the job.
     async fn run_routine_task(&mut self) -> anyhow::Result<()>;
 
     async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()>
@@ -20,10 +24,14 @@ pub trait PeriodicJob: Sync + Send {
             "Starting periodic job: {} with frequency: {timeout:?}",
             Self::SERVICE_NAME
         );
+        let mut retrier = RetryPolicy::indefinite(timeout).retrier(Self::SERVICE_NAME);
         while !*stop_receiver.borrow_and_update() {
-            self.run_routine_task()
-                .await
-                .context("run_routine_task()")?;
+            match self.run_routine_task().await {
+                Ok(()) => retrier.reset(),
+                Err(err) => {
+                    retrier.handle_error(err).context("run_routine_task()")?;
+                }
+            }
             // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
             tokio::time::timeout(timeout, stop_receiver.changed())
                 .await
@@ -0,0 +1,127 @@
+//! Creation of partitions of the `storage_logs` table for upcoming miniblocks.
+
+use async_trait::async_trait;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::MiniblockNumber;
+use zksync_utils::error::{ClassifiedError, ErrorKind};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Number of partitions maintained ahead of the last sealed miniblock.
+const PARTITIONS_AHEAD: u32 = 2;
+
+/// Creates partitions of the `storage_logs` table, each covering a fixed number of miniblocks, ahead of the last
+/// sealed miniblock. Storage logs for miniblocks not covered by a partition (e.g., because the rotator wasn't running)
+/// are stored in the default partition; since these rows cannot be moved without rewriting them, new partitions
+/// always start after the last miniblock in the default partition.
+///
+/// Partitions are never dropped by the rotator: storage logs for all miniblocks are required to compute the current
+/// storage state. Partitioning allows reverts (and, in the future, pruning) to truncate whole partitions instead
+/// of deleting rows one by one.
+#[derive(Debug)]
+pub struct StorageLogsPartitionRotator {
+    pool: ConnectionPool<Core>,
+    partition_size: u32,
+    polling_interval_ms: u64,
+}
+
+impl StorageLogsPartitionRotator {
+    pub fn new(pool: ConnectionPool<Core>, partition_size: u32, polling_interval_ms: u64) -> Self {
+        assert!(partition_size > 0, "Partition size must be positive");
+        Self {
+            pool,
+            partition_size,
+            polling_interval_ms,
+        }
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for StorageLogsPartitionRotator {
+    const SERVICE_NAME: &'static str = "StorageLogsPartitionRotator";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .connection_tagged("storage_logs_partition_rotator")
+            .await?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await?
+            .unwrap_or(MiniblockNumber(0));
+        let partitions = storage.storage_logs_dal().get_partitions().await?;
+        let last_partition_end = partitions
+            .iter()
+            .filter_map(|partition| Some(partition.miniblocks.as_ref()?.end))
+            .max()
+            .unwrap_or(MiniblockNumber(0));
+        let last_default_miniblock = storage
+            .storage_logs_dal()
+            .get_last_miniblock_in_default_partition()
+            .await?;
+        let mut next_start = match last_default_miniblock {
+            Some(number) => last_partition_end.max(number + 1),
+            None => last_partition_end,
+        };
+
+        let target_end = sealed_miniblock + PARTITIONS_AHEAD * self.partition_size;
+        while next_start <= target_end {
+            let miniblocks = next_start..next_start + self.partition_size;
+            // Attaching a partition fails if the default partition has rows for the covered miniblocks
+            // (e.g., if a miniblock was sealed concurrently). This is resolved on the next iteration.
+            let partition = storage
+                .storage_logs_dal()
+                .create_partition(miniblocks.clone())
+                .await
+                .map_err(|err| ClassifiedError::with_kind(ErrorKind::Retryable, err))?;
+            tracing::info!(
+                "Created storage logs partition `{}` for miniblocks {miniblocks:?}",
+                partition.name
+            );
+            next_start = miniblocks.end;
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genesis::{insert_genesis_batch, GenesisParams};
+
+    #[tokio::test]
+    async fn creating_partitions() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let mut rotator = StorageLogsPartitionRotator::new(pool.clone(), 10, 100);
+        rotator.run_routine_task().await.unwrap();
+        let partitions = storage.storage_logs_dal().get_partitions().await.unwrap();
+        let partition_ranges: Vec<_> = partitions
+            .iter()
+            .filter_map(|partition| partition.miniblocks.clone())
+            .filter(|range| !range.is_empty())
+            .collect();
+        // Genesis storage logs are in the default partition.
+        assert_eq!(
+            partition_ranges,
+            [
+                MiniblockNumber(1)..MiniblockNumber(11),
+                MiniblockNumber(11)..MiniblockNumber(21)
+            ]
+        );
+
+        // Partitions are only created once.
+        rotator.run_routine_task().await.unwrap();
+        let new_partitions = storage.storage_logs_dal().get_partitions().await.unwrap();
+        assert_eq!(new_partitions, partitions);
+    }
+}
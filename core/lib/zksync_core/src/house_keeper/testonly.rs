@@ -0,0 +1,129 @@
+//! Test utils shared by house keeper jobs managing object store artifacts.
+
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    snapshots::{
+        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotVersion,
+    },
+    storage::witness_block_state::WitnessBlockState,
+    Address, L1BatchNumber, ProtocolVersion, H256, U256,
+};
+
+use crate::utils::testonly::create_l1_batch;
+
+pub(super) async fn prepare_storage(storage: &mut Connection<'_, Core>, l1_batch_count: u32) {
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    for number in 1..=l1_batch_count {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+    }
+}
+
+pub(super) async fn confirm_execution(storage: &mut Connection<'_, Core>, l1_batch_count: u32) {
+    let eth_tx = storage
+        .eth_sender_dal()
+        .save_eth_tx(
+            0,
+            vec![],
+            AggregatedActionType::Execute,
+            Address::repeat_byte(1),
+            100,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .set_eth_tx_id(
+            L1BatchNumber(1)..=L1BatchNumber(l1_batch_count),
+            eth_tx.id,
+            AggregatedActionType::Execute,
+        )
+        .await
+        .unwrap();
+    let tx_hash = H256::repeat_byte(0x42);
+    storage
+        .eth_sender_dal()
+        .insert_tx_history(eth_tx.id, 100, 10, None, tx_hash, &[])
+        .await
+        .unwrap();
+    storage
+        .eth_sender_dal()
+        .confirm_tx(tx_hash, U256::zero())
+        .await
+        .unwrap();
+}
+
+pub(super) async fn put_artifacts(store: &dyn ObjectStore, l1_batch_number: L1BatchNumber) {
+    store
+        .put(l1_batch_number, &WitnessBlockState::default())
+        .await
+        .unwrap();
+    store
+        .put(l1_batch_number, &PrepareBasicCircuitsJob::new(0))
+        .await
+        .unwrap();
+}
+
+pub(super) async fn has_artifacts(store: &dyn ObjectStore, l1_batch_number: L1BatchNumber) -> bool {
+    match store.get::<WitnessBlockState>(l1_batch_number).await {
+        Ok(_) => true,
+        Err(ObjectStoreError::KeyNotFound(_)) => false,
+        Err(err) => panic!("unexpected error: {err}"),
+    }
+}
+
+pub(super) async fn create_snapshot(
+    storage: &mut Connection<'_, Core>,
+    store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+) {
+    let factory_deps_path = store
+        .put(
+            l1_batch_number,
+            &SnapshotFactoryDependencies {
+                factory_deps: vec![],
+            },
+        )
+        .await
+        .unwrap();
+    storage
+        .snapshots_dal()
+        .add_snapshot(
+            SnapshotVersion::Version0,
+            l1_batch_number,
+            1,
+            &factory_deps_path,
+        )
+        .await
+        .unwrap();
+    let key = SnapshotStorageLogsStorageKey {
+        l1_batch_number,
+        chunk_id: 0,
+    };
+    let chunk_path = store
+        .put(
+            key,
+            &SnapshotStorageLogsChunk {
+                storage_logs: vec![],
+            },
+        )
+        .await
+        .unwrap();
+    storage
+        .snapshots_dal()
+        .add_storage_logs_filepath_for_snapshot(l1_batch_number, 0, &chunk_path, H256::zero())
+        .await
+        .unwrap();
+}
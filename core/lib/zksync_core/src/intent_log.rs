@@ -0,0 +1,114 @@
+//! Write-ahead intent log for operations spanning Postgres and RocksDB.
+//!
+//! Postgres and RocksDB instances (the Merkle tree, the state keeper cache) cannot be updated atomically,
+//! so a crash in the middle of a multi-store operation may leave the stores inconsistent. To prevent this,
+//! an operation records its intent in Postgres before touching any of the stores and removes it once
+//! all the stores are updated. On startup, the component owning the operation rolls forward all intents
+//! left in the log. Thus, operations recorded in the log must be idempotent.
+
+use std::fmt;
+
+use anyhow::Context as _;
+use serde::{de::DeserializeOwned, Serialize};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+/// Operation that can be recorded in the [`IntentLog`].
+pub trait IntentOperation: fmt::Debug + Serialize + DeserializeOwned {
+    /// Unique name of the operation.
+    const NAME: &'static str;
+}
+
+/// ID of an intent recorded in the [`IntentLog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntentId(i64);
+
+/// Write-ahead intent log backed by Postgres.
+#[derive(Debug, Clone)]
+pub struct IntentLog {
+    pool: ConnectionPool<Core>,
+}
+
+impl IntentLog {
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self { pool }
+    }
+
+    /// Records an intent to perform the operation. Must be called before any of the affected stores is updated.
+    pub async fn begin<T: IntentOperation>(&self, operation: &T) -> anyhow::Result<IntentId> {
+        let params = serde_json::to_value(operation)
+            .with_context(|| format!("failed serializing {operation:?}"))?;
+        let mut storage = self.pool.connection_tagged("intent_log").await?;
+        let id = storage
+            .intent_log_dal()
+            .insert_intent(T::NAME, &params)
+            .await
+            .with_context(|| format!("failed recording intent for {operation:?}"))?;
+        tracing::info!("Recorded intent #{id} for {operation:?}");
+        Ok(IntentId(id))
+    }
+
+    /// Marks the intent as completed. Must be called after all the affected stores are updated.
+    pub async fn complete(&self, id: IntentId) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("intent_log").await?;
+        storage
+            .intent_log_dal()
+            .complete_intent(id.0)
+            .await
+            .with_context(|| format!("failed completing intent #{}", id.0))?;
+        tracing::info!("Completed intent #{}", id.0);
+        Ok(())
+    }
+
+    /// Returns operations of the specified type that were started, but not completed,
+    /// in the order they were started.
+    pub async fn pending<T: IntentOperation>(&self) -> anyhow::Result<Vec<(IntentId, T)>> {
+        let mut storage = self.pool.connection_tagged("intent_log").await?;
+        let intents = storage
+            .intent_log_dal()
+            .get_pending_intents(T::NAME)
+            .await
+            .with_context(|| format!("failed getting pending `{}` intents", T::NAME))?;
+        drop(storage);
+
+        intents
+            .into_iter()
+            .map(|intent| {
+                let operation = serde_json::from_value(intent.params).with_context(|| {
+                    format!("failed deserializing `{}` intent #{}", T::NAME, intent.id)
+                })?;
+                Ok((IntentId(intent.id), operation))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TestOperation {
+        value: u32,
+    }
+
+    impl IntentOperation for TestOperation {
+        const NAME: &'static str = "test";
+    }
+
+    #[tokio::test]
+    async fn rolling_forward_pending_intents() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let intent_log = IntentLog::new(pool);
+        let first_id = intent_log.begin(&TestOperation { value: 1 }).await.unwrap();
+        let second_id = intent_log.begin(&TestOperation { value: 2 }).await.unwrap();
+        intent_log.complete(first_id).await.unwrap();
+
+        let pending = intent_log.pending::<TestOperation>().await.unwrap();
+        assert_eq!(pending, [(second_id, TestOperation { value: 2 })]);
+        intent_log.complete(second_id).await.unwrap();
+        let pending = intent_log.pending::<TestOperation>().await.unwrap();
+        assert!(pending.is_empty());
+    }
+}
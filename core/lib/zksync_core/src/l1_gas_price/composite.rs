@@ -0,0 +1,353 @@
+//! L1 client aggregating fee data from multiple L1 providers.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::{self, BoxFuture};
+use zksync_config::GasAdjusterConfig;
+use zksync_eth_client::{
+    Block, ContractCall, Error, EthInterface, ExecutedTxStatus, FailureInfo, RawTransactionBytes,
+};
+use zksync_types::web3::{
+    self, ethabi,
+    types::{
+        Address, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt, H256, U256,
+        U64,
+    },
+};
+
+/// [`EthInterface`] implementation querying fee data from several L1 providers, which makes
+/// the fee model resilient to a single faulty provider.
+///
+/// Fee-related queries (block numbers, base fees, gas prices and block headers) are sent to all providers
+/// concurrently. Values deviating from the median across providers by more than the configured relative
+/// threshold are discarded as outliers, and the median of the remaining values is returned, provided that
+/// at least the configured quorum of providers agrees on it. All other queries are delegated to the primary
+/// (first) provider.
+#[derive(Debug)]
+pub struct CompositeL1GasPriceProvider {
+    clients: Vec<Arc<dyn EthInterface>>,
+    quorum: usize,
+    max_deviation: f64,
+}
+
+impl CompositeL1GasPriceProvider {
+    /// Creates a provider based on the provided `clients`; the first client is used as the primary one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `clients` are empty, or if the quorum / max deviation specified in the config are invalid.
+    pub fn new(
+        clients: Vec<Arc<dyn EthInterface>>,
+        config: &GasAdjusterConfig,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!clients.is_empty(), "no L1 clients provided");
+        let quorum = config.l1_gas_price_quorum;
+        anyhow::ensure!(
+            quorum > 0 && quorum <= clients.len(),
+            "L1 gas price quorum must be in [1, {}], got {quorum}",
+            clients.len()
+        );
+        let max_deviation = config.l1_gas_price_max_deviation;
+        anyhow::ensure!(
+            max_deviation >= 0.0,
+            "L1 gas price max deviation must be non-negative, got {max_deviation}"
+        );
+
+        Ok(Self {
+            clients,
+            quorum,
+            max_deviation,
+        })
+    }
+
+    fn primary(&self) -> &dyn EthInterface {
+        self.clients[0].as_ref()
+    }
+
+    /// Sends a query to all clients concurrently and returns successful responses. Errors if less than
+    /// `quorum` clients have responded successfully.
+    async fn query_all<'a, T>(
+        &'a self,
+        query: impl Fn(&'a dyn EthInterface) -> BoxFuture<'a, Result<T, Error>> + Send + Sync,
+    ) -> Result<Vec<T>, Error> {
+        let queries = self.clients.iter().map(|client| query(client.as_ref()));
+        let responses = future::join_all(queries).await;
+
+        let mut values = Vec::with_capacity(responses.len());
+        let mut last_error = None;
+        for (i, response) in responses.into_iter().enumerate() {
+            match response {
+                Ok(value) => values.push(value),
+                Err(err) => {
+                    tracing::warn!("Request to L1 provider #{i} failed: {err}");
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        if values.len() < self.quorum {
+            return Err(last_error.unwrap_or_else(|| self.no_quorum_error(values.len())));
+        }
+        Ok(values)
+    }
+
+    fn no_quorum_error(&self, agreeing_count: usize) -> Error {
+        let message = format!(
+            "only {agreeing_count} L1 providers agree on the value, while the quorum is {}",
+            self.quorum
+        );
+        Error::EthereumGateway(web3::Error::InvalidResponse(message))
+    }
+
+    /// Discards outliers among `values` and returns the median of the remaining values.
+    fn aggregate<T: Copy + Ord + Into<U256>>(&self, mut values: Vec<T>) -> Result<T, Error> {
+        if values.is_empty() {
+            return Err(self.no_quorum_error(0));
+        }
+        values.sort_unstable();
+        let median: U256 = values[values.len() / 2].into();
+        // The deviation is applied with 0.1% precision, which is sufficient for fees.
+        let max_deviation = median * U256::from((self.max_deviation * 1_000.0) as u64) / 1_000;
+        values.retain(|&value| {
+            let value: U256 = value.into();
+            let deviation = if value > median {
+                value - median
+            } else {
+                median - value
+            };
+            deviation <= max_deviation
+        });
+
+        if values.len() < self.quorum {
+            return Err(self.no_quorum_error(values.len()));
+        }
+        Ok(values[values.len() / 2])
+    }
+
+    /// Aggregates base fee histories for the same requested block range. All histories end with the requested
+    /// block, but may be shorter than requested if a provider has no data for the oldest blocks. Thus, values
+    /// are aligned by the block number counting from the newest block; the oldest blocks are truncated
+    /// once less than `quorum` providers have data for them.
+    fn aggregate_histories(&self, histories: &[Vec<u64>]) -> Result<Vec<u64>, Error> {
+        let max_len = histories.iter().map(Vec::len).max().unwrap_or(0);
+        let mut aggregated = Vec::with_capacity(max_len);
+        for blocks_back in 0..max_len {
+            let values: Vec<_> = histories
+                .iter()
+                .filter_map(|history| history.iter().rev().nth(blocks_back).copied())
+                .collect();
+            if values.len() < self.quorum {
+                break;
+            }
+            aggregated.push(self.aggregate(values)?);
+        }
+        aggregated.reverse();
+        Ok(aggregated)
+    }
+}
+
+#[async_trait]
+impl EthInterface for CompositeL1GasPriceProvider {
+    async fn nonce_at_for_account(
+        &self,
+        account: Address,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.primary()
+            .nonce_at_for_account(account, block, component)
+            .await
+    }
+
+    async fn base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        let histories = self
+            .query_all(|client| client.base_fee_history(from_block, block_count, component))
+            .await?;
+        self.aggregate_histories(&histories)
+    }
+
+    async fn get_pending_block_base_fee_per_gas(
+        &self,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        let fees = self
+            .query_all(|client| client.get_pending_block_base_fee_per_gas(component))
+            .await?;
+        self.aggregate(fees)
+    }
+
+    async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
+        let prices = self
+            .query_all(|client| client.get_gas_price(component))
+            .await?;
+        self.aggregate(prices)
+    }
+
+    async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
+        let numbers = self
+            .query_all(|client| client.block_number(component))
+            .await?;
+        let numbers = numbers.iter().map(U64::as_u64).collect();
+        Ok(self.aggregate(numbers)?.into())
+    }
+
+    async fn send_raw_tx(&self, tx: RawTransactionBytes) -> Result<H256, Error> {
+        self.primary().send_raw_tx(tx).await
+    }
+
+    async fn get_tx_status(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<ExecutedTxStatus>, Error> {
+        self.primary().get_tx_status(hash, component).await
+    }
+
+    async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error> {
+        self.primary().failure_reason(tx_hash).await
+    }
+
+    async fn get_tx(
+        &self,
+        hash: H256,
+        component: &'static str,
+    ) -> Result<Option<Transaction>, Error> {
+        self.primary().get_tx(hash, component).await
+    }
+
+    async fn tx_receipt(
+        &self,
+        tx_hash: H256,
+        component: &'static str,
+    ) -> Result<Option<TransactionReceipt>, Error> {
+        self.primary().tx_receipt(tx_hash, component).await
+    }
+
+    async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error> {
+        self.primary().eth_balance(address, component).await
+    }
+
+    async fn call_contract_function(
+        &self,
+        call: ContractCall,
+    ) -> Result<Vec<ethabi::Token>, Error> {
+        self.primary().call_contract_function(call).await
+    }
+
+    async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
+        self.primary().logs(filter, component).await
+    }
+
+    /// Returns the block header from the first provider that has returned it, with the fee-related fields
+    /// (`base_fee_per_gas` and `excess_blob_gas`) aggregated across providers.
+    async fn block(
+        &self,
+        block_id: BlockId,
+        component: &'static str,
+    ) -> Result<Option<Block<H256>>, Error> {
+        let blocks = self
+            .query_all(|client| client.block(block_id, component))
+            .await?;
+        let blocks: Vec<_> = blocks.into_iter().flatten().collect();
+        let Some(mut block) = blocks.first().cloned() else {
+            return Ok(None);
+        };
+
+        let base_fees: Vec<_> = blocks
+            .iter()
+            .filter_map(|block| block.base_fee_per_gas)
+            .collect();
+        block.base_fee_per_gas = if base_fees.is_empty() {
+            None
+        } else {
+            Some(self.aggregate(base_fees)?)
+        };
+        let excess_blob_gas: Vec<_> = blocks
+            .iter()
+            .filter_map(|block| Some(block.excess_blob_gas?.as_u64()))
+            .collect();
+        block.excess_blob_gas = if excess_blob_gas.is_empty() {
+            None
+        } else {
+            Some(self.aggregate(excess_blob_gas)?.into())
+        };
+        Ok(Some(block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::ETHConfig;
+    use zksync_eth_client::clients::MockEthereum;
+
+    use super::*;
+
+    fn create_provider(
+        fee_histories: Vec<Vec<u64>>,
+        quorum: usize,
+    ) -> anyhow::Result<CompositeL1GasPriceProvider> {
+        let clients = fee_histories
+            .into_iter()
+            .map(|history| {
+                Arc::new(MockEthereum::default().with_fee_history(history)) as Arc<dyn EthInterface>
+            })
+            .collect();
+        let config = GasAdjusterConfig {
+            l1_gas_price_quorum: quorum,
+            ..ETHConfig::for_tests().gas_adjuster.unwrap()
+        };
+        CompositeL1GasPriceProvider::new(clients, &config)
+    }
+
+    #[tokio::test]
+    async fn outliers_are_discarded() {
+        let provider =
+            create_provider(vec![vec![100, 200], vec![110, 190], vec![1_000, 210]], 2).unwrap();
+        let history = provider.base_fee_history(1, 2, "test").await.unwrap();
+        assert_eq!(history, [110, 200]);
+
+        let block = provider
+            .block(BlockId::Number(BlockNumber::Number(0.into())), "test")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.base_fee_per_gas, Some(110.into()));
+    }
+
+    #[tokio::test]
+    async fn quorum_is_enforced() {
+        let provider = create_provider(vec![vec![100], vec![1_000], vec![10_000]], 2).unwrap();
+        provider.base_fee_history(0, 1, "test").await.unwrap_err();
+
+        let provider = create_provider(vec![vec![100], vec![1_000], vec![10_000]], 1).unwrap();
+        let history = provider.base_fee_history(0, 1, "test").await.unwrap();
+        assert_eq!(history, [1_000]);
+    }
+
+    #[test]
+    fn histories_are_aligned_by_block_number() {
+        let provider = create_provider(vec![vec![]; 3], 2).unwrap();
+        // The first provider has no data for the oldest block, and the second one for the 2 oldest blocks.
+        let histories = [vec![200, 300], vec![310], vec![100, 210, 290]];
+        let history = provider.aggregate_histories(&histories).unwrap();
+        assert_eq!(history, [210, 300]);
+
+        let provider = create_provider(vec![vec![]; 3], 1).unwrap();
+        let history = provider.aggregate_histories(&histories).unwrap();
+        assert_eq!(history, [100, 210, 300]);
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        let err = create_provider(vec![vec![100]], 2).unwrap_err().to_string();
+        assert!(err.contains("quorum"), "{err}");
+        let err = create_provider(vec![], 1).unwrap_err().to_string();
+        assert!(err.contains("no L1 clients"), "{err}");
+    }
+}
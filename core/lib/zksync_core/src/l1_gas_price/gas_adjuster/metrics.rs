@@ -7,6 +7,8 @@ use vise::{Gauge, Metrics};
 pub(super) struct GasAdjusterMetrics {
     pub current_base_fee_per_gas: Gauge<u64>,
     pub current_blob_base_fee: Gauge<u64>,
+    // Metrics below report fee estimates produced by the configured estimation strategy;
+    // they are called `median_*` for backward compatibility.
     pub median_base_fee_per_gas: Gauge<u64>,
     pub median_blob_base_fee_per_gas: Gauge<u64>,
     pub median_blob_base_fee: Gauge<u64>,
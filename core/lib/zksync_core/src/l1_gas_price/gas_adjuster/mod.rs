@@ -11,16 +11,21 @@ use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
 use zksync_eth_client::{Error, EthInterface};
 use zksync_types::{U256, U64};
 
-use self::metrics::METRICS;
+use self::{
+    metrics::METRICS,
+    strategy::{strategy_from_config, FeeSample, GasAdjusterStrategy},
+};
 use super::{L1TxParamsProvider, PubdataPricing};
 use crate::state_keeper::metrics::KEEPER_METRICS;
 
 mod metrics;
+mod strategy;
 #[cfg(test)]
 mod tests;
 
-/// This component keeps track of the median `base_fee` from the last `max_base_fee_samples` blocks
-/// and of the median `blob_base_fee` from the last `max_blob_base_fee_sample` blocks.
+/// This component keeps track of `base_fee` from the last `max_base_fee_samples` blocks
+/// and of `blob_base_fee` from the last `max_blob_base_fee_sample` blocks, and estimates the fees
+/// based on them using the configured [`GasAdjusterStrategy`] (by default, the median).
 /// It is used to adjust the base_fee of transactions sent to L1.
 #[derive(Debug)]
 pub struct GasAdjuster {
@@ -41,7 +46,7 @@ impl GasAdjuster {
         config: GasAdjusterConfig,
         pubdata_sending_mode: PubdataSendingMode,
         pubdata_pricing: Arc<dyn PubdataPricing>,
-    ) -> Result<Self, Error> {
+    ) -> anyhow::Result<Self> {
         // Subtracting 1 from the "latest" block number to prevent errors in case
         // the info about the latest block is not yet present on the node.
         // This sometimes happens on Infura.
@@ -64,11 +69,13 @@ impl GasAdjuster {
                 config.max_base_fee_samples,
                 current_block,
                 &base_fee_history,
+                strategy_from_config(&config)?,
             ),
             blob_base_fee_statistics: GasStatistics::new(
                 config.num_samples_for_blob_base_fee_estimate,
                 current_block,
                 &last_block_blob_base_fee,
+                strategy_from_config(&config)?,
             ),
             config,
             pubdata_sending_mode,
@@ -175,18 +182,18 @@ impl GasAdjuster {
             PubdataSendingMode::Blobs => {
                 const BLOB_GAS_PER_BYTE: u64 = 1; // `BYTES_PER_BLOB` = `GAS_PER_BLOB` = 2 ^ 17.
 
-                let blob_base_fee_median = self.blob_base_fee_statistics.median();
+                let blob_base_fee_estimate = self.blob_base_fee_statistics.estimate();
 
                 // Check if blob base fee overflows `u64` before converting. Can happen only in very extreme cases.
-                if blob_base_fee_median > U256::from(u64::MAX) {
+                if blob_base_fee_estimate > U256::from(u64::MAX) {
                     let max_allowed = self.config.max_blob_base_fee();
-                    tracing::error!("Blob base fee is too high: {blob_base_fee_median}, using max allowed: {max_allowed}");
+                    tracing::error!("Blob base fee is too high: {blob_base_fee_estimate}, using max allowed: {max_allowed}");
                     return max_allowed;
                 }
                 METRICS
                     .median_blob_base_fee
-                    .set(blob_base_fee_median.as_u64());
-                let calculated_price = blob_base_fee_median.as_u64() as f64
+                    .set(blob_base_fee_estimate.as_u64());
+                let calculated_price = blob_base_fee_estimate.as_u64() as f64
                     * BLOB_GAS_PER_BYTE as f64
                     * self.config.internal_pubdata_pricing_multiplier;
 
@@ -273,9 +280,9 @@ impl L1TxParamsProvider for GasAdjuster {
         // The alternative is a linear one:
         // `let scale_factor = a + b * time_in_mempool as f64;`
         let scale_factor = a * b.powf(time_in_mempool as f64);
-        let median = self.base_fee_statistics.median();
-        METRICS.median_base_fee_per_gas.set(median);
-        let new_fee = median as f64 * scale_factor;
+        let estimate = self.base_fee_statistics.estimate();
+        METRICS.median_base_fee_per_gas.set(estimate);
+        let new_fee = estimate as f64 * scale_factor;
         new_fee as u64
     }
 
@@ -288,9 +295,9 @@ impl L1TxParamsProvider for GasAdjuster {
         // The alternative is a linear one:
         // `let scale_factor = a + b * time_in_mempool as f64;`
         let scale_factor = a * b.powf(0.0);
-        let median = self.blob_base_fee_statistics.median();
-        METRICS.median_blob_base_fee_per_gas.set(median.as_u64());
-        let new_fee = median.as_u64() as f64 * scale_factor;
+        let estimate = self.blob_base_fee_statistics.estimate();
+        METRICS.median_blob_base_fee_per_gas.set(estimate.as_u64());
+        let new_fee = estimate.as_u64() as f64 * scale_factor;
         new_fee as u64
     }
 
@@ -314,21 +321,28 @@ impl L1TxParamsProvider for GasAdjuster {
 }
 
 /// Helper structure responsible for collecting the data about recent transactions,
-/// calculating the median base fee.
-#[derive(Debug, Clone, Default)]
+/// estimating the base fee using the provided strategy.
+#[derive(Debug)]
 pub(super) struct GasStatisticsInner<T> {
     samples: VecDeque<T>,
-    median_cached: T,
+    estimate_cached: T,
+    strategy: Box<dyn GasAdjusterStrategy<T>>,
     max_samples: usize,
     last_processed_block: usize,
 }
 
-impl<T: Ord + Copy + Default> GasStatisticsInner<T> {
-    fn new(max_samples: usize, block: usize, fee_history: &[T]) -> Self {
+impl<T: FeeSample> GasStatisticsInner<T> {
+    fn new(
+        max_samples: usize,
+        block: usize,
+        fee_history: &[T],
+        strategy: Box<dyn GasAdjusterStrategy<T>>,
+    ) -> Self {
         let mut statistics = Self {
             max_samples,
             samples: VecDeque::with_capacity(max_samples),
-            median_cached: T::default(),
+            estimate_cached: T::default(),
+            strategy,
             last_processed_block: 0,
         };
 
@@ -340,12 +354,12 @@ impl<T: Ord + Copy + Default> GasStatisticsInner<T> {
         }
     }
 
-    fn median(&self) -> T {
-        self.median_cached
+    fn estimate(&self) -> T {
+        self.estimate_cached
     }
 
     fn last_added_value(&self) -> T {
-        self.samples.back().copied().unwrap_or(self.median_cached)
+        self.samples.back().copied().unwrap_or(self.estimate_cached)
     }
 
     fn add_samples(&mut self, fees: &[T]) {
@@ -355,29 +369,32 @@ impl<T: Ord + Copy + Default> GasStatisticsInner<T> {
         let extra = self.samples.len().saturating_sub(self.max_samples);
         self.samples.drain(..extra);
 
-        let mut samples: Vec<_> = self.samples.iter().cloned().collect();
-
         if !self.samples.is_empty() {
-            let (_, &mut median, _) = samples.select_nth_unstable(self.samples.len() / 2);
-            self.median_cached = median;
+            self.estimate_cached = self.strategy.estimate(&self.samples);
         }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(super) struct GasStatistics<T>(RwLock<GasStatisticsInner<T>>);
 
-impl<T: Ord + Copy + Default> GasStatistics<T> {
-    pub fn new(max_samples: usize, block: usize, fee_history: &[T]) -> Self {
+impl<T: FeeSample> GasStatistics<T> {
+    pub fn new(
+        max_samples: usize,
+        block: usize,
+        fee_history: &[T],
+        strategy: Box<dyn GasAdjusterStrategy<T>>,
+    ) -> Self {
         Self(RwLock::new(GasStatisticsInner::new(
             max_samples,
             block,
             fee_history,
+            strategy,
         )))
     }
 
-    pub fn median(&self) -> T {
-        self.0.read().unwrap().median()
+    pub fn estimate(&self) -> T {
+        self.0.read().unwrap().estimate()
     }
 
     pub fn last_added_value(&self) -> T {
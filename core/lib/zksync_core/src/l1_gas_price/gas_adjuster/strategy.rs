@@ -0,0 +1,132 @@
+//! Strategies estimating L1 fees from the samples collected by [`GasAdjuster`](super::GasAdjuster).
+
+use std::{collections::VecDeque, fmt};
+
+use zksync_config::{configs::eth_sender::FeeEstimationStrategy, GasAdjusterConfig};
+use zksync_types::U256;
+
+/// Fee sample that can be processed by [`GasAdjusterStrategy`] implementations.
+pub(crate) trait FeeSample:
+    Copy + Ord + Default + fmt::Debug + Send + Sync + 'static
+{
+    fn to_f64(self) -> f64;
+
+    /// Converts a floating-point value back to a sample. Out-of-range values are saturated.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl FeeSample for u64 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as u64
+    }
+}
+
+impl FeeSample for U256 {
+    fn to_f64(self) -> f64 {
+        // `U256` limbs are stored in the little-endian order.
+        self.0
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &limb| acc * 2.0_f64.powi(64) + limb as f64)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        // Fees not fitting into `u128` are unrealistic, so saturating them is fine.
+        U256::from(value as u128)
+    }
+}
+
+/// Strategy estimating a fee from the fees of recent L1 blocks.
+pub(crate) trait GasAdjusterStrategy<T>: fmt::Debug + Send + Sync {
+    /// Estimates the fee based on `samples` ordered from the oldest to the newest one.
+    /// `samples` are guaranteed to be non-empty.
+    fn estimate(&self, samples: &VecDeque<T>) -> T;
+}
+
+/// Creates a strategy specified in the provided config.
+///
+/// # Errors
+///
+/// Returns an error if strategy parameters in the config are invalid.
+pub(crate) fn strategy_from_config<T: FeeSample>(
+    config: &GasAdjusterConfig,
+) -> anyhow::Result<Box<dyn GasAdjusterStrategy<T>>> {
+    Ok(match config.fee_estimation_strategy {
+        FeeEstimationStrategy::Median => Box::new(MedianStrategy),
+        FeeEstimationStrategy::Ewma => Box::new(EwmaStrategy::new(config.ewma_smoothing_factor)?),
+        FeeEstimationStrategy::Percentile => {
+            Box::new(PercentileStrategy::new(config.fee_estimation_percentile)?)
+        }
+    })
+}
+
+/// Returns the `index`-th smallest sample.
+fn nth_smallest<T: FeeSample>(samples: &VecDeque<T>, index: usize) -> T {
+    let mut samples: Vec<_> = samples.iter().copied().collect();
+    let (_, &mut value, _) = samples.select_nth_unstable(index);
+    value
+}
+
+/// Takes the median of samples. This is the strategy historically used by `GasAdjuster`.
+#[derive(Debug)]
+pub(crate) struct MedianStrategy;
+
+impl<T: FeeSample> GasAdjusterStrategy<T> for MedianStrategy {
+    fn estimate(&self, samples: &VecDeque<T>) -> T {
+        nth_smallest(samples, samples.len() / 2)
+    }
+}
+
+/// Takes the exponentially weighted moving average of samples.
+#[derive(Debug)]
+pub(crate) struct EwmaStrategy {
+    smoothing_factor: f64,
+}
+
+impl EwmaStrategy {
+    pub fn new(smoothing_factor: f64) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            smoothing_factor > 0.0 && smoothing_factor <= 1.0,
+            "EWMA smoothing factor must be in (0, 1], got {smoothing_factor}"
+        );
+        Ok(Self { smoothing_factor })
+    }
+}
+
+impl<T: FeeSample> GasAdjusterStrategy<T> for EwmaStrategy {
+    fn estimate(&self, samples: &VecDeque<T>) -> T {
+        let mut samples = samples.iter().map(|&sample| sample.to_f64());
+        let first_sample = samples.next().expect("no samples");
+        let average = samples.fold(first_sample, |average, sample| {
+            self.smoothing_factor * sample + (1.0 - self.smoothing_factor) * average
+        });
+        T::from_f64(average.round())
+    }
+}
+
+/// Takes the specified percentile of samples (using the nearest-rank method).
+#[derive(Debug)]
+pub(crate) struct PercentileStrategy {
+    percentile: f64,
+}
+
+impl PercentileStrategy {
+    pub fn new(percentile: f64) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&percentile),
+            "fee estimation percentile must be in [0, 1], got {percentile}"
+        );
+        Ok(Self { percentile })
+    }
+}
+
+impl<T: FeeSample> GasAdjusterStrategy<T> for PercentileStrategy {
+    fn estimate(&self, samples: &VecDeque<T>) -> T {
+        let index = ((samples.len() - 1) as f64 * self.percentile).round() as usize;
+        nth_smallest(samples, index)
+    }
+}
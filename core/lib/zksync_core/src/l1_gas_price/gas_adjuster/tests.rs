@@ -1,28 +1,109 @@
 use std::{collections::VecDeque, sync::Arc};
 
 use test_casing::test_casing;
-use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
+use zksync_config::{
+    configs::eth_sender::{FeeEstimationStrategy, PubdataSendingMode},
+    GasAdjusterConfig,
+};
 use zksync_eth_client::clients::MockEthereum;
+use zksync_types::U256;
 
-use super::{GasAdjuster, GasStatisticsInner, PubdataPricing};
+use super::{
+    strategy::{EwmaStrategy, FeeSample, GasAdjusterStrategy, MedianStrategy, PercentileStrategy},
+    GasAdjuster, GasStatisticsInner, PubdataPricing,
+};
 use crate::{
     l1_gas_price::{RollupPubdataPricing, ValidiumPubdataPricing},
     utils::testonly::DeploymentMode,
 };
 
+fn statistics<T: FeeSample>(
+    max_samples: usize,
+    fee_history: &[T],
+    strategy: impl GasAdjusterStrategy<T> + 'static,
+) -> GasStatisticsInner<T> {
+    GasStatisticsInner::new(max_samples, max_samples, fee_history, Box::new(strategy))
+}
+
 /// Check that we compute the median correctly
 #[test]
 fn median() {
     // sorted: 4 4 6 7 8
-    assert_eq!(GasStatisticsInner::new(5, 5, &[6, 4, 7, 8, 4]).median(), 6);
+    assert_eq!(
+        statistics(5, &[6, 4, 7, 8, 4], MedianStrategy).estimate(),
+        6
+    );
     // sorted: 4 4 8 10
-    assert_eq!(GasStatisticsInner::new(4, 4, &[8, 4, 4, 10]).median(), 8);
+    assert_eq!(statistics(4, &[8, 4, 4, 10], MedianStrategy).estimate(), 8);
+}
+
+#[test]
+fn percentile() {
+    let fee_history = [6, 4, 7, 8, 4]; // sorted: 4 4 6 7 8
+    let estimate = |percentile| {
+        statistics(
+            5,
+            &fee_history,
+            PercentileStrategy::new(percentile).unwrap(),
+        )
+        .estimate()
+    };
+    assert_eq!(estimate(0.0), 4);
+    assert_eq!(estimate(0.5), 6);
+    assert_eq!(estimate(0.75), 7);
+    assert_eq!(estimate(1.0), 8);
+
+    // The 50th percentile must coincide with the median for any number of samples.
+    let fee_history = [8, 4, 4, 10];
+    assert_eq!(
+        statistics(4, &fee_history, PercentileStrategy::new(0.5).unwrap()).estimate(),
+        statistics(4, &fee_history, MedianStrategy).estimate()
+    );
+}
+
+#[test]
+fn ewma() {
+    // 10 -> 15 -> 27.5 (rounded to 28)
+    assert_eq!(
+        statistics(3, &[10, 20, 40], EwmaStrategy::new(0.5).unwrap()).estimate(),
+        28
+    );
+    // With the smoothing factor 1, only the last sample matters.
+    assert_eq!(
+        statistics(3, &[10, 20, 40], EwmaStrategy::new(1.0).unwrap()).estimate(),
+        40
+    );
+
+    let mut stats = statistics(3, &[10, 20, 40], EwmaStrategy::new(0.5).unwrap());
+    stats.add_samples(&[100]);
+    // Only the last 3 samples are taken into account: 20 -> 30 -> 65
+    assert_eq!(stats.estimate(), 65);
+
+    let fee_history = [10, 20, 40].map(U256::from);
+    let stats = statistics(3, &fee_history, EwmaStrategy::new(0.5).unwrap());
+    assert_eq!(stats.estimate(), U256::from(28));
+}
+
+#[test]
+fn strategies_with_invalid_params() {
+    let err = EwmaStrategy::new(0.0).unwrap_err().to_string();
+    assert!(err.contains("EWMA smoothing factor"), "{err}");
+    let err = PercentileStrategy::new(1.5).unwrap_err().to_string();
+    assert!(err.contains("fee estimation percentile"), "{err}");
+}
+
+#[test]
+fn large_u256_samples_conversion() {
+    let sample = U256::from(1_u128 << 100);
+    assert_eq!(U256::from_f64(sample.to_f64()), sample);
+    let sample = U256::from(u64::MAX) + 1;
+    assert_eq!(sample.to_f64(), 2.0_f64.powi(64));
 }
 
 /// Check that we properly manage the block base fee queue
 #[test]
 fn samples_queue() {
-    let mut stats = GasStatisticsInner::new(5, 5, &[6, 4, 7, 8, 4, 5]);
+    let mut stats = statistics(5, &[6, 4, 7, 8, 4, 5], MedianStrategy);
 
     assert_eq!(stats.samples, VecDeque::from([4, 7, 8, 4, 5]));
 
@@ -70,6 +151,11 @@ async fn kept_updated(deployment_mode: DeploymentMode) {
             num_samples_for_blob_base_fee_estimate: 3,
             internal_pubdata_pricing_multiplier: 1.0,
             max_blob_base_fee: None,
+            fee_estimation_strategy: FeeEstimationStrategy::Median,
+            ewma_smoothing_factor: 0.2,
+            fee_estimation_percentile: 0.75,
+            l1_gas_price_quorum: 1,
+            l1_gas_price_max_deviation: 0.5,
         },
         PubdataSendingMode::Calldata,
         pubdata_pricing,
@@ -81,7 +167,7 @@ async fn kept_updated(deployment_mode: DeploymentMode) {
         adjuster.base_fee_statistics.0.read().unwrap().samples.len(),
         5
     );
-    assert_eq!(adjuster.base_fee_statistics.0.read().unwrap().median(), 6);
+    assert_eq!(adjuster.base_fee_statistics.0.read().unwrap().estimate(), 6);
 
     let expected_median_blob_base_fee = GasAdjuster::blob_base_fee(393216);
     assert_eq!(
@@ -95,7 +181,12 @@ async fn kept_updated(deployment_mode: DeploymentMode) {
         1
     );
     assert_eq!(
-        adjuster.blob_base_fee_statistics.0.read().unwrap().median(),
+        adjuster
+            .blob_base_fee_statistics
+            .0
+            .read()
+            .unwrap()
+            .estimate(),
         expected_median_blob_base_fee
     );
 
@@ -106,7 +197,7 @@ async fn kept_updated(deployment_mode: DeploymentMode) {
         adjuster.base_fee_statistics.0.read().unwrap().samples.len(),
         5
     );
-    assert_eq!(adjuster.base_fee_statistics.0.read().unwrap().median(), 7);
+    assert_eq!(adjuster.base_fee_statistics.0.read().unwrap().estimate(), 7);
 
     let expected_median_blob_base_fee = GasAdjuster::blob_base_fee(393216 * 3);
     assert_eq!(
@@ -120,7 +211,12 @@ async fn kept_updated(deployment_mode: DeploymentMode) {
         3
     );
     assert_eq!(
-        adjuster.blob_base_fee_statistics.0.read().unwrap().median(),
+        adjuster
+            .blob_base_fee_statistics
+            .0
+            .read()
+            .unwrap()
+            .estimate(),
         expected_median_blob_base_fee
     );
 }
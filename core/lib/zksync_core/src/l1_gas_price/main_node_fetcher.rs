@@ -1,45 +1,242 @@
 use std::{
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::Duration,
 };
 
-use tokio::sync::watch::Receiver;
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::{
+    sync::watch::{self, Receiver},
+    time::Instant,
+};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::fee_model::FeeParams;
 use zksync_web3_decl::{
-    error::ClientRpcContext, jsonrpsee::http_client::HttpClient, namespaces::ZksNamespaceClient,
+    error::ClientRpcContext,
+    jsonrpsee::{http_client::HttpClient, ws_client::WsClientBuilder},
+    namespaces::{ZksNamespaceClient, ZksPubSubClient},
 };
 
-use crate::fee_model::BatchFeeModelInputProvider;
+use crate::{
+    fee_model::BatchFeeModelInputProvider,
+    sync_layer::{MainNodeCapabilities, MainNodeClient},
+};
 
-const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Default number of poll intervals without a successful fee params refresh after which fee params
+/// are considered stale.
+const DEFAULT_MAX_STALE_INTERVALS: u32 = 10;
+/// Interval between attempts to re-establish the fee params subscription. While the subscription
+/// is not available, fee params are polled.
+const SUBSCRIPTION_RETRY_INTERVAL: Duration = Duration::from_secs(60);
 
 /// This structure maintains the known L1 gas price by periodically querying
-/// the main node.
+/// the main node, or by subscribing to fee params updates on the main node if its WebSocket URL is provided.
 /// It is required since the main node doesn't only observe the current L1 gas price,
 /// but also applies adjustments to it in order to smooth out the spikes.
 /// The same algorithm cannot be consistently replicated on the external node side,
 /// since it relies on the configuration, which may change.
+///
+/// The last fetched fee params are persisted in Postgres, so that they can be used after a restart
+/// until the first successful fetch.
+///
+/// If fee params weren't refreshed for a configured number of poll intervals, the fetcher
+/// marks itself as not ready in its health check and notifies subscribers of
+/// [`Self::stale_fee_params_receiver()`].
 #[derive(Debug)]
 pub struct MainNodeFeeParamsFetcher {
     client: HttpClient,
+    pool: ConnectionPool<Core>,
+    ws_url: Option<String>,
+    poll_interval: Duration,
+    max_stale_intervals: u32,
     main_node_fee_params: RwLock<FeeParams>,
+    last_refreshed_at: Mutex<Instant>,
+    is_stale: watch::Sender<bool>,
+    health_updater: HealthUpdater,
+}
+
+/// Health details reported by [`MainNodeFeeParamsFetcher`].
+#[derive(Debug, Serialize)]
+struct FeeParamsFetcherHealthDetails {
+    secs_since_last_refresh: u64,
+    is_stale: bool,
 }
 
 impl MainNodeFeeParamsFetcher {
-    pub fn new(client: HttpClient) -> Self {
-        Self {
+    /// Creates a new fetcher. Fee params persisted in Postgres (if any) are used until
+    /// fee params are fetched from the main node.
+    pub async fn new(client: HttpClient, pool: ConnectionPool<Core>) -> anyhow::Result<Self> {
+        let mut storage = pool
+            .connection_tagged("main_node_fee_params_fetcher")
+            .await?;
+        let persisted_fee_params = storage
+            .fee_params_dal()
+            .get_main_node_fee_params()
+            .await
+            .context("failed loading persisted main node fee params")?;
+        drop(storage);
+
+        let fee_params = if let Some(fee_params) = persisted_fee_params {
+            tracing::info!("Loaded persisted main node fee params: {fee_params:?}");
+            fee_params
+        } else {
+            FeeParams::sensible_v1_default()
+        };
+        Ok(Self {
             client,
-            main_node_fee_params: RwLock::new(FeeParams::sensible_v1_default()),
+            pool,
+            ws_url: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            max_stale_intervals: DEFAULT_MAX_STALE_INTERVALS,
+            main_node_fee_params: RwLock::new(fee_params),
+            last_refreshed_at: Mutex::new(Instant::now()),
+            is_stale: watch::channel(false).0,
+            health_updater: ReactiveHealthCheck::new("main_node_fee_params_fetcher").1,
+        })
+    }
+
+    /// Sets the interval between fee params polls (and between retries on errors).
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets the number of poll intervals without a successful refresh after which fee params
+    /// are considered stale.
+    pub fn with_max_stale_intervals(mut self, max_stale_intervals: u32) -> Self {
+        self.max_stale_intervals = max_stale_intervals;
+        self
+    }
+
+    /// Makes the fetcher receive fee params via the `zks_subscribeFeeParams` subscription
+    /// on the main node WebSocket API, using polling only as a fallback.
+    pub fn with_subscription(mut self, ws_url: String) -> Self {
+        self.ws_url = Some(ws_url);
+        self
+    }
+
+    /// Returns the health check for this fetcher.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Returns a receiver that is set to `true` while fee params are stale.
+    pub fn stale_fee_params_receiver(&self) -> Receiver<bool> {
+        self.is_stale.subscribe()
+    }
+
+    fn max_staleness(&self) -> Duration {
+        self.poll_interval * self.max_stale_intervals
+    }
+
+    /// Updates fee params and returns whether they have changed.
+    fn set_fee_params(&self, fee_params: FeeParams) -> bool {
+        let prev_fee_params =
+            std::mem::replace(&mut *self.main_node_fee_params.write().unwrap(), fee_params);
+        *self.last_refreshed_at.lock().unwrap() = Instant::now();
+        self.update_staleness();
+        prev_fee_params != fee_params
+    }
+
+    /// Updates fee params and persists them in Postgres if they have changed.
+    async fn update_fee_params(&self, fee_params: FeeParams) -> anyhow::Result<()> {
+        if !self.set_fee_params(fee_params) {
+            return Ok(());
+        }
+        let mut storage = self
+            .pool
+            .connection_tagged("main_node_fee_params_fetcher")
+            .await?;
+        storage
+            .fee_params_dal()
+            .set_main_node_fee_params(&fee_params)
+            .await
+            .context("failed persisting main node fee params")
+    }
+
+    fn update_staleness(&self) {
+        let elapsed = self.last_refreshed_at.lock().unwrap().elapsed();
+        let is_stale = elapsed > self.max_staleness();
+        let was_stale = self.is_stale.send_replace(is_stale);
+        if is_stale && !was_stale {
+            tracing::warn!(
+                "Fee params weren't refreshed for {elapsed:?} (more than {} poll intervals); \
+                 considering them stale",
+                self.max_stale_intervals
+            );
+        } else if !is_stale && was_stale {
+            tracing::info!("Fee params are refreshed and are no longer stale");
         }
+
+        let status = if is_stale {
+            HealthStatus::NotReady
+        } else {
+            HealthStatus::Ready
+        };
+        let details = FeeParamsFetcherHealthDetails {
+            secs_since_last_refresh: elapsed.as_secs(),
+            is_stale,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
     }
 
-    pub async fn run(self: Arc<Self>, stop_receiver: Receiver<bool>) -> anyhow::Result<()> {
+    pub async fn run(self: Arc<Self>, mut stop_receiver: Receiver<bool>) -> anyhow::Result<()> {
+        // Older main nodes may not serve fee params; in this case, we stick to the default ones.
+        let capabilities = loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, MainNodeFeeParamsFetcher is shutting down");
+                return Ok(());
+            }
+            match self.client.fetch_capabilities().await {
+                Ok(capabilities) => break capabilities,
+                Err(err) => {
+                    tracing::warn!("Unable to discover main node capabilities: {err}");
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        };
+        if !capabilities.supports(MainNodeCapabilities::FEE_PARAMS) {
+            tracing::warn!(
+                "Main node doesn't serve fee params; using default fee params {:?}",
+                self.get_fee_model_params()
+            );
+            // Default fee params never become stale.
+            self.health_updater.update(HealthStatus::Ready.into());
+            stop_receiver.changed().await.ok();
+            return Ok(());
+        }
+
+        let mut next_subscription_attempt = Instant::now();
         loop {
             if *stop_receiver.borrow() {
                 tracing::info!("Stop signal received, MainNodeFeeParamsFetcher is shutting down");
                 break;
             }
 
+            if let Some(ws_url) = &self.ws_url {
+                if Instant::now() >= next_subscription_attempt {
+                    match self.run_subscription(ws_url, &mut stop_receiver).await {
+                        Ok(()) => {
+                            tracing::info!(
+                                "Stop signal received, MainNodeFeeParamsFetcher is shutting down"
+                            );
+                            break;
+                        }
+                        Err(err) => {
+                            tracing::warn!(
+                                "Fee params subscription failed, falling back to polling \
+                                 for {SUBSCRIPTION_RETRY_INTERVAL:?}: {err:#}"
+                            );
+                            next_subscription_attempt =
+                                Instant::now() + SUBSCRIPTION_RETRY_INTERVAL;
+                        }
+                    }
+                }
+            }
+
             let fetch_result = self
                 .client
                 .get_fee_params()
@@ -49,17 +246,55 @@ impl MainNodeFeeParamsFetcher {
                 Ok(price) => price,
                 Err(err) => {
                     tracing::warn!("Unable to get the gas price: {}", err);
+                    self.update_staleness();
                     // A delay to avoid spamming the main node with requests.
-                    tokio::time::sleep(SLEEP_INTERVAL).await;
+                    tokio::time::sleep(self.poll_interval).await;
                     continue;
                 }
             };
-            *self.main_node_fee_params.write().unwrap() = main_node_fee_params;
+            self.update_fee_params(main_node_fee_params).await?;
 
-            tokio::time::sleep(SLEEP_INTERVAL).await;
+            tokio::time::sleep(self.poll_interval).await;
         }
         Ok(())
     }
+
+    /// Receives fee params from the main node subscription until a stop signal is received (in which case,
+    /// returns `Ok(())`), or the subscription fails.
+    async fn run_subscription(
+        &self,
+        ws_url: &str,
+        stop_receiver: &mut Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let ws_client = WsClientBuilder::default()
+            .build(ws_url)
+            .await
+            .context("failed connecting to main node WebSocket API")?;
+        let mut subscription = ws_client
+            .subscribe_fee_params()
+            .await
+            .context("failed subscribing to fee params")?;
+        tracing::info!("Subscribed to fee params updates on the main node");
+
+        loop {
+            tokio::select! {
+                fee_params = subscription.next() => {
+                    let fee_params = fee_params
+                        .context("fee params subscription was terminated by the main node")?
+                        .context("failed receiving fee params")?;
+                    tracing::debug!("Received fee params from the main node: {fee_params:?}");
+                    self.update_fee_params(fee_params).await?;
+                }
+                // The main node only pushes fee params when they change, so a live subscription
+                // means that fee params are up to date.
+                () = tokio::time::sleep(self.poll_interval) => {
+                    *self.last_refreshed_at.lock().unwrap() = Instant::now();
+                    self.update_staleness();
+                }
+                _ = stop_receiver.changed() => return Ok(()),
+            }
+        }
+    }
 }
 
 impl BatchFeeModelInputProvider for MainNodeFeeParamsFetcher {
@@ -67,3 +302,73 @@ impl BatchFeeModelInputProvider for MainNodeFeeParamsFetcher {
         *self.main_node_fee_params.read().unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_health_check::CheckHealth;
+    use zksync_types::fee_model::{FeeModelConfigV1, FeeParamsV1};
+    use zksync_web3_decl::jsonrpsee::http_client::HttpClientBuilder;
+
+    use super::*;
+
+    fn mock_client() -> HttpClient {
+        HttpClientBuilder::default()
+            .build("http://127.0.0.1:3050")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fee_params_become_stale_without_refreshes() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let fetcher = MainNodeFeeParamsFetcher::new(mock_client(), pool)
+            .await
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10))
+            .with_max_stale_intervals(2);
+        let stale_receiver = fetcher.stale_fee_params_receiver();
+        let health_check = fetcher.health_check();
+
+        fetcher.update_staleness();
+        assert!(!*stale_receiver.borrow());
+        let health = health_check.check_health().await;
+        assert_eq!(health.status(), HealthStatus::Ready);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        fetcher.update_staleness();
+        assert!(*stale_receiver.borrow());
+        let health = health_check.check_health().await;
+        assert_eq!(health.status(), HealthStatus::NotReady);
+
+        fetcher.set_fee_params(FeeParams::sensible_v1_default());
+        assert!(!*stale_receiver.borrow());
+        let health = health_check.check_health().await;
+        assert_eq!(health.status(), HealthStatus::Ready);
+    }
+
+    #[tokio::test]
+    async fn persisting_fee_params() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let fetcher = MainNodeFeeParamsFetcher::new(mock_client(), pool.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            fetcher.get_fee_model_params(),
+            FeeParams::sensible_v1_default()
+        );
+
+        let fee_params = FeeParams::V1(FeeParamsV1 {
+            config: FeeModelConfigV1 {
+                minimal_l2_gas_price: 200_000_000,
+            },
+            l1_gas_price: 5_000_000_000,
+        });
+        fetcher.update_fee_params(fee_params).await.unwrap();
+        assert_eq!(fetcher.get_fee_model_params(), fee_params);
+
+        // Emulate a node restart.
+        let fetcher = MainNodeFeeParamsFetcher::new(mock_client(), pool)
+            .await
+            .unwrap();
+        assert_eq!(fetcher.get_fee_model_params(), fee_params);
+    }
+}
@@ -2,11 +2,13 @@
 
 use std::fmt;
 
+pub use composite::CompositeL1GasPriceProvider;
 pub use gas_adjuster::GasAdjuster;
 pub use main_node_fetcher::MainNodeFeeParamsFetcher;
 pub use pubdata_pricing::{PubdataPricing, RollupPubdataPricing, ValidiumPubdataPricing};
 pub use singleton::GasAdjusterSingleton;
 
+mod composite;
 mod gas_adjuster;
 mod main_node_fetcher;
 mod pubdata_pricing;
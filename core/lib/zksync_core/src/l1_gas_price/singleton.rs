@@ -6,16 +6,17 @@ use tokio::{
     task::JoinHandle,
 };
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
-use zksync_eth_client::clients::QueryClient;
+use zksync_eth_client::{clients::QueryClient, EthInterface};
 
 use super::PubdataPricing;
-use crate::l1_gas_price::GasAdjuster;
+use crate::l1_gas_price::{CompositeL1GasPriceProvider, GasAdjuster};
 
 /// Special struct for creating a singleton of `GasAdjuster`.
 /// This is needed only for running the server.
 #[derive(Debug)]
 pub struct GasAdjusterSingleton {
     web3_url: String,
+    gas_price_web3_urls: Vec<String>,
     gas_adjuster_config: GasAdjusterConfig,
     pubdata_sending_mode: PubdataSendingMode,
     singleton: OnceCell<Result<Arc<GasAdjuster>, Error>>,
@@ -41,6 +42,7 @@ impl GasAdjusterSingleton {
     ) -> Self {
         Self {
             web3_url,
+            gas_price_web3_urls: vec![],
             gas_adjuster_config,
             pubdata_sending_mode,
             singleton: OnceCell::new(),
@@ -48,14 +50,34 @@ impl GasAdjusterSingleton {
         }
     }
 
+    /// Sets additional L1 RPC URLs to aggregate fee data from using [`CompositeL1GasPriceProvider`].
+    pub fn with_gas_price_web3_urls(mut self, urls: Vec<String>) -> Self {
+        self.gas_price_web3_urls = urls;
+        self
+    }
+
+    fn create_eth_client(&self) -> anyhow::Result<Arc<dyn EthInterface>> {
+        let query_client = QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
+        if self.gas_price_web3_urls.is_empty() {
+            return Ok(Arc::new(query_client));
+        }
+
+        let mut clients: Vec<Arc<dyn EthInterface>> = vec![Arc::new(query_client)];
+        for url in &self.gas_price_web3_urls {
+            let client = QueryClient::new(url).context("QueryClient::new()")?;
+            clients.push(Arc::new(client));
+        }
+        let provider = CompositeL1GasPriceProvider::new(clients, &self.gas_adjuster_config)
+            .context("CompositeL1GasPriceProvider::new()")?;
+        Ok(Arc::new(provider))
+    }
+
     pub async fn get_or_init(&mut self) -> Result<Arc<GasAdjuster>, Error> {
         let adjuster = self
             .singleton
             .get_or_init(|| async {
-                let query_client =
-                    QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
                 let adjuster = GasAdjuster::new(
-                    Arc::new(query_client.clone()),
+                    self.create_eth_client()?,
                     self.gas_adjuster_config,
                     self.pubdata_sending_mode,
                     self.pubdata_pricing.clone(),
@@ -2,6 +2,7 @@
 
 use std::{
     net::Ipv4Addr,
+    path::Path,
     str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
@@ -9,7 +10,10 @@ use std::{
 
 use anyhow::Context as _;
 use api_server::tx_sender::master_pool_sink::MasterPoolSink;
-use fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider, MainNodeFeeInputProvider};
+use fee_model::{
+    ApiFeeInputProvider, BatchFeeModelInputProvider, CongestionPricing, CongestionPricingConfig,
+    FeeModelConfigWatcher, MainNodeFeeInputProvider,
+};
 use prometheus_exporter::PrometheusExporterConfig;
 use prover_dal::Prover;
 use temp_config_store::Secrets;
@@ -26,7 +30,7 @@ use zksync_config::{
     configs::{
         api::{MerkleTreeApiConfig, Web3JsonRpcConfig},
         chain::{
-            CircuitBreakerConfig, L1BatchCommitDataGeneratorMode, MempoolConfig,
+            CircuitBreakerConfig, L1BatchCommitDataGeneratorMode, MempoolConfig, MempoolDurability,
             OperationsManagerConfig, StateKeeperConfig,
         },
         database::{MerkleTreeConfig, MerkleTreeMode},
@@ -34,20 +38,20 @@ use zksync_config::{
         wallets::Wallets,
         ContractsConfig, GeneralConfig,
     },
-    ApiConfig, DBConfig, GenesisConfig, PostgresConfig,
+    ApiConfig, DBConfig, GenesisConfig, ObjectStoreConfig, PostgresConfig,
 };
 use zksync_contracts::governance_contract;
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
 use zksync_db_connection::healthcheck::ConnectionPoolHealthCheck;
 use zksync_eth_client::{
     clients::{PKSigningClient, QueryClient},
-    BoundEthInterface,
+    BoundEthInterface, EthInterface,
 };
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
 use zksync_state::PostgresStorageCaches;
-use zksync_types::{fee_model::FeeModelConfig, L2ChainId};
+use zksync_types::{fee_model::FeeModelConfig, Address, L2ChainId, H256};
 
 use crate::{
     api_server::{
@@ -59,7 +63,10 @@ use crate::{
         web3::{self, state::InternalApiConfig, Namespace},
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
+    block_body_compressor::{BlockBodyCompressor, BlockBodyCompressorConfig},
     commitment_generator::CommitmentGenerator,
+    config_export::ConfigExport,
+    data_retention::DataRetention,
     eth_sender::{
         l1_batch_commit_data_generator::{
             L1BatchCommitDataGenerator, RollupModeL1BatchCommitDataGenerator,
@@ -68,6 +75,7 @@ use crate::{
         Aggregator, EthTxAggregator, EthTxManager,
     },
     eth_watch::start_eth_watch,
+    finality_webhooks::FinalityWebhooks,
     genesis::GenesisParams,
     house_keeper::{
         blocks_state_reporter::L1BatchMetricsReporter,
@@ -79,39 +87,55 @@ use crate::{
         fri_scheduler_circuit_queuer::SchedulerCircuitQueuer,
         fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
         fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
-        periodic_job::PeriodicJob,
+        object_store_archiver::ObjectStoreArchiver, object_store_gc::ObjectStoreGarbageCollector,
+        periodic_job::PeriodicJob, storage_logs_partition_rotator::StorageLogsPartitionRotator,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
     l1_gas_price::{
         GasAdjusterSingleton, PubdataPricing, RollupPubdataPricing, ValidiumPubdataPricing,
     },
-    metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
+    metadata_calculator::{
+        MerkleTreeConsistencyCheckerConfig, MerkleTreeL1RootCheckerConfig,
+        MerkleTreeSnapshotExporterConfig, MetadataCalculator, MetadataCalculatorConfig,
+    },
     metrics::{InitStage, APP_METRICS},
+    metrics_snapshotter::{MetricsSnapshotter, MetricsSnapshotterConfig},
     state_keeper::{
-        create_state_keeper, MempoolFetcher, MempoolGuard, OutputHandler, SequencerSealer,
-        StateKeeperPersistence,
+        create_state_keeper, run_admin_server, MempoolFetcher, MempoolGuard, OutputHandler,
+        PolicyTxFilter, SequencerSealer, StateKeeperControl, StateKeeperPersistence,
+        TxExecutionMetricsRecorder, TxFilterPolicy,
     },
     utils::ensure_l1_batch_commit_data_generation_mode,
 };
 
 pub mod api_server;
 pub mod basic_witness_input_producer;
+pub mod block_body_compressor;
 pub mod block_reverter;
 pub mod commitment_generator;
+pub mod config_export;
 pub mod consensus;
 pub mod consistency_checker;
+pub mod data_retention;
+pub mod doctor;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod fee_model;
+pub mod finality_webhooks;
 pub mod gas_tracker;
 pub mod genesis;
+pub mod governance;
 pub mod house_keeper;
+pub mod intent_log;
 pub mod l1_gas_price;
 pub mod metadata_calculator;
 mod metrics;
+pub mod metrics_snapshotter;
 pub mod proof_data_handler;
 pub mod proto;
 pub mod reorg_detector;
+pub mod replay;
+pub mod schema_guard;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod temp_config_store;
@@ -135,6 +159,17 @@ pub async fn genesis_init(
     Ok(())
 }
 
+/// Checks that the Postgres schema is compatible with this binary, i.e., that the database has all migrations
+/// embedded into the binary (and no other migrations) applied.
+pub async fn ensure_schema_compatibility(postgres_config: &PostgresConfig) -> anyhow::Result<()> {
+    let db_url = postgres_config.master_url()?;
+    let pool = ConnectionPool::<Core>::singleton(db_url)
+        .build()
+        .await
+        .context("failed to build connection_pool")?;
+    schema_guard::ensure_compatibility(&pool).await
+}
+
 pub async fn is_genesis_needed(postgres_config: &PostgresConfig) -> bool {
     let db_url = postgres_config.master_url().unwrap();
     let pool = ConnectionPool::<Core>::singleton(db_url)
@@ -193,6 +228,14 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Component periodically persisting selected metrics to Postgres for post-incident analysis.
+    MetricsSnapshotter,
+    /// Component compressing event payloads and transaction bytes of sealed miniblocks in Postgres.
+    BlockBodyCompressor,
+    /// Component pushing notifications about L1 batches being committed, proven and executed to webhooks.
+    FinalityWebhooks,
+    /// Component removing events and transaction traces older than the configured retention period from Postgres.
+    DataRetention,
 }
 
 #[derive(Debug)]
@@ -229,13 +272,24 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "metrics_snapshotter" => Ok(Components(vec![Component::MetricsSnapshotter])),
+            "block_body_compressor" => Ok(Components(vec![Component::BlockBodyCompressor])),
+            "finality_webhooks" => Ok(Components(vec![Component::FinalityWebhooks])),
+            "data_retention" => Ok(Components(vec![Component::DataRetention])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
 }
 
+/// Interval between checks for fee model config updates.
+const FEE_MODEL_CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Initializes the specified components. If `general_config_path` is specified, the fee model config
+/// is reloaded from this file at runtime.
+#[allow(clippy::too_many_arguments)]
 pub async fn initialize_components(
     configs: &GeneralConfig,
+    general_config_path: Option<&Path>,
     wallets: &Wallets,
     genesis_config: &GenesisConfig,
     contracts_config: &ContractsConfig,
@@ -255,9 +309,17 @@ pub async fn initialize_components(
     if let Some(threshold) = postgres_config.slow_query_threshold() {
         ConnectionPool::<Core>::global_config().set_slow_query_threshold(threshold)?;
     }
+    if let Some(timeout) = postgres_config.query_timeout() {
+        ConnectionPool::<Core>::global_config().set_query_timeout(timeout)?;
+    }
     if let Some(threshold) = postgres_config.long_connection_threshold() {
         ConnectionPool::<Core>::global_config().set_long_connection_threshold(threshold)?;
     }
+    if let Some(schema) = &postgres_config.schema {
+        // Migrations put both core and prover tables into the configured schema.
+        ConnectionPool::<Core>::set_global_schema(schema)?;
+        ConnectionPool::<Prover>::set_global_schema(schema)?;
+    }
 
     let pool_size = postgres_config.max_connections()?;
     let pool_size_master = postgres_config
@@ -271,13 +333,22 @@ pub async fn initialize_components(
             .context("failed to build connection_pool")?;
     // We're most interested in setting acquire / statement timeouts for the API server, which puts the most load
     // on Postgres.
-    let replica_connection_pool =
+    let mut replica_pool_builder = if let Some(max_lag) = postgres_config.max_replica_lag() {
+        // Read connections are served by the replica unless it lags behind; other connections use the master DB.
+        let mut builder = ConnectionPool::<Core>::builder(postgres_config.master_url()?, pool_size);
+        builder
+            .set_replica_url(Some(postgres_config.replica_url()?.to_owned()))
+            .set_max_replica_lag(Some(max_lag));
+        builder
+    } else {
         ConnectionPool::<Core>::builder(postgres_config.replica_url()?, pool_size)
-            .set_acquire_timeout(postgres_config.acquire_timeout())
-            .set_statement_timeout(postgres_config.statement_timeout())
-            .build()
-            .await
-            .context("failed to build replica_connection_pool")?;
+    };
+    let replica_connection_pool = replica_pool_builder
+        .set_acquire_timeout(postgres_config.acquire_timeout())
+        .set_statement_timeout(postgres_config.statement_timeout())
+        .build()
+        .await
+        .context("failed to build replica_connection_pool")?;
 
     let health_check_config = configs
         .api_config
@@ -322,7 +393,8 @@ pub async fn initialize_components(
         gas_adjuster_config,
         sender.pubdata_sending_mode,
         pubdata_pricing,
-    );
+    )
+    .with_gas_price_web3_urls(eth.gas_price_web3_urls.clone());
 
     let (stop_sender, stop_receiver) = watch::channel(false);
 
@@ -349,6 +421,46 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(stop_receiver.clone())),
     ];
 
+    // The fee model config is shared among all components using it, so that it's updated for all of them at once.
+    let uses_fee_model = components.contains(&Component::HttpApi)
+        || components.contains(&Component::WsApi)
+        || components.contains(&Component::StateKeeper);
+    let fee_model_config = configs
+        .state_keeper_config
+        .as_ref()
+        .map(FeeModelConfig::from_state_keeper_config);
+    let fee_model_config = match (fee_model_config, general_config_path) {
+        (Some(config), Some(path)) if uses_fee_model => {
+            let watcher =
+                FeeModelConfigWatcher::new(path.to_owned(), config, FEE_MODEL_CONFIG_POLL_INTERVAL);
+            let config_receiver = watcher.subscribe();
+            task_futures.push(tokio::spawn(watcher.run(stop_receiver.clone())));
+            Some(config_receiver)
+        }
+        (config, _) => config.map(|config| watch::channel(config).1),
+    };
+    // Congestion pricing relies on the mempool size reported by the state keeper, so it's only enabled
+    // if the state keeper runs in the same process.
+    let (mempool_size_sender, mempool_size_receiver) = watch::channel(0_u64);
+    let congestion_pricing = configs
+        .state_keeper_config
+        .as_ref()
+        .and_then(CongestionPricingConfig::from_state_keeper_config)
+        .filter(|_| components.contains(&Component::StateKeeper))
+        .map(|config| CongestionPricing::new(config, mempool_size_receiver));
+    let create_fee_input_provider = |gas_adjuster| {
+        let provider = MainNodeFeeInputProvider::from_config_receiver(
+            gas_adjuster,
+            fee_model_config.clone().context("state_keeper_config")?,
+        );
+        anyhow::Ok(Arc::new(match &congestion_pricing {
+            Some(congestion_pricing) => {
+                provider.with_congestion_pricing(congestion_pricing.clone())
+            }
+            None => provider,
+        }))
+    };
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ContractVerificationApi)
@@ -358,6 +470,11 @@ pub async fn initialize_components(
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
+        let mempool_durability = configs
+            .mempool_config
+            .as_ref()
+            .map(|config| config.durability)
+            .unwrap_or_default();
         let tx_sender_config = TxSenderConfig::new(
             &state_keeper_config,
             &api_config.web3_json_rpc,
@@ -371,6 +488,29 @@ pub async fn initialize_components(
         );
         let internal_api_config =
             InternalApiConfig::new(&api_config.web3_json_rpc, contracts_config, genesis_config);
+        let sync_block_signing_key = if api_config.web3_json_rpc.sign_sync_blocks {
+            let signer = wallets
+                .signer
+                .as_ref()
+                .context("signer wallet is required to sign L2 blocks served to external nodes")?;
+            Some(signer.private_key())
+        } else {
+            None
+        };
+        let snapshot_header_signing_key = if api_config.web3_json_rpc.sign_snapshot_headers {
+            let signer = wallets
+                .signer
+                .as_ref()
+                .context("signer wallet is required to sign snapshot headers")?;
+            Some(signer.private_key())
+        } else {
+            None
+        };
+        if api_config.web3_json_rpc.tree_lag_limit.is_some() {
+            tracing::warn!(
+                "Tree lag limit for API servers is only supported by the node framework; the limit is ignored"
+            );
+        }
 
         // Lazily initialize storage caches only when they are needed (e.g., skip their initialization
         // if we only run the explorer APIs). This is required because the cache update task will
@@ -395,16 +535,14 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let batch_fee_input_provider = create_fee_input_provider(bounded_gas_adjuster)?;
             run_http_api(
                 &mut task_futures,
                 &app_health,
                 &postgres_config,
                 &tx_sender_config,
                 &state_keeper_config,
+                mempool_durability,
                 &internal_api_config,
                 &api_config,
                 connection_pool.clone(),
@@ -413,6 +551,8 @@ pub async fn initialize_components(
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                sync_block_signing_key,
+                snapshot_header_signing_key,
             )
             .await
             .context("run_http_api")?;
@@ -443,16 +583,14 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let batch_fee_input_provider = create_fee_input_provider(bounded_gas_adjuster)?;
             run_ws_api(
                 &mut task_futures,
                 &app_health,
                 &postgres_config,
                 &tx_sender_config,
                 &state_keeper_config,
+                mempool_durability,
                 &internal_api_config,
                 &api_config,
                 batch_fee_input_provider,
@@ -460,6 +598,8 @@ pub async fn initialize_components(
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                sync_block_signing_key,
+                snapshot_header_signing_key,
             )
             .await
             .context("run_ws_api")?;
@@ -498,6 +638,7 @@ pub async fn initialize_components(
         .clone()
         .context("object_store_config")?;
     let store_factory = ObjectStoreFactory::new(object_store_config);
+    app_health.insert_component(store_factory.health_check());
 
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
@@ -510,9 +651,13 @@ pub async fn initialize_components(
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
-        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
+        let batch_fee_input_provider = create_fee_input_provider(bounded_gas_adjuster.clone())?;
+        // The mempool filter uses fee inputs without congestion pricing. Otherwise, transactions underpriced
+        // after a price increase would not be loaded into the mempool and would not count towards its size,
+        // which would make the congestion price oscillate.
+        let mempool_fee_input_provider = Arc::new(MainNodeFeeInputProvider::from_config_receiver(
             bounded_gas_adjuster,
-            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+            fee_model_config.clone().context("state_keeper_config")?,
         ));
         add_state_keeper_to_task_futures(
             &mut task_futures,
@@ -527,6 +672,9 @@ pub async fn initialize_components(
             &db_config,
             &configs.mempool_config.clone().context("mempool_config")?,
             batch_fee_input_provider,
+            mempool_fee_input_provider,
+            mempool_size_sender,
+            &app_health,
             stop_receiver.clone(),
         )
         .await
@@ -539,13 +687,23 @@ pub async fn initialize_components(
 
     if components.contains(&Component::Consensus) {
         let secrets = secrets.consensus.as_ref().context("Secrets are missing")?;
-        let cfg = consensus_config
-            .clone()
-            .context("consensus component's config is missing")?
-            .main_node(secrets)?;
+        let consensus_config = consensus_config
+            .as_ref()
+            .context("consensus component's config is missing")?;
         let started_at = Instant::now();
         tracing::info!("initializing Consensus");
+        let api_config = configs.api_config.as_ref().context("api_config")?;
+        let reserved_addrs = [
+            ("HTTP API server", api_config.web3_json_rpc.http_bind_addr()),
+            ("WS API server", api_config.web3_json_rpc.ws_bind_addr()),
+            ("healthcheck server", api_config.healthcheck.bind_addr()),
+        ];
+        consensus_config.validate(secrets, &reserved_addrs)?;
         let pool = connection_pool.clone();
+        consensus_config
+            .validate_genesis(&ctx::root(), secrets, &consensus::Store(pool.clone()))
+            .await?;
+        let cfg = consensus_config.main_node(secrets)?;
         let mut stop_receiver = stop_receiver.clone();
         task_futures.push(tokio::spawn(async move {
             scope::run!(&ctx::root(), |ctx, s| async {
@@ -743,6 +901,8 @@ pub async fn initialize_components(
         &app_health,
         components,
         &store_factory,
+        Arc::new(query_client.clone()),
+        contracts_config.diamond_proxy_addr,
         stop_receiver.clone(),
     )
     .await
@@ -796,11 +956,84 @@ pub async fn initialize_components(
         ));
     }
 
+    if components.contains(&Component::MetricsSnapshotter) {
+        let metrics_snapshotter_pool =
+            ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build metrics_snapshotter_pool")?;
+        let metrics_snapshotter = MetricsSnapshotter::new(
+            MetricsSnapshotterConfig::default(),
+            metrics_snapshotter_pool,
+        );
+        task_futures.push(tokio::spawn(metrics_snapshotter.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::BlockBodyCompressor) {
+        let block_body_compressor_pool =
+            ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build block_body_compressor_pool")?;
+        let block_body_compressor = BlockBodyCompressor::new(
+            BlockBodyCompressorConfig::default(),
+            block_body_compressor_pool,
+        );
+        task_futures.push(tokio::spawn(
+            block_body_compressor.run(stop_receiver.clone()),
+        ));
+    }
+
+    if components.contains(&Component::FinalityWebhooks) {
+        let config = configs
+            .finality_webhooks_config
+            .clone()
+            .context("finality_webhooks_config")?;
+        let signing_key = wallets
+            .signer
+            .as_ref()
+            .context("signer wallet is required to sign finality notifications")?
+            .private_key();
+        let finality_webhooks_pool =
+            ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build finality_webhooks_pool")?;
+        let finality_webhooks =
+            FinalityWebhooks::new(config, finality_webhooks_pool, l2_chain_id, signing_key)?;
+        task_futures.push(tokio::spawn(finality_webhooks.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::DataRetention) {
+        let config = configs
+            .data_retention_config
+            .clone()
+            .context("data_retention_config")?;
+        let data_retention_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build data_retention_pool")?;
+        let data_retention = DataRetention::new(config, data_retention_pool);
+        task_futures.push(tokio::spawn(data_retention.run(stop_receiver.clone())));
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check));
-    let health_check_handle =
-        HealthCheckHandle::spawn_server(health_check_config.bind_addr(), app_health);
+    let health_check_handle = if let Some(token) = health_check_config.config_export_token.clone() {
+        let mut config_export = ConfigExport::new(configs.clone());
+        if let Some(receiver) = fee_model_config.clone() {
+            config_export = config_export.with_fee_model_config(receiver);
+        }
+        HealthCheckHandle::spawn_server_with_config_export(
+            health_check_config.bind_addr(),
+            app_health,
+            token,
+            config_export,
+        )
+    } else {
+        HealthCheckHandle::spawn_server(health_check_config.bind_addr(), app_health)
+    };
 
     if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
         task_futures.push(task);
@@ -820,6 +1053,9 @@ async fn add_state_keeper_to_task_futures(
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    mempool_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    mempool_size_sender: watch::Sender<u64>,
+    app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let pool_builder = ConnectionPool::<Core>::singleton(postgres_config.master_url()?);
@@ -832,7 +1068,9 @@ async fn add_state_keeper_to_task_futures(
             .connection()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity)
+            .await
+            .with_max_nonce_gap(mempool_config.max_nonce_gap);
         mempool.register_metrics();
         mempool
     };
@@ -841,13 +1079,58 @@ async fn add_state_keeper_to_task_futures(
         .build()
         .await
         .context("failed to build miniblock_sealer_pool")?;
-    let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
+    let (mut persistence, miniblock_sealer) = StateKeeperPersistence::new(
         miniblock_sealer_pool,
         contracts_config.l2_erc20_bridge_addr,
         state_keeper_config.miniblock_seal_queue_capacity,
     );
+    let miniblock_sealer = match state_keeper_config.miniblock_seal_batch_latency_target() {
+        Some(latency_target) => miniblock_sealer.with_adaptive_batching(
+            latency_target,
+            state_keeper_config.miniblock_seal_batch_max_delay(),
+        ),
+        None => miniblock_sealer,
+    };
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
+    if state_keeper_config.offload_protective_reads {
+        tracing::info!("Offloading persisting protective reads to a background task");
+        // The writer needs more than one connection to re-execute L1 batches with pending protective reads.
+        let protective_reads_pool =
+            ConnectionPool::<Core>::builder(postgres_config.master_url()?, 2)
+                .build()
+                .await
+                .context("failed to build protective_reads_pool")?;
+        let writer;
+        (persistence, writer) = persistence.with_offloaded_protective_reads(
+            protective_reads_pool,
+            l2chain_id,
+            &state_keeper_config,
+        );
+        task_futures.push(tokio::spawn(writer.run(stop_receiver.clone())));
+    }
 
+    let mut output_handler = OutputHandler::new(Box::new(persistence));
+    if let Some(sampling_rate) = state_keeper_config.tx_execution_metrics_sampling_rate {
+        tracing::info!("Recording execution metrics for {sampling_rate} of transactions");
+        let tx_metrics_pool = pool_builder
+            .build()
+            .await
+            .context("failed to build tx_metrics_pool")?;
+        let recorder = TxExecutionMetricsRecorder::new(
+            tx_metrics_pool,
+            sampling_rate,
+            state_keeper_config.tx_execution_metrics_retention_miniblocks(),
+        );
+        output_handler = output_handler.with_handler(Box::new(recorder));
+    }
+
+    let admin_bind_addr = state_keeper_config.admin_bind_addr();
+    let admin_token = state_keeper_config.admin_token.clone();
+    let tx_filter_policy = TxFilterPolicy::from_config(&state_keeper_config);
+    if !tx_filter_policy.is_permissive() {
+        tracing::info!("Using transaction filter policy: {tx_filter_policy:?}");
+    }
+    let tx_filter = PolicyTxFilter::new(tx_filter_policy);
     let (state_keeper, async_catchup_task) = create_state_keeper(
         state_keeper_config,
         state_keeper_wallets,
@@ -857,10 +1140,11 @@ async fn add_state_keeper_to_task_futures(
         state_keeper_pool.clone(),
         mempool.clone(),
         batch_fee_input_provider.clone(),
-        OutputHandler::new(Box::new(persistence)),
+        output_handler,
+        Arc::new(tx_filter.clone()),
         stop_receiver.clone(),
     )
-    .await;
+    .await?;
 
     let mut stop_receiver_clone = stop_receiver.clone();
     task_futures.push(tokio::task::spawn(async move {
@@ -871,6 +1155,20 @@ async fn add_state_keeper_to_task_futures(
     task_futures.push(tokio::spawn(
         state_keeper.run_fee_address_migration(state_keeper_pool),
     ));
+    let control = StateKeeperControl::default();
+    let state_keeper = state_keeper.with_control(control.clone());
+    app_health.insert_component(state_keeper.health_check());
+    if let Some(admin_bind_addr) = admin_bind_addr {
+        let admin_token =
+            admin_token.context("`admin_token` must be set to run state keeper admin server")?;
+        task_futures.push(tokio::spawn(run_admin_server(
+            admin_bind_addr,
+            admin_token,
+            control,
+            tx_filter,
+            stop_receiver.clone(),
+        )));
+    }
     task_futures.push(tokio::spawn(state_keeper.run()));
 
     let mempool_fetcher_pool = pool_builder
@@ -879,21 +1177,25 @@ async fn add_state_keeper_to_task_futures(
         .context("failed to build mempool_fetcher_pool")?;
     let mempool_fetcher = MempoolFetcher::new(
         mempool,
-        batch_fee_input_provider,
+        mempool_fee_input_provider,
         mempool_config,
         mempool_fetcher_pool,
-    );
+    )
+    .with_mempool_size_sender(mempool_size_sender);
     let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(stop_receiver));
     task_futures.push(mempool_fetcher_handle);
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn add_trees_to_task_futures(
     configs: &GeneralConfig,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
     app_health: &AppHealthCheck,
     components: &[Component],
     store_factory: &ObjectStoreFactory,
+    l1_client: Arc<dyn EthInterface>,
+    diamond_proxy_addr: Address,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     if !components.contains(&Component::Tree) {
@@ -923,6 +1225,18 @@ async fn add_trees_to_task_futures(
         MerkleTreeMode::Lightweight => None,
         MerkleTreeMode::Full => Some(store_factory.create_store().await),
     };
+    // Tree snapshots are exported next to storage log snapshots, so that they are available to nodes
+    // recovering from a snapshot.
+    let snapshot_store = if db_config.merkle_tree.snapshot_export_interval().is_some() {
+        let snapshots_store_config = snapshots_object_store_config(configs)?;
+        Some(
+            ObjectStoreFactory::new(snapshots_store_config)
+                .create_store()
+                .await,
+        )
+    } else {
+        None
+    };
 
     run_tree(
         task_futures,
@@ -932,6 +1246,9 @@ async fn add_trees_to_task_futures(
         api_config,
         &operation_config,
         object_store,
+        snapshot_store,
+        l1_client,
+        diamond_proxy_addr,
         stop_receiver,
     )
     .await
@@ -947,6 +1264,9 @@ async fn run_tree(
     api_config: Option<&MerkleTreeApiConfig>,
     operation_manager: &OperationsManagerConfig,
     object_store: Option<Arc<dyn ObjectStore>>,
+    snapshot_store: Option<Arc<dyn ObjectStore>>,
+    l1_client: Arc<dyn EthInterface>,
+    diamond_proxy_addr: Address,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let started_at = Instant::now();
@@ -963,23 +1283,52 @@ async fn run_tree(
         .context("failed initializing metadata_calculator")?;
     if let Some(api_config) = api_config {
         let address = (Ipv4Addr::UNSPECIFIED, api_config.port).into();
-        let tree_reader = metadata_calculator.tree_reader();
+        let tree_reader_pool = metadata_calculator.tree_reader_pool(api_config.reader_pool_size);
         let stop_receiver = stop_receiver.clone();
-        task_futures.push(tokio::spawn(async move {
-            tree_reader
-                .wait()
-                .await
-                .run_api_server(address, stop_receiver)
-                .await
-        }));
+        task_futures.push(tokio::spawn(
+            tree_reader_pool.run_api_server(address, stop_receiver),
+        ));
     }
 
     let tree_health_check = metadata_calculator.tree_health_check();
     app_health.insert_component(tree_health_check);
+    if let Some(follower_health_check) = metadata_calculator.proof_data_follower_health_check() {
+        app_health.insert_component(follower_health_check);
+    }
     let pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
         .build()
         .await
         .context("failed to build connection pool")?;
+    if let Some(checker_config) = MerkleTreeConsistencyCheckerConfig::new(merkle_tree_config) {
+        let checker_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build connection pool for tree consistency checker")?;
+        let checker = metadata_calculator.consistency_checker(checker_config, checker_pool);
+        task_futures.push(tokio::spawn(checker.run(stop_receiver.clone())));
+    }
+    let exporter_config = MerkleTreeSnapshotExporterConfig::new(merkle_tree_config);
+    if let (Some(exporter_config), Some(snapshot_store)) = (exporter_config, snapshot_store) {
+        let exporter_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build connection pool for tree snapshot exporter")?;
+        let exporter =
+            metadata_calculator.snapshot_exporter(exporter_config, exporter_pool, snapshot_store);
+        task_futures.push(tokio::spawn(exporter.run(stop_receiver.clone())));
+    }
+    let l1_checker_config =
+        MerkleTreeL1RootCheckerConfig::new(merkle_tree_config, diamond_proxy_addr);
+    if let Some(l1_checker_config) = l1_checker_config {
+        let l1_checker_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build connection pool for tree L1 root checker")?;
+        let l1_checker =
+            metadata_calculator.l1_root_checker(l1_checker_config, l1_checker_pool, l1_client);
+        app_health.insert_component(l1_checker.health_check());
+        task_futures.push(tokio::spawn(l1_checker.run(stop_receiver.clone())));
+    }
     let tree_task = tokio::spawn(metadata_calculator.run(pool, stop_receiver));
     task_futures.push(tree_task);
 
@@ -1062,6 +1411,101 @@ async fn add_house_keeper_to_task_futures(
     let task = l1_batch_metrics_reporter.run(stop_receiver.clone());
     task_futures.push(tokio::spawn(task));
 
+    if house_keeper_config.object_store_archiver_enabled() {
+        let object_store_config = configs
+            .prover_config
+            .clone()
+            .context("Prover")?
+            .object_store
+            .context("object_store_config")?;
+        let snapshots_store_config = snapshots_object_store_config(configs)?;
+        for (name, config) in [
+            ("object store", &object_store_config),
+            ("snapshots object store", &snapshots_store_config),
+        ] {
+            if !config.is_archiving_configured() {
+                tracing::warn!(
+                    "Object store archiver is enabled, but cold storage is not configured for the {name}; \
+                     archiving will have no effect"
+                );
+            }
+        }
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        let snapshots_store = ObjectStoreFactory::new(snapshots_store_config)
+            .create_store()
+            .await;
+        // The archiver persists its progress, so it cannot use the replica pool.
+        let object_store_archiver_pool =
+            ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build object_store_archiver_pool")?;
+        let object_store_archiver = ObjectStoreArchiver::new(
+            object_store_archiver_pool,
+            blob_store,
+            snapshots_store,
+            Duration::from_secs(
+                house_keeper_config
+                    .object_store_archiver_retention_secs
+                    .unwrap(),
+            ),
+            house_keeper_config
+                .object_store_archiver_interval_ms
+                .unwrap(),
+        );
+        let task = object_store_archiver.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
+    if house_keeper_config.object_store_gc_enabled() {
+        let object_store_config = configs
+            .prover_config
+            .clone()
+            .context("Prover")?
+            .object_store
+            .context("object_store_config")?;
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        let snapshots_store = ObjectStoreFactory::new(snapshots_object_store_config(configs)?)
+            .create_store()
+            .await;
+        // The collector removes snapshot metadata, so it cannot use the replica pool.
+        let object_store_gc_pool = ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build object_store_gc_pool")?;
+        let object_store_gc = ObjectStoreGarbageCollector::new(
+            object_store_gc_pool,
+            blob_store,
+            snapshots_store,
+            Duration::from_secs(house_keeper_config.object_store_gc_retention_secs.unwrap()),
+            house_keeper_config.object_store_gc_interval_ms.unwrap(),
+        );
+        let task = object_store_gc.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
+    if house_keeper_config.storage_logs_partition_rotation_enabled() {
+        // Partitions are created via DDL statements, which cannot be executed on a replica.
+        let partition_rotator_pool =
+            ConnectionPool::<Core>::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build partition_rotator_pool")?;
+        let partition_rotator = StorageLogsPartitionRotator::new(
+            partition_rotator_pool,
+            house_keeper_config.storage_logs_partition_size.unwrap(),
+            house_keeper_config
+                .storage_logs_partition_rotation_interval_ms
+                .unwrap(),
+        );
+        let task = partition_rotator.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
     // All FRI Prover related components are configured below.
     let fri_prover_config = configs.prover_config.clone().context("fri_prover_config")?;
     let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
@@ -1157,6 +1601,16 @@ async fn add_house_keeper_to_task_futures(
     Ok(())
 }
 
+/// Returns the configuration of the object store containing storage snapshots. It's separate from the object store
+/// with L1 batch artifacts since snapshots are usually made public.
+fn snapshots_object_store_config(configs: &GeneralConfig) -> anyhow::Result<ObjectStoreConfig> {
+    configs
+        .snapshot_creator
+        .as_ref()
+        .and_then(|config| config.object_store.clone())
+        .context("snapshots object store config")
+}
+
 fn build_storage_caches(
     rpc_config: &Web3JsonRpcConfig,
     replica_connection_pool: &ConnectionPool<Core>,
@@ -1177,17 +1631,20 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
     state_keeper_config: &StateKeeperConfig,
+    mempool_durability: MempoolDurability,
     replica_pool: ConnectionPool<Core>,
     master_pool: ConnectionPool<Core>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
-) -> (TxSender, VmConcurrencyBarrier) {
-    let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
-    let master_pool_sink = MasterPoolSink::new(master_pool);
+) -> anyhow::Result<(TxSender, VmConcurrencyBarrier)> {
+    let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone())
+        .context("failed creating sequencer sealer")?;
+    let master_pool_sink = MasterPoolSink::new(master_pool).with_durability(mempool_durability);
     let tx_sender_builder = TxSenderBuilder::new(
         tx_sender_config.clone(),
         replica_pool.clone(),
@@ -1209,7 +1666,7 @@ async fn build_tx_sender(
             storage_caches,
         )
         .await;
-    (tx_sender, vm_barrier)
+    Ok((tx_sender, vm_barrier))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1219,6 +1676,7 @@ async fn run_http_api(
     postgres_config: &PostgresConfig,
     tx_sender_config: &TxSenderConfig,
     state_keeper_config: &StateKeeperConfig,
+    mempool_durability: MempoolDurability,
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
     master_connection_pool: ConnectionPool<Core>,
@@ -1227,21 +1685,24 @@ async fn run_http_api(
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    sync_block_signing_key: Option<H256>,
+    snapshot_header_signing_key: Option<H256>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
         state_keeper_config,
+        mempool_durability,
         replica_connection_pool.clone(),
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
     )
-    .await;
+    .await?;
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     if with_debug_namespace {
-        namespaces.push(Namespace::Debug)
+        namespaces.extend([Namespace::Debug, Namespace::Trace]);
     }
     namespaces.push(Namespace::Snapshots);
 
@@ -1260,11 +1721,29 @@ async fn run_http_api(
             .with_tx_sender(tx_sender)
             .with_vm_barrier(vm_barrier)
             .enable_api_namespaces(namespaces);
+    if let Some(request_deadline) = api_config.web3_json_rpc.request_deadline() {
+        api_builder = api_builder.with_request_timeout(request_deadline);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.method_requests_per_minute_limit {
+        api_builder = api_builder.with_method_requests_per_minute_limit(limit);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.client_requests_per_minute_limit {
+        api_builder = api_builder.with_client_requests_per_minute_limit(limit);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.max_concurrent_requests {
+        api_builder = api_builder.with_max_concurrent_requests(limit);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    if let Some(private_key) = sync_block_signing_key {
+        api_builder = api_builder.with_sync_block_signing_key(private_key);
+    }
+    if let Some(private_key) = snapshot_header_signing_key {
+        api_builder = api_builder.with_snapshot_header_signing_key(private_key);
+    }
 
     let server_handles = api_builder
         .build()
@@ -1283,6 +1762,7 @@ async fn run_ws_api(
     postgres_config: &PostgresConfig,
     tx_sender_config: &TxSenderConfig,
     state_keeper_config: &StateKeeperConfig,
+    mempool_durability: MempoolDurability,
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -1290,17 +1770,20 @@ async fn run_ws_api(
     replica_connection_pool: ConnectionPool<Core>,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    sync_block_signing_key: Option<H256>,
+    snapshot_header_signing_key: Option<H256>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
         state_keeper_config,
+        mempool_durability,
         replica_connection_pool.clone(),
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
     )
-    .await;
+    .await?;
     let last_miniblock_pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
         .build()
         .await
@@ -1326,11 +1809,29 @@ async fn run_ws_api(
             .with_tx_sender(tx_sender)
             .with_vm_barrier(vm_barrier)
             .enable_api_namespaces(namespaces);
+    if let Some(request_deadline) = api_config.web3_json_rpc.request_deadline() {
+        api_builder = api_builder.with_request_timeout(request_deadline);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.method_requests_per_minute_limit {
+        api_builder = api_builder.with_method_requests_per_minute_limit(limit);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.client_requests_per_minute_limit {
+        api_builder = api_builder.with_client_requests_per_minute_limit(limit);
+    }
+    if let Some(limit) = api_config.web3_json_rpc.max_concurrent_requests {
+        api_builder = api_builder.with_max_concurrent_requests(limit);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    if let Some(private_key) = sync_block_signing_key {
+        api_builder = api_builder.with_sync_block_signing_key(private_key);
+    }
+    if let Some(private_key) = snapshot_header_signing_key {
+        api_builder = api_builder.with_snapshot_header_signing_key(private_key);
+    }
 
     let server_handles = api_builder
         .build()
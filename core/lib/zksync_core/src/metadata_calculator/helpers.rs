@@ -1,10 +1,12 @@
 //! Various helpers for the metadata calculator.
 
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     future,
     future::Future,
+    ops,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
@@ -12,16 +14,17 @@ use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
 #[cfg(test)]
 use tokio::sync::mpsc;
-use tokio::sync::watch;
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
     recovery::MerkleTreeRecovery,
+    snapshot::{TreeSnapshotError, TreeSnapshotExporter},
     Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
 };
-use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
+use zksync_storage::{ColumnFamilyOptions, RocksDB, RocksDBOptions, StalledWritesRetries};
 use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
@@ -66,6 +69,7 @@ pub(super) async fn create_db(
     memtable_capacity: usize,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    column_families: HashMap<String, ColumnFamilyOptions>,
 ) -> anyhow::Result<RocksDBWrapper> {
     tokio::task::spawn_blocking(move || {
         create_db_sync(
@@ -74,6 +78,7 @@ pub(super) async fn create_db(
             memtable_capacity,
             stalled_writes_timeout,
             multi_get_chunk_size,
+            column_families,
         )
     })
     .await
@@ -86,11 +91,12 @@ fn create_db_sync(
     memtable_capacity: usize,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    column_families: HashMap<String, ColumnFamilyOptions>,
 ) -> anyhow::Result<RocksDBWrapper> {
     tracing::info!(
         "Initializing Merkle tree database at `{path}` with {multi_get_chunk_size} multi-get chunk size, \
          {block_cache_capacity}B block cache, {memtable_capacity}B memtable capacity, \
-         {stalled_writes_timeout:?} stalled writes timeout",
+         {stalled_writes_timeout:?} stalled writes timeout, column family options {column_families:?}",
         path = path.display()
     );
 
@@ -101,6 +107,7 @@ fn create_db_sync(
             large_memtable_capacity: Some(memtable_capacity),
             stalled_writes_retries: StalledWritesRetries::new(stalled_writes_timeout),
             max_open_files: None,
+            column_families,
         },
     )?;
     if cfg!(test) {
@@ -125,20 +132,29 @@ fn create_db_sync(
 pub(super) struct AsyncTree {
     inner: Option<ZkSyncTree>,
     mode: MerkleTreeMode,
+    hashing_thread_count: Option<usize>,
 }
 
 impl AsyncTree {
     const INCONSISTENT_MSG: &'static str =
         "`AsyncTree` is in inconsistent state, which could occur after one of its async methods was cancelled or returned an error";
 
-    pub fn new(db: RocksDBWrapper, mode: MerkleTreeMode) -> Self {
-        let tree = match mode {
+    pub fn new(
+        db: RocksDBWrapper,
+        mode: MerkleTreeMode,
+        hashing_thread_count: Option<usize>,
+    ) -> Self {
+        let mut tree = match mode {
             MerkleTreeMode::Full => ZkSyncTree::new(db),
             MerkleTreeMode::Lightweight => ZkSyncTree::new_lightweight(db),
         };
+        if let Some(thread_count) = hashing_thread_count {
+            tree.use_dedicated_thread_pool(thread_count);
+        }
         Self {
             inner: Some(tree),
             mode,
+            hashing_thread_count,
         }
     }
 
@@ -239,6 +255,14 @@ impl AsyncTreeReader {
         .unwrap()
     }
 
+    /// Returns the root hash of the tree after processing the specified L1 batch, or `None` if the tree
+    /// doesn't have the corresponding version.
+    pub async fn l1_batch_root_hash(self, l1_batch_number: L1BatchNumber) -> Option<H256> {
+        tokio::task::spawn_blocking(move || self.inner.l1_batch_root_hash(l1_batch_number))
+            .await
+            .unwrap()
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
@@ -248,6 +272,23 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    /// Creates a reader for a point-in-time snapshot of the tree. The snapshot is not affected by subsequent
+    /// tree updates (including reverts and pruning).
+    fn snapshot(&self) -> Self {
+        Self {
+            inner: self.inner.snapshot(),
+            mode: self.mode,
+        }
+    }
+
+    pub(super) fn snapshot_exporter(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        chunk_size: usize,
+    ) -> Result<TreeSnapshotExporter<RocksDBWrapper>, TreeSnapshotError> {
+        self.inner.snapshot_exporter(l1_batch_number, chunk_size)
+    }
 }
 
 /// Lazily initialized [`AsyncTreeReader`].
@@ -274,21 +315,131 @@ impl LazyAsyncTreeReader {
     }
 }
 
+/// Pool of tree readers allowing to serve concurrent reads while the metadata calculator updates the tree.
+/// Each acquired reader works with its own RocksDB snapshot, so it observes a consistent tree state
+/// for its entire lifetime. The pool limits the number of concurrently alive snapshots, since each snapshot
+/// prevents RocksDB from dropping the data overwritten after the snapshot was taken.
+#[derive(Debug, Clone)]
+pub struct TreeReaderPool {
+    /// Shared among all pool clones, so that closing the pool releases the tree for all of them.
+    tree_reader: Arc<Mutex<Option<watch::Receiver<Option<AsyncTreeReader>>>>>,
+    permits: Arc<Semaphore>,
+    size: u32,
+}
+
+impl TreeReaderPool {
+    /// The pool `size` is expected to be checked by
+    /// [`MerkleTreeApiConfig::validate()`](zksync_config::configs::api::MerkleTreeApiConfig::validate()).
+    pub(super) fn new(tree_reader: watch::Receiver<Option<AsyncTreeReader>>, size: usize) -> Self {
+        debug_assert!(size > 0, "Tree reader pool size must be positive");
+        let size = u32::try_from(size).expect("tree reader pool size is not validated");
+        Self {
+            tree_reader: Arc::new(Mutex::new(Some(tree_reader))),
+            permits: Arc::new(Semaphore::new(size as usize)),
+            size,
+        }
+    }
+
+    /// Acquires a reader from the pool, waiting for one to become available if necessary. Returns `None`
+    /// if the tree is not initialized yet (or is already released by the metadata calculator), or if the pool
+    /// is closed.
+    pub async fn acquire(&self) -> Option<PooledTreeReader> {
+        let reader = self
+            .tree_reader
+            .lock()
+            .unwrap()
+            .as_ref()?
+            .borrow()
+            .clone()?;
+        let permit = self.permits.clone().acquire_owned().await.ok()?;
+        // Creating a snapshot may read the tree manifest from RocksDB, so it's offloaded to a blocking thread.
+        let reader = tokio::task::spawn_blocking(move || reader.snapshot())
+            .await
+            .unwrap();
+        Some(PooledTreeReader {
+            reader,
+            _permit: permit,
+        })
+    }
+
+    /// Closes the pool (for all its clones) and waits until all acquired readers are dropped, so that they don't
+    /// hold RocksDB snapshots afterwards. Once closed, the pool no longer references the tree, so the tree RocksDB
+    /// instance can terminate.
+    pub async fn close(&self) {
+        if let Ok(permits) = self.permits.acquire_many(self.size).await {
+            permits.forget();
+        }
+        self.permits.close();
+        self.tree_reader.lock().unwrap().take();
+    }
+}
+
+/// Tree reader acquired from a [`TreeReaderPool`]. Returned to the pool when dropped.
+#[derive(Debug)]
+pub struct PooledTreeReader {
+    reader: AsyncTreeReader,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl ops::Deref for PooledTreeReader {
+    type Target = AsyncTreeReader;
+
+    fn deref(&self) -> &Self::Target {
+        &self.reader
+    }
+}
+
+/// Receiver of the Merkle tree lag, i.e., the number of L1 batches sealed in Postgres, but not yet processed
+/// by the tree.
+#[derive(Debug, Clone)]
+pub struct TreeLagReceiver(pub(super) watch::Receiver<Option<u32>>);
+
+impl TreeLagReceiver {
+    /// Returns the current lag, or `None` if it's unknown (e.g., if the tree is not initialized yet).
+    pub fn get(&self) -> Option<u32> {
+        *self.0.borrow()
+    }
+
+    /// Waits until the lag changes and returns the updated value. Returns `None` if the metadata calculator
+    /// has stopped.
+    pub async fn changed(&mut self) -> Option<Option<u32>> {
+        self.0.changed().await.ok()?;
+        Some(*self.0.borrow_and_update())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn mock(lag: Option<u32>) -> (watch::Sender<Option<u32>>, Self) {
+        let (sender, receiver) = watch::channel(lag);
+        (sender, Self(receiver))
+    }
+}
+
 /// Async wrapper for [`MerkleTreeRecovery`].
 #[derive(Debug, Default)]
 pub(super) struct AsyncTreeRecovery {
     inner: Option<MerkleTreeRecovery<RocksDBWrapper>>,
     mode: MerkleTreeMode,
+    hashing_thread_count: Option<usize>,
 }
 
 impl AsyncTreeRecovery {
     const INCONSISTENT_MSG: &'static str =
         "`AsyncTreeRecovery` is in inconsistent state, which could occur after one of its async methods was cancelled";
 
-    pub fn new(db: RocksDBWrapper, recovered_version: u64, mode: MerkleTreeMode) -> Self {
+    pub fn new(
+        db: RocksDBWrapper,
+        recovered_version: u64,
+        mode: MerkleTreeMode,
+        hashing_thread_count: Option<usize>,
+    ) -> Self {
+        let mut recovery = MerkleTreeRecovery::new(db, recovered_version);
+        if let Some(thread_count) = hashing_thread_count {
+            recovery.use_dedicated_thread_pool(thread_count);
+        }
         Self {
-            inner: Some(MerkleTreeRecovery::new(db, recovered_version)),
+            inner: Some(recovery),
             mode,
+            hashing_thread_count,
         }
     }
 
@@ -337,7 +488,7 @@ impl AsyncTreeRecovery {
         let db = tokio::task::spawn_blocking(|| tree.finalize())
             .await
             .unwrap();
-        AsyncTree::new(db, self.mode)
+        AsyncTree::new(db, self.mode, self.hashing_thread_count)
     }
 }
 
@@ -348,6 +499,7 @@ pub(super) enum GenericAsyncTree {
     Empty {
         db: RocksDBWrapper,
         mode: MerkleTreeMode,
+        hashing_thread_count: Option<usize>,
     },
     /// The tree during recovery.
     Recovering(AsyncTreeRecovery),
@@ -356,15 +508,28 @@ pub(super) enum GenericAsyncTree {
 }
 
 impl GenericAsyncTree {
-    pub async fn new(db: RocksDBWrapper, mode: MerkleTreeMode) -> Self {
+    pub async fn new(
+        db: RocksDBWrapper,
+        mode: MerkleTreeMode,
+        hashing_thread_count: Option<usize>,
+    ) -> Self {
         tokio::task::spawn_blocking(move || {
             let Some(manifest) = db.manifest() else {
-                return Self::Empty { db, mode };
+                return Self::Empty {
+                    db,
+                    mode,
+                    hashing_thread_count,
+                };
             };
             if let Some(version) = manifest.recovered_version() {
-                Self::Recovering(AsyncTreeRecovery::new(db, version, mode))
+                Self::Recovering(AsyncTreeRecovery::new(
+                    db,
+                    version,
+                    mode,
+                    hashing_thread_count,
+                ))
             } else {
-                Self::Ready(AsyncTree::new(db, mode))
+                Self::Ready(AsyncTree::new(db, mode, hashing_thread_count))
             }
         })
         .await
@@ -441,6 +606,19 @@ impl L1BatchWithLogs {
             MerkleTreeMode::Full => {
                 let protective_reads_latency =
                     METRICS.start_load_stage(LoadChangesStage::LoadProtectiveReads);
+                let are_protective_reads_pending = storage
+                    .storage_logs_dedup_dal()
+                    .are_protective_reads_pending(l1_batch_number)
+                    .await
+                    .context("cannot check whether protective reads are pending")?;
+                if are_protective_reads_pending {
+                    // Protective reads are persisted asynchronously by the protective reads writer;
+                    // the batch will be processed once they are persisted.
+                    tracing::debug!(
+                        "Protective reads for L1 batch #{l1_batch_number} are not persisted yet"
+                    );
+                    return Ok(None);
+                }
                 let protective_reads = storage
                     .storage_logs_dedup_dal()
                     .get_protective_reads_for_l1_batch(l1_batch_number)
@@ -657,10 +835,11 @@ mod tests {
             16 << 20,       // 16 MiB,
             Duration::ZERO, // writes should never be stalled in tests
             500,
+            HashMap::new(),
         )
         .await
         .unwrap();
-        AsyncTree::new(db, MerkleTreeMode::Full)
+        AsyncTree::new(db, MerkleTreeMode::Full, None)
     }
 
     async fn assert_log_equivalence(
@@ -0,0 +1,283 @@
+//! Verification of Merkle tree root hashes against state roots committed on L1.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_config::configs::database::MerkleTreeConfig;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_eth_client::EthInterface;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{web3::ethabi, Address, L1BatchNumber, H256, U256};
+
+use super::{metrics::L1_ROOT_CHECK_METRICS, LazyAsyncTreeReader};
+
+/// Configuration of [`MerkleTreeL1RootChecker`].
+#[derive(Debug, Clone)]
+pub struct MerkleTreeL1RootCheckerConfig {
+    /// Interval between checks for newly committed L1 batches.
+    pub interval: Duration,
+    /// Address of the diamond proxy contract emitting `BlockCommit` events.
+    pub diamond_proxy_addr: Address,
+}
+
+impl MerkleTreeL1RootCheckerConfig {
+    /// Returns `None` if L1 root checks are disabled in the provided tree config.
+    pub fn new(config: &MerkleTreeConfig, diamond_proxy_addr: Address) -> Option<Self> {
+        Some(Self {
+            interval: config.l1_root_check_interval()?,
+            diamond_proxy_addr,
+        })
+    }
+}
+
+/// Health details reported by [`MerkleTreeL1RootChecker`].
+#[derive(Debug, Default, Serialize)]
+struct L1RootCheckerDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_checked_l1_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mismatched_l1_batches: Vec<L1BatchNumber>,
+}
+
+impl L1RootCheckerDetails {
+    fn health(&self) -> Health {
+        let status = if self.mismatched_l1_batches.is_empty() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::Affected
+        };
+        Health::from(status).with_details(self)
+    }
+}
+
+/// Task comparing root hashes of the Merkle tree with state roots committed on L1 for each committed L1 batch,
+/// starting from the last L1 batch committed when the task has started. State roots are taken from `BlockCommit`
+/// events emitted by commit transactions, so the check doesn't rely on any data in Postgres other than
+/// commit transaction hashes.
+///
+/// Mismatches are only reported (via metrics, logs and the health check of the task); the tree
+/// is not modified.
+#[derive(Debug)]
+pub struct MerkleTreeL1RootChecker {
+    config: MerkleTreeL1RootCheckerConfig,
+    pool: ConnectionPool<Core>,
+    tree_reader: LazyAsyncTreeReader,
+    l1_client: Arc<dyn EthInterface>,
+    block_commit_event: ethabi::Event,
+    health_updater: HealthUpdater,
+    details: L1RootCheckerDetails,
+    /// Next L1 batch to check. Initialized lazily from Postgres.
+    next_l1_batch: Option<L1BatchNumber>,
+}
+
+impl MerkleTreeL1RootChecker {
+    pub(super) fn new(
+        config: MerkleTreeL1RootCheckerConfig,
+        pool: ConnectionPool<Core>,
+        tree_reader: LazyAsyncTreeReader,
+        l1_client: Arc<dyn EthInterface>,
+    ) -> Self {
+        let block_commit_event = zksync_contracts::zksync_contract()
+            .event("BlockCommit")
+            .expect("`BlockCommit` event not found for zkSync L1 contract")
+            .clone();
+        Self {
+            config,
+            pool,
+            tree_reader,
+            l1_client,
+            block_commit_event,
+            health_updater: ReactiveHealthCheck::new("tree_l1_root_checker").1,
+            details: L1RootCheckerDetails::default(),
+            next_l1_batch: None,
+        }
+    }
+
+    /// Returns a health check for this checker.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn commit_tx_hash(
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        let Some(l1_batch) = storage
+            .blocks_dal()
+            .get_storage_l1_batch(l1_batch_number)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(commit_tx_id) = l1_batch.eth_commit_tx_id else {
+            return Ok(None);
+        };
+        Ok(storage
+            .eth_sender_dal()
+            .get_confirmed_tx_hash_by_eth_tx_id(commit_tx_id as u32)
+            .await?)
+    }
+
+    /// Returns the state root committed for the specified L1 batch by the commit transaction, or `None`
+    /// if the transaction failed or doesn't commit the batch. All returned errors are considered transient.
+    async fn committed_root_hash(
+        &self,
+        commit_tx_hash: H256,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        let commit_tx_status = self
+            .l1_client
+            .get_tx_status(commit_tx_hash, "tree_l1_root_checker")
+            .await?
+            .with_context(|| format!("receipt for tx {commit_tx_hash:?} not found on L1"))?;
+        if !commit_tx_status.success {
+            return Ok(None);
+        }
+
+        let expected_batch_number = U256::from(l1_batch_number.0);
+        let root_hash = commit_tx_status
+            .receipt
+            .logs
+            .into_iter()
+            .filter(|log| log.address == self.config.diamond_proxy_addr)
+            .find_map(|log| {
+                let parsed_log = self
+                    .block_commit_event
+                    .parse_log_whole(ethabi::RawLog {
+                        topics: log.topics,
+                        data: log.data.0,
+                    })
+                    .ok()?;
+                let (mut batch_number, mut batch_hash) = (None, None);
+                for param in parsed_log.params {
+                    match param.name.as_str() {
+                        "batchNumber" => batch_number = param.value.into_uint(),
+                        "batchHash" => batch_hash = param.value.into_fixed_bytes(),
+                        _ => { /* do nothing */ }
+                    }
+                }
+                if batch_number? == expected_batch_number {
+                    Some(H256::from_slice(&batch_hash?))
+                } else {
+                    None
+                }
+            });
+        Ok(root_hash)
+    }
+
+    fn report_check(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        tree_root_hash: H256,
+        l1_root_hash: Option<H256>,
+    ) {
+        L1_ROOT_CHECK_METRICS
+            .checked_l1_batch
+            .set(l1_batch_number.0.into());
+        self.details.last_checked_l1_batch = Some(l1_batch_number);
+
+        match l1_root_hash {
+            Some(hash) if hash == tree_root_hash => {
+                tracing::info!(
+                    "Merkle tree root hash for L1 batch #{l1_batch_number} matches the state root committed on L1"
+                );
+            }
+            Some(hash) => {
+                tracing::error!(
+                    "Merkle tree root hash for L1 batch #{l1_batch_number} ({tree_root_hash:?}) differs \
+                     from the state root committed on L1 ({hash:?})"
+                );
+                L1_ROOT_CHECK_METRICS.mismatches.inc();
+                self.details.mismatched_l1_batches.push(l1_batch_number);
+            }
+            None => {
+                tracing::error!(
+                    "Commit transaction for L1 batch #{l1_batch_number} doesn't commit a state root for the batch"
+                );
+                L1_ROOT_CHECK_METRICS.mismatches.inc();
+                self.details.mismatched_l1_batches.push(l1_batch_number);
+            }
+        }
+        self.health_updater.update(self.details.health());
+    }
+
+    /// Checks all L1 batches committed on L1 and processed by the tree since the previous check.
+    pub(super) async fn check_committed_l1_batches(&mut self) -> anyhow::Result<()> {
+        let Some(tree_reader) = self.tree_reader.read() else {
+            return Ok(()); // The tree is not initialized yet
+        };
+        let tree_info = tree_reader.clone().info().await;
+        let Some(last_tree_l1_batch) = tree_info.next_l1_batch_number.0.checked_sub(1) else {
+            return Ok(()); // The tree is empty
+        };
+
+        let mut storage = self.pool.connection_tagged("tree_l1_root_checker").await?;
+        let Some(last_committed_l1_batch) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await?
+        else {
+            return Ok(()); // No L1 batches are committed yet
+        };
+        let last_l1_batch = last_committed_l1_batch.min(L1BatchNumber(last_tree_l1_batch));
+        let mut l1_batch_number = *self.next_l1_batch.get_or_insert(last_l1_batch);
+
+        while l1_batch_number <= last_l1_batch {
+            let commit_tx_hash = Self::commit_tx_hash(&mut storage, l1_batch_number).await?;
+            let tree_root_hash = tree_reader
+                .clone()
+                .l1_batch_root_hash(l1_batch_number)
+                .await;
+            // Skip L1 batches without a commit transaction or a tree version (e.g., if the tree was recovered
+            // from a snapshot), since they cannot be checked.
+            if let (Some(commit_tx_hash), Some(tree_root_hash)) = (commit_tx_hash, tree_root_hash) {
+                let l1_root_hash = match self
+                    .committed_root_hash(commit_tx_hash, l1_batch_number)
+                    .await
+                {
+                    Ok(hash) => hash,
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed getting state root committed on L1 for L1 batch #{l1_batch_number}; \
+                             will retry after a delay: {err:#}"
+                        );
+                        break;
+                    }
+                };
+                self.report_check(l1_batch_number, tree_root_hash, l1_root_hash);
+            } else {
+                tracing::debug!("Skipping checking L1 batch #{l1_batch_number} against L1");
+            }
+
+            l1_batch_number += 1;
+            self.next_l1_batch = Some(l1_batch_number);
+        }
+        Ok(())
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting Merkle tree L1 root checker with config {:?}",
+            self.config
+        );
+        self.health_updater.update(self.details.health());
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            self.check_committed_l1_batches().await?;
+
+            // Wait for the next check or the stop signal, whichever comes first.
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree L1 root checker is shutting down");
+        Ok(())
+    }
+}
@@ -3,8 +3,8 @@
 use std::time::{Duration, Instant};
 
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
-    Unit,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics, Unit,
 };
 use zksync_types::block::L1BatchHeader;
 use zksync_utils::time::seconds_since_epoch;
@@ -85,6 +85,13 @@ pub(super) struct MetadataCalculatorMetrics {
     /// The lag can only be positive if Postgres was restored from a backup truncating some
     /// of the batches already processed by the tree.
     pub backup_lag: Gauge<u64>,
+    /// Number of L1 batches sealed in Postgres, but not yet processed by the Merkle tree.
+    pub tree_lag: Gauge<u64>,
+    /// Number of L1 batches processed by the main Merkle tree, but not yet processed by the follower tree
+    /// generating proof data.
+    pub proof_data_lag: Gauge<u64>,
+    /// Number of L1 batches reverted in the Merkle tree on requests of the tree consistency checker.
+    pub reverted_l1_batches: Counter,
     /// Number of zero values that need to be checked for L1 batch of the initial write in the process
     /// of updating the Merkle tree.
     #[metrics(buckets = COUNTS_BUCKETS)]
@@ -101,6 +108,9 @@ pub(super) struct MetadataCalculatorMetrics {
     /// Number of L1 batches applied to the Merkle tree in a single iteration.
     #[metrics(buckets = Buckets::linear(1.0..=20.0, 1.0))]
     blocks_batch: Histogram<usize>,
+    /// Number of L1 batches, updates for which were written to RocksDB in a single write batch.
+    #[metrics(buckets = Buckets::exponential(1.0..=1_024.0, 2.0))]
+    pub l1_batches_per_commit: Histogram<usize>,
     /// Latency of updating the Merkle tree per stage.
     #[metrics(buckets = Buckets::LATENCIES)]
     update_tree_latency_stage: Family<TreeUpdateStage, Histogram<Duration>>,
@@ -205,3 +215,69 @@ pub(super) struct MetadataCalculatorRecoveryMetrics {
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<MetadataCalculatorRecoveryMetrics> =
     vise::Global::new();
+
+/// Kind of divergence between the Merkle tree and Postgres detected by the tree consistency checker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// Tree root hash differs from the one in Postgres.
+    RootHash,
+    /// Leaf index of a tree entry differs from the enumeration index in Postgres.
+    LeafIndex,
+    /// Value of a tree entry differs from the one in Postgres.
+    Value,
+    /// Merkle proof for a tree entry doesn't verify against the tree root hash.
+    MerkleProof,
+}
+
+/// Metrics for the Merkle tree consistency checker.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_consistency")]
+pub(super) struct TreeConsistencyMetrics {
+    /// Last L1 batch checked by the consistency checker.
+    pub checked_l1_batch: Gauge<u64>,
+    /// Number of tree leaves checked by the consistency checker.
+    pub checked_leaves: Counter,
+    /// Number of detected divergences between the tree and Postgres.
+    pub divergences: Family<DivergenceKind, Counter>,
+    /// Number of tree reverts requested by the consistency checker.
+    pub repairs: Counter,
+    /// Latency of a single consistency check.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub check_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static CONSISTENCY_METRICS: vise::Global<TreeConsistencyMetrics> = vise::Global::new();
+
+/// Metrics for the Merkle tree snapshot exporter.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_snapshot_export")]
+pub(super) struct TreeSnapshotExportMetrics {
+    /// Last L1 batch for which a tree snapshot was exported.
+    pub exported_l1_batch: Gauge<u64>,
+    /// Number of exported tree snapshot chunks.
+    pub exported_chunks: Counter,
+    /// Latency of exporting a single tree snapshot.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub export_latency: Histogram<Duration>,
+    /// Number of snapshot exports failed with a transient error and retried later.
+    pub failed_exports: Counter,
+}
+
+#[vise::register]
+pub(super) static SNAPSHOT_EXPORT_METRICS: vise::Global<TreeSnapshotExportMetrics> =
+    vise::Global::new();
+
+/// Metrics for checking Merkle tree root hashes against state roots committed on L1.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_l1_root_check")]
+pub(super) struct TreeL1RootCheckMetrics {
+    /// Last L1 batch checked against L1.
+    pub checked_l1_batch: Gauge<u64>,
+    /// Number of L1 batches for which the tree root hash differs from the state root committed on L1.
+    pub mismatches: Counter,
+}
+
+#[vise::register]
+pub(super) static L1_ROOT_CHECK_METRICS: vise::Global<TreeL1RootCheckMetrics> = vise::Global::new();
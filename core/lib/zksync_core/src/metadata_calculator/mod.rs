@@ -2,32 +2,52 @@
 //! stores them in the DB.
 
 use std::{
+    collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
-use tokio::sync::watch;
+use futures::future;
+use tokio::sync::{mpsc, watch};
 use zksync_config::configs::{
     chain::OperationsManagerConfig,
     database::{MerkleTreeConfig, MerkleTreeMode},
 };
 use zksync_dal::{ConnectionPool, Core};
+use zksync_eth_client::EthInterface;
 use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
 use zksync_object_store::ObjectStore;
+use zksync_storage::ColumnFamilyOptions;
+use zksync_types::L1BatchNumber;
 
-pub use self::helpers::LazyAsyncTreeReader;
 pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
     helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth},
-    updater::TreeUpdater,
+    updater::{TreeUpdater, TreeUpdaterRole},
 };
+pub use self::{
+    helpers::{LazyAsyncTreeReader, PooledTreeReader, TreeLagReceiver, TreeReaderPool},
+    l1_root_checker::{MerkleTreeL1RootChecker, MerkleTreeL1RootCheckerConfig},
+    snapshot::{
+        import_tree_snapshot, MerkleTreeSnapshotExporter, MerkleTreeSnapshotExporterConfig,
+        TreeSnapshotHeader,
+    },
+    tree_checker::{
+        DivergenceKind, MerkleTreeConsistencyChecker, MerkleTreeConsistencyCheckerConfig,
+        TreeConsistencyReport, TreeDivergence,
+    },
+};
+use crate::utils::rocksdb_column_families;
 
 mod helpers;
+mod l1_root_checker;
 mod metrics;
 mod recovery;
+mod snapshot;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tree_checker;
 mod updater;
 
 /// Configuration of [`MetadataCalculator`].
@@ -41,6 +61,9 @@ pub struct MetadataCalculatorConfig {
     pub delay_interval: Duration,
     /// Maximum number of L1 batches to get from Postgres on a single update iteration.
     pub max_l1_batches_per_iter: usize,
+    /// Maximum number of L1 batches, updates for which are accumulated in memory and written to RocksDB
+    /// in a single write batch while the tree is catching up.
+    pub max_batches_per_commit: usize,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     pub multi_get_chunk_size: usize,
@@ -51,6 +74,17 @@ pub struct MetadataCalculatorConfig {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Number of threads in a dedicated thread pool used to hash tree nodes during updates and recovery
+    /// (0 means the number of logical CPUs). If not set, the global `rayon` thread pool is used.
+    pub hashing_thread_count: Option<usize>,
+    /// Whether to rebuild the tree from Postgres if the tree is empty and there is no snapshot to recover from.
+    pub rebuild_from_postgres: bool,
+    /// Tuning options for column families in the tree RocksDB keyed by the column family name.
+    pub column_families: HashMap<String, ColumnFamilyOptions>,
+    /// Filesystem path to the RocksDB instance that stores the follower tree generating proof data. If set,
+    /// the main tree is run in the lightweight mode regardless of `mode`, and proof data is generated
+    /// by the follower asynchronously. Only supported in the full mode.
+    pub proof_data_follower_path: Option<String>,
 }
 
 impl MetadataCalculatorConfig {
@@ -63,10 +97,15 @@ impl MetadataCalculatorConfig {
             mode: merkle_tree_config.mode,
             delay_interval: operation_config.delay_interval(),
             max_l1_batches_per_iter: merkle_tree_config.max_l1_batches_per_iter,
+            max_batches_per_commit: merkle_tree_config.max_batches_per_commit,
             multi_get_chunk_size: merkle_tree_config.multi_get_chunk_size,
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            hashing_thread_count: merkle_tree_config.hashing_thread_count,
+            rebuild_from_postgres: merkle_tree_config.rebuild_from_postgres,
+            column_families: rocksdb_column_families(&merkle_tree_config.column_families),
+            proof_data_follower_path: merkle_tree_config.proof_data_follower_path.clone(),
         }
     }
 }
@@ -75,10 +114,14 @@ impl MetadataCalculatorConfig {
 pub struct MetadataCalculator {
     config: MetadataCalculatorConfig,
     tree_reader: watch::Sender<Option<AsyncTreeReader>>,
+    tree_lag: watch::Sender<Option<u32>>,
     object_store: Option<Arc<dyn ObjectStore>>,
     delayer: Delayer,
     health_updater: HealthUpdater,
+    follower_health_updater: Option<HealthUpdater>,
     max_l1_batches_per_iter: usize,
+    revert_sender: mpsc::UnboundedSender<L1BatchNumber>,
+    revert_receiver: mpsc::UnboundedReceiver<L1BatchNumber>,
 }
 
 impl MetadataCalculator {
@@ -91,19 +134,38 @@ impl MetadataCalculator {
             config.max_l1_batches_per_iter > 0,
             "Maximum L1 batches per iteration is misconfigured to be 0; please update it to positive value"
         );
+        anyhow::ensure!(
+            config.max_batches_per_commit > 0,
+            "Maximum L1 batches per commit is misconfigured to be 0; please update it to positive value"
+        );
         if matches!(config.mode, MerkleTreeMode::Lightweight) && object_store.is_some() {
             anyhow::bail!(
                 "Cannot run lightweight tree with an object store; the tree won't produce information to be stored in the store"
             );
         }
+        if matches!(config.mode, MerkleTreeMode::Lightweight) {
+            anyhow::ensure!(
+                config.proof_data_follower_path.is_none(),
+                "Proof data follower tree is only supported in the full tree mode"
+            );
+        }
 
         let (_, health_updater) = ReactiveHealthCheck::new("tree");
+        let follower_health_updater = config
+            .proof_data_follower_path
+            .as_ref()
+            .map(|_| ReactiveHealthCheck::new("tree_proof_data_follower").1);
+        let (revert_sender, revert_receiver) = mpsc::unbounded_channel();
         Ok(Self {
             tree_reader: watch::channel(None).0,
+            tree_lag: watch::channel(None).0,
             object_store,
             delayer: Delayer::new(config.delay_interval),
             health_updater,
+            follower_health_updater,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
+            revert_sender,
+            revert_receiver,
             config,
         })
     }
@@ -113,37 +175,110 @@ impl MetadataCalculator {
         self.health_updater.subscribe()
     }
 
+    /// Returns a health check for the follower tree generating proof data, if the follower is enabled.
+    pub fn proof_data_follower_health_check(&self) -> Option<ReactiveHealthCheck> {
+        self.follower_health_updater
+            .as_ref()
+            .map(HealthUpdater::subscribe)
+    }
+
     /// Returns a reference to the tree reader.
     pub fn tree_reader(&self) -> LazyAsyncTreeReader {
         LazyAsyncTreeReader(self.tree_reader.subscribe())
     }
 
+    /// Returns a pool of tree readers serving reads from RocksDB snapshots. At most `size` readers
+    /// may be acquired from the pool at the same time.
+    pub fn tree_reader_pool(&self, size: usize) -> TreeReaderPool {
+        TreeReaderPool::new(self.tree_reader.subscribe(), size)
+    }
+
+    /// Returns a receiver of the tree lag relative to the last sealed L1 batch in Postgres. The lag is updated
+    /// each time the calculator polls Postgres for new L1 batches.
+    pub fn tree_lag(&self) -> TreeLagReceiver {
+        TreeLagReceiver(self.tree_lag.subscribe())
+    }
+
+    /// Creates a consistency checker for the tree managed by this calculator. If repair is enabled
+    /// in the checker `config`, the checker will revert the tree on detected divergences.
+    pub fn consistency_checker(
+        &self,
+        config: MerkleTreeConsistencyCheckerConfig,
+        pool: ConnectionPool<Core>,
+    ) -> MerkleTreeConsistencyChecker {
+        MerkleTreeConsistencyChecker::new(
+            config,
+            pool,
+            self.tree_reader(),
+            self.revert_sender.clone(),
+        )
+    }
+
+    /// Creates a task exporting tree snapshots to the provided object store.
+    pub fn snapshot_exporter(
+        &self,
+        config: MerkleTreeSnapshotExporterConfig,
+        pool: ConnectionPool<Core>,
+        blob_store: Arc<dyn ObjectStore>,
+    ) -> MerkleTreeSnapshotExporter {
+        MerkleTreeSnapshotExporter::new(config, pool, self.tree_reader(), blob_store)
+    }
+
+    /// Creates a task checking tree root hashes against state roots committed on L1.
+    pub fn l1_root_checker(
+        &self,
+        config: MerkleTreeL1RootCheckerConfig,
+        pool: ConnectionPool<Core>,
+        l1_client: Arc<dyn EthInterface>,
+    ) -> MerkleTreeL1RootChecker {
+        MerkleTreeL1RootChecker::new(config, pool, self.tree_reader(), l1_client)
+    }
+
+    /// Mode of the main tree. If proof data is generated by the follower, the main tree only needs to compute
+    /// root hashes.
+    fn main_tree_mode(&self) -> MerkleTreeMode {
+        if self.config.proof_data_follower_path.is_some() {
+            MerkleTreeMode::Lightweight
+        } else {
+            self.config.mode
+        }
+    }
+
     async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
+        self.open_tree(&self.config.db_path, self.main_tree_mode())
+            .await
+    }
 
+    async fn open_tree(
+        &self,
+        db_path: &str,
+        mode: MerkleTreeMode,
+    ) -> anyhow::Result<GenericAsyncTree> {
         let started_at = Instant::now();
         let db = create_db(
-            self.config.db_path.clone().into(),
+            db_path.into(),
             self.config.block_cache_capacity,
             self.config.memtable_capacity,
             self.config.stalled_writes_timeout,
             self.config.multi_get_chunk_size,
+            self.config.column_families.clone(),
         )
         .await
         .with_context(|| {
             format!(
-                "failed opening Merkle tree RocksDB with configuration {:?}",
+                "failed opening Merkle tree RocksDB at `{db_path}` with configuration {:?}",
                 self.config
             )
         })?;
         tracing::info!(
-            "Opened Merkle tree RocksDB with configuration {:?} in {:?}",
+            "Opened Merkle tree RocksDB at `{db_path}` with configuration {:?} in {:?}",
             self.config,
             started_at.elapsed()
         );
 
-        Ok(GenericAsyncTree::new(db, self.config.mode).await)
+        Ok(GenericAsyncTree::new(db, mode, self.config.hashing_thread_count).await)
     }
 
     pub async fn run(
@@ -153,7 +288,12 @@ impl MetadataCalculator {
     ) -> anyhow::Result<()> {
         let tree = self.create_tree().await?;
         let tree = tree
-            .ensure_ready(&pool, &stop_receiver, &self.health_updater)
+            .ensure_ready(
+                &pool,
+                self.config.rebuild_from_postgres,
+                &stop_receiver,
+                &self.health_updater,
+            )
             .await?;
         let Some(tree) = tree else {
             return Ok(()); // recovery was aborted because a stop signal was received
@@ -165,9 +305,90 @@ impl MetadataCalculator {
         );
         self.tree_reader.send_replace(Some(tree_reader));
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let follower_tree = if let Some(follower_path) = &self.config.proof_data_follower_path {
+            Some(self.open_tree(follower_path, MerkleTreeMode::Full).await?)
+        } else {
+            None
+        };
+        // If the follower is enabled, it's responsible for generating proof data, so the main tree doesn't need the store.
+        let (main_object_store, follower_object_store) = if follower_tree.is_some() {
+            (None, self.object_store)
+        } else {
+            (self.object_store, None)
+        };
+
+        let updater = TreeUpdater::new(
+            tree,
+            self.max_l1_batches_per_iter,
+            self.config.max_batches_per_commit,
+            main_object_store,
+            self.revert_receiver,
+            TreeUpdaterRole::Main {
+                tree_lag: self.tree_lag,
+            },
+        );
+        let main_task = updater.loop_updating_tree(
+            self.delayer,
+            &pool,
+            stop_receiver.clone(),
+            self.health_updater,
+        );
+        let Some(follower_tree) = follower_tree else {
+            return main_task.await;
+        };
+
+        let follower_task = Self::run_proof_data_follower(
+            follower_tree,
+            self.max_l1_batches_per_iter,
+            self.config.max_batches_per_commit,
+            follower_object_store,
+            Delayer::new(self.config.delay_interval),
+            &pool,
+            stop_receiver,
+            self.follower_health_updater
+                .context("follower health updater is not initialized")?,
+        );
+        future::try_join(main_task, follower_task).await?;
+        Ok(())
+    }
+
+    /// Runs the follower tree that lags behind the main tree and generates proof data for L1 batches
+    /// already processed by the main tree.
+    async fn run_proof_data_follower(
+        tree: GenericAsyncTree,
+        max_l1_batches_per_iter: usize,
+        max_batches_per_commit: usize,
+        object_store: Option<Arc<dyn ObjectStore>>,
+        delayer: Delayer,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: watch::Receiver<bool>,
+        health_updater: HealthUpdater,
+    ) -> anyhow::Result<()> {
+        health_updater.update(MerkleTreeHealth::Initialization.into());
+        let tree = tree
+            .ensure_ready(pool, false, &stop_receiver, &health_updater)
+            .await
+            .context("failed initializing proof data follower tree")?;
+        let Some(tree) = tree else {
+            return Ok(()); // recovery was aborted because a stop signal was received
+        };
+        tracing::info!(
+            "Proof data follower tree is initialized; next L1 batch to process: {}",
+            tree.next_l1_batch_number()
+        );
+
+        // The follower never receives revert requests; the tree consistency checker only reverts the main tree.
+        let (_, revert_requests) = mpsc::unbounded_channel();
+        let updater = TreeUpdater::new(
+            tree,
+            max_l1_batches_per_iter,
+            max_batches_per_commit,
+            object_store,
+            revert_requests,
+            TreeUpdaterRole::ProofDataFollower,
+        );
         updater
-            .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
+            .loop_updating_tree(delayer, pool, stop_receiver, health_updater)
             .await
     }
 }
@@ -24,6 +24,14 @@
 //! Recovery performs basic sanity checks to ensure that the tree won't end up containing garbage data.
 //! E.g., it's checked that the tree always recovers from the same snapshot; that the tree root hash
 //! after recovery matches one in the Postgres snapshot etc.
+//!
+//! # Rebuilding from Postgres
+//!
+//! If the tree RocksDB instance is lost, but Postgres is intact, the tree can be rebuilt without a snapshot
+//! (if this is enabled in the config). In this case, the same recovery procedure is used, but tree entries
+//! are taken from initial writes and the latest storage values as of the last L1 batch with metadata.
+//! Since enumeration indices of initial writes are assigned by the tree, the rebuilt tree is identical
+//! to the lost one, which is checked by comparing root hashes.
 
 use std::{
     fmt, ops,
@@ -39,7 +47,7 @@ use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::TreeEntry;
 use zksync_types::{
     snapshots::{uniform_hashed_keys_chunk, SnapshotRecoveryStatus},
-    MiniblockNumber, H256,
+    L1BatchNumber, MiniblockNumber, H256,
 };
 
 use super::{
@@ -108,8 +116,18 @@ impl HandleRecoveryEvent for RecoveryHealthUpdater<'_> {
     }
 }
 
+/// Source of tree entries used during recovery.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecoverySource {
+    /// Storage logs from a snapshot applied to Postgres.
+    Snapshot,
+    /// Initial writes and latest storage values in Postgres as of the specified L1 batch.
+    Postgres(L1BatchNumber),
+}
+
 #[derive(Debug, Clone, Copy)]
 struct SnapshotParameters {
+    source: RecoverySource,
     miniblock: MiniblockNumber,
     expected_root_hash: H256,
     log_count: u64,
@@ -135,6 +153,42 @@ impl SnapshotParameters {
             .with_context(|| format!("Failed getting number of logs for miniblock #{miniblock}"))?;
 
         Ok(Self {
+            source: RecoverySource::Snapshot,
+            miniblock,
+            expected_root_hash,
+            log_count,
+        })
+    }
+
+    /// Creates parameters for rebuilding the tree from Postgres as of the specified L1 batch.
+    async fn for_l1_batch(
+        pool: &ConnectionPool<Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.connection().await?;
+        let (_, miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} doesn't have miniblocks"))?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!("Root hash for L1 batch #{l1_batch_number} is not persisted")
+            })?;
+        let log_count = storage
+            .storage_logs_dedup_dal()
+            .max_enumeration_index_for_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("Failed getting number of initial writes for L1 batch #{l1_batch_number}")
+            })?
+            .context("no initial writes in Postgres")?;
+
+        Ok(Self {
+            source: RecoverySource::Postgres(l1_batch_number),
             miniblock,
             expected_root_hash,
             log_count,
@@ -156,44 +210,86 @@ struct RecoveryOptions<'a> {
 
 impl GenericAsyncTree {
     /// Ensures that the tree is ready for the normal operation, recovering it from a Postgres snapshot
-    /// if necessary.
+    /// if necessary. If `rebuild_from_postgres` is set and there's no snapshot, an empty tree is rebuilt
+    /// from the Postgres state as of the last L1 batch with metadata.
     pub async fn ensure_ready(
         self,
         pool: &ConnectionPool<Core>,
+        rebuild_from_postgres: bool,
         stop_receiver: &watch::Receiver<bool>,
         health_updater: &HealthUpdater,
     ) -> anyhow::Result<Option<AsyncTree>> {
-        let (tree, snapshot_recovery) = match self {
+        let (tree, snapshot) = match self {
             Self::Ready(tree) => return Ok(Some(tree)),
             Self::Recovering(tree) => {
-                let snapshot_recovery = get_snapshot_recovery(pool).await?.context(
-                    "Merkle tree is recovering, but Postgres doesn't contain snapshot recovery information",
-                )?;
                 let recovered_version = tree.recovered_version();
-                anyhow::ensure!(
-                    u64::from(snapshot_recovery.l1_batch_number.0) == recovered_version,
-                    "Snapshot L1 batch in Postgres ({snapshot_recovery:?}) differs from the recovered Merkle tree version \
-                     ({recovered_version})"
-                );
-                tracing::info!("Resuming tree recovery with status: {snapshot_recovery:?}");
-                (tree, snapshot_recovery)
+                let snapshot_recovery = get_snapshot_recovery(pool).await?;
+                let snapshot = match snapshot_recovery {
+                    Some(snapshot_recovery) => {
+                        anyhow::ensure!(
+                            u64::from(snapshot_recovery.l1_batch_number.0) == recovered_version,
+                            "Snapshot L1 batch in Postgres ({snapshot_recovery:?}) differs from the recovered Merkle tree version \
+                             ({recovered_version})"
+                        );
+                        tracing::info!("Resuming tree recovery with status: {snapshot_recovery:?}");
+                        SnapshotParameters::new(pool, &snapshot_recovery).await?
+                    }
+                    None if rebuild_from_postgres => {
+                        let l1_batch_number = L1BatchNumber(
+                            recovered_version
+                                .try_into()
+                                .context("recovered Merkle tree version is too large")?,
+                        );
+                        tracing::info!(
+                            "Resuming rebuilding Merkle tree from Postgres for L1 batch #{l1_batch_number}"
+                        );
+                        SnapshotParameters::for_l1_batch(pool, l1_batch_number).await?
+                    }
+                    None => anyhow::bail!(
+                        "Merkle tree is recovering, but Postgres doesn't contain snapshot recovery information"
+                    ),
+                };
+                (tree, snapshot)
             }
-            Self::Empty { db, mode } => {
+            Self::Empty {
+                db,
+                mode,
+                hashing_thread_count,
+            } => {
                 if let Some(snapshot_recovery) = get_snapshot_recovery(pool).await? {
                     tracing::info!(
                         "Starting Merkle tree recovery with status {snapshot_recovery:?}"
                     );
                     let l1_batch = snapshot_recovery.l1_batch_number;
-                    let tree = AsyncTreeRecovery::new(db, l1_batch.0.into(), mode);
-                    (tree, snapshot_recovery)
+                    let tree =
+                        AsyncTreeRecovery::new(db, l1_batch.0.into(), mode, hashing_thread_count);
+                    (
+                        tree,
+                        SnapshotParameters::new(pool, &snapshot_recovery).await?,
+                    )
+                } else if let Some(l1_batch_number) =
+                    get_l1_batch_to_rebuild(pool, rebuild_from_postgres).await?
+                {
+                    tracing::info!(
+                        "Starting rebuilding Merkle tree from Postgres for L1 batch #{l1_batch_number}"
+                    );
+                    let tree = AsyncTreeRecovery::new(
+                        db,
+                        l1_batch_number.0.into(),
+                        mode,
+                        hashing_thread_count,
+                    );
+                    (
+                        tree,
+                        SnapshotParameters::for_l1_batch(pool, l1_batch_number).await?,
+                    )
                 } else {
                     // Start the tree from scratch. The genesis block will be filled in `TreeUpdater::loop_updating_tree()`.
-                    return Ok(Some(AsyncTree::new(db, mode)));
+                    return Ok(Some(AsyncTree::new(db, mode, hashing_thread_count)));
                 }
             }
         };
 
-        let snapshot = SnapshotParameters::new(pool, &snapshot_recovery).await?;
         tracing::debug!("Obtained snapshot parameters: {snapshot:?}");
         let recovery_options = RecoveryOptions {
             chunk_count: snapshot.chunk_count(),
@@ -218,13 +314,12 @@ impl AsyncTreeRecovery {
             .map(|chunk_id| uniform_hashed_keys_chunk(chunk_id, chunk_count))
             .collect();
         tracing::info!(
-            "Recovering Merkle tree from Postgres snapshot in {chunk_count} concurrent chunks"
+            "Recovering Merkle tree from {:?} in {chunk_count} concurrent chunks",
+            snapshot.source
         );
 
         let mut storage = pool.connection().await?;
-        let remaining_chunks = self
-            .filter_chunks(&mut storage, snapshot.miniblock, &chunks)
-            .await?;
+        let remaining_chunks = self.filter_chunks(&mut storage, &snapshot, &chunks).await?;
         drop(storage);
         options
             .events
@@ -242,7 +337,7 @@ impl AsyncTreeRecovery {
                 .await
                 .context("semaphore is never closed")?;
             options.events.chunk_started().await;
-            Self::recover_key_chunk(&tree, snapshot.miniblock, chunk, pool, stop_receiver).await?;
+            Self::recover_key_chunk(&tree, &snapshot, chunk, pool, stop_receiver).await?;
             options.events.chunk_recovered().await;
             anyhow::Ok(())
         });
@@ -272,16 +367,27 @@ impl AsyncTreeRecovery {
     async fn filter_chunks(
         &mut self,
         storage: &mut Connection<'_, Core>,
-        snapshot_miniblock: MiniblockNumber,
+        snapshot: &SnapshotParameters,
         key_chunks: &[ops::RangeInclusive<H256>],
     ) -> anyhow::Result<Vec<ops::RangeInclusive<H256>>> {
+        let snapshot_miniblock = snapshot.miniblock;
         let chunk_starts_latency =
             RECOVERY_METRICS.latency[&RecoveryStage::LoadChunkStarts].start();
-        let chunk_starts = storage
-            .storage_logs_dal()
-            .get_chunk_starts_for_miniblock(snapshot_miniblock, key_chunks)
-            .await
-            .context("Failed getting chunk starts")?;
+        let chunk_starts = match snapshot.source {
+            RecoverySource::Snapshot => {
+                storage
+                    .storage_logs_dal()
+                    .get_chunk_starts_for_miniblock(snapshot_miniblock, key_chunks)
+                    .await
+            }
+            RecoverySource::Postgres(l1_batch_number) => {
+                storage
+                    .storage_logs_dedup_dal()
+                    .get_chunk_starts_for_l1_batch(l1_batch_number, snapshot_miniblock, key_chunks)
+                    .await
+            }
+        };
+        let chunk_starts = chunk_starts.context("Failed getting chunk starts")?;
         let chunk_starts_latency = chunk_starts_latency.observe();
         tracing::debug!(
             "Loaded start entries for {} chunks in {chunk_starts_latency:?}",
@@ -316,7 +422,7 @@ impl AsyncTreeRecovery {
 
     async fn recover_key_chunk(
         tree: &Mutex<AsyncTreeRecovery>,
-        snapshot_miniblock: MiniblockNumber,
+        snapshot: &SnapshotParameters,
         key_chunk: ops::RangeInclusive<H256>,
         pool: &ConnectionPool<Core>,
         stop_receiver: &watch::Receiver<bool>,
@@ -332,11 +438,26 @@ impl AsyncTreeRecovery {
 
         let entries_latency =
             RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LoadEntries].start();
-        let all_entries = storage
-            .storage_logs_dal()
-            .get_tree_entries_for_miniblock(snapshot_miniblock, key_chunk.clone())
-            .await
-            .with_context(|| {
+        let snapshot_miniblock = snapshot.miniblock;
+        let all_entries = match snapshot.source {
+            RecoverySource::Snapshot => {
+                storage
+                    .storage_logs_dal()
+                    .get_tree_entries_for_miniblock(snapshot_miniblock, key_chunk.clone())
+                    .await
+            }
+            RecoverySource::Postgres(l1_batch_number) => {
+                storage
+                    .storage_logs_dedup_dal()
+                    .get_tree_entries_for_l1_batch(
+                        l1_batch_number,
+                        snapshot_miniblock,
+                        key_chunk.clone(),
+                    )
+                    .await
+            }
+        };
+        let all_entries = all_entries.with_context(|| {
                 format!("Failed getting entries for chunk {key_chunk:?} in snapshot for miniblock #{snapshot_miniblock}")
             })?;
         drop(storage);
@@ -391,6 +512,23 @@ impl AsyncTreeRecovery {
     }
 }
 
+/// Returns the L1 batch to rebuild the tree for, or `None` if the tree should be built from scratch.
+async fn get_l1_batch_to_rebuild(
+    pool: &ConnectionPool<Core>,
+    rebuild_from_postgres: bool,
+) -> anyhow::Result<Option<L1BatchNumber>> {
+    if !rebuild_from_postgres {
+        return Ok(None);
+    }
+    let mut storage = pool.connection_tagged("metadata_calculator").await?;
+    let last_l1_batch_with_metadata = storage
+        .blocks_dal()
+        .get_last_l1_batch_number_with_metadata()
+        .await?;
+    // The genesis L1 batch is cheap to process, so there's no point rebuilding the tree for it.
+    Ok(last_l1_batch_with_metadata.filter(|&number| number > L1BatchNumber(0)))
+}
+
 async fn get_snapshot_recovery(
     pool: &ConnectionPool<Core>,
 ) -> anyhow::Result<Option<SnapshotRecoveryStatus>> {
@@ -1,6 +1,6 @@
 //! Tests for metadata calculator snapshot recovery.
 
-use std::{path::PathBuf, time::Duration};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use assert_matches::assert_matches;
 use tempfile::TempDir;
@@ -21,8 +21,8 @@ use crate::{
     metadata_calculator::{
         helpers::create_db,
         tests::{
-            extend_db_state, extend_db_state_from_l1_batch, gen_storage_logs, run_calculator,
-            setup_calculator,
+            extend_db_state, extend_db_state_from_l1_batch, gen_storage_logs, reset_db_state,
+            run_calculator, setup_calculator,
         },
         MetadataCalculator, MetadataCalculatorConfig,
     },
@@ -32,6 +32,7 @@ use crate::{
 #[test]
 fn calculating_chunk_count() {
     let mut snapshot = SnapshotParameters {
+        source: RecoverySource::Snapshot,
         miniblock: MiniblockNumber(1),
         log_count: 160_000_000,
         expected_root_hash: H256::zero(),
@@ -52,10 +53,11 @@ async fn create_tree_recovery(path: PathBuf, l1_batch: L1BatchNumber) -> AsyncTr
         16 << 20,       // 16 MiB,
         Duration::ZERO, // writes should never be stalled in tests
         500,
+        HashMap::new(),
     )
     .await
     .unwrap();
-    AsyncTreeRecovery::new(db, l1_batch.0.into(), MerkleTreeMode::Full)
+    AsyncTreeRecovery::new(db, l1_batch.0.into(), MerkleTreeMode::Full, None)
 }
 
 #[tokio::test]
@@ -323,3 +325,102 @@ async fn entire_recovery_workflow(case: RecoveryWorkflowCase) {
     stop_sender.send_replace(true);
     calculator_task.await.expect("calculator panicked").unwrap();
 }
+
+/// Prepares Postgres with several L1 batches (some of which overwrite existing storage slots) processed
+/// by the tree. Returns the root hash for the last L1 batch.
+async fn prepare_postgres_for_rebuild(pool: &ConnectionPool<Core>, temp_dir: &TempDir) -> H256 {
+    let (calculator, _) = setup_calculator(&temp_dir.path().join("init"), pool).await;
+    reset_db_state(pool, 5).await;
+    // Overwrite some of the existing slots so that the latest values differ from the initial ones.
+    let mut storage = pool.connection().await.unwrap();
+    let overwriting_logs = gen_storage_logs(0..20, 1);
+    extend_db_state(&mut storage, overwriting_logs).await;
+    drop(storage);
+
+    run_calculator(calculator, pool.clone()).await
+}
+
+#[tokio::test]
+async fn rebuilding_tree_from_postgres() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let expected_root_hash = prepare_postgres_for_rebuild(&pool, &temp_dir).await;
+    let l1_batch_number = L1BatchNumber(6);
+
+    let snapshot = SnapshotParameters::for_l1_batch(&pool, l1_batch_number)
+        .await
+        .unwrap();
+    assert_eq!(snapshot.source, RecoverySource::Postgres(l1_batch_number));
+    assert_eq!(snapshot.miniblock, MiniblockNumber(6));
+    assert_eq!(snapshot.expected_root_hash, expected_root_hash);
+    assert!(snapshot.log_count > 100, "{snapshot:?}");
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    for chunk_count in [1, 4, 16, 60] {
+        println!("Rebuilding tree with {chunk_count} chunks");
+
+        let tree_path = temp_dir.path().join(format!("rebuild-{chunk_count}"));
+        let tree = create_tree_recovery(tree_path, l1_batch_number).await;
+        let (health_check, health_updater) = ReactiveHealthCheck::new("tree");
+        let recovery_options = RecoveryOptions {
+            chunk_count,
+            concurrency_limit: 1,
+            events: Box::new(RecoveryHealthUpdater::new(&health_updater)),
+        };
+        let tree = tree
+            .recover(snapshot, recovery_options, &pool, &stop_receiver)
+            .await
+            .unwrap()
+            .expect("Tree rebuild unexpectedly aborted");
+
+        assert_eq!(tree.root_hash(), expected_root_hash);
+        assert_eq!(tree.next_l1_batch_number(), l1_batch_number + 1);
+        let health = health_check.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+    }
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn calculator_rebuilding_tree_from_postgres(rebuild_from_postgres: bool) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let expected_root_hash = prepare_postgres_for_rebuild(&pool, &temp_dir).await;
+
+    // Emulate losing the tree by using a new RocksDB path.
+    let merkle_tree_config = MerkleTreeConfig {
+        path: temp_dir.path().join("rebuilt").to_str().unwrap().to_owned(),
+        rebuild_from_postgres,
+        ..MerkleTreeConfig::default()
+    };
+    let calculator_config = MetadataCalculatorConfig::for_main_node(
+        &merkle_tree_config,
+        &OperationsManagerConfig { delay_interval: 50 },
+    );
+    let mut calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let (delay_sx, mut delay_rx) = mpsc::unbounded_channel();
+    calculator.delayer.delay_notifier = delay_sx;
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let tree_reader = calculator.tree_reader();
+    let calculator_task = tokio::spawn(calculator.run(pool.clone(), stop_receiver));
+
+    let (next_l1_batch, root_hash) = delay_rx.recv().await.unwrap();
+    assert_eq!(next_l1_batch, L1BatchNumber(7));
+    assert_eq!(root_hash, expected_root_hash);
+
+    let tree_reader = tree_reader.wait().await;
+    let root_hash = tree_reader
+        .clone()
+        .l1_batch_root_hash(L1BatchNumber(6))
+        .await;
+    assert_eq!(root_hash, Some(expected_root_hash));
+    // If the tree is rebuilt, it doesn't contain versions for the earlier L1 batches.
+    let prev_root_hash = tree_reader.l1_batch_root_hash(L1BatchNumber(5)).await;
+    assert_eq!(prev_root_hash.is_none(), rebuild_from_postgres);
+
+    stop_sender.send_replace(true);
+    calculator_task.await.expect("calculator panicked").unwrap();
+}
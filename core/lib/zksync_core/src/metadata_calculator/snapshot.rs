@@ -0,0 +1,386 @@
+//! Export of Merkle tree snapshots to the object store, and their import by nodes recovering from a snapshot.
+
+use std::{
+    error,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use zksync_config::configs::database::MerkleTreeConfig;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_merkle_tree::{
+    snapshot::{TreeSnapshotChunk, TreeSnapshotImporter},
+    MerkleTree, RocksDBWrapper,
+};
+use zksync_object_store::{
+    serialize_using_bincode, Bucket, ObjectStore, ObjectStoreError, StoredObject,
+};
+use zksync_types::{snapshots::SnapshotMetadata, L1BatchNumber, H256};
+use zksync_utils::error::{ClassifiedError, ClassifyError};
+
+use super::{helpers::AsyncTreeReader, metrics::SNAPSHOT_EXPORT_METRICS, LazyAsyncTreeReader};
+
+/// Configuration of [`MerkleTreeSnapshotExporter`].
+#[derive(Debug, Clone)]
+pub struct MerkleTreeSnapshotExporterConfig {
+    /// Interval between checks for new storage log snapshots.
+    pub interval: Duration,
+    /// Maximum number of tree nodes in a single exported chunk.
+    pub chunk_size: usize,
+}
+
+impl MerkleTreeSnapshotExporterConfig {
+    /// Returns `None` if the snapshot export is disabled in the provided tree config.
+    pub fn new(config: &MerkleTreeConfig) -> Option<Self> {
+        Some(Self {
+            interval: config.snapshot_export_interval()?,
+            chunk_size: config.snapshot_export_chunk_size,
+        })
+    }
+}
+
+/// Header of a Merkle tree snapshot stored in the object store. The header is stored after all snapshot chunks,
+/// so its presence means that the snapshot is complete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeSnapshotHeader {
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the tree after processing the L1 batch.
+    pub root_hash: H256,
+    pub chunk_count: u64,
+}
+
+impl StoredObject for TreeSnapshotHeader {
+    const BUCKET: Bucket = Bucket::TreeSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("tree_snapshot_l1_batch_{key}_header.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TreeSnapshotChunkKey {
+    l1_batch_number: L1BatchNumber,
+    chunk_id: u64,
+}
+
+/// Wrapper allowing to store [`TreeSnapshotChunk`]s in the object store.
+#[derive(Debug)]
+struct StoredTreeSnapshotChunk(TreeSnapshotChunk);
+
+impl StoredObject for StoredTreeSnapshotChunk {
+    const BUCKET: Bucket = Bucket::TreeSnapshot;
+    type Key<'a> = TreeSnapshotChunkKey;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!(
+            "tree_snapshot_l1_batch_{}_part_{:0>4}.bin",
+            key.l1_batch_number, key.chunk_id
+        )
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Box<dyn error::Error + Send + Sync>> {
+        Ok(self.0.serialize())
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, Box<dyn error::Error + Send + Sync>> {
+        TreeSnapshotChunk::deserialize(&bytes)
+            .map(Self)
+            .map_err(From::from)
+    }
+}
+
+/// Task periodically exporting the Merkle tree to the object store for the newest complete storage log snapshot,
+/// so that nodes recovering from the snapshot can [import](import_tree_snapshot()) the tree instead of recomputing it
+/// from storage logs.
+#[derive(Debug)]
+pub struct MerkleTreeSnapshotExporter {
+    config: MerkleTreeSnapshotExporterConfig,
+    pool: ConnectionPool<Core>,
+    tree_reader: LazyAsyncTreeReader,
+    blob_store: Arc<dyn ObjectStore>,
+}
+
+impl MerkleTreeSnapshotExporter {
+    pub(super) fn new(
+        config: MerkleTreeSnapshotExporterConfig,
+        pool: ConnectionPool<Core>,
+        tree_reader: LazyAsyncTreeReader,
+        blob_store: Arc<dyn ObjectStore>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            tree_reader,
+            blob_store,
+        }
+    }
+
+    /// Exports the tree for the newest complete storage log snapshot unless it is already exported.
+    /// Returns the header of the exported tree snapshot, or `None` if nothing was exported.
+    pub async fn export_new_snapshot(&self) -> anyhow::Result<Option<TreeSnapshotHeader>> {
+        let Some(tree_reader) = self.tree_reader.read() else {
+            tracing::debug!("Merkle tree is not initialized yet; skipping snapshot export");
+            return Ok(None);
+        };
+        let mut storage = self
+            .pool
+            .connection_tagged("tree_snapshot_exporter")
+            .await?;
+        let snapshot = storage
+            .snapshots_dal()
+            .get_newest_snapshot_metadata()
+            .await?;
+        drop(storage);
+
+        let Some(snapshot) = snapshot.filter(SnapshotMetadata::is_complete) else {
+            tracing::debug!(
+                "There are no complete storage log snapshots; skipping snapshot export"
+            );
+            return Ok(None);
+        };
+        let l1_batch_number = snapshot.l1_batch_number;
+        match self
+            .blob_store
+            .get::<TreeSnapshotHeader>(l1_batch_number)
+            .await
+        {
+            Ok(_) => return Ok(None), // The snapshot is already exported
+            Err(ObjectStoreError::KeyNotFound(_)) => { /* continue */ }
+            Err(err) => {
+                return Err(
+                    anyhow::Error::from(ClassifiedError::new(err)).context(format!(
+                        "failed checking tree snapshot for L1 batch #{l1_batch_number}"
+                    )),
+                );
+            }
+        }
+
+        let tree_info = tree_reader.clone().info().await;
+        if tree_info.next_l1_batch_number <= l1_batch_number {
+            tracing::debug!(
+                "Merkle tree has not processed L1 batch #{l1_batch_number} yet; postponing snapshot export"
+            );
+            return Ok(None);
+        }
+
+        let header = Self::export_snapshot(
+            &tree_reader,
+            &*self.blob_store,
+            l1_batch_number,
+            self.config.chunk_size,
+        )
+        .await?;
+        Ok(Some(header))
+    }
+
+    async fn export_snapshot(
+        tree_reader: &AsyncTreeReader,
+        blob_store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+        chunk_size: usize,
+    ) -> anyhow::Result<TreeSnapshotHeader> {
+        tracing::info!("Exporting Merkle tree snapshot for L1 batch #{l1_batch_number}");
+        let started_at = Instant::now();
+        let root_hash = tree_reader
+            .clone()
+            .l1_batch_root_hash(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("Merkle tree has no version for L1 batch #{l1_batch_number}")
+            })?;
+        let mut exporter = tree_reader
+            .snapshot_exporter(l1_batch_number, chunk_size)
+            .with_context(|| format!("failed exporting tree for L1 batch #{l1_batch_number}"))?;
+
+        let mut chunk_count = 0;
+        loop {
+            let (returned_exporter, chunk) = tokio::task::spawn_blocking(move || {
+                let chunk = exporter.next_chunk();
+                (exporter, chunk)
+            })
+            .await
+            .context("panicked exporting tree snapshot chunk")?;
+            exporter = returned_exporter;
+            let chunk = chunk.with_context(|| {
+                format!("failed exporting tree for L1 batch #{l1_batch_number}")
+            })?;
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            let key = TreeSnapshotChunkKey {
+                l1_batch_number,
+                chunk_id: chunk_count,
+            };
+            blob_store
+                .put(key, &StoredTreeSnapshotChunk(chunk))
+                .await
+                .map_err(ClassifiedError::new)
+                .with_context(|| format!("failed storing tree snapshot chunk {key:?}"))?;
+            chunk_count += 1;
+            SNAPSHOT_EXPORT_METRICS.exported_chunks.inc();
+        }
+
+        let header = TreeSnapshotHeader {
+            l1_batch_number,
+            root_hash,
+            chunk_count,
+        };
+        blob_store
+            .put(l1_batch_number, &header)
+            .await
+            .map_err(ClassifiedError::new)
+            .context("failed storing tree snapshot header")?;
+
+        let elapsed = started_at.elapsed();
+        SNAPSHOT_EXPORT_METRICS.export_latency.observe(elapsed);
+        SNAPSHOT_EXPORT_METRICS
+            .exported_l1_batch
+            .set(l1_batch_number.0.into());
+        tracing::info!(
+            "Exported Merkle tree snapshot for L1 batch #{l1_batch_number} in {chunk_count} chunks in {elapsed:?}"
+        );
+        Ok(header)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting Merkle tree snapshot exporter with config {:?}",
+            self.config
+        );
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if let Err(err) = self.export_new_snapshot().await {
+                // Transient object store errors shouldn't stop the exporter; the export will be retried
+                // on the next iteration.
+                if !err.error_kind().is_retryable() {
+                    return Err(err);
+                }
+                tracing::warn!("Failed exporting Merkle tree snapshot, will retry: {err:#}");
+                SNAPSHOT_EXPORT_METRICS.failed_exports.inc();
+            }
+
+            // Wait for the next check or the stop signal, whichever comes first.
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree snapshot exporter is shutting down");
+        Ok(())
+    }
+}
+
+/// Imports the Merkle tree snapshot for the specified L1 batch from the object store into a RocksDB instance
+/// at `db_path`. Returns `false` if there is no tree snapshot for the L1 batch, or if the tree at `db_path`
+/// already exists; in this case, the tree should be initialized as usual.
+///
+/// The tree is imported into a temporary directory next to `db_path`, which is moved to `db_path` only after
+/// the imported tree is checked for consistency. Thus, an interrupted import doesn't leave a partially imported
+/// tree at `db_path`.
+pub async fn import_tree_snapshot(
+    blob_store: &dyn ObjectStore,
+    l1_batch_number: L1BatchNumber,
+    expected_root_hash: H256,
+    db_path: &Path,
+) -> anyhow::Result<bool> {
+    if db_path.exists() {
+        tracing::info!(
+            "Merkle tree at `{}` already exists; skipping tree snapshot import",
+            db_path.display()
+        );
+        return Ok(false);
+    }
+
+    let header = match blob_store.get::<TreeSnapshotHeader>(l1_batch_number).await {
+        Ok(header) => header,
+        Err(ObjectStoreError::KeyNotFound(_)) => {
+            tracing::info!("There is no Merkle tree snapshot for L1 batch #{l1_batch_number}");
+            return Ok(false);
+        }
+        Err(err) => {
+            return Err(anyhow::Error::from(err).context(format!(
+                "failed getting tree snapshot header for L1 batch #{l1_batch_number}"
+            )));
+        }
+    };
+    anyhow::ensure!(
+        header.root_hash == expected_root_hash,
+        "Root hash in the tree snapshot header ({:?}) differs from the expected root hash ({expected_root_hash:?})",
+        header.root_hash
+    );
+
+    tracing::info!(
+        "Importing Merkle tree snapshot for L1 batch #{l1_batch_number} with {} chunks",
+        header.chunk_count
+    );
+    let started_at = Instant::now();
+    let import_path = db_path.with_extension("import");
+    if import_path.exists() {
+        tracing::info!(
+            "Removing partially imported Merkle tree at `{}`",
+            import_path.display()
+        );
+        tokio::fs::remove_dir_all(&import_path)
+            .await
+            .context("failed removing partially imported Merkle tree")?;
+    }
+    let db_import_path = import_path.clone();
+    let db = tokio::task::spawn_blocking(move || RocksDBWrapper::new(&db_import_path))
+        .await
+        .context("panicked creating RocksDB for imported Merkle tree")?
+        .context("failed creating RocksDB for imported Merkle tree")?;
+
+    let version = u64::from(l1_batch_number.0);
+    let mut importer = TreeSnapshotImporter::new(db, version);
+    for chunk_id in 0..header.chunk_count {
+        let key = TreeSnapshotChunkKey {
+            l1_batch_number,
+            chunk_id,
+        };
+        let StoredTreeSnapshotChunk(chunk) = blob_store
+            .get(key)
+            .await
+            .with_context(|| format!("failed getting tree snapshot chunk {key:?}"))?;
+        importer = tokio::task::spawn_blocking(move || {
+            importer.import_chunk(chunk);
+            importer
+        })
+        .await
+        .context("panicked importing tree snapshot chunk")?;
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let root_hash = importer.root_hash();
+        anyhow::ensure!(
+            root_hash == Some(expected_root_hash),
+            "Root hash of the imported tree ({root_hash:?}) differs from the expected root hash \
+             ({expected_root_hash:?})"
+        );
+        let tree = MerkleTree::new(importer.finalize()?);
+        tree.verify_consistency(version, true)
+            .context("imported Merkle tree is inconsistent")
+    })
+    .await
+    .context("panicked finalizing imported Merkle tree")??;
+
+    tokio::fs::rename(&import_path, db_path)
+        .await
+        .context("failed moving imported Merkle tree")?;
+    tracing::info!(
+        "Imported Merkle tree snapshot for L1 batch #{l1_batch_number} in {:?}",
+        started_at.elapsed()
+    );
+    Ok(true)
+}
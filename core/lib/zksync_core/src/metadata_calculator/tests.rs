@@ -1,8 +1,18 @@
 //! Tests for the metadata calculator component life cycle.
 
-use std::{future::Future, ops, panic, path::Path, sync::Arc, time::Duration};
+use std::{
+    future::Future,
+    ops, panic,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use assert_matches::assert_matches;
+use async_trait::async_trait;
 use itertools::Itertools;
 use tempfile::TempDir;
 use tokio::sync::{mpsc, watch};
@@ -11,17 +21,25 @@ use zksync_config::configs::{
     database::{MerkleTreeConfig, MerkleTreeMode},
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_eth_client::{clients::MockEthereum, EthInterface, Options};
 use zksync_health_check::{CheckHealth, HealthStatus};
-use zksync_merkle_tree::domain::ZkSyncTree;
-use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_merkle_tree::{domain::ZkSyncTree, RocksDBWrapper};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
 use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
 use zksync_types::{
-    block::L1BatchHeader, AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey,
-    StorageLog, H256,
+    aggregated_operations::AggregatedActionType,
+    block::{L1BatchHeader, L1BatchTreeData},
+    snapshots::SnapshotVersion,
+    AccountTreeId, Address, L1BatchNumber, Log, MiniblockNumber, StorageKey, StorageLog, H256,
 };
 use zksync_utils::u32_to_h256;
 
-use super::{GenericAsyncTree, L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig};
+use super::{
+    import_tree_snapshot, DivergenceKind, GenericAsyncTree, L1BatchWithLogs,
+    MerkleTreeConsistencyChecker, MerkleTreeConsistencyCheckerConfig,
+    MerkleTreeL1RootCheckerConfig, MerkleTreeSnapshotExporterConfig, MetadataCalculator,
+    MetadataCalculatorConfig, TreeDivergence, TreeSnapshotHeader,
+};
 use crate::{
     genesis::{insert_genesis_batch, GenesisParams},
     utils::testonly::{create_l1_batch, create_miniblock},
@@ -368,6 +386,561 @@ pub(crate) async fn setup_calculator(
     (calculator, store_factory.create_store().await)
 }
 
+#[tokio::test]
+async fn tree_is_reverted_on_request() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 5).await;
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+
+    let (calculator, object_store) = setup_calculator(temp_dir.path(), &pool).await;
+    calculator.revert_sender.send(L1BatchNumber(2)).unwrap();
+    let new_root_hash = run_calculator(calculator, pool.clone()).await;
+    assert_eq!(new_root_hash, root_hash);
+
+    // Only the L1 batches after the revert point should be recomputed.
+    for l1_batch_number in 1..=5 {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let job = object_store
+            .get::<PrepareBasicCircuitsJob>(l1_batch_number)
+            .await;
+        assert_eq!(job.is_ok(), l1_batch_number > L1BatchNumber(2));
+    }
+}
+
+#[tokio::test]
+async fn tree_lag_is_reported() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Lightweight);
+    merkle_tree_config.max_l1_batches_per_iter = 2;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    reset_db_state(&pool, 5).await;
+
+    let mut tree_lag = calculator.tree_lag();
+    assert_eq!(tree_lag.get(), None);
+    let lag_values_task = tokio::spawn(async move {
+        let mut lag_values = vec![];
+        while let Some(lag) = tree_lag.changed().await {
+            lag_values.push(lag.unwrap());
+        }
+        lag_values
+    });
+    run_calculator(calculator, pool).await;
+
+    let lag_values = lag_values_task.await.unwrap();
+    assert_eq!(lag_values.last(), Some(&0), "{lag_values:?}");
+    // The lag must decrease monotonically since no new L1 batches are added to Postgres.
+    assert!(
+        lag_values.windows(2).all(|window| window[0] >= window[1]),
+        "{lag_values:?}"
+    );
+}
+
+#[tokio::test]
+async fn tree_updates_are_committed_in_batches_when_catching_up() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Lightweight);
+    merkle_tree_config.max_l1_batches_per_iter = 2;
+    merkle_tree_config.max_batches_per_commit = 4;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    reset_db_state(&pool, 9).await;
+
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+    assert_eq!(root_hash, expected_tree_hash(&pool).await);
+
+    // All updates must be persisted once the tree has caught up, even if the number of uncommitted
+    // L1 batches is less than `max_batches_per_commit`.
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    let tree = calculator.create_tree().await.unwrap();
+    let GenericAsyncTree::Ready(tree) = tree else {
+        panic!("Unexpected tree state: {tree:?}");
+    };
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(10));
+    assert_eq!(tree.root_hash(), root_hash);
+}
+
+#[tokio::test]
+async fn proof_data_is_generated_by_follower() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    merkle_tree_config.proof_data_follower_path =
+        Some(path_to_string(&temp_dir.path().join("follower")));
+    let store_factory = ObjectStoreFactory::mock();
+    let object_store = store_factory.create_store().await;
+    let calculator = setup_calculator_with_options(
+        &merkle_tree_config,
+        &operation_config,
+        &pool,
+        Some(object_store.clone()),
+    )
+    .await;
+    assert!(calculator.proof_data_follower_health_check().is_some());
+    reset_db_state(&pool, 3).await;
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let calculator_task = tokio::spawn(calculator.run(pool.clone(), stop_receiver));
+    run_with_timeout(RUN_TIMEOUT, async {
+        while object_store
+            .get::<PrepareBasicCircuitsJob>(L1BatchNumber(3))
+            .await
+            .is_err()
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    stop_sender.send_replace(true);
+    calculator_task.await.unwrap().unwrap();
+
+    for l1_batch_number in 1..=3 {
+        let job: PrepareBasicCircuitsJob = object_store
+            .get(L1BatchNumber(l1_batch_number))
+            .await
+            .unwrap();
+        assert!(job.next_enumeration_index() > 0);
+    }
+
+    // The follower must be in sync with the main tree.
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    let follower_path = merkle_tree_config
+        .proof_data_follower_path
+        .as_ref()
+        .unwrap();
+    let follower_tree = calculator
+        .open_tree(follower_path, MerkleTreeMode::Full)
+        .await
+        .unwrap();
+    let GenericAsyncTree::Ready(follower_tree) = follower_tree else {
+        panic!("Unexpected tree state: {follower_tree:?}");
+    };
+    assert_eq!(follower_tree.next_l1_batch_number(), L1BatchNumber(4));
+    assert_eq!(follower_tree.root_hash(), expected_tree_hash(&pool).await);
+}
+
+fn consistency_checker_config(repair: bool) -> MerkleTreeConsistencyCheckerConfig {
+    MerkleTreeConsistencyCheckerConfig {
+        interval: Duration::from_millis(50),
+        sample_size: 1_000,
+        repair,
+    }
+}
+
+#[tokio::test]
+async fn tree_consistency_checker_basics() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let checker = calculator.consistency_checker(consistency_checker_config(false), pool.clone());
+    // The tree is not initialized yet.
+    assert_eq!(checker.check_consistency().await.unwrap(), None);
+
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+    let report = checker.check_consistency().await.unwrap().unwrap();
+    assert_eq!(report.l1_batch_number, L1BatchNumber(5));
+    assert!(report.checked_leaves >= 100, "{report:?}");
+    assert!(report.is_consistent(), "{report:?}");
+    assert_eq!(report.last_l1_batch_to_keep(), None);
+}
+
+#[tokio::test]
+async fn tree_consistency_checker_detects_divergent_values() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let tree_reader = calculator.tree_reader();
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    // Overwrite a value initially written in L1 batch #2 in L1 batch #4 without updating the tree.
+    let key = gen_storage_logs(0..100, 5)[1][0].key;
+    let log = StorageLog::new_write_log(key, H256::repeat_byte(0xff));
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(MiniblockNumber(4), &[(H256::zero(), vec![log])])
+        .await
+        .unwrap();
+
+    let (revert_sender, mut revert_receiver) = mpsc::unbounded_channel();
+    let checker = MerkleTreeConsistencyChecker::new(
+        consistency_checker_config(true),
+        pool.clone(),
+        tree_reader,
+        revert_sender,
+    );
+    let report = checker.check_consistency().await.unwrap().unwrap();
+    assert_eq!(report.l1_batch_number, L1BatchNumber(5));
+    assert_eq!(
+        report.divergences,
+        [TreeDivergence {
+            kind: DivergenceKind::Value,
+            hashed_key: Some(key.hashed_key()),
+            first_affected_l1_batch: Some(L1BatchNumber(4)),
+        }]
+    );
+    assert_eq!(report.last_l1_batch_to_keep(), Some(L1BatchNumber(3)));
+
+    checker.handle_report(&report).await;
+    assert_eq!(revert_receiver.try_recv().unwrap(), L1BatchNumber(3));
+}
+
+#[tokio::test]
+async fn tree_consistency_checker_detects_divergent_root_hashes() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let tree_reader = calculator.tree_reader();
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    // Replace root hashes for L1 batches #3..=5 in Postgres.
+    let mut storage = pool.connection().await.unwrap();
+    let removed_batches = remove_l1_batches(&mut storage, L1BatchNumber(2)).await;
+    for batch_header in &removed_batches {
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(batch_header)
+            .await
+            .unwrap();
+        insert_initial_writes_for_batch(&mut storage, batch_header.number).await;
+        let tree_data = L1BatchTreeData {
+            hash: H256::repeat_byte(batch_header.number.0 as u8),
+            rollup_last_leaf_index: 1,
+        };
+        storage
+            .blocks_dal()
+            .save_l1_batch_tree_data(batch_header.number, &tree_data)
+            .await
+            .unwrap();
+    }
+
+    let (revert_sender, mut revert_receiver) = mpsc::unbounded_channel();
+    let checker = MerkleTreeConsistencyChecker::new(
+        consistency_checker_config(false),
+        pool.clone(),
+        tree_reader,
+        revert_sender,
+    );
+    let report = checker.check_consistency().await.unwrap().unwrap();
+    assert_eq!(report.l1_batch_number, L1BatchNumber(5));
+    assert_eq!(
+        report.divergences,
+        [TreeDivergence {
+            kind: DivergenceKind::RootHash,
+            hashed_key: None,
+            first_affected_l1_batch: Some(L1BatchNumber(3)),
+        }]
+    );
+    assert_eq!(report.last_l1_batch_to_keep(), Some(L1BatchNumber(2)));
+
+    // Repair is disabled, so the tree must not be reverted.
+    checker.handle_report(&report).await;
+    assert!(revert_receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn tree_snapshot_export_and_import() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let exporter_config = MerkleTreeSnapshotExporterConfig {
+        interval: Duration::from_millis(50),
+        chunk_size: 10,
+    };
+    let exporter = calculator.snapshot_exporter(exporter_config, pool.clone(), blob_store.clone());
+    // The tree is not initialized yet, so there's nothing to export.
+    assert!(exporter.export_new_snapshot().await.unwrap().is_none());
+
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+    // There are no storage log snapshots yet.
+    assert!(exporter.export_new_snapshot().await.unwrap().is_none());
+
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .snapshots_dal()
+        .add_snapshot(
+            SnapshotVersion::Version0,
+            L1BatchNumber(3),
+            1,
+            "factory_deps",
+        )
+        .await
+        .unwrap();
+    storage
+        .snapshots_dal()
+        .add_storage_logs_filepath_for_snapshot(L1BatchNumber(3), 0, "storage_logs", H256::zero())
+        .await
+        .unwrap();
+    let expected_root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(3))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let header = exporter.export_new_snapshot().await.unwrap().unwrap();
+    assert_eq!(header.l1_batch_number, L1BatchNumber(3));
+    assert_eq!(header.root_hash, expected_root_hash);
+    assert!(header.chunk_count > 1);
+    // The snapshot is already exported, so it must not be exported again.
+    assert!(exporter.export_new_snapshot().await.unwrap().is_none());
+
+    let import_path = temp_dir.path().join("imported");
+    let imported = import_tree_snapshot(
+        &*blob_store,
+        L1BatchNumber(3),
+        expected_root_hash,
+        &import_path,
+    )
+    .await
+    .unwrap();
+    assert!(imported);
+    let tree = ZkSyncTree::new_lightweight(RocksDBWrapper::new(&import_path).unwrap());
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(4));
+    assert_eq!(tree.root_hash(), expected_root_hash);
+    drop(tree);
+
+    // The tree directory already exists, so the snapshot must not be imported again.
+    let imported = import_tree_snapshot(
+        &*blob_store,
+        L1BatchNumber(3),
+        expected_root_hash,
+        &import_path,
+    )
+    .await
+    .unwrap();
+    assert!(!imported);
+}
+
+/// Object store failing the first `put_raw()` call with a transient error.
+#[derive(Debug)]
+struct FlakyObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    failed: AtomicBool,
+}
+
+#[async_trait]
+impl ObjectStore for FlakyObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        self.inner.get_raw(bucket, key).await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        if !self.failed.swap(true, Ordering::SeqCst) {
+            return Err(ObjectStoreError::Transient("oops".into()));
+        }
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[tokio::test]
+async fn tree_snapshot_exporter_retries_transient_errors() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let inner_store = ObjectStoreFactory::mock().create_store().await;
+    let blob_store = Arc::new(FlakyObjectStore {
+        inner: inner_store.clone(),
+        failed: AtomicBool::new(false),
+    });
+    let exporter_config = MerkleTreeSnapshotExporterConfig {
+        interval: Duration::from_millis(10),
+        chunk_size: 10,
+    };
+    let exporter = calculator.snapshot_exporter(exporter_config, pool.clone(), blob_store);
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .snapshots_dal()
+        .add_snapshot(
+            SnapshotVersion::Version0,
+            L1BatchNumber(3),
+            1,
+            "factory_deps",
+        )
+        .await
+        .unwrap();
+    storage
+        .snapshots_dal()
+        .add_storage_logs_filepath_for_snapshot(L1BatchNumber(3), 0, "storage_logs", H256::zero())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let exporter_task = tokio::spawn(exporter.run(stop_receiver));
+
+    let header = run_with_timeout(RUN_TIMEOUT, async {
+        loop {
+            match inner_store
+                .get::<TreeSnapshotHeader>(L1BatchNumber(3))
+                .await
+            {
+                Ok(header) => break header,
+                Err(ObjectStoreError::KeyNotFound(_)) => {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                Err(err) => panic!("unexpected object store error: {err}"),
+            }
+        }
+    })
+    .await;
+    assert!(header.chunk_count > 1);
+
+    stop_sender.send_replace(true);
+    exporter_task.await.unwrap().unwrap();
+}
+
+const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(1);
+
+fn block_commit_log(l1_batch_number: L1BatchNumber, root_hash: H256) -> Log {
+    let event_signature = zksync_contracts::zksync_contract()
+        .event("BlockCommit")
+        .unwrap()
+        .signature();
+    Log {
+        address: DIAMOND_PROXY_ADDR,
+        topics: vec![
+            event_signature,
+            H256::from_low_u64_be(l1_batch_number.0.into()), // batch number
+            root_hash,                                       // batch hash
+            H256::zero(),                                    // commitment
+        ],
+        data: vec![].into(),
+        block_hash: None,
+        block_number: None,
+        transaction_hash: None,
+        transaction_index: None,
+        log_index: None,
+        transaction_log_index: None,
+        log_type: Some("mined".into()),
+        removed: None,
+    }
+}
+
+/// Commits the specified L1 batch on L1 with the provided state root, and stores the commit transaction in Postgres.
+async fn commit_l1_batch(
+    storage: &mut Connection<'_, Core>,
+    l1_client: &MockEthereum,
+    l1_batch_number: L1BatchNumber,
+    root_hash: H256,
+) {
+    let options = Options {
+        nonce: Some((l1_batch_number.0 - 1).into()),
+        ..Options::default()
+    };
+    let signed_tx = l1_client
+        .sign_prepared_tx(vec![], DIAMOND_PROXY_ADDR, options)
+        .unwrap();
+    l1_client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    l1_client
+        .execute_tx(signed_tx.hash, true, 1)
+        .with_logs(vec![block_commit_log(l1_batch_number, root_hash)]);
+    storage
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            l1_batch_number,
+            AggregatedActionType::Commit,
+            signed_tx.hash,
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn tree_l1_root_checker_detects_mismatches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let l1_client = Arc::new(MockEthereum::default());
+    let checker_config = MerkleTreeL1RootCheckerConfig {
+        interval: Duration::from_millis(50),
+        diamond_proxy_addr: DIAMOND_PROXY_ADDR,
+    };
+    let mut checker = calculator.l1_root_checker(checker_config, pool.clone(), l1_client.clone());
+    let health_check = checker.health_check();
+    // The tree is not initialized yet.
+    checker.check_committed_l1_batches().await.unwrap();
+
+    reset_db_state(&pool, 5).await;
+    run_calculator(calculator, pool.clone()).await;
+    let mut storage = pool.connection().await.unwrap();
+    let mut root_hashes = vec![];
+    for number in 1..=5 {
+        let root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(L1BatchNumber(number))
+            .await
+            .unwrap()
+            .unwrap();
+        root_hashes.push(root_hash);
+    }
+
+    // No L1 batches are committed yet.
+    checker.check_committed_l1_batches().await.unwrap();
+    for number in 1..=2 {
+        let root_hash = root_hashes[number as usize - 1];
+        commit_l1_batch(&mut storage, &l1_client, L1BatchNumber(number), root_hash).await;
+    }
+    checker.check_committed_l1_batches().await.unwrap();
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
+    assert_eq!(
+        serde_json::to_value(health).unwrap()["details"],
+        serde_json::json!({ "last_checked_l1_batch": 2 })
+    );
+
+    // Commit the remaining L1 batches with a wrong state root for L1 batch #4.
+    for number in 3..=5 {
+        let root_hash = if number == 4 {
+            H256::repeat_byte(0xff)
+        } else {
+            root_hashes[number as usize - 1]
+        };
+        commit_l1_batch(&mut storage, &l1_client, L1BatchNumber(number), root_hash).await;
+    }
+    checker.check_committed_l1_batches().await.unwrap();
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Affected);
+    assert_eq!(
+        serde_json::to_value(health).unwrap()["details"],
+        serde_json::json!({
+            "last_checked_l1_batch": 5,
+            "mismatched_l1_batches": [4],
+        })
+    );
+}
+
 async fn setup_lightweight_calculator(
     db_path: &Path,
     pool: &ConnectionPool<Core>,
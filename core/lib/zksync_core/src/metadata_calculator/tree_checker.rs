@@ -0,0 +1,471 @@
+//! Periodic consistency checks of the Merkle tree against Postgres.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use rand::Rng;
+use tokio::sync::{mpsc, watch};
+use zksync_config::configs::database::MerkleTreeConfig;
+use zksync_crypto::hasher::blake2::Blake2Hasher;
+use zksync_dal::{
+    storage_logs_dedup_dal::DbInitialWrite, Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_merkle_tree::{HashTree, Key, TreeEntryWithProof};
+use zksync_types::{L1BatchNumber, H256, U256};
+
+pub use super::metrics::DivergenceKind;
+use super::{helpers::AsyncTreeReader, metrics::CONSISTENCY_METRICS, LazyAsyncTreeReader};
+
+/// Configuration of [`MerkleTreeConsistencyChecker`].
+#[derive(Debug, Clone)]
+pub struct MerkleTreeConsistencyCheckerConfig {
+    /// Interval between consistency checks.
+    pub interval: Duration,
+    /// Number of tree leaves sampled during a single check.
+    pub sample_size: usize,
+    /// Whether to revert the tree to the last L1 batch unaffected by detected divergences.
+    pub repair: bool,
+}
+
+impl MerkleTreeConsistencyCheckerConfig {
+    /// Returns `None` if consistency checks are disabled in the provided tree config.
+    pub fn new(config: &MerkleTreeConfig) -> Option<Self> {
+        Some(Self {
+            interval: config.consistency_check_interval()?,
+            sample_size: config.consistency_check_sample_size,
+            repair: config.consistency_check_repair,
+        })
+    }
+}
+
+/// Divergence between the Merkle tree and Postgres detected by [`MerkleTreeConsistencyChecker`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeDivergence {
+    pub kind: DivergenceKind,
+    /// Hashed key of the divergent tree leaf; `None` for root hash divergences.
+    pub hashed_key: Option<H256>,
+    /// Earliest L1 batch affected by the divergence, or `None` if it cannot be determined
+    /// (e.g., if the affected value was recovered from a snapshot).
+    pub first_affected_l1_batch: Option<L1BatchNumber>,
+}
+
+/// Outcome of a single consistency check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeConsistencyReport {
+    /// L1 batch that the tree was checked at.
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of sampled tree leaves.
+    pub checked_leaves: usize,
+    pub divergences: Vec<TreeDivergence>,
+}
+
+impl TreeConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    /// Returns the last L1 batch that the tree can be reverted to in order to recompute all affected L1 batches,
+    /// or `None` if there are no divergences, or they cannot be fixed by reverting the tree.
+    pub fn last_l1_batch_to_keep(&self) -> Option<L1BatchNumber> {
+        let mut first_affected_l1_batch: Option<L1BatchNumber> = None;
+        for divergence in &self.divergences {
+            let l1_batch_number = divergence.first_affected_l1_batch?;
+            first_affected_l1_batch = Some(
+                first_affected_l1_batch
+                    .map_or(l1_batch_number, |number| number.min(l1_batch_number)),
+            );
+        }
+        first_affected_l1_batch?.0.checked_sub(1).map(L1BatchNumber)
+    }
+}
+
+/// Probe used to check whether the tree diverges at a certain L1 batch.
+#[derive(Debug, Clone, Copy)]
+enum DivergenceProbe {
+    /// Compares the tree root hash with the one stored in Postgres.
+    RootHash,
+    /// Checks that the Merkle proof for the specified key verifies against the tree root hash.
+    MerkleProof(Key),
+}
+
+fn verify_proof(entry: &TreeEntryWithProof, root_hash: H256) -> bool {
+    Blake2Hasher.fold_merkle_path(&entry.merkle_path, entry.base) == root_hash
+}
+
+/// Task periodically cross-checking a sampled subset of Merkle tree leaves against storage logs in Postgres,
+/// and the tree root hash against the one committed on L1.
+///
+/// If repair is enabled in the config, the checker requests [`MetadataCalculator`](super::MetadataCalculator)
+/// to revert the tree to the last L1 batch unaffected by detected divergences, so that only the affected L1 batches
+/// are recomputed (as opposed to recomputing the entire tree).
+#[derive(Debug)]
+pub struct MerkleTreeConsistencyChecker {
+    config: MerkleTreeConsistencyCheckerConfig,
+    pool: ConnectionPool<Core>,
+    tree_reader: LazyAsyncTreeReader,
+    revert_sender: mpsc::UnboundedSender<L1BatchNumber>,
+}
+
+impl MerkleTreeConsistencyChecker {
+    pub(super) fn new(
+        config: MerkleTreeConsistencyCheckerConfig,
+        pool: ConnectionPool<Core>,
+        tree_reader: LazyAsyncTreeReader,
+        revert_sender: mpsc::UnboundedSender<L1BatchNumber>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            tree_reader,
+            revert_sender,
+        }
+    }
+
+    /// Returns the L1 batch to check the tree at, which is the latest L1 batch processed by the tree
+    /// and committed on L1 (or having metadata in Postgres if no L1 batches are committed yet).
+    async fn l1_batch_to_check(
+        storage: &mut Connection<'_, Core>,
+        tree_reader: &AsyncTreeReader,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let tree_info = tree_reader.clone().info().await;
+        let Some(last_tree_l1_batch) = tree_info.next_l1_batch_number.0.checked_sub(1) else {
+            return Ok(None); // The tree is empty
+        };
+
+        let mut last_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await?;
+        if last_l1_batch.is_none() {
+            last_l1_batch = storage
+                .blocks_dal()
+                .get_last_l1_batch_number_with_metadata()
+                .await?;
+        }
+        Ok(last_l1_batch.map(|number| number.min(L1BatchNumber(last_tree_l1_batch))))
+    }
+
+    async fn is_divergent(
+        storage: &mut Connection<'_, Core>,
+        tree_reader: &AsyncTreeReader,
+        probe: DivergenceProbe,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<bool> {
+        let Some(tree_root_hash) = tree_reader
+            .clone()
+            .l1_batch_root_hash(l1_batch_number)
+            .await
+        else {
+            return Ok(false); // The tree has no version for the L1 batch, e.g. because it was recovered from a snapshot
+        };
+
+        match probe {
+            DivergenceProbe::RootHash => {
+                let postgres_root_hash = storage
+                    .blocks_dal()
+                    .get_l1_batch_state_root(l1_batch_number)
+                    .await?;
+                Ok(postgres_root_hash.map_or(false, |hash| hash != tree_root_hash))
+            }
+            DivergenceProbe::MerkleProof(key) => {
+                let entries = tree_reader
+                    .clone()
+                    .entries_with_proofs(l1_batch_number, vec![key])
+                    .await
+                    .with_context(|| {
+                        format!("failed getting Merkle proof for L1 batch #{l1_batch_number}")
+                    })?;
+                Ok(!verify_proof(&entries[0], tree_root_hash))
+            }
+        }
+    }
+
+    /// Finds the earliest divergent L1 batch using binary search. Assumes that `divergent_l1_batch` is divergent,
+    /// and that the tree stays divergent once a divergence is introduced.
+    async fn find_first_divergent_l1_batch(
+        storage: &mut Connection<'_, Core>,
+        tree_reader: &AsyncTreeReader,
+        probe: DivergenceProbe,
+        divergent_l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let (mut left, mut right) = (0, divergent_l1_batch.0);
+        while left < right {
+            let middle = (left + right) / 2;
+            let l1_batch_number = L1BatchNumber(middle);
+            if Self::is_divergent(storage, tree_reader, probe, l1_batch_number).await? {
+                right = middle;
+            } else {
+                left = middle + 1;
+            }
+        }
+        Ok(L1BatchNumber(right))
+    }
+
+    /// Samples initial writes starting from a random hashed key, wrapping around the key space if necessary.
+    async fn sample_leaves(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<DbInitialWrite>> {
+        let start_key = H256(rand::thread_rng().gen());
+        let mut sample = storage
+            .storage_logs_dedup_dal()
+            .sample_initial_writes(start_key, l1_batch_number, self.config.sample_size)
+            .await?;
+        if sample.len() < self.config.sample_size {
+            let remaining = self.config.sample_size - sample.len();
+            let wrapped_sample = storage
+                .storage_logs_dedup_dal()
+                .sample_initial_writes(H256::zero(), l1_batch_number, remaining)
+                .await?;
+            sample.extend(
+                wrapped_sample
+                    .into_iter()
+                    .take_while(|write| write.hashed_key < start_key),
+            );
+        }
+        Ok(sample)
+    }
+
+    /// Checks sampled tree leaves. Returns the number of checked leaves.
+    async fn check_leaves(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        tree_reader: &AsyncTreeReader,
+        l1_batch_number: L1BatchNumber,
+        tree_root_hash: H256,
+        divergences: &mut Vec<TreeDivergence>,
+    ) -> anyhow::Result<usize> {
+        let Some((_, last_miniblock)) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+        else {
+            tracing::warn!(
+                "L1 batch #{l1_batch_number} has no miniblocks in Postgres; skipping checking tree leaves"
+            );
+            return Ok(0);
+        };
+        let sample = self.sample_leaves(storage, l1_batch_number).await?;
+        if sample.is_empty() {
+            return Ok(0);
+        }
+
+        let hashed_keys: Vec<_> = sample.iter().map(|write| write.hashed_key).collect();
+        let postgres_values = storage
+            .storage_logs_dal()
+            .get_storage_values_with_l1_batches(&hashed_keys, last_miniblock)
+            .await?;
+        let tree_keys = hashed_keys
+            .iter()
+            .map(|key| U256::from_little_endian(key.as_bytes()))
+            .collect();
+        let entries = tree_reader
+            .clone()
+            .entries_with_proofs(l1_batch_number, tree_keys)
+            .await
+            .with_context(|| {
+                format!("failed getting tree entries for L1 batch #{l1_batch_number}")
+            })?;
+
+        for (write, entry) in sample.iter().zip(&entries) {
+            let hashed_key = write.hashed_key;
+            if entry.base.leaf_index != write.index {
+                tracing::error!(
+                    "Leaf index for key {hashed_key:?} in tree ({}) differs from enumeration index in Postgres ({})",
+                    entry.base.leaf_index,
+                    write.index
+                );
+                divergences.push(TreeDivergence {
+                    kind: DivergenceKind::LeafIndex,
+                    hashed_key: Some(hashed_key),
+                    first_affected_l1_batch: Some(write.l1_batch_number),
+                });
+            } else if let Some(&(value, written_at)) = postgres_values.get(&hashed_key) {
+                if entry.base.value != value {
+                    tracing::error!(
+                        "Value for key {hashed_key:?} in tree ({:?}) differs from the value in Postgres ({value:?})",
+                        entry.base.value
+                    );
+                    divergences.push(TreeDivergence {
+                        kind: DivergenceKind::Value,
+                        hashed_key: Some(hashed_key),
+                        first_affected_l1_batch: written_at,
+                    });
+                }
+            }
+
+            if !verify_proof(entry, tree_root_hash) {
+                tracing::error!(
+                    "Merkle proof for key {hashed_key:?} doesn't verify against tree root hash {tree_root_hash:?}"
+                );
+                let probe = DivergenceProbe::MerkleProof(entry.base.key);
+                let first_affected_l1_batch = Self::find_first_divergent_l1_batch(
+                    storage,
+                    tree_reader,
+                    probe,
+                    l1_batch_number,
+                )
+                .await?;
+                divergences.push(TreeDivergence {
+                    kind: DivergenceKind::MerkleProof,
+                    hashed_key: Some(hashed_key),
+                    first_affected_l1_batch: Some(first_affected_l1_batch),
+                });
+            }
+        }
+        Ok(sample.len())
+    }
+
+    /// Performs a single consistency check. Returns `None` if there's nothing to check yet
+    /// (e.g., the tree is not initialized).
+    pub async fn check_consistency(&self) -> anyhow::Result<Option<TreeConsistencyReport>> {
+        let Some(tree_reader) = self.tree_reader.read() else {
+            tracing::debug!("Merkle tree is not initialized yet; skipping consistency check");
+            return Ok(None);
+        };
+        let mut storage = self
+            .pool
+            .connection_tagged("tree_consistency_checker")
+            .await?;
+
+        let Some(l1_batch_number) = Self::l1_batch_to_check(&mut storage, &tree_reader).await?
+        else {
+            return Ok(None);
+        };
+        let postgres_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await?;
+        let tree_root_hash = tree_reader
+            .clone()
+            .l1_batch_root_hash(l1_batch_number)
+            .await;
+        let (Some(postgres_root_hash), Some(tree_root_hash)) = (postgres_root_hash, tree_root_hash)
+        else {
+            return Ok(None);
+        };
+
+        let latency = CONSISTENCY_METRICS.check_latency.start();
+        let mut divergences = vec![];
+        if tree_root_hash != postgres_root_hash {
+            tracing::error!(
+                "Tree root hash for L1 batch #{l1_batch_number} ({tree_root_hash:?}) differs from the one \
+                 in Postgres ({postgres_root_hash:?})"
+            );
+            let first_affected_l1_batch = Self::find_first_divergent_l1_batch(
+                &mut storage,
+                &tree_reader,
+                DivergenceProbe::RootHash,
+                l1_batch_number,
+            )
+            .await?;
+            divergences.push(TreeDivergence {
+                kind: DivergenceKind::RootHash,
+                hashed_key: None,
+                first_affected_l1_batch: Some(first_affected_l1_batch),
+            });
+        }
+
+        let checked_leaves = self
+            .check_leaves(
+                &mut storage,
+                &tree_reader,
+                l1_batch_number,
+                tree_root_hash,
+                &mut divergences,
+            )
+            .await?;
+        latency.observe();
+
+        Ok(Some(TreeConsistencyReport {
+            l1_batch_number,
+            checked_leaves,
+            divergences,
+        }))
+    }
+
+    /// Reports check results and requests to revert the tree if necessary.
+    pub(super) async fn handle_report(&self, report: &TreeConsistencyReport) {
+        let l1_batch_number = report.l1_batch_number;
+        CONSISTENCY_METRICS
+            .checked_l1_batch
+            .set(l1_batch_number.0.into());
+        CONSISTENCY_METRICS
+            .checked_leaves
+            .inc_by(report.checked_leaves as u64);
+        for divergence in &report.divergences {
+            CONSISTENCY_METRICS.divergences[&divergence.kind].inc();
+        }
+
+        if report.is_consistent() {
+            tracing::info!(
+                "Merkle tree is consistent with Postgres at L1 batch #{l1_batch_number} ({} leaves checked)",
+                report.checked_leaves
+            );
+            return;
+        }
+        tracing::error!(
+            "Merkle tree diverges from Postgres at L1 batch #{l1_batch_number}: {:?}",
+            report.divergences
+        );
+        if !self.config.repair {
+            return;
+        }
+
+        let Some(last_l1_batch_to_keep) = report.last_l1_batch_to_keep() else {
+            tracing::error!(
+                "Merkle tree divergences cannot be repaired by reverting the tree; the tree must be recomputed from scratch"
+            );
+            return;
+        };
+        let can_revert = match self.tree_reader.read() {
+            Some(reader) => reader
+                .l1_batch_root_hash(last_l1_batch_to_keep)
+                .await
+                .is_some(),
+            None => false,
+        };
+        if !can_revert {
+            tracing::error!(
+                "Merkle tree cannot be reverted to L1 batch #{last_l1_batch_to_keep} to repair divergences since \
+                 the tree doesn't have this version; the tree must be recomputed from scratch"
+            );
+            return;
+        }
+
+        tracing::warn!(
+            "Requesting to revert Merkle tree to L1 batch #{last_l1_batch_to_keep} to repair divergences"
+        );
+        if self.revert_sender.send(last_l1_batch_to_keep).is_ok() {
+            CONSISTENCY_METRICS.repairs.inc();
+        } else {
+            tracing::warn!("Metadata calculator has stopped; cannot request tree revert");
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting Merkle tree consistency checker with config {:?}",
+            self.config
+        );
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if let Some(report) = self.check_consistency().await? {
+                self.handle_report(&report).await;
+            }
+
+            // Wait for the next check or the stop signal, whichever comes first.
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree consistency checker is shutting down");
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use std::{ops, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use futures::{future, FutureExt};
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::domain::TreeMetadata;
@@ -22,23 +22,48 @@ use super::{
 };
 use crate::utils::wait_for_l1_batch;
 
+/// Role of a [`TreeUpdater`].
+#[derive(Debug)]
+pub(super) enum TreeUpdaterRole {
+    /// Updater of the main tree, which processes all sealed L1 batches and reports the tree lag.
+    Main {
+        tree_lag: watch::Sender<Option<u32>>,
+    },
+    /// Updater of the follower tree, which only processes L1 batches already processed by the main tree
+    /// in order to generate proof data for them.
+    ProofDataFollower,
+}
+
 #[derive(Debug)]
 pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
+    max_batches_per_commit: usize,
+    /// Number of L1 batches processed by the tree, updates for which are not yet written to RocksDB.
+    uncommitted_l1_batches: usize,
     object_store: Option<Arc<dyn ObjectStore>>,
+    /// Requests to revert the tree to the specified L1 batch (inclusive) sent by the tree consistency checker.
+    revert_requests: mpsc::UnboundedReceiver<L1BatchNumber>,
+    role: TreeUpdaterRole,
 }
 
 impl TreeUpdater {
     pub fn new(
         tree: AsyncTree,
         max_l1_batches_per_iter: usize,
+        max_batches_per_commit: usize,
         object_store: Option<Arc<dyn ObjectStore>>,
+        revert_requests: mpsc::UnboundedReceiver<L1BatchNumber>,
+        role: TreeUpdaterRole,
     ) -> Self {
         Self {
             tree,
             max_l1_batches_per_iter,
+            max_batches_per_commit,
+            uncommitted_l1_batches: 0,
             object_store,
+            revert_requests,
+            role,
         }
     }
 
@@ -74,8 +99,8 @@ impl TreeUpdater {
         Ok((l1_batch_header, metadata, object_key))
     }
 
-    /// Processes a range of L1 batches with a single flushing of the tree updates to RocksDB at the end.
-    /// This allows to save on RocksDB I/O ops.
+    /// Processes a range of L1 batches. Tree updates are not flushed to RocksDB; this is the responsibility
+    /// of the caller (see [`Self::commit()`]).
     ///
     /// Returns the number of the next L1 batch to be processed by the tree.
     ///
@@ -168,14 +193,35 @@ impl TreeUpdater {
 
             updated_headers.push(header);
             l1_batch_data = next_l1_batch_data;
+            self.uncommitted_l1_batches += 1;
+        }
+
+        if matches!(self.role, TreeUpdaterRole::Main { .. }) {
+            MetadataCalculator::update_metrics(&updated_headers, total_logs, start);
+        } else {
+            tracing::info!(
+                "Generated proof data for L1 batches #{first_l1_batch_number}..={last_l1_batch_number} in {:?}",
+                start.elapsed()
+            );
         }
 
+        Ok(last_l1_batch_number + 1)
+    }
+
+    /// Writes tree updates accumulated in memory to RocksDB in a single write batch.
+    async fn commit(&mut self) -> anyhow::Result<()> {
         let save_rocksdb_latency = METRICS.start_stage(TreeUpdateStage::SaveRocksdb);
         self.tree.save().await?;
         save_rocksdb_latency.observe();
-        MetadataCalculator::update_metrics(&updated_headers, total_logs, start);
-
-        Ok(last_l1_batch_number + 1)
+        tracing::info!(
+            "Committed tree updates for {} L1 batches to RocksDB",
+            self.uncommitted_l1_batches
+        );
+        METRICS
+            .l1_batches_per_commit
+            .observe(self.uncommitted_l1_batches);
+        self.uncommitted_l1_batches = 0;
+        Ok(())
     }
 
     async fn step(
@@ -192,9 +238,27 @@ impl TreeUpdater {
             tracing::trace!("No L1 batches to seal: Postgres storage is empty");
             return Ok(());
         };
+        let last_available_l1_batch = match &self.role {
+            TreeUpdaterRole::Main { .. } => last_sealed_l1_batch,
+            TreeUpdaterRole::ProofDataFollower => {
+                let last_l1_batch_with_metadata = storage
+                    .blocks_dal()
+                    .get_last_l1_batch_number_with_metadata()
+                    .await
+                    .context("failed loading last L1 batch number with metadata")?;
+                let Some(number) = last_l1_batch_with_metadata else {
+                    tracing::trace!(
+                        "No L1 batches to seal: main tree hasn't processed any L1 batches yet"
+                    );
+                    return Ok(());
+                };
+                number
+            }
+        };
+
         let last_requested_l1_batch =
             next_l1_batch_to_seal.0 + self.max_l1_batches_per_iter as u32 - 1;
-        let last_requested_l1_batch = last_requested_l1_batch.min(last_sealed_l1_batch.0);
+        let last_requested_l1_batch = last_requested_l1_batch.min(last_available_l1_batch.0);
         let l1_batch_numbers = next_l1_batch_to_seal.0..=last_requested_l1_batch;
         if l1_batch_numbers.is_empty() {
             tracing::trace!(
@@ -206,6 +270,28 @@ impl TreeUpdater {
                 .process_multiple_batches(&mut storage, l1_batch_numbers)
                 .await?;
         }
+
+        // While the tree is catching up, updates for multiple iterations are accumulated in memory, so that
+        // they're written to RocksDB in a single write batch. This reduces write amplification and compaction load.
+        // Since tree data is persisted in Postgres for each processed L1 batch, L1 batches with uncommitted updates
+        // are simply recomputed after a restart.
+        let is_catching_up = *next_l1_batch_to_seal <= last_available_l1_batch;
+        if self.uncommitted_l1_batches >= self.max_batches_per_commit
+            || (self.uncommitted_l1_batches > 0 && !is_catching_up)
+        {
+            self.commit().await?;
+        }
+
+        let lag = (last_available_l1_batch.0 + 1).saturating_sub(next_l1_batch_to_seal.0);
+        match &self.role {
+            TreeUpdaterRole::Main { tree_lag } => {
+                METRICS.tree_lag.set(lag.into());
+                tree_lag.send_replace(Some(lag));
+            }
+            TreeUpdaterRole::ProofDataFollower => {
+                METRICS.proof_data_lag.set(lag.into());
+            }
+        }
         Ok(())
     }
 
@@ -293,6 +379,11 @@ impl TreeUpdater {
                 tracing::info!("Stop signal received, metadata_calculator is shutting down");
                 break;
             }
+            if self.handle_revert_requests().await? {
+                next_l1_batch_to_seal = self.tree.next_l1_batch_number();
+                let tree_info = self.tree.reader().info().await;
+                health_updater.update(tree_info.into());
+            }
             let storage = pool.connection_tagged("metadata_calculator").await?;
 
             let snapshot = *next_l1_batch_to_seal;
@@ -323,10 +414,51 @@ impl TreeUpdater {
                 () = delay => { /* The delay has passed */ }
             }
         }
+
+        if self.uncommitted_l1_batches > 0 {
+            self.commit().await?;
+        }
         drop(health_updater); // Explicitly mark where the updater should be dropped
         Ok(())
     }
 
+    /// Reverts the tree if requested by the tree consistency checker. If there are multiple requests,
+    /// the tree is reverted to the earliest requested L1 batch. Returns `true` if the tree was reverted.
+    async fn handle_revert_requests(&mut self) -> anyhow::Result<bool> {
+        let mut last_l1_batch_to_keep = None;
+        while let Ok(l1_batch_number) = self.revert_requests.try_recv() {
+            last_l1_batch_to_keep = Some(
+                last_l1_batch_to_keep.map_or(l1_batch_number, |number| l1_batch_number.min(number)),
+            );
+        }
+        let Some(last_l1_batch_to_keep) = last_l1_batch_to_keep else {
+            return Ok(false);
+        };
+
+        let next_l1_batch_number = self.tree.next_l1_batch_number();
+        if last_l1_batch_to_keep + 1 >= next_l1_batch_number {
+            tracing::info!(
+                "Ignoring request to revert Merkle tree to L1 batch #{last_l1_batch_to_keep}: \
+                 next L1 batch for the tree is #{next_l1_batch_number}"
+            );
+            return Ok(false);
+        }
+
+        tracing::warn!(
+            "Reverting Merkle tree to L1 batch #{last_l1_batch_to_keep} (next L1 batch: #{next_l1_batch_number}) \
+             as requested by tree consistency checker; L1 batches after it will be recomputed"
+        );
+        if self.uncommitted_l1_batches > 0 {
+            self.commit().await?;
+        }
+        self.tree.revert_logs(last_l1_batch_to_keep);
+        self.tree.save().await?;
+        METRICS
+            .reverted_l1_batches
+            .inc_by((next_l1_batch_number.0 - last_l1_batch_to_keep.0 - 1).into());
+        Ok(true)
+    }
+
     async fn check_initial_writes_consistency(
         connection: &mut Connection<'_, Core>,
         l1_batch_number: L1BatchNumber,
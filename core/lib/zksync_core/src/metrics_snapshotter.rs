@@ -0,0 +1,210 @@
+//! Component periodically persisting selected node metrics to Postgres.
+//!
+//! Snapshots allow analyzing incidents post factum even if the external Prometheus stack has lost data
+//! or wasn't scraping the node at the time.
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use vise::{Format, MetricsCollection, Registry};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+/// Metric name prefixes snapshotted by default: sync lag, block and L1 batch numbers, L1 batch ages
+/// and queue depths.
+const DEFAULT_METRIC_PREFIXES: &[&str] = &[
+    "external_node_synced",
+    "external_node_sync_lag",
+    "external_node_unsupported_protocol_version",
+    "external_node_fetcher_l1_batch",
+    "external_node_fetcher_miniblock",
+    "external_node_action_queue_action_queue_size",
+    "server_block_number",
+    "server_miniblock_number",
+    "server_blocks_state_block_eth_stage_latency",
+    "server_state_keeper_mempool_l1_size",
+    "server_state_keeper_mempool_l2_size",
+    "server_state_keeper_l2_priority_queue_size",
+];
+
+/// Configuration of [`MetricsSnapshotter`].
+#[derive(Debug, Clone)]
+pub struct MetricsSnapshotterConfig {
+    /// Interval between snapshots.
+    pub interval: Duration,
+    /// Snapshots older than this are removed from the database.
+    pub retention: Duration,
+    /// Gauges and counters with names starting with one of these prefixes are included into snapshots.
+    pub metric_prefixes: Vec<String>,
+}
+
+impl Default for MetricsSnapshotterConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            retention: Duration::from_secs(7 * 24 * 3_600),
+            metric_prefixes: DEFAULT_METRIC_PREFIXES
+                .iter()
+                .map(|&prefix| prefix.to_owned())
+                .collect(),
+        }
+    }
+}
+
+/// Periodically dumps values of selected gauges and counters into the `metrics_snapshots` table.
+pub struct MetricsSnapshotter {
+    config: MetricsSnapshotterConfig,
+    pool: ConnectionPool<Core>,
+    registry: Registry,
+}
+
+impl fmt::Debug for MetricsSnapshotter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("MetricsSnapshotter")
+            .field("config", &self.config)
+            .field("pool", &self.pool)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MetricsSnapshotter {
+    pub fn new(config: MetricsSnapshotterConfig, pool: ConnectionPool<Core>) -> Self {
+        Self {
+            config,
+            pool,
+            registry: MetricsCollection::lazy().collect(),
+        }
+    }
+
+    fn take_snapshot(&self) -> anyhow::Result<BTreeMap<String, f64>> {
+        let mut encoded = String::new();
+        self.registry
+            .encode(&mut encoded, Format::OpenMetrics)
+            .context("failed encoding metrics")?;
+        Ok(select_metrics(&encoded, &self.config.metric_prefixes))
+    }
+
+    async fn persist_snapshot(&self, metrics: &BTreeMap<String, f64>) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("metrics_snapshotter").await?;
+        storage
+            .metrics_snapshots_dal()
+            .insert_snapshot(metrics)
+            .await
+            .context("failed inserting metrics snapshot")?;
+        let pruned_count = storage
+            .metrics_snapshots_dal()
+            .prune_snapshots(self.config.retention)
+            .await
+            .context("failed pruning metrics snapshots")?;
+        if pruned_count > 0 {
+            tracing::debug!("Pruned {pruned_count} outdated metrics snapshot(s)");
+        }
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting metrics snapshotter with interval {:?} and metric prefixes {:?}",
+            self.config.interval,
+            self.config.metric_prefixes
+        );
+        while !*stop_receiver.borrow_and_update() {
+            // Failing to take or persist a snapshot shouldn't bring the node down.
+            match self.take_snapshot() {
+                Ok(metrics) => {
+                    if let Err(err) = self.persist_snapshot(&metrics).await {
+                        tracing::warn!("Failed persisting metrics snapshot: {err:#}");
+                    }
+                }
+                Err(err) => tracing::warn!("Failed taking metrics snapshot: {err:#}"),
+            }
+
+            if tokio::time::timeout(self.config.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, metrics snapshotter is shutting down");
+        Ok(())
+    }
+}
+
+/// Selects gauge and counter samples with names starting with one of `prefixes` from metrics
+/// encoded in the OpenMetrics text format.
+fn select_metrics(encoded: &str, prefixes: &[String]) -> BTreeMap<String, f64> {
+    let mut metrics = BTreeMap::new();
+    let mut family_type = "";
+    for line in encoded.lines() {
+        if let Some(family) = line.strip_prefix("# TYPE ") {
+            family_type = family.split_whitespace().nth(1).unwrap_or_default();
+            continue;
+        }
+        // Histograms and summaries are too large to be persisted and are not useful for point-in-time analysis.
+        if line.starts_with('#') || !matches!(family_type, "gauge" | "counter") {
+            continue;
+        }
+        let Some((name, value)) = line.rsplit_once(' ') else {
+            continue;
+        };
+        if !prefixes
+            .iter()
+            .any(|prefix| name.starts_with(prefix.as_str()))
+        {
+            continue;
+        }
+        if let Ok(value) = value.parse::<f64>() {
+            metrics.insert(name.to_owned(), value);
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selecting_metrics() {
+        let encoded = "\
+            # HELP external_node_sync_lag Current sync lag of the external node.\n\
+            # TYPE external_node_sync_lag gauge\n\
+            external_node_sync_lag 12\n\
+            # TYPE external_node_fetcher_l1_batch gauge\n\
+            external_node_fetcher_l1_batch{stage=\"open\"} 100\n\
+            external_node_fetcher_l1_batch{stage=\"executed\"} 95\n\
+            # TYPE external_node_fetcher_requests histogram\n\
+            external_node_fetcher_requests_bucket{stage=\"get_block\",le=\"0.001\"} 1\n\
+            # TYPE server_state_keeper_rejected_transactions counter\n\
+            server_state_keeper_rejected_transactions_total 3\n\
+            # EOF\n";
+        let prefixes = ["external_node_".to_owned()];
+        let metrics = select_metrics(encoded, &prefixes);
+        assert_eq!(
+            metrics,
+            BTreeMap::from([
+                ("external_node_sync_lag".to_owned(), 12.0),
+                (
+                    "external_node_fetcher_l1_batch{stage=\"open\"}".to_owned(),
+                    100.0
+                ),
+                (
+                    "external_node_fetcher_l1_batch{stage=\"executed\"}".to_owned(),
+                    95.0
+                ),
+            ])
+        );
+
+        let prefixes = ["server_state_keeper_".to_owned()];
+        let metrics = select_metrics(encoded, &prefixes);
+        assert_eq!(
+            metrics,
+            BTreeMap::from([(
+                "server_state_keeper_rejected_transactions_total".to_owned(),
+                3.0
+            )])
+        );
+    }
+}
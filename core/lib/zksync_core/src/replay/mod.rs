@@ -0,0 +1,556 @@
+//! Deterministic replay of historical L1 batches.
+//!
+//! [`BatchReplayer`] re-executes an L1 batch persisted in Postgres using the same [`MainBatchExecutor`]
+//! as the state keeper, on top of the storage state at the start of the batch, and compares the execution results
+//! (gas usage, events and storage writes) with the stored ones. This is useful to debug divergences
+//! after VM upgrades.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::interface::L2BlockEnv;
+use serde::Serialize;
+use tokio::{runtime::Handle, sync::watch};
+use vm_utils::storage::L1BatchParamsProvider;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_state::PostgresStorage;
+use zksync_types::{
+    api, storage_writes_deduplicator::StorageWritesDeduplicator, zk_evm_types::LogQuery, Address,
+    L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, StorageLogQuery, Transaction, VmEvent,
+    H256, U256, U64,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::state_keeper::{
+    BatchExecutor, MainBatchExecutor, PgOrRocksdbStorage, ReadStorageFactory, TxExecutionResult,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Storage factory returning Postgres storage at the specified miniblock.
+#[derive(Debug)]
+struct HistoricalStorageFactory {
+    pool: ConnectionPool<Core>,
+    miniblock_number: MiniblockNumber,
+}
+
+#[async_trait]
+impl ReadStorageFactory for HistoricalStorageFactory {
+    async fn access_storage(
+        &self,
+        _stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
+        let connection = self.pool.connection_tagged("batch_replay").await?;
+        let storage =
+            PostgresStorage::new_async(Handle::current(), connection, self.miniblock_number, true)
+                .await?;
+        Ok(Some(storage.into()))
+    }
+}
+
+/// Event emitted by a transaction, in the form common for replayed and stored events.
+#[derive(Debug, Clone, PartialEq)]
+struct TxEvent {
+    address: Address,
+    topics: Vec<H256>,
+    data: Vec<u8>,
+}
+
+impl From<VmEvent> for TxEvent {
+    fn from(event: VmEvent) -> Self {
+        Self {
+            address: event.address,
+            topics: event.indexed_topics,
+            data: event.value,
+        }
+    }
+}
+
+impl From<api::Log> for TxEvent {
+    fn from(log: api::Log) -> Self {
+        Self {
+            address: log.address,
+            topics: log.topics,
+            data: log.data.0,
+        }
+    }
+}
+
+/// Outcome of executing a single transaction, either during replay or as persisted in Postgres.
+#[derive(Debug, Clone, PartialEq)]
+struct TxOutcome {
+    is_success: bool,
+    gas_used: U256,
+    events: Vec<TxEvent>,
+}
+
+/// Execution results of an L1 batch, either replayed or loaded from Postgres.
+#[derive(Debug, Default)]
+struct BatchOutcome {
+    /// Transaction hashes in the execution order.
+    tx_hashes: Vec<H256>,
+    txs: HashMap<H256, TxOutcome>,
+    /// Latest values written to storage slots in the batch.
+    storage_writes: HashMap<StorageKey, H256>,
+}
+
+/// Divergence between the replayed and stored execution results of an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayDivergence {
+    /// Transaction was rejected by the VM during replay.
+    TxRejected { tx_hash: H256, reason: String },
+    /// Transaction success status differs.
+    TxStatus {
+        tx_hash: H256,
+        stored_success: bool,
+        replayed_success: bool,
+    },
+    /// Gas used by a transaction differs.
+    GasUsed {
+        tx_hash: H256,
+        stored: U256,
+        replayed: U256,
+    },
+    /// Events emitted by a transaction differ. `first_mismatch` is the index of the first differing event.
+    Events {
+        tx_hash: H256,
+        stored_count: usize,
+        replayed_count: usize,
+        first_mismatch: usize,
+    },
+    /// Latest value written to a storage slot in the batch differs. `None` means that the slot
+    /// was not written to.
+    StorageWrite {
+        address: Address,
+        key: H256,
+        stored: Option<H256>,
+        replayed: Option<H256>,
+    },
+}
+
+impl fmt::Display for ReplayDivergence {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TxRejected { tx_hash, reason } => {
+                write!(formatter, "transaction {tx_hash:?} was rejected: {reason}")
+            }
+            Self::TxStatus {
+                tx_hash,
+                stored_success,
+                replayed_success,
+            } => write!(
+                formatter,
+                "transaction {tx_hash:?} success status differs: stored {stored_success}, replayed {replayed_success}"
+            ),
+            Self::GasUsed {
+                tx_hash,
+                stored,
+                replayed,
+            } => write!(
+                formatter,
+                "gas used by transaction {tx_hash:?} differs: stored {stored}, replayed {replayed}"
+            ),
+            Self::Events {
+                tx_hash,
+                stored_count,
+                replayed_count,
+                first_mismatch,
+            } => write!(
+                formatter,
+                "events emitted by transaction {tx_hash:?} differ starting from #{first_mismatch} \
+                 (stored {stored_count} events, replayed {replayed_count})"
+            ),
+            Self::StorageWrite {
+                address,
+                key,
+                stored,
+                replayed,
+            } => write!(
+                formatter,
+                "value written to slot {key:?} of {address:?} differs: stored {stored:?}, replayed {replayed:?}"
+            ),
+        }
+    }
+}
+
+/// Report produced by [`BatchReplayer`].
+#[derive(Debug, Serialize)]
+pub struct ReplayReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub tx_count: usize,
+    pub divergences: Vec<ReplayDivergence>,
+}
+
+impl ReplayReport {
+    /// Checks whether replayed execution results match the stored ones.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Re-executes historical L1 batches from Postgres and compares the results with the stored ones.
+///
+/// Replay reads the storage state at the start of the batch from Postgres, so it doesn't require
+/// the state keeper RocksDB cache and doesn't modify any data.
+#[derive(Debug)]
+pub struct BatchReplayer {
+    pool: ConnectionPool<Core>,
+    l2_chain_id: L2ChainId,
+    validation_computational_gas_limit: u32,
+    optional_bytecode_compression: bool,
+}
+
+impl BatchReplayer {
+    /// Creates a replayer executing batches with the same VM parameters as the main node state keeper
+    /// with the provided config.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        l2_chain_id: L2ChainId,
+        state_keeper_config: &StateKeeperConfig,
+    ) -> Self {
+        Self {
+            pool,
+            l2_chain_id,
+            validation_computational_gas_limit: state_keeper_config
+                .validation_computational_gas_limit,
+            // The main node state keeper requires published bytecodes to be compressed.
+            optional_bytecode_compression: false,
+        }
+    }
+
+    /// Replays the specified L1 batch, which must be sealed.
+    pub async fn replay(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<ReplayReport> {
+        let replayed = self
+            .execute_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("failed replaying L1 batch #{l1_batch_number}"))?;
+        let stored = self
+            .load_stored_outcome(l1_batch_number, &replayed.outcome.tx_hashes)
+            .await
+            .with_context(|| {
+                format!("failed loading stored data for L1 batch #{l1_batch_number}")
+            })?;
+
+        let mut divergences = replayed.rejections;
+        divergences.extend(compare_outcomes(&stored, &replayed.outcome));
+        Ok(ReplayReport {
+            l1_batch_number,
+            tx_count: replayed.outcome.tx_hashes.len(),
+            divergences,
+        })
+    }
+
+    /// Re-executes the specified L1 batch, which must be sealed, and returns its protective reads
+    /// (i.e., deduplicated reads of storage slots not written to in the batch).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any transaction in the batch is rejected during re-execution, since protective reads
+    /// would be unreliable in this case.
+    pub async fn protective_reads(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<LogQuery>> {
+        let replayed = self
+            .execute_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("failed replaying L1 batch #{l1_batch_number}"))?;
+        anyhow::ensure!(
+            replayed.rejections.is_empty(),
+            "transactions were rejected when replaying L1 batch #{l1_batch_number}: {:?}",
+            replayed.rejections
+        );
+        Ok(replayed.protective_reads)
+    }
+
+    async fn execute_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<ReplayedBatch> {
+        // The genesis batch is not executed by the state keeper, so there is nothing to replay.
+        anyhow::ensure!(
+            l1_batch_number > L1BatchNumber(0),
+            "genesis L1 batch #0 cannot be replayed"
+        );
+        let mut storage = self.pool.connection_tagged("batch_replay").await?;
+        let sealed_l1_batch_number = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("no L1 batches are sealed")?;
+        anyhow::ensure!(
+            l1_batch_number <= sealed_l1_batch_number,
+            "L1 batch #{l1_batch_number} is not sealed; latest sealed L1 batch is #{sealed_l1_batch_number}"
+        );
+
+        let params_provider = L1BatchParamsProvider::new(&mut storage)
+            .await
+            .context("failed initializing L1 batch params provider")?;
+        let first_miniblock_in_batch = params_provider
+            .load_first_miniblock_in_batch(&mut storage, l1_batch_number)
+            .await
+            .context("failed loading first miniblock in batch")?
+            .context("no miniblocks persisted for L1 batch")?;
+        let (system_env, l1_batch_env) = params_provider
+            .load_l1_batch_params(
+                &mut storage,
+                &first_miniblock_in_batch,
+                self.validation_computational_gas_limit,
+                self.l2_chain_id,
+            )
+            .await
+            .context("failed loading L1 batch params")?;
+        let miniblocks = Self::load_miniblocks(&mut storage, l1_batch_number).await?;
+        drop(storage);
+
+        let storage_factory = HistoricalStorageFactory {
+            pool: self.pool.clone(),
+            miniblock_number: first_miniblock_in_batch.number() - 1,
+        };
+        let mut executor = MainBatchExecutor::new(
+            Arc::new(storage_factory),
+            false,
+            self.optional_bytecode_compression,
+        );
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let batch_executor = executor
+            .init_batch(l1_batch_env, system_env, &stop_receiver)
+            .await
+            .context("failed initializing batch executor")?;
+
+        let mut batch = ReplayedBatch::default();
+        let miniblock_count = miniblocks.len();
+        for (i, (miniblock_env, txs)) in miniblocks.into_iter().enumerate() {
+            // The first miniblock is started when initializing the batch.
+            if i > 0 {
+                batch_executor.start_next_miniblock(miniblock_env).await;
+            }
+            tracing::debug!(
+                "Replaying miniblock #{} with {} transactions",
+                miniblock_env.number,
+                txs.len()
+            );
+
+            let mut storage_writes = StorageWritesDeduplicator::new();
+            for tx in txs {
+                let tx_hash = tx.hash();
+                let gas_limit = tx.gas_limit();
+                batch.outcome.tx_hashes.push(tx_hash);
+                let tx_result = match batch_executor.execute_tx(tx).await {
+                    TxExecutionResult::Success { tx_result, .. } => tx_result,
+                    TxExecutionResult::RejectedByVm { reason } => {
+                        batch.reject_tx(tx_hash, reason.to_string());
+                        batch_executor.rollback_last_tx().await;
+                        continue;
+                    }
+                    TxExecutionResult::BootloaderOutOfGasForTx => {
+                        batch.reject_tx(tx_hash, "bootloader is out of gas".to_owned());
+                        batch_executor.rollback_last_tx().await;
+                        continue;
+                    }
+                };
+
+                storage_writes.apply(write_logs(&tx_result.logs.storage_logs));
+                let outcome = TxOutcome {
+                    is_success: !tx_result.result.is_failed(),
+                    gas_used: gas_limit - U256::from(tx_result.refunds.gas_refunded),
+                    events: tx_result.logs.events.into_iter().map(Into::into).collect(),
+                };
+                batch.outcome.txs.insert(tx_hash, outcome);
+            }
+
+            if i + 1 == miniblock_count {
+                // The last (fictive) miniblock in the batch additionally contains the batch tip.
+                let finished_batch = batch_executor.finish_batch().await;
+                storage_writes.apply(write_logs(
+                    &finished_batch.block_tip_execution_result.logs.storage_logs,
+                ));
+                batch.protective_reads = finished_batch
+                    .final_execution_state
+                    .deduplicated_storage_log_queries
+                    .into_iter()
+                    .filter(|log_query| !log_query.rw_flag)
+                    .collect();
+                batch.extend_storage_writes(storage_writes);
+                return Ok(batch);
+            }
+            batch.extend_storage_writes(storage_writes);
+        }
+        unreachable!("L1 batch always contains at least one miniblock");
+    }
+
+    /// Loads environments and transactions for all miniblocks in the batch, including the fictive miniblock.
+    async fn load_miniblocks(
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<(L2BlockEnv, Vec<Transaction>)>> {
+        let mut miniblocks_with_txs: HashMap<_, _> = storage
+            .transactions_dal()
+            .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+            .await?
+            .into_iter()
+            .map(|miniblock| (miniblock.number, miniblock))
+            .collect();
+        let (first_miniblock, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await?
+            .context("L1 batch doesn't have miniblocks")?;
+
+        let mut miniblocks = vec![];
+        for number in (first_miniblock.0..=last_miniblock.0).map(MiniblockNumber) {
+            if let Some(miniblock) = miniblocks_with_txs.remove(&number) {
+                miniblocks.push((L2BlockEnv::from_miniblock_data(&miniblock), miniblock.txs));
+                continue;
+            }
+
+            // Miniblocks without transactions (e.g., the fictive miniblock) are not returned by the query above.
+            let header = storage
+                .blocks_dal()
+                .get_miniblock_header(number)
+                .await?
+                .with_context(|| format!("miniblock #{number} is missing"))?;
+            let prev_block_hash = storage
+                .blocks_dal()
+                .get_miniblock_header(number - 1)
+                .await?
+                .with_context(|| format!("miniblock #{} is missing", number - 1))?
+                .hash;
+            let env = L2BlockEnv {
+                number: number.0,
+                timestamp: header.timestamp,
+                prev_block_hash,
+                max_virtual_blocks_to_create: header.virtual_blocks,
+            };
+            miniblocks.push((env, vec![]));
+        }
+        Ok(miniblocks)
+    }
+
+    async fn load_stored_outcome(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        tx_hashes: &[H256],
+    ) -> anyhow::Result<BatchOutcome> {
+        let mut storage = self.pool.connection_tagged("batch_replay").await?;
+        let receipts = storage
+            .transactions_web3_dal()
+            .get_transaction_receipts(tx_hashes)
+            .await?;
+        let txs = receipts
+            .into_iter()
+            .map(|receipt| {
+                let outcome = TxOutcome {
+                    is_success: receipt.status == U64::one(),
+                    gas_used: receipt.gas_used.unwrap_or_default(),
+                    events: receipt.logs.into_iter().map(Into::into).collect(),
+                };
+                (receipt.transaction_hash, outcome)
+            })
+            .collect();
+        let storage_writes = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_l1_batch(l1_batch_number)
+            .await?;
+        Ok(BatchOutcome {
+            tx_hashes: tx_hashes.to_vec(),
+            txs,
+            storage_writes,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct ReplayedBatch {
+    rejections: Vec<ReplayDivergence>,
+    outcome: BatchOutcome,
+    /// Deduplicated storage reads of slots not written to in the batch.
+    protective_reads: Vec<LogQuery>,
+}
+
+impl ReplayedBatch {
+    fn reject_tx(&mut self, tx_hash: H256, reason: String) {
+        tracing::warn!("Transaction {tx_hash:?} was rejected during replay: {reason}");
+        self.rejections
+            .push(ReplayDivergence::TxRejected { tx_hash, reason });
+    }
+
+    /// Mirrors deduplication of storage writes performed by the state keeper when sealing a miniblock.
+    fn extend_storage_writes(&mut self, deduplicator: StorageWritesDeduplicator) {
+        let writes = deduplicator
+            .into_modified_key_values()
+            .into_iter()
+            .map(|(key, slot)| (key, u256_to_h256(slot.value)));
+        self.outcome.storage_writes.extend(writes);
+    }
+}
+
+fn write_logs(logs: &[StorageLogQuery]) -> impl Iterator<Item = &StorageLogQuery> {
+    logs.iter().filter(|log| log.log_query.rw_flag)
+}
+
+/// Compares stored and replayed outcomes of an L1 batch. Transactions rejected during replay
+/// are expected to be missing from `replayed` and are skipped.
+fn compare_outcomes(stored: &BatchOutcome, replayed: &BatchOutcome) -> Vec<ReplayDivergence> {
+    let mut divergences = vec![];
+    for tx_hash in &stored.tx_hashes {
+        let (Some(stored_tx), Some(replayed_tx)) =
+            (stored.txs.get(tx_hash), replayed.txs.get(tx_hash))
+        else {
+            continue;
+        };
+        let tx_hash = *tx_hash;
+
+        if stored_tx.is_success != replayed_tx.is_success {
+            divergences.push(ReplayDivergence::TxStatus {
+                tx_hash,
+                stored_success: stored_tx.is_success,
+                replayed_success: replayed_tx.is_success,
+            });
+        }
+        if stored_tx.gas_used != replayed_tx.gas_used {
+            divergences.push(ReplayDivergence::GasUsed {
+                tx_hash,
+                stored: stored_tx.gas_used,
+                replayed: replayed_tx.gas_used,
+            });
+        }
+        if stored_tx.events != replayed_tx.events {
+            let first_mismatch = stored_tx
+                .events
+                .iter()
+                .zip(&replayed_tx.events)
+                .position(|(stored, replayed)| stored != replayed)
+                .unwrap_or_else(|| stored_tx.events.len().min(replayed_tx.events.len()));
+            divergences.push(ReplayDivergence::Events {
+                tx_hash,
+                stored_count: stored_tx.events.len(),
+                replayed_count: replayed_tx.events.len(),
+                first_mismatch,
+            });
+        }
+    }
+
+    let all_keys: BTreeSet<_> = stored
+        .storage_writes
+        .keys()
+        .chain(replayed.storage_writes.keys())
+        .collect();
+    let storage_divergences = all_keys.into_iter().filter_map(|key| {
+        let stored = stored.storage_writes.get(key).copied();
+        let replayed = replayed.storage_writes.get(key).copied();
+        (stored != replayed).then(|| ReplayDivergence::StorageWrite {
+            address: *key.address(),
+            key: *key.key(),
+            stored,
+            replayed,
+        })
+    });
+    divergences.extend(storage_divergences);
+    divergences
+}
@@ -0,0 +1,142 @@
+//! Tests for batch replay.
+
+use zksync_types::AccountTreeId;
+
+use super::*;
+use crate::genesis::{insert_genesis_batch, GenesisParams};
+
+fn event(topic: u8) -> TxEvent {
+    TxEvent {
+        address: Address::repeat_byte(1),
+        topics: vec![H256::repeat_byte(topic)],
+        data: vec![topic; 32],
+    }
+}
+
+fn outcome() -> BatchOutcome {
+    let tx_hash = H256::repeat_byte(0x10);
+    let tx = TxOutcome {
+        is_success: true,
+        gas_used: 100_000.into(),
+        events: vec![event(1), event(2)],
+    };
+    let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(2)), H256::zero());
+    BatchOutcome {
+        tx_hashes: vec![tx_hash],
+        txs: HashMap::from([(tx_hash, tx)]),
+        storage_writes: HashMap::from([(key, H256::repeat_byte(3))]),
+    }
+}
+
+#[test]
+fn comparing_identical_outcomes() {
+    let divergences = compare_outcomes(&outcome(), &outcome());
+    assert!(divergences.is_empty(), "{divergences:?}");
+}
+
+#[test]
+fn comparing_diverging_txs() {
+    let stored = outcome();
+    let mut replayed = outcome();
+    let tx_hash = stored.tx_hashes[0];
+    let replayed_tx = replayed.txs.get_mut(&tx_hash).unwrap();
+    replayed_tx.is_success = false;
+    replayed_tx.gas_used = 120_000.into();
+    replayed_tx.events[1] = event(3);
+
+    let divergences = compare_outcomes(&stored, &replayed);
+    assert_eq!(
+        divergences,
+        [
+            ReplayDivergence::TxStatus {
+                tx_hash,
+                stored_success: true,
+                replayed_success: false,
+            },
+            ReplayDivergence::GasUsed {
+                tx_hash,
+                stored: 100_000.into(),
+                replayed: 120_000.into(),
+            },
+            ReplayDivergence::Events {
+                tx_hash,
+                stored_count: 2,
+                replayed_count: 2,
+                first_mismatch: 1,
+            },
+        ]
+    );
+
+    let replayed_tx = replayed.txs.get_mut(&tx_hash).unwrap();
+    *replayed_tx = stored.txs[&tx_hash].clone();
+    replayed_tx.events.pop();
+    let divergences = compare_outcomes(&stored, &replayed);
+    assert_eq!(
+        divergences,
+        [ReplayDivergence::Events {
+            tx_hash,
+            stored_count: 2,
+            replayed_count: 1,
+            first_mismatch: 1,
+        }]
+    );
+}
+
+#[test]
+fn rejected_txs_are_skipped_when_comparing() {
+    let stored = outcome();
+    let mut replayed = outcome();
+    replayed.txs.clear();
+
+    let divergences = compare_outcomes(&stored, &replayed);
+    assert!(divergences.is_empty(), "{divergences:?}");
+}
+
+#[test]
+fn comparing_diverging_storage_writes() {
+    let stored = outcome();
+    let mut replayed = outcome();
+    let (&key, &stored_value) = stored.storage_writes.iter().next().unwrap();
+    replayed.storage_writes.insert(key, H256::zero());
+    let new_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(4)), H256::zero());
+    replayed
+        .storage_writes
+        .insert(new_key, H256::repeat_byte(5));
+
+    let divergences = compare_outcomes(&stored, &replayed);
+    assert_eq!(
+        divergences,
+        [
+            ReplayDivergence::StorageWrite {
+                address: *key.address(),
+                key: *key.key(),
+                stored: Some(stored_value),
+                replayed: Some(H256::zero()),
+            },
+            ReplayDivergence::StorageWrite {
+                address: *new_key.address(),
+                key: *new_key.key(),
+                stored: None,
+                replayed: Some(H256::repeat_byte(5)),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn replaying_genesis_batch_is_rejected() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let replayer = BatchReplayer::new(pool, L2ChainId::default(), &StateKeeperConfig::for_tests());
+    let err = replayer.replay(L1BatchNumber(0)).await.unwrap_err();
+    let err = format!("{err:#}");
+    assert!(
+        err.contains("genesis L1 batch #0 cannot be replayed"),
+        "{err}"
+    );
+}
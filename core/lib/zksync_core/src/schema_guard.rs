@@ -0,0 +1,164 @@
+//! Startup check of compatibility between the Postgres schema and the binary.
+//!
+//! Migrations are applied to the database separately from starting the node, so the database schema may not match
+//! the one the node was built for (e.g., if migrations weren't applied after an upgrade, or if the node binary
+//! was downgraded). Instead of failing later with obscure SQL errors, the node compares migrations applied
+//! to the database with ones embedded into the binary, and refuses to start on a mismatch.
+
+use std::{collections::HashMap, fmt};
+
+use anyhow::Context as _;
+use zksync_dal::{
+    system_dal::{AppliedMigration, MIGRATOR},
+    ConnectionPool, Core, CoreDal,
+};
+
+/// Difference between migrations expected by the binary and applied to the database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    /// Migrations expected by the binary, but not applied to the database.
+    pub missing: Vec<i64>,
+    /// Migrations applied to the database, but unknown to the binary (e.g., applied by a newer node version).
+    pub unknown: Vec<i64>,
+    /// Migrations with SQL differing between the binary and the database.
+    pub modified: Vec<i64>,
+    /// Migrations that have failed to apply.
+    pub failed: Vec<i64>,
+}
+
+impl SchemaDiff {
+    /// Compares expected migrations (versions and checksums) with the applied ones.
+    pub fn new<'a>(
+        expected: impl IntoIterator<Item = (i64, &'a [u8])>,
+        applied: &[AppliedMigration],
+    ) -> Self {
+        let mut applied: HashMap<_, _> = applied
+            .iter()
+            .map(|migration| (migration.version, migration))
+            .collect();
+        let mut diff = Self::default();
+        for (version, checksum) in expected {
+            match applied.remove(&version) {
+                None => diff.missing.push(version),
+                Some(migration) if !migration.success => diff.failed.push(version),
+                Some(migration) if migration.checksum != checksum => diff.modified.push(version),
+                Some(_) => { /* migration is OK */ }
+            }
+        }
+        diff.unknown = applied.into_keys().collect();
+        diff.unknown.sort_unstable();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty()
+            && self.unknown.is_empty()
+            && self.modified.is_empty()
+            && self.failed.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return formatter.write_str("schema is up to date");
+        }
+
+        let mut parts = vec![];
+        if !self.missing.is_empty() {
+            parts.push(format!(
+                "migrations not applied to the database (apply them before starting the node): {:?}",
+                self.missing
+            ));
+        }
+        if !self.unknown.is_empty() {
+            parts.push(format!(
+                "migrations unknown to this binary (was the node downgraded?): {:?}",
+                self.unknown
+            ));
+        }
+        if !self.modified.is_empty() {
+            parts.push(format!(
+                "migrations modified after being applied: {:?}",
+                self.modified
+            ));
+        }
+        if !self.failed.is_empty() {
+            parts.push(format!("failed migrations: {:?}", self.failed));
+        }
+        formatter.write_str(&parts.join("; "))
+    }
+}
+
+/// Compares migrations applied to the database with ones embedded into the binary.
+pub async fn check_schema(pool: &ConnectionPool<Core>) -> anyhow::Result<SchemaDiff> {
+    let mut storage = pool.connection_tagged("schema_guard").await?;
+    let applied = storage
+        .system_dal()
+        .get_applied_migrations()
+        .await
+        .context("failed loading applied migrations; were migrations applied using `sqlx`?")?;
+    let expected = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| (migration.version, &*migration.checksum));
+    Ok(SchemaDiff::new(expected, &applied))
+}
+
+/// Ensures that the database schema is compatible with the binary. Returns an error describing the mismatch
+/// otherwise.
+pub async fn ensure_compatibility(pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+    let diff = check_schema(pool).await?;
+    anyhow::ensure!(
+        diff.is_empty(),
+        "Postgres schema is incompatible with this binary: {diff}"
+    );
+    tracing::info!("Checked Postgres schema compatibility: {diff}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied_migration(version: i64, checksum: &[u8]) -> AppliedMigration {
+        AppliedMigration {
+            version,
+            description: format!("migration {version}"),
+            success: true,
+            checksum: checksum.to_vec(),
+        }
+    }
+
+    #[test]
+    fn computing_schema_diff() {
+        let applied = [
+            applied_migration(1, b"1"),
+            applied_migration(2, b"2"),
+            AppliedMigration {
+                success: false,
+                ..applied_migration(3, b"3")
+            },
+            applied_migration(4, b"4"),
+            applied_migration(6, b"6"),
+        ];
+        let expected: [(i64, &[u8]); 5] = [(1, b"1"), (2, b"!"), (3, b"3"), (4, b"4"), (5, b"5")];
+
+        let diff = SchemaDiff::new(expected, &applied);
+        assert_eq!(
+            diff,
+            SchemaDiff {
+                missing: vec![5],
+                unknown: vec![6],
+                modified: vec![2],
+                failed: vec![3],
+            }
+        );
+        let message = diff.to_string();
+        assert!(message.contains("not applied to the database"), "{message}");
+        assert!(message.contains("unknown to this binary"), "{message}");
+
+        let diff = SchemaDiff::new(expected[..1].iter().copied(), &applied[..1]);
+        assert!(diff.is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! Admin HTTP endpoint of the state keeper.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use axum::{extract::State, handler::Handler, middleware, routing::get, Json, Router};
+use serde::Serialize;
+use tokio::sync::watch;
+
+use super::{
+    control::{SoftShutdownStatus, StateKeeperControl},
+    tx_filter::{PolicyTxFilter, TxFilterPolicy},
+};
+use crate::utils::auth::require_bearer_token;
+
+#[derive(Debug, Clone)]
+struct AdminState {
+    control: StateKeeperControl,
+    tx_filter: PolicyTxFilter,
+}
+
+#[derive(Debug, Serialize)]
+struct SoftShutdownResponse {
+    status: SoftShutdownStatus,
+}
+
+async fn soft_shutdown_status(state: State<AdminState>) -> Json<SoftShutdownResponse> {
+    Json(SoftShutdownResponse {
+        status: state.control.soft_shutdown_status(),
+    })
+}
+
+async fn request_soft_shutdown(state: State<AdminState>) -> Json<SoftShutdownResponse> {
+    state.control.request_soft_shutdown();
+    soft_shutdown_status(state).await
+}
+
+async fn tx_filter_policy(state: State<AdminState>) -> Json<TxFilterPolicy> {
+    Json(state.tx_filter.policy())
+}
+
+async fn update_tx_filter_policy(
+    state: State<AdminState>,
+    Json(policy): Json<TxFilterPolicy>,
+) -> Json<TxFilterPolicy> {
+    state.tx_filter.set_policy(policy);
+    tx_filter_policy(state).await
+}
+
+/// Runs the state keeper admin server until a stop signal is received. The server exposes the following endpoints:
+///
+/// - `GET /soft_shutdown`: returns the soft shutdown status (`not_requested`, `requested` or `completed`).
+/// - `POST /soft_shutdown`: requests the state keeper to seal the current L1 batch and stop processing transactions.
+/// - `GET /tx_filter`: returns the enforced transaction filter policy.
+/// - `PUT /tx_filter`: replaces the transaction filter policy; the new policy is provided as JSON in the request body.
+///
+/// `POST /soft_shutdown` and `PUT /tx_filter` require the `Authorization: Bearer {token}` header.
+pub async fn run_admin_server(
+    bind_address: SocketAddr,
+    token: String,
+    control: StateKeeperControl,
+    tx_filter: PolicyTxFilter,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let token: Arc<str> = token.into();
+    let auth = middleware::from_fn_with_state(token, require_bearer_token);
+    let app = Router::new()
+        .route(
+            "/soft_shutdown",
+            get(soft_shutdown_status).post(request_soft_shutdown.layer(auth.clone())),
+        )
+        .route(
+            "/tx_filter",
+            get(tx_filter_policy).put(update_tx_filter_policy.layer(auth)),
+        )
+        .with_state(AdminState { control, tx_filter });
+
+    tracing::info!("Starting state keeper admin server on {bind_address}");
+    axum::Server::try_bind(&bind_address)
+        .with_context(|| format!("failed binding state keeper admin server to {bind_address}"))?
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!("Stop signal sender for state keeper admin server was dropped without sending a signal");
+            }
+            tracing::info!("Stop signal received, state keeper admin server is shutting down");
+        })
+        .await
+        .context("state keeper admin server failed")?;
+    tracing::info!("State keeper admin server shut down");
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
 use multivm::{
@@ -8,18 +8,24 @@ use multivm::{
     },
     tracers::CallTracer,
     vm_latest::HistoryEnabled,
-    MultiVMTracer, VmInstance,
+    MultiVMTracer, VmInstance, VmVersion,
 };
 use once_cell::sync::OnceCell;
 use tokio::{
     runtime::Handle,
     sync::{mpsc, watch},
 };
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::{ConnectionPool, Core};
 use zksync_state::{ReadStorage, StorageView, WriteStorage};
-use zksync_types::{vm_trace::Call, Transaction};
+use zksync_types::{vm_trace::Call, ProtocolVersionId, Transaction};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
-use super::{BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult};
+use super::{
+    shadow::{ShadowCommand, ShadowVmHandle},
+    speculative::{PrefetchingStorage, SpeculativeExecutor},
+    BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult,
+};
 use crate::{
     metrics::{InteractionType, TxStage, APP_METRICS},
     state_keeper::{
@@ -29,6 +35,17 @@ use crate::{
     },
 };
 
+/// Returns the shadow VM version specified in the state keeper config, or `None` if shadow execution is disabled.
+pub fn shadow_vm_version(config: &StateKeeperConfig) -> anyhow::Result<Option<VmVersion>> {
+    let Some(protocol_version) = config.shadow_vm_protocol_version else {
+        return Ok(None);
+    };
+    let protocol_version = ProtocolVersionId::try_from(protocol_version).map_err(|_| {
+        anyhow::anyhow!("unsupported shadow VM protocol version: {protocol_version}")
+    })?;
+    Ok(Some(protocol_version.into()))
+}
+
 /// The default implementation of [`BatchExecutor`].
 /// Creates a "real" batch executor which maintains the VM (as opposed to the test builder which doesn't use the VM).
 #[derive(Debug, Clone)]
@@ -36,6 +53,8 @@ pub struct MainBatchExecutor {
     storage_factory: Arc<dyn ReadStorageFactory>,
     save_call_traces: bool,
     optional_bytecode_compression: bool,
+    parallel_reexecution_concurrency: usize,
+    shadow_execution: Option<(VmVersion, ConnectionPool<Core>)>,
 }
 
 impl MainBatchExecutor {
@@ -48,8 +67,36 @@ impl MainBatchExecutor {
             storage_factory,
             save_call_traces,
             optional_bytecode_compression,
+            parallel_reexecution_concurrency: 0,
+            shadow_execution: None,
         }
     }
+
+    /// Enables optimistic parallel pre-execution of transactions hinted by the state keeper using
+    /// the specified number of worker threads. Each worker uses a separate storage instance
+    /// obtained from the storage factory, so [`BatchExecutor::init_batch()`] obtains `concurrency + 1` storage instances
+    /// per L1 batch (plus one more for the shadow VM if shadow execution is enabled). Setting `concurrency` to 0
+    /// disables pre-execution.
+    ///
+    /// Pre-execution only prefetches storage; transactions are still executed sequentially by the batch VM,
+    /// and only its results are returned.
+    pub fn with_parallel_reexecution(mut self, concurrency: usize) -> Self {
+        self.parallel_reexecution_concurrency = concurrency;
+        self
+    }
+
+    /// Enables shadow execution: each transaction and the batch tip are additionally executed in the specified
+    /// VM version (on a separate thread using a separate storage instance), and results are compared with the main VM.
+    /// Divergences are recorded to Postgres using the provided pool; they never affect the results returned
+    /// by the executor.
+    pub fn with_shadow_execution(
+        mut self,
+        vm_version: VmVersion,
+        pool: ConnectionPool<Core>,
+    ) -> Self {
+        self.shadow_execution = Some((vm_version, pool));
+        self
+    }
 }
 
 #[async_trait]
@@ -66,17 +113,42 @@ impl BatchExecutor for MainBatchExecutor {
         let executor = CommandReceiver {
             save_call_traces: self.save_call_traces,
             optional_bytecode_compression: self.optional_bytecode_compression,
+            protocol_version: system_env.version,
             commands: commands_receiver,
         };
 
         let storage_factory = self.storage_factory.clone();
+        let worker_count = self.parallel_reexecution_concurrency;
         let stop_receiver = stop_receiver.clone();
+        let shadow = self.shadow_execution.clone().map(|(vm_version, pool)| {
+            ShadowVmHandle::spawn(
+                storage_factory.clone(),
+                stop_receiver.clone(),
+                pool,
+                l1_batch_params.clone(),
+                system_env.clone(),
+                vm_version,
+            )
+        });
         let handle = tokio::task::spawn_blocking(move || {
-            if let Some(storage) = Handle::current()
-                .block_on(storage_factory.access_storage(&stop_receiver))
-                .expect("failed getting access to state keeper storage")
-            {
-                executor.run(storage, l1_batch_params, system_env);
+            // The first storage instance is used by the batch VM, the rest are used by speculative execution workers.
+            let storages: Option<Vec<_>> = (0..=worker_count)
+                .map(|_| {
+                    Handle::current()
+                        .block_on(storage_factory.access_storage(&stop_receiver))
+                        .expect("failed getting access to state keeper storage")
+                })
+                .collect();
+            if let Some(mut storages) = storages {
+                let storage = storages.remove(0);
+                let worker_storages = (worker_count > 0).then_some(storages);
+                executor.run(
+                    storage,
+                    shadow,
+                    worker_storages,
+                    l1_batch_params,
+                    system_env,
+                );
             } else {
                 tracing::info!("Interrupted while trying to access state keeper storage");
             }
@@ -98,39 +170,80 @@ impl BatchExecutor for MainBatchExecutor {
 struct CommandReceiver {
     save_call_traces: bool,
     optional_bytecode_compression: bool,
+    protocol_version: ProtocolVersionId,
     commands: mpsc::Receiver<Command>,
 }
 
 impl CommandReceiver {
-    pub(super) fn run<S: ReadStorage>(
+    pub(super) fn run<S: ReadStorage + fmt::Debug + Send>(
         mut self,
         secondary_storage: S,
+        mut shadow: Option<ShadowVmHandle>,
+        worker_storages: Option<Vec<S>>,
         l1_batch_params: L1BatchEnv,
         system_env: SystemEnv,
     ) {
         tracing::info!("Starting executing batch #{:?}", &l1_batch_params.number);
 
+        let secondary_storage = PrefetchingStorage::new(secondary_storage);
+        // Speculative execution is an optimization, so it's disabled rather than failing the batch on error.
+        let mut speculative_executor = worker_storages.and_then(|storages| {
+            SpeculativeExecutor::new(
+                storages,
+                &secondary_storage,
+                l1_batch_params.clone(),
+                system_env.clone(),
+            )
+            .map_err(|err| tracing::warn!("Speculative execution is disabled: {err:#}"))
+            .ok()
+        });
         let storage_view = StorageView::new(secondary_storage).to_rc_ptr();
 
         let mut vm = VmInstance::new(l1_batch_params, system_env, storage_view.clone());
 
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
+                // Shadow VM commands are enqueued only after responding, so that they don't delay the state keeper.
                 Command::ExecuteTx(tx, resp) => {
                     let result = self.execute_tx(&tx, &mut vm);
+                    let shadow_command = shadow
+                        .is_some()
+                        .then(|| ShadowCommand::execute_tx(tx, &result));
                     resp.send(result).unwrap();
+                    if let (Some(shadow), Some(command)) = (&mut shadow, shadow_command) {
+                        shadow.send(command);
+                    }
                 }
                 Command::RollbackLastTx(resp) => {
                     self.rollback_last_tx(&mut vm);
                     resp.send(()).unwrap();
+                    if let Some(shadow) = &mut shadow {
+                        shadow.send(ShadowCommand::RollbackLastTx);
+                    }
                 }
                 Command::StartNextMiniblock(l2_block_env, resp) => {
                     self.start_next_miniblock(l2_block_env, &mut vm);
                     resp.send(()).unwrap();
+                    if let Some(shadow) = &mut shadow {
+                        shadow.send(ShadowCommand::StartNextMiniblock(l2_block_env));
+                    }
+                }
+                Command::SpeculateTxs(txs, resp) => {
+                    if let Some(speculative_executor) = &mut speculative_executor {
+                        let storage_view = storage_view.as_ref().borrow();
+                        speculative_executor.speculate(&txs, storage_view.modified_storage_keys());
+                    }
+                    resp.send(()).unwrap();
                 }
                 Command::FinishBatch(resp) => {
                     let vm_block_result = self.finish_batch(&mut vm);
+                    let shadow_command = shadow
+                        .is_some()
+                        .then(|| ShadowCommand::FinishBatch(Box::new(vm_block_result.clone())));
                     resp.send(vm_block_result).unwrap();
+                    if let (Some(shadow), Some(command)) = (&mut shadow, shadow_command) {
+                        shadow.send(command);
+                    }
 
                     // `storage_view` cannot be accessed while borrowed by the VM,
                     // so this is the only point at which storage metrics can be obtained
@@ -163,7 +276,7 @@ impl CommandReceiver {
             } else {
                 self.execute_tx_in_vm(tx, vm)
             };
-        latency.observe();
+        let execution_time = latency.observe();
         APP_METRICS.processed_txs[&TxStage::StateKeeper].inc();
         APP_METRICS.processed_l1_txs[&TxStage::StateKeeper].inc_by(tx.is_l1().into());
 
@@ -174,7 +287,8 @@ impl CommandReceiver {
             };
         }
 
-        let tx_metrics = ExecutionMetricsForCriteria::new(Some(tx), &tx_result);
+        let tx_metrics =
+            ExecutionMetricsForCriteria::new(Some(tx), &tx_result, self.protocol_version);
         let gas_remaining = vm.gas_remaining();
 
         TxExecutionResult::Success {
@@ -183,6 +297,7 @@ impl CommandReceiver {
             compressed_bytecodes,
             call_tracer_result,
             gas_remaining,
+            execution_time,
         }
     }
 
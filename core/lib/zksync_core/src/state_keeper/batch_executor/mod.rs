@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use async_trait::async_trait;
 use multivm::interface::{
@@ -20,6 +20,8 @@ use crate::state_keeper::{
 mod tests;
 
 pub mod main_executor;
+mod shadow;
+mod speculative;
 
 /// Representation of a transaction executed in the virtual machine.
 #[derive(Debug, Clone)]
@@ -31,6 +33,8 @@ pub(crate) enum TxExecutionResult {
         compressed_bytecodes: Vec<CompressedBytecodeInfo>,
         call_tracer_result: Vec<Call>,
         gas_remaining: u32,
+        /// Wall time spent on executing the transaction in the VM.
+        execution_time: Duration,
     },
     /// The VM rejected the tx for some reason.
     RejectedByVm { reason: Halt },
@@ -81,7 +85,7 @@ impl BatchExecutorHandle {
         Self { handle, commands }
     }
 
-    pub(super) async fn execute_tx(&self, tx: Transaction) -> TxExecutionResult {
+    pub(crate) async fn execute_tx(&self, tx: Transaction) -> TxExecutionResult {
         let tx_gas_limit = tx.gas_limit().as_u32();
 
         let (response_sender, response_receiver) = oneshot::channel();
@@ -113,7 +117,7 @@ impl BatchExecutorHandle {
         res
     }
 
-    pub(super) async fn start_next_miniblock(&self, miniblock_info: L2BlockEnv) {
+    pub(crate) async fn start_next_miniblock(&self, miniblock_info: L2BlockEnv) {
         // While we don't get anything from the channel, it's useful to have it as a confirmation that the operation
         // indeed has been processed.
         let (response_sender, response_receiver) = oneshot::channel();
@@ -128,7 +132,23 @@ impl BatchExecutorHandle {
         latency.observe();
     }
 
-    pub(super) async fn rollback_last_tx(&self) {
+    /// Hints the executor that the provided transactions will (likely) be executed next in the current batch.
+    /// The executor may use this information to speed up their execution; executing transactions is still
+    /// performed with [`Self::execute_tx()`].
+    pub(super) async fn speculate_txs(&self, txs: Vec<Transaction>) {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.commands
+            .send(Command::SpeculateTxs(txs, response_sender))
+            .await
+            .unwrap();
+        let latency = EXECUTOR_METRICS.batch_executor_command_response_time
+            [&ExecutorCommand::SpeculateTxs]
+            .start();
+        response_receiver.await.unwrap();
+        latency.observe();
+    }
+
+    pub(crate) async fn rollback_last_tx(&self) {
         // While we don't get anything from the channel, it's useful to have it as a confirmation that the operation
         // indeed has been processed.
         let (response_sender, response_receiver) = oneshot::channel();
@@ -143,7 +163,7 @@ impl BatchExecutorHandle {
         latency.observe();
     }
 
-    pub(super) async fn finish_batch(self) -> FinishedL1Batch {
+    pub(crate) async fn finish_batch(self) -> FinishedL1Batch {
         let (response_sender, response_receiver) = oneshot::channel();
         self.commands
             .send(Command::FinishBatch(response_sender))
@@ -164,5 +184,6 @@ pub(super) enum Command {
     ExecuteTx(Box<Transaction>, oneshot::Sender<TxExecutionResult>),
     StartNextMiniblock(L2BlockEnv, oneshot::Sender<()>),
     RollbackLastTx(oneshot::Sender<()>),
+    SpeculateTxs(Vec<Transaction>, oneshot::Sender<()>),
     FinishBatch(oneshot::Sender<FinishedL1Batch>),
 }
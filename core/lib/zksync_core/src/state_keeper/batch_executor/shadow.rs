@@ -0,0 +1,412 @@
+//! Shadow execution of transactions in another VM version, used to validate VM releases against live traffic
+//! before a protocol upgrade switches to them.
+//!
+//! The shadow VM receives the same commands as the main VM but runs on a dedicated thread with a separate storage
+//! instance. Commands are queued only after the main VM has responded to them, so shadow execution doesn't add latency
+//! to the main VM. Shadow VM results are compared with the main VM results, and divergences are persisted
+//! after the batch is finished. Shadow execution never affects results returned by the batch executor.
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, TrySendError},
+        Arc,
+    },
+    thread,
+};
+
+use multivm::{
+    interface::{
+        ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv,
+        VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
+    },
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+use tokio::{runtime::Handle, sync::watch};
+use zksync_dal::{vm_shadow_divergences_dal::VmShadowDivergence, ConnectionPool, Core, CoreDal};
+use zksync_state::{ReadStorage, StorageView};
+use zksync_types::{
+    zk_evm_types::LogQuery, L1BatchNumber, StorageLogQuery, Transaction, VmVersion, H256,
+};
+
+use super::TxExecutionResult;
+use crate::state_keeper::{metrics::EXECUTOR_METRICS, state_keeper_storage::ReadStorageFactory};
+
+/// Maximum number of commands queued for the shadow VM. If the shadow VM lags further behind the main VM,
+/// shadow execution is stopped for the batch, so that the queue doesn't grow unboundedly.
+const COMMAND_QUEUE_CAPACITY: usize = 1_024;
+
+/// Command for the shadow VM, together with the main VM outcome (if any) to compare against.
+#[derive(Debug)]
+pub(super) enum ShadowCommand {
+    ExecuteTx(Box<Transaction>, Box<VmExecutionResultAndLogs>),
+    ExecuteRejectedTx(Box<Transaction>, Halt),
+    RollbackLastTx,
+    StartNextMiniblock(L2BlockEnv),
+    FinishBatch(Box<FinishedL1Batch>),
+}
+
+impl ShadowCommand {
+    pub fn execute_tx(tx: Box<Transaction>, main_result: &TxExecutionResult) -> Self {
+        // Rejected transactions are executed as well, since the state keeper rolls them back
+        // and rollbacks must be applied to both VMs.
+        match main_result {
+            TxExecutionResult::Success { tx_result, .. } => Self::ExecuteTx(tx, tx_result.clone()),
+            TxExecutionResult::BootloaderOutOfGasForTx => {
+                Self::ExecuteRejectedTx(tx, Halt::BootloaderOutOfGas)
+            }
+            TxExecutionResult::RejectedByVm { reason } => {
+                Self::ExecuteRejectedTx(tx, reason.clone())
+            }
+        }
+    }
+}
+
+/// Handle to a [`ShadowVm`] running on a dedicated thread.
+#[derive(Debug)]
+pub(super) struct ShadowVmHandle {
+    l1_batch_number: L1BatchNumber,
+    /// Set to `None` once shadow execution is stopped for the batch.
+    commands: Option<mpsc::SyncSender<ShadowCommand>>,
+}
+
+impl ShadowVmHandle {
+    /// Spawns a shadow VM thread for the batch. Must be called from a Tokio runtime context; the runtime is used
+    /// to access storage and to persist divergences.
+    pub fn spawn(
+        storage_factory: Arc<dyn ReadStorageFactory>,
+        stop_receiver: watch::Receiver<bool>,
+        pool: ConnectionPool<Core>,
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        vm_version: VmVersion,
+    ) -> Self {
+        let l1_batch_number = l1_batch_env.number;
+        let (commands_sender, commands_receiver) = mpsc::sync_channel(COMMAND_QUEUE_CAPACITY);
+        let runtime = Handle::current();
+        let spawn_result = thread::Builder::new()
+            .name(format!("shadow-vm-{l1_batch_number}"))
+            .spawn(move || {
+                let storage = runtime.block_on(storage_factory.access_storage(&stop_receiver));
+                let storage = match storage {
+                    Ok(Some(storage)) => storage,
+                    Ok(None) => {
+                        tracing::info!("Interrupted while trying to access shadow VM storage");
+                        return;
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed getting access to shadow VM storage: {err:#}");
+                        return;
+                    }
+                };
+                let shadow_vm = ShadowVm::new(storage, l1_batch_env, system_env, vm_version);
+                let Some(divergences) = shadow_vm.run(commands_receiver) else {
+                    return; // The batch wasn't finished
+                };
+                if !divergences.is_empty() {
+                    runtime.block_on(save_divergences(&pool, &divergences));
+                }
+                EXECUTOR_METRICS
+                    .shadow_vm_l1_batch
+                    .set(l1_batch_number.0.into());
+            });
+
+        // Shadow execution must not affect the state keeper, so errors are only logged.
+        let commands = match spawn_result {
+            Ok(_) => Some(commands_sender),
+            Err(err) => {
+                tracing::warn!("Failed spawning shadow VM thread: {err}");
+                None
+            }
+        };
+        Self {
+            l1_batch_number,
+            commands,
+        }
+    }
+
+    /// Enqueues a command for the shadow VM without blocking.
+    pub fn send(&mut self, command: ShadowCommand) {
+        let Some(commands) = &self.commands else {
+            return;
+        };
+        match commands.try_send(command) {
+            Ok(()) => { /* Normal operation */ }
+            Err(TrySendError::Full(_)) => {
+                tracing::warn!(
+                    "Shadow VM lags behind the main VM by {COMMAND_QUEUE_CAPACITY} commands \
+                     in L1 batch #{}; shadow execution is stopped for the batch",
+                    self.l1_batch_number
+                );
+                EXECUTOR_METRICS.shadow_vm_stopped_batches.inc();
+                self.commands = None;
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                // The shadow VM thread has exited (e.g., failed to access storage); the reason is already logged.
+                self.commands = None;
+            }
+        }
+    }
+}
+
+async fn save_divergences(pool: &ConnectionPool<Core>, divergences: &[VmShadowDivergence]) {
+    let result = async {
+        let mut connection = pool.connection_tagged("state_keeper").await?;
+        connection
+            .vm_shadow_divergences_dal()
+            .insert_divergences(divergences)
+            .await?;
+        anyhow::Ok(())
+    };
+    if let Err(err) = result.await {
+        tracing::warn!("Failed saving shadow VM divergences: {err:#}");
+    }
+}
+
+/// VM executing transactions in parallel with the main VM of the batch executor.
+#[derive(Debug)]
+struct ShadowVm<S: ReadStorage + fmt::Debug> {
+    /// Set to `None` if the shadow VM has panicked; its state cannot be relied upon afterwards.
+    vm: Option<VmInstance<StorageView<S>, HistoryEnabled>>,
+    vm_version: VmVersion,
+    l1_batch_number: L1BatchNumber,
+    divergences: Vec<VmShadowDivergence>,
+}
+
+impl<S: ReadStorage + fmt::Debug> ShadowVm<S> {
+    pub fn new(
+        storage: S,
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        vm_version: VmVersion,
+    ) -> Self {
+        let l1_batch_number = l1_batch_env.number;
+        let storage_view = StorageView::new(storage).to_rc_ptr();
+        let vm = VmInstance::new_with_specific_version(
+            l1_batch_env,
+            system_env,
+            storage_view,
+            vm_version,
+        );
+        Self {
+            vm: Some(vm),
+            vm_version,
+            l1_batch_number,
+            divergences: vec![],
+        }
+    }
+
+    /// Processes commands until the batch is finished. Returns divergences recorded for the batch, or `None`
+    /// if the command queue was closed before the batch was finished.
+    fn run(mut self, commands: mpsc::Receiver<ShadowCommand>) -> Option<Vec<VmShadowDivergence>> {
+        while let Ok(command) = commands.recv() {
+            match command {
+                ShadowCommand::ExecuteTx(tx, main_result) => self.execute_tx(&tx, &main_result),
+                ShadowCommand::ExecuteRejectedTx(tx, main_reason) => {
+                    self.execute_rejected_tx(&tx, &main_reason);
+                }
+                ShadowCommand::RollbackLastTx => self.rollback_last_tx(),
+                ShadowCommand::StartNextMiniblock(l2_block_env) => {
+                    self.start_next_miniblock(l2_block_env);
+                }
+                ShadowCommand::FinishBatch(main_result) => {
+                    return Some(self.finish_batch(&main_result));
+                }
+            }
+        }
+        None
+    }
+
+    /// Executes the transaction in the shadow VM and compares results with the main VM.
+    pub fn execute_tx(&mut self, tx: &Transaction, main_result: &VmExecutionResultAndLogs) {
+        if let Some(shadow_result) = self.execute_tx_in_vm(tx) {
+            let diffs = compare_results(main_result, &shadow_result);
+            self.record(Some(tx.hash()), diffs);
+        }
+    }
+
+    /// Executes the transaction rejected by the main VM in the shadow VM and checks that it's rejected
+    /// for the same reason.
+    pub fn execute_rejected_tx(&mut self, tx: &Transaction, main_reason: &Halt) {
+        let Some(shadow_result) = self.execute_tx_in_vm(tx) else {
+            return;
+        };
+        let diffs = match &shadow_result.result {
+            ExecutionResult::Halt { reason } if reason == main_reason => vec![],
+            shadow => vec![format!(
+                "execution result: rejected with {main_reason:?} (main) vs {shadow:?} (shadow)"
+            )],
+        };
+        self.record(Some(tx.hash()), diffs);
+    }
+
+    fn execute_tx_in_vm(&mut self, tx: &Transaction) -> Option<VmExecutionResultAndLogs> {
+        let latency = EXECUTOR_METRICS.shadow_vm_tx_execution_time.start();
+        let shadow_result = self.call_vm(Some(tx.hash()), "executing transaction", |vm| {
+            vm.make_snapshot();
+            let (compression_result, mut result) =
+                vm.execute_transaction_with_bytecode_compression(tx.clone(), true);
+            if compression_result.is_err() {
+                // Mirrors the handling in the main VM.
+                result.result = ExecutionResult::Halt {
+                    reason: Halt::FailedToPublishCompressedBytecodes,
+                };
+            }
+            result
+        });
+        latency.observe();
+        shadow_result
+    }
+
+    pub fn rollback_last_tx(&mut self) {
+        self.call_vm(None, "rolling back transaction", |vm| {
+            vm.rollback_to_the_latest_snapshot();
+        });
+    }
+
+    pub fn start_next_miniblock(&mut self, l2_block_env: L2BlockEnv) {
+        self.call_vm(None, "starting miniblock", |vm| {
+            vm.start_new_l2_block(l2_block_env);
+        });
+    }
+
+    /// Finishes the batch in the shadow VM, compares results with the main VM and returns all divergences
+    /// recorded for the batch.
+    pub fn finish_batch(mut self, main_result: &FinishedL1Batch) -> Vec<VmShadowDivergence> {
+        let shadow_result = self.call_vm(None, "finishing batch", VmInterface::finish_batch);
+        if let Some(shadow_result) = shadow_result {
+            let mut diffs = compare_results(
+                &main_result.block_tip_execution_result,
+                &shadow_result.block_tip_execution_result,
+            );
+            if let Some(diff) = compare_writes(
+                &main_result
+                    .final_execution_state
+                    .deduplicated_storage_log_queries,
+                &shadow_result
+                    .final_execution_state
+                    .deduplicated_storage_log_queries,
+                "final storage writes",
+            ) {
+                diffs.push(diff);
+            }
+            self.record(None, diffs);
+        }
+        self.divergences
+    }
+
+    fn call_vm<T>(
+        &mut self,
+        tx_hash: Option<H256>,
+        action: &str,
+        call: impl FnOnce(&mut VmInstance<StorageView<S>, HistoryEnabled>) -> T,
+    ) -> Option<T> {
+        let vm = self.vm.as_mut()?;
+        match panic::catch_unwind(AssertUnwindSafe(|| call(vm))) {
+            Ok(output) => Some(output),
+            Err(_) => {
+                self.vm = None;
+                self.record(
+                    tx_hash,
+                    vec![format!(
+                        "shadow VM panicked {action}; shadow execution is stopped for the batch"
+                    )],
+                );
+                None
+            }
+        }
+    }
+
+    fn record(&mut self, tx_hash: Option<H256>, diffs: Vec<String>) {
+        if diffs.is_empty() {
+            return;
+        }
+
+        let details = diffs.join("; ");
+        tracing::warn!(
+            "Shadow VM {:?} diverged from the main VM in L1 batch #{} (tx: {tx_hash:?}): {details}",
+            self.vm_version,
+            self.l1_batch_number
+        );
+        EXECUTOR_METRICS.shadow_vm_divergences.inc();
+        self.divergences.push(VmShadowDivergence {
+            l1_batch_number: self.l1_batch_number,
+            tx_hash,
+            shadow_vm_version: format!("{:?}", self.vm_version),
+            details,
+        });
+    }
+}
+
+fn compare_results(
+    main: &VmExecutionResultAndLogs,
+    shadow: &VmExecutionResultAndLogs,
+) -> Vec<String> {
+    let mut diffs = vec![];
+    if main.result != shadow.result {
+        diffs.push(format!(
+            "execution result: {:?} (main) vs {:?} (shadow)",
+            main.result, shadow.result
+        ));
+    }
+    if main.statistics.gas_used != shadow.statistics.gas_used {
+        diffs.push(format!(
+            "gas used: {} (main) vs {} (shadow)",
+            main.statistics.gas_used, shadow.statistics.gas_used
+        ));
+    }
+    if main.refunds.gas_refunded != shadow.refunds.gas_refunded {
+        diffs.push(format!(
+            "gas refunded: {} (main) vs {} (shadow)",
+            main.refunds.gas_refunded, shadow.refunds.gas_refunded
+        ));
+    }
+    if main.logs.events != shadow.logs.events {
+        diffs.push(format!(
+            "events differ: {} (main) vs {} (shadow) events",
+            main.logs.events.len(),
+            shadow.logs.events.len()
+        ));
+    }
+    let main_writes: Vec<_> = write_queries(&main.logs.storage_logs).collect();
+    let shadow_writes: Vec<_> = write_queries(&shadow.logs.storage_logs).collect();
+    if let Some(diff) = compare_writes(&main_writes, &shadow_writes, "storage writes") {
+        diffs.push(diff);
+    }
+    diffs
+}
+
+fn write_queries(logs: &[StorageLogQuery]) -> impl Iterator<Item = LogQuery> + '_ {
+    logs.iter()
+        .filter(|log| log.log_query.rw_flag)
+        .map(|log| log.log_query)
+}
+
+/// Compares written slots and values (but not other query fields such as timestamps, which may legitimately differ
+/// among VM versions).
+fn compare_writes(main: &[LogQuery], shadow: &[LogQuery], name: &str) -> Option<String> {
+    let written_values = |queries: &[LogQuery]| -> Vec<_> {
+        queries
+            .iter()
+            .filter(|query| query.rw_flag)
+            .map(|query| (query.address, query.key, query.written_value))
+            .collect()
+    };
+    let main = written_values(main);
+    let shadow = written_values(shadow);
+    if main == shadow {
+        return None;
+    }
+    let first_mismatch = main
+        .iter()
+        .zip(&shadow)
+        .position(|(main, shadow)| main != shadow)
+        .unwrap_or_else(|| main.len().min(shadow.len()));
+    Some(format!(
+        "{name} differ: {} (main) vs {} (shadow) writes, first mismatch at #{first_mismatch}",
+        main.len(),
+        shadow.len()
+    ))
+}
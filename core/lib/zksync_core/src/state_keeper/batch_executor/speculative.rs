@@ -0,0 +1,209 @@
+//! Optimistic parallel pre-execution of transactions, used to speed up re-executing L1 batches on external nodes.
+//!
+//! The VM executes an L1 batch in a single bootloader run, so transactions in a batch cannot be applied
+//! to the VM state concurrently. Instead, transactions known in advance (e.g., the remaining transactions
+//! in a miniblock fetched by the external node) are executed concurrently, each in an isolated VM on top
+//! of the state at the start of the L1 batch. Storage slots read by these speculative executions are prefetched
+//! into the batch storage, so that the authoritative sequential execution doesn't need to hit RocksDB / Postgres.
+//!
+//! Values at the start of the batch are never invalidated during batch execution (slots modified in the batch
+//! are served by the batch [`StorageView`] itself), so prefetching cannot affect execution results.
+//! Transactions likely depending on the preceding transactions in the batch are considered conflicting; they
+//! are not speculated and fall back to purely sequential execution.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+    thread,
+};
+
+use multivm::{
+    interface::{ExecutionResult, L1BatchEnv, SystemEnv, VmExecutionMode, VmInterface},
+    vm_latest::HistoryDisabled,
+    VmInstance,
+};
+use zksync_state::{ReadStorage, StorageView};
+use zksync_types::{get_nonce_key, StorageKey, StorageValue, Transaction, H256};
+
+use crate::state_keeper::metrics::{SpeculationOutcome, EXECUTOR_METRICS};
+
+/// Storage values prefetched by speculative execution, shared between [`SpeculativeExecutor`]
+/// and [`PrefetchingStorage`].
+type PrefetchedValues = Rc<RefCell<HashMap<StorageKey, StorageValue>>>;
+
+/// [`ReadStorage`] wrapper serving values prefetched by [`SpeculativeExecutor`].
+#[derive(Debug)]
+pub(super) struct PrefetchingStorage<S> {
+    inner: S,
+    prefetched: PrefetchedValues,
+}
+
+impl<S> PrefetchingStorage<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            prefetched: PrefetchedValues::default(),
+        }
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for PrefetchingStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        // Prefetched values are removed since they are cached by the batch `StorageView` after the first read.
+        let prefetched = self.prefetched.borrow_mut().remove(key);
+        prefetched.unwrap_or_else(|| self.inner.read_value(key))
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.inner.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.inner.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.inner.get_enumeration_index(key)
+    }
+}
+
+/// Executes transactions speculatively in parallel and prefetches storage slots read by them.
+///
+/// Each worker thread uses a dedicated storage instance reflecting the state at the start of the L1 batch.
+#[derive(Debug)]
+pub(super) struct SpeculativeExecutor<S> {
+    worker_storages: Vec<S>,
+    l1_batch_env: L1BatchEnv,
+    system_env: SystemEnv,
+    prefetched: PrefetchedValues,
+}
+
+impl<S: ReadStorage + fmt::Debug + Send> SpeculativeExecutor<S> {
+    /// Creates an executor prefetching values into the provided batch storage. Returns an error
+    /// if `worker_storages` is empty.
+    pub fn new<T>(
+        worker_storages: Vec<S>,
+        batch_storage: &PrefetchingStorage<T>,
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            !worker_storages.is_empty(),
+            "speculative execution requires at least one worker"
+        );
+        Ok(Self {
+            worker_storages,
+            l1_batch_env,
+            system_env,
+            prefetched: batch_storage.prefetched.clone(),
+        })
+    }
+
+    /// Speculatively executes the provided transactions. `modified_keys` are storage slots modified
+    /// in the L1 batch so far; they are used to detect conflicting transactions.
+    pub fn speculate(
+        &mut self,
+        txs: &[Transaction],
+        modified_keys: &HashMap<StorageKey, StorageValue>,
+    ) {
+        let txs = Self::filter_conflicting_txs(txs, modified_keys);
+        if txs.is_empty() {
+            return;
+        }
+
+        let concurrency = self.worker_storages.len();
+        let chunk_size = (txs.len() + concurrency - 1) / concurrency;
+        let (l1_batch_env, system_env) = (&self.l1_batch_env, &self.system_env);
+        let read_sets: Vec<_> = thread::scope(|scope| {
+            let workers: Vec<_> = self
+                .worker_storages
+                .iter_mut()
+                .zip(txs.chunks(chunk_size))
+                .map(|(storage, chunk)| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|tx| {
+                                Self::execute_in_isolation(storage, l1_batch_env, system_env, tx)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("speculative execution panicked"))
+                .collect()
+        });
+
+        let mut prefetched = self.prefetched.borrow_mut();
+        let prefetched_len = prefetched.len();
+        for read_set in read_sets {
+            let Some(read_set) = read_set else {
+                EXECUTOR_METRICS.speculated_txs[&SpeculationOutcome::Conflict].inc();
+                continue;
+            };
+            EXECUTOR_METRICS.speculated_txs[&SpeculationOutcome::Prefetched].inc();
+            let new_reads = read_set
+                .into_iter()
+                .filter(|(key, _)| !modified_keys.contains_key(key));
+            prefetched.extend(new_reads);
+        }
+        EXECUTOR_METRICS
+            .prefetched_storage_slots
+            .observe(prefetched.len().saturating_sub(prefetched_len));
+    }
+
+    /// Filters out transactions whose initiator nonce was already modified in the batch, either by a previously
+    /// executed transaction or by a preceding transaction in `txs`. Speculative execution of such transactions
+    /// on top of the batch start state would fail validation.
+    fn filter_conflicting_txs<'a>(
+        txs: &'a [Transaction],
+        modified_keys: &HashMap<StorageKey, StorageValue>,
+    ) -> Vec<&'a Transaction> {
+        let mut touched_nonces = HashSet::new();
+        txs.iter()
+            .filter(|tx| {
+                let nonce_key = get_nonce_key(&tx.initiator_account());
+                let is_conflicting =
+                    modified_keys.contains_key(&nonce_key) || !touched_nonces.insert(nonce_key);
+                if is_conflicting {
+                    EXECUTOR_METRICS.speculated_txs[&SpeculationOutcome::Conflict].inc();
+                }
+                !is_conflicting
+            })
+            .collect()
+    }
+
+    /// Executes a transaction in an isolated VM as if it were the first transaction in the L1 batch.
+    /// Returns storage slots read from the underlying storage, or `None` if the transaction was halted
+    /// (in which case, it probably depends on the preceding transactions).
+    fn execute_in_isolation(
+        storage: &mut S,
+        l1_batch_env: &L1BatchEnv,
+        system_env: &SystemEnv,
+        tx: &Transaction,
+    ) -> Option<HashMap<StorageKey, StorageValue>> {
+        let storage_view = StorageView::new(storage).to_rc_ptr();
+        let mut vm: VmInstance<_, HistoryDisabled> = VmInstance::new(
+            l1_batch_env.clone(),
+            system_env.clone(),
+            storage_view.clone(),
+        );
+        vm.push_transaction(tx.clone());
+        let result = vm.execute(VmExecutionMode::OneTx);
+        drop(vm);
+
+        if let ExecutionResult::Halt { reason } = &result.result {
+            tracing::trace!(
+                "Speculative execution of transaction {:?} halted: {reason}",
+                tx.hash()
+            );
+            return None;
+        }
+        let read_set = storage_view.borrow().witness_block_state().read_storage_key;
+        Some(read_set)
+    }
+}
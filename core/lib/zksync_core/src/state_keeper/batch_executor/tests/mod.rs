@@ -1,11 +1,16 @@
+use std::time::Duration;
+
 use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_test_account::Account;
-use zksync_types::{get_nonce_key, utils::storage_key_for_eth_balance, PriorityOpId};
+use zksync_types::{
+    get_nonce_key, utils::storage_key_for_eth_balance, L1BatchNumber, PriorityOpId, VmVersion,
+};
 
 use self::tester::{AccountLoadNextExecutable, StorageSnapshot, TestConfig, Tester};
 use super::TxExecutionResult;
+use crate::state_keeper::metrics::EXECUTOR_METRICS;
 
 mod read_storage_factory;
 mod tester;
@@ -57,6 +62,42 @@ async fn execute_l2_tx(storage_type: StorageType) {
     executor.finish_batch().await;
 }
 
+/// Checks that transactions are correctly executed after being speculatively pre-executed, including
+/// conflicting transactions. `StorageType::Rocksdb` is not tested since it cannot open multiple storage instances
+/// at the same time.
+#[test_casing(2, [StorageType::AsyncRocksdbCache, StorageType::Postgres])]
+#[tokio::test]
+async fn execute_l2_txs_with_speculation(storage_type: StorageType) {
+    // Each speculative execution worker may hold a Postgres connection.
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(3).await;
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address(), bob.address()]).await;
+    let mut config = TestConfig::new();
+    config.parallel_reexecution_workers = 2;
+    tester.set_config(config);
+    let executor = tester.create_batch_executor(storage_type).await;
+
+    // The second transaction from Alice conflicts with the first one, so it's not speculated.
+    let txs = vec![alice.execute(), bob.execute(), alice.execute()];
+    executor.speculate_txs(txs.clone()).await;
+    for tx in txs {
+        let res = executor.execute_tx(tx).await;
+        assert_executed(&res);
+    }
+
+    // Alice's nonce is modified in the batch, so her new transaction is conflicting as well.
+    let txs = vec![alice.execute(), bob.execute()];
+    executor.speculate_txs(txs.clone()).await;
+    for tx in txs {
+        let res = executor.execute_tx(tx).await;
+        assert_executed(&res);
+    }
+    executor.finish_batch().await;
+}
+
 #[derive(Debug, Clone, Copy)]
 enum SnapshotRecoveryMutation {
     RemoveNonce,
@@ -206,6 +247,58 @@ async fn rollback() {
     executor.finish_batch().await;
 }
 
+/// Checks that shadow execution in the same VM version doesn't influence execution results and doesn't record
+/// divergences, including for rolled back and rejected transactions.
+#[tokio::test]
+async fn shadow_execution() {
+    // The shadow VM uses a separate storage instance, which may hold a Postgres connection.
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(3).await;
+    let mut alice = Account::random();
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let mut config = TestConfig::new();
+    config.shadow_vm_version = Some(VmVersion::latest());
+    tester.set_config(config);
+    let executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let tx = alice.execute();
+    let res = executor.execute_tx(tx.clone()).await;
+    assert_executed(&res);
+    executor.rollback_last_tx().await;
+    let res = executor.execute_tx(tx).await;
+    assert_executed(&res);
+    // Wallet is not funded, so the transaction is rejected.
+    let res = executor.execute_tx(Account::random().execute()).await;
+    assert_rejected(&res);
+    executor.rollback_last_tx().await;
+    let res = executor.execute_tx(alice.execute()).await;
+    assert_executed(&res);
+    executor.finish_batch().await;
+
+    // The shadow VM runs on a separate thread and persists divergences asynchronously.
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while EXECUTOR_METRICS.shadow_vm_l1_batch.get() < 1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for the shadow VM to finish the batch");
+
+    let divergences = tester
+        .pool()
+        .connection()
+        .await
+        .unwrap()
+        .vm_shadow_divergences_dal()
+        .get_divergences(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(divergences.is_empty(), "{divergences:#?}");
+}
+
 /// Checks that incorrect transactions are marked as rejected.
 #[tokio::test]
 async fn reject_tx() {
@@ -416,6 +509,8 @@ async fn bootloader_out_of_gas_for_any_tx() {
             save_call_traces: false,
             vm_gas_limit: Some(10),
             validation_computational_gas_limit: u32::MAX,
+            parallel_reexecution_workers: 0,
+            shadow_vm_version: None,
         },
     );
 
@@ -461,6 +556,8 @@ async fn bootloader_tip_out_of_gas() {
                 - 10,
         ),
         validation_computational_gas_limit: u32::MAX,
+        parallel_reexecution_workers: 0,
+        shadow_vm_version: None,
     });
 
     let second_executor = tester
@@ -12,13 +12,14 @@ use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::{get_loadnext_contract, test_contracts::LoadnextContractExecutionParams};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_storage::RocksDBOptions;
 use zksync_test_account::{Account, DeployContractsTx, TxType};
 use zksync_types::{
     block::MiniblockHasher, ethabi::Token, fee::Fee, snapshots::SnapshotRecoveryStatus,
     storage_writes_deduplicator::StorageWritesDeduplicator,
     system_contracts::get_system_smart_contracts, utils::storage_key_for_standard_token_balance,
     AccountTreeId, Address, Execute, L1BatchNumber, L2ChainId, MiniblockNumber, PriorityOpId,
-    ProtocolVersionId, StorageKey, StorageLog, Transaction, H256, L2_ETH_TOKEN_ADDRESS,
+    ProtocolVersionId, StorageKey, StorageLog, Transaction, VmVersion, H256, L2_ETH_TOKEN_ADDRESS,
     SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
 };
 use zksync_utils::u256_to_h256;
@@ -48,6 +49,8 @@ pub(super) struct TestConfig {
     pub(super) save_call_traces: bool,
     pub(super) vm_gas_limit: Option<u32>,
     pub(super) validation_computational_gas_limit: u32,
+    pub(super) parallel_reexecution_workers: usize,
+    pub(super) shadow_vm_version: Option<VmVersion>,
 }
 
 impl TestConfig {
@@ -58,6 +61,8 @@ impl TestConfig {
             vm_gas_limit: None,
             save_call_traces: false,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
+            parallel_reexecution_workers: 0,
+            shadow_vm_version: None,
         }
     }
 }
@@ -105,6 +110,7 @@ impl Tester {
                 let (state_keeper_storage, task) = AsyncRocksdbCache::new(
                     self.pool(),
                     self.state_keeper_db_path(),
+                    RocksDBOptions::default(),
                     self.enum_index_migration_chunk_size(),
                 );
                 let handle = tokio::task::spawn(async move {
@@ -149,7 +155,11 @@ impl Tester {
         system_env: SystemEnv,
     ) -> BatchExecutorHandle {
         let mut batch_executor =
-            MainBatchExecutor::new(storage_factory, self.config.save_call_traces, false);
+            MainBatchExecutor::new(storage_factory, self.config.save_call_traces, false)
+                .with_parallel_reexecution(self.config.parallel_reexecution_workers);
+        if let Some(vm_version) = self.config.shadow_vm_version {
+            batch_executor = batch_executor.with_shadow_execution(vm_version, self.pool());
+        }
         let (_stop_sender, stop_receiver) = watch::channel(false);
         batch_executor
             .init_batch(l1_batch_env, system_env, &stop_receiver)
@@ -164,6 +174,7 @@ impl Tester {
         let (storage_factory, task) = AsyncRocksdbCache::new(
             self.pool(),
             self.state_keeper_db_path(),
+            RocksDBOptions::default(),
             self.enum_index_migration_chunk_size(),
         );
         let (_, stop_receiver) = watch::channel(false);
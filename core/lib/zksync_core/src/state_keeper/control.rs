@@ -0,0 +1,77 @@
+//! Runtime control over the state keeper.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+/// Status of the state keeper soft shutdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoftShutdownStatus {
+    /// Soft shutdown was not requested; the state keeper processes transactions as usual.
+    NotRequested,
+    /// Soft shutdown was requested; the state keeper will seal the current L1 batch and stop processing transactions.
+    Requested,
+    /// The state keeper has sealed the current L1 batch and no longer processes transactions.
+    /// The node can be safely restarted.
+    Completed,
+}
+
+/// Handle allowing to request a soft shutdown of [`ZkSyncStateKeeper`](super::ZkSyncStateKeeper), e.g. to cleanly
+/// restart the sequencer without leaving an open L1 batch.
+///
+/// On a soft shutdown request, the state keeper seals the current miniblock and L1 batch (if they contain
+/// any transactions) and then stops pulling new transactions until the node is stopped. Soft shutdown
+/// cannot be canceled.
+#[derive(Debug, Clone)]
+pub struct StateKeeperControl {
+    status: Arc<watch::Sender<SoftShutdownStatus>>,
+}
+
+impl Default for StateKeeperControl {
+    fn default() -> Self {
+        Self {
+            status: Arc::new(watch::channel(SoftShutdownStatus::NotRequested).0),
+        }
+    }
+}
+
+impl StateKeeperControl {
+    /// Requests the state keeper to seal the current L1 batch and stop processing transactions.
+    pub fn request_soft_shutdown(&self) {
+        let is_requested = self.status.send_if_modified(|status| {
+            let is_modified = *status == SoftShutdownStatus::NotRequested;
+            if is_modified {
+                *status = SoftShutdownStatus::Requested;
+            }
+            is_modified
+        });
+        if is_requested {
+            tracing::info!("State keeper will shut down after sealing the current L1 batch");
+        }
+    }
+
+    /// Returns the current soft shutdown status.
+    pub fn soft_shutdown_status(&self) -> SoftShutdownStatus {
+        *self.status.borrow()
+    }
+
+    pub(super) fn is_soft_shutdown_requested(&self) -> bool {
+        self.soft_shutdown_status() != SoftShutdownStatus::NotRequested
+    }
+
+    pub(super) fn complete_soft_shutdown(&self) {
+        self.status.send_replace(SoftShutdownStatus::Completed);
+    }
+
+    /// Waits until the soft shutdown is completed.
+    pub async fn wait_for_soft_shutdown(&self) {
+        let mut status = self.status.subscribe();
+        // `unwrap()` is safe: the sender is owned by `self`
+        status
+            .wait_for(|status| *status == SoftShutdownStatus::Completed)
+            .await
+            .unwrap();
+    }
+}
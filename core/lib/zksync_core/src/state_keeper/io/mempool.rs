@@ -11,7 +11,7 @@ use multivm::{interface::Halt, utils::derive_base_fee_and_gas_per_pubdata};
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{consensus_dal::Payload, ConnectionPool, Core, CoreDal};
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
     protocol_upgrade::ProtocolUpgradeTx, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
@@ -30,9 +30,11 @@ use crate::{
         },
         mempool_actor::l2_tx_filter,
         metrics::KEEPER_METRICS,
-        seal_criteria::{IoSealCriteria, TimeoutSealer},
+        seal_criteria::{
+            miniblock_seal_policy, IoSealCriteria, MiniblockSealPolicy, TimeoutSealer,
+        },
         updates::UpdatesManager,
-        MempoolGuard,
+        MempoolGuard, PolicyTxFilter, TransactionFilter, TxFilterPolicy,
     },
 };
 
@@ -45,11 +47,14 @@ pub struct MempoolIO {
     mempool: MempoolGuard,
     pool: ConnectionPool<Core>,
     timeout_sealer: TimeoutSealer,
+    miniblock_seal_policy: Box<dyn MiniblockSealPolicy>,
     filter: L2TxFilter,
+    tx_filter: Arc<dyn TransactionFilter>,
     l1_batch_params_provider: L1BatchParamsProvider,
     fee_account: Address,
     validation_computational_gas_limit: u32,
     max_allowed_tx_gas_limit: U256,
+    max_miniblock_payload_size: Option<usize>,
     delay_interval: Duration,
     // Used to keep track of gas prices to set accepted price per pubdata byte in blocks.
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -66,7 +71,32 @@ impl IoSealCriteria for MempoolIO {
     }
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
-        self.timeout_sealer.should_seal_miniblock(manager)
+        self.miniblock_seal_policy.should_seal_miniblock(manager)
+    }
+
+    fn should_seal_miniblock_before_tx(
+        &mut self,
+        manager: &UpdatesManager,
+        tx: &Transaction,
+    ) -> bool {
+        let Some(max_payload_size) = self.max_miniblock_payload_size else {
+            return false;
+        };
+        if manager.miniblock.executed_transactions.is_empty() {
+            // Transactions that don't fit into an empty miniblock are filtered out in `wait_for_next_tx()`.
+            return false;
+        }
+        let payload_size = manager.miniblock.payload_size + Payload::encoded_transaction_size(tx);
+        let should_seal = payload_size > max_payload_size;
+        if should_seal {
+            tracing::debug!(
+                "Miniblock #{} should be sealed before executing transaction {:?}: payload size would be {payload_size}B, \
+                 while the limit is {max_payload_size}B",
+                manager.miniblock.number,
+                tx.hash()
+            );
+        }
+        should_seal
     }
 }
 
@@ -243,6 +273,32 @@ impl StateKeeperIO for MempoolIO {
                     self.reject(&tx, &Halt::TooBigGasLimit.to_string()).await?;
                     continue;
                 }
+                if !tx.is_l1() {
+                    if let Err(reason) = self.tx_filter.check_transaction(&tx) {
+                        tracing::info!(
+                            "Transaction {:?} is rejected by the transaction filter: {reason}",
+                            tx.hash()
+                        );
+                        KEEPER_METRICS.filtered_transactions.inc();
+                        self.reject(&tx, &reason).await?;
+                        continue;
+                    }
+                }
+                // Reject transactions that wouldn't fit into the miniblock payload even if they were the only transaction
+                // in the miniblock. L1 transactions cannot be rejected, so they are executed regardless.
+                if let Some(max_payload_size) = self.max_miniblock_payload_size {
+                    let payload_size = Payload::encoded_transaction_size(&tx);
+                    if payload_size > max_payload_size && !tx.is_l1() {
+                        tracing::warn!(
+                            "Found tx with too big miniblock payload size in state keeper, hash: {:?}, \
+                             payload size: {payload_size}B",
+                            tx.hash()
+                        );
+                        self.reject(&tx, "Transaction is too large to fit into a miniblock")
+                            .await?;
+                        continue;
+                    }
+                }
                 return Ok(Some(tx));
             } else {
                 tokio::time::sleep(self.delay_interval).await;
@@ -412,6 +468,11 @@ impl MempoolIO {
             config.virtual_blocks_per_miniblock > 0,
             "Virtual blocks per miniblock must be positive"
         );
+        let target_percentile = config.miniblock_adaptive_target_percentile();
+        anyhow::ensure!(
+            target_percentile > 0.0 && target_percentile <= 1.0,
+            "Adaptive miniblock target percentile must be in (0, 1]"
+        );
 
         let mut storage = pool.connection_tagged("state_keeper").await?;
         let l1_batch_params_provider = L1BatchParamsProvider::new(&mut storage)
@@ -424,12 +485,15 @@ impl MempoolIO {
             mempool,
             pool,
             timeout_sealer: TimeoutSealer::new(config),
+            miniblock_seal_policy: miniblock_seal_policy(config),
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
+            tx_filter: Arc::new(PolicyTxFilter::new(TxFilterPolicy::from_config(config))),
             l1_batch_params_provider,
             fee_account,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
             max_allowed_tx_gas_limit: config.max_allowed_l2_tx_gas_limit.into(),
+            max_miniblock_payload_size: config.max_miniblock_payload_size,
             delay_interval,
             batch_fee_input_provider,
             chain_id,
@@ -438,6 +502,23 @@ impl MempoolIO {
         })
     }
 
+    /// Sets the filter applied to L2 transactions before they are included into a batch. By default, the filter
+    /// is a [`PolicyTxFilter`] with the policy from the state keeper config.
+    #[must_use]
+    pub fn with_transaction_filter(mut self, tx_filter: Arc<dyn TransactionFilter>) -> Self {
+        self.tx_filter = tx_filter;
+        self
+    }
+
+    /// Sets the policy deciding when to seal miniblocks. By default, the policy is chosen based on the state keeper
+    /// config: [`AdaptiveMiniblockSealPolicy`](crate::state_keeper::AdaptiveMiniblockSealPolicy) if the adaptive
+    /// target block time is configured, and a fixed miniblock commit deadline otherwise.
+    #[must_use]
+    pub fn with_miniblock_seal_policy(mut self, policy: Box<dyn MiniblockSealPolicy>) -> Self {
+        self.miniblock_seal_policy = policy;
+        self
+    }
+
     /// "virtual_blocks_per_miniblock" will be created either if the miniblock_number % virtual_blocks_interval == 0 or
     /// the miniblock is the first one in the batch.
     /// For instance:
@@ -14,6 +14,8 @@ pub use self::{
     common::IoCursor,
     output_handler::{OutputHandler, StateKeeperOutputHandler},
     persistence::{MiniblockSealerTask, StateKeeperPersistence},
+    protective_reads::ProtectiveReadsWriter,
+    tx_metrics::TxExecutionMetricsRecorder,
 };
 use super::seal_criteria::IoSealCriteria;
 
@@ -22,9 +24,12 @@ pub(crate) mod fee_address_migration;
 pub(crate) mod mempool;
 mod output_handler;
 mod persistence;
+mod protective_reads;
 pub(crate) mod seal_logic;
 #[cfg(test)]
 mod tests;
+mod tx_metrics;
+mod unit_of_work;
 
 /// Contains information about the un-synced execution state:
 /// Batch data and transactions that were executed before and are marked as so in the DB,
@@ -132,6 +137,12 @@ pub trait StateKeeperIO: 'static + Send + fmt::Debug + IoSealCriteria {
     /// Returns `None` if no transaction became available until the timeout.
     async fn wait_for_next_tx(&mut self, max_wait: Duration)
         -> anyhow::Result<Option<Transaction>>;
+    /// Returns transactions known to be executed next in the current miniblock without removing them from the IO.
+    /// The returned transactions are only used as a hint for the batch executor. The default implementation
+    /// returns no transactions, which is appropriate for IO implementations sequencing transactions on the fly.
+    fn peek_upcoming_txs(&mut self) -> Vec<Transaction> {
+        Vec::new()
+    }
     /// Marks the transaction as "not executed", so it can be retrieved from the IO again.
     async fn rollback(&mut self, tx: Transaction) -> anyhow::Result<()>;
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
@@ -1,14 +1,24 @@
 //! State keeper persistence logic.
 
-use std::time::Instant;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use async_trait::async_trait;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{
+    mpsc::{self, error::TryRecvError},
+    oneshot, Notify,
+};
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_dal::{ConnectionPool, Core};
-use zksync_types::Address;
+use zksync_types::{Address, L2ChainId};
 
+use super::protective_reads::{ProtectiveReads, ProtectiveReadsWriter};
 use crate::{
     metrics::{BlockStage, APP_METRICS},
+    replay::BatchReplayer,
     state_keeper::{
         io::StateKeeperOutputHandler,
         metrics::{MiniblockQueueStage, MINIBLOCK_METRICS},
@@ -23,21 +33,36 @@ struct Completable<T> {
     completion_sender: oneshot::Sender<()>,
 }
 
+/// Mode of persisting protective reads when sealing an L1 batch.
+#[derive(Debug)]
+pub(super) enum ProtectiveReadsMode {
+    /// Protective reads are inserted in the same DB transaction as the L1 batch.
+    Inline,
+    /// Protective reads are marked as pending when sealing the L1 batch and are sent to the [`ProtectiveReadsWriter`].
+    Offloaded(mpsc::Sender<ProtectiveReads>),
+    /// Protective reads are not persisted.
+    Disabled,
+}
+
 /// Canonical [`HandleStateKeeperOutput`] implementation that stores processed miniblocks and L1 batches to Postgres.
 #[derive(Debug)]
 pub struct StateKeeperPersistence {
     pool: ConnectionPool<Core>,
     l2_erc20_bridge_addr: Address,
     pre_insert_txs: bool,
-    insert_protective_reads: bool,
+    protective_reads_mode: ProtectiveReadsMode,
     commands_sender: mpsc::Sender<Completable<MiniblockSealCommand>>,
     latest_completion_receiver: Option<oneshot::Receiver<()>>,
+    // Notifies the sealer that it shouldn't wait for new commands to fill the current batch.
+    flush_requests: Arc<Notify>,
     // If true, `submit_miniblock()` will wait for the operation to complete.
     is_sync: bool,
 }
 
 impl StateKeeperPersistence {
     const SHUTDOWN_MSG: &'static str = "miniblock sealer unexpectedly shut down";
+    /// Capacity of the queue of L1 batch protective reads waiting to be persisted by the [`ProtectiveReadsWriter`].
+    const PROTECTIVE_READS_CAPACITY: usize = 8;
 
     /// Creates a sealer that will use the provided Postgres connection and will have the specified
     /// `command_capacity` for unprocessed sealing commands.
@@ -50,19 +75,24 @@ impl StateKeeperPersistence {
         command_capacity = command_capacity.max(1);
 
         let (commands_sender, commands_receiver) = mpsc::channel(command_capacity);
+        let flush_requests = Arc::new(Notify::new());
         let sealer = MiniblockSealerTask {
             pool: pool.clone(),
             is_sync,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
+            flush_requests: flush_requests.clone(),
+            row_limit: None,
+            batch_max_delay: Duration::ZERO,
         };
         let this = Self {
             pool,
             l2_erc20_bridge_addr,
             pre_insert_txs: false,
-            insert_protective_reads: true,
+            protective_reads_mode: ProtectiveReadsMode::Inline,
             commands_sender,
             latest_completion_receiver: None,
+            flush_requests,
             is_sync,
         };
         (this, sealer)
@@ -76,10 +106,29 @@ impl StateKeeperPersistence {
     /// Disables inserting protective reads to Postgres when persisting an L1 batch. This is only sound
     /// if the node won't *ever* run a full Merkle tree (such a tree requires protective reads to generate witness inputs).
     pub fn without_protective_reads(mut self) -> Self {
-        self.insert_protective_reads = false;
+        self.protective_reads_mode = ProtectiveReadsMode::Disabled;
         self
     }
 
+    /// Offloads persisting protective reads to the returned [`ProtectiveReadsWriter`], so that it's removed
+    /// from the critical path of sealing an L1 batch. The writer must be run for the node to make progress;
+    /// e.g., a full Merkle tree won't process L1 batches with pending protective reads.
+    ///
+    /// `l2_chain_id` and `state_keeper_config` are used to re-execute L1 batches with protective reads pending
+    /// after a node restart.
+    pub fn with_offloaded_protective_reads(
+        mut self,
+        writer_pool: ConnectionPool<Core>,
+        l2_chain_id: L2ChainId,
+        state_keeper_config: &StateKeeperConfig,
+    ) -> (Self, ProtectiveReadsWriter) {
+        let (reads_sender, reads_receiver) = mpsc::channel(Self::PROTECTIVE_READS_CAPACITY);
+        let replayer = BatchReplayer::new(writer_pool.clone(), l2_chain_id, state_keeper_config);
+        let writer = ProtectiveReadsWriter::new(writer_pool, replayer, reads_receiver);
+        self.protective_reads_mode = ProtectiveReadsMode::Offloaded(reads_sender);
+        (self, writer)
+    }
+
     /// Submits a new sealing `command` to the sealer that this handle is attached to.
     ///
     /// If there are currently too many unprocessed commands, this method will wait until
@@ -129,6 +178,8 @@ impl StateKeeperPersistence {
         let start = Instant::now();
         let completion_receiver = self.latest_completion_receiver.take();
         if let Some(completion_receiver) = completion_receiver {
+            // No new commands will be submitted until the queue is emptied, so waiting for them is pointless.
+            self.flush_requests.notify_one();
             completion_receiver.await.expect(Self::SHUTDOWN_MSG);
         }
 
@@ -166,14 +217,69 @@ impl StateKeeperOutputHandler for StateKeeperPersistence {
             .seal_l1_batch(
                 &mut storage,
                 self.l2_erc20_bridge_addr,
-                self.insert_protective_reads,
+                &self.protective_reads_mode,
             )
-            .await;
+            .await
+            .context("failed sealing L1 batch")?;
+
+        if let ProtectiveReadsMode::Offloaded(reads_sender) = &self.protective_reads_mode {
+            let finished_batch = updates_manager
+                .l1_batch
+                .finished
+                .as_ref()
+                .context("L1 batch is not actually finished")?;
+            let reads = finished_batch
+                .final_execution_state
+                .deduplicated_storage_log_queries
+                .iter()
+                .filter(|log_query| !log_query.rw_flag)
+                .copied()
+                .collect();
+            let reads = ProtectiveReads {
+                l1_batch_number: updates_manager.l1_batch.number,
+                reads,
+            };
+            reads_sender
+                .send(reads)
+                .await
+                .context("protective reads writer unexpectedly shut down")?;
+        }
         APP_METRICS.block_number[&BlockStage::Sealed].set(updates_manager.l1_batch.number.0.into());
         Ok(())
     }
 }
 
+/// Limit on the number of rows (events and storage logs) inserted in a single DB transaction
+/// by the [`MiniblockSealerTask`]. The limit is adjusted based on the observed sealing latency.
+#[derive(Debug)]
+struct AdaptiveRowLimit {
+    latency_target: Duration,
+    value: usize,
+}
+
+impl AdaptiveRowLimit {
+    const MIN: usize = 1_000;
+    const MAX: usize = 100_000;
+
+    fn new(latency_target: Duration) -> Self {
+        Self {
+            latency_target,
+            value: Self::MIN,
+        }
+    }
+
+    /// Updates the limit based on the latency of sealing a batch of miniblocks with `row_count` rows in total.
+    fn update(&mut self, latency: Duration, row_count: usize) {
+        if latency > self.latency_target {
+            self.value = (self.value / 2).max(Self::MIN);
+        } else if row_count >= self.value {
+            // The batch was constrained by the limit, and sealing it was fast enough; try larger batches.
+            self.value = (self.value * 2).min(Self::MAX);
+        }
+        MINIBLOCK_METRICS.seal_batch_row_limit.set(self.value);
+    }
+}
+
 /// Component responsible for sealing miniblocks (i.e., storing their data to Postgres).
 #[derive(Debug)]
 pub struct MiniblockSealerTask {
@@ -182,9 +288,26 @@ pub struct MiniblockSealerTask {
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
     commands_receiver: mpsc::Receiver<Completable<MiniblockSealCommand>>,
+    flush_requests: Arc<Notify>,
+    row_limit: Option<AdaptiveRowLimit>,
+    batch_max_delay: Duration,
 }
 
 impl MiniblockSealerTask {
+    /// Enables sealing miniblocks waiting in the queue in a single DB transaction. The number of rows
+    /// inserted in a transaction is adjusted so that sealing takes approximately `latency_target`.
+    ///
+    /// If the queue is emptied before the row limit is reached, the sealer waits for new commands
+    /// for at most `max_delay` since the first command in the batch was received. Thus, batching
+    /// increases the latency of sealing a miniblock by at most `max_delay`. The sealer doesn't wait
+    /// if the state keeper waits for all miniblocks to be sealed (e.g., before sealing an L1 batch),
+    /// or if the sealer is synchronous.
+    pub fn with_adaptive_batching(mut self, latency_target: Duration, max_delay: Duration) -> Self {
+        self.row_limit = Some(AdaptiveRowLimit::new(latency_target));
+        self.batch_max_delay = max_delay;
+        self
+    }
+
     /// Seals miniblocks as they are received from the [`StateKeeperPersistence`]. This should be run
     /// on a separate Tokio task.
     pub async fn run(mut self) -> anyhow::Result<()> {
@@ -199,23 +322,92 @@ impl MiniblockSealerTask {
             tracing::warn!("Miniblock sealer not started, since its handle is already dropped");
         }
 
+        if let Some(row_limit) = &self.row_limit {
+            tracing::info!(
+                "Miniblock sealer uses adaptive batching with latency target {:?} and max delay {:?}",
+                row_limit.latency_target,
+                self.batch_max_delay
+            );
+        }
+
         let mut miniblock_seal_delta: Option<Instant> = None;
         // Commands must be processed sequentially: a later miniblock cannot be saved before
         // an earlier one.
         while let Some(completable) = self.next_command().await {
+            let batch = self.extend_batch(completable).await;
+            let commands: Vec<_> = batch
+                .iter()
+                .map(|completable| &completable.command)
+                .collect();
             let mut storage = self.pool.connection_tagged("state_keeper").await?;
-            completable.command.seal(&mut storage).await;
+            let started_at = Instant::now();
+            MiniblockSealCommand::seal_many(&commands, &mut storage).await;
+            if let Some(row_limit) = &mut self.row_limit {
+                let row_count = commands
+                    .iter()
+                    .map(|command| command.estimated_row_count())
+                    .sum();
+                row_limit.update(started_at.elapsed(), row_count);
+            }
             if let Some(delta) = miniblock_seal_delta {
                 MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
             miniblock_seal_delta = Some(Instant::now());
+            MINIBLOCK_METRICS.seal_batch_size.observe(batch.len());
 
-            completable.completion_sender.send(()).ok();
-            // ^ We don't care whether anyone listens to the processing progress
+            for completable in batch {
+                completable.completion_sender.send(()).ok();
+                // ^ We don't care whether anyone listens to the processing progress
+            }
         }
         Ok(())
     }
 
+    /// Extends a batch starting from the `first` command with queued commands until the row limit is reached,
+    /// or until the max batch delay has elapsed. If adaptive batching is disabled, the batch consists
+    /// of a single command.
+    async fn extend_batch(
+        &mut self,
+        first: Completable<MiniblockSealCommand>,
+    ) -> Vec<Completable<MiniblockSealCommand>> {
+        let flush_deadline = tokio::time::Instant::now() + self.batch_max_delay;
+        let mut row_count = first.command.estimated_row_count();
+        let mut batch = vec![first];
+        let Some(row_limit) = self.row_limit.as_ref().map(|limit| limit.value) else {
+            return batch;
+        };
+        let may_wait = !self.is_sync && !self.batch_max_delay.is_zero();
+
+        while row_count < row_limit {
+            let completable = match self.commands_receiver.try_recv() {
+                Ok(completable) => completable,
+                Err(TryRecvError::Empty) if may_wait => {
+                    let maybe_completable = tokio::select! {
+                        completable = self.commands_receiver.recv() => completable,
+                        () = tokio::time::sleep_until(flush_deadline) => None,
+                        () = self.flush_requests.notified() => None,
+                    };
+                    let Some(completable) = maybe_completable else {
+                        break;
+                    };
+                    completable
+                }
+                Err(_) => break,
+            };
+            row_count += completable.command.estimated_row_count();
+            batch.push(completable);
+        }
+        if batch.len() > 1 {
+            tracing::debug!(
+                "Sealing miniblocks #{}..=#{} with ~{row_count} events and storage logs \
+                 in a single DB transaction",
+                batch[0].command.miniblock.number,
+                batch[batch.len() - 1].command.miniblock.number
+            );
+        }
+        batch
+    }
+
     async fn next_command(&mut self) -> Option<Completable<MiniblockSealCommand>> {
         tracing::debug!("Polling miniblock seal queue for next command");
         let start = Instant::now();
@@ -247,6 +439,7 @@ mod tests {
     use assert_matches::assert_matches;
     use futures::FutureExt;
     use multivm::zk_evm_latest::ethereum_types::{H256, U256};
+    use tokio::sync::watch;
     use zksync_dal::CoreDal;
     use zksync_types::{
         api::TransactionStatus, block::BlockGasCount, tx::ExecutionMetrics, L1BatchNumber,
@@ -344,6 +537,7 @@ mod tests {
             BlockGasCount::default(),
             ExecutionMetrics::default(),
             vec![],
+            Duration::ZERO,
         );
         persistence.handle_miniblock(&updates).await.unwrap();
         updates.push_miniblock(MiniblockParams {
@@ -427,6 +621,67 @@ mod tests {
         assert_eq!(protective_reads, HashSet::new());
     }
 
+    #[tokio::test]
+    async fn miniblock_and_l1_batch_processing_with_offloaded_protective_reads() {
+        let pool = ConnectionPool::constrained_test_pool(2).await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        // Save metadata for the genesis L1 batch so that we don't hang in `seal_l1_batch`.
+        storage
+            .blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(0), H256::zero())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let (persistence, miniblock_sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 1);
+        let (mut persistence, writer) = persistence.with_offloaded_protective_reads(
+            pool.clone(),
+            L2ChainId::default(),
+            &StateKeeperConfig::for_tests(),
+        );
+        tokio::spawn(miniblock_sealer.run());
+        execute_mock_batch(&mut persistence).await;
+
+        // Check that protective reads are marked as pending, but are not persisted yet.
+        let mut storage = pool.connection().await.unwrap();
+        let are_pending = storage
+            .storage_logs_dedup_dal()
+            .are_protective_reads_pending(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(are_pending);
+        let protective_reads = storage
+            .storage_logs_dedup_dal()
+            .get_protective_reads_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(protective_reads, HashSet::new());
+        drop(storage);
+
+        // The writer will stop after persisting reads since the persistence is dropped.
+        drop(persistence);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        writer.run(stop_receiver).await.unwrap();
+
+        let mut storage = pool.connection().await.unwrap();
+        let are_pending = storage
+            .storage_logs_dedup_dal()
+            .are_protective_reads_pending(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(!are_pending);
+        let protective_reads = storage
+            .storage_logs_dedup_dal()
+            .get_protective_reads_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(protective_reads.len(), 1, "{protective_reads:?}");
+    }
+
     #[tokio::test]
     async fn miniblock_sealer_handle_blocking() {
         let pool = ConnectionPool::constrained_test_pool(1).await;
@@ -503,4 +758,152 @@ mod tests {
 
         persistence.wait_for_all_commands().await;
     }
+
+    #[test]
+    fn adaptive_row_limit_updates() {
+        let mut row_limit = AdaptiveRowLimit::new(Duration::from_millis(100));
+        assert_eq!(row_limit.value, AdaptiveRowLimit::MIN);
+
+        // The batch wasn't constrained by the limit, so it shouldn't change.
+        row_limit.update(Duration::from_millis(10), 100);
+        assert_eq!(row_limit.value, AdaptiveRowLimit::MIN);
+        row_limit.update(Duration::from_millis(10), AdaptiveRowLimit::MIN);
+        assert_eq!(row_limit.value, 2 * AdaptiveRowLimit::MIN);
+        row_limit.update(Duration::from_millis(50), 2_500);
+        assert_eq!(row_limit.value, 4 * AdaptiveRowLimit::MIN);
+
+        row_limit.update(Duration::from_millis(200), 4_000);
+        assert_eq!(row_limit.value, 2 * AdaptiveRowLimit::MIN);
+        for _ in 0..3 {
+            row_limit.update(Duration::from_millis(200), 100);
+        }
+        assert_eq!(row_limit.value, AdaptiveRowLimit::MIN);
+
+        for _ in 0..20 {
+            row_limit.update(Duration::ZERO, AdaptiveRowLimit::MAX);
+        }
+        assert_eq!(row_limit.value, AdaptiveRowLimit::MAX);
+    }
+
+    #[tokio::test]
+    async fn miniblock_sealer_waits_for_new_commands() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let (mut persistence, sealer) = StateKeeperPersistence::new(pool, Address::default(), 5);
+        let mut sealer =
+            sealer.with_adaptive_batching(Duration::from_secs(60), Duration::from_millis(10));
+
+        // The batch should be flushed after the max delay.
+        let mut updates_manager = create_updates_manager();
+        let seal_command = updates_manager.seal_miniblock_command(Address::default(), false);
+        persistence.submit_miniblock(seal_command).await;
+        let first_command = sealer.next_command().await.unwrap();
+        let batch = sealer.extend_batch(first_command).await;
+        assert_eq!(batch.len(), 1);
+        for completable in batch {
+            completable.completion_sender.send(()).unwrap();
+        }
+        persistence.wait_for_all_commands().await;
+
+        sealer.batch_max_delay = Duration::from_secs(60);
+        updates_manager.push_miniblock(MiniblockParams {
+            timestamp: 2,
+            virtual_blocks: 1,
+        });
+        let seal_command = updates_manager.seal_miniblock_command(Address::default(), false);
+        persistence.submit_miniblock(seal_command).await;
+        let first_command = sealer.next_command().await.unwrap();
+        let batch_future = sealer.extend_batch(first_command);
+        futures::pin_mut!(batch_future);
+        assert!((&mut batch_future).now_or_never().is_none());
+
+        // A command submitted while the sealer waits should be added to the batch.
+        updates_manager.push_miniblock(MiniblockParams {
+            timestamp: 3,
+            virtual_blocks: 1,
+        });
+        let seal_command = updates_manager.seal_miniblock_command(Address::default(), false);
+        persistence.submit_miniblock(seal_command).await;
+        assert!((&mut batch_future).now_or_never().is_none());
+
+        // Waiting for all commands should flush the batch.
+        let wait_future = persistence.wait_for_all_commands();
+        futures::pin_mut!(wait_future);
+        assert!((&mut wait_future).now_or_never().is_none());
+        let batch = batch_future.await;
+        let miniblock_numbers: Vec<_> = batch
+            .iter()
+            .map(|completable| completable.command.miniblock.number)
+            .collect();
+        assert_eq!(miniblock_numbers, [MiniblockNumber(2), MiniblockNumber(3)]);
+        for completable in batch {
+            completable.completion_sender.send(()).ok();
+        }
+        wait_future.await;
+    }
+
+    #[tokio::test]
+    async fn queued_miniblocks_are_sealed_in_single_batch() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let (mut persistence, sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 5);
+        let mut sealer = sealer.with_adaptive_batching(Duration::from_secs(60), Duration::ZERO);
+
+        let l1_batch_env = default_l1_batch_env(1, 1, Address::random());
+        let mut updates = UpdatesManager::new(&l1_batch_env, &default_system_env());
+        for i in 1..=3_u16 {
+            let storage_logs = [(U256::from(i), Query::InitialWrite(U256::from(i)))];
+            updates.extend_from_executed_transaction(
+                create_transaction(10, 100),
+                create_execution_result(i - 1, storage_logs),
+                vec![],
+                BlockGasCount::default(),
+                ExecutionMetrics::default(),
+                vec![],
+                Duration::ZERO,
+            );
+            persistence.handle_miniblock(&updates).await.unwrap();
+            updates.push_miniblock(MiniblockParams {
+                timestamp: u64::from(i) + 1,
+                virtual_blocks: 1,
+            });
+        }
+
+        let first_command = sealer.next_command().await.unwrap();
+        let batch = sealer.extend_batch(first_command).await;
+        assert_eq!(batch.len(), 3);
+        let commands: Vec<_> = batch
+            .iter()
+            .map(|completable| &completable.command)
+            .collect();
+        let mut storage = pool.connection().await.unwrap();
+        MiniblockSealCommand::seal_many(&commands, &mut storage).await;
+        for completable in batch {
+            completable.completion_sender.send(()).unwrap();
+        }
+        persistence.wait_for_all_commands().await;
+
+        let sealed_miniblock_number = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_miniblock_number, Some(MiniblockNumber(3)));
+        let mut storage_logs = storage
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        storage_logs.retain(|log| log.miniblock_number > MiniblockNumber(0));
+        storage_logs.sort_unstable_by_key(|log| log.miniblock_number);
+        let log_positions: Vec<_> = storage_logs
+            .iter()
+            .map(|log| (log.miniblock_number.0, log.operation_number))
+            .collect();
+        assert_eq!(log_positions, [(1, 0), (2, 0), (3, 0)]);
+    }
 }
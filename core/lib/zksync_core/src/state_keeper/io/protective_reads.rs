@@ -0,0 +1,269 @@
+//! Asynchronous persistence of protective reads.
+
+use std::time::Instant;
+
+use anyhow::Context as _;
+use tokio::sync::{mpsc, watch};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::{zk_evm_types::LogQuery, L1BatchNumber};
+
+use crate::replay::BatchReplayer;
+
+/// Protective reads of an L1 batch sent by [`StateKeeperPersistence`](super::StateKeeperPersistence)
+/// to the [`ProtectiveReadsWriter`].
+#[derive(Debug)]
+pub(super) struct ProtectiveReads {
+    pub l1_batch_number: L1BatchNumber,
+    pub reads: Vec<LogQuery>,
+}
+
+/// Component persisting protective reads of L1 batches to Postgres in the background, so that it's not done
+/// in the critical path of the state keeper. When protective reads persistence is offloaded, the state keeper
+/// only marks protective reads as pending when sealing an L1 batch, and sends the reads to this component.
+///
+/// Protective reads that are pending after a node restart (i.e., weren't persisted before the node was stopped)
+/// are derived by re-executing the corresponding L1 batches.
+#[derive(Debug)]
+pub struct ProtectiveReadsWriter {
+    pool: ConnectionPool<Core>,
+    replayer: BatchReplayer,
+    reads_receiver: mpsc::Receiver<ProtectiveReads>,
+}
+
+impl ProtectiveReadsWriter {
+    pub(super) fn new(
+        pool: ConnectionPool<Core>,
+        replayer: BatchReplayer,
+        reads_receiver: mpsc::Receiver<ProtectiveReads>,
+    ) -> Self {
+        Self {
+            pool,
+            replayer,
+            reads_receiver,
+        }
+    }
+
+    /// Runs the writer. This should be run on a separate Tokio task.
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.persist_restored_reads(&stop_receiver).await?;
+        loop {
+            let reads = tokio::select! {
+                reads = self.reads_receiver.recv() => reads,
+                _ = stop_receiver.changed() => break,
+            };
+            let Some(reads) = reads else {
+                tracing::info!(
+                    "State keeper persistence is dropped; stopping protective reads writer"
+                );
+                return Ok(());
+            };
+            self.persist(reads).await?;
+        }
+
+        // Persist reads already received from the state keeper, so that they don't need to be restored on restart.
+        while let Ok(reads) = self.reads_receiver.try_recv() {
+            self.persist(reads).await?;
+        }
+        tracing::info!("Stop signal received, protective reads writer is shutting down");
+        Ok(())
+    }
+
+    /// Restores and persists protective reads that were pending when the writer was started.
+    async fn persist_restored_reads(
+        &mut self,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .connection_tagged("protective_reads_writer")
+            .await?;
+        let pending_l1_batches = storage
+            .storage_logs_dedup_dal()
+            .get_l1_batches_with_pending_protective_reads()
+            .await?;
+        drop(storage);
+        if pending_l1_batches.is_empty() {
+            return Ok(());
+        }
+        tracing::info!(
+            "Restoring pending protective reads for {} L1 batches: {pending_l1_batches:?}",
+            pending_l1_batches.len()
+        );
+
+        for l1_batch_number in pending_l1_batches {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, interrupting restoring protective reads");
+                return Ok(());
+            }
+            // Reads received from the state keeper are cheaper to persist than to restore.
+            while let Ok(reads) = self.reads_receiver.try_recv() {
+                self.persist(reads).await?;
+            }
+
+            let mut storage = self
+                .pool
+                .connection_tagged("protective_reads_writer")
+                .await?;
+            let is_pending = storage
+                .storage_logs_dedup_dal()
+                .are_protective_reads_pending(l1_batch_number)
+                .await?;
+            drop(storage);
+            if !is_pending {
+                continue;
+            }
+
+            let started_at = Instant::now();
+            let reads = self
+                .replayer
+                .protective_reads(l1_batch_number)
+                .await
+                .with_context(|| {
+                    format!("failed restoring protective reads for L1 batch #{l1_batch_number}")
+                })?;
+            tracing::info!(
+                "Restored {} protective reads for L1 batch #{l1_batch_number} in {:?}",
+                reads.len(),
+                started_at.elapsed()
+            );
+            self.persist(ProtectiveReads {
+                l1_batch_number,
+                reads,
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn persist(&self, reads: ProtectiveReads) -> anyhow::Result<()> {
+        let ProtectiveReads {
+            l1_batch_number,
+            reads,
+        } = reads;
+        let started_at = Instant::now();
+        let mut storage = self
+            .pool
+            .connection_tagged("protective_reads_writer")
+            .await?;
+        let mut transaction = storage.start_transaction().await?;
+        let was_pending = transaction
+            .storage_logs_dedup_dal()
+            .remove_pending_protective_reads(l1_batch_number)
+            .await?;
+        if !was_pending {
+            // Reads may have been restored concurrently with receiving them, or the L1 batch may have been reverted.
+            tracing::debug!(
+                "Protective reads for L1 batch #{l1_batch_number} are not pending; skipping"
+            );
+            return Ok(());
+        }
+
+        transaction
+            .storage_logs_dedup_dal()
+            .insert_protective_reads(l1_batch_number, &reads)
+            .await
+            .with_context(|| {
+                format!("failed persisting protective reads for L1 batch #{l1_batch_number}")
+            })?;
+        transaction.commit().await?;
+        tracing::debug!(
+            "Persisted {} protective reads for L1 batch #{l1_batch_number} in {:?}",
+            reads.len(),
+            started_at.elapsed()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::chain::StateKeeperConfig;
+    use zksync_types::{zk_evm_types::Timestamp, Address, L2ChainId, U256};
+
+    use super::*;
+    use crate::{
+        genesis::{insert_genesis_batch, GenesisParams},
+        utils::testonly::create_l1_batch,
+    };
+
+    fn read_log_query(key: u8) -> LogQuery {
+        LogQuery {
+            timestamp: Timestamp(0),
+            tx_number_in_block: 0,
+            aux_byte: 0,
+            shard_id: 0,
+            address: Address::repeat_byte(1),
+            key: U256::from(key),
+            read_value: U256::zero(),
+            written_value: U256::zero(),
+            rw_flag: false,
+            rollback: false,
+            is_service: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn persisting_pending_protective_reads() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        let l1_batch = create_l1_batch(1);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&l1_batch)
+            .await
+            .unwrap();
+        storage
+            .storage_logs_dedup_dal()
+            .mark_protective_reads_as_pending(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let (reads_sender, reads_receiver) = mpsc::channel(1);
+        let replayer = BatchReplayer::new(
+            pool.clone(),
+            L2ChainId::default(),
+            &StateKeeperConfig::for_tests(),
+        );
+        let writer = ProtectiveReadsWriter::new(pool.clone(), replayer, reads_receiver);
+        let reads = vec![read_log_query(1), read_log_query(2)];
+        reads_sender
+            .send(ProtectiveReads {
+                l1_batch_number: L1BatchNumber(1),
+                reads: reads.clone(),
+            })
+            .await
+            .unwrap();
+        // Reads for the same L1 batch must be persisted only once.
+        writer
+            .persist(ProtectiveReads {
+                l1_batch_number: L1BatchNumber(1),
+                reads: reads.clone(),
+            })
+            .await
+            .unwrap();
+        drop(reads_sender);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        writer.run(stop_receiver).await.unwrap();
+
+        assert!(!storage
+            .storage_logs_dedup_dal()
+            .are_protective_reads_pending(L1BatchNumber(1))
+            .await
+            .unwrap());
+        let persisted_reads = storage
+            .storage_logs_dedup_dal()
+            .get_protective_reads_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(persisted_reads.len(), reads.len());
+        assert!(storage
+            .storage_logs_dedup_dal()
+            .get_l1_batches_with_pending_protective_reads()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -3,12 +3,13 @@
 
 use std::time::{Duration, Instant};
 
+use anyhow::Context as _;
 use itertools::Itertools;
 use multivm::utils::{get_max_batch_gas_limit, get_max_gas_per_pubdata_byte};
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_types::{
     block::{unpack_block_info, L1BatchHeader, MiniblockHeader},
-    event::{extract_added_tokens, extract_long_l2_to_l1_messages},
+    event::{build_logs_bloom, extract_added_tokens, extract_long_l2_to_l1_messages},
     helpers::unix_timestamp_ms,
     l1::L1Tx,
     l2::L2Tx,
@@ -20,15 +21,16 @@ use zksync_types::{
         TransactionExecutionResult,
     },
     zk_evm_types::LogQuery,
-    AccountTreeId, Address, ExecuteTransactionCommon, L1BlockNumber, ProtocolVersionId, StorageKey,
-    StorageLog, StorageLogQuery, Transaction, VmEvent, CURRENT_VIRTUAL_BLOCK_INFO_POSITION, H256,
-    SYSTEM_CONTEXT_ADDRESS,
+    AccountTreeId, Address, ExecuteTransactionCommon, L1BlockNumber, MiniblockNumber,
+    ProtocolVersionId, StorageKey, StorageLog, StorageLogQuery, Transaction, VmEvent,
+    CURRENT_VIRTUAL_BLOCK_INFO_POSITION, H256, SYSTEM_CONTEXT_ADDRESS,
 };
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
 use crate::{
     metrics::{BlockStage, MiniblockStage, APP_METRICS},
     state_keeper::{
+        io::{persistence::ProtectiveReadsMode, unit_of_work::SealBatchUnitOfWork},
         metrics::{
             L1BatchSealStage, MiniblockSealStage, TxExecutionType, KEEPER_METRICS,
             L1_BATCH_METRICS, MINIBLOCK_METRICS,
@@ -41,28 +43,33 @@ impl UpdatesManager {
     /// Persists an L1 batch in the storage.
     /// This action includes a creation of an empty "fictive" miniblock that contains
     /// the events generated during the bootloader "tip phase". Returns updates for this fictive miniblock.
+    ///
+    /// All writes are performed as a single [`SealBatchUnitOfWork`], so either the L1 batch is persisted
+    /// completely, or nothing is persisted at all.
     pub(super) async fn seal_l1_batch(
         &self,
         storage: &mut Connection<'_, Core>,
         l2_erc20_bridge_addr: Address,
-        insert_protective_reads: bool,
-    ) {
+        protective_reads_mode: &ProtectiveReadsMode,
+    ) -> anyhow::Result<()> {
         let started_at = Instant::now();
         let finished_batch = self
             .l1_batch
             .finished
             .as_ref()
-            .expect("L1 batch is not actually finished");
-        let mut transaction = storage.start_transaction().await.unwrap();
+            .context("L1 batch is not actually finished")?;
+        let mut unit_of_work = SealBatchUnitOfWork::begin(storage, self.l1_batch.number).await?;
 
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::FictiveMiniblock);
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::FictiveMiniblock)
+            .await?;
         // Seal fictive miniblock with last events and storage logs.
         let miniblock_command = self.seal_miniblock_command(
             l2_erc20_bridge_addr,
             false, // fictive miniblocks don't have txs, so it's fine to pass `false` here.
         );
-        miniblock_command.seal_inner(&mut transaction, true).await;
-        progress.observe(None);
+        MiniblockSealCommand::seal_inner(&[&miniblock_command], stage.connection(), true).await;
+        stage.finish(None).await?;
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::LogDeduplication);
 
@@ -98,7 +105,6 @@ impl UpdatesManager {
             current_l1_batch_number = self.l1_batch.number
         );
 
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::InsertL1BatchHeader);
         let l2_to_l1_messages =
             extract_long_l2_to_l1_messages(&finished_batch.final_execution_state.events);
         let l1_batch = L1BatchHeader {
@@ -127,53 +133,87 @@ impl UpdatesManager {
             .final_bootloader_memory
             .clone()
             .unwrap_or_default();
-        transaction
+        let execution_metrics = self.pending_execution_metrics();
+
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::InsertL1BatchHeader)
+            .await?;
+        let result = stage
+            .connection()
             .blocks_dal()
             .insert_l1_batch(
                 &l1_batch,
                 &final_bootloader_memory,
                 self.pending_l1_gas_count(),
                 &finished_batch.final_execution_state.storage_refunds,
-                self.pending_execution_metrics().circuit_statistic,
+                execution_metrics.storage_slots_reset as u64,
+                execution_metrics.circuit_statistic,
             )
-            .await
-            .unwrap();
-        progress.observe(None);
-
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::SetL1BatchNumberForMiniblocks);
-        transaction
+            .await;
+        result.map_err(|err| stage.context(err))?;
+        stage.finish(None).await?;
+
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::SetL1BatchNumberForMiniblocks)
+            .await?;
+        let result = stage
+            .connection()
             .blocks_dal()
             .mark_miniblocks_as_executed_in_l1_batch(self.l1_batch.number)
-            .await
-            .unwrap();
-        progress.observe(None);
-
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::MarkTxsAsExecutedInL1Batch);
-        transaction
+            .await;
+        result.map_err(|err| stage.context(err))?;
+        stage.finish(None).await?;
+
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::MarkTxsAsExecutedInL1Batch)
+            .await?;
+        stage
+            .connection()
             .transactions_dal()
             .mark_txs_as_executed_in_l1_batch(
                 self.l1_batch.number,
                 &self.l1_batch.executed_transactions,
             )
             .await;
-        progress.observe(None);
+        stage.finish(None).await?;
 
         let (deduplicated_writes, protective_reads): (Vec<_>, Vec<_>) = finished_batch
             .final_execution_state
             .deduplicated_storage_log_queries
             .iter()
             .partition(|log_query| log_query.rw_flag);
-        if insert_protective_reads {
-            let progress = L1_BATCH_METRICS.start(L1BatchSealStage::InsertProtectiveReads);
-            transaction
-                .storage_logs_dedup_dal()
-                .insert_protective_reads(self.l1_batch.number, &protective_reads)
-                .await
-                .unwrap();
-            progress.observe(protective_reads.len());
+        match protective_reads_mode {
+            ProtectiveReadsMode::Inline => {
+                let mut stage = unit_of_work
+                    .stage(L1BatchSealStage::InsertProtectiveReads)
+                    .await?;
+                let result = stage
+                    .connection()
+                    .storage_logs_dedup_dal()
+                    .insert_protective_reads(self.l1_batch.number, &protective_reads)
+                    .await;
+                result.map_err(|err| stage.context(err))?;
+                stage.finish(protective_reads.len()).await?;
+            }
+            ProtectiveReadsMode::Offloaded(_) => {
+                // Protective reads will be persisted by the protective reads writer.
+                let mut stage = unit_of_work
+                    .stage(L1BatchSealStage::InsertProtectiveReads)
+                    .await?;
+                let result = stage
+                    .connection()
+                    .storage_logs_dedup_dal()
+                    .mark_protective_reads_as_pending(self.l1_batch.number)
+                    .await;
+                result.map_err(|err| stage.context(err))?;
+                stage.finish(None).await?;
+            }
+            ProtectiveReadsMode::Disabled => { /* Do nothing */ }
         }
 
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::FilterWrittenSlots);
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::FilterWrittenSlots)
+            .await?;
         let deduplicated_writes_hashed_keys: Vec<_> = deduplicated_writes
             .iter()
             .map(|log| {
@@ -183,14 +223,17 @@ impl UpdatesManager {
                 ))
             })
             .collect();
-        let non_initial_writes = transaction
+        let result = stage
+            .connection()
             .storage_logs_dedup_dal()
             .filter_written_slots(&deduplicated_writes_hashed_keys)
-            .await
-            .expect("cannot filter out previously written VM storage slots");
-        progress.observe(deduplicated_writes.len());
+            .await;
+        let non_initial_writes = result.map_err(|err| stage.context(err))?;
+        stage.finish(deduplicated_writes.len()).await?;
 
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::InsertInitialWrites);
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::InsertInitialWrites)
+            .await?;
         let written_storage_keys: Vec<_> = deduplicated_writes
             .iter()
             .filter_map(|log| {
@@ -199,16 +242,15 @@ impl UpdatesManager {
             })
             .collect();
 
-        transaction
+        let result = stage
+            .connection()
             .storage_logs_dedup_dal()
             .insert_initial_writes(self.l1_batch.number, &written_storage_keys)
-            .await
-            .unwrap();
-        progress.observe(deduplicated_writes.len());
+            .await;
+        result.map_err(|err| stage.context(err))?;
+        stage.finish(deduplicated_writes.len()).await?;
 
-        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::CommitL1Batch);
-        transaction.commit().await.unwrap();
-        progress.observe(None);
+        unit_of_work.commit().await?;
 
         let writes_metrics = self.storage_writes_deduplicator.metrics();
         // Sanity check metrics.
@@ -219,6 +261,7 @@ impl UpdatesManager {
         );
 
         self.report_l1_batch_metrics(started_at, &writes_metrics);
+        Ok(())
     }
 
     fn report_l1_batch_metrics(
@@ -235,6 +278,9 @@ impl UpdatesManager {
         L1_BATCH_METRICS
             .transactions_in_l1_batch
             .observe(self.l1_batch.executed_transactions.len());
+        L1_BATCH_METRICS
+            .storage_slots_reset
+            .observe(self.pending_execution_metrics().storage_slots_reset);
 
         let batch_timestamp = self.batch_timestamp();
         let l1_batch_latency =
@@ -249,8 +295,14 @@ impl UpdatesManager {
 }
 
 impl MiniblockSealCommand {
-    pub(super) async fn seal(&self, storage: &mut Connection<'_, Core>) {
-        self.seal_inner(storage, false).await;
+    /// Seals several consecutive miniblocks in a single DB transaction.
+    pub(super) async fn seal_many(commands: &[&Self], storage: &mut Connection<'_, Core>) {
+        Self::seal_inner(commands, storage, false).await;
+    }
+
+    /// Estimated number of rows inserted into the events and storage logs tables when sealing the miniblock.
+    pub(super) fn estimated_row_count(&self) -> usize {
+        self.miniblock.events.len() + self.miniblock.storage_logs.len()
     }
 
     async fn insert_transactions(&self, transaction: &mut Connection<'_, Core>) {
@@ -288,7 +340,7 @@ impl MiniblockSealCommand {
         }
     }
 
-    /// Seals a miniblock with the given number.
+    /// Seals miniblocks specified by the provided commands.
     ///
     /// If `is_fictive` flag is set to true, then it is assumed that we should seal a fictive miniblock
     /// with no transactions in it. It is needed because there might be some storage logs / events
@@ -297,19 +349,68 @@ impl MiniblockSealCommand {
     /// one for sending fees to the operator).
     ///
     /// `l2_erc20_bridge_addr` is required to extract the information on newly added tokens.
-    async fn seal_inner(&self, storage: &mut Connection<'_, Core>, is_fictive: bool) {
+    ///
+    /// All miniblocks are sealed in a single DB transaction. Storage logs and events for all miniblocks
+    /// are inserted with a single `COPY` statement per table.
+    async fn seal_inner(commands: &[&Self], storage: &mut Connection<'_, Core>, is_fictive: bool) {
+        let mut transaction = storage.start_transaction().await.unwrap();
+        let started_at = Instant::now();
+        let mut write_logs = Vec::with_capacity(commands.len());
+        let mut events = Vec::with_capacity(commands.len());
+        for &command in commands {
+            let (miniblock_write_logs, miniblock_events) = command
+                .insert_miniblock_data(&mut transaction, is_fictive)
+                .await;
+            write_logs.push((command.miniblock.number, miniblock_write_logs));
+            events.push((command.miniblock.number, miniblock_events));
+        }
+        Self::insert_storage_logs_and_events(&mut transaction, &write_logs, &events, is_fictive)
+            .await;
+
+        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::CommitMiniblock, is_fictive);
+        let current_l2_virtual_block_info = transaction
+            .storage_web3_dal()
+            .get_value(&StorageKey::new(
+                AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
+                CURRENT_VIRTUAL_BLOCK_INFO_POSITION,
+            ))
+            .await
+            .expect("failed getting virtual block info from VM state");
+        let (current_l2_virtual_block_number, _) =
+            unpack_block_info(h256_to_u256(current_l2_virtual_block_info));
+
+        transaction.commit().await.unwrap();
+        progress.observe(None);
+
+        for &command in commands {
+            let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ReportTxMetrics, is_fictive);
+            command.report_transaction_metrics();
+            progress.observe(Some(command.miniblock.executed_transactions.len()));
+
+            command.report_miniblock_metrics(started_at, current_l2_virtual_block_number);
+        }
+    }
+
+    /// Inserts miniblock data except for storage logs and events, which are returned to be inserted
+    /// for all sealed miniblocks at once.
+    async fn insert_miniblock_data(
+        &self,
+        transaction: &mut Connection<'_, Core>,
+        is_fictive: bool,
+    ) -> (
+        Vec<(H256, Vec<StorageLog>)>,
+        Vec<(IncludedTxLocation, Vec<&VmEvent>)>,
+    ) {
         self.assert_valid_miniblock(is_fictive);
 
-        let mut transaction = storage.start_transaction().await.unwrap();
         if self.pre_insert_txs {
             let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::PreInsertTxs, is_fictive);
-            self.insert_transactions(&mut transaction).await;
+            self.insert_transactions(transaction).await;
             progress.observe(Some(self.miniblock.executed_transactions.len()));
         }
 
         let l1_batch_number = self.l1_batch_number;
         let miniblock_number = self.miniblock.number;
-        let started_at = Instant::now();
         let progress =
             MINIBLOCK_METRICS.start(MiniblockSealStage::InsertMiniblockHeader, is_fictive);
 
@@ -343,6 +444,7 @@ impl MiniblockSealCommand {
             gas_per_pubdata_limit: get_max_gas_per_pubdata_byte(definite_vm_version),
             virtual_blocks: self.miniblock.virtual_blocks,
             gas_limit: get_max_batch_gas_limit(definite_vm_version),
+            logs_bloom: build_logs_bloom(&self.miniblock.events),
         };
 
         transaction
@@ -364,16 +466,8 @@ impl MiniblockSealCommand {
             .await;
         progress.observe(self.miniblock.executed_transactions.len());
 
-        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertStorageLogs, is_fictive);
         let write_logs = self.extract_deduplicated_write_logs(is_fictive);
         let write_log_count: usize = write_logs.iter().map(|(_, logs)| logs.len()).sum();
-        transaction
-            .storage_logs_dal()
-            .insert_storage_logs(miniblock_number, &write_logs)
-            .await
-            .unwrap();
-        progress.observe(write_log_count);
-
         #[allow(deprecated)] // Will be removed shortly
         {
             let progress =
@@ -418,12 +512,14 @@ impl MiniblockSealCommand {
             .map(|(_, events)| events.len())
             .sum();
         progress.observe(miniblock_event_count);
-        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertEvents, is_fictive);
+
+        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::AggregateLogsBlooms, is_fictive);
         transaction
             .events_dal()
-            .save_events(miniblock_number, &miniblock_events)
-            .await;
-        progress.observe(miniblock_event_count);
+            .aggregate_logs_blooms(miniblock_number)
+            .await
+            .unwrap();
+        progress.observe(None);
 
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ExtractL2ToL1Logs, is_fictive);
 
@@ -447,27 +543,48 @@ impl MiniblockSealCommand {
             .save_user_l2_to_l1_logs(miniblock_number, &user_l2_to_l1_logs)
             .await;
         progress.observe(user_l2_to_l1_log_count);
+        (write_logs, miniblock_events)
+    }
 
-        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::CommitMiniblock, is_fictive);
-        let current_l2_virtual_block_info = transaction
-            .storage_web3_dal()
-            .get_value(&StorageKey::new(
-                AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
-                CURRENT_VIRTUAL_BLOCK_INFO_POSITION,
-            ))
+    async fn insert_storage_logs_and_events(
+        transaction: &mut Connection<'_, Core>,
+        write_logs: &[(MiniblockNumber, Vec<(H256, Vec<StorageLog>)>)],
+        events: &[(MiniblockNumber, Vec<(IncludedTxLocation, Vec<&VmEvent>)>)],
+        is_fictive: bool,
+    ) {
+        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertStorageLogs, is_fictive);
+        let write_logs: Vec<_> = write_logs
+            .iter()
+            .map(|(number, logs)| (*number, logs.as_slice()))
+            .collect();
+        let write_log_count: usize = write_logs
+            .iter()
+            .flat_map(|(_, logs)| *logs)
+            .map(|(_, logs)| logs.len())
+            .sum();
+        transaction
+            .storage_logs_dal()
+            .insert_storage_logs_copy_for_miniblocks(&write_logs)
             .await
-            .expect("failed getting virtual block info from VM state");
-        let (current_l2_virtual_block_number, _) =
-            unpack_block_info(h256_to_u256(current_l2_virtual_block_info));
-
-        transaction.commit().await.unwrap();
-        progress.observe(None);
-
-        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ReportTxMetrics, is_fictive);
-        self.report_transaction_metrics();
-        progress.observe(Some(self.miniblock.executed_transactions.len()));
+            .unwrap();
+        progress.observe(write_log_count);
 
-        self.report_miniblock_metrics(started_at, current_l2_virtual_block_number);
+        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertEvents, is_fictive);
+        let events: Vec<_> = events
+            .iter()
+            .map(|(number, events)| (*number, events.as_slice()))
+            .collect();
+        let event_count: usize = events
+            .iter()
+            .flat_map(|(_, events)| *events)
+            .map(|(_, events)| events.len())
+            .sum();
+        transaction
+            .events_dal()
+            .insert_events_copy_for_miniblocks(&events)
+            .await
+            .unwrap();
+        progress.observe(event_count);
     }
 
     /// Performs several sanity checks to make sure that the miniblock is valid.
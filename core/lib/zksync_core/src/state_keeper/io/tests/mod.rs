@@ -1,9 +1,10 @@
-use std::time::Duration;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
 use test_casing::test_casing;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{consensus_dal::Payload, ConnectionPool, Core, CoreDal};
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
     block::{BlockGasCount, MiniblockHasher},
@@ -20,11 +21,15 @@ use crate::{
     state_keeper::{
         io::StateKeeperIO,
         mempool_actor::l2_tx_filter,
-        tests::{create_execution_result, create_transaction, Query, BASE_SYSTEM_CONTRACTS},
+        seal_criteria::IoSealCriteria,
+        tests::{
+            create_execution_result, create_transaction, create_updates_manager, Query,
+            BASE_SYSTEM_CONTRACTS,
+        },
         updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
-        StateKeeperOutputHandler, StateKeeperPersistence,
+        PolicyTxFilter, StateKeeperOutputHandler, StateKeeperPersistence, TxFilterPolicy,
     },
-    utils::testonly::{prepare_recovery_snapshot, DeploymentMode},
+    utils::testonly::{create_l2_transaction, prepare_recovery_snapshot, DeploymentMode},
 };
 
 mod tester;
@@ -241,6 +246,7 @@ async fn processing_storage_logs_when_sealing_miniblock() {
         ExecutionMetrics::default(),
         vec![],
         vec![],
+        Duration::ZERO,
     );
 
     let tx = create_transaction(10, 100);
@@ -259,6 +265,7 @@ async fn processing_storage_logs_when_sealing_miniblock() {
         ExecutionMetrics::default(),
         vec![],
         vec![],
+        Duration::ZERO,
     );
 
     let l1_batch_number = L1BatchNumber(2);
@@ -282,7 +289,7 @@ async fn processing_storage_logs_when_sealing_miniblock() {
     conn.protocol_versions_dal()
         .save_protocol_version_with_tx(Default::default())
         .await;
-    seal_command.seal(&mut conn).await;
+    MiniblockSealCommand::seal_many(&[&seal_command], &mut conn).await;
 
     // Manually mark the miniblock as executed so that getting touched slots from it works
     conn.blocks_dal()
@@ -341,6 +348,7 @@ async fn processing_events_when_sealing_miniblock() {
             ExecutionMetrics::default(),
             vec![],
             vec![],
+            Duration::ZERO,
         );
     }
 
@@ -364,7 +372,7 @@ async fn processing_events_when_sealing_miniblock() {
     conn.protocol_versions_dal()
         .save_protocol_version_with_tx(Default::default())
         .await;
-    seal_command.seal(&mut conn).await;
+    MiniblockSealCommand::seal_many(&[&seal_command], &mut conn).await;
 
     let logs = conn
         .events_web3_dal()
@@ -442,6 +450,7 @@ async fn miniblock_processing_after_snapshot_recovery(deployment_mode: Deploymen
         BlockGasCount::default(),
         ExecutionMetrics::default(),
         vec![],
+        Duration::ZERO,
     );
 
     let (mut persistence, miniblock_sealer) =
@@ -537,3 +546,95 @@ async fn different_timestamp_for_miniblocks_in_same_batch(deployment_mode: Deplo
         .expect("no new miniblock params");
     assert!(miniblock_params.timestamp > current_timestamp);
 }
+
+#[tokio::test]
+async fn miniblock_payload_size_is_limited() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(&DeploymentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+
+    let first_tx = create_transaction(10, 100);
+    let second_tx = create_transaction(10, 100);
+    let first_tx_size = Payload::encoded_transaction_size(&first_tx);
+    let max_payload_size = first_tx_size + Payload::encoded_transaction_size(&second_tx) - 1;
+    let config = StateKeeperConfig {
+        max_miniblock_payload_size: Some(max_payload_size),
+        ..tester.state_keeper_config()
+    };
+    let (mut mempool, mut guard) = tester
+        .create_test_mempool_io_with_config(connection_pool, &config)
+        .await;
+
+    // A transaction always fits into an empty miniblock.
+    let mut updates = create_updates_manager();
+    assert!(!mempool.should_seal_miniblock_before_tx(&updates, &first_tx));
+    updates.extend_from_executed_transaction(
+        first_tx,
+        create_execution_result(0, []),
+        vec![],
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+        Duration::ZERO,
+    );
+    assert_eq!(updates.miniblock.payload_size, first_tx_size);
+    assert!(mempool.should_seal_miniblock_before_tx(&updates, &second_tx));
+
+    // Transactions that don't fit into a miniblock on their own should be rejected when selected from the mempool.
+    let mut large_tx = create_l2_transaction(10, 100);
+    large_tx.execute.calldata = vec![1; max_payload_size];
+    let small_tx = create_l2_transaction(10, 100);
+    guard.insert(
+        vec![large_tx.into(), small_tx.clone().into()],
+        Default::default(),
+    );
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("no transaction");
+    assert_eq!(tx.hash(), small_tx.hash());
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert!(tx.is_none(), "{tx:?}");
+}
+
+#[tokio::test]
+async fn transactions_are_filtered() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(&DeploymentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+
+    let (mempool, mut guard) = tester.create_test_mempool_io(connection_pool).await;
+    let tx_filter = PolicyTxFilter::new(TxFilterPolicy::default());
+    let mut mempool = mempool.with_transaction_filter(Arc::new(tx_filter.clone()));
+
+    let denied_tx = create_l2_transaction(10, 100);
+    let allowed_tx = create_l2_transaction(10, 100);
+    assert_ne!(
+        denied_tx.initiator_account(),
+        allowed_tx.initiator_account()
+    );
+    tx_filter.set_policy(TxFilterPolicy {
+        denied_addresses: HashSet::from([denied_tx.initiator_account()]),
+        ..TxFilterPolicy::default()
+    });
+    guard.insert(
+        vec![denied_tx.into(), allowed_tx.clone().into()],
+        Default::default(),
+    );
+
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("no transaction");
+    assert_eq!(tx.hash(), allowed_tx.hash());
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert!(tx.is_none(), "{tx:?}");
+}
@@ -4,7 +4,11 @@ use std::{slice, sync::Arc, time::Duration};
 
 use multivm::vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT;
 use zksync_config::{
-    configs::{chain::StateKeeperConfig, eth_sender::PubdataSendingMode, wallets::Wallets},
+    configs::{
+        chain::StateKeeperConfig,
+        eth_sender::{FeeEstimationStrategy, PubdataSendingMode},
+        wallets::Wallets,
+    },
     GasAdjusterConfig,
 };
 use zksync_contracts::BaseSystemContracts;
@@ -71,6 +75,11 @@ impl Tester {
             num_samples_for_blob_base_fee_estimate: 10,
             internal_pubdata_pricing_multiplier: 1.0,
             max_blob_base_fee: None,
+            fee_estimation_strategy: FeeEstimationStrategy::Median,
+            ewma_smoothing_factor: 0.2,
+            fee_estimation_percentile: 0.75,
+            l1_gas_price_quorum: 1,
+            l1_gas_price_max_deviation: 0.5,
         };
 
         GasAdjuster::new(
@@ -98,9 +107,28 @@ impl Tester {
         100
     }
 
+    pub(super) fn state_keeper_config(&self) -> StateKeeperConfig {
+        StateKeeperConfig {
+            minimal_l2_gas_price: self.minimal_l2_gas_price(),
+            virtual_blocks_interval: 1,
+            virtual_blocks_per_miniblock: 1,
+            validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
+            ..StateKeeperConfig::for_tests()
+        }
+    }
+
     pub(super) async fn create_test_mempool_io(
         &self,
         pool: ConnectionPool<Core>,
+    ) -> (MempoolIO, MempoolGuard) {
+        let config = self.state_keeper_config();
+        self.create_test_mempool_io_with_config(pool, &config).await
+    }
+
+    pub(super) async fn create_test_mempool_io_with_config(
+        &self,
+        pool: ConnectionPool<Core>,
+        config: &StateKeeperConfig,
     ) -> (MempoolIO, MempoolGuard) {
         let gas_adjuster = Arc::new(self.create_gas_adjuster().await);
         let batch_fee_input_provider = MainNodeFeeInputProvider::new(
@@ -111,19 +139,12 @@ impl Tester {
         );
 
         let mempool = MempoolGuard::new(PriorityOpId(0), 100);
-        let config = StateKeeperConfig {
-            minimal_l2_gas_price: self.minimal_l2_gas_price(),
-            virtual_blocks_interval: 1,
-            virtual_blocks_per_miniblock: 1,
-            validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
-            ..StateKeeperConfig::for_tests()
-        };
         let wallets = Wallets::for_tests();
         let io = MempoolIO::new(
             mempool.clone(),
             Arc::new(batch_fee_input_provider),
             pool,
-            &config,
+            config,
             wallets.state_keeper.unwrap().fee_account.address(),
             Duration::from_secs(1),
             L2ChainId::from(270),
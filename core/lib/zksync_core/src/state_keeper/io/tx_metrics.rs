@@ -0,0 +1,229 @@
+//! Recording of per-transaction execution metrics to Postgres.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::{
+    tx_execution_metrics_dal::TxExecutionMetricsEntry, ConnectionPool, Core, CoreDal,
+};
+use zksync_types::{MiniblockNumber, H256};
+
+use crate::state_keeper::{io::StateKeeperOutputHandler, updates::UpdatesManager};
+
+/// Checks whether a transaction with the specified hash should be recorded. Sampling is deterministic,
+/// so that the same transaction is consistently sampled across node restarts.
+fn is_sampled(tx_hash: H256, sampling_rate: f64) -> bool {
+    if sampling_rate >= 1.0 {
+        return true;
+    }
+    let hash_prefix = u64::from_be_bytes(tx_hash[..8].try_into().unwrap());
+    (hash_prefix as f64) < sampling_rate * u64::MAX as f64
+}
+
+/// [`StateKeeperOutputHandler`] recording execution metrics (wall time, gas used, pubdata, the number
+/// of storage writes, computational gas and circuits usage) for a sample of executed transactions.
+/// The recorded metrics allow identifying contracts that degrade sequencer throughput or dominate batch capacity.
+///
+/// Errors recording metrics are logged, but don't stop the state keeper.
+#[derive(Debug)]
+pub struct TxExecutionMetricsRecorder {
+    pool: ConnectionPool<Core>,
+    sampling_rate: f64,
+    retention_miniblocks: u64,
+}
+
+impl TxExecutionMetricsRecorder {
+    pub fn new(pool: ConnectionPool<Core>, sampling_rate: f64, retention_miniblocks: u64) -> Self {
+        Self {
+            pool,
+            sampling_rate,
+            retention_miniblocks,
+        }
+    }
+
+    fn collect_entries(&self, updates_manager: &UpdatesManager) -> Vec<TxExecutionMetricsEntry> {
+        let miniblock = &updates_manager.miniblock;
+        let first_tx_index = updates_manager.l1_batch.executed_transactions.len();
+        let mut storage_writes_by_tx = HashMap::<usize, u64>::new();
+        for log in &miniblock.storage_logs {
+            if log.log_query.rw_flag {
+                let tx_index = log.log_query.tx_number_in_block as usize;
+                *storage_writes_by_tx.entry(tx_index).or_default() += 1;
+            }
+        }
+
+        let sampled_txs = miniblock
+            .executed_transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx_result)| is_sampled(tx_result.hash, self.sampling_rate));
+        sampled_txs
+            .map(|(i, tx_result)| TxExecutionMetricsEntry {
+                tx_hash: tx_result.hash,
+                miniblock_number: miniblock.number,
+                contract_address: tx_result.transaction.recipient_account(),
+                execution_time: tx_result.execution_time,
+                gas_used: tx_result.execution_info.gas_used as u64,
+                pubdata_bytes: tx_result.execution_info.pubdata_published.into(),
+                storage_writes: storage_writes_by_tx
+                    .get(&(first_tx_index + i))
+                    .copied()
+                    .unwrap_or(0),
+                computational_gas_used: tx_result.execution_info.computational_gas_used.into(),
+                circuits_used: tx_result
+                    .execution_info
+                    .circuit_statistic
+                    .total_f32()
+                    .into(),
+            })
+            .collect()
+    }
+
+    async fn record(&self, entries: &[TxExecutionMetricsEntry]) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("tx_metrics_recorder").await?;
+        storage
+            .tx_execution_metrics_dal()
+            .insert_metrics(entries)
+            .await
+            .context("insert_metrics()")?;
+        Ok(())
+    }
+
+    async fn prune(&self, last_miniblock: MiniblockNumber) -> anyhow::Result<u64> {
+        let retention = u32::try_from(self.retention_miniblocks).unwrap_or(u32::MAX);
+        let first_retained_miniblock = MiniblockNumber(last_miniblock.0.saturating_sub(retention));
+        let mut storage = self.pool.connection_tagged("tx_metrics_recorder").await?;
+        storage
+            .tx_execution_metrics_dal()
+            .prune_metrics(first_retained_miniblock)
+            .await
+            .context("prune_metrics()")
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for TxExecutionMetricsRecorder {
+    async fn handle_miniblock(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        let entries = self.collect_entries(updates_manager);
+        if entries.is_empty() {
+            return Ok(());
+        }
+        if let Err(err) = self.record(&entries).await {
+            tracing::warn!(
+                "Failed recording execution metrics for {} transactions in miniblock #{}: {err:#}",
+                entries.len(),
+                updates_manager.miniblock.number
+            );
+        }
+        Ok(())
+    }
+
+    async fn handle_l1_batch(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        // Pruning once per L1 batch is frequent enough, and is cheaper than doing it for each miniblock.
+        match self.prune(updates_manager.miniblock.number).await {
+            Ok(pruned_count) => {
+                tracing::debug!("Pruned execution metrics for {pruned_count} transactions");
+            }
+            Err(err) => {
+                tracing::warn!("Failed pruning transaction execution metrics: {err:#}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zksync_types::{api, circuit::CircuitStatistic, tx::ExecutionMetrics, Address, U256};
+
+    use super::*;
+    use crate::{
+        gas_tracker::new_block_gas_count,
+        state_keeper::tests::{
+            create_execution_result, create_transaction, create_updates_manager, Query,
+        },
+    };
+
+    #[test]
+    fn sampling_transactions() {
+        let tx_hashes: Vec<_> = (0..1_000).map(|_| H256::random()).collect();
+        assert!(tx_hashes.iter().all(|&hash| is_sampled(hash, 1.0)));
+        assert!(!tx_hashes.iter().any(|&hash| is_sampled(hash, 0.0)));
+
+        let sampled_count = tx_hashes
+            .iter()
+            .filter(|&&hash| is_sampled(hash, 0.5))
+            .count();
+        assert!((350..=650).contains(&sampled_count), "{sampled_count}");
+    }
+
+    #[tokio::test]
+    async fn recording_tx_execution_metrics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut recorder = TxExecutionMetricsRecorder::new(pool.clone(), 1.0, 100);
+        let mut updates_manager = create_updates_manager();
+        let contract_address = Address::repeat_byte(0x42);
+        let execution_metrics = ExecutionMetrics {
+            gas_used: 10_000,
+            pubdata_published: 100,
+            computational_gas_used: 5_000,
+            circuit_statistic: CircuitStatistic {
+                main_vm: 0.5,
+                storage_application: 0.25,
+                ..CircuitStatistic::default()
+            },
+            ..ExecutionMetrics::default()
+        };
+
+        let tx_logs = [
+            vec![(U256::from(1), Query::InitialWrite(U256::one()))],
+            vec![
+                (U256::from(1), Query::Read(U256::one())),
+                (U256::from(2), Query::InitialWrite(U256::one())),
+                (
+                    U256::from(1),
+                    Query::RepeatedWrite(U256::one(), U256::zero()),
+                ),
+            ],
+        ];
+        for (i, storage_logs) in tx_logs.into_iter().enumerate() {
+            let mut tx = create_transaction(10, 100);
+            tx.execute.contract_address = contract_address;
+            updates_manager.extend_from_executed_transaction(
+                tx,
+                create_execution_result(i as u16, storage_logs),
+                vec![],
+                new_block_gas_count(),
+                execution_metrics,
+                vec![],
+                Duration::from_millis(5 * (i as u64 + 1)),
+            );
+        }
+        recorder.handle_miniblock(&updates_manager).await.unwrap();
+
+        let miniblock_number = updates_manager.miniblock.number;
+        let mut storage = pool.connection().await.unwrap();
+        let stats = storage
+            .tx_execution_metrics_dal()
+            .get_contract_execution_stats(miniblock_number..=miniblock_number, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            [api::ContractExecutionStats {
+                contract_address,
+                tx_count: 2,
+                total_execution_time_us: 15_000,
+                max_execution_time_us: 10_000,
+                total_gas_used: 20_000,
+                total_pubdata_bytes: 200,
+                total_storage_writes: 3,
+                total_computational_gas_used: 10_000,
+                total_circuits_used: 1.5,
+            }]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Unit of work grouping DB writes performed when sealing an L1 batch.
+
+use anyhow::Context as _;
+use zksync_dal::{Connection, Core};
+use zksync_types::L1BatchNumber;
+
+use crate::state_keeper::metrics::{L1BatchSealStage, SealProgress, L1_BATCH_METRICS};
+
+/// Groups all writes performed when sealing an L1 batch into a single explicit DB transaction.
+///
+/// Each sealing stage is executed in a separate savepoint created by [`Self::stage()`]. A stage is merged into
+/// the enclosing transaction only once it's [finished](SealBatchStage::finish()); if a stage is dropped
+/// (e.g., because one of its writes has failed), its savepoint is rolled back, and the unit of work is left
+/// in the state after the last finished stage. Writes become visible to other connections only after
+/// [`Self::commit()`]; if the unit of work is dropped without committing, all its writes are discarded.
+#[derive(Debug)]
+pub(super) struct SealBatchUnitOfWork<'a> {
+    transaction: Connection<'a, Core>,
+    l1_batch_number: L1BatchNumber,
+    finished_stages: Vec<L1BatchSealStage>,
+}
+
+impl<'a> SealBatchUnitOfWork<'a> {
+    /// Starts a unit of work for sealing the specified L1 batch.
+    pub async fn begin(
+        storage: &'a mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        let transaction = storage.start_transaction().await.with_context(|| {
+            format!("failed starting transaction for sealing L1 batch #{l1_batch_number}")
+        })?;
+        Ok(Self {
+            transaction,
+            l1_batch_number,
+            finished_stages: vec![],
+        })
+    }
+
+    /// Returns stages finished so far, in the order they were finished.
+    #[cfg(test)]
+    pub fn finished_stages(&self) -> &[L1BatchSealStage] {
+        &self.finished_stages
+    }
+
+    /// Starts a new sealing stage. Stage latency is reported to metrics once the stage is finished.
+    pub async fn stage(&mut self, stage: L1BatchSealStage) -> anyhow::Result<SealBatchStage<'_>> {
+        let l1_batch_number = self.l1_batch_number;
+        let savepoint = self
+            .transaction
+            .start_transaction()
+            .await
+            .with_context(|| {
+                format!("failed starting stage {stage:?} for sealing L1 batch #{l1_batch_number}")
+            })?;
+        Ok(SealBatchStage {
+            savepoint,
+            stage,
+            l1_batch_number,
+            progress: L1_BATCH_METRICS.start(stage),
+            finished_stages: &mut self.finished_stages,
+        })
+    }
+
+    /// Commits all finished stages.
+    pub async fn commit(self) -> anyhow::Result<()> {
+        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::CommitL1Batch);
+        self.transaction.commit().await.with_context(|| {
+            format!(
+                "failed committing L1 batch #{} (finished stages: {:?})",
+                self.l1_batch_number, self.finished_stages
+            )
+        })?;
+        progress.observe(None);
+        Ok(())
+    }
+}
+
+/// Single stage of [`SealBatchUnitOfWork`] executed in a savepoint.
+#[must_use = "Stage must be `finish()`ed; otherwise, its writes are rolled back"]
+#[derive(Debug)]
+pub(super) struct SealBatchStage<'a> {
+    savepoint: Connection<'a, Core>,
+    stage: L1BatchSealStage,
+    l1_batch_number: L1BatchNumber,
+    progress: SealProgress<'static>,
+    finished_stages: &'a mut Vec<L1BatchSealStage>,
+}
+
+impl<'a> SealBatchStage<'a> {
+    /// Returns the connection writes for this stage should be performed with.
+    pub fn connection(&mut self) -> &mut Connection<'a, Core> {
+        &mut self.savepoint
+    }
+
+    /// Wraps an error returned by one of the stage writes, adding context about the stage.
+    pub fn context<E>(&self, err: E) -> anyhow::Error
+    where
+        E: Into<anyhow::Error>,
+    {
+        err.into().context(format!(
+            "failed stage {:?} of sealing L1 batch #{}",
+            self.stage, self.l1_batch_number
+        ))
+    }
+
+    /// Finishes this stage, merging its writes into the enclosing unit of work.
+    pub async fn finish(self, entity_count: impl Into<Option<usize>>) -> anyhow::Result<()> {
+        let stage = self.stage;
+        let l1_batch_number = self.l1_batch_number;
+        self.savepoint.commit().await.with_context(|| {
+            format!("failed finishing stage {stage:?} of sealing L1 batch #{l1_batch_number}")
+        })?;
+        self.progress.observe(entity_count);
+        self.finished_stages.push(stage);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::{ConnectionPool, CoreDal};
+
+    use super::*;
+    use crate::{
+        genesis::{insert_genesis_batch, GenesisParams},
+        utils::testonly::create_l1_batch,
+    };
+
+    async fn prepare_storage(pool: &ConnectionPool<Core>) -> Connection<'_, Core> {
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        storage
+    }
+
+    async fn insert_l1_batch(
+        unit_of_work: &mut SealBatchUnitOfWork<'_>,
+        number: u32,
+    ) -> anyhow::Result<()> {
+        let mut stage = unit_of_work
+            .stage(L1BatchSealStage::InsertL1BatchHeader)
+            .await?;
+        let result = stage
+            .connection()
+            .blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await;
+        result.map_err(|err| stage.context(err))?;
+        stage.finish(None).await
+    }
+
+    #[tokio::test]
+    async fn failed_stage_is_rolled_back() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = prepare_storage(&pool).await;
+
+        let mut unit_of_work = SealBatchUnitOfWork::begin(&mut storage, L1BatchNumber(1))
+            .await
+            .unwrap();
+        insert_l1_batch(&mut unit_of_work, 1).await.unwrap();
+        // Inserting the same L1 batch again violates the primary key constraint.
+        let err = insert_l1_batch(&mut unit_of_work, 1).await.unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("InsertL1BatchHeader"), "{err}");
+        assert_eq!(
+            unit_of_work.finished_stages(),
+            [L1BatchSealStage::InsertL1BatchHeader]
+        );
+
+        // The unit of work must remain usable after the failed stage is rolled back.
+        insert_l1_batch(&mut unit_of_work, 2).await.unwrap();
+        unit_of_work.commit().await.unwrap();
+
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(2)));
+    }
+
+    #[tokio::test]
+    async fn dropped_unit_of_work_is_rolled_back() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = prepare_storage(&pool).await;
+
+        let mut unit_of_work = SealBatchUnitOfWork::begin(&mut storage, L1BatchNumber(1))
+            .await
+            .unwrap();
+        insert_l1_batch(&mut unit_of_work, 1).await.unwrap();
+        drop(unit_of_work);
+
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(0)));
+    }
+}
@@ -1,14 +1,17 @@
 use std::{
     convert::Infallible,
     future::Future,
+    mem,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
 use multivm::interface::{Halt, L1BatchEnv, SystemEnv};
+use serde::Serialize;
 use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{
     block::MiniblockExecutionData, l2::TransactionType, protocol_upgrade::ProtocolUpgradeTx,
     protocol_version::ProtocolVersionId, storage_writes_deduplicator::StorageWritesDeduplicator,
@@ -17,6 +20,7 @@ use zksync_types::{
 
 use super::{
     batch_executor::{BatchExecutor, BatchExecutorHandle, TxExecutionResult},
+    control::{SoftShutdownStatus, StateKeeperControl},
     extractors,
     io::{
         fee_address_migration, IoCursor, MiniblockParams, OutputHandler, PendingBatchData,
@@ -42,6 +46,15 @@ pub(super) enum Error {
     Fatal(#[from] anyhow::Error),
 }
 
+/// Health details reported by the state keeper.
+#[derive(Debug, Serialize)]
+struct StateKeeperHealthDetails {
+    soft_shutdown: SoftShutdownStatus,
+    /// Number of the L1 batch that will be opened after the soft shutdown is completed and the node is restarted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_l1_batch: Option<L1BatchNumber>,
+}
+
 impl Error {
     fn context(self, msg: &'static str) -> Self {
         match self {
@@ -67,6 +80,8 @@ pub struct ZkSyncStateKeeper {
     output_handler: OutputHandler,
     batch_executor_base: Box<dyn BatchExecutor>,
     sealer: Arc<dyn ConditionalSealer>,
+    control: StateKeeperControl,
+    health_updater: HealthUpdater,
 }
 
 impl ZkSyncStateKeeper {
@@ -83,9 +98,22 @@ impl ZkSyncStateKeeper {
             batch_executor_base,
             output_handler,
             sealer,
+            control: StateKeeperControl::default(),
+            health_updater: ReactiveHealthCheck::new("state_keeper").1,
         }
     }
 
+    /// Sets the handle allowing to request a soft shutdown of this state keeper.
+    pub fn with_control(mut self, control: StateKeeperControl) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Returns the health check for this state keeper.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
     /// Temporary method to migrate fee addresses from L1 batches to miniblocks.
     pub fn run_fee_address_migration(
         &self,
@@ -121,6 +149,7 @@ impl ZkSyncStateKeeper {
             cursor.l1_batch,
             cursor.next_miniblock
         );
+        self.update_health(HealthStatus::Ready, None);
 
         // Re-execute pending batch if it exists. Otherwise, initialize a new batch.
         let PendingBatchData {
@@ -179,6 +208,12 @@ impl ZkSyncStateKeeper {
             // This function will run until the batch can be sealed.
             self.process_l1_batch(&batch_executor, &mut updates_manager, protocol_upgrade_tx)
                 .await?;
+            if self.control.is_soft_shutdown_requested()
+                && updates_manager.pending_executed_transactions_len() == 0
+            {
+                // The L1 batch is empty, so there's nothing to seal.
+                return self.complete_soft_shutdown(l1_batch_env.number).await;
+            }
 
             // Finish current batch.
             if !updates_manager.miniblock.executed_transactions.is_empty() {
@@ -208,6 +243,10 @@ impl ZkSyncStateKeeper {
             }
             l1_batch_seal_delta = Some(Instant::now());
 
+            if self.control.is_soft_shutdown_requested() {
+                return self.complete_soft_shutdown(l1_batch_env.number + 1).await;
+            }
+
             // Start the new batch.
             let mut next_cursor = updates_manager.io_cursor();
             next_cursor.l1_batch += 1;
@@ -286,6 +325,30 @@ impl ZkSyncStateKeeper {
         *self.stop_receiver.borrow()
     }
 
+    fn update_health(&self, status: HealthStatus, next_l1_batch: Option<L1BatchNumber>) {
+        let details = StateKeeperHealthDetails {
+            soft_shutdown: self.control.soft_shutdown_status(),
+            next_l1_batch,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+    }
+
+    /// Marks the soft shutdown as completed and waits for the stop signal. The state keeper task doesn't exit
+    /// on its own since this would shut down the node.
+    async fn complete_soft_shutdown(
+        &mut self,
+        next_l1_batch: L1BatchNumber,
+    ) -> Result<Infallible, Error> {
+        self.control.complete_soft_shutdown();
+        self.update_health(HealthStatus::ShutDown, Some(next_l1_batch));
+        tracing::info!(
+            "State keeper is soft-shut down; L1 batch #{next_l1_batch} will be opened after restart"
+        );
+        self.stop_receiver.wait_for(|stop| *stop).await.ok();
+        Err(Error::Canceled)
+    }
+
     async fn load_upgrade_tx(
         &mut self,
         protocol_version: ProtocolVersionId,
@@ -342,6 +405,14 @@ impl ZkSyncStateKeeper {
             .await;
     }
 
+    /// Passes transactions that will be executed next to the batch executor, so that it can speed up their execution.
+    async fn speculate_upcoming_txs(&mut self, batch_executor: &BatchExecutorHandle) {
+        let txs = self.io.peek_upcoming_txs();
+        if !txs.is_empty() {
+            batch_executor.speculate_txs(txs).await;
+        }
+    }
+
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
         self.output_handler
             .handle_miniblock(updates_manager)
@@ -396,6 +467,7 @@ impl ZkSyncStateKeeper {
                     tx_metrics,
                     compressed_bytecodes,
                     call_tracer_result,
+                    execution_time,
                     ..
                 } = result
                 else {
@@ -427,6 +499,7 @@ impl ZkSyncStateKeeper {
                     tx_l1_gas_this_tx,
                     tx_execution_metrics,
                     call_tracer_result,
+                    execution_time,
                 );
 
                 tracing::debug!(
@@ -467,7 +540,10 @@ impl ZkSyncStateKeeper {
             self.process_upgrade_tx(batch_executor, updates_manager, protocol_upgrade_tx)
                 .await;
         }
+        self.speculate_upcoming_txs(batch_executor).await;
 
+        // Set if the next transaction doesn't fit into the current miniblock.
+        let mut seal_miniblock_before_next_tx = false;
         while !self.is_canceled() {
             if self
                 .io
@@ -480,7 +556,18 @@ impl ZkSyncStateKeeper {
                 return Ok(());
             }
 
-            if self.io.should_seal_miniblock(updates_manager) {
+            if self.control.is_soft_shutdown_requested() {
+                tracing::info!(
+                    "L1 batch #{} will be sealed because soft shutdown is requested",
+                    updates_manager.l1_batch.number
+                );
+                self.update_health(HealthStatus::ShuttingDown, None);
+                return Ok(());
+            }
+
+            if mem::take(&mut seal_miniblock_before_next_tx)
+                || self.io.should_seal_miniblock(updates_manager)
+            {
                 tracing::debug!(
                     "Miniblock #{} (L1 batch #{}) should be sealed as per sealing rules",
                     updates_manager.miniblock.number,
@@ -500,6 +587,7 @@ impl ZkSyncStateKeeper {
                 );
                 Self::start_next_miniblock(new_miniblock_params, updates_manager, batch_executor)
                     .await;
+                self.speculate_upcoming_txs(batch_executor).await;
             }
 
             let waiting_latency = KEEPER_METRICS.waiting_for_tx.start();
@@ -515,6 +603,19 @@ impl ZkSyncStateKeeper {
             };
             waiting_latency.observe();
 
+            if self
+                .io
+                .should_seal_miniblock_before_tx(updates_manager, &tx)
+            {
+                // Return the transaction to the IO; it will be executed in the next miniblock.
+                self.io
+                    .rollback(tx)
+                    .await
+                    .context("failed rolling back transaction")?;
+                seal_miniblock_before_next_tx = true;
+                continue;
+            }
+
             let tx_hash = tx.hash();
             let (seal_resolution, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
@@ -527,6 +628,7 @@ impl ZkSyncStateKeeper {
                         tx_metrics,
                         call_tracer_result,
                         compressed_bytecodes,
+                        execution_time,
                         ..
                     } = exec_result
                     else {
@@ -545,6 +647,7 @@ impl ZkSyncStateKeeper {
                         tx_l1_gas_this_tx,
                         tx_execution_metrics,
                         call_tracer_result,
+                        execution_time,
                     );
                 }
                 SealResolution::ExcludeAndSeal => {
@@ -595,6 +698,7 @@ impl ZkSyncStateKeeper {
                     tx_result,
                     tx_metrics,
                     compressed_bytecodes,
+                    execution_time,
                     ..
                 } = exec_result
                 else {
@@ -621,6 +725,7 @@ impl ZkSyncStateKeeper {
                     tx_l1_gas_this_tx,
                     tx_execution_metrics,
                     vec![],
+                    execution_time,
                 );
             }
             SealResolution::ExcludeAndSeal => {
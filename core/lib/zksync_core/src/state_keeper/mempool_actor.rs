@@ -32,6 +32,14 @@ pub async fn l2_tx_filter(
     }
 }
 
+/// Component loading pending transactions from Postgres into the in-memory [`MempoolGuard`].
+///
+/// The in-memory mempool is a cache over the `transactions` table: transactions are persisted by the API server
+/// (or the Ethereum watcher for L1 transactions) before they can be loaded into the mempool, and are marked
+/// with the `in_mempool` flag once loaded. Removals from the mempool (stashed and purged accounts) are mirrored
+/// to Postgres on each sync. On startup, the fetcher resets the `in_mempool` flag for all transactions, so that
+/// the mempool is fully rehydrated from Postgres after a restart, including transactions that were loaded into
+/// the mempool, but not included into a sealed miniblock before the restart.
 #[derive(Debug)]
 pub struct MempoolFetcher {
     mempool: MempoolGuard,
@@ -40,6 +48,7 @@ pub struct MempoolFetcher {
     sync_interval: Duration,
     sync_batch_size: usize,
     stuck_tx_timeout: Option<Duration>,
+    mempool_size_sender: Option<watch::Sender<u64>>,
     #[cfg(test)]
     transaction_hashes_sender: mpsc::UnboundedSender<Vec<H256>>,
 }
@@ -58,11 +67,22 @@ impl MempoolFetcher {
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
             stuck_tx_timeout: config.remove_stuck_txs.then(|| config.stuck_tx_timeout()),
+            mempool_size_sender: None,
             #[cfg(test)]
             transaction_hashes_sender: mpsc::unbounded_channel().0,
         }
     }
 
+    /// Makes the fetcher report the total number of transactions in the mempool after each sync,
+    /// e.g. for [`CongestionPricing`](crate::fee_model::CongestionPricing). In this case, the fee input provider
+    /// passed to the fetcher should not apply congestion pricing; otherwise, transactions underpriced
+    /// due to congestion are not loaded into the mempool and are not accounted in its size.
+    #[must_use]
+    pub fn with_mempool_size_sender(mut self, sender: watch::Sender<u64>) -> Self {
+        self.mempool_size_sender = Some(sender);
+        self
+    }
+
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut storage = self.pool.connection_tagged("state_keeper").await?;
         if let Some(stuck_tx_timeout) = self.stuck_tx_timeout {
@@ -88,6 +108,14 @@ impl MempoolFetcher {
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut storage = self.pool.connection_tagged("state_keeper").await?;
             let mempool_info = self.mempool.get_mempool_info();
+            let evicted_count = storage
+                .transactions_dal()
+                .remove_evicted_txs(&mempool_info.evicted_transactions)
+                .await
+                .context("failed removing evicted transactions")?;
+            KEEPER_METRICS
+                .mempool_evicted_transactions
+                .inc_by(evicted_count as u64);
             let protocol_version = pending_protocol_version(&mut storage)
                 .await
                 .context("failed getting pending protocol version")?;
@@ -119,6 +147,10 @@ impl MempoolFetcher {
             }
             let all_transactions_loaded = transactions.len() < self.sync_batch_size;
             self.mempool.insert(transactions, nonces);
+            if let Some(sender) = &self.mempool_size_sender {
+                let stats = self.mempool.stats();
+                sender.send_replace(stats.l1_transaction_count as u64 + stats.l2_transaction_count);
+            }
             latency.observe();
 
             if all_transactions_loaded {
@@ -160,6 +192,7 @@ async fn get_transaction_nonces(
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::configs::chain::MempoolDurability;
     use zksync_types::{
         fee::TransactionExecutionMetrics, MiniblockNumber, PriorityOpId, ProtocolVersionId,
         StorageLog, H256,
@@ -179,6 +212,8 @@ mod tests {
         stuck_tx_timeout: 0,
         remove_stuck_txs: false,
         delay_interval: 10,
+        max_nonce_gap: None,
+        durability: MempoolDurability::Synchronous,
     };
 
     #[tokio::test]
@@ -261,6 +296,53 @@ mod tests {
         fetcher_task.await.unwrap().expect("fetcher errored");
     }
 
+    #[tokio::test]
+    async fn rehydrating_mempool_after_restart() {
+        let pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let fee_params_provider = Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await;
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+        let transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let transaction_hash = transaction.hash();
+        let mut storage = pool.connection().await.unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        drop(storage);
+
+        // Load the transaction into the mempool, and then emulate a restart by dropping the mempool
+        // without executing the transaction.
+        for _ in 0..2 {
+            let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+            let mut fetcher = MempoolFetcher::new(
+                mempool.clone(),
+                fee_params_provider.clone(),
+                &TEST_MEMPOOL_CONFIG,
+                pool.clone(),
+            );
+            let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+            fetcher.transaction_hashes_sender = tx_hashes_sender;
+            let (stop_sender, stop_receiver) = watch::channel(false);
+            let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+
+            let tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+            assert_eq!(tx_hashes, [transaction_hash]);
+            assert_eq!(mempool.stats().l2_transaction_count, 1);
+
+            stop_sender.send_replace(true);
+            fetcher_task.await.unwrap().expect("fetcher errored");
+        }
+    }
+
     async fn wait_for_new_transactions(
         tx_hashes_receiver: &mut mpsc::UnboundedReceiver<Vec<H256>>,
     ) -> Vec<H256> {
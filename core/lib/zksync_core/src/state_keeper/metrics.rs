@@ -7,8 +7,8 @@ use std::{
 
 use multivm::interface::VmExecutionResultAndLogs;
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics,
 };
 use zksync_mempool::MempoolStore;
 use zksync_types::{tx::tx_execution_info::DeduplicatedWritesMetrics, ProtocolVersionId};
@@ -59,6 +59,11 @@ pub(crate) struct StateKeeperMetrics {
     pub get_tx_from_mempool: Histogram<Duration>,
     /// Number of transactions rejected by the state keeper.
     pub rejected_transactions: Counter,
+    /// Number of transactions rejected by the transaction filter. These transactions are also counted
+    /// in `rejected_transactions`.
+    pub filtered_transactions: Counter,
+    /// Number of L2 transactions evicted from the mempool because of a nonce gap and removed from Postgres.
+    pub mempool_evicted_transactions: Counter,
     /// Time spent waiting for the hash of a previous L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub wait_for_prev_hash_time: Histogram<Duration>,
@@ -149,6 +154,10 @@ struct TxAggregationLabels {
 #[metrics(prefix = "server_tx_aggregation")]
 pub(super) struct TxAggregationMetrics {
     reason: Family<TxAggregationLabels, Counter>,
+    /// Number of L1 batches sealed because of a specific seal criterion. If several criteria
+    /// have decided to seal a batch at the same time, all of them are counted.
+    #[metrics(labels = ["criterion"])]
+    pub l1_batch_sealed_by: LabeledFamily<&'static str, Counter>,
 }
 
 impl TxAggregationMetrics {
@@ -208,6 +217,9 @@ pub(crate) struct L1BatchMetrics {
     /// Number of repeated writes in a single L1 batch.
     #[metrics(buckets = COUNT_BUCKETS)]
     pub repeated_writes: Histogram<usize>,
+    /// Number of storage slots reset to their original values by transactions in a single L1 batch.
+    #[metrics(buckets = COUNT_BUCKETS)]
+    pub storage_slots_reset: Histogram<usize>,
     /// Number of transactions in a single L1 batch.
     #[metrics(buckets = COUNT_BUCKETS)]
     pub transactions_in_l1_batch: Histogram<usize>,
@@ -262,6 +274,7 @@ pub(super) enum MiniblockSealStage {
     InsertTokens,
     ExtractEvents,
     InsertEvents,
+    AggregateLogsBlooms,
     ExtractL2ToL1Logs,
     InsertL2ToL1Logs,
     CommitMiniblock,
@@ -291,6 +304,14 @@ pub(super) struct MiniblockMetrics {
     /// Total latency of sealing a miniblock.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub sealed_time: Histogram<Duration>,
+    /// Number of miniblocks sealed in a single DB transaction by the miniblock sealer.
+    #[metrics(buckets = Buckets::linear(1.0..=10.0, 1.0))]
+    pub seal_batch_size: Histogram<usize>,
+    /// Current limit on the number of events and storage logs in a single DB transaction
+    /// set by the adaptive miniblock sealer.
+    pub seal_batch_row_limit: Gauge<usize>,
+    /// Current miniblock sealing deadline set by the adaptive sealing policy.
+    pub adaptive_seal_deadline: Gauge<Duration>,
     /// Latency of sealing a miniblock split by the stage.
     #[metrics(buckets = Buckets::LATENCIES)]
     sealed_time_stage: Family<MiniblockSealLabels, Histogram<Duration>>,
@@ -363,6 +384,17 @@ pub(super) enum ExecutorCommand {
     StartNextMiniblock,
     RollbackLastTx,
     FinishBatch,
+    SpeculateTxs,
+}
+
+/// Outcome of speculatively pre-executing a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum SpeculationOutcome {
+    /// Storage reads of the transaction were prefetched.
+    Prefetched,
+    /// The transaction (probably) depends on preceding transactions in the batch, so it is only executed sequentially.
+    Conflict,
 }
 
 const GAS_PER_NANOSECOND_BUCKETS: Buckets = Buckets::values(&[
@@ -384,6 +416,20 @@ pub(super) struct ExecutorMetrics {
     pub computational_gas_per_nanosecond: Histogram<f64>,
     #[metrics(buckets = GAS_PER_NANOSECOND_BUCKETS)]
     pub failed_tx_gas_limit_per_nanosecond: Histogram<f64>,
+    /// Number of transactions speculatively pre-executed by the batch executor, grouped by the outcome.
+    pub speculated_txs: Family<SpeculationOutcome, Counter>,
+    /// Number of storage slots prefetched by speculative execution of a group of transactions.
+    #[metrics(buckets = Buckets::exponential(1.0..=16_384.0, 4.0))]
+    pub prefetched_storage_slots: Histogram<usize>,
+    /// Latency of executing a single transaction in the shadow VM.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub shadow_vm_tx_execution_time: Histogram<Duration>,
+    /// Number of divergences between the main VM and the shadow VM.
+    pub shadow_vm_divergences: Counter,
+    /// Number of L1 batches for which shadow execution was stopped because the shadow VM lagged behind the main VM.
+    pub shadow_vm_stopped_batches: Counter,
+    /// Last L1 batch fully processed by the shadow VM (i.e., with divergences persisted).
+    pub shadow_vm_l1_batch: Gauge<u64>,
 }
 
 #[vise::register]
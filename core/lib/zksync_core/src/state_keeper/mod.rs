@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::{
     configs::{
@@ -12,20 +13,36 @@ use zksync_dal::{ConnectionPool, Core};
 use zksync_types::L2ChainId;
 
 pub use self::{
-    batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
+    admin::run_admin_server,
+    batch_executor::{
+        main_executor::{shadow_vm_version, MainBatchExecutor},
+        BatchExecutor,
+    },
+    control::{SoftShutdownStatus, StateKeeperControl},
     io::{
-        mempool::MempoolIO, MiniblockSealerTask, OutputHandler, StateKeeperIO,
-        StateKeeperOutputHandler, StateKeeperPersistence,
+        mempool::MempoolIO, MiniblockSealerTask, OutputHandler, ProtectiveReadsWriter,
+        StateKeeperIO, StateKeeperOutputHandler, StateKeeperPersistence,
+        TxExecutionMetricsRecorder,
     },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
-    seal_criteria::SequencerSealer,
-    state_keeper_storage::{AsyncCatchupTask, AsyncRocksdbCache},
+    seal_criteria::{AdaptiveMiniblockSealPolicy, MiniblockSealPolicy, SequencerSealer},
+    state_keeper_storage::{
+        state_keeper_rocksdb_caches, state_keeper_rocksdb_options, AsyncCatchupTask,
+        AsyncRocksdbCache,
+    },
+    tx_filter::{PolicyTxFilter, TransactionFilter, TxFilterPolicy},
     types::MempoolGuard,
 };
+pub(crate) use self::{
+    batch_executor::TxExecutionResult,
+    state_keeper_storage::{PgOrRocksdbStorage, ReadStorageFactory},
+};
 use crate::fee_model::BatchFeeModelInputProvider;
 
+mod admin;
 mod batch_executor;
+mod control;
 pub(crate) mod extractors;
 pub(crate) mod io;
 mod keeper;
@@ -35,6 +52,7 @@ pub mod seal_criteria;
 mod state_keeper_storage;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tx_filter;
 pub(crate) mod types;
 pub(crate) mod updates;
 
@@ -49,18 +67,29 @@ pub(crate) async fn create_state_keeper(
     mempool: MempoolGuard,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     output_handler: OutputHandler,
+    tx_filter: Arc<dyn TransactionFilter>,
     stop_receiver: watch::Receiver<bool>,
-) -> (ZkSyncStateKeeper, AsyncCatchupTask) {
+) -> anyhow::Result<(ZkSyncStateKeeper, AsyncCatchupTask)> {
     let (storage_factory, task) = AsyncRocksdbCache::new(
         pool.clone(),
         db_config.state_keeper_db_path.clone(),
+        state_keeper_rocksdb_options(db_config),
         state_keeper_config.enum_index_migration_chunk_size(),
     );
-    let batch_executor_base = MainBatchExecutor::new(
+    let task = task.with_caches(
+        state_keeper_rocksdb_caches(db_config),
+        db_config.state_keeper_db_cache_warm_up_l1_batches,
+    );
+    let mut batch_executor_base = MainBatchExecutor::new(
         Arc::new(storage_factory),
         state_keeper_config.save_call_traces,
         false,
     );
+    let shadow_vm_version = shadow_vm_version(&state_keeper_config)
+        .context("Invalid shadow VM config for state keeper")?;
+    if let Some(vm_version) = shadow_vm_version {
+        batch_executor_base = batch_executor_base.with_shadow_execution(vm_version, pool.clone());
+    }
 
     let io = MempoolIO::new(
         mempool,
@@ -72,10 +101,12 @@ pub(crate) async fn create_state_keeper(
         l2chain_id,
     )
     .await
-    .expect("Failed initializing main node I/O for state keeper");
+    .context("Failed initializing main node I/O for state keeper")?
+    .with_transaction_filter(tx_filter);
 
-    let sealer = SequencerSealer::new(state_keeper_config);
-    (
+    let sealer = SequencerSealer::new(state_keeper_config)
+        .context("Invalid seal criteria config for state keeper")?;
+    Ok((
         ZkSyncStateKeeper::new(
             stop_receiver,
             Box::new(io),
@@ -84,5 +115,5 @@ pub(crate) async fn create_state_keeper(
             Arc::new(sealer),
         ),
         task,
-    )
+    ))
 }
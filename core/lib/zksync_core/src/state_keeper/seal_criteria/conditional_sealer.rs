@@ -9,7 +9,7 @@ use std::fmt;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::ProtocolVersionId;
 
-use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use super::{SealCriteriaRegistry, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
@@ -86,6 +86,7 @@ impl ConditionalSealer for SequencerSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
+        let mut resolutions = Vec::with_capacity(self.sealers.len());
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
                 &self.config,
@@ -108,16 +109,43 @@ impl ConditionalSealer for SequencerSealer {
                 SealResolution::NoSeal => { /* Don't do anything */ }
             }
 
-            final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
+            final_seal_resolution = final_seal_resolution.stricter(seal_resolution.clone());
+            resolutions.push((sealer.prom_criterion_name(), seal_resolution));
+        }
+
+        if final_seal_resolution.should_seal() {
+            for (name, resolution) in resolutions {
+                if resolution == final_seal_resolution {
+                    AGGREGATION_METRICS.l1_batch_sealed_by[&name].inc();
+                }
+            }
         }
         final_seal_resolution
     }
 }
 
 impl SequencerSealer {
-    pub fn new(config: StateKeeperConfig) -> Self {
-        let sealers = Self::default_sealers(&config);
-        Self { config, sealers }
+    /// Creates a sealer with the built-in seal criteria that are not disabled in the provided `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `config` is invalid, e.g. disables unknown seal criteria.
+    pub fn new(config: StateKeeperConfig) -> anyhow::Result<Self> {
+        let registry = SealCriteriaRegistry::new(&config);
+        Self::with_registry(config, registry)
+    }
+
+    /// Creates a sealer with criteria from the provided registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `config` is invalid, e.g. disables unknown seal criteria.
+    pub fn with_registry(
+        config: StateKeeperConfig,
+        registry: SealCriteriaRegistry,
+    ) -> anyhow::Result<Self> {
+        let sealers = registry.into_criteria()?;
+        Ok(Self { config, sealers })
     }
 
     #[cfg(test)]
@@ -127,19 +155,6 @@ impl SequencerSealer {
     ) -> Self {
         Self { config, sealers }
     }
-
-    fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
-        vec![
-            Box::new(criteria::SlotsCriterion),
-            Box::new(criteria::GasCriterion),
-            Box::new(criteria::PubDataBytesCriterion {
-                max_pubdata_per_batch: config.max_pubdata_per_batch,
-            }),
-            Box::new(criteria::CircuitsCriterion),
-            Box::new(criteria::TxEncodingSizeCriterion),
-            Box::new(criteria::GasForBatchTipCriterion),
-        ]
-    }
 }
 
 /// Implementation of [`ConditionalSealer`] that never seals the batch.
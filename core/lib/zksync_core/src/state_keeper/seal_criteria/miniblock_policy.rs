@@ -0,0 +1,251 @@
+//! Policies deciding when to seal miniblocks.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::MiniblockNumber;
+
+use super::{IoSealCriteria, TimeoutSealer};
+use crate::state_keeper::{metrics::MINIBLOCK_METRICS, updates::UpdatesManager};
+
+/// Policy deciding when to seal miniblocks used by [`MempoolIO`](crate::state_keeper::MempoolIO).
+///
+/// Policies are only consulted between executing transactions; regardless of the policy, the state keeper never seals
+/// empty miniblocks.
+pub trait MiniblockSealPolicy: fmt::Debug + Send + 'static {
+    /// Checks whether the current miniblock should be sealed.
+    fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool;
+}
+
+impl MiniblockSealPolicy for TimeoutSealer {
+    fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
+        IoSealCriteria::should_seal_miniblock(self, manager)
+    }
+}
+
+/// Creates the miniblock sealing policy based on the provided config: [`AdaptiveMiniblockSealPolicy`] if
+/// the adaptive target block time is configured, and a fixed timeout otherwise.
+pub(in crate::state_keeper) fn miniblock_seal_policy(
+    config: &StateKeeperConfig,
+) -> Box<dyn MiniblockSealPolicy> {
+    if let Some(target_ms) = config.miniblock_adaptive_target_ms {
+        let max_deadline = Duration::from_millis(config.miniblock_commit_deadline_ms);
+        let policy = AdaptiveMiniblockSealPolicy::new(
+            Duration::from_millis(target_ms),
+            config.miniblock_adaptive_target_percentile(),
+            max_deadline,
+        )
+        .with_max_tx_count(config.miniblock_adaptive_max_tx_count);
+        tracing::info!("Using adaptive miniblock sealing policy: {policy:?}");
+        Box::new(policy)
+    } else {
+        Box::new(TimeoutSealer::new(config))
+    }
+}
+
+/// Adaptive miniblock sealing policy.
+///
+/// The policy seals a miniblock once its age exceeds the current deadline. After each sealed miniblock, the deadline
+/// is adjusted so that the configured percentile of recent miniblock times (which may exceed the deadline under load,
+/// since the policy is only consulted between transactions) tracks the target block time. The deadline never exceeds
+/// the configured maximum.
+///
+/// Additionally, a miniblock is sealed immediately once it reaches the configured number of transactions.
+#[derive(Debug)]
+pub struct AdaptiveMiniblockSealPolicy {
+    target: Duration,
+    percentile: f64,
+    max_deadline: Duration,
+    max_tx_count: Option<usize>,
+    deadline: Duration,
+    /// Times of recently sealed miniblocks, from the oldest to the newest.
+    recent_block_times: VecDeque<Duration>,
+    /// Number and the opening time of the current miniblock, as observed by the policy.
+    current_miniblock: Option<(MiniblockNumber, Instant)>,
+}
+
+impl AdaptiveMiniblockSealPolicy {
+    /// Number of recent miniblocks used to compute the block time percentile.
+    const WINDOW_SIZE: usize = 50;
+    /// Minimum deadline to prevent sealing a miniblock after each transaction.
+    const MIN_DEADLINE: Duration = Duration::from_millis(10);
+
+    /// Creates a policy targeting the specified `percentile` of miniblock times to be equal to `target`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percentile` is not in `(0, 1]`.
+    pub fn new(target: Duration, percentile: f64, max_deadline: Duration) -> Self {
+        assert!(
+            percentile > 0.0 && percentile <= 1.0,
+            "Target percentile must be in (0, 1]"
+        );
+        let deadline = target.clamp(Self::MIN_DEADLINE, max_deadline.max(Self::MIN_DEADLINE));
+        Self {
+            target,
+            percentile,
+            max_deadline,
+            max_tx_count: None,
+            deadline,
+            recent_block_times: VecDeque::with_capacity(Self::WINDOW_SIZE),
+            current_miniblock: None,
+        }
+    }
+
+    /// Sets the number of transactions after which a miniblock is sealed immediately.
+    #[must_use]
+    pub fn with_max_tx_count(mut self, max_tx_count: Option<usize>) -> Self {
+        self.max_tx_count = max_tx_count;
+        self
+    }
+
+    /// Returns the current sealing deadline.
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+
+    fn should_seal_miniblock_at(&mut self, manager: &UpdatesManager, now: Instant) -> bool {
+        let age = self.miniblock_age(manager, now);
+        let tx_count = manager.miniblock.executed_transactions.len();
+        if tx_count == 0 {
+            return false;
+        }
+
+        let should_seal = self
+            .max_tx_count
+            .map_or(false, |max_tx_count| tx_count >= max_tx_count)
+            || age > self.deadline;
+        if should_seal {
+            self.record_block_time(age);
+            self.current_miniblock = None;
+        }
+        should_seal
+    }
+
+    fn miniblock_age(&mut self, manager: &UpdatesManager, now: Instant) -> Duration {
+        let miniblock_number = manager.miniblock.number;
+        match self.current_miniblock {
+            Some((number, opened_at)) if number == miniblock_number => {
+                now.saturating_duration_since(opened_at)
+            }
+            _ => {
+                self.current_miniblock = Some((miniblock_number, now));
+                Duration::ZERO
+            }
+        }
+    }
+
+    fn record_block_time(&mut self, block_time: Duration) {
+        if self.recent_block_times.len() == Self::WINDOW_SIZE {
+            self.recent_block_times.pop_front();
+        }
+        self.recent_block_times.push_back(block_time);
+
+        let mut block_times: Vec<_> = self.recent_block_times.iter().copied().collect();
+        block_times.sort_unstable();
+        let idx = ((block_times.len() as f64 * self.percentile).ceil() as usize).max(1) - 1;
+        let observed = block_times[idx];
+        // Shift the deadline by the difference between the target and the observed block time. This compensates
+        // for the overshoot of block times compared to the deadline.
+        let deadline = if observed > self.target {
+            self.deadline.saturating_sub((observed - self.target) / 2)
+        } else {
+            self.deadline + (self.target - observed) / 2
+        };
+        self.deadline = deadline.clamp(
+            Self::MIN_DEADLINE,
+            self.max_deadline.max(Self::MIN_DEADLINE),
+        );
+        MINIBLOCK_METRICS.adaptive_seal_deadline.set(self.deadline);
+    }
+}
+
+impl MiniblockSealPolicy for AdaptiveMiniblockSealPolicy {
+    fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
+        self.should_seal_miniblock_at(manager, Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::BlockGasCount, tx::ExecutionMetrics};
+
+    use super::*;
+    use crate::state_keeper::tests::{
+        create_execution_result, create_transaction, create_updates_manager,
+    };
+
+    fn apply_tx_to_manager(manager: &mut UpdatesManager) {
+        let tx = create_transaction(10, 100);
+        manager.extend_from_executed_transaction(
+            tx,
+            create_execution_result(0, []),
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![],
+            Duration::ZERO,
+        );
+    }
+
+    #[test]
+    fn adaptive_policy_seals_by_deadline() {
+        let mut policy = AdaptiveMiniblockSealPolicy::new(
+            Duration::from_millis(100),
+            0.9,
+            Duration::from_secs(1),
+        );
+        let mut manager = create_updates_manager();
+        let start = Instant::now();
+        assert!(!policy.should_seal_miniblock_at(&manager, start));
+        let now = start + Duration::from_millis(200);
+        // Empty miniblocks are never sealed.
+        assert!(!policy.should_seal_miniblock_at(&manager, now));
+
+        apply_tx_to_manager(&mut manager);
+        assert!(policy.should_seal_miniblock_at(&manager, now));
+        // Block time is greater than the target, so the deadline should be decreased.
+        assert_eq!(policy.deadline(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn adaptive_policy_seals_by_tx_count() {
+        let mut policy =
+            AdaptiveMiniblockSealPolicy::new(Duration::from_secs(1), 0.9, Duration::from_secs(1))
+                .with_max_tx_count(Some(2));
+        let mut manager = create_updates_manager();
+        let now = Instant::now();
+        apply_tx_to_manager(&mut manager);
+        assert!(!policy.should_seal_miniblock_at(&manager, now));
+        apply_tx_to_manager(&mut manager);
+        assert!(policy.should_seal_miniblock_at(&manager, now));
+    }
+
+    #[test]
+    fn adaptive_policy_deadline_is_bounded() {
+        let max_deadline = Duration::from_millis(500);
+        let mut policy =
+            AdaptiveMiniblockSealPolicy::new(Duration::from_millis(400), 0.5, max_deadline)
+                .with_max_tx_count(Some(1));
+        let mut manager = create_updates_manager();
+        apply_tx_to_manager(&mut manager);
+        let mut now = Instant::now();
+        for _ in 0..10 {
+            // Miniblocks are sealed immediately by the tx count, so the deadline should grow.
+            assert!(policy.should_seal_miniblock_at(&manager, now));
+        }
+        assert_eq!(policy.deadline(), max_deadline);
+
+        policy.max_tx_count = None;
+        for _ in 0..100 {
+            assert!(!policy.should_seal_miniblock_at(&manager, now));
+            now += Duration::from_secs(2);
+            assert!(policy.should_seal_miniblock_at(&manager, now));
+        }
+        assert_eq!(policy.deadline(), AdaptiveMiniblockSealPolicy::MIN_DEADLINE);
+    }
+}
@@ -24,8 +24,15 @@ use zksync_utils::time::millis_since;
 
 mod conditional_sealer;
 pub(super) mod criteria;
+mod miniblock_policy;
+mod registry;
 
-pub use self::conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer};
+pub(super) use self::miniblock_policy::miniblock_seal_policy;
+pub use self::{
+    conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer},
+    miniblock_policy::{AdaptiveMiniblockSealPolicy, MiniblockSealPolicy},
+    registry::SealCriteriaRegistry,
+};
 use super::{extractors, metrics::AGGREGATION_METRICS, updates::UpdatesManager};
 use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
 
@@ -94,8 +101,9 @@ impl SealData {
     ) -> Self {
         let execution_metrics = ExecutionMetrics::from_tx_metrics(tx_metrics);
         let writes_metrics = DeduplicatedWritesMetrics::from_tx_metrics(tx_metrics);
-        let gas_count = gas_count_from_tx_and_metrics(&transaction, &execution_metrics)
-            + gas_count_from_writes(&writes_metrics, protocol_version);
+        let gas_count =
+            gas_count_from_tx_and_metrics(&transaction, &execution_metrics, protocol_version)
+                + gas_count_from_writes(&writes_metrics, protocol_version);
         Self {
             execution_metrics,
             gas_count,
@@ -104,9 +112,39 @@ impl SealData {
             gas_remaining: tx_metrics.gas_remaining,
         }
     }
+
+    /// Returns VM execution metrics.
+    pub fn execution_metrics(&self) -> &ExecutionMetrics {
+        &self.execution_metrics
+    }
+
+    /// Returns L1 gas consumed by commit, prove and execute operations.
+    pub fn gas_count(&self) -> BlockGasCount {
+        self.gas_count
+    }
+
+    /// Returns the size of transactions in the bootloader encoding.
+    pub fn cumulative_size(&self) -> usize {
+        self.cumulative_size
+    }
+
+    /// Returns metrics for deduplicated storage writes.
+    pub fn writes_metrics(&self) -> &DeduplicatedWritesMetrics {
+        &self.writes_metrics
+    }
+
+    /// Returns the amount of gas remaining in the batch after execution.
+    pub fn gas_remaining(&self) -> u32 {
+        self.gas_remaining
+    }
 }
 
-pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
+/// Name of the I/O-dependent seal criterion sealing L1 batches after a timeout.
+const NO_TXS_TIMEOUT_CRITERION: &str = "no_txs_timeout";
+
+/// Deterministic criterion determining whether an L1 batch should be sealed after executing a transaction.
+/// Criteria are managed by [`SealCriteriaRegistry`].
+pub trait SealCriterion: fmt::Debug + Send + Sync + 'static {
     fn should_seal(
         &self,
         config: &StateKeeperConfig,
@@ -117,6 +155,7 @@ pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Returns the name of this criterion used in metrics and [`StateKeeperConfig`]. Must be unique.
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -129,18 +168,35 @@ pub trait IoSealCriteria {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool;
     /// Checks whether a miniblock should be sealed given the provided `manager` state.
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool;
+
+    /// Checks whether the current miniblock should be sealed before executing `tx` in it, e.g. because the transaction
+    /// doesn't fit into the miniblock. In this case, the transaction is rolled back and will be executed in the next miniblock.
+    /// Unlike other sealing checks, this check is performed before executing the transaction.
+    fn should_seal_miniblock_before_tx(
+        &mut self,
+        _manager: &UpdatesManager,
+        _tx: &Transaction,
+    ) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub(super) struct TimeoutSealer {
-    block_commit_deadline_ms: u64,
+    /// `None` if the L1 batch timeout criterion is disabled.
+    block_commit_deadline_ms: Option<u64>,
     miniblock_commit_deadline_ms: u64,
 }
 
 impl TimeoutSealer {
     pub fn new(config: &StateKeeperConfig) -> Self {
+        let is_l1_batch_timeout_disabled = config
+            .disabled_seal_criteria
+            .iter()
+            .any(|name| name == NO_TXS_TIMEOUT_CRITERION);
         Self {
-            block_commit_deadline_ms: config.block_commit_deadline_ms,
+            block_commit_deadline_ms: (!is_l1_batch_timeout_disabled)
+                .then_some(config.block_commit_deadline_ms),
             miniblock_commit_deadline_ms: config.miniblock_commit_deadline_ms,
         }
     }
@@ -148,20 +204,23 @@ impl TimeoutSealer {
 
 impl IoSealCriteria for TimeoutSealer {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
-        const RULE_NAME: &str = "no_txs_timeout";
+        const RULE_NAME: &str = NO_TXS_TIMEOUT_CRITERION;
 
         if manager.pending_executed_transactions_len() == 0 {
             // Regardless of which sealers are provided, we never want to seal an empty batch.
             return false;
         }
 
-        let block_commit_deadline_ms = self.block_commit_deadline_ms;
+        let Some(block_commit_deadline_ms) = self.block_commit_deadline_ms else {
+            return false;
+        };
         // Verify timestamp
         let should_seal_timeout =
             millis_since(manager.batch_timestamp()) > block_commit_deadline_ms;
 
         if should_seal_timeout {
             AGGREGATION_METRICS.inc_criterion(RULE_NAME);
+            AGGREGATION_METRICS.l1_batch_sealed_by[&RULE_NAME].inc();
             tracing::debug!(
                 "Decided to seal L1 batch using rule `{RULE_NAME}`; batch timestamp: {}, \
                  commit deadline: {block_commit_deadline_ms}ms",
@@ -179,6 +238,8 @@ impl IoSealCriteria for TimeoutSealer {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use zksync_utils::time::seconds_since_epoch;
 
     use super::*;
@@ -195,6 +256,7 @@ mod tests {
             BlockGasCount::default(),
             ExecutionMetrics::default(),
             vec![],
+            Duration::ZERO,
         );
     }
 
@@ -202,7 +264,7 @@ mod tests {
     #[test]
     fn timeout_miniblock_sealer() {
         let mut timeout_miniblock_sealer = TimeoutSealer {
-            block_commit_deadline_ms: 10_000,
+            block_commit_deadline_ms: Some(10_000),
             miniblock_commit_deadline_ms: 10_000,
         };
 
@@ -230,4 +292,17 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[test]
+    fn disabling_timeout_l1_batch_sealer() {
+        let mut config = StateKeeperConfig::for_tests();
+        let mut manager = create_updates_manager();
+        apply_tx_to_manager(&mut manager);
+        // The L1 batch timestamp in `manager` is far in the past.
+        assert!(TimeoutSealer::new(&config).should_seal_l1_batch_unconditionally(&manager));
+
+        config.disabled_seal_criteria = vec![NO_TXS_TIMEOUT_CRITERION.to_owned()];
+        let mut timeout_sealer = TimeoutSealer::new(&config);
+        assert!(!timeout_sealer.should_seal_l1_batch_unconditionally(&manager));
+    }
 }
@@ -0,0 +1,192 @@
+//! Registry of seal criteria used by [`SequencerSealer`](super::SequencerSealer).
+
+use std::collections::HashSet;
+
+use zksync_config::configs::chain::StateKeeperConfig;
+
+use super::{criteria, SealCriterion, NO_TXS_TIMEOUT_CRITERION};
+
+/// Registry of [`SealCriterion`]s used to construct a [`SequencerSealer`](super::SequencerSealer).
+///
+/// The registry is initialized with the built-in criteria and allows registering custom ones. Criteria disabled
+/// in [`StateKeeperConfig`] (matched by [`SealCriterion::prom_criterion_name()`]) are skipped on registration,
+/// both for built-in and custom criteria.
+#[derive(Debug)]
+pub struct SealCriteriaRegistry {
+    disabled: HashSet<String>,
+    /// Names of all criteria that were attempted to be registered, including disabled ones.
+    known_names: HashSet<&'static str>,
+    criteria: Vec<Box<dyn SealCriterion>>,
+}
+
+impl SealCriteriaRegistry {
+    /// Creates a registry with the built-in criteria that are not disabled in the provided `config`.
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        let mut this = Self {
+            disabled: config.disabled_seal_criteria.iter().cloned().collect(),
+            known_names: HashSet::from([NO_TXS_TIMEOUT_CRITERION]),
+            criteria: vec![],
+        };
+        let builtin_criteria: [Box<dyn SealCriterion>; 6] = [
+            Box::new(criteria::SlotsCriterion),
+            Box::new(criteria::GasCriterion),
+            Box::new(criteria::PubDataBytesCriterion {
+                max_pubdata_per_batch: config.max_pubdata_per_batch,
+            }),
+            Box::new(criteria::CircuitsCriterion),
+            Box::new(criteria::TxEncodingSizeCriterion),
+            Box::new(criteria::GasForBatchTipCriterion),
+        ];
+        for criterion in builtin_criteria {
+            this.register_boxed(criterion);
+        }
+        this
+    }
+
+    /// Registers a custom seal criterion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a criterion with the same name is already registered.
+    pub fn register(&mut self, criterion: impl SealCriterion) -> &mut Self {
+        self.register_boxed(Box::new(criterion));
+        self
+    }
+
+    fn register_boxed(&mut self, criterion: Box<dyn SealCriterion>) {
+        let name = criterion.prom_criterion_name();
+        assert!(
+            self.known_names.insert(name),
+            "Seal criterion `{name}` is already registered"
+        );
+        if self.disabled.contains(name) {
+            tracing::warn!("Seal criterion `{name}` is disabled in state keeper config");
+        } else {
+            self.criteria.push(criterion);
+        }
+    }
+
+    /// Returns names of the enabled criteria in the order of their registration.
+    pub fn criterion_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.criteria
+            .iter()
+            .map(|criterion| criterion.prom_criterion_name())
+    }
+
+    /// Returns the enabled criteria.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config disables a criterion that is not known to the registry, which is likely a typo.
+    pub(super) fn into_criteria(self) -> anyhow::Result<Vec<Box<dyn SealCriterion>>> {
+        let mut unknown_names: Vec<_> = self
+            .disabled
+            .iter()
+            .filter(|name| !self.known_names.contains(name.as_str()))
+            .collect();
+        unknown_names.sort_unstable();
+        anyhow::ensure!(
+            unknown_names.is_empty(),
+            "Unknown seal criteria are disabled in state keeper config: {unknown_names:?}; known criteria: {:?}",
+            self.known_names
+        );
+        Ok(self.criteria)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::ProtocolVersionId;
+
+    use super::*;
+    use crate::state_keeper::seal_criteria::{SealData, SealResolution};
+
+    #[derive(Debug)]
+    struct CustomCriterion;
+
+    impl SealCriterion for CustomCriterion {
+        fn should_seal(
+            &self,
+            _config: &StateKeeperConfig,
+            _block_open_timestamp_ms: u128,
+            tx_count: usize,
+            _block_data: &SealData,
+            _tx_data: &SealData,
+            _protocol_version: ProtocolVersionId,
+        ) -> SealResolution {
+            if tx_count >= 3 {
+                SealResolution::IncludeAndSeal
+            } else {
+                SealResolution::NoSeal
+            }
+        }
+
+        fn prom_criterion_name(&self) -> &'static str {
+            "custom"
+        }
+    }
+
+    #[test]
+    fn default_registry() {
+        let config = StateKeeperConfig::for_tests();
+        let registry = SealCriteriaRegistry::new(&config);
+        let names: Vec<_> = registry.criterion_names().collect();
+        assert_eq!(
+            names,
+            [
+                "slots",
+                "gas",
+                "pub_data_size",
+                "circuits",
+                "tx_encoding_size",
+                "gas_for_batch_tip"
+            ]
+        );
+        assert_eq!(registry.into_criteria().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn disabling_criteria() {
+        let mut config = StateKeeperConfig::for_tests();
+        config.disabled_seal_criteria = vec![
+            "pub_data_size".to_owned(),
+            "no_txs_timeout".to_owned(),
+            "custom".to_owned(),
+        ];
+        let mut registry = SealCriteriaRegistry::new(&config);
+        registry.register(CustomCriterion);
+        let names: Vec<_> = registry.criterion_names().collect();
+        assert!(!names.contains(&"pub_data_size"), "{names:?}");
+        assert!(!names.contains(&"custom"), "{names:?}");
+        assert_eq!(registry.into_criteria().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn registering_custom_criterion() {
+        let config = StateKeeperConfig::for_tests();
+        let mut registry = SealCriteriaRegistry::new(&config);
+        registry.register(CustomCriterion);
+        assert_eq!(registry.criterion_names().last(), Some("custom"));
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn registering_duplicate_criterion() {
+        let config = StateKeeperConfig::for_tests();
+        let mut registry = SealCriteriaRegistry::new(&config);
+        registry.register(CustomCriterion).register(CustomCriterion);
+    }
+
+    #[test]
+    fn disabling_unknown_criterion() {
+        let mut config = StateKeeperConfig::for_tests();
+        config.disabled_seal_criteria = vec!["pubdata".to_owned()];
+        let err = SealCriteriaRegistry::new(&config)
+            .into_criteria()
+            .map(drop)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown seal criteria"), "{err}");
+        assert!(err.contains("pubdata"), "{err}");
+    }
+}
@@ -4,13 +4,17 @@ use anyhow::Context;
 use async_trait::async_trait;
 use once_cell::sync::OnceCell;
 use tokio::{runtime::Handle, sync::watch};
+use zksync_config::DBConfig;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_state::{
-    PostgresStorage, ReadStorage, RocksdbStorage, RocksdbStorageBuilder, StateKeeperColumnFamily,
+    PostgresStorage, ReadStorage, RocksdbStorage, RocksdbStorageBuilder, RocksdbStorageCaches,
+    StateKeeperColumnFamily,
 };
-use zksync_storage::RocksDB;
+use zksync_storage::{RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, MiniblockNumber};
 
+use crate::utils::rocksdb_column_families;
+
 /// Factory that can produce a [`ReadStorage`] implementation on demand.
 #[async_trait]
 pub trait ReadStorageFactory: Debug + Send + Sync + 'static {
@@ -78,7 +82,14 @@ impl<'a> From<RocksdbStorage> for PgOrRocksdbStorage<'a> {
 #[derive(Debug, Clone)]
 pub struct AsyncRocksdbCache {
     pool: ConnectionPool<Core>,
-    rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    rocksdb_cell: Arc<OnceCell<CaughtUpRocksdb>>,
+}
+
+/// RocksDB instance caught up by [`AsyncCatchupTask`] together with in-memory caches shared by storages created from it.
+#[derive(Debug, Clone)]
+struct CaughtUpRocksdb {
+    db: RocksDB<StateKeeperColumnFamily>,
+    caches: Option<RocksdbStorageCaches>,
 }
 
 impl AsyncRocksdbCache {
@@ -139,11 +150,14 @@ impl AsyncRocksdbCache {
     /// returns a [`ReadStorage`] implementation backed by caught-up RocksDB.
     async fn access_storage_rocksdb<'a>(
         connection: &mut Connection<'_, Core>,
-        rocksdb: RocksDB<StateKeeperColumnFamily>,
+        rocksdb: CaughtUpRocksdb,
         stop_receiver: &watch::Receiver<bool>,
     ) -> anyhow::Result<Option<PgOrRocksdbStorage<'a>>> {
         tracing::debug!("Catching up RocksDB synchronously");
-        let rocksdb_builder = RocksdbStorageBuilder::from_rocksdb(rocksdb);
+        let mut rocksdb_builder = RocksdbStorageBuilder::from_rocksdb(rocksdb.db);
+        if let Some(caches) = rocksdb.caches {
+            rocksdb_builder.enable_caches(caches);
+        }
         let rocksdb = rocksdb_builder
             .synchronize(connection, stop_receiver)
             .await
@@ -182,19 +196,45 @@ impl AsyncRocksdbCache {
     pub fn new(
         pool: ConnectionPool<Core>,
         state_keeper_db_path: String,
+        state_keeper_db_options: RocksDBOptions,
         enum_index_migration_chunk_size: usize,
     ) -> (Self, AsyncCatchupTask) {
         let rocksdb_cell = Arc::new(OnceCell::new());
         let task = AsyncCatchupTask {
             pool: pool.clone(),
             state_keeper_db_path,
+            state_keeper_db_options,
             enum_index_migration_chunk_size,
+            caches: None,
+            cache_warm_up_l1_batches: 0,
             rocksdb_cell: rocksdb_cell.clone(),
         };
         (Self { pool, rocksdb_cell }, task)
     }
 }
 
+/// Returns options for the state keeper RocksDB cache based on the node config.
+pub fn state_keeper_rocksdb_options(db_config: &DBConfig) -> RocksDBOptions {
+    RocksDBOptions {
+        block_cache_capacity: db_config.state_keeper_db_block_cache_size(),
+        column_families: rocksdb_column_families(&db_config.state_keeper_db_column_families),
+        ..RocksDBOptions::default()
+    }
+}
+
+/// Returns in-memory caches for the state keeper RocksDB based on the node config, or `None` if caches are disabled.
+pub fn state_keeper_rocksdb_caches(db_config: &DBConfig) -> Option<RocksdbStorageCaches> {
+    let values_capacity = db_config.state_keeper_db_values_cache_size();
+    let factory_deps_capacity = db_config.state_keeper_db_factory_deps_cache_size();
+    if values_capacity == 0 && factory_deps_capacity == 0 {
+        return None;
+    }
+    Some(RocksdbStorageCaches::new(
+        values_capacity,
+        factory_deps_capacity,
+    ))
+}
+
 #[async_trait]
 impl ReadStorageFactory for AsyncRocksdbCache {
     async fn access_storage(
@@ -209,18 +249,39 @@ impl ReadStorageFactory for AsyncRocksdbCache {
 pub struct AsyncCatchupTask {
     pool: ConnectionPool<Core>,
     state_keeper_db_path: String,
+    state_keeper_db_options: RocksDBOptions,
     enum_index_migration_chunk_size: usize,
-    rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    caches: Option<RocksdbStorageCaches>,
+    cache_warm_up_l1_batches: u32,
+    rocksdb_cell: Arc<OnceCell<CaughtUpRocksdb>>,
 }
 
 impl AsyncCatchupTask {
+    /// Enables in-memory caches for the RocksDB storage. Once RocksDB is caught up, the values cache is warmed up
+    /// with storage slots touched in the specified number of latest L1 batches.
+    #[must_use]
+    pub fn with_caches(
+        mut self,
+        caches: Option<RocksdbStorageCaches>,
+        warm_up_l1_batches: u32,
+    ) -> Self {
+        self.caches = caches;
+        self.cache_warm_up_l1_batches = warm_up_l1_batches;
+        self
+    }
+
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         tracing::debug!("Catching up RocksDB asynchronously");
-        let mut rocksdb_builder: RocksdbStorageBuilder =
-            RocksdbStorage::builder(self.state_keeper_db_path.as_ref())
-                .await
-                .context("Failed initializing RocksDB storage")?;
+        let mut rocksdb_builder: RocksdbStorageBuilder = RocksdbStorage::builder_with_options(
+            self.state_keeper_db_path.as_ref(),
+            self.state_keeper_db_options,
+        )
+        .await
+        .context("Failed initializing RocksDB storage")?;
         rocksdb_builder.enable_enum_index_migration(self.enum_index_migration_chunk_size);
+        if let Some(caches) = &self.caches {
+            rocksdb_builder.enable_caches(caches.clone());
+        }
         let mut connection = self
             .pool
             .connection()
@@ -230,10 +291,20 @@ impl AsyncCatchupTask {
             .synchronize(&mut connection, &stop_receiver)
             .await
             .context("Failed to catch up RocksDB to Postgres")?;
+        if let Some(rocksdb) = &rocksdb {
+            rocksdb
+                .warm_up_caches(&mut connection, self.cache_warm_up_l1_batches)
+                .await
+                .context("Failed warming up RocksDB caches")?;
+        }
         drop(connection);
         if let Some(rocksdb) = rocksdb {
+            let rocksdb = CaughtUpRocksdb {
+                db: rocksdb.into_rocksdb(),
+                caches: self.caches,
+            };
             self.rocksdb_cell
-                .set(rocksdb.into_rocksdb())
+                .set(rocksdb)
                 .map_err(|_| anyhow::anyhow!("Async RocksDB cache was initialized twice"))?;
         } else {
             tracing::info!("Synchronizing RocksDB interrupted");
@@ -46,7 +46,7 @@ use crate::{
         },
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
-        ZkSyncStateKeeper,
+        SoftShutdownStatus, StateKeeperControl, ZkSyncStateKeeper,
     },
     utils::testonly::create_l2_transaction,
 };
@@ -651,3 +651,34 @@ async fn protocol_upgrade() {
         .run(sealer)
         .await;
 }
+
+/// Soft shutdown seals the current L1 batch and completes after that.
+#[tokio::test]
+async fn soft_shutdown_seals_l1_batch() {
+    let control = StateKeeperControl::default();
+    let control_in_sealer = control.clone();
+
+    TestScenario::new()
+        .with_control(control.clone())
+        .seal_l1_batch_when(move |updates| {
+            if updates.pending_executed_transactions_len() == 2 {
+                control_in_sealer.request_soft_shutdown();
+            }
+            false
+        })
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed_with("Miniblock is sealed on soft shutdown", |updates| {
+            assert_eq!(updates.miniblock.executed_transactions.len(), 2);
+        })
+        .batch_sealed_with("Batch is sealed on soft shutdown", |updates| {
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 2);
+        })
+        .run(SequencerSealer::default())
+        .await;
+
+    assert_eq!(
+        control.soft_shutdown_status(),
+        SoftShutdownStatus::Completed
+    );
+}
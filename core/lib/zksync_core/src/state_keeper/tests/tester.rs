@@ -28,7 +28,7 @@ use crate::{
         tests::{default_l1_batch_env, default_vm_batch_result, BASE_SYSTEM_CONTRACTS},
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
-        OutputHandler, StateKeeperOutputHandler, ZkSyncStateKeeper,
+        OutputHandler, StateKeeperControl, StateKeeperOutputHandler, ZkSyncStateKeeper,
     },
     utils::testonly::create_l2_transaction,
 };
@@ -52,6 +52,7 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     miniblock_seal_fn: Box<SealFn>,
+    control: StateKeeperControl,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send;
@@ -73,6 +74,7 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             miniblock_seal_fn: Box::new(|_| false),
+            control: StateKeeperControl::default(),
         }
     }
 
@@ -188,12 +190,19 @@ impl TestScenario {
         self
     }
 
+    /// Sets the control handle passed to the state keeper.
+    pub(crate) fn with_control(mut self, control: StateKeeperControl) -> Self {
+        self.control = control;
+        self
+    }
+
     /// Launches the test.
     /// Provided `SealManager` is expected to be externally configured to adhere the written scenario logic.
     pub(crate) async fn run(self, sealer: SequencerSealer) {
         assert!(!self.actions.is_empty(), "Test scenario can't be empty");
 
         let batch_executor_base = TestBatchExecutorBuilder::new(&self);
+        let control = self.control.clone();
         let (stop_sender, stop_receiver) = watch::channel(false);
         let (io, output_handler) = TestIO::new(stop_sender, self);
         let state_keeper = ZkSyncStateKeeper::new(
@@ -202,7 +211,8 @@ impl TestScenario {
             Box::new(batch_executor_base),
             output_handler,
             Arc::new(sealer),
-        );
+        )
+        .with_control(control);
         let sk_thread = tokio::spawn(state_keeper.run());
 
         // We must assume that *theoretically* state keeper may ignore the stop signal from IO once scenario is
@@ -262,6 +272,7 @@ pub(crate) fn successful_exec() -> TxExecutionResult {
         compressed_bytecodes: vec![],
         call_tracer_result: vec![],
         gas_remaining: Default::default(),
+        execution_time: Duration::ZERO,
     }
 }
 
@@ -280,6 +291,7 @@ pub(crate) fn successful_exec_with_metrics(
         compressed_bytecodes: vec![],
         call_tracer_result: vec![],
         gas_remaining: Default::default(),
+        execution_time: Duration::ZERO,
     }
 }
 
@@ -507,7 +519,7 @@ impl TestBatchExecutor {
                     resp.send(result).unwrap();
                     self.last_tx = tx.hash();
                 }
-                Command::StartNextMiniblock(_, resp) => {
+                Command::StartNextMiniblock(_, resp) | Command::SpeculateTxs(_, resp) => {
                     resp.send(()).unwrap();
                 }
                 Command::RollbackLastTx(resp) => {
@@ -853,7 +865,9 @@ impl BatchExecutor for MockBatchExecutor {
             while let Some(cmd) = recv.recv().await {
                 match cmd {
                     Command::ExecuteTx(_, resp) => resp.send(successful_exec()).unwrap(),
-                    Command::StartNextMiniblock(_, resp) => resp.send(()).unwrap(),
+                    Command::StartNextMiniblock(_, resp) | Command::SpeculateTxs(_, resp) => {
+                        resp.send(()).unwrap()
+                    }
                     Command::RollbackLastTx(_) => panic!("unexpected rollback"),
                     Command::FinishBatch(resp) => {
                         // Blanket result, it doesn't really matter.
@@ -0,0 +1,205 @@
+//! Filtering of transactions before they are included into batches.
+
+use std::{collections::HashSet, fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::{Address, Transaction, CONTRACT_DEPLOYER_ADDRESS};
+
+/// Filter invoked by [`MempoolIO`](super::MempoolIO) for each L2 transaction before it is included into a batch.
+/// Transactions that don't pass the filter are rejected.
+///
+/// L1 transactions cannot be rejected, so they are never checked by the filter.
+pub trait TransactionFilter: fmt::Debug + Send + Sync + 'static {
+    /// Checks whether the transaction can be included into a batch. Returns the rejection reason otherwise.
+    fn check_transaction(&self, tx: &Transaction) -> Result<(), String>;
+}
+
+/// Declarative transaction filter policy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TxFilterPolicy {
+    /// Addresses that cannot initiate or be the recipient of transactions.
+    pub denied_addresses: HashSet<Address>,
+    /// If not empty, only transactions initiated by these accounts pass the filter.
+    pub allowed_initiators: HashSet<Address>,
+    /// If not empty, only these accounts can deploy contracts.
+    pub allowed_deployers: HashSet<Address>,
+    /// Maximum transaction calldata size in bytes.
+    pub max_calldata_size: Option<usize>,
+}
+
+impl TxFilterPolicy {
+    /// Creates a policy based on the state keeper config.
+    pub fn from_config(config: &StateKeeperConfig) -> Self {
+        Self {
+            denied_addresses: config.tx_filter_denied_addresses.iter().copied().collect(),
+            allowed_initiators: config
+                .tx_filter_allowed_initiators
+                .iter()
+                .copied()
+                .collect(),
+            allowed_deployers: config.tx_filter_allowed_deployers.iter().copied().collect(),
+            max_calldata_size: config.tx_filter_max_calldata_size,
+        }
+    }
+
+    /// Checks whether the policy doesn't restrict any transactions.
+    pub fn is_permissive(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn check(&self, tx: &Transaction) -> Result<(), String> {
+        let initiator = tx.initiator_account();
+        if self.denied_addresses.contains(&initiator) {
+            return Err(format!("Transaction initiator {initiator:?} is denied"));
+        }
+        let recipient = tx.recipient_account();
+        if self.denied_addresses.contains(&recipient) {
+            return Err(format!("Transaction recipient {recipient:?} is denied"));
+        }
+        if !self.allowed_initiators.is_empty() && !self.allowed_initiators.contains(&initiator) {
+            return Err(format!(
+                "Transaction initiator {initiator:?} is not allowed"
+            ));
+        }
+        if recipient == CONTRACT_DEPLOYER_ADDRESS
+            && !self.allowed_deployers.is_empty()
+            && !self.allowed_deployers.contains(&initiator)
+        {
+            return Err(format!(
+                "Account {initiator:?} is not allowed to deploy contracts"
+            ));
+        }
+        if let Some(max_calldata_size) = self.max_calldata_size {
+            let calldata_size = tx.execute.calldata.len();
+            if calldata_size > max_calldata_size {
+                return Err(format!(
+                    "Transaction calldata size {calldata_size}B exceeds the limit {max_calldata_size}B"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`TransactionFilter`] enforcing a [`TxFilterPolicy`] that can be replaced at runtime.
+///
+/// The filter is cheaply cloneable; all clones share the same policy.
+#[derive(Debug, Clone)]
+pub struct PolicyTxFilter {
+    policy: Arc<watch::Sender<TxFilterPolicy>>,
+}
+
+impl PolicyTxFilter {
+    pub fn new(policy: TxFilterPolicy) -> Self {
+        Self {
+            policy: Arc::new(watch::channel(policy).0),
+        }
+    }
+
+    /// Returns the currently enforced policy.
+    pub fn policy(&self) -> TxFilterPolicy {
+        self.policy.borrow().clone()
+    }
+
+    /// Replaces the enforced policy. The new policy applies to all transactions selected from the mempool afterwards;
+    /// already executed transactions are not affected.
+    pub fn set_policy(&self, policy: TxFilterPolicy) {
+        let old_policy = self.policy.send_replace(policy.clone());
+        tracing::info!("Updated transaction filter policy from {old_policy:?} to {policy:?}");
+    }
+}
+
+impl TransactionFilter for PolicyTxFilter {
+    fn check_transaction(&self, tx: &Transaction) -> Result<(), String> {
+        self.policy.borrow().check(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    fn transaction(initiator: Address, recipient: Address, calldata_size: usize) -> Transaction {
+        let mut tx = create_l2_transaction(10, 100);
+        tx.common_data.initiator_address = initiator;
+        tx.execute.contract_address = recipient;
+        tx.execute.calldata = vec![0; calldata_size];
+        tx.into()
+    }
+
+    #[test]
+    fn default_policy_is_permissive() {
+        let policy = TxFilterPolicy::default();
+        assert!(policy.is_permissive());
+        let tx = transaction(Address::repeat_byte(1), CONTRACT_DEPLOYER_ADDRESS, 1_000);
+        policy.check(&tx).unwrap();
+    }
+
+    #[test]
+    fn checking_denied_addresses() {
+        let policy = TxFilterPolicy {
+            denied_addresses: HashSet::from([Address::repeat_byte(1)]),
+            ..TxFilterPolicy::default()
+        };
+        let tx = transaction(Address::repeat_byte(1), Address::repeat_byte(2), 0);
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.contains("initiator"), "{err}");
+        let tx = transaction(Address::repeat_byte(2), Address::repeat_byte(1), 0);
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.contains("recipient"), "{err}");
+        let tx = transaction(Address::repeat_byte(2), Address::repeat_byte(3), 0);
+        policy.check(&tx).unwrap();
+    }
+
+    #[test]
+    fn checking_allowed_initiators_and_deployers() {
+        let policy = TxFilterPolicy {
+            allowed_initiators: HashSet::from([Address::repeat_byte(1), Address::repeat_byte(2)]),
+            allowed_deployers: HashSet::from([Address::repeat_byte(1)]),
+            ..TxFilterPolicy::default()
+        };
+        let tx = transaction(Address::repeat_byte(3), Address::repeat_byte(1), 0);
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.contains("not allowed"), "{err}");
+
+        let tx = transaction(Address::repeat_byte(1), CONTRACT_DEPLOYER_ADDRESS, 0);
+        policy.check(&tx).unwrap();
+        let tx = transaction(Address::repeat_byte(2), CONTRACT_DEPLOYER_ADDRESS, 0);
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.contains("deploy contracts"), "{err}");
+        let tx = transaction(Address::repeat_byte(2), Address::repeat_byte(3), 0);
+        policy.check(&tx).unwrap();
+    }
+
+    #[test]
+    fn checking_calldata_size() {
+        let policy = TxFilterPolicy {
+            max_calldata_size: Some(100),
+            ..TxFilterPolicy::default()
+        };
+        let tx = transaction(Address::repeat_byte(1), Address::repeat_byte(2), 100);
+        policy.check(&tx).unwrap();
+        let tx = transaction(Address::repeat_byte(1), Address::repeat_byte(2), 101);
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.contains("calldata size"), "{err}");
+    }
+
+    #[test]
+    fn updating_policy() {
+        let filter = PolicyTxFilter::new(TxFilterPolicy::default());
+        let tx = transaction(Address::repeat_byte(1), Address::repeat_byte(2), 0);
+        filter.check_transaction(&tx).unwrap();
+
+        let policy = TxFilterPolicy {
+            denied_addresses: HashSet::from([Address::repeat_byte(2)]),
+            ..TxFilterPolicy::default()
+        };
+        filter.clone().set_policy(policy.clone());
+        assert_eq!(filter.policy(), policy);
+        filter.check_transaction(&tx).unwrap_err();
+    }
+}
@@ -7,7 +7,8 @@ use multivm::interface::VmExecutionResultAndLogs;
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, ProtocolVersionId,
+    Transaction,
 };
 
 use super::metrics::StateKeeperGauges;
@@ -30,6 +31,19 @@ impl MempoolGuard {
         Self(Arc::new(Mutex::new(store)))
     }
 
+    /// Sets the maximum gap between the account nonce and the nonce of an L2 transaction in the mempool.
+    /// Transactions exceeding the gap are evicted from the mempool and removed from Postgres by [`MempoolFetcher`].
+    ///
+    /// [`MempoolFetcher`]: crate::state_keeper::MempoolFetcher
+    #[must_use]
+    pub fn with_max_nonce_gap(self, max_nonce_gap: Option<u32>) -> Self {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .set_max_nonce_gap(max_nonce_gap);
+        self
+    }
+
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
         self.0
             .lock()
@@ -65,7 +79,6 @@ impl MempoolGuard {
             .get_mempool_info()
     }
 
-    #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
         self.0
             .lock()
@@ -88,10 +101,11 @@ impl ExecutionMetricsForCriteria {
     pub fn new(
         tx: Option<&Transaction>,
         execution_result: &VmExecutionResultAndLogs,
+        protocol_version: ProtocolVersionId,
     ) -> ExecutionMetricsForCriteria {
         let execution_metrics = execution_result.get_execution_metrics(tx);
         let l1_gas = match tx {
-            Some(tx) => gas_count_from_tx_and_metrics(tx, &execution_metrics),
+            Some(tx) => gas_count_from_tx_and_metrics(tx, &execution_metrics, protocol_version),
             None => gas_count_from_metrics(&execution_metrics),
         };
 
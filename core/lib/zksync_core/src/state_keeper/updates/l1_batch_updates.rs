@@ -52,6 +52,8 @@ impl L1BatchUpdates {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use multivm::vm_latest::TransactionVmExt;
     use zksync_types::{MiniblockNumber, ProtocolVersionId, H256};
 
@@ -80,6 +82,7 @@ mod tests {
             ExecutionMetrics::default(),
             vec![],
             vec![],
+            Duration::ZERO,
         );
 
         let mut l1_batch_accumulator = L1BatchUpdates::new(L1BatchNumber(1));
@@ -1,9 +1,10 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use multivm::{
     interface::{ExecutionResult, L2BlockEnv, VmExecutionResultAndLogs},
     vm_latest::TransactionVmExt,
 };
+use zksync_dal::consensus_dal::Payload;
 use zksync_types::{
     block::{BlockGasCount, MiniblockHasher},
     event::extract_bytecodes_marked_as_known,
@@ -26,6 +27,8 @@ pub struct MiniblockUpdates {
     pub l1_gas_count: BlockGasCount,
     pub block_execution_metrics: ExecutionMetrics,
     pub txs_encoding_size: usize,
+    /// Total size of executed transactions as encoded in the consensus miniblock payload.
+    pub payload_size: usize,
     pub timestamp: u64,
     pub number: MiniblockNumber,
     pub prev_block_hash: H256,
@@ -51,6 +54,7 @@ impl MiniblockUpdates {
             l1_gas_count: BlockGasCount::default(),
             block_execution_metrics: ExecutionMetrics::default(),
             txs_encoding_size: 0,
+            payload_size: 0,
             timestamp,
             number,
             prev_block_hash,
@@ -84,6 +88,7 @@ impl MiniblockUpdates {
         execution_metrics: ExecutionMetrics,
         compressed_bytecodes: Vec<CompressedBytecodeInfo>,
         call_traces: Vec<Call>,
+        execution_time: Duration,
     ) {
         let saved_factory_deps =
             extract_bytecodes_marked_as_known(&tx_execution_result.logs.events);
@@ -130,6 +135,7 @@ impl MiniblockUpdates {
         self.l1_gas_count += tx_l1_gas_this_tx;
         self.block_execution_metrics += execution_metrics;
         self.txs_encoding_size += tx.bootloader_encoding_size();
+        self.payload_size += Payload::encoded_transaction_size(&tx);
         self.storage_logs
             .extend(tx_execution_result.logs.storage_logs);
 
@@ -143,6 +149,7 @@ impl MiniblockUpdates {
             compressed_bytecodes,
             call_traces,
             revert_reason,
+            execution_time,
         });
     }
 
@@ -183,6 +190,7 @@ mod tests {
         );
         let tx = create_transaction(10, 100);
         let bootloader_encoding_size = tx.bootloader_encoding_size();
+        let payload_size = Payload::encoded_transaction_size(&tx);
         accumulator.extend_from_executed_transaction(
             tx,
             create_execution_result(0, []),
@@ -190,6 +198,7 @@ mod tests {
             ExecutionMetrics::default(),
             vec![],
             vec![],
+            Duration::ZERO,
         );
 
         assert_eq!(accumulator.executed_transactions.len(), 1);
@@ -201,5 +210,6 @@ mod tests {
         assert_eq!(accumulator.new_factory_deps.len(), 0);
         assert_eq!(accumulator.block_execution_metrics.l2_to_l1_logs, 0);
         assert_eq!(accumulator.txs_encoding_size, bootloader_encoding_size);
+        assert_eq!(accumulator.payload_size, payload_size);
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use multivm::{
     interface::{FinishedL1Batch, L1BatchEnv, SystemEnv, VmExecutionResultAndLogs},
     utils::get_batch_base_fee,
@@ -110,6 +112,7 @@ impl UpdatesManager {
         tx_l1_gas_this_tx: BlockGasCount,
         execution_metrics: ExecutionMetrics,
         call_traces: Vec<Call>,
+        execution_time: Duration,
     ) {
         self.storage_writes_deduplicator
             .apply(&tx_execution_result.logs.storage_logs);
@@ -120,6 +123,7 @@ impl UpdatesManager {
             execution_metrics,
             compressed_bytecodes,
             call_traces,
+            execution_time,
         );
     }
 
@@ -130,7 +134,8 @@ impl UpdatesManager {
         );
 
         let result = &finished_batch.block_tip_execution_result;
-        let batch_tip_metrics = ExecutionMetricsForCriteria::new(None, result);
+        let batch_tip_metrics =
+            ExecutionMetricsForCriteria::new(None, result, self.protocol_version);
 
         let before = self.storage_writes_deduplicator.metrics();
         self.storage_writes_deduplicator
@@ -221,6 +226,7 @@ mod tests {
             new_block_gas_count(),
             ExecutionMetrics::default(),
             vec![],
+            Duration::ZERO,
         );
 
         // Check that only pending state is updated.
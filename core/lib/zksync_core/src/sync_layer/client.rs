@@ -1,20 +1,74 @@
 //! Client abstractions for syncing between the external node and the main node.
 
-use std::fmt;
+use std::{collections::HashSet, fmt};
 
 use async_trait::async_trait;
 use zksync_config::GenesisConfig;
 use zksync_system_constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_types::{
     api::{self, en},
-    get_code_key, Address, MiniblockNumber, ProtocolVersionId, H256, U64,
+    get_code_key, Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256, U64,
 };
 use zksync_web3_decl::{
     error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
-    namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
+    namespaces::{
+        EnNamespaceClient, EthNamespaceClient, SnapshotsNamespaceClient, ZksNamespaceClient,
+    },
 };
 
+/// Capabilities of the main node. Allows the external node components to adapt to older main nodes,
+/// which may not serve all the methods used by the external node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MainNodeCapabilities {
+    /// JSON-RPC methods served by the main node. Only contains methods from [`Self::PROBED_METHODS`].
+    pub supported_methods: HashSet<&'static str>,
+    /// Whether the main node has consensus genesis, i.e., whether blocks can be fetched over the gossip network.
+    pub consensus_genesis: bool,
+}
+
+impl MainNodeCapabilities {
+    pub const SYNC_L2_BLOCK: &'static str = "en_syncL2Block";
+    pub const CONSENSUS_GENESIS: &'static str = "en_consensusGenesis";
+    pub const GENESIS_CONFIG: &'static str = "en_genesisConfig";
+    pub const L1_BATCH_BLOCK_RANGE: &'static str = "zks_getL1BatchBlockRange";
+    pub const FEE_PARAMS: &'static str = "zks_getFeeParams";
+    pub const ALL_SNAPSHOTS: &'static str = "snapshots_getAllSnapshots";
+
+    /// Methods probed during capability discovery.
+    pub const PROBED_METHODS: [&'static str; 6] = [
+        Self::SYNC_L2_BLOCK,
+        Self::CONSENSUS_GENESIS,
+        Self::GENESIS_CONFIG,
+        Self::L1_BATCH_BLOCK_RANGE,
+        Self::FEE_PARAMS,
+        Self::ALL_SNAPSHOTS,
+    ];
+
+    /// Capabilities of a main node supporting all probed methods.
+    pub fn all(consensus_genesis: bool) -> Self {
+        Self {
+            supported_methods: Self::PROBED_METHODS.into_iter().collect(),
+            consensus_genesis,
+        }
+    }
+
+    /// Checks whether the specified JSON-RPC method is served by the main node.
+    pub fn supports(&self, method: &str) -> bool {
+        self.supported_methods.contains(method)
+    }
+
+    /// Whether the main node serves snapshots, which are required for snapshot recovery.
+    pub fn snapshots(&self) -> bool {
+        self.supports(Self::ALL_SNAPSHOTS)
+    }
+
+    /// Whether the main node can resolve L1 batches to ranges of L2 blocks.
+    pub fn ranged_sync(&self) -> bool {
+        self.supports(Self::L1_BATCH_BLOCK_RANGE)
+    }
+}
+
 /// Client abstracting connection to the main node.
 #[async_trait]
 pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
@@ -33,6 +87,11 @@ pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
         protocol_version: ProtocolVersionId,
     ) -> EnrichedClientResult<Option<api::ProtocolVersion>>;
 
+    /// Fetches the latest protocol version known to the main node, which may be scheduled to activate in the future.
+    async fn fetch_latest_protocol_version(
+        &self,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>>;
+
     async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber>;
 
     async fn fetch_l2_block(
@@ -44,6 +103,10 @@ pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
     async fn fetch_consensus_genesis(&self) -> EnrichedClientResult<Option<en::ConsensusGenesis>>;
 
     async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig>;
+
+    /// Discovers capabilities of the main node. Unlike other methods, this one should work with main nodes
+    /// of any version.
+    async fn fetch_capabilities(&self) -> EnrichedClientResult<MainNodeCapabilities>;
 }
 
 impl dyn MainNodeClient {
@@ -90,7 +153,7 @@ impl MainNodeClient for HttpClient {
             .get_storage_at(
                 ACCOUNT_CODE_STORAGE_ADDRESS,
                 zksync_utils::h256_to_u256(*code_key.key()),
-                Some(GENESIS_BLOCK),
+                Some(GENESIS_BLOCK.into()),
             )
             .rpc_context("get_storage_at")
             .with_arg("address", &address)
@@ -111,6 +174,14 @@ impl MainNodeClient for HttpClient {
             .await
     }
 
+    async fn fetch_latest_protocol_version(
+        &self,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        self.get_protocol_version(None)
+            .rpc_context("fetch_latest_protocol_version")
+            .await
+    }
+
     async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig> {
         self.genesis_config().rpc_context("genesis_config").await
     }
@@ -142,4 +213,66 @@ impl MainNodeClient for HttpClient {
             .rpc_context("consensus_genesis")
             .await
     }
+
+    async fn fetch_capabilities(&self) -> EnrichedClientResult<MainNodeCapabilities> {
+        /// Returns `None` if the probed method is not served by the main node. If the method is served,
+        /// but the call has failed with a non-transient error, returns `Some(None)`.
+        fn probe<T>(result: EnrichedClientResult<T>) -> EnrichedClientResult<Option<Option<T>>> {
+            match result {
+                Ok(value) => Ok(Some(Some(value))),
+                Err(err) if err.is_method_not_found() => Ok(None),
+                Err(err) if err.is_transient() => Err(err),
+                Err(err) => {
+                    tracing::debug!("Probing main node capabilities: {err}");
+                    Ok(Some(None))
+                }
+            }
+        }
+
+        let mut supported_methods = HashSet::new();
+        let mut consensus_genesis = false;
+
+        let block = self
+            .sync_l2_block(MiniblockNumber(0), false)
+            .rpc_context("sync_l2_block")
+            .await;
+        if probe(block)?.is_some() {
+            supported_methods.insert(MainNodeCapabilities::SYNC_L2_BLOCK);
+        }
+        let genesis = self
+            .consensus_genesis()
+            .rpc_context("consensus_genesis")
+            .await;
+        if let Some(genesis) = probe(genesis)? {
+            supported_methods.insert(MainNodeCapabilities::CONSENSUS_GENESIS);
+            consensus_genesis = matches!(genesis, Some(Some(_)));
+        }
+        let genesis_config = self.genesis_config().rpc_context("genesis_config").await;
+        if probe(genesis_config)?.is_some() {
+            supported_methods.insert(MainNodeCapabilities::GENESIS_CONFIG);
+        }
+        let block_range = self
+            .get_miniblock_range(L1BatchNumber(0))
+            .rpc_context("get_miniblock_range")
+            .await;
+        if probe(block_range)?.is_some() {
+            supported_methods.insert(MainNodeCapabilities::L1_BATCH_BLOCK_RANGE);
+        }
+        let fee_params = self.get_fee_params().rpc_context("get_fee_params").await;
+        if probe(fee_params)?.is_some() {
+            supported_methods.insert(MainNodeCapabilities::FEE_PARAMS);
+        }
+        let snapshots = self
+            .get_all_snapshots()
+            .rpc_context("get_all_snapshots")
+            .await;
+        if probe(snapshots)?.is_some() {
+            supported_methods.insert(MainNodeCapabilities::ALL_SNAPSHOTS);
+        }
+
+        Ok(MainNodeCapabilities {
+            supported_methods,
+            consensus_genesis,
+        })
+    }
 }
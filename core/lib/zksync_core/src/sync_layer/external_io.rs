@@ -1,7 +1,12 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
+use tokio::sync::watch;
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
@@ -13,6 +18,7 @@ use zksync_utils::bytes_to_be_words;
 
 use super::{
     client::MainNodeClient,
+    metrics::QUEUE_METRICS,
     sync_action::{ActionQueue, SyncAction},
 };
 use crate::state_keeper::{
@@ -34,6 +40,9 @@ const POLL_INTERVAL: Duration = Duration::from_millis(100);
 ///
 /// It is also responsible for the persisting of data, and this slice of logic is pretty close
 /// to the one in the mempool IO (which is used in the main node).
+///
+/// Actions that cannot be applied are persisted as dead letters before the IO returns an error,
+/// so that the node operator can inspect them and decide whether to retry or skip them.
 #[derive(Debug)]
 pub struct ExternalIO {
     pool: ConnectionPool<Core>,
@@ -41,6 +50,11 @@ pub struct ExternalIO {
     actions: ActionQueue,
     main_node_client: Box<dyn MainNodeClient>,
     chain_id: L2ChainId,
+    stale_fee_params: Option<watch::Receiver<bool>>,
+    /// Miniblock currently being processed; used to attribute dead letters.
+    current_miniblock: MiniblockNumber,
+    /// Transactions that the operator has decided to skip.
+    skipped_txs: HashSet<H256>,
 }
 
 impl ExternalIO {
@@ -57,6 +71,18 @@ impl ExternalIO {
         // We must run the migration for pending miniblocks synchronously, since we use `fee_account_address`
         // from a pending miniblock in `load_pending_batch()` implementation.
         fee_address_migration::migrate_pending_miniblocks(&mut storage).await?;
+        let skipped_txs: HashSet<_> = storage
+            .sync_dead_letters_dal()
+            .get_skipped_tx_hashes()
+            .await?
+            .into_iter()
+            .collect();
+        if !skipped_txs.is_empty() {
+            tracing::warn!(
+                "{} transaction(s) will be skipped as requested by the node operator: {skipped_txs:?}",
+                skipped_txs.len()
+            );
+        }
         drop(storage);
 
         Ok(Self {
@@ -65,9 +91,94 @@ impl ExternalIO {
             actions,
             main_node_client,
             chain_id,
+            stale_fee_params: None,
+            current_miniblock: MiniblockNumber(0),
+            skipped_txs,
         })
     }
 
+    /// Makes the IO refuse to start new miniblocks while the provided receiver is set to `true`
+    /// (i.e., while fee params fetched from the main node are stale).
+    pub fn with_stale_fee_params_guard(mut self, stale_fee_params: watch::Receiver<bool>) -> Self {
+        self.stale_fee_params = Some(stale_fee_params);
+        self
+    }
+
+    fn has_stale_fee_params(&self) -> bool {
+        self.stale_fee_params
+            .as_ref()
+            .map_or(false, |receiver| *receiver.borrow())
+    }
+
+    /// Persists an action that has failed to apply as a dead letter. Returns the provided error
+    /// annotated with the dead letter ID.
+    async fn record_dead_letter(
+        &self,
+        tx_hash: Option<H256>,
+        action: &dyn fmt::Debug,
+        err: anyhow::Error,
+    ) -> anyhow::Error {
+        let action = format!("{action:?}");
+        let error = format!("{err:#}");
+        let result = async {
+            let mut storage = self.pool.connection_tagged("sync_layer").await?;
+            let id = storage
+                .sync_dead_letters_dal()
+                .insert_dead_letter(self.current_miniblock, tx_hash, &action, &error)
+                .await?;
+            anyhow::Ok(id)
+        };
+        match result.await {
+            Ok(id) => {
+                QUEUE_METRICS.dead_letters.inc();
+                tracing::error!(
+                    "Failed applying action in miniblock #{}; recorded it as dead letter #{id}",
+                    self.current_miniblock
+                );
+                err.context(format!("action is recorded as dead letter #{id}"))
+            }
+            Err(db_err) => {
+                tracing::warn!("Failed recording dead letter: {db_err:#}");
+                err
+            }
+        }
+    }
+
+    fn check_batch_start(
+        cursor: &IoCursor,
+        number: L1BatchNumber,
+        first_miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            number == cursor.l1_batch,
+            "Batch number mismatch: expected {}, got {number}",
+            cursor.l1_batch
+        );
+        Self::check_miniblock_start(cursor, first_miniblock_number)
+    }
+
+    fn check_miniblock_start(cursor: &IoCursor, number: MiniblockNumber) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            number == cursor.next_miniblock,
+            "Miniblock number mismatch: expected {}, got {number}",
+            cursor.next_miniblock
+        );
+        Ok(())
+    }
+
+    /// Checks an action popped from the queue with the provided `check`, recording the action as a dead letter
+    /// if the check fails.
+    async fn check_action(
+        &self,
+        action: &SyncAction,
+        check: anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        match check {
+            Ok(()) => Ok(()),
+            Err(err) => Err(self.record_dead_letter(None, action, err).await),
+        }
+    }
+
     async fn get_base_system_contract(
         &self,
         hash: H256,
@@ -149,6 +260,7 @@ impl StateKeeperIO for ExternalIO {
             cursor.l1_batch,
             cursor.next_miniblock,
         );
+        self.current_miniblock = cursor.next_miniblock;
 
         let pending_miniblock_header = self
             .l1_batch_params_provider
@@ -226,26 +338,29 @@ impl StateKeeperIO for ExternalIO {
         tracing::debug!("Waiting for the new batch params");
         for _ in 0..poll_iters(POLL_INTERVAL, max_wait) {
             match self.actions.pop_action() {
-                Some(SyncAction::OpenBatch {
-                    params,
-                    number,
-                    first_miniblock_number,
-                }) => {
-                    anyhow::ensure!(
-                        number == cursor.l1_batch,
-                        "Batch number mismatch: expected {}, got {number}",
-                        cursor.l1_batch
-                    );
-                    anyhow::ensure!(
-                        first_miniblock_number == cursor.next_miniblock,
-                        "Miniblock number mismatch: expected {}, got {first_miniblock_number}",
-                        cursor.next_miniblock
-                    );
+                Some(action) => {
+                    let check = match &action {
+                        SyncAction::OpenBatch {
+                            number,
+                            first_miniblock_number,
+                            ..
+                        } => Self::check_batch_start(cursor, *number, *first_miniblock_number),
+                        other => Err(anyhow::anyhow!(
+                            "unexpected action in the action queue: {other:?}"
+                        )),
+                    };
+                    self.check_action(&action, check).await?;
+                    let SyncAction::OpenBatch {
+                        params,
+                        first_miniblock_number,
+                        ..
+                    } = action
+                    else {
+                        unreachable!()
+                    };
+                    self.current_miniblock = first_miniblock_number;
                     return Ok(Some(params));
                 }
-                Some(other) => {
-                    anyhow::bail!("unexpected action in the action queue: {other:?}");
-                }
                 None => {
                     tokio::time::sleep(POLL_INTERVAL).await;
                 }
@@ -260,22 +375,32 @@ impl StateKeeperIO for ExternalIO {
         max_wait: Duration,
     ) -> anyhow::Result<Option<MiniblockParams>> {
         // Wait for the next miniblock to appear in the queue.
-        let actions = &mut self.actions;
         for _ in 0..poll_iters(POLL_INTERVAL, max_wait) {
-            match actions.pop_action() {
-                Some(SyncAction::Miniblock { params, number }) => {
-                    anyhow::ensure!(
-                        number == cursor.next_miniblock,
-                        "Miniblock number mismatch: expected {}, got {number}",
-                        cursor.next_miniblock
-                    );
+            if self.has_stale_fee_params() {
+                tracing::debug!(
+                    "Fee params are stale; not starting miniblock #{}",
+                    cursor.next_miniblock
+                );
+                tokio::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+            match self.actions.pop_action() {
+                Some(action) => {
+                    let check = match &action {
+                        SyncAction::Miniblock { number, .. } => {
+                            Self::check_miniblock_start(cursor, *number)
+                        }
+                        other => Err(anyhow::anyhow!(
+                            "Unexpected action in the queue while waiting for the next miniblock: {other:?}"
+                        )),
+                    };
+                    self.check_action(&action, check).await?;
+                    let SyncAction::Miniblock { params, number } = action else {
+                        unreachable!()
+                    };
+                    self.current_miniblock = number;
                     return Ok(Some(params));
                 }
-                Some(other) => {
-                    anyhow::bail!(
-                        "Unexpected action in the queue while waiting for the next miniblock: {other:?}"
-                    );
-                }
                 None => {
                     tokio::time::sleep(POLL_INTERVAL).await;
                 }
@@ -288,27 +413,37 @@ impl StateKeeperIO for ExternalIO {
         &mut self,
         max_wait: Duration,
     ) -> anyhow::Result<Option<Transaction>> {
-        let actions = &mut self.actions;
         tracing::debug!(
             "Waiting for the new tx, next action is {:?}",
-            actions.peek_action()
+            self.actions.peek_action()
         );
         for _ in 0..poll_iters(POLL_INTERVAL, max_wait) {
-            match actions.peek_action() {
+            match self.actions.peek_action() {
                 Some(SyncAction::Tx(_)) => {
-                    let SyncAction::Tx(tx) = actions.pop_action().unwrap() else {
+                    let SyncAction::Tx(tx) = self.actions.pop_action().unwrap() else {
                         unreachable!()
                     };
+                    let tx_hash = tx.hash();
+                    if self.skipped_txs.contains(&tx_hash) {
+                        tracing::warn!(
+                            "Skipping transaction {tx_hash:?} in miniblock #{} as requested by the node operator",
+                            self.current_miniblock
+                        );
+                        QUEUE_METRICS.skipped_transactions.inc();
+                        continue;
+                    }
                     return Ok(Some(Transaction::from(*tx)));
                 }
                 Some(SyncAction::SealMiniblock | SyncAction::SealBatch) => {
                     // No more transactions in the current miniblock; the state keeper should seal it.
                     return Ok(None);
                 }
-                Some(other) => {
-                    anyhow::bail!(
-                        "Unexpected action in the queue while waiting for the next transaction: {other:?}"
+                Some(_) => {
+                    let action = self.actions.pop_action().unwrap();
+                    let err = anyhow::anyhow!(
+                        "Unexpected action in the queue while waiting for the next transaction: {action:?}"
                     );
+                    return Err(self.record_dead_letter(None, &action, err).await);
                 }
                 _ => {
                     tokio::time::sleep(POLL_INTERVAL).await;
@@ -318,18 +453,26 @@ impl StateKeeperIO for ExternalIO {
         Ok(None)
     }
 
+    fn peek_upcoming_txs(&mut self) -> Vec<Transaction> {
+        let mut txs = self.actions.peek_txs();
+        txs.retain(|tx| !self.skipped_txs.contains(&tx.hash()));
+        txs
+    }
+
     async fn rollback(&mut self, tx: Transaction) -> anyhow::Result<()> {
         // We are replaying the already sealed batches so no rollbacks are expected to occur.
-        anyhow::bail!("Rollback requested. Transaction hash: {:?}", tx.hash());
+        let err = anyhow::anyhow!("Rollback requested. Transaction hash: {:?}", tx.hash());
+        Err(self.record_dead_letter(Some(tx.hash()), &tx, err).await)
     }
 
     async fn reject(&mut self, tx: &Transaction, error: &str) -> anyhow::Result<()> {
         // We are replaying the already executed transactions so no rejections are expected to occur.
-        anyhow::bail!(
+        let err = anyhow::anyhow!(
             "Requested rejection of transaction {:?} because of the following error: {error}. \
              This is not supported on external node",
             tx.hash()
         );
+        Err(self.record_dead_letter(Some(tx.hash()), tx, err).await)
     }
 
     async fn load_base_system_contracts(
@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_types::aggregated_operations::AggregatedActionType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -49,7 +49,17 @@ pub(super) static FETCHER_METRICS: vise::Global<FetcherMetrics> = vise::Global::
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "external_node_action_queue")]
 pub(super) struct ActionQueueMetrics {
+    /// Number of actions in the queue.
     pub action_queue_size: Gauge<usize>,
+    /// Maximum number of actions in the queue.
+    pub action_queue_capacity: Gauge<usize>,
+    /// Time spent by the queue producer waiting for the queue to have free capacity.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub backpressure_latency: Histogram<Duration>,
+    /// Number of actions that have failed to apply and were recorded as dead letters.
+    pub dead_letters: Counter,
+    /// Number of transactions skipped as requested by the node operator.
+    pub skipped_transactions: Counter,
 }
 
 #[vise::register]
@@ -4,13 +4,19 @@ pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
 mod metrics;
+mod node_state;
+pub(crate) mod protocol_version;
 pub(crate) mod sync_action;
 mod sync_state;
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    client::MainNodeClient, external_io::ExternalIO, sync_action::ActionQueue,
+    client::{MainNodeCapabilities, MainNodeClient},
+    external_io::ExternalIO,
+    node_state::{NodeState, NodeStateHandle},
+    protocol_version::{ProtocolVersionCheck, UnsupportedProtocolVersion},
+    sync_action::{ActionQueue, ActionQueueSender},
     sync_state::SyncState,
 };
 
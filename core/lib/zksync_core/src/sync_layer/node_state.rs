@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+/// Coarse-grained state of the node. Used to restrict functionality of node components (e.g., the API server)
+/// while the node storage is being prepared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeState {
+    /// Node storage is being initialized, either from genesis or from a snapshot.
+    InitializingStorage,
+    /// Node storage is being rolled back, e.g. after a reorg.
+    RollingBack,
+    /// Node storage is consistent; the node operates normally.
+    Ready,
+}
+
+/// Shared handle to the [`NodeState`]. Cloned handles refer to the same state.
+#[derive(Debug, Clone)]
+pub struct NodeStateHandle(Arc<watch::Sender<NodeState>>);
+
+impl NodeStateHandle {
+    pub fn new(state: NodeState) -> Self {
+        Self(Arc::new(watch::channel(state).0))
+    }
+
+    pub fn get(&self) -> NodeState {
+        *self.0.borrow()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.get() == NodeState::Ready
+    }
+
+    pub fn set(&self, state: NodeState) {
+        let prev_state = self.0.send_replace(state);
+        if prev_state != state {
+            tracing::info!("Node state changed: {prev_state:?} -> {state:?}");
+        }
+    }
+
+    /// Waits until the node is [ready](NodeState::Ready).
+    pub async fn wait_until_ready(&self) {
+        let mut subscriber = self.0.subscribe();
+        // `unwrap()` is safe: the sender is owned by `self`, so it cannot be dropped while we wait.
+        subscriber
+            .wait_for(|&state| state == NodeState::Ready)
+            .await
+            .unwrap();
+    }
+}
+
+impl Default for NodeStateHandle {
+    fn default() -> Self {
+        Self::new(NodeState::Ready)
+    }
+}
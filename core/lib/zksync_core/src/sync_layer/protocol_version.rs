@@ -0,0 +1,123 @@
+//! Protocol version handshake between the external node and the main node.
+//!
+//! The main node may activate a protocol upgrade that this node cannot execute, e.g. because it runs
+//! a binary released before the upgrade. Instead of failing mid-sync, the node detects such upgrades,
+//! reports them via the sync state health check and stops fetching blocks before the first block
+//! with the unsupported version.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_types::ProtocolVersionId;
+use zksync_web3_decl::error::EnrichedClientResult;
+
+use super::{MainNodeClient, SyncState};
+
+/// Protocol version known to the main node that cannot be executed by this node.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct UnsupportedProtocolVersion {
+    /// Protocol version ID.
+    pub version_id: u16,
+    /// Timestamp at which the version is (or was) activated on the main node.
+    pub timestamp: u64,
+}
+
+/// Checks whether the latest protocol version of the main node can be executed by this node
+/// and updates `sync_state` accordingly.
+pub(crate) async fn check_protocol_version(
+    client: &dyn MainNodeClient,
+    sync_state: &SyncState,
+) -> EnrichedClientResult<Option<UnsupportedProtocolVersion>> {
+    let Some(latest) = client.fetch_latest_protocol_version().await? else {
+        return Ok(None);
+    };
+    // Each protocol version known to this node is mapped to a VM version in the multi-VM,
+    // so it's enough to check that the version can be parsed.
+    let unsupported = ProtocolVersionId::try_from(latest.version_id)
+        .is_err()
+        .then_some(UnsupportedProtocolVersion {
+            version_id: latest.version_id,
+            timestamp: latest.timestamp,
+        });
+
+    if unsupported != sync_state.unsupported_protocol_version() {
+        if let Some(version) = &unsupported {
+            tracing::error!(
+                "Protocol version {} activated on the main node at timestamp {} is not supported \
+                 by this node (the latest supported version is {}). The node will stop syncing \
+                 before the first block with this version; update the node to continue syncing",
+                version.version_id,
+                version.timestamp,
+                ProtocolVersionId::next() as u16
+            );
+        } else {
+            tracing::info!("All protocol versions of the main node are supported by this node");
+        }
+        sync_state.set_unsupported_protocol_version(unsupported);
+    }
+    Ok(unsupported)
+}
+
+/// Task periodically checking that the protocol versions of the main node are supported by this node.
+#[derive(Debug)]
+pub struct ProtocolVersionCheck {
+    client: Box<dyn MainNodeClient>,
+    sync_state: SyncState,
+}
+
+impl ProtocolVersionCheck {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(client: Box<dyn MainNodeClient>, sync_state: SyncState) -> Self {
+        Self { client, sync_state }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            let res = check_protocol_version(self.client.as_ref(), &self.sync_state).await;
+            if let Err(err) = res {
+                tracing::warn!("Failed checking protocol version of the main node: {err}");
+            }
+            tokio::time::timeout(Self::CHECK_INTERVAL, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::api;
+
+    use super::*;
+    use crate::consensus::testonly::MockMainNodeClient;
+
+    fn protocol_version(version_id: u16) -> api::ProtocolVersion {
+        api::ProtocolVersion {
+            version_id,
+            timestamp: 1_000,
+            ..api::ProtocolVersion::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn checking_protocol_version() {
+        let sync_state = SyncState::default();
+        let mut client = MockMainNodeClient::default();
+        client.insert_protocol_version(protocol_version(ProtocolVersionId::latest() as u16));
+        let unsupported = check_protocol_version(&client, &sync_state).await.unwrap();
+        assert_eq!(unsupported, None);
+        assert_eq!(sync_state.unsupported_protocol_version(), None);
+
+        client.insert_protocol_version(protocol_version(u16::MAX));
+        let unsupported = check_protocol_version(&client, &sync_state)
+            .await
+            .unwrap()
+            .expect("unsupported version not detected");
+        assert_eq!(unsupported.version_id, u16::MAX);
+        assert_eq!(unsupported.timestamp, 1_000);
+        assert_eq!(sync_state.unsupported_protocol_version(), Some(unsupported));
+    }
+}
@@ -1,5 +1,7 @@
+use std::{collections::VecDeque, num::NonZeroUsize};
+
 use tokio::sync::mpsc;
-use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_types::{L1BatchNumber, MiniblockNumber, Transaction};
 
 use super::{fetcher::FetchedTransaction, metrics::QUEUE_METRICS};
 use crate::state_keeper::io::{L1BatchParams, MiniblockParams};
@@ -13,10 +15,24 @@ impl ActionQueueSender {
     /// Requires that the actions are in the correct order: starts with a new open batch/miniblock,
     /// followed by 0 or more transactions, have mandatory `SealMiniblock` and optional `SealBatch` at the end.
     /// Would panic if the order is incorrect.
+    ///
+    /// If the queue is full, waits until the queue consumer processes enough actions, thus applying backpressure
+    /// to the caller (e.g., the fetcher).
     pub(crate) async fn push_actions(&self, actions: Vec<SyncAction>) {
         Self::check_action_sequence(&actions).unwrap();
         for action in actions {
-            self.0.send(action).await.expect("EN sync logic panicked");
+            if let Err(err) = self.0.try_send(action) {
+                let mpsc::error::TrySendError::Full(action) = err else {
+                    panic!("EN sync logic panicked");
+                };
+                tracing::debug!(
+                    "Action queue is full (capacity: {}); waiting for queued actions to be processed",
+                    self.0.max_capacity()
+                );
+                let latency = QUEUE_METRICS.backpressure_latency.start();
+                self.0.send(action).await.expect("EN sync logic panicked");
+                latency.observe();
+            }
             QUEUE_METRICS
                 .action_queue_size
                 .set(self.0.max_capacity() - self.0.capacity());
@@ -68,25 +84,37 @@ impl ActionQueueSender {
 #[derive(Debug)]
 pub struct ActionQueue {
     receiver: mpsc::Receiver<SyncAction>,
-    peeked: Option<SyncAction>,
+    peeked: VecDeque<SyncAction>,
 }
 
 impl ActionQueue {
+    /// Default maximum number of actions in the queue.
+    pub const DEFAULT_CAPACITY: NonZeroUsize = match NonZeroUsize::new(32_768) {
+        Some(capacity) => capacity,
+        None => unreachable!(),
+    };
+
+    /// Creates a queue with the [default capacity](Self::DEFAULT_CAPACITY).
     pub fn new() -> (ActionQueueSender, Self) {
-        const ACTION_CAPACITY: usize = 32_768; // TODO: Make it configurable.
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
 
-        let (sender, receiver) = mpsc::channel(ACTION_CAPACITY);
+    /// Creates a queue with the specified maximum number of actions. Once the queue is full,
+    /// [`ActionQueueSender`] waits until some actions are processed.
+    pub fn with_capacity(capacity: NonZeroUsize) -> (ActionQueueSender, Self) {
+        let (sender, receiver) = mpsc::channel(capacity.get());
+        QUEUE_METRICS.action_queue_capacity.set(capacity.get());
         let sender = ActionQueueSender(sender);
         let this = Self {
             receiver,
-            peeked: None,
+            peeked: VecDeque::new(),
         };
         (sender, this)
     }
 
     /// Removes the first action from the queue.
     pub(super) fn pop_action(&mut self) -> Option<SyncAction> {
-        if let Some(peeked) = self.peeked.take() {
+        if let Some(peeked) = self.peeked.pop_front() {
             QUEUE_METRICS.action_queue_size.dec_by(1);
             return Some(peeked);
         }
@@ -99,11 +127,28 @@ impl ActionQueue {
 
     /// Returns the first action from the queue without removing it.
     pub(super) fn peek_action(&mut self) -> Option<SyncAction> {
-        if let Some(action) = &self.peeked {
-            return Some(action.clone());
+        if self.peeked.is_empty() {
+            self.peeked.extend(self.receiver.try_recv().ok());
+        }
+        self.peeked.front().cloned()
+    }
+
+    /// Returns transactions at the start of the queue (i.e., ones that will be executed next in the current miniblock)
+    /// without removing them from the queue. Only returns transactions already received from the fetcher.
+    pub(super) fn peek_txs(&mut self) -> Vec<Transaction> {
+        while matches!(self.peeked.back(), None | Some(SyncAction::Tx(_))) {
+            let Ok(action) = self.receiver.try_recv() else {
+                break;
+            };
+            self.peeked.push_back(action);
         }
-        self.peeked = self.receiver.try_recv().ok();
-        self.peeked.clone()
+        self.peeked
+            .iter()
+            .map_while(|action| match action {
+                SyncAction::Tx(tx) => Some(Transaction::from((**tx).clone())),
+                _ => None,
+            })
+            .collect()
     }
 }
 
@@ -139,6 +184,9 @@ impl From<FetchedTransaction> for SyncAction {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
     use zksync_types::{fee_model::BatchFeeInput, l2::L2Tx, Address, ProtocolVersionId, H256};
 
     use super::*;
@@ -271,4 +319,45 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn peeking_txs_does_not_consume_actions() {
+        let (sender, mut queue) = ActionQueue::new();
+        assert!(queue.peek_txs().is_empty());
+
+        sender
+            .push_actions(vec![miniblock(), tx(), tx(), seal_miniblock()])
+            .await;
+        assert!(queue.peek_txs().is_empty()); // The queue starts with a non-transaction action
+        assert_matches!(queue.pop_action(), Some(SyncAction::Miniblock { .. }));
+
+        assert_eq!(queue.peek_txs().len(), 2);
+        assert_matches!(queue.peek_action(), Some(SyncAction::Tx(_)));
+        assert_matches!(queue.pop_action(), Some(SyncAction::Tx(_)));
+        assert_eq!(queue.peek_txs().len(), 1);
+        assert_matches!(queue.pop_action(), Some(SyncAction::Tx(_)));
+        assert!(queue.peek_txs().is_empty());
+        assert_matches!(queue.pop_action(), Some(SyncAction::SealMiniblock));
+        assert_matches!(queue.pop_action(), None);
+    }
+
+    #[tokio::test]
+    async fn full_queue_applies_backpressure() {
+        let (sender, mut queue) = ActionQueue::with_capacity(NonZeroUsize::new(2).unwrap());
+        let push_task = tokio::spawn(async move {
+            sender
+                .push_actions(vec![miniblock(), tx(), tx(), seal_miniblock()])
+                .await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!push_task.is_finished());
+        assert_matches!(queue.pop_action(), Some(SyncAction::Miniblock { .. }));
+        assert_matches!(queue.pop_action(), Some(SyncAction::Tx(_)));
+
+        push_task.await.unwrap();
+        assert_matches!(queue.pop_action(), Some(SyncAction::Tx(_)));
+        assert_matches!(queue.pop_action(), Some(SyncAction::SealMiniblock));
+        assert_matches!(queue.pop_action(), None);
+    }
 }
@@ -10,6 +10,7 @@ use zksync_health_check::{CheckHealth, Health, HealthStatus};
 use zksync_types::MiniblockNumber;
 use zksync_web3_decl::{jsonrpsee::http_client::HttpClient, namespaces::EthNamespaceClient};
 
+use super::protocol_version::UnsupportedProtocolVersion;
 use crate::{
     metrics::EN_METRICS,
     state_keeper::{io::IoCursor, updates::UpdatesManager, StateKeeperOutputHandler},
@@ -74,6 +75,23 @@ impl SyncState {
         self.0.send_modify(|inner| inner.set_local_block(block));
     }
 
+    /// Returns the protocol version activated (or scheduled) on the main node that cannot be executed
+    /// by this node, if any.
+    pub(crate) fn unsupported_protocol_version(&self) -> Option<UnsupportedProtocolVersion> {
+        self.0.borrow().unsupported_protocol_version
+    }
+
+    pub(crate) fn set_unsupported_protocol_version(
+        &self,
+        version: Option<UnsupportedProtocolVersion>,
+    ) {
+        EN_METRICS
+            .unsupported_protocol_version
+            .set(version.map_or(0, |version| version.version_id.into()));
+        self.0
+            .send_modify(|inner| inner.unsupported_protocol_version = version);
+    }
+
     pub(crate) fn is_synced(&self) -> bool {
         self.0.borrow().is_synced().0
     }
@@ -139,6 +157,7 @@ impl StateKeeperOutputHandler for SyncState {
 pub(crate) struct SyncStateInner {
     pub(crate) main_node_block: Option<MiniblockNumber>,
     pub(crate) local_block: Option<MiniblockNumber>,
+    pub(crate) unsupported_protocol_version: Option<UnsupportedProtocolVersion>,
 }
 
 impl SyncStateInner {
@@ -209,10 +228,15 @@ impl From<&SyncStateInner> for Health {
             main_node_block: Option<MiniblockNumber>,
             #[serde(skip_serializing_if = "Option::is_none")]
             local_block: Option<MiniblockNumber>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            unsupported_protocol_version: Option<UnsupportedProtocolVersion>,
         }
 
         let (is_synced, block_diff) = state.is_synced();
-        let status = if is_synced {
+        let status = if state.unsupported_protocol_version.is_some() {
+            // The node will stop syncing before the first block with the unsupported version.
+            HealthStatus::Affected
+        } else if is_synced {
             HealthStatus::Ready
         } else if block_diff.is_some() {
             HealthStatus::Affected
@@ -223,6 +247,7 @@ impl From<&SyncStateInner> for Health {
             is_synced,
             main_node_block: state.main_node_block,
             local_block: state.local_block,
+            unsupported_protocol_version: state.unsupported_protocol_version,
         })
     }
 }
@@ -279,6 +304,32 @@ mod tests {
         assert!(sync_state.is_synced());
     }
 
+    #[tokio::test]
+    async fn test_sync_state_with_unsupported_protocol_version() {
+        let sync_state = SyncState::default();
+        sync_state.set_local_block(MiniblockNumber(1));
+        sync_state.set_main_node_block(MiniblockNumber(1));
+        let health = sync_state.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+
+        let version = UnsupportedProtocolVersion {
+            version_id: u16::MAX,
+            timestamp: 1_000,
+        };
+        sync_state.set_unsupported_protocol_version(Some(version));
+        let health = sync_state.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+        let health = serde_json::to_value(health).unwrap();
+        assert_eq!(
+            health["details"]["unsupported_protocol_version"]["version_id"],
+            u16::MAX
+        );
+
+        sync_state.set_unsupported_protocol_version(None);
+        let health = sync_state.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+    }
+
     #[test]
     fn test_sync_state_doesnt_panic_on_main_node_block() {
         let sync_state = SyncState::default();
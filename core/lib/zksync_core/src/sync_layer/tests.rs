@@ -5,7 +5,9 @@ use std::{iter, sync::Arc, time::Duration};
 use test_casing::test_casing;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    sync_dead_letters_dal::DeadLetterResolution, Connection, ConnectionPool, Core, CoreDal,
+};
 use zksync_types::{
     api,
     block::MiniblockHasher,
@@ -19,7 +21,7 @@ use crate::{
     consensus::testonly::MockMainNodeClient,
     genesis::{insert_genesis_batch, GenesisParams},
     state_keeper::{
-        io::{L1BatchParams, MiniblockParams},
+        io::{L1BatchParams, MiniblockParams, StateKeeperIO},
         seal_criteria::NoopSealer,
         tests::TestBatchExecutorBuilder,
         OutputHandler, StateKeeperPersistence, ZkSyncStateKeeper,
@@ -231,6 +233,116 @@ async fn external_io_basics(snapshot_recovery: bool) {
     assert_eq!(tx_receipt.transaction_index, 0.into());
 }
 
+#[tokio::test]
+async fn mismatched_action_is_recorded_as_dead_letter() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis(&mut storage).await;
+
+    let (actions_sender, action_queue) = ActionQueue::new();
+    let mut io = ExternalIO::new(
+        pool.clone(),
+        action_queue,
+        Box::<MockMainNodeClient>::default(),
+        L2ChainId::default(),
+    )
+    .await
+    .unwrap();
+    let (cursor, _) = io.initialize().await.unwrap();
+    // Open an L1 batch with an unexpected number.
+    actions_sender
+        .push_actions(vec![open_l1_batch(2, 1, 1), SyncAction::SealMiniblock])
+        .await;
+
+    let err = io
+        .wait_for_new_batch_params(&cursor, TEST_TIMEOUT)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("Batch number mismatch"), "{err}");
+
+    let dead_letters = storage
+        .sync_dead_letters_dal()
+        .get_dead_letters(true)
+        .await
+        .unwrap();
+    assert_eq!(dead_letters.len(), 1, "{dead_letters:?}");
+    let dead_letter = &dead_letters[0];
+    assert!(
+        err.contains(&format!("dead letter #{}", dead_letter.id)),
+        "{err}"
+    );
+    assert_eq!(dead_letter.miniblock_number, MiniblockNumber(1));
+    assert_eq!(dead_letter.tx_hash, None);
+    assert!(
+        dead_letter.action.starts_with("OpenBatch"),
+        "{dead_letter:?}"
+    );
+    assert!(
+        dead_letter.error.contains("Batch number mismatch"),
+        "{dead_letter:?}"
+    );
+}
+
+#[tokio::test]
+async fn skipped_transactions_are_not_executed() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis(&mut storage).await;
+
+    let skipped_tx = create_l2_transaction(10, 100);
+    let skipped_tx_hash = skipped_tx.hash();
+    let dead_letter_id = storage
+        .sync_dead_letters_dal()
+        .insert_dead_letter(
+            MiniblockNumber(1),
+            Some(skipped_tx_hash),
+            "Tx(..)",
+            "rejected",
+        )
+        .await
+        .unwrap();
+    storage
+        .sync_dead_letters_dal()
+        .resolve_dead_letter(dead_letter_id, DeadLetterResolution::Skip)
+        .await
+        .unwrap();
+
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    let actions = vec![
+        open_l1_batch(1, 1, 1),
+        FetchedTransaction::new(skipped_tx.into()).into(),
+        FetchedTransaction::new(tx.into()).into(),
+        SyncAction::SealMiniblock,
+    ];
+    let (actions_sender, action_queue) = ActionQueue::new();
+    let state_keeper = StateKeeperHandles::new(
+        pool.clone(),
+        MockMainNodeClient::default(),
+        action_queue,
+        &[&[tx_hash]],
+    )
+    .await;
+    actions_sender.push_actions(actions).await;
+    state_keeper.wait_for_local_block(MiniblockNumber(1)).await;
+
+    let miniblock = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(1))
+        .await
+        .unwrap()
+        .expect("New miniblock is not persisted");
+    assert_eq!(miniblock.l2_tx_count, 1);
+    let receipts = storage
+        .transactions_web3_dal()
+        .get_transaction_receipts(&[skipped_tx_hash, tx_hash])
+        .await
+        .unwrap();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].transaction_hash, tx_hash);
+}
+
 #[test_casing(2, [false, true])]
 #[tokio::test]
 async fn external_io_works_without_local_protocol_version(snapshot_recovery: bool) {
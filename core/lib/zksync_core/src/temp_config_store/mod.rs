@@ -9,9 +9,10 @@ use zksync_config::{
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
         wallets::{AddressWallet, EthSender, StateKeeper, Wallet, Wallets},
-        FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
-        FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, GeneralConfig,
-        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        DataRetentionConfig, FinalityWebhooksConfig, FriProofCompressorConfig, FriProverConfig,
+        FriProverGatewayConfig, FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig,
+        GeneralConfig, ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig,
+        WitnessGeneratorConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, ETHConfig, ETHWatchConfig, GasAdjusterConfig,
     ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
@@ -31,6 +32,14 @@ pub fn decode_yaml_repr<T: ProtoRepr>(yaml: &str) -> anyhow::Result<T::Type> {
     let this: T = zksync_protobuf::serde::deserialize_proto_with_options(d, false)?;
     this.read()
 }
+
+/// Encodes a config to YAML. Fields are emitted in the order of the Protobuf schema, so the output is stable.
+pub fn encode_yaml_repr<T: ProtoRepr>(value: &T::Type) -> anyhow::Result<String> {
+    let mut buffer = vec![];
+    let mut serializer = serde_yaml::Serializer::new(&mut buffer);
+    zksync_protobuf::serde::serialize_proto(&T::build(value), &mut serializer)?;
+    Ok(String::from_utf8(buffer)?)
+}
 //
 // TODO (QIT-22): This structure is going to be removed when components will be responsible for their own configs.
 /// A temporary config store allowing to pass deserialized configs from `zksync_server` to `zksync_core`.
@@ -65,6 +74,8 @@ pub struct TempConfigStore {
     pub object_store_config: Option<ObjectStoreConfig>,
     pub observability: Option<ObservabilityConfig>,
     pub snapshot_creator: Option<SnapshotsCreatorConfig>,
+    pub finality_webhooks_config: Option<FinalityWebhooksConfig>,
+    pub data_retention_config: Option<DataRetentionConfig>,
 }
 
 #[derive(Debug)]
@@ -110,6 +121,8 @@ impl TempConfigStore {
             eth: self.eth_sender_config.clone(),
             snapshot_creator: self.snapshot_creator.clone(),
             observability: self.observability.clone(),
+            finality_webhooks_config: self.finality_webhooks_config.clone(),
+            data_retention_config: self.data_retention_config.clone(),
         }
     }
 
@@ -142,6 +155,9 @@ impl TempConfigStore {
         Wallets {
             eth_sender,
             state_keeper,
+            // The signer wallet is not a part of component configs; it's loaded separately
+            // (see `Wallets::from_env()`).
+            signer: None,
         }
     }
 }
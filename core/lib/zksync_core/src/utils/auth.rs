@@ -0,0 +1,71 @@
+//! Bearer token authorization for admin HTTP servers.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use subtle::ConstantTimeEq as _;
+
+/// Checks whether `headers` contain the `Authorization: Bearer {token}` header. The token is compared
+/// in constant time.
+pub(crate) fn is_bearer_token_valid(headers: &HeaderMap, token: &str) -> bool {
+    let Some(value) = headers.get(header::AUTHORIZATION) else {
+        return false;
+    };
+    let Some(provided_token) = value.as_bytes().strip_prefix(b"Bearer ") else {
+        return false;
+    };
+    provided_token.ct_eq(token.as_bytes()).into()
+}
+
+/// Axum middleware rejecting requests without a valid bearer token with 401 Unauthorized.
+/// Should be applied using [`axum::middleware::from_fn_with_state()`].
+pub(crate) async fn require_bearer_token<B>(
+    State(token): State<Arc<str>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if is_bearer_token_valid(request.headers(), &token) {
+        next.run(request).await
+    } else {
+        tracing::info!(
+            "Rejected unauthorized admin request {} {}",
+            request.method(),
+            request.uri().path()
+        );
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn validating_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_bearer_token_valid(&headers, "token"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer token"),
+        );
+        assert!(is_bearer_token_valid(&headers, "token"));
+        assert!(!is_bearer_token_valid(&headers, "other-token"));
+        assert!(!is_bearer_token_valid(&headers, "toke"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("token"));
+        assert!(!is_bearer_token_valid(&headers, "token"));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic token"),
+        );
+        assert!(!is_bearer_token_valid(&headers, "token"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Miscellaneous utils used by multiple components.
 
 use std::{
+    collections::HashMap,
     future::Future,
     sync::atomic::{AtomicBool, Ordering},
     time::Duration,
@@ -9,18 +10,52 @@ use std::{
 use anyhow::Context as _;
 use async_trait::async_trait;
 use tokio::sync::watch;
-use zksync_config::configs::chain::L1BatchCommitDataGeneratorMode;
+use zksync_config::configs::{
+    chain::L1BatchCommitDataGeneratorMode,
+    database::{RocksdbColumnFamilyConfig, RocksdbCompactionStyle, RocksdbCompression},
+};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface};
 use zksync_l1_contract_interface::Detokenize;
+use zksync_storage::{
+    rocksdb::{DBCompactionStyle, DBCompressionType},
+    ColumnFamilyOptions,
+};
 use zksync_types::{
     ethabi::{self, Address},
     L1BatchNumber, ProtocolVersionId,
 };
 
+pub(crate) mod auth;
 #[cfg(test)]
 pub(crate) mod testonly;
 
+/// Converts RocksDB column family configs from the node config to options keyed by the column family name.
+pub fn rocksdb_column_families(
+    configs: &[RocksdbColumnFamilyConfig],
+) -> HashMap<String, ColumnFamilyOptions> {
+    let column_families = configs.iter().map(|config| {
+        let compaction_style = config.compaction_style.map(|style| match style {
+            RocksdbCompactionStyle::Level => DBCompactionStyle::Level,
+            RocksdbCompactionStyle::Universal => DBCompactionStyle::Universal,
+        });
+        let compression = config.compression.map(|compression| match compression {
+            RocksdbCompression::None => DBCompressionType::None,
+            RocksdbCompression::Snappy => DBCompressionType::Snappy,
+            RocksdbCompression::Lz4 => DBCompressionType::Lz4,
+            RocksdbCompression::Zstd => DBCompressionType::Zstd,
+        });
+        let options = ColumnFamilyOptions {
+            write_buffer_size: config.write_buffer_size(),
+            compaction_style,
+            bloom_filter_bits_per_key: config.bloom_filter_bits_per_key,
+            compression,
+        };
+        (config.name.clone(), options)
+    });
+    column_families.collect()
+}
+
 /// Fallible and async predicate for binary search.
 #[async_trait]
 pub(crate) trait BinarySearchPredicate: Send {
@@ -1,5 +1,5 @@
 //! Test utils.
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use multivm::utils::get_max_gas_per_pubdata_byte;
 use zksync_contracts::BaseSystemContractsHashes;
@@ -40,6 +40,7 @@ pub(crate) fn create_miniblock(number: u32) -> MiniblockHeader {
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 1,
         gas_limit: 0,
+        logs_bloom: Default::default(),
     }
 }
 
@@ -145,6 +146,7 @@ pub(crate) fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionR
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        execution_time: Duration::ZERO,
     }
 }
 
@@ -188,6 +190,7 @@ impl Snapshot {
             protocol_version: Some(genesis_params.protocol_version()),
             virtual_blocks: 1,
             gas_limit: 0,
+            logs_bloom: Default::default(),
         };
         Snapshot {
             l1_batch,
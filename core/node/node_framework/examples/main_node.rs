@@ -117,7 +117,8 @@ impl MainNodeBuilder {
                 .sender
                 .context("eth_sender")?
                 .pubdata_sending_mode,
-        );
+        )
+        .with_gas_price_web3_urls(eth_sender_config.gas_price_web3_urls);
         self.node.add_layer(sequencer_l1_gas_layer);
         Ok(self)
     }
@@ -136,8 +137,11 @@ impl MainNodeBuilder {
             &merkle_tree_env_config,
             &operations_manager_env_config,
         );
-        self.node
-            .add_layer(MetadataCalculatorLayer(metadata_calculator_config));
+        let tree_api_config = ApiConfig::from_env()?.merkle_tree;
+        self.node.add_layer(
+            MetadataCalculatorLayer::new(metadata_calculator_config)
+                .with_tree_api_config(tree_api_config),
+        );
         Ok(self)
     }
 
@@ -193,7 +197,9 @@ impl MainNodeBuilder {
         let wallets = Wallets::from_env()?;
 
         // On main node we always use master pool sink.
-        self.node.add_layer(TxSinkLayer::MasterPoolSink);
+        self.node.add_layer(TxSinkLayer::MasterPoolSink {
+            durability: MempoolConfig::from_env()?.durability,
+        });
         self.node.add_layer(TxSenderLayer::new(
             TxSenderConfig::new(
                 &state_keeper_config,
@@ -228,7 +234,7 @@ impl MainNodeBuilder {
 
         let mut namespaces = Namespace::DEFAULT.to_vec();
         if with_debug_namespace {
-            namespaces.push(Namespace::Debug)
+            namespaces.extend([Namespace::Debug, Namespace::Trace]);
         }
         namespaces.push(Namespace::Snapshots);
 
@@ -238,6 +244,12 @@ impl MainNodeBuilder {
             subscriptions_limit: Some(rpc_config.subscriptions_limit()),
             batch_request_size_limit: Some(rpc_config.max_batch_request_size()),
             response_body_size_limit: Some(rpc_config.max_response_body_size()),
+            method_requests_per_minute_limit: rpc_config.method_requests_per_minute_limit,
+            client_requests_per_minute_limit: rpc_config.client_requests_per_minute_limit,
+            max_concurrent_requests: rpc_config.max_concurrent_requests,
+            request_timeout: rpc_config.request_deadline(),
+            tree_lag_limit: rpc_config.tree_lag_limit,
+            reject_proofs_on_tree_lag: rpc_config.reject_proofs_on_tree_lag,
             ..Default::default()
         };
         self.node.add_layer(Web3ServerLayer::http(
@@ -259,7 +271,7 @@ impl MainNodeBuilder {
 
         let mut namespaces = Namespace::DEFAULT.to_vec();
         if with_debug_namespace {
-            namespaces.push(Namespace::Debug)
+            namespaces.extend([Namespace::Debug, Namespace::Trace]);
         }
         namespaces.push(Namespace::Snapshots);
 
@@ -272,7 +284,13 @@ impl MainNodeBuilder {
             websocket_requests_per_minute_limit: Some(
                 rpc_config.websocket_requests_per_minute_limit(),
             ),
+            method_requests_per_minute_limit: rpc_config.method_requests_per_minute_limit,
+            client_requests_per_minute_limit: rpc_config.client_requests_per_minute_limit,
+            max_concurrent_requests: rpc_config.max_concurrent_requests,
+            request_timeout: rpc_config.request_deadline(),
             replication_lag_limit_sec: circuit_breaker_config.replication_lag_limit_sec,
+            tree_lag_limit: rpc_config.tree_lag_limit,
+            reject_proofs_on_tree_lag: rpc_config.reject_proofs_on_tree_lag,
         };
         self.node.add_layer(Web3ServerLayer::ws(
             rpc_config.ws_port,
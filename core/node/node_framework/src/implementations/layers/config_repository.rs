@@ -0,0 +1,70 @@
+use std::fmt;
+
+use crate::{
+    implementations::resources::config::ConfigResource,
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+type ConfigInserter =
+    Box<dyn FnOnce(&mut ServiceContext<'_>) -> Result<(), WiringError> + Send + Sync>;
+
+/// Central repository of configuration sections for the node. Each section is provided
+/// as a [`ConfigResource`] parameterized by the section type, so that layers can request the configs
+/// they need instead of receiving them from the node builder.
+///
+/// This layer must be added before any layer requesting configs from it.
+///
+/// ## Adds resources
+///
+/// - `ConfigResource<T>` for each added config section
+#[derive(Default)]
+pub struct ConfigRepositoryLayer {
+    configs: Vec<(&'static str, ConfigInserter)>,
+}
+
+impl fmt::Debug for ConfigRepositoryLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let config_types = self.configs.iter().map(|(type_name, _)| type_name);
+        f.debug_struct("ConfigRepositoryLayer")
+            .field("configs", &config_types.collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ConfigRepositoryLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a config section to the repository.
+    pub fn with_config<T: 'static + Send + Sync>(mut self, config: T) -> Self {
+        let inserter: ConfigInserter =
+            Box::new(move |context| context.insert_resource(ConfigResource::new(config)));
+        self.configs.push((std::any::type_name::<T>(), inserter));
+        self
+    }
+
+    /// Adds a config section to the repository if it is present. Layers should treat
+    /// the lack of an optional section as the corresponding functionality being disabled.
+    pub fn with_optional_config<T: 'static + Send + Sync>(self, config: Option<T>) -> Self {
+        match config {
+            Some(config) => self.with_config(config),
+            None => self,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ConfigRepositoryLayer {
+    fn layer_name(&self) -> &'static str {
+        "config_repository_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        for (_, insert_config) in self.configs {
+            insert_config(&mut context)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,408 @@
+use std::{num::NonZeroUsize, sync::Arc};
+
+use anyhow::Context as _;
+use zksync_concurrency::{ctx, limiter, scope, time};
+use zksync_core::{
+    consensus,
+    state_keeper::{seal_criteria::NoopSealer, OutputHandler, StateKeeperPersistence},
+    sync_layer::{
+        batch_status_updater::BatchStatusUpdater, ActionQueue, ExternalIO, ProtocolVersionCheck,
+        SyncState,
+    },
+};
+use zksync_types::{Address, L2ChainId};
+use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
+
+use super::state_keeper::mempool_io::MiniblockSealerTask;
+use crate::{
+    implementations::resources::{
+        action_queue::ActionQueueSenderResource,
+        config::ConfigResource,
+        healthcheck::AppHealthCheckResource,
+        main_node_client::MainNodeClientResource,
+        pools::MasterPoolResource,
+        state_keeper::{ConditionalSealerResource, OutputHandlerResource, StateKeeperIOResource},
+        sync_state::SyncStateResource,
+    },
+    resource::Unique,
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wires the sync stack of the external node: everything needed to replicate blocks from
+/// the main node, except for the state keeper itself (wired by `StateKeeperLayer`) and its batch executor.
+///
+/// ## Requests resources
+///
+/// - `MasterPoolResource`
+/// - `MainNodeClientResource`
+/// - `AppHealthCheckResource` (adds health checks)
+/// - `ConfigResource<consensus::Config>` and `ConfigResource<consensus::Secrets>` (optional; only if
+///   consensus wasn't configured via [`Self::with_consensus()`])
+///
+/// ## Adds resources
+///
+/// - `SyncStateResource`
+/// - `ActionQueueSenderResource` (consumed by the fetcher task)
+/// - `StateKeeperIOResource`
+/// - `OutputHandlerResource`
+/// - `ConditionalSealerResource`
+///
+/// ## Adds tasks
+///
+/// - `FetcherTask`
+/// - `MiniblockSealerTask`
+/// - `BatchStatusUpdaterTask`
+/// - `ProtocolVersionCheckTask`
+#[derive(Debug)]
+pub struct ExternalNodeSyncLayer {
+    l2_chain_id: L2ChainId,
+    l2_erc20_bridge_addr: Address,
+    miniblock_seal_queue_capacity: usize,
+    action_queue_capacity: NonZeroUsize,
+    consensus: Option<(Arc<consensus::Config>, Arc<consensus::Secrets>)>,
+}
+
+impl ExternalNodeSyncLayer {
+    pub fn new(
+        l2_chain_id: L2ChainId,
+        l2_erc20_bridge_addr: Address,
+        miniblock_seal_queue_capacity: usize,
+    ) -> Self {
+        Self {
+            l2_chain_id,
+            l2_erc20_bridge_addr,
+            miniblock_seal_queue_capacity,
+            action_queue_capacity: ActionQueue::DEFAULT_CAPACITY,
+            consensus: None,
+        }
+    }
+
+    /// Sets the maximum number of fetched actions not yet processed by the state keeper.
+    /// If not called, [`ActionQueue::DEFAULT_CAPACITY`] is used.
+    pub fn with_action_queue_capacity(mut self, capacity: NonZeroUsize) -> Self {
+        self.action_queue_capacity = capacity;
+        self
+    }
+
+    /// Makes the node fetch blocks over the consensus gossip network rather than the main node
+    /// JSON-RPC. If not called, consensus configuration is taken from the config resources, if they are provided.
+    pub fn with_consensus(
+        mut self,
+        config: consensus::Config,
+        secrets: consensus::Secrets,
+    ) -> Self {
+        self.consensus = Some((Arc::new(config), Arc::new(secrets)));
+        self
+    }
+
+    async fn consensus_config(
+        &self,
+        context: &mut ServiceContext<'_>,
+    ) -> Result<Option<(Arc<consensus::Config>, Arc<consensus::Secrets>)>, WiringError> {
+        if let Some(consensus) = &self.consensus {
+            return Ok(Some(consensus.clone()));
+        }
+        let config = match context
+            .get_resource::<ConfigResource<consensus::Config>>()
+            .await
+        {
+            Ok(ConfigResource(config)) => config,
+            Err(WiringError::ResourceLacking(_)) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let ConfigResource(secrets) = context
+            .get_resource::<ConfigResource<consensus::Secrets>>()
+            .await?;
+        Ok(Some((config, secrets)))
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ExternalNodeSyncLayer {
+    fn layer_name(&self) -> &'static str {
+        "external_node_sync_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let master_pool = context.get_resource::<MasterPoolResource>().await?;
+        let MainNodeClientResource(main_node_client) = context.get_resource().await?;
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+
+        let p2p = match self.consensus_config(&mut context).await? {
+            Some((config, secrets)) => {
+                config
+                    .validate(&secrets, &[])
+                    .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+                let p2p = config
+                    .p2p(&secrets)
+                    .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+                let admin = config
+                    .admin(&secrets)
+                    .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+                Some((p2p, admin))
+            }
+            None => None,
+        };
+
+        let sync_state = SyncState::default();
+        app_health.insert_custom_component(Arc::new(sync_state.clone()));
+        context.insert_resource(SyncStateResource(sync_state.clone()))?;
+
+        let (action_queue_sender, action_queue) =
+            ActionQueue::with_capacity(self.action_queue_capacity);
+        let action_queue_sender = ActionQueueSenderResource(Unique::new(action_queue_sender));
+        context.insert_resource(action_queue_sender.clone())?;
+
+        // Create state keeper IO.
+        let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
+            master_pool
+                .get_singleton()
+                .await
+                .context("Get master pool")?,
+            self.l2_erc20_bridge_addr,
+            self.miniblock_seal_queue_capacity,
+        );
+        let output_handler = OutputHandler::new(Box::new(persistence.with_tx_insertion()))
+            .with_handler(Box::new(sync_state.clone()));
+        context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
+        context.add_task(Box::new(MiniblockSealerTask(miniblock_sealer)));
+
+        let io = ExternalIO::new(
+            master_pool
+                .get_singleton()
+                .await
+                .context("Get master pool")?,
+            action_queue,
+            Box::new(main_node_client.clone()),
+            self.l2_chain_id,
+        )
+        .await
+        .context("Failed initializing I/O for external node state keeper")?;
+        context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
+        // The external node only replicates blocks sealed by the main node.
+        context.insert_resource(ConditionalSealerResource(Arc::new(NoopSealer)))?;
+
+        // Create fetcher and auxiliary tasks.
+        context.add_task(Box::new(FetcherTask {
+            pool: master_pool.get().await?,
+            main_node_client: main_node_client.clone(),
+            sync_state: sync_state.clone(),
+            action_queue_sender,
+            p2p,
+        }));
+
+        let batch_status_updater = BatchStatusUpdater::new(
+            main_node_client.clone(),
+            master_pool
+                .get_singleton()
+                .await
+                .context("Get master pool")?,
+        );
+        app_health.insert_component(batch_status_updater.health_check());
+        context.add_task(Box::new(BatchStatusUpdaterTask(batch_status_updater)));
+
+        let protocol_version_check =
+            ProtocolVersionCheck::new(Box::new(main_node_client), sync_state);
+        context.add_task(Box::new(ProtocolVersionCheckTask(protocol_version_check)));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct FetcherTask {
+    pool: zksync_dal::ConnectionPool<zksync_dal::Core>,
+    main_node_client: HttpClient,
+    sync_state: SyncState,
+    action_queue_sender: ActionQueueSenderResource,
+    p2p: Option<(consensus::P2PConfig, Option<std::net::SocketAddr>)>,
+}
+
+#[async_trait::async_trait]
+impl Task for FetcherTask {
+    fn name(&self) -> &'static str {
+        "external_node/fetcher"
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let Self {
+            pool,
+            main_node_client,
+            sync_state,
+            action_queue_sender,
+            p2p,
+        } = *self;
+        let actions = action_queue_sender
+            .0
+            .take()
+            .context("ActionQueueSender was provided but taken by another task")?;
+        let ctx = ctx::root();
+        let fetcher = consensus::Fetcher {
+            store: consensus::Store(pool),
+            sync_state,
+            client: Box::new(main_node_client),
+            limiter: limiter::Limiter::new(
+                &ctx,
+                limiter::Rate {
+                    burst: 10,
+                    refresh: time::Duration::milliseconds(30),
+                },
+            ),
+        };
+        scope::run!(&ctx, |ctx, s| async {
+            s.spawn_bg(async {
+                let res = match p2p {
+                    Some((p2p, admin)) => fetcher.run_p2p(ctx, actions, p2p, admin).await,
+                    None => fetcher.run_centralized(ctx, actions).await,
+                };
+                tracing::info!("Consensus actor stopped");
+                res
+            });
+            ctx.wait(stop_receiver.0.wait_for(|stop| *stop)).await??;
+            Ok(())
+        })
+        .await
+        .context("consensus actor")
+    }
+}
+
+#[derive(Debug)]
+struct BatchStatusUpdaterTask(BatchStatusUpdater);
+
+#[async_trait::async_trait]
+impl Task for BatchStatusUpdaterTask {
+    fn name(&self) -> &'static str {
+        "external_node/batch_status_updater"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
+
+#[derive(Debug)]
+struct ProtocolVersionCheckTask(ProtocolVersionCheck);
+
+#[async_trait::async_trait]
+impl Task for ProtocolVersionCheckTask {
+    fn name(&self) -> &'static str {
+        "external_node/protocol_version_check"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::{Core, CoreDal};
+    use zksync_db_connection::connection_pool::TestTemplate;
+    use zksync_types::{
+        block::MiniblockHasher, snapshots::SnapshotRecoveryStatus, L1BatchNumber, MiniblockNumber,
+        ProtocolVersionId, H256,
+    };
+    use zksync_web3_decl::jsonrpsee::http_client::HttpClientBuilder;
+
+    use super::*;
+    use crate::{resource::Resource, service::ZkStackServiceBuilder};
+
+    /// Provides resources required by [`ExternalNodeSyncLayer`] backed by a test database.
+    #[derive(Debug)]
+    struct TestEnvLayer;
+
+    #[async_trait::async_trait]
+    impl WiringLayer for TestEnvLayer {
+        fn layer_name(&self) -> &'static str {
+            "test_env_layer"
+        }
+
+        async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+            let pool_builder = TestTemplate::empty()?.create_db::<Core>(10).await?;
+            let pool = pool_builder.build().await?;
+            // Emulate a node recovered from genesis, so that the state keeper I/O can be initialized.
+            let recovery_status = SnapshotRecoveryStatus {
+                l1_batch_number: L1BatchNumber(0),
+                l1_batch_root_hash: H256::zero(),
+                l1_batch_timestamp: 0,
+                miniblock_number: MiniblockNumber(0),
+                miniblock_hash: MiniblockHasher::legacy_hash(MiniblockNumber(0)),
+                miniblock_timestamp: 0,
+                protocol_version: ProtocolVersionId::default(),
+                storage_logs_chunks_processed: vec![],
+            };
+            pool.connection()
+                .await?
+                .snapshot_recovery_dal()
+                .insert_initial_recovery_status(&recovery_status)
+                .await
+                .context("failed inserting snapshot recovery status")?;
+            pool.close().await;
+
+            let main_node_client = HttpClientBuilder::default()
+                .build("http://127.0.0.1:3050")
+                .context("failed building main node client")?;
+            context.insert_resource(MasterPoolResource::new(pool_builder))?;
+            context.insert_resource(MainNodeClientResource(main_node_client))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn external_node_sync_layer_registers_resources_and_tasks() {
+        let mut service = ZkStackServiceBuilder::new();
+        service
+            .add_layer(TestEnvLayer)
+            .add_layer(ExternalNodeSyncLayer::new(
+                L2ChainId::default(),
+                Address::repeat_byte(1),
+                10,
+            ));
+        let mut service = service.build().unwrap();
+        service.wire().unwrap();
+
+        let snapshot = service.introspection().snapshot();
+        let layer = snapshot
+            .layers
+            .iter()
+            .find(|layer| layer.name == "external_node_sync_layer")
+            .unwrap();
+        assert!(layer.error.is_none(), "{layer:?}");
+        let expected_resources = [
+            SyncStateResource::resource_id(),
+            ActionQueueSenderResource::resource_id(),
+            StateKeeperIOResource::resource_id(),
+            OutputHandlerResource::resource_id(),
+            ConditionalSealerResource::resource_id(),
+        ];
+        for id in expected_resources {
+            assert!(
+                layer.provided_resources.contains(&id.to_string()),
+                "{id} is not provided: {layer:?}"
+            );
+        }
+        // Consensus wasn't configured, so its config is requested from the resources.
+        let consensus_config_id = ConfigResource::<consensus::Config>::resource_id();
+        assert!(layer
+            .requested_resources
+            .contains(&consensus_config_id.to_string()));
+
+        let mut task_names: Vec<_> = snapshot
+            .tasks
+            .iter()
+            .filter(|task| task.layer == layer.name)
+            .map(|task| task.name)
+            .collect();
+        task_names.sort_unstable();
+        assert_eq!(
+            task_names,
+            [
+                "external_node/batch_status_updater",
+                "external_node/fetcher",
+                "external_node/protocol_version_check",
+                "state_keeper/miniblock_sealer",
+            ]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::Context as _;
+use axum::{extract::State, routing::get, Json, Router};
+
+use crate::{
+    service::{ServiceContext, ServiceIntrospection, ServiceSnapshot, StopReceiver},
+    task::UnconstrainedTask,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for an HTTP server exposing the wiring graph of the node and the live status of its tasks
+/// on the `/debug/framework` endpoint. Useful to debug misconfigured node assemblies.
+///
+/// ## Effects
+///
+/// - Adds `framework_introspection_server` to the node.
+#[derive(Debug)]
+pub struct FrameworkIntrospectionLayer {
+    bind_addr: SocketAddr,
+}
+
+impl FrameworkIntrospectionLayer {
+    pub fn new(bind_addr: SocketAddr) -> Self {
+        Self { bind_addr }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for FrameworkIntrospectionLayer {
+    fn layer_name(&self) -> &'static str {
+        "framework_introspection_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        let task = FrameworkIntrospectionTask {
+            bind_addr: self.bind_addr,
+            introspection: node.introspection(),
+        };
+        // The server should be available even if some preconditions are never met, since
+        // this is exactly the situation it helps to debug.
+        node.add_unconstrained_task(Box::new(task));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct FrameworkIntrospectionTask {
+    bind_addr: SocketAddr,
+    introspection: ServiceIntrospection,
+}
+
+async fn get_snapshot(introspection: State<ServiceIntrospection>) -> Json<ServiceSnapshot> {
+    Json(introspection.snapshot())
+}
+
+#[async_trait::async_trait]
+impl UnconstrainedTask for FrameworkIntrospectionTask {
+    fn name(&self) -> &'static str {
+        "framework_introspection_server"
+    }
+
+    async fn run_unconstrained(
+        self: Box<Self>,
+        mut stop_receiver: StopReceiver,
+    ) -> anyhow::Result<()> {
+        // `hyper` server may be slow to shut down if it isn't queried; see `HealthCheckHandle::stop()`.
+        const GRACEFUL_SHUTDOWN_WAIT: Duration = Duration::from_secs(10);
+
+        let app = Router::new()
+            .route("/debug/framework", get(get_snapshot))
+            .with_state(self.introspection);
+        let server = axum::Server::try_bind(&self.bind_addr)
+            .with_context(|| format!("Failed binding introspection server to {}", self.bind_addr))?
+            .serve(app.into_make_service());
+        tracing::info!(
+            "Started framework introspection server on {}",
+            self.bind_addr
+        );
+
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_receiver.await.ok();
+        }));
+        stop_receiver.0.changed().await?;
+        tracing::info!("Stop signal received, framework introspection server is shutting down");
+        shutdown_sender.send(()).ok();
+        match tokio::time::timeout(GRACEFUL_SHUTDOWN_WAIT, server).await {
+            Ok(server_result) => server_result
+                .context("Framework introspection server panicked")?
+                .context("Framework introspection server failed")?,
+            Err(_) => tracing::debug!(
+                "Timed out {GRACEFUL_SHUTDOWN_WAIT:?} waiting for introspection server to shut down"
+            ),
+        }
+        Ok(())
+    }
+}
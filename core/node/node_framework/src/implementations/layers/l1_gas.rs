@@ -10,8 +10,12 @@ use zksync_config::{
 };
 use zksync_core::{
     fee_model::MainNodeFeeInputProvider,
-    l1_gas_price::{GasAdjuster, PubdataPricing, RollupPubdataPricing, ValidiumPubdataPricing},
+    l1_gas_price::{
+        CompositeL1GasPriceProvider, GasAdjuster, PubdataPricing, RollupPubdataPricing,
+        ValidiumPubdataPricing,
+    },
 };
+use zksync_eth_client::{clients::QueryClient, EthInterface};
 use zksync_types::fee_model::FeeModelConfig;
 
 use crate::{
@@ -20,7 +24,7 @@ use crate::{
         l1_tx_params::L1TxParamsResource,
     },
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{Backoff, RestartPolicy, Task},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -30,6 +34,7 @@ pub struct SequencerL1GasLayer {
     genesis_config: GenesisConfig,
     pubdata_sending_mode: PubdataSendingMode,
     state_keeper_config: StateKeeperConfig,
+    gas_price_web3_urls: Vec<String>,
 }
 
 impl SequencerL1GasLayer {
@@ -44,8 +49,16 @@ impl SequencerL1GasLayer {
             genesis_config,
             pubdata_sending_mode,
             state_keeper_config,
+            gas_price_web3_urls: vec![],
         }
     }
+
+    /// Sets additional L1 RPC URLs to aggregate fee data from together with the L1 client
+    /// provided as a resource.
+    pub fn with_gas_price_web3_urls(mut self, urls: Vec<String>) -> Self {
+        self.gas_price_web3_urls = urls;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -60,7 +73,18 @@ impl WiringLayer for SequencerL1GasLayer {
                 L1BatchCommitDataGeneratorMode::Rollup => Arc::new(RollupPubdataPricing {}),
                 L1BatchCommitDataGeneratorMode::Validium => Arc::new(ValidiumPubdataPricing {}),
             };
-        let client = context.get_resource::<EthInterfaceResource>().await?.0;
+        let mut client = context.get_resource::<EthInterfaceResource>().await?.0;
+        if !self.gas_price_web3_urls.is_empty() {
+            let mut clients: Vec<Arc<dyn EthInterface>> = vec![client];
+            for url in &self.gas_price_web3_urls {
+                let additional_client = QueryClient::new(url).context("QueryClient::new()")?;
+                clients.push(Arc::new(additional_client));
+            }
+            client = Arc::new(
+                CompositeL1GasPriceProvider::new(clients, &self.gas_adjuster_config)
+                    .context("CompositeL1GasPriceProvider::new()")?,
+            );
+        }
         let adjuster = GasAdjuster::new(
             client,
             self.gas_adjuster_config,
@@ -84,7 +108,7 @@ impl WiringLayer for SequencerL1GasLayer {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct GasAdjusterTask {
     gas_adjuster: Arc<GasAdjuster>,
 }
@@ -95,6 +119,15 @@ impl Task for GasAdjusterTask {
         "gas_adjuster"
     }
 
+    // Failures to fetch gas prices from L1 are usually transient, and shouldn't take the sequencer down.
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::OnFailure(Backoff::default())
+    }
+
+    fn respawn(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         self.gas_adjuster.run(stop_receiver.0).await
     }
@@ -0,0 +1,33 @@
+use anyhow::Context;
+use zksync_core::sync_layer::MainNodeClient;
+
+use crate::{
+    implementations::resources::main_node_client::MainNodeClientResource,
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+#[derive(Debug)]
+pub struct MainNodeClientLayer {
+    url: String,
+}
+
+impl MainNodeClientLayer {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for MainNodeClientLayer {
+    fn layer_name(&self) -> &'static str {
+        "main_node_client_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let client = <dyn MainNodeClient>::json_rpc(&self.url)
+            .context("Failed creating JSON-RPC client for main node")?;
+        context.insert_resource(MainNodeClientResource(client))?;
+        Ok(())
+    }
+}
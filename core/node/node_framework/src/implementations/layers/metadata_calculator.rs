@@ -1,11 +1,18 @@
-use zksync_core::metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig};
+use std::net::{Ipv4Addr, SocketAddr};
+
+use zksync_config::configs::api::MerkleTreeApiConfig;
+use zksync_core::metadata_calculator::{
+    MetadataCalculator, MetadataCalculatorConfig, TreeReaderPool,
+};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_storage::RocksDB;
 
 use crate::{
     implementations::resources::{
-        healthcheck::AppHealthCheckResource, object_store::ObjectStoreResource,
+        healthcheck::AppHealthCheckResource,
+        object_store::ObjectStoreResource,
         pools::MasterPoolResource,
+        web3_api::{TreeLagResource, TreeReaderPoolResource},
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -19,13 +26,35 @@ use crate::{
 /// - Resolves `MasterPoolResource`.
 /// - Resolves `ObjectStoreResource` (optional).
 /// - Adds `tree_health_check` to the `ResourceCollection<HealthCheckResource>`.
+/// - Adds the proof data follower health check, if the follower is enabled.
+/// - Adds `TreeLagResource` to the resources.
+/// - Adds `TreeReaderPoolResource` to the resources. The pool size is taken from the tree API config, if any.
 /// - Adds `metadata_calculator` to the node.
+/// - Adds `tree_api_server` to the node, if the tree API config is provided.
 #[derive(Debug)]
-pub struct MetadataCalculatorLayer(pub MetadataCalculatorConfig);
+pub struct MetadataCalculatorLayer {
+    config: MetadataCalculatorConfig,
+    tree_api_config: Option<MerkleTreeApiConfig>,
+}
+
+impl MetadataCalculatorLayer {
+    pub fn new(config: MetadataCalculatorConfig) -> Self {
+        Self {
+            config,
+            tree_api_config: None,
+        }
+    }
+
+    pub fn with_tree_api_config(mut self, tree_api_config: MerkleTreeApiConfig) -> Self {
+        self.tree_api_config = Some(tree_api_config);
+        self
+    }
+}
 
 #[derive(Debug)]
 pub struct MetadataCalculatorTask {
     metadata_calculator: MetadataCalculator,
+    tree_reader_pool: TreeReaderPool,
     main_pool: ConnectionPool<Core>,
 }
 
@@ -47,13 +76,33 @@ impl WiringLayer for MetadataCalculatorLayer {
         }
 
         let metadata_calculator =
-            MetadataCalculator::new(self.0, object_store.map(|os| os.0)).await?;
+            MetadataCalculator::new(self.config, object_store.map(|os| os.0)).await?;
 
         let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
         app_health.insert_component(metadata_calculator.tree_health_check());
+        if let Some(follower_health_check) = metadata_calculator.proof_data_follower_health_check()
+        {
+            app_health.insert_component(follower_health_check);
+        }
+        context.insert_resource(TreeLagResource(metadata_calculator.tree_lag()))?;
+        let reader_pool_size = self
+            .tree_api_config
+            .as_ref()
+            .map_or_else(MerkleTreeApiConfig::default_reader_pool_size, |config| {
+                config.reader_pool_size
+            });
+        let tree_reader_pool = metadata_calculator.tree_reader_pool(reader_pool_size);
+        context.insert_resource(TreeReaderPoolResource(tree_reader_pool.clone()))?;
+        if let Some(tree_api_config) = &self.tree_api_config {
+            context.add_task(Box::new(TreeApiTask {
+                bind_addr: (Ipv4Addr::UNSPECIFIED, tree_api_config.port).into(),
+                tree_reader_pool: tree_reader_pool.clone(),
+            }));
+        }
 
         let task = Box::new(MetadataCalculatorTask {
             metadata_calculator,
+            tree_reader_pool,
             main_pool,
         });
         context.add_task(task);
@@ -72,6 +121,8 @@ impl Task for MetadataCalculatorTask {
             .metadata_calculator
             .run(self.main_pool, stop_receiver.0)
             .await;
+        // Pooled readers reference the tree RocksDB instance, so the pool must be closed before awaiting termination.
+        self.tree_reader_pool.close().await;
 
         // Wait for all the instances of RocksDB to be destroyed.
         tokio::task::spawn_blocking(RocksDB::await_rocksdb_termination)
@@ -81,3 +132,22 @@ impl Task for MetadataCalculatorTask {
         result
     }
 }
+
+#[derive(Debug)]
+pub struct TreeApiTask {
+    bind_addr: SocketAddr,
+    tree_reader_pool: TreeReaderPool,
+}
+
+#[async_trait::async_trait]
+impl Task for TreeApiTask {
+    fn name(&self) -> &'static str {
+        "tree_api"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.tree_reader_pool
+            .run_api_server(self.bind_addr, stop_receiver.0)
+            .await
+    }
+}
@@ -2,7 +2,9 @@ use zksync_config::ObjectStoreConfig;
 use zksync_object_store::ObjectStoreFactory;
 
 use crate::{
-    implementations::resources::object_store::ObjectStoreResource,
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource, object_store::ObjectStoreResource,
+    },
     service::ServiceContext,
     wiring_layer::{WiringError, WiringLayer},
 };
@@ -25,8 +27,11 @@ impl WiringLayer for ObjectStoreLayer {
     }
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
-        let object_store = ObjectStoreFactory::new(self.config).create_store().await;
-        context.insert_resource(ObjectStoreResource(object_store))?;
+        let factory = ObjectStoreFactory::new(self.config);
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health.insert_component(factory.health_check());
+        let object_store = factory.create_store().await;
+        context.insert_resource(ObjectStoreResource::new(object_store))?;
         Ok(())
     }
 }
@@ -79,16 +79,33 @@ impl WiringLayer for PoolsLayer {
                 self.config.master_url()?,
                 self.config.max_connections()?,
             );
-            master_pool.set_statement_timeout(self.config.statement_timeout());
+            master_pool
+                .set_statement_timeout(self.config.statement_timeout())
+                .set_schema(self.config.schema.clone());
             context.insert_resource(MasterPoolResource::new(master_pool))?;
         }
 
         if self.with_replica {
-            let mut replica_pool = ConnectionPool::<Core>::builder(
-                self.config.replica_url()?,
-                self.config.max_connections()?,
-            );
-            replica_pool.set_statement_timeout(self.config.statement_timeout());
+            let mut replica_pool = if let Some(max_lag) = self.config.max_replica_lag() {
+                // Read connections are served by the replica unless it lags behind; other connections
+                // use the master DB.
+                let mut builder = ConnectionPool::<Core>::builder(
+                    self.config.master_url()?,
+                    self.config.max_connections()?,
+                );
+                builder
+                    .set_replica_url(Some(self.config.replica_url()?.to_owned()))
+                    .set_max_replica_lag(Some(max_lag));
+                builder
+            } else {
+                ConnectionPool::<Core>::builder(
+                    self.config.replica_url()?,
+                    self.config.max_connections()?,
+                )
+            };
+            replica_pool
+                .set_statement_timeout(self.config.statement_timeout())
+                .set_schema(self.config.schema.clone());
             context.insert_resource(ReplicaPoolResource::new(replica_pool))?;
         }
 
@@ -97,7 +114,9 @@ impl WiringLayer for PoolsLayer {
                 self.config.prover_url()?,
                 self.config.max_connections()?,
             );
-            prover_pool.set_statement_timeout(self.config.statement_timeout());
+            prover_pool
+                .set_statement_timeout(self.config.statement_timeout())
+                .set_schema(self.config.schema.clone());
             context.insert_resource(ProverPoolResource::new(prover_pool))?;
         }
 
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use zksync_config::{configs::chain::StateKeeperConfig, DBConfig};
-use zksync_core::state_keeper::{AsyncCatchupTask, AsyncRocksdbCache, MainBatchExecutor};
+use zksync_core::state_keeper::{
+    shadow_vm_version, state_keeper_rocksdb_caches, state_keeper_rocksdb_options, AsyncCatchupTask,
+    AsyncRocksdbCache, MainBatchExecutor,
+};
 
 use crate::{
     implementations::resources::{pools::MasterPoolResource, state_keeper::BatchExecutorResource},
@@ -34,17 +37,29 @@ impl WiringLayer for MainBatchExecutorLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
+        let shadow_vm_version = shadow_vm_version(&self.state_keeper_config)
+            .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
 
+        let state_keeper_db_options = state_keeper_rocksdb_options(&self.db_config);
+        let state_keeper_db_caches = state_keeper_rocksdb_caches(&self.db_config);
         let (storage_factory, task) = AsyncRocksdbCache::new(
             master_pool.get_singleton().await?,
             self.db_config.state_keeper_db_path,
+            state_keeper_db_options,
             self.state_keeper_config.enum_index_migration_chunk_size(),
         );
-        let builder = MainBatchExecutor::new(
+        let task = task.with_caches(
+            state_keeper_db_caches,
+            self.db_config.state_keeper_db_cache_warm_up_l1_batches,
+        );
+        let mut builder = MainBatchExecutor::new(
             Arc::new(storage_factory),
             self.state_keeper_config.save_call_traces,
             false,
         );
+        if let Some(vm_version) = shadow_vm_version {
+            builder = builder.with_shadow_execution(vm_version, master_pool.get().await?);
+        }
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
         context.add_task(Box::new(RocksdbCatchupTask(task)));
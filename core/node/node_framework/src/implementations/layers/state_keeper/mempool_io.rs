@@ -9,8 +9,8 @@ use zksync_config::{
     ContractsConfig,
 };
 use zksync_core::state_keeper::{
-    self, MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler, SequencerSealer,
-    StateKeeperPersistence,
+    self, MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler, ProtectiveReadsWriter,
+    SequencerSealer, StateKeeperPersistence, TxExecutionMetricsRecorder,
 };
 
 use crate::{
@@ -63,7 +63,9 @@ impl MempoolIOLayer {
             .connection()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity)
+            .await
+            .with_max_nonce_gap(self.mempool_config.max_nonce_gap);
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -81,7 +83,7 @@ impl WiringLayer for MempoolIOLayer {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
 
         // Create miniblock sealer task.
-        let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
+        let (mut persistence, miniblock_sealer) = StateKeeperPersistence::new(
             master_pool
                 .get_singleton()
                 .await
@@ -89,8 +91,39 @@ impl WiringLayer for MempoolIOLayer {
             self.contracts_config.l2_erc20_bridge_addr,
             self.state_keeper_config.miniblock_seal_queue_capacity,
         );
-        let output_handler = OutputHandler::new(Box::new(persistence));
+        if self.state_keeper_config.offload_protective_reads {
+            let writer;
+            (persistence, writer) = persistence.with_offloaded_protective_reads(
+                master_pool.get_custom(2).await.context("Get master pool")?,
+                self.network_config.zksync_network_id,
+                &self.state_keeper_config,
+            );
+            context.add_task(Box::new(ProtectiveReadsWriterTask(writer)));
+        }
+        let mut output_handler = OutputHandler::new(Box::new(persistence));
+        if let Some(sampling_rate) = self.state_keeper_config.tx_execution_metrics_sampling_rate {
+            let recorder = TxExecutionMetricsRecorder::new(
+                master_pool
+                    .get_singleton()
+                    .await
+                    .context("Get master pool")?,
+                sampling_rate,
+                self.state_keeper_config
+                    .tx_execution_metrics_retention_miniblocks(),
+            );
+            output_handler = output_handler.with_handler(Box::new(recorder));
+        }
         context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
+        let miniblock_sealer = match self
+            .state_keeper_config
+            .miniblock_seal_batch_latency_target()
+        {
+            Some(latency_target) => miniblock_sealer.with_adaptive_batching(
+                latency_target,
+                self.state_keeper_config.miniblock_seal_batch_max_delay(),
+            ),
+            None => miniblock_sealer,
+        };
         context.add_task(Box::new(MiniblockSealerTask(miniblock_sealer)));
 
         // Create mempool fetcher task.
@@ -125,7 +158,8 @@ impl WiringLayer for MempoolIOLayer {
         context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
 
         // Create sealer.
-        let sealer = SequencerSealer::new(self.state_keeper_config);
+        let sealer = SequencerSealer::new(self.state_keeper_config)
+            .context("failed creating sequencer sealer")?;
         context.insert_resource(ConditionalSealerResource(Arc::new(sealer)))?;
 
         Ok(())
@@ -133,7 +167,7 @@ impl WiringLayer for MempoolIOLayer {
 }
 
 #[derive(Debug)]
-struct MiniblockSealerTask(state_keeper::MiniblockSealerTask);
+pub(crate) struct MiniblockSealerTask(pub(crate) state_keeper::MiniblockSealerTask);
 
 #[async_trait::async_trait]
 impl Task for MiniblockSealerTask {
@@ -147,6 +181,20 @@ impl Task for MiniblockSealerTask {
     }
 }
 
+#[derive(Debug)]
+struct ProtectiveReadsWriterTask(ProtectiveReadsWriter);
+
+#[async_trait::async_trait]
+impl Task for ProtectiveReadsWriterTask {
+    fn name(&self) -> &'static str {
+        "state_keeper/protective_reads_writer"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
+
 #[derive(Debug)]
 struct MempoolFetcherTask(MempoolFetcher);
 
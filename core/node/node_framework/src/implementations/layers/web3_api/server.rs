@@ -1,16 +1,21 @@
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
 
 use tokio::{sync::oneshot, task::JoinHandle};
 use zksync_circuit_breaker::replication_lag::ReplicationLagChecker;
-use zksync_core::api_server::web3::{state::InternalApiConfig, ApiBuilder, ApiServer, Namespace};
+use zksync_core::api_server::{
+    tree::TreeApiClient,
+    web3::{state::InternalApiConfig, ApiBuilder, ApiServer, Namespace},
+};
 
 use crate::{
     implementations::resources::{
         circuit_breakers::CircuitBreakersResource,
         healthcheck::AppHealthCheckResource,
         pools::ReplicaPoolResource,
-        sync_state::SyncStateResource,
-        web3_api::{TreeApiClientResource, TxSenderResource},
+        sync_state::{NodeStateResource, SyncStateResource},
+        web3_api::{
+            TreeApiClientResource, TreeLagResource, TreeReaderPoolResource, TxSenderResource,
+        },
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -26,8 +31,15 @@ pub struct Web3ServerOptionalConfig {
     pub batch_request_size_limit: Option<usize>,
     pub response_body_size_limit: Option<usize>,
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    pub method_requests_per_minute_limit: Option<NonZeroU32>,
+    pub client_requests_per_minute_limit: Option<NonZeroU32>,
+    pub max_concurrent_requests: Option<usize>,
+    pub request_timeout: Option<Duration>,
     // used by circuit breaker.
     pub replication_lag_limit_sec: Option<u32>,
+    /// Maximum acceptable Merkle tree lag in L1 batches. Requires `TreeLagResource` to be provided.
+    pub tree_lag_limit: Option<u32>,
+    pub reject_proofs_on_tree_lag: bool,
 }
 
 impl Web3ServerOptionalConfig {
@@ -52,6 +64,20 @@ impl Web3ServerOptionalConfig {
             api_builder = api_builder
                 .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
         }
+        if let Some(method_requests_per_minute_limit) = self.method_requests_per_minute_limit {
+            api_builder =
+                api_builder.with_method_requests_per_minute_limit(method_requests_per_minute_limit);
+        }
+        if let Some(client_requests_per_minute_limit) = self.client_requests_per_minute_limit {
+            api_builder =
+                api_builder.with_client_requests_per_minute_limit(client_requests_per_minute_limit);
+        }
+        if let Some(max_concurrent_requests) = self.max_concurrent_requests {
+            api_builder = api_builder.with_max_concurrent_requests(max_concurrent_requests);
+        }
+        if let Some(request_timeout) = self.request_timeout {
+            api_builder = api_builder.with_request_timeout(request_timeout);
+        }
         api_builder
     }
 }
@@ -119,11 +145,30 @@ impl WiringLayer for Web3ServerLayer {
             Err(WiringError::ResourceLacking(_)) => None,
             Err(err) => return Err(err),
         };
+        let node_state = match context.get_resource::<NodeStateResource>().await {
+            Ok(node_state) => Some(node_state.0),
+            Err(WiringError::ResourceLacking(_)) => None,
+            Err(err) => return Err(err),
+        };
         let tree_api_client = match context.get_resource::<TreeApiClientResource>().await {
             Ok(client) => Some(client.0),
             Err(WiringError::ResourceLacking(_)) => None,
             Err(err) => return Err(err),
         };
+        // If there's no remote tree API, serve tree requests from the local tree (if any).
+        let tree_api_client = match tree_api_client {
+            Some(client) => Some(client),
+            None => match context.get_resource::<TreeReaderPoolResource>().await {
+                Ok(pool) => Some(Arc::new(pool.0) as Arc<dyn TreeApiClient>),
+                Err(WiringError::ResourceLacking(_)) => None,
+                Err(err) => return Err(err),
+            },
+        };
+        let tree_lag = match context.get_resource::<TreeLagResource>().await {
+            Ok(tree_lag) => Some(tree_lag.0),
+            Err(WiringError::ResourceLacking(_)) => None,
+            Err(err) => return Err(err),
+        };
 
         // Build server.
         let mut api_builder =
@@ -144,6 +189,19 @@ impl WiringLayer for Web3ServerLayer {
         if let Some(sync_state) = sync_state {
             api_builder = api_builder.with_sync_state(sync_state);
         }
+        if let Some(node_state) = node_state {
+            api_builder = api_builder.with_node_state(node_state);
+        }
+        if let Some(max_lag) = self.optional_config.tree_lag_limit {
+            if let Some(tree_lag) = tree_lag {
+                let reject_proofs = self.optional_config.reject_proofs_on_tree_lag;
+                api_builder = api_builder.with_tree_lag_limit(tree_lag, max_lag, reject_proofs);
+            } else {
+                tracing::warn!(
+                    "Tree lag limit is set, but the Merkle tree doesn't run in this process; the limit is ignored"
+                );
+            }
+        }
         let replication_lag_limit_sec = self.optional_config.replication_lag_limit_sec;
         api_builder = self.optional_config.apply(api_builder);
         let server = api_builder.build()?;
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use zksync_config::configs::chain::MempoolDurability;
 use zksync_core::api_server::tx_sender::{master_pool_sink::MasterPoolSink, proxy::TxProxy};
 use zksync_web3_decl::jsonrpsee::http_client::{transport::HttpBackend, HttpClient};
 
@@ -12,7 +13,7 @@ use crate::{
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum TxSinkLayer {
-    MasterPoolSink,
+    MasterPoolSink { durability: MempoolDurability },
     ProxySink { main_node_url: String },
 }
 
@@ -24,13 +25,14 @@ impl WiringLayer for TxSinkLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let tx_sink = match self.as_ref() {
-            TxSinkLayer::MasterPoolSink => {
+            TxSinkLayer::MasterPoolSink { durability } => {
                 let pool = context
                     .get_resource::<MasterPoolResource>()
                     .await?
                     .get()
                     .await?;
-                TxSinkResource(Arc::new(MasterPoolSink::new(pool)))
+                let sink = MasterPoolSink::new(pool).with_durability(*durability);
+                TxSinkResource(Arc::new(sink))
             }
             TxSinkLayer::ProxySink { main_node_url } => {
                 let client = HttpClient::<HttpBackend>::builder()
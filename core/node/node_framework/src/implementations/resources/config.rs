@@ -0,0 +1,36 @@
+use std::{fmt, sync::Arc};
+
+use crate::resource::{Resource, ResourceId};
+
+/// Strongly-typed configuration section provided by [`ConfigRepositoryLayer`].
+///
+/// Allows layers to obtain their configuration from the node assembly rather than from their constructor,
+/// so that the same layer can be reused across binaries with different config sources (e.g., the main node
+/// and the external node). The config is wrapped in an `Arc`, so config types are not required to be `Clone`.
+///
+/// [`ConfigRepositoryLayer`]: crate::implementations::layers::config_repository::ConfigRepositoryLayer
+pub struct ConfigResource<T>(pub Arc<T>);
+
+impl<T> ConfigResource<T> {
+    pub fn new(config: T) -> Self {
+        Self(Arc::new(config))
+    }
+}
+
+impl<T> Clone for ConfigResource<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConfigResource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConfigResource").field(&self.0).finish()
+    }
+}
+
+impl<T: 'static + Send + Sync> Resource for ConfigResource<T> {
+    fn resource_id() -> ResourceId {
+        ResourceId::new("config") + ResourceId::new(std::any::type_name::<T>())
+    }
+}
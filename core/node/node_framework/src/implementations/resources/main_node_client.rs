@@ -0,0 +1,12 @@
+use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
+
+use crate::resource::{Resource, ResourceId};
+
+#[derive(Debug, Clone)]
+pub struct MainNodeClientResource(pub HttpClient);
+
+impl Resource for MainNodeClientResource {
+    fn resource_id() -> ResourceId {
+        "external_node/main_node_client".into()
+    }
+}
@@ -1,15 +1,87 @@
 use std::sync::Arc;
 
-use zksync_object_store::ObjectStore;
+use tokio::sync::RwLock;
+use zksync_object_store::{Bucket, ChunkStream, ObjectStore, ObjectStoreError};
 
 use crate::resource::Resource;
 
 /// Wrapper for the object store.
+///
+/// Requests to the wrapped store are tracked, so that the service can wait for requests in flight
+/// (e.g., uploads started by tasks that didn't stop in time) to complete once it stops.
 #[derive(Debug, Clone)]
-pub struct ObjectStoreResource(pub Arc<dyn ObjectStore>);
+pub struct ObjectStoreResource(pub Arc<dyn ObjectStore>, Arc<RwLock<()>>);
 
+impl ObjectStoreResource {
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        let requests = Arc::new(RwLock::new(()));
+        let store = TrackedObjectStore {
+            inner: store,
+            requests: requests.clone(),
+        };
+        Self(Arc::new(store), requests)
+    }
+}
+
+#[async_trait::async_trait]
 impl Resource for ObjectStoreResource {
     fn resource_id() -> crate::resource::ResourceId {
         "common/object_store".into()
     }
+
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        // Each request holds a read lock, so acquiring a write lock waits for all requests in flight.
+        let _guard = self.1.write().await;
+        tracing::info!("All object store requests have completed");
+        Ok(())
+    }
+}
+
+/// [`ObjectStore`] holding a read lock for the duration of each request.
+#[derive(Debug)]
+struct TrackedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    requests: Arc<RwLock<()>>,
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for TrackedObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let _guard = self.requests.read().await;
+        self.inner.get_raw(bucket, key).await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let _guard = self.requests.read().await;
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    async fn put_stream_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        chunks: ChunkStream<'_>,
+    ) -> Result<(), ObjectStoreError> {
+        let _guard = self.requests.read().await;
+        self.inner.put_stream_raw(bucket, key, chunks).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let _guard = self.requests.read().await;
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    async fn archive_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let _guard = self.requests.read().await;
+        self.inner.archive_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
 }
@@ -1,11 +1,11 @@
 use std::sync::{
     atomic::{AtomicU32, Ordering},
-    Arc,
+    Arc, Mutex,
 };
 
 use prover_dal::Prover;
 use zksync_dal::{ConnectionPool, Core};
-use zksync_db_connection::connection_pool::ConnectionPoolBuilder;
+use zksync_db_connection::connection_pool::{ConnectionPoolBuilder, DbMarker};
 
 use crate::resource::Resource;
 
@@ -14,12 +14,19 @@ use crate::resource::Resource;
 pub struct MasterPoolResource {
     connections_count: Arc<AtomicU32>,
     builder: ConnectionPoolBuilder<Core>,
+    created_pools: CreatedPools<Core>,
 }
 
+#[async_trait::async_trait]
 impl Resource for MasterPoolResource {
     fn resource_id() -> crate::resource::ResourceId {
         "common/master_pool".into()
     }
+
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        self.created_pools.close_all("master").await;
+        Ok(())
+    }
 }
 
 impl MasterPoolResource {
@@ -27,11 +34,13 @@ impl MasterPoolResource {
         Self {
             connections_count: Arc::new(AtomicU32::new(0)),
             builder,
+            created_pools: CreatedPools::default(),
         }
     }
 
     pub async fn get(&self) -> anyhow::Result<ConnectionPool<Core>> {
         let result = self.builder.build().await;
+        self.created_pools.track(&result);
 
         if result.is_ok() {
             self.connections_count
@@ -51,6 +60,7 @@ impl MasterPoolResource {
 
     pub async fn get_custom(&self, size: u32) -> anyhow::Result<ConnectionPool<Core>> {
         let result = self.builder.clone().set_max_size(size).build().await;
+        self.created_pools.track(&result);
 
         if result.is_ok() {
             let old_count = self.connections_count.fetch_add(size, Ordering::Relaxed);
@@ -69,12 +79,19 @@ impl MasterPoolResource {
 pub struct ReplicaPoolResource {
     connections_count: Arc<AtomicU32>,
     builder: ConnectionPoolBuilder<Core>,
+    created_pools: CreatedPools<Core>,
 }
 
+#[async_trait::async_trait]
 impl Resource for ReplicaPoolResource {
     fn resource_id() -> crate::resource::ResourceId {
         "common/replica_pool".into()
     }
+
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        self.created_pools.close_all("replica").await;
+        Ok(())
+    }
 }
 
 impl ReplicaPoolResource {
@@ -82,11 +99,13 @@ impl ReplicaPoolResource {
         Self {
             connections_count: Arc::new(AtomicU32::new(0)),
             builder,
+            created_pools: CreatedPools::default(),
         }
     }
 
     pub async fn get(&self) -> anyhow::Result<ConnectionPool<Core>> {
         let result = self.builder.build().await;
+        self.created_pools.track(&result);
 
         if result.is_ok() {
             self.connections_count
@@ -106,6 +125,7 @@ impl ReplicaPoolResource {
 
     pub async fn get_custom(&self, size: u32) -> anyhow::Result<ConnectionPool<Core>> {
         let result = self.builder.clone().set_max_size(size).build().await;
+        self.created_pools.track(&result);
 
         if result.is_ok() {
             let old_count = self.connections_count.fetch_add(size, Ordering::Relaxed);
@@ -124,12 +144,19 @@ impl ReplicaPoolResource {
 pub struct ProverPoolResource {
     connections_count: Arc<AtomicU32>,
     builder: ConnectionPoolBuilder<Prover>,
+    created_pools: CreatedPools<Prover>,
 }
 
+#[async_trait::async_trait]
 impl Resource for ProverPoolResource {
     fn resource_id() -> crate::resource::ResourceId {
         "common/prover_pool".into()
     }
+
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        self.created_pools.close_all("prover").await;
+        Ok(())
+    }
 }
 
 impl ProverPoolResource {
@@ -137,11 +164,13 @@ impl ProverPoolResource {
         Self {
             connections_count: Arc::new(AtomicU32::new(0)),
             builder,
+            created_pools: CreatedPools::default(),
         }
     }
 
     pub async fn get(&self) -> anyhow::Result<ConnectionPool<Prover>> {
         let result = self.builder.build().await;
+        self.created_pools.track(&result);
 
         if result.is_ok() {
             self.connections_count
@@ -161,6 +190,7 @@ impl ProverPoolResource {
 
     pub async fn get_custom(&self, size: u32) -> anyhow::Result<ConnectionPool<Prover>> {
         let result = self.builder.clone().set_max_size(size).build().await;
+        self.created_pools.track(&result);
 
         if result.is_ok() {
             let old_count = self.connections_count.fetch_add(size, Ordering::Relaxed);
@@ -173,3 +203,34 @@ impl ProverPoolResource {
         result
     }
 }
+
+/// Pools created by a pool resource. Tracked so that they can be closed once the service stops.
+#[derive(Debug)]
+struct CreatedPools<DB: DbMarker>(Arc<Mutex<Vec<ConnectionPool<DB>>>>);
+
+impl<DB: DbMarker> Clone for CreatedPools<DB> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<DB: DbMarker> Default for CreatedPools<DB> {
+    fn default() -> Self {
+        Self(Arc::default())
+    }
+}
+
+impl<DB: DbMarker + Clone> CreatedPools<DB> {
+    fn track(&self, result: &anyhow::Result<ConnectionPool<DB>>) {
+        if let Ok(pool) = result {
+            self.0.lock().unwrap().push(pool.clone());
+        }
+    }
+
+    async fn close_all(&self, kind: &str) {
+        let pools = std::mem::take(&mut *self.0.lock().unwrap());
+        let pools_count = pools.len();
+        futures::future::join_all(pools.iter().map(ConnectionPool::close)).await;
+        tracing::info!("Closed {pools_count} {kind} pool(s)");
+    }
+}
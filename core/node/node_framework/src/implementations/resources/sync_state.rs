@@ -1,4 +1,4 @@
-use zksync_core::sync_layer::SyncState;
+use zksync_core::sync_layer::{NodeStateHandle, SyncState};
 
 use crate::resource::{Resource, ResourceId};
 
@@ -10,3 +10,14 @@ impl Resource for SyncStateResource {
         "sync_state".into()
     }
 }
+
+/// Wrapper for the [`NodeStateHandle`]. If present, the API server rejects methods that require
+/// the node storage until the node becomes ready.
+#[derive(Debug, Clone)]
+pub struct NodeStateResource(pub NodeStateHandle);
+
+impl Resource for NodeStateResource {
+    fn resource_id() -> ResourceId {
+        "node_state".into()
+    }
+}
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use zksync_core::api_server::{
-    tree::TreeApiClient,
-    tx_sender::{tx_sink::TxSink, TxSender},
+use zksync_core::{
+    api_server::{
+        tree::TreeApiClient,
+        tx_sender::{tx_sink::TxSink, TxSender},
+    },
+    metadata_calculator::{TreeLagReceiver, TreeReaderPool},
 };
 
 use crate::resource::{Resource, ResourceId};
@@ -33,3 +36,30 @@ impl Resource for TreeApiClientResource {
         "api/tree_api_client".into()
     }
 }
+
+/// Lag of the Merkle tree relative to the latest sealed L1 batch; provided by the metadata calculator.
+#[derive(Debug, Clone)]
+pub struct TreeLagResource(pub TreeLagReceiver);
+
+impl Resource for TreeLagResource {
+    fn resource_id() -> ResourceId {
+        "api/tree_lag".into()
+    }
+}
+
+/// Pool of Merkle tree readers serving concurrent reads from RocksDB snapshots; provided by the metadata calculator.
+#[derive(Debug, Clone)]
+pub struct TreeReaderPoolResource(pub TreeReaderPool);
+
+#[async_trait::async_trait]
+impl Resource for TreeReaderPoolResource {
+    fn resource_id() -> ResourceId {
+        "api/tree_reader_pool".into()
+    }
+
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        self.0.close().await;
+        tracing::info!("Closed Merkle tree reader pool");
+        Ok(())
+    }
+}
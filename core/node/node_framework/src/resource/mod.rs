@@ -1,5 +1,7 @@
 use std::{any::TypeId, fmt};
 
+use futures::future::BoxFuture;
+
 pub use self::{
     lazy_resource::LazyResource, resource_collection::ResourceCollection, resource_id::ResourceId,
     unique::Unique,
@@ -14,6 +16,16 @@ mod unique;
 /// Typically, the type that implements this trait also should implement `Clone`
 /// since the same resource may be requested by several tasks and thus it would be an additional
 /// bound on most methods that work with [`Resource`].
+///
+/// ## Lifecycle hooks
+///
+/// Besides [`Self::on_resource_wired()`], resources may define hooks invoked by the service once it starts
+/// ([`Self::on_service_start()`]) and once all its tasks have stopped ([`Self::on_service_stop()`]).
+/// The latter can be used to release the resource gracefully, e.g. to close database connections.
+/// Note that the hooks are invoked on the resource instance stored in the service; since resources are
+/// usually cloned, the instance should share its state with the clones (e.g., via an `Arc`) for the hooks
+/// to be useful.
+#[async_trait::async_trait]
 pub trait Resource: 'static + Send + Sync + std::any::Any {
     /// Unique identifier of the resource.
     /// Used to fetch the resource from the provider.
@@ -24,6 +36,18 @@ pub trait Resource: 'static + Send + Sync + std::any::Any {
     fn resource_id() -> ResourceId;
 
     fn on_resource_wired(&mut self) {}
+
+    /// Invoked once the wiring is complete, before any task is launched.
+    /// An error returned from this hook prevents the service from starting.
+    async fn on_service_start(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Invoked once all the service tasks have stopped (or didn't stop in time).
+    /// Errors returned from this hook are logged.
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 /// Internal, object-safe version of [`Resource`].
@@ -37,6 +61,12 @@ pub(crate) trait StoredResource: 'static + std::any::Any + Send + Sync {
 
     /// An object-safe version of [`Resource::on_resoure_wired`].
     fn stored_resource_wired(&mut self);
+
+    /// An object-safe version of [`Resource::on_service_start`].
+    fn stored_on_service_start(&self) -> BoxFuture<'_, anyhow::Result<()>>;
+
+    /// An object-safe version of [`Resource::on_service_stop`].
+    fn stored_on_service_stop(&self) -> BoxFuture<'_, anyhow::Result<()>>;
 }
 
 impl fmt::Debug for dyn StoredResource {
@@ -55,6 +85,14 @@ impl<T: Resource> StoredResource for T {
     fn stored_resource_wired(&mut self) {
         Resource::on_resource_wired(self);
     }
+
+    fn stored_on_service_start(&self) -> BoxFuture<'_, anyhow::Result<()>> {
+        Resource::on_service_start(self)
+    }
+
+    fn stored_on_service_stop(&self) -> BoxFuture<'_, anyhow::Result<()>> {
+        Resource::on_service_stop(self)
+    }
 }
 
 impl dyn StoredResource {
@@ -1,7 +1,7 @@
 use crate::{
     precondition::Precondition,
     resource::{Resource, StoredResource},
-    service::ZkStackService,
+    service::{ServiceIntrospection, TaskKind, ZkStackService},
     task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
     wiring_layer::WiringError,
 };
@@ -33,11 +33,24 @@ impl<'a> ServiceContext<'a> {
         self.service.runtime.handle()
     }
 
+    /// Provides access to the wiring graph and the live status of the service tasks.
+    /// Can be used to expose this information for debugging, e.g. via an HTTP endpoint.
+    pub fn introspection(&self) -> ServiceIntrospection {
+        self.service.introspection.clone()
+    }
+
+    fn record_task(&self, name: &'static str, kind: TaskKind) {
+        self.service
+            .introspection
+            .record_task(self.layer, name, kind);
+    }
+
     /// Adds a task to the service.
-    /// Added tasks will be launched after the wiring process will be finished and all the preconditions
-    /// are met.
+    /// Added tasks will be launched after the wiring process will be finished, all the preconditions
+    /// are met, and all the oneshot tasks the task [waits for](Task::wait_for) are finished.
     pub fn add_task(&mut self, task: Box<dyn Task>) -> &mut Self {
         tracing::info!("Layer {} has added a new task: {}", self.layer, task.name());
+        self.record_task(task.name(), TaskKind::Task);
         self.service.runnables.tasks.push(task);
         self
     }
@@ -50,6 +63,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.record_task(task.name(), TaskKind::UnconstrainedTask);
         self.service.runnables.unconstrained_tasks.push(task);
         self
     }
@@ -61,6 +75,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             precondition.name()
         );
+        self.record_task(precondition.name(), TaskKind::Precondition);
         self.service.runnables.preconditions.push(precondition);
         self
     }
@@ -72,6 +87,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.record_task(task.name(), TaskKind::OneshotTask);
         self.service.runnables.oneshot_tasks.push(task);
         self
     }
@@ -86,6 +102,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.record_task(task.name(), TaskKind::UnconstrainedOneshotTask);
         self.service
             .runnables
             .unconstrained_oneshot_tasks
@@ -116,6 +133,7 @@ impl<'a> ServiceContext<'a> {
         };
 
         let name = T::resource_id();
+        self.service.introspection.record_requested_resource(&name);
         // Check whether the resource is already available.
         if let Some(resource) = self.service.resources.get(&name) {
             tracing::info!("Layer {} has requested resource {}", self.layer, name);
@@ -148,6 +166,9 @@ impl<'a> ServiceContext<'a> {
         self.service
             .resources
             .insert(T::resource_id(), Box::new(resource.clone()));
+        self.service
+            .introspection
+            .record_provided_resource(&T::resource_id());
         tracing::info!(
             "Layer {} has created a new resource {}",
             self.layer,
@@ -174,6 +195,7 @@ impl<'a> ServiceContext<'a> {
             );
             return Err(WiringError::ResourceAlreadyProvided(name));
         }
+        self.service.introspection.record_provided_resource(&name);
         self.service.resources.insert(name, Box::new(resource));
         tracing::info!(
             "Layer {} has provided a new resource {}",
@@ -6,6 +6,13 @@ pub enum ZkStackServiceError {
     RuntimeDetected,
     #[error("No tasks have been added to the service")]
     NoTasks,
+    #[error(
+        "Task {task} waits for task {dependency}, which is not a oneshot task added to the service"
+    )]
+    UnknownTaskDependency {
+        task: &'static str,
+        dependency: &'static str,
+    },
     #[error("One or more wiring layers failed to initialize: {0:?}")]
     Wiring(Vec<(String, WiringError)>),
     #[error(transparent)]
@@ -0,0 +1,158 @@
+//! Introspection of the service: which layers were wired, which resources they requested and provided,
+//! and the live status of every task.
+
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+use serde::Serialize;
+
+use crate::{resource::ResourceId, wiring_layer::WiringError};
+
+/// Kind of a runnable added to the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Task,
+    OneshotTask,
+    UnconstrainedTask,
+    UnconstrainedOneshotTask,
+    Precondition,
+}
+
+/// Live status of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    /// The task was added to the service, but wasn't launched yet.
+    Pending,
+    /// The task was launched. It may still wait for preconditions or other tasks.
+    Running,
+    /// The task has finished successfully.
+    Finished,
+    /// The task has returned an error.
+    Failed,
+}
+
+/// Information about a task added to the service.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub name: &'static str,
+    pub kind: TaskKind,
+    /// Name of the layer that has added the task.
+    pub layer: String,
+    pub status: TaskStatus,
+    /// Error returned by the task, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Information about a wiring layer processed by the service.
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerInfo {
+    pub name: String,
+    /// IDs of the resources requested by the layer, including ones that weren't available.
+    pub requested_resources: Vec<String>,
+    /// IDs of the resources provided by the layer.
+    pub provided_resources: Vec<String>,
+    /// Wiring error, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Snapshot of the service state returned by [`ServiceIntrospection::snapshot()`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServiceSnapshot {
+    /// Wiring layers in the order they were wired.
+    pub layers: Vec<LayerInfo>,
+    /// Tasks in the order they were added to the service.
+    pub tasks: Vec<TaskInfo>,
+}
+
+/// Shared handle allowing to observe the wiring graph and the live task state of the service.
+/// Can be obtained during wiring via [`ServiceContext::introspection()`](super::ServiceContext::introspection).
+#[derive(Debug, Clone, Default)]
+pub struct ServiceIntrospection(Arc<Mutex<ServiceSnapshot>>);
+
+impl ServiceIntrospection {
+    /// Returns the current state of the service.
+    pub fn snapshot(&self) -> ServiceSnapshot {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub(super) fn start_layer(&self, name: &str) {
+        self.0.lock().unwrap().layers.push(LayerInfo {
+            name: name.to_owned(),
+            requested_resources: vec![],
+            provided_resources: vec![],
+            error: None,
+        });
+    }
+
+    pub(super) fn finish_layer(&self, error: Option<&WiringError>) {
+        if let Some(layer) = self.0.lock().unwrap().layers.last_mut() {
+            layer.error = error.map(ToString::to_string);
+        }
+    }
+
+    pub(super) fn record_requested_resource(&self, id: &ResourceId) {
+        if let Some(layer) = self.0.lock().unwrap().layers.last_mut() {
+            let id = id.to_string();
+            if !layer.requested_resources.contains(&id) {
+                layer.requested_resources.push(id);
+            }
+        }
+    }
+
+    pub(super) fn record_provided_resource(&self, id: &ResourceId) {
+        if let Some(layer) = self.0.lock().unwrap().layers.last_mut() {
+            layer.provided_resources.push(id.to_string());
+        }
+    }
+
+    pub(super) fn record_task(&self, layer: &str, name: &'static str, kind: TaskKind) {
+        self.0.lock().unwrap().tasks.push(TaskInfo {
+            name,
+            kind,
+            layer: layer.to_owned(),
+            status: TaskStatus::Pending,
+            error: None,
+        });
+    }
+
+    pub(super) fn update_task(
+        &self,
+        name: &str,
+        kind: TaskKind,
+        status: TaskStatus,
+        error: Option<String>,
+    ) {
+        let mut snapshot = self.0.lock().unwrap();
+        let tasks = snapshot.tasks.iter_mut();
+        for task in tasks.filter(|task| task.name == name && task.kind == kind) {
+            task.status = status;
+            task.error = error.clone();
+        }
+    }
+}
+
+/// Wraps a task future so that its status is reflected in `introspection`.
+pub(super) fn track_task(
+    introspection: &ServiceIntrospection,
+    name: &'static str,
+    kind: TaskKind,
+    task_future: BoxFuture<'static, anyhow::Result<()>>,
+) -> BoxFuture<'static, anyhow::Result<()>> {
+    let introspection = introspection.clone();
+    Box::pin(async move {
+        introspection.update_task(name, kind, TaskStatus::Running, None);
+        let result = task_future.await;
+        match &result {
+            Ok(()) => introspection.update_task(name, kind, TaskStatus::Finished, None),
+            Err(err) => {
+                let err = Some(format!("{err:#}"));
+                introspection.update_task(name, kind, TaskStatus::Failed, err);
+            }
+        }
+        result
+    })
+}
@@ -6,7 +6,14 @@ use tokio::{runtime::Runtime, sync::watch};
 use zksync_utils::panic_extractor::try_extract_panic_message;
 
 use self::runnables::Runnables;
-pub use self::{context::ServiceContext, error::ZkStackServiceError, stop_receiver::StopReceiver};
+pub use self::{
+    context::ServiceContext,
+    error::ZkStackServiceError,
+    introspection::{
+        LayerInfo, ServiceIntrospection, ServiceSnapshot, TaskInfo, TaskKind, TaskStatus,
+    },
+    stop_receiver::StopReceiver,
+};
 use crate::{
     resource::{ResourceId, StoredResource},
     service::runnables::TaskReprs,
@@ -15,6 +22,7 @@ use crate::{
 
 mod context;
 mod error;
+mod introspection;
 mod runnables;
 mod stop_receiver;
 #[cfg(test)]
@@ -69,6 +77,7 @@ impl ZkStackServiceBuilder {
             layers: std::mem::take(&mut self.layers),
             resources: Default::default(),
             runnables: Default::default(),
+            introspection: Default::default(),
             stop_sender,
             runtime,
         })
@@ -85,6 +94,8 @@ pub struct ZkStackService {
     layers: Vec<Box<dyn WiringLayer>>,
     /// Different kinds of tasks for the service.
     runnables: Runnables,
+    /// Wiring graph and live task state exposed to the layers.
+    introspection: ServiceIntrospection,
 
     /// Sender used to stop the tasks.
     stop_sender: watch::Sender<bool>,
@@ -93,9 +104,8 @@ pub struct ZkStackService {
 }
 
 impl ZkStackService {
-    /// Runs the system.
-    pub fn run(mut self) -> Result<(), ZkStackServiceError> {
-        // Initialize tasks.
+    /// Wires all the layers added to the service, collecting resources and tasks provided by them.
+    pub(crate) fn wire(&mut self) -> Result<(), ZkStackServiceError> {
         let wiring_layers = std::mem::take(&mut self.layers);
 
         let mut errors: Vec<(String, WiringError)> = Vec::new();
@@ -103,9 +113,10 @@ impl ZkStackService {
         let runtime_handle = self.runtime.handle().clone();
         for layer in wiring_layers {
             let name = layer.layer_name().to_string();
+            self.introspection.start_layer(&name);
             // We must process wiring layers sequentially and in the same order as they were added.
-            let task_result =
-                runtime_handle.block_on(layer.wire(ServiceContext::new(&name, &mut self)));
+            let task_result = runtime_handle.block_on(layer.wire(ServiceContext::new(&name, self)));
+            self.introspection.finish_layer(task_result.as_ref().err());
             if let Err(err) = task_result {
                 // We don't want to bail on the first error, since it'll provide worse DevEx:
                 // People likely want to fix as much problems as they can in one go, rather than have
@@ -122,10 +133,23 @@ impl ZkStackService {
             }
             return Err(ZkStackServiceError::Wiring(errors));
         }
+        Ok(())
+    }
+
+    /// Returns the wiring graph and task state of the service.
+    #[cfg(test)]
+    pub(crate) fn introspection(&self) -> &ServiceIntrospection {
+        &self.introspection
+    }
+
+    /// Runs the system.
+    pub fn run(mut self) -> Result<(), ZkStackServiceError> {
+        self.wire()?;
 
         if self.runnables.is_empty() {
             return Err(ZkStackServiceError::NoTasks);
         }
+        self.runnables.check_task_dependencies()?;
 
         let only_oneshot_tasks = self.runnables.is_oneshot_only();
 
@@ -138,9 +162,11 @@ impl ZkStackService {
         let TaskReprs {
             mut long_running_tasks,
             oneshot_tasks,
-        } = self
-            .runnables
-            .prepare_tasks(task_barrier.clone(), stop_receiver.clone());
+        } = self.runnables.prepare_tasks(
+            task_barrier.clone(),
+            stop_receiver.clone(),
+            &self.introspection,
+        );
 
         // Wiring is now complete.
         for resource in self.resources.values_mut() {
@@ -148,6 +174,11 @@ impl ZkStackService {
         }
         tracing::info!("Wiring complete");
 
+        if let Err(err) = self.runtime.block_on(self.start_resources()) {
+            self.runtime.block_on(self.stop_resources());
+            return Err(err.into());
+        }
+
         // Create a system task that is cancellation-aware and will only exit on either oneshot task failure or
         // stop signal.
         let oneshot_runner_system_task =
@@ -195,10 +226,39 @@ impl ZkStackService {
         } else {
             tracing::info!("Remaining tasks finished without reaching timeouts");
         }
+        self.runtime.block_on(self.stop_resources());
 
         result?;
         Ok(())
     }
+
+    /// Invokes [`Resource::on_service_start()`](crate::resource::Resource::on_service_start) hooks
+    /// for all resources, failing on the first error.
+    async fn start_resources(&self) -> anyhow::Result<()> {
+        for (id, resource) in &self.resources {
+            resource
+                .stored_on_service_start()
+                .await
+                .with_context(|| format!("Failed starting resource {id}"))?;
+        }
+        Ok(())
+    }
+
+    /// Invokes [`Resource::on_service_stop()`](crate::resource::Resource::on_service_stop) hooks
+    /// for all resources. Since the service is shutting down, errors and timeouts are only logged.
+    async fn stop_resources(&self) {
+        let stop_hooks = self.resources.iter().map(|(id, resource)| async move {
+            let stop_result =
+                tokio::time::timeout(TASK_SHUTDOWN_TIMEOUT, resource.stored_on_service_stop())
+                    .await;
+            match stop_result {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => tracing::warn!("Failed stopping resource {id}: {err:#}"),
+                Err(_) => tracing::warn!("Resource {id} didn't stop in {TASK_SHUTDOWN_TIMEOUT:?}"),
+            }
+        });
+        futures::future::join_all(stop_hooks).await;
+    }
 }
 
 fn oneshot_runner_task(
@@ -1,10 +1,13 @@
-use std::{fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 use anyhow::Context as _;
 use futures::future::BoxFuture;
-use tokio::sync::Barrier;
+use tokio::sync::{watch, Barrier};
 
-use super::StopReceiver;
+use super::{
+    introspection::{track_task, ServiceIntrospection, TaskKind},
+    StopReceiver, ZkStackServiceError,
+};
 use crate::{
     precondition::Precondition,
     task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
@@ -88,18 +91,60 @@ impl Runnables {
         ))
     }
 
+    /// Checks that every task [waits for](Task::wait_for) oneshot tasks added to the service.
+    pub(super) fn check_task_dependencies(&self) -> Result<(), ZkStackServiceError> {
+        for task in &self.tasks {
+            for dependency in task.wait_for() {
+                let is_known = self.oneshot_tasks.iter().any(|t| t.name() == dependency)
+                    || self
+                        .unconstrained_oneshot_tasks
+                        .iter()
+                        .any(|t| t.name() == dependency);
+                if !is_known {
+                    return Err(ZkStackServiceError::UnknownTaskDependency {
+                        task: task.name(),
+                        dependency,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates senders signaling that a oneshot task with a certain name has finished.
+    fn completion_senders(&self) -> HashMap<&'static str, Arc<watch::Sender<bool>>> {
+        let oneshot_names = self.oneshot_tasks.iter().map(|task| task.name());
+        let unconstrained_oneshot_names = self
+            .unconstrained_oneshot_tasks
+            .iter()
+            .map(|task| task.name());
+        oneshot_names
+            .chain(unconstrained_oneshot_names)
+            .map(|name| (name, Arc::new(watch::channel(false).0)))
+            .collect()
+    }
+
     /// Transforms the collection of tasks into a set of universal futures.
     pub(super) fn prepare_tasks(
         mut self,
         task_barrier: Arc<Barrier>,
         stop_receiver: StopReceiver,
+        introspection: &ServiceIntrospection,
     ) -> TaskReprs {
+        let completion_senders = self.completion_senders();
+
         let mut long_running_tasks = Vec::new();
-        self.collect_unconstrained_tasks(&mut long_running_tasks, stop_receiver.clone());
+        self.collect_unconstrained_tasks(
+            &mut long_running_tasks,
+            stop_receiver.clone(),
+            introspection,
+        );
         self.collect_tasks(
             &mut long_running_tasks,
+            &completion_senders,
             task_barrier.clone(),
             stop_receiver.clone(),
+            introspection,
         );
 
         let mut oneshot_tasks = Vec::new();
@@ -107,13 +152,21 @@ impl Runnables {
             &mut oneshot_tasks,
             task_barrier.clone(),
             stop_receiver.clone(),
+            introspection,
         );
         self.collect_oneshot_tasks(
             &mut oneshot_tasks,
+            &completion_senders,
             task_barrier.clone(),
             stop_receiver.clone(),
+            introspection,
+        );
+        self.collect_unconstrained_oneshot_tasks(
+            &mut oneshot_tasks,
+            &completion_senders,
+            stop_receiver.clone(),
+            introspection,
         );
-        self.collect_unconstrained_oneshot_tasks(&mut oneshot_tasks, stop_receiver.clone());
 
         TaskReprs {
             long_running_tasks,
@@ -125,6 +178,7 @@ impl Runnables {
         &mut self,
         tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
         stop_receiver: StopReceiver,
+        introspection: &ServiceIntrospection,
     ) {
         for task in std::mem::take(&mut self.unconstrained_tasks) {
             let name = task.name();
@@ -134,26 +188,42 @@ impl Runnables {
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            tasks.push(track_task(
+                introspection,
+                name,
+                TaskKind::UnconstrainedTask,
+                task_future,
+            ));
         }
     }
 
     fn collect_tasks(
         &mut self,
         tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        completion_senders: &HashMap<&'static str, Arc<watch::Sender<bool>>>,
         task_barrier: Arc<Barrier>,
         stop_receiver: StopReceiver,
+        introspection: &ServiceIntrospection,
     ) {
         for task in std::mem::take(&mut self.tasks) {
             let name = task.name();
+            let dependency_names = task.wait_for();
+            if !dependency_names.is_empty() {
+                tracing::info!("Task {name} will wait for tasks {dependency_names:?} to finish");
+            }
+            // Unknown dependencies are rejected by `check_task_dependencies()`.
+            let dependencies = dependency_names
+                .into_iter()
+                .filter_map(|dependency| Some(completion_senders.get(dependency)?.subscribe()))
+                .collect();
             let stop_receiver = stop_receiver.clone();
             let task_barrier = task_barrier.clone();
             let task_future = Box::pin(async move {
-                task.run_with_barrier(stop_receiver, task_barrier)
+                task.run_with_barrier(stop_receiver, task_barrier, dependencies)
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            tasks.push(track_task(introspection, name, TaskKind::Task, task_future));
         }
     }
 
@@ -162,6 +232,7 @@ impl Runnables {
         oneshot_tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
         task_barrier: Arc<Barrier>,
         stop_receiver: StopReceiver,
+        introspection: &ServiceIntrospection,
     ) {
         for precondition in std::mem::take(&mut self.preconditions) {
             let name = precondition.name();
@@ -173,45 +244,70 @@ impl Runnables {
                     .await
                     .with_context(|| format!("Precondition {name} failed"))
             });
-            oneshot_tasks.push(task_future);
+            oneshot_tasks.push(track_task(
+                introspection,
+                name,
+                TaskKind::Precondition,
+                task_future,
+            ));
         }
     }
 
     fn collect_oneshot_tasks(
         &mut self,
         oneshot_tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        completion_senders: &HashMap<&'static str, Arc<watch::Sender<bool>>>,
         task_barrier: Arc<Barrier>,
         stop_receiver: StopReceiver,
+        introspection: &ServiceIntrospection,
     ) {
         for oneshot_task in std::mem::take(&mut self.oneshot_tasks) {
             let name = oneshot_task.name();
+            let completion_sender = completion_senders[name].clone();
             let stop_receiver = stop_receiver.clone();
             let task_barrier = task_barrier.clone();
             let task_future = Box::pin(async move {
                 oneshot_task
                     .run_oneshot_with_barrier(stop_receiver, task_barrier)
                     .await
-                    .with_context(|| format!("Oneshot task {name} failed"))
+                    .with_context(|| format!("Oneshot task {name} failed"))?;
+                completion_sender.send_replace(true);
+                anyhow::Ok(())
             });
-            oneshot_tasks.push(task_future);
+            oneshot_tasks.push(track_task(
+                introspection,
+                name,
+                TaskKind::OneshotTask,
+                task_future,
+            ));
         }
     }
 
     fn collect_unconstrained_oneshot_tasks(
         &mut self,
         oneshot_tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        completion_senders: &HashMap<&'static str, Arc<watch::Sender<bool>>>,
         stop_receiver: StopReceiver,
+        introspection: &ServiceIntrospection,
     ) {
         for unconstrained_oneshot_task in std::mem::take(&mut self.unconstrained_oneshot_tasks) {
             let name = unconstrained_oneshot_task.name();
+            let completion_sender = completion_senders[name].clone();
             let stop_receiver = stop_receiver.clone();
             let task_future = Box::pin(async move {
                 unconstrained_oneshot_task
                     .run_unconstrained_oneshot(stop_receiver)
                     .await
-                    .with_context(|| format!("Unconstrained oneshot task {name} failed"))
+                    .with_context(|| format!("Unconstrained oneshot task {name} failed"))?;
+                completion_sender.send_replace(true);
+                anyhow::Ok(())
             });
-            oneshot_tasks.push(task_future);
+            oneshot_tasks.push(track_task(
+                introspection,
+                name,
+                TaskKind::UnconstrainedOneshotTask,
+                task_future,
+            ));
         }
     }
 }
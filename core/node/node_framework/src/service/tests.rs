@@ -1,15 +1,25 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use anyhow::anyhow;
 use assert_matches::assert_matches;
 use tokio::runtime::Runtime;
 
 use crate::{
+    implementations::{
+        layers::config_repository::ConfigRepositoryLayer, resources::config::ConfigResource,
+    },
+    resource::{Resource, ResourceId},
     service::{
-        ServiceContext, StopReceiver, WiringError, WiringLayer, ZkStackServiceBuilder,
-        ZkStackServiceError,
+        ServiceContext, ServiceIntrospection, StopReceiver, TaskKind, TaskStatus, WiringError,
+        WiringLayer, ZkStackServiceBuilder, ZkStackServiceError,
     },
-    task::Task,
+    task::{Backoff, OneshotTask, RestartPolicy, Task},
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -222,3 +232,366 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug)]
+struct DependentTasksLayer {
+    recovery_finished: Arc<Mutex<bool>>,
+    dependency: &'static str,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for DependentTasksLayer {
+    fn layer_name(&self) -> &'static str {
+        "dependent_tasks_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.add_oneshot_task(Box::new(RecoveryTask(self.recovery_finished.clone())))
+            .add_task(Box::new(DependentTask {
+                recovery_finished: self.recovery_finished,
+                dependency: self.dependency,
+            }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct RecoveryTask(Arc<Mutex<bool>>);
+
+#[async_trait::async_trait]
+impl OneshotTask for RecoveryTask {
+    fn name(&self) -> &'static str {
+        "recovery_task"
+    }
+
+    async fn run_oneshot(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        *self.0.lock().unwrap() = true;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct DependentTask {
+    recovery_finished: Arc<Mutex<bool>>,
+    dependency: &'static str,
+}
+
+#[async_trait::async_trait]
+impl Task for DependentTask {
+    fn name(&self) -> &'static str {
+        "dependent_task"
+    }
+
+    fn wait_for(&self) -> Vec<&'static str> {
+        vec![self.dependency]
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            *self.recovery_finished.lock().unwrap(),
+            "task started before its dependency has finished"
+        );
+        Ok(())
+    }
+}
+
+// Tasks must only be started after all the oneshot tasks they wait for are finished.
+#[test]
+fn test_task_dependencies() {
+    let recovery_finished = Arc::new(Mutex::new(false));
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(DependentTasksLayer {
+        recovery_finished: recovery_finished.clone(),
+        dependency: "recovery_task",
+    });
+    zk_stack_service.build().unwrap().run().unwrap();
+    assert!(*recovery_finished.lock().unwrap());
+}
+
+// Tasks cannot wait for tasks not added to the service.
+#[test]
+fn test_unknown_task_dependency() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(DependentTasksLayer {
+        recovery_finished: Arc::default(),
+        dependency: "unknown_task",
+    });
+    let result = zk_stack_service.build().unwrap().run();
+    assert_matches!(
+        result.unwrap_err(),
+        ZkStackServiceError::UnknownTaskDependency {
+            task: "dependent_task",
+            dependency: "unknown_task",
+        }
+    );
+}
+
+#[derive(Debug)]
+struct FlakyTaskLayer {
+    runs: Arc<AtomicUsize>,
+    policy: RestartPolicy,
+    panic: bool,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for FlakyTaskLayer {
+    fn layer_name(&self) -> &'static str {
+        "flaky_task_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.add_task(Box::new(FlakyTask {
+            runs: self.runs,
+            policy: self.policy,
+            panic: self.panic,
+        }));
+        Ok(())
+    }
+}
+
+/// Task that fails on the first 2 runs and succeeds afterwards.
+#[derive(Debug, Clone)]
+struct FlakyTask {
+    runs: Arc<AtomicUsize>,
+    policy: RestartPolicy,
+    panic: bool,
+}
+
+#[async_trait::async_trait]
+impl Task for FlakyTask {
+    fn name(&self) -> &'static str {
+        "flaky_task"
+    }
+
+    fn restart_policy(&self) -> RestartPolicy {
+        self.policy
+    }
+
+    fn respawn(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let run = self.runs.fetch_add(1, Ordering::SeqCst);
+        if run < 2 {
+            if self.panic {
+                panic!("flaky task panicked");
+            }
+            anyhow::bail!("flaky task failed");
+        }
+        Ok(())
+    }
+}
+
+const TEST_BACKOFF: Backoff = Backoff {
+    initial_delay: Duration::from_millis(10),
+    max_delay: Duration::from_millis(50),
+};
+
+// Failed tasks must be restarted according to their restart policy.
+#[test]
+fn test_task_restarts() {
+    for panic in [false, true] {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut zk_stack_service = ZkStackServiceBuilder::new();
+        zk_stack_service.add_layer(FlakyTaskLayer {
+            runs: runs.clone(),
+            policy: RestartPolicy::OnFailure(TEST_BACKOFF),
+            panic,
+        });
+        zk_stack_service.build().unwrap().run().unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+}
+
+// Tasks without a restart policy must bring the service down on failure.
+#[test]
+fn test_task_without_restarts() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(FlakyTaskLayer {
+        runs: runs.clone(),
+        policy: RestartPolicy::Never,
+        panic: false,
+    });
+    let result = zk_stack_service.build().unwrap().run();
+    assert_matches!(result.unwrap_err(), ZkStackServiceError::Task(_));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+type Events = Arc<Mutex<Vec<&'static str>>>;
+
+#[derive(Debug, Clone)]
+struct LifecycleResource {
+    events: Events,
+    fail_on_start: bool,
+}
+
+#[async_trait::async_trait]
+impl Resource for LifecycleResource {
+    fn resource_id() -> ResourceId {
+        "test/lifecycle".into()
+    }
+
+    async fn on_service_start(&self) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push("start");
+        if self.fail_on_start {
+            anyhow::bail!("resource failed to start");
+        }
+        Ok(())
+    }
+
+    async fn on_service_stop(&self) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push("stop");
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct LifecycleLayer(LifecycleResource);
+
+#[async_trait::async_trait]
+impl WiringLayer for LifecycleLayer {
+    fn layer_name(&self) -> &'static str {
+        "lifecycle_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        let events = self.0.events.clone();
+        node.insert_resource(self.0)?;
+        node.add_task(Box::new(LifecycleTask(events)));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct LifecycleTask(Events);
+
+#[async_trait::async_trait]
+impl Task for LifecycleTask {
+    fn name(&self) -> &'static str {
+        "lifecycle_task"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.lock().unwrap().push("task");
+        Ok(())
+    }
+}
+
+// Resource hooks must be invoked before the tasks are started and after they are stopped.
+#[test]
+fn test_resource_lifecycle_hooks() {
+    let events = Events::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(LifecycleLayer(LifecycleResource {
+        events: events.clone(),
+        fail_on_start: false,
+    }));
+    zk_stack_service.build().unwrap().run().unwrap();
+    assert_eq!(*events.lock().unwrap(), ["start", "task", "stop"]);
+}
+
+// A resource failing to start must prevent tasks from running.
+#[test]
+fn test_resource_start_failure() {
+    let events = Events::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(LifecycleLayer(LifecycleResource {
+        events: events.clone(),
+        fail_on_start: true,
+    }));
+    let err = zk_stack_service.build().unwrap().run().unwrap_err();
+    assert!(err.to_string().contains("test/lifecycle"), "{err}");
+    assert_matches!(err, ZkStackServiceError::Task(_));
+    assert_eq!(*events.lock().unwrap(), ["start", "stop"]);
+}
+
+#[derive(Debug)]
+struct IntrospectionLayer(Arc<Mutex<Option<ServiceIntrospection>>>);
+
+#[async_trait::async_trait]
+impl WiringLayer for IntrospectionLayer {
+    fn layer_name(&self) -> &'static str {
+        "introspection_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.get_resource::<LifecycleResource>().await?;
+        *self.0.lock().unwrap() = Some(node.introspection());
+        Ok(())
+    }
+}
+
+// Introspection must reflect the wiring graph and the final task statuses.
+#[test]
+fn test_service_introspection() {
+    let introspection = Arc::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .add_layer(LifecycleLayer(LifecycleResource {
+            events: Events::default(),
+            fail_on_start: false,
+        }))
+        .add_layer(IntrospectionLayer(Arc::clone(&introspection)));
+    zk_stack_service.build().unwrap().run().unwrap();
+
+    let snapshot = introspection.lock().unwrap().take().unwrap().snapshot();
+    let layers: Vec<_> = snapshot.layers.iter().map(|layer| &layer.name).collect();
+    assert_eq!(layers, ["lifecycle_layer", "introspection_layer"]);
+    assert_eq!(snapshot.layers[0].provided_resources, ["test/lifecycle"]);
+    assert_eq!(snapshot.layers[1].requested_resources, ["test/lifecycle"]);
+    assert!(snapshot.layers.iter().all(|layer| layer.error.is_none()));
+
+    assert_eq!(snapshot.tasks.len(), 1);
+    let task = &snapshot.tasks[0];
+    assert_eq!(task.name, "lifecycle_task");
+    assert_eq!(task.layer, "lifecycle_layer");
+    assert_eq!(task.kind, TaskKind::Task);
+    assert_eq!(task.status, TaskStatus::Finished);
+}
+
+#[derive(Debug)]
+struct TestConfig {
+    value: u32,
+}
+
+#[derive(Debug)]
+struct ConfigConsumerLayer(Arc<Mutex<Option<u32>>>);
+
+#[async_trait::async_trait]
+impl WiringLayer for ConfigConsumerLayer {
+    fn layer_name(&self) -> &'static str {
+        "config_consumer_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        let ConfigResource(config) = node.get_resource::<ConfigResource<TestConfig>>().await?;
+        let missing_config = node.get_resource::<ConfigResource<String>>().await;
+        assert_matches!(missing_config, Err(WiringError::ResourceLacking(_)));
+        *self.0.lock().unwrap() = Some(config.value);
+        Ok(())
+    }
+}
+
+// Layers must be able to request config sections provided by the config repository.
+#[test]
+fn test_config_injection() {
+    let consumed_value = Arc::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .add_layer(
+            ConfigRepositoryLayer::new()
+                .with_config(TestConfig { value: 42 })
+                .with_optional_config(None::<String>),
+        )
+        .add_layer(ConfigConsumerLayer(Arc::clone(&consumed_value)))
+        .add_layer(LifecycleLayer(LifecycleResource {
+            events: Events::default(),
+            fail_on_start: false,
+        }));
+    zk_stack_service.build().unwrap().run().unwrap();
+
+    assert_eq!(*consumed_value.lock().unwrap(), Some(42));
+}
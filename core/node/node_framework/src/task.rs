@@ -27,13 +27,68 @@
 //! The unrestricted tasks are rarely needed, but two common cases for them are:
 //! - A task that must be started as soon as possible, e.g. healthcheck server.
 //! - A task that may be a driving force for some precondition to be met.
+//!
+//! ## Task dependencies
+//!
+//! Preconditions are global: every constrained task waits for all of them. If a task must only start
+//! after a certain oneshot task has finished (e.g., consensus must only start after snapshot recovery
+//! has completed), it can list the names of such oneshot tasks in [`Task::wait_for`]. The service will
+//! only launch the task once all the preconditions are met *and* all the listed oneshot tasks
+//! (either constrained or unconstrained) have finished successfully.
+//!
+//! ## Restarting tasks
+//!
+//! By default, a failed or panicked [`Task`] brings the whole service down. Non-critical tasks (e.g., ones fetching
+//! auxiliary data like gas prices) may opt into being restarted by specifying a [`RestartPolicy`]
+//! and implementing [`Task::respawn`]. Each run of such a task is isolated in a separate Tokio task, so that a panic
+//! is treated as an ordinary failure.
 
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use tokio::sync::Barrier;
+use tokio::sync::{watch, Barrier};
+use zksync_utils::panic_extractor::try_extract_panic_message;
 
 use crate::service::StopReceiver;
 
+/// Policy of restarting a [`Task`] after it has exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// The task is never restarted; once it exits, the service shuts down. This is the default.
+    #[default]
+    Never,
+    /// The task is restarted if it returns an error or panics. Successful exit shuts down the service.
+    OnFailure(Backoff),
+    /// The task is restarted whenever it exits, until the service receives the stop signal.
+    Always(Backoff),
+}
+
+/// Exponential backoff between task restarts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay before the first restart. Each consecutive restart doubles the delay.
+    pub initial_delay: Duration,
+    /// Upper bound for the restart delay.
+    pub max_delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Backoff {
+    fn next_delay(&self, delay: Duration) -> Duration {
+        (delay * 2).min(self.max_delay)
+    }
+}
+
 /// A task implementation.
 ///
 /// Note: any `Task` added to the service will only start after all the [preconditions](crate::precondition::Precondition)
@@ -43,6 +98,27 @@ pub trait Task: 'static + Send {
     /// Unique name of the task.
     fn name(&self) -> &'static str;
 
+    /// Names of the [oneshot tasks](OneshotTask) (or [unconstrained ones](UnconstrainedOneshotTask))
+    /// that must finish before this task is started. Referring to a task not added to the service
+    /// is an error. By default, the task doesn't depend on any other tasks.
+    fn wait_for(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Policy of restarting the task after it has exited. By default, the task is never restarted.
+    ///
+    /// Policies other than [`RestartPolicy::Never`] require implementing [`Self::respawn()`].
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::Never
+    }
+
+    /// Creates a fresh instance of the task that will be run on restart. Called before each run of the task
+    /// with a restart policy other than [`RestartPolicy::Never`]. If this method returns `None` (the default),
+    /// the task is not restarted regardless of its policy.
+    fn respawn(&self) -> Option<Box<dyn Task>> {
+        None
+    }
+
     /// Runs the task.
     ///
     /// Once any of the task returns, the node will shutdown.
@@ -59,22 +135,93 @@ pub trait Task: 'static + Send {
 
 impl dyn Task {
     /// An internal helper method that guards running the task with a tokio Barrier.
-    /// Used to make sure that the task is not started until all the preconditions are met.
+    /// Used to make sure that the task is not started until all the preconditions are met
+    /// and all the oneshot tasks it [waits for](Task::wait_for) are finished.
+    ///
+    /// Each of `dependencies` is a receiver that changes its value to `true` once the corresponding
+    /// oneshot task is finished.
     pub(super) async fn run_with_barrier(
         self: Box<Self>,
         mut stop_receiver: StopReceiver,
         preconditions_barrier: Arc<Barrier>,
+        dependencies: Vec<watch::Receiver<bool>>,
     ) -> anyhow::Result<()> {
-        // Wait either for barrier to be lifted or for the stop signal to be received.
+        let wait_for_dependencies = async {
+            preconditions_barrier.wait().await;
+            for mut dependency in dependencies {
+                if dependency.wait_for(|&finished| finished).await.is_err() {
+                    // The dependency has failed, which will lead to the service shutdown.
+                    // The task should not start in this case, so we just wait for the stop signal.
+                    futures::future::pending::<()>().await;
+                }
+            }
+        };
+
+        // Wait either for barrier to be lifted and dependencies to finish, or for the stop signal
+        // to be received.
         tokio::select! {
-            _ = preconditions_barrier.wait() => {
-                self.run(stop_receiver).await
+            () = wait_for_dependencies => {
+                self.run_with_restarts(stop_receiver).await
             }
             _ = stop_receiver.0.changed() => {
                 Ok(())
             }
         }
     }
+
+    /// Runs the task, restarting it according to its [`RestartPolicy`].
+    async fn run_with_restarts(
+        self: Box<Self>,
+        mut stop_receiver: StopReceiver,
+    ) -> anyhow::Result<()> {
+        let name = self.name();
+        let policy = self.restart_policy();
+        let backoff = match policy {
+            RestartPolicy::Never => return self.run(stop_receiver).await,
+            RestartPolicy::OnFailure(backoff) | RestartPolicy::Always(backoff) => backoff,
+        };
+
+        let mut task = self;
+        let mut delay = backoff.initial_delay;
+        loop {
+            let next_task = task.respawn();
+            let started_at = Instant::now();
+            // Run the task as a separate Tokio task, so that its panic doesn't bring down the service.
+            let result = match tokio::spawn(task.run(stop_receiver.clone())).await {
+                Ok(result) => result,
+                Err(panic_err) => {
+                    let panic_msg = try_extract_panic_message(panic_err);
+                    Err(anyhow::format_err!("Task {name} panicked: {panic_msg}"))
+                }
+            };
+
+            if *stop_receiver.0.borrow() {
+                return result;
+            }
+            let should_restart = result.is_err() || matches!(policy, RestartPolicy::Always(_));
+            let Some(next_task) = next_task.filter(|_| should_restart) else {
+                return result;
+            };
+
+            // Reset backoff if the task has been running for a while.
+            if started_at.elapsed() >= backoff.max_delay {
+                delay = backoff.initial_delay;
+            }
+            match &result {
+                Err(err) => tracing::warn!("Task {name} failed: {err:#}; restarting in {delay:?}"),
+                Ok(()) => tracing::info!("Task {name} exited; restarting in {delay:?}"),
+            }
+            if tokio::time::timeout(delay, stop_receiver.0.changed())
+                .await
+                .is_ok()
+            {
+                // The stop signal is received.
+                return Ok(());
+            }
+            task = next_task;
+            delay = backoff.next_delay(delay);
+        }
+    }
 }
 
 /// A oneshot task implementation.
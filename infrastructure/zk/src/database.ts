@@ -84,11 +84,28 @@ export async function drop(opts: DbOpts) {
     }
 }
 
+// If `DATABASE_SCHEMA` is set, creates the schema and returns the database URL with `search_path` pointing to it,
+// so that migrations create tables in this schema. This allows multiple chains to share a single database.
+async function prepareSchema(dbUrl: string): Promise<string> {
+    const schema = process.env.DATABASE_SCHEMA;
+    if (!schema) {
+        return dbUrl;
+    }
+    if (!/^[a-z_][a-z0-9_]*$/.test(schema)) {
+        throw new Error(`Invalid database schema name: ${schema}`);
+    }
+    console.log(`Using database schema ${schema}`);
+    await utils.spawn(`psql "${dbUrl}" -c "CREATE SCHEMA IF NOT EXISTS ${schema}"`);
+    const url = new URL(dbUrl);
+    url.searchParams.set('options', `-c search_path=${schema}`);
+    return url.toString();
+}
+
 async function migrateForDal(dalPath: DalPath, dbUrl: string) {
     console.log(`Running migrations for ${dalPath}...`);
-    await utils.spawn(
-        `cd ${dalPath} && cargo sqlx database create --database-url ${dbUrl} && cargo sqlx migrate run --database-url ${dbUrl}`
-    );
+    await utils.spawn(`cd ${dalPath} && cargo sqlx database create --database-url ${dbUrl}`);
+    const schemaDbUrl = await prepareSchema(dbUrl);
+    await utils.spawn(`cd ${dalPath} && cargo sqlx migrate run --database-url "${schemaDbUrl}"`);
 }
 
 export async function migrate(opts: DbOpts) {
@@ -129,7 +146,8 @@ export async function setupForDal(dalPath: DalPath, dbUrl: string) {
         console.log(`WARNING! Using prod db!`);
     }
     await utils.spawn(`cargo sqlx database create --database-url ${dbUrl}`);
-    await utils.spawn(`cargo sqlx migrate run --database-url ${dbUrl}`);
+    const schemaDbUrl = await prepareSchema(dbUrl);
+    await utils.spawn(`cargo sqlx migrate run --database-url "${schemaDbUrl}"`);
     if (dbUrl.startsWith(localDbUrl)) {
         await utils.spawn(
             `cargo sqlx prepare --check --database-url ${dbUrl} -- --tests || cargo sqlx prepare --database-url ${dbUrl} -- --tests`
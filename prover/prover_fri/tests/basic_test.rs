@@ -29,8 +29,19 @@ async fn prover_and_assert_base_layer(
     let object_store_config = ObjectStoreConfig {
         mode: ObjectStoreMode::FileBacked {
             file_backed_base_path: "./tests/data/".to_owned(),
+            file_backed_fsync: false,
+            file_backed_sharding: false,
+            file_backed_archive_path: None,
         },
         max_retries: 5,
+        request_timeout_secs: None,
+        circuit_breaker_threshold: 10,
+        circuit_breaker_cooldown_secs: 30,
+        cold_storage_class: None,
+        content_addressed: false,
+        encryption_master_key: None,
+        encryption_kms_key_id: None,
+        encrypted_buckets: vec![],
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
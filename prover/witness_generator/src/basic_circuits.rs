@@ -15,8 +15,9 @@ use circuit_definitions::{
     encodings::recursion_request::RecursionQueueSimulator,
     zkevm_circuits::fsm_input_output::ClosedFormInputCompactFormWitness,
 };
-use multivm::vm_latest::{
-    constants::MAX_CYCLES_FOR_TX, HistoryDisabled, StorageOracle as VmStorageOracle,
+use multivm::{
+    utils::get_used_bootloader_memory_bytes,
+    vm_latest::{constants::MAX_CYCLES_FOR_TX, HistoryDisabled, StorageOracle as VmStorageOracle},
 };
 use prover_dal::{
     fri_witness_generator_dal::FriWitnessJobStatus, ConnectionPool, Prover, ProverDal,
@@ -62,6 +63,7 @@ use zksync_utils::{bytes_to_chunks, h256_to_u256, u256_to_h256};
 use crate::{
     metrics::WITNESS_GENERATOR_METRICS,
     precalculated_merkle_paths_provider::PrecalculatedMerklePathsProvider,
+    sanity_check::WitnessInputSanityCheck,
     storage_oracle::StorageOracle,
     utils::{
         expand_bootloader_contents, save_circuit, save_eip_4844_circuit, ClosedFormInputWrapper,
@@ -137,7 +139,7 @@ impl BasicWitnessGenerator {
         basic_job: BasicWitnessGeneratorJob,
         started_at: Instant,
         config: Arc<FriWitnessGeneratorConfig>,
-    ) -> Option<BasicCircuitArtifacts> {
+    ) -> anyhow::Result<Option<BasicCircuitArtifacts>> {
         let BasicWitnessGeneratorJob {
             block_number,
             job,
@@ -171,7 +173,7 @@ impl BasicWitnessGenerator {
                     .mark_witness_job(FriWitnessJobStatus::Skipped, block_number)
                     .await;
                 transaction.commit().await.unwrap();
-                return None;
+                return Ok(None);
             }
         }
 
@@ -182,18 +184,17 @@ impl BasicWitnessGenerator {
             block_number.0
         );
 
-        Some(
-            process_basic_circuits_job(
-                &*object_store,
-                config,
-                connection_pool,
-                started_at,
-                block_number,
-                job,
-                eip_4844_blobs,
-            )
-            .await,
+        let artifacts = process_basic_circuits_job(
+            &*object_store,
+            config,
+            connection_pool,
+            started_at,
+            block_number,
+            job,
+            eip_4844_blobs,
         )
+        .await?;
+        Ok(Some(artifacts))
     }
 }
 
@@ -259,7 +260,7 @@ impl JobProcessor for BasicWitnessGenerator {
         let prover_connection_pool = self.prover_connection_pool.clone();
         tokio::spawn(async move {
             let block_number = job.block_number;
-            Ok(Self::process_job_impl(
+            Self::process_job_impl(
                 object_store,
                 connection_pool,
                 prover_connection_pool,
@@ -268,7 +269,7 @@ impl JobProcessor for BasicWitnessGenerator {
                 config,
             )
             .instrument(tracing::info_span!("basic_circuit", %block_number))
-            .await)
+            .await
         })
     }
 
@@ -340,7 +341,7 @@ async fn process_basic_circuits_job(
     block_number: L1BatchNumber,
     job: PrepareBasicCircuitsJob,
     eip_4844_blobs: Eip4844Blobs,
-) -> BasicCircuitArtifacts {
+) -> anyhow::Result<BasicCircuitArtifacts> {
     let witness_gen_input =
         build_basic_circuits_witness_generator_input(&connection_pool, job, block_number).await;
     let (circuit_urls, eip_4844_circuit_urls, queue_urls, scheduler_witness, aux_output_witness) =
@@ -352,7 +353,7 @@ async fn process_basic_circuits_job(
             witness_gen_input,
             eip_4844_blobs,
         )
-        .await;
+        .await?;
     WITNESS_GENERATOR_METRICS.witness_generation_time[&AggregationRound::BasicCircuits.into()]
         .observe(started_at.elapsed());
     tracing::info!(
@@ -361,13 +362,13 @@ async fn process_basic_circuits_job(
         started_at.elapsed()
     );
 
-    BasicCircuitArtifacts {
+    Ok(BasicCircuitArtifacts {
         circuit_urls,
         eip_4844_circuit_urls,
         queue_urls,
         scheduler_witness,
         aux_output_witness,
-    }
+    })
 }
 
 async fn update_database(
@@ -538,7 +539,7 @@ async fn generate_witness(
     connection_pool: ConnectionPool<Core>,
     input: BasicCircuitWitnessGeneratorInput,
     eip_4844_blobs: Eip4844Blobs,
-) -> (
+) -> anyhow::Result<(
     Vec<(u8, String)>,
     Vec<(usize, String)>,
     Vec<(u8, String, usize)>,
@@ -548,7 +549,7 @@ async fn generate_witness(
         GoldilocksExt2,
     >,
     BlockAuxilaryOutputWitness<GoldilocksField>,
-) {
+)> {
     let mut connection = connection_pool.connection().await.unwrap();
     let header = connection
         .blocks_dal()
@@ -584,8 +585,6 @@ async fn generate_witness(
         .expect("Failed fetching default account bytecode from DB")
         .expect("Default account bytecode should exist");
     let account_bytecode = bytes_to_chunks(&account_bytecode_bytes);
-    let bootloader_contents =
-        expand_bootloader_contents(&input.initial_heap_content, protocol_version);
     let account_code_hash = h256_to_u256(header.base_system_contracts_hashes.default_aa);
 
     let hashes: HashSet<H256> = input
@@ -611,12 +610,25 @@ async fn generate_witness(
         used_bytecodes.insert(account_code_hash, account_bytecode);
     }
 
-    assert_eq!(
-        hashes.len(),
-        used_bytecodes.len(),
-        "{} factory deps are not found in DB",
-        hashes.len() - used_bytecodes.len()
-    );
+    let geometry_config = get_geometry_config();
+    // Reject inputs that are guaranteed to fail before starting heavy computations.
+    let sanity_check = WitnessInputSanityCheck {
+        cycles_per_storage_application: geometry_config.cycles_per_storage_application,
+        bootloader_memory_bytes: get_used_bootloader_memory_bytes(protocol_version.into()),
+        initial_heap_content: &input.initial_heap_content,
+        storage_logs_count: input.merkle_paths_input.storage_logs_count(),
+        used_bytecode_hashes: &hashes,
+        used_bytecodes: &used_bytecodes,
+    };
+    if let Err(err) = sanity_check.run() {
+        WITNESS_GENERATOR_METRICS.rejected_blocks.inc();
+        return Err(err.context(format!(
+            "witness inputs for L1 batch #{} failed sanity check",
+            input.block_number
+        )));
+    }
+    let bootloader_contents =
+        expand_bootloader_contents(&input.initial_heap_content, protocol_version);
 
     // `DbStorageProvider` was designed to be used in API, so it accepts miniblock numbers.
     // Probably, we should make it work with L1 batch numbers too.
@@ -632,7 +644,6 @@ async fn generate_witness(
         input.merkle_paths_input,
         input.previous_block_hash.0,
     );
-    let geometry_config = get_geometry_config();
     let mut hasher = DefaultHasher::new();
     geometry_config.hash(&mut hasher);
     tracing::info!(
@@ -771,13 +782,13 @@ async fn generate_witness(
     scheduler_witness.eip4844_witnesses =
         Some([eip_4844_witnesses[0].clone(), eip_4844_witnesses[1].clone()]);
 
-    (
+    Ok((
         circuit_urls,
         eip_4844_blob_urls,
         recursion_urls,
         scheduler_witness,
         block_aux_witness,
-    )
+    ))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -4,6 +4,7 @@ pub mod basic_circuits;
 pub mod leaf_aggregation;
 pub mod node_aggregation;
 pub mod precalculated_merkle_paths_provider;
+mod sanity_check;
 pub mod scheduler;
 mod storage_oracle;
 pub mod utils;
@@ -22,7 +22,7 @@ use zksync_vk_setup_data_server_fri::commitment_utils::get_cached_commitments;
 use crate::{
     basic_circuits::BasicWitnessGenerator, leaf_aggregation::LeafAggregationWitnessGenerator,
     metrics::SERVER_METRICS, node_aggregation::NodeAggregationWitnessGenerator,
-    scheduler::SchedulerWitnessGenerator,
+    scheduler::SchedulerWitnessGenerator, utils::ensure_sequencer_circuit_capacity,
 };
 
 mod basic_circuits;
@@ -30,6 +30,7 @@ mod leaf_aggregation;
 mod metrics;
 mod node_aggregation;
 mod precalculated_merkle_paths_provider;
+mod sanity_check;
 mod scheduler;
 mod storage_oracle;
 mod utils;
@@ -138,6 +139,8 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
+    ensure_sequencer_circuit_capacity(&protocol_versions)?;
+
     let rounds = match (opt.round, opt.all_rounds) {
         (Some(round), false) => vec![round],
         (None, true) => vec![
@@ -17,6 +17,8 @@ pub(crate) struct WitnessGeneratorMetrics {
 
     pub sampled_blocks: Counter,
     pub skipped_blocks: Counter,
+    /// Number of blocks rejected by the witness input sanity check.
+    pub rejected_blocks: Counter,
 }
 
 #[vise::register]
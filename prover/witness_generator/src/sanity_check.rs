@@ -0,0 +1,150 @@
+//! Cheap structural checks of basic witness generator inputs.
+//!
+//! Generating and proving circuits for an L1 batch takes hours of CPU / GPU time. The checks here
+//! run before witness generation and reject inputs that cannot produce a valid proof, e.g. ones not
+//! fitting into the circuit geometry, with a precise reason.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_prover_fri_types::circuit_definitions::circuit_definitions::recursion_layer::SCHEDULER_CAPACITY;
+use zksync_types::{H256, U256};
+use zksync_utils::h256_to_u256;
+
+/// Maximum number of missing factory deps listed in the error message.
+const MAX_REPORTED_MISSING_FACTORY_DEPS: usize = 10;
+
+/// Basic witness generator inputs subject to the sanity check.
+#[derive(Debug)]
+pub(crate) struct WitnessInputSanityCheck<'a> {
+    /// Number of storage queries processed by a single storage application circuit.
+    pub cycles_per_storage_application: u32,
+    /// Size of the bootloader memory in bytes.
+    pub bootloader_memory_bytes: usize,
+    /// Initial bootloader heap content as `(word offset, value)` pairs.
+    pub initial_heap_content: &'a [(usize, U256)],
+    /// Number of storage logs with Merkle paths in the L1 batch.
+    pub storage_logs_count: usize,
+    /// Hashes of the bytecodes used in the L1 batch.
+    pub used_bytecode_hashes: &'a HashSet<H256>,
+    /// Bytecodes loaded from storage, keyed by their hashes.
+    pub used_bytecodes: &'a HashMap<U256, Vec<[u8; 32]>>,
+}
+
+impl WitnessInputSanityCheck<'_> {
+    /// Runs all checks, returning an error describing the first failed one.
+    pub fn run(&self) -> anyhow::Result<()> {
+        self.check_factory_deps()?;
+        self.check_bootloader_heap()?;
+        self.check_storage_logs()
+    }
+
+    fn check_factory_deps(&self) -> anyhow::Result<()> {
+        let mut missing_deps: Vec<_> = self
+            .used_bytecode_hashes
+            .iter()
+            .filter(|&&hash| !self.used_bytecodes.contains_key(&h256_to_u256(hash)))
+            .collect();
+        if missing_deps.is_empty() {
+            return Ok(());
+        }
+
+        missing_deps.sort_unstable();
+        let missing_count = missing_deps.len();
+        missing_deps.truncate(MAX_REPORTED_MISSING_FACTORY_DEPS);
+        anyhow::bail!(
+            "{missing_count} factory deps used in the L1 batch are not found in DB, \
+             e.g.: {missing_deps:?}"
+        );
+    }
+
+    fn check_bootloader_heap(&self) -> anyhow::Result<()> {
+        let memory_words = self.bootloader_memory_bytes / 32;
+        let max_offset = self
+            .initial_heap_content
+            .iter()
+            .map(|(offset, _)| *offset)
+            .max();
+        if let Some(max_offset) = max_offset {
+            anyhow::ensure!(
+                max_offset < memory_words,
+                "initial bootloader heap content has word offset {max_offset}, while the bootloader \
+                 memory has only {memory_words} words"
+            );
+        }
+        Ok(())
+    }
+
+    fn check_storage_logs(&self) -> anyhow::Result<()> {
+        let logs_per_circuit = self.cycles_per_storage_application as usize;
+        let storage_application_circuits = self.storage_logs_count.div_ceil(logs_per_circuit);
+        anyhow::ensure!(
+            storage_application_circuits <= SCHEDULER_CAPACITY,
+            "{} storage logs require {storage_application_circuits} storage application circuits, \
+             while at most {SCHEDULER_CAPACITY} circuits of a single type can be aggregated",
+            self.storage_logs_count
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static HEAP_CONTENT: [(usize, U256); 2] = [(0, U256([1, 0, 0, 0])), (31, U256([2, 0, 0, 0]))];
+
+    fn check<'a>(
+        used_bytecode_hashes: &'a HashSet<H256>,
+        used_bytecodes: &'a HashMap<U256, Vec<[u8; 32]>>,
+    ) -> WitnessInputSanityCheck<'a> {
+        WitnessInputSanityCheck {
+            cycles_per_storage_application: 100,
+            bootloader_memory_bytes: 1_024,
+            initial_heap_content: &HEAP_CONTENT,
+            storage_logs_count: 1_000,
+            used_bytecode_hashes,
+            used_bytecodes,
+        }
+    }
+
+    #[test]
+    fn sanity_check_for_valid_inputs() {
+        let hashes = HashSet::from([H256::repeat_byte(1), H256::repeat_byte(2)]);
+        let bytecodes = hashes
+            .iter()
+            .map(|&hash| (h256_to_u256(hash), vec![[0_u8; 32]]))
+            .collect();
+        check(&hashes, &bytecodes).run().unwrap();
+    }
+
+    #[test]
+    fn sanity_check_with_missing_factory_deps() {
+        let hashes = HashSet::from([H256::repeat_byte(1), H256::repeat_byte(2)]);
+        let bytecodes = HashMap::from([(h256_to_u256(H256::repeat_byte(1)), vec![[0_u8; 32]])]);
+        let err = check(&hashes, &bytecodes).run().unwrap_err().to_string();
+        assert!(err.contains("1 factory deps"), "{err}");
+        assert!(err.contains("0x0202"), "{err}");
+    }
+
+    #[test]
+    fn sanity_check_with_out_of_bounds_heap_content() {
+        let (hashes, bytecodes) = (HashSet::new(), HashMap::new());
+        let heap_content = [(0, U256::one()), (32, U256::one())];
+        let mut check = check(&hashes, &bytecodes);
+        check.initial_heap_content = &heap_content;
+        let err = check.run().unwrap_err().to_string();
+        assert!(err.contains("word offset 32"), "{err}");
+    }
+
+    #[test]
+    fn sanity_check_with_too_many_storage_logs() {
+        let (hashes, bytecodes) = (HashSet::new(), HashMap::new());
+        let mut check = check(&hashes, &bytecodes);
+        check.storage_logs_count = SCHEDULER_CAPACITY * 100;
+        check.run().unwrap();
+
+        check.storage_logs_count += 1;
+        let err = check.run().unwrap_err().to_string();
+        assert!(err.contains("storage application circuits"), "{err}");
+    }
+}
@@ -1,11 +1,14 @@
 use std::io::{BufWriter, Write as _};
 
+use anyhow::Context as _;
 use circuit_definitions::circuit_definitions::{
     base_layer::ZkSyncBaseLayerCircuit, eip4844::EIP4844Circuit,
 };
-use multivm::utils::get_used_bootloader_memory_bytes;
+use multivm::utils::{get_circuit_capacity, get_used_bootloader_memory_bytes};
 use once_cell::sync::Lazy;
-use zkevm_test_harness::boojum::field::goldilocks::GoldilocksField;
+use zkevm_test_harness::{
+    boojum::field::goldilocks::GoldilocksField, geometry_config::get_geometry_config,
+};
 use zksync_object_store::{serialize_using_bincode, Bucket, ObjectStore, StoredObject};
 use zksync_prover_fri_types::{
     circuit_definitions::{
@@ -23,7 +26,10 @@ use zksync_prover_fri_types::{
     keys::{AggregationsKey, ClosedFormInputKey, FriCircuitKey},
     CircuitWrapper, FriProofWrapper, EIP_4844_CIRCUIT_ID,
 };
-use zksync_types::{basic_fri_types::AggregationRound, L1BatchNumber, ProtocolVersionId, U256};
+use zksync_types::{
+    basic_fri_types::AggregationRound, circuit::CircuitCapacity, L1BatchNumber, ProtocolVersionId,
+    U256,
+};
 
 // Creates a temporary file with the serialized KZG setup usable by `zkevm_test_harness` functions.
 pub(crate) static KZG_TRUSTED_SETUP_FILE: Lazy<tempfile::NamedTempFile> = Lazy::new(|| {
@@ -49,6 +55,43 @@ pub fn expand_bootloader_contents(
     result
 }
 
+/// Returns the capacity of base layer circuits generated by the prover.
+pub fn prover_circuit_capacity() -> CircuitCapacity {
+    let geometry = get_geometry_config();
+    CircuitCapacity {
+        main_vm: geometry.cycles_per_vm_snapshot,
+        ram_permutation: geometry.cycles_per_ram_permutation,
+        storage_application: geometry.cycles_per_storage_application,
+        storage_sorter: geometry.cycles_per_storage_sorter,
+        code_decommitter: geometry.cycles_per_code_decommitter,
+        code_decommitter_sorter: geometry.cycles_code_decommitter_sorter,
+        log_demuxer: geometry.cycles_per_log_demuxer,
+        events_sorter: geometry.cycles_per_events_or_l1_messages_sorter,
+        keccak256: geometry.cycles_per_keccak256_circuit,
+        ecrecover: geometry.cycles_per_ecrecover_circuit,
+        sha256: geometry.cycles_per_sha256_circuit,
+    }
+}
+
+/// Checks that for each of `protocol_versions`, the sequencer seals L1 batches assuming circuit capacity
+/// not exceeding the prover one. Otherwise, the sequencer may produce L1 batches that cannot be proven.
+pub fn ensure_sequencer_circuit_capacity(
+    protocol_versions: &[ProtocolVersionId],
+) -> anyhow::Result<()> {
+    let prover_capacity = prover_circuit_capacity();
+    for &protocol_version in protocol_versions {
+        if let Some(capacity) = get_circuit_capacity(protocol_version.into()) {
+            capacity.ensure_within(&prover_capacity).with_context(|| {
+                format!(
+                    "sequencer circuit capacity for protocol version {protocol_version:?} \
+                     is not supported by the prover"
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ClosedFormInputWrapper(
     pub(crate) Vec<ZkSyncBaseLayerClosedFormInput<GoldilocksField>>,
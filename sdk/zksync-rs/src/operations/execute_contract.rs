@@ -157,6 +157,7 @@ where
             .provider
             .estimate_fee(execute.into())
             .await
+            .map(Fee::from)
             .map_err(Into::into)
     }
 }
@@ -183,6 +183,7 @@ where
             .provider
             .estimate_fee(l2_tx.into())
             .await
+            .map(Fee::from)
             .map_err(Into::into)
     }
 }
@@ -71,7 +71,7 @@ where
             self.provider
                 .get_balance(
                     self.address(),
-                    Some(BlockIdVariant::BlockNumber(block_number)),
+                    Some(BlockIdVariant::BlockNumber(block_number).into()),
                 )
                 .await?
         } else {
@@ -89,7 +89,7 @@ where
             };
             let bytes = self
                 .provider
-                .call(req, Some(BlockIdVariant::BlockNumber(block_number)))
+                .call(req, Some(BlockIdVariant::BlockNumber(block_number).into()))
                 .await?;
             if bytes.0.len() == 32 {
                 U256::from_big_endian(&bytes.0)